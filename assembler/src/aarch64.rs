@@ -0,0 +1,1152 @@
+//! AArch64 instructions, covering the general purpose data processing, branch, load and store
+//! instructions together with the aliases the decoder shows them as.
+
+use crate::parse::Parser;
+use crate::ErrorKind;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+struct Reg {
+    num: u32,
+    /// Whether this is a 64-bit register.
+    sf: bool,
+    /// Whether register 31 is the stack pointer rather than the zero register.
+    sp: bool,
+}
+
+impl Reg {
+    fn zr(sf: bool) -> Self {
+        Self {
+            num: 31,
+            sf,
+            sp: false,
+        }
+    }
+
+    fn is_sp(&self) -> bool {
+        self.num == 31 && self.sp
+    }
+
+    fn is_zr(&self) -> bool {
+        self.num == 31 && !self.sp
+    }
+
+    fn sf(&self) -> u32 {
+        self.sf as u32
+    }
+}
+
+/// SIMD&FP register as used by loads and stores, e.g. `q0` or `d8`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+struct Fp {
+    num: u32,
+    /// Size in log2 bytes.
+    size: u32,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Mem {
+    base: Reg,
+    offset: i64,
+    index: Option<Reg>,
+    /// Extend option and amount applied to the index, where `lsl` is `uxtx`.
+    extend: Option<(u32, Option<u32>)>,
+    /// Whether the base is updated before the access, e.g. `[sp, #-0x10]!`.
+    pre_index: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Operand {
+    Reg(Reg),
+    Fp(Fp),
+    Imm(i64),
+    /// Shift type and amount, e.g. `lsl #12`.
+    Shift(u32, u32),
+    /// Extend option and amount, e.g. `sxtw #3`.
+    Extend(u32, Option<u32>),
+    Mem(Mem),
+    Cond(u32),
+    Barrier(u32),
+    Prefetch(u32),
+}
+
+const CONDITIONS: [&str; 16] = [
+    "eq", "ne", "hs", "lo", "mi", "pl", "vs", "vc", "hi", "ls", "ge", "lt", "gt", "le", "al", "nv",
+];
+
+const SHIFTS: [&str; 4] = ["lsl", "lsr", "asr", "ror"];
+
+const EXTENDS: [&str; 8] = [
+    "uxtb", "uxth", "uxtw", "uxtx", "sxtb", "sxth", "sxtw", "sxtx",
+];
+
+/// Options of `dmb` and `dsb` by their encoding.
+#[rustfmt::skip]
+const BARRIERS: [(&str, u32); 12] = [
+    ("oshld", 1), ("oshst", 2), ("osh", 3), ("nshld", 5), ("nshst", 6), ("nsh", 7),
+    ("ishld", 9), ("ishst", 10), ("ish", 11), ("ld", 13), ("st", 14), ("sy", 15),
+];
+
+/// Instructions without operands.
+#[rustfmt::skip]
+const IMPLIED: [(&str, u32); 9] = [
+    ("nop", 0xd503201f), ("yield", 0xd503203f), ("wfe", 0xd503205f), ("wfi", 0xd503207f),
+    ("sev", 0xd503209f), ("sevl", 0xd50320bf), ("isb", 0xd5033fdf), ("eret", 0xd69f03e0),
+    ("drps", 0xd6bf03e0),
+];
+
+/// Exception generating instructions that take a 16-bit immediate.
+#[rustfmt::skip]
+const EXCEPTIONS: [(&str, u32); 6] = [
+    ("svc", 0xd4000001), ("hvc", 0xd4000002), ("smc", 0xd4000003), ("brk", 0xd4200000),
+    ("hlt", 0xd4400000), ("udf", 0x00000000),
+];
+
+/// Data processing with two sources by their opcode.
+const TWO_SOURCE: [(&str, u32); 8] = [
+    ("udiv", 2),
+    ("sdiv", 3),
+    ("lslv", 8),
+    ("lsrv", 9),
+    ("asrv", 10),
+    ("rorv", 11),
+    ("lsl", 8),
+    ("lsr", 9),
+];
+
+/// Logical instructions by their opc and N bit.
+#[rustfmt::skip]
+const LOGICAL: [(&str, u32, u32); 8] = [
+    ("and", 0, 0), ("orr", 1, 0), ("eor", 2, 0), ("ands", 3, 0),
+    ("bic", 0, 1), ("orn", 1, 1), ("eon", 2, 1), ("bics", 3, 1),
+];
+
+fn register(name: &str) -> Option<Operand> {
+    let gpr = |num, sf, sp| Some(Operand::Reg(Reg { num, sf, sp }));
+    match name {
+        "sp" => return gpr(31, true, true),
+        "wsp" => return gpr(31, false, true),
+        "xzr" => return gpr(31, true, false),
+        "wzr" => return gpr(31, false, false),
+        "fp" => return gpr(29, true, false),
+        "lr" => return gpr(30, true, false),
+        _ => {}
+    }
+
+    let (prefix, num) = (name.get(..1)?, &name[1..]);
+    if !num.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let num = num.parse::<u32>().ok()?;
+    let fp = |size| (num < 32).then_some(Operand::Fp(Fp { num, size }));
+
+    match prefix {
+        "x" if num < 31 => gpr(num, true, false),
+        "w" if num < 31 => gpr(num, false, false),
+        "b" => fp(0),
+        "h" => fp(1),
+        "s" => fp(2),
+        "d" => fp(3),
+        "q" => fp(4),
+        _ => None,
+    }
+}
+
+fn condition(name: &str) -> Option<u32> {
+    match name {
+        "cs" => Some(2),
+        "cc" => Some(3),
+        _ => CONDITIONS.iter().position(|c| *c == name).map(|c| c as u32),
+    }
+}
+
+/// Prefetch operation such as `pldl1keep`.
+fn prefetch(name: &str) -> Option<u32> {
+    let kind = match name.get(..3)? {
+        "pld" => 0,
+        "pli" => 1,
+        "pst" => 2,
+        _ => return None,
+    };
+
+    let target = match name.get(3..5)? {
+        "l1" => 0,
+        "l2" => 1,
+        "l3" => 2,
+        _ => return None,
+    };
+
+    let policy = match name.get(5..)? {
+        "keep" => 0,
+        "strm" => 1,
+        _ => return None,
+    };
+
+    Some(kind << 3 | target << 1 | policy)
+}
+
+fn operand(p: &mut Parser) -> Result<Operand, ErrorKind> {
+    if p.eat('#') {
+        return Ok(Operand::Imm(p.expr()?.value));
+    }
+
+    if p.peek() == Some('[') {
+        return memory(p).map(Operand::Mem);
+    }
+
+    if let Some(op) = p.ident_with(register) {
+        return Ok(op);
+    }
+
+    if let Some(shift) = p.ident_with(|name| SHIFTS.iter().position(|s| *s == name)) {
+        p.expect('#', "'#'")?;
+        return Ok(Operand::Shift(shift as u32, p.expr()?.value as u32));
+    }
+
+    if let Some(extend) = p.ident_with(|name| EXTENDS.iter().position(|e| *e == name)) {
+        let amount = if p.eat('#') {
+            Some(p.expr()?.value as u32)
+        } else {
+            None
+        };
+
+        return Ok(Operand::Extend(extend as u32, amount));
+    }
+
+    if let Some(cond) = p.ident_with(condition) {
+        return Ok(Operand::Cond(cond));
+    }
+
+    let barrier = |name: &str| BARRIERS.iter().find(|(b, _)| *b == name).map(|(_, crm)| *crm);
+    if let Some(crm) = p.ident_with(barrier) {
+        return Ok(Operand::Barrier(crm));
+    }
+
+    if let Some(op) = p.ident_with(prefetch) {
+        return Ok(Operand::Prefetch(op));
+    }
+
+    Ok(Operand::Imm(p.expr()?.value))
+}
+
+/// Memory operand such as `[x0, #0x8]!` or `[x1, w2, sxtw #3]`.
+fn memory(p: &mut Parser) -> Result<Mem, ErrorKind> {
+    p.expect('[', "'['")?;
+    let base = match p.ident_with(register) {
+        Some(Operand::Reg(reg)) if reg.sf && !reg.is_zr() => reg,
+        _ => return Err(ErrorKind::Syntax("a base register")),
+    };
+
+    let mut mem = Mem {
+        base,
+        offset: 0,
+        index: None,
+        extend: None,
+        pre_index: false,
+    };
+
+    if p.eat(',') {
+        match operand(p)? {
+            Operand::Imm(offset) => mem.offset = offset,
+            Operand::Reg(reg) if !reg.is_sp() => {
+                mem.index = Some(reg);
+                if p.eat(',') {
+                    mem.extend = match operand(p)? {
+                        Operand::Shift(0, amount) => Some((3, Some(amount))),
+                        Operand::Extend(option, amount) => Some((option, amount)),
+                        _ => return Err(ErrorKind::InvalidOperands),
+                    };
+                }
+            }
+            _ => return Err(ErrorKind::InvalidOperands),
+        }
+    }
+
+    p.expect(']', "']'")?;
+    mem.pre_index = p.eat('!');
+    Ok(mem)
+}
+
+pub fn assemble(p: &mut Parser) -> Result<Vec<u8>, ErrorKind> {
+    let mnemonic = p.ident().ok_or(ErrorKind::Syntax("an instruction"))?;
+    let mnemonic = mnemonic.to_ascii_lowercase();
+
+    let mut ops = Vec::new();
+    if !p.is_empty() {
+        loop {
+            ops.push(operand(p)?);
+            if !p.eat(',') {
+                break;
+            }
+        }
+    }
+
+    p.end()?;
+    let word = encode(&mnemonic, &ops, p.addr)?;
+    Ok(word.to_le_bytes().to_vec())
+}
+
+/// Number of a register in a field where 31 is the zero register.
+fn zr(reg: Reg) -> Result<u32, ErrorKind> {
+    if reg.is_sp() {
+        return Err(ErrorKind::InvalidOperands);
+    }
+
+    Ok(reg.num)
+}
+
+/// Number of a register in a field where 31 is the stack pointer.
+fn sp(reg: Reg) -> Result<u32, ErrorKind> {
+    if reg.is_zr() {
+        return Err(ErrorKind::InvalidOperands);
+    }
+
+    Ok(reg.num)
+}
+
+/// Fail unless all registers are the same size.
+fn same_size(regs: &[Reg]) -> Result<(), ErrorKind> {
+    if regs.windows(2).any(|pair| pair[0].sf != pair[1].sf) {
+        return Err(ErrorKind::SizeMismatch);
+    }
+
+    Ok(())
+}
+
+/// Unsigned immediate that fits in `bits`.
+fn uimm(value: i64, bits: u32) -> Result<u32, ErrorKind> {
+    if !(0..1 << bits).contains(&value) {
+        return Err(ErrorKind::ImmediateOutOfRange(value));
+    }
+
+    Ok(value as u32)
+}
+
+/// Signed immediate that fits in `bits`, truncated to that many bits.
+fn simm(value: i64, bits: u32) -> Result<u32, ErrorKind> {
+    if !(-(1 << (bits - 1))..1 << (bits - 1)).contains(&value) {
+        return Err(ErrorKind::ImmediateOutOfRange(value));
+    }
+
+    Ok(value as u32 & ((1 << bits) - 1))
+}
+
+/// Offset to a branch target in instructions, truncated to `bits`.
+fn target(target: i64, addr: u64, bits: u32) -> Result<u32, ErrorKind> {
+    let target = target as u64;
+    if target & 3 != 0 {
+        return Err(ErrorKind::MisalignedTarget(target));
+    }
+
+    let offset = target.wrapping_sub(addr) as i64 / 4;
+    simm(offset, bits).map_err(|_| ErrorKind::TargetOutOfRange(target))
+}
+
+/// Bit width of a register.
+fn width(sf: bool) -> u32 {
+    if sf {
+        64
+    } else {
+        32
+    }
+}
+
+/// Value of an immediate for an operation of the given size, accepting both signed and unsigned
+/// forms of 32-bit values.
+fn value(imm: i64, sf: bool) -> Result<u64, ErrorKind> {
+    if sf || (i32::MIN as i64..=u32::MAX as i64).contains(&imm) {
+        Ok(if sf { imm as u64 } else { imm as u32 as u64 })
+    } else {
+        Err(ErrorKind::ImmediateOutOfRange(imm))
+    }
+}
+
+/// N, immr and imms fields of a logical immediate, which is a repeating element consisting of a
+/// rotated run of ones.
+fn bitmask(value: u64, sf: bool) -> Option<u32> {
+    let width = width(sf);
+    let mask = u64::MAX >> (64 - width);
+    if value == 0 || value == mask {
+        return None;
+    }
+
+    let mut size = width;
+    while size > 2 {
+        let half = size / 2;
+        let half_mask = (1u64 << half) - 1;
+        if value & half_mask != (value >> half) & half_mask {
+            break;
+        }
+
+        size = half;
+    }
+
+    let elem_mask = u64::MAX >> (64 - size);
+    let elem = value & elem_mask;
+    let ones = elem.count_ones();
+    let run = (1u64 << ones) - 1;
+
+    let rotr = |x: u64, r: u32| {
+        if r == 0 {
+            x
+        } else {
+            (x >> r | x << (size - r)) & elem_mask
+        }
+    };
+
+    let rotation = (0..size).find(|r| rotr(elem, *r) == run)?;
+    let immr = (size - rotation) % size;
+    let imms = (!(size * 2 - 1) & 0x3f) | (ones - 1);
+    let n = (size == 64) as u32;
+
+    Some(n << 12 | immr << 6 | imms)
+}
+
+/// Instructions that aren't in any of the tables above.
+#[rustfmt::skip]
+const OTHER: &[&str] = &[
+    "add", "adds", "sub", "subs", "cmp", "cmn", "neg", "negs", "adc", "adcs", "sbc", "sbcs", "tst",
+    "mvn", "mov", "movz", "movn", "movk", "asr", "ror", "extr", "ubfx", "sbfx", "bfxil", "ubfiz",
+    "sbfiz", "bfi", "ubfm", "sbfm", "bfm", "sxtb", "sxth", "sxtw", "uxtb", "uxth", "rbit",
+    "rev16", "rev32", "rev", "clz", "cls", "madd", "msub", "mul", "mneg", "smaddl", "smsubl",
+    "umaddl", "umsubl", "smull", "smnegl", "umull", "umnegl", "smulh", "umulh", "csel", "csinc",
+    "csinv", "csneg", "cset", "csetm", "cinc", "cinv", "cneg", "ccmp", "ccmn", "adr", "adrp", "b",
+    "bl", "cbz", "cbnz", "tbz", "tbnz", "br", "blr", "ret", "dmb", "dsb", "stp", "ldp", "ldpsw",
+    "stnp", "ldnp", "prfm", "prfum",
+];
+
+fn encode(mnemonic: &str, ops: &[Operand], addr: u64) -> Result<u32, ErrorKind> {
+    use Operand as Op;
+
+    if let Some((_, word)) = IMPLIED.iter().find(|(name, _)| *name == mnemonic) {
+        return match ops {
+            [] => Ok(*word),
+            _ => Err(ErrorKind::InvalidOperands),
+        };
+    }
+
+    if let Some((_, word)) = EXCEPTIONS.iter().find(|(name, _)| *name == mnemonic) {
+        return match ops {
+            [Op::Imm(imm)] if mnemonic == "udf" => Ok(word | uimm(*imm, 16)?),
+            [Op::Imm(imm)] => Ok(word | uimm(*imm, 16)? << 5),
+            [] if mnemonic == "brk" => Ok(*word),
+            _ => Err(ErrorKind::InvalidOperands),
+        };
+    }
+
+    if let Some(&(_, opc, n)) = LOGICAL.iter().find(|(name, ..)| *name == mnemonic) {
+        return match ops {
+            [Op::Reg(rd), Op::Reg(rn), rest @ ..] => logical(opc, n, *rd, *rn, rest),
+            _ => Err(ErrorKind::InvalidOperands),
+        };
+    }
+
+    if let Some(cond) = mnemonic.strip_prefix("b.").and_then(condition) {
+        return match ops {
+            [Op::Imm(dst)] => Ok(0x54000000 | target(*dst, addr, 19)? << 5 | cond),
+            _ => Err(ErrorKind::InvalidOperands),
+        };
+    }
+
+    if let Some(kind) = load_store_kind(mnemonic) {
+        return load_store(kind, ops, addr);
+    }
+
+    let word = match (mnemonic, ops) {
+        ("add" | "adds" | "sub" | "subs", [Op::Reg(rd), Op::Reg(rn), rest @ ..]) => {
+            let sub = mnemonic.starts_with("sub");
+            add_sub(sub, mnemonic.ends_with('s'), *rd, *rn, rest)?
+        }
+        ("cmp" | "cmn", [Op::Reg(rn), rest @ ..]) => {
+            add_sub(mnemonic == "cmp", true, Reg::zr(rn.sf), *rn, rest)?
+        }
+        ("neg" | "negs", [Op::Reg(rd), Op::Reg(rm), rest @ ..]) => {
+            let op2 = [&[Op::Reg(*rm)], rest].concat();
+            add_sub(true, mnemonic == "negs", *rd, Reg::zr(rd.sf), &op2)?
+        }
+        ("adc" | "adcs" | "sbc" | "sbcs", [Op::Reg(rd), Op::Reg(rn), Op::Reg(rm)]) => {
+            same_size(&[*rd, *rn, *rm])?;
+            let op = mnemonic.starts_with("sbc") as u32;
+            let s = mnemonic.ends_with('s') as u32;
+            rd.sf() << 31
+                | op << 30
+                | s << 29
+                | 0x1a000000
+                | zr(*rm)? << 16
+                | zr(*rn)? << 5
+                | zr(*rd)?
+        }
+        ("tst", [Op::Reg(rn), rest @ ..]) => logical(3, 0, Reg::zr(rn.sf), *rn, rest)?,
+        ("mvn", [Op::Reg(rd), rest @ ..]) => logical(1, 1, *rd, Reg::zr(rd.sf), rest)?,
+        ("mov", [Op::Reg(rd), Op::Reg(rm)]) if rd.is_sp() || rm.is_sp() => {
+            add_sub(false, false, *rd, *rm, &[Op::Imm(0)])?
+        }
+        ("mov", [Op::Reg(rd), Op::Reg(rm)]) => logical(1, 0, *rd, Reg::zr(rd.sf), &[Op::Reg(*rm)])?,
+        ("mov", [Op::Reg(rd), Op::Imm(imm)]) => mov(*rd, *imm)?,
+        ("movz" | "movn" | "movk", [Op::Reg(rd), Op::Imm(imm), rest @ ..]) => {
+            let opc = match mnemonic {
+                "movn" => 0,
+                "movz" => 2,
+                _ => 3,
+            };
+
+            let hw = match rest {
+                [] => 0,
+                [Op::Shift(0, amount)] if amount % 16 == 0 && *amount < width(rd.sf) => amount / 16,
+                _ => return Err(ErrorKind::InvalidOperands),
+            };
+
+            move_wide(opc, *rd, hw, uimm(*imm, 16)?)?
+        }
+        ("lsl" | "lsr" | "asr", [Op::Reg(rd), Op::Reg(rn), Op::Imm(shift)]) => {
+            let width = width(rd.sf);
+            let shift = uimm(*shift, 6)?;
+            if shift >= width {
+                return Err(ErrorKind::ImmediateOutOfRange(shift as i64));
+            }
+
+            match mnemonic {
+                "lsl" => bitfield(2, *rd, *rn, (width - shift) % width, width - 1 - shift)?,
+                "lsr" => bitfield(2, *rd, *rn, shift, width - 1)?,
+                _ => bitfield(0, *rd, *rn, shift, width - 1)?,
+            }
+        }
+        ("ror", [Op::Reg(rd), Op::Reg(rn), Op::Imm(shift)]) => extr(*rd, *rn, *rn, *shift)?,
+        ("extr", [Op::Reg(rd), Op::Reg(rn), Op::Reg(rm), Op::Imm(lsb)]) => {
+            extr(*rd, *rn, *rm, *lsb)?
+        }
+        ("asr" | "ror", [Op::Reg(rd), Op::Reg(rn), Op::Reg(rm)]) => {
+            let opcode = if mnemonic == "asr" { 10 } else { 11 };
+            two_source(opcode, *rd, *rn, *rm)?
+        }
+        (_, [Op::Reg(rd), Op::Reg(rn), Op::Reg(rm)])
+            if TWO_SOURCE.iter().any(|(name, _)| *name == mnemonic) =>
+        {
+            let (_, opcode) = TWO_SOURCE.iter().find(|(name, _)| *name == mnemonic).unwrap();
+            two_source(*opcode, *rd, *rn, *rm)?
+        }
+        (
+            "ubfx" | "sbfx" | "bfxil" | "ubfiz" | "sbfiz" | "bfi",
+            [Op::Reg(rd), Op::Reg(rn), Op::Imm(lsb), Op::Imm(len)],
+        ) => {
+            let width = width(rd.sf);
+            let lsb = uimm(*lsb, 6)?;
+            if lsb >= width {
+                return Err(ErrorKind::ImmediateOutOfRange(lsb as i64));
+            }
+
+            let len = uimm(*len, 7)?;
+            if len == 0 || len > width - lsb {
+                return Err(ErrorKind::ImmediateOutOfRange(len as i64));
+            }
+
+            let opc = match mnemonic {
+                "sbfx" | "sbfiz" => 0,
+                "bfxil" | "bfi" => 1,
+                _ => 2,
+            };
+
+            // extracts move the field down, inserts move it up
+            if mnemonic.ends_with('x') || mnemonic == "bfxil" {
+                bitfield(opc, *rd, *rn, lsb, lsb + len - 1)?
+            } else {
+                bitfield(opc, *rd, *rn, (width - lsb) % width, len - 1)?
+            }
+        }
+        ("ubfm" | "sbfm" | "bfm", [Op::Reg(rd), Op::Reg(rn), Op::Imm(immr), Op::Imm(imms)]) => {
+            let opc = match mnemonic {
+                "sbfm" => 0,
+                "bfm" => 1,
+                _ => 2,
+            };
+
+            bitfield(opc, *rd, *rn, uimm(*immr, 6)?, uimm(*imms, 6)?)?
+        }
+        ("sxtb" | "sxth" | "sxtw" | "uxtb" | "uxth", [Op::Reg(rd), Op::Reg(rn)]) => {
+            let bits = match &mnemonic[3..] {
+                "b" => 8,
+                "h" => 16,
+                _ => 32,
+            };
+
+            // unsigned extends only exist for 32-bit registers, as those clear the upper half
+            let signed = mnemonic.starts_with('s');
+            if rn.sf || (!signed && rd.sf) || (bits == 32 && !rd.sf) {
+                return Err(ErrorKind::InvalidOperands);
+            }
+
+            let rn = Reg { sf: rd.sf, ..*rn };
+            let opc = if signed { 0 } else { 2 };
+            bitfield(opc, *rd, rn, 0, bits - 1)?
+        }
+        ("rbit" | "rev16" | "rev32" | "rev" | "clz" | "cls", [Op::Reg(rd), Op::Reg(rn)]) => {
+            same_size(&[*rd, *rn])?;
+            let opcode = match mnemonic {
+                "rbit" => 0,
+                "rev16" => 1,
+                "rev32" if rd.sf => 2,
+                "rev" => 2 + rd.sf(),
+                "clz" => 4,
+                "cls" => 5,
+                _ => return Err(ErrorKind::InvalidOperands),
+            };
+
+            rd.sf() << 31 | 0x5ac00000 | opcode << 10 | zr(*rn)? << 5 | zr(*rd)?
+        }
+        ("madd" | "msub", [Op::Reg(rd), Op::Reg(rn), Op::Reg(rm), Op::Reg(ra)]) => {
+            same_size(&[*rd, *rn, *rm, *ra])?;
+            three_source(0, mnemonic == "msub", *rd, *rn, *rm, *ra)?
+        }
+        ("mul" | "mneg", [Op::Reg(rd), Op::Reg(rn), Op::Reg(rm)]) => {
+            same_size(&[*rd, *rn, *rm])?;
+            three_source(0, mnemonic == "mneg", *rd, *rn, *rm, Reg::zr(rd.sf))?
+        }
+        (
+            "smaddl" | "smsubl" | "umaddl" | "umsubl",
+            [Op::Reg(rd), Op::Reg(rn), Op::Reg(rm), Op::Reg(ra)],
+        ) => {
+            if !rd.sf || !ra.sf || rn.sf || rm.sf {
+                return Err(ErrorKind::SizeMismatch);
+            }
+
+            let op31 = if mnemonic.starts_with('u') { 5 } else { 1 };
+            three_source(op31, mnemonic.contains("sub"), *rd, *rn, *rm, *ra)?
+        }
+        ("smull" | "smnegl" | "umull" | "umnegl", [Op::Reg(rd), Op::Reg(rn), Op::Reg(rm)]) => {
+            if !rd.sf || rn.sf || rm.sf {
+                return Err(ErrorKind::SizeMismatch);
+            }
+
+            let op31 = if mnemonic.starts_with('u') { 5 } else { 1 };
+            three_source(op31, mnemonic.contains("neg"), *rd, *rn, *rm, Reg::zr(true))?
+        }
+        ("smulh" | "umulh", [Op::Reg(rd), Op::Reg(rn), Op::Reg(rm)]) => {
+            if !rd.sf || !rn.sf || !rm.sf {
+                return Err(ErrorKind::SizeMismatch);
+            }
+
+            let op31 = if mnemonic == "umulh" { 6 } else { 2 };
+            three_source(op31, false, *rd, *rn, *rm, Reg::zr(true))?
+        }
+        (
+            "csel" | "csinc" | "csinv" | "csneg",
+            [Op::Reg(rd), Op::Reg(rn), Op::Reg(rm), Op::Cond(cond)],
+        ) => {
+            let (op, o2) = match mnemonic {
+                "csel" => (0, 0),
+                "csinc" => (0, 1),
+                "csinv" => (1, 0),
+                _ => (1, 1),
+            };
+
+            select(op, o2, *rd, *rn, *rm, *cond)?
+        }
+        // the aliases hold the condition under which the result differs, so it's inverted
+        ("cset" | "csetm", [Op::Reg(rd), Op::Cond(cond)]) if *cond < 14 => {
+            let (op, o2) = if mnemonic == "cset" { (0, 1) } else { (1, 0) };
+            let zr = Reg::zr(rd.sf);
+            select(op, o2, *rd, zr, zr, cond ^ 1)?
+        }
+        ("cinc" | "cinv" | "cneg", [Op::Reg(rd), Op::Reg(rn), Op::Cond(cond)]) if *cond < 14 => {
+            let (op, o2) = match mnemonic {
+                "cinc" => (0, 1),
+                "cinv" => (1, 0),
+                _ => (1, 1),
+            };
+
+            select(op, o2, *rd, *rn, *rn, cond ^ 1)?
+        }
+        ("ccmp" | "ccmn", [Op::Reg(rn), rm, Op::Imm(nzcv), Op::Cond(cond)]) => {
+            let op = (mnemonic == "ccmp") as u32;
+            let (rm, imm) = match rm {
+                Op::Reg(rm) => {
+                    same_size(&[*rn, *rm])?;
+                    (zr(*rm)?, 0)
+                }
+                Op::Imm(imm) => (uimm(*imm, 5)?, 1),
+                _ => return Err(ErrorKind::InvalidOperands),
+            };
+
+            rn.sf() << 31
+                | op << 30
+                | 0x3a400000
+                | rm << 16
+                | cond << 12
+                | imm << 11
+                | zr(*rn)? << 5
+                | uimm(*nzcv, 4)?
+        }
+        ("adr" | "adrp", [Op::Reg(rd), Op::Imm(dst)]) if rd.sf => {
+            let dst = *dst as u64;
+            let offset = if mnemonic == "adrp" {
+                (dst >> 12).wrapping_sub(addr >> 12) as i64
+            } else {
+                dst.wrapping_sub(addr) as i64
+            };
+
+            let imm = simm(offset, 21).map_err(|_| ErrorKind::TargetOutOfRange(dst))?;
+            let op = (mnemonic == "adrp") as u32;
+            op << 31 | (imm & 3) << 29 | 0x10000000 | (imm >> 2) << 5 | zr(*rd)?
+        }
+        ("b", [Op::Imm(dst)]) => 0x14000000 | target(*dst, addr, 26)?,
+        ("bl", [Op::Imm(dst)]) => 0x94000000 | target(*dst, addr, 26)?,
+        ("cbz" | "cbnz", [Op::Reg(rt), Op::Imm(dst)]) => {
+            let op = (mnemonic == "cbnz") as u32;
+            rt.sf() << 31 | 0x34000000 | op << 24 | target(*dst, addr, 19)? << 5 | zr(*rt)?
+        }
+        ("tbz" | "tbnz", [Op::Reg(rt), Op::Imm(bit), Op::Imm(dst)]) => {
+            let op = (mnemonic == "tbnz") as u32;
+            let bit = uimm(*bit, if rt.sf { 6 } else { 5 })?;
+            (bit >> 5) << 31
+                | 0x36000000
+                | op << 24
+                | (bit & 0x1f) << 19
+                | target(*dst, addr, 14)? << 5
+                | zr(*rt)?
+        }
+        ("br" | "blr", [Op::Reg(rn)]) if rn.sf => {
+            let word = if mnemonic == "br" {
+                0xd61f0000
+            } else {
+                0xd63f0000
+            };
+            word | zr(*rn)? << 5
+        }
+        ("ret", []) => 0xd65f03c0,
+        ("ret", [Op::Reg(rn)]) if rn.sf => 0xd65f0000 | zr(*rn)? << 5,
+        ("dmb" | "dsb", [option]) => {
+            let crm = match option {
+                Op::Barrier(crm) => *crm,
+                Op::Imm(imm) => uimm(*imm, 4)?,
+                _ => return Err(ErrorKind::InvalidOperands),
+            };
+
+            let word = if mnemonic == "dmb" {
+                0xd50330bf
+            } else {
+                0xd503309f
+            };
+            word | crm << 8
+        }
+        ("stp" | "ldp" | "ldpsw" | "stnp" | "ldnp", _) => pair(mnemonic, ops)?,
+        ("prfm" | "prfum", [op, addr_op]) => {
+            let op = match op {
+                Op::Prefetch(op) => *op,
+                Op::Imm(op) => uimm(*op, 5)?,
+                _ => return Err(ErrorKind::InvalidOperands),
+            };
+
+            match addr_op {
+                Op::Mem(mem) => address(0xc0800000 | op, 3, mnemonic == "prfum", mem, None)?,
+                Op::Imm(dst) if mnemonic == "prfm" => {
+                    0xd8000000 | target(*dst, addr, 19)? << 5 | op
+                }
+                _ => return Err(ErrorKind::InvalidOperands),
+            }
+        }
+        _ if OTHER.contains(&mnemonic) || TWO_SOURCE.iter().any(|(name, _)| *name == mnemonic) => {
+            return Err(ErrorKind::InvalidOperands);
+        }
+        _ => return Err(ErrorKind::UnknownMnemonic(mnemonic.to_string())),
+    };
+
+    Ok(word)
+}
+
+fn add_sub(sub: bool, s: bool, rd: Reg, rn: Reg, op2: &[Operand]) -> Result<u32, ErrorKind> {
+    same_size(&[rd, rn])?;
+
+    // flag setting forms write to the zero register rather than the stack pointer
+    let rd_sp = if s { zr(rd)? } else { sp(rd)? };
+    let fixed = rd.sf() << 31 | (s as u32) << 29;
+
+    match op2 {
+        [Operand::Imm(imm), rest @ ..] => {
+            let (value, sub) = if *imm < 0 && rest.is_empty() {
+                (imm.wrapping_neg(), !sub)
+            } else {
+                (*imm, sub)
+            };
+
+            let (value, sh) = match rest {
+                [] if value >= 1 << 12 && value & 0xfff == 0 => (value >> 12, 1),
+                [] | [Operand::Shift(0, 0)] => (value, 0),
+                [Operand::Shift(0, 12)] => (value, 1),
+                _ => return Err(ErrorKind::InvalidOperands),
+            };
+
+            let imm12 = uimm(value, 12).map_err(|_| ErrorKind::ImmediateOutOfRange(*imm))?;
+            Ok(fixed
+                | (sub as u32) << 30
+                | 0x11000000
+                | sh << 22
+                | imm12 << 10
+                | sp(rn)? << 5
+                | rd_sp)
+        }
+        [Operand::Reg(rm), rest @ ..] => {
+            let extended = rd.is_sp()
+                || rn.is_sp()
+                || (rd.sf && !rm.sf)
+                || matches!(rest, [Operand::Extend(..)]);
+
+            if !extended {
+                same_size(&[rd, *rm])?;
+                let (shift, amount) = match rest {
+                    [] => (0, 0),
+                    [Operand::Shift(shift, amount)] if *shift < 3 && *amount < width(rd.sf) => {
+                        (*shift, *amount)
+                    }
+                    _ => return Err(ErrorKind::InvalidOperands),
+                };
+
+                return Ok(fixed
+                    | (sub as u32) << 30
+                    | 0x0b000000
+                    | shift << 22
+                    | zr(*rm)? << 16
+                    | amount << 10
+                    | zr(rn)? << 5
+                    | zr(rd)?);
+            }
+
+            // `lsl` is an alias of the extend that matches the register size
+            let (option, amount) = match rest {
+                [] | [Operand::Shift(0, _)] if rm.sf => (3, 0),
+                [] | [Operand::Shift(0, _)] => (2, 0),
+                [Operand::Extend(option, amount)] => (*option, amount.unwrap_or(0)),
+                _ => return Err(ErrorKind::InvalidOperands),
+            };
+
+            let amount = match rest {
+                [Operand::Shift(_, amount)] => *amount,
+                _ => amount,
+            };
+
+            // only `uxtx` and `sxtx` take a 64-bit register
+            if (option & 3 == 3) != rm.sf || (!rd.sf && rm.sf) {
+                return Err(ErrorKind::SizeMismatch);
+            }
+
+            if amount > 4 {
+                return Err(ErrorKind::ImmediateOutOfRange(amount as i64));
+            }
+
+            Ok(fixed
+                | (sub as u32) << 30
+                | 0x0b200000
+                | zr(*rm)? << 16
+                | option << 13
+                | amount << 10
+                | sp(rn)? << 5
+                | rd_sp)
+        }
+        _ => Err(ErrorKind::InvalidOperands),
+    }
+}
+
+fn logical(opc: u32, n: u32, rd: Reg, rn: Reg, op2: &[Operand]) -> Result<u32, ErrorKind> {
+    same_size(&[rd, rn])?;
+    let fixed = rd.sf() << 31 | opc << 29 | zr(rn)? << 5;
+
+    match op2 {
+        [Operand::Imm(imm)] if n == 0 => {
+            let fields =
+                bitmask(value(*imm, rd.sf)?, rd.sf).ok_or(ErrorKind::ImmediateOutOfRange(*imm))?;
+
+            // `ands` writes to the zero register rather than the stack pointer
+            let rd = if opc == 3 { zr(rd)? } else { sp(rd)? };
+            Ok(fixed | 0x12000000 | fields << 10 | rd)
+        }
+        [Operand::Reg(rm), rest @ ..] => {
+            same_size(&[rd, *rm])?;
+            let (shift, amount) = match rest {
+                [] => (0, 0),
+                [Operand::Shift(shift, amount)] if *amount < width(rd.sf) => (*shift, *amount),
+                _ => return Err(ErrorKind::InvalidOperands),
+            };
+
+            Ok(
+                fixed
+                    | 0x0a000000
+                    | shift << 22
+                    | n << 21
+                    | zr(*rm)? << 16
+                    | amount << 10
+                    | zr(rd)?,
+            )
+        }
+        _ => Err(ErrorKind::InvalidOperands),
+    }
+}
+
+/// `mov` of an immediate, which is a `movz`, `movn` or `orr` depending on the value.
+fn mov(rd: Reg, imm: i64) -> Result<u32, ErrorKind> {
+    let value = value(imm, rd.sf)?;
+    let width = width(rd.sf);
+    let mask = u64::MAX >> (64 - width);
+
+    if !rd.is_sp() {
+        for (opc, value) in [(2, value), (0, !value & mask)] {
+            for hw in 0..width / 16 {
+                if value & !(0xffff << (16 * hw)) == 0 {
+                    return move_wide(opc, rd, hw, (value >> (16 * hw)) as u32);
+                }
+            }
+        }
+    }
+
+    logical(1, 0, rd, Reg::zr(rd.sf), &[Operand::Imm(imm)])
+}
+
+fn move_wide(opc: u32, rd: Reg, hw: u32, imm16: u32) -> Result<u32, ErrorKind> {
+    Ok(rd.sf() << 31 | opc << 29 | 0x12800000 | hw << 21 | imm16 << 5 | zr(rd)?)
+}
+
+fn bitfield(opc: u32, rd: Reg, rn: Reg, immr: u32, imms: u32) -> Result<u32, ErrorKind> {
+    same_size(&[rd, rn])?;
+    if immr >= width(rd.sf) || imms >= width(rd.sf) {
+        return Err(ErrorKind::InvalidOperands);
+    }
+
+    Ok(rd.sf() << 31
+        | opc << 29
+        | 0x13000000
+        | rd.sf() << 22
+        | immr << 16
+        | imms << 10
+        | zr(rn)? << 5
+        | zr(rd)?)
+}
+
+fn extr(rd: Reg, rn: Reg, rm: Reg, lsb: i64) -> Result<u32, ErrorKind> {
+    same_size(&[rd, rn, rm])?;
+    let lsb = uimm(lsb, 6)?;
+    if lsb >= width(rd.sf) {
+        return Err(ErrorKind::ImmediateOutOfRange(lsb as i64));
+    }
+
+    Ok(rd.sf() << 31
+        | 0x13800000
+        | rd.sf() << 22
+        | zr(rm)? << 16
+        | lsb << 10
+        | zr(rn)? << 5
+        | zr(rd)?)
+}
+
+fn two_source(opcode: u32, rd: Reg, rn: Reg, rm: Reg) -> Result<u32, ErrorKind> {
+    same_size(&[rd, rn, rm])?;
+    Ok(rd.sf() << 31 | 0x1ac00000 | zr(rm)? << 16 | opcode << 10 | zr(rn)? << 5 | zr(rd)?)
+}
+
+fn three_source(op31: u32, o0: bool, rd: Reg, rn: Reg, rm: Reg, ra: Reg) -> Result<u32, ErrorKind> {
+    Ok(rd.sf() << 31
+        | 0x1b000000
+        | op31 << 21
+        | zr(rm)? << 16
+        | (o0 as u32) << 15
+        | zr(ra)? << 10
+        | zr(rn)? << 5
+        | zr(rd)?)
+}
+
+fn select(op: u32, o2: u32, rd: Reg, rn: Reg, rm: Reg, cond: u32) -> Result<u32, ErrorKind> {
+    same_size(&[rd, rn, rm])?;
+    Ok(rd.sf() << 31
+        | op << 30
+        | 0x1a800000
+        | zr(rm)? << 16
+        | cond << 12
+        | o2 << 10
+        | zr(rn)? << 5
+        | zr(rd)?)
+}
+
+/// Whether a mnemonic is a single register load or store, whether it's unscaled and it's suffix,
+/// e.g. `ldursb` is `(true, true, "sb")`.
+fn load_store_kind(mnemonic: &str) -> Option<(bool, bool, &str)> {
+    let (load, rest) = match mnemonic.strip_prefix("ld") {
+        Some(rest) => (true, rest),
+        None => (false, mnemonic.strip_prefix("st")?),
+    };
+
+    let (unscaled, suffix) = match rest.strip_prefix("ur") {
+        Some(suffix) => (true, suffix),
+        None => (false, rest.strip_prefix('r')?),
+    };
+
+    let valid = match suffix {
+        "" | "b" | "h" => true,
+        "sb" | "sh" | "sw" => load,
+        _ => false,
+    };
+
+    valid.then_some((load, unscaled, suffix))
+}
+
+fn load_store(
+    (load, unscaled, suffix): (bool, bool, &str),
+    ops: &[Operand],
+    addr: u64,
+) -> Result<u32, ErrorKind> {
+    let (rt, rest) = match ops {
+        [rt, rest @ ..] => (rt, rest),
+        _ => return Err(ErrorKind::InvalidOperands),
+    };
+
+    let load = load as u32;
+    let (size, v, opc, rt_num) = match (rt, suffix) {
+        (Operand::Reg(rt), "") => (2 + rt.sf(), 0, load, zr(*rt)?),
+        (Operand::Reg(rt), "b" | "h") if !rt.sf => ((suffix == "h") as u32, 0, load, zr(*rt)?),
+        (Operand::Reg(rt), "sb" | "sh") => {
+            let size = (suffix == "sh") as u32;
+            (size, 0, 3 - rt.sf(), zr(*rt)?)
+        }
+        (Operand::Reg(rt), "sw") if rt.sf => (2, 0, 2, zr(*rt)?),
+        (Operand::Fp(rt), "") => (rt.size & 3, 1, load | (rt.size >> 2) << 1, rt.num),
+        (Operand::Reg(_), _) => return Err(ErrorKind::SizeMismatch),
+        _ => return Err(ErrorKind::InvalidOperands),
+    };
+
+    // the access size, which differs from the size field for 128-bit registers
+    let scale = match rt {
+        Operand::Fp(rt) => rt.size,
+        _ => size,
+    };
+
+    match rest {
+        [Operand::Mem(mem)] => address(
+            size << 30 | v << 26 | opc << 22 | rt_num,
+            scale,
+            unscaled,
+            mem,
+            None,
+        ),
+        [Operand::Mem(mem), Operand::Imm(offset)] => address(
+            size << 30 | v << 26 | opc << 22 | rt_num,
+            scale,
+            unscaled,
+            mem,
+            Some(*offset),
+        ),
+        [Operand::Imm(dst)] if load == 1 && !unscaled && matches!(suffix, "" | "sw") => {
+            let opc = match rt {
+                Operand::Fp(rt) if rt.size >= 2 => rt.size - 2,
+                Operand::Fp(_) => return Err(ErrorKind::InvalidOperands),
+                Operand::Reg(_) if suffix == "sw" => 2,
+                Operand::Reg(rt) => rt.sf(),
+                _ => unreachable!(),
+            };
+
+            Ok(opc << 30 | 0x18000000 | v << 26 | target(*dst, addr, 19)? << 5 | rt_num)
+        }
+        _ => Err(ErrorKind::InvalidOperands),
+    }
+}
+
+/// Addressing mode of a single register load or store, `word` holds the other fields.
+fn address(
+    word: u32,
+    scale: u32,
+    unscaled: bool,
+    mem: &Mem,
+    post_index: Option<i64>,
+) -> Result<u32, ErrorKind> {
+    let word = word | 0x38000000 | sp(mem.base)? << 5;
+    let imm9 = |offset, mode: u32| Ok(word | simm(offset, 9)? << 12 | mode << 10);
+
+    match (mem.index, post_index) {
+        (None, Some(offset)) if !mem.pre_index && mem.offset == 0 && !unscaled => imm9(offset, 1),
+        (None, None) if mem.pre_index && !unscaled => imm9(mem.offset, 3),
+        (None, None) if !mem.pre_index => {
+            let offset = mem.offset;
+            let scaled = offset >> scale;
+            if !unscaled && offset >= 0 && offset & ((1 << scale) - 1) == 0 && scaled < 1 << 12 {
+                return Ok(word | 1 << 24 | (scaled as u32) << 10);
+            }
+
+            // offsets that can't be scaled fall back to the unscaled form of the instruction
+            imm9(offset, 0)
+        }
+        (Some(index), None) if !mem.pre_index && !unscaled => {
+            let (option, amount) = match mem.extend {
+                None => (3, None),
+                Some((option, amount)) if option & 2 != 0 => (option, amount),
+                _ => return Err(ErrorKind::InvalidOperands),
+            };
+
+            // `uxtx`, `sxtx` and `lsl` take a 64-bit index, `uxtw` and `sxtw` a 32-bit one
+            if (option & 1 == 1) != index.sf {
+                return Err(ErrorKind::SizeMismatch);
+            }
+
+            let s = match amount {
+                None => 0,
+                Some(0) if scale != 0 => 0,
+                Some(amount) if amount == scale => 1,
+                Some(amount) => return Err(ErrorKind::ImmediateOutOfRange(amount as i64)),
+            };
+
+            Ok(word | 1 << 21 | zr(index)? << 16 | option << 13 | s << 12 | 0b10 << 10)
+        }
+        _ => Err(ErrorKind::InvalidOperands),
+    }
+}
+
+/// Load or store of a pair of registers.
+fn pair(mnemonic: &str, ops: &[Operand]) -> Result<u32, ErrorKind> {
+    let (rt, rt2, mem, post_index) = match ops {
+        [rt, rt2, Operand::Mem(mem)] => (rt, rt2, mem, None),
+        [rt, rt2, Operand::Mem(mem), Operand::Imm(offset)] => (rt, rt2, mem, Some(*offset)),
+        _ => return Err(ErrorKind::InvalidOperands),
+    };
+
+    let load = mnemonic.starts_with("ld") as u32;
+    let non_temporal = mnemonic.ends_with("np");
+
+    let (opc, v, scale, rt, rt2) = match (rt, rt2) {
+        (Operand::Reg(rt), Operand::Reg(rt2)) if mnemonic == "ldpsw" => {
+            if !rt.sf || !rt2.sf {
+                return Err(ErrorKind::SizeMismatch);
+            }
+
+            (1, 0, 2, zr(*rt)?, zr(*rt2)?)
+        }
+        (Operand::Reg(rt), Operand::Reg(rt2)) => {
+            same_size(&[*rt, *rt2])?;
+            (rt.sf() << 1, 0, 2 + rt.sf(), zr(*rt)?, zr(*rt2)?)
+        }
+        (Operand::Fp(rt), Operand::Fp(rt2)) if mnemonic != "ldpsw" && rt.size >= 2 => {
+            if rt.size != rt2.size {
+                return Err(ErrorKind::SizeMismatch);
+            }
+
+            (rt.size - 2, 1, rt.size, rt.num, rt2.num)
+        }
+        _ => return Err(ErrorKind::InvalidOperands),
+    };
+
+    let (mode, offset) = match (post_index, mem.pre_index) {
+        _ if mem.index.is_some() => return Err(ErrorKind::InvalidOperands),
+        (Some(offset), false) if mem.offset == 0 && !non_temporal => (1, offset),
+        (None, true) if !non_temporal => (3, mem.offset),
+        (None, false) => (if non_temporal { 0 } else { 2 }, mem.offset),
+        _ => return Err(ErrorKind::InvalidOperands),
+    };
+
+    if offset & ((1 << scale) - 1) != 0 {
+        return Err(ErrorKind::ImmediateOutOfRange(offset));
+    }
+
+    let imm7 = simm(offset >> scale, 7).map_err(|_| ErrorKind::ImmediateOutOfRange(offset))?;
+    Ok(opc << 30
+        | 0x28000000
+        | v << 26
+        | mode << 23
+        | load << 22
+        | imm7 << 15
+        | rt2 << 10
+        | sp(mem.base)? << 5
+        | rt)
+}
@@ -0,0 +1,123 @@
+//! Assembler for x86-64 and AArch64, accepting the Intel and ARM syntax the decoders emit.
+//!
+//! Instructions are assembled one at a time, branch targets and symbols are absolute addresses
+//! that are encoded relative to where the instruction is placed. `$` refers to the address of
+//! the instruction, such that `b $+0x8` assembles the same as the decoder shows it.
+
+mod aarch64;
+mod parse;
+mod tests;
+mod x86_64;
+
+use std::fmt;
+
+use parse::Parser;
+
+/// Architectures instructions can be assembled for.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Arch {
+    X86_64,
+    Aarch64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Error {
+    /// What went wrong in assembling the instruction.
+    pub kind: ErrorKind,
+
+    /// Source of the instruction that failed to assemble.
+    pub src: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ErrorKind {
+    /// There's no instruction with this name.
+    UnknownMnemonic(String),
+    /// A name that's neither a register nor a known symbol.
+    UnknownSymbol(String),
+    /// The operands don't match any encoding of the instruction.
+    InvalidOperands,
+    /// Operands of different sizes that have to be the same size, e.g. `mov eax, rbx`.
+    SizeMismatch,
+    /// A memory operand that doesn't say how many bytes it accesses, e.g. `inc [rax]`.
+    AmbiguousSize,
+    /// The immediate can't be encoded in the instruction.
+    ImmediateOutOfRange(i64),
+    /// The branch target is further away than the instruction can reach.
+    TargetOutOfRange(u64),
+    /// The branch target isn't aligned to what the instruction can encode.
+    MisalignedTarget(u64),
+    /// The source couldn't be parsed, holds what was expected.
+    Syntax(&'static str),
+}
+
+/// An assembled instruction.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Encoded {
+    /// Address the instruction is placed at.
+    pub addr: u64,
+    /// Source the instruction was assembled from.
+    pub src: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Assemble instructions separated by `;` or newlines, placing the first at `addr`.
+///
+/// Names that aren't registers are looked up in `symbols`.
+pub fn assemble(
+    arch: Arch,
+    addr: u64,
+    src: &str,
+    symbols: &dyn Fn(&str) -> Option<u64>,
+) -> Result<Vec<Encoded>, Error> {
+    let mut addr = addr;
+    let mut encoded = Vec::new();
+
+    for line in src.split([';', '\n']).map(str::trim).filter(|line| !line.is_empty()) {
+        let mut parser = Parser::new(line, addr, symbols);
+        let bytes = match arch {
+            Arch::X86_64 => x86_64::assemble(&mut parser),
+            Arch::Aarch64 => aarch64::assemble(&mut parser),
+        };
+
+        let bytes = bytes.map_err(|kind| Error {
+            kind,
+            src: line.to_string(),
+        })?;
+
+        encoded.push(Encoded {
+            addr,
+            src: line.to_string(),
+            bytes,
+        });
+
+        addr = addr.wrapping_add(encoded.last().unwrap().bytes.len() as u64);
+    }
+
+    Ok(encoded)
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownMnemonic(name) => write!(f, "unknown instruction '{name}'"),
+            Self::UnknownSymbol(name) => write!(f, "unknown symbol '{name}'"),
+            Self::InvalidOperands => f.write_str("invalid operands"),
+            Self::SizeMismatch => f.write_str("operand sizes don't match"),
+            Self::AmbiguousSize => f.write_str("operand size has to be specified"),
+            Self::ImmediateOutOfRange(imm) if *imm < 0 => {
+                write!(f, "immediate -{:#x} is out of range", imm.unsigned_abs())
+            }
+            Self::ImmediateOutOfRange(imm) => write!(f, "immediate {imm:#x} is out of range"),
+            Self::TargetOutOfRange(addr) => write!(f, "target {addr:#x} is out of range"),
+            Self::MisalignedTarget(addr) => write!(f, "target {addr:#x} is misaligned"),
+            Self::Syntax(expected) => write!(f, "expected {expected}"),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} in '{}'", self.kind, self.src)
+    }
+}
@@ -0,0 +1,214 @@
+//! Lexing of operands and expressions shared by the architectures.
+
+use crate::ErrorKind;
+
+/// Cursor over the source of a single instruction.
+pub struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    /// Address the instruction is placed at, the value of `$`.
+    pub addr: u64,
+    symbols: &'a dyn Fn(&str) -> Option<u64>,
+}
+
+/// Value of an expression.
+#[derive(Debug, Copy, Clone)]
+pub struct Expr {
+    pub value: i64,
+    /// Whether the expression refers to an address, either by a symbol or `$`.
+    pub relocatable: bool,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(src: &'a str, addr: u64, symbols: &'a dyn Fn(&str) -> Option<u64>) -> Self {
+        Self {
+            src,
+            pos: 0,
+            addr,
+            symbols,
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Whether all of the source was consumed.
+    pub fn is_empty(&mut self) -> bool {
+        self.skip_whitespace();
+        self.rest().is_empty()
+    }
+
+    /// Fail if there's anything left to parse.
+    pub fn end(&mut self) -> Result<(), ErrorKind> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(ErrorKind::Syntax("end of instruction"))
+        }
+    }
+
+    pub fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.rest().chars().next()
+    }
+
+    /// Consume `c` if it's next.
+    pub fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            return true;
+        }
+
+        false
+    }
+
+    pub fn expect(&mut self, c: char, expected: &'static str) -> Result<(), ErrorKind> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(ErrorKind::Syntax(expected))
+        }
+    }
+
+    /// Name of a register, mnemonic or symbol that's next, without consuming it.
+    pub fn peek_ident(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '.') {
+            return None;
+        }
+
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .unwrap_or(rest.len());
+
+        Some(&rest[..len])
+    }
+
+    pub fn ident(&mut self) -> Option<&'a str> {
+        let ident = self.peek_ident()?;
+        self.pos += ident.len();
+        Some(ident)
+    }
+
+    /// Consume `keyword` if it's the next identifier, ignoring case.
+    pub fn eat_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek_ident() {
+            Some(ident) if ident.eq_ignore_ascii_case(keyword) => {
+                self.pos += ident.len();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Consume the identifier if `f` recognizes it.
+    pub fn ident_with<T>(&mut self, f: impl FnOnce(&str) -> Option<T>) -> Option<T> {
+        let value = f(&self.peek_ident()?.to_ascii_lowercase())?;
+        self.ident();
+        Some(value)
+    }
+
+    /// Address of a symbol.
+    pub fn symbol(&self, name: &str) -> Result<u64, ErrorKind> {
+        (self.symbols)(name).ok_or_else(|| ErrorKind::UnknownSymbol(name.to_string()))
+    }
+
+    fn number(&mut self) -> Result<i64, ErrorKind> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let (radix, digits) = match rest.get(..2) {
+            Some("0x" | "0X") => (16, &rest[2..]),
+            Some("0b" | "0B") => (2, &rest[2..]),
+            _ => (10, rest),
+        };
+
+        let len = digits.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(digits.len());
+        let value = u64::from_str_radix(&digits[..len], radix)
+            .map_err(|_| ErrorKind::Syntax("a number"))?;
+
+        self.pos += rest.len() - digits.len() + len;
+        Ok(value as i64)
+    }
+
+    /// Number, symbol, `$` or parenthesized expression.
+    pub fn term(&mut self) -> Result<Expr, ErrorKind> {
+        match self.peek() {
+            Some('(') => {
+                self.eat('(');
+                let expr = self.expr()?;
+                self.expect(')', "')'")?;
+                Ok(expr)
+            }
+            Some('$') => {
+                self.eat('$');
+                Ok(Expr {
+                    value: self.addr as i64,
+                    relocatable: true,
+                })
+            }
+            // symbols as the decoders show them, allowing for names that aren't identifiers
+            Some('<') => {
+                self.eat('<');
+                let rest = self.rest();
+                let len = rest.find('>').ok_or(ErrorKind::Syntax("'>'"))?;
+                let addr = self.symbol(rest[..len].trim())?;
+                self.pos += len + 1;
+                Ok(Expr {
+                    value: addr as i64,
+                    relocatable: true,
+                })
+            }
+            Some(c) if c.is_ascii_digit() => Ok(Expr {
+                value: self.number()?,
+                relocatable: false,
+            }),
+            _ => match self.ident() {
+                Some(name) => Ok(Expr {
+                    value: self.symbol(name)? as i64,
+                    relocatable: true,
+                }),
+                None => Err(ErrorKind::Syntax("an expression")),
+            },
+        }
+    }
+
+    /// Sum of numbers and symbols, e.g. `main + 0x10`.
+    pub fn expr(&mut self) -> Result<Expr, ErrorKind> {
+        let negate = if self.eat('-') {
+            true
+        } else {
+            self.eat('+');
+            false
+        };
+
+        let mut expr = self.term()?;
+        if negate {
+            expr.value = expr.value.wrapping_neg();
+        }
+
+        loop {
+            let negate = if self.eat('+') {
+                false
+            } else if self.eat('-') {
+                true
+            } else {
+                return Ok(expr);
+            };
+
+            let term = self.term()?;
+            expr.relocatable |= term.relocatable;
+            expr.value = if negate {
+                expr.value.wrapping_sub(term.value)
+            } else {
+                expr.value.wrapping_add(term.value)
+            };
+        }
+    }
+}
@@ -0,0 +1,746 @@
+#![cfg(test)]
+
+use crate::{assemble, Arch, ErrorKind};
+use decoder::{Decodable, Decoded, FlowKind, Reader, ToTokens};
+
+fn symbols(name: &str) -> Option<u64> {
+    match name {
+        "main" => Some(0x401000),
+        "std::rt::lang_start" => Some(0x402000),
+        _ => None,
+    }
+}
+
+fn encode(arch: Arch, addr: u64, src: &str) -> Vec<u8> {
+    match assemble(arch, addr, src, &symbols) {
+        Ok(encoded) => encoded.into_iter().flat_map(|inst| inst.bytes).collect(),
+        Err(err) => panic!("{err}"),
+    }
+}
+
+fn error(arch: Arch, src: &str) -> ErrorKind {
+    match assemble(arch, 0x1000, src, &symbols) {
+        Ok(encoded) => panic!("'{src}' assembled to {:02x?}", encoded[0].bytes),
+        Err(err) => err.kind,
+    }
+}
+
+/// Decode an instruction placed at `addr`, showing branch targets as absolute addresses.
+fn decode_x86(bytes: &[u8], addr: u64) -> Option<String> {
+    let mut reader = Reader::new(bytes);
+    let mut inst = x86_64::long_mode::Decoder::default().decode(&mut reader).ok()?;
+    if inst.width() != bytes.len() {
+        return None;
+    }
+
+    if addr != 0 {
+        inst.update_rel_addrs(addr as usize, None);
+    }
+
+    let mut stream = tokenizing::TokenStream::new();
+    inst.tokenize(&mut stream, &debugvault::Index::default());
+    Some(stream.to_string())
+}
+
+fn decode_a64(bytes: &[u8]) -> Option<String> {
+    let mut reader = Reader::new(bytes);
+    let inst = arm::armv8::a64::Decoder::default().decode(&mut reader).ok()?;
+    Some(inst.to_string())
+}
+
+fn flow_x86(bytes: &[u8]) -> Option<FlowKind> {
+    let mut reader = Reader::new(bytes);
+    x86_64::long_mode::Decoder::default()
+        .decode(&mut reader)
+        .ok()
+        .map(|inst| inst.flow())
+}
+
+/// Assemble `text` and compare it to `bytes`, checking both sides against the decoder.
+fn test_x86(cases: &[(&[u8], &str)]) {
+    for (bytes, text) in cases {
+        assert_eq!(
+            decode_x86(bytes, 0).as_deref(),
+            Some(*text),
+            "decoding {bytes:02x?}"
+        );
+        assert_eq!(encode(Arch::X86_64, 0, text), *bytes, "assembling '{text}'");
+    }
+}
+
+fn test_a64(cases: &[([u8; 4], &str)]) {
+    for (bytes, text) in cases {
+        assert_eq!(
+            decode_a64(bytes).as_deref(),
+            Some(*text),
+            "decoding {bytes:02x?}"
+        );
+        assert_eq!(
+            encode(Arch::Aarch64, 0x1000, text),
+            *bytes,
+            "assembling '{text}'"
+        );
+    }
+}
+
+/// Test cases from the decoder's tests in the form `test_display(&[0x.., ..], "..")`.
+fn display_tests(src: &str) -> Vec<(Vec<u8>, String)> {
+    let mut cases = Vec::new();
+    for case in src.split("test_display(").skip(1) {
+        let case = case.trim_start();
+        let Some(case) = case.strip_prefix("&[") else {
+            continue;
+        };
+
+        let Some((bytes, rest)) = case.split_once(']') else {
+            continue;
+        };
+
+        let bytes = bytes
+            .split(',')
+            .map(str::trim)
+            .filter(|byte| !byte.is_empty())
+            .map(|byte| u8::from_str_radix(byte.trim_start_matches("0x"), 16))
+            .collect::<Result<Vec<u8>, _>>();
+
+        let text = rest.split('"').nth(1);
+        if let (Ok(bytes), Some(text)) = (bytes, text) {
+            cases.push((bytes, text.to_string()));
+        }
+    }
+
+    cases
+}
+
+/// Test cases from the decoder's tests in the form `([0x.., 0x.., 0x.., 0x..], "..")`.
+fn table_tests(src: &str) -> Vec<([u8; 4], String)> {
+    let mut cases = Vec::new();
+    for line in src.lines() {
+        let Some(case) = line.trim().strip_prefix("([") else {
+            continue;
+        };
+
+        let Some((bytes, rest)) = case.split_once(']') else {
+            continue;
+        };
+
+        let bytes = bytes
+            .split(',')
+            .map(|byte| u8::from_str_radix(byte.trim().trim_start_matches("0x"), 16))
+            .collect::<Result<Vec<u8>, _>>();
+
+        let text = rest.split('"').nth(1);
+        if let (Ok(Ok(bytes)), Some(text)) = (bytes.map(<[u8; 4]>::try_from), text) {
+            cases.push((bytes, text.to_string()));
+        }
+    }
+
+    cases
+}
+
+/// Forms in the decoder's tests that the assembler doesn't support.
+struct Unsupported {
+    /// Mnemonics that can't be assembled in any form.
+    mnemonics: &'static [&'static str],
+    /// Kinds of registers (named without their number) and keywords that can't be operands.
+    operands: &'static [&'static str],
+    /// Instructions that can't be assembled for any other reason.
+    instructions: &'static [&'static str],
+}
+
+impl Unsupported {
+    /// Entries explaining why `text` can't be assembled.
+    fn reasons(&self, text: &str) -> Vec<&'static str> {
+        let mut words = text
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .filter(|word| !word.is_empty());
+        let mnemonic = words.next().unwrap_or_default();
+        let kinds: Vec<&str> = words
+            .filter_map(|word| word.split(|c: char| c.is_ascii_digit()).next())
+            .collect();
+
+        let mnemonics = self.mnemonics.iter().filter(|entry| **entry == mnemonic);
+        let operands = self.operands.iter().filter(|entry| kinds.contains(entry));
+        let instructions = self.instructions.iter().filter(|entry| **entry == text);
+        mnemonics.chain(operands).chain(instructions).copied().collect()
+    }
+
+    /// Every failure has to be explained by an entry, and every entry has to explain a failure so
+    /// forms that became supported are checked from then on.
+    fn check(&self, failures: &[String]) {
+        let mut used = std::collections::HashSet::new();
+        let mut unexplained = Vec::new();
+        for text in failures {
+            let reasons = self.reasons(text);
+            if reasons.is_empty() {
+                unexplained.push(text.as_str());
+            }
+            used.extend(reasons);
+        }
+
+        assert!(
+            unexplained.is_empty(),
+            "failed to assemble {unexplained:#?}"
+        );
+
+        let entries = self.mnemonics.iter().chain(self.operands).chain(self.instructions);
+        let stale: Vec<_> = entries.filter(|entry| !used.contains(*entry)).collect();
+        assert!(stale.is_empty(), "{stale:?} can be assembled now");
+    }
+}
+
+#[test]
+fn x86_mov() {
+    test_x86(&[
+        (&[0x48, 0x89, 0xd8], "mov rax, rbx"),
+        (&[0x89, 0xc8], "mov eax, ecx"),
+        (&[0x66, 0x89, 0xc8], "mov ax, cx"),
+        (&[0x88, 0xc8], "mov al, cl"),
+        (&[0x40, 0xb4, 0x01], "mov spl, 0x1"),
+        (&[0xb8, 0x01, 0x00, 0x00, 0x00], "mov eax, 0x1"),
+        (&[0x41, 0xb8, 0xff, 0xff, 0xff, 0xff], "mov r8d, -0x1"),
+        (&[0x48, 0xc7, 0xc0, 0x01, 0x00, 0x00, 0x00], "mov rax, 0x1"),
+        (
+            &[0x48, 0x89, 0x7c, 0x24, 0x08],
+            "mov qword [rsp + 0x8], rdi",
+        ),
+        (&[0x48, 0x8b, 0x45, 0xf0], "mov rax, qword [rbp - 0x10]"),
+        (&[0x4c, 0x8b, 0x6d, 0x00], "mov r13, qword [rbp]"),
+        (&[0x4b, 0x8b, 0x04, 0xe4], "mov rax, qword [r12 + r12 * 8]"),
+        (&[0xc6, 0x00, 0x00], "mov byte [rax], 0x0"),
+        (
+            &[0xc7, 0x40, 0x04, 0x78, 0x56, 0x34, 0x12],
+            "mov dword [rax + 0x4], 0x12345678",
+        ),
+        (
+            &[0x89, 0x05, 0x78, 0x56, 0x34, 0x12],
+            "mov dword [rip + 0x12345678], eax",
+        ),
+        (
+            &[0x64, 0x48, 0x8b, 0x04, 0x25, 0x28, 0x00, 0x00, 0x00],
+            "mov rax, qword fs:[0x28]",
+        ),
+        (&[0x66, 0x8c, 0xd8], "mov ax, ds"),
+        (&[0x8e, 0xd8], "mov ds, ax"),
+        (&[0x48, 0x0f, 0xb6, 0x07], "movzx rax, byte [rdi]"),
+        (&[0x0f, 0xbf, 0xc1], "movsx eax, cx"),
+        (&[0x48, 0x63, 0x47, 0x04], "movsxd rax, dword [rdi + 0x4]"),
+        (&[0x48, 0x8d, 0x0c, 0x12], "lea rcx, qword [rdx + rdx * 1]"),
+        (
+            &[0x48, 0x8d, 0x44, 0x8a, 0xf8],
+            "lea rax, qword [rdx + rcx * 4 - 0x8]",
+        ),
+        (&[0x91], "xchg eax, ecx"),
+        (&[0x87, 0x1f], "xchg dword [rdi], ebx"),
+    ]);
+}
+
+#[test]
+fn x86_arithmetic() {
+    test_x86(&[
+        (&[0x48, 0x83, 0xc4, 0x08], "add rsp, 0x8"),
+        (
+            &[0x48, 0x81, 0xec, 0x18, 0x01, 0x00, 0x00],
+            "sub rsp, 0x118",
+        ),
+        (&[0x05, 0x45, 0x23, 0x01, 0x00], "add eax, 0x12345"),
+        (&[0x48, 0x83, 0xf8, 0xff], "cmp rax, -0x1"),
+        (&[0x24, 0x0f], "and al, 0xf"),
+        (&[0x80, 0x4f, 0x01, 0x80], "or byte [rdi + 0x1], -0x80"),
+        (&[0x31, 0xc0], "xor eax, eax"),
+        (&[0x4d, 0x29, 0xc8], "sub r8, r9"),
+        (&[0x03, 0x47, 0x10], "add eax, dword [rdi + 0x10]"),
+        (&[0x85, 0xff], "test edi, edi"),
+        (&[0xa8, 0x01], "test al, 0x1"),
+        (&[0xf6, 0x47, 0x01, 0x02], "test byte [rdi + 0x1], 0x2"),
+        (&[0x48, 0xf7, 0xd8], "neg rax"),
+        (&[0xf7, 0xf1], "div ecx"),
+        (&[0x48, 0x0f, 0xaf, 0xc1], "imul rax, rcx"),
+        (&[0x6b, 0xc1, 0x64], "imul eax, ecx, 0x64"),
+        (&[0x48, 0xff, 0xc0], "inc rax"),
+        (&[0xfe, 0x0f], "dec byte [rdi]"),
+        (&[0x48, 0xc1, 0xe0, 0x04], "shl rax, 0x4"),
+        (&[0xd3, 0xfa], "sar edx, cl"),
+        (&[0x0f, 0x94, 0xc0], "setz al"),
+        (&[0x0f, 0x43, 0xc1], "cmovnb eax, ecx"),
+        (&[0x48, 0x0f, 0xbc, 0xc7], "bsf rax, rdi"),
+        (&[0xf3, 0x48, 0x0f, 0xb8, 0xc7], "popcnt rax, rdi"),
+        (&[0x48, 0x0f, 0xc9], "bswap rcx"),
+        (
+            &[0xf0, 0x48, 0x0f, 0xb1, 0x37],
+            "lock cmpxchg qword [rdi], rsi",
+        ),
+    ]);
+}
+
+#[test]
+fn x86_misc() {
+    test_x86(&[
+        (&[0x55], "push rbp"),
+        (&[0x41, 0x54], "push r12"),
+        (&[0x41, 0x5f], "pop r15"),
+        (&[0x0f, 0xa0], "push fs"),
+        (
+            &[0xff, 0x15, 0xe2, 0x2f, 0x00, 0x00],
+            "call qword [rip + 0x2fe2]",
+        ),
+        (&[0xff, 0xe0], "jmp rax"),
+        (&[0xc3], "ret"),
+        (&[0x90], "nop"),
+        (&[0xcc], "int 0x3"),
+        (&[0xcd, 0x80], "int 0x80"),
+        (&[0x0f, 0x05], "syscall"),
+        (&[0xc9], "leave"),
+        (&[0x0f, 0x0b], "ud2"),
+        (&[0x48, 0x99], "cqo"),
+        (&[0xf3, 0x0f, 0x1e, 0xfa], "endbr64"),
+        (&[0x0f, 0x1f, 0x00], "nop dword [rax]"),
+        (&[0x66, 0x0f, 0x1f, 0x04, 0x00], "nop word [rax + rax * 1]"),
+        (&[0xf3, 0x48, 0xab], "rep stos qword es:[rdi], rax"),
+        (
+            &[0x0f, 0x28, 0x44, 0x24, 0x10],
+            "movaps xmm0, xmmword [rsp + 0x10]",
+        ),
+        (&[0x66, 0x48, 0x0f, 0x6e, 0xc0], "movq xmm0, rax"),
+        (&[0x66, 0x0f, 0x7e, 0xc1], "movd ecx, xmm0"),
+        (&[0x66, 0x0f, 0xef, 0xc0], "pxor xmm0, xmm0"),
+        (&[0xf2, 0x0f, 0x2a, 0x00], "cvtsi2sd xmm0, dword [rax]"),
+        (&[0xf2, 0x41, 0x0f, 0x59, 0xc1], "mulsd xmm0, xmm9"),
+    ]);
+}
+
+#[test]
+fn x86_branches() {
+    let cases = [
+        ("jmp 0x1010", &[0xeb, 0x0e][..]),
+        ("jmp 0x2000", &[0xe9, 0xfb, 0x0f, 0x00, 0x00]),
+        ("jz 0x2000", &[0x0f, 0x84, 0xfa, 0x0f, 0x00, 0x00]),
+        ("jnz 0xff0", &[0x75, 0xee]),
+        ("call 0x1000", &[0xe8, 0xfb, 0xff, 0xff, 0xff]),
+        ("loop 0x1000", &[0xe2, 0xfe]),
+    ];
+
+    for (text, bytes) in cases {
+        assert_eq!(
+            encode(Arch::X86_64, 0x1000, text),
+            bytes,
+            "assembling '{text}'"
+        );
+        assert_eq!(decode_x86(bytes, 0x1000).as_deref(), Some(text));
+    }
+
+    assert_eq!(encode(Arch::X86_64, 0x401000, "jmp $"), [0xeb, 0xfe]);
+    assert_eq!(
+        encode(Arch::X86_64, 0x400000, "call main"),
+        [0xe8, 0xfb, 0x0f, 0x00, 0x00]
+    );
+    assert_eq!(
+        encode(Arch::X86_64, 0x400000, "call <std::rt::lang_start>"),
+        [0xe8, 0xfb, 0x1f, 0x00, 0x00]
+    );
+
+    // symbols in memory operands are relative to the end of the instruction
+    assert_eq!(
+        encode(Arch::X86_64, 0x400000, "mov eax, dword [main + 0x10]"),
+        [0x8b, 0x05, 0x0a, 0x10, 0x00, 0x00]
+    );
+    assert_eq!(
+        encode(Arch::X86_64, 0x400000, "mov dword [rip + main], 0x1"),
+        [0xc7, 0x05, 0xf6, 0x0f, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]
+    );
+}
+
+#[test]
+fn x86_sequence() {
+    let encoded = assemble(
+        Arch::X86_64,
+        0x1000,
+        "push rbp; mov rbp, rsp\njmp 0x1000",
+        &symbols,
+    );
+    let encoded = encoded.unwrap();
+
+    assert_eq!(encoded.len(), 3);
+    assert_eq!(encoded[1].addr, 0x1001);
+    assert_eq!(encoded[1].src, "mov rbp, rsp");
+    assert_eq!(encoded[2].addr, 0x1004);
+    assert_eq!(encoded[2].bytes, [0xeb, 0xfa]);
+}
+
+#[test]
+fn x86_errors() {
+    assert_eq!(
+        error(Arch::X86_64, "frobnicate rax"),
+        ErrorKind::UnknownMnemonic("frobnicate".into())
+    );
+    assert_eq!(error(Arch::X86_64, "mov eax, rbx"), ErrorKind::SizeMismatch);
+    assert_eq!(error(Arch::X86_64, "inc [rax]"), ErrorKind::AmbiguousSize);
+    assert_eq!(
+        error(Arch::X86_64, "mov al, 0x100"),
+        ErrorKind::ImmediateOutOfRange(0x100)
+    );
+    assert_eq!(
+        error(Arch::X86_64, "add rax, 0x80000000"),
+        ErrorKind::ImmediateOutOfRange(0x80000000)
+    );
+    assert_eq!(
+        error(Arch::X86_64, "mov ah, sil"),
+        ErrorKind::InvalidOperands
+    );
+    assert_eq!(
+        error(Arch::X86_64, "lea rax, rbx"),
+        ErrorKind::InvalidOperands
+    );
+    assert_eq!(
+        error(Arch::X86_64, "mov rax, [rsp * 2]"),
+        ErrorKind::InvalidOperands
+    );
+    assert_eq!(
+        error(Arch::X86_64, "call foo"),
+        ErrorKind::UnknownSymbol("foo".into())
+    );
+    assert_eq!(
+        error(Arch::X86_64, "mov rax, [rbx"),
+        ErrorKind::Syntax("']'")
+    );
+    assert_eq!(
+        error(Arch::X86_64, "jmp 0x100001000"),
+        ErrorKind::TargetOutOfRange(0x100001000)
+    );
+}
+
+/// What the x86 assembler can't encode from the decoder's display tests.
+#[rustfmt::skip]
+const X86_UNSUPPORTED: Unsupported = Unsupported {
+    mnemonics: &[
+        "adcx", "adox", "aesdeclast", "aesimc", "blendpd", "clac", "clflush", "clflushopt", "clgi",
+        "clrssbsy", "clts", "clui", "clwb", "clzero", "cmpss", "cmpxchg16b", "cmpxchg8b", "crc32",
+        "cvtdq2ps", "cvtpi2pd", "cvtpi2ps", "cvttps2dq", "dppd", "encls", "enclu", "enclv",
+        "encodekey128", "encodekey256", "enqcmd", "enqcmds", "enter", "f2xm1", "fabs", "fchs",
+        "fcompp", "fcos", "fdecstp", "fdisi8087_nop", "femms", "feni8087_nop", "fincstp", "fld1",
+        "fldcw", "fldl2e", "fldl2t", "fldlg2", "fldln2", "fldpi", "fldz", "fnclex", "fninit",
+        "fnop", "fnstcw", "fnstsw", "fpatan", "fprem", "fprem1", "fptan", "frndint", "fscale",
+        "fsetpm287_nop", "fsin", "fsincos", "fsqrt", "ftst", "fucompp", "fxam", "fxtract", "fyl2x",
+        "fyl2xp1", "getsec", "gf2p8affineinvqb", "gf2p8affineqb", "gf2p8mulb", "hreset", "in",
+        "incssp", "insertps", "invept", "invlpg", "invlpga", "invlpgb", "invpcid", "invvpid",
+        "iret", "iretd", "lar", "lddqu", "ldmxcsr", "lmsw", "loadiwkey", "lsl", "lss", "monitor",
+        "monitorx", "movbe", "movdir64b", "movdiri", "movhlps", "movhps", "movlhps", "movlps",
+        "movmskps", "movntdqa", "movnti", "movntps", "movshdup", "mpsadbw", "mwait", "mwaitx",
+        "out", "pclmulqdq", "pconfig", "pextrw", "pmaxud", "pmovsxwd", "prefetch0", "prefetch1",
+        "prefetch2", "prefetchnta", "prefetchw", "pshufhw", "psmash", "ptwrite", "pvalidate",
+        "rcpps", "rcpss", "rdfsbase", "rdgsbase", "rdmsr", "rdpid", "rdpkru", "rdpmc", "rdpru",
+        "rdrand", "rdseed", "retf", "rmpadjust", "rmpupdate", "rsqrtps", "rsqrtss", "rstorssp",
+        "salc", "saveprevssp", "seamcall", "seamops", "seamret", "senduipi", "setssbsy",
+        "sha1msg1", "sha1msg2", "sha1nexte", "sha1rnds4", "sha256msg1", "sha256msg2",
+        "sha256rnds2", "shld", "shrd", "skinit", "smsw", "stac", "stgi", "stmxcsr", "stui",
+        "swapgs", "sysenter", "sysexit", "tdcall", "testui", "tlbsync", "tpause", "ud0", "uiret",
+        "umonitor", "umwait", "vcompresspd", "vfnmadd213ss", "vmcall", "vmclear", "vmfunc",
+        "vmlaunch", "vmload", "vmmcall", "vmovntdqa", "vmptrld", "vmptrst", "vmread", "vmresume",
+        "vmrun", "vmsave", "vmwrite", "vmxoff", "vmxon", "vpscatterdd", "vunpcklps", "wrfsbase",
+        "wrgsbase", "wrmsr", "wrpkru", "wrss", "wrssd", "wruss", "xabort", "xacquire", "xbegin",
+        "xend", "xgetbv", "xresldtrk", "xsetbv", "xsusldtrk", "xtest",
+    ],
+    operands: &[
+        // x87, MMX, debug and control registers
+        "st", "mm", "dr", "cr",
+        // registers only encodable with VEX or EVEX
+        "ymm", "zmm",
+        // operand sizes the decoder shows for memory that isn't a plain integer
+        "ptr", "mword",
+    ],
+    instructions: &[
+        "nop dword [rdi], ecx",
+        "nop esp, edi",
+        "nop zmmword [r8]",
+        "nop zmmword [rax]",
+    ],
+};
+
+/// Everything the decoder's display tests show has to assemble to something that decodes the
+/// same, unless it's a form listed in [`X86_UNSUPPORTED`].
+#[test]
+fn x86_conformance() {
+    let src = include_str!("../../decoder-x86_64/src/long_mode/tests/mod.rs");
+    let mut failures = Vec::new();
+
+    for (bytes, text) in display_tests(src) {
+        // relative branches are shown relative to the instruction rather than absolute
+        let flow = flow_x86(&bytes);
+        if matches!(
+            flow,
+            Some(FlowKind::Jump | FlowKind::ConditionalJump | FlowKind::Call)
+        ) {
+            continue;
+        }
+
+        let Ok(encoded) = assemble(Arch::X86_64, 0, &text, &symbols) else {
+            failures.push(text);
+            continue;
+        };
+
+        let ours = &encoded[0].bytes;
+        if *ours != bytes {
+            assert_eq!(
+                decode_x86(ours, 0).as_deref(),
+                Some(text.as_str()),
+                "'{text}' assembled to {ours:02x?} rather than {bytes:02x?}"
+            );
+        }
+    }
+
+    X86_UNSUPPORTED.check(&failures);
+}
+
+#[test]
+fn a64_arithmetic() {
+    test_a64(&[
+        ([0x61, 0x06, 0x13, 0x0b], "add w1, w19, w19, lsl #1"),
+        ([0x08, 0x7d, 0x48, 0x0b], "add w8, w8, w8, lsr #31"),
+        ([0x02, 0xfc, 0x3f, 0x11], "add w2, w0, #0xfff"),
+        ([0x00, 0x00, 0x60, 0x11], "add w0, w0, #0x800, lsl #12"),
+        ([0x0a, 0x0c, 0x00, 0x31], "adds w10, w0, #0x3"),
+        ([0x62, 0xce, 0x21, 0x8b], "add x2, x19, w1, sxtw #3"),
+        ([0x2a, 0x41, 0x22, 0x8b], "add x10, x9, w2"),
+        ([0x18, 0x4f, 0x22, 0x8b], "add x24, x24, w2, lsl #3"),
+        ([0xff, 0x43, 0x00, 0x91], "add sp, sp, #0x10"),
+        ([0xfd, 0x43, 0x00, 0x91], "add x29, sp, #0x10"),
+        ([0xbf, 0x06, 0x1b, 0xeb], "cmp x21, x27, lsl #1"),
+        ([0xff, 0x63, 0x21, 0xeb], "cmp sp, x1"),
+        ([0x5f, 0xc0, 0x21, 0xeb], "cmp x2, w1, sxtw"),
+        ([0x3f, 0x08, 0x40, 0xf1], "cmp x1, #0x2, lsl #12"),
+        ([0x1f, 0x58, 0x00, 0x31], "cmn w0, #0x16"),
+        ([0xa1, 0x72, 0x00, 0x1b], "madd w1, w21, w0, w28"),
+        ([0x9c, 0x7f, 0x1b, 0x9b], "mul x28, x28, x27"),
+        ([0x02, 0x7f, 0x3a, 0x9b], "smull x2, w24, w26"),
+        ([0x63, 0x98, 0xa4, 0x9b], "umsubl x3, w3, w4, x6"),
+        ([0xa0, 0x0e, 0xc1, 0x1a], "sdiv w0, w21, w1"),
+        ([0x39, 0x08, 0xc0, 0x1a], "udiv w25, w1, w0"),
+    ]);
+}
+
+#[test]
+fn a64_logical() {
+    test_a64(&[
+        ([0x42, 0x2c, 0x00, 0x12], "and w2, w2, #0xfff"),
+        ([0x08, 0x79, 0x1a, 0x12], "and w8, w8, #0xffffffdf"),
+        ([0xa5, 0xf0, 0x7d, 0x92], "and x5, x5, #0xfffffffffffffff8"),
+        ([0x00, 0x07, 0x7f, 0x92], "and x0, x24, #0x6"),
+        ([0x88, 0x0a, 0x40, 0xf2], "ands x8, x20, #0x7"),
+        ([0x1e, 0x00, 0x11, 0x32], "orr w30, w0, #0x8000"),
+        ([0x48, 0x01, 0x08, 0xca], "eor x8, x10, x8"),
+        ([0x63, 0x00, 0x05, 0x6a], "ands w3, w3, w5"),
+        ([0x1f, 0x78, 0x1e, 0x72], "tst w0, #0xfffffffd"),
+        ([0x5f, 0x03, 0x13, 0xea], "tst x26, x19"),
+        ([0xfb, 0x03, 0x3c, 0xaa], "mvn x27, x28"),
+        ([0x0a, 0x7d, 0x02, 0x13], "asr w10, w8, #0x2"),
+        ([0xe0, 0x56, 0x16, 0x53], "lsl w0, w23, #0xa"),
+        ([0x00, 0xfc, 0x63, 0xd3], "lsr x0, x0, #0x23"),
+        ([0x79, 0xf3, 0x7d, 0xd3], "lsl x25, x27, #0x3"),
+        ([0xe9, 0x22, 0xce, 0x9a], "lsl x9, x23, x14"),
+        ([0xa1, 0x7e, 0x79, 0x93], "sbfiz x1, x21, #0x7, #0x20"),
+        ([0xc3, 0x0f, 0x7a, 0xd3], "ubfiz x3, x30, #0x6, #0x4"),
+    ]);
+}
+
+#[test]
+fn a64_mov() {
+    test_a64(&[
+        ([0xe5, 0x03, 0x1e, 0x2a], "mov w5, w30"),
+        ([0xe0, 0x03, 0x1f, 0x2a], "mov w0, wzr"),
+        ([0x20, 0x0f, 0x80, 0x52], "mov w0, #0x79"),
+        ([0xfd, 0x03, 0x00, 0x91], "mov x29, sp"),
+        ([0x40, 0x09, 0xe8, 0xf2], "movk x0, #0x404a, lsl #48"),
+        ([0x80, 0x99, 0xb9, 0x72], "movk w0, #0xcccc, lsl #16"),
+    ]);
+
+    let mov = |src| u32::from_le_bytes(encode(Arch::Aarch64, 0, src).try_into().unwrap());
+    assert_eq!(mov("mov w0, #0x42800000"), 0x52a85000);
+    assert_eq!(mov("mov x0, #-1"), 0x92800000);
+    assert_eq!(mov("mov w0, #0xfffffffe"), 0x12800020);
+    assert_eq!(mov("mov x0, #0x5555555555555555"), 0xb200f3e0);
+}
+
+#[test]
+fn a64_conditional() {
+    test_a64(&[
+        ([0x82, 0xd2, 0x80, 0x1a], "csel w2, w20, w0, le"),
+        ([0xe1, 0xa7, 0x81, 0x1a], "csinc w1, wzr, w1, ge"),
+        ([0x42, 0x54, 0x82, 0x1a], "cinc w2, w2, mi"),
+        ([0xf4, 0xc7, 0x9f, 0x1a], "cset w20, le"),
+        ([0x16, 0x04, 0x80, 0x5a], "cneg w22, w0, ne"),
+        ([0xfc, 0x03, 0x9f, 0x5a], "csetm w28, ne"),
+        ([0x84, 0x1b, 0x40, 0xfa], "ccmp x28, #0x0, #0x4, ne"),
+        ([0x64, 0x12, 0x43, 0xfa], "ccmp x19, x3, #0x4, ne"),
+        ([0x84, 0x1b, 0x4a, 0x3a], "ccmn w28, #0xa, #0x4, ne"),
+    ]);
+}
+
+#[test]
+fn a64_branches() {
+    test_a64(&[
+        ([0xcd, 0xff, 0xff, 0x34], "cbz w13, $-0x8"),
+        ([0x96, 0x00, 0x00, 0xb5], "cbnz x22, $+0x10"),
+        ([0x73, 0x00, 0xf8, 0x36], "tbz w19, #0x1f, $+0xc"),
+        ([0x23, 0xf8, 0xff, 0xb7], "tbnz x3, #0x3f, $-0xfc"),
+        ([0xfb, 0xff, 0xff, 0x17], "b $-0x14"),
+        ([0x6d, 0x02, 0x00, 0x54], "b.le $+0x4c"),
+        ([0x97, 0x1e, 0x00, 0x94], "bl $+0x7a5c"),
+        ([0x40, 0x00, 0x1f, 0xd6], "br x2"),
+        ([0x80, 0x03, 0x3f, 0xd6], "blr x28"),
+        ([0xc0, 0x03, 0x5f, 0xd6], "ret"),
+    ]);
+
+    assert_eq!(
+        encode(Arch::Aarch64, 0x400000, "bl main"),
+        [0x00, 0x04, 0x00, 0x94]
+    );
+    assert_eq!(
+        encode(Arch::Aarch64, 0x400ffc, "b.eq 0x401000"),
+        [0x20, 0x00, 0x00, 0x54]
+    );
+    assert_eq!(
+        encode(Arch::Aarch64, 0x400ffc, "adrp x0, main"),
+        [0x00, 0x00, 0x00, 0xb0]
+    );
+    assert_eq!(
+        encode(Arch::Aarch64, 0x400ffc, "adr x0, #0x401000"),
+        [0x20, 0x00, 0x00, 0x10]
+    );
+}
+
+#[test]
+fn a64_load_store() {
+    test_a64(&[
+        ([0x83, 0x68, 0x60, 0x38], "ldrb w3, [x4, x0]"),
+        ([0x82, 0x08, 0x40, 0x39], "ldrb w2, [x4, #0x2]"),
+        ([0x60, 0x02, 0x72, 0x39], "ldrb w0, [x19, #0xc80]"),
+        ([0x63, 0x03, 0x40, 0x29], "ldp w3, w0, [x27]"),
+        ([0x43, 0x80, 0x40, 0x69], "ldpsw x3, x0, [x2, #0x4]"),
+        ([0xfd, 0x7b, 0xc1, 0xa8], "ldp x29, x30, [sp], #0x10"),
+        ([0xfd, 0x7b, 0xbf, 0xa9], "stp x29, x30, [sp, #-0x10]!"),
+        ([0x29, 0x2d, 0x7f, 0xa9], "ldp x9, x11, [x9, #-0x10]"),
+        ([0xe0, 0x0f, 0x40, 0xf9], "ldr x0, [sp, #0x18]"),
+        ([0x00, 0x84, 0x40, 0xf8], "ldr x0, [x0], #0x8"),
+        ([0x1f, 0x8c, 0x00, 0xf8], "str xzr, [x0, #0x8]!"),
+        ([0xa0, 0x83, 0x5f, 0xf8], "ldur x0, [x29, #-0x8]"),
+        ([0x88, 0x7a, 0x77, 0xf8], "ldr x8, [x20, x23, lsl #3]"),
+        ([0x88, 0xda, 0x77, 0xb8], "ldr w8, [x20, w23, sxtw #2]"),
+        ([0x21, 0x78, 0xa0, 0xb8], "ldrsw x1, [x1, x0, lsl #2]"),
+        ([0x80, 0xff, 0xff, 0x18], "ldr w0, $-0x10"),
+        ([0xff, 0xff, 0x00, 0x9c], "ldr q31, $+0x1ffc"),
+        ([0xe0, 0x07, 0x00, 0xfd], "str d0, [sp, #0x8]"),
+        ([0x80, 0x00, 0x80, 0xf9], "prfm pldl1keep, [x4]"),
+    ]);
+
+    // offsets that can't be scaled are encoded unscaled
+    assert_eq!(
+        encode(Arch::Aarch64, 0, "ldr x0, [x29, #-0x8]"),
+        [0xa0, 0x83, 0x5f, 0xf8]
+    );
+}
+
+#[test]
+fn a64_system() {
+    test_a64(&[
+        ([0x1f, 0x20, 0x03, 0xd5], "nop"),
+        ([0xbf, 0x3b, 0x03, 0xd5], "dmb ish"),
+        ([0xdf, 0x3f, 0x03, 0xd5], "isb"),
+        ([0x01, 0x00, 0x00, 0xd4], "svc #0x0"),
+        ([0x00, 0x00, 0x20, 0xd4], "brk #0x0"),
+    ]);
+}
+
+#[test]
+fn a64_errors() {
+    assert_eq!(
+        error(Arch::Aarch64, "frobnicate x0"),
+        ErrorKind::UnknownMnemonic("frobnicate".into())
+    );
+    assert_eq!(
+        error(Arch::Aarch64, "add x0, w1, #0x1"),
+        ErrorKind::SizeMismatch
+    );
+    assert_eq!(
+        error(Arch::Aarch64, "add x0, x1, #0x1001"),
+        ErrorKind::ImmediateOutOfRange(0x1001)
+    );
+    assert_eq!(
+        error(Arch::Aarch64, "and x0, x1, #0x0"),
+        ErrorKind::ImmediateOutOfRange(0)
+    );
+    assert_eq!(
+        error(Arch::Aarch64, "mov w0, #0x123456789"),
+        ErrorKind::ImmediateOutOfRange(0x123456789)
+    );
+    assert_eq!(
+        error(Arch::Aarch64, "b 0x1002"),
+        ErrorKind::MisalignedTarget(0x1002)
+    );
+    assert_eq!(
+        error(Arch::Aarch64, "b.ne 0x200000"),
+        ErrorKind::TargetOutOfRange(0x200000)
+    );
+    assert_eq!(
+        error(Arch::Aarch64, "ldr x0, [x1, #0x10000]"),
+        ErrorKind::ImmediateOutOfRange(0x10000)
+    );
+    assert_eq!(
+        error(Arch::Aarch64, "add xzr, x0, #0x1"),
+        ErrorKind::InvalidOperands
+    );
+    assert_eq!(
+        error(Arch::Aarch64, "ldr x0, [xzr]"),
+        ErrorKind::Syntax("a base register")
+    );
+}
+
+/// What the AArch64 assembler can't encode from the decoder's table tests.
+#[rustfmt::skip]
+const A64_UNSUPPORTED: Unsupported = Unsupported {
+    mnemonics: &[
+        "addspl", "addvl", "autiasp", "autib1716", "autibsp", "autibz", "blraa", "braa", "braaz",
+        "bti", "cas", "casb", "casp", "cmgt", "cntb", "cntd", "cnth", "crc32cb", "crc32h",
+        "crc32x", "fabd", "fabs", "fadd", "fccmp", "fccmpe", "fcmeq", "fcmgt", "fcmp", "fcmpe",
+        "fcsel", "fcvtps", "fcvtzs", "fdiv", "fjcvtzs", "fmaxnm", "fminnm", "fmov", "fmsub",
+        "fmul", "fneg", "fnmadd", "fnmsub", "fnmul", "frecps", "fsqrt", "fsub", "ldaprb", "ldar",
+        "ldaxr", "ldraa", "ldrab", "movi", "mrs", "msr", "pacga", "pacia1716", "paciasp", "paciaz",
+        "pacib", "pacibsp", "rdsvl", "rdvl", "scvtf", "setf8", "smstart", "smstop", "sqincb",
+        "sqrdmlah", "sqshl", "sqshrn", "staddb", "stllrb", "stlr", "stlurb", "stlxr", "sttr",
+        "sttrb", "stxr", "stxrb", "uqincb", "xpaclri",
+    ],
+    operands: &[
+        // SIMD, SVE and SME registers, predicates and tiles
+        "v", "z", "za", "p",
+        // operands of system instructions and SVE element counts
+        "c", "vl",
+    ],
+    instructions: &[
+        "cmn w0, w0, lsr",
+    ],
+};
+
+/// Everything the decoder's table tests show has to assemble to something that decodes the same,
+/// unless it's a form listed in [`A64_UNSUPPORTED`].
+#[test]
+fn a64_conformance() {
+    let src = include_str!("../../decoder-arm/tests/armv8/a64.rs");
+    let mut failures = Vec::new();
+
+    for (bytes, text) in table_tests(src) {
+        let Ok(encoded) = assemble(Arch::Aarch64, 0x1000, &text, &symbols) else {
+            failures.push(text);
+            continue;
+        };
+
+        let ours = &encoded[0].bytes;
+        if *ours != bytes {
+            assert_eq!(
+                decode_a64(ours).as_deref(),
+                Some(text.as_str()),
+                "'{text}' assembled to {ours:02x?} rather than {bytes:02x?}"
+            );
+        }
+    }
+
+    A64_UNSUPPORTED.check(&failures);
+}
@@ -0,0 +1,1279 @@
+//! x86-64 instructions in Intel syntax, covering the general purpose instructions and the common
+//! SSE moves and arithmetic.
+
+use crate::parse::Parser;
+use crate::ErrorKind;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Bank {
+    Gpr,
+    /// `ah`, `ch`, `dh` and `bh`, which can't be encoded in an instruction with a REX prefix.
+    High,
+    Xmm,
+    Segment,
+    Rip,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+struct Reg {
+    num: u8,
+    /// Size in bytes.
+    size: u8,
+    bank: Bank,
+}
+
+impl Reg {
+    fn is_gpr(&self) -> bool {
+        matches!(self.bank, Bank::Gpr | Bank::High)
+    }
+
+    fn is_xmm(&self) -> bool {
+        self.bank == Bank::Xmm
+    }
+
+    /// Whether this is `al`, `ax`, `eax` or `rax`, which have shorter encodings.
+    fn is_accumulator(&self) -> bool {
+        self.bank == Bank::Gpr && self.num == 0
+    }
+}
+
+#[rustfmt::skip]
+const GPR_64: [&str; 16] = [
+    "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi",
+    "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15",
+];
+
+#[rustfmt::skip]
+const GPR_32: [&str; 16] = [
+    "eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi",
+    "r8d", "r9d", "r10d", "r11d", "r12d", "r13d", "r14d", "r15d",
+];
+
+#[rustfmt::skip]
+const GPR_16: [&str; 16] = [
+    "ax", "cx", "dx", "bx", "sp", "bp", "si", "di",
+    "r8w", "r9w", "r10w", "r11w", "r12w", "r13w", "r14w", "r15w",
+];
+
+#[rustfmt::skip]
+const GPR_8: [&str; 16] = [
+    "al", "cl", "dl", "bl", "spl", "bpl", "sil", "dil",
+    "r8b", "r9b", "r10b", "r11b", "r12b", "r13b", "r14b", "r15b",
+];
+
+const HIGH_8: [&str; 4] = ["ah", "ch", "dh", "bh"];
+
+const SEGMENTS: [&str; 6] = ["es", "cs", "ss", "ds", "fs", "gs"];
+
+/// Segment override prefixes, in the same order as [`SEGMENTS`].
+const SEGMENT_PREFIXES: [u8; 6] = [0x26, 0x2e, 0x36, 0x3e, 0x64, 0x65];
+
+/// Arithmetic instructions, in the order of their opcode extension.
+const ALU: [&str; 8] = ["add", "or", "adc", "sbb", "and", "sub", "xor", "cmp"];
+
+/// Shifts and rotates, in the order of their opcode extension.
+const SHIFTS: [&str; 8] = ["rol", "ror", "rcl", "rcr", "shl", "shr", "sal", "sar"];
+
+/// Instructions with a single operand encoded as `f6 /ext` or `f7 /ext`.
+const UNARY: [(&str, u8); 6] = [
+    ("not", 2),
+    ("neg", 3),
+    ("mul", 4),
+    ("imul", 5),
+    ("div", 6),
+    ("idiv", 7),
+];
+
+/// Instructions without operands.
+#[rustfmt::skip]
+const IMPLIED: &[(&str, &[u8])] = &[
+    ("nop", &[0x90]), ("ret", &[0xc3]), ("leave", &[0xc9]), ("int3", &[0xcc]), ("int1", &[0xf1]),
+    ("hlt", &[0xf4]), ("syscall", &[0x0f, 0x05]), ("sysret", &[0x0f, 0x07]),
+    ("ud2", &[0x0f, 0x0b]), ("cbw", &[0x66, 0x98]), ("cwde", &[0x98]), ("cdqe", &[0x48, 0x98]),
+    ("cwd", &[0x66, 0x99]), ("cdq", &[0x99]), ("cqo", &[0x48, 0x99]), ("clc", &[0xf8]),
+    ("stc", &[0xf9]), ("cmc", &[0xf5]), ("cld", &[0xfc]), ("std", &[0xfd]), ("cli", &[0xfa]),
+    ("sti", &[0xfb]), ("pushf", &[0x9c]), ("pushfq", &[0x9c]), ("popf", &[0x9d]),
+    ("popfq", &[0x9d]), ("sahf", &[0x9e]), ("lahf", &[0x9f]), ("cpuid", &[0x0f, 0xa2]),
+    ("rdtsc", &[0x0f, 0x31]), ("rdtscp", &[0x0f, 0x01, 0xf9]), ("pause", &[0xf3, 0x90]),
+    ("endbr64", &[0xf3, 0x0f, 0x1e, 0xfa]), ("endbr32", &[0xf3, 0x0f, 0x1e, 0xfb]),
+    ("lfence", &[0x0f, 0xae, 0xe8]), ("mfence", &[0x0f, 0xae, 0xf0]),
+    ("sfence", &[0x0f, 0xae, 0xf8]), ("iretq", &[0x48, 0xcf]), ("movsb", &[0xa4]),
+    ("movsw", &[0x66, 0xa5]), ("movsd", &[0xa5]), ("movsq", &[0x48, 0xa5]), ("cmpsb", &[0xa6]),
+    ("cmpsw", &[0x66, 0xa7]), ("cmpsd", &[0xa7]), ("cmpsq", &[0x48, 0xa7]), ("stosb", &[0xaa]),
+    ("stosw", &[0x66, 0xab]), ("stosd", &[0xab]), ("stosq", &[0x48, 0xab]), ("lodsb", &[0xac]),
+    ("lodsw", &[0x66, 0xad]), ("lodsd", &[0xad]), ("lodsq", &[0x48, 0xad]), ("scasb", &[0xae]),
+    ("scasw", &[0x66, 0xaf]), ("scasd", &[0xaf]), ("scasq", &[0x48, 0xaf]),
+];
+
+/// String instructions as the decoder shows them, with their operands spelled out.
+const STRINGS: [(&str, u8); 5] = [
+    ("movs", 0xa4),
+    ("cmps", 0xa6),
+    ("stos", 0xaa),
+    ("lods", 0xac),
+    ("scas", 0xae),
+];
+
+/// SSE instructions of the form `op xmm, xmm/m` with their mandatory prefix and opcode.
+#[rustfmt::skip]
+const SSE: &[(&str, Option<u8>, u8)] = &[
+    ("sqrtps", None, 0x51), ("sqrtpd", Some(0x66), 0x51), ("sqrtss", Some(0xf3), 0x51),
+    ("sqrtsd", Some(0xf2), 0x51), ("andps", None, 0x54), ("andpd", Some(0x66), 0x54),
+    ("andnps", None, 0x55), ("andnpd", Some(0x66), 0x55), ("orps", None, 0x56),
+    ("orpd", Some(0x66), 0x56), ("xorps", None, 0x57), ("xorpd", Some(0x66), 0x57),
+    ("addps", None, 0x58), ("addpd", Some(0x66), 0x58), ("addss", Some(0xf3), 0x58),
+    ("addsd", Some(0xf2), 0x58), ("mulps", None, 0x59), ("mulpd", Some(0x66), 0x59),
+    ("mulss", Some(0xf3), 0x59), ("mulsd", Some(0xf2), 0x59), ("cvtps2pd", None, 0x5a),
+    ("cvtpd2ps", Some(0x66), 0x5a), ("cvtss2sd", Some(0xf3), 0x5a),
+    ("cvtsd2ss", Some(0xf2), 0x5a), ("subps", None, 0x5c), ("subpd", Some(0x66), 0x5c),
+    ("subss", Some(0xf3), 0x5c), ("subsd", Some(0xf2), 0x5c), ("minps", None, 0x5d),
+    ("minpd", Some(0x66), 0x5d), ("minss", Some(0xf3), 0x5d), ("minsd", Some(0xf2), 0x5d),
+    ("divps", None, 0x5e), ("divpd", Some(0x66), 0x5e), ("divss", Some(0xf3), 0x5e),
+    ("divsd", Some(0xf2), 0x5e), ("maxps", None, 0x5f), ("maxpd", Some(0x66), 0x5f),
+    ("maxss", Some(0xf3), 0x5f), ("maxsd", Some(0xf2), 0x5f), ("unpcklps", None, 0x14),
+    ("unpcklpd", Some(0x66), 0x14), ("unpckhps", None, 0x15), ("unpckhpd", Some(0x66), 0x15),
+    ("ucomiss", None, 0x2e), ("ucomisd", Some(0x66), 0x2e), ("comiss", None, 0x2f),
+    ("comisd", Some(0x66), 0x2f), ("punpcklbw", Some(0x66), 0x60),
+    ("punpcklwd", Some(0x66), 0x61), ("punpckldq", Some(0x66), 0x62),
+    ("punpcklqdq", Some(0x66), 0x6c), ("punpckhqdq", Some(0x66), 0x6d),
+    ("pcmpeqb", Some(0x66), 0x74), ("pcmpeqw", Some(0x66), 0x75), ("pcmpeqd", Some(0x66), 0x76),
+    ("paddq", Some(0x66), 0xd4), ("pand", Some(0x66), 0xdb), ("pandn", Some(0x66), 0xdf),
+    ("psubb", Some(0x66), 0xf8), ("psubw", Some(0x66), 0xf9), ("psubd", Some(0x66), 0xfa),
+    ("psubq", Some(0x66), 0xfb), ("paddb", Some(0x66), 0xfc), ("paddw", Some(0x66), 0xfd),
+    ("paddd", Some(0x66), 0xfe), ("por", Some(0x66), 0xeb), ("pxor", Some(0x66), 0xef),
+];
+
+/// SSE moves with their mandatory prefix, load and store opcode.
+#[rustfmt::skip]
+const SSE_MOVES: &[(&str, Option<u8>, u8, u8)] = &[
+    ("movups", None, 0x10, 0x11), ("movupd", Some(0x66), 0x10, 0x11),
+    ("movss", Some(0xf3), 0x10, 0x11), ("movsd", Some(0xf2), 0x10, 0x11),
+    ("movaps", None, 0x28, 0x29), ("movapd", Some(0x66), 0x28, 0x29),
+    ("movdqa", Some(0x66), 0x6f, 0x7f), ("movdqu", Some(0xf3), 0x6f, 0x7f),
+];
+
+/// Instructions that aren't in any of the tables above.
+#[rustfmt::skip]
+const OTHER: &[&str] = &[
+    "mov", "movabs", "test", "xchg", "lea", "push", "pop", "inc", "dec", "movzx", "movsx",
+    "movsxd", "jmp", "call", "jrcxz", "loop", "loopz", "loope", "loopnz", "loopne", "int", "bt",
+    "bts", "btr", "btc", "bsf", "bsr", "tzcnt", "lzcnt", "popcnt", "xadd", "cmpxchg", "bswap",
+    "movd", "movq", "cvtsi2ss", "cvtsi2sd", "cvttss2si", "cvttsd2si", "cvtss2si", "cvtsd2si",
+];
+
+fn register(name: &str) -> Option<Reg> {
+    let find = |names: &[&str], size, bank| {
+        names.iter().position(|n| *n == name).map(|num| Reg {
+            num: num as u8,
+            size,
+            bank,
+        })
+    };
+
+    if let Some(reg) = find(&GPR_64, 8, Bank::Gpr)
+        .or_else(|| find(&GPR_32, 4, Bank::Gpr))
+        .or_else(|| find(&GPR_16, 2, Bank::Gpr))
+        .or_else(|| find(&GPR_8, 1, Bank::Gpr))
+        .or_else(|| find(&SEGMENTS, 2, Bank::Segment))
+    {
+        return Some(reg);
+    }
+
+    if let Some(num) = HIGH_8.iter().position(|n| *n == name) {
+        return Some(Reg {
+            num: num as u8 + 4,
+            size: 1,
+            bank: Bank::High,
+        });
+    }
+
+    if name == "rip" {
+        return Some(Reg {
+            num: 0,
+            size: 8,
+            bank: Bank::Rip,
+        });
+    }
+
+    let num = name.strip_prefix("xmm")?.parse::<u8>().ok().filter(|num| *num < 16)?;
+    Some(Reg {
+        num,
+        size: 16,
+        bank: Bank::Xmm,
+    })
+}
+
+fn mem_size(name: &str) -> Option<u8> {
+    Some(match name {
+        "byte" => 1,
+        "word" => 2,
+        "dword" => 4,
+        "qword" => 8,
+        "tword" | "tbyte" => 10,
+        "xmmword" | "oword" => 16,
+        "ymmword" => 32,
+        "zmmword" => 64,
+        _ => return None,
+    })
+}
+
+/// Condition code of `jcc`, `setcc` and `cmovcc`.
+fn condition(name: &str) -> Option<u8> {
+    Some(match name {
+        "o" => 0x0,
+        "no" => 0x1,
+        "b" | "c" | "nae" => 0x2,
+        "nb" | "nc" | "ae" => 0x3,
+        "z" | "e" => 0x4,
+        "nz" | "ne" => 0x5,
+        "na" | "be" => 0x6,
+        "a" | "nbe" => 0x7,
+        "s" => 0x8,
+        "ns" => 0x9,
+        "p" | "pe" => 0xa,
+        "np" | "po" => 0xb,
+        "l" | "nge" => 0xc,
+        "ge" | "nl" => 0xd,
+        "le" | "ng" => 0xe,
+        "g" | "nle" => 0xf,
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Mem {
+    /// Number of bytes accessed, if it was given.
+    size: Option<u8>,
+    segment: Option<u8>,
+    base: Option<Reg>,
+    index: Option<Reg>,
+    scale: u8,
+    disp: i64,
+    /// Address that's encoded relative to `rip`, for operands that refer to symbols.
+    target: Option<u64>,
+}
+
+impl Mem {
+    /// Whether the address is computed from 32-bit registers.
+    fn is_addr32(&self) -> bool {
+        self.base.or(self.index).is_some_and(|reg| reg.size == 4)
+    }
+
+    /// Append ModRM, SIB and displacement, returning where a `rip` relative displacement to
+    /// the target has to be written once the length of the instruction is known.
+    fn encode(&self, reg: u8, bytes: &mut Vec<u8>) -> Result<Option<(usize, u64)>, ErrorKind> {
+        let reg = (reg & 7) << 3;
+        let scale = match self.scale {
+            1 => 0,
+            2 => 1,
+            4 => 2,
+            8 => 3,
+            _ => return Err(ErrorKind::InvalidOperands),
+        };
+
+        if self.target.is_some() || self.base.is_some_and(|reg| reg.bank == Bank::Rip) {
+            if self.index.is_some() {
+                return Err(ErrorKind::InvalidOperands);
+            }
+
+            bytes.push(0x05 | reg);
+            let pos = bytes.len();
+            bytes.extend(disp32(self.disp)?);
+            return Ok(self.target.map(|target| (pos, target)));
+        }
+
+        match (self.base, self.index) {
+            (None, None) => {
+                bytes.extend([0x04 | reg, 0x25]);
+                bytes.extend(disp32(self.disp)?);
+            }
+            (None, Some(index)) => {
+                bytes.extend([0x04 | reg, scale << 6 | (index.num & 7) << 3 | 0x05]);
+                bytes.extend(disp32(self.disp)?);
+            }
+            (Some(base), index) => {
+                // `rbp` and `r13` without a displacement would mean `rip` or no base
+                let mode = if self.disp == 0 && base.num & 7 != 5 {
+                    0
+                } else if i8::try_from(self.disp).is_ok() {
+                    1
+                } else {
+                    2
+                };
+
+                if index.is_some() || base.num & 7 == 4 {
+                    let index = index.map_or(4, |reg| reg.num & 7);
+                    bytes.push(mode << 6 | reg | 0x04);
+                    bytes.push(scale << 6 | index << 3 | base.num & 7);
+                } else {
+                    bytes.push(mode << 6 | reg | base.num & 7);
+                }
+
+                match mode {
+                    1 => bytes.push(self.disp as u8),
+                    2 => bytes.extend(disp32(self.disp)?),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Operand {
+    Reg(Reg),
+    Mem(Mem),
+    Imm(i64),
+}
+
+impl Operand {
+    fn gpr(&self) -> Option<Reg> {
+        match self {
+            Self::Reg(reg) if reg.is_gpr() => Some(*reg),
+            _ => None,
+        }
+    }
+
+    fn xmm(&self) -> Option<Reg> {
+        match self {
+            Self::Reg(reg) if reg.is_xmm() => Some(*reg),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a general purpose register or memory.
+    fn is_rm(&self) -> bool {
+        self.gpr().is_some() || self.is_mem()
+    }
+
+    /// Whether this is an xmm register or memory.
+    fn is_xmm_rm(&self) -> bool {
+        self.xmm().is_some() || self.is_mem()
+    }
+
+    fn is_mem(&self) -> bool {
+        matches!(self, Self::Mem(_))
+    }
+
+    fn size(&self) -> Option<u8> {
+        match self {
+            Self::Reg(reg) => Some(reg.size),
+            Self::Mem(mem) => mem.size,
+            Self::Imm(_) => None,
+        }
+    }
+
+    /// Size of this operand, which has to match `reg` if it's given.
+    fn same_size(&self, reg: Reg) -> Result<u8, ErrorKind> {
+        match self.size() {
+            Some(size) if size != reg.size => Err(ErrorKind::SizeMismatch),
+            _ => Ok(reg.size),
+        }
+    }
+}
+
+/// Contents of the `reg` field of ModRM.
+#[derive(Debug, Copy, Clone)]
+enum Field {
+    Reg(Reg),
+    /// Extension of the opcode.
+    Ext(u8),
+}
+
+#[derive(Debug, Default, Clone)]
+struct Encoding {
+    /// `lock`, `rep` or `repne` prefix.
+    group1: Option<u8>,
+    /// Operand size override prefix.
+    opsize: bool,
+    /// Address size override prefix, for memory operands that aren't in `rm`.
+    addr32: bool,
+    /// Prefix that's part of the opcode, e.g. the `f3` of `tzcnt`.
+    mandatory: Option<u8>,
+    w: bool,
+    opcode: Vec<u8>,
+    /// Register encoded in the low bits of the opcode.
+    opreg: Option<Reg>,
+    reg: Option<Field>,
+    rm: Option<Operand>,
+    imm: Vec<u8>,
+    /// Branch target and the number of bytes of it's relative offset.
+    rel: Option<(u64, u8)>,
+}
+
+impl Encoding {
+    fn new(opcode: &[u8]) -> Self {
+        Self {
+            opcode: opcode.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the operand size to `size` bytes.
+    fn sized(mut self, size: u8) -> Self {
+        self.opsize = size == 2;
+        self.w = size == 8;
+        self
+    }
+
+    fn mandatory(mut self, prefix: Option<u8>) -> Self {
+        self.mandatory = prefix;
+        self
+    }
+
+    fn opreg(mut self, reg: Reg) -> Self {
+        self.opreg = Some(reg);
+        self
+    }
+
+    fn reg(mut self, reg: Reg) -> Self {
+        self.reg = Some(Field::Reg(reg));
+        self
+    }
+
+    fn ext(mut self, ext: u8) -> Self {
+        self.reg = Some(Field::Ext(ext));
+        self
+    }
+
+    fn rm(mut self, rm: Operand) -> Self {
+        self.rm = Some(rm);
+        self
+    }
+
+    fn imm(mut self, imm: Vec<u8>) -> Self {
+        self.imm = imm;
+        self
+    }
+
+    fn rel(mut self, target: u64, size: u8) -> Self {
+        self.rel = Some((target, size));
+        self
+    }
+
+    fn emit(&self, addr: u64) -> Result<Vec<u8>, ErrorKind> {
+        let mut bytes = Vec::new();
+        let mut regs = Vec::new();
+
+        let mem = match self.rm {
+            Some(Operand::Mem(mem)) => Some(mem),
+            _ => None,
+        };
+
+        if let Some(segment) = mem.and_then(|mem| mem.segment) {
+            bytes.push(SEGMENT_PREFIXES[segment as usize]);
+        }
+
+        if self.addr32 || mem.is_some_and(|mem| mem.is_addr32()) {
+            bytes.push(0x67);
+        }
+
+        if self.opsize {
+            bytes.push(0x66);
+        }
+
+        bytes.extend(self.group1);
+        bytes.extend(self.mandatory);
+
+        let reg = match self.reg {
+            Some(Field::Reg(reg)) => {
+                regs.push(reg);
+                reg.num
+            }
+            Some(Field::Ext(ext)) => ext,
+            None => 0,
+        };
+
+        let (mut x, mut b) = (0, 0);
+        if let Some(reg) = self.opreg {
+            regs.push(reg);
+            b = reg.num >> 3;
+        }
+
+        match self.rm {
+            Some(Operand::Reg(reg)) => {
+                regs.push(reg);
+                b = reg.num >> 3;
+            }
+            Some(Operand::Mem(mem)) => {
+                b = mem.base.map_or(0, |reg| reg.num >> 3);
+                x = mem.index.map_or(0, |reg| reg.num >> 3);
+            }
+            _ => {}
+        }
+
+        // `spl`, `bpl`, `sil` and `dil` are only accessible with a REX prefix
+        let rex = 0x40 | (self.w as u8) << 3 | (reg >> 3) << 2 | x << 1 | b;
+        let uniform_byte = regs.iter().any(|r| r.bank == Bank::Gpr && r.size == 1 && r.num >= 4);
+        if rex != 0x40 || uniform_byte {
+            if regs.iter().any(|r| r.bank == Bank::High) {
+                return Err(ErrorKind::InvalidOperands);
+            }
+
+            bytes.push(rex);
+        }
+
+        bytes.extend(&self.opcode);
+        if let (Some(reg), Some(last)) = (self.opreg, bytes.last_mut()) {
+            *last |= reg.num & 7;
+        }
+
+        let mut rip_relative = None;
+        match self.rm {
+            Some(Operand::Reg(rm)) => bytes.push(0xc0 | (reg & 7) << 3 | rm.num & 7),
+            Some(Operand::Mem(mem)) => rip_relative = mem.encode(reg, &mut bytes)?,
+            _ => {}
+        }
+
+        bytes.extend(&self.imm);
+
+        if let Some((target, size)) = self.rel {
+            let end = addr.wrapping_add((bytes.len() + size as usize) as u64);
+            let offset = target.wrapping_sub(end) as i64;
+            if truncate(offset, size) != offset {
+                return Err(ErrorKind::TargetOutOfRange(target));
+            }
+
+            bytes.extend(&offset.to_le_bytes()[..size as usize]);
+        }
+
+        // the displacement is relative to the end of the instruction, including the immediate
+        if let Some((pos, target)) = rip_relative {
+            let end = addr.wrapping_add(bytes.len() as u64);
+            let disp = i32::try_from(target.wrapping_sub(end) as i64)
+                .map_err(|_| ErrorKind::TargetOutOfRange(target))?;
+            bytes[pos..pos + 4].copy_from_slice(&disp.to_le_bytes());
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// Sign extend the low `size` bytes of `value`.
+fn truncate(value: i64, size: u8) -> i64 {
+    let shift = 64 - 8 * size as u32;
+    (value << shift) >> shift
+}
+
+/// Little endian immediate of `size` bytes, given either as a signed or unsigned number.
+fn imm(value: i64, size: u8) -> Result<Vec<u8>, ErrorKind> {
+    let bits = 8 * size as u32;
+    if bits < 64 && !(-(1 << (bits - 1))..(1 << bits)).contains(&value) {
+        return Err(ErrorKind::ImmediateOutOfRange(value));
+    }
+
+    Ok(value.to_le_bytes()[..size as usize].to_vec())
+}
+
+/// Value of an immediate once it's sign-extended to an operand of `size` bytes.
+fn signed(value: i64, size: u8) -> Result<i64, ErrorKind> {
+    imm(value, size)?;
+    Ok(truncate(value, size))
+}
+
+/// Immediate that's sign-extended from at most 32 bits, as used by 64-bit operations.
+fn imm_sx(value: i64, size: u8) -> Result<Vec<u8>, ErrorKind> {
+    let value = signed(value, size)?;
+    if truncate(value, size.min(4)) != value {
+        return Err(ErrorKind::ImmediateOutOfRange(value));
+    }
+
+    Ok(value.to_le_bytes()[..size.min(4) as usize].to_vec())
+}
+
+fn disp32(disp: i64) -> Result<[u8; 4], ErrorKind> {
+    i32::try_from(disp)
+        .map(i32::to_le_bytes)
+        .map_err(|_| ErrorKind::ImmediateOutOfRange(disp))
+}
+
+/// Opcode of the byte sized form of an instruction, or the one following it.
+fn sized_op(opcode: u8, size: u8) -> u8 {
+    if size == 1 {
+        opcode
+    } else {
+        opcode + 1
+    }
+}
+
+fn operand(p: &mut Parser) -> Result<Operand, ErrorKind> {
+    let size = p.ident_with(mem_size);
+    if size.is_some() {
+        p.eat_keyword("ptr");
+    }
+
+    let mut segment = None;
+    if let Some(reg) = p.ident_with(register) {
+        if reg.bank == Bank::Segment && p.eat(':') {
+            segment = Some(reg.num);
+        } else if size.is_some() {
+            return Err(ErrorKind::Syntax("'['"));
+        } else {
+            return Ok(Operand::Reg(reg));
+        }
+    }
+
+    if p.peek() != Some('[') {
+        if size.is_some() || segment.is_some() {
+            return Err(ErrorKind::Syntax("'['"));
+        }
+
+        return Ok(Operand::Imm(p.expr()?.value));
+    }
+
+    let mut mem = memory(p)?;
+    mem.size = size;
+    mem.segment = segment;
+    Ok(Operand::Mem(mem))
+}
+
+/// Memory operand such as `[rbx + rcx * 8 - 0x10]`.
+fn memory(p: &mut Parser) -> Result<Mem, ErrorKind> {
+    let mut mem = Mem {
+        size: None,
+        segment: None,
+        base: None,
+        index: None,
+        scale: 1,
+        disp: 0,
+        target: None,
+    };
+
+    let mut relocatable = false;
+    let mut first = true;
+
+    p.expect('[', "'['")?;
+    while !p.eat(']') {
+        let negate = if p.eat('-') {
+            true
+        } else if p.eat('+') || first {
+            false
+        } else {
+            return Err(ErrorKind::Syntax("']'"));
+        };
+
+        first = false;
+
+        if let Some(reg) = p.ident_with(register) {
+            if negate {
+                return Err(ErrorKind::InvalidOperands);
+            }
+
+            if p.eat('*') {
+                mem.scale = p.term()?.value as u8;
+                index(&mut mem, reg)?;
+            } else if mem.base.is_none() {
+                mem.base = Some(reg);
+            } else {
+                index(&mut mem, reg)?;
+            }
+
+            continue;
+        }
+
+        let term = p.term()?;
+
+        // scale before the index, e.g. `[8 * rax]`
+        if p.eat('*') {
+            let reg = p.ident_with(register).ok_or(ErrorKind::Syntax("a register"))?;
+            mem.scale = term.value as u8;
+            index(&mut mem, reg)?;
+            continue;
+        }
+
+        relocatable |= term.relocatable;
+        mem.disp = if negate {
+            mem.disp.wrapping_sub(term.value)
+        } else {
+            mem.disp.wrapping_add(term.value)
+        };
+    }
+
+    for reg in mem.base.iter().chain(mem.index.iter()) {
+        let valid = match reg.bank {
+            Bank::Gpr => reg.size == 4 || reg.size == 8,
+            Bank::Rip => mem.index.is_none(),
+            _ => false,
+        };
+
+        if !valid {
+            return Err(ErrorKind::InvalidOperands);
+        }
+    }
+
+    if let (Some(base), Some(index)) = (mem.base, mem.index) {
+        if base.size != index.size {
+            return Err(ErrorKind::SizeMismatch);
+        }
+    }
+
+    let rip = mem.base.is_some_and(|reg| reg.bank == Bank::Rip);
+    if relocatable && (rip || (mem.base.is_none() && mem.index.is_none())) {
+        mem.base = None;
+        mem.target = Some(mem.disp as u64);
+        mem.disp = 0;
+    }
+
+    Ok(mem)
+}
+
+fn index(mem: &mut Mem, reg: Reg) -> Result<(), ErrorKind> {
+    // `rsp` can't be an index, it encodes that there is none
+    if mem.index.is_some() || (reg.bank == Bank::Gpr && reg.num == 4) {
+        return Err(ErrorKind::InvalidOperands);
+    }
+
+    mem.index = Some(reg);
+    Ok(())
+}
+
+pub fn assemble(p: &mut Parser) -> Result<Vec<u8>, ErrorKind> {
+    let mut group1 = None;
+    let mnemonic = loop {
+        let mnemonic = p.ident().ok_or(ErrorKind::Syntax("an instruction"))?;
+        let mnemonic = mnemonic.to_ascii_lowercase();
+        let prefix = match mnemonic.as_str() {
+            "lock" => 0xf0,
+            "rep" | "repe" | "repz" => 0xf3,
+            "repne" | "repnz" => 0xf2,
+            _ => break mnemonic,
+        };
+
+        group1 = Some(prefix);
+    };
+
+    let mut ops = Vec::new();
+    if !p.is_empty() {
+        loop {
+            ops.push(operand(p)?);
+            if !p.eat(',') {
+                break;
+            }
+        }
+    }
+
+    p.end()?;
+
+    let mut encoding = encode(&mnemonic, &ops, p.addr)?;
+    if group1.is_some() {
+        encoding.group1 = group1;
+    }
+
+    encoding.emit(p.addr)
+}
+
+fn encode(mnemonic: &str, ops: &[Operand], addr: u64) -> Result<Encoding, ErrorKind> {
+    if ops.is_empty() {
+        if let Some((_, bytes)) = IMPLIED.iter().find(|(name, _)| *name == mnemonic) {
+            return Ok(Encoding::new(bytes));
+        }
+    }
+
+    if let Some(ext) = ALU.iter().position(|name| *name == mnemonic) {
+        return alu(ext as u8, ops);
+    }
+
+    if let Some(ext) = SHIFTS.iter().position(|name| *name == mnemonic) {
+        // `sal` is another name for `shl`
+        let ext = if ext == 6 { 4 } else { ext as u8 };
+        return shift(ext, ops);
+    }
+
+    if let Some((_, ext)) = UNARY.iter().find(|(name, _)| *name == mnemonic) {
+        if let [op] = ops {
+            let size = op.size().ok_or(ErrorKind::AmbiguousSize)?;
+            if op.is_rm() {
+                return Ok(Encoding::new(&[sized_op(0xf6, size)]).ext(*ext).rm(*op).sized(size));
+            }
+        }
+
+        if mnemonic != "imul" {
+            return Err(ErrorKind::InvalidOperands);
+        }
+    }
+
+    if let Some(&(_, prefix, opcode)) = SSE.iter().find(|(name, ..)| *name == mnemonic) {
+        return match ops {
+            [Operand::Reg(dst), src] if dst.is_xmm() && src.is_xmm_rm() => {
+                Ok(Encoding::new(&[0x0f, opcode]).mandatory(prefix).reg(*dst).rm(*src))
+            }
+            _ => Err(ErrorKind::InvalidOperands),
+        };
+    }
+
+    if let Some(&(_, prefix, load, store)) = SSE_MOVES.iter().find(|(name, ..)| *name == mnemonic) {
+        return match ops {
+            [Operand::Reg(dst), src] if dst.is_xmm() && src.is_xmm_rm() => {
+                Ok(Encoding::new(&[0x0f, load]).mandatory(prefix).reg(*dst).rm(*src))
+            }
+            [dst @ Operand::Mem(_), Operand::Reg(src)] if src.is_xmm() => {
+                Ok(Encoding::new(&[0x0f, store]).mandatory(prefix).reg(*src).rm(*dst))
+            }
+            _ => Err(ErrorKind::InvalidOperands),
+        };
+    }
+
+    if let Some(&(_, opcode)) = STRINGS.iter().find(|(name, _)| *name == mnemonic) {
+        // the operands are implied, they only tell the size
+        let size = ops.iter().find_map(Operand::size).ok_or(ErrorKind::AmbiguousSize)?;
+        let mut encoding = Encoding::new(&[sized_op(opcode, size)]).sized(size);
+        encoding.addr32 = ops.iter().any(|op| matches!(op, Operand::Mem(mem) if mem.is_addr32()));
+        return Ok(encoding);
+    }
+
+    if let Some(cc) = mnemonic.strip_prefix('j').and_then(condition) {
+        return match ops {
+            [Operand::Imm(target)] => {
+                Ok(branch(Some(0x70 | cc), &[0x0f, 0x80 | cc], *target, addr))
+            }
+            _ => Err(ErrorKind::InvalidOperands),
+        };
+    }
+
+    if let Some(cc) = mnemonic.strip_prefix("set").and_then(condition) {
+        return match ops {
+            [op] if op.is_rm() && op.size().unwrap_or(1) == 1 => {
+                Ok(Encoding::new(&[0x0f, 0x90 | cc]).ext(0).rm(*op))
+            }
+            _ => Err(ErrorKind::InvalidOperands),
+        };
+    }
+
+    if let Some(cc) = mnemonic.strip_prefix("cmov").and_then(condition) {
+        return match ops {
+            [Operand::Reg(dst), src] if dst.is_gpr() && src.is_rm() && dst.size > 1 => {
+                let size = src.same_size(*dst)?;
+                Ok(Encoding::new(&[0x0f, 0x40 | cc]).reg(*dst).rm(*src).sized(size))
+            }
+            _ => Err(ErrorKind::InvalidOperands),
+        };
+    }
+
+    let encoding = match (mnemonic, ops) {
+        ("mov" | "movabs", [dst, src]) => mov(dst, src)?,
+        ("test", [dst, src]) => test(dst, src)?,
+        ("xchg", [dst, src]) => xchg(dst, src)?,
+        ("lea", [Operand::Reg(dst), Operand::Mem(src)]) if dst.is_gpr() && dst.size > 1 => {
+            let mut src = *src;
+            src.size = None;
+            Encoding::new(&[0x8d]).reg(*dst).rm(Operand::Mem(src)).sized(dst.size)
+        }
+        ("push", [op]) => push(op, true)?,
+        ("pop", [op]) => push(op, false)?,
+        ("inc" | "dec", [op]) if op.is_rm() => {
+            let size = op.size().ok_or(ErrorKind::AmbiguousSize)?;
+            let ext = (mnemonic == "dec") as u8;
+            Encoding::new(&[sized_op(0xfe, size)]).ext(ext).rm(*op).sized(size)
+        }
+        ("imul", [Operand::Reg(dst), Operand::Imm(imm)]) if dst.is_gpr() && dst.size > 1 => {
+            imul(*dst, &Operand::Reg(*dst), *imm)?
+        }
+        ("imul", [Operand::Reg(dst), src]) if dst.is_gpr() && dst.size > 1 && src.is_rm() => {
+            let size = src.same_size(*dst)?;
+            Encoding::new(&[0x0f, 0xaf]).reg(*dst).rm(*src).sized(size)
+        }
+        ("imul", [Operand::Reg(dst), src, Operand::Imm(imm)])
+            if dst.is_gpr() && dst.size > 1 && src.is_rm() =>
+        {
+            src.same_size(*dst)?;
+            imul(*dst, src, *imm)?
+        }
+        ("movzx" | "movsx", [Operand::Reg(dst), src]) if dst.is_gpr() && src.is_rm() => {
+            let size = src.size().ok_or(ErrorKind::AmbiguousSize)?;
+            if size >= dst.size || size > 2 || dst.size == 1 {
+                return Err(ErrorKind::SizeMismatch);
+            }
+
+            let opcode = if mnemonic == "movzx" { 0xb6 } else { 0xbe };
+            Encoding::new(&[0x0f, sized_op(opcode, size)])
+                .reg(*dst)
+                .rm(*src)
+                .sized(dst.size)
+        }
+        ("movsxd", [Operand::Reg(dst), src]) if dst.is_gpr() && src.is_rm() => {
+            if dst.size != 8 || src.size().is_some_and(|size| size != 4) {
+                return Err(ErrorKind::SizeMismatch);
+            }
+
+            Encoding::new(&[0x63]).reg(*dst).rm(*src).sized(8)
+        }
+        ("jmp", [Operand::Imm(target)]) => branch(Some(0xeb), &[0xe9], *target, addr),
+        ("call", [Operand::Imm(target)]) => branch(None, &[0xe8], *target, addr),
+        ("jmp" | "call", [op]) if op.is_rm() && op.size().unwrap_or(8) == 8 => {
+            let ext = if mnemonic == "call" { 2 } else { 4 };
+            Encoding::new(&[0xff]).ext(ext).rm(*op)
+        }
+        ("jrcxz" | "loop" | "loopz" | "loope" | "loopnz" | "loopne", [Operand::Imm(target)]) => {
+            let opcode = match mnemonic {
+                "loopnz" | "loopne" => 0xe0,
+                "loopz" | "loope" => 0xe1,
+                "loop" => 0xe2,
+                _ => 0xe3,
+            };
+
+            Encoding::new(&[opcode]).rel(*target as u64, 1)
+        }
+        ("ret", [Operand::Imm(imm)]) => Encoding::new(&[0xc2]).imm(self::imm(*imm, 2)?),
+        // the decoder shows `int3` as `int 0x3`, which should assemble back to the same byte
+        ("int", [Operand::Imm(3)]) => Encoding::new(&[0xcc]),
+        ("int", [Operand::Imm(imm)]) => Encoding::new(&[0xcd]).imm(self::imm(*imm, 1)?),
+        ("nop", [op]) if op.is_rm() => {
+            let size = op.size().ok_or(ErrorKind::AmbiguousSize)?;
+            if !matches!(size, 2 | 4 | 8) {
+                return Err(ErrorKind::InvalidOperands);
+            }
+
+            Encoding::new(&[0x0f, 0x1f]).ext(0).rm(*op).sized(size)
+        }
+        ("bt" | "bts" | "btr" | "btc", [dst, src]) if dst.is_rm() => {
+            let ext = match mnemonic {
+                "bt" => 4,
+                "bts" => 5,
+                "btr" => 6,
+                _ => 7,
+            };
+
+            match src {
+                Operand::Reg(src) if src.is_gpr() && src.size > 1 => {
+                    let size = dst.same_size(*src)?;
+                    Encoding::new(&[0x0f, 0xa3 + 8 * (ext - 4)]).reg(*src).rm(*dst).sized(size)
+                }
+                Operand::Imm(imm) => {
+                    let size = dst.size().ok_or(ErrorKind::AmbiguousSize)?;
+                    Encoding::new(&[0x0f, 0xba])
+                        .ext(ext)
+                        .rm(*dst)
+                        .imm(self::imm(*imm, 1)?)
+                        .sized(size)
+                }
+                _ => return Err(ErrorKind::InvalidOperands),
+            }
+        }
+        ("bsf" | "bsr" | "tzcnt" | "lzcnt" | "popcnt", [Operand::Reg(dst), src])
+            if dst.is_gpr() && dst.size > 1 && src.is_rm() =>
+        {
+            let size = src.same_size(*dst)?;
+            let (prefix, opcode) = match mnemonic {
+                "bsf" => (None, 0xbc),
+                "bsr" => (None, 0xbd),
+                "tzcnt" => (Some(0xf3), 0xbc),
+                "lzcnt" => (Some(0xf3), 0xbd),
+                _ => (Some(0xf3), 0xb8),
+            };
+
+            Encoding::new(&[0x0f, opcode]).mandatory(prefix).reg(*dst).rm(*src).sized(size)
+        }
+        ("xadd" | "cmpxchg", [dst, Operand::Reg(src)]) if dst.is_rm() && src.is_gpr() => {
+            let size = dst.same_size(*src)?;
+            let opcode = if mnemonic == "xadd" { 0xc0 } else { 0xb0 };
+            Encoding::new(&[0x0f, sized_op(opcode, size)]).reg(*src).rm(*dst).sized(size)
+        }
+        ("bswap", [Operand::Reg(reg)]) if reg.is_gpr() && reg.size >= 4 => {
+            Encoding::new(&[0x0f, 0xc8]).opreg(*reg).sized(reg.size)
+        }
+        ("movd" | "movq", [dst, src]) => movq(mnemonic == "movq", dst, src)?,
+        ("cvtsi2ss" | "cvtsi2sd", [Operand::Reg(dst), src]) if dst.is_xmm() && src.is_rm() => {
+            let size = src.size().ok_or(ErrorKind::AmbiguousSize)?;
+            if size < 4 {
+                return Err(ErrorKind::SizeMismatch);
+            }
+
+            let prefix = if mnemonic == "cvtsi2ss" { 0xf3 } else { 0xf2 };
+            let mut encoding = Encoding::new(&[0x0f, 0x2a]).mandatory(Some(prefix));
+            encoding.w = size == 8;
+            encoding.reg(*dst).rm(*src)
+        }
+        ("cvttss2si" | "cvttsd2si" | "cvtss2si" | "cvtsd2si", [Operand::Reg(dst), src])
+            if dst.is_gpr() && dst.size >= 4 && src.is_xmm_rm() =>
+        {
+            let prefix = if mnemonic.ends_with("ss2si") {
+                0xf3
+            } else {
+                0xf2
+            };
+            let opcode = if mnemonic.starts_with("cvtt") {
+                0x2c
+            } else {
+                0x2d
+            };
+            let mut encoding = Encoding::new(&[0x0f, opcode]).mandatory(Some(prefix));
+            encoding.w = dst.size == 8;
+            encoding.reg(*dst).rm(*src)
+        }
+        _ if IMPLIED.iter().any(|(name, _)| *name == mnemonic) || OTHER.contains(&mnemonic) => {
+            return Err(ErrorKind::InvalidOperands);
+        }
+        _ => return Err(ErrorKind::UnknownMnemonic(mnemonic.to_string())),
+    };
+
+    Ok(encoding)
+}
+
+/// Branch with a relative offset, using the short encoding if the target is in reach of it.
+fn branch(short: Option<u8>, near: &[u8], target: i64, addr: u64) -> Encoding {
+    let target = target as u64;
+    if let Some(opcode) = short {
+        let offset = target.wrapping_sub(addr.wrapping_add(2)) as i64;
+        if i8::try_from(offset).is_ok() {
+            return Encoding::new(&[opcode]).rel(target, 1);
+        }
+    }
+
+    Encoding::new(near).rel(target, 4)
+}
+
+fn alu(ext: u8, ops: &[Operand]) -> Result<Encoding, ErrorKind> {
+    let base = ext * 8;
+    match ops {
+        [dst, Operand::Reg(src)] if dst.is_rm() && src.is_gpr() => {
+            let size = dst.same_size(*src)?;
+            Ok(Encoding::new(&[sized_op(base, size)]).reg(*src).rm(*dst).sized(size))
+        }
+        [Operand::Reg(dst), src @ Operand::Mem(_)] if dst.is_gpr() => {
+            let size = src.same_size(*dst)?;
+            Ok(Encoding::new(&[sized_op(base + 2, size)]).reg(*dst).rm(*src).sized(size))
+        }
+        [dst, Operand::Imm(imm)] if dst.is_rm() => {
+            let size = dst.size().ok_or(ErrorKind::AmbiguousSize)?;
+            let value = signed(*imm, size)?;
+            let accumulator = dst.gpr().is_some_and(|reg| reg.is_accumulator());
+
+            let encoding = if size == 1 {
+                let imm = self::imm(value, 1)?;
+                if accumulator {
+                    Encoding::new(&[base + 4]).imm(imm)
+                } else {
+                    Encoding::new(&[0x80]).ext(ext).rm(*dst).imm(imm)
+                }
+            } else if i8::try_from(value).is_ok() {
+                Encoding::new(&[0x83]).ext(ext).rm(*dst).imm(vec![value as u8])
+            } else if accumulator {
+                Encoding::new(&[base + 5]).imm(imm_sx(value, size)?)
+            } else {
+                Encoding::new(&[0x81]).ext(ext).rm(*dst).imm(imm_sx(value, size)?)
+            };
+
+            Ok(encoding.sized(size))
+        }
+        _ => Err(ErrorKind::InvalidOperands),
+    }
+}
+
+fn shift(ext: u8, ops: &[Operand]) -> Result<Encoding, ErrorKind> {
+    let (dst, count) = match ops {
+        [dst] => (dst, Operand::Imm(1)),
+        [dst, count] => (dst, *count),
+        _ => return Err(ErrorKind::InvalidOperands),
+    };
+
+    if !dst.is_rm() {
+        return Err(ErrorKind::InvalidOperands);
+    }
+
+    let size = dst.size().ok_or(ErrorKind::AmbiguousSize)?;
+    let encoding = match count {
+        Operand::Imm(1) => Encoding::new(&[sized_op(0xd0, size)]),
+        Operand::Imm(imm) => Encoding::new(&[sized_op(0xc0, size)]).imm(self::imm(imm, 1)?),
+        Operand::Reg(reg) if reg.is_gpr() && reg.num == 1 && reg.size == 1 => {
+            Encoding::new(&[sized_op(0xd2, size)])
+        }
+        _ => return Err(ErrorKind::InvalidOperands),
+    };
+
+    Ok(encoding.ext(ext).rm(*dst).sized(size))
+}
+
+fn imul(dst: Reg, src: &Operand, imm: i64) -> Result<Encoding, ErrorKind> {
+    let value = signed(imm, dst.size)?;
+    let encoding = if i8::try_from(value).is_ok() {
+        Encoding::new(&[0x6b]).imm(vec![value as u8])
+    } else {
+        Encoding::new(&[0x69]).imm(imm_sx(value, dst.size)?)
+    };
+
+    Ok(encoding.reg(dst).rm(*src).sized(dst.size))
+}
+
+fn mov(dst: &Operand, src: &Operand) -> Result<Encoding, ErrorKind> {
+    let encoding = match (dst, src) {
+        // absolute addresses that don't fit in 32 bits are only encodable with the accumulator
+        (Operand::Reg(reg), Operand::Mem(mem)) | (Operand::Mem(mem), Operand::Reg(reg))
+            if reg.is_accumulator()
+                && mem.base.is_none()
+                && mem.index.is_none()
+                && mem.target.is_none()
+                && i32::try_from(mem.disp).is_err() =>
+        {
+            let size = Operand::Mem(*mem).same_size(*reg)?;
+            let opcode = if dst.is_mem() { 0xa2 } else { 0xa0 };
+            let mut encoding = Encoding::new(&[sized_op(opcode, size)])
+                .imm(mem.disp.to_le_bytes().to_vec())
+                .sized(size);
+            if let Some(segment) = mem.segment {
+                encoding.group1 = Some(SEGMENT_PREFIXES[segment as usize]);
+            }
+            encoding
+        }
+        (dst, Operand::Reg(src)) if dst.is_rm() && src.is_gpr() => {
+            let size = dst.same_size(*src)?;
+            Encoding::new(&[sized_op(0x88, size)]).reg(*src).rm(*dst).sized(size)
+        }
+        (Operand::Reg(dst), src @ Operand::Mem(_)) if dst.is_gpr() => {
+            let size = src.same_size(*dst)?;
+            Encoding::new(&[sized_op(0x8a, size)]).reg(*dst).rm(*src).sized(size)
+        }
+        (Operand::Reg(dst), Operand::Imm(imm)) if dst.is_gpr() => match dst.size {
+            8 if i32::try_from(*imm).is_ok() => Encoding::new(&[0xc7])
+                .ext(0)
+                .rm(Operand::Reg(*dst))
+                .imm(imm_sx(*imm, 8)?)
+                .sized(8),
+            size => {
+                let opcode = if size == 1 { 0xb0 } else { 0xb8 };
+                Encoding::new(&[opcode]).opreg(*dst).imm(self::imm(*imm, size)?).sized(size)
+            }
+        },
+        (Operand::Mem(_), Operand::Imm(imm)) => {
+            let size = dst.size().ok_or(ErrorKind::AmbiguousSize)?;
+            Encoding::new(&[sized_op(0xc6, size)])
+                .ext(0)
+                .rm(*dst)
+                .imm(imm_sx(*imm, size)?)
+                .sized(size)
+        }
+        (Operand::Reg(dst), src) if dst.bank == Bank::Segment && src.is_rm() => {
+            // `cs` can't be loaded with `mov`
+            if dst.num == 1 {
+                return Err(ErrorKind::InvalidOperands);
+            }
+
+            src.same_size(*dst)?;
+            Encoding::new(&[0x8e]).reg(*dst).rm(*src)
+        }
+        (dst, Operand::Reg(src)) if src.bank == Bank::Segment && dst.is_rm() => {
+            let size = match dst {
+                Operand::Mem(_) => dst.same_size(*src)?,
+                _ => dst.size().unwrap_or(2),
+            };
+
+            Encoding::new(&[0x8c]).reg(*src).rm(*dst).sized(size)
+        }
+        _ => return Err(ErrorKind::InvalidOperands),
+    };
+
+    Ok(encoding)
+}
+
+fn test(dst: &Operand, src: &Operand) -> Result<Encoding, ErrorKind> {
+    let encoding = match (dst, src) {
+        (dst, Operand::Reg(src)) | (Operand::Reg(src), dst) if dst.is_rm() && src.is_gpr() => {
+            let size = dst.same_size(*src)?;
+            Encoding::new(&[sized_op(0x84, size)]).reg(*src).rm(*dst).sized(size)
+        }
+        (dst, Operand::Imm(imm)) if dst.is_rm() => {
+            let size = dst.size().ok_or(ErrorKind::AmbiguousSize)?;
+            let imm = match size {
+                1 => self::imm(*imm, 1)?,
+                _ => imm_sx(*imm, size)?,
+            };
+
+            if dst.gpr().is_some_and(|reg| reg.is_accumulator()) {
+                Encoding::new(&[sized_op(0xa8, size)]).imm(imm).sized(size)
+            } else {
+                Encoding::new(&[sized_op(0xf6, size)]).ext(0).rm(*dst).imm(imm).sized(size)
+            }
+        }
+        _ => return Err(ErrorKind::InvalidOperands),
+    };
+
+    Ok(encoding)
+}
+
+fn xchg(dst: &Operand, src: &Operand) -> Result<Encoding, ErrorKind> {
+    if let (Some(a), Some(b)) = (dst.gpr(), src.gpr()) {
+        if a.size != b.size {
+            return Err(ErrorKind::SizeMismatch);
+        }
+
+        // `xchg eax, eax` can't be `90` as that's a `nop` which doesn't clear the upper bits
+        let short = a.size > 1 && (a.size != 4 || a.num != b.num);
+        if short && (a.is_accumulator() || b.is_accumulator()) {
+            let other = if a.is_accumulator() { b } else { a };
+            return Ok(Encoding::new(&[0x90]).opreg(other).sized(a.size));
+        }
+    }
+
+    match (dst, src) {
+        (rm, Operand::Reg(reg)) | (Operand::Reg(reg), rm) if rm.is_rm() && reg.is_gpr() => {
+            let size = rm.same_size(*reg)?;
+            Ok(Encoding::new(&[sized_op(0x86, size)]).reg(*reg).rm(*rm).sized(size))
+        }
+        _ => Err(ErrorKind::InvalidOperands),
+    }
+}
+
+fn push(op: &Operand, push: bool) -> Result<Encoding, ErrorKind> {
+    let encoding = match op {
+        Operand::Reg(reg) if reg.bank == Bank::Segment && reg.num >= 4 => {
+            let opcode = if reg.num == 4 { 0xa0 } else { 0xa8 };
+            Encoding::new(&[0x0f, opcode + !push as u8])
+        }
+        Operand::Reg(reg) if reg.is_gpr() && (reg.size == 8 || reg.size == 2) => {
+            let opcode = if push { 0x50 } else { 0x58 };
+            let mut encoding = Encoding::new(&[opcode]).opreg(*reg);
+            encoding.opsize = reg.size == 2;
+            encoding
+        }
+        Operand::Mem(mem) if matches!(mem.size, None | Some(8) | Some(2)) => {
+            let (opcode, ext) = if push { (0xff, 6) } else { (0x8f, 0) };
+            let mut encoding = Encoding::new(&[opcode]).ext(ext).rm(*op);
+            encoding.opsize = mem.size == Some(2);
+            encoding
+        }
+        Operand::Imm(imm) if push => {
+            let value = signed(*imm, 4)?;
+            if i8::try_from(value).is_ok() {
+                Encoding::new(&[0x6a]).imm(vec![value as u8])
+            } else {
+                Encoding::new(&[0x68]).imm(self::imm(value, 4)?)
+            }
+        }
+        _ => return Err(ErrorKind::InvalidOperands),
+    };
+
+    Ok(encoding)
+}
+
+/// Moves between general purpose and xmm registers.
+fn movq(quad: bool, dst: &Operand, src: &Operand) -> Result<Encoding, ErrorKind> {
+    let size = if quad { 8 } else { 4 };
+    let sized = |mut encoding: Encoding, op: &Operand| {
+        if op.size().is_some_and(|s| s != size) {
+            return Err(ErrorKind::SizeMismatch);
+        }
+
+        encoding.w = quad;
+        Ok(encoding)
+    };
+
+    match (dst, src) {
+        // `movq xmm, xmm/m64` has it's own encoding that doesn't involve general purpose registers
+        (Operand::Reg(dst), src) if quad && dst.is_xmm() && src.is_xmm_rm() => {
+            Ok(Encoding::new(&[0x0f, 0x7e]).mandatory(Some(0xf3)).reg(*dst).rm(*src))
+        }
+        (dst @ Operand::Mem(_), Operand::Reg(src)) if quad && src.is_xmm() => {
+            Ok(Encoding::new(&[0x0f, 0xd6]).mandatory(Some(0x66)).reg(*src).rm(*dst))
+        }
+        (Operand::Reg(dst), src) if dst.is_xmm() && src.is_rm() => sized(
+            Encoding::new(&[0x0f, 0x6e]).mandatory(Some(0x66)).reg(*dst).rm(*src),
+            src,
+        ),
+        (dst, Operand::Reg(src)) if src.is_xmm() && dst.is_rm() => sized(
+            Encoding::new(&[0x0f, 0x7e]).mandatory(Some(0x66)).reg(*src).rm(*dst),
+            dst,
+        ),
+        _ => Err(ErrorKind::InvalidOperands),
+    }
+}
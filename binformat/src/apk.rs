@@ -0,0 +1,246 @@
+//! Android application packages.
+//!
+//! APKs are ZIP archives, the only parts we care about are the DEX files holding the code. Those
+//! are named `classes.dex`, `classes2.dex` and so on, in the order they're loaded.
+
+use crate::dex::{u16_at, u32_at};
+
+/// Signatures of the records in a ZIP archive.
+const LOCAL_HEADER: u32 = 0x04034b50;
+const CENTRAL_DIRECTORY: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+
+/// How the files in the archive are compressed.
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+
+/// Whether the bytes start like a ZIP archive.
+pub fn is_apk(bytes: &[u8]) -> bool {
+    u32_at(bytes, 0) == Some(LOCAL_HEADER)
+}
+
+/// Extracts the DEX files from an APK, sorted in the order they're loaded.
+///
+/// Files that can't be extracted are skipped.
+pub fn dex_files(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut files = Vec::new();
+
+    let entries = match central_directory(bytes) {
+        Some(entries) => entries,
+        None => {
+            log::complex!(
+                w "[apk::dex_files] ",
+                y "Failed to find the central directory.",
+            );
+            return Vec::new();
+        }
+    };
+
+    for entry in entries {
+        let number = match dex_number(&entry.name) {
+            Some(number) => number,
+            None => continue,
+        };
+
+        match entry.extract(bytes) {
+            Some(data) => files.push((number, entry.name, data)),
+            None => log::complex!(
+                w "[apk::dex_files] ",
+                y "Failed to extract ",
+                b &entry.name,
+                y "."
+            ),
+        }
+    }
+
+    files.sort_unstable_by_key(|(number, ..)| *number);
+    files.into_iter().map(|(_, name, data)| (name, data)).collect()
+}
+
+/// Position of a DEX file in the load order, `classes.dex` being the first.
+fn dex_number(name: &str) -> Option<usize> {
+    let number = name.strip_prefix("classes")?.strip_suffix(".dex")?;
+    match number {
+        "" => Some(1),
+        _ if number.starts_with('0') => None,
+        _ => number.parse().ok().filter(|&number| number > 1),
+    }
+}
+
+/// File in the archive as recorded in the central directory.
+struct Entry {
+    name: String,
+    method: u16,
+    compressed_size: usize,
+    uncompressed_size: usize,
+    /// Offset of the file's local header.
+    offset: usize,
+}
+
+impl Entry {
+    fn extract(&self, bytes: &[u8]) -> Option<Vec<u8>> {
+        if u32_at(bytes, self.offset)? != LOCAL_HEADER {
+            return None;
+        }
+
+        // the local header can have a different extra field than the central directory
+        let name_len = u16_at(bytes, self.offset + 26)? as usize;
+        let extra_len = u16_at(bytes, self.offset + 28)? as usize;
+        let start = self.offset + 30 + name_len + extra_len;
+        let data = bytes.get(start..start.checked_add(self.compressed_size)?)?;
+
+        match self.method {
+            STORED => Some(data.to_vec()),
+            DEFLATED => {
+                miniz_oxide::inflate::decompress_to_vec_with_limit(data, self.uncompressed_size)
+                    .ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Entries of the central directory, found through the end of central directory record.
+fn central_directory(bytes: &[u8]) -> Option<Vec<Entry>> {
+    // the record is at the very end, followed by a comment of at most 65535 bytes
+    let min = bytes.len().saturating_sub(22 + u16::MAX as usize);
+    let eocd = (min..bytes.len().saturating_sub(21))
+        .rev()
+        .find(|&offset| u32_at(bytes, offset) == Some(END_OF_CENTRAL_DIRECTORY))?;
+
+    let len = u16_at(bytes, eocd + 10)? as usize;
+    let mut offset = u32_at(bytes, eocd + 16)? as usize;
+    let mut entries = Vec::with_capacity(len);
+
+    for _ in 0..len {
+        if u32_at(bytes, offset)? != CENTRAL_DIRECTORY {
+            return None;
+        }
+
+        let name_len = u16_at(bytes, offset + 28)? as usize;
+        let extra_len = u16_at(bytes, offset + 30)? as usize;
+        let comment_len = u16_at(bytes, offset + 32)? as usize;
+        let name = bytes.get(offset + 46..offset + 46 + name_len)?;
+
+        entries.push(Entry {
+            name: String::from_utf8_lossy(name).into_owned(),
+            method: u16_at(bytes, offset + 10)?,
+            compressed_size: u32_at(bytes, offset + 20)? as usize,
+            uncompressed_size: u32_at(bytes, offset + 24)? as usize,
+            offset: u32_at(bytes, offset + 42)? as usize,
+        });
+
+        offset += 46 + name_len + extra_len + comment_len;
+    }
+
+    Some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ZIP archive of `files`, compressed with the given method.
+    fn archive(files: &[(&str, u16, &[u8])]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut central = Vec::new();
+
+        for &(name, method, data) in files {
+            let compressed = match method {
+                DEFLATED => miniz_oxide::deflate::compress_to_vec(data, 6),
+                _ => data.to_vec(),
+            };
+
+            let offset = bytes.len() as u32;
+            let sizes = [compressed.len() as u32, data.len() as u32];
+
+            bytes.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+            bytes.extend_from_slice(&[20, 0, 0, 0]);
+            bytes.extend_from_slice(&method.to_le_bytes());
+            bytes.extend_from_slice(&[0; 8]);
+            bytes.extend(sizes.iter().flat_map(|size| size.to_le_bytes()));
+            bytes.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(&[0; 2]);
+            bytes.extend_from_slice(name.as_bytes());
+            bytes.extend_from_slice(&compressed);
+
+            central.extend_from_slice(&CENTRAL_DIRECTORY.to_le_bytes());
+            central.extend_from_slice(&[20, 0, 20, 0, 0, 0]);
+            central.extend_from_slice(&method.to_le_bytes());
+            central.extend_from_slice(&[0; 8]);
+            central.extend(sizes.iter().flat_map(|size| size.to_le_bytes()));
+            central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            central.extend_from_slice(&[0; 12]);
+            central.extend_from_slice(&offset.to_le_bytes());
+            central.extend_from_slice(name.as_bytes());
+        }
+
+        let offset = bytes.len() as u32;
+        let entries = files.len() as u16;
+        bytes.extend_from_slice(&central);
+        bytes.extend_from_slice(&END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&entries.to_le_bytes());
+        bytes.extend_from_slice(&entries.to_le_bytes());
+        bytes.extend_from_slice(&(central.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&offset.to_le_bytes());
+        bytes.extend_from_slice(&[0; 2]);
+        bytes
+    }
+
+    fn files() -> Vec<u8> {
+        archive(&[
+            ("classes2.dex", DEFLATED, &[0x22; 0x200]),
+            ("AndroidManifest.xml", STORED, b"<manifest/>"),
+            ("classes.dex", STORED, b"dex\n035\0"),
+            ("classes02.dex", STORED, b"not loaded"),
+        ])
+    }
+
+    #[test]
+    fn extract() {
+        let bytes = files();
+        assert!(is_apk(&bytes));
+
+        let files = dex_files(&bytes);
+        let names: Vec<_> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["classes.dex", "classes2.dex"]);
+        assert_eq!(files[0].1, b"dex\n035\0");
+        assert_eq!(files[1].1, [0x22; 0x200]);
+    }
+
+    #[test]
+    fn load_order() {
+        assert_eq!(dex_number("classes.dex"), Some(1));
+        assert_eq!(dex_number("classes2.dex"), Some(2));
+        assert_eq!(dex_number("classes10.dex"), Some(10));
+        assert_eq!(dex_number("classes1.dex"), None);
+        assert_eq!(dex_number("classes02.dex"), None);
+        assert_eq!(dex_number("lib/classes.dex.so"), None);
+    }
+
+    #[test]
+    fn corrupt() {
+        // an entry that doesn't inflate is skipped, the others are still extracted
+        let mut bytes = archive(&[
+            ("classes.dex", STORED, b"dex\n035\0"),
+            ("classes2.dex", DEFLATED, &[0x22; 0x200]),
+        ]);
+        let data = 30 + "classes.dex".len() + 8 + 30 + "classes2.dex".len();
+        bytes[data] = 0xff;
+
+        let files = dex_files(&bytes);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].0, "classes.dex");
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = files();
+
+        // the central directory is at the end, without it nothing can be extracted
+        for len in 0..bytes.len() {
+            assert!(dex_files(&bytes[..len]).is_empty());
+        }
+    }
+}
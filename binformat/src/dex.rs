@@ -0,0 +1,609 @@
+//! Dalvik executables.
+//!
+//! DEX files aren't loaded at an address either, so everything is addressed by it's offset in the
+//! file. Files extracted from the same APK are placed one after another, each starting at their
+//! own base.
+
+use crate::RawSymbol;
+use processor_shared::{AddressMap, Addressed, Section, SectionKind};
+use std::ops::Range;
+
+/// Size of the header, the tables start right after it.
+const HEADER_SIZE: usize = 0x70;
+
+/// Byte order marker of a little endian file.
+const ENDIAN_CONSTANT: u32 = 0x12345678;
+
+/// Whether the bytes start like a DEX file, e.g. `dex\n039\0`.
+pub fn is_dex(bytes: &[u8]) -> bool {
+    bytes.len() >= 8 && bytes.starts_with(b"dex\n") && bytes[7] == 0
+}
+
+/// Offsets and sizes of the tables, in the order they appear in the header.
+#[derive(Debug, Default, Clone, Copy)]
+struct Header {
+    link: (usize, usize),
+    string_ids: (usize, usize),
+    type_ids: (usize, usize),
+    proto_ids: (usize, usize),
+    field_ids: (usize, usize),
+    method_ids: (usize, usize),
+    class_defs: (usize, usize),
+    data: (usize, usize),
+}
+
+impl Header {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        if !is_dex(bytes) || u32_at(bytes, 40)? != ENDIAN_CONSTANT {
+            return None;
+        }
+
+        // tables are stored as their size followed by their offset, except for the link and data
+        let table = |offset: usize| Some((u32_at(bytes, offset + 4)?, u32_at(bytes, offset)?));
+        let (link_size, link_off) = (u32_at(bytes, 44)?, u32_at(bytes, 48)?);
+        let (data_size, data_off) = (u32_at(bytes, 104)?, u32_at(bytes, 108)?);
+
+        Some(Self {
+            link: (link_off as usize, link_size as usize),
+            string_ids: usize_pair(table(56)?),
+            type_ids: usize_pair(table(64)?),
+            proto_ids: usize_pair(table(72)?),
+            field_ids: usize_pair(table(80)?),
+            method_ids: usize_pair(table(88)?),
+            class_defs: usize_pair(table(96)?),
+            data: (data_off as usize, data_size as usize),
+        })
+    }
+}
+
+fn usize_pair((off, size): (u32, u32)) -> (usize, usize) {
+    (off as usize, size as usize)
+}
+
+/// Method defined in the file along with it's code.
+#[derive(Debug, Clone)]
+pub struct Code {
+    /// Index of the method in the method table.
+    pub method: usize,
+    /// Number of registers the method uses, including it's arguments.
+    pub registers: u16,
+    /// Number of registers holding the method's arguments, they're the last ones.
+    pub ins: u16,
+    /// Addresses of the method's instructions.
+    pub insns: Range<usize>,
+}
+
+/// The parts of a DEX file required to disassemble it.
+#[derive(Debug, Default)]
+pub struct Dex<'data> {
+    bytes: &'data [u8],
+    /// Address the file starts at.
+    pub base: usize,
+    /// Strings, decoded from modified UTF-8.
+    pub strings: Vec<String>,
+    /// Type descriptors like `Ljava/lang/String;`.
+    pub types: Vec<String>,
+    /// Method prototypes like `(I)V`.
+    pub protos: Vec<String>,
+    /// Field references like `Lcom/foo/Bar;->baz:I`.
+    pub fields: Vec<String>,
+    /// Method references like `Lcom/foo/Bar;->baz(I)V`.
+    pub methods: Vec<String>,
+    /// Methods that aren't abstract or native, sorted by address.
+    pub code: Vec<Code>,
+    header: Header,
+}
+
+impl<'data> Dex<'data> {
+    /// Parses a DEX file that starts at address `base`.
+    pub fn parse(bytes: &'data [u8], base: usize) -> Option<Self> {
+        let header = Header::parse(bytes)?;
+        let mut dex = Self {
+            bytes,
+            base,
+            header,
+            ..Self::default()
+        };
+
+        dex.strings = dex.entries(header.string_ids, 4, |dex, entry| {
+            dex.string(u32_at(bytes, entry)? as usize)
+        });
+        dex.types = dex.entries(header.type_ids, 4, |dex, entry| {
+            dex.strings.get(u32_at(bytes, entry)? as usize).cloned()
+        });
+        dex.protos = dex.entries(header.proto_ids, 12, |dex, entry| dex.proto(entry));
+        dex.fields = dex.entries(header.field_ids, 8, |dex, entry| {
+            let class = dex.types.get(u16_at(bytes, entry)? as usize)?;
+            let tipe = dex.types.get(u16_at(bytes, entry + 2)? as usize)?;
+            let name = dex.strings.get(u32_at(bytes, entry + 4)? as usize)?;
+            Some(format!("{class}->{name}:{tipe}"))
+        });
+        dex.methods = dex.entries(header.method_ids, 8, |dex, entry| {
+            let class = dex.types.get(u16_at(bytes, entry)? as usize)?;
+            let proto = dex.protos.get(u16_at(bytes, entry + 2)? as usize)?;
+            let name = dex.strings.get(u32_at(bytes, entry + 4)? as usize)?;
+            Some(format!("{class}->{name}{proto}"))
+        });
+
+        let (start, len) = header.class_defs;
+        for idx in 0..len {
+            // offset of the class's fields and methods, classes without any have none
+            let class_data_off = match u32_at(bytes, start + idx * 32 + 24) {
+                Some(0) => continue,
+                Some(off) => off as usize,
+                None => break,
+            };
+
+            if dex.parse_class_data(class_data_off).is_none() {
+                log::complex!(
+                    w "[dex::parse] ",
+                    y "Failed to parse class ",
+                    b format!("{idx}"),
+                    y "."
+                );
+            }
+        }
+
+        dex.code.sort_unstable_by_key(|code| code.insns.start);
+        Some(dex)
+    }
+
+    /// Parses every entry of a table, entries that can't be parsed are left empty.
+    fn entries(
+        &self,
+        (start, len): (usize, usize),
+        size: usize,
+        parse: impl Fn(&Self, usize) -> Option<String>,
+    ) -> Vec<String> {
+        (0..len)
+            .map(|idx| parse(self, start + idx * size).unwrap_or_default())
+            .collect()
+    }
+
+    /// String at `offset`, stored as it's length in UTF-16 code units followed by the
+    /// null-terminated modified UTF-8.
+    fn string(&self, offset: usize) -> Option<String> {
+        let mut cursor = Cursor::new(self.bytes.get(offset..)?);
+        cursor.uleb()?;
+
+        let len = cursor.bytes.iter().position(|&b| b == 0)?;
+        Some(mutf8(&cursor.bytes[..len]))
+    }
+
+    /// Prototype at `entry` like `(I[Ljava/lang/String;)V`.
+    fn proto(&self, entry: usize) -> Option<String> {
+        let ret = self.types.get(u32_at(self.bytes, entry + 4)? as usize)?;
+        let params_off = u32_at(self.bytes, entry + 8)? as usize;
+
+        let mut proto = String::from("(");
+
+        // prototypes without parameters don't have a type list
+        if params_off != 0 {
+            for idx in 0..u32_at(self.bytes, params_off)? as usize {
+                let tipe = u16_at(self.bytes, params_off + 4 + idx * 2)?;
+                proto += self.types.get(tipe as usize)?;
+            }
+        }
+
+        proto += ")";
+        proto += ret;
+        Some(proto)
+    }
+
+    /// Collects the code of the methods of a class.
+    fn parse_class_data(&mut self, offset: usize) -> Option<()> {
+        let mut cursor = Cursor::new(self.bytes.get(offset..)?);
+        let static_fields = cursor.uleb()?;
+        let instance_fields = cursor.uleb()?;
+        let direct_methods = cursor.uleb()?;
+        let virtual_methods = cursor.uleb()?;
+
+        for _ in 0..static_fields + instance_fields {
+            cursor.uleb()?;
+            cursor.uleb()?;
+        }
+
+        // method indices are the difference to the previous method in each list
+        for len in [direct_methods, virtual_methods] {
+            let mut method = 0usize;
+            for _ in 0..len {
+                method = method.checked_add(cursor.uleb()? as usize)?;
+                cursor.uleb()?;
+                let code_off = cursor.uleb()? as usize;
+
+                if code_off != 0 {
+                    self.parse_code(method, code_off)?;
+                }
+            }
+        }
+
+        Some(())
+    }
+
+    fn parse_code(&mut self, method: usize, offset: usize) -> Option<()> {
+        let registers = u16_at(self.bytes, offset)?;
+        let ins = u16_at(self.bytes, offset + 2)?;
+        let insns_size = u32_at(self.bytes, offset + 12)? as usize;
+
+        // the instructions follow the code item's header and are made of 16-bit code units
+        let start = offset + 16;
+        let end = start.checked_add(insns_size * 2)?;
+        if end > self.bytes.len() {
+            return None;
+        }
+
+        self.code.push(Code {
+            method,
+            registers,
+            ins,
+            insns: self.base + start..self.base + end,
+        });
+
+        Some(())
+    }
+
+    /// Sections of the file's header, tables and data, the names of files extracted from an APK
+    /// are prefixed with the file's name.
+    pub fn sections(&self, prefix: &str) -> Vec<Section> {
+        let header = self.header;
+        let tables = [
+            ("header", "HEADER", SectionKind::Raw, (0, HEADER_SIZE)),
+            (
+                "string_ids",
+                "STRING_IDS",
+                SectionKind::Raw4,
+                (header.string_ids.0, header.string_ids.1 * 4),
+            ),
+            (
+                "type_ids",
+                "TYPE_IDS",
+                SectionKind::Raw4,
+                (header.type_ids.0, header.type_ids.1 * 4),
+            ),
+            (
+                "proto_ids",
+                "PROTO_IDS",
+                SectionKind::Raw4,
+                (header.proto_ids.0, header.proto_ids.1 * 12),
+            ),
+            (
+                "field_ids",
+                "FIELD_IDS",
+                SectionKind::Raw8,
+                (header.field_ids.0, header.field_ids.1 * 8),
+            ),
+            (
+                "method_ids",
+                "METHOD_IDS",
+                SectionKind::Raw8,
+                (header.method_ids.0, header.method_ids.1 * 8),
+            ),
+            (
+                "class_defs",
+                "CLASS_DEFS",
+                SectionKind::Raw4,
+                (header.class_defs.0, header.class_defs.1 * 32),
+            ),
+            // the code of every method is somewhere in the data
+            ("data", "DATA", SectionKind::Code, header.data),
+            ("link", "LINK", SectionKind::Raw, header.link),
+        ];
+
+        let mut sections = Vec::new();
+        for (name, ident, kind, (start, len)) in tables {
+            if len == 0 {
+                continue;
+            }
+
+            let bytes = match start.checked_add(len).and_then(|end| self.bytes.get(start..end)) {
+                Some(bytes) => bytes,
+                None => {
+                    log::complex!(
+                        w "[dex::sections] ",
+                        y "Section ",
+                        b name,
+                        y " is out of bounds."
+                    );
+                    continue;
+                }
+            };
+
+            // The file is either memory mapped or extracted into a buffer owned by the
+            // processor, so the bytes live as long as the sections.
+            let bytes: &'static [u8] = unsafe { std::mem::transmute(bytes) };
+            let start = self.base + start;
+
+            sections.push(Section::new(
+                format!("{prefix}{name}"),
+                ident,
+                kind,
+                bytes,
+                start,
+                start + len,
+            ));
+        }
+
+        sections
+    }
+
+    /// Symbols at the start of every method's instructions, named by their method reference.
+    pub fn symbols(&self) -> AddressMap<RawSymbol<'_>> {
+        let mut syms = AddressMap::default();
+
+        for code in self.code.iter() {
+            if let Some(name) = self.methods.get(code.method) {
+                syms.push(Addressed {
+                    addr: code.insns.start,
+                    item: RawSymbol { name, module: None },
+                });
+            }
+        }
+
+        syms
+    }
+}
+
+/// Decodes modified UTF-8, which encodes null as two bytes and characters outside of the basic
+/// multilingual plane as a surrogate pair of three bytes each.
+fn mutf8(bytes: &[u8]) -> String {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        let byte = bytes[idx] as u16;
+        let next = |offset: usize| bytes.get(idx + offset).map_or(0, |&b| b as u16 & 0x3f);
+
+        let (unit, len) = match byte {
+            0x00..=0x7f => (byte, 1),
+            0xc0..=0xdf => ((byte & 0x1f) << 6 | next(1), 2),
+            0xe0..=0xef => ((byte & 0x0f) << 12 | next(1) << 6 | next(2), 3),
+            _ => (char::REPLACEMENT_CHARACTER as u16, 1),
+        };
+
+        units.push(unit);
+        idx += len;
+    }
+
+    String::from_utf16_lossy(&units)
+}
+
+pub(crate) fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    let bytes = bytes.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes(bytes.try_into().ok()?))
+}
+
+pub(crate) fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+struct Cursor<'data> {
+    bytes: &'data [u8],
+}
+
+impl<'data> Cursor<'data> {
+    fn new(bytes: &'data [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn uleb(&mut self) -> Option<u32> {
+        let mut value = 0u32;
+
+        // values are at most 32 bits, so at most five bytes long
+        for shift in (0..35).step_by(7) {
+            let (&byte, rest) = self.bytes.split_first()?;
+            self.bytes = rest;
+            value |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put_u16(bytes: &mut [u8], offset: usize, value: u16) {
+        bytes[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(bytes: &mut [u8], offset: usize, value: u32) {
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn uleb(bytes: &mut Vec<u8>, mut value: u32) {
+        while value >= 0x80 {
+            bytes.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+    }
+
+    /// Class `LFoo;` with a field `count` and the methods `main()V` and `add(II)I`.
+    fn fixture() -> Vec<u8> {
+        const STRING_IDS: usize = HEADER_SIZE;
+        const TYPE_IDS: usize = STRING_IDS + 7 * 4;
+        const PROTO_IDS: usize = TYPE_IDS + 3 * 4;
+        const FIELD_IDS: usize = PROTO_IDS + 2 * 12;
+        const METHOD_IDS: usize = FIELD_IDS + 8;
+        const CLASS_DEFS: usize = METHOD_IDS + 2 * 8;
+        const DATA: usize = CLASS_DEFS + 32;
+
+        let mut bytes = vec![0; DATA];
+        bytes[..8].copy_from_slice(b"dex\n035\0");
+        put_u32(&mut bytes, 40, ENDIAN_CONSTANT);
+        for (offset, size, table) in [
+            (56, 7, STRING_IDS),
+            (64, 3, TYPE_IDS),
+            (72, 2, PROTO_IDS),
+            (80, 1, FIELD_IDS),
+            (88, 2, METHOD_IDS),
+            (96, 1, CLASS_DEFS),
+        ] {
+            put_u32(&mut bytes, offset, size);
+            put_u32(&mut bytes, offset + 4, table as u32);
+        }
+
+        // `caf\u{e9}` and a null character are encoded as modified UTF-8
+        let strings: [&[u8]; 7] = [
+            b"\x01I\0",
+            b"\x05LFoo;\0",
+            b"\x01V\0",
+            b"\x03add\0",
+            b"\x05count\0",
+            b"\x04main\0",
+            b"\x05caf\xc3\xa9\xc0\x80\0",
+        ];
+        for (idx, string) in strings.iter().enumerate() {
+            let offset = bytes.len() as u32;
+            put_u32(&mut bytes, STRING_IDS + idx * 4, offset);
+            bytes.extend_from_slice(string);
+        }
+
+        // types `I`, `LFoo;` and `V`
+        for (idx, string) in [0, 1, 2].into_iter().enumerate() {
+            put_u32(&mut bytes, TYPE_IDS + idx * 4, string);
+        }
+
+        bytes.resize(bytes.len().next_multiple_of(4), 0);
+        let params = bytes.len() as u32;
+        bytes.extend_from_slice(&[2, 0, 0, 0, 0, 0, 0, 0]);
+
+        // `()V` and `(II)I`
+        put_u32(&mut bytes, PROTO_IDS + 4, 2);
+        put_u32(&mut bytes, PROTO_IDS + 12 + 4, 0);
+        put_u32(&mut bytes, PROTO_IDS + 12 + 8, params);
+
+        put_u16(&mut bytes, FIELD_IDS, 1);
+        put_u16(&mut bytes, FIELD_IDS + 2, 0);
+        put_u32(&mut bytes, FIELD_IDS + 4, 4);
+
+        for (idx, (proto, name)) in [(0, 5), (1, 3)].into_iter().enumerate() {
+            put_u16(&mut bytes, METHOD_IDS + idx * 8, 1);
+            put_u16(&mut bytes, METHOD_IDS + idx * 8 + 2, proto);
+            put_u32(&mut bytes, METHOD_IDS + idx * 8 + 4, name);
+        }
+
+        // `return-void`, and `add-int v0, v1, v2` followed by `return v0`
+        let mut code_offs = Vec::new();
+        for (registers, ins, insns) in [(1, 0, &[0x000e][..]), (3, 2, &[0x0090, 0x0201, 0x000f])] {
+            bytes.resize(bytes.len().next_multiple_of(4), 0);
+            code_offs.push(bytes.len() as u32);
+            bytes.extend_from_slice(&u16::to_le_bytes(registers));
+            bytes.extend_from_slice(&u16::to_le_bytes(ins));
+            bytes.extend_from_slice(&[0; 8]);
+            bytes.extend_from_slice(&u32::to_le_bytes(insns.len() as u32));
+            bytes.extend(insns.iter().flat_map(|unit: &u16| unit.to_le_bytes()));
+        }
+
+        // no fields and the direct methods 0 and 1, each stored as the difference to the last
+        let class_data = bytes.len() as u32;
+        bytes.extend_from_slice(&[0, 0, 2, 0]);
+        for (diff, code_off) in [0, 1].into_iter().zip(code_offs) {
+            bytes.extend_from_slice(&[diff, 0x09]);
+            uleb(&mut bytes, code_off);
+        }
+        put_u32(&mut bytes, CLASS_DEFS + 24, class_data);
+
+        let len = bytes.len() as u32;
+        put_u32(&mut bytes, 32, len);
+        put_u32(&mut bytes, 104, len - DATA as u32);
+        put_u32(&mut bytes, 108, DATA as u32);
+        bytes
+    }
+
+    #[test]
+    fn tables() {
+        let bytes = fixture();
+        assert!(is_dex(&bytes));
+
+        let dex = Dex::parse(&bytes, 0x1000).unwrap();
+        assert_eq!(
+            dex.strings,
+            ["I", "LFoo;", "V", "add", "count", "main", "caf\u{e9}\0"]
+        );
+        assert_eq!(dex.types, ["I", "LFoo;", "V"]);
+        assert_eq!(dex.protos, ["()V", "(II)I"]);
+        assert_eq!(dex.fields, ["LFoo;->count:I"]);
+        assert_eq!(dex.methods, ["LFoo;->main()V", "LFoo;->add(II)I"]);
+
+        let code: Vec<_> = dex
+            .code
+            .iter()
+            .map(|code| (code.method, code.registers, code.ins, code.insns.len()))
+            .collect();
+        assert_eq!(code, [(0, 1, 0, 2), (1, 3, 2, 6)]);
+
+        // instructions are addressed relative to the base the file starts at
+        let add = &dex.code[1];
+        let start = add.insns.start - 0x1000;
+        assert_eq!(
+            bytes[start..start + 6],
+            [0x90, 0x00, 0x01, 0x02, 0x0f, 0x00]
+        );
+
+        let syms: Vec<_> = dex.symbols().mapping.iter().map(|s| (s.addr, s.item.name)).collect();
+        assert_eq!(
+            syms,
+            [
+                (dex.code[0].insns.start, "LFoo;->main()V"),
+                (add.insns.start, "LFoo;->add(II)I")
+            ]
+        );
+    }
+
+    #[test]
+    fn sections() {
+        let bytes = fixture();
+        let dex = Dex::parse(&bytes, 0x1000).unwrap();
+        let sections = dex.sections("classes.dex:");
+
+        let names: Vec<_> = sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "classes.dex:header",
+                "classes.dex:string_ids",
+                "classes.dex:type_ids",
+                "classes.dex:proto_ids",
+                "classes.dex:field_ids",
+                "classes.dex:method_ids",
+                "classes.dex:class_defs",
+                "classes.dex:data",
+            ]
+        );
+
+        for section in &sections {
+            let start = section.start - 0x1000;
+            assert_eq!(section.bytes(), &bytes[start..section.end - 0x1000]);
+        }
+
+        let data = sections.iter().find(|s| s.kind == SectionKind::Code).unwrap();
+        assert!(dex.code.iter().all(|code| data.start <= code.insns.start));
+        assert_eq!(data.end, 0x1000 + bytes.len());
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = fixture();
+
+        assert!(Dex::parse(&bytes[..HEADER_SIZE - 1], 0).is_none());
+        for len in 0..bytes.len() {
+            if let Some(dex) = Dex::parse(&bytes[..len], 0) {
+                // tables past the end are left out
+                for section in dex.sections("") {
+                    assert!(section.end <= len);
+                }
+                assert!(dex.code.iter().all(|code| code.insns.end <= len));
+            }
+        }
+
+        let mut corrupt = bytes.clone();
+        put_u32(&mut corrupt, 40, ENDIAN_CONSTANT.swap_bytes());
+        assert!(Dex::parse(&corrupt, 0).is_none());
+    }
+}
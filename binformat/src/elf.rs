@@ -1,11 +1,11 @@
 use std::fmt;
-use crate::{datastructure, RawSymbol};
+use crate::{datastructure, Datastructure, RawSymbol, ToData};
 use processor_shared::{AddressMap, Addressed, Section, SectionKind};
 use object::elf;
 use object::read::elf::{ElfFile, FileHeader, SectionHeader};
 use object::{
-    Endian, Object, ObjectSection, ObjectSymbol, ObjectSymbolTable, RelocationKind,
-    RelocationTarget,
+    Architecture, Endian, Object, ObjectKind, ObjectSection, ObjectSymbol, ObjectSymbolTable,
+    RelocationKind, RelocationTarget, SectionFlags, SectionIndex, SymbolKind,
 };
 
 pub struct ElfDebugInfo<'data, Elf: FileHeader> {
@@ -95,7 +95,11 @@ impl<'data, Elf: FileHeader> ElfDebugInfo<'data, Elf> {
     }
 
     pub fn parse_symbols(&mut self) {
-        self.syms.extend(crate::parse_symbol_table(self.obj));
+        match section_bases(self.obj) {
+            Some(bases) => self.parse_relocatable_symbols(&bases),
+            None => self.syms.extend(crate::parse_symbol_table(self.obj)),
+        }
+
         self.syms.push(Addressed {
             addr: self.obj.entry() as usize,
             item: RawSymbol {
@@ -104,8 +108,70 @@ impl<'data, Elf: FileHeader> ElfDebugInfo<'data, Elf> {
             },
         });
     }
+
+    /// Symbols of relocatable objects are relative to the section they're defined in.
+    fn parse_relocatable_symbols(&mut self, bases: &[usize]) {
+        // section symbols would shadow the first symbol in their section
+        for sym in self.obj.symbols().filter(|sym| sym.kind() != SymbolKind::Section) {
+            let base = match sym.section_index() {
+                Some(SectionIndex(idx)) => bases[idx],
+                None => continue,
+            };
+
+            if let Ok(name) = sym.name() {
+                self.syms.push(Addressed {
+                    addr: base + sym.address() as usize,
+                    item: RawSymbol { name, module: None },
+                });
+            }
+        }
+    }
 }
 
+/// Address the first section of a relocatable object is placed at.
+const RELOCATABLE_BASE: usize = 0x1000;
+
+/// Addresses to place the sections of a relocatable object at, indexed by section index.
+///
+/// All sections of a relocatable object start at address zero, so the loaded ones are laid out
+/// one after another like a linker would. This is only done for eBPF, as it's objects are what's
+/// loaded into the kernel and never get linked.
+pub fn section_bases<'data: 'file, 'file, O: Object<'data, 'file>>(
+    obj: &'file O,
+) -> Option<Vec<usize>> {
+    if obj.kind() != ObjectKind::Relocatable || obj.architecture() != Architecture::Bpf {
+        return None;
+    }
+
+    let mut bases = Vec::new();
+    let mut addr = RELOCATABLE_BASE;
+    for section in obj.sections() {
+        let idx = section.index().0;
+        if bases.len() <= idx {
+            bases.resize(idx + 1, 0);
+        }
+
+        let is_alloc = match section.flags() {
+            SectionFlags::Elf { sh_flags } => sh_flags as u32 & elf::SHF_ALLOC != 0,
+            _ => false,
+        };
+
+        if !is_alloc || section.size() == 0 {
+            bases[idx] = section.address() as usize;
+            continue;
+        }
+
+        addr = addr.next_multiple_of(section.align().max(1) as usize);
+        bases[idx] = addr;
+        addr += section.size() as usize;
+    }
+
+    Some(bases)
+}
+
+/// Section type of `.riscv.attributes`, it's value is reused by other architectures.
+const SHT_RISCV_ATTRIBUTES: u32 = 0x70000003;
+
 /// Common ELF dwarf section names I've found so far.
 const DWARF_SECTIONS: [&str; 20] = [
     ".debug_abbrev",
@@ -134,10 +200,16 @@ fn parse_sections<'data, Elf: FileHeader>(obj: &'data ElfFile<'data, Elf>) -> Ve
     let mut sections = Vec::new();
     let endian = obj.endian();
     let section_headers = obj.raw_header().sections(endian, obj.data()).unwrap();
+    let bases = section_bases(obj);
 
     for (header, section) in section_headers.iter().zip(obj.sections()) {
         let sh_flags = header.sh_flags(endian).into();
-        let (name, bytes, start, end) = crate::parse_section_generics(&section);
+        let (name, bytes, mut start, mut end) = crate::parse_section_generics(&section);
+
+        if let Some(ref bases) = bases {
+            end = bases[section.index().0] + (end - start);
+            start = bases[section.index().0];
+        }
 
         let (mut kind, ident) = match header.sh_type(endian) {
             // Section header table entry is unused.
@@ -202,6 +274,10 @@ fn parse_sections<'data, Elf: FileHeader>(obj: &'data ElfFile<'data, Elf>) -> Ve
             elf::SHT_GNU_VERNEED => (SectionKind::Raw, "GNU_VERNEED"),
             // Version symbol table.
             elf::SHT_GNU_VERSYM => (SectionKind::Raw, "GNU_VERSYM"),
+            // RISC-V build attributes.
+            SHT_RISCV_ATTRIBUTES if obj.raw_header().e_machine(endian) == elf::EM_RISCV => {
+                (SectionKind::RiscvAttributes, "RISCV_ATTRIBUTES")
+            }
             // Start of processor-specific section types.
             elf::SHT_LOPROC => (SectionKind::Raw, "LOPROC"),
             // End of processor-specific section types.
@@ -238,7 +314,7 @@ fn parse_sections<'data, Elf: FileHeader>(obj: &'data ElfFile<'data, Elf>) -> Ve
         }
 
         // Section that isn't loaded into memory at all.
-        if sh_flags as u32 & elf::SHF_ALLOC == 0 {
+        if sh_flags as u32 & elf::SHF_ALLOC == 0 && kind != SectionKind::RiscvAttributes {
             kind = SectionKind::Unloaded;
         }
 
@@ -386,3 +462,161 @@ datastructure! {
         d_val: u32,
     }
 }
+
+/// Value of a build attribute.
+enum Attribute<'data> {
+    Byte(u8),
+    Word(u32),
+    Uleb(u64),
+    Str(&'data str),
+}
+
+/// Contents of a `.riscv.attributes` section, recording the ISA and ABI an object was built for.
+pub struct RiscvAttributes<'data> {
+    bytes: &'data [u8],
+}
+
+impl<'data> RiscvAttributes<'data> {
+    pub fn new(bytes: &'data [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Target ISA string, e.g. `rv64i2p1_m2p0_a2p1_c2p0_zicsr2p0`.
+    pub fn arch(&self) -> Option<&'data str> {
+        let mut arch = None;
+        self.walk(|_, name, value| {
+            if let ("arch", Attribute::Str(value)) = (name, value) {
+                arch = Some(value);
+            }
+        });
+        arch
+    }
+
+    /// Visits every field of the section with it's offset, name and value.
+    ///
+    /// Only the file scoped attributes of the `riscv` vendor are parsed, any other
+    /// subsections are skipped. Parsing stops at the first malformed field.
+    fn walk(&self, mut visit: impl FnMut(usize, &'static str, Attribute<'data>)) {
+        let bytes = self.bytes;
+
+        if bytes.first() != Some(&b'A') {
+            return;
+        }
+        visit(0, "format_version", Attribute::Byte(b'A'));
+
+        let mut offset = 1;
+        while let Some(length) = read_u32(bytes, offset) {
+            let end = offset + length as usize;
+            if length < 4 || end > bytes.len() {
+                return;
+            }
+            visit(offset, "length", Attribute::Word(length));
+
+            let vendor = match read_str(&bytes[..end], offset + 4) {
+                Some(vendor) => vendor,
+                None => return,
+            };
+            visit(offset + 4, "vendor", Attribute::Str(vendor));
+
+            let mut pos = offset + 4 + vendor.len() + 1;
+            while vendor == "riscv" && pos < end {
+                let (tag, tag_len) = match read_uleb(&bytes[..end], pos) {
+                    Some(tag) => tag,
+                    None => return,
+                };
+                let size = match read_u32(&bytes[..end], pos + tag_len) {
+                    Some(size) if size as usize > tag_len + 4 && pos + size as usize <= end => size,
+                    _ => return,
+                };
+                visit(pos, "scope", Attribute::Uleb(tag));
+                visit(pos + tag_len, "size", Attribute::Word(size));
+
+                // section and symbol scoped attributes aren't emitted by any toolchain
+                let scope_end = pos + size as usize;
+                pos += tag_len + 4;
+                while tag == 1 && pos < scope_end {
+                    let (tag, tag_len) = match read_uleb(&bytes[..scope_end], pos) {
+                        Some(tag) => tag,
+                        None => return,
+                    };
+
+                    let name = match tag {
+                        4 => "stack_align",
+                        5 => "arch",
+                        6 => "unaligned_access",
+                        8 => "priv_spec",
+                        10 => "priv_spec_minor",
+                        12 => "priv_spec_revision",
+                        14 => "atomic_abi",
+                        16 => "x3_reg_usage",
+                        _ => "unknown",
+                    };
+
+                    // odd tags hold strings, even tags hold numbers
+                    let value_pos = pos + tag_len;
+                    let (value, value_len) = if tag % 2 == 1 {
+                        match read_str(&bytes[..scope_end], value_pos) {
+                            Some(value) => (Attribute::Str(value), value.len() + 1),
+                            None => return,
+                        }
+                    } else {
+                        match read_uleb(&bytes[..scope_end], value_pos) {
+                            Some((value, len)) => (Attribute::Uleb(value), len),
+                            None => return,
+                        }
+                    };
+
+                    visit(pos, name, value);
+                    pos = value_pos + value_len;
+                }
+                pos = scope_end;
+            }
+
+            offset = end;
+        }
+    }
+}
+
+impl ToData for RiscvAttributes<'_> {
+    fn to_fields(&self, addr: usize) -> Datastructure {
+        let mut fields = Vec::new();
+        self.walk(|offset, name, value| {
+            let (tipe, value) = match value {
+                Attribute::Byte(value) => ("u8", format!("{value:#x}")),
+                Attribute::Word(value) => ("u32", format!("{value:#x}")),
+                Attribute::Uleb(value) => ("uleb128", format!("{value:#x}")),
+                Attribute::Str(value) => ("ntbs", format!("{value:?}")),
+            };
+            fields.push((addr + offset, name, tipe, value));
+        });
+
+        Datastructure {
+            ident: "RiscvAttributes",
+            fields,
+        }
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Reads a null terminated string.
+fn read_str(bytes: &[u8], offset: usize) -> Option<&str> {
+    let bytes = bytes.get(offset..)?;
+    let len = bytes.iter().position(|&b| b == 0)?;
+    std::str::from_utf8(&bytes[..len]).ok()
+}
+
+/// Reads an unsigned LEB128 number, returning it's value and encoded length.
+fn read_uleb(bytes: &[u8], offset: usize) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (idx, &byte) in bytes.get(offset..)?.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as u64) << (idx * 7);
+        if byte & 0x80 == 0 {
+            return Some((value, idx + 1));
+        }
+    }
+    None
+}
@@ -1,9 +1,12 @@
 use object::{Object, ObjectSection, ObjectSymbol};
 use processor_shared::{AddressMap, Addressed};
 
+pub mod apk;
+pub mod dex;
 pub mod elf;
 pub mod macho;
 pub mod pe;
+pub mod wasm;
 
 pub struct RawSymbol<'data> {
     pub name: &'data str,
@@ -0,0 +1,764 @@
+//! WebAssembly modules.
+//!
+//! Modules don't have an address space their code is loaded at, so everything is addressed by
+//! it's offset in the file instead.
+
+use crate::{Datastructure, RawSymbol, ToData};
+use object::{Object, ObjectSection};
+use processor_shared::{AddressMap, Addressed, Section, SectionKind};
+use std::ops::Range;
+
+pub struct WasmDebugInfo<'data> {
+    /// Parsed sections with extra metadata.
+    pub sections: Vec<Section>,
+    /// Any parsed but not yet relocated symbols.
+    pub syms: AddressMap<RawSymbol<'data>>,
+}
+
+impl<'data> WasmDebugInfo<'data> {
+    pub fn parse<'file, Obj: Object<'data, 'file>>(obj: &'file Obj) -> Result<Self, object::Error>
+    where
+        'data: 'file,
+    {
+        let module = Module::parse(obj);
+        let mut syms = AddressMap::default();
+
+        for (idx, function) in module.functions.iter().enumerate() {
+            let body = match function {
+                Function::Local(body) => body,
+                Function::Import { .. } => continue,
+            };
+
+            if let Some(name) = module.name(idx) {
+                syms.push(Addressed {
+                    addr: body.start,
+                    item: RawSymbol { name, module: None },
+                });
+            }
+        }
+
+        if let Some(Function::Local(body)) = module.start.and_then(|idx| module.functions.get(idx))
+        {
+            syms.push(Addressed {
+                addr: body.start,
+                item: RawSymbol {
+                    name: "entry",
+                    module: None,
+                },
+            });
+        }
+
+        Ok(Self {
+            sections: parse_sections(obj),
+            syms,
+        })
+    }
+}
+
+fn parse_sections<'data: 'file, 'file, Obj: Object<'data, 'file>>(obj: &'file Obj) -> Vec<Section> {
+    let mut sections = Vec::new();
+
+    for section in obj.sections() {
+        let name = section.name().unwrap_or("unknown");
+        let (start, bytes) = match (section.file_range(), section.data()) {
+            (Some((start, _)), Ok(bytes)) => (start as usize, bytes),
+            _ => {
+                log::complex!(
+                    w "[wasm::parse_sections] ",
+                    y "Failed to read section ",
+                    b name,
+                    y "."
+                );
+                continue;
+            }
+        };
+
+        // The file is memory mapped so only the bytes are of lifetime &'static [u8].
+        let bytes: &'static [u8] = unsafe { std::mem::transmute(bytes) };
+
+        let (kind, ident) = match name {
+            // Function signatures.
+            "<type>" => (SectionKind::WasmType, "TYPE"),
+            // Imported functions, tables, memories and globals.
+            "<import>" => (SectionKind::WasmImport, "IMPORT"),
+            // Signatures of the functions in the code section.
+            "<function>" => (SectionKind::Raw, "FUNCTION"),
+            "<table>" => (SectionKind::Raw, "TABLE"),
+            "<memory>" => (SectionKind::Raw, "MEMORY"),
+            "<global>" => (SectionKind::WasmGlobal, "GLOBAL"),
+            "<export>" => (SectionKind::WasmExport, "EXPORT"),
+            // Index of the function called when the module is instantiated.
+            "<start>" => (SectionKind::Raw, "START"),
+            // Initializers of tables.
+            "<element>" => (SectionKind::Raw, "ELEMENT"),
+            // Bodies of the functions defined in the module.
+            "<code>" => (SectionKind::Code, "CODE"),
+            // Initializers of memories.
+            "<data>" => (SectionKind::Raw, "DATA"),
+            "<data_count>" => (SectionKind::Raw, "DATA_COUNT"),
+            // Custom sections holding DWARF debug info.
+            _ if name.starts_with(".debug_") => (SectionKind::Debug, "CUSTOM"),
+            _ => (SectionKind::Raw, "CUSTOM"),
+        };
+
+        sections.push(Section::new(
+            name.to_string(),
+            ident,
+            kind,
+            bytes,
+            start,
+            start + bytes.len(),
+        ));
+    }
+
+    sections
+}
+
+/// Body of a function defined in the module.
+#[derive(Debug, Clone)]
+pub struct Body {
+    /// File offset of the body, including it's size and local declarations.
+    pub start: usize,
+    /// File offsets of the function's instructions.
+    pub code: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Function<'data> {
+    Import {
+        module: &'data str,
+        name: &'data str,
+    },
+    Local(Body),
+}
+
+/// The parts of a module required to disassemble it.
+#[derive(Debug, Default)]
+pub struct Module<'data> {
+    /// Functions in index space order, imported functions come first.
+    pub functions: Vec<Function<'data>>,
+    /// Index of the function called when the module is instantiated.
+    pub start: Option<usize>,
+    /// Function names from the name section.
+    names: Vec<(usize, &'data str)>,
+    /// Names of exported functions.
+    exports: Vec<(usize, &'data str)>,
+}
+
+impl<'data> Module<'data> {
+    pub fn parse<'file, Obj: Object<'data, 'file>>(obj: &'file Obj) -> Self
+    where
+        'data: 'file,
+    {
+        let mut module = Self::default();
+        let mut code = None;
+
+        for section in obj.sections() {
+            let (name, bytes) = match (section.name(), section.data()) {
+                (Ok(name), Ok(bytes)) => (name, bytes),
+                _ => continue,
+            };
+
+            let start = match section.file_range() {
+                Some((start, _)) => start as usize,
+                None => continue,
+            };
+
+            let mut cursor = Cursor::new(bytes);
+            match name {
+                "<import>" => {
+                    let _ = module.parse_imports(&mut cursor);
+                }
+                "<export>" => {
+                    let _ = module.parse_exports(&mut cursor);
+                }
+                "<start>" => module.start = cursor.u32().map(|idx| idx as usize),
+                "<code>" => code = Some((start, bytes)),
+                "name" => {
+                    let _ = module.parse_names(&mut cursor);
+                }
+                _ => {}
+            }
+        }
+
+        // Local functions are indexed after all imports, so the import section has to be
+        // parsed first.
+        if let Some((start, bytes)) = code {
+            let _ = module.parse_bodies(&mut Cursor::new(bytes), start);
+        }
+
+        module
+    }
+
+    /// Name of the function at `idx`, from the name section or otherwise it's export.
+    pub fn name(&self, idx: usize) -> Option<&'data str> {
+        let find = |names: &[(usize, &'data str)]| {
+            names.iter().find(|(name_idx, _)| *name_idx == idx).map(|(_, name)| *name)
+        };
+
+        find(&self.names).or_else(|| find(&self.exports))
+    }
+
+    fn parse_imports(&mut self, cursor: &mut Cursor<'data>) -> Option<()> {
+        for _ in 0..cursor.u32()? {
+            let module = cursor.name()?;
+            let name = cursor.name()?;
+            if let ImportDesc::Func(_) = ImportDesc::parse(cursor)? {
+                self.functions.push(Function::Import { module, name });
+            }
+        }
+        Some(())
+    }
+
+    fn parse_exports(&mut self, cursor: &mut Cursor<'data>) -> Option<()> {
+        for _ in 0..cursor.u32()? {
+            let name = cursor.name()?;
+            let kind = cursor.byte()?;
+            let idx = cursor.u32()? as usize;
+            if kind == 0 {
+                self.exports.push((idx, name));
+            }
+        }
+        Some(())
+    }
+
+    /// Parses the function names subsection, any other subsections are skipped.
+    fn parse_names(&mut self, cursor: &mut Cursor<'data>) -> Option<()> {
+        while let Some(id) = cursor.byte() {
+            let size = cursor.u32()? as usize;
+            let mut subsection = Cursor::new(cursor.bytes(size)?);
+
+            if id == 1 {
+                for _ in 0..subsection.u32()? {
+                    let idx = subsection.u32()? as usize;
+                    let name = subsection.name()?;
+                    self.names.push((idx, name));
+                }
+            }
+        }
+        Some(())
+    }
+
+    fn parse_bodies(&mut self, cursor: &mut Cursor<'data>, base: usize) -> Option<()> {
+        for _ in 0..cursor.u32()? {
+            let start = base + cursor.offset;
+            let size = cursor.u32()? as usize;
+            let end = base + cursor.offset + size;
+            let mut body = Cursor::new(cursor.bytes(size)?);
+
+            for _ in 0..body.u32()? {
+                body.u32()?;
+                body.byte()?;
+            }
+
+            self.functions.push(Function::Local(Body {
+                start,
+                code: end - body.remaining()..end,
+            }));
+        }
+        Some(())
+    }
+}
+
+/// Entries of a type, import, export or global section, shown as data structures.
+pub struct Entries<'data> {
+    kind: SectionKind,
+    bytes: &'data [u8],
+    /// File offset of the section.
+    start: usize,
+}
+
+impl<'data> Entries<'data> {
+    pub fn new(kind: SectionKind, bytes: &'data [u8], start: usize) -> Self {
+        Self { kind, bytes, start }
+    }
+
+    /// File offsets of every entry, starting with the vector's length.
+    pub fn offsets(&self) -> Vec<usize> {
+        let mut offsets: Vec<usize> = Vec::new();
+        self.walk(|entry, _, _, _, _| {
+            if offsets.last() != Some(&entry) {
+                offsets.push(entry);
+            }
+        });
+        offsets.into_iter().map(|offset| self.start + offset).collect()
+    }
+
+    /// Visits every field of the section with the offset of the entry it belongs to, and it's
+    /// own offset, name, type and value.
+    ///
+    /// Parsing stops at the first malformed entry.
+    fn walk(&self, mut visit: impl FnMut(usize, usize, &'static str, &'static str, String)) {
+        let mut cursor = Cursor::new(self.bytes);
+
+        let len = match cursor.u32() {
+            Some(len) => len,
+            None => return,
+        };
+        visit(0, 0, "len", "u32", format!("{len:#x}"));
+
+        for _ in 0..len {
+            let entry = cursor.offset;
+            let mut fields = Vec::new();
+            let mut field = |cursor: &Cursor, name, tipe, value| {
+                fields.push((cursor.offset, name, tipe, value));
+            };
+
+            let parsed = match self.kind {
+                SectionKind::WasmType => parse_type(&mut cursor, &mut field),
+                SectionKind::WasmImport => parse_import(&mut cursor, &mut field),
+                SectionKind::WasmExport => parse_export(&mut cursor, &mut field),
+                SectionKind::WasmGlobal => parse_global(&mut cursor, &mut field),
+                _ => None,
+            };
+
+            if parsed.is_none() {
+                return;
+            }
+
+            for (offset, name, tipe, value) in fields {
+                visit(entry, offset, name, tipe, value);
+            }
+        }
+    }
+}
+
+impl ToData for Entries<'_> {
+    fn to_fields(&self, addr: usize) -> Datastructure {
+        let offset = addr - self.start;
+        let mut fields = Vec::new();
+        self.walk(|entry, field, name, tipe, value| {
+            if entry == offset {
+                fields.push((self.start + field, name, tipe, value));
+            }
+        });
+
+        let ident = match self.kind {
+            _ if offset == 0 => "vec",
+            SectionKind::WasmType => "functype",
+            SectionKind::WasmImport => "import",
+            SectionKind::WasmExport => "export",
+            _ => "global",
+        };
+
+        Datastructure { ident, fields }
+    }
+}
+
+/// Callback receiving the cursor positioned at a field along with it's name, type and value.
+type Field<'a> = dyn FnMut(&Cursor, &'static str, &'static str, String) + 'a;
+
+fn parse_type(cursor: &mut Cursor, field: &mut Field) -> Option<()> {
+    field(cursor, "form", "u8", format!("{:#x}", cursor.peek()?));
+    if cursor.byte()? != 0x60 {
+        return None;
+    }
+
+    field(
+        cursor,
+        "params",
+        "resulttype",
+        result_type(&mut cursor.clone())?,
+    );
+    result_type(cursor)?;
+    field(
+        cursor,
+        "results",
+        "resulttype",
+        result_type(&mut cursor.clone())?,
+    );
+    result_type(cursor)?;
+    Some(())
+}
+
+fn parse_import(cursor: &mut Cursor, field: &mut Field) -> Option<()> {
+    field(
+        cursor,
+        "module",
+        "name",
+        format!("{:?}", cursor.clone().name()?),
+    );
+    cursor.name()?;
+    field(
+        cursor,
+        "name",
+        "name",
+        format!("{:?}", cursor.clone().name()?),
+    );
+    cursor.name()?;
+
+    let desc = cursor.clone();
+    let (tipe, value) = match ImportDesc::parse(cursor)? {
+        ImportDesc::Func(idx) => ("func", format!("(type {idx})")),
+        ImportDesc::Table(table) => ("table", table),
+        ImportDesc::Memory(limits) => ("memory", limits),
+        ImportDesc::Global(global) => ("global", global),
+        ImportDesc::Tag(idx) => ("tag", format!("(type {idx})")),
+    };
+    field(&desc, "desc", tipe, value);
+    Some(())
+}
+
+fn parse_export(cursor: &mut Cursor, field: &mut Field) -> Option<()> {
+    field(
+        cursor,
+        "name",
+        "name",
+        format!("{:?}", cursor.clone().name()?),
+    );
+    cursor.name()?;
+
+    let tipe = match cursor.peek()? {
+        0x00 => "funcidx",
+        0x01 => "tableidx",
+        0x02 => "memidx",
+        0x03 => "globalidx",
+        0x04 => "tagidx",
+        _ => return None,
+    };
+    field(
+        cursor,
+        "kind",
+        "u8",
+        tipe.trim_end_matches("idx").to_string(),
+    );
+    cursor.byte()?;
+    field(
+        cursor,
+        "index",
+        tipe,
+        format!("{:#x}", cursor.clone().u32()?),
+    );
+    cursor.u32()?;
+    Some(())
+}
+
+fn parse_global(cursor: &mut Cursor, field: &mut Field) -> Option<()> {
+    field(
+        cursor,
+        "type",
+        "globaltype",
+        global_type(&mut cursor.clone())?,
+    );
+    global_type(cursor)?;
+    field(cursor, "init", "expr", const_expr(&mut cursor.clone())?);
+    const_expr(cursor)?;
+    Some(())
+}
+
+enum ImportDesc {
+    Func(u32),
+    Table(String),
+    Memory(String),
+    Global(String),
+    Tag(u32),
+}
+
+impl ImportDesc {
+    fn parse(cursor: &mut Cursor) -> Option<Self> {
+        Some(match cursor.byte()? {
+            0x00 => Self::Func(cursor.u32()?),
+            0x01 => {
+                let tipe = value_type(cursor)?;
+                Self::Table(format!("{tipe} {}", limits(cursor)?))
+            }
+            0x02 => Self::Memory(limits(cursor)?),
+            0x03 => Self::Global(global_type(cursor)?),
+            0x04 => {
+                cursor.byte()?;
+                Self::Tag(cursor.u32()?)
+            }
+            _ => return None,
+        })
+    }
+}
+
+fn value_type(cursor: &mut Cursor) -> Option<&'static str> {
+    Some(match cursor.byte()? {
+        0x7f => "i32",
+        0x7e => "i64",
+        0x7d => "f32",
+        0x7c => "f64",
+        0x7b => "v128",
+        0x70 => "funcref",
+        0x6f => "externref",
+        0x69 => "exnref",
+        _ => return None,
+    })
+}
+
+fn result_type(cursor: &mut Cursor) -> Option<String> {
+    let types = (0..cursor.u32()?).map(|_| value_type(cursor)).collect::<Option<Vec<_>>>()?;
+    Some(format!("({})", types.join(", ")))
+}
+
+fn global_type(cursor: &mut Cursor) -> Option<String> {
+    let tipe = value_type(cursor)?;
+    Some(match cursor.byte()? {
+        0x00 => tipe.to_string(),
+        0x01 => format!("mut {tipe}"),
+        _ => return None,
+    })
+}
+
+fn limits(cursor: &mut Cursor) -> Option<String> {
+    let flags = cursor.byte()?;
+    let mut limits = format!("min={:#x}", cursor.uleb()?);
+    if flags & 1 != 0 {
+        limits += &format!(" max={:#x}", cursor.uleb()?);
+    }
+    if flags & 2 != 0 {
+        limits += " shared";
+    }
+    Some(limits)
+}
+
+/// Constant expression initializing a global, e.g. `i32.const 0x100000`.
+fn const_expr(cursor: &mut Cursor) -> Option<String> {
+    let mut insts = Vec::new();
+
+    loop {
+        let inst = match cursor.byte()? {
+            0x0b => break,
+            0x41 => format!("i32.const {}", signed_hex(cursor.sleb()?)),
+            0x42 => format!("i64.const {}", signed_hex(cursor.sleb()?)),
+            0x43 => format!(
+                "f32.const {}",
+                f32::from_le_bytes(cursor.bytes(4)?.try_into().ok()?)
+            ),
+            0x44 => format!(
+                "f64.const {}",
+                f64::from_le_bytes(cursor.bytes(8)?.try_into().ok()?)
+            ),
+            0x23 => format!("global.get {}", cursor.u32()?),
+            0xd0 => format!("ref.null {}", value_type(cursor)?),
+            0xd2 => format!("ref.func {}", cursor.u32()?),
+            0x6a => "i32.add".to_string(),
+            0x6b => "i32.sub".to_string(),
+            0x6c => "i32.mul".to_string(),
+            0x7c => "i64.add".to_string(),
+            0x7d => "i64.sub".to_string(),
+            0x7e => "i64.mul".to_string(),
+            0xfd if cursor.u32()? == 0x0c => {
+                let bytes = cursor.bytes(16)?;
+                let value = u128::from_le_bytes(bytes.try_into().ok()?);
+                format!("v128.const {value:#x}")
+            }
+            _ => return None,
+        };
+        insts.push(inst);
+    }
+
+    Some(insts.join(", "))
+}
+
+fn signed_hex(value: i64) -> String {
+    if value < 0 {
+        format!("-{:#x}", value.unsigned_abs())
+    } else {
+        format!("{value:#x}")
+    }
+}
+
+#[derive(Clone)]
+struct Cursor<'data> {
+    bytes: &'data [u8],
+    offset: usize,
+}
+
+impl<'data> Cursor<'data> {
+    fn new(bytes: &'data [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.offset).copied()
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.offset += 1;
+        Some(byte)
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'data [u8]> {
+        let bytes = self.bytes.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(bytes)
+    }
+
+    fn uleb(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn sleb(&mut self) -> Option<i64> {
+        let mut value = 0i64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as i64) << shift;
+            if byte & 0x80 == 0 {
+                if shift < 57 && byte & 0x40 != 0 {
+                    value |= -1 << (shift + 7);
+                }
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.uleb()?.try_into().ok()
+    }
+
+    /// Reads a length prefixed UTF-8 string.
+    fn name(&mut self) -> Option<&'data str> {
+        let len = self.u32()? as usize;
+        std::str::from_utf8(self.bytes(len)?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Module importing `env.log` and defining `main`, exported, and `init`, named in the name
+    /// section and called on instantiation. Returns the module and the file offsets of the
+    /// bodies of `main` and `init`.
+    fn module() -> (Vec<u8>, usize, usize) {
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        let mut section = |id: u8, contents: &[u8]| {
+            bytes.push(id);
+            bytes.push(contents.len() as u8);
+            bytes.extend_from_slice(contents);
+            bytes.len() - contents.len()
+        };
+
+        section(1, &[0x01, 0x60, 0x00, 0x00]);
+        section(2, b"\x01\x03env\x03log\x00\x00");
+        section(3, &[0x02, 0x00, 0x00]);
+        section(6, &[0x01, 0x7f, 0x01, 0x41, 0x80, 0x80, 0xc0, 0x00, 0x0b]);
+        section(7, b"\x01\x04main\x00\x01");
+        section(8, &[0x02]);
+        let code = section(10, &[0x02, 0x02, 0x00, 0x0b, 0x04, 0x01, 0x01, 0x7f, 0x0b]);
+        section(0, b"\x04name\x01\x07\x01\x02\x04init");
+
+        (bytes, code + 1, code + 4)
+    }
+
+    #[test]
+    fn sections_and_symbols() {
+        let (bytes, main, init) = module();
+        let obj = object::File::parse(&bytes[..]).unwrap();
+        let info = WasmDebugInfo::parse(&obj).unwrap();
+
+        let sections: Vec<_> = info.sections.iter().map(|s| (s.name.as_str(), s.ident)).collect();
+        assert_eq!(
+            sections,
+            [
+                ("<type>", "TYPE"),
+                ("<import>", "IMPORT"),
+                ("<function>", "FUNCTION"),
+                ("<global>", "GLOBAL"),
+                ("<export>", "EXPORT"),
+                ("<start>", "START"),
+                ("<code>", "CODE"),
+                ("name", "CUSTOM"),
+            ]
+        );
+
+        // sections are addressed by their offset in the file
+        for section in &info.sections {
+            assert_eq!(&bytes[section.start..section.end], section.bytes());
+        }
+
+        let code = info.sections.iter().find(|s| s.kind == SectionKind::Code).unwrap();
+        assert_eq!(code.start, main - 1);
+
+        let syms: Vec<_> = info.syms.mapping.iter().map(|s| (s.addr, s.item.name)).collect();
+        assert_eq!(syms, [(main, "main"), (init, "init"), (init, "entry")]);
+
+        let module = Module::parse(&obj);
+        assert!(matches!(
+            module.functions[0],
+            Function::Import {
+                module: "env",
+                name: "log"
+            }
+        ));
+        let code = |idx: usize| match &module.functions[idx] {
+            Function::Local(body) => Some(body.code.clone()),
+            Function::Import { .. } => None,
+        };
+        assert_eq!(code(1), Some(main + 2..main + 3));
+        assert_eq!(code(2), Some(init + 4..init + 5));
+        assert_eq!(module.start, Some(2));
+        assert_eq!(module.name(0), None);
+    }
+
+    #[test]
+    fn entries() {
+        let (bytes, ..) = module();
+        let obj = object::File::parse(&bytes[..]).unwrap();
+        let info = WasmDebugInfo::parse(&obj).unwrap();
+        let entries = |kind: SectionKind| {
+            let section = info.sections.iter().find(|s| s.kind == kind).unwrap();
+            Entries::new(kind, section.bytes(), section.start)
+        };
+
+        let import = entries(SectionKind::WasmImport);
+        let offsets = import.offsets();
+        assert_eq!(offsets.len(), 2);
+        let fields: Vec<_> = import
+            .to_fields(offsets[1])
+            .fields
+            .into_iter()
+            .map(|(_, name, tipe, value)| (name, tipe, value))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("module", "name", "\"env\"".to_string()),
+                ("name", "name", "\"log\"".to_string()),
+                ("desc", "func", "(type 0)".to_string()),
+            ]
+        );
+
+        let global = entries(SectionKind::WasmGlobal);
+        let fields = global.to_fields(global.offsets()[1]).fields;
+        assert_eq!(fields[0].3, "mut i32");
+        assert_eq!(fields[1].3, "i32.const 0x100000");
+    }
+
+    #[test]
+    fn truncated() {
+        let (bytes, ..) = module();
+
+        // cutting a section short is an error, modules can end at any section boundary
+        assert!(object::File::parse(&bytes[..bytes.len() - 1]).is_err());
+        for len in 0..bytes.len() {
+            if let Ok(obj) = object::File::parse(&bytes[..len]) {
+                assert!(WasmDebugInfo::parse(&obj).is_ok());
+            }
+        }
+
+        // malformed entries stop the listing without a panic
+        for kind in [SectionKind::WasmImport, SectionKind::WasmGlobal] {
+            let section = [0x01, 0x03, b'e', b'n'];
+            let entries = Entries::new(kind, &section, 0);
+            assert_eq!(entries.offsets(), [0]);
+            assert!(entries.to_fields(1).fields.is_empty());
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use decoder::Syntax;
 use egui::Color32;
 use serde::de::{self, Deserializer, Visitor};
 use serde::Deserialize;
@@ -7,6 +8,28 @@ use std::fmt;
 pub struct Config {
     #[serde(default = "defaults::colors")]
     pub colors: Colors,
+    #[serde(default = "defaults::disassembly")]
+    pub disassembly: Disassembly,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Disassembly {
+    #[serde(default, deserialize_with = "syntax")]
+    pub syntax: Syntax,
+    #[serde(default = "defaults::pseudo")]
+    pub pseudo: bool,
+    /// x86 microarchitecture instructions are checked against, e.g. `haswell`.
+    #[serde(default)]
+    pub uarch: Option<String>,
+}
+
+impl Disassembly {
+    pub fn dialect(&self) -> decoder::Dialect {
+        decoder::Dialect {
+            syntax: self.syntax,
+            pseudo: self.pseudo,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,6 +56,8 @@ pub struct Colors {
     pub constant: Color32,
     #[serde(default = "defaults::highlight", deserialize_with = "color32")]
     pub highlight: Color32,
+    #[serde(default = "defaults::patched", deserialize_with = "color32")]
+    pub patched: Color32,
 }
 
 impl Colors {
@@ -97,6 +122,14 @@ mod defaults {
         serde_yaml::from_str("").unwrap()
     }
 
+    pub fn disassembly() -> super::Disassembly {
+        serde_yaml::from_str("").unwrap()
+    }
+
+    pub fn pseudo() -> bool {
+        true
+    }
+
     pub fn anything() -> Color32 {
         Color32::from_rgb(200, 200, 200)
     }
@@ -134,6 +167,9 @@ mod defaults {
     pub fn highlight() -> Color32 {
         Color32::from_rgba_unmultiplied(255, 100, 0, 120)
     }
+    pub fn patched() -> Color32 {
+        Color32::from_rgb(0xff, 0xd7, 0x00)
+    }
 }
 
 impl Config {
@@ -176,3 +212,29 @@ fn color32<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color32, D::Err
 
     deserializer.deserialize_str(ColorParsing)
 }
+
+/// Parses the name of an assembly syntax.
+pub(crate) fn parse_syntax(s: &str) -> Option<Syntax> {
+    match s {
+        "intel" => Some(Syntax::Intel),
+        "att" | "at&t" => Some(Syntax::Att),
+        _ => None,
+    }
+}
+
+fn syntax<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Syntax, D::Error> {
+    struct SyntaxParsing;
+    impl<'de> Visitor<'de> for SyntaxParsing {
+        type Value = Syntax;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("expected either 'intel' or 'att'")
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+            parse_syntax(s).ok_or_else(|| E::custom(format!("unknown syntax '{s}'")))
+        }
+    }
+
+    deserializer.deserialize_str(SyntaxParsing)
+}
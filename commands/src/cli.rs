@@ -19,9 +19,10 @@ OPTIONS:
   -D, --disassemble   Path to object you're disassembling
   -T, --tracing       Trace all syscalls performed
   -C, --config        Path to config used for disassembling
+  -R, --raw           Decode object as a raw <ARCH>[@BASE] image, e.g. x86_16@0x7c00
   -B, --debug         Enable extra debug information";
 
-const ABBRV: &[&str] = &["-H", "-L", "-S", "-D", "-C", "-T", "-B"];
+const ABBRV: &[&str] = &["-H", "-L", "-S", "-D", "-C", "-R", "-T", "-B"];
 const NAMES: &[&str] = &[
     "--help",
     "--libs",
@@ -30,6 +31,7 @@ const NAMES: &[&str] = &[
     "--disassemble",
     "--tracing",
     "--config",
+    "--raw",
     "--debug",
 ];
 
@@ -58,12 +60,19 @@ pub struct Cli {
 
     /// Optional path to config.
     pub config: Option<PathBuf>,
+
+    /// Architecture and base address of an object without headers, such as a boot sector.
+    pub raw: Option<(String, usize)>,
 }
 
 impl Cli {
     pub fn parse() -> Self {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    fn parse_from(args: impl Iterator<Item = String>) -> Self {
         let mut cli = Cli::default();
-        let mut args = std::env::args().skip(1).peekable();
+        let mut args = args.peekable();
 
         while let Some(arg) = args.next() {
             match arg.as_str() {
@@ -96,6 +105,27 @@ impl Cli {
                         }
                     }
                 }
+                "-R" | "--raw" => {
+                    let raw = match args.next() {
+                        Some(raw) => raw,
+                        None => exit!(1 => "Missing architecture of raw image."),
+                    };
+
+                    let (arch, base) = match raw.split_once('@') {
+                        Some((arch, base)) => {
+                            let digits = base.trim_start_matches("0x").trim_start_matches("0X");
+                            match usize::from_str_radix(digits, 16) {
+                                Ok(base) => (arch, base),
+                                Err(..) => {
+                                    exit!(1 => "Invalid base address '{base}' of raw image.")
+                                }
+                            }
+                        }
+                        None => (raw.as_str(), 0),
+                    };
+
+                    cli.raw = Some((arch.to_string(), base));
+                }
                 "-T" | "--tracing" => cli.tracing = true,
                 "-B" | "--debug" => cli.debug = true,
                 unknown => {
@@ -143,3 +173,21 @@ impl Cli {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Cli;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::parse_from(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn raw_base() {
+        let raw = |arg| parse(&["--raw", arg]).raw.unwrap();
+        assert_eq!(raw("x86_16"), ("x86_16".to_string(), 0));
+        assert_eq!(raw("x86_16@7c00"), ("x86_16".to_string(), 0x7c00));
+        assert_eq!(raw("x86_16@0x7c00"), ("x86_16".to_string(), 0x7c00));
+        assert_eq!(raw("x86_16@0X7C00"), ("x86_16".to_string(), 0x7c00));
+    }
+}
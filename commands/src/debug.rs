@@ -2,7 +2,7 @@
 //!
 //! ```text
 //! <input> = <ws> <expr> <ws>
-//! <expr>  = <compound> | <number> | <symbol>
+//! <expr>  = <compound> | <number> | <symbol> | <register> | <deref>
 //!
 //! <number> = ['-'] <integer> | ['-'] <hex>
 //!
@@ -10,10 +10,14 @@
 //! <integer> = {'0'..'9'}+
 //!
 //! <symbol>  = {<characters>}+ # if known in database
+//! <register> = '$' {<characters>}+ # if a target is being debugged
+//! <deref>    = '*' <expr>            # if a target is being debugged
 //!
 //! <compound> = <expr> <ws> <op> <ws> <expr> | '(' <ws> <expr> <ws> ')'
-//! <op> = '+' | '-' | '*' | '/' | '%'
+//! <op> = '+' | '-' | '*' | '/' | '%' | '==' | '!=' | '<' | '>' | '<=' | '>=' | '&&' | '||'
 //! ```
+//!
+//! Comparisons and logical operators evaluate to either 1 or 0.
 
 // TODO: Implement binary presidence (10 + 10 * 10 == 110).
 //       This likely requires parsing in two steps where we first generate tokens.
@@ -161,6 +165,16 @@ impl<'src> Context<'src> {
         }
     }
 
+    /// Conditionally increments the stream if it starts with `s`.
+    fn consume_str(&mut self, s: &str) -> bool {
+        if self.src().starts_with(s) {
+            self.offset += s.len();
+            return true;
+        }
+
+        false
+    }
+
     /// Increments the stream whilst any whitespace is encountered.
     fn consume_whitespace(&mut self) {
         // all forms of accepted whitespace
@@ -279,17 +293,47 @@ impl<'src> Context<'src> {
         return Ok(int);
     }
 
+    /// Whether the '<' the stream starts with opens a generic rather than being a comparison.
+    ///
+    /// A generic starts with a type and is closed before any logical operator, so `rip<main`
+    /// compares two symbols.
+    fn opens_generic(&mut self) -> bool {
+        let rest = &self.src()[1..];
+        if !rest.starts_with(|chr: char| chr.is_alphabetic() || matches!(chr, '_' | '&' | '[')) {
+            return false;
+        }
+
+        let mut depth = 1;
+        for (idx, chr) in rest.char_indices() {
+            match chr {
+                '<' => depth += 1,
+                '>' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return true;
+                    }
+                }
+                _ if rest[idx..].starts_with("&&") || rest[idx..].starts_with("||") => {
+                    return false
+                }
+                _ => {}
+            }
+        }
+
+        false
+    }
+
     /// Reads a serious of characters.
     /// If a pair of <..> is detected, it will allow any series of characters
     /// in-between the generic.
-    fn symbol(&mut self) -> Result<&'src str, Error> {
+    fn symbol(&mut self, generics: bool) -> Result<&'src str, Error> {
         let start = self.offset;
         let mut depth = 0isize;
 
         loop {
             match self.peek() {
                 // operators are ambiguous
-                Some('+' | '-' | '*' | '/' | '%') if depth == 0 => break,
+                Some('+' | '-' | '*' | '/' | '%' | '=' | '!' | '&' | '|') if depth == 0 => break,
                 // brackets are ambiguous
                 Some('(' | ')') if depth == 0 => break,
                 // whitespace is ambiguous
@@ -297,15 +341,17 @@ impl<'src> Context<'src> {
                 // EOF means there we must be at the end of a symbol
                 None => break,
                 // entering a generic
-                Some('<') => {
+                Some('<') if depth > 0 || (generics && self.opens_generic()) => {
                     depth += 1;
                     self.offset += 1;
                 }
                 // existing a generic
-                Some('>') => {
+                Some('>') if depth > 0 => {
                     depth -= 1;
                     self.offset += 1;
                 }
+                // comparisons are ambiguous
+                Some('<' | '>') => break,
                 // any other character should be part of a valid symbol
                 Some(chr) => self.offset += chr.len_utf8(),
             }
@@ -317,12 +363,26 @@ impl<'src> Context<'src> {
     fn parse_primary(&mut self) -> Result<Expr, Error> {
         self.consume_whitespace();
 
+        if self.consume('*').is_ok() {
+            let addr = self.parse_primary()?;
+            return Ok(Expr::Deref(self.store(addr)));
+        }
+
         if let Ok(num) = self.number() {
             return Ok(Expr::Number(num));
         }
 
+        if self.consume('$').is_ok() {
+            let name = self.symbol(false)?;
+            if name.is_empty() {
+                return self.failing("Expected a register");
+            }
+
+            return Ok(Expr::Register(name.to_string()));
+        }
+
         let start = self.offset;
-        let sym = self.symbol()?;
+        let sym = self.symbol(true)?;
         if !sym.is_empty() {
             let end = self.offset;
             return Ok(Expr::Symbol {
@@ -411,9 +471,77 @@ impl<'src> Context<'src> {
         Ok(lhs)
     }
 
+    fn parse_comparison(&mut self) -> Result<Expr, Error> {
+        let lhs = self.parse_low_precedence()?;
+        self.consume_whitespace();
+
+        // Order matters as '<' is a prefix of '<='.
+        const OPS: [(&str, Operator); 6] = [
+            ("==", Operator::Eq),
+            ("!=", Operator::Ne),
+            ("<=", Operator::Le),
+            (">=", Operator::Ge),
+            ("<", Operator::Lt),
+            (">", Operator::Gt),
+        ];
+
+        for (s, op) in OPS {
+            if self.consume_str(s) {
+                let rhs = self.parse_low_precedence()?;
+                return Ok(Expr::Compound {
+                    lhs: self.store(lhs),
+                    op,
+                    rhs: self.store(rhs),
+                });
+            }
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, Error> {
+        let mut lhs = self.parse_comparison()?;
+
+        loop {
+            self.consume_whitespace();
+            if !self.consume_str("&&") {
+                break;
+            }
+
+            let rhs = self.parse_comparison()?;
+            lhs = Expr::Compound {
+                lhs: self.store(lhs),
+                op: Operator::And,
+                rhs: self.store(rhs),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, Error> {
+        let mut lhs = self.parse_and()?;
+
+        loop {
+            self.consume_whitespace();
+            if !self.consume_str("||") {
+                break;
+            }
+
+            let rhs = self.parse_and()?;
+            lhs = Expr::Compound {
+                lhs: self.store(lhs),
+                op: Operator::Or,
+                rhs: self.store(rhs),
+            };
+        }
+
+        Ok(lhs)
+    }
+
     fn expr_inner(&mut self) -> Result<Expr, Error> {
         self.descent()?;
-        let expr = self.parse_low_precedence()?;
+        let expr = self.parse_or()?;
         self.ascent();
         Ok(expr)
     }
@@ -433,20 +561,31 @@ impl<'src> Context<'src> {
     }
 }
 
-/// Basic mathematical operations.
-#[derive(Debug, PartialEq, Eq)]
+/// Basic mathematical, comparison and logical operations.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Operator {
     Add,
     Min,
     Mul,
     Div,
     Mod,
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    And,
+    Or,
 }
 
 /// Representation of any given expression.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 enum Expr {
     Number(isize),
+    Register(String),
+    /// Pointer sized value at an address.
+    Deref(ExprRef),
     Symbol {
         val: String,
         span: Span,
@@ -458,8 +597,29 @@ enum Expr {
     },
 }
 
+/// State of a stopped debug target that expressions can read.
+pub trait Target {
+    fn register(&mut self, name: &str) -> Option<u64>;
+
+    /// Read a pointer sized value.
+    fn read_ptr(&mut self, addr: usize) -> Option<u64>;
+}
+
+/// Used when not debugging anything.
+struct NoTarget;
+
+impl Target for NoTarget {
+    fn register(&mut self, _: &str) -> Option<u64> {
+        None
+    }
+
+    fn read_ptr(&mut self, _: usize) -> Option<u64> {
+        None
+    }
+}
+
 /// Storable [`Expr`] with needed [`ExprRef`]'s.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct CompleteExpr {
     /// Arena of expressions.
     children: Vec<Expr>,
@@ -474,9 +634,31 @@ impl CompleteExpr {
         &self.children[expr_ref.0]
     }
 
-    fn eval_recursive(&self, node: &Expr, index: &Index) -> Result<isize, Error> {
+    fn eval_recursive(
+        &self,
+        node: &Expr,
+        index: &Index,
+        target: &mut dyn Target,
+    ) -> Result<isize, Error> {
         match node {
             Expr::Number(val) => Ok(*val),
+            Expr::Register(name) => match target.register(name) {
+                Some(val) => Ok(val as isize),
+                None => Err(Error {
+                    offset: None,
+                    msg: format!("Register '${name}' isn't available"),
+                }),
+            },
+            Expr::Deref(addr) => {
+                let addr = self.eval_recursive(self.load(*addr), index, target)?;
+                match target.read_ptr(addr as usize) {
+                    Some(val) => Ok(val as isize),
+                    None => Err(Error {
+                        offset: None,
+                        msg: format!("Can't read memory at {addr:#x}"),
+                    }),
+                }
+            }
             Expr::Symbol { val, .. } => match index.get_func_by_name(val) {
                 Some(addr) => Ok(addr as isize),
                 None => Err(Error {
@@ -485,8 +667,16 @@ impl CompleteExpr {
                 }),
             },
             Expr::Compound { lhs, op, rhs } => {
-                let lhs = self.eval_recursive(self.load(*lhs), index)?;
-                let rhs = self.eval_recursive(self.load(*rhs), index)?;
+                let lhs = self.eval_recursive(self.load(*lhs), index, target)?;
+
+                // Short circuit, so that a condition can guard dereferencing a pointer.
+                match op {
+                    Operator::And if lhs == 0 => return Ok(0),
+                    Operator::Or if lhs != 0 => return Ok(1),
+                    _ => {}
+                }
+
+                let rhs = self.eval_recursive(self.load(*rhs), index, target)?;
                 let err = || Error {
                     offset: None,
                     msg: "Expression overflowed".to_string(),
@@ -498,6 +688,13 @@ impl CompleteExpr {
                     Operator::Mod => lhs.checked_rem(rhs).ok_or_else(err),
                     Operator::Add => lhs.checked_add(rhs).ok_or_else(err),
                     Operator::Min => lhs.checked_sub(rhs).ok_or_else(err),
+                    Operator::Eq => Ok((lhs == rhs) as isize),
+                    Operator::Ne => Ok((lhs != rhs) as isize),
+                    Operator::Lt => Ok((lhs < rhs) as isize),
+                    Operator::Gt => Ok((lhs > rhs) as isize),
+                    Operator::Le => Ok((lhs <= rhs) as isize),
+                    Operator::Ge => Ok((lhs >= rhs) as isize),
+                    Operator::And | Operator::Or => Ok((rhs != 0) as isize),
                 }
             }
         }
@@ -507,7 +704,26 @@ impl CompleteExpr {
     ///
     /// Returns [`None`] if the expression overflows.
     pub fn eval(&self, index: &Index) -> Result<isize, Error> {
-        self.eval_recursive(&self.root, index)
+        self.eval_recursive(&self.root, index, &mut NoTarget)
+    }
+
+    /// Evaluate an expression that may read the registers and memory of `target`.
+    pub fn eval_with_target(&self, index: &Index, target: &mut dyn Target) -> Result<isize, Error> {
+        self.eval_recursive(&self.root, index, target)
+    }
+
+    /// Whether the expression can only be evaluated whilst debugging.
+    pub fn uses_target(&self) -> bool {
+        let is_runtime = |expr: &Expr| matches!(expr, Expr::Register(_) | Expr::Deref(_));
+        is_runtime(&self.root) || self.children.iter().any(is_runtime)
+    }
+
+    /// The register if the expression is nothing but a register.
+    pub fn as_register(&self) -> Option<&str> {
+        match &self.root {
+            Expr::Register(name) => Some(name),
+            _ => None,
+        }
     }
 
     fn find_matching_symbol<'src>(
@@ -528,7 +744,8 @@ impl CompleteExpr {
 
                 None
             }
-            Expr::Number(_) => None,
+            Expr::Deref(addr) => self.find_matching_symbol(self.load(*addr), cursor),
+            Expr::Number(_) | Expr::Register(_) => None,
         }
     }
 
@@ -649,6 +866,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn register() {
+        ast_eq!("$rsp", Expr::Register("rsp".to_string()));
+
+        let index = debugvault::Index::default();
+        let expr = CompleteExpr::parse("$rsp + 0x10").unwrap();
+        assert!(expr.uses_target());
+        assert!(expr.eval(&index).is_err());
+        assert_eq!(expr.eval_with_target(&index, &mut Stack), Ok(0x8000));
+    }
+
+    /// Target with `rsp` pointing at a single value.
+    struct Stack;
+
+    impl Target for Stack {
+        fn register(&mut self, name: &str) -> Option<u64> {
+            (name == "rsp").then_some(0x7ff0)
+        }
+
+        fn read_ptr(&mut self, addr: usize) -> Option<u64> {
+            (addr == 0x7ff0).then_some(42)
+        }
+    }
+
+    #[test]
+    fn deref() {
+        ast_eq!("*$rsp", Expr::Deref(ExprRef(0)));
+
+        let index = debugvault::Index::default();
+        let expr = CompleteExpr::parse("*($rsp) * 2").unwrap();
+        assert!(expr.uses_target());
+        assert_eq!(expr.eval_with_target(&index, &mut Stack), Ok(84));
+
+        let expr = CompleteExpr::parse("*0x10").unwrap();
+        assert!(expr.uses_target());
+        assert!(expr.eval_with_target(&index, &mut Stack).is_err());
+    }
+
+    #[test]
+    fn conditions() {
+        eval_eq!("1 + 1 == 2", 1);
+        eval_eq!("3 != 3", 0);
+        eval_eq!("2 < 3 && 3 <= 3", 1);
+        eval_eq!("2 > 3 || 4 >= 5", 0);
+        eval_eq!("0 || 0 == 0", 1);
+        eval_eq!(["main"; 0x1000], "main==0x1000", 1);
+
+        // Short circuiting guards a bad dereference.
+        let index = debugvault::Index::default();
+        let expr = CompleteExpr::parse("$rsp == 0 && *0x10 == 1").unwrap();
+        assert_eq!(expr.eval_with_target(&index, &mut Stack), Ok(0));
+    }
+
+    #[test]
+    fn comparisons_without_spaces() {
+        eval_eq!(["main"; 0x1000], "main<0x2000", 1);
+        eval_eq!(["main"; 0x1000], "main>0x2000", 0);
+        eval_eq!(["main"; 0x1000], "main<=0x1000&&main>=0x1000", 1);
+        eval_eq!(
+            ["abc::f<std::fmt::Display>"; 0x100],
+            "abc::f<std::fmt::Display><0x200",
+            1
+        );
+
+        let mut index = debugvault::Index::default();
+        index.insert_func(0x800, "rip");
+        index.insert_func(0x1000, "main");
+        assert_eq!(parse(&index, "rip<main"), Ok(1));
+        assert_eq!(parse(&index, "rip<main&&main>rip"), Ok(1));
+
+        let expr = CompleteExpr::parse("$rsp>0x10").unwrap();
+        assert_eq!(expr.eval_with_target(&index, &mut Stack), Ok(1));
+    }
+
     #[test]
     fn operation_order() {
         eval_eq!("1 + 10 * 10", 101);
@@ -9,9 +9,40 @@ Available commands:
     cd <path>          -- Change the current directory to the specified path
     quit               -- Exit the program
     goto <expr>        -- Jump to code/data at the specified expression
+    attach <pid>       -- Attach to a running process, showing its memory and debugging it
+    connect <addr>     -- Connect to a gdbserver listening on host:port or a unix socket
+    ni                 -- Execute the next instruction
+    c                  -- Continue execution
+    stop               -- Interrupt a running target
+    b <expr> [if <c>]  -- Set a breakpoint, only stopping when the condition c is non-zero
+    db <expr>          -- Delete a breakpoint
+    watch[/N] <expr>   -- Stop when N bytes at the expression are written (rwatch: read, awatch: either)
+    dw <expr>          -- Delete a watchpoint
+    x/Nfu <expr>       -- Examine N units of u bytes (b, h, w, g) in format f (x, d, u, o, t, c, a, s)
+    p <expr>           -- Print an expression or register such as $rip
+    set $reg = <expr>  -- Change the value of a register
+    follow-children    -- Debug forked children, `follow-children off` detaches from them
+    inferior [pid]     -- Select a process being debugged, or list them
+    syntax <name>      -- Show x86 assembly in either intel or att syntax
+    pseudo [on|off]    -- Show pseudo-instructions instead of their canonical encoding
+    ir [on|off]        -- Show the intermediate representation instructions lift to
+    uarch [name|none]  -- Flag x86 instructions a microarchitecture doesn't support, or list them
+    extensions         -- Summarize the instruction set extensions used by the binary
+    patch <e> = <hex>  -- Overwrite the bytes at an expression, e.g. `patch main = 31 c0 c3`
+    patch/i <e> = <i>  -- Overwrite instructions with assembled ones, separated by ';'
+    asm [<e> =] <i>    -- Print the encoding of instructions, placed at an expression or 0
+    undo               -- Revert the last patch
+    save <path>        -- Write a copy of the binary with all patches applied
+    emulate <e> [n]    -- Emulate at most n instructions from an expression, printing the registers
+    up [n]             -- Select the frame n calls up the stack
+    down [n]           -- Select the frame n calls down the stack
+    detach             -- Stop debugging, letting the target run freely
     clear              -- Clear out terminal
     help               -- Display this help message";
 
+/// Number of instructions `emulate` executes if no count is given.
+const EMULATION_STEPS: usize = 10_000;
+
 #[derive(Debug, PartialEq)]
 pub enum Command {
     Load(PathBuf),
@@ -19,10 +50,70 @@ pub enum Command {
     ChangeDir(PathBuf),
     Quit,
     Goto(usize),
+    Attach(i32),
+    Connect(String),
+    StepInstruction,
+    Continue,
+    Stop,
+    Break {
+        addr: CompleteExpr,
+        condition: Option<CompleteExpr>,
+    },
+    Delete(CompleteExpr),
+    Watch {
+        kind: WatchKind,
+        len: Option<usize>,
+        addr: CompleteExpr,
+    },
+    Unwatch(CompleteExpr),
+    Examine {
+        count: usize,
+        format: char,
+        unit: usize,
+        addr: CompleteExpr,
+    },
+    Print(CompleteExpr),
+    SetRegister(String, CompleteExpr),
+    FollowChildren(bool),
+    Inferior(Option<i32>),
+    Syntax(decoder::Syntax),
+    Pseudo(bool),
+    Ir(bool),
+    Uarch(Option<String>),
+    Extensions,
+    Patch {
+        addr: usize,
+        bytes: Vec<u8>,
+    },
+    PatchAsm {
+        addr: usize,
+        asm: String,
+    },
+    Asm {
+        addr: usize,
+        asm: String,
+    },
+    Undo,
+    Save(PathBuf),
+    Emulate {
+        addr: usize,
+        steps: usize,
+    },
+    Up(usize),
+    Down(usize),
+    Detach,
     Clear,
     Help,
 }
 
+/// Accesses that trigger a watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    Write,
+    Read,
+    Access,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
     Missing(&'static str),
@@ -31,6 +122,12 @@ pub enum Error {
     PathIsntFile(PathBuf),
     PathIsntDir(PathBuf),
     InvalidEnv,
+    InvalidPid(String),
+    InvalidFormat(String),
+    InvalidCount(String),
+    InvalidToggle(String),
+    InvalidSyntax(String),
+    InvalidBytes(String),
     Debugger(crate::debug::Error),
 }
 
@@ -52,6 +149,20 @@ impl fmt::Display for Error {
                 f.write_fmt(format_args!("Path {path:?} isn't a directory."))
             }
             Self::InvalidEnv => f.write_str("Invalid environmental variable pair."),
+            Self::InvalidPid(pid) => f.write_fmt(format_args!("'{pid}' isn't a process id.")),
+            Self::InvalidFormat(fmt) => {
+                f.write_fmt(format_args!("'{fmt}' isn't a valid format, expected x/Nfu."))
+            }
+            Self::InvalidCount(count) => f.write_fmt(format_args!("'{count}' isn't a count.")),
+            Self::InvalidToggle(toggle) => {
+                f.write_fmt(format_args!("Expected 'on' or 'off', got '{toggle}'."))
+            }
+            Self::InvalidSyntax(syntax) => {
+                f.write_fmt(format_args!("Expected 'intel' or 'att', got '{syntax}'."))
+            }
+            Self::InvalidBytes(bytes) => {
+                f.write_fmt(format_args!("'{bytes}' isn't a sequence of hex bytes."))
+            }
             Self::Debugger(err) => err.fmt(f),
         }
     }
@@ -89,14 +200,35 @@ fn possible_command(unknown: &str) -> Option<&str> {
         "quit",
         "run",
         "goto",
+        "attach",
+        "connect",
+        "nexti",
+        "print",
+        "detach",
+        "up",
+        "down",
         "set",
         "break",
         "delete",
+        "watch",
+        "rwatch",
+        "awatch",
         "stop",
         "continue",
         "clear",
         "trace",
         "follow-children",
+        "inferior",
+        "syntax",
+        "pseudo",
+        "ir",
+        "uarch",
+        "extensions",
+        "patch",
+        "asm",
+        "undo",
+        "save",
+        "emulate",
         "help",
     ];
 
@@ -316,7 +448,66 @@ impl<'src> Context<'src> {
         let offset = self.offset;
         let s = self.parse_arg("expr")?;
         let expr = CompleteExpr::parse(s).map_err(Error::Debugger)?;
+        self.eval_debug_expr(&expr, offset)
+    }
+
+    /// Parses an expression that might reference registers, these are only known at runtime.
+    fn parse_runtime_expr(&mut self) -> Result<CompleteExpr, Error> {
+        let offset = self.offset;
+        let s = self.parse_arg("expr")?;
+        self.runtime_expr(s, offset)
+    }
+
+    fn runtime_expr(&mut self, s: &str, offset: usize) -> Result<CompleteExpr, Error> {
+        let expr = CompleteExpr::parse(s).map_err(Error::Debugger)?;
 
+        // Check any symbols now, so that they can be autocompleted.
+        if !expr.uses_target() {
+            self.eval_debug_expr(&expr, offset)?;
+        }
+
+        Ok(expr)
+    }
+
+    /// Parses `<expr> [if <condition>]`.
+    fn parse_break(&mut self) -> Result<Command, Error> {
+        let offset = self.offset;
+        let s = self.parse_arg("expr")?;
+
+        let (addr, condition) = match s.split_once(" if ") {
+            Some((addr, condition)) => {
+                let cond_offset = offset + s.len() - condition.len();
+                let condition = self.runtime_expr(condition.trim(), cond_offset)?;
+                (addr.trim(), Some(condition))
+            }
+            None => (s, None),
+        };
+
+        Ok(Command::Break {
+            addr: self.runtime_expr(addr, offset)?,
+            condition,
+        })
+    }
+
+    /// Parses the optional `/N` suffix of the watch commands.
+    fn parse_watch(&mut self, kind: WatchKind, spec: &str) -> Result<Command, Error> {
+        let len = match spec.strip_prefix('/') {
+            Some(len) => match len.parse() {
+                Ok(len) if len > 0 => Some(len),
+                _ => return Err(Error::InvalidCount(len.to_string())),
+            },
+            None if spec.is_empty() => None,
+            None => return Err(Error::InvalidCount(spec.to_string())),
+        };
+
+        Ok(Command::Watch {
+            kind,
+            len,
+            addr: self.parse_runtime_expr()?,
+        })
+    }
+
+    fn eval_debug_expr(&mut self, expr: &CompleteExpr, offset: usize) -> Result<usize, Error> {
         let err = match expr.eval(self.index) {
             Ok(val) => return Ok(val as usize),
             Err(err) => err,
@@ -340,6 +531,199 @@ impl<'src> Context<'src> {
         Err(Error::Debugger(err))
     }
 
+    /// Parses the `/Nfu` suffix of the examine command.
+    fn parse_examine(&mut self, spec: &str) -> Result<Command, Error> {
+        let invalid = || Error::InvalidFormat(spec.to_string());
+        let spec = match spec.strip_prefix('/') {
+            Some(spec) => spec,
+            None if spec.is_empty() => "",
+            None => return Err(invalid()),
+        };
+
+        let digits = spec.find(|c: char| !c.is_ascii_digit()).unwrap_or(spec.len());
+        let count = match &spec[..digits] {
+            "" => 1,
+            count => count.parse().map_err(|_| invalid())?,
+        };
+
+        let mut format = None;
+        let mut unit = None;
+        for chr in spec[digits..].chars() {
+            match chr {
+                'x' | 'd' | 'u' | 'o' | 't' | 'c' | 'a' | 's' if format.is_none() => {
+                    format = Some(chr)
+                }
+                'b' if unit.is_none() => unit = Some(1),
+                'h' if unit.is_none() => unit = Some(2),
+                'w' if unit.is_none() => unit = Some(4),
+                'g' if unit.is_none() => unit = Some(8),
+                _ => return Err(invalid()),
+            }
+        }
+
+        let format = format.unwrap_or('x');
+        let unit = unit.unwrap_or(match format {
+            'a' => 8,
+            'c' | 's' => 1,
+            _ => 4,
+        });
+
+        Ok(Command::Examine {
+            count,
+            format,
+            unit,
+            addr: self.parse_runtime_expr()?,
+        })
+    }
+
+    fn parse_set_register(&mut self) -> Result<Command, Error> {
+        let s = self.parse_arg("register")?;
+        let (reg, val) = s.split_once('=').ok_or(Error::Missing("="))?;
+        let reg = reg.trim().strip_prefix('$').ok_or(Error::Missing("register"))?;
+        let val = CompleteExpr::parse(val.trim()).map_err(Error::Debugger)?;
+        Ok(Command::SetRegister(reg.to_string(), val))
+    }
+
+    fn parse_count(&mut self) -> Result<usize, Error> {
+        let s = match self.parse_arg("count") {
+            Ok(s) => s,
+            Err(Error::Missing(_)) => return Ok(1),
+            Err(err) => return Err(err),
+        };
+
+        s.parse().map_err(|_| Error::InvalidCount(s.to_string()))
+    }
+
+    /// Parses an optional `on` or `off`, defaulting to `on`.
+    fn parse_toggle(&mut self) -> Result<bool, Error> {
+        match self.parse_arg("toggle") {
+            Ok("on") | Err(Error::Missing(_)) => Ok(true),
+            Ok("off") => Ok(false),
+            Ok(s) => Err(Error::InvalidToggle(s.to_string())),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn parse_syntax(&mut self) -> Result<decoder::Syntax, Error> {
+        let s = self.parse_arg("syntax")?;
+        crate::cfg::parse_syntax(s).ok_or_else(|| Error::InvalidSyntax(s.to_string()))
+    }
+
+    fn parse_uarch(&mut self) -> Result<Command, Error> {
+        match self.parse_arg("microarchitecture") {
+            Ok(name) => Ok(Command::Uarch(Some(name.to_string()))),
+            Err(Error::Missing(_)) => Ok(Command::Uarch(None)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Parses `<expr> = <bytes>` or `<expr> = <instructions>` if `asm` is set.
+    fn parse_patch(&mut self, asm: bool) -> Result<Command, Error> {
+        let offset = self.offset;
+        let s = self.parse_arg("expr")?;
+        let (addr, data) = s.split_once('=').ok_or(Error::Missing("="))?;
+
+        let expr = CompleteExpr::parse(addr.trim()).map_err(Error::Debugger)?;
+        let addr = self.eval_debug_expr(&expr, offset)?;
+        let data = data.trim();
+
+        if asm {
+            if data.is_empty() {
+                return Err(Error::Missing("instructions"));
+            }
+
+            return Ok(Command::PatchAsm {
+                addr,
+                asm: data.to_string(),
+            });
+        }
+
+        let digits: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+        if digits.is_empty() {
+            return Err(Error::Missing("bytes"));
+        }
+
+        let bytes = (0..digits.len())
+            .step_by(2)
+            .map(|idx| digits.get(idx..idx + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(|| Error::InvalidBytes(data.to_string()))?;
+
+        Ok(Command::Patch { addr, bytes })
+    }
+
+    /// Parses `<instructions>` or `<expr> = <instructions>`.
+    fn parse_asm(&mut self) -> Result<Command, Error> {
+        let offset = self.offset;
+        let s = self.parse_arg("instructions")?;
+
+        let (addr, asm) = match s.split_once('=') {
+            Some((addr, asm)) => {
+                let expr = CompleteExpr::parse(addr.trim()).map_err(Error::Debugger)?;
+                (self.eval_debug_expr(&expr, offset)?, asm.trim())
+            }
+            None => (0, s),
+        };
+
+        if asm.is_empty() {
+            return Err(Error::Missing("instructions"));
+        }
+
+        Ok(Command::Asm {
+            addr,
+            asm: asm.to_string(),
+        })
+    }
+
+    /// Parses `<expr> [steps]`.
+    fn parse_emulate(&mut self) -> Result<Command, Error> {
+        let offset = self.offset;
+        let s = self.parse_arg("expr")?;
+
+        // The expression can contain spaces, so only a trailing count that isn't part of it
+        // is taken as the number of steps.
+        let (addr, steps) = match s.rsplit_once(' ') {
+            Some((addr, steps)) if CompleteExpr::parse(addr.trim()).is_ok() => {
+                match steps.parse() {
+                    Ok(steps) => (addr.trim(), steps),
+                    Err(_) => (s, EMULATION_STEPS),
+                }
+            }
+            _ => (s, EMULATION_STEPS),
+        };
+
+        let expr = CompleteExpr::parse(addr).map_err(Error::Debugger)?;
+        let addr = self.eval_debug_expr(&expr, offset)?;
+        Ok(Command::Emulate { addr, steps })
+    }
+
+    /// Parses the path of a file to be written, which doesn't have to exist yet.
+    fn parse_output_path(&mut self) -> Result<PathBuf, Error> {
+        let path = expand_homedir(PathBuf::from(self.parse_arg("path")?));
+
+        if path.is_dir() {
+            return Err(Error::PathIsntFile(path));
+        }
+
+        Ok(path)
+    }
+
+    fn parse_inferior(&mut self) -> Result<Command, Error> {
+        match self.parse_pid() {
+            Ok(pid) => Ok(Command::Inferior(Some(pid))),
+            Err(Error::Missing(_)) => Ok(Command::Inferior(None)),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn parse_pid(&mut self) -> Result<i32, Error> {
+        let s = self.parse_arg("pid")?;
+        match s.parse::<i32>() {
+            Ok(pid) if pid > 0 => Ok(pid),
+            _ => Err(Error::InvalidPid(s.to_string())),
+        }
+    }
+
     fn parse(&mut self) -> Result<Command, Error> {
         let name = match self.parse_next("command")? {
             "exec" | "e" => Command::Load(self.parse_file_path()?),
@@ -347,6 +731,39 @@ impl<'src> Context<'src> {
             "cd" => Command::ChangeDir(self.parse_dir_path()?),
             "quit" | "q" => Command::Quit,
             "goto" | "g" => Command::Goto(self.parse_debug_expr()?),
+            "attach" => Command::Attach(self.parse_pid()?),
+            "connect" => Command::Connect(self.parse_arg("address")?.to_string()),
+            "nexti" | "ni" => Command::StepInstruction,
+            "continue" | "c" => Command::Continue,
+            "stop" => Command::Stop,
+            "break" | "b" => self.parse_break()?,
+            "delete" | "db" => Command::Delete(self.parse_runtime_expr()?),
+            "w" => self.parse_watch(WatchKind::Write, "")?,
+            name if name.starts_with("watch") => self.parse_watch(WatchKind::Write, &name[5..])?,
+            name if name.starts_with("rwatch") => self.parse_watch(WatchKind::Read, &name[6..])?,
+            name if name.starts_with("awatch") => self.parse_watch(WatchKind::Access, &name[6..])?,
+            "dw" => Command::Unwatch(self.parse_runtime_expr()?),
+            "print" | "p" => Command::Print(self.parse_runtime_expr()?),
+            "set" => self.parse_set_register()?,
+            "follow-children" => Command::FollowChildren(self.parse_toggle()?),
+            "inferior" => self.parse_inferior()?,
+            "syntax" => Command::Syntax(self.parse_syntax()?),
+            "pseudo" => Command::Pseudo(self.parse_toggle()?),
+            "ir" => Command::Ir(self.parse_toggle()?),
+            "uarch" => self.parse_uarch()?,
+            "extensions" => Command::Extensions,
+            "patch" => self.parse_patch(false)?,
+            "patch/i" => self.parse_patch(true)?,
+            "asm" => self.parse_asm()?,
+            "undo" => Command::Undo,
+            "save" => Command::Save(self.parse_output_path()?),
+            "emulate" => self.parse_emulate()?,
+            "up" => Command::Up(self.parse_count()?),
+            "down" => Command::Down(self.parse_count()?),
+            "detach" => Command::Detach,
+            name if name.starts_with('x') && matches!(name.as_bytes().get(1), None | Some(b'/')) => {
+                self.parse_examine(&name[1..])?
+            }
             "clear" => Command::Clear,
             "help" | "?" => Command::Help,
             name => return Err(Error::UnknownName(name.to_string())),
@@ -371,6 +788,10 @@ impl Command {
 mod tests {
     use super::*;
 
+    fn expr(s: &str) -> CompleteExpr {
+        CompleteExpr::parse(s).unwrap()
+    }
+
     macro_rules! eval_eq {
         ($expr:expr, $expected:expr) => {{
             let index = debugvault::Index::default();
@@ -423,6 +844,270 @@ mod tests {
         );
     }
 
+    #[test]
+    fn attach() {
+        eval_eq!("attach 1234", Command::Attach(1234));
+        eval_eq!("attach   42 ", Command::Attach(42));
+    }
+
+    #[test]
+    #[should_panic]
+    fn attach_invalid() {
+        eval_eq!("attach -1", Command::Attach(-1));
+    }
+
+    #[test]
+    fn debugging() {
+        eval_eq!("ni", Command::StepInstruction);
+        eval_eq!("c", Command::Continue);
+        eval_eq!("connect localhost:1234", Command::Connect("localhost:1234".to_string()));
+        eval_eq!(
+            ["main"; 0x1000],
+            "b main",
+            Command::Break {
+                addr: expr("main"),
+                condition: None
+            }
+        );
+        eval_eq!("db $rip + 4", Command::Delete(expr("$rip + 4")));
+        eval_eq!("p $rax", Command::Print(expr("$rax")));
+        eval_eq!(
+            "set $rax = 0x10",
+            Command::SetRegister("rax".to_string(), expr("0x10"))
+        );
+    }
+
+    #[test]
+    fn conditions() {
+        eval_eq!(
+            ["main"; 0x1000],
+            "b main if $rdi == 2",
+            Command::Break {
+                addr: expr("main"),
+                condition: Some(expr("$rdi == 2"))
+            }
+        );
+        eval_eq!(
+            "break 0x10 if *($rsp + 8) != 0 && $rax < 4 ",
+            Command::Break {
+                addr: expr("0x10"),
+                condition: Some(expr("*($rsp + 8) != 0 && $rax < 4"))
+            }
+        );
+    }
+
+    #[test]
+    fn watchpoints() {
+        eval_eq!(
+            "w $rsp",
+            Command::Watch {
+                kind: WatchKind::Write,
+                len: None,
+                addr: expr("$rsp")
+            }
+        );
+        eval_eq!(
+            "watch/4 0x1000",
+            Command::Watch {
+                kind: WatchKind::Write,
+                len: Some(4),
+                addr: expr("0x1000")
+            }
+        );
+        eval_eq!(
+            "rwatch $rsp - 8",
+            Command::Watch {
+                kind: WatchKind::Read,
+                len: None,
+                addr: expr("$rsp - 8")
+            }
+        );
+        eval_eq!(
+            "awatch/2 0x1002",
+            Command::Watch {
+                kind: WatchKind::Access,
+                len: Some(2),
+                addr: expr("0x1002")
+            }
+        );
+        eval_eq!("dw 0x1000", Command::Unwatch(expr("0x1000")));
+    }
+
+    #[test]
+    #[should_panic]
+    fn watchpoints_invalid() {
+        eval_eq!("watch/0 $rsp", Command::Unwatch(expr("$rsp")));
+    }
+
+    #[test]
+    fn inferiors() {
+        eval_eq!("follow-children", Command::FollowChildren(true));
+        eval_eq!("follow-children off", Command::FollowChildren(false));
+        eval_eq!("inferior", Command::Inferior(None));
+        eval_eq!("inferior 1234 ", Command::Inferior(Some(1234)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn inferiors_invalid() {
+        eval_eq!("follow-children maybe", Command::FollowChildren(true));
+    }
+
+    #[test]
+    fn dialects() {
+        eval_eq!("syntax att", Command::Syntax(decoder::Syntax::Att));
+        eval_eq!("syntax intel ", Command::Syntax(decoder::Syntax::Intel));
+        eval_eq!("pseudo", Command::Pseudo(true));
+        eval_eq!("pseudo off", Command::Pseudo(false));
+        eval_eq!("ir", Command::Ir(true));
+        eval_eq!("ir off", Command::Ir(false));
+        eval_eq!("uarch", Command::Uarch(None));
+        eval_eq!("uarch haswell ", Command::Uarch(Some("haswell".to_string())));
+        eval_eq!("extensions", Command::Extensions);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dialects_invalid() {
+        eval_eq!("syntax masm", Command::Syntax(decoder::Syntax::Intel));
+    }
+
+    #[test]
+    fn patching() {
+        eval_eq!(
+            ["main"; 0x1000],
+            "patch main + 4 = 90 90",
+            Command::Patch {
+                addr: 0x1004,
+                bytes: vec![0x90, 0x90]
+            }
+        );
+        eval_eq!(
+            "patch 0x10 = 31c0c3",
+            Command::Patch {
+                addr: 0x10,
+                bytes: vec![0x31, 0xc0, 0xc3]
+            }
+        );
+        eval_eq!(
+            ["main"; 0x1000],
+            "patch/i main = xor eax, eax; ret ",
+            Command::PatchAsm {
+                addr: 0x1000,
+                asm: "xor eax, eax; ret".to_string()
+            }
+        );
+        eval_eq!(
+            "asm mov rax, [rip + 0x10]",
+            Command::Asm {
+                addr: 0,
+                asm: "mov rax, [rip + 0x10]".to_string()
+            }
+        );
+        eval_eq!(
+            ["main"; 0x1000],
+            "asm main + 2 = jmp main",
+            Command::Asm {
+                addr: 0x1002,
+                asm: "jmp main".to_string()
+            }
+        );
+        eval_eq!("undo", Command::Undo);
+        eval_eq!("save /tmp/patched", Command::Save(PathBuf::from("/tmp/patched")));
+    }
+
+    #[test]
+    #[should_panic]
+    fn patching_invalid() {
+        eval_eq!("patch 0x10 = 9", Command::Undo);
+    }
+
+    #[test]
+    fn frames() {
+        eval_eq!("up", Command::Up(1));
+        eval_eq!("up 3", Command::Up(3));
+        eval_eq!("down", Command::Down(1));
+        eval_eq!("down  2 ", Command::Down(2));
+    }
+
+    #[test]
+    fn emulation() {
+        eval_eq!(
+            ["main"; 0x1000],
+            "emulate main",
+            Command::Emulate {
+                addr: 0x1000,
+                steps: 10_000
+            }
+        );
+        eval_eq!(
+            ["main"; 0x1000],
+            "emulate main 200 ",
+            Command::Emulate {
+                addr: 0x1000,
+                steps: 200
+            }
+        );
+        eval_eq!(
+            ["main"; 0x1000],
+            "emulate main + 4",
+            Command::Emulate {
+                addr: 0x1004,
+                steps: 10_000
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn emulation_invalid() {
+        eval_eq!("emulate", Command::Emulate { addr: 0, steps: 1 });
+    }
+
+    #[test]
+    #[should_panic]
+    fn frames_invalid() {
+        eval_eq!("up -1", Command::Up(1));
+    }
+
+    #[test]
+    fn examine() {
+        eval_eq!(
+            "x/4xg $rsp",
+            Command::Examine {
+                count: 4,
+                format: 'x',
+                unit: 8,
+                addr: expr("$rsp"),
+            }
+        );
+        eval_eq!(
+            ["main"; 0x1000],
+            "x/s main",
+            Command::Examine {
+                count: 1,
+                format: 's',
+                unit: 1,
+                addr: expr("main"),
+            }
+        );
+        eval_eq!(
+            "x 0x10",
+            Command::Examine {
+                count: 1,
+                format: 'x',
+                unit: 4,
+                addr: expr("0x10"),
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn examine_invalid() {
+        eval_eq!("x/4q $rsp", Command::Print(expr("$rsp")));
+    }
+
     #[test]
     fn change_dir() {
         let home = expand_homedir(PathBuf::from("~"));
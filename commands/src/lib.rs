@@ -4,7 +4,8 @@ mod debug;
 mod gui;
 
 pub use cli::Cli;
-pub use gui::{Command, Error as CommandError, HELP as CMD_HELP};
+pub use debug::{CompleteExpr, Target};
+pub use gui::{Command, Error as CommandError, WatchKind, HELP as CMD_HELP};
 use once_cell::sync::Lazy;
 
 pub static ARGS: Lazy<cli::Cli> = Lazy::new(cli::Cli::parse);
@@ -0,0 +1,152 @@
+//! Formatting of memory in the style of gdb's `x` command.
+
+use debugvault::Index;
+use std::fmt::Write;
+
+/// Number of bytes displayed on a single line.
+const LINE_WIDTH: usize = 16;
+
+/// Format `bytes` read at `addr` as units of `unit` bytes.
+///
+/// `format` is one of:
+/// * `x` hexadecimal
+/// * `d` signed decimal
+/// * `u` unsigned decimal
+/// * `o` octal
+/// * `t` binary
+/// * `c` character
+/// * `a` address, followed by the nearest symbol
+/// * `s` null terminated string, ignoring `unit`
+pub fn examine(bytes: &[u8], addr: usize, format: char, unit: usize, index: &Index) -> String {
+    let mut out = String::new();
+
+    if format == 's' {
+        let mut offset = 0;
+        for string in bytes.split(|&b| b == 0) {
+            if offset >= bytes.len() {
+                break;
+            }
+
+            let _ = writeln!(
+                out,
+                "{}:\t{:?}",
+                location(addr + offset, index),
+                String::from_utf8_lossy(string)
+            );
+            offset += string.len() + 1;
+        }
+        return out;
+    }
+
+    let unit = unit.clamp(1, 8);
+    let per_line = match format {
+        'a' => 1,
+        't' => std::cmp::max(8 / unit, 1),
+        _ => std::cmp::max(LINE_WIDTH / unit, 1),
+    };
+
+    for (line, chunk) in bytes.chunks(unit * per_line).enumerate() {
+        out += &location(addr + line * unit * per_line, index);
+        out += ":";
+
+        for value in chunk.chunks(unit) {
+            let mut le = [0u8; 8];
+            le[..value.len()].copy_from_slice(value);
+            let value = u64::from_le_bytes(le);
+            let bits = unit as u32 * 8;
+
+            out += "\t";
+            match format {
+                'd' => {
+                    // Sign extend from the unit's size.
+                    let shift = 64 - bits;
+                    let _ = write!(out, "{}", ((value << shift) as i64) >> shift);
+                }
+                'u' => {
+                    let _ = write!(out, "{value}");
+                }
+                'o' => {
+                    let _ = write!(out, "0{value:o}");
+                }
+                't' => {
+                    let _ = write!(out, "{value:0width$b}", width = bits as usize);
+                }
+                'c' => {
+                    let c = value as u8;
+                    let _ = write!(out, "{c} {:?}", c as char);
+                }
+                'a' => {
+                    let _ = write!(out, "{value:#x}");
+                    if let Some(sym) = symbol(value as usize, index) {
+                        let _ = write!(out, " {sym}");
+                    }
+                }
+                _ => {
+                    let _ = write!(out, "{value:#0width$x}", width = unit * 2 + 2);
+                }
+            }
+        }
+
+        out += "\n";
+    }
+
+    out
+}
+
+/// Address followed by the nearest symbol, e.g. `0x401004 <main+4>`.
+fn location(addr: usize, index: &Index) -> String {
+    match symbol(addr, index) {
+        Some(sym) => format!("{addr:#x} {sym}"),
+        None => format!("{addr:#x}"),
+    }
+}
+
+fn symbol(addr: usize, index: &Index) -> Option<String> {
+    let (sym, offset) = index.get_sym_containing_addr(addr)?;
+    Some(match offset {
+        0 => format!("<{}>", sym.as_str()),
+        offset => format!("<{}+{offset}>", sym.as_str()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats() {
+        let mut index = Index::default();
+        index.insert_func(0x1000, "main");
+
+        let bytes = [0xff, 0x00, 0x10, 0x00];
+        assert_eq!(
+            examine(&bytes, 0x1000, 'x', 2, &index),
+            "0x1000 <main>:\t0x00ff\t0x0010\n"
+        );
+        assert_eq!(
+            examine(&bytes, 0x1000, 'd', 1, &index),
+            "0x1000 <main>:\t-1\t0\t16\t0\n"
+        );
+        assert_eq!(
+            examine(&bytes[..2], 0x1000, 'u', 2, &index),
+            "0x1000 <main>:\t255\n"
+        );
+        assert_eq!(
+            examine(&bytes[2..3], 0x1002, 'o', 1, &index),
+            "0x1002 <main+2>:\t020\n"
+        );
+        assert_eq!(
+            examine(&bytes[2..3], 0x1002, 't', 1, &index),
+            "0x1002 <main+2>:\t00010000\n"
+        );
+        assert_eq!(examine(b"A", 0x0, 'c', 1, &index), "0x0:\t65 'A'\n");
+        assert_eq!(
+            examine(&0x1008u32.to_le_bytes(), 0x0, 'a', 4, &index),
+            "0x0:\t0x1008 <main+8>\n"
+        );
+        assert_eq!(
+            examine(b"hi\0yo\0", 0x1000, 's', 1, &index),
+            "0x1000 <main>:\t\"hi\"\n0x1003 <main+3>:\t\"yo\"\n"
+        );
+    }
+}
@@ -0,0 +1,780 @@
+//! Client for the GDB remote serial protocol, as spoken by `gdbserver`, QEMU's gdbstub,
+//! OpenOCD and friends.
+
+mod packet;
+mod target;
+
+pub use target::{Layout, RegisterInfo};
+
+use crate::{Debugger, Error, Register, StopReason, WatchKind};
+use object::{Architecture, Endianness};
+use packet::{decode_hex, encode_hex, parse_hex_usize, protocol, Incoming, Parser};
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// How long to wait on a reply before giving up on the remote.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Packet size assumed if the remote doesn't tell us.
+const DEFAULT_PACKET_SIZE: usize = 0x400;
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixStream),
+}
+
+impl Stream {
+    fn connect(addr: &str) -> Result<Self, Error> {
+        #[cfg(unix)]
+        if addr.contains('/') {
+            let stream = std::os::unix::net::UnixStream::connect(addr)?;
+            stream.set_read_timeout(Some(TIMEOUT))?;
+            return Ok(Stream::Unix(stream));
+        }
+
+        // Allow omitting the host like gdb does, e.g. `:1234`.
+        let addr = match addr.strip_prefix(':') {
+            Some(port) => format!("localhost:{port}"),
+            None => addr.to_string(),
+        };
+
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        Ok(Stream::Tcp(stream))
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> std::io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.set_nonblocking(nonblocking),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+/// A connection to a remote stub.
+pub struct Client {
+    stream: Stream,
+    parser: Parser,
+    /// Whether `QStartNoAckMode` was negotiated.
+    no_ack: bool,
+    packet_size: usize,
+    /// Features the remote reported in it's `qSupported` reply.
+    features: Vec<String>,
+    layout: Layout,
+    arch: Architecture,
+    /// Byte order registers are sent in.
+    endianness: Endianness,
+    running: bool,
+    exited: bool,
+}
+
+impl Client {
+    /// Connect to a remote listening on `addr`, either `host:port` or the path of a unix socket.
+    ///
+    /// `arch` is used if the remote doesn't describe it's own registers. The protocol doesn't
+    /// say what byte order the target uses, it's assumed to be little endian if `endianness`
+    /// isn't given.
+    pub fn connect(
+        addr: &str,
+        arch: Option<Architecture>,
+        endianness: Option<Endianness>,
+    ) -> Result<Self, Error> {
+        let mut client = Self {
+            stream: Stream::connect(addr)?,
+            parser: Parser::default(),
+            no_ack: false,
+            packet_size: DEFAULT_PACKET_SIZE,
+            features: Vec::new(),
+            layout: Layout::default(),
+            arch: Architecture::Unknown,
+            endianness: endianness.unwrap_or(Endianness::Little),
+            running: false,
+            exited: false,
+        };
+
+        let supported = client.request(b"qSupported:swbreak+;hwbreak+;xmlRegisters=i386")?;
+        client.features =
+            String::from_utf8_lossy(&supported).split(';').map(str::to_string).collect();
+
+        if let Some(size) = client.feature_value("PacketSize") {
+            client.packet_size = parse_hex_usize(size.as_bytes())?;
+        }
+
+        if client.supports("QStartNoAckMode") && client.request(b"QStartNoAckMode")? == b"OK" {
+            client.no_ack = true;
+        }
+
+        if client.supports("qXfer:features:read") {
+            let mut layout = Layout::default();
+            let xml = client.read_feature("target.xml")?;
+            layout.parse_xml(&xml, &mut |href| client.read_feature(href).ok());
+            client.layout = layout;
+        }
+
+        if client.layout.registers.is_empty() {
+            let arch = client.layout.arch.or(arch).ok_or(Error::UnknownArchitecture)?;
+            client.layout = Layout::builtin(arch).ok_or(Error::UnknownArchitecture)?;
+        }
+
+        client.arch = client.layout.arch.or(arch).ok_or(Error::UnknownArchitecture)?;
+
+        // Ask why the target is stopped, this also makes sure it is.
+        let reply = client.request(b"?")?;
+        if let StopReason::Exited(_) | StopReason::Killed(_) = parse_stop(&reply)? {
+            client.exited = true;
+        }
+
+        Ok(client)
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f.strip_suffix('+') == Some(feature))
+    }
+
+    fn feature_value(&self, feature: &str) -> Option<&str> {
+        self.features.iter().find_map(|f| f.strip_prefix(feature)?.strip_prefix('='))
+    }
+
+    /// Read a whole document with `qXfer:features:read`.
+    fn read_feature(&mut self, annex: &str) -> Result<String, Error> {
+        let mut doc = Vec::new();
+        let len = self.packet_size.saturating_sub(8);
+
+        loop {
+            let req = format!("qXfer:features:read:{annex}:{:x},{len:x}", doc.len());
+            let reply = self.request(req.as_bytes())?;
+            match reply.split_first() {
+                Some((b'm', data)) => doc.extend_from_slice(data),
+                Some((b'l', data)) => {
+                    doc.extend_from_slice(data);
+                    break;
+                }
+                _ => return Err(protocol("unexpected qXfer reply")),
+            }
+        }
+
+        String::from_utf8(doc).map_err(|_| protocol("target description isn't utf-8"))
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<(), Error> {
+        let packet = packet::encode(data);
+
+        // Retransmit a couple times if the remote didn't get the packet intact.
+        for _ in 0..3 {
+            self.stream.write_all(&packet)?;
+            if self.no_ack {
+                return Ok(());
+            }
+
+            loop {
+                match self.next_incoming()? {
+                    Incoming::Ack => return Ok(()),
+                    Incoming::Nack => break,
+                    Incoming::Packet(..) => log::trace!("[gdb] Dropped packet awaiting ack."),
+                }
+            }
+        }
+
+        Err(protocol("remote keeps rejecting packets"))
+    }
+
+    /// Block until the next packet arrives.
+    fn recv(&mut self) -> Result<Vec<u8>, Error> {
+        loop {
+            if let Incoming::Packet(data, valid) = self.next_incoming()? {
+                if let Some(data) = self.accept(data, valid)? {
+                    return Ok(data);
+                }
+            }
+        }
+    }
+
+    /// Acknowledge a packet, returning it's decoded data if it was received intact.
+    fn accept(&mut self, data: Vec<u8>, valid: bool) -> Result<Option<Vec<u8>>, Error> {
+        if !self.no_ack {
+            self.stream.write_all(if valid { b"+" } else { b"-" })?;
+        }
+
+        if valid || self.no_ack {
+            return packet::decode(&data).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    fn next_incoming(&mut self) -> Result<Incoming, Error> {
+        let mut buf = [0u8; 4096];
+        loop {
+            if let Some(incoming) = self.parser.next() {
+                return Ok(incoming);
+            }
+
+            match self.stream.read(&mut buf) {
+                Ok(0) => return Err(Error::Exited),
+                Ok(n) => self.parser.feed(&buf[..n]),
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(Error::IO(err)),
+            }
+        }
+    }
+
+    /// Send a packet and wait for it's reply, turning error replies into an [`Error`].
+    fn request(&mut self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.send(data)?;
+        let reply = self.recv()?;
+
+        if reply.is_empty() {
+            return Err(Error::Unsupported(packet_name(data)));
+        }
+
+        if reply.len() == 3 && reply[0] == b'E' {
+            let code = parse_hex_usize(&reply[1..])?;
+            return Err(Error::Remote(code as u8));
+        }
+
+        Ok(reply)
+    }
+
+    fn request_ok(&mut self, data: &[u8]) -> Result<(), Error> {
+        match &self.request(data)?[..] {
+            b"OK" => Ok(()),
+            _ => Err(protocol("expected OK")),
+        }
+    }
+
+    /// Wait for a stop reply, logging any console output in between.
+    fn wait(&mut self) -> Result<StopReason, Error> {
+        loop {
+            let reply = self.recv()?;
+            if let Some(reason) = self.handle_stop(&reply)? {
+                return Ok(reason);
+            }
+        }
+    }
+
+    fn handle_stop(&mut self, reply: &[u8]) -> Result<Option<StopReason>, Error> {
+        if let Some(output) = reply.strip_prefix(b"O").filter(|_| reply != b"OK") {
+            let output = decode_hex(output)?;
+            log::complex!(
+                w "[gdb] ",
+                y String::from_utf8_lossy(&output).trim_end().to_string(),
+            );
+            return Ok(None);
+        }
+
+        let reason = parse_stop(reply)?;
+        self.running = false;
+        if let StopReason::Exited(_) | StopReason::Killed(_) = reason {
+            self.exited = true;
+        }
+
+        Ok(Some(reason))
+    }
+
+    fn ensure_stopped(&self) -> Result<(), Error> {
+        if self.exited {
+            return Err(Error::Exited);
+        }
+
+        if self.running {
+            return Err(Error::Running);
+        }
+
+        Ok(())
+    }
+
+    /// Size of a software breakpoint instruction, the `kind` of a `Z0` packet.
+    fn breakpoint_kind(&self) -> usize {
+        match self.arch {
+            Architecture::X86_64 | Architecture::X86_64_X32 | Architecture::I386 => 1,
+            _ => 4,
+        }
+    }
+
+    fn read_registers(&mut self) -> Result<Vec<u8>, Error> {
+        let reply = self.request(b"g")?;
+        decode_hex(&reply)
+    }
+
+    /// Convert a register between the target's byte order and little endian.
+    fn swap_register(&self, value: &mut [u8]) {
+        if self.endianness == Endianness::Big {
+            value.reverse();
+        }
+    }
+}
+
+impl Debugger for Client {
+    fn arch(&self) -> Architecture {
+        self.arch
+    }
+
+    fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    fn registers(&mut self) -> Result<Vec<Register>, Error> {
+        self.ensure_stopped()?;
+
+        let bytes = self.read_registers()?;
+        let mut registers = Vec::with_capacity(self.layout.registers.len());
+        let mut offset = 0;
+
+        for info in self.layout.registers.clone() {
+            let size = info.bits / 8;
+            let mut value = match bytes.get(offset..offset + size) {
+                Some(value) => value.to_vec(),
+                // Registers past the `g` packet have to be read one by one.
+                None => match self.request(format!("p{:x}", info.regnum).as_bytes()) {
+                    Ok(reply) => decode_hex(&reply)?,
+                    Err(_) => continue,
+                },
+            };
+
+            offset += size;
+            self.swap_register(&mut value);
+            registers.push(Register {
+                name: info.name,
+                group: info.group,
+                value,
+            });
+        }
+
+        Ok(registers)
+    }
+
+    fn write_register(&mut self, name: &str, value: u64) -> Result<(), Error> {
+        self.ensure_stopped()?;
+
+        let info = self
+            .layout
+            .find(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownRegister(name.to_string()))?;
+
+        let mut bytes = value.to_le_bytes().to_vec();
+        bytes.resize(info.bits / 8, 0);
+        self.swap_register(&mut bytes);
+
+        let req = format!("P{:x}={}", info.regnum, encode_hex(&bytes));
+        match self.request_ok(req.as_bytes()) {
+            Err(Error::Unsupported(_)) => {}
+            result => return result,
+        }
+
+        // Fall back to rewriting every register.
+        let mut all = self.read_registers()?;
+        let offset: usize = self
+            .layout
+            .registers
+            .iter()
+            .take_while(|reg| reg.regnum != info.regnum)
+            .map(|reg| reg.bits / 8)
+            .sum();
+
+        let dst = all.get_mut(offset..offset + bytes.len()).ok_or(Error::Unsupported("P"))?;
+        dst.copy_from_slice(&bytes);
+
+        let req = format!("G{}", encode_hex(&all));
+        self.request_ok(req.as_bytes())
+    }
+
+    fn read_memory(&mut self, addr: usize, len: usize) -> Result<Vec<u8>, Error> {
+        self.ensure_stopped()?;
+
+        let chunk_size = std::cmp::max(self.packet_size.saturating_sub(4) / 2, 1);
+        let mut data = Vec::with_capacity(len);
+
+        while data.len() < len {
+            let size = std::cmp::min(len - data.len(), chunk_size);
+            let req = format!("m{:x},{size:x}", addr + data.len());
+            let chunk = match self.request(req.as_bytes()) {
+                Ok(reply) => decode_hex(&reply)?,
+                // Return what was read before hitting unmapped memory.
+                Err(Error::Remote(_)) if !data.is_empty() => break,
+                Err(err) => return Err(err),
+            };
+
+            let partial = chunk.len() < size;
+            data.extend(chunk);
+            if partial {
+                break;
+            }
+        }
+
+        Ok(data)
+    }
+
+    fn write_memory(&mut self, addr: usize, data: &[u8]) -> Result<(), Error> {
+        self.ensure_stopped()?;
+
+        let chunk_size = std::cmp::max(self.packet_size.saturating_sub(32) / 2, 1);
+        for (idx, chunk) in data.chunks(chunk_size).enumerate() {
+            let addr = addr + idx * chunk_size;
+            let req = format!("M{addr:x},{:x}:{}", chunk.len(), encode_hex(chunk));
+            self.request_ok(req.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    fn set_breakpoint(&mut self, addr: usize) -> Result<(), Error> {
+        self.ensure_stopped()?;
+        let req = format!("Z0,{addr:x},{}", self.breakpoint_kind());
+        self.request_ok(req.as_bytes())
+    }
+
+    fn remove_breakpoint(&mut self, addr: usize) -> Result<(), Error> {
+        self.ensure_stopped()?;
+        let req = format!("z0,{addr:x},{}", self.breakpoint_kind());
+        self.request_ok(req.as_bytes())
+    }
+
+    fn set_watchpoint(&mut self, addr: usize, len: usize, kind: WatchKind) -> Result<(), Error> {
+        self.ensure_stopped()?;
+        let req = format!("Z{},{addr:x},{len:x}", watch_type(kind));
+        self.request_ok(req.as_bytes())
+    }
+
+    fn remove_watchpoint(&mut self, addr: usize, len: usize, kind: WatchKind) -> Result<(), Error> {
+        self.ensure_stopped()?;
+        let req = format!("z{},{addr:x},{len:x}", watch_type(kind));
+        self.request_ok(req.as_bytes())
+    }
+
+    fn step(&mut self) -> Result<StopReason, Error> {
+        self.ensure_stopped()?;
+        self.send(b"s")?;
+        self.running = true;
+        self.wait()
+    }
+
+    fn cont(&mut self) -> Result<(), Error> {
+        self.ensure_stopped()?;
+        self.send(b"c")?;
+        self.running = true;
+        Ok(())
+    }
+
+    fn poll(&mut self) -> Result<Option<StopReason>, Error> {
+        if !self.running {
+            return Ok(None);
+        }
+
+        self.stream.set_nonblocking(true)?;
+        let mut buf = [0u8; 4096];
+        let read = loop {
+            match self.stream.read(&mut buf) {
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                result => break result,
+            }
+        };
+        self.stream.set_nonblocking(false)?;
+
+        match read {
+            Ok(0) => {
+                self.running = false;
+                self.exited = true;
+                return Err(Error::Exited);
+            }
+            Ok(n) => self.parser.feed(&buf[..n]),
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {}
+            Err(err) => return Err(Error::IO(err)),
+        }
+
+        while let Some(incoming) = self.parser.next() {
+            if let Incoming::Packet(data, valid) = incoming {
+                if let Some(reply) = self.accept(data, valid)? {
+                    if let Some(reason) = self.handle_stop(&reply)? {
+                        return Ok(Some(reason));
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn interrupt(&mut self) -> Result<(), Error> {
+        if self.running {
+            self.stream.write_all(&[packet::INTERRUPT])?;
+        }
+        Ok(())
+    }
+
+    fn detach(&mut self) -> Result<(), Error> {
+        if self.exited {
+            return Ok(());
+        }
+
+        if self.running {
+            self.interrupt()?;
+            self.wait()?;
+        }
+
+        self.request_ok(b"D")?;
+        self.exited = true;
+        Ok(())
+    }
+}
+
+/// Name of a packet used in error messages, e.g. `qXfer` or `Z0`.
+fn packet_name(data: &[u8]) -> &'static str {
+    match data {
+        [b'q' | b'Q', ..] => "queries",
+        [b'Z' | b'z', b'0', ..] => "software breakpoints",
+        [b'Z' | b'z', b'1', ..] => "hardware breakpoints",
+        [b'Z' | b'z', ..] => "watchpoints",
+        [b'p' | b'P', ..] => "single register access",
+        [b'm' | b'M', ..] => "memory access",
+        [b'v', ..] => "extended packets",
+        _ => "the request",
+    }
+}
+
+/// Type of a `Z` packet inserting a watchpoint.
+fn watch_type(kind: WatchKind) -> u8 {
+    match kind {
+        WatchKind::Write => 2,
+        WatchKind::Read => 3,
+        WatchKind::Access => 4,
+    }
+}
+
+/// Parse a stop reply such as `T05thread:01;swbreak:;` or `W00`.
+fn parse_stop(reply: &[u8]) -> Result<StopReason, Error> {
+    let (&kind, rest) = reply.split_first().ok_or_else(|| protocol("empty stop reply"))?;
+    let code = rest.get(..2).ok_or_else(|| protocol("stop reply without signal"))?;
+    let code = parse_hex_usize(code)? as u8;
+
+    match kind {
+        b'S' => Ok(StopReason::Signal(code)),
+        b'W' => Ok(StopReason::Exited(code)),
+        b'X' => Ok(StopReason::Killed(code)),
+        b'T' => {
+            let rest = std::str::from_utf8(&rest[2..]).map_err(|_| protocol("stop reply"))?;
+            for pair in rest.split(';') {
+                let (key, value) = pair.split_once(':').unwrap_or((pair, ""));
+                let kind = match key {
+                    "watch" => WatchKind::Write,
+                    "rwatch" => WatchKind::Read,
+                    "awatch" => WatchKind::Access,
+                    "swbreak" | "hwbreak" => return Ok(StopReason::Breakpoint),
+                    _ => continue,
+                };
+
+                let addr = parse_hex_usize(value.as_bytes())?;
+                return Ok(StopReason::Watchpoint { kind, addr });
+            }
+
+            Ok(StopReason::Signal(code))
+        }
+        _ => Err(protocol("unknown stop reply")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// Serve a single connection, replying to each packet with `reply`.
+    fn serve(reply: fn(&str) -> String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut parser = Parser::default();
+            let mut no_ack = false;
+            let mut buf = [0u8; 4096];
+
+            loop {
+                let n = match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => n,
+                };
+
+                parser.feed(&buf[..n]);
+                while let Some(incoming) = parser.next() {
+                    let data = match incoming {
+                        Incoming::Packet(data, true) => packet::decode(&data).unwrap(),
+                        _ => continue,
+                    };
+
+                    let data = String::from_utf8(data).unwrap();
+                    if !no_ack {
+                        stream.write_all(b"+").unwrap();
+                    }
+
+                    if data == "QStartNoAckMode" {
+                        no_ack = true;
+                    }
+
+                    stream.write_all(&packet::encode(reply(&data).as_bytes())).unwrap();
+                }
+            }
+        });
+
+        addr
+    }
+
+    fn stub(req: &str) -> String {
+        let mut regs = vec![0u8; 17 * 8 + 7 * 4];
+        regs[16 * 8..17 * 8].copy_from_slice(&0x401000u64.to_le_bytes());
+
+        match req {
+            _ if req.starts_with("qSupported") => "PacketSize=100;QStartNoAckMode+".to_string(),
+            "QStartNoAckMode" => "OK".to_string(),
+            "?" => "S05".to_string(),
+            "g" => encode_hex(&regs),
+            "m401000,4" => "554889e5".to_string(),
+            "Z0,401000,1" => "OK".to_string(),
+            "Z2,7ffc10,8" => "OK".to_string(),
+            "z3,7ffc10,8" => "E16".to_string(),
+            "s" => "T05thread:01;".to_string(),
+            "D" => "OK".to_string(),
+            _ if req.starts_with('m') => "E14".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    #[test]
+    fn session() {
+        let addr = serve(stub);
+        let mut client = Client::connect(&addr, Some(Architecture::X86_64), None).unwrap();
+        assert!(client.no_ack);
+        assert_eq!(client.packet_size, 0x100);
+        assert_eq!(client.arch(), Architecture::X86_64);
+
+        assert_eq!(client.pc().unwrap(), 0x401000);
+        assert_eq!(
+            client.read_memory(0x401000, 4).unwrap(),
+            [0x55, 0x48, 0x89, 0xe5]
+        );
+        assert!(matches!(client.read_memory(0, 4), Err(Error::Remote(0x14))));
+        client.set_breakpoint(0x401000).unwrap();
+        assert!(matches!(
+            client.remove_breakpoint(0x401000),
+            Err(Error::Unsupported(_))
+        ));
+        client.set_watchpoint(0x7ffc10, 8, WatchKind::Write).unwrap();
+        assert!(matches!(
+            client.remove_watchpoint(0x7ffc10, 8, WatchKind::Read),
+            Err(Error::Remote(0x16))
+        ));
+        assert!(matches!(
+            client.set_watchpoint(0x7ffc10, 8, WatchKind::Access),
+            Err(Error::Unsupported("watchpoints"))
+        ));
+        assert_eq!(client.step().unwrap(), StopReason::Signal(5));
+        client.detach().unwrap();
+    }
+
+    fn big_endian_stub(req: &str) -> String {
+        let mut regs = vec![0u8; 38 * 4];
+        regs[37 * 4..].copy_from_slice(&0x400120u32.to_be_bytes());
+
+        match req {
+            _ if req.starts_with("qSupported") => "PacketSize=100".to_string(),
+            "?" => "S05".to_string(),
+            "g" => encode_hex(&regs),
+            "P25=00400124" => "OK".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    #[test]
+    fn big_endian_registers() {
+        let addr = serve(big_endian_stub);
+        let mut client =
+            Client::connect(&addr, Some(Architecture::Mips), Some(Endianness::Big)).unwrap();
+
+        assert_eq!(client.pc().unwrap(), 0x400120);
+        let pc = client.registers().unwrap().into_iter().find(|reg| reg.name == "pc").unwrap();
+        assert_eq!(pc.value, [0x20, 0x01, 0x40, 0x00]);
+        client.write_register("pc", 0x400124).unwrap();
+    }
+
+    fn small_packet_stub(req: &str) -> String {
+        match req {
+            _ if req.starts_with("qSupported") => "PacketSize=10".to_string(),
+            "?" => "S05".to_string(),
+            "m401000,6" => "554889e54883".to_string(),
+            "m401006,2" => "ec10".to_string(),
+            _ if req.starts_with("M40100") && req.ends_with(":90") => "OK".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    #[test]
+    fn small_packets() {
+        let addr = serve(small_packet_stub);
+        let mut client = Client::connect(&addr, Some(Architecture::X86_64), None).unwrap();
+        assert_eq!(client.packet_size, 0x10);
+
+        assert_eq!(
+            client.read_memory(0x401000, 8).unwrap(),
+            [0x55, 0x48, 0x89, 0xe5, 0x48, 0x83, 0xec, 0x10]
+        );
+        client.write_memory(0x401000, &[0x90; 4]).unwrap();
+    }
+
+    #[test]
+    fn stop_replies() {
+        assert_eq!(parse_stop(b"S0b").unwrap(), StopReason::Signal(11));
+        assert_eq!(
+            parse_stop(b"T05thread:p1.1;swbreak:;").unwrap(),
+            StopReason::Breakpoint
+        );
+        assert_eq!(
+            parse_stop(b"T05watch:7ffc10;").unwrap(),
+            StopReason::Watchpoint {
+                kind: WatchKind::Write,
+                addr: 0x7ffc10
+            }
+        );
+        assert_eq!(
+            parse_stop(b"W01;process:1a").unwrap(),
+            StopReason::Exited(1)
+        );
+        assert_eq!(parse_stop(b"X09").unwrap(), StopReason::Killed(9));
+        assert!(parse_stop(b"Q").is_err());
+    }
+}
@@ -0,0 +1,194 @@
+//! Framing of remote serial protocol packets.
+//!
+//! ```text
+//! $<data>#<checksum>
+//! ```
+//!
+//! Where `<checksum>` is the two digit hex encoded sum of all bytes in `<data>` modulo 256.
+//! The characters `$`, `#`, `}` and `*` are escaped in `<data>` by a `}` followed by the
+//! character xor'd with 0x20. Replies may be run-length encoded with `*`.
+
+use crate::Error;
+
+/// Ctrl-C, sent out of band to interrupt a running target.
+pub const INTERRUPT: u8 = 0x03;
+
+pub fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+}
+
+/// Frame `data` into a packet, escaping any special characters.
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(data.len() + 4);
+    for &byte in data {
+        if matches!(byte, b'$' | b'#' | b'}' | b'*') {
+            escaped.push(b'}');
+            escaped.push(byte ^ 0x20);
+        } else {
+            escaped.push(byte);
+        }
+    }
+
+    let sum = checksum(&escaped);
+    let mut packet = Vec::with_capacity(escaped.len() + 4);
+    packet.push(b'$');
+    packet.extend_from_slice(&escaped);
+    packet.push(b'#');
+    packet.extend_from_slice(format!("{sum:02x}").as_bytes());
+    packet
+}
+
+/// Unescape and expand the run-length encoding of a packet's data.
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decoded = Vec::with_capacity(data.len());
+    let mut bytes = data.iter().copied();
+
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'}' => {
+                let escaped = bytes.next().ok_or_else(|| protocol("trailing escape"))?;
+                decoded.push(escaped ^ 0x20);
+            }
+            b'*' => {
+                let prev = *decoded.last().ok_or_else(|| protocol("leading repeat"))?;
+                let count = bytes.next().ok_or_else(|| protocol("missing repeat count"))?;
+                let count = count.checked_sub(29).ok_or_else(|| protocol("bad repeat count"))?;
+                decoded.resize(decoded.len() + count as usize, prev);
+            }
+            byte => decoded.push(byte),
+        }
+    }
+
+    Ok(decoded)
+}
+
+/// Incrementally parses packets out of a stream of bytes.
+#[derive(Default)]
+pub struct Parser {
+    buf: Vec<u8>,
+}
+
+/// Something read from the remote.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Incoming {
+    Ack,
+    Nack,
+    /// A packet with it's data and whether the checksum matched.
+    Packet(Vec<u8>, bool),
+}
+
+impl Parser {
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Try to take the next complete message out of the buffer.
+    pub fn next(&mut self) -> Option<Incoming> {
+        loop {
+            let first = *self.buf.first()?;
+            match first {
+                b'+' => {
+                    self.buf.remove(0);
+                    return Some(Incoming::Ack);
+                }
+                b'-' => {
+                    self.buf.remove(0);
+                    return Some(Incoming::Nack);
+                }
+                b'$' => {
+                    let end = self.buf.iter().position(|&b| b == b'#')?;
+                    if self.buf.len() < end + 3 {
+                        return None;
+                    }
+
+                    let data = self.buf[1..end].to_vec();
+                    let sum = std::str::from_utf8(&self.buf[end + 1..end + 3])
+                        .ok()
+                        .and_then(|sum| u8::from_str_radix(sum, 16).ok());
+
+                    self.buf.drain(..end + 3);
+                    let valid = sum == Some(checksum(&data));
+                    return Some(Incoming::Packet(data, valid));
+                }
+                // Garbage in between packets, e.g. stray newlines from some stubs.
+                _ => {
+                    self.buf.remove(0);
+                }
+            }
+        }
+    }
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub fn decode_hex(hex: &[u8]) -> Result<Vec<u8>, Error> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(protocol("odd number of hex digits"));
+    }
+
+    hex.chunks(2)
+        .map(|pair| {
+            // Unavailable registers are reported as 'xx'.
+            if pair == b"xx" {
+                return Ok(0);
+            }
+
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| protocol("invalid hex"))
+        })
+        .collect()
+}
+
+pub fn parse_hex_usize(hex: &[u8]) -> Result<usize, Error> {
+    std::str::from_utf8(hex)
+        .ok()
+        .and_then(|hex| usize::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| protocol("invalid hex number"))
+}
+
+pub fn protocol(msg: &str) -> Error {
+    Error::Protocol(msg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn framing() {
+        assert_eq!(encode(b"g"), b"$g#67");
+        assert_eq!(encode(b"m4015bc,2"), b"$m4015bc,2#5a");
+        assert_eq!(encode(b"X0,1:#"), b"$X0,1:}\x03#9f");
+    }
+
+    #[test]
+    fn run_length() {
+        assert_eq!(decode(b"0* ").unwrap(), b"0000");
+        assert_eq!(decode(b"ab}]").unwrap(), b"ab}");
+        assert!(decode(b"*").is_err());
+    }
+
+    #[test]
+    fn parser() {
+        let mut parser = Parser::default();
+        parser.feed(b"+$OK#9a$T05");
+        assert_eq!(parser.next(), Some(Incoming::Ack));
+        assert_eq!(parser.next(), Some(Incoming::Packet(b"OK".to_vec(), true)));
+        assert_eq!(parser.next(), None);
+        parser.feed(b"#b9-");
+        assert_eq!(parser.next(), Some(Incoming::Packet(b"T05".to_vec(), true)));
+        assert_eq!(parser.next(), Some(Incoming::Nack));
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(decode_hex(b"00ff10").unwrap(), [0x00, 0xff, 0x10]);
+        assert_eq!(decode_hex(b"xx01").unwrap(), [0x00, 0x01]);
+        assert_eq!(encode_hex(&[0xde, 0xad]), "dead");
+        assert_eq!(parse_hex_usize(b"4015bc").unwrap(), 0x4015bc);
+    }
+}
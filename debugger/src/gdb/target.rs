@@ -0,0 +1,310 @@
+//! Register layouts, either described by the remote's `target.xml` or built-in.
+
+use crate::RegisterGroup;
+use object::Architecture;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisterInfo {
+    pub name: String,
+    pub bits: usize,
+    /// Number used by the `p` and `P` packets.
+    pub regnum: usize,
+    pub group: RegisterGroup,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Layout {
+    pub arch: Option<Architecture>,
+    /// Sorted by `regnum`.
+    pub registers: Vec<RegisterInfo>,
+}
+
+impl Layout {
+    pub fn find(&self, name: &str) -> Option<&RegisterInfo> {
+        self.registers.iter().find(|reg| reg.name == name)
+    }
+
+    /// Built-in layout of the `g` packet as used by stubs without target descriptions.
+    pub fn builtin(arch: Architecture) -> Option<Self> {
+        let mut layout = Layout {
+            arch: Some(arch),
+            registers: Vec::new(),
+        };
+
+        match arch {
+            Architecture::X86_64 => {
+                const GPRS: [&str; 17] = [
+                    "rax", "rbx", "rcx", "rdx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10",
+                    "r11", "r12", "r13", "r14", "r15", "rip",
+                ];
+                layout.extend(&GPRS, 64, RegisterGroup::General);
+                layout.push("eflags", 32, RegisterGroup::Flags);
+                layout.extend(
+                    &["cs", "ss", "ds", "es", "fs", "gs"],
+                    32,
+                    RegisterGroup::System,
+                );
+            }
+            Architecture::I386 | Architecture::X86_64_X32 => {
+                const GPRS: [&str; 9] = [
+                    "eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "eip",
+                ];
+                layout.extend(&GPRS, 32, RegisterGroup::General);
+                layout.push("eflags", 32, RegisterGroup::Flags);
+                layout.extend(
+                    &["cs", "ss", "ds", "es", "fs", "gs"],
+                    32,
+                    RegisterGroup::System,
+                );
+            }
+            Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => {
+                for idx in 0..31 {
+                    layout.push(&format!("x{idx}"), 64, RegisterGroup::General);
+                }
+                layout.push("sp", 64, RegisterGroup::General);
+                layout.push("pc", 64, RegisterGroup::General);
+                layout.push("cpsr", 32, RegisterGroup::Flags);
+            }
+            Architecture::Arm => {
+                for idx in 0..13 {
+                    layout.push(&format!("r{idx}"), 32, RegisterGroup::General);
+                }
+                layout.extend(&["sp", "lr", "pc"], 32, RegisterGroup::General);
+                // Legacy FPA registers which are still part of the `g` packet.
+                for idx in 0..8 {
+                    layout.push(&format!("f{idx}"), 96, RegisterGroup::Float);
+                }
+                layout.push("fps", 32, RegisterGroup::Float);
+                layout.push("cpsr", 32, RegisterGroup::Flags);
+            }
+            Architecture::Riscv32 | Architecture::Riscv64 => {
+                const GPRS: [&str; 32] = [
+                    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2",
+                    "a3", "a4", "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9",
+                    "s10", "s11", "t3", "t4", "t5", "t6",
+                ];
+                let bits = if arch == Architecture::Riscv64 {
+                    64
+                } else {
+                    32
+                };
+                layout.extend(&GPRS, bits, RegisterGroup::General);
+                layout.push("pc", bits, RegisterGroup::General);
+            }
+            Architecture::Mips | Architecture::Mips64 => {
+                const GPRS: [&str; 32] = [
+                    "zero", "at", "v0", "v1", "a0", "a1", "a2", "a3", "t0", "t1", "t2", "t3", "t4",
+                    "t5", "t6", "t7", "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7", "t8", "t9",
+                    "k0", "k1", "gp", "sp", "s8", "ra",
+                ];
+                let bits = if arch == Architecture::Mips64 { 64 } else { 32 };
+                layout.extend(&GPRS, bits, RegisterGroup::General);
+                layout.push("sr", bits, RegisterGroup::Flags);
+                layout.extend(&["lo", "hi"], bits, RegisterGroup::General);
+                layout.extend(&["bad", "cause"], bits, RegisterGroup::System);
+                layout.push("pc", bits, RegisterGroup::General);
+            }
+            _ => return None,
+        }
+
+        Some(layout)
+    }
+
+    fn push(&mut self, name: &str, bits: usize, group: RegisterGroup) {
+        let regnum = self.registers.last().map_or(0, |reg| reg.regnum + 1);
+        self.registers.push(RegisterInfo {
+            name: name.to_string(),
+            bits,
+            regnum,
+            group,
+        });
+    }
+
+    fn extend(&mut self, names: &[&str], bits: usize, group: RegisterGroup) {
+        for name in names {
+            self.push(name, bits, group);
+        }
+    }
+
+    /// Add the registers and architecture described in a target description. `include` is
+    /// used to fetch documents referenced by `<xi:include href="..."/>`.
+    pub fn parse_xml(&mut self, xml: &str, include: &mut dyn FnMut(&str) -> Option<String>) {
+        let mut feature = String::new();
+
+        for tag in tags(xml) {
+            let name = tag.split_ascii_whitespace().next().unwrap_or_default();
+            match name {
+                "architecture" => {}
+                "feature" => feature = attribute(tag, "name").unwrap_or_default().to_string(),
+                "xi:include" => {
+                    if let Some(doc) = attribute(tag, "href").and_then(&mut *include) {
+                        self.parse_xml(&doc, include);
+                    }
+                }
+                "reg" => {
+                    let name = match attribute(tag, "name") {
+                        Some(name) => name.to_string(),
+                        None => continue,
+                    };
+
+                    let bits = attribute(tag, "bitsize").and_then(|s| s.parse().ok()).unwrap_or(0);
+                    let regnum = match attribute(tag, "regnum").and_then(|s| s.parse().ok()) {
+                        Some(regnum) => regnum,
+                        None => self.registers.last().map_or(0, |reg| reg.regnum + 1),
+                    };
+
+                    let group = infer_group(&name, &feature, tag);
+                    self.registers.push(RegisterInfo {
+                        name,
+                        bits,
+                        regnum,
+                        group,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(start) = xml.find("<architecture>") {
+            let rest = &xml[start + "<architecture>".len()..];
+            if let Some(end) = rest.find("</architecture>") {
+                self.arch = parse_architecture(rest[..end].trim());
+            }
+        }
+
+        self.registers.sort_by_key(|reg| reg.regnum);
+    }
+}
+
+/// Contents of every `<..>` tag, excluding comments, closing tags and processing instructions.
+fn tags(xml: &str) -> impl Iterator<Item = &str> {
+    xml.split('<').skip(1).filter_map(|part| {
+        let end = part.find('>')?;
+        let tag = part[..end].trim_end_matches('/').trim();
+        if tag.starts_with(['/', '!', '?']) {
+            return None;
+        }
+        Some(tag)
+    })
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(idx) = rest.find(name) {
+        let after = &rest[idx + name.len()..];
+        let is_boundary = idx == 0 || rest.as_bytes()[idx - 1].is_ascii_whitespace();
+        if is_boundary {
+            if let Some(after) = after.trim_start().strip_prefix('=') {
+                let after = after.trim_start();
+                let quote = after.chars().next()?;
+                let value = &after[1..];
+                return value.find(quote).map(|end| &value[..end]);
+            }
+        }
+        rest = after;
+    }
+    None
+}
+
+fn infer_group(name: &str, feature: &str, tag: &str) -> RegisterGroup {
+    match attribute(tag, "group") {
+        Some("general") => return RegisterGroup::General,
+        Some("float") => return RegisterGroup::Float,
+        Some("vector") => return RegisterGroup::Vector,
+        Some("system") => return RegisterGroup::System,
+        _ => {}
+    }
+
+    if matches!(
+        name,
+        "eflags" | "cpsr" | "fpsr" | "fpcr" | "mxcsr" | "fpscr" | "sr" | "fcsr"
+    ) {
+        return RegisterGroup::Flags;
+    }
+
+    let tipe = attribute(tag, "type").unwrap_or_default();
+    if tipe.starts_with("vec") || tipe.starts_with("uint128") || tipe.starts_with("neon") {
+        return RegisterGroup::Vector;
+    }
+
+    if tipe.starts_with("i387") || tipe.starts_with("ieee") {
+        return RegisterGroup::Float;
+    }
+
+    let feature = feature.rsplit('.').next().unwrap_or_default();
+    match feature {
+        "sse" | "avx" | "avx512" | "neon" | "sve" | "vector" => RegisterGroup::Vector,
+        "fpu" | "vfp" | "fpa" => RegisterGroup::Float,
+        "linux" | "segments" | "system" | "cp0" => RegisterGroup::System,
+        _ => RegisterGroup::General,
+    }
+}
+
+pub fn parse_architecture(name: &str) -> Option<Architecture> {
+    Some(match name {
+        "i386:x86-64" => Architecture::X86_64,
+        "i386:x64-32" => Architecture::X86_64_X32,
+        "i386" | "i386:intel" => Architecture::I386,
+        "aarch64" => Architecture::Aarch64,
+        "riscv:rv64" => Architecture::Riscv64,
+        "riscv:rv32" => Architecture::Riscv32,
+        name if name.starts_with("arm") => Architecture::Arm,
+        name if name.starts_with("mips:isa64") => Architecture::Mips64,
+        name if name.starts_with("mips") => Architecture::Mips,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGET: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target>
+  <architecture>i386:x86-64</architecture>
+  <xi:include href="64bit-core.xml"/>
+  <xi:include href="64bit-sse.xml"/>
+</target>"#;
+
+    const CORE: &str = r#"<feature name="org.gnu.gdb.i386.core">
+  <reg name="rax" bitsize="64" type="int64"/>
+  <reg name="rbx" bitsize="64" type="int64"/>
+  <reg name="rip" bitsize="64" type="code_ptr"/>
+  <reg name="eflags" bitsize="32" type="i386_eflags"/>
+</feature>"#;
+
+    const SSE: &str = r#"<feature name="org.gnu.gdb.i386.sse">
+  <reg name="xmm0" bitsize="128" type="vec128" regnum="40"/>
+  <reg name="mxcsr" bitsize="32" type="i386_mxcsr" group="vector"/>
+</feature>"#;
+
+    #[test]
+    fn target_description() {
+        let mut layout = Layout::default();
+        layout.parse_xml(TARGET, &mut |href| match href {
+            "64bit-core.xml" => Some(CORE.to_string()),
+            "64bit-sse.xml" => Some(SSE.to_string()),
+            _ => None,
+        });
+
+        assert_eq!(layout.arch, Some(Architecture::X86_64));
+        let names: Vec<&str> = layout.registers.iter().map(|reg| &reg.name[..]).collect();
+        assert_eq!(names, ["rax", "rbx", "rip", "eflags", "xmm0", "mxcsr"]);
+        assert_eq!(layout.find("rip").unwrap().regnum, 2);
+        assert_eq!(layout.find("eflags").unwrap().group, RegisterGroup::Flags);
+        assert_eq!(layout.find("xmm0").unwrap().regnum, 40);
+        assert_eq!(layout.find("xmm0").unwrap().group, RegisterGroup::Vector);
+        assert_eq!(layout.find("mxcsr").unwrap().regnum, 41);
+    }
+
+    #[test]
+    fn builtin() {
+        let layout = Layout::builtin(Architecture::Aarch64).unwrap();
+        assert_eq!(layout.find("pc").unwrap().regnum, 32);
+        assert_eq!(layout.find("cpsr").unwrap().bits, 32);
+
+        let layout = Layout::builtin(Architecture::Arm).unwrap();
+        assert_eq!(layout.find("cpsr").unwrap().regnum, 25);
+    }
+}
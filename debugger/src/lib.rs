@@ -0,0 +1,266 @@
+//! Debugger backends that can be driven from the terminal.
+
+mod fmt;
+pub mod gdb;
+pub mod ptrace;
+pub mod unwind;
+
+pub use fmt::examine;
+
+use object::{Architecture, Endianness};
+
+#[derive(Debug)]
+pub enum Error {
+    IO(std::io::Error),
+    /// The remote sent something we don't understand.
+    Protocol(String),
+    /// The remote replied with `E NN`.
+    Remote(u8),
+    /// The remote doesn't support the request.
+    Unsupported(&'static str),
+    UnknownRegister(String),
+    UnknownArchitecture,
+    /// The target isn't stopped, so it can't be inspected.
+    Running,
+    /// The target exited or was killed.
+    Exited,
+    /// No process with the id is being debugged.
+    UnknownInferior(i32),
+    /// Every hardware debug register is already in use.
+    NoDebugRegisters,
+    /// The watched range can't be expressed by the hardware.
+    UnalignedWatchpoint {
+        addr: usize,
+        len: usize,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IO(err) => f.write_fmt(format_args!("{err}.")),
+            Self::Protocol(msg) => f.write_fmt(format_args!("Malformed reply: {msg}.")),
+            Self::Remote(code) => f.write_fmt(format_args!("Remote replied with error {code}.")),
+            Self::Unsupported(what) => f.write_fmt(format_args!("Remote doesn't support {what}.")),
+            Self::UnknownRegister(name) => f.write_fmt(format_args!("Unknown register '{name}'.")),
+            Self::UnknownArchitecture => f.write_str("Target architecture is unknown."),
+            Self::Running => f.write_str("Target is running."),
+            Self::Exited => f.write_str("Target has exited."),
+            Self::UnknownInferior(pid) => {
+                f.write_fmt(format_args!("Process {pid} isn't being debugged."))
+            }
+            Self::NoDebugRegisters => f.write_str("All hardware debug registers are in use."),
+            Self::UnalignedWatchpoint { addr, len } => f.write_fmt(format_args!(
+                "Can't watch {len} bytes at {addr:#x}, the address must be aligned to the length."
+            )),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::IO(err)
+    }
+}
+
+/// Where a register is displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterGroup {
+    General,
+    Flags,
+    Vector,
+    Float,
+    System,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Register {
+    pub name: String,
+    pub group: RegisterGroup,
+    /// Value in little endian byte order regardless of the target's, `bits / 8` bytes long.
+    pub value: Vec<u8>,
+}
+
+impl Register {
+    /// Lower 64 bits of the register.
+    pub fn as_u64(&self) -> u64 {
+        let mut bytes = [0u8; 8];
+        let len = std::cmp::min(self.value.len(), 8);
+        bytes[..len].copy_from_slice(&self.value[..len]);
+        u64::from_le_bytes(bytes)
+    }
+}
+
+/// What kind of memory access triggers a watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    Write,
+    Read,
+    Access,
+}
+
+/// Why the target stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    /// Stopped by a signal, single steps and traps show up as a `SIGTRAP`.
+    Signal(u8),
+    Breakpoint,
+    Watchpoint {
+        kind: WatchKind,
+        addr: usize,
+    },
+    /// Stopped in a newly forked child, which became the selected inferior.
+    Forked {
+        parent: i32,
+        child: i32,
+    },
+    /// The selected inferior replaced its program.
+    Exec(std::path::PathBuf),
+    /// One of multiple processes being debugged exited or was killed, the others are still
+    /// being debugged.
+    InferiorExited {
+        pid: i32,
+        reason: Box<StopReason>,
+    },
+    Exited(u8),
+    Killed(u8),
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Signal(5) => f.write_str("trace trap"),
+            Self::Signal(sig) => f.write_fmt(format_args!("signal {sig}")),
+            Self::Breakpoint => f.write_str("breakpoint"),
+            Self::Watchpoint { kind, addr } => {
+                f.write_fmt(format_args!("{kind:?} watchpoint at {addr:#x}"))
+            }
+            Self::Forked { parent, child } => {
+                f.write_fmt(format_args!("fork of process {parent} into {child}"))
+            }
+            Self::Exec(path) => f.write_fmt(format_args!("exec of {}", path.display())),
+            Self::InferiorExited { pid, reason } => {
+                f.write_fmt(format_args!("process {pid} {reason}"))
+            }
+            Self::Exited(code) => f.write_fmt(format_args!("exited with code {code}")),
+            Self::Killed(sig) => f.write_fmt(format_args!("killed by signal {sig}")),
+        }
+    }
+}
+
+/// A process being debugged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Inferior {
+    pub pid: i32,
+    pub running: bool,
+    /// Whether commands act on this process.
+    pub selected: bool,
+}
+
+/// Common interface of every debugger backend.
+pub trait Debugger: Send {
+    /// Instruction set of the target.
+    fn arch(&self) -> Architecture;
+
+    /// Byte order of the target's memory.
+    fn endianness(&self) -> Endianness {
+        Endianness::Little
+    }
+
+    /// All registers of the currently selected thread.
+    fn registers(&mut self) -> Result<Vec<Register>, Error>;
+
+    fn write_register(&mut self, name: &str, value: u64) -> Result<(), Error>;
+
+    fn read_memory(&mut self, addr: usize, len: usize) -> Result<Vec<u8>, Error>;
+
+    fn write_memory(&mut self, addr: usize, data: &[u8]) -> Result<(), Error>;
+
+    /// Read a `size` byte word in the target's byte order.
+    fn read_word(&mut self, addr: usize, size: usize) -> Result<u64, Error> {
+        let bytes = self.read_memory(addr, size.min(8))?;
+        if bytes.len() < size.min(8) {
+            return Err(Error::Unsupported("reading partial words"));
+        }
+
+        let mut word = [0u8; 8];
+        Ok(match self.endianness() {
+            Endianness::Little => {
+                word[..bytes.len()].copy_from_slice(&bytes);
+                u64::from_le_bytes(word)
+            }
+            Endianness::Big => {
+                word[8 - bytes.len()..].copy_from_slice(&bytes);
+                u64::from_be_bytes(word)
+            }
+        })
+    }
+
+    fn set_breakpoint(&mut self, addr: usize) -> Result<(), Error>;
+
+    fn remove_breakpoint(&mut self, addr: usize) -> Result<(), Error>;
+
+    /// Software breakpoints along with the bytes they replaced, for reading the target's memory
+    /// without going through the debugger. Empty if the target hides them by itself.
+    fn breakpoints(&self) -> Vec<(usize, Vec<u8>)> {
+        Vec::new()
+    }
+
+    /// Stop when `len` bytes at `addr` are accessed, using the hardware's debug registers.
+    fn set_watchpoint(&mut self, addr: usize, len: usize, kind: WatchKind) -> Result<(), Error>;
+
+    fn remove_watchpoint(&mut self, addr: usize, len: usize, kind: WatchKind) -> Result<(), Error>;
+
+    /// Execute a single instruction, blocking until the target stops.
+    fn step(&mut self) -> Result<StopReason, Error>;
+
+    /// Resume the target without waiting for it to stop, see [`Debugger::poll`].
+    fn cont(&mut self) -> Result<(), Error>;
+
+    /// Check whether a running target has stopped, without blocking.
+    fn poll(&mut self) -> Result<Option<StopReason>, Error>;
+
+    /// Ask a running target to stop.
+    fn interrupt(&mut self) -> Result<(), Error>;
+
+    /// Stop debugging, letting the target run freely.
+    fn detach(&mut self) -> Result<(), Error>;
+
+    /// Processes being debugged, empty if the backend can't tell them apart.
+    fn inferiors(&self) -> Vec<Inferior> {
+        Vec::new()
+    }
+
+    /// Select the process that commands act on.
+    fn select_inferior(&mut self, _pid: i32) -> Result<(), Error> {
+        Err(Error::Unsupported("multiple inferiors"))
+    }
+
+    /// Whether to debug children forked by the target, instead of detaching from them.
+    fn follow_children(&mut self, _follow: bool) -> Result<(), Error> {
+        Err(Error::Unsupported("following children"))
+    }
+
+    /// Program counter of the currently selected thread.
+    fn pc(&mut self) -> Result<usize, Error> {
+        let name = pc_name(self.arch()).ok_or(Error::UnknownArchitecture)?;
+        self.registers()?
+            .iter()
+            .find(|reg| reg.name == name)
+            .map(|reg| reg.as_u64() as usize)
+            .ok_or_else(|| Error::UnknownRegister(name.to_string()))
+    }
+}
+
+/// Name of the program counter register.
+pub fn pc_name(arch: Architecture) -> Option<&'static str> {
+    Some(match arch {
+        Architecture::X86_64 => "rip",
+        Architecture::I386 | Architecture::X86_64_X32 => "eip",
+        Architecture::Arm => "pc",
+        Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => "pc",
+        Architecture::Riscv32 | Architecture::Riscv64 => "pc",
+        Architecture::Mips | Architecture::Mips64 => "pc",
+        _ => return None,
+    })
+}
@@ -0,0 +1,200 @@
+//! Registers and hardware watchpoints of AArch64.
+
+use super::{Field, Watchpoint, NT_PRFPREG, NT_PRSTATUS};
+use crate::{Error, RegisterGroup, WatchKind};
+use nix::libc;
+use nix::sys::ptrace;
+use nix::unistd::Pid;
+use object::Architecture;
+
+pub const ARCH: Architecture = Architecture::Aarch64;
+pub const PC: &str = "pc";
+
+/// `brk #0`.
+pub const TRAP: &[u8] = &[0x00, 0x00, 0x20, 0xd4];
+
+/// `brk` traps before executing, so the pc is left at the breakpoint.
+pub const TRAP_PC_OFFSET: usize = 0;
+
+/// `si_code` of a `SIGTRAP` raised by `brk`, `TRAP_BRKPT`.
+pub const BREAKPOINT_CODE: i32 = 1;
+
+/// Register set holding the watchpoint registers, `struct user_hwdebug_state`.
+const NT_ARM_HW_WATCH: usize = 0x403;
+
+/// Size of the `dbg_info` field and padding preceding the registers.
+const HWDEBUG_HEADER: usize = 8;
+
+/// Size of an address and control register pair, including padding.
+const HWDEBUG_REG: usize = 16;
+
+/// Watchpoints can only observe bytes within a single aligned doubleword.
+const DOUBLEWORD: usize = 8;
+
+pub fn registers() -> Vec<Field> {
+    let mut fields = Vec::new();
+
+    // `user_pt_regs` holds x0-x30, sp, pc and pstate as 8-byte words.
+    const NAMES: [&str; 33] = [
+        "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+        "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26",
+        "x27", "x28", "x29", "x30", "sp", "pc",
+    ];
+
+    for (idx, name) in NAMES.into_iter().enumerate() {
+        fields.push(Field {
+            name,
+            regset: NT_PRSTATUS,
+            offset: idx * 8,
+            size: 8,
+            group: RegisterGroup::General,
+        });
+    }
+
+    fields.push(Field {
+        name: "cpsr",
+        regset: NT_PRSTATUS,
+        offset: NAMES.len() * 8,
+        size: 4,
+        group: RegisterGroup::Flags,
+    });
+
+    // `user_fpsimd_state` holds v0-v31 as 16-byte words, followed by fpsr and fpcr.
+    const VECTORS: [&str; 32] = [
+        "v0", "v1", "v2", "v3", "v4", "v5", "v6", "v7", "v8", "v9", "v10", "v11", "v12", "v13",
+        "v14", "v15", "v16", "v17", "v18", "v19", "v20", "v21", "v22", "v23", "v24", "v25", "v26",
+        "v27", "v28", "v29", "v30", "v31",
+    ];
+
+    for (idx, name) in VECTORS.into_iter().enumerate() {
+        fields.push(Field {
+            name,
+            regset: NT_PRFPREG,
+            offset: idx * 16,
+            size: 16,
+            group: RegisterGroup::Vector,
+        });
+    }
+
+    for (idx, name) in ["fpsr", "fpcr"].into_iter().enumerate() {
+        fields.push(Field {
+            name,
+            regset: NT_PRFPREG,
+            offset: VECTORS.len() * 16 + idx * 4,
+            size: 4,
+            group: RegisterGroup::Float,
+        });
+    }
+
+    fields
+}
+
+pub fn can_watch(addr: usize, len: usize) -> bool {
+    (1..=DOUBLEWORD).contains(&len) && addr % DOUBLEWORD + len <= DOUBLEWORD
+}
+
+/// Address and control register of a watchpoint.
+fn watch_registers(wp: &Watchpoint) -> (u64, u32) {
+    let offset = wp.addr % DOUBLEWORD;
+
+    // Load/store control.
+    let lsc = match wp.kind {
+        WatchKind::Read => 0b01,
+        WatchKind::Write => 0b10,
+        WatchKind::Access => 0b11,
+    };
+
+    // Byte address select, which bytes of the doubleword are watched.
+    let bas = ((1u32 << wp.len) - 1) << offset;
+
+    // Enabled, only trapping accesses from EL0.
+    let ctrl = (bas << 5) | (lsc << 3) | (0b10 << 1) | 1;
+
+    ((wp.addr - offset) as u64, ctrl)
+}
+
+fn hwdebug_state(watchpoints: &[Option<Watchpoint>]) -> Vec<u8> {
+    let mut state = vec![0u8; HWDEBUG_HEADER + watchpoints.len() * HWDEBUG_REG];
+
+    for (idx, wp) in watchpoints.iter().enumerate() {
+        let (addr, ctrl) = wp.as_ref().map_or((0, 0), watch_registers);
+        let reg = &mut state[HWDEBUG_HEADER + idx * HWDEBUG_REG..][..HWDEBUG_REG];
+        reg[..8].copy_from_slice(&addr.to_ne_bytes());
+        reg[8..12].copy_from_slice(&ctrl.to_ne_bytes());
+    }
+
+    state
+}
+
+/// Number of watchpoints that can be set, according to `dbg_info`.
+pub fn debug_registers(pid: Pid) -> Result<usize, Error> {
+    let mut state = [0u8; HWDEBUG_HEADER + 16 * HWDEBUG_REG];
+    let mut iov = libc::iovec {
+        iov_base: state.as_mut_ptr().cast(),
+        iov_len: state.len(),
+    };
+
+    let iov_ptr = &mut iov as *mut libc::iovec;
+    super::request(
+        ptrace::Request::PTRACE_GETREGSET,
+        pid,
+        NT_ARM_HW_WATCH,
+        iov_ptr.cast(),
+    )?;
+    Ok(state[0] as usize)
+}
+
+pub fn write_debug_registers(pid: Pid, watchpoints: &[Option<Watchpoint>]) -> Result<(), Error> {
+    let mut state = hwdebug_state(watchpoints);
+    let mut iov = libc::iovec {
+        iov_base: state.as_mut_ptr().cast(),
+        iov_len: state.len(),
+    };
+
+    let iov_ptr = &mut iov as *mut libc::iovec;
+    super::request(
+        ptrace::Request::PTRACE_SETREGSET,
+        pid,
+        NT_ARM_HW_WATCH,
+        iov_ptr.cast(),
+    )
+}
+
+/// Which watchpoint caused the last trap, the kernel reports the accessed address.
+pub fn triggered_watchpoint(
+    _: Pid,
+    info: &libc::siginfo_t,
+    watchpoints: &[Option<Watchpoint>],
+) -> Result<Option<usize>, Error> {
+    let accessed = unsafe { info.si_addr() } as usize;
+
+    Ok(watchpoints.iter().position(|wp| match wp {
+        Some(wp) => {
+            let start = wp.addr - wp.addr % DOUBLEWORD;
+            (start..start + DOUBLEWORD).contains(&accessed)
+        }
+        None => false,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control_register() {
+        let wp = Watchpoint {
+            addr: 0x1004,
+            len: 4,
+            kind: WatchKind::Write,
+        };
+
+        // BAS = 0b11110000, LSC = store, PAC = EL0, enabled.
+        let ctrl = (0b1111_0000 << 5) | (0b10 << 3) | (0b10 << 1) | 1;
+        assert_eq!(watch_registers(&wp), (0x1000, ctrl));
+        assert_eq!(hwdebug_state(&[Some(wp), None]).len(), 40);
+        assert!(can_watch(0x1004, 4));
+        assert!(!can_watch(0x1006, 4));
+        assert!(!can_watch(0x1000, 16));
+    }
+}
@@ -0,0 +1,1160 @@
+//! Native debugging of local processes through `ptrace`.
+//!
+//! Every `ptrace` request has to come from the thread that attached. All threads of a process
+//! are traced: once one of them stops the others are stopped as well, and they're resumed
+//! together. Threads created later are traced automatically and get the same watchpoints, while
+//! breakpoints live in the memory every thread shares. Forks and execs are always traced, so
+//! that children can either be followed or have our breakpoints removed before they're let go.
+
+#![cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+#[cfg(target_arch = "x86_64")]
+mod x86_64;
+
+#[cfg(target_arch = "aarch64")]
+use aarch64 as native;
+#[cfg(target_arch = "x86_64")]
+use x86_64 as native;
+
+use crate::{Debugger, Error, Inferior, Register, RegisterGroup, StopReason, WatchKind};
+use nix::errno::Errno;
+use nix::libc;
+use nix::sys::ptrace;
+use nix::sys::signal::{self, Signal};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+use object::Architecture;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::os::unix::fs::FileExt;
+
+/// Register set holding the general purpose registers.
+const NT_PRSTATUS: usize = 1;
+
+/// Register set holding the floating point and vector registers.
+const NT_PRFPREG: usize = 2;
+
+/// A register stored in one of the kernel's register sets.
+struct Field {
+    name: &'static str,
+    regset: usize,
+    offset: usize,
+    size: usize,
+    group: RegisterGroup,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Watchpoint {
+    addr: usize,
+    len: usize,
+    kind: WatchKind,
+}
+
+impl From<Errno> for Error {
+    fn from(err: Errno) -> Self {
+        Error::IO(err.into())
+    }
+}
+
+/// A thread of a traced process.
+#[derive(Debug, Default)]
+struct Thread {
+    /// Signal that stopped the thread, which is delivered once it resumes.
+    pending: Option<Signal>,
+    /// Whether the thread stopped for something else before our `SIGSTOP` arrived, which is
+    /// then reported once it resumes.
+    stopping: bool,
+}
+
+/// A single process being traced by us.
+struct Tracee {
+    pid: Pid,
+    /// Thread that stopped last, which registers are read from and steps are taken by.
+    current: Pid,
+    /// Every thread of the process, including the main thread.
+    threads: BTreeMap<Pid, Thread>,
+    /// `/proc/<pid>/mem`, unlike `PTRACE_POKEDATA` it can be written to in large chunks.
+    mem: File,
+    /// Original bytes overwritten by software breakpoints.
+    breakpoints: BTreeMap<usize, Vec<u8>>,
+    /// Hardware watchpoints, indexed by debug register.
+    watchpoints: Vec<Option<Watchpoint>>,
+    /// Stop that happened before the tracee was resumed, reported by the next poll.
+    deferred: Option<StopReason>,
+    /// Whether the last fork was a `vfork`, which shares the address space with the child.
+    vfork: bool,
+    running: bool,
+}
+
+impl Tracee {
+    /// Attach to a process, stopping every thread of it.
+    pub fn attach(pid: i32) -> Result<Self, Error> {
+        let pid = Pid::from_raw(pid);
+        let mem = open_mem(pid)?;
+
+        if !attach_thread(pid)? {
+            return Err(Error::Exited);
+        }
+
+        // Threads that aren't stopped yet can still create new ones, so keep looking until
+        // every thread was attached to.
+        let mut threads = BTreeMap::from([(pid, Thread::default())]);
+        loop {
+            let new: Vec<Pid> =
+                tasks(pid)?.into_iter().filter(|tid| !threads.contains_key(tid)).collect();
+
+            if new.is_empty() {
+                break;
+            }
+
+            for tid in new {
+                if attach_thread(tid)? {
+                    threads.insert(tid, Thread::default());
+                }
+            }
+        }
+
+        let slots = native::debug_registers(pid)?;
+
+        Ok(Self {
+            pid,
+            current: pid,
+            threads,
+            mem,
+            breakpoints: BTreeMap::new(),
+            watchpoints: vec![None; slots],
+            deferred: None,
+            vfork: false,
+            running: false,
+        })
+    }
+
+    /// Take over a child forked by `parent`, which the kernel already attached us to.
+    fn adopt(pid: i32, parent: &Tracee) -> Result<Self, Error> {
+        let pid = Pid::from_raw(pid);
+
+        // Wait for the child's initial `SIGSTOP`.
+        waitpid(pid, Some(WaitPidFlag::__WALL))?;
+
+        Ok(Self {
+            pid,
+            // Only the thread that forked is copied into the child.
+            current: pid,
+            threads: BTreeMap::from([(pid, Thread::default())]),
+            mem: open_mem(pid)?,
+            // The child got a copy of the parent's memory, including our breakpoints.
+            breakpoints: parent.breakpoints.clone(),
+            // Debug registers aren't inherited.
+            watchpoints: vec![None; parent.watchpoints.len()],
+            deferred: None,
+            vfork: false,
+            running: false,
+        })
+    }
+
+    /// Start tracing a thread created by `parent`, which the kernel already attached us to.
+    fn add_thread(&mut self, parent: Pid) -> Result<Pid, Error> {
+        let tid = Pid::from_raw(ptrace::getevent(parent)? as i32);
+
+        // Wait for the thread's initial `SIGSTOP`.
+        waitpid(tid, Some(WaitPidFlag::__WALL))?;
+        self.threads.insert(tid, Thread::default());
+
+        // Debug registers aren't inherited by new threads.
+        native::write_debug_registers(tid, &self.watchpoints)?;
+        Ok(tid)
+    }
+
+    fn thread(&mut self) -> &mut Thread {
+        self.threads.entry(self.current).or_default()
+    }
+
+    fn ensure_stopped(&self) -> Result<(), Error> {
+        match self.running {
+            true => Err(Error::Running),
+            false => Ok(()),
+        }
+    }
+
+    fn read_regset(&self, regset: usize) -> Result<Vec<u8>, Error> {
+        let mut buf = vec![0u8; 4096];
+        let mut iov = libc::iovec {
+            iov_base: buf.as_mut_ptr().cast(),
+            iov_len: buf.len(),
+        };
+
+        let iov_ptr = &mut iov as *mut libc::iovec;
+        request(
+            ptrace::Request::PTRACE_GETREGSET,
+            self.current,
+            regset,
+            iov_ptr.cast(),
+        )?;
+        buf.truncate(iov.iov_len);
+        Ok(buf)
+    }
+
+    fn write_regset(&self, regset: usize, buf: &mut [u8]) -> Result<(), Error> {
+        let mut iov = libc::iovec {
+            iov_base: buf.as_mut_ptr().cast(),
+            iov_len: buf.len(),
+        };
+
+        let iov_ptr = &mut iov as *mut libc::iovec;
+        request(
+            ptrace::Request::PTRACE_SETREGSET,
+            self.current,
+            regset,
+            iov_ptr.cast(),
+        )
+    }
+
+    /// Write the watchpoints into the debug registers of every thread.
+    fn write_debug_registers(&self) -> Result<(), Error> {
+        for &tid in self.threads.keys() {
+            native::write_debug_registers(tid, &self.watchpoints)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_pc(&mut self, pc: usize) -> Result<(), Error> {
+        self.write_register(native::PC, pc as u64)
+    }
+
+    /// Execute the instruction under a breakpoint without triggering it, returning [`None`]
+    /// if there isn't a breakpoint at the pc.
+    ///
+    /// Only the current thread runs, the others can't miss the breakpoint while it's removed.
+    fn step_over_breakpoint(&mut self) -> Result<Option<StopReason>, Error> {
+        let pc = self.pc()?;
+        let original = match self.breakpoints.get(&pc) {
+            Some(original) => original.clone(),
+            None => return Ok(None),
+        };
+
+        let tid = self.current;
+        let signal = self.thread().pending.take();
+        self.mem.write_all_at(&original, pc as u64)?;
+        ptrace::step(tid, signal)?;
+        let status = waitpid(tid, Some(WaitPidFlag::__WALL));
+        self.mem.write_all_at(native::TRAP, pc as u64)?;
+        self.stop_reason(status?).map(Some)
+    }
+
+    /// Translate a wait status into why the tracee stopped, making the thread it's about the
+    /// current one.
+    fn stop_reason(&mut self, status: WaitStatus) -> Result<StopReason, Error> {
+        self.running = false;
+
+        let signal = match status {
+            WaitStatus::Exited(tid, _) | WaitStatus::Signaled(tid, ..) if tid != self.pid => {
+                // Commands act on the main thread once the current one is gone.
+                self.threads.remove(&tid);
+                self.current = self.pid;
+                return Ok(StopReason::Signal(Signal::SIGTRAP as u8));
+            }
+            WaitStatus::Exited(_, code) => return Ok(StopReason::Exited(code as u8)),
+            WaitStatus::Signaled(_, signal, _) => return Ok(StopReason::Killed(signal as u8)),
+            WaitStatus::PtraceEvent(tid, _, event) => {
+                self.current = tid;
+                return self.event_reason(event);
+            }
+            WaitStatus::Stopped(tid, signal) => {
+                self.current = tid;
+                signal
+            }
+            status => {
+                return Err(Error::Protocol(format!(
+                    "unexpected wait status {status:?}"
+                )))
+            }
+        };
+
+        if signal != Signal::SIGTRAP {
+            // Stops caused by `interrupt` shouldn't be redelivered.
+            if signal != Signal::SIGSTOP {
+                self.thread().pending = Some(signal);
+            }
+
+            return Ok(StopReason::Signal(signal as u8));
+        }
+
+        let info = ptrace::getsiginfo(self.current)?;
+        if info.si_code == libc::TRAP_HWBKPT {
+            let slot = native::triggered_watchpoint(self.current, &info, &self.watchpoints)?;
+            if let Some(Watchpoint { addr, kind, .. }) =
+                slot.and_then(|slot| self.watchpoints[slot])
+            {
+                return Ok(StopReason::Watchpoint { kind, addr });
+            }
+        }
+
+        if info.si_code == native::BREAKPOINT_CODE {
+            let addr = self.pc()? - native::TRAP_PC_OFFSET;
+            if self.breakpoints.contains_key(&addr) {
+                self.set_pc(addr)?;
+                return Ok(StopReason::Breakpoint);
+            }
+        }
+
+        Ok(StopReason::Signal(signal as u8))
+    }
+
+    /// Translate a `PTRACE_EVENT_*` stop of the current thread.
+    fn event_reason(&mut self, event: i32) -> Result<StopReason, Error> {
+        match event {
+            libc::PTRACE_EVENT_FORK | libc::PTRACE_EVENT_VFORK => {
+                self.vfork = event == libc::PTRACE_EVENT_VFORK;
+                let child = ptrace::getevent(self.current)? as i32;
+                Ok(StopReason::Forked {
+                    parent: self.pid.as_raw(),
+                    child,
+                })
+            }
+            libc::PTRACE_EVENT_CLONE => {
+                // The new thread stays stopped until the others are resumed.
+                self.add_thread(self.current)?;
+                Ok(StopReason::Signal(Signal::SIGTRAP as u8))
+            }
+            libc::PTRACE_EVENT_EXEC => {
+                // The other threads were killed, and the thread that called exec took over the
+                // process id.
+                for &tid in self.threads.keys().filter(|&&tid| tid != self.pid) {
+                    let _ = waitpid(tid, Some(WaitPidFlag::WNOHANG | WaitPidFlag::__WALL));
+                }
+
+                let pid = self.pid;
+                self.threads.retain(|&tid, _| tid == pid);
+                self.current = pid;
+
+                // The old address space is gone, along with our breakpoints and watchpoints.
+                self.breakpoints.clear();
+                self.watchpoints.fill(None);
+                self.mem = open_mem(self.pid)?;
+
+                let path = std::fs::read_link(format!("/proc/{}/exe", self.pid))?;
+                Ok(StopReason::Exec(path))
+            }
+            _ => Ok(StopReason::Signal(Signal::SIGTRAP as u8)),
+        }
+    }
+
+    /// Stop every thread except `running`.
+    ///
+    /// Threads that stopped for something else first report our `SIGSTOP` once they resume.
+    /// Their breakpoint is hit again and their signal delivered then, any other stop is
+    /// reported by the next poll.
+    fn stop_threads(&mut self, running: Option<Pid>) -> Result<(), Error> {
+        let tids: Vec<Pid> =
+            self.threads.keys().copied().filter(|&tid| Some(tid) != running).collect();
+
+        for &tid in &tids {
+            match tgkill(self.pid, tid, Signal::SIGSTOP) {
+                // The thread exited, which the wait below reports.
+                Err(Errno::ESRCH) => {}
+                result => result?,
+            }
+        }
+
+        let current = self.current;
+        for tid in tids {
+            loop {
+                // An exec while stopping the threads gets rid of the others.
+                if !self.threads.contains_key(&tid) {
+                    break;
+                }
+
+                match waitpid(tid, Some(WaitPidFlag::__WALL))? {
+                    WaitStatus::Stopped(_, Signal::SIGSTOP) => break,
+                    WaitStatus::Exited(..) | WaitStatus::Signaled(..) => {
+                        self.threads.remove(&tid);
+                        break;
+                    }
+                    // The new thread stays stopped, while the one creating it still has to
+                    // receive our `SIGSTOP`.
+                    WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_CLONE) => {
+                        self.add_thread(tid)?;
+                        ptrace::cont(tid, None)?;
+                    }
+                    status => {
+                        self.threads.entry(tid).or_default().stopping = true;
+
+                        match self.stop_reason(status)? {
+                            StopReason::Breakpoint | StopReason::Signal(_) => {}
+                            reason => {
+                                self.deferred = self.deferred.take().or(Some(reason));
+                            }
+                        }
+
+                        break;
+                    }
+                }
+            }
+        }
+
+        if self.threads.contains_key(&current) {
+            self.current = current;
+        }
+
+        Ok(())
+    }
+
+    /// Stop a running tracee so that it can be detached, returning [`None`] if it exited.
+    ///
+    /// If it stopped for any other reason than our `SIGSTOP`, the signal is still queued and
+    /// the tracee has to be sent a `SIGCONT` once detached.
+    fn halt(&mut self) -> Result<Option<bool>, Error> {
+        if self.running {
+            if self.deferred.take().is_none() {
+                self.stop_threads(None)?;
+            }
+
+            self.running = false;
+        }
+
+        if !self.threads.contains_key(&self.pid) {
+            return Ok(None);
+        }
+
+        Ok(Some(self.threads.values().any(|thread| thread.stopping)))
+    }
+}
+
+/// Attach to a single thread and wait for it to stop, returning `false` if it exited first.
+fn attach_thread(tid: Pid) -> Result<bool, Error> {
+    match ptrace::attach(tid) {
+        Err(Errno::ESRCH) => return Ok(false),
+        result => result?,
+    }
+
+    // Our `SIGSTOP` can arrive after other stops, such as the trap of an exec in progress.
+    loop {
+        match waitpid(tid, Some(WaitPidFlag::__WALL))? {
+            WaitStatus::Stopped(_, Signal::SIGSTOP) => break,
+            WaitStatus::Stopped(_, Signal::SIGTRAP) => ptrace::cont(tid, None)?,
+            WaitStatus::Stopped(_, signal) => ptrace::cont(tid, signal)?,
+            WaitStatus::Exited(..) | WaitStatus::Signaled(..) => return Ok(false),
+            status => {
+                return Err(Error::Protocol(format!(
+                    "unexpected wait status {status:?}"
+                )))
+            }
+        }
+    }
+
+    // Children and threads inherit these options when they're automatically traced.
+    let options = ptrace::Options::PTRACE_O_TRACEFORK
+        | ptrace::Options::PTRACE_O_TRACEVFORK
+        | ptrace::Options::PTRACE_O_TRACECLONE
+        | ptrace::Options::PTRACE_O_TRACEEXEC;
+    ptrace::setoptions(tid, options)?;
+    Ok(true)
+}
+
+/// Ids of every thread of a process, from `/proc/<pid>/task`.
+fn tasks(pid: Pid) -> Result<Vec<Pid>, Error> {
+    let mut tasks = Vec::new();
+    for entry in std::fs::read_dir(format!("/proc/{pid}/task"))? {
+        if let Some(tid) = entry?.file_name().to_str().and_then(|name| name.parse().ok()) {
+            tasks.push(Pid::from_raw(tid));
+        }
+    }
+
+    Ok(tasks)
+}
+
+/// Send a signal to a single thread, `kill` lets any thread of the process handle it.
+fn tgkill(pid: Pid, tid: Pid, signal: Signal) -> nix::Result<()> {
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_tgkill,
+            pid.as_raw(),
+            tid.as_raw(),
+            signal as libc::c_int,
+        )
+    };
+
+    Errno::result(ret).map(drop)
+}
+
+/// `/proc/<pid>/mem` refers to the address space at the time it's opened, so it has to be
+/// reopened after an exec.
+fn open_mem(pid: Pid) -> Result<File, Error> {
+    let mem = File::options().read(true).write(true).open(format!("/proc/{pid}/mem"))?;
+    Ok(mem)
+}
+
+/// Raw `ptrace` request for the requests `nix` doesn't wrap in a portable way.
+fn request(
+    request: ptrace::Request,
+    pid: Pid,
+    addr: usize,
+    data: *mut libc::c_void,
+) -> Result<(), Error> {
+    let ret = unsafe {
+        libc::ptrace(
+            request as ptrace::RequestType,
+            pid.as_raw(),
+            addr as *mut libc::c_void,
+            data,
+        )
+    };
+
+    Errno::result(ret).map(drop).map_err(Error::from)
+}
+
+impl Debugger for Tracee {
+    fn arch(&self) -> Architecture {
+        native::ARCH
+    }
+
+    fn registers(&mut self) -> Result<Vec<Register>, Error> {
+        self.ensure_stopped()?;
+
+        let general = self.read_regset(NT_PRSTATUS)?;
+        let vector = self.read_regset(NT_PRFPREG).unwrap_or_default();
+
+        let mut registers = Vec::new();
+        for field in native::registers() {
+            let regset = match field.regset {
+                NT_PRSTATUS => &general,
+                _ => &vector,
+            };
+
+            if let Some(value) = regset.get(field.offset..field.offset + field.size) {
+                registers.push(Register {
+                    name: field.name.to_string(),
+                    group: field.group,
+                    value: value.to_vec(),
+                });
+            }
+        }
+
+        Ok(registers)
+    }
+
+    fn write_register(&mut self, name: &str, value: u64) -> Result<(), Error> {
+        self.ensure_stopped()?;
+
+        let field = native::registers()
+            .into_iter()
+            .find(|field| field.name == name)
+            .ok_or_else(|| Error::UnknownRegister(name.to_string()))?;
+
+        let mut regset = self.read_regset(field.regset)?;
+        let dst = regset
+            .get_mut(field.offset..field.offset + field.size)
+            .ok_or_else(|| Error::UnknownRegister(name.to_string()))?;
+
+        // Registers wider than 64 bits are zero extended.
+        let value = value.to_le_bytes();
+        let len = std::cmp::min(dst.len(), value.len());
+        dst.fill(0);
+        dst[..len].copy_from_slice(&value[..len]);
+
+        self.write_regset(field.regset, &mut regset)
+    }
+
+    fn read_memory(&mut self, addr: usize, len: usize) -> Result<Vec<u8>, Error> {
+        let mut buf = vec![0u8; len];
+        self.mem.read_exact_at(&mut buf, addr as u64)?;
+
+        // Hide our own breakpoints.
+        for (&bp, original) in self.breakpoints.range(addr.saturating_sub(8)..addr + len) {
+            for (idx, &byte) in original.iter().enumerate() {
+                if let Some(dst) = (bp + idx).checked_sub(addr).and_then(|off| buf.get_mut(off)) {
+                    *dst = byte;
+                }
+            }
+        }
+
+        Ok(buf)
+    }
+
+    fn write_memory(&mut self, addr: usize, data: &[u8]) -> Result<(), Error> {
+        self.mem.write_all_at(data, addr as u64)?;
+        Ok(())
+    }
+
+    fn set_breakpoint(&mut self, addr: usize) -> Result<(), Error> {
+        self.ensure_stopped()?;
+
+        if self.breakpoints.contains_key(&addr) {
+            return Ok(());
+        }
+
+        let mut original = vec![0u8; native::TRAP.len()];
+        self.mem.read_exact_at(&mut original, addr as u64)?;
+        self.mem.write_all_at(native::TRAP, addr as u64)?;
+        self.breakpoints.insert(addr, original);
+        Ok(())
+    }
+
+    fn remove_breakpoint(&mut self, addr: usize) -> Result<(), Error> {
+        self.ensure_stopped()?;
+
+        if let Some(original) = self.breakpoints.remove(&addr) {
+            self.mem.write_all_at(&original, addr as u64)?;
+        }
+
+        Ok(())
+    }
+
+    fn breakpoints(&self) -> Vec<(usize, Vec<u8>)> {
+        self.breakpoints.iter().map(|(&addr, original)| (addr, original.clone())).collect()
+    }
+
+    fn set_watchpoint(&mut self, addr: usize, len: usize, kind: WatchKind) -> Result<(), Error> {
+        self.ensure_stopped()?;
+
+        let watchpoint = Watchpoint { addr, len, kind };
+        if !native::can_watch(addr, len) {
+            return Err(Error::UnalignedWatchpoint { addr, len });
+        }
+
+        if self.watchpoints.contains(&Some(watchpoint)) {
+            return Ok(());
+        }
+
+        let slot = self
+            .watchpoints
+            .iter()
+            .position(Option::is_none)
+            .ok_or(Error::NoDebugRegisters)?;
+
+        self.watchpoints[slot] = Some(watchpoint);
+        if let Err(err) = self.write_debug_registers() {
+            // Take it back out of the threads that did get it.
+            self.watchpoints[slot] = None;
+            let _ = self.write_debug_registers();
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn remove_watchpoint(&mut self, addr: usize, len: usize, kind: WatchKind) -> Result<(), Error> {
+        self.ensure_stopped()?;
+
+        let watchpoint = Some(Watchpoint { addr, len, kind });
+        for slot in self.watchpoints.iter_mut().filter(|slot| **slot == watchpoint) {
+            *slot = None;
+        }
+
+        self.write_debug_registers()
+    }
+
+    fn step(&mut self) -> Result<StopReason, Error> {
+        self.ensure_stopped()?;
+
+        if let Some(reason) = self.step_over_breakpoint()? {
+            return Ok(reason);
+        }
+
+        let tid = self.current;
+        let signal = self.thread().pending.take();
+        ptrace::step(tid, signal)?;
+        let status = waitpid(tid, Some(WaitPidFlag::__WALL))?;
+        self.stop_reason(status)
+    }
+
+    fn cont(&mut self) -> Result<(), Error> {
+        self.ensure_stopped()?;
+
+        // A thread stopped for something else while the others were being stopped.
+        if self.deferred.is_some() {
+            self.running = true;
+            return Ok(());
+        }
+
+        // Anything other than the step completing is reported by the next `poll`.
+        match self.step_over_breakpoint()? {
+            None | Some(StopReason::Signal(5)) => {}
+            Some(reason) => {
+                self.deferred = Some(reason);
+                self.running = true;
+                return Ok(());
+            }
+        }
+
+        for (&tid, thread) in &mut self.threads {
+            ptrace::cont(tid, thread.pending.take())?;
+        }
+
+        self.running = true;
+        Ok(())
+    }
+
+    fn poll(&mut self) -> Result<Option<StopReason>, Error> {
+        if !self.running {
+            return Ok(None);
+        }
+
+        if let Some(reason) = self.deferred.take() {
+            self.running = false;
+            return Ok(Some(reason));
+        }
+
+        let tids: Vec<Pid> = self.threads.keys().copied().collect();
+        for tid in tids {
+            match waitpid(tid, Some(WaitPidFlag::WNOHANG | WaitPidFlag::__WALL))? {
+                WaitStatus::StillAlive => {}
+                // The process keeps running when a thread other than the main one exits.
+                WaitStatus::Exited(..) | WaitStatus::Signaled(..) if tid != self.pid => {
+                    self.threads.remove(&tid);
+                }
+                // New threads are traced along with the others, without stopping them.
+                WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_CLONE) => {
+                    let thread = self.add_thread(tid)?;
+                    ptrace::cont(thread, None)?;
+                    ptrace::cont(tid, None)?;
+                }
+                WaitStatus::Stopped(_, Signal::SIGSTOP)
+                    if self.threads.get(&tid).is_some_and(|thread| thread.stopping) =>
+                {
+                    self.threads.entry(tid).or_default().stopping = false;
+                    ptrace::cont(tid, None)?;
+                }
+                status => {
+                    let reason = self.stop_reason(status)?;
+                    if !matches!(reason, StopReason::Exited(_) | StopReason::Killed(_)) {
+                        self.stop_threads(Some(self.current))?;
+                    }
+
+                    return Ok(Some(reason));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn interrupt(&mut self) -> Result<(), Error> {
+        if self.running && self.deferred.is_none() {
+            signal::kill(self.pid, Signal::SIGSTOP)?;
+        }
+
+        Ok(())
+    }
+
+    fn detach(&mut self) -> Result<(), Error> {
+        self.ensure_stopped()?;
+
+        for (addr, original) in std::mem::take(&mut self.breakpoints) {
+            self.mem.write_all_at(&original, addr as u64)?;
+        }
+
+        self.watchpoints.fill(None);
+        self.write_debug_registers()?;
+
+        for (&tid, thread) in &mut self.threads {
+            ptrace::detach(tid, thread.pending.take())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Every process being debugged, the attached process and any children it forked.
+pub struct Session {
+    tracees: Vec<Tracee>,
+    /// Index of the tracee commands act on.
+    selected: usize,
+    follow_children: bool,
+}
+
+impl Session {
+    /// Attach to a process, stopping it.
+    pub fn attach(pid: i32) -> Result<Self, Error> {
+        Ok(Self {
+            tracees: vec![Tracee::attach(pid)?],
+            selected: 0,
+            follow_children: false,
+        })
+    }
+
+    fn current(&mut self) -> &mut Tracee {
+        &mut self.tracees[self.selected]
+    }
+
+    /// Start tracing a child forked by the tracee at `parent`, selecting it if we're following
+    /// children and otherwise letting it go.
+    fn fork(&mut self, parent: usize, child: i32) -> Result<Option<StopReason>, Error> {
+        let mut tracee = Tracee::adopt(child, &self.tracees[parent])?;
+
+        if !self.follow_children {
+            // A vfork'd child shares it's memory with the parent, which still needs the
+            // breakpoints.
+            if self.tracees[parent].vfork {
+                tracee.breakpoints.clear();
+            }
+
+            tracee.detach()?;
+            return Ok(None);
+        }
+
+        self.tracees.push(tracee);
+        self.selected = self.tracees.len() - 1;
+
+        Ok(Some(StopReason::Forked {
+            parent: self.tracees[parent].pid.as_raw(),
+            child,
+        }))
+    }
+}
+
+impl Debugger for Session {
+    fn arch(&self) -> Architecture {
+        native::ARCH
+    }
+
+    fn registers(&mut self) -> Result<Vec<Register>, Error> {
+        self.current().registers()
+    }
+
+    fn write_register(&mut self, name: &str, value: u64) -> Result<(), Error> {
+        self.current().write_register(name, value)
+    }
+
+    fn read_memory(&mut self, addr: usize, len: usize) -> Result<Vec<u8>, Error> {
+        self.current().read_memory(addr, len)
+    }
+
+    fn write_memory(&mut self, addr: usize, data: &[u8]) -> Result<(), Error> {
+        self.current().write_memory(addr, data)
+    }
+
+    fn set_breakpoint(&mut self, addr: usize) -> Result<(), Error> {
+        self.current().set_breakpoint(addr)
+    }
+
+    fn remove_breakpoint(&mut self, addr: usize) -> Result<(), Error> {
+        self.current().remove_breakpoint(addr)
+    }
+
+    fn breakpoints(&self) -> Vec<(usize, Vec<u8>)> {
+        self.tracees[self.selected].breakpoints()
+    }
+
+    fn set_watchpoint(&mut self, addr: usize, len: usize, kind: WatchKind) -> Result<(), Error> {
+        self.current().set_watchpoint(addr, len, kind)
+    }
+
+    fn remove_watchpoint(&mut self, addr: usize, len: usize, kind: WatchKind) -> Result<(), Error> {
+        self.current().remove_watchpoint(addr, len, kind)
+    }
+
+    fn step(&mut self) -> Result<StopReason, Error> {
+        match self.current().step()? {
+            StopReason::Forked { child, .. } => {
+                let selected = self.selected;
+                let reason = self.fork(selected, child)?;
+                Ok(reason.unwrap_or(StopReason::Signal(Signal::SIGTRAP as u8)))
+            }
+            reason => Ok(reason),
+        }
+    }
+
+    fn cont(&mut self) -> Result<(), Error> {
+        self.current().cont()
+    }
+
+    fn poll(&mut self) -> Result<Option<StopReason>, Error> {
+        for idx in 0..self.tracees.len() {
+            let reason = match self.tracees[idx].poll()? {
+                Some(reason) => reason,
+                None => continue,
+            };
+
+            match reason {
+                StopReason::Forked { child, .. } => {
+                    let reason = self.fork(idx, child)?;
+                    self.tracees[idx].cont()?;
+                    if reason.is_some() {
+                        return Ok(reason);
+                    }
+                }
+                // The session only ends once every process is gone.
+                StopReason::Exited(_) | StopReason::Killed(_) if self.tracees.len() > 1 => {
+                    let pid = self.tracees.remove(idx).pid.as_raw();
+                    if self.selected >= idx {
+                        self.selected = self.selected.saturating_sub(1);
+                    }
+
+                    return Ok(Some(StopReason::InferiorExited {
+                        pid,
+                        reason: Box::new(reason),
+                    }));
+                }
+                reason => {
+                    self.selected = idx;
+                    return Ok(Some(reason));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn interrupt(&mut self) -> Result<(), Error> {
+        self.current().interrupt()
+    }
+
+    fn detach(&mut self) -> Result<(), Error> {
+        self.current().ensure_stopped()?;
+
+        for mut tracee in std::mem::take(&mut self.tracees) {
+            let needs_cont = match tracee.halt()? {
+                Some(needs_cont) => needs_cont,
+                None => continue,
+            };
+
+            tracee.detach()?;
+            if needs_cont {
+                signal::kill(tracee.pid, Signal::SIGCONT)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn inferiors(&self) -> Vec<Inferior> {
+        self.tracees
+            .iter()
+            .enumerate()
+            .map(|(idx, tracee)| Inferior {
+                pid: tracee.pid.as_raw(),
+                running: tracee.running,
+                selected: idx == self.selected,
+            })
+            .collect()
+    }
+
+    fn select_inferior(&mut self, pid: i32) -> Result<(), Error> {
+        self.selected = self
+            .tracees
+            .iter()
+            .position(|tracee| tracee.pid.as_raw() == pid)
+            .ok_or(Error::UnknownInferior(pid))?;
+
+        Ok(())
+    }
+
+    fn follow_children(&mut self, follow: bool) -> Result<(), Error> {
+        self.follow_children = follow;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::process::Command;
+
+    #[test]
+    fn attach() {
+        let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+        let mut tracee = Tracee::attach(child.id() as i32).unwrap();
+
+        let pc = tracee.pc().unwrap();
+        assert_ne!(pc, 0);
+        assert!(tracee.registers().unwrap().iter().any(|reg| reg.group == RegisterGroup::Vector));
+
+        // Breakpoints are invisible when reading memory.
+        let original = tracee.read_memory(pc, 8).unwrap();
+        tracee.set_breakpoint(pc).unwrap();
+        assert_eq!(tracee.read_memory(pc, 8).unwrap(), original);
+        assert_eq!(
+            tracee.breakpoints(),
+            [(pc, original[..native::TRAP.len()].to_vec())]
+        );
+        tracee.remove_breakpoint(pc).unwrap();
+
+        let registers = tracee.registers().unwrap();
+        let sp = registers.iter().find(|reg| matches!(&reg.name[..], "rsp" | "sp")).unwrap();
+        let sp = sp.as_u64() as usize;
+        tracee.set_watchpoint(sp & !7, 8, WatchKind::Write).unwrap();
+        assert!(matches!(
+            tracee.set_watchpoint(sp | 1, 8, WatchKind::Write),
+            Err(Error::UnalignedWatchpoint { .. })
+        ));
+        tracee.remove_watchpoint(sp & !7, 8, WatchKind::Write).unwrap();
+
+        tracee.detach().unwrap();
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    /// Address a process started executing at, from its auxiliary vector.
+    fn entry_point(pid: u32) -> usize {
+        const AT_ENTRY: u64 = 9;
+
+        let auxv = std::fs::read(format!("/proc/{pid}/auxv")).unwrap();
+        let words: Vec<u64> = auxv
+            .chunks_exact(8)
+            .map(|word| u64::from_ne_bytes(word.try_into().unwrap()))
+            .collect();
+        let entry = words.chunks_exact(2).find(|pair| pair[0] == AT_ENTRY).unwrap()[1];
+        entry as usize
+    }
+
+    /// Poll until the session stops, giving up after a few seconds.
+    fn wait_for_stop(session: &mut Session) -> StopReason {
+        for _ in 0..500 {
+            if let Some(reason) = session.poll().unwrap() {
+                return reason;
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        panic!("session didn't stop");
+    }
+
+    #[test]
+    fn follow_children() {
+        // The shell forks a child for the background job once it reads a line, which then
+        // execs `sleep`.
+        let mut shell = Command::new("sh")
+            .arg("-c")
+            .arg("read line; sleep 10 & wait")
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut session = Session::attach(shell.id() as i32).unwrap();
+        session.follow_children(true).unwrap();
+
+        // The shell's entry point isn't executed again, so the breakpoint is never hit.
+        session.set_breakpoint(entry_point(shell.id())).unwrap();
+        session.cont().unwrap();
+        shell.stdin.take().unwrap().write_all(b"\n").unwrap();
+
+        let child = match wait_for_stop(&mut session) {
+            StopReason::Forked { parent, child } => {
+                assert_eq!(parent, shell.id() as i32);
+                child
+            }
+            reason => panic!("expected a fork, got {reason:?}"),
+        };
+
+        let inferiors = session.inferiors();
+        assert_eq!(inferiors.len(), 2);
+        assert!(inferiors.iter().any(|inferior| inferior.pid == child && inferior.selected));
+        assert_eq!(session.current().breakpoints.len(), 1);
+
+        session.cont().unwrap();
+        match wait_for_stop(&mut session) {
+            StopReason::Exec(path) => assert!(path.ends_with("sleep")),
+            reason => panic!("expected an exec, got {reason:?}"),
+        }
+
+        // The child's copy of the breakpoint was replaced along with the rest of its memory.
+        assert!(session.current().breakpoints.is_empty());
+        assert_eq!(session.tracees[0].breakpoints.len(), 1);
+
+        // The shell exits once its child is killed, which is reported before the session ends.
+        session.cont().unwrap();
+        signal::kill(Pid::from_raw(child), Signal::SIGKILL).unwrap();
+        let mut exits = Vec::new();
+        loop {
+            match wait_for_stop(&mut session) {
+                StopReason::Signal(_) => session.cont().unwrap(),
+                StopReason::Exited(code) => break assert_eq!(code, 0),
+                reason => exits.push(reason),
+            }
+        }
+
+        assert_eq!(
+            exits,
+            [StopReason::InferiorExited {
+                pid: child,
+                reason: Box::new(StopReason::Killed(Signal::SIGKILL as u8))
+            }]
+        );
+
+        // The shell was already reaped while tracing it.
+        let _ = shell.wait();
+    }
+
+    /// Written by threads created after attaching, watched by [`threads`].
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    /// Run by [`threads`] in a separate process, as the test harness runs tests on threads.
+    #[test]
+    #[ignore]
+    fn count_on_threads() {
+        println!("counter {:#x}", &COUNTER as *const _ as usize);
+        std::thread::spawn(|| loop {
+            std::thread::spawn(|| COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+                .join()
+                .unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        });
+        std::thread::sleep(std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn threads() {
+        use std::io::BufRead;
+
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--ignored",
+                "--exact",
+                "--nocapture",
+                "ptrace::tests::count_on_threads",
+            ])
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let counter = std::io::BufReader::new(child.stdout.take().unwrap())
+            .lines()
+            .find_map(|line| {
+                let line = line.unwrap();
+                // The harness prints the test's name on the same line.
+                let (_, addr) = line.split_once("counter 0x")?;
+                usize::from_str_radix(addr, 16).ok()
+            })
+            .unwrap();
+
+        // Wait for the test to start running.
+        let task = format!("/proc/{}/task", child.id());
+        while std::fs::read_dir(&task).unwrap().count() < 3 {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        let mut session = Session::attach(child.id() as i32).unwrap();
+        assert!(session.current().threads.len() >= 3);
+        let pc = session.pc().unwrap();
+        session.set_breakpoint(pc).unwrap();
+        session.remove_breakpoint(pc).unwrap();
+
+        // Only threads created after attaching write the counter.
+        session.set_watchpoint(counter, 8, WatchKind::Write).unwrap();
+        session.cont().unwrap();
+        assert_eq!(
+            wait_for_stop(&mut session),
+            StopReason::Watchpoint {
+                kind: WatchKind::Write,
+                addr: counter
+            }
+        );
+
+        let tracee = session.current();
+        assert_ne!(tracee.current, tracee.pid);
+        assert!(!tracee.running);
+
+        session.detach().unwrap();
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
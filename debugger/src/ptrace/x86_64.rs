@@ -0,0 +1,217 @@
+//! Registers and debug registers (DR0-DR7) of x86-64.
+
+use super::{Field, Watchpoint, NT_PRFPREG, NT_PRSTATUS};
+use crate::{Error, RegisterGroup, WatchKind};
+use nix::libc;
+use nix::sys::ptrace;
+use nix::unistd::Pid;
+use object::Architecture;
+
+pub const ARCH: Architecture = Architecture::X86_64;
+pub const PC: &str = "rip";
+
+/// `int3`.
+pub const TRAP: &[u8] = &[0xcc];
+
+/// `int3` traps after executing, leaving the pc just past the breakpoint.
+pub const TRAP_PC_OFFSET: usize = 1;
+
+/// `si_code` of a `SIGTRAP` raised by `int3`.
+pub const BREAKPOINT_CODE: i32 = 0x80;
+
+/// DR0-DR3 hold addresses, DR6 is the status and DR7 the control register.
+const DEBUG_ADDRESSES: usize = 4;
+const DR6: usize = 6;
+const DR7: usize = 7;
+
+/// Index into `user_regs_struct`, which is made up of 8-byte words.
+const GENERAL: [(&str, usize, RegisterGroup); 26] = [
+    ("rax", 10, RegisterGroup::General),
+    ("rbx", 5, RegisterGroup::General),
+    ("rcx", 11, RegisterGroup::General),
+    ("rdx", 12, RegisterGroup::General),
+    ("rsi", 13, RegisterGroup::General),
+    ("rdi", 14, RegisterGroup::General),
+    ("rbp", 4, RegisterGroup::General),
+    ("rsp", 19, RegisterGroup::General),
+    ("r8", 9, RegisterGroup::General),
+    ("r9", 8, RegisterGroup::General),
+    ("r10", 7, RegisterGroup::General),
+    ("r11", 6, RegisterGroup::General),
+    ("r12", 3, RegisterGroup::General),
+    ("r13", 2, RegisterGroup::General),
+    ("r14", 1, RegisterGroup::General),
+    ("r15", 0, RegisterGroup::General),
+    ("rip", 16, RegisterGroup::General),
+    ("eflags", 18, RegisterGroup::Flags),
+    ("cs", 17, RegisterGroup::System),
+    ("ss", 20, RegisterGroup::System),
+    ("ds", 23, RegisterGroup::System),
+    ("es", 24, RegisterGroup::System),
+    ("fs", 25, RegisterGroup::System),
+    ("gs", 26, RegisterGroup::System),
+    ("fs_base", 21, RegisterGroup::System),
+    ("gs_base", 22, RegisterGroup::System),
+];
+
+const XMM: [&str; 16] = [
+    "xmm0", "xmm1", "xmm2", "xmm3", "xmm4", "xmm5", "xmm6", "xmm7", "xmm8", "xmm9", "xmm10",
+    "xmm11", "xmm12", "xmm13", "xmm14", "xmm15",
+];
+
+/// Offset of `xmm_space` in `user_fpregs_struct`.
+const XMM_OFFSET: usize = 160;
+
+/// Offset of `mxcsr` in `user_fpregs_struct`.
+const MXCSR_OFFSET: usize = 24;
+
+pub fn registers() -> Vec<Field> {
+    let mut fields = Vec::new();
+
+    for (name, word, group) in GENERAL {
+        let size = match group {
+            RegisterGroup::General => 8,
+            _ if name.ends_with("_base") => 8,
+            _ => 4,
+        };
+
+        fields.push(Field {
+            name,
+            regset: NT_PRSTATUS,
+            offset: word * 8,
+            size,
+            group,
+        });
+    }
+
+    for (idx, name) in XMM.into_iter().enumerate() {
+        fields.push(Field {
+            name,
+            regset: NT_PRFPREG,
+            offset: XMM_OFFSET + idx * 16,
+            size: 16,
+            group: RegisterGroup::Vector,
+        });
+    }
+
+    fields.push(Field {
+        name: "mxcsr",
+        regset: NT_PRFPREG,
+        offset: MXCSR_OFFSET,
+        size: 4,
+        group: RegisterGroup::Vector,
+    });
+
+    fields
+}
+
+/// Debug registers are aligned to their length, which is either 1, 2, 4 or 8 bytes.
+pub fn can_watch(addr: usize, len: usize) -> bool {
+    matches!(len, 1 | 2 | 4 | 8) && addr & (len - 1) == 0
+}
+
+/// Bits of DR7 enabling a debug register.
+///
+/// x86 can't trap on reads alone, so read watchpoints also trigger on writes.
+fn dr7_bits(slot: usize, len: usize, kind: WatchKind) -> u64 {
+    let rw = match kind {
+        WatchKind::Write => 0b01,
+        WatchKind::Read | WatchKind::Access => 0b11,
+    };
+
+    let len = match len {
+        1 => 0b00,
+        2 => 0b01,
+        8 => 0b10,
+        _ => 0b11,
+    };
+
+    (1 << (slot * 2)) | (rw << (16 + slot * 4)) | (len << (18 + slot * 4))
+}
+
+fn dr7(watchpoints: &[Option<Watchpoint>]) -> u64 {
+    watchpoints
+        .iter()
+        .enumerate()
+        .filter_map(|(slot, wp)| wp.map(|wp| dr7_bits(slot, wp.len, wp.kind)))
+        .fold(0, |dr7, bits| dr7 | bits)
+}
+
+fn debug_register_offset(idx: usize) -> usize {
+    std::mem::offset_of!(libc::user, u_debugreg) + idx * 8
+}
+
+fn write_debug_register(pid: Pid, idx: usize, value: u64) -> Result<(), Error> {
+    super::request(
+        ptrace::Request::PTRACE_POKEUSER,
+        pid,
+        debug_register_offset(idx),
+        value as *mut libc::c_void,
+    )
+}
+
+fn read_debug_register(pid: Pid, idx: usize) -> Result<u64, Error> {
+    let offset = debug_register_offset(idx) as ptrace::AddressType;
+    Ok(ptrace::read_user(pid, offset)? as u64)
+}
+
+/// Number of watchpoints that can be set.
+pub fn debug_registers(_: Pid) -> Result<usize, Error> {
+    Ok(DEBUG_ADDRESSES)
+}
+
+pub fn write_debug_registers(pid: Pid, watchpoints: &[Option<Watchpoint>]) -> Result<(), Error> {
+    // Disable every watchpoint first, as the kernel validates each address against DR7.
+    write_debug_register(pid, DR7, 0)?;
+
+    for (idx, wp) in watchpoints.iter().enumerate() {
+        if let Some(wp) = wp {
+            write_debug_register(pid, idx, wp.addr as u64)?;
+        }
+    }
+
+    write_debug_register(pid, DR7, dr7(watchpoints))
+}
+
+/// Which debug register caused the last trap, according to DR6.
+pub fn triggered_watchpoint(
+    pid: Pid,
+    _: &libc::siginfo_t,
+    _: &[Option<Watchpoint>],
+) -> Result<Option<usize>, Error> {
+    let dr6 = read_debug_register(pid, DR6)?;
+
+    // DR6 is sticky, so it has to be cleared for the next trap.
+    write_debug_register(pid, DR6, 0)?;
+
+    Ok((0..DEBUG_ADDRESSES).find(|slot| dr6 & (1 << slot) != 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control_register() {
+        let watchpoints = [
+            Some(Watchpoint {
+                addr: 0x1000,
+                len: 8,
+                kind: WatchKind::Write,
+            }),
+            None,
+            Some(Watchpoint {
+                addr: 0x2002,
+                len: 2,
+                kind: WatchKind::Read,
+            }),
+            None,
+        ];
+
+        // L0, R/W0 = write, LEN0 = 8 bytes, L2, R/W2 = access, LEN2 = 2 bytes.
+        assert_eq!(dr7(&watchpoints), 0b0111_0000_1001_0000_0000_0001_0001);
+        assert!(can_watch(0x2002, 2));
+        assert!(!can_watch(0x2002, 4));
+        assert!(!can_watch(0x2000, 3));
+    }
+}
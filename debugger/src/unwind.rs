@@ -0,0 +1,480 @@
+//! Walking the call stack of a stopped target.
+//!
+//! Frames are unwound using the call frame information in `.eh_frame` or `.debug_frame`,
+//! falling back to following frame pointers for code without any.
+
+use crate::{Debugger, Error, Register};
+use gimli::{
+    BaseAddresses, CfaRule, DebugFrame, EhFrame, EndianSlice, RegisterRule, RunTimeEndian,
+    UnwindContext, UnwindSection,
+};
+use object::{Architecture, Endianness};
+
+/// Upper bound on the depth of a call stack, in case the stack is corrupt.
+const MAX_FRAMES: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Address of the instruction being executed in the frame.
+    pub pc: usize,
+    /// Stack pointer of the frame.
+    pub sp: usize,
+}
+
+/// Call frame information of a module.
+pub struct Cfi {
+    endian: RunTimeEndian,
+    address_size: u8,
+    /// Contents and address of `.eh_frame`.
+    eh_frame: Option<(Vec<u8>, u64)>,
+    debug_frame: Option<Vec<u8>>,
+    /// Address of `.text`, pointers in `.eh_frame` may be relative to it.
+    text: u64,
+}
+
+/// How to recover a register's value in the caller.
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    Undefined,
+    Offset(i64),
+    ValOffset(i64),
+    Register(u16),
+}
+
+/// An unwinding table row, with the rules this module knows how to evaluate.
+struct Row {
+    /// Register and offset that make up the canonical frame address.
+    cfa: (u16, i64),
+    rules: Vec<(u16, Rule)>,
+    return_address: u16,
+}
+
+impl Cfi {
+    pub fn new(
+        arch: Architecture,
+        endian: Endianness,
+        eh_frame: Option<(&[u8], usize)>,
+        debug_frame: Option<&[u8]>,
+        text: usize,
+    ) -> Self {
+        Self {
+            endian: match endian {
+                Endianness::Little => RunTimeEndian::Little,
+                Endianness::Big => RunTimeEndian::Big,
+            },
+            address_size: pointer_width(arch) as u8,
+            eh_frame: eh_frame.map(|(bytes, addr)| (bytes.to_vec(), addr as u64)),
+            debug_frame: debug_frame.map(<[u8]>::to_vec),
+            text: text as u64,
+        }
+    }
+
+    fn row(&self, pc: u64) -> Option<Row> {
+        if let Some((bytes, addr)) = &self.eh_frame {
+            let mut section = EhFrame::new(bytes, self.endian);
+            section.set_address_size(self.address_size);
+            let bases = BaseAddresses::default().set_eh_frame(*addr).set_text(self.text);
+            if let Some(row) = row(&section, &bases, pc) {
+                return Some(row);
+            }
+        }
+
+        if let Some(bytes) = &self.debug_frame {
+            let mut section = DebugFrame::new(bytes, self.endian);
+            section.set_address_size(self.address_size);
+            return row(&section, &BaseAddresses::default(), pc);
+        }
+
+        None
+    }
+}
+
+fn row<'a, S>(section: &S, bases: &BaseAddresses, pc: u64) -> Option<Row>
+where
+    S: UnwindSection<EndianSlice<'a, RunTimeEndian>>,
+{
+    let mut ctx = UnwindContext::new();
+    let fde = section.fde_for_address(bases, pc, S::cie_from_offset).ok()?;
+    let row = fde.unwind_info_for_address(section, bases, &mut ctx, pc).ok()?;
+
+    let cfa = match *row.cfa() {
+        CfaRule::RegisterAndOffset { register, offset } => (register.0, offset),
+        // DWARF expressions aren't evaluated.
+        CfaRule::Expression(_) => return None,
+    };
+
+    let rules = row
+        .registers()
+        .map(|(reg, rule)| {
+            let rule = match *rule {
+                RegisterRule::Offset(offset) => Rule::Offset(offset),
+                RegisterRule::ValOffset(offset) => Rule::ValOffset(offset),
+                RegisterRule::Register(reg) => Rule::Register(reg.0),
+                _ => Rule::Undefined,
+            };
+            (reg.0, rule)
+        })
+        .collect();
+
+    Some(Row {
+        cfa,
+        rules,
+        return_address: fde.cie().return_address_register().0,
+    })
+}
+
+/// Registers indexed by their DWARF register number.
+fn dwarf_registers(arch: Architecture) -> Option<&'static [&'static str]> {
+    Some(match arch {
+        Architecture::X86_64 => &[
+            "rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11",
+            "r12", "r13", "r14", "r15", "rip",
+        ],
+        Architecture::I386 | Architecture::X86_64_X32 => &[
+            "eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "eip",
+        ],
+        Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => &[
+            "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+            "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25",
+            "x26", "x27", "x28", "x29", "x30", "sp",
+        ],
+        Architecture::Arm => &[
+            "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10", "r11", "r12", "sp",
+            "lr", "pc",
+        ],
+        Architecture::Riscv32 | Architecture::Riscv64 => &[
+            "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3",
+            "a4", "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11",
+            "t3", "t4", "t5", "t6",
+        ],
+        Architecture::Mips | Architecture::Mips64 => &[
+            "zero", "at", "v0", "v1", "a0", "a1", "a2", "a3", "t0", "t1", "t2", "t3", "t4", "t5",
+            "t6", "t7", "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7", "t8", "t9", "k0", "k1",
+            "gp", "sp", "s8", "ra",
+        ],
+        _ => return None,
+    })
+}
+
+/// DWARF register numbers of the stack and frame pointer.
+fn stack_registers(arch: Architecture) -> Option<(u16, u16)> {
+    Some(match arch {
+        Architecture::X86_64 => (7, 6),
+        Architecture::I386 | Architecture::X86_64_X32 => (4, 5),
+        Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => (31, 29),
+        Architecture::Arm => (13, 11),
+        Architecture::Riscv32 | Architecture::Riscv64 => (2, 8),
+        Architecture::Mips | Architecture::Mips64 => (29, 30),
+        _ => return None,
+    })
+}
+
+fn pointer_width(arch: Architecture) -> usize {
+    match arch {
+        Architecture::X86_64
+        | Architecture::Aarch64
+        | Architecture::Riscv64
+        | Architecture::Mips64 => 8,
+        _ => 4,
+    }
+}
+
+struct Unwinder<'a> {
+    dbg: &'a mut dyn Debugger,
+    arch: Architecture,
+    ptr: usize,
+    /// Register values of the frame being unwound, indexed by DWARF register number.
+    regs: Vec<Option<u64>>,
+}
+
+impl Unwinder<'_> {
+    fn read_ptr(&mut self, addr: u64) -> Option<u64> {
+        self.dbg.read_word(addr as usize, self.ptr).ok()
+    }
+
+    fn reg(&self, reg: u16) -> Option<u64> {
+        self.regs.get(reg as usize).copied().flatten()
+    }
+
+    /// Recover the caller's registers, returning the return address.
+    fn unwind_cfi(&mut self, row: &Row) -> Option<u64> {
+        let (cfa_reg, cfa_offset) = row.cfa;
+        let cfa = self.reg(cfa_reg)?.wrapping_add(cfa_offset as u64);
+
+        // Registers without a rule are preserved by convention.
+        let mut caller = self.regs.clone();
+        for &(reg, rule) in &row.rules {
+            let value = match rule {
+                Rule::Undefined => None,
+                Rule::Offset(offset) => self.read_ptr(cfa.wrapping_add(offset as u64)),
+                Rule::ValOffset(offset) => Some(cfa.wrapping_add(offset as u64)),
+                Rule::Register(reg) => self.reg(reg),
+            };
+
+            if let Some(slot) = caller.get_mut(reg as usize) {
+                *slot = value;
+            }
+        }
+
+        let ret = caller.get(row.return_address as usize).copied().flatten()?;
+        let (sp, _) = stack_registers(self.arch)?;
+        caller[sp as usize] = Some(cfa);
+        self.regs = caller;
+        Some(ret)
+    }
+
+    /// Recover the caller's stack and frame pointer, returning the return address.
+    fn unwind_frame_pointer(&mut self) -> Option<u64> {
+        let (sp, fp) = stack_registers(self.arch)?;
+        let frame = self.reg(fp).filter(|&fp| fp != 0)?;
+        let ptr = self.ptr as u64;
+
+        let (prev_frame, ret, prev_sp) = match self.arch {
+            // The frame pointer points at the previous frame pointer, followed by the
+            // return address.
+            Architecture::X86_64
+            | Architecture::X86_64_X32
+            | Architecture::I386
+            | Architecture::Aarch64
+            | Architecture::Aarch64_Ilp32
+            | Architecture::Arm => (
+                self.read_ptr(frame)?,
+                self.read_ptr(frame + ptr)?,
+                frame + 2 * ptr,
+            ),
+            // The frame pointer points past the return address and previous frame pointer.
+            Architecture::Riscv32 | Architecture::Riscv64 => (
+                self.read_ptr(frame - 2 * ptr)?,
+                self.read_ptr(frame - ptr)?,
+                frame,
+            ),
+            _ => return None,
+        };
+
+        self.regs[fp as usize] = Some(prev_frame);
+        self.regs[sp as usize] = Some(prev_sp);
+        Some(ret)
+    }
+}
+
+/// Walk the call stack of the selected thread, starting with the innermost frame.
+pub fn unwind(dbg: &mut dyn Debugger, cfi: Option<&Cfi>) -> Result<Vec<Frame>, Error> {
+    let arch = dbg.arch();
+    let names = dwarf_registers(arch).ok_or(Error::UnknownArchitecture)?;
+    let (sp, _) = stack_registers(arch).ok_or(Error::UnknownArchitecture)?;
+    let registers: Vec<Register> = dbg.registers()?;
+    let pc_name = crate::pc_name(arch).ok_or(Error::UnknownArchitecture)?;
+
+    let lookup = |name: &str| registers.iter().find(|reg| reg.name == name).map(Register::as_u64);
+    let mut pc = lookup(pc_name).ok_or_else(|| Error::UnknownRegister(pc_name.to_string()))?;
+    let regs = names.iter().map(|&name| lookup(name)).collect();
+
+    let mut unwinder = Unwinder {
+        dbg,
+        arch,
+        ptr: pointer_width(arch),
+        regs,
+    };
+
+    let mut frames = Vec::new();
+    while frames.len() < MAX_FRAMES {
+        let stack = unwinder.reg(sp).unwrap_or(0);
+        frames.push(Frame {
+            pc: pc as usize,
+            sp: stack as usize,
+        });
+
+        // Return addresses point past the call, which might be the start of another function.
+        let lookup = if frames.len() == 1 {
+            pc
+        } else {
+            pc.saturating_sub(1)
+        };
+        let ret = match cfi.and_then(|cfi| cfi.row(lookup)) {
+            Some(row) => unwinder.unwind_cfi(&row),
+            None => unwinder.unwind_frame_pointer(),
+        };
+
+        match ret {
+            Some(ret) if ret != 0 => pc = ret,
+            _ => break,
+        }
+
+        // The stack grows down, so a caller's frame can't be below it's callee's.
+        if unwinder.reg(sp).is_none_or(|caller| caller <= stack) {
+            break;
+        }
+    }
+
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RegisterGroup, StopReason, WatchKind};
+
+    /// A stopped x86-64 target with some memory and registers.
+    struct Target {
+        regs: Vec<(&'static str, u64)>,
+        stack: Vec<u8>,
+        stack_base: usize,
+    }
+
+    impl Debugger for Target {
+        fn arch(&self) -> Architecture {
+            Architecture::X86_64
+        }
+
+        fn registers(&mut self) -> Result<Vec<Register>, Error> {
+            Ok(self
+                .regs
+                .iter()
+                .map(|&(name, value)| Register {
+                    name: name.to_string(),
+                    group: RegisterGroup::General,
+                    value: value.to_le_bytes().to_vec(),
+                })
+                .collect())
+        }
+
+        fn write_register(&mut self, _: &str, _: u64) -> Result<(), Error> {
+            Err(Error::Unsupported("writes"))
+        }
+
+        fn read_memory(&mut self, addr: usize, len: usize) -> Result<Vec<u8>, Error> {
+            let start = addr.checked_sub(self.stack_base).ok_or(Error::Remote(14))?;
+            let bytes = self.stack.get(start..start + len).ok_or(Error::Remote(14))?;
+            Ok(bytes.to_vec())
+        }
+
+        fn write_memory(&mut self, _: usize, _: &[u8]) -> Result<(), Error> {
+            Err(Error::Unsupported("writes"))
+        }
+
+        fn set_breakpoint(&mut self, _: usize) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn remove_breakpoint(&mut self, _: usize) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_watchpoint(&mut self, _: usize, _: usize, _: WatchKind) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn remove_watchpoint(&mut self, _: usize, _: usize, _: WatchKind) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn step(&mut self) -> Result<StopReason, Error> {
+            Ok(StopReason::Signal(5))
+        }
+
+        fn cont(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn poll(&mut self) -> Result<Option<StopReason>, Error> {
+            Ok(None)
+        }
+
+        fn interrupt(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn detach(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    fn push(stack: &mut Vec<u8>, values: &[u64]) {
+        for value in values {
+            stack.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    #[test]
+    fn frame_pointers() {
+        // Two frames, each with a saved frame pointer followed by the return address.
+        let base = 0x7000;
+        let mut stack = Vec::new();
+        push(
+            &mut stack,
+            &[0, 0, 0x7020, 0x401234, 0x7030, 0x401567, 0, 0],
+        );
+
+        let mut target = Target {
+            regs: vec![("rip", 0x401000), ("rsp", 0x7008), ("rbp", 0x7010)],
+            stack,
+            stack_base: base,
+        };
+
+        let frames = unwind(&mut target, None).unwrap();
+        let pcs: Vec<usize> = frames.iter().map(|frame| frame.pc).collect();
+        assert_eq!(pcs, [0x401000, 0x401234, 0x401567]);
+        assert_eq!(frames[1].sp, 0x7020);
+    }
+
+    #[test]
+    fn call_frame_information() {
+        use gimli::write::{
+            Address, CallFrameInstruction, CommonInformationEntry, EndianVec,
+            FrameDescriptionEntry, FrameTable,
+        };
+        use gimli::{Encoding, Format, X86_64};
+
+        // A function at 0x401000 that pushes a register after 4 bytes.
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 1,
+            address_size: 8,
+        };
+        let mut cie = CommonInformationEntry::new(encoding, 1, -8, X86_64::RA);
+        cie.add_instruction(CallFrameInstruction::Cfa(X86_64::RSP, 8));
+        cie.add_instruction(CallFrameInstruction::Offset(X86_64::RA, -8));
+
+        let mut fde = FrameDescriptionEntry::new(Address::Constant(0x401000), 0x100);
+        fde.add_instruction(4, CallFrameInstruction::CfaOffset(16));
+
+        let mut table = FrameTable::default();
+        let cie = table.add_cie(cie);
+        table.add_fde(cie, fde);
+
+        let mut eh_frame = gimli::write::EhFrame(EndianVec::new(gimli::LittleEndian));
+        table.write_eh_frame(&mut eh_frame).unwrap();
+        let eh_frame = eh_frame.0.into_vec();
+        let cfi = Cfi::new(
+            Architecture::X86_64,
+            Endianness::Little,
+            Some((&eh_frame, 0x500000)),
+            None,
+            0,
+        );
+
+        let mut stack = Vec::new();
+        push(&mut stack, &[0, 0x402000]);
+
+        let mut target = Target {
+            regs: vec![("rip", 0x401010), ("rsp", 0x7000), ("rbp", 0)],
+            stack,
+            stack_base: 0x7000,
+        };
+
+        let frames = unwind(&mut target, Some(&cfi)).unwrap();
+        assert_eq!(
+            frames,
+            [
+                Frame {
+                    pc: 0x401010,
+                    sp: 0x7000
+                },
+                Frame {
+                    pc: 0x402000,
+                    sp: 0x7010
+                },
+            ]
+        );
+    }
+}
@@ -30,6 +30,11 @@ pub fn parse(s: &str) -> TokenStream {
         return s;
     }
 
+    // parse dalvik method references
+    if let Some(s) = crate::java::parse(s) {
+        return s;
+    }
+
     // return the original mangled symbol on failure
     TokenStream::simple(s)
 }
@@ -1,5 +1,5 @@
 use crate::intern::InternMap;
-use crate::{AddressMap, Addressed, FileAttr};
+use crate::{AddressMap, Addressed, FileAttr, Location, Variable};
 use object::{Object, ObjectSection, ObjectSymbol};
 use rustc_hash::FxHasher;
 use std::borrow::Cow;
@@ -39,6 +39,8 @@ impl From<object::Error> for Error {
 pub struct Dwarf {
     /// Mapping from addresses starting at the header base to source files.
     pub file_attrs: AddressMap<FileAttr>,
+    /// Mapping from the start of functions to their parameters and local variables.
+    pub variables: AddressMap<Vec<Variable>>,
 }
 
 impl Dwarf {
@@ -59,8 +61,9 @@ impl Dwarf {
         let mut dwarf = gimli::Dwarf::load(&mut load_section)?;
         dwarf.populate_abbreviations_cache(gimli::AbbreviationsCacheStrategy::All);
         let file_attrs = dump_line(&dwarf)?;
+        let variables = dump_variables(&dwarf)?;
 
-        Ok(Dwarf { file_attrs })
+        Ok(Dwarf { file_attrs, variables })
     }
 
     pub fn load(path: &Path) -> Result<Self> {
@@ -72,6 +75,7 @@ impl Dwarf {
 
     pub fn merge(&mut self, other: Self) {
         self.file_attrs.extend(other.file_attrs);
+        self.variables.extend(other.variables);
     }
 }
 
@@ -426,3 +430,193 @@ fn dump_line_program<R: Reader>(
 
     Ok(())
 }
+
+/// Where the frame base of a function is, which `DW_OP_fbreg` locations are relative to.
+#[derive(Clone, Copy)]
+enum FrameBase {
+    Register(u16),
+    Cfa,
+    Unknown,
+}
+
+/// Function whose variables are being collected.
+struct Function {
+    depth: isize,
+    addr: usize,
+    frame_base: FrameBase,
+    variables: Vec<Variable>,
+}
+
+fn dump_variables<R: Reader>(dwarf: &gimli::Dwarf<R>) -> Result<AddressMap<Vec<Variable>>> {
+    let mut iter = dwarf.units();
+    let mut variables = AddressMap::default();
+
+    while let Some(header) = iter.next()? {
+        let unit = match dwarf.unit(header) {
+            Ok(unit) => unit,
+            Err(err) => {
+                log::complex!(
+                    w "[dwarf::dump_variables] ",
+                    y "Failed to parse unit root entry for dump_variables: ",
+                    y format!("{err:?}."),
+                );
+                continue;
+            }
+        };
+        if let Err(err) = dump_unit_variables(&unit, dwarf, &mut variables) {
+            log::complex!(
+                w "[dwarf::dump_unit_variables] ",
+                y "Failed to dump variables: ",
+                y format!("{err:?}."),
+            );
+        }
+    }
+
+    Ok(variables)
+}
+
+fn dump_unit_variables<R: Reader>(
+    unit: &gimli::Unit<R>,
+    dwarf: &gimli::Dwarf<R>,
+    variables: &mut AddressMap<Vec<Variable>>,
+) -> Result<()> {
+    let mut entries = unit.entries();
+    let mut functions: Vec<Function> = Vec::new();
+    // Variables of inlined functions only live in part of the function, so they're skipped.
+    let mut inlined: Option<isize> = None;
+    let mut depth = 0;
+
+    while let Some((delta, entry)) = entries.next_dfs()? {
+        depth += delta;
+
+        while functions.last().is_some_and(|func| func.depth >= depth) {
+            let func = functions.pop().unwrap();
+            if !func.variables.is_empty() {
+                variables.push(Addressed { addr: func.addr, item: func.variables });
+            }
+        }
+
+        if inlined.is_some_and(|inlined| inlined >= depth) {
+            inlined = None;
+        }
+
+        if inlined.is_some() {
+            continue;
+        }
+
+        match entry.tag() {
+            gimli::DW_TAG_subprogram => {
+                let addr = match entry.attr_value(gimli::DW_AT_low_pc)? {
+                    Some(value) => dwarf.attr_address(unit, value)?,
+                    None => None,
+                };
+
+                let frame_base = match entry.attr_value(gimli::DW_AT_frame_base)? {
+                    Some(gimli::AttributeValue::Exprloc(expr)) => {
+                        match single_operation(unit, expr)? {
+                            Some(gimli::Operation::Register { register }) => {
+                                FrameBase::Register(register.0)
+                            }
+                            Some(gimli::Operation::CallFrameCFA) => FrameBase::Cfa,
+                            _ => FrameBase::Unknown,
+                        }
+                    }
+                    _ => FrameBase::Unknown,
+                };
+
+                if let Some(addr) = addr {
+                    functions.push(Function {
+                        depth,
+                        addr: addr as usize,
+                        frame_base,
+                        variables: Vec::new(),
+                    });
+                }
+            }
+            gimli::DW_TAG_inlined_subroutine => inlined = Some(depth),
+            gimli::DW_TAG_formal_parameter | gimli::DW_TAG_variable => {
+                let func = match functions.last_mut() {
+                    Some(func) => func,
+                    None => continue,
+                };
+
+                let expr = match entry.attr_value(gimli::DW_AT_location)? {
+                    Some(gimli::AttributeValue::Exprloc(expr)) => expr,
+                    _ => continue,
+                };
+
+                let location = match single_operation(unit, expr)? {
+                    Some(gimli::Operation::Register { register }) => Location::Register(register.0),
+                    Some(gimli::Operation::RegisterOffset { register, offset, .. }) => {
+                        Location::Relative(register.0, offset)
+                    }
+                    Some(gimli::Operation::FrameOffset { offset }) => match func.frame_base {
+                        FrameBase::Register(register) => Location::Relative(register, offset),
+                        FrameBase::Cfa => Location::Frame(offset),
+                        FrameBase::Unknown => continue,
+                    },
+                    _ => continue,
+                };
+
+                let name = match entry_name(unit, dwarf, entry)? {
+                    Some(name) => name,
+                    None => continue,
+                };
+
+                func.variables.push(Variable {
+                    name,
+                    location,
+                    param: entry.tag() == gimli::DW_TAG_formal_parameter,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    for func in functions {
+        if !func.variables.is_empty() {
+            variables.push(Addressed { addr: func.addr, item: func.variables });
+        }
+    }
+
+    Ok(())
+}
+
+/// The operation of an expression, if it consists of exactly one.
+fn single_operation<R: Reader>(
+    unit: &gimli::Unit<R>,
+    expr: gimli::Expression<R>,
+) -> Result<Option<gimli::Operation<R>>> {
+    let mut ops = expr.operations(unit.encoding());
+    let op = ops.next()?;
+    match ops.next()? {
+        Some(_) => Ok(None),
+        None => Ok(op),
+    }
+}
+
+/// Name of an entry, or the name of the entry it's a concrete instance of.
+fn entry_name<R: Reader>(
+    unit: &gimli::Unit<R>,
+    dwarf: &gimli::Dwarf<R>,
+    entry: &gimli::DebuggingInformationEntry<R>,
+) -> Result<Option<String>> {
+    if let Some(value) = entry.attr_value(gimli::DW_AT_name)? {
+        let name = dwarf.attr_string(unit, value)?;
+        return Ok(Some(name.to_string_lossy()?.into_owned()));
+    }
+
+    match entry.attr_value(gimli::DW_AT_abstract_origin)? {
+        Some(gimli::AttributeValue::UnitRef(offset)) => {
+            let origin = unit.entry(offset)?;
+            match origin.attr_value(gimli::DW_AT_name)? {
+                Some(value) => {
+                    let name = dwarf.attr_string(unit, value)?;
+                    Ok(Some(name.to_string_lossy()?.into_owned()))
+                }
+                None => Ok(None),
+            }
+        }
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,111 @@
+//! Dalvik method references, e.g. `Lcom/foo/Bar;->baz(I[Ljava/lang/String;)V`.
+
+use crate::{Colors, TokenStream};
+use tokenizing::ColorScheme;
+
+mod tests;
+
+pub fn parse(s: &str) -> Option<TokenStream> {
+    let (class, method) = s.split_once("->")?;
+    let (name, descriptor) = method.split_once('(')?;
+    let (params, ret) = descriptor.split_once(')')?;
+
+    if name.is_empty() || name.contains(['/', ';', '[']) {
+        return None;
+    }
+
+    let mut stream = TokenStream::new(s);
+
+    // the class has to be an object type, there are no methods on primitives
+    let mut unparsed = class;
+    if !unparsed.starts_with('L')
+        || !parse_type(&mut unparsed, &mut stream)?
+        || !unparsed.is_empty()
+    {
+        return None;
+    }
+
+    stream.push(".", Colors::delimiter());
+    stream.push_string(name.to_string(), Colors::item());
+    stream.push("(", Colors::brackets());
+
+    let mut unparsed = params;
+    let mut first = true;
+    while !unparsed.is_empty() {
+        if !first {
+            stream.push(", ", Colors::delimiter());
+        }
+
+        // void is only valid as a return type
+        if !parse_type(&mut unparsed, &mut stream)? {
+            return None;
+        }
+
+        first = false;
+    }
+
+    stream.push(")", Colors::brackets());
+
+    // the return type isn't shown but still has to be valid
+    let mut unparsed = ret;
+    parse_type(&mut unparsed, &mut TokenStream::new(""))?;
+    if !unparsed.is_empty() {
+        return None;
+    }
+
+    Some(stream)
+}
+
+/// Parses a single type descriptor, returning whether it isn't `void`.
+fn parse_type(s: &mut &str, stream: &mut TokenStream) -> Option<bool> {
+    let mut dimensions = 0;
+    while let Some(rest) = s.strip_prefix('[') {
+        dimensions += 1;
+        *s = rest;
+    }
+
+    let primitive = match s.as_bytes().first()? {
+        b'V' if dimensions == 0 => {
+            *s = &s[1..];
+            return Some(false);
+        }
+        b'Z' => "boolean",
+        b'B' => "byte",
+        b'S' => "short",
+        b'C' => "char",
+        b'I' => "int",
+        b'J' => "long",
+        b'F' => "float",
+        b'D' => "double",
+        b'L' => {
+            let end = s.find(';')?;
+            let path = &s[1..end];
+
+            if path.is_empty() || path.split('/').any(str::is_empty) {
+                return None;
+            }
+
+            for (idx, part) in path.split('/').enumerate() {
+                if idx != 0 {
+                    stream.push(".", Colors::delimiter());
+                }
+                stream.push_string(part.to_string(), Colors::item());
+            }
+
+            *s = &s[end + 1..];
+            ""
+        }
+        _ => return None,
+    };
+
+    if !primitive.is_empty() {
+        stream.push(primitive, Colors::known());
+        *s = &s[1..];
+    }
+
+    for _ in 0..dimensions {
+        stream.push("[]", Colors::brackets());
+    }
+
+    Some(true)
+}
@@ -0,0 +1,52 @@
+#![cfg(test)]
+
+use super::*;
+
+macro_rules! none {
+    ($mangled:literal) => {
+        if parse($mangled).is_some() {
+            panic!(
+                "Formatting '{}' succeeded when it wasn't supposed to.",
+                $mangled
+            );
+        }
+    };
+}
+
+macro_rules! eq {
+    ($mangled:literal => $demangled:literal) => {
+        let symbol = parse($mangled).expect(&format!("Formatting '{}' failed.", $mangled));
+
+        assert_eq!(
+            String::from_iter(symbol.tokens().iter().map(|t| &t.text[..])),
+            $demangled
+        );
+    };
+}
+
+#[test]
+fn basic() {
+    eq!("Lcom/foo/Bar;->baz(I)V" => "com.foo.Bar.baz(int)");
+    eq!("LMain;->main()V" => "Main.main()");
+    eq!("Lcom/foo/Bar$Inner;-><init>()V" => "com.foo.Bar$Inner.<init>()");
+    eq!("Landroid/app/Activity;->onCreate(Landroid/os/Bundle;)V" => "android.app.Activity.onCreate(android.os.Bundle)");
+}
+
+#[test]
+fn parameters() {
+    eq!("La/B;->f(ZBSCIJFD)Z" => "a.B.f(boolean, byte, short, char, int, long, float, double)");
+    eq!("La/B;->f([I[[Ljava/lang/String;)[J" => "a.B.f(int[], java.lang.String[][])");
+}
+
+#[test]
+fn invalid() {
+    none!("main");
+    none!("Lcom/foo/Bar;->baz");
+    none!("Lcom/foo/Bar;->baz(V)V");
+    none!("Lcom/foo/Bar;->baz(I)");
+    none!("Lcom/foo/Bar;->baz(Q)V");
+    none!("I->baz(I)V");
+    none!("Lcom//Bar;->baz()V");
+    none!("Lcom/foo/Bar;->baz(Lcom/foo)V");
+    none!("Lcom/foo/Bar;->baz()[V");
+}
@@ -15,6 +15,7 @@ mod dwarf;
 mod error;
 mod intern;
 mod itanium;
+mod java;
 mod msvc;
 mod pdb;
 mod rust;
@@ -35,6 +36,25 @@ pub struct FileAttr {
     pub column_end: usize,
 }
 
+/// Where a variable is stored, registers are numbered like they are in DWARF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    /// Held in a register.
+    Register(u16),
+    /// In memory at an offset from the address in a register.
+    Relative(u16, i64),
+    /// In memory at an offset from the canonical frame address, the stack pointer at the call.
+    Frame(i64),
+}
+
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub name: String,
+    pub location: Location,
+    /// Whether the variable is one of the function's parameters.
+    pub param: bool,
+}
+
 pub struct Symbol {
     name: TokenStream,
     name_as_str: ArcStr,
@@ -121,6 +141,10 @@ pub struct Index {
     /// The addresses are sorted.
     pub file_attrs: AddressMap<FileAttr>,
 
+    /// Mapping from the start of functions to their parameters and local variables.
+    /// The addresses are sorted.
+    pub variables: AddressMap<Vec<Variable>>,
+
     /// Prefix tree for finding symbols.
     trie: Trie<ArcStr, Arc<Symbol>>,
 
@@ -143,6 +167,7 @@ impl Index {
         };
 
         this.file_attrs.extend(dwarf.file_attrs);
+        this.variables.extend(dwarf.variables);
 
         let mut pdb = None;
         if let Some(parsed_pdb) = pdb::PDB::parse(obj) {
@@ -153,11 +178,36 @@ impl Index {
         //       otherwise the PDB will be dropped and so will the symbols.
         if let Some(ref mut pdb) = pdb {
             this.file_attrs.extend(std::mem::take(&mut pdb.file_attrs));
+            this.variables.extend(std::mem::take(&mut pdb.variables));
             syms.extend(std::mem::take(&mut pdb.syms));
         }
 
+        this.demangle(syms);
+        this.sort_and_validate();
+        this.build_prefix_tree();
+
+        log::complex!(
+            w "[index::parse] found ",
+            g this.syms.len().to_string(),
+            w " functions."
+        );
+
+        Ok(this)
+    }
+
+    /// Build an index from symbols alone, for formats without any debug info we can parse.
+    pub fn from_symbols(syms: AddressMap<RawSymbol>) -> Self {
+        let mut this = Self::default();
+
+        this.demangle(syms);
+        this.sort_and_validate();
+        this.build_prefix_tree();
+        this
+    }
+
+    fn demangle(&mut self, syms: AddressMap<RawSymbol>) {
         log::PROGRESS.set("Parsing symbols.", syms.len());
-        parallel_compute(syms.mapping, &mut this.syms, |Addressed { addr, item }| {
+        parallel_compute(syms.mapping, &mut self.syms, |Addressed { addr, item }| {
             let demangled = demangler::parse(item.name);
             let is_intrinsics = is_name_an_intrinsic(item.name);
             let name_as_str = String::from_iter(demangled.tokens().iter().map(|t| &t.text[..]));
@@ -175,17 +225,36 @@ impl Index {
                 item: Arc::new(symbol),
             }
         });
+    }
 
-        this.sort_and_validate();
-        this.build_prefix_tree();
+    /// Combine the indices of multiple modules loaded in the same address space.
+    /// Each index is relocated by the base address it was loaded at.
+    pub fn combine(modules: Vec<(usize, Self)>) -> Self {
+        let mut this = Self::default();
 
-        log::complex!(
-            w "[index::parse] found ",
-            g this.syms.len().to_string(),
-            w " functions."
-        );
+        for (base, module) in modules {
+            let syms = module.syms.mapping.into_iter();
+            this.syms.mapping.extend(syms.map(|sym| Addressed {
+                addr: sym.addr.wrapping_add(base),
+                item: sym.item,
+            }));
+
+            let file_attrs = module.file_attrs.mapping.into_iter();
+            this.file_attrs.mapping.extend(file_attrs.map(|attr| Addressed {
+                addr: attr.addr.wrapping_add(base),
+                item: attr.item,
+            }));
+
+            let variables = module.variables.mapping.into_iter();
+            this.variables.mapping.extend(variables.map(|vars| Addressed {
+                addr: vars.addr.wrapping_add(base),
+                item: vars.item,
+            }));
+        }
 
-        Ok(this)
+        this.sort_and_validate();
+        this.build_prefix_tree();
+        this
     }
 
     fn sort_and_validate(&mut self) {
@@ -213,6 +282,9 @@ impl Index {
 
         // Keep file attrs sorted so it can be binary searched.
         self.file_attrs.sort_unstable();
+
+        // Keep variables sorted so they can be binary searched.
+        self.variables.sort_unstable();
     }
 
     fn build_prefix_tree(&mut self) {
@@ -247,6 +319,37 @@ impl Index {
         }
     }
 
+    /// Symbol at or before `addr`, alongside `addr`'s offset into it.
+    pub fn get_sym_containing_addr(&self, addr: usize) -> Option<(Arc<Symbol>, usize)> {
+        let idx = match self.syms.search(addr) {
+            Ok(idx) => idx,
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+
+        let sym = &self.syms[idx];
+        Some((sym.item.clone(), addr - sym.addr))
+    }
+
+    /// Source location at or before `addr`.
+    pub fn get_file_containing_addr(&self, addr: usize) -> Option<&FileAttr> {
+        let idx = match self.file_attrs.search(addr) {
+            Ok(idx) => idx,
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+
+        Some(&self.file_attrs[idx].item)
+    }
+
+    /// Parameters and local variables of the function starting at `addr`.
+    pub fn get_vars_by_addr(&self, addr: usize) -> &[Variable] {
+        match self.variables.search(addr) {
+            Ok(idx) => &self.variables[idx].item,
+            Err(..) => &[],
+        }
+    }
+
     pub fn get_func_by_name(&self, name: &str) -> Option<usize> {
         self.syms.iter().find(|func| func.item.as_str() == name).map(|func| func.addr)
     }
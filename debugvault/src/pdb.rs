@@ -1,5 +1,5 @@
 use crate::intern::InternMap;
-use crate::{AddressMap, Addressed, FileAttr, RawSymbol};
+use crate::{AddressMap, Addressed, FileAttr, Location, RawSymbol, Variable};
 use crossbeam_queue::SegQueue;
 use object::Object;
 use pdb::{FallibleIterator, SymbolData};
@@ -11,6 +11,8 @@ use std::sync::Arc;
 pub struct PDB<'data> {
     /// Mapping from addresses starting at the header base to source files.
     pub file_attrs: AddressMap<FileAttr>,
+    /// Mapping from the start of functions to their parameters and local variables.
+    pub variables: AddressMap<Vec<Variable>>,
     /// Container for holding syms.
     global_syms: pdb::SymbolTable<'data>,
     /// Container for holding libraries.
@@ -40,6 +42,7 @@ fn parse_pdb<'data>(
 
     let mut this = Box::pin(PDB {
         file_attrs: AddressMap::default(),
+        variables: AddressMap::default(),
         global_syms: pdb.global_symbols()?,
         dbi: pdb.debug_information()?,
         syms: AddressMap::default(),
@@ -97,6 +100,7 @@ fn parse_pdb<'data>(
                 s.spawn(|| -> Result<_, pdb::Error> {
                     let mut syms = AddressMap::default();
                     let mut file_attrs = AddressMap::default();
+                    let mut variables = AddressMap::default();
 
                     while let Some((id, module_name, module_info)) = module_info_queue.pop() {
                         parse_pdb_module(
@@ -108,19 +112,21 @@ fn parse_pdb<'data>(
                             &address_map,
                             &string_table,
                             &mut file_attrs,
+                            &mut variables,
                             &mut syms,
                         )?;
                         log::PROGRESS.step();
                     }
 
-                    Ok((file_attrs, syms))
+                    Ok((file_attrs, variables, syms))
                 })
             })
             .collect();
 
         for thread in threads {
-            let (local_file_attrs, local_syms) = thread.join().unwrap()?;
+            let (local_file_attrs, local_variables, local_syms) = thread.join().unwrap()?;
             file_attrs.extend(local_file_attrs);
+            this.variables.extend(local_variables);
             this.syms.extend(local_syms);
         }
 
@@ -183,12 +189,30 @@ fn parse_pdb_module<'data>(
     address_map: &pdb::AddressMap,
     string_table: &pdb::StringTable<'data>,
     file_attrs: &mut AddressMap<FileAttr>,
+    variables: &mut AddressMap<Vec<Variable>>,
     syms: &mut AddressMap<RawSymbol<'data>>,
 ) -> Result<(), pdb::Error> {
     let program = module_info.line_program()?;
     let mut symbols = module_info.symbols()?;
 
+    // Procedure whose variables are being collected, alongside how deeply nested the scope is.
+    let mut proc_vars: Option<Addressed<Vec<Variable>>> = None;
+    let mut depth = 0usize;
+
     while let Some(symbol) = symbols.next()? {
+        if symbol.starts_scope() {
+            depth += 1;
+        }
+
+        if symbol.ends_scope() {
+            depth = depth.saturating_sub(1);
+            if depth == 0 {
+                if let Some(vars) = proc_vars.take().filter(|vars| !vars.item.is_empty()) {
+                    variables.push(vars);
+                }
+            }
+        }
+
         match symbol.parse() {
             Ok(SymbolData::Public(symbol)) if symbol.function => {
                 let addr = match symbol.offset.to_rva(address_map) {
@@ -207,6 +231,13 @@ fn parse_pdb_module<'data>(
                 });
             }
             Ok(SymbolData::Procedure(proc)) => {
+                if let Some(rva) = proc.offset.to_rva(address_map) {
+                    proc_vars = Some(Addressed {
+                        addr: base_addr + rva.0 as usize,
+                        item: Vec::new(),
+                    });
+                }
+
                 let mut lines = program.lines_for_symbol(proc.offset);
                 while let Some(line_info) = lines.next()? {
                     let addr = match line_info.offset.to_rva(address_map) {
@@ -245,6 +276,26 @@ fn parse_pdb_module<'data>(
                     });
                 }
             }
+            Ok(SymbolData::RegisterRelative(var)) => {
+                if let (Some(vars), Some(register)) = (&mut proc_vars, dwarf_register(var.register))
+                {
+                    vars.item.push(Variable {
+                        name: var.name.to_string().into_owned(),
+                        location: Location::Relative(register, var.offset as i64),
+                        param: false,
+                    });
+                }
+            }
+            Ok(SymbolData::RegisterVariable(var)) => {
+                if let (Some(vars), Some(register)) = (&mut proc_vars, dwarf_register(var.register))
+                {
+                    vars.item.push(Variable {
+                        name: var.name.to_string().into_owned(),
+                        location: Location::Register(register),
+                        param: false,
+                    });
+                }
+            }
             Ok(_) => {
                 // TODO: implement support for other types of symbols
             }
@@ -258,3 +309,32 @@ fn parse_pdb_module<'data>(
 
     Ok(())
 }
+
+/// DWARF number of an x86-64 CodeView register.
+fn dwarf_register(register: pdb::Register) -> Option<u16> {
+    Some(match register.0 {
+        // 32-bit registers shared with x86
+        17 => 0,
+        18 => 2,
+        19 => 1,
+        20 => 3,
+        21 => 7,
+        22 => 6,
+        23 => 4,
+        24 => 5,
+        // rax, rbx, rcx, rdx, rsi, rdi, rbp and rsp
+        328 => 0,
+        329 => 3,
+        330 => 2,
+        331 => 1,
+        332 => 4,
+        333 => 5,
+        334 => 6,
+        335 => 7,
+        // r8 to r15
+        336..=343 => register.0 - 328,
+        // r8d to r15d
+        360..=367 => register.0 - 352,
+        _ => return None,
+    })
+}
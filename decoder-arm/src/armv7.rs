@@ -3,10 +3,12 @@
 
 use core::fmt::{self, Display, Formatter};
 
-use decoder::{Decoded, Decodable, Error, ErrorKind, Reader, ToTokens};
+use decoder::{Decoded, Decodable, Error, ErrorKind, FlowKind, MemoryAccess, Reader, ToTokens};
 use debugvault::Index;
 use tokenizing::{colors, ColorScheme, Colors, TokenStream};
 
+mod behavior;
+mod simd;
 mod thumb;
 
 // opcode, s, w, cond
@@ -25,7 +27,11 @@ impl Display for ConditionedOpcode {
             if self.1 { "s" } else { "" },
             if self.2 { ".w" } else { "" },
             self.3
-        )
+        )?;
+        for dt in self.0.data_types().iter().flatten() {
+            write!(f, ".{}", dt)?;
+        }
+        Ok(())
     }
 }
 
@@ -259,10 +265,146 @@ impl ToTokens for ConditionedOpcode {
             | Opcode::MRC2(_, _, _)
             | Opcode::MCRR(_, _)
             | Opcode::MRRC(_, _)
-            | Opcode::CDP2(_, _, _) => {
+            | Opcode::CDP2(_, _, _)
+            | Opcode::MCR(_, _, _)
+            | Opcode::MRC(_, _, _)
+            | Opcode::CDP(_, _, _) => {
                 // platform_op
                 stream.push_owned(self.to_string(), Colors::opcode())
             }
+            Opcode::VMOV(_)
+            | Opcode::VORR(_)
+            | Opcode::VBIC(_)
+            | Opcode::VMVN(_)
+            | Opcode::VAND
+            | Opcode::VEOR
+            | Opcode::VORN
+            | Opcode::VBSL
+            | Opcode::VBIT
+            | Opcode::VBIF
+            | Opcode::VSWP
+            | Opcode::VMRS
+            | Opcode::VMSR
+            | Opcode::VLDR
+            | Opcode::VSTR
+            | Opcode::VLDM(_)
+            | Opcode::VSTM(_)
+            | Opcode::VPUSH
+            | Opcode::VPOP
+            | Opcode::VADD(_)
+            | Opcode::VSUB(_)
+            | Opcode::VMUL(_)
+            | Opcode::VMLA(_)
+            | Opcode::VMLS(_)
+            | Opcode::VNMUL(_)
+            | Opcode::VNMLA(_)
+            | Opcode::VNMLS(_)
+            | Opcode::VDIV(_)
+            | Opcode::VFMA(_)
+            | Opcode::VFMS(_)
+            | Opcode::VFNMA(_)
+            | Opcode::VFNMS(_)
+            | Opcode::VABS(_)
+            | Opcode::VNEG(_)
+            | Opcode::VSQRT(_)
+            | Opcode::VCMP(_)
+            | Opcode::VCMPE(_)
+            | Opcode::VCVT(_, _)
+            | Opcode::VCVTR(_, _)
+            | Opcode::VCVTB(_, _)
+            | Opcode::VCVTT(_, _)
+            | Opcode::VHADD(_)
+            | Opcode::VQADD(_)
+            | Opcode::VRHADD(_)
+            | Opcode::VHSUB(_)
+            | Opcode::VQSUB(_)
+            | Opcode::VCGT(_)
+            | Opcode::VCGE(_)
+            | Opcode::VCEQ(_)
+            | Opcode::VCLE(_)
+            | Opcode::VCLT(_)
+            | Opcode::VTST(_)
+            | Opcode::VACGE(_)
+            | Opcode::VACGT(_)
+            | Opcode::VSHL(_)
+            | Opcode::VQSHL(_)
+            | Opcode::VQSHLU(_)
+            | Opcode::VRSHL(_)
+            | Opcode::VQRSHL(_)
+            | Opcode::VMAX(_)
+            | Opcode::VMIN(_)
+            | Opcode::VPMAX(_)
+            | Opcode::VPMIN(_)
+            | Opcode::VABD(_)
+            | Opcode::VABA(_)
+            | Opcode::VPADD(_)
+            | Opcode::VQDMULH(_)
+            | Opcode::VQRDMULH(_)
+            | Opcode::VRECPS(_)
+            | Opcode::VRSQRTS(_)
+            | Opcode::VRECPE(_)
+            | Opcode::VRSQRTE(_)
+            | Opcode::VADDL(_)
+            | Opcode::VADDW(_)
+            | Opcode::VSUBL(_)
+            | Opcode::VSUBW(_)
+            | Opcode::VADDHN(_)
+            | Opcode::VRADDHN(_)
+            | Opcode::VSUBHN(_)
+            | Opcode::VRSUBHN(_)
+            | Opcode::VABAL(_)
+            | Opcode::VABDL(_)
+            | Opcode::VMLAL(_)
+            | Opcode::VMLSL(_)
+            | Opcode::VMULL(_)
+            | Opcode::VQDMLAL(_)
+            | Opcode::VQDMLSL(_)
+            | Opcode::VQDMULL(_)
+            | Opcode::VSHR(_)
+            | Opcode::VSRA(_)
+            | Opcode::VRSHR(_)
+            | Opcode::VRSRA(_)
+            | Opcode::VSRI(_)
+            | Opcode::VSLI(_)
+            | Opcode::VSHRN(_)
+            | Opcode::VRSHRN(_)
+            | Opcode::VQSHRN(_)
+            | Opcode::VQRSHRN(_)
+            | Opcode::VQSHRUN(_)
+            | Opcode::VQRSHRUN(_)
+            | Opcode::VSHLL(_)
+            | Opcode::VMOVL(_)
+            | Opcode::VMOVN(_)
+            | Opcode::VQMOVN(_)
+            | Opcode::VQMOVUN(_)
+            | Opcode::VREV64(_)
+            | Opcode::VREV32(_)
+            | Opcode::VREV16(_)
+            | Opcode::VPADDL(_)
+            | Opcode::VPADAL(_)
+            | Opcode::VCLS(_)
+            | Opcode::VCLZ(_)
+            | Opcode::VCNT(_)
+            | Opcode::VQABS(_)
+            | Opcode::VQNEG(_)
+            | Opcode::VTRN(_)
+            | Opcode::VUZP(_)
+            | Opcode::VZIP(_)
+            | Opcode::VTBL(_)
+            | Opcode::VTBX(_)
+            | Opcode::VDUP(_)
+            | Opcode::VEXT(_)
+            | Opcode::VLD1(_)
+            | Opcode::VLD2(_)
+            | Opcode::VLD3(_)
+            | Opcode::VLD4(_)
+            | Opcode::VST1(_)
+            | Opcode::VST2(_)
+            | Opcode::VST3(_)
+            | Opcode::VST4(_) => {
+                // simd_op
+                stream.push_owned(self.to_string(), Colors::opcode())
+            }
         }
     }
 }
@@ -333,6 +475,149 @@ impl Display for Opcode {
             Opcode::CDP2(_, _, _) => {
                 write!(f, "cdp2")
             }
+            Opcode::MCR(_, _, _) => {
+                write!(f, "mcr")
+            }
+            Opcode::MRC(_, _, _) => {
+                write!(f, "mrc")
+            }
+            Opcode::CDP(_, _, _) => {
+                write!(f, "cdp")
+            }
+            Opcode::VLDM(add) => {
+                write!(f, "vldm{}", if *add { "ia" } else { "db" })
+            }
+            Opcode::VSTM(add) => {
+                write!(f, "vstm{}", if *add { "ia" } else { "db" })
+            }
+            Opcode::VMOV(_) => write!(f, "vmov"),
+            Opcode::VORR(_) => write!(f, "vorr"),
+            Opcode::VBIC(_) => write!(f, "vbic"),
+            Opcode::VMVN(_) => write!(f, "vmvn"),
+            Opcode::VAND => write!(f, "vand"),
+            Opcode::VEOR => write!(f, "veor"),
+            Opcode::VORN => write!(f, "vorn"),
+            Opcode::VBSL => write!(f, "vbsl"),
+            Opcode::VBIT => write!(f, "vbit"),
+            Opcode::VBIF => write!(f, "vbif"),
+            Opcode::VSWP => write!(f, "vswp"),
+            Opcode::VMRS => write!(f, "vmrs"),
+            Opcode::VMSR => write!(f, "vmsr"),
+            Opcode::VLDR => write!(f, "vldr"),
+            Opcode::VSTR => write!(f, "vstr"),
+            Opcode::VPUSH => write!(f, "vpush"),
+            Opcode::VPOP => write!(f, "vpop"),
+            Opcode::VADD(_) => write!(f, "vadd"),
+            Opcode::VSUB(_) => write!(f, "vsub"),
+            Opcode::VMUL(_) => write!(f, "vmul"),
+            Opcode::VMLA(_) => write!(f, "vmla"),
+            Opcode::VMLS(_) => write!(f, "vmls"),
+            Opcode::VNMUL(_) => write!(f, "vnmul"),
+            Opcode::VNMLA(_) => write!(f, "vnmla"),
+            Opcode::VNMLS(_) => write!(f, "vnmls"),
+            Opcode::VDIV(_) => write!(f, "vdiv"),
+            Opcode::VFMA(_) => write!(f, "vfma"),
+            Opcode::VFMS(_) => write!(f, "vfms"),
+            Opcode::VFNMA(_) => write!(f, "vfnma"),
+            Opcode::VFNMS(_) => write!(f, "vfnms"),
+            Opcode::VABS(_) => write!(f, "vabs"),
+            Opcode::VNEG(_) => write!(f, "vneg"),
+            Opcode::VSQRT(_) => write!(f, "vsqrt"),
+            Opcode::VCMP(_) => write!(f, "vcmp"),
+            Opcode::VCMPE(_) => write!(f, "vcmpe"),
+            Opcode::VCVT(_, _) => write!(f, "vcvt"),
+            Opcode::VCVTR(_, _) => write!(f, "vcvtr"),
+            Opcode::VCVTB(_, _) => write!(f, "vcvtb"),
+            Opcode::VCVTT(_, _) => write!(f, "vcvtt"),
+            Opcode::VHADD(_) => write!(f, "vhadd"),
+            Opcode::VQADD(_) => write!(f, "vqadd"),
+            Opcode::VRHADD(_) => write!(f, "vrhadd"),
+            Opcode::VHSUB(_) => write!(f, "vhsub"),
+            Opcode::VQSUB(_) => write!(f, "vqsub"),
+            Opcode::VCGT(_) => write!(f, "vcgt"),
+            Opcode::VCGE(_) => write!(f, "vcge"),
+            Opcode::VCEQ(_) => write!(f, "vceq"),
+            Opcode::VCLE(_) => write!(f, "vcle"),
+            Opcode::VCLT(_) => write!(f, "vclt"),
+            Opcode::VTST(_) => write!(f, "vtst"),
+            Opcode::VACGE(_) => write!(f, "vacge"),
+            Opcode::VACGT(_) => write!(f, "vacgt"),
+            Opcode::VSHL(_) => write!(f, "vshl"),
+            Opcode::VQSHL(_) => write!(f, "vqshl"),
+            Opcode::VQSHLU(_) => write!(f, "vqshlu"),
+            Opcode::VRSHL(_) => write!(f, "vrshl"),
+            Opcode::VQRSHL(_) => write!(f, "vqrshl"),
+            Opcode::VMAX(_) => write!(f, "vmax"),
+            Opcode::VMIN(_) => write!(f, "vmin"),
+            Opcode::VPMAX(_) => write!(f, "vpmax"),
+            Opcode::VPMIN(_) => write!(f, "vpmin"),
+            Opcode::VABD(_) => write!(f, "vabd"),
+            Opcode::VABA(_) => write!(f, "vaba"),
+            Opcode::VPADD(_) => write!(f, "vpadd"),
+            Opcode::VQDMULH(_) => write!(f, "vqdmulh"),
+            Opcode::VQRDMULH(_) => write!(f, "vqrdmulh"),
+            Opcode::VRECPS(_) => write!(f, "vrecps"),
+            Opcode::VRSQRTS(_) => write!(f, "vrsqrts"),
+            Opcode::VRECPE(_) => write!(f, "vrecpe"),
+            Opcode::VRSQRTE(_) => write!(f, "vrsqrte"),
+            Opcode::VADDL(_) => write!(f, "vaddl"),
+            Opcode::VADDW(_) => write!(f, "vaddw"),
+            Opcode::VSUBL(_) => write!(f, "vsubl"),
+            Opcode::VSUBW(_) => write!(f, "vsubw"),
+            Opcode::VADDHN(_) => write!(f, "vaddhn"),
+            Opcode::VRADDHN(_) => write!(f, "vraddhn"),
+            Opcode::VSUBHN(_) => write!(f, "vsubhn"),
+            Opcode::VRSUBHN(_) => write!(f, "vrsubhn"),
+            Opcode::VABAL(_) => write!(f, "vabal"),
+            Opcode::VABDL(_) => write!(f, "vabdl"),
+            Opcode::VMLAL(_) => write!(f, "vmlal"),
+            Opcode::VMLSL(_) => write!(f, "vmlsl"),
+            Opcode::VMULL(_) => write!(f, "vmull"),
+            Opcode::VQDMLAL(_) => write!(f, "vqdmlal"),
+            Opcode::VQDMLSL(_) => write!(f, "vqdmlsl"),
+            Opcode::VQDMULL(_) => write!(f, "vqdmull"),
+            Opcode::VSHR(_) => write!(f, "vshr"),
+            Opcode::VSRA(_) => write!(f, "vsra"),
+            Opcode::VRSHR(_) => write!(f, "vrshr"),
+            Opcode::VRSRA(_) => write!(f, "vrsra"),
+            Opcode::VSRI(_) => write!(f, "vsri"),
+            Opcode::VSLI(_) => write!(f, "vsli"),
+            Opcode::VSHRN(_) => write!(f, "vshrn"),
+            Opcode::VRSHRN(_) => write!(f, "vrshrn"),
+            Opcode::VQSHRN(_) => write!(f, "vqshrn"),
+            Opcode::VQRSHRN(_) => write!(f, "vqrshrn"),
+            Opcode::VQSHRUN(_) => write!(f, "vqshrun"),
+            Opcode::VQRSHRUN(_) => write!(f, "vqrshrun"),
+            Opcode::VSHLL(_) => write!(f, "vshll"),
+            Opcode::VMOVL(_) => write!(f, "vmovl"),
+            Opcode::VMOVN(_) => write!(f, "vmovn"),
+            Opcode::VQMOVN(_) => write!(f, "vqmovn"),
+            Opcode::VQMOVUN(_) => write!(f, "vqmovun"),
+            Opcode::VREV64(_) => write!(f, "vrev64"),
+            Opcode::VREV32(_) => write!(f, "vrev32"),
+            Opcode::VREV16(_) => write!(f, "vrev16"),
+            Opcode::VPADDL(_) => write!(f, "vpaddl"),
+            Opcode::VPADAL(_) => write!(f, "vpadal"),
+            Opcode::VCLS(_) => write!(f, "vcls"),
+            Opcode::VCLZ(_) => write!(f, "vclz"),
+            Opcode::VCNT(_) => write!(f, "vcnt"),
+            Opcode::VQABS(_) => write!(f, "vqabs"),
+            Opcode::VQNEG(_) => write!(f, "vqneg"),
+            Opcode::VTRN(_) => write!(f, "vtrn"),
+            Opcode::VUZP(_) => write!(f, "vuzp"),
+            Opcode::VZIP(_) => write!(f, "vzip"),
+            Opcode::VTBL(_) => write!(f, "vtbl"),
+            Opcode::VTBX(_) => write!(f, "vtbx"),
+            Opcode::VDUP(_) => write!(f, "vdup"),
+            Opcode::VEXT(_) => write!(f, "vext"),
+            Opcode::VLD1(_) => write!(f, "vld1"),
+            Opcode::VLD2(_) => write!(f, "vld2"),
+            Opcode::VLD3(_) => write!(f, "vld3"),
+            Opcode::VLD4(_) => write!(f, "vld4"),
+            Opcode::VST1(_) => write!(f, "vst1"),
+            Opcode::VST2(_) => write!(f, "vst2"),
+            Opcode::VST3(_) => write!(f, "vst3"),
+            Opcode::VST4(_) => write!(f, "vst4"),
             Opcode::SRS(p, u) => {
                 write!(
                     f,
@@ -1162,6 +1447,344 @@ pub enum Opcode {
     UDIV,
     SMLALD(bool),
     SMLSLD(bool),
+
+    MCR(u8, u8, u8),
+    MRC(u8, u8, u8),
+    CDP(u8, u8, u8),
+
+    // floating-point and advanced simd instructions. those that are written both with and
+    // without a data type carry an `Option<DataType>`.
+    VMOV(Option<DataType>),
+    VORR(Option<DataType>),
+    VBIC(Option<DataType>),
+    VMVN(Option<DataType>),
+    VAND,
+    VEOR,
+    VORN,
+    VBSL,
+    VBIT,
+    VBIF,
+    VSWP,
+    VMRS,
+    VMSR,
+    VLDR,
+    VSTR,
+    VLDM(bool),
+    VSTM(bool),
+    VPUSH,
+    VPOP,
+
+    VADD(DataType),
+    VSUB(DataType),
+    VMUL(DataType),
+    VMLA(DataType),
+    VMLS(DataType),
+    VNMUL(DataType),
+    VNMLA(DataType),
+    VNMLS(DataType),
+    VDIV(DataType),
+    VFMA(DataType),
+    VFMS(DataType),
+    VFNMA(DataType),
+    VFNMS(DataType),
+    VABS(DataType),
+    VNEG(DataType),
+    VSQRT(DataType),
+    VCMP(DataType),
+    VCMPE(DataType),
+    VCVT(DataType, DataType),
+    VCVTR(DataType, DataType),
+    VCVTB(DataType, DataType),
+    VCVTT(DataType, DataType),
+
+    VHADD(DataType),
+    VQADD(DataType),
+    VRHADD(DataType),
+    VHSUB(DataType),
+    VQSUB(DataType),
+    VCGT(DataType),
+    VCGE(DataType),
+    VCEQ(DataType),
+    VCLE(DataType),
+    VCLT(DataType),
+    VTST(DataType),
+    VACGE(DataType),
+    VACGT(DataType),
+    VSHL(DataType),
+    VQSHL(DataType),
+    VQSHLU(DataType),
+    VRSHL(DataType),
+    VQRSHL(DataType),
+    VMAX(DataType),
+    VMIN(DataType),
+    VPMAX(DataType),
+    VPMIN(DataType),
+    VABD(DataType),
+    VABA(DataType),
+    VPADD(DataType),
+    VQDMULH(DataType),
+    VQRDMULH(DataType),
+    VRECPS(DataType),
+    VRSQRTS(DataType),
+    VRECPE(DataType),
+    VRSQRTE(DataType),
+
+    VADDL(DataType),
+    VADDW(DataType),
+    VSUBL(DataType),
+    VSUBW(DataType),
+    VADDHN(DataType),
+    VRADDHN(DataType),
+    VSUBHN(DataType),
+    VRSUBHN(DataType),
+    VABAL(DataType),
+    VABDL(DataType),
+    VMLAL(DataType),
+    VMLSL(DataType),
+    VMULL(DataType),
+    VQDMLAL(DataType),
+    VQDMLSL(DataType),
+    VQDMULL(DataType),
+
+    VSHR(DataType),
+    VSRA(DataType),
+    VRSHR(DataType),
+    VRSRA(DataType),
+    VSRI(DataType),
+    VSLI(DataType),
+    VSHRN(DataType),
+    VRSHRN(DataType),
+    VQSHRN(DataType),
+    VQRSHRN(DataType),
+    VQSHRUN(DataType),
+    VQRSHRUN(DataType),
+    VSHLL(DataType),
+    VMOVL(DataType),
+    VMOVN(DataType),
+    VQMOVN(DataType),
+    VQMOVUN(DataType),
+
+    VREV64(DataType),
+    VREV32(DataType),
+    VREV16(DataType),
+    VPADDL(DataType),
+    VPADAL(DataType),
+    VCLS(DataType),
+    VCLZ(DataType),
+    VCNT(DataType),
+    VQABS(DataType),
+    VQNEG(DataType),
+    VTRN(DataType),
+    VUZP(DataType),
+    VZIP(DataType),
+    VTBL(DataType),
+    VTBX(DataType),
+    VDUP(DataType),
+    VEXT(DataType),
+
+    VLD1(DataType),
+    VLD2(DataType),
+    VLD3(DataType),
+    VLD4(DataType),
+    VST1(DataType),
+    VST2(DataType),
+    VST3(DataType),
+    VST4(DataType),
+}
+
+impl Opcode {
+    /// the `.<dt>` suffixes of a floating-point or advanced simd opcode. these are written after
+    /// the condition code, as in `vaddeq.f32`.
+    pub fn data_types(&self) -> [Option<DataType>; 2] {
+        match *self {
+            Opcode::VCVT(to, from)
+            | Opcode::VCVTR(to, from)
+            | Opcode::VCVTB(to, from)
+            | Opcode::VCVTT(to, from) => [Some(to), Some(from)],
+            Opcode::VMOV(dt) | Opcode::VORR(dt) | Opcode::VBIC(dt) | Opcode::VMVN(dt) => {
+                [dt, None]
+            }
+            Opcode::VADD(dt)
+            | Opcode::VSUB(dt)
+            | Opcode::VMUL(dt)
+            | Opcode::VMLA(dt)
+            | Opcode::VMLS(dt)
+            | Opcode::VNMUL(dt)
+            | Opcode::VNMLA(dt)
+            | Opcode::VNMLS(dt)
+            | Opcode::VDIV(dt)
+            | Opcode::VFMA(dt)
+            | Opcode::VFMS(dt)
+            | Opcode::VFNMA(dt)
+            | Opcode::VFNMS(dt)
+            | Opcode::VABS(dt)
+            | Opcode::VNEG(dt)
+            | Opcode::VSQRT(dt)
+            | Opcode::VCMP(dt)
+            | Opcode::VCMPE(dt)
+            | Opcode::VHADD(dt)
+            | Opcode::VQADD(dt)
+            | Opcode::VRHADD(dt)
+            | Opcode::VHSUB(dt)
+            | Opcode::VQSUB(dt)
+            | Opcode::VCGT(dt)
+            | Opcode::VCGE(dt)
+            | Opcode::VCEQ(dt)
+            | Opcode::VCLE(dt)
+            | Opcode::VCLT(dt)
+            | Opcode::VTST(dt)
+            | Opcode::VACGE(dt)
+            | Opcode::VACGT(dt)
+            | Opcode::VSHL(dt)
+            | Opcode::VQSHL(dt)
+            | Opcode::VQSHLU(dt)
+            | Opcode::VRSHL(dt)
+            | Opcode::VQRSHL(dt)
+            | Opcode::VMAX(dt)
+            | Opcode::VMIN(dt)
+            | Opcode::VPMAX(dt)
+            | Opcode::VPMIN(dt)
+            | Opcode::VABD(dt)
+            | Opcode::VABA(dt)
+            | Opcode::VPADD(dt)
+            | Opcode::VQDMULH(dt)
+            | Opcode::VQRDMULH(dt)
+            | Opcode::VRECPS(dt)
+            | Opcode::VRSQRTS(dt)
+            | Opcode::VRECPE(dt)
+            | Opcode::VRSQRTE(dt)
+            | Opcode::VADDL(dt)
+            | Opcode::VADDW(dt)
+            | Opcode::VSUBL(dt)
+            | Opcode::VSUBW(dt)
+            | Opcode::VADDHN(dt)
+            | Opcode::VRADDHN(dt)
+            | Opcode::VSUBHN(dt)
+            | Opcode::VRSUBHN(dt)
+            | Opcode::VABAL(dt)
+            | Opcode::VABDL(dt)
+            | Opcode::VMLAL(dt)
+            | Opcode::VMLSL(dt)
+            | Opcode::VMULL(dt)
+            | Opcode::VQDMLAL(dt)
+            | Opcode::VQDMLSL(dt)
+            | Opcode::VQDMULL(dt)
+            | Opcode::VSHR(dt)
+            | Opcode::VSRA(dt)
+            | Opcode::VRSHR(dt)
+            | Opcode::VRSRA(dt)
+            | Opcode::VSRI(dt)
+            | Opcode::VSLI(dt)
+            | Opcode::VSHRN(dt)
+            | Opcode::VRSHRN(dt)
+            | Opcode::VQSHRN(dt)
+            | Opcode::VQRSHRN(dt)
+            | Opcode::VQSHRUN(dt)
+            | Opcode::VQRSHRUN(dt)
+            | Opcode::VSHLL(dt)
+            | Opcode::VMOVL(dt)
+            | Opcode::VMOVN(dt)
+            | Opcode::VQMOVN(dt)
+            | Opcode::VQMOVUN(dt)
+            | Opcode::VREV64(dt)
+            | Opcode::VREV32(dt)
+            | Opcode::VREV16(dt)
+            | Opcode::VPADDL(dt)
+            | Opcode::VPADAL(dt)
+            | Opcode::VCLS(dt)
+            | Opcode::VCLZ(dt)
+            | Opcode::VCNT(dt)
+            | Opcode::VQABS(dt)
+            | Opcode::VQNEG(dt)
+            | Opcode::VTRN(dt)
+            | Opcode::VUZP(dt)
+            | Opcode::VZIP(dt)
+            | Opcode::VTBL(dt)
+            | Opcode::VTBX(dt)
+            | Opcode::VDUP(dt)
+            | Opcode::VEXT(dt)
+            | Opcode::VLD1(dt)
+            | Opcode::VLD2(dt)
+            | Opcode::VLD3(dt)
+            | Opcode::VLD4(dt)
+            | Opcode::VST1(dt)
+            | Opcode::VST2(dt)
+            | Opcode::VST3(dt)
+            | Opcode::VST4(dt) => [Some(dt), None],
+            _ => [None, None],
+        }
+    }
+}
+
+/// the element type of a floating-point or advanced simd instruction, written as a `.<dt>` suffix
+/// on its opcode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum DataType {
+    I8,
+    I16,
+    I32,
+    I64,
+    S8,
+    S16,
+    S32,
+    S64,
+    U8,
+    U16,
+    U32,
+    U64,
+    P8,
+    F16,
+    F32,
+    F64,
+    /// an untyped element size, as in `vld1.8` or `vrev32.16`.
+    Size8,
+    Size16,
+    Size32,
+    Size64,
+}
+
+impl DataType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DataType::I8 => "i8",
+            DataType::I16 => "i16",
+            DataType::I32 => "i32",
+            DataType::I64 => "i64",
+            DataType::S8 => "s8",
+            DataType::S16 => "s16",
+            DataType::S32 => "s32",
+            DataType::S64 => "s64",
+            DataType::U8 => "u8",
+            DataType::U16 => "u16",
+            DataType::U32 => "u32",
+            DataType::U64 => "u64",
+            DataType::P8 => "p8",
+            DataType::F16 => "f16",
+            DataType::F32 => "f32",
+            DataType::F64 => "f64",
+            DataType::Size8 => "8",
+            DataType::Size16 => "16",
+            DataType::Size32 => "32",
+            DataType::Size64 => "64",
+        }
+    }
+
+    /// the width of one element of this type, in bytes.
+    pub fn width(&self) -> usize {
+        match self {
+            DataType::I8 | DataType::S8 | DataType::U8 | DataType::P8 | DataType::Size8 => 1,
+            DataType::I16 | DataType::S16 | DataType::U16 | DataType::F16 | DataType::Size16 => 2,
+            DataType::I32 | DataType::S32 | DataType::U32 | DataType::F32 | DataType::Size32 => 4,
+            DataType::I64 | DataType::S64 | DataType::U64 | DataType::F64 | DataType::Size64 => 8,
+        }
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str(self.as_str())
+    }
 }
 
 static DATA_PROCESSING_OPCODES: [Opcode; 16] = [
@@ -1443,6 +2066,131 @@ impl CReg {
     }
 }
 
+/// the width of a floating-point or advanced simd register.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SIMDWidth {
+    /// a 32-bit single-precision register, `s0` through `s31`.
+    S,
+    /// a 64-bit doubleword register, `d0` through `d31`.
+    D,
+    /// a 128-bit quadword register, `q0` through `q15`.
+    Q,
+}
+
+/// a floating-point or advanced simd register.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SIMDReg {
+    width: SIMDWidth,
+    bits: u8,
+}
+
+impl SIMDReg {
+    /// create a new `s` register with the specified number.
+    ///
+    /// panics if `bits` is out of range (32 or above).
+    pub fn single(bits: u8) -> SIMDReg {
+        if bits > 31 {
+            panic!("register number out of range");
+        }
+
+        SIMDReg { width: SIMDWidth::S, bits }
+    }
+
+    /// create a new `d` register with the specified number.
+    ///
+    /// panics if `bits` is out of range (32 or above).
+    pub fn double(bits: u8) -> SIMDReg {
+        if bits > 31 {
+            panic!("register number out of range");
+        }
+
+        SIMDReg { width: SIMDWidth::D, bits }
+    }
+
+    /// create a new `q` register with the specified number.
+    ///
+    /// panics if `bits` is out of range (16 or above).
+    pub fn quad(bits: u8) -> SIMDReg {
+        if bits > 15 {
+            panic!("register number out of range");
+        }
+
+        SIMDReg { width: SIMDWidth::Q, bits }
+    }
+
+    /// the width of this register.
+    pub fn width(&self) -> SIMDWidth {
+        self.width
+    }
+
+    /// get the number of this register.
+    pub fn number(&self) -> u8 {
+        self.bits
+    }
+
+    /// the register `offset` registers after this one, of the same width.
+    fn nth(&self, offset: u8) -> SIMDReg {
+        SIMDReg { width: self.width, bits: self.bits + offset }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        const SINGLE: &[&str] = &[
+            "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "s12", "s13",
+            "s14", "s15", "s16", "s17", "s18", "s19", "s20", "s21", "s22", "s23", "s24", "s25",
+            "s26", "s27", "s28", "s29", "s30", "s31",
+        ];
+        const DOUBLE: &[&str] = &[
+            "d0", "d1", "d2", "d3", "d4", "d5", "d6", "d7", "d8", "d9", "d10", "d11", "d12", "d13",
+            "d14", "d15", "d16", "d17", "d18", "d19", "d20", "d21", "d22", "d23", "d24", "d25",
+            "d26", "d27", "d28", "d29", "d30", "d31",
+        ];
+        const QUAD: &[&str] = &[
+            "q0", "q1", "q2", "q3", "q4", "q5", "q6", "q7", "q8", "q9", "q10", "q11", "q12", "q13",
+            "q14", "q15",
+        ];
+
+        match self.width {
+            SIMDWidth::S => SINGLE[self.bits as usize],
+            SIMDWidth::D => DOUBLE[self.bits as usize],
+            SIMDWidth::Q => QUAD[self.bits as usize],
+        }
+    }
+}
+
+/// a floating-point system register, as accessed by `vmrs` and `vmsr`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum FPSysReg {
+    FPSID,
+    FPSCR,
+    MVFR1,
+    MVFR0,
+    FPEXC,
+}
+
+impl FPSysReg {
+    fn from_raw(raw: u8) -> Result<FPSysReg, ErrorKind> {
+        match raw {
+            0b0000 => Ok(FPSysReg::FPSID),
+            0b0001 => Ok(FPSysReg::FPSCR),
+            0b0110 => Ok(FPSysReg::MVFR1),
+            0b0111 => Ok(FPSysReg::MVFR0),
+            0b1000 => Ok(FPSysReg::FPEXC),
+            _ => Err(ErrorKind::InvalidRegister),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FPSysReg::FPSID => "fpsid",
+            FPSysReg::FPSCR => "fpscr",
+            FPSysReg::MVFR1 => "mvfr1",
+            FPSysReg::MVFR0 => "mvfr0",
+            FPSysReg::FPEXC => "fpexc",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 #[allow(missing_docs)]
@@ -1634,6 +2382,32 @@ pub enum Operand {
     SPSR,
     /// the `cpsr` register.
     CPSR,
+    /// the `apsr` condition flags, as written by `vmrs` or `mrc` with `pc` as the destination.
+    APSRFlags,
+    /// a floating-point system register.
+    FPSysReg(FPSysReg),
+    /// a floating-point or advanced simd register.
+    SIMDReg(SIMDReg),
+    /// a single lane of a `d` register, `d<n>[<lane>]`.
+    SIMDRegLane(SIMDReg, u8),
+    /// a list of registers. the first `u8` is the number of registers in the list, the second is
+    /// the spacing between consecutive registers.
+    SIMDRegList(SIMDReg, u8, u8),
+    /// a list of registers as in `SIMDRegList`, naming the same lane of each register. the last
+    /// `u8` is the lane.
+    SIMDRegListLane(SIMDReg, u8, u8, u8),
+    /// a list of registers as in `SIMDRegList`, naming all lanes of each register.
+    SIMDRegListAllLanes(SIMDReg, u8, u8),
+    /// a memory access, dereferencing a general-purpose register with an alignment of some
+    /// number of bits (zero if unspecified). the bool indicates if the base register is
+    /// incremented by the transfer size, as in `[r0]!`; an increment by register is a separate
+    /// `Reg` operand after this one.
+    RegDerefAligned(Reg, u16, bool),
+    /// an advanced simd modified immediate, already expanded to the instruction's element size.
+    SIMDImm(u64),
+    /// a floating-point immediate, stored as the bits of an `f32`. every immediate a `vmov` can
+    /// encode is exactly representable as an `f32`.
+    FloatImm(u32),
     /// "no operand". since an instruction's `operands` array is always four entries, this is used
     /// to fill space, if any, after recording an instruction's extant operands.
     Nothing,
@@ -1831,6 +2605,53 @@ impl Operand {
             Operand::APSR => stream.push("apsr", Colors::register()),
             Operand::SPSR => stream.push("spsr", Colors::register()),
             Operand::CPSR => stream.push("cpsr", Colors::register()),
+            Operand::APSRFlags => stream.push("apsr_nzcv", Colors::register()),
+            Operand::FPSysReg(reg) => stream.push(reg.as_str(), Colors::register()),
+            Operand::SIMDReg(reg) => stream.push(reg.as_str(), Colors::register()),
+            Operand::SIMDRegLane(reg, lane) => {
+                stream.push(reg.as_str(), Colors::register());
+                stream.push("[", Colors::brackets());
+                stream.push_owned(lane.to_string(), Colors::immediate());
+                stream.push("]", Colors::brackets());
+            }
+            Operand::SIMDRegList(first, count, stride) => {
+                format_simd_reg_list(stream, *first, *count, *stride, |_| {});
+            }
+            Operand::SIMDRegListLane(first, count, stride, lane) => {
+                format_simd_reg_list(stream, *first, *count, *stride, |stream| {
+                    stream.push("[", Colors::brackets());
+                    stream.push_owned(lane.to_string(), Colors::immediate());
+                    stream.push("]", Colors::brackets());
+                });
+            }
+            Operand::SIMDRegListAllLanes(first, count, stride) => {
+                format_simd_reg_list(stream, *first, *count, *stride, |stream| {
+                    stream.push("[]", Colors::brackets());
+                });
+            }
+            Operand::RegDerefAligned(reg, align, wback) => {
+                stream.push("[", Colors::brackets());
+                stream.push(reg.as_str(), Colors::register());
+                if *align != 0 {
+                    stream.push(":", Colors::expr());
+                    stream.push_owned(align.to_string(), Colors::immediate());
+                }
+                stream.push("]", Colors::brackets());
+                if *wback {
+                    stream.push("!", Colors::expr());
+                }
+            }
+            Operand::SIMDImm(imm) => {
+                stream.push_owned(decoder::encode_uhex(*imm), Colors::immediate());
+            }
+            Operand::FloatImm(bits) => {
+                let imm = f32::from_bits(*bits);
+                if imm.fract() == 0.0 {
+                    stream.push_owned(format!("{imm:0.1}"), Colors::immediate());
+                } else {
+                    stream.push_owned(format!("{imm}"), Colors::immediate());
+                }
+            }
             Operand::Nothing => panic!("tried to print Nothing operand"),
         }
     }
@@ -1928,6 +2749,24 @@ fn format_reg_list(stream: &mut TokenStream, mut list: u16) {
     stream.push("}", Colors::brackets());
 }
 
+fn format_simd_reg_list(
+    stream: &mut TokenStream,
+    first: SIMDReg,
+    count: u8,
+    stride: u8,
+    lane: impl Fn(&mut TokenStream),
+) {
+    stream.push("{", Colors::brackets());
+    for idx in 0..count {
+        if idx != 0 {
+            stream.push(", ", Colors::expr());
+        }
+        stream.push(first.nth(idx * stride).as_str(), Colors::register());
+        lane(stream);
+    }
+    stream.push("}", Colors::brackets());
+}
+
 fn format_shift(stream: &mut TokenStream, shift: RegShift) {
     match shift.into_shift() {
         RegShiftStyle::RegImm(imm_shift) => {
@@ -2098,6 +2937,26 @@ impl Decoded for Instruction {
             }
         }
     }
+
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
+    }
+
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
+
+    fn regs_read(&self) -> Vec<&'static str> {
+        behavior::registers(self).0
+    }
+
+    fn regs_written(&self) -> Vec<&'static str> {
+        behavior::registers(self).1
+    }
+
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
 }
 
 impl Display for Instruction {
@@ -2299,164 +3158,47 @@ impl ToTokens for Instruction {
                     unreachable!();
                 }
             },
-            Opcode::STCL(coproc) => {
-                stream.push("stcl ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
-                    stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
-                }
-            }
-            Opcode::STC(coproc) => {
-                stream.push("stc ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
-                    stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
-                }
-            }
-            Opcode::STC2L(coproc) => {
-                stream.push("stc2l ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
-                    stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
-                }
-            }
-            Opcode::STC2(coproc) => {
-                stream.push("stc2 ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
-                    stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
-                }
-            }
-            Opcode::LDC(coproc) => {
-                stream.push("ldc ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
-                    stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
-                }
-            }
-            Opcode::LDCL(coproc) => {
-                stream.push("ldcl ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
-                    stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
-                }
-            }
-            Opcode::LDC2(coproc) => {
-                stream.push("ldc2 ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
-                    stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
-                }
-            }
-            Opcode::LDC2L(coproc) => {
-                stream.push("ldc2l ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
-                    stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
-                }
-            }
-            Opcode::MRRC2(coproc, opc) => {
-                stream.push("mrrc2 ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                stream.push(", ", Colors::expr());
-                stream.push_owned(opc.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
-                    stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
-                }
-            }
-            Opcode::MCRR2(coproc, opc) => {
-                stream.push("mcrr2 ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                stream.push(", ", Colors::expr());
-                stream.push_owned(opc.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
-                    stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
-                }
-            }
-            Opcode::MRC2(coproc, opc1, opc2) => {
-                stream.push("mrc2 ", Colors::opcode());
+            Opcode::LDC(coproc)
+            | Opcode::LDCL(coproc)
+            | Opcode::LDC2(coproc)
+            | Opcode::LDC2L(coproc)
+            | Opcode::STC(coproc)
+            | Opcode::STCL(coproc)
+            | Opcode::STC2(coproc)
+            | Opcode::STC2L(coproc)
+            | Opcode::MCRR(coproc, _)
+            | Opcode::MRRC(coproc, _)
+            | Opcode::MCRR2(coproc, _)
+            | Opcode::MRRC2(coproc, _)
+            | Opcode::MCR(coproc, _, _)
+            | Opcode::MRC(coproc, _, _)
+            | Opcode::CDP(coproc, _, _)
+            | Opcode::MCR2(coproc, _, _)
+            | Opcode::MRC2(coproc, _, _)
+            | Opcode::CDP2(coproc, _, _) => {
+                let (opc1, opc2) = match self.opcode {
+                    Opcode::MCRR(_, opc)
+                    | Opcode::MRRC(_, opc)
+                    | Opcode::MCRR2(_, opc)
+                    | Opcode::MRRC2(_, opc) => (Some(opc), None),
+                    Opcode::MCR(_, opc1, opc2)
+                    | Opcode::MRC(_, opc1, opc2)
+                    | Opcode::CDP(_, opc1, opc2)
+                    | Opcode::MCR2(_, opc1, opc2)
+                    | Opcode::MRC2(_, opc1, opc2)
+                    | Opcode::CDP2(_, opc1, opc2) => (Some(opc1), Some(opc2)),
+                    _ => (None, None),
+                };
+
+                ConditionedOpcode(self.opcode, false, false, self.condition)
+                    .tokenize(stream, symbols);
+                stream.push(" ", Colors::expr());
                 stream.push("p", Colors::register());
                 stream.push_owned(coproc.to_string(), Colors::register());
-                stream.push(", ", Colors::expr());
-                stream.push_owned(opc1.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
+                if let Some(opc1) = opc1 {
                     stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
+                    stream.push_owned(opc1.to_string(), Colors::register());
                 }
-
-                stream.push(", ", Colors::expr());
-                stream.push_owned(opc2.to_string(), Colors::register());
-            }
-            Opcode::MCR2(coproc, opc1, opc2) => {
-                stream.push("mcr2 ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                stream.push(", ", Colors::expr());
-                stream.push_owned(opc1.to_string(), Colors::register());
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
@@ -2465,27 +3207,10 @@ impl ToTokens for Instruction {
                     stream.push(", ", Colors::expr());
                     op.tokenize(stream, symbols, imm_override);
                 }
-
-                stream.push(", ", Colors::expr());
-                stream.push_owned(opc2.to_string(), Colors::register());
-            }
-            Opcode::CDP2(coproc, opc1, opc2) => {
-                stream.push("cdp2 ", Colors::opcode());
-                stream.push("p", Colors::register());
-                stream.push_owned(coproc.to_string(), Colors::register());
-                stream.push(", ", Colors::expr());
-                stream.push_owned(opc1.to_string(), Colors::register());
-                let ops = self.operands.iter();
-                for op in ops {
-                    if let Operand::Nothing = op {
-                        break;
-                    }
+                if let Some(opc2) = opc2 {
                     stream.push(", ", Colors::expr());
-                    op.tokenize(stream, symbols, imm_override);
+                    stream.push_owned(opc2.to_string(), Colors::register());
                 }
-
-                stream.push(", ", Colors::expr());
-                stream.push_owned(opc2.to_string(), Colors::register());
             }
             _ => {
                 ConditionedOpcode(self.opcode, self.s(), self.w(), self.condition)
@@ -3069,7 +3794,17 @@ fn read(decoder: &Decoder, words: &mut Reader, inst: &mut Instruction) -> Result
         } else {
             // op1=0xxxxxxx, "Memory hints, Advanced SIMD instructions, and miscellaneous
             // instructions on pge A5-215"
-            return Err(ErrorKind::Incomplete);
+            if (word >> 25) & 0b111 == 0b001 {
+                // op1=01xxxxx, "Advanced SIMD data-processing instructions" on page A7-261
+                simd::decode_data_processing(word, inst)?;
+            } else if (word >> 24) & 0b1111 == 0b0100 && (word >> 20) & 1 == 0 {
+                // op1=100xxx0, "Advanced SIMD element or structure load/store instructions" on
+                // page A7-275
+                simd::decode_load_store(word, inst)?;
+            } else {
+                // TODO: memory hints and miscellaneous instructions
+                return Err(ErrorKind::Incomplete);
+            }
         }
         return Ok(());
     } else {
@@ -4689,7 +5424,132 @@ fn read(decoder: &Decoder, words: &mut Reader, inst: &mut Instruction) -> Result
             // coprocessor instructions and supervisor call
             // page A5-213
             // low bit of 0b110 or 0b111 corresponds to high bit of op1
-            return Err(ErrorKind::Incomplete);
+            let op1 = (word >> 20) & 0b111111;
+            let coproc = (word >> 8) as u8 & 0b1111;
+
+            if op1 & 0b110000 == 0b110000 {
+                // `SVC` (`A8-721`)
+                inst.opcode = Opcode::SVC;
+                inst.operands = [
+                    Operand::Imm32(word & 0x00ff_ffff),
+                    Operand::Nothing,
+                    Operand::Nothing,
+                    Operand::Nothing,
+                ];
+            } else if op1 & 0b111110 == 0b000000 {
+                return Err(ErrorKind::Undefined);
+            } else if coproc & 0b1110 == 0b1010 {
+                // "Floating-point data-processing instructions" on page A7-270 and the extension
+                // register transfers around it
+                simd::decode_vfp(decoder, word, inst)?;
+            } else if op1 & 0b100000 == 0 {
+                let Rn = (word >> 16) as u8 & 0b1111;
+                let CRd = (word >> 12) as u8 & 0b1111;
+                let CRm = word as u8 & 0b1111;
+
+                if op1 & 0b111010 == 0b000000 {
+                    // op1=00010x, `MCRR` (`A8-479`) or `MRRC` (`A8-495`)
+                    let opc1 = (word >> 4) as u8 & 0b1111;
+                    if CRd == 15 || Rn == 15 || (op1 & 1 != 0 && CRd == Rn) {
+                        decoder.unpredictable()?;
+                    }
+                    inst.opcode = if op1 & 1 != 0 {
+                        Opcode::MRRC(coproc, opc1)
+                    } else {
+                        Opcode::MCRR(coproc, opc1)
+                    };
+                    inst.operands = [
+                        Operand::Reg(Reg::from_u8(CRd)),
+                        Operand::Reg(Reg::from_u8(Rn)),
+                        Operand::CReg(CReg::from_u8(CRm)),
+                        Operand::Nothing,
+                    ];
+                } else {
+                    // `STC` (`A8-663`) or `LDC` (`A8-393`)
+                    let pudw = (word >> 21) as u8 & 0b1111;
+                    let imm8 = word & 0b11111111;
+                    let P = pudw & 0b1000 != 0;
+                    let U = pudw & 0b0100 != 0;
+                    let W = pudw & 0b0001 != 0;
+                    let long = pudw & 0b0010 != 0;
+
+                    inst.opcode = match (op1 & 1 != 0, long) {
+                        (false, false) => Opcode::STC(coproc),
+                        (false, true) => Opcode::STCL(coproc),
+                        (true, false) => Opcode::LDC(coproc),
+                        (true, true) => Opcode::LDCL(coproc),
+                    };
+                    inst.operands = [
+                        Operand::CReg(CReg::from_u8(CRd)),
+                        if P {
+                            Operand::RegDerefPreindexOffset(
+                                Reg::from_u8(Rn),
+                                (imm8 << 2) as u16,
+                                U,
+                                W,
+                            )
+                        } else if W {
+                            // preindex has no wback
+                            Operand::RegDerefPostindexOffset(
+                                Reg::from_u8(Rn),
+                                (imm8 << 2) as u16,
+                                U,
+                                false,
+                            )
+                        } else {
+                            Operand::RegDeref(Reg::from_u8(Rn))
+                        },
+                        if !P && !W {
+                            Operand::CoprocOption(imm8 as u8)
+                        } else {
+                            Operand::Nothing
+                        },
+                        Operand::Nothing,
+                    ];
+                }
+            } else {
+                // operands are shared between cdp and mcr/mrc, but Rt is repurposed as CRd
+                let CRm = word as u8 & 0b1111;
+                let opc2 = (word >> 5) as u8 & 0b111;
+                let Rt = (word >> 12) as u8 & 0b1111;
+                let CRn = (word >> 16) as u8 & 0b1111;
+
+                if (word >> 4) & 1 == 0 {
+                    // `CDP` (`A8-357`)
+                    let opc1 = (word >> 20) as u8 & 0b1111;
+                    inst.opcode = Opcode::CDP(coproc, opc1, opc2);
+                    inst.operands = [
+                        Operand::CReg(CReg::from_u8(Rt)),
+                        Operand::CReg(CReg::from_u8(CRn)),
+                        Operand::CReg(CReg::from_u8(CRm)),
+                        Operand::Nothing,
+                    ];
+                } else {
+                    // `MCR` (`A8-477`) or `MRC` (`A8-493`)
+                    let opc1 = (word >> 21) as u8 & 0b111;
+                    let mrc = (word >> 20) & 1 != 0;
+                    let rt = if mrc && Rt == 15 {
+                        // condition flags are transferred to `APSR.{N, Z, C, V}`
+                        Operand::APSRFlags
+                    } else {
+                        if Rt == 15 {
+                            decoder.unpredictable()?;
+                        }
+                        Operand::Reg(Reg::from_u8(Rt))
+                    };
+                    inst.opcode = if mrc {
+                        Opcode::MRC(coproc, opc1, opc2)
+                    } else {
+                        Opcode::MCR(coproc, opc1, opc2)
+                    };
+                    inst.operands = [
+                        rt,
+                        Operand::CReg(CReg::from_u8(CRn)),
+                        Operand::CReg(CReg::from_u8(CRm)),
+                        Operand::Nothing,
+                    ];
+                }
+            }
         }
         _ => {
             unreachable!("opc category is three bits");
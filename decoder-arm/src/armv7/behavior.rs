@@ -0,0 +1,510 @@
+//! Semantic queries on decoded instructions: control flow, registers and memory accesses.
+//!
+//! The status flags are reported as `cpsr`.
+
+use super::{
+    ConditionCode, Instruction, Opcode, Operand, Reg, RegShift, RegShiftStyle, SIMDReg, SIMDWidth,
+};
+use decoder::{Direction, FlowKind, MemoryAccess};
+
+const SP: u8 = 13;
+const LR: u8 = 14;
+const PC: u8 = 15;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+fn is_load(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::LDR
+            | Opcode::LDRH
+            | Opcode::LDRB
+            | Opcode::LDRSH
+            | Opcode::LDRSHT
+            | Opcode::LDRSB
+            | Opcode::LDRSBT
+            | Opcode::LDRD
+            | Opcode::LDREX
+            | Opcode::LDREXH
+            | Opcode::LDREXB
+            | Opcode::LDREXD
+            | Opcode::LDRT
+            | Opcode::LDRHT
+            | Opcode::LDRBT
+            | Opcode::LDM(..)
+            | Opcode::POP
+            | Opcode::VLDR
+            | Opcode::VLDM(..)
+            | Opcode::VPOP
+            | Opcode::VLD1(_)
+            | Opcode::VLD2(_)
+            | Opcode::VLD3(_)
+            | Opcode::VLD4(_)
+    )
+}
+
+fn is_store(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::STR
+            | Opcode::STRH
+            | Opcode::STRB
+            | Opcode::STRD
+            | Opcode::STREX
+            | Opcode::STREXH
+            | Opcode::STREXB
+            | Opcode::STREXD
+            | Opcode::STRT
+            | Opcode::STRHT
+            | Opcode::STRBT
+            | Opcode::STM(..)
+            | Opcode::PUSH
+            | Opcode::VSTR
+            | Opcode::VSTM(..)
+            | Opcode::VPUSH
+            | Opcode::VST1(_)
+            | Opcode::VST2(_)
+            | Opcode::VST3(_)
+            | Opcode::VST4(_)
+    )
+}
+
+/// Instructions that accumulate into, or only partially replace, their destination.
+fn accumulates(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::VMLA(_)
+            | Opcode::VMLS(_)
+            | Opcode::VNMLA(_)
+            | Opcode::VNMLS(_)
+            | Opcode::VFMA(_)
+            | Opcode::VFMS(_)
+            | Opcode::VFNMA(_)
+            | Opcode::VFNMS(_)
+            | Opcode::VABA(_)
+            | Opcode::VABAL(_)
+            | Opcode::VMLAL(_)
+            | Opcode::VMLSL(_)
+            | Opcode::VQDMLAL(_)
+            | Opcode::VQDMLSL(_)
+            | Opcode::VSRA(_)
+            | Opcode::VRSRA(_)
+            | Opcode::VSRI(_)
+            | Opcode::VSLI(_)
+            | Opcode::VPADAL(_)
+            | Opcode::VBSL
+            | Opcode::VBIT
+            | Opcode::VBIF
+            | Opcode::VTBX(_)
+            | Opcode::VORR(Some(_))
+            | Opcode::VBIC(Some(_))
+    )
+}
+
+/// How the operand at `idx` is accessed, ignoring registers used for addressing.
+fn access(inst: &Instruction, idx: usize) -> Access {
+    let opcode = inst.opcode;
+
+    match (opcode, idx) {
+        // the status result of a store exclusive
+        (Opcode::STREX | Opcode::STREXH | Opcode::STREXB | Opcode::STREXD, 0) => Access::Write,
+        (Opcode::SWP | Opcode::SWPB, 0) => Access::Write,
+        (Opcode::LDRD | Opcode::LDREXD, 1) => Access::Write,
+        (Opcode::UMULL | Opcode::SMULL, 0 | 1) => Access::Write,
+        (Opcode::UMLAL | Opcode::SMLAL | Opcode::UMAAL | Opcode::SMLAL_halfword(..), 0 | 1) => {
+            Access::ReadWrite
+        }
+        (Opcode::SMLALD(_) | Opcode::SMLSLD(_), 0 | 1) => Access::ReadWrite,
+        (Opcode::LDM(..) | Opcode::POP | Opcode::VLDM(..) | Opcode::VPOP, _) => Access::Write,
+        (Opcode::MRRC(..) | Opcode::MRRC2(..), 0 | 1) => Access::Write,
+        (Opcode::VSWP | Opcode::VTRN(_) | Opcode::VUZP(_) | Opcode::VZIP(_), 0 | 1) => {
+            Access::ReadWrite
+        }
+        // `vmov r0, r1, d0` and `vmov s0, s1, r0, r1` write a pair of registers
+        (Opcode::VMOV(None), 1)
+            if inst.operands[3] != Operand::Nothing
+                || matches!(inst.operands[..2], [Operand::Reg(_), Operand::Reg(_)]) =>
+        {
+            Access::Write
+        }
+        (_, 0) if accumulates(opcode) => Access::ReadWrite,
+        (_, 0) if is_load(opcode) => Access::Write,
+        (_, 0) if is_store(opcode) => Access::Read,
+        (
+            Opcode::CMP
+            | Opcode::CMN
+            | Opcode::TST
+            | Opcode::TEQ
+            | Opcode::B
+            | Opcode::BL
+            | Opcode::BLX
+            | Opcode::BX
+            | Opcode::BXJ
+            | Opcode::CBZ
+            | Opcode::CBNZ
+            | Opcode::TBB
+            | Opcode::TBH
+            | Opcode::PLD
+            | Opcode::PLI
+            | Opcode::SVC
+            | Opcode::BKPT
+            | Opcode::HVC
+            | Opcode::SMC
+            | Opcode::UDF
+            | Opcode::IT
+            | Opcode::SETEND
+            | Opcode::DBG
+            | Opcode::HINT
+            | Opcode::MCR(..)
+            | Opcode::MCR2(..)
+            | Opcode::MCRR(..)
+            | Opcode::MCRR2(..)
+            | Opcode::VCMP(_)
+            | Opcode::VCMPE(_),
+            0,
+        ) => Access::Read,
+        (Opcode::MOVT | Opcode::BFI | Opcode::BFC, 0) => Access::ReadWrite,
+        (_, 0) => Access::Write,
+        _ => Access::Read,
+    }
+}
+
+fn push(regs: &mut Vec<&'static str>, reg: Reg) {
+    push_name(regs, reg.as_str());
+}
+
+fn push_name(regs: &mut Vec<&'static str>, name: &'static str) {
+    if !regs.contains(&name) {
+        regs.push(name);
+    }
+}
+
+/// Size of a floating-point or Advanced SIMD register, in bytes.
+fn simd_size(reg: SIMDReg) -> usize {
+    match reg.width() {
+        SIMDWidth::S => 4,
+        SIMDWidth::D => 8,
+        SIMDWidth::Q => 16,
+    }
+}
+
+fn simd_list(first: SIMDReg, count: u8, stride: u8) -> impl Iterator<Item = SIMDReg> {
+    (0..count).map(move |idx| first.nth(idx * stride))
+}
+
+fn shift_regs(shift: RegShift, regs: &mut Vec<&'static str>) {
+    match shift.into_shift() {
+        RegShiftStyle::RegImm(shift) => push(regs, shift.shiftee()),
+        RegShiftStyle::RegReg(shift) => {
+            push(regs, shift.shiftee());
+            push(regs, shift.shifter());
+        }
+    }
+}
+
+fn reg_list(list: u16) -> impl Iterator<Item = Reg> {
+    (0..16u8).filter(move |idx| list & (1 << idx) != 0).map(Reg::from_u8)
+}
+
+/// Registers named by an operand, split by how they're accessed.
+fn operand_regs(
+    operand: &Operand,
+    access: Access,
+    read: &mut Vec<&'static str>,
+    written: &mut Vec<&'static str>,
+) {
+    let value = match *operand {
+        Operand::Reg(reg) => 1 << reg.number(),
+        Operand::RegList(list) => list,
+        _ => 0,
+    };
+
+    for reg in reg_list(value) {
+        if access != Access::Write {
+            push(read, reg);
+        }
+        if access != Access::Read {
+            push(written, reg);
+        }
+    }
+
+    let (simd, access) = match *operand {
+        Operand::SIMDReg(reg) => (Some((reg, 1, 1)), access),
+        Operand::SIMDRegList(first, count, stride)
+        | Operand::SIMDRegListAllLanes(first, count, stride) => {
+            (Some((first, count, stride)), access)
+        }
+        // writing a single lane leaves the rest of the register as it was
+        Operand::SIMDRegLane(reg, _) if access == Access::Write => {
+            (Some((reg, 1, 1)), Access::ReadWrite)
+        }
+        Operand::SIMDRegLane(reg, _) => (Some((reg, 1, 1)), access),
+        Operand::SIMDRegListLane(first, count, stride, _) if access == Access::Write => {
+            (Some((first, count, stride)), Access::ReadWrite)
+        }
+        Operand::SIMDRegListLane(first, count, stride, _) => (Some((first, count, stride)), access),
+        _ => (None, access),
+    };
+
+    if let Some((first, count, stride)) = simd {
+        for reg in simd_list(first, count, stride) {
+            if access != Access::Write {
+                push_name(read, reg.as_str());
+            }
+            if access != Access::Read {
+                push_name(written, reg.as_str());
+            }
+        }
+    }
+
+    match *operand {
+        Operand::RegShift(shift) => shift_regs(shift, read),
+        Operand::FPSysReg(reg) => {
+            if access != Access::Write {
+                push_name(read, reg.as_str());
+            }
+            if access != Access::Read {
+                push_name(written, reg.as_str());
+            }
+        }
+        Operand::APSRFlags => push_name(written, "cpsr"),
+        Operand::RegDerefAligned(base, _, wback) => {
+            push(read, base);
+            if wback {
+                push(written, base);
+            }
+        }
+        Operand::RegWBack(base, wback) => {
+            push(read, base);
+            if wback {
+                push(written, base);
+            }
+        }
+        Operand::RegDeref(base) => push(read, base),
+        Operand::RegDerefPostindexRegShift(base, shift, _, wback)
+        | Operand::RegDerefPreindexRegShift(base, shift, _, wback) => {
+            push(read, base);
+            shift_regs(shift, read);
+            if wback {
+                push(written, base);
+            }
+        }
+        Operand::RegDerefPostindexOffset(base, _, _, wback)
+        | Operand::RegDerefPreindexOffset(base, _, _, wback) => {
+            push(read, base);
+            if wback {
+                push(written, base);
+            }
+        }
+        Operand::RegDerefPostindexReg(base, index, _, wback)
+        | Operand::RegDerefPreindexReg(base, index, _, wback) => {
+            push(read, base);
+            push(read, index);
+            if wback {
+                push(written, base);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects the registers read and written by an instruction.
+pub(super) fn registers(inst: &Instruction) -> (Vec<&'static str>, Vec<&'static str>) {
+    let mut read = Vec::new();
+    let mut written = Vec::new();
+
+    for (idx, operand) in inst.operands.iter().enumerate() {
+        operand_regs(operand, access(inst, idx), &mut read, &mut written);
+    }
+
+    match inst.opcode {
+        Opcode::PUSH | Opcode::POP | Opcode::VPUSH | Opcode::VPOP => {
+            push(&mut read, Reg::from_u8(SP));
+            push(&mut written, Reg::from_u8(SP));
+        }
+        Opcode::BL | Opcode::BLX => push(&mut written, Reg::from_u8(LR)),
+        // the comparison result is left in `fpscr` for `vmrs` to copy out
+        Opcode::VCMP(_) | Opcode::VCMPE(_) => push_name(&mut written, "fpscr"),
+        _ => {}
+    }
+
+    // `vld1.8 {d0}, [r0], r1` adds `r1` to the base after the transfer
+    if let [_, Operand::RegDerefAligned(base, _, _), Operand::Reg(_), _] = inst.operands {
+        push(&mut written, base);
+    }
+
+    let reads_flags = inst.condition != ConditionCode::AL
+        || matches!(
+            inst.opcode,
+            Opcode::ADC | Opcode::SBC | Opcode::RSC | Opcode::RRX
+        );
+    let writes_flags = inst.s
+        || matches!(
+            inst.opcode,
+            Opcode::CMP | Opcode::CMN | Opcode::TST | Opcode::TEQ
+        );
+
+    if reads_flags {
+        read.push("cpsr");
+    }
+
+    if writes_flags {
+        push_name(&mut written, "cpsr");
+    }
+
+    (read, written)
+}
+
+fn writes_pc(inst: &Instruction) -> bool {
+    inst.operands.iter().enumerate().any(|(idx, operand)| {
+        let reg = match *operand {
+            Operand::Reg(reg) => reg.number() == PC,
+            Operand::RegList(list) => list & (1 << PC) != 0,
+            _ => false,
+        };
+
+        reg && access(inst, idx) != Access::Read
+    })
+}
+
+pub(super) fn flow(inst: &Instruction) -> FlowKind {
+    let flow = match inst.opcode {
+        Opcode::B | Opcode::BXJ | Opcode::TBB | Opcode::TBH => FlowKind::Jump,
+        Opcode::BL | Opcode::BLX => FlowKind::Call,
+        Opcode::BX => match inst.operands[0] {
+            Operand::Reg(reg) if reg.number() == LR => FlowKind::Return,
+            _ => FlowKind::Jump,
+        },
+        Opcode::CBZ | Opcode::CBNZ => FlowKind::ConditionalJump,
+        Opcode::ERET | Opcode::RFE(..) => FlowKind::Return,
+        Opcode::SVC | Opcode::BKPT | Opcode::HVC | Opcode::SMC => FlowKind::Interrupt,
+        Opcode::UDF => FlowKind::Halt,
+        Opcode::POP if writes_pc(inst) => FlowKind::Return,
+        Opcode::LDM(..) if writes_pc(inst) => match inst.operands[0] {
+            Operand::RegWBack(base, _) if base.number() == SP => FlowKind::Return,
+            _ => FlowKind::Jump,
+        },
+        // `ldr pc, [sp], #4` is a single register pop
+        Opcode::LDR if writes_pc(inst) => match inst.operands[1] {
+            Operand::RegDerefPostindexOffset(base, _, _, _) if base.number() == SP => {
+                FlowKind::Return
+            }
+            _ => FlowKind::Jump,
+        },
+        Opcode::MOV if writes_pc(inst) => match inst.operands[1] {
+            Operand::Reg(reg) if reg.number() == LR => FlowKind::Return,
+            _ => FlowKind::Jump,
+        },
+        _ if writes_pc(inst) => FlowKind::Jump,
+        _ => FlowKind::Sequential,
+    };
+
+    match flow {
+        FlowKind::Jump | FlowKind::Return if inst.condition != ConditionCode::AL => {
+            FlowKind::ConditionalJump
+        }
+        flow => flow,
+    }
+}
+
+pub(super) fn targets(inst: &Instruction) -> Vec<usize> {
+    if !matches!(
+        flow(inst),
+        FlowKind::Jump | FlowKind::ConditionalJump | FlowKind::Call
+    ) {
+        return Vec::new();
+    }
+
+    inst.operands
+        .iter()
+        .filter_map(|operand| match operand {
+            Operand::Imm64(addr) => Some(*addr as usize),
+            _ => None,
+        })
+        .collect()
+}
+
+pub(super) fn memory(inst: &Instruction) -> Option<MemoryAccess> {
+    let list_size = || {
+        inst.operands.iter().find_map(|operand| match operand {
+            Operand::RegList(list) => Some(list.count_ones() as usize * 4),
+            _ => None,
+        })
+    };
+
+    let reg_size = || match inst.operands[0] {
+        Operand::SIMDReg(reg) => Some(simd_size(reg)),
+        _ => None,
+    };
+
+    let simd_list_size = || {
+        inst.operands.iter().find_map(|operand| match *operand {
+            Operand::SIMDRegList(first, count, _) => Some(count as usize * simd_size(first)),
+            _ => None,
+        })
+    };
+
+    // `vld2.16 {d0[1], d1[1]}, [r0]` transfers one element per register
+    let structure_size = || {
+        let elements = match inst.operands[0] {
+            Operand::SIMDRegList(first, count, _) => {
+                return Some(count as usize * simd_size(first))
+            }
+            Operand::SIMDRegListLane(_, count, _, _) => count as usize,
+            Operand::SIMDRegListAllLanes(_, count, _) => match inst.opcode {
+                // `vld1.8 {d0[], d1[]}, [r0]` loads a single element into both registers
+                Opcode::VLD1(_) => 1,
+                _ => count as usize,
+            },
+            _ => return None,
+        };
+        inst.opcode.data_types()[0].map(|dt| elements * dt.width())
+    };
+
+    let (size, direction) = match inst.opcode {
+        Opcode::LDR | Opcode::LDRT | Opcode::LDREX => (Some(4), Direction::Read),
+        Opcode::STR | Opcode::STRT | Opcode::STREX => (Some(4), Direction::Write),
+        Opcode::LDRH | Opcode::LDRSH | Opcode::LDRSHT | Opcode::LDRHT | Opcode::LDREXH => {
+            (Some(2), Direction::Read)
+        }
+        Opcode::STRH | Opcode::STRHT | Opcode::STREXH => (Some(2), Direction::Write),
+        Opcode::LDRB | Opcode::LDRSB | Opcode::LDRSBT | Opcode::LDRBT | Opcode::LDREXB => {
+            (Some(1), Direction::Read)
+        }
+        Opcode::STRB | Opcode::STRBT | Opcode::STREXB => (Some(1), Direction::Write),
+        Opcode::LDRD | Opcode::LDREXD => (Some(8), Direction::Read),
+        Opcode::STRD | Opcode::STREXD => (Some(8), Direction::Write),
+        Opcode::SWP => (Some(4), Direction::ReadWrite),
+        Opcode::SWPB => (Some(1), Direction::ReadWrite),
+        Opcode::TBB => (Some(1), Direction::Read),
+        Opcode::TBH => (Some(2), Direction::Read),
+        Opcode::LDM(..) | Opcode::POP => (list_size(), Direction::Read),
+        Opcode::STM(..) | Opcode::PUSH => (list_size(), Direction::Write),
+        Opcode::RFE(..) => (Some(8), Direction::Read),
+        Opcode::SRS(..) => (Some(8), Direction::Write),
+        Opcode::LDC(_) | Opcode::LDCL(_) | Opcode::LDC2(_) | Opcode::LDC2L(_) => {
+            (None, Direction::Read)
+        }
+        Opcode::STC(_) | Opcode::STCL(_) | Opcode::STC2(_) | Opcode::STC2L(_) => {
+            (None, Direction::Write)
+        }
+        Opcode::VLDR => (reg_size(), Direction::Read),
+        Opcode::VSTR => (reg_size(), Direction::Write),
+        Opcode::VLDM(_) | Opcode::VPOP => (simd_list_size(), Direction::Read),
+        Opcode::VSTM(_) | Opcode::VPUSH => (simd_list_size(), Direction::Write),
+        Opcode::VLD1(_) | Opcode::VLD2(_) | Opcode::VLD3(_) | Opcode::VLD4(_) => {
+            (structure_size(), Direction::Read)
+        }
+        Opcode::VST1(_) | Opcode::VST2(_) | Opcode::VST3(_) | Opcode::VST4(_) => {
+            (structure_size(), Direction::Write)
+        }
+        _ => return None,
+    };
+
+    Some(MemoryAccess { size, direction })
+}
@@ -0,0 +1,1673 @@
+//! Floating-point (`VFPv3`/`VFPv4`) and Advanced SIMD decoding, shared by the `arm` and `thumb`
+//! decoders.
+//!
+//! Everything here takes instructions in their `arm` layout. `thumb` encodings of these
+//! instructions only differ in their top byte, which `thumb::read` rewrites before calling in.
+
+use crate::armv7::{DataType, Decoder, FPSysReg, Instruction, Opcode, Operand, Reg, SIMDReg};
+
+use decoder::ErrorKind;
+
+const INT_TYPES: [DataType; 4] = [DataType::I8, DataType::I16, DataType::I32, DataType::I64];
+const SIGNED_TYPES: [DataType; 4] = [DataType::S8, DataType::S16, DataType::S32, DataType::S64];
+const UNSIGNED_TYPES: [DataType; 4] = [DataType::U8, DataType::U16, DataType::U32, DataType::U64];
+const SIZE_TYPES: [DataType; 4] = [
+    DataType::Size8,
+    DataType::Size16,
+    DataType::Size32,
+    DataType::Size64,
+];
+
+fn int_type(size: u32) -> DataType {
+    INT_TYPES[size as usize]
+}
+
+fn signed_type(size: u32) -> DataType {
+    SIGNED_TYPES[size as usize]
+}
+
+fn sign_type(size: u32, unsigned: bool) -> DataType {
+    if unsigned {
+        UNSIGNED_TYPES[size as usize]
+    } else {
+        SIGNED_TYPES[size as usize]
+    }
+}
+
+fn size_type(size: u32) -> DataType {
+    SIZE_TYPES[size as usize]
+}
+
+/// `D:Vd`, the destination register of an advanced simd or double-precision instruction.
+fn vd(word: u32) -> u8 {
+    ((((word >> 22) & 1) << 4) | ((word >> 12) & 0b1111)) as u8
+}
+
+/// `N:Vn`, the first source register of an advanced simd or double-precision instruction.
+fn vn(word: u32) -> u8 {
+    ((((word >> 7) & 1) << 4) | ((word >> 16) & 0b1111)) as u8
+}
+
+/// `M:Vm`, the second source register of an advanced simd or double-precision instruction.
+fn vm(word: u32) -> u8 {
+    ((((word >> 5) & 1) << 4) | (word & 0b1111)) as u8
+}
+
+/// `Vd:D`, the destination register of a single-precision instruction.
+fn sd(word: u32) -> u8 {
+    ((((word >> 12) & 0b1111) << 1) | ((word >> 22) & 1)) as u8
+}
+
+/// `Vn:N`, the first source register of a single-precision instruction.
+fn sn(word: u32) -> u8 {
+    ((((word >> 16) & 0b1111) << 1) | ((word >> 7) & 1)) as u8
+}
+
+/// `Vm:M`, the second source register of a single-precision instruction.
+fn sm(word: u32) -> u8 {
+    (((word & 0b1111) << 1) | ((word >> 5) & 1)) as u8
+}
+
+fn dreg(num: u8) -> Operand {
+    Operand::SIMDReg(SIMDReg::double(num))
+}
+
+fn sreg(num: u8) -> Operand {
+    Operand::SIMDReg(SIMDReg::single(num))
+}
+
+/// the quadword register starting at `d<num>`. `q` registers are encoded as the even `d` register
+/// they start at; odd register numbers are `UNDEFINED`.
+fn qreg(num: u8) -> Result<Operand, ErrorKind> {
+    if num & 1 != 0 {
+        return Err(ErrorKind::Undefined);
+    }
+    Ok(Operand::SIMDReg(SIMDReg::quad(num >> 1)))
+}
+
+/// a `d` register, or a `q` register if `q` is set.
+fn vreg(num: u8, q: bool) -> Result<Operand, ErrorKind> {
+    if q {
+        qreg(num)
+    } else {
+        Ok(dreg(num))
+    }
+}
+
+/// a single-precision register, or a double-precision register if `double` is set.
+fn freg(single: u8, double: u8, is_double: bool) -> Operand {
+    if is_double {
+        dreg(double)
+    } else {
+        sreg(single)
+    }
+}
+
+#[allow(non_snake_case)]
+fn VFPExpandImm(imm8: u8) -> u32 {
+    let sign = (imm8 >> 7) as u32;
+    let b = (imm8 >> 6) as u32 & 1;
+    let exp =
+        ((b ^ 1) << 7) | (if b != 0 { 0b11111 << 2 } else { 0 }) | ((imm8 >> 4) as u32 & 0b11);
+    let frac = (imm8 as u32 & 0b1111) << 19;
+
+    (sign << 31) | (exp << 23) | frac
+}
+
+#[allow(non_snake_case)]
+fn AdvSIMDExpandImm(op: bool, cmode: u32, imm8: u32) -> Result<(Opcode, Operand), ErrorKind> {
+    let imm8 = imm8 as u64;
+    let (dt, imm) = match cmode {
+        0b0000 | 0b0001 => (DataType::I32, imm8),
+        0b0010 | 0b0011 => (DataType::I32, imm8 << 8),
+        0b0100 | 0b0101 => (DataType::I32, imm8 << 16),
+        0b0110 | 0b0111 => (DataType::I32, imm8 << 24),
+        0b1000 | 0b1001 => (DataType::I16, imm8),
+        0b1010 | 0b1011 => (DataType::I16, imm8 << 8),
+        0b1100 => (DataType::I32, (imm8 << 8) | 0xff),
+        0b1101 => (DataType::I32, (imm8 << 16) | 0xffff),
+        0b1110 => {
+            if op {
+                let mut imm = 0u64;
+                for byte in 0..8 {
+                    if imm8 & (1 << byte) != 0 {
+                        imm |= 0xff << (byte * 8);
+                    }
+                }
+                return Ok((Opcode::VMOV(Some(DataType::I64)), Operand::SIMDImm(imm)));
+            }
+            (DataType::I8, imm8)
+        }
+        _ => {
+            if op {
+                return Err(ErrorKind::Undefined);
+            }
+            let imm = Operand::FloatImm(VFPExpandImm(imm8 as u8));
+            return Ok((Opcode::VMOV(Some(DataType::F32)), imm));
+        }
+    };
+
+    let opcode = if cmode & 1 != 0 && cmode < 0b1100 {
+        if op {
+            Opcode::VBIC(Some(dt))
+        } else {
+            Opcode::VORR(Some(dt))
+        }
+    } else if op {
+        Opcode::VMVN(Some(dt))
+    } else {
+        Opcode::VMOV(Some(dt))
+    };
+
+    Ok((opcode, Operand::SIMDImm(imm)))
+}
+
+/// `Advanced SIMD data-processing instructions` (`A7-261`), for `arm` encodings `1111001U` and
+/// `thumb` encodings `111U1111`.
+pub(super) fn decode_data_processing(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let u = (word >> 24) & 1 != 0;
+    let a = (word >> 19) & 0b11111;
+    let b = (word >> 8) & 0b1111;
+    let c = (word >> 4) & 0b1111;
+
+    if a & 0b10000 == 0 {
+        return three_registers_same_length(word, inst);
+    }
+
+    if c & 0b0001 != 0 {
+        if a & 0b10111 == 0b10000 && c & 0b1000 == 0 {
+            return one_register_and_modified_immediate(word, inst);
+        }
+        return two_registers_and_shift(word, inst);
+    }
+
+    if a & 0b00110 != 0b00110 {
+        if c & 0b0100 == 0 {
+            return three_registers_different_lengths(word, inst);
+        }
+        return two_registers_and_scalar(word, inst);
+    }
+
+    let q = (word >> 6) & 1 != 0;
+    if !u {
+        // `VEXT` (`A8-890`)
+        let imm4 = (word >> 8) & 0b1111;
+        if !q && imm4 & 0b1000 != 0 {
+            return Err(ErrorKind::Undefined);
+        }
+        inst.opcode = Opcode::VEXT(DataType::Size8);
+        inst.operands = [
+            vreg(vd(word), q)?,
+            vreg(vn(word), q)?,
+            vreg(vm(word), q)?,
+            Operand::Imm32(imm4),
+        ];
+        return Ok(());
+    }
+
+    if b & 0b1000 == 0 {
+        two_registers_misc(word, inst)
+    } else if b & 0b1100 == 0b1000 {
+        // `VTBL, VTBX` (`A8-1096`)
+        let len = ((word >> 8) & 0b11) as u8 + 1;
+        let n = vn(word);
+        if n + len > 32 {
+            return Err(ErrorKind::Unpredictable);
+        }
+        inst.opcode = if (word >> 6) & 1 == 0 {
+            Opcode::VTBL(DataType::Size8)
+        } else {
+            Opcode::VTBX(DataType::Size8)
+        };
+        inst.operands = [
+            dreg(vd(word)),
+            Operand::SIMDRegList(SIMDReg::double(n), len, 1),
+            dreg(vm(word)),
+            Operand::Nothing,
+        ];
+        Ok(())
+    } else if b == 0b1100 && c & 0b1000 == 0 {
+        // `VDUP (scalar)` (`A8-884`)
+        let imm4 = (word >> 16) & 0b1111;
+        let (dt, lane) = if imm4 & 0b0001 != 0 {
+            (DataType::Size8, imm4 >> 1)
+        } else if imm4 & 0b0010 != 0 {
+            (DataType::Size16, imm4 >> 2)
+        } else if imm4 & 0b0100 != 0 {
+            (DataType::Size32, imm4 >> 3)
+        } else {
+            return Err(ErrorKind::Undefined);
+        };
+        inst.opcode = Opcode::VDUP(dt);
+        inst.operands = [
+            vreg(vd(word), q)?,
+            Operand::SIMDRegLane(SIMDReg::double(vm(word)), lane as u8),
+            Operand::Nothing,
+            Operand::Nothing,
+        ];
+        Ok(())
+    } else {
+        Err(ErrorKind::Undefined)
+    }
+}
+
+/// `Three registers of the same length` (`A7-262`)
+fn three_registers_same_length(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let u = (word >> 24) & 1 != 0;
+    let a = (word >> 8) & 0b1111;
+    let b = (word >> 4) & 1 != 0;
+    let c = (word >> 20) & 0b11;
+    let q = (word >> 6) & 1 != 0;
+    let size = c;
+
+    // integer forms that do not have a 64-bit variant
+    let no_i64 = || {
+        if size == 0b11 {
+            Err(ErrorKind::Undefined)
+        } else {
+            Ok(())
+        }
+    };
+    // single-precision forms, with `sz` in the low bit of `C`
+    let f32_only = || {
+        if c & 1 != 0 {
+            Err(ErrorKind::Undefined)
+        } else {
+            Ok(DataType::F32)
+        }
+    };
+    // pairwise operations only operate on doubleword registers
+    let no_q = || if q { Err(ErrorKind::Undefined) } else { Ok(()) };
+
+    // shifts by register take `<Vm>` before `<Vn>`
+    let mut swap = false;
+
+    let opcode = match (a, b) {
+        (0b0000, false) => {
+            no_i64()?;
+            Opcode::VHADD(sign_type(size, u))
+        }
+        (0b0000, true) => Opcode::VQADD(sign_type(size, u)),
+        (0b0001, false) => {
+            no_i64()?;
+            Opcode::VRHADD(sign_type(size, u))
+        }
+        (0b0001, true) => match (u, c) {
+            (false, 0b00) => Opcode::VAND,
+            (false, 0b01) => Opcode::VBIC(None),
+            (false, 0b10) => {
+                if vn(word) == vm(word) {
+                    // `VMOV (register)` is `VORR` with both sources the same register.
+                    inst.opcode = Opcode::VMOV(None);
+                    inst.operands = [
+                        vreg(vd(word), q)?,
+                        vreg(vm(word), q)?,
+                        Operand::Nothing,
+                        Operand::Nothing,
+                    ];
+                    return Ok(());
+                }
+                Opcode::VORR(None)
+            }
+            (false, _) => Opcode::VORN,
+            (true, 0b00) => Opcode::VEOR,
+            (true, 0b01) => Opcode::VBSL,
+            (true, 0b10) => Opcode::VBIT,
+            (true, _) => Opcode::VBIF,
+        },
+        (0b0010, false) => {
+            no_i64()?;
+            Opcode::VHSUB(sign_type(size, u))
+        }
+        (0b0010, true) => Opcode::VQSUB(sign_type(size, u)),
+        (0b0011, false) => {
+            no_i64()?;
+            Opcode::VCGT(sign_type(size, u))
+        }
+        (0b0011, true) => {
+            no_i64()?;
+            Opcode::VCGE(sign_type(size, u))
+        }
+        (0b0100, false) => {
+            swap = true;
+            Opcode::VSHL(sign_type(size, u))
+        }
+        (0b0100, true) => {
+            swap = true;
+            Opcode::VQSHL(sign_type(size, u))
+        }
+        (0b0101, false) => {
+            swap = true;
+            Opcode::VRSHL(sign_type(size, u))
+        }
+        (0b0101, true) => {
+            swap = true;
+            Opcode::VQRSHL(sign_type(size, u))
+        }
+        (0b0110, false) => {
+            no_i64()?;
+            Opcode::VMAX(sign_type(size, u))
+        }
+        (0b0110, true) => {
+            no_i64()?;
+            Opcode::VMIN(sign_type(size, u))
+        }
+        (0b0111, false) => {
+            no_i64()?;
+            Opcode::VABD(sign_type(size, u))
+        }
+        (0b0111, true) => {
+            no_i64()?;
+            Opcode::VABA(sign_type(size, u))
+        }
+        (0b1000, false) => {
+            if u {
+                Opcode::VSUB(int_type(size))
+            } else {
+                Opcode::VADD(int_type(size))
+            }
+        }
+        (0b1000, true) => {
+            no_i64()?;
+            if u {
+                Opcode::VCEQ(int_type(size))
+            } else {
+                Opcode::VTST(size_type(size))
+            }
+        }
+        (0b1001, false) => {
+            no_i64()?;
+            if u {
+                Opcode::VMLS(int_type(size))
+            } else {
+                Opcode::VMLA(int_type(size))
+            }
+        }
+        (0b1001, true) => {
+            no_i64()?;
+            if u {
+                if size != 0b00 {
+                    return Err(ErrorKind::Undefined);
+                }
+                Opcode::VMUL(DataType::P8)
+            } else {
+                Opcode::VMUL(int_type(size))
+            }
+        }
+        (0b1010, false) => {
+            no_i64()?;
+            no_q()?;
+            Opcode::VPMAX(sign_type(size, u))
+        }
+        (0b1010, true) => {
+            no_i64()?;
+            no_q()?;
+            Opcode::VPMIN(sign_type(size, u))
+        }
+        (0b1011, false) => {
+            if size == 0b00 || size == 0b11 {
+                return Err(ErrorKind::Undefined);
+            }
+            if u {
+                Opcode::VQRDMULH(signed_type(size))
+            } else {
+                Opcode::VQDMULH(signed_type(size))
+            }
+        }
+        (0b1011, true) => {
+            if u {
+                return Err(ErrorKind::Undefined);
+            }
+            no_i64()?;
+            no_q()?;
+            Opcode::VPADD(int_type(size))
+        }
+        (0b1100, true) if !u => {
+            if c & 0b10 == 0 {
+                Opcode::VFMA(f32_only()?)
+            } else {
+                Opcode::VFMS(f32_only()?)
+            }
+        }
+        (0b1101, false) => match (u, c & 0b10 != 0) {
+            (false, false) => Opcode::VADD(f32_only()?),
+            (false, true) => Opcode::VSUB(f32_only()?),
+            (true, false) => {
+                no_q()?;
+                Opcode::VPADD(f32_only()?)
+            }
+            (true, true) => Opcode::VABD(f32_only()?),
+        },
+        (0b1101, true) => match (u, c & 0b10 != 0) {
+            (false, false) => Opcode::VMLA(f32_only()?),
+            (false, true) => Opcode::VMLS(f32_only()?),
+            (true, false) => Opcode::VMUL(f32_only()?),
+            (true, true) => return Err(ErrorKind::Undefined),
+        },
+        (0b1110, false) => match (u, c & 0b10 != 0) {
+            (false, false) => Opcode::VCEQ(f32_only()?),
+            (false, true) => return Err(ErrorKind::Undefined),
+            (true, false) => Opcode::VCGE(f32_only()?),
+            (true, true) => Opcode::VCGT(f32_only()?),
+        },
+        (0b1110, true) => match (u, c & 0b10 != 0) {
+            (false, _) => return Err(ErrorKind::Undefined),
+            (true, false) => Opcode::VACGE(f32_only()?),
+            (true, true) => Opcode::VACGT(f32_only()?),
+        },
+        (0b1111, false) => match (u, c & 0b10 != 0) {
+            (false, false) => Opcode::VMAX(f32_only()?),
+            (false, true) => Opcode::VMIN(f32_only()?),
+            (true, false) => {
+                no_q()?;
+                Opcode::VPMAX(f32_only()?)
+            }
+            (true, true) => {
+                no_q()?;
+                Opcode::VPMIN(f32_only()?)
+            }
+        },
+        (0b1111, true) => match (u, c & 0b10 != 0) {
+            (false, false) => Opcode::VRECPS(f32_only()?),
+            (false, true) => Opcode::VRSQRTS(f32_only()?),
+            (true, _) => return Err(ErrorKind::Undefined),
+        },
+        _ => {
+            return Err(ErrorKind::Undefined);
+        }
+    };
+
+    let (n, m) = if swap {
+        (vm(word), vn(word))
+    } else {
+        (vn(word), vm(word))
+    };
+
+    inst.opcode = opcode;
+    inst.operands = [
+        vreg(vd(word), q)?,
+        vreg(n, q)?,
+        vreg(m, q)?,
+        Operand::Nothing,
+    ];
+    Ok(())
+}
+
+/// `Three registers of different lengths` (`A7-264`)
+fn three_registers_different_lengths(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let u = (word >> 24) & 1 != 0;
+    let a = (word >> 8) & 0b1111;
+    let size = (word >> 20) & 0b11;
+
+    #[derive(PartialEq)]
+    enum Shape {
+        // `<Qd>, <Dn>, <Dm>`
+        Long,
+        // `<Qd>, <Qn>, <Dm>`
+        Wide,
+        // `<Dd>, <Qn>, <Qm>`
+        Narrow,
+    }
+
+    let saturating = || {
+        if size == 0b00 {
+            Err(ErrorKind::Undefined)
+        } else {
+            Ok(signed_type(size))
+        }
+    };
+
+    let (opcode, shape) = match a {
+        0b0000 => (Opcode::VADDL(sign_type(size, u)), Shape::Long),
+        0b0001 => (Opcode::VADDW(sign_type(size, u)), Shape::Wide),
+        0b0010 => (Opcode::VSUBL(sign_type(size, u)), Shape::Long),
+        0b0011 => (Opcode::VSUBW(sign_type(size, u)), Shape::Wide),
+        0b0100 => {
+            if u {
+                (Opcode::VRADDHN(int_type(size + 1)), Shape::Narrow)
+            } else {
+                (Opcode::VADDHN(int_type(size + 1)), Shape::Narrow)
+            }
+        }
+        0b0101 => (Opcode::VABAL(sign_type(size, u)), Shape::Long),
+        0b0110 => {
+            if u {
+                (Opcode::VRSUBHN(int_type(size + 1)), Shape::Narrow)
+            } else {
+                (Opcode::VSUBHN(int_type(size + 1)), Shape::Narrow)
+            }
+        }
+        0b0111 => (Opcode::VABDL(sign_type(size, u)), Shape::Long),
+        0b1000 => (Opcode::VMLAL(sign_type(size, u)), Shape::Long),
+        0b1010 => (Opcode::VMLSL(sign_type(size, u)), Shape::Long),
+        0b1001 if !u => (Opcode::VQDMLAL(saturating()?), Shape::Long),
+        0b1011 if !u => (Opcode::VQDMLSL(saturating()?), Shape::Long),
+        0b1100 => (Opcode::VMULL(sign_type(size, u)), Shape::Long),
+        0b1101 if !u => (Opcode::VQDMULL(saturating()?), Shape::Long),
+        0b1110 if !u => {
+            if size != 0b00 {
+                return Err(ErrorKind::Undefined);
+            }
+            (Opcode::VMULL(DataType::P8), Shape::Long)
+        }
+        _ => {
+            return Err(ErrorKind::Undefined);
+        }
+    };
+
+    inst.opcode = opcode;
+    inst.operands = match shape {
+        Shape::Long => [
+            qreg(vd(word))?,
+            dreg(vn(word)),
+            dreg(vm(word)),
+            Operand::Nothing,
+        ],
+        Shape::Wide => [
+            qreg(vd(word))?,
+            qreg(vn(word))?,
+            dreg(vm(word)),
+            Operand::Nothing,
+        ],
+        Shape::Narrow => [
+            dreg(vd(word)),
+            qreg(vn(word))?,
+            qreg(vm(word))?,
+            Operand::Nothing,
+        ],
+    };
+    Ok(())
+}
+
+/// `Two registers and a scalar` (`A7-265`)
+fn two_registers_and_scalar(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let u = (word >> 24) & 1 != 0;
+    let a = (word >> 8) & 0b1111;
+    let size = (word >> 20) & 0b11;
+
+    let scalar = match size {
+        0b01 => Operand::SIMDRegLane(
+            SIMDReg::double((word & 0b111) as u8),
+            ((((word >> 5) & 1) << 1) | ((word >> 3) & 1)) as u8,
+        ),
+        0b10 => Operand::SIMDRegLane(
+            SIMDReg::double((word & 0b1111) as u8),
+            ((word >> 5) & 1) as u8,
+        ),
+        _ => return Err(ErrorKind::Undefined),
+    };
+
+    // `F` selects the floating-point form, which only exists for 32-bit elements.
+    let float = || {
+        if size == 0b10 {
+            Ok(DataType::F32)
+        } else {
+            Err(ErrorKind::Undefined)
+        }
+    };
+    let saturating = || {
+        if u {
+            Err(ErrorKind::Undefined)
+        } else {
+            Ok(signed_type(size))
+        }
+    };
+
+    // `true` for forms writing a quadword from doubleword sources
+    let (opcode, long) = match a {
+        0b0000 => (Opcode::VMLA(int_type(size)), false),
+        0b0001 => (Opcode::VMLA(float()?), false),
+        0b0100 => (Opcode::VMLS(int_type(size)), false),
+        0b0101 => (Opcode::VMLS(float()?), false),
+        0b0010 => (Opcode::VMLAL(sign_type(size, u)), true),
+        0b0110 => (Opcode::VMLSL(sign_type(size, u)), true),
+        0b0011 => (Opcode::VQDMLAL(saturating()?), true),
+        0b0111 => (Opcode::VQDMLSL(saturating()?), true),
+        0b1000 => (Opcode::VMUL(int_type(size)), false),
+        0b1001 => (Opcode::VMUL(float()?), false),
+        0b1010 => (Opcode::VMULL(sign_type(size, u)), true),
+        0b1011 => (Opcode::VQDMULL(saturating()?), true),
+        0b1100 => (Opcode::VQDMULH(signed_type(size)), false),
+        0b1101 => (Opcode::VQRDMULH(signed_type(size)), false),
+        _ => {
+            return Err(ErrorKind::Undefined);
+        }
+    };
+
+    inst.opcode = opcode;
+    inst.operands = if long {
+        [qreg(vd(word))?, dreg(vn(word)), scalar, Operand::Nothing]
+    } else {
+        // `Q` is encoded in the `U` position for these
+        [
+            vreg(vd(word), u)?,
+            vreg(vn(word), u)?,
+            scalar,
+            Operand::Nothing,
+        ]
+    };
+    Ok(())
+}
+
+/// `Two registers and a shift amount` (`A7-266`)
+fn two_registers_and_shift(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let u = (word >> 24) & 1 != 0;
+    let a = (word >> 8) & 0b1111;
+    let q = (word >> 6) & 1 != 0;
+    let l = (word >> 7) & 1 != 0;
+    let imm6 = (word >> 16) & 0b111111;
+
+    let size = if l {
+        0b11
+    } else if imm6 & 0b100000 != 0 {
+        0b10
+    } else if imm6 & 0b010000 != 0 {
+        0b01
+    } else if imm6 & 0b001000 != 0 {
+        0b00
+    } else {
+        return Err(ErrorKind::Undefined);
+    };
+    let esize = 8 << size;
+    // shift amounts as encoded for right and left shifts respectively
+    let right = if l { 64 - imm6 } else { esize * 2 - imm6 };
+    let left = if l { imm6 } else { imm6 - esize };
+
+    let (opcode, shift) = match a {
+        0b0000 => (Opcode::VSHR(sign_type(size, u)), right),
+        0b0001 => (Opcode::VSRA(sign_type(size, u)), right),
+        0b0010 => (Opcode::VRSHR(sign_type(size, u)), right),
+        0b0011 => (Opcode::VRSRA(sign_type(size, u)), right),
+        0b0100 if u => (Opcode::VSRI(size_type(size)), right),
+        0b0101 => {
+            if u {
+                (Opcode::VSLI(size_type(size)), left)
+            } else {
+                (Opcode::VSHL(int_type(size)), left)
+            }
+        }
+        0b0110 if u => (Opcode::VQSHLU(signed_type(size)), left),
+        0b0111 => (Opcode::VQSHL(sign_type(size, u)), left),
+        0b1000 | 0b1001 => {
+            // narrowing shifts, from quadword elements of twice the size
+            if l {
+                return Err(ErrorKind::Undefined);
+            }
+            let opcode = match (a & 1 != 0, u, q) {
+                (false, false, false) => Opcode::VSHRN(int_type(size + 1)),
+                (false, false, true) => Opcode::VRSHRN(int_type(size + 1)),
+                (false, true, false) => Opcode::VQSHRUN(signed_type(size + 1)),
+                (false, true, true) => Opcode::VQRSHRUN(signed_type(size + 1)),
+                (true, u, false) => Opcode::VQSHRN(sign_type(size + 1, u)),
+                (true, u, true) => Opcode::VQRSHRN(sign_type(size + 1, u)),
+            };
+            inst.opcode = opcode;
+            inst.operands = [
+                dreg(vd(word)),
+                qreg(vm(word))?,
+                Operand::Imm32(right),
+                Operand::Nothing,
+            ];
+            return Ok(());
+        }
+        0b1010 => {
+            if l || q {
+                return Err(ErrorKind::Undefined);
+            }
+            if left == 0 {
+                inst.opcode = Opcode::VMOVL(sign_type(size, u));
+                inst.operands = [
+                    qreg(vd(word))?,
+                    dreg(vm(word)),
+                    Operand::Nothing,
+                    Operand::Nothing,
+                ];
+            } else {
+                inst.opcode = Opcode::VSHLL(sign_type(size, u));
+                inst.operands = [
+                    qreg(vd(word))?,
+                    dreg(vm(word)),
+                    Operand::Imm32(left),
+                    Operand::Nothing,
+                ];
+            }
+            return Ok(());
+        }
+        0b1110 | 0b1111 => {
+            // `VCVT (between floating-point and fixed-point, Advanced SIMD)` (`A8-872`)
+            if l || imm6 & 0b100000 == 0 {
+                return Err(ErrorKind::Undefined);
+            }
+            let fixed = if u { DataType::U32 } else { DataType::S32 };
+            let opcode = if a & 1 == 0 {
+                Opcode::VCVT(DataType::F32, fixed)
+            } else {
+                Opcode::VCVT(fixed, DataType::F32)
+            };
+            (opcode, 64 - imm6)
+        }
+        _ => {
+            return Err(ErrorKind::Undefined);
+        }
+    };
+
+    inst.opcode = opcode;
+    inst.operands = [
+        vreg(vd(word), q)?,
+        vreg(vm(word), q)?,
+        Operand::Imm32(shift),
+        Operand::Nothing,
+    ];
+    Ok(())
+}
+
+/// `Two registers, miscellaneous` (`A7-267`)
+fn two_registers_misc(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let a = (word >> 16) & 0b11;
+    let b = (word >> 6) & 0b11111;
+    let size = (word >> 18) & 0b11;
+    let q = (word >> 6) & 1 != 0;
+
+    let sized = |max: u32| {
+        if size > max {
+            Err(ErrorKind::Undefined)
+        } else {
+            Ok(size)
+        }
+    };
+
+    enum Shape {
+        // `<Vd>, <Vm>`
+        Same,
+        // `<Vd>, <Vm>, #0`
+        CompareZero,
+        // `<Dd>, <Qm>`
+        Narrow,
+    }
+
+    let (opcode, shape) = match a {
+        0b00 => match b >> 1 {
+            0b0000 => (Opcode::VREV64(size_type(sized(0b10)?)), Shape::Same),
+            0b0001 => (Opcode::VREV32(size_type(sized(0b01)?)), Shape::Same),
+            0b0010 => (Opcode::VREV16(size_type(sized(0b00)?)), Shape::Same),
+            0b0100 | 0b0101 => (
+                Opcode::VPADDL(sign_type(sized(0b10)?, b & 0b10 != 0)),
+                Shape::Same,
+            ),
+            0b1000 => (Opcode::VCLS(signed_type(sized(0b10)?)), Shape::Same),
+            0b1001 => (Opcode::VCLZ(int_type(sized(0b10)?)), Shape::Same),
+            0b1010 => (Opcode::VCNT(size_type(sized(0b00)?)), Shape::Same),
+            0b1011 => {
+                sized(0b00)?;
+                (Opcode::VMVN(None), Shape::Same)
+            }
+            0b1100 | 0b1101 => (
+                Opcode::VPADAL(sign_type(sized(0b10)?, b & 0b10 != 0)),
+                Shape::Same,
+            ),
+            0b1110 => (Opcode::VQABS(signed_type(sized(0b10)?)), Shape::Same),
+            0b1111 => (Opcode::VQNEG(signed_type(sized(0b10)?)), Shape::Same),
+            _ => return Err(ErrorKind::Undefined),
+        },
+        0b01 => {
+            let float = b & 0b10000 != 0;
+            let dt = |int: DataType| {
+                if float {
+                    if size != 0b10 {
+                        return Err(ErrorKind::Undefined);
+                    }
+                    Ok(DataType::F32)
+                } else {
+                    sized(0b10)?;
+                    Ok(int)
+                }
+            };
+            match (b >> 1) & 0b111 {
+                0b000 => (Opcode::VCGT(dt(signed_type(size))?), Shape::CompareZero),
+                0b001 => (Opcode::VCGE(dt(signed_type(size))?), Shape::CompareZero),
+                0b010 => (Opcode::VCEQ(dt(int_type(size))?), Shape::CompareZero),
+                0b011 => (Opcode::VCLE(dt(signed_type(size))?), Shape::CompareZero),
+                0b100 => (Opcode::VCLT(dt(signed_type(size))?), Shape::CompareZero),
+                0b110 => (Opcode::VABS(dt(signed_type(size))?), Shape::Same),
+                0b111 => (Opcode::VNEG(dt(signed_type(size))?), Shape::Same),
+                _ => return Err(ErrorKind::Undefined),
+            }
+        }
+        0b10 => match b {
+            0b00000 | 0b00001 => {
+                sized(0b00)?;
+                (Opcode::VSWP, Shape::Same)
+            }
+            0b00010 | 0b00011 => (Opcode::VTRN(size_type(sized(0b10)?)), Shape::Same),
+            0b00100 | 0b00101 => {
+                if !q && size == 0b10 {
+                    return Err(ErrorKind::Undefined);
+                }
+                (Opcode::VUZP(size_type(sized(0b10)?)), Shape::Same)
+            }
+            0b00110 | 0b00111 => {
+                if !q && size == 0b10 {
+                    return Err(ErrorKind::Undefined);
+                }
+                (Opcode::VZIP(size_type(sized(0b10)?)), Shape::Same)
+            }
+            0b01000 => (Opcode::VMOVN(int_type(sized(0b10)? + 1)), Shape::Narrow),
+            0b01001 => (
+                Opcode::VQMOVUN(signed_type(sized(0b10)? + 1)),
+                Shape::Narrow,
+            ),
+            0b01010 | 0b01011 => (
+                Opcode::VQMOVN(sign_type(sized(0b10)? + 1, b & 1 != 0)),
+                Shape::Narrow,
+            ),
+            0b01100 => {
+                // `VSHLL` (`A8-1034`), encoding A2: shift by the element size
+                let size = sized(0b10)?;
+                inst.opcode = Opcode::VSHLL(int_type(size));
+                inst.operands = [
+                    qreg(vd(word))?,
+                    dreg(vm(word)),
+                    Operand::Imm32(8 << size),
+                    Operand::Nothing,
+                ];
+                return Ok(());
+            }
+            0b11000 | 0b11100 => {
+                // `VCVT (between half-precision and single-precision, Advanced SIMD)` (`A8-878`)
+                if size != 0b01 {
+                    return Err(ErrorKind::Undefined);
+                }
+                if b & 0b00100 == 0 {
+                    inst.opcode = Opcode::VCVT(DataType::F16, DataType::F32);
+                    inst.operands = [
+                        dreg(vd(word)),
+                        qreg(vm(word))?,
+                        Operand::Nothing,
+                        Operand::Nothing,
+                    ];
+                } else {
+                    inst.opcode = Opcode::VCVT(DataType::F32, DataType::F16);
+                    inst.operands = [
+                        qreg(vd(word))?,
+                        dreg(vm(word)),
+                        Operand::Nothing,
+                        Operand::Nothing,
+                    ];
+                }
+                return Ok(());
+            }
+            _ => return Err(ErrorKind::Undefined),
+        },
+        _ => {
+            if size != 0b10 {
+                return Err(ErrorKind::Undefined);
+            }
+            let float = (word >> 8) & 1 != 0;
+            match b >> 1 {
+                0b1000 | 0b1010 => {
+                    let dt = if float { DataType::F32 } else { DataType::U32 };
+                    (Opcode::VRECPE(dt), Shape::Same)
+                }
+                0b1001 | 0b1011 => {
+                    let dt = if float { DataType::F32 } else { DataType::U32 };
+                    (Opcode::VRSQRTE(dt), Shape::Same)
+                }
+                0b1100..=0b1111 => {
+                    let opcode = match (word >> 7) & 0b11 {
+                        0b00 => Opcode::VCVT(DataType::F32, DataType::S32),
+                        0b01 => Opcode::VCVT(DataType::F32, DataType::U32),
+                        0b10 => Opcode::VCVT(DataType::S32, DataType::F32),
+                        _ => Opcode::VCVT(DataType::U32, DataType::F32),
+                    };
+                    (opcode, Shape::Same)
+                }
+                _ => return Err(ErrorKind::Undefined),
+            }
+        }
+    };
+
+    inst.opcode = opcode;
+    inst.operands = match shape {
+        Shape::Same => [
+            vreg(vd(word), q)?,
+            vreg(vm(word), q)?,
+            Operand::Nothing,
+            Operand::Nothing,
+        ],
+        Shape::CompareZero => [
+            vreg(vd(word), q)?,
+            vreg(vm(word), q)?,
+            Operand::Imm32(0),
+            Operand::Nothing,
+        ],
+        Shape::Narrow => [
+            dreg(vd(word)),
+            qreg(vm(word))?,
+            Operand::Nothing,
+            Operand::Nothing,
+        ],
+    };
+    Ok(())
+}
+
+/// `One register and a modified immediate value` (`A7-269`)
+fn one_register_and_modified_immediate(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let q = (word >> 6) & 1 != 0;
+    let op = (word >> 5) & 1 != 0;
+    let cmode = (word >> 8) & 0b1111;
+    let imm8 = (((word >> 24) & 1) << 7) | (((word >> 16) & 0b111) << 4) | (word & 0b1111);
+
+    let (opcode, imm) = AdvSIMDExpandImm(op, cmode, imm8)?;
+    inst.opcode = opcode;
+    inst.operands = [vreg(vd(word), q)?, imm, Operand::Nothing, Operand::Nothing];
+    Ok(())
+}
+
+/// `Advanced SIMD element or structure load/store instructions` (`A7-275`), for `arm` encodings
+/// `11110100xxx0` and `thumb` encodings `11111001xxx0`.
+pub(super) fn decode_load_store(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let a = (word >> 23) & 1 != 0;
+    let load = (word >> 21) & 1 != 0;
+    let rn = ((word >> 16) & 0b1111) as u8;
+    let rm = (word & 0b1111) as u8;
+    let d = vd(word);
+    let b = (word >> 8) & 0b1111;
+
+    if rn == 15 {
+        return Err(ErrorKind::Unpredictable);
+    }
+
+    // `(structure elements, list)`
+    let (elements, list, align) = if !a {
+        // multiple single elements or structures
+        let size = (word >> 6) & 0b11;
+        let align_bits = (word >> 4) & 0b11;
+        let (elements, count, stride) = match b {
+            0b0111 => (1, 1, 1),
+            0b1010 => (1, 2, 1),
+            0b0110 => (1, 3, 1),
+            0b0010 => (1, 4, 1),
+            0b1000 => (2, 2, 1),
+            0b1001 => (2, 2, 2),
+            0b0011 => (2, 4, 1),
+            0b0100 => (3, 3, 1),
+            0b0101 => (3, 3, 2),
+            0b0000 => (4, 4, 1),
+            0b0001 => (4, 4, 2),
+            _ => return Err(ErrorKind::Undefined),
+        };
+        if elements != 1 && size == 0b11 {
+            return Err(ErrorKind::Undefined);
+        }
+        let undefined_align = match b {
+            // `vld1` of one or three registers, and `vld3`
+            0b0100..=0b0111 => align_bits & 0b10 != 0,
+            0b1000 | 0b1001 => align_bits == 0b11,
+            _ => false,
+        };
+        if undefined_align {
+            return Err(ErrorKind::Undefined);
+        }
+        if d + (count - 1) * stride > 31 {
+            return Err(ErrorKind::Unpredictable);
+        }
+        let align = if align_bits == 0 { 0 } else { 32 << align_bits };
+        (
+            elements,
+            (
+                size,
+                Operand::SIMDRegList(SIMDReg::double(d), count, stride),
+            ),
+            align,
+        )
+    } else if b & 0b1100 == 0b1100 {
+        // single element or structure to all lanes, only loads
+        if !load {
+            return Err(ErrorKind::Undefined);
+        }
+        let elements = ((word >> 8) & 0b11) as u8 + 1;
+        let size = (word >> 6) & 0b11;
+        let t = (word >> 5) & 1 != 0;
+        let align_bit = (word >> 4) & 1 != 0;
+        let esize = 8u16 << size;
+
+        let (count, stride, align, size) = match elements {
+            1 => {
+                if size == 0b11 || (size == 0b00 && align_bit) {
+                    return Err(ErrorKind::Undefined);
+                }
+                (
+                    if t { 2 } else { 1 },
+                    1,
+                    if align_bit { esize } else { 0 },
+                    size,
+                )
+            }
+            2 => {
+                if size == 0b11 {
+                    return Err(ErrorKind::Undefined);
+                }
+                (
+                    2,
+                    if t { 2 } else { 1 },
+                    if align_bit { esize * 2 } else { 0 },
+                    size,
+                )
+            }
+            3 => {
+                if size == 0b11 || align_bit {
+                    return Err(ErrorKind::Undefined);
+                }
+                (3, if t { 2 } else { 1 }, 0, size)
+            }
+            _ => {
+                if size == 0b11 && !align_bit {
+                    return Err(ErrorKind::Undefined);
+                }
+                let align = match (align_bit, size) {
+                    (false, _) => 0,
+                    (true, 0b00) => 32,
+                    (true, 0b11) => 128,
+                    (true, _) => 64,
+                };
+                (4, if t { 2 } else { 1 }, align, size.min(0b10))
+            }
+        };
+        if d + (count - 1) * stride > 31 {
+            return Err(ErrorKind::Unpredictable);
+        }
+        (
+            elements,
+            (
+                size,
+                Operand::SIMDRegListAllLanes(SIMDReg::double(d), count, stride),
+            ),
+            align,
+        )
+    } else {
+        // single element or structure to one lane
+        let elements = ((word >> 8) & 0b11) as u8 + 1;
+        let size = (word >> 10) & 0b11;
+        let index_align = (word >> 4) & 0b1111;
+        let esize = 8u16 << size;
+
+        let lane = (index_align >> (size + 1)) as u8;
+        let spaced = match size {
+            0b00 => false,
+            0b01 => index_align & 0b0010 != 0,
+            _ => index_align & 0b0100 != 0,
+        };
+        let low = index_align & 0b1;
+
+        let (stride, align) = match (elements, size) {
+            (1, 0b00) => {
+                if low != 0 {
+                    return Err(ErrorKind::Undefined);
+                }
+                (1, 0)
+            }
+            (1, 0b01) => {
+                if index_align & 0b0010 != 0 {
+                    return Err(ErrorKind::Undefined);
+                }
+                (1, if low != 0 { 16 } else { 0 })
+            }
+            (1, _) => {
+                let align = match index_align & 0b0111 {
+                    0b000 => 0,
+                    0b011 => 32,
+                    _ => return Err(ErrorKind::Undefined),
+                };
+                (1, align)
+            }
+            (2, 0b10) if index_align & 0b0010 != 0 => return Err(ErrorKind::Undefined),
+            (2, _) => (
+                if spaced { 2 } else { 1 },
+                if low != 0 { esize * 2 } else { 0 },
+            ),
+            (3, 0b10) if index_align & 0b0011 != 0 => return Err(ErrorKind::Undefined),
+            (3, _) => {
+                if low != 0 {
+                    return Err(ErrorKind::Undefined);
+                }
+                (if spaced { 2 } else { 1 }, 0)
+            }
+            (_, 0b10) => {
+                let align = match index_align & 0b0011 {
+                    0b00 => 0,
+                    0b01 => 64,
+                    0b10 => 128,
+                    _ => return Err(ErrorKind::Undefined),
+                };
+                (if spaced { 2 } else { 1 }, align)
+            }
+            (_, _) => (
+                if spaced { 2 } else { 1 },
+                if low != 0 { esize * 4 } else { 0 },
+            ),
+        };
+        let align = match (elements, size) {
+            // `VLD4`/`VST4` of 16-bit elements align to 64 bits, not 4 * 16
+            (4, 0b01) if align != 0 => 64,
+            _ => align,
+        };
+        if d + (elements - 1) * stride > 31 {
+            return Err(ErrorKind::Unpredictable);
+        }
+        (
+            elements,
+            (
+                size,
+                Operand::SIMDRegListLane(SIMDReg::double(d), elements, stride, lane),
+            ),
+            align,
+        )
+    };
+    let (size, list) = list;
+
+    let dt = size_type(size);
+    inst.opcode = match (load, elements) {
+        (true, 1) => Opcode::VLD1(dt),
+        (true, 2) => Opcode::VLD2(dt),
+        (true, 3) => Opcode::VLD3(dt),
+        (true, _) => Opcode::VLD4(dt),
+        (false, 1) => Opcode::VST1(dt),
+        (false, 2) => Opcode::VST2(dt),
+        (false, 3) => Opcode::VST3(dt),
+        (false, _) => Opcode::VST4(dt),
+    };
+    inst.operands = [
+        list,
+        // `Rm == 13` writes back the transfer size, any other `Rm` is added after the
+        // transfer
+        Operand::RegDerefAligned(Reg::from_u8(rn), align, rm == 13),
+        if rm != 13 && rm != 15 {
+            Operand::Reg(Reg::from_u8(rm))
+        } else {
+            Operand::Nothing
+        },
+        Operand::Nothing,
+    ];
+    Ok(())
+}
+
+/// the floating-point rows (coprocessors `101x`) of `Coprocessor instructions` (`A5-215`) and
+/// `Coprocessor, Advanced SIMD, and Floating-point instructions` (`A6-249`).
+pub(super) fn decode_vfp(
+    decoder: &Decoder,
+    word: u32,
+    inst: &mut Instruction,
+) -> Result<(), ErrorKind> {
+    let op1 = (word >> 20) & 0b111111;
+
+    if op1 & 0b100000 == 0 {
+        if op1 & 0b111010 == 0b000000 {
+            // `00000x` is undefined, `00010x` is a 64-bit transfer
+            if op1 & 0b111110 == 0b000100 {
+                return transfer_64(word, inst);
+            }
+            return Err(ErrorKind::Undefined);
+        }
+        return extension_register_load_store(decoder, word, inst);
+    }
+
+    if op1 & 0b110000 != 0b100000 {
+        return Err(ErrorKind::Undefined);
+    }
+
+    if (word >> 4) & 1 == 0 {
+        data_processing(word, inst)
+    } else {
+        transfer_32(word, inst)
+    }
+}
+
+/// `Floating-point data-processing instructions` (`A7-270`)
+fn data_processing(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let opc1 = (word >> 20) & 0b1011;
+    let opc2 = (word >> 16) & 0b1111;
+    let opc3 = (word >> 6) & 0b11;
+    let double = (word >> 8) & 1 != 0;
+    let dt = if double { DataType::F64 } else { DataType::F32 };
+
+    let d = freg(sd(word), vd(word), double);
+    let n = freg(sn(word), vn(word), double);
+    let m = freg(sm(word), vm(word), double);
+
+    let op = opc3 & 0b01 != 0;
+    let three = |opcode: Opcode, inst: &mut Instruction| {
+        inst.opcode = opcode;
+        inst.operands = [d, n, m, Operand::Nothing];
+        Ok(())
+    };
+
+    match opc1 {
+        0b0000 => {
+            return three(
+                if op {
+                    Opcode::VMLS(dt)
+                } else {
+                    Opcode::VMLA(dt)
+                },
+                inst,
+            )
+        }
+        0b0001 => {
+            return three(
+                if op {
+                    Opcode::VNMLA(dt)
+                } else {
+                    Opcode::VNMLS(dt)
+                },
+                inst,
+            )
+        }
+        0b0010 => {
+            return three(
+                if op {
+                    Opcode::VNMUL(dt)
+                } else {
+                    Opcode::VMUL(dt)
+                },
+                inst,
+            )
+        }
+        0b0011 => {
+            return three(
+                if op {
+                    Opcode::VSUB(dt)
+                } else {
+                    Opcode::VADD(dt)
+                },
+                inst,
+            )
+        }
+        0b1000 if !op => return three(Opcode::VDIV(dt), inst),
+        0b1001 => {
+            return three(
+                if op {
+                    Opcode::VFNMA(dt)
+                } else {
+                    Opcode::VFNMS(dt)
+                },
+                inst,
+            )
+        }
+        0b1010 => {
+            return three(
+                if op {
+                    Opcode::VFMS(dt)
+                } else {
+                    Opcode::VFMA(dt)
+                },
+                inst,
+            )
+        }
+        0b1011 => {}
+        _ => return Err(ErrorKind::Undefined),
+    }
+
+    // `opc1 == 1x11`, other floating-point data-processing instructions
+    if !op {
+        // `VMOV (immediate)` (`A8-938`)
+        if word & 0b1010_0000 != 0 {
+            return Err(ErrorKind::Undefined);
+        }
+        let imm8 = ((opc2 << 4) | (word & 0b1111)) as u8;
+        inst.opcode = Opcode::VMOV(Some(dt));
+        inst.operands = [
+            d,
+            Operand::FloatImm(VFPExpandImm(imm8)),
+            Operand::Nothing,
+            Operand::Nothing,
+        ];
+        return Ok(());
+    }
+
+    let two = |opcode: Opcode, d: Operand, m: Operand, inst: &mut Instruction| {
+        inst.opcode = opcode;
+        inst.operands = [d, m, Operand::Nothing, Operand::Nothing];
+        Ok(())
+    };
+    let t = (word >> 7) & 1 != 0;
+
+    match opc2 {
+        0b0000 => two(
+            if t {
+                Opcode::VABS(dt)
+            } else {
+                Opcode::VMOV(Some(dt))
+            },
+            d,
+            m,
+            inst,
+        ),
+        0b0001 => two(
+            if t {
+                Opcode::VSQRT(dt)
+            } else {
+                Opcode::VNEG(dt)
+            },
+            d,
+            m,
+            inst,
+        ),
+        0b0010 | 0b0011 => {
+            // `VCVTB, VCVTT` (`A8-880`)
+            if double {
+                return Err(ErrorKind::Undefined);
+            }
+            let (to, from) = if opc2 & 1 == 0 {
+                (DataType::F32, DataType::F16)
+            } else {
+                (DataType::F16, DataType::F32)
+            };
+            let opcode = if t {
+                Opcode::VCVTT(to, from)
+            } else {
+                Opcode::VCVTB(to, from)
+            };
+            two(opcode, d, m, inst)
+        }
+        0b0100 | 0b0101 => {
+            // `VCMP, VCMPE` (`A8-864`)
+            let opcode = if t {
+                Opcode::VCMPE(dt)
+            } else {
+                Opcode::VCMP(dt)
+            };
+            if opc2 & 1 == 0 {
+                two(opcode, d, m, inst)
+            } else {
+                if word & 0b0010_1111 != 0 {
+                    return Err(ErrorKind::Undefined);
+                }
+                two(opcode, d, Operand::FloatImm(0), inst)
+            }
+        }
+        0b0111 if t => {
+            // `VCVT (between double-precision and single-precision)` (`A8-876`)
+            if double {
+                two(
+                    Opcode::VCVT(DataType::F32, DataType::F64),
+                    sreg(sd(word)),
+                    m,
+                    inst,
+                )
+            } else {
+                two(
+                    Opcode::VCVT(DataType::F64, DataType::F32),
+                    dreg(vd(word)),
+                    m,
+                    inst,
+                )
+            }
+        }
+        0b1000 => {
+            // `VCVT (between floating-point and integer)` (`A8-870`), from integer
+            let from = if t { DataType::S32 } else { DataType::U32 };
+            two(Opcode::VCVT(dt, from), d, sreg(sm(word)), inst)
+        }
+        0b1100 | 0b1101 => {
+            // `VCVT, VCVTR (between floating-point and integer)` (`A8-870`), to integer
+            let to = if opc2 & 1 != 0 {
+                DataType::S32
+            } else {
+                DataType::U32
+            };
+            let opcode = if t {
+                Opcode::VCVT(to, dt)
+            } else {
+                Opcode::VCVTR(to, dt)
+            };
+            two(opcode, sreg(sd(word)), m, inst)
+        }
+        0b1010 | 0b1011 | 0b1110 | 0b1111 => {
+            // `VCVT (between floating-point and fixed-point)` (`A8-874`)
+            let unsigned = opc2 & 0b0001 != 0;
+            let to_fixed = opc2 & 0b0100 != 0;
+            let size = if t { 32 } else { 16 };
+            let imm5 = ((word & 0b1111) << 1) | ((word >> 5) & 1);
+            if imm5 > size {
+                return Err(ErrorKind::Unpredictable);
+            }
+            let fixed = match (unsigned, t) {
+                (false, false) => DataType::S16,
+                (false, true) => DataType::S32,
+                (true, false) => DataType::U16,
+                (true, true) => DataType::U32,
+            };
+            inst.opcode = if to_fixed {
+                Opcode::VCVT(fixed, dt)
+            } else {
+                Opcode::VCVT(dt, fixed)
+            };
+            inst.operands = [d, d, Operand::Imm32(size - imm5), Operand::Nothing];
+            Ok(())
+        }
+        _ => Err(ErrorKind::Undefined),
+    }
+}
+
+/// `Floating-point extension register load/store instructions` (`A7-274`)
+fn extension_register_load_store(
+    decoder: &Decoder,
+    word: u32,
+    inst: &mut Instruction,
+) -> Result<(), ErrorKind> {
+    let p = (word >> 24) & 1 != 0;
+    let u = (word >> 23) & 1 != 0;
+    let w = (word >> 21) & 1 != 0;
+    let load = (word >> 20) & 1 != 0;
+    let rn = ((word >> 16) & 0b1111) as u8;
+    let double = (word >> 8) & 1 != 0;
+    let imm8 = (word & 0xff) as u8;
+
+    if p && !w {
+        // `VLDR` (`A8-924`), `VSTR` (`A8-1082`)
+        inst.opcode = if load { Opcode::VLDR } else { Opcode::VSTR };
+        inst.operands = [
+            freg(sd(word), vd(word), double),
+            Operand::RegDerefPreindexOffset(Reg::from_u8(rn), (imm8 as u16) << 2, u, false),
+            Operand::Nothing,
+            Operand::Nothing,
+        ];
+        return Ok(());
+    }
+
+    if p == u {
+        // `P == U && W == 1` is undefined
+        return Err(ErrorKind::Undefined);
+    }
+
+    // `VLDM` (`A8-922`), `VSTM` (`A8-1080`), `VPUSH` (`A8-992`), `VPOP` (`A8-990`)
+    // an odd `imm8` with doubleword registers is `FLDMX`/`FSTMX`, deprecated forms that transfer
+    // the same registers
+    let (first, count) = if double {
+        (SIMDReg::double(vd(word)), imm8 / 2)
+    } else {
+        (SIMDReg::single(sd(word)), imm8)
+    };
+    if count == 0 {
+        return Err(ErrorKind::Unpredictable);
+    }
+    // lists running past the last register are shown up to that register
+    let available = 32 - first.number();
+    if count > available || (double && count > 16) {
+        decoder.unpredictable()?;
+    }
+    let count = count.min(available);
+    let list = Operand::SIMDRegList(first, count, 1);
+
+    if rn == 13 && w && (load == u) {
+        inst.opcode = if load { Opcode::VPOP } else { Opcode::VPUSH };
+        inst.operands = [list, Operand::Nothing, Operand::Nothing, Operand::Nothing];
+        return Ok(());
+    }
+
+    inst.opcode = if load {
+        Opcode::VLDM(u)
+    } else {
+        Opcode::VSTM(u)
+    };
+    inst.operands = [
+        Operand::RegWBack(Reg::from_u8(rn), w),
+        list,
+        Operand::Nothing,
+        Operand::Nothing,
+    ];
+    Ok(())
+}
+
+/// `8, 16, and 32-bit transfer between ARM core and extension registers` (`A7-278`)
+fn transfer_32(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let l = (word >> 20) & 1 != 0;
+    let c = (word >> 8) & 1 != 0;
+    let a = (word >> 21) & 0b111;
+    let rt = ((word >> 12) & 0b1111) as u8;
+
+    if !c {
+        match a {
+            0b000 => {
+                // `VMOV (between ARM core register and single-precision register)` (`A8-944`)
+                inst.opcode = Opcode::VMOV(None);
+                inst.operands = if l {
+                    [
+                        Operand::Reg(Reg::from_u8(rt)),
+                        sreg(sn(word)),
+                        Operand::Nothing,
+                        Operand::Nothing,
+                    ]
+                } else {
+                    [
+                        sreg(sn(word)),
+                        Operand::Reg(Reg::from_u8(rt)),
+                        Operand::Nothing,
+                        Operand::Nothing,
+                    ]
+                };
+            }
+            0b111 => {
+                // `VMRS` (`A8-954`), `VMSR` (`A8-956`)
+                let reg = Operand::FPSysReg(FPSysReg::from_raw(((word >> 16) & 0b1111) as u8)?);
+                if l {
+                    let rt = if rt == 15 {
+                        if reg != Operand::FPSysReg(FPSysReg::FPSCR) {
+                            return Err(ErrorKind::Unpredictable);
+                        }
+                        Operand::APSRFlags
+                    } else {
+                        Operand::Reg(Reg::from_u8(rt))
+                    };
+                    inst.opcode = Opcode::VMRS;
+                    inst.operands = [rt, reg, Operand::Nothing, Operand::Nothing];
+                } else {
+                    inst.opcode = Opcode::VMSR;
+                    inst.operands = [
+                        reg,
+                        Operand::Reg(Reg::from_u8(rt)),
+                        Operand::Nothing,
+                        Operand::Nothing,
+                    ];
+                }
+            }
+            _ => return Err(ErrorKind::Undefined),
+        }
+        return Ok(());
+    }
+
+    let dreg_num = vn(word);
+    let opc1 = (word >> 21) & 0b11;
+    let opc2 = (word >> 5) & 0b11;
+
+    if !l && a & 0b100 != 0 {
+        // `VDUP (ARM core register)` (`A8-886`)
+        if opc2 & 0b01 != 0 && opc1 & 0b10 != 0 {
+            return Err(ErrorKind::Undefined);
+        }
+        let q = (word >> 21) & 1 != 0;
+        let dt = match ((word >> 22) & 1, (word >> 5) & 1) {
+            (0, 0) => DataType::Size32,
+            (0, _) => DataType::Size16,
+            (_, 0) => DataType::Size8,
+            _ => return Err(ErrorKind::Undefined),
+        };
+        if (word >> 6) & 1 != 0 {
+            return Err(ErrorKind::Undefined);
+        }
+        inst.opcode = Opcode::VDUP(dt);
+        inst.operands = [
+            vreg(dreg_num, q)?,
+            Operand::Reg(Reg::from_u8(rt)),
+            Operand::Nothing,
+            Operand::Nothing,
+        ];
+        return Ok(());
+    }
+
+    // `VMOV (ARM core register to scalar)` (`A8-940`), `VMOV (scalar to ARM core register)`
+    // (`A8-942`)
+    let u = (word >> 23) & 1 != 0;
+    let opc = (opc1 << 2) | opc2;
+    let (size, lane) = if opc & 0b1000 != 0 {
+        (0, opc & 0b0111)
+    } else if opc & 0b0001 != 0 {
+        (1, (opc >> 1) & 0b011)
+    } else if opc & 0b0010 == 0 {
+        (2, (opc >> 2) & 0b001)
+    } else {
+        return Err(ErrorKind::Undefined);
+    };
+    let scalar = Operand::SIMDRegLane(SIMDReg::double(dreg_num), lane as u8);
+    let rt = Operand::Reg(Reg::from_u8(rt));
+
+    if l {
+        let dt = match (size, u) {
+            (2, false) => DataType::Size32,
+            (2, true) => return Err(ErrorKind::Undefined),
+            (size, u) => sign_type(size, u),
+        };
+        inst.opcode = Opcode::VMOV(Some(dt));
+        inst.operands = [rt, scalar, Operand::Nothing, Operand::Nothing];
+    } else {
+        if u {
+            return Err(ErrorKind::Undefined);
+        }
+        inst.opcode = Opcode::VMOV(Some(size_type(size)));
+        inst.operands = [scalar, rt, Operand::Nothing, Operand::Nothing];
+    }
+    Ok(())
+}
+
+/// `64-bit transfers between ARM core and extension registers` (`A7-279`)
+fn transfer_64(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    if (word >> 4) & 0b1101 != 0b0001 {
+        return Err(ErrorKind::Undefined);
+    }
+
+    let to_core = (word >> 20) & 1 != 0;
+    let rt = Operand::Reg(Reg::from_u8(((word >> 12) & 0b1111) as u8));
+    let rt2 = Operand::Reg(Reg::from_u8(((word >> 16) & 0b1111) as u8));
+
+    inst.opcode = Opcode::VMOV(None);
+    inst.operands = if (word >> 8) & 1 == 0 {
+        // `VMOV (between two ARM core registers and two single-precision registers)` (`A8-946`)
+        let m = sm(word);
+        if m == 31 {
+            return Err(ErrorKind::Unpredictable);
+        }
+        if to_core {
+            [rt, rt2, sreg(m), sreg(m + 1)]
+        } else {
+            [sreg(m), sreg(m + 1), rt, rt2]
+        }
+    } else {
+        // `VMOV (between two ARM core registers and a doubleword extension register)` (`A8-948`)
+        if to_core {
+            [rt, rt2, dreg(vm(word)), Operand::Nothing]
+        } else {
+            [dreg(vm(word)), rt, rt2, Operand::Nothing]
+        }
+    };
+    Ok(())
+}
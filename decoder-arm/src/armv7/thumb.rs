@@ -2293,6 +2293,11 @@ pub fn read(
                         } else {
                             // `Advanced SIMD element or structure load/store instructions`
                             // (`A7-273`)
+                            // `11111001` here is `11110100` in the `arm` encoding.
+                            inst.set_w(false);
+                            let word = ((word as u32) << 16) | lower as u32;
+                            let word = 0xf400_0000 | (word & 0x00ff_ffff);
+                            super::simd::decode_load_store(word, inst)?;
                         }
                     } else {
                         // this section is a merger of three tables:
@@ -4242,9 +4247,16 @@ fn decode_table_a6_30(decoder: &Decoder, inst: &mut Instruction, instr2: BitArra
             Operand::Nothing,
         ];
         return Err(ErrorKind::InvalidOpcode);
-    } else if op1 & 0b110000 == 0b110000 {
-        // TODO: `Advanced SIMD data-processing instructions on A7-259`
-        return Err(ErrorKind::Incomplete);
+    }
+
+    let word = (instr2[..16].load::<u32>() << 16) | lower2[..16].load::<u32>();
+
+    if op1 & 0b110000 == 0b110000 {
+        // `Advanced SIMD data-processing instructions on A7-259`
+        // `111U1111` here is `1111001U` in the `arm` encoding.
+        inst.set_w(false);
+        let u = (word >> 28) & 1;
+        super::simd::decode_data_processing(((0xf2 | u) << 24) | (word & 0x00ff_ffff), inst)?;
     } else {
         let coproc = lower2[8..12].load::<u8>();
         if coproc & 0b1110 != 0b1010 {
@@ -4288,6 +4300,52 @@ fn decode_table_a6_30(decoder: &Decoder, inst: &mut Instruction, instr2: BitArra
                     Operand::CReg(CReg::from_u8(crm)),
                     Operand::Nothing,
                 ];
+            } else if op1 & 0b100000 != 0 {
+                // operands are shared between cdp and mcr/mrc, but Rt is repurposed as CRd
+                let crm = lower2[0..4].load::<u8>();
+                let opc2 = lower2[5..8].load::<u8>();
+                let rt = lower2[12..16].load::<u8>();
+                let crn = instr2[0..4].load::<u8>();
+
+                if !lower2[4] {
+                    // `CDP, CDP2 on page A8-357`
+                    let opc1 = instr2[4..8].load::<u8>();
+                    if instr2[12] {
+                        inst.opcode = Opcode::CDP2(coproc, opc1, opc2);
+                    } else {
+                        inst.opcode = Opcode::CDP(coproc, opc1, opc2);
+                    }
+                    inst.operands = [
+                        Operand::CReg(CReg::from_u8(rt)),
+                        Operand::CReg(CReg::from_u8(crn)),
+                        Operand::CReg(CReg::from_u8(crm)),
+                        Operand::Nothing,
+                    ];
+                } else {
+                    // `MCR, MCR2 on page A8-477` or `MRC, MRC2 on page A8-493`
+                    let opc1 = instr2[5..8].load::<u8>();
+                    let mrc = instr2[4];
+                    let rt = if mrc && rt == 15 {
+                        Operand::APSRFlags
+                    } else {
+                        if rt == 13 || rt == 15 {
+                            decoder.unpredictable()?;
+                        }
+                        Operand::Reg(Reg::from_u8(rt))
+                    };
+                    inst.opcode = match (mrc, instr2[12]) {
+                        (false, false) => Opcode::MCR(coproc, opc1, opc2),
+                        (false, true) => Opcode::MCR2(coproc, opc1, opc2),
+                        (true, false) => Opcode::MRC(coproc, opc1, opc2),
+                        (true, true) => Opcode::MRC2(coproc, opc1, opc2),
+                    };
+                    inst.operands = [
+                        rt,
+                        Operand::CReg(CReg::from_u8(crn)),
+                        Operand::CReg(CReg::from_u8(crm)),
+                        Operand::Nothing,
+                    ];
+                }
             } else {
                 if op1 & 1 == 0 {
                     // `STC, STC2 on page A8-663`
@@ -4402,8 +4460,13 @@ fn decode_table_a6_30(decoder: &Decoder, inst: &mut Instruction, instr2: BitArra
                 }
             }
         } else {
-            // `101x` rows
-            return Err(ErrorKind::Incomplete);
+            // `101x` rows, which are only allocated with `T == 0`
+            if instr2[12] {
+                return Err(ErrorKind::Undefined);
+            }
+            // the low 28 bits are laid out the same as in the `arm` encoding.
+            inst.set_w(false);
+            super::simd::decode_vfp(decoder, word, inst)?;
         }
     }
     Ok(())
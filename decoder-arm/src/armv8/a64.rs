@@ -8,9 +8,13 @@
 use core::fmt::{self, Display, Formatter};
 
 use debugvault::Index;
-use decoder::{Decodable, Decoded, Error, ErrorKind, Reader, ToTokens};
+use decoder::{Decodable, Decoded, Error, ErrorKind, FlowKind, MemoryAccess, Reader, ToTokens};
 use tokenizing::{colors, ColorScheme, Colors, TokenStream};
 
+mod behavior;
+mod lift;
+mod sve;
+
 #[allow(non_snake_case)]
 mod docs {
     use crate::armv8::a64::ErrorKind;
@@ -246,22 +250,24 @@ impl Decoded for Instruction {
                 self.operands[1] = Operand::Imm64Special(addr);
             },
             Opcode::LDR => {
-                let mut a_imm = 0;
-                let mut a_reg = 0;
-                if let Some(Instruction { opcode: Opcode::ADRP | Opcode::ADR, operands }) = prev_inst {
-                    if let [Operand::Register(_, reg), Operand::Imm64Special(imm), ..] = operands {
-                        a_imm = *imm;
-                        a_reg = *reg;
-                    } else {
-                        return;
+                // only a load relative to the address computed by the previous instruction is
+                // known, without writeback the base register keeps holding that address
+                if let Some(Instruction {
+                    opcode: Opcode::ADRP | Opcode::ADR,
+                    operands: [Operand::Register(_, a_reg), Operand::Imm64Special(a_imm), ..],
+                }) = prev_inst
+                {
+                    if let Operand::RegPreIndex(l_reg, off, false) = self.operands[1] {
+                        if *a_reg == l_reg {
+                            let addr = a_imm.saturating_add_signed(off as i64);
+                            self.operands[1] = Operand::Imm64(addr);
+                        }
                     }
                 }
 
-                if let Operand::RegPreIndex(l_reg, off, _) = self.operands[1] {
-                    if a_reg == l_reg {
-                        let addr = a_imm.saturating_add_signed(off as i64);
-                        self.operands[1] = Operand::Imm64(addr);
-                    }
+                if let Operand::PCOffset(offs) = self.operands[1] {
+                    let addr = (addr as u64).saturating_add_signed(offs);
+                    self.operands[1] = Operand::Imm64(addr);
                 }
             }
             _ => {
@@ -274,6 +280,30 @@ impl Decoded for Instruction {
             }
         }
     }
+
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
+    }
+
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
+
+    fn regs_read(&self) -> Vec<&'static str> {
+        behavior::registers(self).0
+    }
+
+    fn regs_written(&self) -> Vec<&'static str> {
+        behavior::registers(self).1
+    }
+
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
+
+    fn lift(&self, addr: usize, builder: &mut decoder::ir::Builder) {
+        lift::lift(self, addr, builder)
+    }
 }
 
 impl Display for Instruction {
@@ -351,6 +381,17 @@ impl ToTokens for Instruction {
                 return;
             }
             Opcode::ORR => {
+                if let (Operand::PRegisterZeroing(pg), Operand::PRegisterElements(pn, _)) =
+                    (self.operands[1], self.operands[2])
+                {
+                    if pg == pn && self.operands[2] == self.operands[3] {
+                        stream.push("mov ", Colors::opcode());
+                        self.operands[0].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[2].tokenize(stream, symbols);
+                        return;
+                    }
+                }
                 if let Operand::Register(_, 31) = self.operands[1] {
                     if let Operand::Immediate(0) = self.operands[2] {
                         stream.push("mov ", Colors::opcode());
@@ -393,6 +434,19 @@ impl ToTokens for Instruction {
                 stream.push("orn", Colors::opcode());
             }
             Opcode::ANDS => {
+                if let (Operand::PRegisterZeroing(_), Operand::PRegisterElements(..)) =
+                    (self.operands[1], self.operands[2])
+                {
+                    if self.operands[2] == self.operands[3] {
+                        stream.push("movs ", Colors::opcode());
+                        self.operands[0].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[1].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[2].tokenize(stream, symbols);
+                        return;
+                    }
+                }
                 if let Operand::Register(_, 31) = self.operands[0] {
                     stream.push("tst ", Colors::opcode());
                     self.operands[1].tokenize(stream, symbols);
@@ -404,8 +458,114 @@ impl ToTokens for Instruction {
             }
             Opcode::NOT => {
                 // `This instruction is used by the alias MVN. The alias is always the preferred
-                // disassembly.`
-                stream.push("mvn", Colors::opcode());
+                // disassembly.` the predicated SVE `not` has no such alias.
+                if let Operand::ZRegisterElements(..) = self.operands[0] {
+                    stream.push("not", Colors::opcode());
+                } else {
+                    stream.push("mvn", Colors::opcode());
+                }
+            }
+            Opcode::AND => {
+                // `and pd.b, pg/z, pn.b, pn.b` copies `pn` under a zeroing predicate.
+                if let (Operand::PRegisterZeroing(_), Operand::PRegisterElements(..)) =
+                    (self.operands[1], self.operands[2])
+                {
+                    if self.operands[2] == self.operands[3] {
+                        stream.push("mov ", Colors::opcode());
+                        self.operands[0].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[1].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[2].tokenize(stream, symbols);
+                        return;
+                    }
+                }
+                stream.push("and", Colors::opcode());
+            }
+            Opcode::EOR => {
+                // `eor pd.b, pg/z, pn.b, pg.b` inverts `pn` under a zeroing predicate.
+                if let (Operand::PRegisterZeroing(pg), Operand::PRegisterElements(pm, _)) =
+                    (self.operands[1], self.operands[3])
+                {
+                    if pg == pm {
+                        stream.push("not ", Colors::opcode());
+                        self.operands[0].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[1].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[2].tokenize(stream, symbols);
+                        return;
+                    }
+                }
+                stream.push("eor", Colors::opcode());
+            }
+            Opcode::ORRS => {
+                if let (Operand::PRegisterZeroing(pg), Operand::PRegisterElements(pn, _)) =
+                    (self.operands[1], self.operands[2])
+                {
+                    if pg == pn && self.operands[2] == self.operands[3] {
+                        stream.push("movs ", Colors::opcode());
+                        self.operands[0].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[2].tokenize(stream, symbols);
+                        return;
+                    }
+                }
+                stream.push("orrs", Colors::opcode());
+            }
+            Opcode::EORS => {
+                if let (Operand::PRegisterZeroing(pg), Operand::PRegisterElements(pm, _)) =
+                    (self.operands[1], self.operands[3])
+                {
+                    if pg == pm {
+                        stream.push("nots ", Colors::opcode());
+                        self.operands[0].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[1].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[2].tokenize(stream, symbols);
+                        return;
+                    }
+                }
+                stream.push("eors", Colors::opcode());
+            }
+            Opcode::SEL => {
+                // selecting the destination itself for inactive elements is a merging move.
+                if let Operand::PRegister(pg) = self.operands[1] {
+                    if self.operands[0] == self.operands[3] {
+                        stream.push("mov ", Colors::opcode());
+                        self.operands[0].tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        Operand::PRegisterMerging(pg).tokenize(stream, symbols);
+                        stream.push(", ", Colors::expr());
+                        self.operands[2].tokenize(stream, symbols);
+                        return;
+                    }
+                }
+                stream.push("sel", Colors::opcode());
+            }
+            Opcode::MOVA => {
+                // `mov` is the preferred disassembly for every form of `mova`.
+                stream.push("mov", Colors::opcode());
+            }
+            Opcode::DUPM => {
+                // `mov` is preferred, unless `dup` could have encoded the same immediate.
+                if let (Operand::ZRegisterElements(_, size), Operand::Imm64Special(imm)) =
+                    (self.operands[0], self.operands[1])
+                {
+                    let bits = size.width() as u32 * 8;
+                    let imm = (imm << (64 - bits)) as i64 >> (64 - bits);
+                    let dup_encodable = size == SIMDSizeCode::B
+                        || (-128..=127).contains(&imm)
+                        || (imm & 0xff == 0 && (-0x8000..=0x7f00).contains(&imm));
+                    if !dup_encodable {
+                        stream.push("mov", Colors::opcode());
+                    } else {
+                        stream.push("dupm", Colors::opcode());
+                    }
+                } else {
+                    stream.push("dupm", Colors::opcode());
+                }
             }
             Opcode::ADDS => {
                 if let Operand::Register(_, 31) = self.operands[0] {
@@ -821,6 +981,25 @@ impl ToTokens for Instruction {
                         0x12 => stream.push("tsb csync", Colors::opcode()),
                         0x14 => stream.push("csdb", Colors::opcode()),
                         0x15 => stream.push("sevl", Colors::opcode()),
+                        // pointer authentication and branch target identification live in the
+                        // hint space so they execute as `nop` on cores without them.
+                        0x7 => stream.push("xpaclri", Colors::special()),
+                        0x8 => stream.push("pacia1716", Colors::special()),
+                        0xa => stream.push("pacib1716", Colors::special()),
+                        0xc => stream.push("autia1716", Colors::special()),
+                        0xe => stream.push("autib1716", Colors::special()),
+                        0x18 => stream.push("paciaz", Colors::special()),
+                        0x19 => stream.push("paciasp", Colors::special()),
+                        0x1a => stream.push("pacibz", Colors::special()),
+                        0x1b => stream.push("pacibsp", Colors::special()),
+                        0x1c => stream.push("autiaz", Colors::special()),
+                        0x1d => stream.push("autiasp", Colors::special()),
+                        0x1e => stream.push("autibz", Colors::special()),
+                        0x1f => stream.push("autibsp", Colors::special()),
+                        0x20 => stream.push("bti", Colors::special()),
+                        0x22 => stream.push("bti c", Colors::special()),
+                        0x24 => stream.push("bti j", Colors::special()),
+                        0x26 => stream.push("bti jc", Colors::special()),
                         _ => {
                             stream.push("hint #", Colors::opcode());
                             stream.push_owned(
@@ -2087,6 +2266,219 @@ pub enum Opcode {
     IRG,
     SUBP,
     SUBPS,
+
+    // SVE and SVE2
+    SUBR,
+    SDIVR,
+    UDIVR,
+    SADDV,
+    UADDV,
+    ORV,
+    EORV,
+    ANDV,
+    MOVPRFX,
+    ASR,
+    LSR,
+    LSL,
+    ASRD,
+    ASRR,
+    LSRR,
+    LSLR,
+    SXTB,
+    UXTB,
+    SXTH,
+    UXTH,
+    SXTW,
+    UXTW,
+    CNOT,
+    MAD,
+    MSB,
+    BSL1N,
+    BSL2N,
+    NBSL,
+    INDEX,
+    ADDVL,
+    ADDPL,
+    RDVL,
+    CNTB,
+    CNTH,
+    CNTW,
+    CNTD,
+    INCB,
+    INCH,
+    INCW,
+    INCD,
+    DECB,
+    DECH,
+    DECW,
+    DECD,
+    SQINCB,
+    SQINCH,
+    SQINCW,
+    SQINCD,
+    UQINCB,
+    UQINCH,
+    UQINCW,
+    UQINCD,
+    SQDECB,
+    SQDECH,
+    SQDECW,
+    SQDECD,
+    UQDECB,
+    UQDECH,
+    UQDECW,
+    UQDECD,
+    INCP,
+    DECP,
+    SQINCP,
+    UQINCP,
+    SQDECP,
+    UQDECP,
+    INSR,
+    SEL,
+    DUPM,
+    CMPEQ,
+    CMPNE,
+    CMPGE,
+    CMPGT,
+    CMPHS,
+    CMPHI,
+    CMPLT,
+    CMPLE,
+    CMPLO,
+    CMPLS,
+    PTRUE,
+    PTRUES,
+    PFALSE,
+    PTEST,
+    WHILELT,
+    WHILELE,
+    WHILELO,
+    WHILELS,
+    WHILEGE,
+    WHILEGT,
+    WHILEHS,
+    WHILEHI,
+    NOR,
+    NAND,
+    EORS,
+    ORRS,
+    ORNS,
+    NORS,
+    NANDS,
+    FDUP,
+    CNTP,
+    FSUBR,
+    FSCALE,
+    FDIVR,
+    FTSMUL,
+    FNMLA,
+    FNMLS,
+    FMAD,
+    FMSB,
+    FNMAD,
+    FNMSB,
+    FCMNE,
+    FCMUO,
+    FADDV,
+    LD1B,
+    LD1H,
+    LD1W,
+    LD1D,
+    LD1SB,
+    LD1SH,
+    LD1SW,
+    LD1RB,
+    LD1RH,
+    LD1RW,
+    LD1RD,
+    LD1RSB,
+    LD1RSH,
+    LD1RSW,
+    LDFF1B,
+    LDFF1H,
+    LDFF1W,
+    LDFF1D,
+    LDFF1SB,
+    LDFF1SH,
+    LDFF1SW,
+    LDNF1B,
+    LDNF1H,
+    LDNF1W,
+    LDNF1D,
+    LDNF1SB,
+    LDNF1SH,
+    LDNF1SW,
+    LD2B,
+    LD2H,
+    LD2W,
+    LD2D,
+    LD3B,
+    LD3H,
+    LD3W,
+    LD3D,
+    LD4B,
+    LD4H,
+    LD4W,
+    LD4D,
+    LDNT1B,
+    LDNT1H,
+    LDNT1W,
+    LDNT1D,
+    LDNT1SB,
+    LDNT1SH,
+    LDNT1SW,
+    ST1B,
+    ST1H,
+    ST1W,
+    ST1D,
+    ST2B,
+    ST2H,
+    ST2W,
+    ST2D,
+    ST3B,
+    ST3H,
+    ST3W,
+    ST3D,
+    ST4B,
+    ST4H,
+    ST4W,
+    ST4D,
+    STNT1B,
+    STNT1H,
+    STNT1W,
+    STNT1D,
+    PRFB,
+    PRFH,
+    PRFW,
+    PRFD,
+    SADDLB,
+    SADDLT,
+    UADDLB,
+    UADDLT,
+    SSUBLB,
+    SSUBLT,
+    USUBLB,
+    USUBLT,
+
+    // SME
+    ADDSVL,
+    ADDSPL,
+    RDSVL,
+    FMOPA,
+    FMOPS,
+    SMOPA,
+    SMOPS,
+    UMOPA,
+    UMOPS,
+    SUMOPA,
+    SUMOPS,
+    USMOPA,
+    USMOPS,
+    ZERO,
+    MOVA,
+    SMSTART(u8),
+    SMSTOP(u8),
 }
 
 impl ToTokens for Opcode {
@@ -2916,12 +3308,233 @@ impl ToTokens for Opcode {
             Opcode::IRG => "irg",
             Opcode::SUBP => "subp",
             Opcode::SUBPS => "subps",
+            Opcode::SUBR => "subr",
+            Opcode::SDIVR => "sdivr",
+            Opcode::UDIVR => "udivr",
+            Opcode::SADDV => "saddv",
+            Opcode::UADDV => "uaddv",
+            Opcode::ORV => "orv",
+            Opcode::EORV => "eorv",
+            Opcode::ANDV => "andv",
+            Opcode::MOVPRFX => "movprfx",
+            Opcode::ASR => "asr",
+            Opcode::LSR => "lsr",
+            Opcode::LSL => "lsl",
+            Opcode::ASRD => "asrd",
+            Opcode::ASRR => "asrr",
+            Opcode::LSRR => "lsrr",
+            Opcode::LSLR => "lslr",
+            Opcode::SXTB => "sxtb",
+            Opcode::UXTB => "uxtb",
+            Opcode::SXTH => "sxth",
+            Opcode::UXTH => "uxth",
+            Opcode::SXTW => "sxtw",
+            Opcode::UXTW => "uxtw",
+            Opcode::CNOT => "cnot",
+            Opcode::MAD => "mad",
+            Opcode::MSB => "msb",
+            Opcode::BSL1N => "bsl1n",
+            Opcode::BSL2N => "bsl2n",
+            Opcode::NBSL => "nbsl",
+            Opcode::INDEX => "index",
+            Opcode::ADDVL => "addvl",
+            Opcode::ADDPL => "addpl",
+            Opcode::RDVL => "rdvl",
+            Opcode::CNTB => "cntb",
+            Opcode::CNTH => "cnth",
+            Opcode::CNTW => "cntw",
+            Opcode::CNTD => "cntd",
+            Opcode::INCB => "incb",
+            Opcode::INCH => "inch",
+            Opcode::INCW => "incw",
+            Opcode::INCD => "incd",
+            Opcode::DECB => "decb",
+            Opcode::DECH => "dech",
+            Opcode::DECW => "decw",
+            Opcode::DECD => "decd",
+            Opcode::SQINCB => "sqincb",
+            Opcode::SQINCH => "sqinch",
+            Opcode::SQINCW => "sqincw",
+            Opcode::SQINCD => "sqincd",
+            Opcode::UQINCB => "uqincb",
+            Opcode::UQINCH => "uqinch",
+            Opcode::UQINCW => "uqincw",
+            Opcode::UQINCD => "uqincd",
+            Opcode::SQDECB => "sqdecb",
+            Opcode::SQDECH => "sqdech",
+            Opcode::SQDECW => "sqdecw",
+            Opcode::SQDECD => "sqdecd",
+            Opcode::UQDECB => "uqdecb",
+            Opcode::UQDECH => "uqdech",
+            Opcode::UQDECW => "uqdecw",
+            Opcode::UQDECD => "uqdecd",
+            Opcode::INCP => "incp",
+            Opcode::DECP => "decp",
+            Opcode::SQINCP => "sqincp",
+            Opcode::UQINCP => "uqincp",
+            Opcode::SQDECP => "sqdecp",
+            Opcode::UQDECP => "uqdecp",
+            Opcode::INSR => "insr",
+            Opcode::SEL => "sel",
+            Opcode::DUPM => "dupm",
+            Opcode::CMPEQ => "cmpeq",
+            Opcode::CMPNE => "cmpne",
+            Opcode::CMPGE => "cmpge",
+            Opcode::CMPGT => "cmpgt",
+            Opcode::CMPHS => "cmphs",
+            Opcode::CMPHI => "cmphi",
+            Opcode::CMPLT => "cmplt",
+            Opcode::CMPLE => "cmple",
+            Opcode::CMPLO => "cmplo",
+            Opcode::CMPLS => "cmpls",
+            Opcode::PTRUE => "ptrue",
+            Opcode::PTRUES => "ptrues",
+            Opcode::PFALSE => "pfalse",
+            Opcode::PTEST => "ptest",
+            Opcode::WHILELT => "whilelt",
+            Opcode::WHILELE => "whilele",
+            Opcode::WHILELO => "whilelo",
+            Opcode::WHILELS => "whilels",
+            Opcode::WHILEGE => "whilege",
+            Opcode::WHILEGT => "whilegt",
+            Opcode::WHILEHS => "whilehs",
+            Opcode::WHILEHI => "whilehi",
+            Opcode::NOR => "nor",
+            Opcode::NAND => "nand",
+            Opcode::EORS => "eors",
+            Opcode::ORRS => "orrs",
+            Opcode::ORNS => "orns",
+            Opcode::NORS => "nors",
+            Opcode::NANDS => "nands",
+            Opcode::FDUP => "fmov",
+            Opcode::CNTP => "cntp",
+            Opcode::FSUBR => "fsubr",
+            Opcode::FSCALE => "fscale",
+            Opcode::FDIVR => "fdivr",
+            Opcode::FTSMUL => "ftsmul",
+            Opcode::FNMLA => "fnmla",
+            Opcode::FNMLS => "fnmls",
+            Opcode::FMAD => "fmad",
+            Opcode::FMSB => "fmsb",
+            Opcode::FNMAD => "fnmad",
+            Opcode::FNMSB => "fnmsb",
+            Opcode::FCMNE => "fcmne",
+            Opcode::FCMUO => "fcmuo",
+            Opcode::FADDV => "faddv",
+            Opcode::LD1B => "ld1b",
+            Opcode::LD1H => "ld1h",
+            Opcode::LD1W => "ld1w",
+            Opcode::LD1D => "ld1d",
+            Opcode::LD1SB => "ld1sb",
+            Opcode::LD1SH => "ld1sh",
+            Opcode::LD1SW => "ld1sw",
+            Opcode::LD1RB => "ld1rb",
+            Opcode::LD1RH => "ld1rh",
+            Opcode::LD1RW => "ld1rw",
+            Opcode::LD1RD => "ld1rd",
+            Opcode::LD1RSB => "ld1rsb",
+            Opcode::LD1RSH => "ld1rsh",
+            Opcode::LD1RSW => "ld1rsw",
+            Opcode::LDFF1B => "ldff1b",
+            Opcode::LDFF1H => "ldff1h",
+            Opcode::LDFF1W => "ldff1w",
+            Opcode::LDFF1D => "ldff1d",
+            Opcode::LDFF1SB => "ldff1sb",
+            Opcode::LDFF1SH => "ldff1sh",
+            Opcode::LDFF1SW => "ldff1sw",
+            Opcode::LDNF1B => "ldnf1b",
+            Opcode::LDNF1H => "ldnf1h",
+            Opcode::LDNF1W => "ldnf1w",
+            Opcode::LDNF1D => "ldnf1d",
+            Opcode::LDNF1SB => "ldnf1sb",
+            Opcode::LDNF1SH => "ldnf1sh",
+            Opcode::LDNF1SW => "ldnf1sw",
+            Opcode::LD2B => "ld2b",
+            Opcode::LD2H => "ld2h",
+            Opcode::LD2W => "ld2w",
+            Opcode::LD2D => "ld2d",
+            Opcode::LD3B => "ld3b",
+            Opcode::LD3H => "ld3h",
+            Opcode::LD3W => "ld3w",
+            Opcode::LD3D => "ld3d",
+            Opcode::LD4B => "ld4b",
+            Opcode::LD4H => "ld4h",
+            Opcode::LD4W => "ld4w",
+            Opcode::LD4D => "ld4d",
+            Opcode::LDNT1B => "ldnt1b",
+            Opcode::LDNT1H => "ldnt1h",
+            Opcode::LDNT1W => "ldnt1w",
+            Opcode::LDNT1D => "ldnt1d",
+            Opcode::LDNT1SB => "ldnt1sb",
+            Opcode::LDNT1SH => "ldnt1sh",
+            Opcode::LDNT1SW => "ldnt1sw",
+            Opcode::ST1B => "st1b",
+            Opcode::ST1H => "st1h",
+            Opcode::ST1W => "st1w",
+            Opcode::ST1D => "st1d",
+            Opcode::ST2B => "st2b",
+            Opcode::ST2H => "st2h",
+            Opcode::ST2W => "st2w",
+            Opcode::ST2D => "st2d",
+            Opcode::ST3B => "st3b",
+            Opcode::ST3H => "st3h",
+            Opcode::ST3W => "st3w",
+            Opcode::ST3D => "st3d",
+            Opcode::ST4B => "st4b",
+            Opcode::ST4H => "st4h",
+            Opcode::ST4W => "st4w",
+            Opcode::ST4D => "st4d",
+            Opcode::STNT1B => "stnt1b",
+            Opcode::STNT1H => "stnt1h",
+            Opcode::STNT1W => "stnt1w",
+            Opcode::STNT1D => "stnt1d",
+            Opcode::PRFB => "prfb",
+            Opcode::PRFH => "prfh",
+            Opcode::PRFW => "prfw",
+            Opcode::PRFD => "prfd",
+            Opcode::SADDLB => "saddlb",
+            Opcode::SADDLT => "saddlt",
+            Opcode::UADDLB => "uaddlb",
+            Opcode::UADDLT => "uaddlt",
+            Opcode::SSUBLB => "ssublb",
+            Opcode::SSUBLT => "ssublt",
+            Opcode::USUBLB => "usublb",
+            Opcode::USUBLT => "usublt",
+            Opcode::ADDSVL => "addsvl",
+            Opcode::ADDSPL => "addspl",
+            Opcode::RDSVL => "rdsvl",
+            Opcode::FMOPA => "fmopa",
+            Opcode::FMOPS => "fmops",
+            Opcode::SMOPA => "smopa",
+            Opcode::SMOPS => "smops",
+            Opcode::UMOPA => "umopa",
+            Opcode::UMOPS => "umops",
+            Opcode::SUMOPA => "sumopa",
+            Opcode::SUMOPS => "sumops",
+            Opcode::USMOPA => "usmopa",
+            Opcode::USMOPS => "usmops",
+            Opcode::ZERO => "zero",
+            Opcode::MOVA => "mova",
 
             Opcode::Bcc(cond) => {
                 stream.push("b.", Colors::opcode());
                 Operand::ConditionCode(cond).tokenize(stream, symbols);
                 return;
             }
+            Opcode::SMSTART(mode) => {
+                return match mode {
+                    0b01 => stream.push("smstart sm", Colors::opcode()),
+                    0b10 => stream.push("smstart za", Colors::opcode()),
+                    _ => stream.push("smstart", Colors::opcode()),
+                };
+            }
+            Opcode::SMSTOP(mode) => {
+                return match mode {
+                    0b01 => stream.push("smstop sm", Colors::opcode()),
+                    0b10 => stream.push("smstop za", Colors::opcode()),
+                    _ => stream.push("smstop", Colors::opcode()),
+                };
+            }
             Opcode::DMB(option) => {
                 return match option {
                     0b0001 => stream.push("dmb oshld", Colors::opcode()),
@@ -3003,78 +3616,139 @@ impl ToTokens for Opcode {
             }
         };
 
-        stream.push(text, Colors::opcode());
+        if is_pointer_auth(*self) {
+            stream.push(text, Colors::special());
+        } else {
+            stream.push(text, Colors::opcode());
+        }
     }
 }
 
-fn format_register_32(stream: &mut TokenStream, reg: u16) {
-    const LOOKUP: &[&str] = &[
-        "w0", "w1", "w2", "w3", "w4", "w5", "w6", "w7", "w8", "w9", "w10", "w11", "w12", "w13",
-        "w14", "w15", "w16", "w17", "w18", "w19", "w20", "w21", "w22", "w23", "w24", "w25", "w26",
-        "w27", "w28", "w29", "w30", "w31",
-    ];
+/// Instructions signing, authenticating or stripping pointer authentication codes.
+fn is_pointer_auth(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::BLRAA
+            | Opcode::BLRAAZ
+            | Opcode::BLRAB
+            | Opcode::BLRABZ
+            | Opcode::BRAA
+            | Opcode::BRAAZ
+            | Opcode::BRAB
+            | Opcode::BRABZ
+            | Opcode::RETAA
+            | Opcode::RETAB
+            | Opcode::ERETAA
+            | Opcode::ERETAB
+            | Opcode::LDRAA
+            | Opcode::LDRAB
+            | Opcode::PACIA
+            | Opcode::PACIB
+            | Opcode::PACDA
+            | Opcode::PACDB
+            | Opcode::AUTIA
+            | Opcode::AUTIB
+            | Opcode::AUTDA
+            | Opcode::AUTDB
+            | Opcode::PACIZA
+            | Opcode::PACIZB
+            | Opcode::PACDZA
+            | Opcode::PACDZB
+            | Opcode::AUTIZA
+            | Opcode::AUTIZB
+            | Opcode::AUTDZA
+            | Opcode::AUTDZB
+            | Opcode::XPACI
+            | Opcode::XPACD
+            | Opcode::PACGA
+    )
+}
 
-    stream.push(LOOKUP[reg as usize], Colors::register());
+const REGS_W: [&str; 32] = [
+    "w0", "w1", "w2", "w3", "w4", "w5", "w6", "w7", "w8", "w9", "w10", "w11", "w12", "w13",
+    "w14", "w15", "w16", "w17", "w18", "w19", "w20", "w21", "w22", "w23", "w24", "w25", "w26",
+    "w27", "w28", "w29", "w30", "w31",
+];
+
+const REGS_X: [&str; 32] = [
+    "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+    "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26",
+    "x27", "x28", "x29", "x30", "x31",
+];
+
+const REGS_B: [&str; 32] = [
+    "b0", "b1", "b2", "b3", "b4", "b5", "b6", "b7", "b8", "b9", "b10", "b11", "b12", "b13",
+    "b14", "b15", "b16", "b17", "b18", "b19", "b20", "b21", "b22", "b23", "b24", "b25", "b26",
+    "b27", "b28", "b29", "b30", "b31",
+];
+
+const REGS_H: [&str; 32] = [
+    "h0", "h1", "h2", "h3", "h4", "h5", "h6", "h7", "h8", "h9", "h10", "h11", "h12", "h13",
+    "h14", "h15", "h16", "h17", "h18", "h19", "h20", "h21", "h22", "h23", "h24", "h25", "h26",
+    "h27", "h28", "h29", "h30", "h31",
+];
+
+const REGS_S: [&str; 32] = [
+    "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "s12", "s13",
+    "s14", "s15", "s16", "s17", "s18", "s19", "s20", "s21", "s22", "s23", "s24", "s25", "s26",
+    "s27", "s28", "s29", "s30", "s31",
+];
+
+const REGS_D: [&str; 32] = [
+    "d0", "d1", "d2", "d3", "d4", "d5", "d6", "d7", "d8", "d9", "d10", "d11", "d12", "d13",
+    "d14", "d15", "d16", "d17", "d18", "d19", "d20", "d21", "d22", "d23", "d24", "d25", "d26",
+    "d27", "d28", "d29", "d30", "d31",
+];
+
+const REGS_Q: [&str; 32] = [
+    "q0", "q1", "q2", "q3", "q4", "q5", "q6", "q7", "q8", "q9", "q10", "q11", "q12", "q13",
+    "q14", "q15", "q16", "q17", "q18", "q19", "q20", "q21", "q22", "q23", "q24", "q25", "q26",
+    "q27", "q28", "q29", "q30", "q31",
+];
+
+const REGS_V: [&str; 32] = [
+    "v0", "v1", "v2", "v3", "v4", "v5", "v6", "v7", "v8", "v9", "v10", "v11", "v12", "v13",
+    "v14", "v15", "v16", "v17", "v18", "v19", "v20", "v21", "v22", "v23", "v24", "v25", "v26",
+    "v27", "v28", "v29", "v30", "v31",
+];
+
+const REGS_Z: [&str; 32] = [
+    "z0", "z1", "z2", "z3", "z4", "z5", "z6", "z7", "z8", "z9", "z10", "z11", "z12", "z13",
+    "z14", "z15", "z16", "z17", "z18", "z19", "z20", "z21", "z22", "z23", "z24", "z25", "z26",
+    "z27", "z28", "z29", "z30", "z31",
+];
+
+const REGS_P: [&str; 16] = [
+    "p0", "p1", "p2", "p3", "p4", "p5", "p6", "p7", "p8", "p9", "p10", "p11", "p12", "p13",
+    "p14", "p15",
+];
+
+fn format_register_32(stream: &mut TokenStream, reg: u16) {
+    stream.push(REGS_W[reg as usize], Colors::register());
 }
 
 fn format_register_64(stream: &mut TokenStream, reg: u16) {
-    const LOOKUP: &[&str] = &[
-        "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
-        "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26",
-        "x27", "x28", "x29", "x30", "x31",
-    ];
-
-    stream.push(LOOKUP[reg as usize], Colors::register());
+    stream.push(REGS_X[reg as usize], Colors::register());
 }
 
 fn format_register_b(stream: &mut TokenStream, reg: u16) {
-    const LOOKUP: &[&str] = &[
-        "b0", "b1", "b2", "b3", "b4", "b5", "b6", "b7", "b8", "b9", "b10", "b11", "b12", "b13",
-        "b14", "b15", "b16", "b17", "b18", "b19", "b20", "b21", "b22", "b23", "b24", "b25", "b26",
-        "b27", "b28", "b29", "b30", "b31",
-    ];
-
-    stream.push(LOOKUP[reg as usize], Colors::register());
+    stream.push(REGS_B[reg as usize], Colors::register());
 }
 
 fn format_register_h(stream: &mut TokenStream, reg: u16) {
-    const LOOKUP: &[&str] = &[
-        "h0", "h1", "h2", "h3", "h4", "h5", "h6", "h7", "h8", "h9", "h10", "h11", "h12", "h13",
-        "h14", "h15", "h16", "h17", "h18", "h19", "h20", "h21", "h22", "h23", "h24", "h25", "h26",
-        "h27", "h28", "h29", "h30", "h31",
-    ];
-
-    stream.push(LOOKUP[reg as usize], Colors::register());
+    stream.push(REGS_H[reg as usize], Colors::register());
 }
 
 fn format_register_s(stream: &mut TokenStream, reg: u16) {
-    const LOOKUP: &[&str] = &[
-        "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "s12", "s13",
-        "s14", "s15", "s16", "s17", "s18", "s19", "s20", "s21", "s22", "s23", "s24", "s25", "s26",
-        "s27", "s28", "s29", "s30", "s31",
-    ];
-
-    stream.push(LOOKUP[reg as usize], Colors::register());
+    stream.push(REGS_S[reg as usize], Colors::register());
 }
 
 fn format_register_d(stream: &mut TokenStream, reg: u16) {
-    const LOOKUP: &[&str] = &[
-        "d0", "d1", "d2", "d3", "d4", "d5", "d6", "d7", "d8", "d9", "d10", "d11", "d12", "d13",
-        "d14", "d15", "d16", "d17", "d18", "d19", "d20", "d21", "d22", "d23", "d24", "d25", "d26",
-        "d27", "d28", "d29", "d30", "d31",
-    ];
-
-    stream.push(LOOKUP[reg as usize], Colors::register());
+    stream.push(REGS_D[reg as usize], Colors::register());
 }
 
 fn format_register_q(stream: &mut TokenStream, reg: u16) {
-    const LOOKUP: &[&str] = &[
-        "q0", "q1", "q2", "q3", "q4", "q5", "q6", "q7", "q8", "q9", "q10", "q11", "q12", "q13",
-        "q14", "q15", "q16", "q17", "q18", "q19", "q20", "q21", "q22", "q23", "q24", "q25", "q26",
-        "q27", "q28", "q29", "q30", "q31",
-    ];
-
-    stream.push(LOOKUP[reg as usize], Colors::register());
+    stream.push(REGS_Q[reg as usize], Colors::register());
 }
 
 fn format_register_ctrl(stream: &mut TokenStream, reg: u16) {
@@ -3312,6 +3986,102 @@ pub enum Operand {
     /// `yaxpeax-arm` does not name specific fields of `pstate` yet, so this operand displays as
     /// `pstate.0x50`.
     PstateField(u8),
+    /// an immediate that is signed in the encoded instruction.
+    ///
+    /// this is displayed as, for example, `#-0x80`. unlike `Operand::Immediate`, it is never
+    /// resolved to a symbol.
+    SignedImmediate(i64),
+    /// a scalable vector register, used without an element size.
+    ///
+    /// for example, `z5` would be represented as `Operand::ZRegister(5)`. the register number
+    /// ranges between 0 and 31.
+    ZRegister(u16),
+    /// a scalable vector register, used as a vector of elements.
+    ///
+    /// for example, `z5.s` would be represented as
+    /// `Operand::ZRegisterElements(5, SIMDSizeCode::S)`.
+    ZRegisterElements(u16, SIMDSizeCode),
+    /// one element in a scalable vector register.
+    ///
+    /// for example, `z5.s[3]` would be represented as
+    /// `Operand::ZRegisterElementsLane(5, SIMDSizeCode::S, 3)`.
+    ZRegisterElementsLane(u16, SIMDSizeCode, u8),
+    /// consecutive scalable vector registers, wrapping around from `z31` to `z0`.
+    ///
+    /// for example, `{z30.b, z31.b, z0.b}` would be represented as
+    /// `Operand::ZRegisterGroup(30, SIMDSizeCode::B, 3)`. the number of registers ranges from 1
+    /// to 4.
+    ZRegisterGroup(u16, SIMDSizeCode, u8),
+    /// a predicate register, used as a whole.
+    ///
+    /// for example, `p3` would be represented as `Operand::PRegister(3)`. the register number
+    /// ranges between 0 and 15.
+    PRegister(u16),
+    /// a predicate register, with one bit per element of the given size.
+    ///
+    /// for example, `p3.h` would be represented as `Operand::PRegisterElements(3, SIMDSizeCode::H)`.
+    PRegisterElements(u16, SIMDSizeCode),
+    /// a governing predicate, where inactive elements of the destination keep their value.
+    ///
+    /// this is displayed as, for example, `p3/m`.
+    PRegisterMerging(u16),
+    /// a governing predicate, where inactive elements of the destination are zeroed.
+    ///
+    /// this is displayed as, for example, `p3/z`.
+    PRegisterZeroing(u16),
+    /// the two merging predicates of an `SME` outer product, for rows and columns.
+    ///
+    /// this is displayed as, for example, `p0/m, p1/m`, and would be represented by
+    /// `Operand::PRegisterMergingPair(0, 1)`.
+    PRegisterMergingPair(u16, u16),
+    /// a predicate constraint and multiplier, as used by `ptrue` and element counts.
+    ///
+    /// the constraint is displayed by name where it has one, for example `vl4` or `all`. a
+    /// multiplier other than 1 is displayed after it, as in `vl4, mul #0x2`.
+    SVEPattern(u8, u8),
+    /// a memory access, with a register base address and an offset in multiples of the vector (or
+    /// predicate) length.
+    ///
+    /// this is displayed as, for example, `[x5, #-0x3, mul vl]`, or `[x5]` for an offset of 0.
+    RegOffsetMulVl(u16, i16),
+    /// a memory access, with a register base address offset by each element of a vector,
+    /// optionally extended from 32 bits and scaled by the memory element size.
+    ///
+    /// this is displayed as, for example, `[x5, z3.s, uxtw #2]`, and would be represented by
+    /// `Operand::RegZRegOffset(5, 3, SIMDSizeCode::S, ShiftStyle::UXTW, 2)`. offsets used as
+    /// they are have `ShiftStyle::LSL`, and display as `[x5, z3.d]` when not scaled.
+    RegZRegOffset(u16, u16, SIMDSizeCode, ShiftStyle, u8),
+    /// a memory access, with a vector of base addresses and a constant offset.
+    ///
+    /// this is displayed as, for example, `[z3.d, #0x10]`, or `[z3.d]` for an offset of 0.
+    ZRegOffset(u16, SIMDSizeCode, u16),
+    /// a memory access, with a vector of base addresses and a register offset.
+    ///
+    /// this is displayed as, for example, `[z3.d, x5]`, or `[z3.d]` when the offset is `xzr`.
+    ZRegRegOffset(u16, SIMDSizeCode, u16),
+    /// the operation of an `SVE` prefetch.
+    ///
+    /// operations with a name display as `{pld,pst}{l1,l2,l3}{keep,strm}`, the others as the
+    /// encoded number, for example `#0x6`.
+    SVEPrefetchOp(u8),
+    /// one tile of the `SME` matrix array.
+    ///
+    /// for example, `za3.s` would be represented as `Operand::ZATile(3, SIMDSizeCode::S)`.
+    ZATile(u16, SIMDSizeCode),
+    /// a horizontal or vertical slice of one tile of the `SME` matrix array.
+    ///
+    /// for example, `za1v.s[w13, 2]` would be represented as
+    /// `Operand::ZATileSlice(1, SIMDSizeCode::S, true, 13, 2)`.
+    ZATileSlice(u16, SIMDSizeCode, bool, u16, u8),
+    /// a vector of the `SME` matrix array, selected by a register and offset.
+    ///
+    /// for example, `za[w12, 3]` would be represented as `Operand::ZAArrayVector(12, 3)`.
+    ZAArrayVector(u16, u8),
+    /// the set of 64-bit tiles selected by a `zero` instruction.
+    ///
+    /// this is displayed with the widest tiles that cover the mask, for example `{za}` or
+    /// `{za0.s, za1.d}`.
+    ZATileList(u8),
 }
 
 impl Operand {
@@ -3687,6 +4457,198 @@ impl ToTokens for Operand {
                 stream.push("], ", Colors::expr());
                 format_register_64(stream, *offset_reg);
             }
+            Operand::SignedImmediate(imm) => {
+                stream.push("#", Colors::expr());
+                stream.push_owned(decoder::encode_hex(*imm), Colors::immediate());
+            }
+            Operand::ZRegister(reg) => {
+                stream.push(REGS_Z[*reg as usize], Colors::register());
+            }
+            Operand::ZRegisterElements(reg, size) => {
+                stream.push_owned(format!("z{}.{}", reg, size.name()), Colors::register());
+            }
+            Operand::ZRegisterElementsLane(reg, size, lane) => {
+                let op = format!("z{}.{}[{}]", reg, size.name(), lane);
+                stream.push_owned(op, Colors::register());
+            }
+            Operand::ZRegisterGroup(reg, size, group_size) => {
+                stream.push("{", Colors::brackets());
+                for i in 0..*group_size as u16 {
+                    if i != 0 {
+                        stream.push(", ", Colors::expr());
+                    }
+                    let op = format!("z{}.{}", (reg + i) % 32, size.name());
+                    stream.push_owned(op, Colors::register());
+                }
+                stream.push("}", Colors::brackets());
+            }
+            Operand::PRegister(reg) => {
+                stream.push(REGS_P[*reg as usize], Colors::register());
+            }
+            Operand::PRegisterElements(reg, size) => {
+                stream.push_owned(format!("p{}.{}", reg, size.name()), Colors::register());
+            }
+            Operand::PRegisterMerging(reg) => {
+                stream.push(REGS_P[*reg as usize], Colors::register());
+                stream.push("/m", Colors::expr());
+            }
+            Operand::PRegisterZeroing(reg) => {
+                stream.push(REGS_P[*reg as usize], Colors::register());
+                stream.push("/z", Colors::expr());
+            }
+            Operand::PRegisterMergingPair(row, column) => {
+                Operand::PRegisterMerging(*row).tokenize(stream, symbols);
+                stream.push(", ", Colors::expr());
+                Operand::PRegisterMerging(*column).tokenize(stream, symbols);
+            }
+            Operand::SVEPattern(pattern, mul) => {
+                match pattern {
+                    0b00000 => stream.push("pow2", Colors::opcode()),
+                    0b00001..=0b01000 => {
+                        stream.push_owned(format!("vl{pattern}"), Colors::opcode());
+                    }
+                    0b01001..=0b01101 => {
+                        let elements = 16 << (pattern - 0b01001);
+                        stream.push_owned(format!("vl{elements}"), Colors::opcode());
+                    }
+                    0b11101 => stream.push("mul4", Colors::opcode()),
+                    0b11110 => stream.push("mul3", Colors::opcode()),
+                    0b11111 => stream.push("all", Colors::opcode()),
+                    _ => {
+                        stream.push("#", Colors::expr());
+                        stream.push_owned(
+                            decoder::encode_uhex(*pattern as u64),
+                            Colors::immediate(),
+                        );
+                    }
+                }
+
+                if *mul != 1 {
+                    stream.push(", ", Colors::expr());
+                    stream.push("mul ", Colors::opcode());
+                    stream.push("#", Colors::expr());
+                    stream.push_owned(decoder::encode_uhex(*mul as u64), Colors::immediate());
+                }
+            }
+            Operand::RegOffsetMulVl(reg, offset) => {
+                stream.push("[", Colors::brackets());
+                Operand::RegisterOrSP(SizeCode::X, *reg).tokenize(stream, symbols);
+                if *offset != 0 {
+                    stream.push(", ", Colors::expr());
+                    stream.push("#", Colors::expr());
+                    stream.push_owned(decoder::encode_hex(*offset as i64), Colors::immediate());
+                    stream.push(", ", Colors::expr());
+                    stream.push("mul vl", Colors::opcode());
+                }
+                stream.push("]", Colors::brackets());
+            }
+            Operand::RegZRegOffset(reg, index_reg, size, extend, amount) => {
+                stream.push("[", Colors::brackets());
+                Operand::RegisterOrSP(SizeCode::X, *reg).tokenize(stream, symbols);
+                stream.push(", ", Colors::expr());
+                Operand::ZRegisterElements(*index_reg, *size).tokenize(stream, symbols);
+                if extend != &ShiftStyle::LSL || *amount != 0 {
+                    stream.push(", ", Colors::expr());
+                    stream.push(extend.as_str(), Colors::opcode());
+                }
+                if *amount != 0 {
+                    stream.push(" ", Colors::expr());
+                    stream.push("#", Colors::expr());
+                    stream.push_owned(amount.to_string(), Colors::immediate());
+                }
+                stream.push("]", Colors::brackets());
+            }
+            Operand::ZRegOffset(reg, size, offset) => {
+                stream.push("[", Colors::brackets());
+                Operand::ZRegisterElements(*reg, *size).tokenize(stream, symbols);
+                if *offset != 0 {
+                    stream.push(", ", Colors::expr());
+                    stream.push("#", Colors::expr());
+                    stream.push_owned(decoder::encode_uhex(*offset as u64), Colors::immediate());
+                }
+                stream.push("]", Colors::brackets());
+            }
+            Operand::ZRegRegOffset(reg, size, offset_reg) => {
+                stream.push("[", Colors::brackets());
+                Operand::ZRegisterElements(*reg, *size).tokenize(stream, symbols);
+                if *offset_reg != 31 {
+                    stream.push(", ", Colors::expr());
+                    format_register_64(stream, *offset_reg);
+                }
+                stream.push("]", Colors::brackets());
+            }
+            Operand::SVEPrefetchOp(op) => {
+                if op & 0b0110 == 0b0110 {
+                    stream.push("#", Colors::expr());
+                    stream.push_owned(decoder::encode_uhex(*op as u64), Colors::immediate());
+                } else {
+                    let op = format!(
+                        "{}{}{}",
+                        ["pld", "pst"][(op >> 3) as usize],
+                        ["l1", "l2", "l3"][((op >> 1) & 0b11) as usize],
+                        ["keep", "strm"][(op & 1) as usize],
+                    );
+                    stream.push_owned(op, Colors::opcode());
+                }
+            }
+            Operand::ZATile(tile, size) => {
+                stream.push_owned(format!("za{}.{}", tile, size.name()), Colors::register());
+            }
+            Operand::ZATileSlice(tile, size, vertical, index, offset) => {
+                let direction = if *vertical { 'v' } else { 'h' };
+                let op = format!("za{}{}.{}", tile, direction, size.name());
+                stream.push_owned(op, Colors::register());
+                stream.push("[", Colors::brackets());
+                format_register_32(stream, *index);
+                stream.push(", ", Colors::expr());
+                stream.push_owned(offset.to_string(), Colors::immediate());
+                stream.push("]", Colors::brackets());
+            }
+            Operand::ZAArrayVector(index, offset) => {
+                stream.push("za", Colors::register());
+                stream.push("[", Colors::brackets());
+                format_register_32(stream, *index);
+                stream.push(", ", Colors::expr());
+                stream.push_owned(offset.to_string(), Colors::immediate());
+                stream.push("]", Colors::brackets());
+            }
+            Operand::ZATileList(mask) => {
+                stream.push("{", Colors::brackets());
+                if *mask == 0xff {
+                    stream.push("za", Colors::register());
+                } else {
+                    // each bit selects a 64-bit tile. wider tiles interleave the 64-bit ones, so
+                    // `za1.h` is every other bit starting from bit 1.
+                    const TILES: [(u8, &str); 14] = [
+                        (0x55, "za0.h"),
+                        (0xaa, "za1.h"),
+                        (0x11, "za0.s"),
+                        (0x22, "za1.s"),
+                        (0x44, "za2.s"),
+                        (0x88, "za3.s"),
+                        (0x01, "za0.d"),
+                        (0x02, "za1.d"),
+                        (0x04, "za2.d"),
+                        (0x08, "za3.d"),
+                        (0x10, "za4.d"),
+                        (0x20, "za5.d"),
+                        (0x40, "za6.d"),
+                        (0x80, "za7.d"),
+                    ];
+
+                    let mut remaining = *mask;
+                    for (bits, name) in TILES {
+                        if remaining & bits == bits {
+                            if remaining != *mask {
+                                stream.push(", ", Colors::expr());
+                            }
+                            stream.push(name, Colors::register());
+                            remaining &= !bits;
+                        }
+                    }
+                }
+                stream.push("}", Colors::brackets());
+            }
             Operand::Nothing => panic!("Tried to format nothing opcode"),
         }
     }
@@ -3779,10 +4741,13 @@ fn read(words: &mut Reader, inst: &mut Instruction) -> Result<(), ErrorKind> {
 
     match section {
         Section::SME => {
+            if word >> 31 == 1 {
+                return sve::decode_sme(word, inst);
+            }
             return Err(ErrorKind::IncompleteDecoder);
         }
         Section::SVE => {
-            return Err(ErrorKind::IncompleteDecoder);
+            return sve::decode_sve(word, inst);
         }
         Section::DataProcessingSimd | Section::DataProcessingSimd2 => {
             let op3 = (word >> 10) & 0b1_1111_1111;
@@ -12005,6 +12970,28 @@ fn read(words: &mut Reader, inst: &mut Instruction) -> Result<(), ErrorKind> {
                                             };
                                         }
                                         0b0100 => {
+                                            // `MSR SVCR*` is how SME enters and leaves streaming
+                                            // mode, and is disassembled as `smstart`/`smstop`.
+                                            let svcr = (CRm >> 1) & 0b11;
+                                            if op1 == 0b011
+                                                && op2 == 0b011
+                                                && CRm & 0b1000 == 0
+                                                && svcr != 0
+                                            {
+                                                inst.opcode = if CRm & 1 == 1 {
+                                                    Opcode::SMSTART(svcr as u8)
+                                                } else {
+                                                    Opcode::SMSTOP(svcr as u8)
+                                                };
+                                                inst.operands = [
+                                                    Operand::Nothing,
+                                                    Operand::Nothing,
+                                                    Operand::Nothing,
+                                                    Operand::Nothing,
+                                                ];
+                                                return Ok(());
+                                            }
+
                                             inst.opcode = Opcode::MSR;
 
                                             /*
@@ -0,0 +1,926 @@
+//! Semantic queries on decoded instructions: control flow, registers and memory accesses.
+//!
+//! The condition flags are reported as `nzcv`. Register 31 is reported as `sp` where it's the
+//! stack pointer and left out where it's the zero register.
+
+use super::{Instruction, Opcode, Operand, SIMDSizeCode, SizeCode};
+use super::{REGS_B, REGS_D, REGS_H, REGS_P, REGS_Q, REGS_S, REGS_V, REGS_W, REGS_X, REGS_Z};
+use decoder::{Direction, FlowKind, MemoryAccess};
+
+const LR: u16 = 30;
+const SP: u16 = 31;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+fn is_load(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::LDAR
+            | Opcode::LDLAR
+            | Opcode::LDARB
+            | Opcode::LDLARB
+            | Opcode::LDAXRB
+            | Opcode::LDARH
+            | Opcode::LDLARH
+            | Opcode::LDAXP
+            | Opcode::LDAXR
+            | Opcode::LDAXRH
+            | Opcode::LDP
+            | Opcode::LDPSW
+            | Opcode::LDR
+            | Opcode::LDRB
+            | Opcode::LDRSB
+            | Opcode::LDRSW
+            | Opcode::LDRSH
+            | Opcode::LDRH
+            | Opcode::LDTR
+            | Opcode::LDTRB
+            | Opcode::LDTRH
+            | Opcode::LDTRSB
+            | Opcode::LDTRSH
+            | Opcode::LDTRSW
+            | Opcode::LDUR
+            | Opcode::LDURB
+            | Opcode::LDURSB
+            | Opcode::LDURSW
+            | Opcode::LDURSH
+            | Opcode::LDURH
+            | Opcode::LDXP
+            | Opcode::LDXR
+            | Opcode::LDXRB
+            | Opcode::LDXRH
+            | Opcode::LDNP
+            | Opcode::LD1
+            | Opcode::LD2
+            | Opcode::LD3
+            | Opcode::LD4
+            | Opcode::LD1R
+            | Opcode::LD2R
+            | Opcode::LD3R
+            | Opcode::LD4R
+            | Opcode::LDRAA
+            | Opcode::LDRAB
+            | Opcode::LDAPR
+            | Opcode::LDAPRH
+            | Opcode::LDAPRB
+            | Opcode::LDAPUR
+            | Opcode::LDAPURB
+            | Opcode::LDAPURH
+            | Opcode::LDAPURSB
+            | Opcode::LDAPURSH
+            | Opcode::LDAPURSW
+            | Opcode::LDG
+            | Opcode::LDGM
+            | Opcode::LD1B
+            | Opcode::LD1H
+            | Opcode::LD1W
+            | Opcode::LD1D
+            | Opcode::LD1SB
+            | Opcode::LD1SH
+            | Opcode::LD1SW
+            | Opcode::LD2B
+            | Opcode::LD2H
+            | Opcode::LD2W
+            | Opcode::LD2D
+            | Opcode::LD3B
+            | Opcode::LD3H
+            | Opcode::LD3W
+            | Opcode::LD3D
+            | Opcode::LD4B
+            | Opcode::LD4H
+            | Opcode::LD4W
+            | Opcode::LD4D
+            | Opcode::LDNT1B
+            | Opcode::LDNT1H
+            | Opcode::LDNT1W
+            | Opcode::LDNT1D
+            | Opcode::LDNT1SB
+            | Opcode::LDNT1SH
+            | Opcode::LDNT1SW
+            | Opcode::LD1RB
+            | Opcode::LD1RH
+            | Opcode::LD1RW
+            | Opcode::LD1RD
+            | Opcode::LD1RSB
+            | Opcode::LD1RSH
+            | Opcode::LD1RSW
+            | Opcode::LDFF1B
+            | Opcode::LDFF1H
+            | Opcode::LDFF1W
+            | Opcode::LDFF1D
+            | Opcode::LDFF1SB
+            | Opcode::LDFF1SH
+            | Opcode::LDFF1SW
+            | Opcode::LDNF1B
+            | Opcode::LDNF1H
+            | Opcode::LDNF1W
+            | Opcode::LDNF1D
+            | Opcode::LDNF1SB
+            | Opcode::LDNF1SH
+            | Opcode::LDNF1SW
+    )
+}
+
+fn is_store(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::STLR
+            | Opcode::STLLR
+            | Opcode::STLRB
+            | Opcode::STLLRB
+            | Opcode::STLRH
+            | Opcode::STLLRH
+            | Opcode::STP
+            | Opcode::STR
+            | Opcode::STTR
+            | Opcode::STTRB
+            | Opcode::STTRH
+            | Opcode::STRB
+            | Opcode::STRH
+            | Opcode::STRW
+            | Opcode::STUR
+            | Opcode::STURB
+            | Opcode::STURH
+            | Opcode::STNP
+            | Opcode::ST1
+            | Opcode::ST2
+            | Opcode::ST3
+            | Opcode::ST4
+            | Opcode::STGM
+            | Opcode::STZGM
+            | Opcode::STG
+            | Opcode::STZG
+            | Opcode::ST2G
+            | Opcode::STZ2G
+            | Opcode::STLUR
+            | Opcode::STLURB
+            | Opcode::STLURH
+            | Opcode::ST1B
+            | Opcode::ST1H
+            | Opcode::ST1W
+            | Opcode::ST1D
+            | Opcode::ST2B
+            | Opcode::ST2H
+            | Opcode::ST2W
+            | Opcode::ST2D
+            | Opcode::ST3B
+            | Opcode::ST3H
+            | Opcode::ST3W
+            | Opcode::ST3D
+            | Opcode::ST4B
+            | Opcode::ST4H
+            | Opcode::ST4W
+            | Opcode::ST4D
+            | Opcode::STNT1B
+            | Opcode::STNT1H
+            | Opcode::STNT1W
+            | Opcode::STNT1D
+    )
+}
+
+/// Store exclusives, their first operand is the status result.
+fn is_store_exclusive(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::STXR
+            | Opcode::STXRB
+            | Opcode::STXRH
+            | Opcode::STXP
+            | Opcode::STLXR
+            | Opcode::STLXRB
+            | Opcode::STLXRH
+            | Opcode::STLXP
+    )
+}
+
+/// Atomic memory operations taking the source in `rs` and returning the old value in `rt`.
+fn is_atomic(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::SWP(_)
+            | Opcode::SWPB(_)
+            | Opcode::SWPH(_)
+            | Opcode::LDADD(_)
+            | Opcode::LDCLR(_)
+            | Opcode::LDEOR(_)
+            | Opcode::LDSET(_)
+            | Opcode::LDSMAX(_)
+            | Opcode::LDSMIN(_)
+            | Opcode::LDUMAX(_)
+            | Opcode::LDUMIN(_)
+            | Opcode::LDADDB(_)
+            | Opcode::LDCLRB(_)
+            | Opcode::LDEORB(_)
+            | Opcode::LDSETB(_)
+            | Opcode::LDSMAXB(_)
+            | Opcode::LDSMINB(_)
+            | Opcode::LDUMAXB(_)
+            | Opcode::LDUMINB(_)
+            | Opcode::LDADDH(_)
+            | Opcode::LDCLRH(_)
+            | Opcode::LDEORH(_)
+            | Opcode::LDSETH(_)
+            | Opcode::LDSMAXH(_)
+            | Opcode::LDSMINH(_)
+            | Opcode::LDUMAXH(_)
+            | Opcode::LDUMINH(_)
+    )
+}
+
+fn is_compare_and_swap(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::CAS(_) | Opcode::CASH(_) | Opcode::CASB(_) | Opcode::CASP(_)
+    )
+}
+
+/// Instructions that merge their result into the destination rather than replacing it.
+fn is_accumulating(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::MOVK
+            | Opcode::BFM
+            | Opcode::MLA
+            | Opcode::MLS
+            | Opcode::FMLA
+            | Opcode::FMLS
+            | Opcode::FMLAL
+            | Opcode::FMLAL2
+            | Opcode::FMLSL
+            | Opcode::FMLSL2
+            | Opcode::FCMLA
+            | Opcode::BSL
+            | Opcode::BIT
+            | Opcode::BIF
+            | Opcode::INS
+            | Opcode::TBX
+            | Opcode::SSRA
+            | Opcode::USRA
+            | Opcode::SRSRA
+            | Opcode::URSRA
+            | Opcode::SRI
+            | Opcode::SLI
+            | Opcode::SABA
+            | Opcode::UABA
+            | Opcode::SABAL
+            | Opcode::SABAL2
+            | Opcode::UABAL
+            | Opcode::UABAL2
+            | Opcode::SADALP
+            | Opcode::UADALP
+            | Opcode::SUQADD
+            | Opcode::USQADD
+            | Opcode::SDOT
+            | Opcode::UDOT
+            | Opcode::SQRDMLAH
+            | Opcode::SQRDMLSH
+            | Opcode::SMLAL
+            | Opcode::SMLAL2
+            | Opcode::SMLSL
+            | Opcode::SMLSL2
+            | Opcode::UMLAL
+            | Opcode::UMLAL2
+            | Opcode::UMLSL
+            | Opcode::UMLSL2
+            | Opcode::SQDMLAL
+            | Opcode::SQDMLAL2
+            | Opcode::SQDMLSL
+            | Opcode::SQDMLSL2
+            | Opcode::AESE
+            | Opcode::AESD
+            | Opcode::SHA1C
+            | Opcode::SHA1P
+            | Opcode::SHA1M
+            | Opcode::SHA1SU0
+            | Opcode::SHA1SU1
+            | Opcode::SHA256H
+            | Opcode::SHA256H2
+            | Opcode::SHA256SU0
+            | Opcode::SHA256SU1
+            | Opcode::SHA512H
+            | Opcode::SHA512H2
+            | Opcode::SHA512SU0
+            | Opcode::SHA512SU1
+            | Opcode::SM3PARTW1
+            | Opcode::SM3PARTW2
+            | Opcode::SM3TT1A
+            | Opcode::SM3TT1B
+            | Opcode::SM3TT2A
+            | Opcode::SM3TT2B
+            | Opcode::SM4E
+            | Opcode::PACIA
+            | Opcode::PACIB
+            | Opcode::PACDA
+            | Opcode::PACDB
+            | Opcode::AUTIA
+            | Opcode::AUTIB
+            | Opcode::AUTDA
+            | Opcode::AUTDB
+            | Opcode::PACIZA
+            | Opcode::PACIZB
+            | Opcode::PACDZA
+            | Opcode::PACDZB
+            | Opcode::AUTIZA
+            | Opcode::AUTIZB
+            | Opcode::AUTDZA
+            | Opcode::AUTDZB
+            | Opcode::XPACI
+            | Opcode::XPACD
+            | Opcode::MAD
+            | Opcode::MSB
+            | Opcode::FNMLA
+            | Opcode::FNMLS
+            | Opcode::FMAD
+            | Opcode::FMSB
+            | Opcode::FNMAD
+            | Opcode::FNMSB
+            | Opcode::INSR
+            | Opcode::INCB
+            | Opcode::INCH
+            | Opcode::INCW
+            | Opcode::INCD
+            | Opcode::DECB
+            | Opcode::DECH
+            | Opcode::DECW
+            | Opcode::DECD
+            | Opcode::SQINCB
+            | Opcode::SQINCH
+            | Opcode::SQINCW
+            | Opcode::SQINCD
+            | Opcode::UQINCB
+            | Opcode::UQINCH
+            | Opcode::UQINCW
+            | Opcode::UQINCD
+            | Opcode::SQDECB
+            | Opcode::SQDECH
+            | Opcode::SQDECW
+            | Opcode::SQDECD
+            | Opcode::UQDECB
+            | Opcode::UQDECH
+            | Opcode::UQDECW
+            | Opcode::UQDECD
+            | Opcode::INCP
+            | Opcode::DECP
+            | Opcode::SQINCP
+            | Opcode::UQINCP
+            | Opcode::SQDECP
+            | Opcode::UQDECP
+            // narrowing into the upper half keeps the lower half
+            | Opcode::XTN2
+            | Opcode::SQXTN2
+            | Opcode::UQXTN2
+            | Opcode::SQXTUN2
+            | Opcode::FCVTN2
+            | Opcode::FCVTXN2
+            | Opcode::SHRN2
+            | Opcode::RSHRN2
+            | Opcode::SQSHRN2
+            | Opcode::SQRSHRN2
+            | Opcode::SQSHRUN2
+            | Opcode::SQRSHRUN2
+            | Opcode::UQSHRN2
+            | Opcode::UQRSHRN2
+            | Opcode::ADDHN2
+            | Opcode::SUBHN2
+            | Opcode::RADDHN2
+            | Opcode::RSUBHN2
+    )
+}
+
+/// How the operand at `idx` is accessed, ignoring registers used for addressing.
+fn access(inst: &Instruction, idx: usize) -> Access {
+    let opcode = inst.opcode;
+
+    match (opcode, idx) {
+        (_, 0) if is_store_exclusive(opcode) => Access::Write,
+        (_, 1) if is_atomic(opcode) => Access::Write,
+        (_, 0) if is_compare_and_swap(opcode) => Access::ReadWrite,
+        (Opcode::LDP | Opcode::LDPSW | Opcode::LDNP | Opcode::LDXP | Opcode::LDAXP, 1) => {
+            Access::Write
+        }
+        (_, 0) if is_load(opcode) => Access::Write,
+        (_, 0) if is_store(opcode) || is_atomic(opcode) => Access::Read,
+        (
+            Opcode::CCMP
+            | Opcode::CCMN
+            | Opcode::FCMP
+            | Opcode::FCMPE
+            | Opcode::FCCMP
+            | Opcode::FCCMPE
+            | Opcode::CBZ
+            | Opcode::CBNZ
+            | Opcode::TBZ
+            | Opcode::TBNZ
+            | Opcode::BR
+            | Opcode::BLR
+            | Opcode::RET
+            | Opcode::BRAA
+            | Opcode::BRAAZ
+            | Opcode::BRAB
+            | Opcode::BRABZ
+            | Opcode::BLRAA
+            | Opcode::BLRAAZ
+            | Opcode::BLRAB
+            | Opcode::BLRABZ
+            | Opcode::MSR
+            | Opcode::SYS(_)
+            | Opcode::PRFM
+            | Opcode::PRFUM
+            | Opcode::PRFB
+            | Opcode::PRFH
+            | Opcode::PRFW
+            | Opcode::PRFD
+            | Opcode::SETF8
+            | Opcode::SETF16
+            | Opcode::RMIF
+            | Opcode::PTEST,
+            0,
+        ) => Access::Read,
+        (_, 0) if is_accumulating(opcode) => Access::ReadWrite,
+        // inactive elements keep their value under a merging predicate
+        (_, 0)
+            if matches!(
+                inst.operands[1],
+                Operand::PRegisterMerging(_) | Operand::PRegisterMergingPair(..)
+            ) =>
+        {
+            Access::ReadWrite
+        }
+        (_, 0) => Access::Write,
+        _ => Access::Read,
+    }
+}
+
+fn push(regs: &mut Vec<&'static str>, name: &'static str) {
+    if !regs.contains(&name) {
+        regs.push(name);
+    }
+}
+
+fn gpr(size: SizeCode, reg: u16) -> &'static str {
+    match size {
+        SizeCode::X => REGS_X[reg as usize],
+        SizeCode::W => REGS_W[reg as usize],
+    }
+}
+
+fn gpr_or_sp(size: SizeCode, reg: u16) -> &'static str {
+    match (size, reg) {
+        (SizeCode::X, SP) => "sp",
+        (SizeCode::W, SP) => "wsp",
+        _ => gpr(size, reg),
+    }
+}
+
+fn simd(size: SIMDSizeCode, reg: u16) -> &'static str {
+    let table = match size {
+        SIMDSizeCode::B => &REGS_B,
+        SIMDSizeCode::H => &REGS_H,
+        SIMDSizeCode::S => &REGS_S,
+        SIMDSizeCode::D => &REGS_D,
+        SIMDSizeCode::Q => &REGS_Q,
+    };
+
+    table[reg as usize]
+}
+
+/// Registers holding the value of an operand, as opposed to ones used for addressing.
+fn value_regs(operand: &Operand, regs: &mut Vec<&'static str>) {
+    match *operand {
+        Operand::Register(_, SP) => {}
+        Operand::Register(size, reg) | Operand::RegShift(_, _, size, reg) => {
+            regs.push(gpr(size, reg))
+        }
+        Operand::RegisterOrSP(size, reg) => regs.push(gpr_or_sp(size, reg)),
+        Operand::RegisterPair(size, reg) => {
+            for reg in [reg, reg + 1] {
+                if reg != SP {
+                    regs.push(gpr(size, reg));
+                }
+            }
+        }
+        Operand::SIMDRegister(size, reg) => regs.push(simd(size, reg)),
+        Operand::SIMDRegisterElements(_, reg, _)
+        | Operand::SIMDRegisterElementsLane(_, reg, _, _)
+        | Operand::SIMDRegisterElementsMultipleLane(_, reg, _, _, _) => {
+            regs.push(REGS_V[reg as usize])
+        }
+        Operand::SIMDRegisterGroup(_, reg, _, count)
+        | Operand::SIMDRegisterGroupLane(reg, _, count, _) => {
+            for idx in 0..count as u16 {
+                regs.push(REGS_V[((reg + idx) % 32) as usize]);
+            }
+        }
+        Operand::ZRegister(reg)
+        | Operand::ZRegisterElements(reg, _)
+        | Operand::ZRegisterElementsLane(reg, _, _) => regs.push(REGS_Z[reg as usize]),
+        Operand::ZRegisterGroup(reg, _, count) => {
+            for idx in 0..count as u16 {
+                regs.push(REGS_Z[((reg + idx) % 32) as usize]);
+            }
+        }
+        Operand::PRegister(reg)
+        | Operand::PRegisterElements(reg, _)
+        | Operand::PRegisterMerging(reg)
+        | Operand::PRegisterZeroing(reg) => regs.push(REGS_P[reg as usize]),
+        Operand::PRegisterMergingPair(row, column) => {
+            regs.push(REGS_P[row as usize]);
+            regs.push(REGS_P[column as usize]);
+        }
+        Operand::ZATile(..)
+        | Operand::ZATileSlice(..)
+        | Operand::ZAArrayVector(..)
+        | Operand::ZATileList(_) => regs.push("za"),
+        _ => {}
+    }
+}
+
+/// Registers named by an operand, split by how they're accessed.
+fn operand_regs(
+    operand: &Operand,
+    mut access: Access,
+    read: &mut Vec<&'static str>,
+    written: &mut Vec<&'static str>,
+) {
+    // writing a single lane (or a part of `za`) keeps the rest of the vector
+    if access == Access::Write
+        && matches!(
+            operand,
+            Operand::SIMDRegisterElementsLane(..)
+                | Operand::SIMDRegisterGroupLane(..)
+                | Operand::ZATileSlice(..)
+                | Operand::ZAArrayVector(..)
+        )
+    {
+        access = Access::ReadWrite;
+    }
+
+    let mut value = Vec::new();
+    value_regs(operand, &mut value);
+
+    for reg in value {
+        if access != Access::Write {
+            push(read, reg);
+        }
+        if access != Access::Read {
+            push(written, reg);
+        }
+    }
+
+    match *operand {
+        Operand::RegPreIndex(base, _, wback) => {
+            push(read, gpr_or_sp(SizeCode::X, base));
+            if wback {
+                push(written, gpr_or_sp(SizeCode::X, base));
+            }
+        }
+        Operand::RegPostIndex(base, _) => {
+            push(read, gpr_or_sp(SizeCode::X, base));
+            push(written, gpr_or_sp(SizeCode::X, base));
+        }
+        Operand::RegPostIndexReg(base, index) => {
+            push(read, gpr_or_sp(SizeCode::X, base));
+            if index != SP {
+                push(read, gpr(SizeCode::X, index));
+            }
+            push(written, gpr_or_sp(SizeCode::X, base));
+        }
+        Operand::RegRegOffset(base, index, size, _, _) => {
+            push(read, gpr_or_sp(SizeCode::X, base));
+            if index != SP {
+                push(read, gpr(size, index));
+            }
+        }
+        Operand::RegOffsetMulVl(base, _) => push(read, gpr_or_sp(SizeCode::X, base)),
+        Operand::RegZRegOffset(base, index, _, _, _) => {
+            push(read, gpr_or_sp(SizeCode::X, base));
+            push(read, REGS_Z[index as usize]);
+        }
+        Operand::ZRegOffset(base, _, _) => push(read, REGS_Z[base as usize]),
+        Operand::ZRegRegOffset(base, _, offset) => {
+            push(read, REGS_Z[base as usize]);
+            if offset != SP {
+                push(read, gpr(SizeCode::X, offset));
+            }
+        }
+        Operand::ZATileSlice(_, _, _, index, _) | Operand::ZAArrayVector(index, _) => {
+            push(read, REGS_W[index as usize])
+        }
+        _ => {}
+    }
+}
+
+/// Collects the registers read and written by an instruction.
+pub(super) fn registers(inst: &Instruction) -> (Vec<&'static str>, Vec<&'static str>) {
+    let mut read = Vec::new();
+    let mut written = Vec::new();
+
+    for (idx, operand) in inst.operands.iter().enumerate() {
+        operand_regs(operand, access(inst, idx), &mut read, &mut written);
+    }
+
+    match inst.opcode {
+        Opcode::BL
+        | Opcode::BLR
+        | Opcode::BLRAA
+        | Opcode::BLRAAZ
+        | Opcode::BLRAB
+        | Opcode::BLRABZ => push(&mut written, REGS_X[LR as usize]),
+        Opcode::RETAA | Opcode::RETAB => {
+            push(&mut read, REGS_X[LR as usize]);
+            push(&mut read, "sp");
+        }
+        Opcode::HINT => {
+            // the pointer authentication hints sign or authenticate `x30` (with `sp` as the
+            // modifier for the `*sp` forms) or `x17` (with `x16` as the modifier).
+            if let (Operand::ControlReg(crm), Operand::Immediate(op2)) =
+                (inst.operands[0], inst.operands[1])
+            {
+                match (crm << 3) | op2 as u16 {
+                    0x7 | 0x18 | 0x1a | 0x1c | 0x1e => {
+                        push(&mut read, REGS_X[LR as usize]);
+                        push(&mut written, REGS_X[LR as usize]);
+                    }
+                    0x19 | 0x1b | 0x1d | 0x1f => {
+                        push(&mut read, REGS_X[LR as usize]);
+                        push(&mut read, "sp");
+                        push(&mut written, REGS_X[LR as usize]);
+                    }
+                    0x8 | 0xa | 0xc | 0xe => {
+                        push(&mut read, REGS_X[17]);
+                        push(&mut read, REGS_X[16]);
+                        push(&mut written, REGS_X[17]);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        // first-faulting and non-faulting loads clear the elements of `ffr` they didn't load
+        Opcode::LDFF1B
+        | Opcode::LDFF1H
+        | Opcode::LDFF1W
+        | Opcode::LDFF1D
+        | Opcode::LDFF1SB
+        | Opcode::LDFF1SH
+        | Opcode::LDFF1SW
+        | Opcode::LDNF1B
+        | Opcode::LDNF1H
+        | Opcode::LDNF1W
+        | Opcode::LDNF1D
+        | Opcode::LDNF1SB
+        | Opcode::LDNF1SH
+        | Opcode::LDNF1SW => {
+            push(&mut read, "ffr");
+            push(&mut written, "ffr");
+        }
+        _ => {}
+    }
+
+    let reads_flags = matches!(
+        inst.opcode,
+        Opcode::Bcc(_)
+            | Opcode::CSEL
+            | Opcode::CSNEG
+            | Opcode::CSINC
+            | Opcode::CSINV
+            | Opcode::CCMN
+            | Opcode::CCMP
+            | Opcode::FCSEL
+            | Opcode::FCCMP
+            | Opcode::FCCMPE
+            | Opcode::ADC
+            | Opcode::ADCS
+            | Opcode::SBC
+            | Opcode::SBCS
+    );
+    let writes_flags = matches!(
+        inst.opcode,
+        Opcode::ADDS
+            | Opcode::SUBS
+            | Opcode::ANDS
+            | Opcode::BICS
+            | Opcode::ADCS
+            | Opcode::SBCS
+            | Opcode::CCMN
+            | Opcode::CCMP
+            | Opcode::FCMP
+            | Opcode::FCMPE
+            | Opcode::FCCMP
+            | Opcode::FCCMPE
+            | Opcode::SETF8
+            | Opcode::SETF16
+            | Opcode::RMIF
+            | Opcode::SUBPS
+            | Opcode::CMPEQ
+            | Opcode::CMPNE
+            | Opcode::CMPGE
+            | Opcode::CMPGT
+            | Opcode::CMPHS
+            | Opcode::CMPHI
+            | Opcode::CMPLT
+            | Opcode::CMPLE
+            | Opcode::CMPLO
+            | Opcode::CMPLS
+            | Opcode::PTRUES
+            | Opcode::PTEST
+            | Opcode::WHILELT
+            | Opcode::WHILELE
+            | Opcode::WHILELO
+            | Opcode::WHILELS
+            | Opcode::WHILEGE
+            | Opcode::WHILEGT
+            | Opcode::WHILEHS
+            | Opcode::WHILEHI
+            | Opcode::EORS
+            | Opcode::ORRS
+            | Opcode::ORNS
+            | Opcode::NORS
+            | Opcode::NANDS
+    );
+
+    if reads_flags {
+        read.push("nzcv");
+    }
+
+    if writes_flags {
+        written.push("nzcv");
+    }
+
+    (read, written)
+}
+
+pub(super) fn flow(inst: &Instruction) -> FlowKind {
+    match inst.opcode {
+        Opcode::B | Opcode::BR | Opcode::BRAA | Opcode::BRAAZ | Opcode::BRAB | Opcode::BRABZ => {
+            FlowKind::Jump
+        }
+        Opcode::Bcc(_) | Opcode::CBZ | Opcode::CBNZ | Opcode::TBZ | Opcode::TBNZ => {
+            FlowKind::ConditionalJump
+        }
+        Opcode::BL
+        | Opcode::BLR
+        | Opcode::BLRAA
+        | Opcode::BLRAAZ
+        | Opcode::BLRAB
+        | Opcode::BLRABZ => FlowKind::Call,
+        Opcode::RET
+        | Opcode::RETAA
+        | Opcode::RETAB
+        | Opcode::ERET
+        | Opcode::ERETAA
+        | Opcode::ERETAB
+        | Opcode::DRPS => FlowKind::Return,
+        Opcode::SVC | Opcode::HVC | Opcode::SMC | Opcode::BRK | Opcode::HLT => FlowKind::Interrupt,
+        _ => FlowKind::Sequential,
+    }
+}
+
+pub(super) fn targets(inst: &Instruction) -> Vec<usize> {
+    if !matches!(
+        flow(inst),
+        FlowKind::Jump | FlowKind::ConditionalJump | FlowKind::Call
+    ) {
+        return Vec::new();
+    }
+
+    inst.operands
+        .iter()
+        .filter_map(|operand| match operand {
+            Operand::Imm64(addr) => Some(*addr as usize),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Number of bytes held by a register operand.
+fn width(operand: &Operand) -> Option<usize> {
+    let width = match *operand {
+        Operand::Register(SizeCode::X, _) | Operand::RegisterOrSP(SizeCode::X, _) => 8,
+        Operand::Register(SizeCode::W, _) | Operand::RegisterOrSP(SizeCode::W, _) => 4,
+        Operand::RegisterPair(SizeCode::X, _) => 16,
+        Operand::RegisterPair(SizeCode::W, _) => 8,
+        Operand::SIMDRegister(size, _) | Operand::SIMDRegisterElements(size, _, _) => {
+            size.width() as usize
+        }
+        Operand::SIMDRegisterElementsLane(_, _, lane, _) => lane.width() as usize,
+        Operand::SIMDRegisterGroup(size, _, _, count) => size.width() as usize * count as usize,
+        Operand::SIMDRegisterGroupLane(_, lane, count, _) => lane.width() as usize * count as usize,
+        _ => return None,
+    };
+
+    Some(width)
+}
+
+pub(super) fn memory(inst: &Instruction) -> Option<MemoryAccess> {
+    let opcode = inst.opcode;
+
+    let direction = if is_atomic(opcode) || is_compare_and_swap(opcode) {
+        Direction::ReadWrite
+    } else if is_load(opcode) {
+        Direction::Read
+    } else if is_store(opcode) || is_store_exclusive(opcode) {
+        Direction::Write
+    } else {
+        return None;
+    };
+
+    let size = match opcode {
+        // allocation tags rather than data
+        Opcode::LDG
+        | Opcode::LDGM
+        | Opcode::STG
+        | Opcode::STZG
+        | Opcode::ST2G
+        | Opcode::STZ2G
+        | Opcode::STGM
+        | Opcode::STZGM => None,
+        Opcode::LDARB
+        | Opcode::LDLARB
+        | Opcode::LDAXRB
+        | Opcode::LDRB
+        | Opcode::LDRSB
+        | Opcode::LDTRB
+        | Opcode::LDTRSB
+        | Opcode::LDURB
+        | Opcode::LDURSB
+        | Opcode::LDXRB
+        | Opcode::LDAPRB
+        | Opcode::LDAPURB
+        | Opcode::LDAPURSB
+        | Opcode::STLRB
+        | Opcode::STLLRB
+        | Opcode::STTRB
+        | Opcode::STRB
+        | Opcode::STURB
+        | Opcode::STLURB
+        | Opcode::STXRB
+        | Opcode::STLXRB
+        | Opcode::SWPB(_)
+        | Opcode::LDADDB(_)
+        | Opcode::LDCLRB(_)
+        | Opcode::LDEORB(_)
+        | Opcode::LDSETB(_)
+        | Opcode::LDSMAXB(_)
+        | Opcode::LDSMINB(_)
+        | Opcode::LDUMAXB(_)
+        | Opcode::LDUMINB(_)
+        | Opcode::CASB(_) => Some(1),
+        Opcode::LDARH
+        | Opcode::LDLARH
+        | Opcode::LDAXRH
+        | Opcode::LDRH
+        | Opcode::LDRSH
+        | Opcode::LDTRH
+        | Opcode::LDTRSH
+        | Opcode::LDURH
+        | Opcode::LDURSH
+        | Opcode::LDXRH
+        | Opcode::LDAPRH
+        | Opcode::LDAPURH
+        | Opcode::LDAPURSH
+        | Opcode::STLRH
+        | Opcode::STLLRH
+        | Opcode::STTRH
+        | Opcode::STRH
+        | Opcode::STURH
+        | Opcode::STLURH
+        | Opcode::STXRH
+        | Opcode::STLXRH
+        | Opcode::SWPH(_)
+        | Opcode::LDADDH(_)
+        | Opcode::LDCLRH(_)
+        | Opcode::LDEORH(_)
+        | Opcode::LDSETH(_)
+        | Opcode::LDSMAXH(_)
+        | Opcode::LDSMINH(_)
+        | Opcode::LDUMAXH(_)
+        | Opcode::LDUMINH(_)
+        | Opcode::CASH(_) => Some(2),
+        Opcode::LDRSW | Opcode::LDTRSW | Opcode::LDURSW | Opcode::LDAPURSW => Some(4),
+        Opcode::LDPSW => Some(8),
+        Opcode::LDP | Opcode::STP | Opcode::LDNP | Opcode::STNP | Opcode::LDXP | Opcode::LDAXP => {
+            width(&inst.operands[0]).map(|width| width * 2)
+        }
+        Opcode::STXP | Opcode::STLXP => width(&inst.operands[1]).map(|width| width * 2),
+        // replicating loads read a single element per register
+        Opcode::LD1R | Opcode::LD2R | Opcode::LD3R | Opcode::LD4R => match inst.operands[0] {
+            Operand::SIMDRegisterGroup(_, _, lane, count) => {
+                Some(lane.width() as usize * count as usize)
+            }
+            _ => None,
+        },
+        Opcode::LD1RB | Opcode::LD1RSB => Some(1),
+        Opcode::LD1RH | Opcode::LD1RSH => Some(2),
+        Opcode::LD1RW | Opcode::LD1RSW => Some(4),
+        Opcode::LD1RD => Some(8),
+        _ if is_store_exclusive(opcode) || is_atomic(opcode) => width(&inst.operands[1]),
+        _ => width(&inst.operands[0]),
+    };
+
+    Some(MemoryAccess { size, direction })
+}
@@ -0,0 +1,808 @@
+//! Lifting of instructions to the architecture neutral [`ir`](decoder::ir).
+//!
+//! General purpose registers are accessed by their 64-bit name, writing a `w` register zeroes
+//! the upper half of it. The condition flags are 1-bit registers named `n`, `z`, `c` and `v`.
+//! SIMD, SVE and most system instructions are unsupported.
+
+use super::{Instruction, Opcode, Operand, ShiftStyle, SizeCode, REGS_X};
+use decoder::ir::{mask, BinOp, Builder, Cmp, UnOp, Value};
+
+const LR: u16 = 30;
+const SP: u16 = 31;
+
+/// Encoding of `tpidr_el0` in `mrs` and `msr`.
+const TPIDR_EL0: u16 = 0x5e82;
+
+pub(super) fn lift(inst: &Instruction, addr: usize, builder: &mut Builder) {
+    builder.attempt(|b| {
+        Lifter {
+            inst,
+            b,
+            addr: addr as u64,
+        }
+        .lift()
+    });
+}
+
+fn imm(value: u64, bits: u8) -> Value {
+    Value::constant(value, bits)
+}
+
+fn bits(size: SizeCode) -> u8 {
+    match size {
+        SizeCode::X => 64,
+        SizeCode::W => 32,
+    }
+}
+
+/// How a load or store accesses memory.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Access {
+    Load,
+    /// Load, sign extending the value to the register.
+    LoadSigned,
+    Store,
+    /// Store exclusive, the first operand receives the status.
+    StoreExclusive,
+}
+
+/// The access and width of a load or store, a width of `None` is the width of the register.
+fn memory(opcode: Opcode) -> Option<(Access, Option<u8>)> {
+    let access = match opcode {
+        Opcode::LDR
+        | Opcode::LDUR
+        | Opcode::LDTR
+        | Opcode::LDAR
+        | Opcode::LDAXR
+        | Opcode::LDXR
+        | Opcode::LDAPR
+        | Opcode::LDAPUR
+        | Opcode::LDP
+        | Opcode::LDNP => (Access::Load, None),
+        Opcode::LDRB
+        | Opcode::LDURB
+        | Opcode::LDTRB
+        | Opcode::LDARB
+        | Opcode::LDAXRB
+        | Opcode::LDXRB
+        | Opcode::LDAPRB
+        | Opcode::LDAPURB => (Access::Load, Some(8)),
+        Opcode::LDRH
+        | Opcode::LDURH
+        | Opcode::LDTRH
+        | Opcode::LDARH
+        | Opcode::LDAXRH
+        | Opcode::LDXRH
+        | Opcode::LDAPRH
+        | Opcode::LDAPURH => (Access::Load, Some(16)),
+        Opcode::LDRSB | Opcode::LDURSB | Opcode::LDTRSB | Opcode::LDAPURSB => {
+            (Access::LoadSigned, Some(8))
+        }
+        Opcode::LDRSH | Opcode::LDURSH | Opcode::LDTRSH | Opcode::LDAPURSH => {
+            (Access::LoadSigned, Some(16))
+        }
+        Opcode::LDRSW | Opcode::LDURSW | Opcode::LDTRSW | Opcode::LDAPURSW | Opcode::LDPSW => {
+            (Access::LoadSigned, Some(32))
+        }
+        Opcode::STR
+        | Opcode::STUR
+        | Opcode::STTR
+        | Opcode::STLR
+        | Opcode::STLUR
+        | Opcode::STP
+        | Opcode::STNP => (Access::Store, None),
+        Opcode::STRB | Opcode::STURB | Opcode::STTRB | Opcode::STLRB | Opcode::STLURB => {
+            (Access::Store, Some(8))
+        }
+        Opcode::STRH | Opcode::STURH | Opcode::STTRH | Opcode::STLRH | Opcode::STLURH => {
+            (Access::Store, Some(16))
+        }
+        Opcode::STXR | Opcode::STLXR => (Access::StoreExclusive, None),
+        Opcode::STXRB | Opcode::STLXRB => (Access::StoreExclusive, Some(8)),
+        Opcode::STXRH | Opcode::STLXRH => (Access::StoreExclusive, Some(16)),
+        _ => return None,
+    };
+
+    Some(access)
+}
+
+struct Lifter<'a> {
+    inst: &'a Instruction,
+    b: &'a mut Builder,
+    addr: u64,
+}
+
+impl Lifter<'_> {
+    /// Register `reg`, where register 31 is either the zero register or the stack pointer.
+    fn get(&mut self, size: SizeCode, reg: u16, sp: bool) -> Value {
+        match reg {
+            SP if sp => self.b.reg("sp", bits(size)),
+            SP => imm(0, bits(size)),
+            _ => self.b.reg(REGS_X[reg as usize], bits(size)),
+        }
+    }
+
+    fn set(&mut self, reg: u16, value: Value, sp: bool) {
+        let value = self.b.zext(value, 64);
+        match reg {
+            SP if sp => self.b.set_reg("sp", value),
+            SP => {}
+            _ => self.b.set_reg(REGS_X[reg as usize], value),
+        }
+    }
+
+    fn flag(&mut self, name: &'static str) -> Value {
+        self.b.reg(name, 1)
+    }
+
+    /// Width in bits of a register operand.
+    fn width(&self, idx: usize) -> Option<u8> {
+        match self.inst.operands[idx] {
+            Operand::Register(size, _) | Operand::RegisterOrSP(size, _) => Some(bits(size)),
+            _ => None,
+        }
+    }
+
+    /// Value of an operand, immediates are `bits` wide.
+    fn read(&mut self, idx: usize, bits: u8) -> Option<Value> {
+        let value = match self.inst.operands[idx] {
+            Operand::Register(size, reg) => self.get(size, reg, false),
+            Operand::RegisterOrSP(size, reg) => self.get(size, reg, true),
+            Operand::Immediate(value) => imm(value as u64, bits),
+            Operand::Imm64(value) | Operand::Imm64Special(value) => imm(value, bits),
+            Operand::Imm16(value) => imm(value as u64, bits),
+            Operand::ImmShift(value, shift) => imm((value as u64) << shift, bits),
+            Operand::SignedImmediate(value) => imm(value as u64, bits),
+            Operand::RegShift(style, amount, size, reg) => {
+                let value = self.get(size, reg, false);
+                self.shifted(value, style, amount, bits)
+            }
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    fn write(&mut self, idx: usize, value: Value) -> Option<()> {
+        match self.inst.operands[idx] {
+            Operand::Register(_, reg) => self.set(reg, value, false),
+            Operand::RegisterOrSP(_, reg) => self.set(reg, value, true),
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    /// A register operand that's shifted or extended to `bits`.
+    fn shifted(&mut self, value: Value, style: ShiftStyle, amount: u8, bits: u8) -> Value {
+        let value = match style {
+            ShiftStyle::LSL | ShiftStyle::LSR | ShiftStyle::ASR | ShiftStyle::ROR => {
+                if amount == 0 {
+                    return self.b.zext(value, bits);
+                }
+
+                // an extended register is shown as a plain shift if it's already as wide
+                let op = match style {
+                    _ if value.bits() != bits => BinOp::Shl,
+                    ShiftStyle::LSL => BinOp::Shl,
+                    ShiftStyle::LSR => BinOp::LShr,
+                    ShiftStyle::ASR => BinOp::AShr,
+                    _ => BinOp::Ror,
+                };
+
+                let value = self.b.zext(value, bits);
+                return self.b.binary(op, value, imm(amount as u64, bits));
+            }
+            ShiftStyle::UXTB | ShiftStyle::SXTB => self.b.trunc(value, 8),
+            ShiftStyle::UXTH | ShiftStyle::SXTH => self.b.trunc(value, 16),
+            ShiftStyle::UXTW | ShiftStyle::SXTW => self.b.trunc(value, 32),
+            ShiftStyle::UXTX | ShiftStyle::SXTX => value,
+        };
+
+        let value = match style {
+            ShiftStyle::SXTB | ShiftStyle::SXTH | ShiftStyle::SXTW | ShiftStyle::SXTX => {
+                self.b.sext(value, bits)
+            }
+            _ => self.b.zext(value, bits),
+        };
+
+        match amount {
+            0 => value,
+            _ => self.b.binary(BinOp::Shl, value, imm(amount as u64, bits)),
+        }
+    }
+
+    /// Address accessed by a memory operand, with the value to write back to the base register.
+    fn address(&mut self, idx: usize) -> Option<(Value, Option<(u16, Value)>)> {
+        let address = match self.inst.operands[idx] {
+            Operand::RegPreIndex(base, offset, wback) => {
+                let base_value = self.get(SizeCode::X, base, true);
+                let addr = match offset {
+                    0 => base_value,
+                    _ => self.b.binary(BinOp::Add, base_value, imm(offset as i64 as u64, 64)),
+                };
+                (addr, wback.then_some((base, addr)))
+            }
+            Operand::RegPostIndex(base, offset) => {
+                let addr = self.get(SizeCode::X, base, true);
+                let next = self.b.binary(BinOp::Add, addr, imm(offset as i64 as u64, 64));
+                (addr, Some((base, next)))
+            }
+            Operand::RegPostIndexReg(base, index) => {
+                let addr = self.get(SizeCode::X, base, true);
+                let index = self.get(SizeCode::X, index, false);
+                let next = self.b.binary(BinOp::Add, addr, index);
+                (addr, Some((base, next)))
+            }
+            Operand::RegRegOffset(base, index, size, style, amount) => {
+                let base = self.get(SizeCode::X, base, true);
+                let index = self.get(size, index, false);
+                let index = self.shifted(index, style, amount, 64);
+                (self.b.binary(BinOp::Add, base, index), None)
+            }
+            Operand::Imm64(addr) => (imm(addr, 64), None),
+            Operand::PCOffset(offset) => (imm(self.addr.wrapping_add(offset as u64), 64), None),
+            _ => return None,
+        };
+
+        Some(address)
+    }
+
+    /// Destination of a branch.
+    fn target(&mut self, idx: usize) -> Option<Value> {
+        match self.inst.operands[idx] {
+            Operand::Imm64(addr) => Some(imm(addr, 64)),
+            Operand::PCOffset(offset) => Some(imm(self.addr.wrapping_add(offset as u64), 64)),
+            Operand::Register(size, reg) => Some(self.get(size, reg, false)),
+            _ => None,
+        }
+    }
+
+    fn condition(&mut self, cond: u8) -> Value {
+        // `al` and `nv` both always hold
+        if cond >= 14 {
+            return imm(1, 1);
+        }
+
+        let value = match cond >> 1 {
+            0 => self.flag("z"),
+            1 => self.flag("c"),
+            2 => self.flag("n"),
+            3 => self.flag("v"),
+            4 => {
+                let c = self.flag("c");
+                let z = self.flag("z");
+                let nz = self.b.unary(UnOp::Not, z);
+                self.b.binary(BinOp::And, c, nz)
+            }
+            5 => {
+                let n = self.flag("n");
+                let v = self.flag("v");
+                self.b.compare(Cmp::Eq, n, v)
+            }
+            _ => {
+                let z = self.flag("z");
+                let n = self.flag("n");
+                let v = self.flag("v");
+                let nz = self.b.unary(UnOp::Not, z);
+                let ge = self.b.compare(Cmp::Eq, n, v);
+                self.b.binary(BinOp::And, nz, ge)
+            }
+        };
+
+        if cond & 1 == 1 {
+            self.b.unary(UnOp::Not, value)
+        } else {
+            value
+        }
+    }
+
+    fn set_nz(&mut self, res: Value) {
+        let n = self.b.compare(Cmp::Slt, res, imm(0, res.bits()));
+        let z = self.b.compare(Cmp::Eq, res, imm(0, res.bits()));
+        self.b.set_reg("n", n);
+        self.b.set_reg("z", z);
+    }
+
+    /// Flags of `a + b + carry`, where the carry is either 1-bit or `None` for no carry.
+    fn add_flags(&mut self, a: Value, b: Value, carry: Option<Value>, res: Value) {
+        self.set_nz(res);
+
+        let c = match carry {
+            None => self.b.compare(Cmp::Ult, res, a),
+            Some(carry) => {
+                let wrapped = self.b.compare(Cmp::Ult, res, a);
+                let same = self.b.compare(Cmp::Eq, res, a);
+                let same = self.b.binary(BinOp::And, same, carry);
+                self.b.binary(BinOp::Or, wrapped, same)
+            }
+        };
+
+        let x = self.b.binary(BinOp::Xor, a, res);
+        let y = self.b.binary(BinOp::Xor, b, res);
+        let overflow = self.b.binary(BinOp::And, x, y);
+        let v = self.b.compare(Cmp::Slt, overflow, imm(0, overflow.bits()));
+        self.b.set_reg("c", c);
+        self.b.set_reg("v", v);
+    }
+
+    /// Flags of `a - b`, the carry is set if there's no borrow.
+    fn sub_flags(&mut self, a: Value, b: Value, res: Value) {
+        self.set_nz(res);
+
+        let c = self.b.compare(Cmp::Ule, b, a);
+        let x = self.b.binary(BinOp::Xor, a, b);
+        let y = self.b.binary(BinOp::Xor, a, res);
+        let overflow = self.b.binary(BinOp::And, x, y);
+        let v = self.b.compare(Cmp::Slt, overflow, imm(0, overflow.bits()));
+        self.b.set_reg("c", c);
+        self.b.set_reg("v", v);
+    }
+
+    fn lift(&mut self) -> Option<()> {
+        let inst = self.inst;
+        let opcode = inst.opcode;
+
+        if let Some((access, width)) = memory(opcode) {
+            return self.load_store(access, width);
+        }
+
+        match opcode {
+            Opcode::ADD | Opcode::ADDS | Opcode::SUB | Opcode::SUBS => {
+                let bits = self.width(0)?;
+                let a = self.read(1, bits)?;
+                let b = self.read(2, bits)?;
+                let sub = matches!(opcode, Opcode::SUB | Opcode::SUBS);
+                let res = self.b.binary(if sub { BinOp::Sub } else { BinOp::Add }, a, b);
+
+                match opcode {
+                    Opcode::ADDS => self.add_flags(a, b, None, res),
+                    Opcode::SUBS => self.sub_flags(a, b, res),
+                    _ => {}
+                }
+                self.write(0, res)?;
+            }
+            Opcode::ADC | Opcode::ADCS | Opcode::SBC | Opcode::SBCS => {
+                // subtracting with a borrow is adding the inverse with the carry
+                let bits = self.width(0)?;
+                let a = self.read(1, bits)?;
+                let mut b = self.read(2, bits)?;
+                if matches!(opcode, Opcode::SBC | Opcode::SBCS) {
+                    b = self.b.unary(UnOp::Not, b);
+                }
+
+                let carry = self.flag("c");
+                let carry_in = self.b.zext(carry, bits);
+                let res = self.b.binary(BinOp::Add, a, b);
+                let res = self.b.binary(BinOp::Add, res, carry_in);
+                if matches!(opcode, Opcode::ADCS | Opcode::SBCS) {
+                    self.add_flags(a, b, Some(carry), res);
+                }
+                self.write(0, res)?;
+            }
+            Opcode::AND
+            | Opcode::ANDS
+            | Opcode::ORR
+            | Opcode::EOR
+            | Opcode::BIC
+            | Opcode::BICS
+            | Opcode::ORN
+            | Opcode::EON => {
+                let bits = self.width(0)?;
+                let a = self.read(1, bits)?;
+                let mut b = self.read(2, bits)?;
+                if matches!(
+                    opcode,
+                    Opcode::BIC | Opcode::BICS | Opcode::ORN | Opcode::EON
+                ) {
+                    b = self.b.unary(UnOp::Not, b);
+                }
+
+                let op = match opcode {
+                    Opcode::ORR | Opcode::ORN => BinOp::Or,
+                    Opcode::EOR | Opcode::EON => BinOp::Xor,
+                    _ => BinOp::And,
+                };
+
+                // `mov` is an `orr` with the zero register
+                let res = match a.as_const() {
+                    Some(0) if op == BinOp::Or => b,
+                    _ => self.b.binary(op, a, b),
+                };
+
+                if matches!(opcode, Opcode::ANDS | Opcode::BICS) {
+                    self.set_nz(res);
+                    self.b.set_reg("c", imm(0, 1));
+                    self.b.set_reg("v", imm(0, 1));
+                }
+                self.write(0, res)?;
+            }
+            Opcode::MOVZ | Opcode::MOVN => {
+                let bits = self.width(0)?;
+                let value = self.read(1, bits)?;
+                let value = match opcode {
+                    Opcode::MOVN => self.b.unary(UnOp::Not, value),
+                    _ => value,
+                };
+                self.write(0, value)?;
+            }
+            Opcode::MOVK => {
+                let bits = self.width(0)?;
+                let Operand::ImmShift(value, shift) = inst.operands[1] else {
+                    return None;
+                };
+
+                let old = self.read(0, bits)?;
+                let keep = imm(!(0xffff_u64 << shift), bits);
+                let old = self.b.binary(BinOp::And, old, keep);
+                let value = self.b.binary(BinOp::Or, old, imm((value as u64) << shift, bits));
+                self.write(0, value)?;
+            }
+            Opcode::ADR | Opcode::ADRP => {
+                let addr = match inst.operands[1] {
+                    Operand::Imm64Special(addr) => addr,
+                    Operand::PCOffset(offset) if opcode == Opcode::ADRP => {
+                        (self.addr & !0xfff).wrapping_add(offset as u64)
+                    }
+                    Operand::PCOffset(offset) => self.addr.wrapping_add(offset as u64),
+                    _ => return None,
+                };
+                self.write(0, imm(addr, 64))?;
+            }
+            Opcode::UBFM | Opcode::SBFM | Opcode::BFM => self.bitfield()?,
+            Opcode::EXTR => {
+                let bits = self.width(0)?;
+                let high = self.read(1, bits)?;
+                let low = self.read(2, bits)?;
+                let Operand::Immediate(lsb) = inst.operands[3] else {
+                    return None;
+                };
+
+                let res = if inst.operands[1] == inst.operands[2] {
+                    self.b.binary(BinOp::Ror, low, imm(lsb as u64, bits))
+                } else if lsb == 0 {
+                    low
+                } else {
+                    let low = self.b.binary(BinOp::LShr, low, imm(lsb as u64, bits));
+                    let shift = imm(bits as u64 - lsb as u64, bits);
+                    let high = self.b.binary(BinOp::Shl, high, shift);
+                    self.b.binary(BinOp::Or, high, low)
+                };
+                self.write(0, res)?;
+            }
+            Opcode::LSLV | Opcode::LSRV | Opcode::ASRV | Opcode::RORV => {
+                let bits = self.width(0)?;
+                let a = self.read(1, bits)?;
+                let count = self.read(2, bits)?;
+                let count = self.b.binary(BinOp::And, count, imm(bits as u64 - 1, bits));
+                let op = match opcode {
+                    Opcode::LSLV => BinOp::Shl,
+                    Opcode::LSRV => BinOp::LShr,
+                    Opcode::ASRV => BinOp::AShr,
+                    _ => BinOp::Ror,
+                };
+                let res = self.b.binary(op, a, count);
+                self.write(0, res)?;
+            }
+            Opcode::MADD | Opcode::MSUB => {
+                let bits = self.width(0)?;
+                let a = self.read(1, bits)?;
+                let b = self.read(2, bits)?;
+                let acc = self.read(3, bits)?;
+                let res = self.b.binary(BinOp::Mul, a, b);
+                let res = match opcode {
+                    Opcode::MADD if acc.as_const() == Some(0) => res,
+                    Opcode::MADD => self.b.binary(BinOp::Add, acc, res),
+                    _ => self.b.binary(BinOp::Sub, acc, res),
+                };
+                self.write(0, res)?;
+            }
+            Opcode::SMADDL | Opcode::SMSUBL | Opcode::UMADDL | Opcode::UMSUBL => {
+                let a = self.read(1, 32)?;
+                let b = self.read(2, 32)?;
+                let acc = self.read(3, 64)?;
+                let (a, b) = match opcode {
+                    Opcode::SMADDL | Opcode::SMSUBL => (self.b.sext(a, 64), self.b.sext(b, 64)),
+                    _ => (self.b.zext(a, 64), self.b.zext(b, 64)),
+                };
+
+                let res = self.b.binary(BinOp::Mul, a, b);
+                let res = match (opcode, acc.as_const()) {
+                    (Opcode::SMADDL | Opcode::UMADDL, Some(0)) => res,
+                    (Opcode::SMADDL | Opcode::UMADDL, _) => self.b.binary(BinOp::Add, acc, res),
+                    _ => self.b.binary(BinOp::Sub, acc, res),
+                };
+                self.write(0, res)?;
+            }
+            Opcode::SMULH | Opcode::UMULH => {
+                let a = self.read(1, 64)?;
+                let b = self.read(2, 64)?;
+                let op = if opcode == Opcode::SMULH {
+                    BinOp::MulHiS
+                } else {
+                    BinOp::MulHiU
+                };
+                let res = self.b.binary(op, a, b);
+                self.write(0, res)?;
+            }
+            Opcode::UDIV | Opcode::SDIV => {
+                // dividing by zero results in zero rather than a fault
+                let bits = self.width(0)?;
+                let a = self.read(1, bits)?;
+                let b = self.read(2, bits)?;
+                let zero = self.b.compare(Cmp::Eq, b, imm(0, bits));
+                let divisor = self.b.select(zero, imm(1, bits), b);
+                let op = if opcode == Opcode::SDIV {
+                    BinOp::SDiv
+                } else {
+                    BinOp::UDiv
+                };
+                let res = self.b.binary(op, a, divisor);
+                let res = self.b.select(zero, imm(0, bits), res);
+                self.write(0, res)?;
+            }
+            Opcode::CLZ | Opcode::CLS | Opcode::REV => {
+                let bits = self.width(0)?;
+                let a = self.read(1, bits)?;
+                let res = match opcode {
+                    Opcode::CLZ => self.b.unary(UnOp::Clz, a),
+                    Opcode::REV => self.b.unary(UnOp::Bswap, a),
+                    _ => {
+                        let shifted = self.b.binary(BinOp::AShr, a, imm(1, bits));
+                        let changes = self.b.binary(BinOp::Xor, a, shifted);
+                        let leading = self.b.unary(UnOp::Clz, changes);
+                        self.b.binary(BinOp::Sub, leading, imm(1, bits))
+                    }
+                };
+                self.write(0, res)?;
+            }
+            Opcode::CSEL | Opcode::CSINC | Opcode::CSINV | Opcode::CSNEG => {
+                let bits = self.width(0)?;
+                let Operand::ConditionCode(cond) = inst.operands[3] else {
+                    return None;
+                };
+
+                let cond = self.condition(cond);
+                let a = self.read(1, bits)?;
+                let b = self.read(2, bits)?;
+                let b = match opcode {
+                    Opcode::CSINC => self.b.binary(BinOp::Add, b, imm(1, bits)),
+                    Opcode::CSINV => self.b.unary(UnOp::Not, b),
+                    Opcode::CSNEG => self.b.unary(UnOp::Neg, b),
+                    _ => b,
+                };
+                let res = self.b.select(cond, a, b);
+                self.write(0, res)?;
+            }
+            Opcode::CCMP | Opcode::CCMN => {
+                let bits = self.width(0)?;
+                let (Operand::Immediate(nzcv), Operand::ConditionCode(cond)) =
+                    (inst.operands[2], inst.operands[3])
+                else {
+                    return None;
+                };
+
+                let cond = self.condition(cond);
+                let a = self.read(0, bits)?;
+                let b = self.read(1, bits)?;
+                if opcode == Opcode::CCMP {
+                    let res = self.b.binary(BinOp::Sub, a, b);
+                    self.sub_flags(a, b, res);
+                } else {
+                    let res = self.b.binary(BinOp::Add, a, b);
+                    self.add_flags(a, b, None, res);
+                }
+
+                // the flags are set to the immediate if the condition doesn't hold
+                for (bit, flag) in ["n", "z", "c", "v"].into_iter().enumerate() {
+                    let compared = self.flag(flag);
+                    let given = imm((nzcv as u64 >> (3 - bit)) & 1, 1);
+                    let value = self.b.select(cond, compared, given);
+                    self.b.set_reg(flag, value);
+                }
+            }
+            Opcode::B => {
+                let target = self.target(0)?;
+                self.b.jump(target);
+            }
+            Opcode::Bcc(cond) => {
+                let cond = self.condition(cond);
+                let target = self.target(0)?;
+                self.b.branch(cond, target);
+            }
+            Opcode::CBZ | Opcode::CBNZ => {
+                let bits = self.width(0)?;
+                let value = self.read(0, bits)?;
+                let cmp = if opcode == Opcode::CBZ {
+                    Cmp::Eq
+                } else {
+                    Cmp::Ne
+                };
+                let cond = self.b.compare(cmp, value, imm(0, bits));
+                let target = self.target(1)?;
+                self.b.branch(cond, target);
+            }
+            Opcode::TBZ | Opcode::TBNZ => {
+                let bits = self.width(0)?;
+                let value = self.read(0, bits)?;
+                let bit = self.read(1, bits)?;
+                let value = self.b.binary(BinOp::LShr, value, bit);
+                let mut cond = self.b.trunc(value, 1);
+                if opcode == Opcode::TBZ {
+                    cond = self.b.unary(UnOp::Not, cond);
+                }
+                let target = self.target(2)?;
+                self.b.branch(cond, target);
+            }
+            Opcode::BL | Opcode::BLR => {
+                let target = self.target(0)?;
+                self.set(LR, imm(self.addr.wrapping_add(4), 64), false);
+                self.b.call(target);
+            }
+            Opcode::BR => {
+                let target = self.target(0)?;
+                self.b.jump(target);
+            }
+            Opcode::RET => {
+                let target = match inst.operands[0] {
+                    Operand::Nothing => self.get(SizeCode::X, LR, false),
+                    _ => self.target(0)?,
+                };
+                self.b.ret(target);
+            }
+            Opcode::MRS => {
+                let Operand::SystemReg(TPIDR_EL0) = inst.operands[1] else {
+                    return None;
+                };
+
+                let value = self.b.reg("tpidr_el0", 64);
+                self.write(0, value)?;
+            }
+            Opcode::MSR => {
+                let Operand::SystemReg(TPIDR_EL0) = inst.operands[0] else {
+                    return None;
+                };
+
+                let value = self.read(1, 64)?;
+                self.b.set_reg("tpidr_el0", value);
+            }
+            // pointer authentication and branch target hints don't change the behavior of
+            // code that's valid
+            Opcode::HINT => {}
+            Opcode::PRFM | Opcode::PRFUM => {}
+            Opcode::DMB(_) | Opcode::DSB(_) | Opcode::ISB => self.b.intrinsic("fence", Vec::new()),
+            Opcode::SVC | Opcode::BRK | Opcode::HLT => {
+                let value = self.read(0, 16)?;
+                let name = match opcode {
+                    Opcode::SVC => "svc",
+                    Opcode::BRK => "brk",
+                    _ => "hlt",
+                };
+                self.b.intrinsic(name, vec![value]);
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    /// `ubfm`, `sbfm` and `bfm`, which are behind shifts, extensions and bitfield moves.
+    fn bitfield(&mut self) -> Option<()> {
+        let inst = self.inst;
+        let opcode = inst.opcode;
+        let bits = self.width(0)?;
+        let (Operand::Immediate(immr), Operand::Immediate(imms)) =
+            (inst.operands[2], inst.operands[3])
+        else {
+            return None;
+        };
+
+        let (immr, imms) = (immr as u8, imms as u8);
+        let src = self.read(1, bits)?;
+        let src = self.b.trunc(src, bits);
+
+        if opcode != Opcode::BFM {
+            // shifts by a constant
+            if imms == bits - 1 {
+                let op = if opcode == Opcode::SBFM {
+                    BinOp::AShr
+                } else {
+                    BinOp::LShr
+                };
+                let res = self.b.binary(op, src, imm(immr as u64, bits));
+                return self.write(0, res);
+            }
+
+            if opcode == Opcode::UBFM && imms + 1 == immr {
+                let res = self.b.binary(BinOp::Shl, src, imm((bits - immr) as u64, bits));
+                return self.write(0, res);
+            }
+        }
+
+        // either a field starting at `immr` is extracted, or the low bits are inserted at the
+        // position `bits - immr`
+        let (field, width, pos) = if imms >= immr {
+            let field = match immr {
+                0 => src,
+                _ => self.b.binary(BinOp::LShr, src, imm(immr as u64, bits)),
+            };
+            (field, imms - immr + 1, 0)
+        } else {
+            (src, imms + 1, bits - immr)
+        };
+
+        let field = self.b.trunc(field, width);
+        let field = match opcode {
+            Opcode::SBFM => self.b.sext(field, bits),
+            _ => self.b.zext(field, bits),
+        };
+
+        let field = match pos {
+            0 => field,
+            _ => self.b.binary(BinOp::Shl, field, imm(pos as u64, bits)),
+        };
+
+        let res = if opcode == Opcode::BFM {
+            let old = self.read(0, bits)?;
+            let keep = imm(!(mask(width) << pos), bits);
+            let old = self.b.binary(BinOp::And, old, keep);
+            self.b.binary(BinOp::Or, old, field)
+        } else {
+            field
+        };
+
+        self.write(0, res)
+    }
+
+    fn load_store(&mut self, access: Access, width: Option<u8>) -> Option<()> {
+        let inst = self.inst;
+
+        // the status of a store exclusive comes first
+        let regs = match access {
+            Access::StoreExclusive => 1..2,
+            _ => match inst.operands[1] {
+                Operand::Register(..) => 0..2,
+                _ => 0..1,
+            },
+        };
+
+        let bits = self.width(regs.start)?;
+        let width = width.unwrap_or(bits);
+        let bytes = width as u64 / 8;
+        let (addr, writeback) = self.address(regs.end)?;
+
+        for (idx, reg) in regs.clone().enumerate() {
+            let addr = match idx {
+                0 => addr,
+                _ => self.b.binary(BinOp::Add, addr, imm(bytes * idx as u64, 64)),
+            };
+
+            match access {
+                Access::Load | Access::LoadSigned => {
+                    let value = self.b.load(addr, width);
+                    let value = match access {
+                        Access::LoadSigned => self.b.sext(value, bits),
+                        _ => self.b.zext(value, bits),
+                    };
+                    self.write(reg, value)?;
+                }
+                Access::Store | Access::StoreExclusive => {
+                    let value = self.read(reg, bits)?;
+                    let value = self.b.trunc(value, width);
+                    self.b.store(addr, value);
+                }
+            }
+        }
+
+        // the exclusive monitor isn't modeled, so storing always succeeds
+        if access == Access::StoreExclusive {
+            self.write(0, imm(0, 32))?;
+        }
+
+        if let Some((base, value)) = writeback {
+            self.set(base, value, true);
+        }
+
+        Some(())
+    }
+}
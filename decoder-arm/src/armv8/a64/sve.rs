@@ -0,0 +1,1687 @@
+//! Scalable Vector Extension (`SVE`, `SVE2`) and Scalable Matrix Extension (`SME`) decoding.
+//!
+//! The common integer, floating-point, predicate and permute groups are decoded here, along with
+//! the loads and stores: contiguous, first-faulting, broadcast, gathers, scatters and prefetches.
+//! Encodings in the remaining groups (such as the `FFR` and complex number instructions) are
+//! reported as `ErrorKind::IncompleteDecoder`, not as invalid instructions.
+
+use crate::armv8::a64::{docs, Instruction, Opcode, Operand, SIMDSizeCode, ShiftStyle, SizeCode};
+
+use decoder::ErrorKind;
+
+const SIZES: [SIMDSizeCode; 4] = [
+    SIMDSizeCode::B,
+    SIMDSizeCode::H,
+    SIMDSizeCode::S,
+    SIMDSizeCode::D,
+];
+
+/// bits `23:22`, the element size of most vector instructions.
+fn size(word: u32) -> SIMDSizeCode {
+    SIZES[((word >> 22) & 0b11) as usize]
+}
+
+/// `Zd`, bits `4:0`.
+fn zd(word: u32) -> u16 {
+    (word & 0b11111) as u16
+}
+
+/// `Zn`, bits `9:5`.
+fn zn(word: u32) -> u16 {
+    ((word >> 5) & 0b11111) as u16
+}
+
+/// `Zm`, bits `20:16`.
+fn zm(word: u32) -> u16 {
+    ((word >> 16) & 0b11111) as u16
+}
+
+/// `Pg`, the governing predicate of a predicated vector instruction, bits `12:10`.
+fn pg(word: u32) -> u16 {
+    ((word >> 10) & 0b111) as u16
+}
+
+/// `Pd`, the destination of an instruction writing a predicate, bits `3:0`.
+fn pd(word: u32) -> u16 {
+    (word & 0b1111) as u16
+}
+
+fn z(reg: u16, size: SIMDSizeCode) -> Operand {
+    Operand::ZRegisterElements(reg, size)
+}
+
+fn p(reg: u16, size: SIMDSizeCode) -> Operand {
+    Operand::PRegisterElements(reg, size)
+}
+
+/// a general-purpose register, `x` for 64-bit elements and `w` otherwise.
+fn element_register(reg: u16, size: SIMDSizeCode) -> Operand {
+    if size == SIMDSizeCode::D {
+        Operand::Register(SizeCode::X, reg)
+    } else {
+        Operand::Register(SizeCode::W, reg)
+    }
+}
+
+/// sign-extend the low `bits` bits of `value`.
+fn sign_extend(value: u32, bits: u32) -> i64 {
+    ((value << (32 - bits)) as i32 >> (32 - bits)) as i64
+}
+
+/// the element size and shift amount of a shift by immediate, encoded as `tsz:imm3`. right shifts
+/// encode `2 * esize - shift`, left shifts encode `esize + shift`.
+fn shift_immediate(
+    tsz: u32,
+    imm3: u32,
+    left: bool,
+) -> Result<(SIMDSizeCode, Operand), ErrorKind> {
+    if tsz == 0 {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
+    let size = 31 - tsz.leading_zeros();
+    let esize = 8 << size;
+    let imm = (tsz << 3) | imm3;
+    let shift = if left { imm - esize } else { 2 * esize - imm };
+    Ok((SIZES[size as usize], Operand::Imm16(shift as u16)))
+}
+
+/// the predicate constraint of an element count, omitted when it is the default `all` without a
+/// multiplier.
+fn pattern(pattern: u32, mul: u32) -> Operand {
+    if pattern == 0b11111 && mul == 1 {
+        Operand::Nothing
+    } else {
+        Operand::SVEPattern(pattern as u8, mul as u8)
+    }
+}
+
+/// `SVE encodings`, top-level bits `0010`.
+pub(super) fn decode_sve(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    match word >> 24 {
+        0x04 => {
+            if (word >> 21) & 1 == 0 {
+                int_predicated(word, inst)
+            } else {
+                int_unpredicated(word, inst)
+            }
+        }
+        0x05 => permute(word, inst),
+        0x24 => compare_vectors(word, inst),
+        0x25 => {
+            if (word >> 21) & 1 == 0 {
+                predicates(word, inst)
+            } else {
+                predicate_count_and_immediates(word, inst)
+            }
+        }
+        0x44 | 0x45 => sve2(word, inst),
+        0x65 => floating_point(word, inst),
+        0x84 | 0x85 | 0xc4 | 0xc5 => gather(word, inst),
+        0xa4 | 0xa5 => contiguous_load(word, inst),
+        0xe4 | 0xe5 => contiguous_store(word, inst),
+        _ => Err(ErrorKind::IncompleteDecoder),
+    }
+}
+
+/// `SVE Integer Binary Arithmetic - Predicated` and the rest of the predicated integer groups:
+/// reductions, multiply-add, shifts and unary operations.
+fn int_predicated(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let size = size(word);
+    let pg = pg(word);
+    let opc = (word >> 16) & 0b11111;
+
+    match (word >> 13) & 0b111 {
+        0b000 => {
+            inst.opcode = match opc {
+                0b00000 => Opcode::ADD,
+                0b00001 => Opcode::SUB,
+                0b00011 => Opcode::SUBR,
+                0b01000 => Opcode::SMAX,
+                0b01001 => Opcode::UMAX,
+                0b01010 => Opcode::SMIN,
+                0b01011 => Opcode::UMIN,
+                0b01100 => Opcode::SABD,
+                0b01101 => Opcode::UABD,
+                0b10000 => Opcode::MUL,
+                0b10010 => Opcode::SMULH,
+                0b10011 => Opcode::UMULH,
+                0b10100 => Opcode::SDIV,
+                0b10101 => Opcode::UDIV,
+                0b10110 => Opcode::SDIVR,
+                0b10111 => Opcode::UDIVR,
+                0b11000 => Opcode::ORR,
+                0b11001 => Opcode::EOR,
+                0b11010 => Opcode::AND,
+                0b11011 => Opcode::BIC,
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+            if opc & 0b11100 == 0b10100 && (size == SIMDSizeCode::B || size == SIMDSizeCode::H) {
+                return Err(ErrorKind::InvalidOperand);
+            }
+            inst.operands = [
+                z(zd(word), size),
+                Operand::PRegisterMerging(pg),
+                z(zd(word), size),
+                z(zn(word), size),
+            ];
+        }
+        0b001 => {
+            let (opcode, dest_size) = match opc {
+                0b00000 if size != SIMDSizeCode::D => (Opcode::SADDV, SIMDSizeCode::D),
+                0b00001 => (Opcode::UADDV, SIMDSizeCode::D),
+                0b01000 => (Opcode::SMAXV, size),
+                0b01001 => (Opcode::UMAXV, size),
+                0b01010 => (Opcode::SMINV, size),
+                0b01011 => (Opcode::UMINV, size),
+                0b10000 | 0b10001 => {
+                    let predicate = if opc & 1 == 1 {
+                        Operand::PRegisterMerging(pg)
+                    } else {
+                        Operand::PRegisterZeroing(pg)
+                    };
+                    inst.opcode = Opcode::MOVPRFX;
+                    inst.operands = [
+                        z(zd(word), size),
+                        predicate,
+                        z(zn(word), size),
+                        Operand::Nothing,
+                    ];
+                    return Ok(());
+                }
+                0b11000 => (Opcode::ORV, size),
+                0b11001 => (Opcode::EORV, size),
+                0b11010 => (Opcode::ANDV, size),
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+            inst.opcode = opcode;
+            inst.operands = [
+                Operand::SIMDRegister(dest_size, zd(word)),
+                Operand::PRegister(pg),
+                z(zn(word), size),
+                Operand::Nothing,
+            ];
+        }
+        0b010 | 0b011 => {
+            inst.opcode = if (word >> 13) & 1 == 0 {
+                Opcode::MLA
+            } else {
+                Opcode::MLS
+            };
+            inst.operands = [
+                z(zd(word), size),
+                Operand::PRegisterMerging(pg),
+                z(zn(word), size),
+                z(zm(word), size),
+            ];
+        }
+        0b110 | 0b111 => {
+            inst.opcode = if (word >> 13) & 1 == 0 {
+                Opcode::MAD
+            } else {
+                Opcode::MSB
+            };
+            inst.operands = [
+                z(zd(word), size),
+                Operand::PRegisterMerging(pg),
+                z(zm(word), size),
+                z(zn(word), size),
+            ];
+        }
+        0b100 => match (word >> 19) & 0b11 {
+            0b00 => {
+                let (opcode, left) = match (word >> 16) & 0b111 {
+                    0b000 => (Opcode::ASR, false),
+                    0b001 => (Opcode::LSR, false),
+                    0b011 => (Opcode::LSL, true),
+                    0b100 => (Opcode::ASRD, false),
+                    _ => return Err(ErrorKind::IncompleteDecoder),
+                };
+                let tsz = (((word >> 22) & 0b11) << 2) | ((word >> 8) & 0b11);
+                let (size, shift) = shift_immediate(tsz, (word >> 5) & 0b111, left)?;
+                inst.opcode = opcode;
+                inst.operands = [
+                    z(zd(word), size),
+                    Operand::PRegisterMerging(pg),
+                    z(zd(word), size),
+                    shift,
+                ];
+            }
+            0b10 => {
+                inst.opcode = match (word >> 16) & 0b111 {
+                    0b000 => Opcode::ASR,
+                    0b001 => Opcode::LSR,
+                    0b011 => Opcode::LSL,
+                    0b100 => Opcode::ASRR,
+                    0b101 => Opcode::LSRR,
+                    0b111 => Opcode::LSLR,
+                    _ => return Err(ErrorKind::InvalidOpcode),
+                };
+                inst.operands = [
+                    z(zd(word), size),
+                    Operand::PRegisterMerging(pg),
+                    z(zd(word), size),
+                    z(zn(word), size),
+                ];
+            }
+            _ => return Err(ErrorKind::IncompleteDecoder),
+        },
+        _ => {
+            // 0b101, unary operations
+            let opc = (word >> 16) & 0b111;
+            let (opcode, valid) = match (word >> 19) & 0b11 {
+                0b10 => match opc {
+                    0b000 => (Opcode::SXTB, size != SIMDSizeCode::B),
+                    0b001 => (Opcode::UXTB, size != SIMDSizeCode::B),
+                    0b010 => (Opcode::SXTH, size == SIMDSizeCode::S || size == SIMDSizeCode::D),
+                    0b011 => (Opcode::UXTH, size == SIMDSizeCode::S || size == SIMDSizeCode::D),
+                    0b100 => (Opcode::SXTW, size == SIMDSizeCode::D),
+                    0b101 => (Opcode::UXTW, size == SIMDSizeCode::D),
+                    0b110 => (Opcode::ABS, true),
+                    _ => (Opcode::NEG, true),
+                },
+                0b11 => match opc {
+                    0b000 => (Opcode::CLS, true),
+                    0b001 => (Opcode::CLZ, true),
+                    0b010 => (Opcode::CNT, true),
+                    0b011 => (Opcode::CNOT, true),
+                    0b100 => (Opcode::FABS, size != SIMDSizeCode::B),
+                    0b101 => (Opcode::FNEG, size != SIMDSizeCode::B),
+                    0b110 => (Opcode::NOT, true),
+                    _ => return Err(ErrorKind::InvalidOpcode),
+                },
+                _ => return Err(ErrorKind::IncompleteDecoder),
+            };
+            if !valid {
+                return Err(ErrorKind::InvalidOperand);
+            }
+            inst.opcode = opcode;
+            inst.operands = [
+                z(zd(word), size),
+                Operand::PRegisterMerging(pg),
+                z(zn(word), size),
+                Operand::Nothing,
+            ];
+        }
+    }
+
+    Ok(())
+}
+
+/// unpredicated integer arithmetic, bitwise operations, `INDEX`, stack frame sizes, shifts by
+/// immediate and element counts.
+fn int_unpredicated(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let size = size(word);
+
+    match (word >> 13) & 0b111 {
+        0b000 => {
+            inst.opcode = match (word >> 10) & 0b111 {
+                0b000 => Opcode::ADD,
+                0b001 => Opcode::SUB,
+                0b100 => Opcode::SQADD,
+                0b101 => Opcode::UQADD,
+                0b110 => Opcode::SQSUB,
+                0b111 => Opcode::UQSUB,
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+            inst.operands = [
+                z(zd(word), size),
+                z(zn(word), size),
+                z(zm(word), size),
+                Operand::Nothing,
+            ];
+        }
+        0b001 => {
+            if (word >> 10) & 0b111 == 0b100 {
+                inst.opcode = [Opcode::AND, Opcode::ORR, Opcode::EOR, Opcode::BIC]
+                    [((word >> 22) & 0b11) as usize];
+                inst.operands = [
+                    z(zd(word), SIMDSizeCode::D),
+                    z(zn(word), SIMDSizeCode::D),
+                    z(zm(word), SIMDSizeCode::D),
+                    Operand::Nothing,
+                ];
+            } else if (word >> 11) & 0b11 == 0b11 {
+                // `SVE2 bitwise ternary operations`
+                inst.opcode = match ((word >> 22) & 0b11, (word >> 10) & 1) {
+                    (0b00, 0) => Opcode::EOR3,
+                    (0b01, 0) => Opcode::BCAX,
+                    (0b00, _) => Opcode::BSL,
+                    (0b01, _) => Opcode::BSL1N,
+                    (0b10, 1) => Opcode::BSL2N,
+                    (0b11, 1) => Opcode::NBSL,
+                    _ => return Err(ErrorKind::InvalidOpcode),
+                };
+                inst.operands = [
+                    z(zd(word), SIMDSizeCode::D),
+                    z(zd(word), SIMDSizeCode::D),
+                    z(zm(word), SIMDSizeCode::D),
+                    z(zn(word), SIMDSizeCode::D),
+                ];
+            } else {
+                return Err(ErrorKind::IncompleteDecoder);
+            }
+        }
+        0b010 => {
+            if (word >> 12) & 1 == 0 {
+                let first = (word >> 5) & 0b11111;
+                let second = (word >> 16) & 0b11111;
+                let start = if (word >> 10) & 1 == 0 {
+                    Operand::SignedImmediate(sign_extend(first, 5))
+                } else {
+                    element_register(first as u16, size)
+                };
+                let increment = if (word >> 11) & 1 == 0 {
+                    Operand::SignedImmediate(sign_extend(second, 5))
+                } else {
+                    element_register(second as u16, size)
+                };
+                inst.opcode = Opcode::INDEX;
+                inst.operands = [z(zd(word), size), start, increment, Operand::Nothing];
+                return Ok(());
+            }
+
+            let sme = (word >> 11) & 1 == 1;
+            let imm6 = Operand::SignedImmediate(sign_extend((word >> 5) & 0b111111, 6));
+            let (opcode, reads) = match ((word >> 22) & 0b11, sme) {
+                (0b00, false) => (Opcode::ADDVL, true),
+                (0b01, false) => (Opcode::ADDPL, true),
+                (0b10, false) => (Opcode::RDVL, false),
+                (0b00, true) => (Opcode::ADDSVL, true),
+                (0b01, true) => (Opcode::ADDSPL, true),
+                (0b10, true) => (Opcode::RDSVL, false),
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+            inst.opcode = opcode;
+            if reads {
+                inst.operands = [
+                    Operand::RegisterOrSP(SizeCode::X, zd(word)),
+                    Operand::RegisterOrSP(SizeCode::X, zm(word)),
+                    imm6,
+                    Operand::Nothing,
+                ];
+            } else {
+                if zm(word) != 0b11111 {
+                    return Err(ErrorKind::InvalidOperand);
+                }
+                inst.operands = [
+                    Operand::Register(SizeCode::X, zd(word)),
+                    imm6,
+                    Operand::Nothing,
+                    Operand::Nothing,
+                ];
+            }
+        }
+        0b011 => {
+            // `SVE2 integer multiply vectors (unpredicated)`
+            inst.opcode = match (word >> 10) & 0b111 {
+                0b000 => Opcode::MUL,
+                0b001 if size == SIMDSizeCode::B => Opcode::PMUL,
+                0b010 => Opcode::SMULH,
+                0b011 => Opcode::UMULH,
+                0b100 => Opcode::SQDMULH,
+                0b101 => Opcode::SQRDMULH,
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+            inst.operands = [
+                z(zd(word), size),
+                z(zn(word), size),
+                z(zm(word), size),
+                Operand::Nothing,
+            ];
+        }
+        0b100 => {
+            if (word >> 12) & 1 == 0 {
+                return Err(ErrorKind::IncompleteDecoder);
+            }
+            let (opcode, left) = match (word >> 10) & 0b11 {
+                0b00 => (Opcode::ASR, false),
+                0b01 => (Opcode::LSR, false),
+                0b11 => (Opcode::LSL, true),
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+            let tsz = (((word >> 22) & 0b11) << 2) | ((word >> 19) & 0b11);
+            let (size, shift) = shift_immediate(tsz, (word >> 16) & 0b111, left)?;
+            inst.opcode = opcode;
+            inst.operands = [z(zd(word), size), z(zn(word), size), shift, Operand::Nothing];
+        }
+        0b101 => {
+            if word & 0xfffffc00 != 0x0420bc00 {
+                return Err(ErrorKind::IncompleteDecoder);
+            }
+            inst.opcode = Opcode::MOVPRFX;
+            inst.operands = [
+                Operand::ZRegister(zd(word)),
+                Operand::ZRegister(zn(word)),
+                Operand::Nothing,
+                Operand::Nothing,
+            ];
+        }
+        _ => {
+            // `SVE element count`, and increments/decrements by it
+            let vector = (word >> 13) & 1 == 0;
+            let sf = (word >> 20) & 1 == 1;
+            let dec = (word >> 10) & 1 == 1;
+            let elements = ((word >> 22) & 0b11) as usize;
+            let mul = ((word >> 16) & 0b1111) + 1;
+            let pattern = pattern((word >> 5) & 0b11111, mul);
+
+            const COUNTS: [[Opcode; 4]; 3] = [
+                [Opcode::CNTB, Opcode::CNTH, Opcode::CNTW, Opcode::CNTD],
+                [Opcode::INCB, Opcode::INCH, Opcode::INCW, Opcode::INCD],
+                [Opcode::DECB, Opcode::DECH, Opcode::DECW, Opcode::DECD],
+            ];
+            // by `D:U`, the saturating forms keep `U` in the bit the others use for `D`.
+            const SATURATING: [[Opcode; 4]; 4] = [
+                [Opcode::SQINCB, Opcode::SQINCH, Opcode::SQINCW, Opcode::SQINCD],
+                [Opcode::UQINCB, Opcode::UQINCH, Opcode::UQINCW, Opcode::UQINCD],
+                [Opcode::SQDECB, Opcode::SQDECH, Opcode::SQDECW, Opcode::SQDECD],
+                [Opcode::UQDECB, Opcode::UQDECH, Opcode::UQDECW, Opcode::UQDECD],
+            ];
+            let saturating = SATURATING[((word >> 10) & 0b11) as usize][elements];
+
+            match (vector, sf, (word >> 11) & 0b11) {
+                (false, false, 0b00) if !dec => {
+                    inst.opcode = COUNTS[0][elements];
+                    inst.operands = [
+                        Operand::Register(SizeCode::X, zd(word)),
+                        pattern,
+                        Operand::Nothing,
+                        Operand::Nothing,
+                    ];
+                }
+                (_, true, 0b00) => {
+                    let dest = if vector {
+                        if size == SIMDSizeCode::B {
+                            return Err(ErrorKind::InvalidOperand);
+                        }
+                        z(zd(word), size)
+                    } else {
+                        Operand::Register(SizeCode::X, zd(word))
+                    };
+                    inst.opcode = COUNTS[if dec { 2 } else { 1 }][elements];
+                    inst.operands = [dest, pattern, Operand::Nothing, Operand::Nothing];
+                }
+                (true, false, 0b00 | 0b01) => {
+                    if size == SIMDSizeCode::B {
+                        return Err(ErrorKind::InvalidOperand);
+                    }
+                    inst.opcode = saturating;
+                    inst.operands = [
+                        z(zd(word), size),
+                        pattern,
+                        Operand::Nothing,
+                        Operand::Nothing,
+                    ];
+                }
+                (false, _, 0b10 | 0b11) => {
+                    // a signed 32-bit result is sign-extended, so the register is named as both
+                    // `x` and `w`.
+                    inst.opcode = saturating;
+                    inst.operands = match (sf, (word >> 10) & 1) {
+                        (true, _) => [
+                            Operand::Register(SizeCode::X, zd(word)),
+                            pattern,
+                            Operand::Nothing,
+                            Operand::Nothing,
+                        ],
+                        (false, 0) => [
+                            Operand::Register(SizeCode::X, zd(word)),
+                            Operand::Register(SizeCode::W, zd(word)),
+                            pattern,
+                            Operand::Nothing,
+                        ],
+                        (false, _) => [
+                            Operand::Register(SizeCode::W, zd(word)),
+                            pattern,
+                            Operand::Nothing,
+                            Operand::Nothing,
+                        ],
+                    };
+                }
+                _ => return Err(ErrorKind::InvalidOpcode),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// bitwise operations with an immediate, and the permute groups: `DUP`, `INSR`, `REV`, `TBL`,
+/// interleaves and `SEL`.
+fn permute(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let size = size(word);
+
+    if (word >> 21) & 1 == 0 {
+        if (word >> 18) & 0b111 != 0b000 {
+            return Err(ErrorKind::IncompleteDecoder);
+        }
+        let N = ((word >> 17) & 1) as u8;
+        let immr = ((word >> 11) & 0b111111) as u8;
+        let imms = ((word >> 5) & 0b111111) as u8;
+        let (wmask, _) = docs::DecodeBitMasks_64(N, imms, immr)?;
+        let size = if N == 1 {
+            SIMDSizeCode::D
+        } else if imms & 0b100000 == 0 {
+            SIMDSizeCode::S
+        } else if imms & 0b010000 == 0 {
+            SIMDSizeCode::H
+        } else {
+            SIMDSizeCode::B
+        };
+        let ones = match size {
+            SIMDSizeCode::B => 0xff,
+            SIMDSizeCode::H => 0xffff,
+            SIMDSizeCode::S => 0xffff_ffff,
+            _ => u64::MAX,
+        };
+        // elements of all ones are a reserved encoding.
+        let imm = wmask & ones;
+        if imm == ones {
+            return Err(ErrorKind::InvalidOperand);
+        }
+
+        let opc = (word >> 22) & 0b11;
+        inst.opcode = [Opcode::ORR, Opcode::EOR, Opcode::AND, Opcode::DUPM][opc as usize];
+        inst.operands = if opc == 0b11 {
+            [
+                z(zd(word), size),
+                Operand::Imm64Special(imm),
+                Operand::Nothing,
+                Operand::Nothing,
+            ]
+        } else {
+            [
+                z(zd(word), size),
+                z(zd(word), size),
+                Operand::Imm64Special(imm),
+                Operand::Nothing,
+            ]
+        };
+        return Ok(());
+    }
+
+    if (word >> 14) & 0b11 == 0b11 {
+        inst.opcode = Opcode::SEL;
+        inst.operands = [
+            z(zd(word), size),
+            Operand::PRegister(((word >> 10) & 0b1111) as u16),
+            z(zn(word), size),
+            z(zm(word), size),
+        ];
+        return Ok(());
+    }
+
+    if (word >> 13) & 0b111 == 0b011 {
+        inst.opcode = match (word >> 10) & 0b111 {
+            0b000 => Opcode::ZIP1,
+            0b001 => Opcode::ZIP2,
+            0b010 => Opcode::UZP1,
+            0b011 => Opcode::UZP2,
+            0b100 => Opcode::TRN1,
+            0b101 => Opcode::TRN2,
+            _ => return Err(ErrorKind::InvalidOpcode),
+        };
+        inst.operands = [
+            z(zd(word), size),
+            z(zn(word), size),
+            z(zm(word), size),
+            Operand::Nothing,
+        ];
+        return Ok(());
+    }
+
+    match (word >> 10) & 0b111111 {
+        0b001000 => {
+            // `DUP (indexed)`: the lowest set bit of `tsz` selects the element size, and the
+            // bits above it are the index.
+            let imm = (((word >> 22) & 0b11) << 5) | ((word >> 16) & 0b11111);
+            if imm & 0b11111 == 0 {
+                return Err(ErrorKind::InvalidOperand);
+            }
+            let size_bits = imm.trailing_zeros();
+            let size = [
+                SIMDSizeCode::B,
+                SIMDSizeCode::H,
+                SIMDSizeCode::S,
+                SIMDSizeCode::D,
+                SIMDSizeCode::Q,
+            ][size_bits as usize];
+            let index = imm >> (size_bits + 1);
+            inst.opcode = Opcode::DUP;
+            inst.operands = [
+                z(zd(word), size),
+                if index == 0 {
+                    Operand::SIMDRegister(size, zn(word))
+                } else {
+                    Operand::ZRegisterElementsLane(zn(word), size, index as u8)
+                },
+                Operand::Nothing,
+                Operand::Nothing,
+            ];
+        }
+        0b001100 => {
+            inst.opcode = Opcode::TBL;
+            inst.operands = [
+                z(zd(word), size),
+                Operand::ZRegisterGroup(zn(word), size, 1),
+                z(zm(word), size),
+                Operand::Nothing,
+            ];
+        }
+        0b001110 => {
+            let (opcode, source) = match (word >> 16) & 0b11111 {
+                0b00000 => {
+                    let reg = if size == SIMDSizeCode::D {
+                        Operand::RegisterOrSP(SizeCode::X, zn(word))
+                    } else {
+                        Operand::RegisterOrSP(SizeCode::W, zn(word))
+                    };
+                    (Opcode::DUP, reg)
+                }
+                0b00100 => (Opcode::INSR, element_register(zn(word), size)),
+                0b10100 => (Opcode::INSR, Operand::SIMDRegister(size, zn(word))),
+                0b11000 => (Opcode::REV, z(zn(word), size)),
+                _ => return Err(ErrorKind::IncompleteDecoder),
+            };
+            inst.opcode = opcode;
+            inst.operands = [z(zd(word), size), source, Operand::Nothing, Operand::Nothing];
+        }
+        _ => return Err(ErrorKind::IncompleteDecoder),
+    }
+
+    Ok(())
+}
+
+/// `SVE Integer Compare - Vectors` and `SVE Integer Compare - Unsigned Immediate`.
+fn compare_vectors(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let size = size(word);
+    let ne = (word >> 4) & 1;
+
+    let source = if (word >> 21) & 1 == 0 {
+        if (word >> 14) & 1 == 1 {
+            return Err(ErrorKind::IncompleteDecoder);
+        }
+        inst.opcode = match ((word >> 15) & 1, (word >> 13) & 1, ne) {
+            (0, 0, 0) => Opcode::CMPHS,
+            (0, 0, _) => Opcode::CMPHI,
+            (1, 0, 0) => Opcode::CMPGE,
+            (1, 0, _) => Opcode::CMPGT,
+            (1, _, 0) => Opcode::CMPEQ,
+            (1, _, _) => Opcode::CMPNE,
+            _ => return Err(ErrorKind::IncompleteDecoder),
+        };
+        z(zm(word), size)
+    } else {
+        inst.opcode = match ((word >> 13) & 1, ne) {
+            (0, 0) => Opcode::CMPHS,
+            (0, _) => Opcode::CMPHI,
+            (_, 0) => Opcode::CMPLO,
+            (_, _) => Opcode::CMPLS,
+        };
+        Operand::Imm64Special(((word >> 14) & 0b1111111) as u64)
+    };
+
+    inst.operands = [
+        p(pd(word), size),
+        Operand::PRegisterZeroing(pg(word)),
+        z(zn(word), size),
+        source,
+    ];
+    Ok(())
+}
+
+/// compares with a signed immediate, predicate logical operations, `PTRUE`, `PFALSE` and `PTEST`.
+fn predicates(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let size = size(word);
+
+    if (word >> 14) & 1 == 0 {
+        inst.opcode = match ((word >> 15) & 1, (word >> 13) & 1, (word >> 4) & 1) {
+            (0, 0, 0) => Opcode::CMPGE,
+            (0, 0, _) => Opcode::CMPGT,
+            (0, _, 0) => Opcode::CMPLT,
+            (0, _, _) => Opcode::CMPLE,
+            (_, 0, 0) => Opcode::CMPEQ,
+            (_, 0, _) => Opcode::CMPNE,
+            _ => return Err(ErrorKind::InvalidOpcode),
+        };
+        inst.operands = [
+            p(pd(word), size),
+            Operand::PRegisterZeroing(pg(word)),
+            z(zn(word), size),
+            Operand::SignedImmediate(sign_extend((word >> 16) & 0b11111, 5)),
+        ];
+        return Ok(());
+    }
+
+    if (word >> 15) & 1 == 0 {
+        if (word >> 20) & 1 != 0 {
+            return Err(ErrorKind::IncompleteDecoder);
+        }
+        // `SVE Predicate Logical Operations`
+        let s = (word >> 22) & 1 == 1;
+        let opc = (((word >> 23) & 1) << 2) | (((word >> 9) & 1) << 1) | ((word >> 4) & 1);
+        const OPCODES: [(Opcode, Opcode); 8] = [
+            (Opcode::AND, Opcode::ANDS),
+            (Opcode::BIC, Opcode::BICS),
+            (Opcode::EOR, Opcode::EORS),
+            (Opcode::SEL, Opcode::Invalid),
+            (Opcode::ORR, Opcode::ORRS),
+            (Opcode::ORN, Opcode::ORNS),
+            (Opcode::NOR, Opcode::NORS),
+            (Opcode::NAND, Opcode::NANDS),
+        ];
+        let (opcode, opcode_s) = OPCODES[opc as usize];
+        inst.opcode = if s { opcode_s } else { opcode };
+        let pg = ((word >> 10) & 0b1111) as u16;
+        let governing = match inst.opcode {
+            Opcode::Invalid => return Err(ErrorKind::InvalidOpcode),
+            Opcode::SEL => Operand::PRegister(pg),
+            _ => Operand::PRegisterZeroing(pg),
+        };
+        inst.operands = [
+            p(pd(word), SIMDSizeCode::B),
+            governing,
+            p(((word >> 5) & 0b1111) as u16, SIMDSizeCode::B),
+            p(((word >> 16) & 0b1111) as u16, SIMDSizeCode::B),
+        ];
+        return Ok(());
+    }
+
+    if word & 0xff3efc10 == 0x2518e000 {
+        inst.opcode = if (word >> 16) & 1 == 1 {
+            Opcode::PTRUES
+        } else {
+            Opcode::PTRUE
+        };
+        inst.operands = [
+            p(pd(word), size),
+            pattern((word >> 5) & 0b11111, 1),
+            Operand::Nothing,
+            Operand::Nothing,
+        ];
+    } else if word & 0xfffffff0 == 0x2518e400 {
+        inst.opcode = Opcode::PFALSE;
+        inst.operands = [
+            p(pd(word), SIMDSizeCode::B),
+            Operand::Nothing,
+            Operand::Nothing,
+            Operand::Nothing,
+        ];
+    } else if word & 0xffffc21f == 0x2550c000 {
+        inst.opcode = Opcode::PTEST;
+        inst.operands = [
+            Operand::PRegister(((word >> 10) & 0b1111) as u16),
+            p(((word >> 5) & 0b1111) as u16, SIMDSizeCode::B),
+            Operand::Nothing,
+            Operand::Nothing,
+        ];
+    } else {
+        return Err(ErrorKind::IncompleteDecoder);
+    }
+
+    Ok(())
+}
+
+/// `WHILE*`, `CNTP`, and arithmetic with or broadcasts of an immediate.
+fn predicate_count_and_immediates(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let size = size(word);
+
+    if (word >> 13) & 0b111 == 0b000 {
+        let lt = (word >> 10) & 1 == 1;
+        let opc = (((word >> 11) & 1) << 1) | ((word >> 4) & 1);
+        inst.opcode = if lt {
+            [Opcode::WHILELT, Opcode::WHILELE, Opcode::WHILELO, Opcode::WHILELS][opc as usize]
+        } else {
+            [Opcode::WHILEGE, Opcode::WHILEGT, Opcode::WHILEHS, Opcode::WHILEHI][opc as usize]
+        };
+        let reg_size = if (word >> 12) & 1 == 1 {
+            SizeCode::X
+        } else {
+            SizeCode::W
+        };
+        inst.operands = [
+            p(pd(word), size),
+            Operand::Register(reg_size, zn(word)),
+            Operand::Register(reg_size, zm(word)),
+            Operand::Nothing,
+        ];
+        return Ok(());
+    }
+
+    let opc = (word >> 16) & 0b111111;
+    match (word >> 14) & 0b11 {
+        0b10 if opc == 0b100000 && word & 0x0000_0200 == 0 => {
+            inst.opcode = Opcode::CNTP;
+            inst.operands = [
+                Operand::Register(SizeCode::X, zd(word)),
+                Operand::PRegister(((word >> 10) & 0b1111) as u16),
+                p(((word >> 5) & 0b1111) as u16, size),
+                Operand::Nothing,
+            ];
+        }
+        0b10 if opc & 0b111100 == 0b101000 || opc & 0b111110 == 0b101100 => {
+            // increments and decrements by the number of active elements, saturating or not.
+            let saturating = opc & 0b000100 == 0;
+            let dec = if saturating { opc >> 1 } else { opc } & 1 == 1;
+            let unsigned = saturating && opc & 1 == 1;
+            inst.opcode = match (saturating, dec, unsigned) {
+                (false, false, _) => Opcode::INCP,
+                (false, true, _) => Opcode::DECP,
+                (true, false, false) => Opcode::SQINCP,
+                (true, false, true) => Opcode::UQINCP,
+                (true, true, false) => Opcode::SQDECP,
+                (true, true, true) => Opcode::UQDECP,
+            };
+            let count = p(((word >> 5) & 0b1111) as u16, size);
+            let reg = zd(word);
+            // bits `15:9`, with `sf` in bit 10 for the saturating scalar forms.
+            inst.operands = match ((word >> 9) & 0b1111111, saturating, unsigned) {
+                (0b1000000, _, _) if size != SIMDSizeCode::B => {
+                    [z(reg, size), count, Operand::Nothing, Operand::Nothing]
+                }
+                (0b1000100, false, _) | (0b1000110, true, _) => [
+                    Operand::Register(SizeCode::X, reg),
+                    count,
+                    Operand::Nothing,
+                    Operand::Nothing,
+                ],
+                // the signed 32-bit forms sign-extend their result into the whole register.
+                (0b1000100, true, false) => [
+                    Operand::Register(SizeCode::X, reg),
+                    count,
+                    Operand::Register(SizeCode::W, reg),
+                    Operand::Nothing,
+                ],
+                (0b1000100, true, true) => [
+                    Operand::Register(SizeCode::W, reg),
+                    count,
+                    Operand::Nothing,
+                    Operand::Nothing,
+                ],
+                // `SETFFR` and `WRFFR`
+                (0b1001000..=0b1001011, _, _) => return Err(ErrorKind::IncompleteDecoder),
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+        }
+        0b11 => {
+            let sh = (word >> 13) & 1;
+            if sh == 1 && size == SIMDSizeCode::B {
+                return Err(ErrorKind::InvalidOperand);
+            }
+            let imm8 = (word >> 5) & 0xff;
+
+            if opc & 0b111000 == 0b100000 {
+                inst.opcode = match opc & 0b111 {
+                    0b000 => Opcode::ADD,
+                    0b001 => Opcode::SUB,
+                    0b011 => Opcode::SUBR,
+                    0b100 => Opcode::SQADD,
+                    0b101 => Opcode::UQADD,
+                    0b110 => Opcode::SQSUB,
+                    0b111 => Opcode::UQSUB,
+                    _ => return Err(ErrorKind::InvalidOpcode),
+                };
+                inst.operands = [
+                    z(zd(word), size),
+                    z(zd(word), size),
+                    Operand::Imm64Special((imm8 << (sh * 8)) as u64),
+                    Operand::Nothing,
+                ];
+            } else if opc == 0b111000 {
+                inst.opcode = Opcode::DUP;
+                inst.operands = [
+                    z(zd(word), size),
+                    Operand::SignedImmediate(sign_extend(imm8, 8) << (sh * 8)),
+                    Operand::Nothing,
+                    Operand::Nothing,
+                ];
+            } else if opc == 0b111001 && sh == 0 && size != SIMDSizeCode::B {
+                inst.opcode = Opcode::FDUP;
+                inst.operands = [
+                    z(zd(word), size),
+                    Operand::ImmediateDouble(docs::VFPExpandImm(imm8 as u8)),
+                    Operand::Nothing,
+                    Operand::Nothing,
+                ];
+            } else {
+                return Err(ErrorKind::IncompleteDecoder);
+            }
+        }
+        _ => return Err(ErrorKind::IncompleteDecoder),
+    }
+
+    Ok(())
+}
+
+/// the `SVE2` groups: dot products, widening add and subtract, and the `AES` instructions.
+fn sve2(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let size = size(word);
+
+    if word >> 24 == 0x44 {
+        if (word >> 21) & 1 != 0 || (word >> 11) & 0b11111 != 0b00000 {
+            return Err(ErrorKind::IncompleteDecoder);
+        }
+        let (dest, source) = match size {
+            SIMDSizeCode::S => (SIMDSizeCode::S, SIMDSizeCode::B),
+            SIMDSizeCode::D => (SIMDSizeCode::D, SIMDSizeCode::H),
+            _ => return Err(ErrorKind::InvalidOperand),
+        };
+        inst.opcode = if (word >> 10) & 1 == 0 {
+            Opcode::SDOT
+        } else {
+            Opcode::UDOT
+        };
+        inst.operands = [
+            z(zd(word), dest),
+            z(zn(word), source),
+            z(zm(word), source),
+            Operand::Nothing,
+        ];
+        return Ok(());
+    }
+
+    if (word >> 21) & 1 == 0 && (word >> 13) & 0b111 == 0b000 {
+        let source = match size {
+            SIMDSizeCode::H => SIMDSizeCode::B,
+            SIMDSizeCode::S => SIMDSizeCode::H,
+            SIMDSizeCode::D => SIMDSizeCode::S,
+            _ => return Err(ErrorKind::InvalidOperand),
+        };
+        inst.opcode = [
+            Opcode::SADDLB,
+            Opcode::SADDLT,
+            Opcode::UADDLB,
+            Opcode::UADDLT,
+            Opcode::SSUBLB,
+            Opcode::SSUBLT,
+            Opcode::USUBLB,
+            Opcode::USUBLT,
+        ][((word >> 10) & 0b111) as usize];
+        inst.operands = [
+            z(zd(word), size),
+            z(zn(word), source),
+            z(zm(word), source),
+            Operand::Nothing,
+        ];
+        return Ok(());
+    }
+
+    let decrypt = (word >> 10) & 1 == 1;
+    if word & 0xfffff800 == 0x4522e000 {
+        inst.opcode = if decrypt { Opcode::AESD } else { Opcode::AESE };
+        inst.operands = [
+            z(zd(word), SIMDSizeCode::B),
+            z(zd(word), SIMDSizeCode::B),
+            z(zn(word), SIMDSizeCode::B),
+            Operand::Nothing,
+        ];
+    } else if word & 0xfffffbe0 == 0x4520e000 {
+        inst.opcode = if decrypt {
+            Opcode::AESIMC
+        } else {
+            Opcode::AESMC
+        };
+        inst.operands = [
+            z(zd(word), SIMDSizeCode::B),
+            z(zd(word), SIMDSizeCode::B),
+            Operand::Nothing,
+            Operand::Nothing,
+        ];
+    } else {
+        return Err(ErrorKind::IncompleteDecoder);
+    }
+
+    Ok(())
+}
+
+/// floating-point arithmetic, multiply-add, compares, reductions and conversions.
+fn floating_point(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let size = size(word);
+    if size == SIMDSizeCode::B {
+        return Err(ErrorKind::IncompleteDecoder);
+    }
+    let pg = pg(word);
+
+    if (word >> 21) & 1 == 1 {
+        // the multiply-add forms take the addend from bits `20:16` rather than the destination,
+        // but both keep their multiplicands in operand order.
+        let opc = ((word >> 13) & 0b11) as usize;
+        inst.opcode = if (word >> 15) & 1 == 0 {
+            [Opcode::FMLA, Opcode::FMLS, Opcode::FNMLA, Opcode::FNMLS][opc]
+        } else {
+            [Opcode::FMAD, Opcode::FMSB, Opcode::FNMAD, Opcode::FNMSB][opc]
+        };
+        inst.operands = [
+            z(zd(word), size),
+            Operand::PRegisterMerging(pg),
+            z(zn(word), size),
+            z(zm(word), size),
+        ];
+        return Ok(());
+    }
+
+    if (word >> 14) & 1 == 1 {
+        let opc = (((word >> 15) & 1) << 2) | (((word >> 13) & 1) << 1) | ((word >> 4) & 1);
+        inst.opcode = match opc {
+            0b000 => Opcode::FCMGE,
+            0b001 => Opcode::FCMGT,
+            0b010 => Opcode::FCMEQ,
+            0b011 => Opcode::FCMNE,
+            0b100 => Opcode::FCMUO,
+            0b101 => Opcode::FACGE,
+            0b111 => Opcode::FACGT,
+            _ => return Err(ErrorKind::InvalidOpcode),
+        };
+        inst.operands = [
+            p(pd(word), size),
+            Operand::PRegisterZeroing(pg),
+            z(zn(word), size),
+            z(zm(word), size),
+        ];
+        return Ok(());
+    }
+
+    match ((word >> 13) & 0b111, (word >> 19) & 0b11) {
+        (0b000, _) => {
+            inst.opcode = match (word >> 10) & 0b111 {
+                0b000 => Opcode::FADD,
+                0b001 => Opcode::FSUB,
+                0b010 => Opcode::FMUL,
+                0b011 => Opcode::FTSMUL,
+                0b110 => Opcode::FRECPS,
+                0b111 => Opcode::FRSQRTS,
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+            inst.operands = [
+                z(zd(word), size),
+                z(zn(word), size),
+                z(zm(word), size),
+                Operand::Nothing,
+            ];
+        }
+        (0b001, 0b00) => {
+            inst.opcode = match (word >> 16) & 0b111 {
+                0b000 => Opcode::FADDV,
+                0b100 => Opcode::FMAXNMV,
+                0b101 => Opcode::FMINNMV,
+                0b110 => Opcode::FMAXV,
+                0b111 => Opcode::FMINV,
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+            inst.operands = [
+                Operand::SIMDRegister(size, zd(word)),
+                Operand::PRegister(pg),
+                z(zn(word), size),
+                Operand::Nothing,
+            ];
+        }
+        (0b100, 0b00) | (0b100, 0b01) => {
+            inst.opcode = match (word >> 16) & 0b1111 {
+                0b0000 => Opcode::FADD,
+                0b0001 => Opcode::FSUB,
+                0b0010 => Opcode::FMUL,
+                0b0011 => Opcode::FSUBR,
+                0b0100 => Opcode::FMAXNM,
+                0b0101 => Opcode::FMINNM,
+                0b0110 => Opcode::FMAX,
+                0b0111 => Opcode::FMIN,
+                0b1000 => Opcode::FABD,
+                0b1001 => Opcode::FSCALE,
+                0b1010 => Opcode::FMULX,
+                0b1100 => Opcode::FDIVR,
+                0b1101 => Opcode::FDIV,
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+            inst.operands = [
+                z(zd(word), size),
+                Operand::PRegisterMerging(pg),
+                z(zd(word), size),
+                z(zn(word), size),
+            ];
+        }
+        (0b101, 0b10) | (0b101, 0b11) => {
+            // the integer convert groups, keyed by bits `23:16` with `U` cleared.
+            use SIMDSizeCode::{D, H, S};
+            let to_int = (word >> 19) & 1 == 1;
+            let (dest, source) = match ((word >> 16) & 0xfe, to_int) {
+                (0x52, false) => (H, H),
+                (0x54, false) => (H, S),
+                (0x56, false) => (H, D),
+                (0x94, false) => (S, S),
+                (0xd0, false) => (D, S),
+                (0xd4, false) => (S, D),
+                (0xd6, false) => (D, D),
+                (0x5a, true) => (H, H),
+                (0x5c, true) => (S, H),
+                (0x5e, true) => (D, H),
+                (0x9c, true) => (S, S),
+                (0xdc, true) => (D, S),
+                (0xd8, true) => (S, D),
+                (0xde, true) => (D, D),
+                _ => return Err(ErrorKind::IncompleteDecoder),
+            };
+            let unsigned = (word >> 16) & 1 == 1;
+            inst.opcode = match (to_int, unsigned) {
+                (false, false) => Opcode::SCVTF,
+                (false, true) => Opcode::UCVTF,
+                (true, false) => Opcode::FCVTZS,
+                (true, true) => Opcode::FCVTZU,
+            };
+            inst.operands = [
+                z(zd(word), dest),
+                Operand::PRegisterMerging(pg),
+                z(zn(word), source),
+                Operand::Nothing,
+            ];
+        }
+        _ => return Err(ErrorKind::IncompleteDecoder),
+    }
+
+    Ok(())
+}
+
+/// `LDR`/`STR` of a whole vector or predicate register. `store` selects `STR`.
+fn fill_spill(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let store = word >> 24 == 0xe5;
+    let imm9 = (((word >> 16) & 0b111111) << 3) | ((word >> 10) & 0b111);
+    let address = Operand::RegOffsetMulVl(zn(word), sign_extend(imm9, 9) as i16);
+
+    let reg = if word & 0xffc0e010 == 0x85800000 || word & 0xffc0e010 == 0xe5800000 {
+        Operand::PRegister(pd(word))
+    } else if word & 0xffc0e000 == 0x85804000 || word & 0xffc0e000 == 0xe5804000 {
+        Operand::ZRegister(zd(word))
+    } else {
+        return Err(ErrorKind::InvalidOpcode);
+    };
+
+    inst.opcode = if store { Opcode::STR } else { Opcode::LDR };
+    inst.operands = [reg, address, Operand::Nothing, Operand::Nothing];
+    Ok(())
+}
+
+/// the opcode of a multiple-structure load or store, or its non-temporal single register form.
+fn structure_opcode(store: bool, msz: u32, opc: u32) -> Opcode {
+    const LOADS: [[Opcode; 4]; 4] = [
+        [Opcode::LDNT1B, Opcode::LDNT1H, Opcode::LDNT1W, Opcode::LDNT1D],
+        [Opcode::LD2B, Opcode::LD2H, Opcode::LD2W, Opcode::LD2D],
+        [Opcode::LD3B, Opcode::LD3H, Opcode::LD3W, Opcode::LD3D],
+        [Opcode::LD4B, Opcode::LD4H, Opcode::LD4W, Opcode::LD4D],
+    ];
+    const STORES: [[Opcode; 4]; 4] = [
+        [Opcode::STNT1B, Opcode::STNT1H, Opcode::STNT1W, Opcode::STNT1D],
+        [Opcode::ST2B, Opcode::ST2H, Opcode::ST2W, Opcode::ST2D],
+        [Opcode::ST3B, Opcode::ST3H, Opcode::ST3W, Opcode::ST3D],
+        [Opcode::ST4B, Opcode::ST4H, Opcode::ST4W, Opcode::ST4D],
+    ];
+
+    if store {
+        STORES[opc as usize][msz as usize]
+    } else {
+        LOADS[opc as usize][msz as usize]
+    }
+}
+
+/// the loads of each kind, by memory element size and then sign-extended by memory element size.
+const LD1: [Opcode; 7] = [
+    Opcode::LD1B,
+    Opcode::LD1H,
+    Opcode::LD1W,
+    Opcode::LD1D,
+    Opcode::LD1SB,
+    Opcode::LD1SH,
+    Opcode::LD1SW,
+];
+const LD1R: [Opcode; 7] = [
+    Opcode::LD1RB,
+    Opcode::LD1RH,
+    Opcode::LD1RW,
+    Opcode::LD1RD,
+    Opcode::LD1RSB,
+    Opcode::LD1RSH,
+    Opcode::LD1RSW,
+];
+const LDFF1: [Opcode; 7] = [
+    Opcode::LDFF1B,
+    Opcode::LDFF1H,
+    Opcode::LDFF1W,
+    Opcode::LDFF1D,
+    Opcode::LDFF1SB,
+    Opcode::LDFF1SH,
+    Opcode::LDFF1SW,
+];
+const LDNT1: [Opcode; 7] = [
+    Opcode::LDNT1B,
+    Opcode::LDNT1H,
+    Opcode::LDNT1W,
+    Opcode::LDNT1D,
+    Opcode::LDNT1SB,
+    Opcode::LDNT1SH,
+    Opcode::LDNT1SW,
+];
+const LDNF1: [Opcode; 7] = [
+    Opcode::LDNF1B,
+    Opcode::LDNF1H,
+    Opcode::LDNF1W,
+    Opcode::LDNF1D,
+    Opcode::LDNF1SB,
+    Opcode::LDNF1SH,
+    Opcode::LDNF1SW,
+];
+
+fn load_opcode(opcodes: &[Opcode; 7], msz: u32, signed: bool) -> Opcode {
+    if signed {
+        opcodes[4 + msz as usize]
+    } else {
+        opcodes[msz as usize]
+    }
+}
+
+/// `dtype` of the contiguous and broadcast loads: the memory element size, whether it is
+/// sign-extended, and the element size.
+const DTYPES: [(u32, bool, SIMDSizeCode); 16] = {
+    use SIMDSizeCode::{B, D, H, S};
+    [
+        (0, false, B),
+        (0, false, H),
+        (0, false, S),
+        (0, false, D),
+        (2, true, D),
+        (1, false, H),
+        (1, false, S),
+        (1, false, D),
+        (1, true, D),
+        (1, true, S),
+        (2, false, S),
+        (2, false, D),
+        (0, true, D),
+        (0, true, S),
+        (0, true, H),
+        (3, false, D),
+    ]
+};
+
+/// `SVE Memory - Contiguous Load`, for the scalar plus immediate and scalar plus scalar forms.
+fn contiguous_load(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let imm4 = sign_extend((word >> 16) & 0b1111, 4);
+    let rm = zm(word);
+
+    let (opcode, group, address) = match ((word >> 13) & 0b111, (word >> 20) & 1) {
+        (0b010 | 0b011 | 0b101, nf) => {
+            // `LD1*` and its first-faulting and non-faulting forms, where `dtype` selects both
+            // the memory and element sizes.
+            let (msz, signed, size) = DTYPES[((word >> 21) & 0b1111) as usize];
+            let (opcodes, address) = match (word >> 13) & 0b111 {
+                0b101 if nf == 0 => (&LD1, Operand::RegOffsetMulVl(zn(word), imm4 as i16)),
+                0b101 => (&LDNF1, Operand::RegOffsetMulVl(zn(word), imm4 as i16)),
+                // only first-faulting loads may leave out the offset register.
+                0b010 if rm == 0b11111 => return Err(ErrorKind::InvalidOperand),
+                0b011 if rm == 0b11111 => (&LDFF1, Operand::RegPreIndex(zn(word), 0, false)),
+                ff => (
+                    if ff == 0b011 { &LDFF1 } else { &LD1 },
+                    Operand::RegRegOffset(zn(word), rm, SizeCode::X, ShiftStyle::LSL, msz as u8),
+                ),
+            };
+            let group = Operand::ZRegisterGroup(zd(word), size, 1);
+            (load_opcode(opcodes, msz, signed), group, address)
+        }
+        (0b111, 0) | (0b110, _) => {
+            let msz = (word >> 23) & 0b11;
+            let opc = (word >> 21) & 0b11;
+            // `opc` is one less than the register count, except for the single register `*NT1*`.
+            let count = if opc == 0 { 1 } else { opc as u8 + 1 };
+            let address = if (word >> 13) & 0b111 == 0b111 {
+                Operand::RegOffsetMulVl(zn(word), (imm4 * count as i64) as i16)
+            } else if rm == 0b11111 {
+                return Err(ErrorKind::InvalidOperand);
+            } else {
+                Operand::RegRegOffset(zn(word), rm, SizeCode::X, ShiftStyle::LSL, msz as u8)
+            };
+            let group = Operand::ZRegisterGroup(zd(word), SIZES[msz as usize], count);
+            (structure_opcode(false, msz, opc), group, address)
+        }
+        _ => return Err(ErrorKind::IncompleteDecoder),
+    };
+
+    inst.opcode = opcode;
+    inst.operands = [
+        group,
+        Operand::PRegisterZeroing(pg(word)),
+        address,
+        Operand::Nothing,
+    ];
+    Ok(())
+}
+
+/// a prefetch of elements of size `msz`, where `bit 4` is reserved.
+fn prefetch(
+    word: u32,
+    msz: u32,
+    address: Operand,
+    inst: &mut Instruction,
+) -> Result<(), ErrorKind> {
+    if (word >> 4) & 1 != 0 {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    inst.opcode = [Opcode::PRFB, Opcode::PRFH, Opcode::PRFW, Opcode::PRFD][msz as usize];
+    inst.operands = [
+        Operand::SVEPrefetchOp((word & 0b1111) as u8),
+        Operand::PRegister(pg(word)),
+        address,
+        Operand::Nothing,
+    ];
+    Ok(())
+}
+
+/// a gather into elements of `size` from memory elements of `msz`, which are no larger.
+fn gather_load(
+    word: u32,
+    opcodes: &[Opcode; 7],
+    msz: u32,
+    signed: bool,
+    size: SIMDSizeCode,
+    address: Operand,
+    inst: &mut Instruction,
+) -> Result<(), ErrorKind> {
+    let esize = if size == SIMDSizeCode::S { 2 } else { 3 };
+    if msz > esize || (signed && msz == esize) {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    inst.opcode = load_opcode(opcodes, msz, signed);
+    inst.operands = [
+        Operand::ZRegisterGroup(zd(word), size, 1),
+        Operand::PRegisterZeroing(pg(word)),
+        address,
+        Operand::Nothing,
+    ];
+    Ok(())
+}
+
+/// `SVE Memory - 32-bit Gather and Unsized Contiguous` and `SVE Memory - 64-bit Gather`: gathers,
+/// their non-temporal forms, prefetches, broadcasts of one element and `LDR` of whole registers.
+fn gather(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let wide = (word >> 30) & 1 == 1;
+    let size = if wide { SIMDSizeCode::D } else { SIMDSizeCode::S };
+    let msz = (word >> 23) & 0b11;
+    let xs = (word >> 22) & 1 == 1;
+    let scaled = (word >> 21) & 1 == 1;
+    let vector_imm = zm(word) << msz;
+    let op = (word >> 13) & 0b11;
+    let offsets = (word >> 15) & 1 == 0;
+    // `U:ff` in bits `14:13` for the `LD1*` and `LDFF1*` gathers.
+    let opcodes = if op & 1 == 1 { &LDFF1 } else { &LD1 };
+    let signed = op & 0b10 == 0;
+
+    if !wide && offsets && msz == 0b11 {
+        if !xs {
+            return fill_spill(word, inst);
+        }
+        let imm6 = sign_extend((word >> 16) & 0b111111, 6);
+        return prefetch(word, op, Operand::RegOffsetMulVl(zn(word), imm6 as i16), inst);
+    }
+
+    if !wide && !offsets && xs {
+        // `LD1R*`, replicating one element to the whole vector.
+        let (msz, signed, size) = DTYPES[(((word >> 21) & 0b1100) | op) as usize];
+        let offset = ((word >> 16) & 0b111111) << msz;
+        inst.opcode = load_opcode(&LD1R, msz, signed);
+        inst.operands = [
+            Operand::ZRegisterGroup(zd(word), size, 1),
+            Operand::PRegisterZeroing(pg(word)),
+            Operand::RegPreIndex(zn(word), offset as i32, false),
+            Operand::Nothing,
+        ];
+        return Ok(());
+    }
+
+    if offsets || xs {
+        // scalar plus vector, with 32-bit offsets extended by `xs` or 64-bit offsets, scaled by
+        // the memory element size or not.
+        let extend = match (offsets, xs) {
+            (false, _) => ShiftStyle::LSL,
+            (true, false) => ShiftStyle::UXTW,
+            (true, true) => ShiftStyle::SXTW,
+        };
+        if msz == 0 && scaled {
+            let address = Operand::RegZRegOffset(zn(word), zm(word), size, extend, op as u8);
+            return prefetch(word, op, address, inst);
+        }
+        let shift = if scaled { msz as u8 } else { 0 };
+        let address = Operand::RegZRegOffset(zn(word), zm(word), size, extend, shift);
+        return gather_load(word, opcodes, msz, signed, size, address, inst);
+    }
+
+    if scaled {
+        let address = Operand::ZRegOffset(zn(word), size, vector_imm);
+        return gather_load(word, opcodes, msz, signed, size, address, inst);
+    }
+
+    match ((word >> 13) & 0b111, wide) {
+        (0b111, _) => {
+            let address = Operand::ZRegOffset(zn(word), size, vector_imm);
+            prefetch(word, msz, address, inst)
+        }
+        (0b110, false) => {
+            if zm(word) == 0b11111 {
+                return Err(ErrorKind::InvalidOperand);
+            }
+            let address =
+                Operand::RegRegOffset(zn(word), zm(word), SizeCode::X, ShiftStyle::LSL, msz as u8);
+            prefetch(word, msz, address, inst)
+        }
+        // `LDNT1*`, vector plus scalar. the unsigned forms are in bit 13 for 32-bit elements, and
+        // in bit 14 for 64-bit elements.
+        (0b100 | 0b101, false) | (0b100 | 0b110, true) => {
+            let address = Operand::ZRegRegOffset(zn(word), size, zm(word));
+            gather_load(word, &LDNT1, msz, op == 0b00, size, address, inst)
+        }
+        _ => Err(ErrorKind::InvalidOpcode),
+    }
+}
+
+/// `SVE Memory - Contiguous Store`, for the scalar plus immediate and scalar plus scalar forms,
+/// `STR` of whole registers, and the scatters.
+fn contiguous_store(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    if word & 0xffc0a000 == 0xe5800000 {
+        return fill_spill(word, inst);
+    }
+
+    let imm4 = sign_extend((word >> 16) & 0b1111, 4);
+    let rm = zm(word);
+    let msz = (word >> 23) & 0b11;
+    let opc = (word >> 21) & 0b11;
+
+    let (opcode, group, address) = match ((word >> 13) & 0b111, (word >> 20) & 1) {
+        (0b111, 0) | (0b010, _) => {
+            // `ST1*`, where `opc` is the element size, no smaller than the memory size.
+            if opc < msz {
+                return Err(ErrorKind::InvalidOpcode);
+            }
+            let opcode = [Opcode::ST1B, Opcode::ST1H, Opcode::ST1W, Opcode::ST1D][msz as usize];
+            let address = if (word >> 13) & 0b111 == 0b111 {
+                Operand::RegOffsetMulVl(zn(word), imm4 as i16)
+            } else if rm == 0b11111 {
+                return Err(ErrorKind::InvalidOperand);
+            } else {
+                Operand::RegRegOffset(zn(word), rm, SizeCode::X, ShiftStyle::LSL, msz as u8)
+            };
+            (opcode, Operand::ZRegisterGroup(zd(word), SIZES[opc as usize], 1), address)
+        }
+        (0b111, 1) | (0b011, _) => {
+            let count = if opc == 0 { 1 } else { opc as u8 + 1 };
+            let address = if (word >> 13) & 0b111 == 0b111 {
+                Operand::RegOffsetMulVl(zn(word), (imm4 * count as i64) as i16)
+            } else if rm == 0b11111 {
+                return Err(ErrorKind::InvalidOperand);
+            } else {
+                Operand::RegRegOffset(zn(word), rm, SizeCode::X, ShiftStyle::LSL, msz as u8)
+            };
+            let group = Operand::ZRegisterGroup(zd(word), SIZES[msz as usize], count);
+            (structure_opcode(true, msz, opc), group, address)
+        }
+        (op, _) => {
+            // scatters, where `opc` selects the element size and the form of the address.
+            use SIMDSizeCode::{D, S};
+            let scaled = opc & 1 == 1;
+            let shift = if scaled { msz as u8 } else { 0 };
+            let (size, address) = match (op, opc) {
+                (0b001, 0b00) => (D, Operand::ZRegRegOffset(zn(word), D, rm)),
+                (0b001, 0b10) => (S, Operand::ZRegRegOffset(zn(word), S, rm)),
+                (0b100 | 0b110, _) => {
+                    let size = if opc & 0b10 == 0 { D } else { S };
+                    let extend = if op == 0b100 {
+                        ShiftStyle::UXTW
+                    } else {
+                        ShiftStyle::SXTW
+                    };
+                    (size, Operand::RegZRegOffset(zn(word), rm, size, extend, shift))
+                }
+                (0b101, 0b00 | 0b01) => {
+                    (D, Operand::RegZRegOffset(zn(word), rm, D, ShiftStyle::LSL, shift))
+                }
+                (0b101, 0b10) => (D, Operand::ZRegOffset(zn(word), D, rm << msz)),
+                (0b101, _) => (S, Operand::ZRegOffset(zn(word), S, rm << msz)),
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+            let vector_offsets = matches!(address, Operand::RegZRegOffset(..));
+            if (vector_offsets && scaled && msz == 0) || (size == S && msz == 0b11) {
+                return Err(ErrorKind::InvalidOpcode);
+            }
+            let opcode = if op == 0b001 {
+                [Opcode::STNT1B, Opcode::STNT1H, Opcode::STNT1W, Opcode::STNT1D][msz as usize]
+            } else {
+                [Opcode::ST1B, Opcode::ST1H, Opcode::ST1W, Opcode::ST1D][msz as usize]
+            };
+            (opcode, Operand::ZRegisterGroup(zd(word), size, 1), address)
+        }
+    };
+
+    inst.opcode = opcode;
+    inst.operands = [
+        group,
+        Operand::PRegister(pg(word)),
+        address,
+        Operand::Nothing,
+    ];
+    Ok(())
+}
+
+/// the tile and slice index packed into the `ZA` operand of `MOVA`, for elements of `size`.
+fn tile_slice(bits: u32, size: SIMDSizeCode) -> (u16, u8) {
+    let index_bits = match size {
+        SIMDSizeCode::B => 4,
+        SIMDSizeCode::H => 3,
+        SIMDSizeCode::S => 2,
+        SIMDSizeCode::D => 1,
+        SIMDSizeCode::Q => 0,
+    };
+    ((bits >> index_bits) as u16, (bits & ((1 << index_bits) - 1)) as u8)
+}
+
+/// `SME encodings`, top-level bits `1xx0000`: outer products, `ZERO`, `MOVA` and loads and stores
+/// of `ZA` vectors.
+pub(super) fn decode_sme(word: u32, inst: &mut Instruction) -> Result<(), ErrorKind> {
+    let s = (word >> 4) & 1 == 1;
+
+    if word & 0xffa00000 == 0x80800000 {
+        let size = if (word >> 22) & 1 == 0 {
+            SIMDSizeCode::S
+        } else {
+            SIMDSizeCode::D
+        };
+        let tile = word & 0b1111;
+        if (size == SIMDSizeCode::S && tile > 0b11) || tile > 0b111 {
+            return Err(ErrorKind::InvalidOperand);
+        }
+        inst.opcode = if s { Opcode::FMOPS } else { Opcode::FMOPA };
+        inst.operands = [
+            Operand::ZATile(tile as u16, size),
+            Operand::PRegisterMergingPair(pg(word), ((word >> 13) & 0b111) as u16),
+            z(zn(word), size),
+            z(zm(word), size),
+        ];
+    } else if word & 0xfe800000 == 0xa0800000 {
+        let (size, source) = if (word >> 22) & 1 == 0 {
+            (SIMDSizeCode::S, SIMDSizeCode::B)
+        } else {
+            (SIMDSizeCode::D, SIMDSizeCode::H)
+        };
+        let tile = word & 0b1111;
+        if (size == SIMDSizeCode::S && tile > 0b11) || tile > 0b111 {
+            return Err(ErrorKind::InvalidOperand);
+        }
+        const OPCODES: [(Opcode, Opcode); 4] = [
+            (Opcode::SMOPA, Opcode::SMOPS),
+            (Opcode::SUMOPA, Opcode::SUMOPS),
+            (Opcode::USMOPA, Opcode::USMOPS),
+            (Opcode::UMOPA, Opcode::UMOPS),
+        ];
+        let (opa, ops) = OPCODES[((((word >> 24) & 1) << 1) | ((word >> 21) & 1)) as usize];
+        inst.opcode = if s { ops } else { opa };
+        inst.operands = [
+            Operand::ZATile(tile as u16, size),
+            Operand::PRegisterMergingPair(pg(word), ((word >> 13) & 0b111) as u16),
+            z(zn(word), source),
+            z(zm(word), source),
+        ];
+    } else if word & 0xffffff00 == 0xc0080000 {
+        inst.opcode = Opcode::ZERO;
+        inst.operands = [
+            Operand::ZATileList(word as u8),
+            Operand::Nothing,
+            Operand::Nothing,
+            Operand::Nothing,
+        ];
+    } else if word & 0xff3e0010 == 0xc0000000 || word & 0xff3e0200 == 0xc0020000 {
+        // `MOVA`, between a vector and a horizontal or vertical tile slice.
+        let size = if (word >> 16) & 1 == 1 {
+            if (word >> 22) & 0b11 != 0b11 {
+                return Err(ErrorKind::InvalidOperand);
+            }
+            SIMDSizeCode::Q
+        } else {
+            size(word)
+        };
+        let vertical = (word >> 15) & 1 == 1;
+        let index = 12 + ((word >> 13) & 0b11) as u16;
+        let predicate = Operand::PRegisterMerging(pg(word));
+
+        inst.opcode = Opcode::MOVA;
+        if (word >> 17) & 1 == 0 {
+            let (tile, offset) = tile_slice(word & 0b1111, size);
+            inst.operands = [
+                Operand::ZATileSlice(tile, size, vertical, index, offset),
+                predicate,
+                z(zn(word), size),
+                Operand::Nothing,
+            ];
+        } else {
+            let (tile, offset) = tile_slice((word >> 5) & 0b1111, size);
+            inst.operands = [
+                z(zd(word), size),
+                predicate,
+                Operand::ZATileSlice(tile, size, vertical, index, offset),
+                Operand::Nothing,
+            ];
+        }
+    } else if word & 0xffdf9c10 == 0xe1000000 {
+        let offset = (word & 0b1111) as u8;
+        inst.opcode = if (word >> 21) & 1 == 1 {
+            Opcode::STR
+        } else {
+            Opcode::LDR
+        };
+        inst.operands = [
+            Operand::ZAArrayVector(12 + ((word >> 13) & 0b11) as u16, offset),
+            Operand::RegOffsetMulVl(zn(word), offset as i16),
+            Operand::Nothing,
+            Operand::Nothing,
+        ];
+    } else {
+        return Err(ErrorKind::IncompleteDecoder);
+    }
+
+    Ok(())
+}
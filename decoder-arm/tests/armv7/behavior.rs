@@ -0,0 +1,177 @@
+use arm::armv7::Instruction;
+use decoder::{Decodable, Decoded, Direction, FlowKind, MemoryAccess, Reader};
+
+fn decode(data: [u8; 4]) -> Instruction {
+    let mut reader = Reader::new(&data[..]);
+    arm::armv7::Decoder::default().decode(&mut reader).unwrap()
+}
+
+#[test]
+fn flow() {
+    assert_eq!(
+        decode([0x02, 0x00, 0x81, 0xe0]).flow(),
+        FlowKind::Sequential
+    );
+    assert_eq!(decode([0x00, 0x00, 0x00, 0xea]).flow(), FlowKind::Jump);
+    assert_eq!(
+        decode([0x00, 0x00, 0x00, 0x0a]).flow(),
+        FlowKind::ConditionalJump
+    );
+    assert_eq!(decode([0x00, 0x00, 0x00, 0xeb]).flow(), FlowKind::Call);
+    assert_eq!(decode([0x1e, 0xff, 0x2f, 0xe1]).flow(), FlowKind::Return);
+    assert_eq!(decode([0x0e, 0xf0, 0xa0, 0xe1]).flow(), FlowKind::Return);
+    assert_eq!(decode([0x10, 0x80, 0xbd, 0xe8]).flow(), FlowKind::Return);
+    assert_eq!(decode([0x70, 0x00, 0x20, 0xe1]).flow(), FlowKind::Interrupt);
+
+    // bxeq lr
+    assert_eq!(
+        decode([0x1e, 0xff, 0x2f, 0x01]).flow(),
+        FlowKind::ConditionalJump
+    );
+    assert!(decode([0x1e, 0xff, 0x2f, 0x01]).can_fall_through());
+    assert!(!decode([0x1e, 0xff, 0x2f, 0xe1]).can_fall_through());
+}
+
+#[test]
+fn targets() {
+    let mut inst = decode([0x00, 0x00, 0x00, 0xeb]);
+    assert_eq!(inst.targets(), vec![]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![0x1008]);
+
+    // blx r3
+    let mut inst = decode([0x33, 0xff, 0x2f, 0xe1]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.flow(), FlowKind::Call);
+    assert_eq!(inst.targets(), vec![]);
+}
+
+#[test]
+fn registers() {
+    // ldr r0, [r1, #0x4]
+    let inst = decode([0x04, 0x00, 0x91, 0xe5]);
+    assert_eq!(inst.regs_read(), vec!["r1"]);
+    assert_eq!(inst.regs_written(), vec!["r0"]);
+
+    // str r0, [sp, #-0x4]!
+    let inst = decode([0x04, 0x00, 0x2d, 0xe5]);
+    assert_eq!(inst.regs_read(), vec!["r0", "sp"]);
+    assert_eq!(inst.regs_written(), vec!["sp"]);
+
+    // pop {r4, pc}
+    let inst = decode([0x10, 0x80, 0xbd, 0xe8]);
+    assert_eq!(inst.regs_read(), vec!["sp"]);
+    assert_eq!(inst.regs_written(), vec!["sp", "r4", "pc"]);
+
+    // cmp r0, #0x1
+    let inst = decode([0x01, 0x00, 0x50, 0xe3]);
+    assert_eq!(inst.regs_read(), vec!["r0"]);
+    assert_eq!(inst.regs_written(), vec!["cpsr"]);
+
+    // addeq r0, r1, r2
+    let inst = decode([0x02, 0x00, 0x81, 0x00]);
+    assert_eq!(inst.regs_read(), vec!["r1", "r2", "cpsr"]);
+    assert_eq!(inst.regs_written(), vec!["r0"]);
+
+    // bl
+    let inst = decode([0x00, 0x00, 0x00, 0xeb]);
+    assert_eq!(inst.regs_written(), vec!["lr"]);
+}
+
+#[test]
+fn memory() {
+    assert_eq!(decode([0x02, 0x00, 0x81, 0xe0]).memory(), None);
+
+    assert_eq!(
+        decode([0x04, 0x00, 0x91, 0xe5]).memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::Read
+        })
+    );
+
+    // strb r0, [r1]
+    assert_eq!(
+        decode([0x00, 0x00, 0xc1, 0xe5]).memory(),
+        Some(MemoryAccess {
+            size: Some(1),
+            direction: Direction::Write
+        })
+    );
+
+    // push {r4, r5, lr}
+    assert_eq!(
+        decode([0x30, 0x40, 0x2d, 0xe9]).memory(),
+        Some(MemoryAccess {
+            size: Some(12),
+            direction: Direction::Write
+        })
+    );
+}
+
+#[test]
+fn simd() {
+    // vmla.f32 q0, q1, q2
+    let inst = decode([0x54, 0x0d, 0x02, 0xf2]);
+    assert_eq!(inst.regs_read(), vec!["q0", "q1", "q2"]);
+    assert_eq!(inst.regs_written(), vec!["q0"]);
+
+    // vmov r0, r1, d5
+    let inst = decode([0x15, 0x0b, 0x51, 0xec]);
+    assert_eq!(inst.regs_read(), vec!["d5"]);
+    assert_eq!(inst.regs_written(), vec!["r0", "r1"]);
+
+    // vmrs apsr_nzcv, fpscr
+    let inst = decode([0x10, 0xfa, 0xf1, 0xee]);
+    assert_eq!(inst.regs_read(), vec!["fpscr"]);
+    assert_eq!(inst.regs_written(), vec!["cpsr"]);
+
+    // vpush {d8, d9, d10, d11, d12, d13, d14, d15}
+    let inst = decode([0x10, 0x8b, 0x2d, 0xed]);
+    assert_eq!(inst.regs_written(), vec!["sp"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(64),
+            direction: Direction::Write
+        })
+    );
+
+    // vld1.32 {d0, d1, d2}, [r0:64], r2
+    let inst = decode([0x92, 0x06, 0x20, 0xf4]);
+    assert_eq!(inst.regs_read(), vec!["r0", "r2"]);
+    assert_eq!(inst.regs_written(), vec!["d0", "d1", "d2", "r0"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(24),
+            direction: Direction::Read
+        })
+    );
+
+    // vld2.16 {d0[1], d2[1]}, [r0]
+    let inst = decode([0x6f, 0x05, 0xa0, 0xf4]);
+    assert_eq!(inst.regs_read(), vec!["d0", "d2", "r0"]);
+    assert_eq!(inst.regs_written(), vec!["d0", "d2"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::Read
+        })
+    );
+
+    // vstr d16, [sp, 0x3fc]
+    assert_eq!(
+        decode([0xff, 0x0b, 0xcd, 0xed]).memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Write
+        })
+    );
+
+    // mrrc p14, 3, r2, r3, c4
+    let inst = decode([0x34, 0x2e, 0x53, 0xec]);
+    assert_eq!(inst.regs_read(), Vec::<&str>::new());
+    assert_eq!(inst.regs_written(), vec!["r2", "r3"]);
+}
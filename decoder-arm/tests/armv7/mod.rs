@@ -1,3 +1,5 @@
+mod behavior;
+mod simd;
 mod thumb;
 
 use arm::armv7::{ConditionCode, Instruction, Opcode, Operand, Reg, RegShift};
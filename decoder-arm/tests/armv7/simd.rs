@@ -0,0 +1,387 @@
+use super::{test_display, test_invalid};
+use decoder::{Decodable, ErrorKind, Reader};
+
+fn test_error(data: [u8; 4], expected: ErrorKind) {
+    let mut reader = Reader::new(&data[..]);
+    let result = arm::armv7::Decoder::default().decode(&mut reader);
+    assert_eq!(
+        result.map_err(|err| err.kind).err(),
+        Some(expected),
+        "unexpected result for {:02x}{:02x}{:02x}{:02x}",
+        data[0],
+        data[1],
+        data[2],
+        data[3]
+    );
+}
+
+#[test]
+fn test_neon_three_registers_same_length() {
+    test_display([0x12, 0x01, 0x01, 0xf2], "vand d0, d1, d2");
+    test_display([0x54, 0x01, 0x12, 0xf2], "vbic q0, q1, q2");
+    test_display([0x12, 0x01, 0x21, 0xf2], "vorr d0, d1, d2");
+    // `vorr` of a register with itself is `vmov`
+    test_display([0x52, 0x01, 0x22, 0xf2], "vmov q0, q1");
+    test_display([0x12, 0x01, 0x31, 0xf2], "vorn d0, d1, d2");
+    test_display([0xf4, 0x01, 0x42, 0xf3], "veor q8, q9, q10");
+    test_display([0x12, 0x01, 0x11, 0xf3], "vbsl d0, d1, d2");
+    test_display([0x12, 0x01, 0x21, 0xf3], "vbit d0, d1, d2");
+    test_display([0x12, 0x01, 0x31, 0xf3], "vbif d0, d1, d2");
+    test_display([0x02, 0x00, 0x01, 0xf2], "vhadd.s8 d0, d1, d2");
+    test_display([0x54, 0x00, 0x32, 0xf3], "vqadd.u64 q0, q1, q2");
+    test_display([0x02, 0x01, 0x11, 0xf3], "vrhadd.u16 d0, d1, d2");
+    test_display([0x02, 0x03, 0x21, 0xf2], "vcgt.s32 d0, d1, d2");
+    test_display([0x12, 0x03, 0x01, 0xf3], "vcge.u8 d0, d1, d2");
+    test_display([0x12, 0x08, 0x11, 0xf3], "vceq.i16 d0, d1, d2");
+    test_display([0x12, 0x08, 0x21, 0xf2], "vtst.32 d0, d1, d2");
+    test_display([0x02, 0x06, 0x11, 0xf2], "vmax.s16 d0, d1, d2");
+    test_display([0x44, 0x0f, 0x22, 0xf2], "vmin.f32 q0, q1, q2");
+    test_display([0x02, 0x07, 0x01, 0xf3], "vabd.u8 d0, d1, d2");
+    test_display([0x54, 0x07, 0x22, 0xf2], "vaba.s32 q0, q1, q2");
+    test_display([0x44, 0x09, 0x22, 0xf2], "vmla.i32 q0, q1, q2");
+    test_display([0x12, 0x0d, 0x21, 0xf2], "vmls.f32 d0, d1, d2");
+    test_display([0x12, 0x09, 0x01, 0xf3], "vmul.p8 d0, d1, d2");
+    test_display([0x02, 0x0a, 0x21, 0xf3], "vpmax.u32 d0, d1, d2");
+    test_display([0x12, 0x0b, 0x11, 0xf2], "vpadd.i16 d0, d1, d2");
+    test_display([0x02, 0x0d, 0x01, 0xf3], "vpadd.f32 d0, d1, d2");
+    test_display([0x02, 0x0b, 0x11, 0xf2], "vqdmulh.s16 d0, d1, d2");
+    test_display([0x44, 0x0b, 0x22, 0xf3], "vqrdmulh.s32 q0, q1, q2");
+    test_display([0x54, 0x0c, 0x02, 0xf2], "vfma.f32 q0, q1, q2");
+    test_display([0x12, 0x0c, 0x21, 0xf2], "vfms.f32 d0, d1, d2");
+    test_display([0x02, 0x0e, 0x01, 0xf2], "vceq.f32 d0, d1, d2");
+    test_display([0x02, 0x0e, 0x01, 0xf3], "vcge.f32 d0, d1, d2");
+    test_display([0x02, 0x0e, 0x21, 0xf3], "vcgt.f32 d0, d1, d2");
+    test_display([0x12, 0x0e, 0x01, 0xf3], "vacge.f32 d0, d1, d2");
+    test_display([0x12, 0x0e, 0x21, 0xf3], "vacgt.f32 d0, d1, d2");
+    test_display([0x12, 0x0f, 0x01, 0xf2], "vrecps.f32 d0, d1, d2");
+    test_display([0x54, 0x0f, 0x22, 0xf2], "vrsqrts.f32 q0, q1, q2");
+    test_display([0x44, 0x08, 0x22, 0xf2], "vadd.i32 q0, q1, q2");
+    test_display([0x02, 0x0d, 0x01, 0xf2], "vadd.f32 d0, d1, d2");
+    // shifts by register are encoded with the shift amount in `Vn` but print it last
+    test_display([0x42, 0x04, 0x24, 0xf2], "vshl.s32 q0, q1, q2");
+
+    // `q` registers must be even-numbered `d` registers
+    test_error([0x44, 0x18, 0x22, 0xf2], ErrorKind::Undefined);
+    // `vhadd` has no 64-bit form
+    test_error([0x02, 0x00, 0x31, 0xf2], ErrorKind::Undefined);
+}
+
+#[test]
+fn test_neon_three_registers_different_lengths() {
+    test_display([0x02, 0x00, 0x81, 0xf2], "vaddl.s8 q0, d1, d2");
+    test_display([0x02, 0x01, 0x92, 0xf3], "vaddw.u16 q0, q1, d2");
+    test_display([0x02, 0x02, 0xa1, 0xf3], "vsubl.u32 q0, d1, d2");
+    test_display([0x02, 0x03, 0x82, 0xf2], "vsubw.s8 q0, q1, d2");
+    test_display([0x04, 0x04, 0x82, 0xf2], "vaddhn.i16 d0, q1, q2");
+    test_display([0x04, 0x04, 0xa2, 0xf3], "vraddhn.i64 d0, q1, q2");
+    test_display([0x04, 0x06, 0x92, 0xf2], "vsubhn.i32 d0, q1, q2");
+    test_display([0x04, 0x06, 0x82, 0xf3], "vrsubhn.i16 d0, q1, q2");
+    test_display([0x02, 0x05, 0x81, 0xf3], "vabal.u8 q0, d1, d2");
+    test_display([0x02, 0x07, 0x91, 0xf2], "vabdl.s16 q0, d1, d2");
+    test_display([0x02, 0x08, 0xa1, 0xf2], "vmlal.s32 q0, d1, d2");
+    test_display([0x02, 0x0a, 0x91, 0xf3], "vmlsl.u16 q0, d1, d2");
+    test_display([0x02, 0x09, 0x91, 0xf2], "vqdmlal.s16 q0, d1, d2");
+    test_display([0x02, 0x0b, 0xa1, 0xf2], "vqdmlsl.s32 q0, d1, d2");
+    test_display([0x02, 0x0c, 0x81, 0xf3], "vmull.u8 q0, d1, d2");
+    test_display([0x02, 0x0e, 0x81, 0xf2], "vmull.p8 q0, d1, d2");
+    test_display([0x02, 0x0d, 0x91, 0xf2], "vqdmull.s16 q0, d1, d2");
+}
+
+#[test]
+fn test_neon_two_registers_and_scalar() {
+    test_display([0xe3, 0x09, 0xe2, 0xf3], "vmul.f32 q8, q9, d3[1]");
+    test_display([0x6a, 0x00, 0x91, 0xf2], "vmla.i16 d0, d1, d2[3]");
+    test_display([0x4a, 0x0a, 0x91, 0xf2], "vmull.s16 q0, d1, d2[1]");
+    test_display([0x42, 0x0c, 0xa2, 0xf3], "vqdmulh.s32 q0, q1, d2[0]");
+}
+
+#[test]
+fn test_neon_two_registers_and_shift() {
+    test_display([0x11, 0x00, 0x8d, 0xf3], "vshr.u8 d0, d1, 0x3");
+    test_display([0xd2, 0x00, 0x80, 0xf2], "vshr.s64 q0, q1, 0x40");
+    test_display([0x11, 0x05, 0x9f, 0xf2], "vshl.i16 d0, d1, 0xf");
+    test_display([0x11, 0x04, 0xb9, 0xf3], "vsri.32 d0, d1, 0x7");
+    test_display([0x91, 0x05, 0xbf, 0xf3], "vsli.64 d0, d1, 0x3f");
+    test_display([0x12, 0x08, 0x9b, 0xf2], "vshrn.i32 d0, q1, 0x5");
+    test_display([0x52, 0x08, 0xbd, 0xf3], "vqrshrun.s64 d0, q1, 0x3");
+    test_display([0x12, 0x09, 0x88, 0xf3], "vqshrn.u16 d0, q1, 0x8");
+    test_display([0x11, 0x0a, 0x8b, 0xf3], "vshll.u8 q0, d1, 0x3");
+    test_display([0x01, 0x03, 0xb2, 0xf3], "vshll.i8 q0, d1, 0x8");
+    test_display([0x11, 0x0a, 0x90, 0xf2], "vmovl.s16 q0, d1");
+    test_display([0x02, 0x02, 0xba, 0xf3], "vmovn.i64 d0, q1");
+    test_display([0x42, 0x02, 0xb6, 0xf3], "vqmovun.s32 d0, q1");
+    test_display([0xc2, 0x02, 0xb2, 0xf3], "vqmovn.u16 d0, q1");
+    test_display([0x42, 0x06, 0xbb, 0xf3], "vcvt.f32.s32 q0, q1");
+    test_display([0x81, 0x07, 0xbb, 0xf3], "vcvt.u32.f32 d0, d1");
+    test_display([0x11, 0x0f, 0xb0, 0xf2], "vcvt.s32.f32 d0, d1, 0x10");
+    test_display([0x52, 0x0e, 0xbf, 0xf3], "vcvt.f32.u32 q0, q1, 0x1");
+}
+
+#[test]
+fn test_neon_modified_immediate() {
+    test_display([0x5f, 0x04, 0x87, 0xf3], "vmov.i32 q0, 0xff0000");
+    test_display([0x15, 0x0e, 0x85, 0xf2], "vmov.i8 d0, 0x55");
+    test_display([0x39, 0x0e, 0x82, 0xf3], "vmov.i64 d0, 0xff00ff00ff0000ff");
+    test_display([0x50, 0x0f, 0x87, 0xf2], "vmov.f32 q0, 1.0");
+    test_display([0x50, 0x0f, 0x86, 0xf3], "vmov.f32 q0, -0.5");
+    test_display([0x3f, 0x00, 0x87, 0xf3], "vmvn.i32 d0, 0xff");
+    test_display([0x10, 0x0b, 0x81, 0xf2], "vorr.i16 d0, 0x1000");
+    test_display([0x7f, 0x03, 0x87, 0xf3], "vbic.i32 q0, 0xff00");
+    test_display([0x11, 0x0d, 0x80, 0xf2], "vmov.i32 d0, 0x1ffff");
+    test_display([0x31, 0x0c, 0x80, 0xf2], "vmvn.i32 d0, 0x1ff");
+}
+
+#[test]
+fn test_neon_two_registers_misc() {
+    test_display([0x44, 0x03, 0xb2, 0xf2], "vext.8 q0, q1, q2, 0x3");
+    test_display([0x02, 0x07, 0xb1, 0xf2], "vext.8 d0, d1, d2, 0x7");
+    test_display([0x03, 0x09, 0xb1, 0xf3], "vtbl.8 d0, {d1, d2}, d3");
+    test_display([0x45, 0x0b, 0xb1, 0xf3], "vtbx.8 d0, {d1, d2, d3, d4}, d5");
+    test_display([0x41, 0x0c, 0xbf, 0xf3], "vdup.8 q0, d1[7]");
+    test_display([0x01, 0x0c, 0xba, 0xf3], "vdup.16 d0, d1[2]");
+    test_display([0x41, 0x0c, 0xbc, 0xf3], "vdup.32 q0, d1[1]");
+    test_display([0x01, 0x00, 0xb0, 0xf3], "vrev64.8 d0, d1");
+    test_display([0xc2, 0x00, 0xb4, 0xf3], "vrev32.16 q0, q1");
+    test_display([0x01, 0x01, 0xb0, 0xf3], "vrev16.8 d0, d1");
+    test_display([0x01, 0x02, 0xb0, 0xf3], "vpaddl.s8 d0, d1");
+    test_display([0xc2, 0x06, 0xb8, 0xf3], "vpadal.u32 q0, q1");
+    test_display([0x01, 0x04, 0xb4, 0xf3], "vcls.s16 d0, d1");
+    test_display([0xc2, 0x04, 0xb8, 0xf3], "vclz.i32 q0, q1");
+    test_display([0x01, 0x05, 0xb0, 0xf3], "vcnt.8 d0, d1");
+    test_display([0x81, 0x05, 0xb0, 0xf3], "vmvn d0, d1");
+    test_display([0x01, 0x07, 0xb0, 0xf3], "vqabs.s8 d0, d1");
+    test_display([0xc2, 0x07, 0xb8, 0xf3], "vqneg.s32 q0, q1");
+    test_display([0x01, 0x00, 0xb5, 0xf3], "vcgt.s16 d0, d1, 0x0");
+    test_display([0xc2, 0x04, 0xb9, 0xf3], "vcge.f32 q0, q1, 0x0");
+    test_display([0x01, 0x01, 0xb1, 0xf3], "vceq.i8 d0, d1, 0x0");
+    test_display([0x81, 0x01, 0xb9, 0xf3], "vcle.s32 d0, d1, 0x0");
+    test_display([0x01, 0x06, 0xb9, 0xf3], "vclt.f32 d0, d1, 0x0");
+    test_display([0x01, 0x03, 0xb5, 0xf3], "vabs.s16 d0, d1");
+    test_display([0xc2, 0x07, 0xb9, 0xf3], "vneg.f32 q0, q1");
+    test_display([0x01, 0x00, 0xb2, 0xf3], "vswp d0, d1");
+    test_display([0x81, 0x00, 0xb6, 0xf3], "vtrn.16 d0, d1");
+    test_display([0x42, 0x01, 0xb2, 0xf3], "vuzp.8 q0, q1");
+    test_display([0xc2, 0x01, 0xba, 0xf3], "vzip.32 q0, q1");
+    test_display([0x01, 0x04, 0xbb, 0xf3], "vrecpe.u32 d0, d1");
+    test_display([0xc2, 0x05, 0xbb, 0xf3], "vrsqrte.f32 q0, q1");
+    test_display([0x42, 0x07, 0xbb, 0xf3], "vcvt.s32.f32 q0, q1");
+    test_display([0x81, 0x06, 0xbb, 0xf3], "vcvt.f32.u32 d0, d1");
+    test_display([0x02, 0x06, 0xb6, 0xf3], "vcvt.f16.f32 d0, q1");
+    test_display([0x01, 0x07, 0xb6, 0xf3], "vcvt.f32.f16 q0, d1");
+}
+
+#[test]
+fn test_neon_load_store() {
+    test_display([0x0f, 0x07, 0x20, 0xf4], "vld1.8 {d0}, [r0]");
+    test_display([0x6d, 0x0a, 0x20, 0xf4], "vld1.16 {d0, d1}, [r0:128]!");
+    test_display(
+        [0x92, 0x06, 0x20, 0xf4],
+        "vld1.32 {d0, d1, d2}, [r0:64], r2",
+    );
+    test_display(
+        [0xff, 0x02, 0x20, 0xf4],
+        "vld1.64 {d0, d1, d2, d3}, [r0:256]",
+    );
+    test_display([0x0f, 0x08, 0x20, 0xf4], "vld2.8 {d0, d1}, [r0]");
+    test_display([0x6d, 0x09, 0x20, 0xf4], "vld2.16 {d0, d2}, [r0:128]!");
+    test_display(
+        [0xb3, 0x03, 0x20, 0xf4],
+        "vld2.32 {d0, d1, d2, d3}, [r0:256], r3",
+    );
+    test_display([0x1f, 0x04, 0x20, 0xf4], "vld3.8 {d0, d1, d2}, [r0:64]");
+    test_display([0x4d, 0x05, 0x20, 0xf4], "vld3.16 {d0, d2, d4}, [r0]!");
+    test_display(
+        [0xbf, 0x00, 0x20, 0xf4],
+        "vld4.32 {d0, d1, d2, d3}, [r0:256]",
+    );
+    test_display([0x0f, 0x01, 0x20, 0xf4], "vld4.8 {d0, d2, d4, d6}, [r0]");
+    test_display([0x0f, 0x07, 0x00, 0xf4], "vst1.8 {d0}, [r0]");
+    test_display([0x8d, 0x0a, 0x41, 0xf4], "vst1.32 {d16, d17}, [r1]!");
+    test_display([0x4f, 0x08, 0x00, 0xf4], "vst2.16 {d0, d1}, [r0]");
+    test_display([0x8f, 0x04, 0x00, 0xf4], "vst3.32 {d0, d1, d2}, [r0]");
+    test_display(
+        [0x01, 0x00, 0x00, 0xf4],
+        "vst4.8 {d0, d1, d2, d3}, [r0], r1",
+    );
+    test_display([0x6f, 0x00, 0xa0, 0xf4], "vld1.8 {d0[3]}, [r0]");
+    test_display([0x9f, 0x04, 0xa0, 0xf4], "vld1.16 {d0[2]}, [r0:16]");
+    test_display([0xbd, 0x08, 0xa0, 0xf4], "vld1.32 {d0[1]}, [r0:32]!");
+    test_display([0x3f, 0x01, 0xa0, 0xf4], "vld2.8 {d0[1], d1[1]}, [r0:16]");
+    test_display([0x6f, 0x05, 0xa0, 0xf4], "vld2.16 {d0[1], d2[1]}, [r0]");
+    test_display([0x9f, 0x09, 0xa0, 0xf4], "vld2.32 {d0[1], d1[1]}, [r0:64]");
+    test_display(
+        [0x2f, 0x02, 0xa0, 0xf4],
+        "vld3.8 {d0[1], d1[1], d2[1]}, [r0]",
+    );
+    test_display(
+        [0x6f, 0x06, 0xa0, 0xf4],
+        "vld3.16 {d0[1], d2[1], d4[1]}, [r0]",
+    );
+    test_display(
+        [0x8f, 0x0a, 0xa0, 0xf4],
+        "vld3.32 {d0[1], d1[1], d2[1]}, [r0]",
+    );
+    test_display(
+        [0x3f, 0x03, 0xa0, 0xf4],
+        "vld4.8 {d0[1], d1[1], d2[1], d3[1]}, [r0:32]",
+    );
+    test_display(
+        [0x7f, 0x07, 0xa0, 0xf4],
+        "vld4.16 {d0[1], d2[1], d4[1], d6[1]}, [r0:64]",
+    );
+    test_display(
+        [0xaf, 0x0b, 0xa0, 0xf4],
+        "vld4.32 {d0[1], d1[1], d2[1], d3[1]}, [r0:128]",
+    );
+    test_display([0x6f, 0x00, 0x80, 0xf4], "vst1.8 {d0[3]}, [r0]");
+    test_display([0x4f, 0x05, 0x80, 0xf4], "vst2.16 {d0[1], d1[1]}, [r0]");
+    test_display(
+        [0x0f, 0x0b, 0x80, 0xf4],
+        "vst4.32 {d0[0], d1[0], d2[0], d3[0]}, [r0]",
+    );
+    test_display([0x0f, 0x0c, 0xa0, 0xf4], "vld1.8 {d0[]}, [r0]");
+    test_display([0x7f, 0x0c, 0xa0, 0xf4], "vld1.16 {d0[], d1[]}, [r0:16]");
+    test_display([0x9d, 0x0c, 0xa0, 0xf4], "vld1.32 {d0[]}, [r0:32]!");
+    test_display([0x1f, 0x0d, 0xa0, 0xf4], "vld2.8 {d0[], d1[]}, [r0:16]");
+    test_display(
+        [0xb1, 0x0d, 0xa0, 0xf4],
+        "vld2.32 {d0[], d2[]}, [r0:64], r1",
+    );
+    test_display([0x4f, 0x0e, 0xa0, 0xf4], "vld3.16 {d0[], d1[], d2[]}, [r0]");
+    test_display(
+        [0x1f, 0x0f, 0xa0, 0xf4],
+        "vld4.8 {d0[], d1[], d2[], d3[]}, [r0:32]",
+    );
+    test_display(
+        [0x5f, 0x0f, 0xa0, 0xf4],
+        "vld4.16 {d0[], d1[], d2[], d3[]}, [r0:64]",
+    );
+    test_display(
+        [0xdf, 0x0f, 0xa0, 0xf4],
+        "vld4.32 {d0[], d1[], d2[], d3[]}, [r0:128]",
+    );
+    test_display(
+        [0x9f, 0x0f, 0xa0, 0xf4],
+        "vld4.32 {d0[], d1[], d2[], d3[]}, [r0:64]",
+    );
+
+    // the register list may not run past `d31`
+    test_error([0x0f, 0xe2, 0x60, 0xf4], ErrorKind::Unpredictable);
+}
+
+#[test]
+fn test_vfp_data_processing() {
+    test_display([0x81, 0x0a, 0x00, 0xee], "vmla.f32 s0, s1, s2");
+    test_display([0x42, 0x0b, 0x01, 0xee], "vmls.f64 d0, d1, d2");
+    test_display([0xc1, 0x0a, 0x10, 0xee], "vnmla.f32 s0, s1, s2");
+    test_display([0xa2, 0x0b, 0x51, 0xee], "vnmls.f64 d16, d17, d18");
+    test_display([0xc1, 0x0a, 0x20, 0xee], "vnmul.f32 s0, s1, s2");
+    test_display([0x02, 0x0b, 0x21, 0xee], "vmul.f64 d0, d1, d2");
+    test_display([0x6e, 0xfa, 0x7f, 0xee], "vsub.f32 s31, s30, s29");
+    test_display([0x02, 0x0b, 0x81, 0xee], "vdiv.f64 d0, d1, d2");
+    test_display([0x81, 0x0a, 0xa0, 0xee], "vfma.f32 s0, s1, s2");
+    test_display([0x42, 0x0b, 0xa1, 0xee], "vfms.f64 d0, d1, d2");
+    test_display([0xc1, 0x0a, 0x90, 0xee], "vfnma.f32 s0, s1, s2");
+    test_display([0x02, 0x0b, 0x91, 0xee], "vfnms.f64 d0, d1, d2");
+    test_display([0x00, 0x0a, 0xb7, 0xee], "vmov.f32 s0, 1.0");
+    test_display([0x04, 0x0b, 0xb8, 0xee], "vmov.f64 d0, -2.5");
+    test_display([0x00, 0x0a, 0xb4, 0xee], "vmov.f32 s0, 0.125");
+    test_display([0x60, 0x0a, 0xb0, 0xee], "vmov.f32 s0, s1");
+    test_display([0x61, 0x0b, 0xb0, 0xee], "vmov.f64 d0, d17");
+    test_display([0xe0, 0x0a, 0xb0, 0xee], "vabs.f32 s0, s1");
+    test_display([0x41, 0x0b, 0xb1, 0xee], "vneg.f64 d0, d1");
+    test_display([0xe0, 0x0a, 0xb1, 0xee], "vsqrt.f32 s0, s1");
+    test_display([0x60, 0x0a, 0xb2, 0xee], "vcvtb.f32.f16 s0, s1");
+    test_display([0xe0, 0x0a, 0xb3, 0xee], "vcvtt.f16.f32 s0, s1");
+    test_display([0x60, 0x0a, 0xb4, 0xee], "vcmp.f32 s0, s1");
+    test_display([0xc1, 0x0b, 0xb4, 0xee], "vcmpe.f64 d0, d1");
+    test_display([0x40, 0x0a, 0xb5, 0xee], "vcmp.f32 s0, 0.0");
+    test_display([0xc0, 0x0b, 0xb5, 0xee], "vcmpe.f64 d0, 0.0");
+    test_display([0xe0, 0x0a, 0xb7, 0xee], "vcvt.f64.f32 d0, s1");
+    test_display([0xc1, 0x0b, 0xb7, 0xee], "vcvt.f32.f64 s0, d1");
+    test_display([0xe0, 0x0a, 0xb8, 0xee], "vcvt.f32.s32 s0, s1");
+    test_display([0x60, 0x0b, 0xb8, 0xee], "vcvt.f64.u32 d0, s1");
+    test_display([0xc1, 0x0b, 0xbd, 0xee], "vcvt.s32.f64 s0, d1");
+    test_display([0x60, 0x0a, 0xbc, 0xee], "vcvtr.u32.f32 s0, s1");
+    test_display([0x44, 0x0a, 0xbe, 0xee], "vcvt.s16.f32 s0, s0, 0x8");
+    test_display([0xc6, 0x0b, 0xbf, 0xee], "vcvt.u32.f64 d0, d0, 0x14");
+    test_display([0xef, 0x0a, 0xba, 0xee], "vcvt.f32.s32 s0, s0, 0x1");
+    test_display([0x40, 0x0b, 0xbb, 0xee], "vcvt.f64.u16 d0, d0, 0x10");
+    test_display([0x81, 0x0a, 0x30, 0x0e], "vaddeq.f32 s0, s1, s2");
+    test_display([0x00, 0x0b, 0xb7, 0xbe], "vmovlt.f64 d0, 1.0");
+}
+
+#[test]
+fn test_vfp_load_store() {
+    test_display([0x00, 0x0a, 0x90, 0xed], "vldr s0, [r0]");
+    test_display([0x02, 0x0b, 0x11, 0xed], "vldr d0, [r1, -0x8]");
+    test_display([0xff, 0x0b, 0xcd, 0xed], "vstr d16, [sp, 0x3fc]");
+    test_display([0x01, 0x2a, 0xc2, 0xed], "vstr s5, [r2, 0x4]");
+    test_display([0x06, 0x0b, 0x90, 0xec], "vldmia r0, {d0, d1, d2}");
+    test_display([0x02, 0x0a, 0xb0, 0xec], "vldmia r0!, {s0, s1}");
+    test_display(
+        [0x10, 0x8b, 0x21, 0xed],
+        "vstmdb r1!, {d8, d9, d10, d11, d12, d13, d14, d15}",
+    );
+    test_display([0x01, 0x2a, 0x82, 0xec], "vstmia r2, {s4}");
+    test_display(
+        [0x10, 0x8b, 0x2d, 0xed],
+        "vpush {d8, d9, d10, d11, d12, d13, d14, d15}",
+    );
+    test_display([0x04, 0x8b, 0xbd, 0xec], "vpop {d8, d9}");
+    test_display(
+        [0x10, 0x8a, 0x2d, 0xed],
+        "vpush {s16, s17, s18, s19, s20, s21, s22, s23, s24, s25, s26, s27, s28, s29, s30, s31}",
+    );
+    test_display([0x02, 0x0b, 0x30, 0xed], "vldmdb r0!, {d0}");
+    test_display([0x00, 0x0b, 0x90, 0x1d], "vldrne d0, [r0]");
+}
+
+#[test]
+fn test_vfp_transfers() {
+    test_display([0x90, 0x0a, 0x10, 0xee], "vmov r0, s1");
+    test_display([0x90, 0xca, 0x0f, 0xee], "vmov s31, ip");
+    test_display([0x10, 0x0a, 0xf1, 0xee], "vmrs r0, fpscr");
+    test_display([0x10, 0xfa, 0xf1, 0xee], "vmrs apsr_nzcv, fpscr");
+    test_display([0x10, 0x1a, 0xf8, 0xee], "vmrs r1, fpexc");
+    test_display([0x10, 0x1a, 0xf0, 0xee], "vmrs r1, fpsid");
+    test_display([0x10, 0x0a, 0xe1, 0xee], "vmsr fpscr, r0");
+    test_display([0x10, 0x2a, 0xe8, 0xee], "vmsr fpexc, r2");
+    test_display([0x10, 0x0b, 0x20, 0xee], "vmov.32 d0[1], r0");
+    test_display([0x70, 0x2b, 0x21, 0xee], "vmov.16 d1[3], r2");
+    test_display([0xf0, 0x3b, 0x61, 0xee], "vmov.8 d17[7], r3");
+    test_display([0x10, 0x0b, 0x30, 0xee], "vmov.32 r0, d0[1]");
+    test_display([0x30, 0x0b, 0x31, 0xee], "vmov.s16 r0, d1[2]");
+    test_display([0xb0, 0x0b, 0xff, 0xee], "vmov.u8 r0, d31[5]");
+    test_display([0x30, 0x0b, 0x91, 0xee], "vmov.u16 r0, d1[0]");
+    test_display([0x10, 0x0b, 0xe0, 0xee], "vdup.8 q0, r0");
+    test_display([0x30, 0x1b, 0x80, 0xee], "vdup.16 d0, r1");
+    test_display([0x90, 0x2b, 0xa0, 0xee], "vdup.32 q8, r2");
+    test_display([0x15, 0x0b, 0x51, 0xec], "vmov r0, r1, d5");
+    test_display([0x15, 0x0b, 0x41, 0xec], "vmov d5, r0, r1");
+    test_display([0x31, 0x0a, 0x51, 0xec], "vmov r0, r1, s3, s4");
+    test_display([0x31, 0x0a, 0x41, 0xec], "vmov s3, s4, r0, r1");
+    test_display([0x10, 0x0a, 0xf1, 0xce], "vmrsgt r0, fpscr");
+
+    // `vmrs` of an unallocated system register
+    test_invalid([0x10, 0x0a, 0xf2, 0xee]);
+    // only `fpscr` can be transferred to the condition flags
+    test_error([0x10, 0xfa, 0xf8, 0xee], ErrorKind::Unpredictable);
+}
+
+#[test]
+fn test_coprocessor() {
+    test_display([0xba, 0x0f, 0x07, 0xee], "mcr p15, 0, r0, c7, c10, 5");
+    test_display([0x70, 0x0f, 0x1d, 0xee], "mrc p15, 0, r0, c13, c0, 3");
+    test_display([0x11, 0xfe, 0x10, 0xee], "mrc p14, 0, apsr_nzcv, c0, c1, 0");
+    test_display([0x32, 0x3f, 0x41, 0x1e], "mcrne p15, 2, r3, c1, c2, 1");
+    test_display([0x83, 0x17, 0x12, 0xee], "cdp p7, 1, c1, c2, c3, 4");
+    test_display([0x02, 0x0f, 0x41, 0xec], "mcrr p15, 0, r0, r1, c2");
+    test_display([0x34, 0x2e, 0x53, 0xec], "mrrc p14, 3, r2, r3, c4");
+    test_display([0x01, 0x15, 0x10, 0xed], "ldc p5, c1, [r0, -0x4]");
+    test_display([0x02, 0x26, 0xf1, 0xed], "ldcl p6, c2, [r1, 0x8]!");
+    test_display([0x04, 0x37, 0xa2, 0xec], "stc p7, c3, [r2], 0x10");
+    test_display([0x10, 0x48, 0xc3, 0xec], "stcl p8, c4, [r3], {0x10}");
+    test_display([0x00, 0x00, 0x00, 0xef], "svc 0x0");
+    test_display([0x56, 0x34, 0x12, 0x1f], "svcne 0x123456");
+}
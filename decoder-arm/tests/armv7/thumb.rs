@@ -4049,7 +4049,7 @@ fn test_decode_arithmetic_32b_cases() {
         "rsbs.w r10, r3, 0x3f80"
     );
 }
-#[ignore]
+
 #[test]
 fn test_decode_simd_32b_cases() {
     test_display(
@@ -4136,3 +4136,159 @@ fn test_decode_simd_32b_cases() {
         "vstmdb r3!, {s11, s12, s13, s14, s15, s16, s17, s18, s19, s20, s21, s22, s23, s24, s25, s26, s27, s28, s29, s30, s31}"
     );
 }
+
+#[test]
+fn test_decode_neon_32b_cases() {
+    test_display(
+        &[0x22, 0xef, 0x44, 0x08],
+        "vadd.i32 q0, q1, q2"
+    );
+    test_display(
+        &[0x24, 0xef, 0x42, 0x04],
+        "vshl.s32 q0, q1, q2"
+    );
+    test_display(
+        &[0x42, 0xff, 0xf4, 0x01],
+        "veor q8, q9, q10"
+    );
+    test_display(
+        &[0x91, 0xef, 0x4a, 0x0a],
+        "vmull.s16 q0, d1, d2[1]"
+    );
+    test_display(
+        &[0x82, 0xef, 0x04, 0x04],
+        "vaddhn.i16 d0, q1, q2"
+    );
+    test_display(
+        &[0x8d, 0xff, 0x11, 0x00],
+        "vshr.u8 d0, d1, 0x3"
+    );
+    test_display(
+        &[0xbd, 0xff, 0x52, 0x08],
+        "vqrshrun.s64 d0, q1, 0x3"
+    );
+    test_display(
+        &[0x82, 0xff, 0x39, 0x0e],
+        "vmov.i64 d0, 0xff00ff00ff0000ff"
+    );
+    test_display(
+        &[0x86, 0xff, 0x50, 0x0f],
+        "vmov.f32 q0, -0.5"
+    );
+    test_display(
+        &[0xb2, 0xef, 0x44, 0x03],
+        "vext.8 q0, q1, q2, 0x3"
+    );
+    test_display(
+        &[0xb1, 0xff, 0x03, 0x09],
+        "vtbl.8 d0, {d1, d2}, d3"
+    );
+    test_display(
+        &[0xbf, 0xff, 0x41, 0x0c],
+        "vdup.8 q0, d1[7]"
+    );
+    test_display(
+        &[0xba, 0xff, 0xc2, 0x01],
+        "vzip.32 q0, q1"
+    );
+    test_display(
+        &[0xbb, 0xff, 0x81, 0x06],
+        "vcvt.f32.u32 d0, d1"
+    );
+    test_display(
+        &[0x20, 0xf9, 0x6d, 0x0a],
+        "vld1.16 {d0, d1}, [r0:128]!"
+    );
+    test_display(
+        &[0x20, 0xf9, 0xb3, 0x03],
+        "vld2.32 {d0, d1, d2, d3}, [r0:256], r3"
+    );
+    test_display(
+        &[0xa0, 0xf9, 0x6f, 0x06],
+        "vld3.16 {d0[1], d2[1], d4[1]}, [r0]"
+    );
+    test_display(
+        &[0xa0, 0xf9, 0xdf, 0x0f],
+        "vld4.32 {d0[], d1[], d2[], d3[]}, [r0:128]"
+    );
+    test_display(
+        &[0x41, 0xf9, 0x8d, 0x0a],
+        "vst1.32 {d16, d17}, [r1]!"
+    );
+    test_display(
+        &[0x00, 0xee, 0x81, 0x0a],
+        "vmla.f32 s0, s1, s2"
+    );
+    test_display(
+        &[0x81, 0xee, 0x02, 0x0b],
+        "vdiv.f64 d0, d1, d2"
+    );
+    test_display(
+        &[0xb8, 0xee, 0x04, 0x0b],
+        "vmov.f64 d0, -2.5"
+    );
+    test_display(
+        &[0xb5, 0xee, 0xc0, 0x0b],
+        "vcmpe.f64 d0, 0.0"
+    );
+    test_display(
+        &[0xbd, 0xee, 0xc1, 0x0b],
+        "vcvt.s32.f64 s0, d1"
+    );
+    test_display(
+        &[0xbf, 0xee, 0xc6, 0x0b],
+        "vcvt.u32.f64 d0, d0, 0x14"
+    );
+    test_display(
+        &[0x2d, 0xed, 0x10, 0x8b],
+        "vpush {d8, d9, d10, d11, d12, d13, d14, d15}"
+    );
+    test_display(
+        &[0xbd, 0xec, 0x04, 0x8b],
+        "vpop {d8, d9}"
+    );
+    test_display(
+        &[0x10, 0xee, 0x90, 0x0a],
+        "vmov r0, s1"
+    );
+    test_display(
+        &[0xf1, 0xee, 0x10, 0xfa],
+        "vmrs apsr_nzcv, fpscr"
+    );
+    test_display(
+        &[0x31, 0xee, 0x30, 0x0b],
+        "vmov.s16 r0, d1[2]"
+    );
+    test_display(
+        &[0xa0, 0xee, 0x90, 0x2b],
+        "vdup.32 q8, r2"
+    );
+    test_display(
+        &[0x51, 0xec, 0x15, 0x0b],
+        "vmov r0, r1, d5"
+    );
+    test_display(
+        &[0x07, 0xee, 0xba, 0x0f],
+        "mcr p15, 0, r0, c7, c10, 5"
+    );
+    test_display(
+        &[0x10, 0xee, 0x11, 0xfe],
+        "mrc p14, 0, apsr_nzcv, c0, c1, 0"
+    );
+    test_display(
+        &[0x12, 0xee, 0x83, 0x17],
+        "cdp p7, 1, c1, c2, c3, 4"
+    );
+    test_display(
+        &[0x4f, 0xfe, 0x34, 0x78],
+        "mcr2 p8, 2, r7, c15, c4, 1"
+    );
+    test_display(
+        &[0x33, 0xfe, 0xb4, 0x29],
+        "mrc2 p9, 1, r2, c3, c4, 5"
+    );
+    test_display(
+        &[0x5f, 0xfe, 0x24, 0x78],
+        "cdp2 p8, 5, c7, c15, c4, 1"
+    );
+}
@@ -48,7 +48,296 @@ fn test_neon() {
 
 #[test]
 fn test_sve() {
-    test_err([0x00, 0x00, 0x00, 0x04], ErrorKind::IncompleteDecoder);
+    const TESTS: &[([u8; 4], &'static str)] = &[
+        ([0x20, 0x00, 0x80, 0x04], "add z0.s, p0/m, z0.s, z1.s"),
+        ([0xe3, 0x1f, 0x01, 0x04], "sub z3.b, p7/m, z3.b, z31.b"),
+        ([0x20, 0x00, 0x94, 0x04], "sdiv z0.s, p0/m, z0.s, z1.s"),
+        ([0x20, 0x20, 0x00, 0x04], "saddv d0, p0, z1.b"),
+        ([0x20, 0x20, 0x09, 0x04], "umaxv b0, p0, z1.b"),
+        ([0x20, 0x20, 0x91, 0x04], "movprfx z0.s, p0/m, z1.s"),
+        ([0xe0, 0x83, 0x40, 0x04], "asr z0.s, p0/m, z0.s, #0x1"),
+        ([0xe0, 0x83, 0xc3, 0x04], "lsl z0.d, p0/m, z0.d, #0x3f"),
+        ([0xa0, 0x83, 0x04, 0x04], "asrd z0.h, p0/m, z0.h, #0x3"),
+        ([0x20, 0x80, 0x97, 0x04], "lslr z0.s, p0/m, z0.s, z1.s"),
+        ([0x20, 0xa0, 0xd4, 0x04], "sxtw z0.d, p0/m, z1.d"),
+        ([0x20, 0xa0, 0x9e, 0x04], "not z0.s, p0/m, z1.s"),
+        ([0x20, 0x40, 0x82, 0x04], "mla z0.s, p0/m, z1.s, z2.s"),
+        ([0x40, 0xc0, 0x81, 0x04], "mad z0.s, p0/m, z1.s, z2.s"),
+        ([0x20, 0x00, 0xa2, 0x04], "add z0.s, z1.s, z2.s"),
+        ([0x20, 0x1c, 0xa2, 0x04], "uqsub z0.s, z1.s, z2.s"),
+        ([0x20, 0x30, 0x61, 0x04], "mov z0.d, z1.d"),
+        ([0x20, 0x30, 0xe2, 0x04], "bic z0.d, z1.d, z2.d"),
+        ([0x20, 0x44, 0xbf, 0x04], "index z0.s, w1, #-0x1"),
+        ([0x60, 0x48, 0xe2, 0x04], "index z0.d, #0x3, x2"),
+        ([0xdf, 0x57, 0x3f, 0x04], "addvl sp, sp, #-0x2"),
+        ([0x20, 0x50, 0xbf, 0x04], "rdvl x0, #0x1"),
+        ([0x20, 0x94, 0x30, 0x04], "lsr z0.h, z1.h, #0x10"),
+        ([0xe0, 0xe3, 0x20, 0x04], "cntb x0"),
+        ([0xe0, 0xe3, 0x62, 0x04], "cnth x0, all, mul #0x3"),
+        ([0xc5, 0xe1, 0xe0, 0x04], "cntd x5, #0xe"),
+        ([0x00, 0xe5, 0xf1, 0x04], "decd x0, vl8, mul #0x2"),
+        ([0xe0, 0xc3, 0xb0, 0x04], "incw z0.s"),
+        ([0x20, 0x38, 0xa0, 0x05], "mov z0.s, w1"),
+        ([0xe0, 0x3b, 0xe0, 0x05], "mov z0.d, sp"),
+        ([0x20, 0x38, 0xa4, 0x05], "insr z0.s, w1"),
+        ([0x20, 0x30, 0xa2, 0x05], "tbl z0.s, {z1.s}, z2.s"),
+        ([0x20, 0x60, 0xa2, 0x05], "zip1 z0.s, z1.s, z2.s"),
+        ([0x20, 0xc0, 0xa2, 0x05], "sel z0.s, p0, z1.s, z2.s"),
+        ([0x20, 0xc0, 0xa0, 0x05], "mov z0.s, p0/m, z1.s"),
+        ([0x00, 0xa4, 0x81, 0x24], "cmpeq p0.s, p1/z, z0.s, z1.s"),
+        ([0x10, 0xc4, 0xbf, 0x24], "cmphi p0.s, p1/z, z0.s, #0x7f"),
+        ([0x00, 0x84, 0x1f, 0x25], "cmpeq p0.b, p1/z, z0.b, #-0x1"),
+        ([0x10, 0x24, 0x40, 0x25], "cmple p0.h, p1/z, z0.h, #0x0"),
+        ([0xe0, 0xe3, 0x98, 0x25], "ptrue p0.s"),
+        ([0x80, 0xe0, 0x18, 0x25], "ptrue p0.b, vl4"),
+        ([0xa1, 0xe1, 0xd9, 0x25], "ptrues p1.d, vl256"),
+        ([0xe7, 0xe1, 0x98, 0x25], "ptrue p7.s, #0xf"),
+        ([0x00, 0xe4, 0x18, 0x25], "pfalse p0.b"),
+        ([0x20, 0xc0, 0x50, 0x25], "ptest p0, p1.b"),
+        ([0x00, 0x14, 0xa1, 0x25], "whilelt p0.s, x0, x1"),
+        ([0x10, 0x04, 0xa1, 0x25], "whilele p0.s, w0, w1"),
+        ([0x10, 0x18, 0xa1, 0x25], "whilehi p0.s, x0, x1"),
+        ([0x40, 0x44, 0x03, 0x25], "and p0.b, p1/z, p2.b, p3.b"),
+        ([0x50, 0x46, 0x03, 0x25], "sel p0.b, p1, p2.b, p3.b"),
+        ([0x50, 0x46, 0xc3, 0x25], "nands p0.b, p1/z, p2.b, p3.b"),
+        ([0x00, 0xd0, 0x78, 0x25], "mov z0.h, #-0x80"),
+        ([0x20, 0xe0, 0x78, 0x25], "mov z0.h, #0x100"),
+        ([0x00, 0xdc, 0xf9, 0x25], "fmov z0.d, #-0.5"),
+        ([0x20, 0xc0, 0xa0, 0x25], "add z0.s, z0.s, #0x1"),
+        ([0x20, 0xc0, 0x63, 0x25], "subr z0.h, z0.h, #0x1"),
+        ([0x40, 0x84, 0xa0, 0x25], "cntp x0, p1, p2.s"),
+        ([0x20, 0x80, 0x80, 0x65], "fadd z0.s, p0/m, z0.s, z1.s"),
+        ([0x20, 0x80, 0x8c, 0x65], "fdivr z0.s, p0/m, z0.s, z1.s"),
+        ([0x20, 0x0c, 0x82, 0x65], "ftsmul z0.s, z1.s, z2.s"),
+        ([0x20, 0x00, 0xa2, 0x65], "fmla z0.s, p0/m, z1.s, z2.s"),
+        ([0x20, 0xe0, 0xa2, 0x65], "fnmsb z0.s, p0/m, z1.s, z2.s"),
+        ([0x20, 0xa0, 0x56, 0x65], "scvtf z0.h, p0/m, z1.d"),
+        ([0x20, 0xa0, 0x95, 0x65], "ucvtf z0.s, p0/m, z1.s"),
+        ([0x20, 0xa0, 0x5e, 0x65], "fcvtzs z0.d, p0/m, z1.h"),
+        ([0x20, 0xa0, 0xdf, 0x65], "fcvtzu z0.d, p0/m, z1.d"),
+        ([0x00, 0xc4, 0x81, 0x65], "fcmuo p0.s, p1/z, z0.s, z1.s"),
+        ([0x10, 0xe4, 0x81, 0x65], "facgt p0.s, p1/z, z0.s, z1.s"),
+        ([0x20, 0x20, 0x80, 0x65], "faddv s0, p0, z1.s"),
+        ([0x20, 0x20, 0xc5, 0x65], "fminnmv d0, p0, z1.d"),
+        ([0x20, 0x44, 0x81, 0x25], "mov p0.b, p1.b"),
+        ([0x40, 0x44, 0x02, 0x25], "mov p0.b, p1/z, p2.b"),
+        ([0x40, 0x46, 0x01, 0x25], "not p0.b, p1/z, p2.b"),
+        ([0x50, 0x46, 0x00, 0x25], "mov p0.b, p1/m, p2.b"),
+        ([0x20, 0x20, 0x2c, 0x05], "mov z0.s, z1.s[1]"),
+        ([0x20, 0x20, 0x24, 0x05], "mov z0.s, s1"),
+        ([0xe0, 0x00, 0x80, 0x05], "and z0.s, z0.s, #0xff"),
+        ([0xe0, 0x04, 0xc0, 0x05], "mov z0.h, #0xff"),
+        ([0xe0, 0xc2, 0xc0, 0x05], "dupm z0.s, #0xffffff00"),
+        ([0x20, 0xbc, 0x20, 0x04], "movprfx z0, z1"),
+        ([0x20, 0x48, 0x41, 0x25], "movs p0.b, p2/z, p1.b"),
+        ([0x20, 0x44, 0xc1, 0x25], "movs p0.b, p1.b"),
+        ([0x40, 0x46, 0x41, 0x25], "nots p0.b, p1/z, p2.b"),
+    ];
+    let errs = run_tests(TESTS);
+
+    for err in errs.iter() {
+        println!("{}", err);
+    }
+
+    assert!(errs.is_empty());
+}
+
+#[test]
+fn test_sve_memory() {
+    const TESTS: &[([u8; 4], &'static str)] = &[
+        ([0x00, 0x40, 0x80, 0x85], "ldr z0, [x0]"),
+        ([0xe1, 0x43, 0xa0, 0x85], "ldr z1, [sp, #-0x100, mul vl]"),
+        ([0x00, 0x1c, 0x9f, 0x85], "ldr p0, [x0, #0xff, mul vl]"),
+        ([0x00, 0x44, 0x80, 0xe5], "str z0, [x0, #0x1, mul vl]"),
+        ([0xef, 0x03, 0x80, 0xe5], "str p15, [sp]"),
+        ([0x00, 0xa0, 0x00, 0xa4], "ld1b {z0.b}, p0/z, [x0]"),
+        ([0x00, 0xa0, 0x48, 0xa4], "ld1b {z0.s}, p0/z, [x0, #-0x8, mul vl]"),
+        ([0x00, 0x40, 0x81, 0xa4], "ld1sw {z0.d}, p0/z, [x0, x1, lsl #2]"),
+        ([0x00, 0xa0, 0x41, 0xa5], "ld1w {z0.s}, p0/z, [x0, #0x1, mul vl]"),
+        ([0x00, 0x40, 0xc3, 0xa5], "ld1sb {z0.h}, p0/z, [x0, x3]"),
+        ([0xff, 0x5f, 0xe3, 0xa5], "ld1d {z31.d}, p7/z, [sp, x3, lsl #3]"),
+        ([0x00, 0xe0, 0x21, 0xa5], "ld2w {z0.s, z1.s}, p0/z, [x0, #0x2, mul vl]"),
+        ([0x00, 0xc0, 0xc1, 0xa5], "ld3d {z0.d, z1.d, z2.d}, p0/z, [x0, x1, lsl #3]"),
+        ([0x1e, 0xe0, 0x60, 0xa4], "ld4b {z30.b, z31.b, z0.b, z1.b}, p0/z, [x0]"),
+        ([0x00, 0xc0, 0x81, 0xa5], "ldnt1d {z0.d}, p0/z, [x0, x1, lsl #3]"),
+        ([0x00, 0xe0, 0x61, 0xe4], "st1b {z0.d}, p0, [x0, #0x1, mul vl]"),
+        ([0x00, 0x40, 0xc1, 0xe4], "st1h {z0.s}, p0, [x0, x1, lsl #1]"),
+        ([0x00, 0xe0, 0x4f, 0xe5], "st1w {z0.s}, p0, [x0, #-0x1, mul vl]"),
+        ([0x00, 0xe0, 0x31, 0xe5], "st2w {z0.s, z1.s}, p0, [x0, #0x2, mul vl]"),
+        ([0x00, 0xe0, 0xf0, 0xe5], "st4d {z0.d, z1.d, z2.d, z3.d}, p0, [x0]"),
+        ([0x00, 0x60, 0x01, 0xe5], "stnt1w {z0.s}, p0, [x0, x1, lsl #2]"),
+        ([0x00, 0x60, 0x01, 0xa4], "ldff1b {z0.b}, p0/z, [x0, x1]"),
+        ([0x00, 0x60, 0xe1, 0xa5], "ldff1d {z0.d}, p0/z, [x0, x1, lsl #3]"),
+        ([0x00, 0x60, 0x9f, 0xa4], "ldff1sw {z0.d}, p0/z, [x0]"),
+        ([0x00, 0xa0, 0x11, 0xa4], "ldnf1b {z0.b}, p0/z, [x0, #0x1, mul vl]"),
+        ([0x00, 0xa0, 0x90, 0xa4], "ldnf1sw {z0.d}, p0/z, [x0]"),
+        ([0x00, 0x80, 0x41, 0x84], "ld1rb {z0.b}, p0/z, [x0, #0x1]"),
+        ([0x00, 0xe0, 0x41, 0x85], "ld1rw {z0.d}, p0/z, [x0, #0x4]"),
+        ([0x00, 0x80, 0xc1, 0x84], "ld1rsw {z0.d}, p0/z, [x0, #0x4]"),
+        ([0x00, 0x00, 0xdf, 0x85], "prfb pldl1keep, p0, [x0, #0x1f, mul vl]"),
+        ([0x00, 0x60, 0xe0, 0x85], "prfd pldl1keep, p0, [x0, #-0x20, mul vl]"),
+    ];
+    let errs = run_tests(TESTS);
+
+    for err in errs.iter() {
+        println!("{}", err);
+    }
+
+    assert!(errs.is_empty());
+}
+
+#[test]
+fn test_sve_gather_scatter() {
+    const TESTS: &[([u8; 4], &'static str)] = &[
+        ([0x00, 0x40, 0x01, 0x84], "ld1b {z0.s}, p0/z, [x0, z1.s, uxtw]"),
+        ([0x00, 0x00, 0x41, 0x84], "ld1sb {z0.s}, p0/z, [x0, z1.s, sxtw]"),
+        ([0x00, 0x60, 0x01, 0x84], "ldff1b {z0.s}, p0/z, [x0, z1.s, uxtw]"),
+        ([0x00, 0x40, 0xa1, 0x84], "ld1h {z0.s}, p0/z, [x0, z1.s, uxtw #1]"),
+        ([0x00, 0x40, 0x61, 0x85], "ld1w {z0.s}, p0/z, [x0, z1.s, sxtw #2]"),
+        ([0x20, 0xc0, 0x21, 0x84], "ld1b {z0.s}, p0/z, [z1.s, #0x1]"),
+        ([0x20, 0xc0, 0x21, 0x85], "ld1w {z0.s}, p0/z, [z1.s, #0x4]"),
+        ([0x20, 0xa0, 0xa1, 0x84], "ldff1sh {z0.s}, p0/z, [z1.s, #0x2]"),
+        ([0x20, 0xa0, 0x00, 0x85], "ldnt1w {z0.s}, p0/z, [z1.s, x0]"),
+        ([0x00, 0xc0, 0xc1, 0xc5], "ld1d {z0.d}, p0/z, [x0, z1.d]"),
+        ([0x00, 0xc0, 0xe1, 0xc5], "ld1d {z0.d}, p0/z, [x0, z1.d, lsl #3]"),
+        ([0x00, 0x40, 0x81, 0xc5], "ld1d {z0.d}, p0/z, [x0, z1.d, uxtw]"),
+        ([0x00, 0x40, 0xe1, 0xc5], "ld1d {z0.d}, p0/z, [x0, z1.d, sxtw #3]"),
+        ([0x20, 0x80, 0x22, 0xc5], "ld1sw {z0.d}, p0/z, [z1.d, #0x8]"),
+        ([0x20, 0xe0, 0xa1, 0xc5], "ldff1d {z0.d}, p0/z, [z1.d, #0x8]"),
+        ([0x20, 0xc0, 0x80, 0xc5], "ldnt1d {z0.d}, p0/z, [z1.d, x0]"),
+        ([0x00, 0x00, 0x21, 0x84], "prfb pldl1keep, p0, [x0, z1.s, uxtw]"),
+        ([0x20, 0xe0, 0x01, 0x85], "prfw pldl1keep, p0, [z1.s, #0x4]"),
+        ([0x2b, 0xe0, 0x00, 0x84], "prfb pstl2strm, p0, [z1.s]"),
+        ([0x26, 0xe0, 0x00, 0x84], "prfb #0x6, p0, [z1.s]"),
+        ([0x00, 0x80, 0x41, 0xe4], "st1b {z0.s}, p0, [x0, z1.s, uxtw]"),
+        ([0x00, 0xc0, 0x61, 0xe5], "st1w {z0.s}, p0, [x0, z1.s, sxtw #2]"),
+        ([0x20, 0xa0, 0x61, 0xe5], "st1w {z0.s}, p0, [z1.s, #0x4]"),
+        ([0x00, 0xa0, 0xa1, 0xe5], "st1d {z0.d}, p0, [x0, z1.d, lsl #3]"),
+        ([0x00, 0xa0, 0x81, 0xe5], "st1d {z0.d}, p0, [x0, z1.d]"),
+        ([0x00, 0x80, 0xa1, 0xe5], "st1d {z0.d}, p0, [x0, z1.d, uxtw #3]"),
+        ([0x00, 0xc0, 0x81, 0xe4], "st1h {z0.d}, p0, [x0, z1.d, sxtw]"),
+        ([0x20, 0xa0, 0xc1, 0xe5], "st1d {z0.d}, p0, [z1.d, #0x8]"),
+        ([0x20, 0x20, 0x40, 0xe5], "stnt1w {z0.s}, p0, [z1.s, x0]"),
+    ];
+    let errs = run_tests(TESTS);
+
+    for err in errs.iter() {
+        println!("{}", err);
+    }
+
+    assert!(errs.is_empty());
+}
+
+#[test]
+fn test_sve_saturating_counts() {
+    const TESTS: &[([u8; 4], &'static str)] = &[
+        ([0xe0, 0xf3, 0x20, 0x04], "sqincb x0, w0"),
+        ([0xe0, 0xf3, 0x30, 0x04], "sqincb x0"),
+        ([0xe0, 0xf7, 0x20, 0x04], "uqincb w0"),
+        ([0xe0, 0xcf, 0x60, 0x04], "uqdech z0.h"),
+        ([0x00, 0xc0, 0xa0, 0x04], "sqincw z0.s, pow2"),
+        ([0x80, 0xf8, 0xe1, 0x04], "sqdecd x0, w0, vl4, mul #0x2"),
+        ([0x00, 0x8c, 0x28, 0x25], "sqincp x0, p0.b"),
+        ([0x00, 0x88, 0x28, 0x25], "sqincp x0, p0.b, w0"),
+        ([0x00, 0x88, 0x29, 0x25], "uqincp w0, p0.b"),
+        ([0x00, 0x80, 0xaa, 0x25], "sqdecp z0.s, p0.s"),
+        ([0x00, 0x88, 0x2c, 0x25], "incp x0, p0.b"),
+        ([0x00, 0x88, 0xed, 0x25], "decp x0, p0.d"),
+        ([0x00, 0x80, 0x6c, 0x25], "incp z0.h, p0.h"),
+    ];
+    let errs = run_tests(TESTS);
+
+    for err in errs.iter() {
+        println!("{}", err);
+    }
+
+    assert!(errs.is_empty());
+}
+
+#[test]
+fn test_sve2() {
+    const TESTS: &[([u8; 4], &'static str)] = &[
+        ([0x40, 0x38, 0x21, 0x04], "eor3 z0.d, z0.d, z1.d, z2.d"),
+        ([0x40, 0x38, 0x61, 0x04], "bcax z0.d, z0.d, z1.d, z2.d"),
+        ([0x40, 0x3c, 0x61, 0x04], "bsl1n z0.d, z0.d, z1.d, z2.d"),
+        ([0x40, 0x3c, 0xe1, 0x04], "nbsl z0.d, z0.d, z1.d, z2.d"),
+        ([0x20, 0x64, 0x22, 0x04], "pmul z0.b, z1.b, z2.b"),
+        ([0x20, 0x74, 0xa2, 0x04], "sqrdmulh z0.s, z1.s, z2.s"),
+        ([0x00, 0x10, 0xa1, 0x25], "whilege p0.s, x0, x1"),
+        ([0x20, 0x00, 0x82, 0x44], "sdot z0.s, z1.b, z2.b"),
+        ([0x20, 0x04, 0xc2, 0x44], "udot z0.d, z1.h, z2.h"),
+        ([0x20, 0x00, 0x82, 0x45], "saddlb z0.s, z1.h, z2.h"),
+        ([0x20, 0x1c, 0x42, 0x45], "usublt z0.h, z1.b, z2.b"),
+        ([0x20, 0xe0, 0x22, 0x45], "aese z0.b, z0.b, z1.b"),
+        ([0x00, 0xe4, 0x20, 0x45], "aesimc z0.b, z0.b"),
+    ];
+    let errs = run_tests(TESTS);
+
+    for err in errs.iter() {
+        println!("{}", err);
+    }
+
+    assert!(errs.is_empty());
+}
+
+#[test]
+fn test_sme() {
+    const TESTS: &[([u8; 4], &'static str)] = &[
+        ([0x00, 0x20, 0x81, 0x80], "fmopa za0.s, p0/m, p1/m, z0.s, z1.s"),
+        ([0xf3, 0xdf, 0x9e, 0x80], "fmops za3.s, p7/m, p6/m, z31.s, z30.s"),
+        ([0x07, 0x20, 0xc1, 0x80], "fmopa za7.d, p0/m, p1/m, z0.d, z1.d"),
+        ([0x00, 0x20, 0x81, 0xa0], "smopa za0.s, p0/m, p1/m, z0.b, z1.b"),
+        ([0x01, 0x20, 0xa1, 0xa1], "umopa za1.s, p0/m, p1/m, z0.b, z1.b"),
+        ([0x02, 0x20, 0xa1, 0xa0], "sumopa za2.s, p0/m, p1/m, z0.b, z1.b"),
+        ([0x03, 0x20, 0x81, 0xa1], "usmopa za3.s, p0/m, p1/m, z0.b, z1.b"),
+        ([0x10, 0x20, 0x81, 0xa0], "smops za0.s, p0/m, p1/m, z0.b, z1.b"),
+        ([0xff, 0x00, 0x08, 0xc0], "zero {za}"),
+        ([0x01, 0x00, 0x08, 0xc0], "zero {za0.d}"),
+        ([0x11, 0x00, 0x08, 0xc0], "zero {za0.s}"),
+        ([0xaa, 0x00, 0x08, 0xc0], "zero {za1.h}"),
+        ([0x00, 0x00, 0x00, 0xe1], "ldr za[w12, 0], [x0]"),
+        ([0xef, 0x63, 0x00, 0xe1], "ldr za[w15, 15], [sp, #0xf, mul vl]"),
+        ([0x21, 0x20, 0x20, 0xe1], "str za[w13, 1], [x1, #0x1, mul vl]"),
+        ([0x00, 0x00, 0x80, 0xc0], "mov za0h.s[w12, 0], p0/m, z0.s"),
+        ([0x00, 0x00, 0x82, 0xc0], "mov z0.s, p0/m, za0h.s[w12, 0]"),
+        ([0xef, 0xff, 0x80, 0xc0], "mov za3v.s[w15, 3], p7/m, z31.s"),
+        ([0x0f, 0x00, 0x00, 0xc0], "mov za0h.b[w12, 15], p0/m, z0.b"),
+        ([0xe1, 0xc9, 0xc2, 0xc0], "mov z1.d, p2/m, za7v.d[w14, 1]"),
+        ([0xe0, 0x81, 0xc3, 0xc0], "mov z0.q, p0/m, za15v.q[w12, 0]"),
+        ([0x7f, 0x47, 0x03, 0xd5], "smstart"),
+        ([0x7f, 0x46, 0x03, 0xd5], "smstop"),
+        ([0x7f, 0x43, 0x03, 0xd5], "smstart sm"),
+        ([0x7f, 0x45, 0x03, 0xd5], "smstart za"),
+        ([0x7f, 0x42, 0x03, 0xd5], "smstop sm"),
+        ([0x7f, 0x44, 0x03, 0xd5], "smstop za"),
+        ([0xe0, 0x5f, 0x61, 0x04], "addspl x0, x1, #-0x1"),
+        ([0x83, 0x58, 0xbf, 0x04], "rdsvl x3, #0x4"),
+    ];
+    let errs = run_tests(TESTS);
+
+    for err in errs.iter() {
+        println!("{}", err);
+    }
+
+    assert!(errs.is_empty());
+}
+
+#[test]
+fn test_sve_reserved() {
+    // `and z2.h, z2.h, #0xffff`: an immediate of all ones is reserved.
+    test_err([0xe2, 0x9d, 0x81, 0x05], ErrorKind::InvalidOperand);
+    // `ld1sw` into 32-bit elements, which would not extend anything.
+    test_err([0x00, 0x00, 0x01, 0x85], ErrorKind::InvalidOpcode);
+    // a scatter of bytes with scaled offsets.
+    test_err([0x00, 0x80, 0x21, 0xe4], ErrorKind::InvalidOpcode);
+    // a prefetch with bit 4 set.
+    test_err([0x30, 0xe0, 0x00, 0x84], ErrorKind::InvalidOpcode);
+}
+
+#[test]
+fn test_sve_incomplete() {
+    // `setffr`: the first-fault register instructions are not decoded yet, but are not invalid.
+    test_err([0x00, 0x90, 0x2c, 0x25], ErrorKind::IncompleteDecoder);
 }
 
 #[test]
@@ -4778,6 +5067,32 @@ fn test_pac() {
     assert!(errs.is_empty());
 }
 
+#[test]
+fn test_pac_hints() {
+    const TESTS: &[([u8; 4], &'static str)] = &[
+        ([0x3f, 0x23, 0x03, 0xd5], "paciasp"),
+        ([0xbf, 0x23, 0x03, 0xd5], "autiasp"),
+        ([0x7f, 0x23, 0x03, 0xd5], "pacibsp"),
+        ([0xff, 0x23, 0x03, 0xd5], "autibsp"),
+        ([0x1f, 0x23, 0x03, 0xd5], "paciaz"),
+        ([0xdf, 0x23, 0x03, 0xd5], "autibz"),
+        ([0x1f, 0x21, 0x03, 0xd5], "pacia1716"),
+        ([0xdf, 0x21, 0x03, 0xd5], "autib1716"),
+        ([0xff, 0x20, 0x03, 0xd5], "xpaclri"),
+        ([0x1f, 0x24, 0x03, 0xd5], "bti"),
+        ([0x5f, 0x24, 0x03, 0xd5], "bti c"),
+        ([0x9f, 0x24, 0x03, 0xd5], "bti j"),
+        ([0xdf, 0x24, 0x03, 0xd5], "bti jc"),
+    ];
+    let errs = run_tests(TESTS);
+
+    for err in errs.iter() {
+        println!("{}", err);
+    }
+
+    assert!(errs.is_empty());
+}
+
 #[test]
 fn test_uq() {
     const TESTS: &[([u8; 4], &'static str)] = &[
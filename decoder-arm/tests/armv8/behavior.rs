@@ -0,0 +1,183 @@
+use arm::armv8::a64::Instruction;
+use decoder::{Decodable, Decoded, Direction, FlowKind, MemoryAccess, Reader};
+
+fn decode(data: [u8; 4]) -> Instruction {
+    let mut reader = Reader::new(&data[..]);
+    arm::armv8::a64::Decoder::default().decode(&mut reader).unwrap()
+}
+
+#[test]
+fn flow() {
+    assert_eq!(
+        decode([0x20, 0x00, 0x02, 0x8b]).flow(),
+        FlowKind::Sequential
+    );
+    assert_eq!(decode([0x00, 0x00, 0x00, 0x14]).flow(), FlowKind::Jump);
+    assert_eq!(decode([0x00, 0x02, 0x1f, 0xd6]).flow(), FlowKind::Jump);
+    assert_eq!(
+        decode([0x00, 0x00, 0x00, 0x54]).flow(),
+        FlowKind::ConditionalJump
+    );
+    assert_eq!(
+        decode([0x00, 0x00, 0x00, 0xb4]).flow(),
+        FlowKind::ConditionalJump
+    );
+    assert_eq!(decode([0x00, 0x00, 0x00, 0x94]).flow(), FlowKind::Call);
+    assert_eq!(decode([0x00, 0x01, 0x3f, 0xd6]).flow(), FlowKind::Call);
+    assert_eq!(decode([0xc0, 0x03, 0x5f, 0xd6]).flow(), FlowKind::Return);
+    assert_eq!(decode([0x01, 0x00, 0x00, 0xd4]).flow(), FlowKind::Interrupt);
+
+    assert!(decode([0x00, 0x00, 0x00, 0x54]).can_fall_through());
+    assert!(!decode([0xc0, 0x03, 0x5f, 0xd6]).can_fall_through());
+}
+
+#[test]
+fn targets() {
+    let mut inst = decode([0x02, 0x00, 0x00, 0x94]);
+    assert_eq!(inst.targets(), vec![]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![0x1008]);
+
+    // adr isn't a branch
+    let mut inst = decode([0x40, 0x00, 0x00, 0x10]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![]);
+}
+
+#[test]
+fn registers() {
+    // add x0, x1, x2
+    let inst = decode([0x20, 0x00, 0x02, 0x8b]);
+    assert_eq!(inst.regs_read(), vec!["x1", "x2"]);
+    assert_eq!(inst.regs_written(), vec!["x0"]);
+
+    // ldr x0, [x1, #0x8]
+    let inst = decode([0x20, 0x04, 0x40, 0xf9]);
+    assert_eq!(inst.regs_read(), vec!["x1"]);
+    assert_eq!(inst.regs_written(), vec!["x0"]);
+
+    // stp x29, x30, [sp, #-0x10]!
+    let inst = decode([0xfd, 0x7b, 0xbf, 0xa9]);
+    assert_eq!(inst.regs_read(), vec!["x29", "x30", "sp"]);
+    assert_eq!(inst.regs_written(), vec!["sp"]);
+
+    // cmp x0, #0x1
+    let inst = decode([0x1f, 0x04, 0x00, 0xf1]);
+    assert_eq!(inst.regs_read(), vec!["x0"]);
+    assert_eq!(inst.regs_written(), vec!["nzcv"]);
+
+    // b.eq
+    let inst = decode([0x00, 0x00, 0x00, 0x54]);
+    assert_eq!(inst.regs_read(), vec!["nzcv"]);
+
+    // blr x8
+    let inst = decode([0x00, 0x01, 0x3f, 0xd6]);
+    assert_eq!(inst.regs_read(), vec!["x8"]);
+    assert_eq!(inst.regs_written(), vec!["x30"]);
+
+    // ldadd w1, w2, [x0]
+    let inst = decode([0x02, 0x00, 0x21, 0xb8]);
+    assert_eq!(inst.regs_read(), vec!["w1", "x0"]);
+    assert_eq!(inst.regs_written(), vec!["w2"]);
+
+    // add z0.s, p0/m, z0.s, z1.s
+    let inst = decode([0x20, 0x00, 0x80, 0x04]);
+    assert_eq!(inst.regs_read(), vec!["z0", "p0", "z1"]);
+    assert_eq!(inst.regs_written(), vec!["z0"]);
+
+    // ld1w {z0.s}, p0/z, [x0, #0x1, mul vl]
+    let inst = decode([0x00, 0xa0, 0x41, 0xa5]);
+    assert_eq!(inst.regs_read(), vec!["p0", "x0"]);
+    assert_eq!(inst.regs_written(), vec!["z0"]);
+
+    // ld1w {z0.s}, p0/z, [x0, z1.s, sxtw #2]
+    let inst = decode([0x00, 0x40, 0x61, 0x85]);
+    assert_eq!(inst.regs_read(), vec!["p0", "x0", "z1"]);
+    assert_eq!(inst.regs_written(), vec!["z0"]);
+
+    // st1d {z0.d}, p0, [z1.d, #0x8]
+    let inst = decode([0x20, 0xa0, 0xc1, 0xe5]);
+    assert_eq!(inst.regs_read(), vec!["z0", "p0", "z1"]);
+    assert!(inst.regs_written().is_empty());
+
+    // ldff1b {z0.b}, p0/z, [x0, x1]
+    let inst = decode([0x00, 0x60, 0x01, 0xa4]);
+    assert_eq!(inst.regs_read(), vec!["p0", "x0", "x1", "ffr"]);
+    assert_eq!(inst.regs_written(), vec!["z0", "ffr"]);
+
+    // sqincp x0, p0.b, w0
+    let inst = decode([0x00, 0x88, 0x28, 0x25]);
+    assert_eq!(inst.regs_read(), vec!["x0", "p0", "w0"]);
+    assert_eq!(inst.regs_written(), vec!["x0"]);
+
+    // whilelt p0.s, x0, x1
+    let inst = decode([0x00, 0x14, 0xa1, 0x25]);
+    assert_eq!(inst.regs_read(), vec!["x0", "x1"]);
+    assert_eq!(inst.regs_written(), vec!["p0", "nzcv"]);
+
+    // mov za0h.s[w12, 0], p0/m, z0.s
+    let inst = decode([0x00, 0x00, 0x80, 0xc0]);
+    assert_eq!(inst.regs_read(), vec!["za", "w12", "p0", "z0"]);
+    assert_eq!(inst.regs_written(), vec!["za"]);
+
+    // paciasp
+    let inst = decode([0x3f, 0x23, 0x03, 0xd5]);
+    assert_eq!(inst.regs_read(), vec!["x30", "sp"]);
+    assert_eq!(inst.regs_written(), vec!["x30"]);
+}
+
+#[test]
+fn memory() {
+    assert_eq!(decode([0x20, 0x00, 0x02, 0x8b]).memory(), None);
+
+    assert_eq!(
+        decode([0x20, 0x04, 0x40, 0xf9]).memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Read
+        })
+    );
+
+    assert_eq!(
+        decode([0xfd, 0x7b, 0xbf, 0xa9]).memory(),
+        Some(MemoryAccess {
+            size: Some(16),
+            direction: Direction::Write
+        })
+    );
+
+    // ldrb w0, [x1]
+    assert_eq!(
+        decode([0x20, 0x00, 0x40, 0x39]).memory(),
+        Some(MemoryAccess {
+            size: Some(1),
+            direction: Direction::Read
+        })
+    );
+
+    assert_eq!(
+        decode([0x02, 0x00, 0x21, 0xb8]).memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::ReadWrite
+        })
+    );
+
+    // ld1rw {z0.d}, p0/z, [x0, #0x4] reads a single element
+    assert_eq!(
+        decode([0x00, 0xe0, 0x41, 0x85]).memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::Read
+        })
+    );
+
+    // st1w {z0.s}, p0, [x0, #-0x1, mul vl], whose size depends on the vector length
+    assert_eq!(
+        decode([0x00, 0xe0, 0x4f, 0xe5]).memory(),
+        Some(MemoryAccess {
+            size: None,
+            direction: Direction::Write
+        })
+    );
+}
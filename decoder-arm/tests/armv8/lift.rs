@@ -0,0 +1,156 @@
+use arm::armv8::a64::Instruction;
+use decoder::ir::Builder;
+use decoder::{Decodable, Decoded, Reader};
+
+fn decode(data: [u8; 4]) -> Instruction {
+    let mut reader = Reader::new(&data[..]);
+    arm::armv8::a64::Decoder::default().decode(&mut reader).unwrap()
+}
+
+fn lift(data: [u8; 4]) -> Vec<String> {
+    let mut inst = decode(data);
+    inst.update_rel_addrs(0x1000, None);
+    let mut builder = Builder::new();
+    inst.lift(0x1000, &mut builder);
+    builder.take().iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn data_processing() {
+    // mov x0, x1
+    assert_eq!(lift([0xe0, 0x03, 0x01, 0xaa]), ["t0 = x1", "x0 = t0"]);
+    // add x0, sp, #0x10
+    assert_eq!(
+        lift([0xe0, 0x43, 0x00, 0x91]),
+        ["t0 = sp", "t1 = t0 + 0x10", "x0 = t1"]
+    );
+    // lsr w0, w1, #0x2
+    assert_eq!(
+        lift([0x20, 0x7c, 0x02, 0x53]),
+        ["t0 = x1", "t1 = t0 >>u 0x2", "t2 = zext64 t1", "x0 = t2"]
+    );
+    // ubfx x0, x1, #0x4, #0x8
+    assert_eq!(
+        lift([0x20, 0x2c, 0x44, 0xd3]),
+        [
+            "t0 = x1",
+            "t1 = t0 >>u 0x4",
+            "t2 = trunc8 t1",
+            "t3 = zext64 t2",
+            "x0 = t3"
+        ]
+    );
+    // mov x0, #0xfffffffffffffffe
+    assert_eq!(lift([0x20, 0x00, 0x80, 0x92]), ["x0 = 0xfffffffffffffffe"]);
+    // adrp x0, $+0x4000
+    assert_eq!(lift([0x20, 0x00, 0x00, 0x90]), ["x0 = 0x5000"]);
+    // cset w0, eq
+    assert_eq!(
+        lift([0xe0, 0x17, 0x9f, 0x1a]),
+        [
+            "t0 = z",
+            "t1 = ~t0",
+            "t2 = t1 ? 0x0 : 0x1",
+            "t3 = zext64 t2",
+            "x0 = t3"
+        ]
+    );
+}
+
+#[test]
+fn flags() {
+    // cmp x0, x1
+    assert_eq!(
+        lift([0x1f, 0x00, 0x01, 0xeb]),
+        [
+            "t0 = x0",
+            "t1 = x1",
+            "t2 = t0 - t1",
+            "t3 = t2 <s 0x0",
+            "t4 = t2 == 0x0",
+            "n = t3",
+            "z = t4",
+            "t5 = t1 <=u t0",
+            "t6 = t0 ^ t1",
+            "t7 = t0 ^ t2",
+            "t8 = t6 & t7",
+            "t9 = t8 <s 0x0",
+            "c = t5",
+            "v = t9",
+        ]
+    );
+}
+
+#[test]
+fn memory() {
+    // ldr x0, [x1, #0x8]
+    assert_eq!(
+        lift([0x20, 0x04, 0x40, 0xf9]),
+        ["t0 = x1", "t1 = t0 + 0x8", "t2 = load64 [t1]", "x0 = t2"]
+    );
+    // stp x29, x30, [sp, #-0x10]!
+    assert_eq!(
+        lift([0xfd, 0x7b, 0xbf, 0xa9]),
+        [
+            "t0 = sp",
+            "t1 = t0 + 0xfffffffffffffff0",
+            "t2 = x29",
+            "store64 [t1], t2",
+            "t3 = t1 + 0x8",
+            "t4 = x30",
+            "store64 [t3], t4",
+            "sp = t1",
+        ]
+    );
+    // ldrsw x0, [x1]
+    assert_eq!(
+        lift([0x20, 0x00, 0x80, 0xb9]),
+        ["t0 = x1", "t1 = load32 [t0]", "t2 = sext64 t1", "x0 = t2"]
+    );
+    // ldr q0, [x0]
+    assert_eq!(lift([0x00, 0x00, 0xc0, 0x3d]), ["unsupported"]);
+}
+
+#[test]
+fn control_flow() {
+    // b.gt $+0x10
+    assert_eq!(
+        lift([0x8c, 0x00, 0x00, 0x54]),
+        [
+            "t0 = z",
+            "t1 = n",
+            "t2 = v",
+            "t3 = ~t0",
+            "t4 = t1 == t2",
+            "t5 = t3 & t4",
+            "if t5 goto 0x1010"
+        ]
+    );
+    // tbnz w0, #0x3, $+0x10
+    assert_eq!(
+        lift([0x80, 0x00, 0x18, 0x37]),
+        [
+            "t0 = x0",
+            "t1 = t0 >>u 0x3",
+            "t2 = trunc1 t1",
+            "if t2 goto 0x1010"
+        ]
+    );
+    // bl $+0x1000
+    assert_eq!(
+        lift([0x00, 0x04, 0x00, 0x94]),
+        ["x30 = 0x1004", "call 0x2000"]
+    );
+    // ret
+    assert_eq!(lift([0xc0, 0x03, 0x5f, 0xd6]), ["t0 = x30", "ret t0"]);
+    // svc #0x0
+    assert_eq!(lift([0x01, 0x00, 0x00, 0xd4]), ["svc(0x0)"]);
+}
+
+#[test]
+fn rel_addrs() {
+    // a load that doesn't follow an `adrp` keeps its base register
+    let mut inst = decode([0x00, 0x00, 0xc0, 0x3d]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.to_string(), "ldr q0, [x0]");
+}
@@ -1 +1,3 @@
 mod a64;
+mod behavior;
+mod lift;
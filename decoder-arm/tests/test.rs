@@ -1,7 +1,8 @@
 mod armv7;
 mod armv8;
 
-use decoder::{Decodable, Reader, TokenStream, ToTokens};
+use decoder::{Decodable, Reader, ToTokens};
+use tokenizing::TokenStream;
 use debugvault::Index;
 
 fn test_range<A: Decodable>(decoder: &A, start: u64, end: u64) {
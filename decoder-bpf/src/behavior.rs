@@ -0,0 +1,135 @@
+//! Semantic queries on decoded instructions: control flow, registers and memory accesses.
+//!
+//! Registers are always reported by their 64-bit name, as the 32-bit ones alias them. Calls
+//! clobber the argument registers `r1` to `r5` and return their result in `r0`.
+
+use crate::{Instruction, Kind, Operand, BPF_IND, REGISTERS};
+use decoder::{Direction, FlowKind, MemoryAccess};
+
+/// Registers a call returns in or clobbers.
+const CALL_CLOBBERED: [&str; 6] = ["r0", "r1", "r2", "r3", "r4", "r5"];
+
+/// Register legacy packet loads implicitly read the socket buffer from.
+const SKB: u8 = 6;
+
+pub(crate) fn flow(inst: &Instruction) -> FlowKind {
+    match inst.kind {
+        Kind::Jump => FlowKind::Jump,
+        Kind::Branch => FlowKind::ConditionalJump,
+        Kind::Call | Kind::CallReg => FlowKind::Call,
+        Kind::Exit => FlowKind::Return,
+        _ => FlowKind::Sequential,
+    }
+}
+
+pub(crate) fn targets(inst: &Instruction) -> Vec<usize> {
+    // `ld_imm64` of a function's address doesn't transfer control
+    if !matches!(inst.kind, Kind::Jump | Kind::Branch | Kind::Call) {
+        return Vec::new();
+    }
+
+    [&inst.operand, &inst.target]
+        .into_iter()
+        .filter_map(|operand| match *operand {
+            Operand::Branch(target) => Some(target as usize),
+            _ => None,
+        })
+        .collect()
+}
+
+fn push(regs: &mut Vec<&'static str>, reg: u8) {
+    let reg = REGISTERS[reg as usize];
+    if !regs.contains(&reg) {
+        regs.push(reg);
+    }
+}
+
+/// Collects the registers read and written by an instruction.
+pub(crate) fn registers(inst: &Instruction) -> (Vec<&'static str>, Vec<&'static str>) {
+    let mut read = Vec::new();
+    let mut written = Vec::new();
+
+    let operand = match inst.operand {
+        Operand::Register(reg) | Operand::Register32(reg) => Some(reg),
+        _ => None,
+    };
+
+    match inst.kind {
+        Kind::Alu => {
+            // moves don't depend on the destination
+            if inst.op != "=" {
+                push(&mut read, inst.dst);
+            }
+            if let Some(reg) = operand {
+                push(&mut read, reg);
+            }
+            push(&mut written, inst.dst);
+        }
+        Kind::Neg | Kind::Endian => {
+            push(&mut read, inst.dst);
+            push(&mut written, inst.dst);
+        }
+        Kind::MovSx => {
+            push(&mut read, inst.src);
+            push(&mut written, inst.dst);
+        }
+        Kind::Load => {
+            push(&mut read, inst.src);
+            push(&mut written, inst.dst);
+        }
+        Kind::Store | Kind::Atomic => {
+            push(&mut read, inst.dst);
+            if let Some(reg) = operand {
+                push(&mut read, reg);
+            }
+        }
+        Kind::AtomicFetch | Kind::Xchg => {
+            push(&mut read, inst.dst);
+            push(&mut read, inst.src);
+            push(&mut written, inst.src);
+        }
+        Kind::CmpXchg => {
+            push(&mut read, inst.dst);
+            push(&mut read, 0);
+            push(&mut read, inst.src);
+            push(&mut written, 0);
+        }
+        Kind::LoadPacket => {
+            push(&mut read, SKB);
+            if inst.opcode & 0xe0 == BPF_IND {
+                push(&mut read, inst.src);
+            }
+            written.extend(CALL_CLOBBERED);
+        }
+        Kind::LoadImm64 => push(&mut written, inst.dst),
+        Kind::Branch => {
+            push(&mut read, inst.dst);
+            if let Some(reg) = operand {
+                push(&mut read, reg);
+            }
+        }
+        Kind::Call => written.extend(CALL_CLOBBERED),
+        Kind::CallReg => {
+            push(&mut read, inst.dst);
+            written.extend(CALL_CLOBBERED);
+        }
+        Kind::Exit => push(&mut read, 0),
+        Kind::Jump => {}
+    }
+
+    (read, written)
+}
+
+pub(crate) fn memory(inst: &Instruction) -> Option<MemoryAccess> {
+    let direction = match inst.kind {
+        Kind::Load | Kind::LoadPacket => Direction::Read,
+        Kind::Store => Direction::Write,
+        Kind::Atomic | Kind::AtomicFetch | Kind::Xchg | Kind::CmpXchg => Direction::ReadWrite,
+        _ => return None,
+    };
+
+    Some(MemoryAccess {
+        size: Some(inst.size as usize),
+        direction,
+    })
+}
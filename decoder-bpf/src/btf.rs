@@ -0,0 +1,141 @@
+//! Reading of the BPF Type Format section (`.BTF`) objects carry their type information in.
+//!
+//! Only what's needed for naming calls is kept: functions by their type ID, which kernel functions
+//! (`kfunc`s) are called by, and the variants of `enum bpf_func_id` which name helpers.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const BTF_MAGIC: u16 = 0xeb9f;
+
+const BTF_KIND_INT: u32 = 1;
+const BTF_KIND_ARRAY: u32 = 3;
+const BTF_KIND_STRUCT: u32 = 4;
+const BTF_KIND_UNION: u32 = 5;
+const BTF_KIND_ENUM: u32 = 6;
+const BTF_KIND_FUNC: u32 = 12;
+const BTF_KIND_FUNC_PROTO: u32 = 13;
+const BTF_KIND_VAR: u32 = 14;
+const BTF_KIND_DATASEC: u32 = 15;
+const BTF_KIND_DECL_TAG: u32 = 17;
+const BTF_KIND_ENUM64: u32 = 19;
+
+/// Prefix of the variants of `enum bpf_func_id`.
+const HELPER_PREFIX: &str = "BPF_FUNC_";
+
+#[derive(Debug, Default, Clone)]
+pub struct Btf {
+    /// Function names by their type ID.
+    funcs: HashMap<u32, Arc<str>>,
+    /// Helper names by their ID.
+    helpers: HashMap<u32, Arc<str>>,
+}
+
+/// Reads integers in the byte order of the section.
+struct Cursor<'data> {
+    data: &'data [u8],
+    little_endian: bool,
+}
+
+impl Cursor<'_> {
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self.data.get(offset..offset + 2)?.try_into().ok()?;
+        Some(match self.little_endian {
+            true => u16::from_le_bytes(bytes),
+            false => u16::from_be_bytes(bytes),
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(match self.little_endian {
+            true => u32::from_le_bytes(bytes),
+            false => u32::from_be_bytes(bytes),
+        })
+    }
+}
+
+impl Btf {
+    /// Parse the contents of a `.BTF` section, [`None`] if the header is malformed.
+    pub fn parse(data: &[u8], little_endian: bool) -> Option<Self> {
+        let cursor = Cursor {
+            data,
+            little_endian,
+        };
+
+        if cursor.u16(0)? != BTF_MAGIC {
+            return None;
+        }
+
+        let hdr_len = cursor.u32(4)? as usize;
+        let type_off = hdr_len + cursor.u32(8)? as usize;
+        let type_end = type_off + cursor.u32(12)? as usize;
+        let str_off = hdr_len + cursor.u32(16)? as usize;
+        let str_end = str_off + cursor.u32(20)? as usize;
+        let strings = data.get(str_off..str_end)?;
+
+        let name = |offset: u32| -> Option<&str> {
+            let bytes = strings.get(offset as usize..)?;
+            let len = bytes.iter().position(|&b| b == 0)?;
+            std::str::from_utf8(&bytes[..len]).ok()
+        };
+
+        let mut this = Self::default();
+
+        // type ID zero is `void`, the first type in the section has ID one
+        let mut id = 1;
+        let mut offset = type_off;
+        while offset + 12 <= type_end {
+            let name_off = cursor.u32(offset)?;
+            let info = cursor.u32(offset + 4)?;
+            let vlen = (info & 0xffff) as usize;
+            let kind = info >> 24 & 0x1f;
+            offset += 12;
+
+            // what follows the common part of the type
+            let trailing = match kind {
+                BTF_KIND_INT | BTF_KIND_VAR | BTF_KIND_DECL_TAG => 4,
+                BTF_KIND_ARRAY => 12,
+                BTF_KIND_STRUCT | BTF_KIND_UNION | BTF_KIND_DATASEC => vlen * 12,
+                BTF_KIND_ENUM | BTF_KIND_FUNC_PROTO => vlen * 8,
+                BTF_KIND_ENUM64 => vlen * 12,
+                _ => 0,
+            };
+
+            match kind {
+                BTF_KIND_FUNC => {
+                    if let Some(func) = name(name_off).filter(|func| !func.is_empty()) {
+                        this.funcs.insert(id, Arc::from(func));
+                    }
+                }
+                BTF_KIND_ENUM if name(name_off) == Some("bpf_func_id") => {
+                    for variant in 0..vlen {
+                        let variant = offset + variant * 8;
+                        let helper = name(cursor.u32(variant)?)?;
+                        let value = cursor.u32(variant + 4)?;
+
+                        if let Some(helper) = helper.strip_prefix(HELPER_PREFIX) {
+                            this.helpers.insert(value, Arc::from(format!("bpf_{helper}")));
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            offset += trailing;
+            id += 1;
+        }
+
+        Some(this)
+    }
+
+    /// Name of the function with the given type ID.
+    pub fn func(&self, id: u32) -> Option<&Arc<str>> {
+        self.funcs.get(&id)
+    }
+
+    /// Name of the helper with the given ID, if the type information has `enum bpf_func_id`.
+    pub fn helper(&self, id: u32) -> Option<&Arc<str>> {
+        self.helpers.get(&id)
+    }
+}
@@ -0,0 +1,776 @@
+//! eBPF disassembler, covering the 32 and 64-bit ALU and jump instructions, loads, stores,
+//! atomics, calls to helpers, kernel functions and other programs and the 128-bit immediate loads
+//! that refer to maps. Instructions are written in the syntax used by LLVM and the kernel.
+
+mod behavior;
+mod btf;
+mod helpers;
+mod tests;
+
+pub use btf::Btf;
+
+use std::sync::Arc;
+
+use debugvault::Index;
+use decoder::{Error, ErrorKind, FlowKind, MemoryAccess, ToTokens};
+use tokenizing::{ColorScheme, Colors, TokenStream};
+
+#[rustfmt::skip]
+pub const REGISTERS: [&str; 11] = [
+    "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10",
+];
+
+/// Low 32 bits of the registers, used by ALU32 and JMP32 instructions.
+#[rustfmt::skip]
+pub const REGISTERS_32: [&str; 11] = [
+    "w0", "w1", "w2", "w3", "w4", "w5", "w6", "w7", "w8", "w9", "w10",
+];
+
+/// Instruction classes, in the low three bits of the opcode.
+const BPF_LD: u8 = 0x00;
+const BPF_LDX: u8 = 0x01;
+const BPF_ST: u8 = 0x02;
+const BPF_STX: u8 = 0x03;
+const BPF_ALU: u8 = 0x04;
+const BPF_JMP: u8 = 0x05;
+const BPF_JMP32: u8 = 0x06;
+const BPF_ALU64: u8 = 0x07;
+
+/// Source operand of ALU and jump instructions is `src_reg` instead of the immediate.
+const BPF_X: u8 = 0x08;
+
+/// Opcode of `ld_imm64`, which takes up two instruction slots.
+const BPF_LD_IMM64: u8 = 0x18;
+
+/// Values of `src_reg` that change what the immediate of `ld_imm64` refers to.
+const BPF_PSEUDO_MAP_FD: u8 = 1;
+const BPF_PSEUDO_MAP_VALUE: u8 = 2;
+const BPF_PSEUDO_BTF_ID: u8 = 3;
+const BPF_PSEUDO_FUNC: u8 = 4;
+const BPF_PSEUDO_MAP_IDX: u8 = 5;
+const BPF_PSEUDO_MAP_IDX_VALUE: u8 = 6;
+
+/// Values of `src_reg` that change what a call's immediate refers to.
+const BPF_PSEUDO_CALL: u8 = 1;
+const BPF_PSEUDO_KFUNC_CALL: u8 = 2;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Operand {
+    /// 64-bit register.
+    Register(u8),
+    /// Low 32 bits of a register.
+    Register32(u8),
+    Immediate(i64),
+    /// Target of a jump or call, relative to the instruction until it's address is known.
+    Branch(i64),
+    /// Map or global variable, resolved from a relocation.
+    Address(usize),
+    /// Object the loader substituted, like `map_fd[0x3]` or `map_value[0x3] + 0x10`.
+    Pseudo {
+        kind: &'static str,
+        num: i32,
+        offset: Option<i32>,
+    },
+    /// Helper function called by it's ID.
+    Helper(&'static str),
+    /// Function or variable referred to by name, like kernel functions and `extern` variables.
+    Named(Arc<str>),
+    #[default]
+    Nothing,
+}
+
+impl ToTokens for Operand {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        match *self {
+            Self::Register(reg) => stream.push(REGISTERS[reg as usize], Colors::register()),
+            Self::Register32(reg) => stream.push(REGISTERS_32[reg as usize], Colors::register()),
+            Self::Immediate(imm) => {
+                stream.push_owned(decoder::encode_hex(imm), Colors::immediate())
+            }
+            Self::Branch(addr) => tokenize_addr(stream, symbols, addr as usize),
+            Self::Address(addr) => tokenize_addr(stream, symbols, addr),
+            Self::Pseudo { kind, num, offset } => {
+                stream.push(kind, Colors::item());
+                stream.push("[", Colors::brackets());
+                stream.push_owned(decoder::encode_hex(num as i64), Colors::immediate());
+                stream.push("]", Colors::brackets());
+                if let Some(offset) = offset {
+                    stream.push(" + ", Colors::expr());
+                    stream.push_owned(decoder::encode_hex(offset as i64), Colors::immediate());
+                }
+            }
+            Self::Helper(name) => stream.push(name, Colors::item()),
+            Self::Named(ref name) => stream.push_owned(name.to_string(), Colors::item()),
+            Self::Nothing => unreachable!("empty operand encountered"),
+        }
+    }
+}
+
+/// Symbol at an address, the address itself if there isn't one.
+fn tokenize_addr(stream: &mut TokenStream, symbols: &Index, addr: usize) {
+    match symbols.get_sym_by_addr(addr) {
+        Some(symbol) => {
+            for token in symbol.name() {
+                stream.push_token(token.clone());
+            }
+        }
+        None => stream.push_owned(format!("{addr:#x}"), Colors::immediate()),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// `r0 += r1`, including moves.
+    Alu,
+    /// `r0 = -r0`.
+    Neg,
+    /// `r0 = (s8)r1`.
+    MovSx,
+    /// `r0 = be16 r0`.
+    Endian,
+    /// `r0 = *(u32 *)(r1 + 0x8)`.
+    Load,
+    /// `*(u32 *)(r1 + 0x8) = r2`.
+    Store,
+    /// `lock *(u64 *)(r1 + 0x0) += r2`.
+    Atomic,
+    /// `r2 = atomic_fetch_add((u64 *)(r1 + 0x0), r2)`.
+    AtomicFetch,
+    /// `r2 = xchg_64(r1 + 0x0, r2)`.
+    Xchg,
+    /// `r0 = cmpxchg_64(r1 + 0x0, r0, r2)`.
+    CmpXchg,
+    /// `r0 = *(u32 *)skb[r1 + 0x8]`, legacy loads from socket buffers.
+    LoadPacket,
+    /// `r1 = 0x1234 ll`.
+    LoadImm64,
+    /// `goto 0x48`.
+    Jump,
+    /// `if r1 > 0x5 goto 0x48`.
+    Branch,
+    /// `call bpf_map_lookup_elem`.
+    Call,
+    /// `callx r1`.
+    CallReg,
+    /// `exit`.
+    Exit,
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    kind: Kind,
+    opcode: u8,
+    /// Operator or name of the operation, like `+=`, `s>` or `be16`.
+    op: &'static str,
+    /// Number of bytes accessed in memory.
+    size: u8,
+    /// Operates on the low 32 bits of it's registers.
+    is_32: bool,
+    dst: u8,
+    src: u8,
+    off: i16,
+    imm: i32,
+    /// The value operated on, stored, compared or called.
+    operand: Operand,
+    /// Target of conditional jumps.
+    target: Operand,
+    width: usize,
+}
+
+/// Fields of a single instruction slot.
+struct Fields {
+    opcode: u8,
+    dst: u8,
+    src: u8,
+    off: i16,
+    imm: i32,
+}
+
+impl Fields {
+    fn new(bytes: [u8; 8], little_endian: bool) -> Self {
+        // the register nibbles are swapped in big endian objects
+        let (dst, src) = match little_endian {
+            true => (bytes[1] & 0xf, bytes[1] >> 4),
+            false => (bytes[1] >> 4, bytes[1] & 0xf),
+        };
+
+        let off = [bytes[2], bytes[3]];
+        let imm = [bytes[4], bytes[5], bytes[6], bytes[7]];
+        let (off, imm) = match little_endian {
+            true => (i16::from_le_bytes(off), i32::from_le_bytes(imm)),
+            false => (i16::from_be_bytes(off), i32::from_be_bytes(imm)),
+        };
+
+        Self {
+            opcode: bytes[0],
+            dst,
+            src,
+            off,
+            imm,
+        }
+    }
+}
+
+impl Instruction {
+    fn new(kind: Kind, op: &'static str, f: &Fields) -> Self {
+        Self {
+            kind,
+            opcode: f.opcode,
+            op,
+            size: 0,
+            is_32: false,
+            dst: f.dst,
+            src: f.src,
+            off: f.off,
+            imm: f.imm,
+            operand: Operand::Nothing,
+            target: Operand::Nothing,
+            width: 8,
+        }
+    }
+
+    /// Register operand of the width this instruction operates on.
+    fn reg(&self, reg: u8) -> Operand {
+        match self.is_32 {
+            true => Operand::Register32(reg),
+            false => Operand::Register(reg),
+        }
+    }
+
+    /// The value operated on, stored, compared or called.
+    pub fn operand(&self) -> &Operand {
+        &self.operand
+    }
+
+    /// Resolve a relocation against a symbol at `addr`, the way the loader would.
+    ///
+    /// `ld_imm64` refers to the map or variable at the symbol offset by it's immediate and calls
+    /// are relative to the symbol, which is usually the start of the section they call into.
+    pub fn relocate(&mut self, addr: usize) {
+        match self.kind {
+            Kind::LoadImm64 if self.src == 0 => {
+                self.operand = Operand::Address(addr.wrapping_add(self.imm as isize as usize));
+            }
+            Kind::Call if self.src == BPF_PSEUDO_CALL => {
+                self.operand = Operand::Branch(addr as i64 + (self.imm as i64 + 1) * 8);
+            }
+            _ => {}
+        }
+    }
+
+    /// Resolve a relocation against a symbol that isn't defined in the object, like kernel
+    /// functions and variables.
+    pub fn relocate_extern(&mut self, name: &str) {
+        if matches!(self.kind, Kind::LoadImm64 | Kind::Call) {
+            self.operand = Operand::Named(Arc::from(name));
+        }
+    }
+
+    /// Address like `r1 + 0x8`.
+    fn tokenize_offset(&self, stream: &mut TokenStream, base: u8) {
+        stream.push(REGISTERS[base as usize], Colors::register());
+        if self.off < 0 {
+            stream.push(" - ", Colors::expr());
+            stream.push_owned(decoder::encode_hex(-(self.off as i64)), Colors::immediate());
+        } else {
+            stream.push(" + ", Colors::expr());
+            stream.push_owned(decoder::encode_hex(self.off as i64), Colors::immediate());
+        }
+    }
+
+    /// Memory operand like `(r1 + 0x8)`.
+    fn tokenize_addr(&self, stream: &mut TokenStream, base: u8) {
+        stream.push("(", Colors::brackets());
+        self.tokenize_offset(stream, base);
+        stream.push(")", Colors::brackets());
+    }
+
+    /// Type of the memory accessed, like `u32` or `s8`.
+    fn mem_type(&self) -> &'static str {
+        const BPF_MEMSX: u8 = 0x80;
+
+        let signed = self.kind == Kind::Load && self.opcode & 0xe0 == BPF_MEMSX;
+        type_name(self.size, signed)
+    }
+
+    /// Dereferenced memory operand like `*(u32 *)(r1 + 0x8)`.
+    fn tokenize_deref(&self, stream: &mut TokenStream, base: u8) {
+        stream.push("*(", Colors::brackets());
+        stream.push(self.mem_type(), Colors::item());
+        stream.push(" *)", Colors::brackets());
+        self.tokenize_addr(stream, base);
+    }
+
+    fn tokenize_assign(&self, stream: &mut TokenStream, symbols: &Index, dst: &Operand) {
+        dst.tokenize(stream, symbols);
+        stream.push(" = ", Colors::expr());
+    }
+}
+
+/// Name of the type of a memory access.
+fn type_name(size: u8, signed: bool) -> &'static str {
+    match (size, signed) {
+        (1, false) => "u8",
+        (2, false) => "u16",
+        (4, false) => "u32",
+        (1, true) => "s8",
+        (2, true) => "s16",
+        (4, true) => "s32",
+        _ => "u64",
+    }
+}
+
+impl ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        let dst = self.reg(self.dst);
+
+        match self.kind {
+            Kind::Alu => {
+                dst.tokenize(stream, symbols);
+                stream.push(" ", Colors::spacing());
+                stream.push(self.op, Colors::expr());
+                stream.push(" ", Colors::spacing());
+                self.operand.tokenize(stream, symbols);
+            }
+            Kind::Neg => {
+                self.tokenize_assign(stream, symbols, &dst);
+                stream.push("-", Colors::expr());
+                dst.tokenize(stream, symbols);
+            }
+            Kind::MovSx => {
+                self.tokenize_assign(stream, symbols, &dst);
+                stream.push(self.op, Colors::item());
+                self.operand.tokenize(stream, symbols);
+            }
+            Kind::Endian => {
+                self.tokenize_assign(stream, symbols, &dst);
+                stream.push(self.op, Colors::opcode());
+                stream.push(" ", Colors::spacing());
+                dst.tokenize(stream, symbols);
+            }
+            Kind::Load => {
+                self.tokenize_assign(stream, symbols, &dst);
+                self.tokenize_deref(stream, self.src);
+            }
+            Kind::Store => {
+                self.tokenize_deref(stream, self.dst);
+                stream.push(" = ", Colors::expr());
+                self.operand.tokenize(stream, symbols);
+            }
+            Kind::Atomic => {
+                stream.push("lock ", Colors::opcode());
+                self.tokenize_deref(stream, self.dst);
+                stream.push(" ", Colors::spacing());
+                stream.push(self.op, Colors::expr());
+                stream.push(" ", Colors::spacing());
+                self.operand.tokenize(stream, symbols);
+            }
+            Kind::AtomicFetch => {
+                self.tokenize_assign(stream, symbols, &self.operand);
+                stream.push(self.op, Colors::opcode());
+                stream.push("((", Colors::brackets());
+                stream.push(self.mem_type(), Colors::item());
+                stream.push(" *)", Colors::brackets());
+                self.tokenize_addr(stream, self.dst);
+                stream.push(", ", Colors::expr());
+                self.operand.tokenize(stream, symbols);
+                stream.push(")", Colors::brackets());
+            }
+            Kind::Xchg | Kind::CmpXchg => {
+                let result = match self.kind {
+                    Kind::CmpXchg => self.reg(0),
+                    _ => self.operand.clone(),
+                };
+
+                self.tokenize_assign(stream, symbols, &result);
+                stream.push(self.op, Colors::opcode());
+                stream.push("(", Colors::brackets());
+                self.tokenize_offset(stream, self.dst);
+                stream.push(", ", Colors::expr());
+                if self.kind == Kind::CmpXchg {
+                    result.tokenize(stream, symbols);
+                    stream.push(", ", Colors::expr());
+                }
+                self.operand.tokenize(stream, symbols);
+                stream.push(")", Colors::brackets());
+            }
+            Kind::LoadPacket => {
+                self.tokenize_assign(stream, symbols, &Operand::Register(0));
+                stream.push("*(", Colors::brackets());
+                stream.push(self.mem_type(), Colors::item());
+                stream.push(" *)", Colors::brackets());
+                stream.push("skb", Colors::register());
+                stream.push("[", Colors::brackets());
+                if self.opcode & 0xe0 == BPF_IND {
+                    stream.push(REGISTERS[self.src as usize], Colors::register());
+                    stream.push(" + ", Colors::expr());
+                }
+                self.operand.tokenize(stream, symbols);
+                stream.push("]", Colors::brackets());
+            }
+            Kind::LoadImm64 => {
+                self.tokenize_assign(stream, symbols, &dst);
+                self.operand.tokenize(stream, symbols);
+                stream.push(" ll", Colors::opcode());
+            }
+            Kind::Jump => {
+                stream.push(self.op, Colors::opcode());
+                stream.push(" ", Colors::spacing());
+                self.operand.tokenize(stream, symbols);
+            }
+            Kind::Branch => {
+                stream.push("if ", Colors::opcode());
+                dst.tokenize(stream, symbols);
+                stream.push(" ", Colors::spacing());
+                stream.push(self.op, Colors::expr());
+                stream.push(" ", Colors::spacing());
+                self.operand.tokenize(stream, symbols);
+                stream.push(" goto ", Colors::opcode());
+                self.target.tokenize(stream, symbols);
+            }
+            Kind::Call => {
+                stream.push("call ", Colors::opcode());
+                self.operand.tokenize(stream, symbols);
+            }
+            Kind::CallReg => {
+                stream.push("callx ", Colors::opcode());
+                dst.tokenize(stream, symbols);
+            }
+            Kind::Exit => stream.push("exit", Colors::opcode()),
+        }
+    }
+}
+
+impl decoder::Decoded for Instruction {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+        for operand in [&mut self.operand, &mut self.target] {
+            if let Operand::Branch(ref mut target) = *operand {
+                *target = (addr as i64).wrapping_add(*target);
+            }
+        }
+    }
+
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
+    }
+
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
+
+    fn regs_read(&self) -> Vec<&'static str> {
+        behavior::registers(self).0
+    }
+
+    fn regs_written(&self) -> Vec<&'static str> {
+        behavior::registers(self).1
+    }
+
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
+}
+
+/// Mode of legacy packet loads that are offset by a register.
+const BPF_IND: u8 = 0x40;
+
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    /// Byte order of the object, nearly all programs are little endian.
+    pub little_endian: bool,
+    /// Type information, used for naming the functions and helpers called.
+    pub btf: Btf,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self {
+            little_endian: true,
+            btf: Btf::default(),
+        }
+    }
+}
+
+impl decoder::Decodable for Decoder {
+    type Instruction = Instruction;
+
+    fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
+        let mut bytes = [0u8; 8];
+        reader.next_n(&mut bytes).ok_or(Error::new(ErrorKind::ExhaustedInput, 8))?;
+
+        let f = Fields::new(bytes, self.little_endian);
+        let mut inst = self.decode_slot(&f).map_err(|err| Error::new(err, 8))?;
+
+        // the upper half of the immediate is stored in a second slot
+        if inst.kind == Kind::LoadImm64 {
+            reader.next_n(&mut bytes).ok_or(Error::new(ErrorKind::ExhaustedInput, 8))?;
+
+            let next = Fields::new(bytes, self.little_endian);
+            if next.opcode != 0 || next.dst != 0 || next.src != 0 || next.off != 0 {
+                return Err(Error::new(ErrorKind::InvalidOperand, 16));
+            }
+
+            inst.operand = ld_imm64(&f, &next).map_err(|err| Error::new(err, 16))?;
+            inst.width = 16;
+        }
+
+        Ok(inst)
+    }
+
+    fn max_width(&self) -> usize {
+        16
+    }
+}
+
+/// What the immediate of `ld_imm64` refers to.
+fn ld_imm64(f: &Fields, next: &Fields) -> Result<Operand, ErrorKind> {
+    let pseudo = |kind, offset| Operand::Pseudo {
+        kind,
+        num: f.imm,
+        offset,
+    };
+
+    let operand = match f.src {
+        0 => Operand::Immediate((next.imm as i64) << 32 | f.imm as u32 as i64),
+        BPF_PSEUDO_MAP_FD => pseudo("map_fd", None),
+        BPF_PSEUDO_MAP_VALUE => pseudo("map_value", Some(next.imm)),
+        BPF_PSEUDO_BTF_ID => pseudo("btf_id", None),
+        BPF_PSEUDO_FUNC => Operand::Branch((f.imm as i64 + 1) * 8),
+        BPF_PSEUDO_MAP_IDX => pseudo("map_idx", None),
+        BPF_PSEUDO_MAP_IDX_VALUE => pseudo("map_idx_value", Some(next.imm)),
+        _ => return Err(ErrorKind::InvalidOperand),
+    };
+
+    Ok(operand)
+}
+
+impl Decoder {
+    fn decode_slot(&self, f: &Fields) -> Result<Instruction, ErrorKind> {
+        if f.dst as usize >= REGISTERS.len() || f.src as usize >= REGISTERS.len() {
+            return Err(ErrorKind::InvalidRegister);
+        }
+
+        match f.opcode & 0b111 {
+            BPF_LD => decode_ld(f),
+            BPF_LDX => decode_ldx(f),
+            BPF_ST | BPF_STX => decode_store(f),
+            BPF_ALU => decode_alu(f, true),
+            BPF_ALU64 => decode_alu(f, false),
+            BPF_JMP => self.decode_jump(f, false),
+            BPF_JMP32 => self.decode_jump(f, true),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Name of a helper, from the type information if it's there.
+    fn helper(&self, id: i32) -> Operand {
+        if let Some(name) = self.btf.helper(id as u32) {
+            return Operand::Named(name.clone());
+        }
+
+        match helpers::HELPERS.get(id as usize) {
+            Some(name) if id > 0 => Operand::Helper(name),
+            _ => Operand::Immediate(id as i64),
+        }
+    }
+
+    fn decode_jump(&self, f: &Fields, is_32: bool) -> Result<Instruction, ErrorKind> {
+        let x = f.opcode & BPF_X != 0;
+
+        let mut inst = match f.opcode & 0xf0 {
+            0x00 if x => return Err(ErrorKind::InvalidOpcode),
+            // the 32-bit variant has a wider offset in it's immediate
+            0x00 if is_32 => {
+                let mut inst = Instruction::new(Kind::Jump, "gotol", f);
+                inst.operand = Operand::Branch((f.imm as i64 + 1) * 8);
+                return Ok(inst);
+            }
+            0x00 => {
+                let mut inst = Instruction::new(Kind::Jump, "goto", f);
+                inst.operand = Operand::Branch((f.off as i64 + 1) * 8);
+                return Ok(inst);
+            }
+            0x80 if is_32 => return Err(ErrorKind::InvalidOpcode),
+            0x80 if x => return Ok(Instruction::new(Kind::CallReg, "callx", f)),
+            0x80 => {
+                let mut inst = Instruction::new(Kind::Call, "call", f);
+                inst.operand = match f.src {
+                    0 => self.helper(f.imm),
+                    BPF_PSEUDO_CALL => Operand::Branch((f.imm as i64 + 1) * 8),
+                    BPF_PSEUDO_KFUNC_CALL => match self.btf.func(f.imm as u32) {
+                        Some(name) => Operand::Named(name.clone()),
+                        None => Operand::Immediate(f.imm as i64),
+                    },
+                    _ => return Err(ErrorKind::InvalidOperand),
+                };
+                return Ok(inst);
+            }
+            0x90 if is_32 || x => return Err(ErrorKind::InvalidOpcode),
+            0x90 => return Ok(Instruction::new(Kind::Exit, "exit", f)),
+            0x10 => Instruction::new(Kind::Branch, "==", f),
+            0x20 => Instruction::new(Kind::Branch, ">", f),
+            0x30 => Instruction::new(Kind::Branch, ">=", f),
+            0x40 => Instruction::new(Kind::Branch, "&", f),
+            0x50 => Instruction::new(Kind::Branch, "!=", f),
+            0x60 => Instruction::new(Kind::Branch, "s>", f),
+            0x70 => Instruction::new(Kind::Branch, "s>=", f),
+            0xa0 => Instruction::new(Kind::Branch, "<", f),
+            0xb0 => Instruction::new(Kind::Branch, "<=", f),
+            0xc0 => Instruction::new(Kind::Branch, "s<", f),
+            0xd0 => Instruction::new(Kind::Branch, "s<=", f),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        };
+
+        inst.is_32 = is_32;
+        inst.operand = match x {
+            true => inst.reg(f.src),
+            false => Operand::Immediate(f.imm as i64),
+        };
+        inst.target = Operand::Branch((f.off as i64 + 1) * 8);
+        Ok(inst)
+    }
+}
+
+/// Arithmetic, moves and byte swaps.
+fn decode_alu(f: &Fields, is_32: bool) -> Result<Instruction, ErrorKind> {
+    let x = f.opcode & BPF_X != 0;
+
+    let mut inst = match (f.opcode & 0xf0, f.off) {
+        (0x00, 0) => Instruction::new(Kind::Alu, "+=", f),
+        (0x10, 0) => Instruction::new(Kind::Alu, "-=", f),
+        (0x20, 0) => Instruction::new(Kind::Alu, "*=", f),
+        (0x30, 0) => Instruction::new(Kind::Alu, "/=", f),
+        (0x30, 1) => Instruction::new(Kind::Alu, "s/=", f),
+        (0x40, 0) => Instruction::new(Kind::Alu, "|=", f),
+        (0x50, 0) => Instruction::new(Kind::Alu, "&=", f),
+        (0x60, 0) => Instruction::new(Kind::Alu, "<<=", f),
+        (0x70, 0) => Instruction::new(Kind::Alu, ">>=", f),
+        (0x80, 0) if !x => Instruction::new(Kind::Neg, "-", f),
+        (0x90, 0) => Instruction::new(Kind::Alu, "%=", f),
+        (0x90, 1) => Instruction::new(Kind::Alu, "s%=", f),
+        (0xa0, 0) => Instruction::new(Kind::Alu, "^=", f),
+        (0xb0, 0) => Instruction::new(Kind::Alu, "=", f),
+        (0xb0, 8) if x => Instruction::new(Kind::MovSx, "(s8)", f),
+        (0xb0, 16) if x => Instruction::new(Kind::MovSx, "(s16)", f),
+        (0xb0, 32) if x && !is_32 => Instruction::new(Kind::MovSx, "(s32)", f),
+        (0xc0, 0) => Instruction::new(Kind::Alu, "s>>=", f),
+        (0xd0, 0) => {
+            // ALU32 converts to the byte order chosen by the source bit, ALU64 always swaps
+            let op = match (is_32, x, f.imm) {
+                (true, false, 16) => "le16",
+                (true, false, 32) => "le32",
+                (true, false, 64) => "le64",
+                (true, true, 16) => "be16",
+                (true, true, 32) => "be32",
+                (true, true, 64) => "be64",
+                (false, false, 16) => "bswap16",
+                (false, false, 32) => "bswap32",
+                (false, false, 64) => "bswap64",
+                _ => return Err(ErrorKind::InvalidOperand),
+            };
+
+            return Ok(Instruction::new(Kind::Endian, op, f));
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    inst.is_32 = is_32;
+    inst.operand = match x {
+        true => inst.reg(f.src),
+        false => Operand::Immediate(f.imm as i64),
+    };
+    Ok(inst)
+}
+
+/// Number of bytes accessed, by the size bits of the opcode.
+fn access_size(opcode: u8) -> u8 {
+    [4, 2, 1, 8][(opcode >> 3 & 0b11) as usize]
+}
+
+/// `ld_imm64` and the legacy loads from socket buffers.
+fn decode_ld(f: &Fields) -> Result<Instruction, ErrorKind> {
+    const BPF_ABS: u8 = 0x20;
+
+    if f.opcode == BPF_LD_IMM64 {
+        return Ok(Instruction::new(Kind::LoadImm64, "", f));
+    }
+
+    let size = access_size(f.opcode);
+    match f.opcode & 0xe0 {
+        BPF_ABS | BPF_IND if size != 8 => {
+            let mut inst = Instruction::new(Kind::LoadPacket, "", f);
+            inst.size = size;
+            inst.operand = Operand::Immediate(f.imm as i64);
+            Ok(inst)
+        }
+        _ => Err(ErrorKind::InvalidOpcode),
+    }
+}
+
+/// Loads from memory, optionally sign extending.
+fn decode_ldx(f: &Fields) -> Result<Instruction, ErrorKind> {
+    const BPF_MEM: u8 = 0x60;
+    const BPF_MEMSX: u8 = 0x80;
+
+    let size = access_size(f.opcode);
+    match f.opcode & 0xe0 {
+        BPF_MEM => {}
+        BPF_MEMSX if size != 8 => {}
+        _ => return Err(ErrorKind::InvalidOpcode),
+    }
+
+    let mut inst = Instruction::new(Kind::Load, "", f);
+    inst.size = size;
+    Ok(inst)
+}
+
+/// Stores of an immediate or register and atomic operations.
+fn decode_store(f: &Fields) -> Result<Instruction, ErrorKind> {
+    const BPF_MEM: u8 = 0x60;
+    const BPF_ATOMIC: u8 = 0xc0;
+    const BPF_FETCH: i32 = 0x01;
+
+    let size = access_size(f.opcode);
+    let is_stx = f.opcode & 0b111 == BPF_STX;
+
+    let mut inst = match f.opcode & 0xe0 {
+        BPF_MEM => {
+            let mut inst = Instruction::new(Kind::Store, "", f);
+            inst.operand = match is_stx {
+                true => Operand::Register(f.src),
+                false => Operand::Immediate(f.imm as i64),
+            };
+            inst
+        }
+        BPF_ATOMIC if is_stx && matches!(size, 4 | 8) => {
+            let is_64 = size == 8;
+            let mut inst = match (f.imm & !BPF_FETCH, f.imm & BPF_FETCH != 0) {
+                (0x00, false) => Instruction::new(Kind::Atomic, "+=", f),
+                (0x40, false) => Instruction::new(Kind::Atomic, "|=", f),
+                (0x50, false) => Instruction::new(Kind::Atomic, "&=", f),
+                (0xa0, false) => Instruction::new(Kind::Atomic, "^=", f),
+                (0x00, true) => Instruction::new(Kind::AtomicFetch, "atomic_fetch_add", f),
+                (0x40, true) => Instruction::new(Kind::AtomicFetch, "atomic_fetch_or", f),
+                (0x50, true) => Instruction::new(Kind::AtomicFetch, "atomic_fetch_and", f),
+                (0xa0, true) => Instruction::new(Kind::AtomicFetch, "atomic_fetch_xor", f),
+                (0xe0, true) if is_64 => Instruction::new(Kind::Xchg, "xchg_64", f),
+                (0xe0, true) => Instruction::new(Kind::Xchg, "xchg32_32", f),
+                (0xf0, true) if is_64 => Instruction::new(Kind::CmpXchg, "cmpxchg_64", f),
+                (0xf0, true) => Instruction::new(Kind::CmpXchg, "cmpxchg32_32", f),
+                _ => return Err(ErrorKind::InvalidOperand),
+            };
+
+            inst.is_32 = !is_64;
+            inst.operand = inst.reg(f.src);
+            inst
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    inst.size = size;
+    Ok(inst)
+}
@@ -0,0 +1,343 @@
+#![cfg(test)]
+
+use decoder::{Decodable, Decoded, ToTokens};
+
+fn test_display(bytes: &[u8], str: &str) {
+    test_display_with(&crate::Decoder::default(), bytes, str);
+}
+
+fn test_display_with(decoder: &crate::Decoder, bytes: &[u8], str: &str) {
+    let mut reader = decoder::Reader::new(bytes);
+    let mut line = tokenizing::TokenStream::new();
+    let symbols = debugvault::Index::default();
+
+    let decoded = match decoder.decode(&mut reader) {
+        Ok(mut inst) => {
+            inst.update_rel_addrs(0x1000, None);
+            inst.tokenize(&mut line, &symbols);
+            line.to_string()
+        }
+        Err(err) => format!("{err:?}"),
+    };
+
+    assert_eq!(decoded, str);
+}
+
+fn decode(bytes: &[u8]) -> crate::Instruction {
+    let mut reader = decoder::Reader::new(bytes);
+    crate::Decoder::default().decode(&mut reader).unwrap()
+}
+
+#[test]
+fn alu() {
+    test_display(&[0xbf, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], "r1 = r2");
+    test_display(
+        &[0x07, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00],
+        "r0 += 0x5",
+    );
+    test_display(
+        &[0x1c, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        "w1 -= w2",
+    );
+    test_display(
+        &[0x87, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        "r1 = -r1",
+    );
+    test_display(
+        &[0xbf, 0x21, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00],
+        "r1 = (s8)r2",
+    );
+    test_display(
+        &[0x3f, 0x21, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00],
+        "r1 s/= r2",
+    );
+    test_display(
+        &[0xdc, 0x01, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00],
+        "r1 = be16 r1",
+    );
+    test_display(
+        &[0xd7, 0x01, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00],
+        "r1 = bswap32 r1",
+    );
+    test_display(
+        &[0xbf, 0xb1, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        "Error { kind: InvalidRegister, size: 8 }",
+    );
+}
+
+#[test]
+fn memory() {
+    test_display(
+        &[0x61, 0x10, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00],
+        "r0 = *(u32 *)(r1 + 0x8)",
+    );
+    test_display(
+        &[0x89, 0xa1, 0xfc, 0xff, 0x00, 0x00, 0x00, 0x00],
+        "r1 = *(s16 *)(r10 - 0x4)",
+    );
+    test_display(
+        &[0x7b, 0x1a, 0xf8, 0xff, 0x00, 0x00, 0x00, 0x00],
+        "*(u64 *)(r10 - 0x8) = r1",
+    );
+    test_display(
+        &[0x62, 0x0a, 0xfc, 0xff, 0x07, 0x00, 0x00, 0x00],
+        "*(u32 *)(r10 - 0x4) = 0x7",
+    );
+    test_display(
+        &[0x30, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00],
+        "r0 = *(u8 *)skb[0xc]",
+    );
+    test_display(
+        &[0x48, 0x10, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00],
+        "r0 = *(u16 *)skb[r1 + 0x4]",
+    );
+}
+
+#[test]
+fn atomics() {
+    test_display(
+        &[0xdb, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        "lock *(u64 *)(r1 + 0x0) += r2",
+    );
+    test_display(
+        &[0xdb, 0x21, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00],
+        "r2 = atomic_fetch_add((u64 *)(r1 + 0x0), r2)",
+    );
+    test_display(
+        &[0xc3, 0x21, 0x00, 0x00, 0xe1, 0x00, 0x00, 0x00],
+        "w2 = xchg32_32(r1 + 0x0, w2)",
+    );
+    test_display(
+        &[0xdb, 0x21, 0x00, 0x00, 0xf1, 0x00, 0x00, 0x00],
+        "r0 = cmpxchg_64(r1 + 0x0, r0, r2)",
+    );
+}
+
+#[test]
+fn ld_imm64() {
+    test_display(
+        &[
+            0x18, 0x01, 0x00, 0x00, 0x89, 0x67, 0x45, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00,
+        ],
+        "r1 = 0x123456789 ll",
+    );
+    test_display(
+        &[
+            0x18, 0x11, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00,
+        ],
+        "r1 = map_fd[0x3] ll",
+    );
+    test_display(
+        &[
+            0x18, 0x21, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00,
+            0x00, 0x00,
+        ],
+        "r1 = map_value[0x3] + 0x10 ll",
+    );
+
+    // missing second slot
+    test_display(
+        &[0x18, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        "Error { kind: ExhaustedInput, size: 8 }",
+    );
+}
+
+#[test]
+fn jumps() {
+    test_display(
+        &[0x25, 0x01, 0x02, 0x00, 0x10, 0x00, 0x00, 0x00],
+        "if r1 > 0x10 goto 0x1018",
+    );
+    test_display(
+        &[0xce, 0x21, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00],
+        "if w1 s< w2 goto 0x1000",
+    );
+    test_display(
+        &[0x05, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00],
+        "goto 0x1020",
+    );
+    test_display(&[0x95, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], "exit");
+}
+
+#[test]
+fn calls() {
+    test_display(
+        &[0x85, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00],
+        "call bpf_map_lookup_elem",
+    );
+    test_display(
+        &[0x85, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00],
+        "call bpf_ktime_get_ns",
+    );
+    test_display(
+        &[0x85, 0x00, 0x00, 0x00, 0xb5, 0x00, 0x00, 0x00],
+        "call bpf_loop",
+    );
+    test_display(
+        &[0x85, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00],
+        "call 0x1000",
+    );
+
+    // bpf to bpf call
+    test_display(
+        &[0x85, 0x10, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00],
+        "call 0x1028",
+    );
+}
+
+#[test]
+fn btf() {
+    let mut types = Vec::new();
+    let mut push = |values: &[u32]| {
+        for value in values {
+            types.extend_from_slice(&value.to_le_bytes());
+        }
+    };
+
+    // [1] FUNC_PROTO, [2] FUNC 'bpf_obj_new_impl', [3] ENUM 'bpf_func_id'
+    push(&[0, 13 << 24, 0]);
+    push(&[1, 12 << 24, 1]);
+    push(&[18, 6 << 24 | 1, 4, 30, 300]);
+
+    let strings = b"\0bpf_obj_new_impl\0bpf_func_id\0BPF_FUNC_new_helper\0";
+    let mut data = Vec::new();
+    data.extend_from_slice(&0xeb9fu16.to_le_bytes());
+    data.extend_from_slice(&[1, 0]);
+    for value in [
+        24,
+        0,
+        types.len() as u32,
+        types.len() as u32,
+        strings.len() as u32,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&types);
+    data.extend_from_slice(strings);
+
+    let decoder = crate::Decoder {
+        little_endian: true,
+        btf: crate::Btf::parse(&data, true).unwrap(),
+    };
+
+    // kfunc call by it's type ID
+    test_display_with(
+        &decoder,
+        &[0x85, 0x20, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00],
+        "call bpf_obj_new_impl",
+    );
+
+    // helper that isn't in the table
+    test_display_with(
+        &decoder,
+        &[0x85, 0x00, 0x00, 0x00, 0x2c, 0x01, 0x00, 0x00],
+        "call bpf_new_helper",
+    );
+}
+
+#[test]
+fn big_endian() {
+    let decoder = crate::Decoder {
+        little_endian: false,
+        ..Default::default()
+    };
+
+    test_display_with(
+        &decoder,
+        &[0x61, 0x01, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00],
+        "r0 = *(u32 *)(r1 + 0x8)",
+    );
+    test_display_with(
+        &decoder,
+        &[0x25, 0x10, 0x00, 0x02, 0x00, 0x00, 0x00, 0x10],
+        "if r1 > 0x10 goto 0x1018",
+    );
+}
+
+#[test]
+fn relocations() {
+    let tokenize = |inst: &crate::Instruction| {
+        let mut line = tokenizing::TokenStream::new();
+        inst.tokenize(&mut line, &debugvault::Index::default());
+        line.to_string()
+    };
+
+    // r1 = 0x0 ll, against a map at 0x2000
+    let mut inst = decode(&[
+        0x18, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00,
+    ]);
+    inst.relocate(0x2000);
+    assert_eq!(tokenize(&inst), "r1 = 0x2000 ll");
+
+    // call -0x1, against a function at 0x3000
+    let mut inst = decode(&[0x85, 0x10, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff]);
+    inst.update_rel_addrs(0x1000, None);
+    inst.relocate(0x3000);
+    assert_eq!(inst.targets(), vec![0x3000]);
+
+    // call -0x1, against a kernel function
+    let mut inst = decode(&[0x85, 0x10, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff]);
+    inst.relocate_extern("bpf_rcu_read_lock");
+    assert_eq!(tokenize(&inst), "call bpf_rcu_read_lock");
+}
+
+#[test]
+fn behavior() {
+    use decoder::{Direction, FlowKind, MemoryAccess};
+
+    // r1 = r2
+    let inst = decode(&[0xbf, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(inst.regs_read(), vec!["r2"]);
+    assert_eq!(inst.regs_written(), vec!["r1"]);
+
+    // w1 -= w2
+    let inst = decode(&[0x1c, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(inst.regs_read(), vec!["r1", "r2"]);
+    assert_eq!(inst.regs_written(), vec!["r1"]);
+
+    // *(u64 *)(r10 - 0x8) = r1
+    let inst = decode(&[0x7b, 0x1a, 0xf8, 0xff, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(inst.regs_read(), vec!["r10", "r1"]);
+    assert_eq!(inst.regs_written(), Vec::<&str>::new());
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Write
+        })
+    );
+
+    // r0 = cmpxchg_64(r1 + 0x0, r0, r2)
+    let inst = decode(&[0xdb, 0x21, 0x00, 0x00, 0xf1, 0x00, 0x00, 0x00]);
+    assert_eq!(inst.regs_read(), vec!["r1", "r0", "r2"]);
+    assert_eq!(inst.regs_written(), vec!["r0"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::ReadWrite
+        })
+    );
+
+    // if r1 > 0x10 goto +2
+    let inst = decode(&[0x25, 0x01, 0x02, 0x00, 0x10, 0x00, 0x00, 0x00]);
+    assert_eq!(inst.flow(), FlowKind::ConditionalJump);
+    assert_eq!(inst.regs_read(), vec!["r1"]);
+
+    // call bpf_map_lookup_elem
+    let inst = decode(&[0x85, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]);
+    assert_eq!(inst.flow(), FlowKind::Call);
+    assert_eq!(
+        inst.regs_written(),
+        vec!["r0", "r1", "r2", "r3", "r4", "r5"]
+    );
+    assert_eq!(inst.targets(), Vec::<usize>::new());
+
+    // exit
+    let inst = decode(&[0x95, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(inst.flow(), FlowKind::Return);
+    assert_eq!(inst.regs_read(), vec!["r0"]);
+}
@@ -0,0 +1,135 @@
+//! Semantic queries on decoded instructions: control flow, registers and memory accesses.
+//!
+//! Wide values take up a pair of registers, only the first of the pair is reported. Arguments and
+//! results of calls are passed through the argument list and `move-result`, so calls don't
+//! clobber any registers. Fields and array elements are reported as memory accesses.
+
+use crate::{Instruction, Method, Operand};
+use decoder::{Direction, FlowKind, MemoryAccess};
+use std::sync::LazyLock;
+
+/// Names of all registers, as a method can have up to 65536 of them.
+static REGISTERS: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
+    (0..=u16::MAX)
+        .map(|reg| &*Box::leak(format!("v{reg}").into_boxed_str()))
+        .collect()
+});
+
+pub(crate) fn flow(inst: &Instruction) -> FlowKind {
+    let mnemomic = inst.mnemomic;
+
+    if mnemomic.starts_with("goto") {
+        FlowKind::Jump
+    } else if mnemomic.starts_with("if-") || mnemomic.ends_with("-switch") {
+        FlowKind::ConditionalJump
+    } else if mnemomic.starts_with("invoke-") {
+        FlowKind::Call
+    } else if mnemomic.starts_with("return") || mnemomic == "throw" {
+        FlowKind::Return
+    } else {
+        FlowKind::Sequential
+    }
+}
+
+pub(crate) fn targets(inst: &Instruction) -> Vec<usize> {
+    // the operand of a switch is it's payload, not where it jumps to
+    if inst.mnemomic.ends_with("-switch") {
+        return Vec::new();
+    }
+
+    inst.operands
+        .iter()
+        .filter_map(|operand| match *operand {
+            Operand::Branch(target) => Some(target as usize),
+            Operand::Method(Method {
+                addr: Some(addr), ..
+            }) => Some(addr),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether the first register of an instruction is it's destination.
+fn writes_first(mnemomic: &str) -> bool {
+    const READ_ONLY: [&str; 13] = [
+        "return",
+        "throw",
+        "monitor-",
+        "if-",
+        "packed-switch",
+        "sparse-switch",
+        "fill-array-data",
+        "check-cast",
+        "aput",
+        "iput",
+        "sput",
+        "invoke-",
+        "filled-new-array",
+    ];
+
+    !READ_ONLY.iter().any(|prefix| mnemomic.starts_with(prefix))
+}
+
+fn push(regs: &mut Vec<&'static str>, reg: u16) {
+    let reg = REGISTERS[reg as usize];
+    if !regs.contains(&reg) {
+        regs.push(reg);
+    }
+}
+
+/// Collects the registers read and written by an instruction.
+pub(crate) fn registers(inst: &Instruction) -> (Vec<&'static str>, Vec<&'static str>) {
+    let mut read = Vec::new();
+    let mut written = Vec::new();
+
+    let mut regs = Vec::new();
+    for operand in inst.operands.iter() {
+        match *operand {
+            Operand::Register(reg) => regs.push(reg),
+            Operand::List(ref list) => regs.extend(list),
+            Operand::Range(first, count) => {
+                regs.extend((0..count).filter_map(|idx| first.checked_add(idx)))
+            }
+            _ => {}
+        }
+    }
+
+    let mut regs = regs.into_iter();
+    if writes_first(inst.mnemomic) {
+        if let Some(dst) = regs.next() {
+            // the destination is also the first source of the two address forms
+            if inst.mnemomic.ends_with("/2addr") {
+                push(&mut read, dst);
+            }
+            push(&mut written, dst);
+        }
+    }
+
+    for reg in regs {
+        push(&mut read, reg);
+    }
+
+    (read, written)
+}
+
+pub(crate) fn memory(inst: &Instruction) -> Option<MemoryAccess> {
+    let mnemomic = inst.mnemomic;
+    let (kind, ty) = mnemomic.split_at_checked(4)?;
+
+    let direction = match kind {
+        "aget" | "iget" | "sget" => Direction::Read,
+        "aput" | "iput" | "sput" => Direction::Write,
+        _ => return None,
+    };
+
+    // references don't have a size in the bytecode
+    let size = match ty {
+        "" => Some(4),
+        "-wide" => Some(8),
+        "-boolean" | "-byte" => Some(1),
+        "-char" | "-short" => Some(2),
+        _ => None,
+    };
+
+    Some(MemoryAccess { size, direction })
+}
@@ -0,0 +1,680 @@
+//! Dalvik bytecode disassembler, covering every opcode of DEX version 039 along with the switch
+//! and array data payloads that are embedded in a method's instructions.
+//!
+//! Instructions are printed in the syntax of `smali`, with strings, types, fields and methods
+//! resolved through the tables of the DEX file they're from. Calls to methods defined in the file
+//! are resolved to their body's address.
+
+mod behavior;
+mod tests;
+
+use debugvault::Index;
+use decoder::{Error, ErrorKind, FlowKind, MemoryAccess, Reader, ToTokens};
+use std::sync::Arc;
+use tokenizing::{ColorScheme, Colors, TokenStream};
+
+/// Method referred to by an instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Method {
+    /// Method reference like `Lcom/foo/Bar;->baz(I)V`.
+    pub name: Arc<str>,
+    /// Address of the method's instructions, if it's defined in the file.
+    pub addr: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Register(u16),
+    /// Registers passed to `invoke-*` and `filled-new-array`.
+    List(Vec<u16>),
+    /// Registers passed to the `/range` variants, by the first register and how many follow.
+    Range(u16, u16),
+    Immediate(i64),
+    /// Target of a branch, relative to the instruction until it's address is known.
+    Branch(i64),
+    /// Key of a switch payload and it's target in code units, relative to the switch.
+    Case(i32, i32),
+    String(Arc<str>),
+    /// Type descriptor like `Ljava/lang/String;`.
+    Type(Arc<str>),
+    /// Field reference like `Lcom/foo/Bar;->baz:I`.
+    Field(Arc<str>),
+    Method(Method),
+    /// Method prototype like `(I)V`.
+    Proto(Arc<str>),
+    /// Index into a table the decoder doesn't have, like `call_site@0x2`.
+    Index(&'static str, u32),
+}
+
+impl ToTokens for Operand {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        match *self {
+            Self::Register(reg) => stream.push_owned(format!("v{reg}"), Colors::register()),
+            Self::List(ref regs) => {
+                stream.push("{", Colors::brackets());
+                for (idx, reg) in regs.iter().enumerate() {
+                    if idx != 0 {
+                        stream.push(", ", Colors::expr());
+                    }
+                    stream.push_owned(format!("v{reg}"), Colors::register());
+                }
+                stream.push("}", Colors::brackets());
+            }
+            Self::Range(first, count) => {
+                stream.push("{", Colors::brackets());
+                if count > 0 {
+                    stream.push_owned(format!("v{first}"), Colors::register());
+                }
+                if count > 1 {
+                    let last = first as u32 + count as u32 - 1;
+                    stream.push(" .. ", Colors::expr());
+                    stream.push_owned(format!("v{last}"), Colors::register());
+                }
+                stream.push("}", Colors::brackets());
+            }
+            Self::Immediate(imm) => {
+                // `encode_hex` can't negate the smallest 64-bit constant
+                let imm = match imm {
+                    i64::MIN => format!("-{:#x}", imm as u64),
+                    _ => decoder::encode_hex(imm),
+                };
+                stream.push_owned(imm, Colors::immediate())
+            }
+            Self::Branch(addr) => tokenize_addr(stream, symbols, addr as usize),
+            Self::Case(key, target) => {
+                stream.push_owned(decoder::encode_hex(key as i64), Colors::immediate());
+                stream.push(": ", Colors::expr());
+                let sign = if target < 0 { "-" } else { "+" };
+                stream.push(sign, Colors::expr());
+                stream.push_owned(format!("{:#x}", target.unsigned_abs()), Colors::immediate());
+            }
+            Self::String(ref string) => stream.push_owned(format!("{string:?}"), Colors::item()),
+            Self::Type(ref name) | Self::Field(ref name) | Self::Proto(ref name) => {
+                stream.push_owned(name.to_string(), Colors::item())
+            }
+            Self::Method(Method {
+                addr: Some(addr), ..
+            }) if symbols.get_sym_by_addr(addr).is_some() => tokenize_addr(stream, symbols, addr),
+            Self::Method(Method { ref name, .. }) => {
+                stream.push_owned(name.to_string(), Colors::item())
+            }
+            Self::Index(table, idx) => {
+                stream.push(table, Colors::item());
+                stream.push("@", Colors::expr());
+                stream.push_owned(format!("{idx:#x}"), Colors::immediate());
+            }
+        }
+    }
+}
+
+/// Symbol at an address, the address itself if there isn't one.
+fn tokenize_addr(stream: &mut TokenStream, symbols: &Index, addr: usize) {
+    match symbols.get_sym_by_addr(addr) {
+        Some(symbol) => {
+            for token in symbol.name() {
+                stream.push_token(token.clone());
+            }
+        }
+        None => stream.push_owned(format!("{addr:#x}"), Colors::immediate()),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    mnemomic: &'static str,
+    operands: Vec<Operand>,
+    width: usize,
+}
+
+impl Instruction {
+    fn new(mnemomic: &'static str, operands: Vec<Operand>) -> Self {
+        Self {
+            mnemomic,
+            operands,
+            width: 0,
+        }
+    }
+
+    pub fn mnemomic(&self) -> &'static str {
+        self.mnemomic
+    }
+
+    pub fn operands(&self) -> &[Operand] {
+        &self.operands
+    }
+}
+
+impl ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        stream.push(self.mnemomic, Colors::opcode());
+
+        for (idx, operand) in self.operands.iter().enumerate() {
+            if idx == 0 {
+                stream.push(" ", Colors::spacing());
+            } else {
+                stream.push(", ", Colors::expr());
+            }
+            operand.tokenize(stream, symbols);
+        }
+    }
+}
+
+impl decoder::Decoded for Instruction {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+        for operand in self.operands.iter_mut() {
+            if let Operand::Branch(ref mut target) = *operand {
+                *target = (addr as i64).wrapping_add(*target);
+            }
+        }
+    }
+
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
+    }
+
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
+
+    fn regs_read(&self) -> Vec<&'static str> {
+        behavior::registers(self).0
+    }
+
+    fn regs_written(&self) -> Vec<&'static str> {
+        behavior::registers(self).1
+    }
+
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
+}
+
+/// Table an index in an instruction refers to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ref {
+    String,
+    Type,
+    Field,
+    Method,
+    Proto,
+    CallSite,
+    MethodHandle,
+}
+
+/// Instruction formats, named like in the Dalvik bytecode specification by the number of code
+/// units, registers and the kind of the last operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    /// `op`
+    F10x,
+    /// `op vA, vB`
+    F12x,
+    /// `op vA, #+B`
+    F11n,
+    /// `op vAA`
+    F11x,
+    /// `op +AA`
+    F10t,
+    /// `op +AAAA`
+    F20t,
+    /// `op vAA, vBBBB`
+    F22x,
+    /// `op vAA, +BBBB`
+    F21t,
+    /// `op vAA, #+BBBB`
+    F21s,
+    /// `op vAA, #+BBBB0000`, shifted by the given amount.
+    F21h(u8),
+    /// `op vAA, kind@BBBB`
+    F21c(Ref),
+    /// `op vAA, vBB, vCC`
+    F23x,
+    /// `op vAA, vBB, #+CC`
+    F22b,
+    /// `op vA, vB, +CCCC`
+    F22t,
+    /// `op vA, vB, #+CCCC`
+    F22s,
+    /// `op vA, vB, kind@CCCC`
+    F22c(Ref),
+    /// `op vAAAA, vBBBB`
+    F32x,
+    /// `op +AAAAAAAA`
+    F30t,
+    /// `op vAA, +BBBBBBBB`
+    F31t,
+    /// `op vAA, #+BBBBBBBB`
+    F31i,
+    /// `op vAA, string@BBBBBBBB`
+    F31c,
+    /// `op {vC, vD, vE, vF, vG}, kind@BBBB`
+    F35c(Ref),
+    /// `op {vCCCC .. vNNNN}, kind@BBBB`
+    F3rc(Ref),
+    /// `op {vC, vD, vE, vF, vG}, meth@BBBB, proto@HHHH`
+    F45cc,
+    /// `op {vCCCC .. vNNNN}, meth@BBBB, proto@HHHH`
+    F4rcc,
+    /// `op vAA, #+BBBBBBBBBBBBBBBB`
+    F51l,
+}
+
+#[rustfmt::skip]
+const UNOPS: [&str; 21] = [
+    "neg-int", "not-int", "neg-long", "not-long", "neg-float", "neg-double", "int-to-long",
+    "int-to-float", "int-to-double", "long-to-int", "long-to-float", "long-to-double",
+    "float-to-int", "float-to-long", "float-to-double", "double-to-int", "double-to-long",
+    "double-to-float", "int-to-byte", "int-to-char", "int-to-short",
+];
+
+#[rustfmt::skip]
+const BINOPS: [&str; 32] = [
+    "add-int", "sub-int", "mul-int", "div-int", "rem-int", "and-int", "or-int", "xor-int",
+    "shl-int", "shr-int", "ushr-int", "add-long", "sub-long", "mul-long", "div-long", "rem-long",
+    "and-long", "or-long", "xor-long", "shl-long", "shr-long", "ushr-long", "add-float",
+    "sub-float", "mul-float", "div-float", "rem-float", "add-double", "sub-double", "mul-double",
+    "div-double", "rem-double",
+];
+
+#[rustfmt::skip]
+const BINOPS_2ADDR: [&str; 32] = [
+    "add-int/2addr", "sub-int/2addr", "mul-int/2addr", "div-int/2addr", "rem-int/2addr",
+    "and-int/2addr", "or-int/2addr", "xor-int/2addr", "shl-int/2addr", "shr-int/2addr",
+    "ushr-int/2addr", "add-long/2addr", "sub-long/2addr", "mul-long/2addr", "div-long/2addr",
+    "rem-long/2addr", "and-long/2addr", "or-long/2addr", "xor-long/2addr", "shl-long/2addr",
+    "shr-long/2addr", "ushr-long/2addr", "add-float/2addr", "sub-float/2addr", "mul-float/2addr",
+    "div-float/2addr", "rem-float/2addr", "add-double/2addr", "sub-double/2addr",
+    "mul-double/2addr", "div-double/2addr", "rem-double/2addr",
+];
+
+#[rustfmt::skip]
+const BINOPS_LIT16: [&str; 8] = [
+    "add-int/lit16", "rsub-int", "mul-int/lit16", "div-int/lit16", "rem-int/lit16",
+    "and-int/lit16", "or-int/lit16", "xor-int/lit16",
+];
+
+#[rustfmt::skip]
+const BINOPS_LIT8: [&str; 11] = [
+    "add-int/lit8", "rsub-int/lit8", "mul-int/lit8", "div-int/lit8", "rem-int/lit8",
+    "and-int/lit8", "or-int/lit8", "xor-int/lit8", "shl-int/lit8", "shr-int/lit8",
+    "ushr-int/lit8",
+];
+
+#[rustfmt::skip]
+const ARRAY_OPS: [&str; 14] = [
+    "aget", "aget-wide", "aget-object", "aget-boolean", "aget-byte", "aget-char", "aget-short",
+    "aput", "aput-wide", "aput-object", "aput-boolean", "aput-byte", "aput-char", "aput-short",
+];
+
+#[rustfmt::skip]
+const INSTANCE_OPS: [&str; 14] = [
+    "iget", "iget-wide", "iget-object", "iget-boolean", "iget-byte", "iget-char", "iget-short",
+    "iput", "iput-wide", "iput-object", "iput-boolean", "iput-byte", "iput-char", "iput-short",
+];
+
+#[rustfmt::skip]
+const STATIC_OPS: [&str; 14] = [
+    "sget", "sget-wide", "sget-object", "sget-boolean", "sget-byte", "sget-char", "sget-short",
+    "sput", "sput-wide", "sput-object", "sput-boolean", "sput-byte", "sput-char", "sput-short",
+];
+
+#[rustfmt::skip]
+const INVOKES: [&str; 5] = [
+    "invoke-virtual", "invoke-super", "invoke-direct", "invoke-static", "invoke-interface",
+];
+
+#[rustfmt::skip]
+const INVOKES_RANGE: [&str; 5] = [
+    "invoke-virtual/range", "invoke-super/range", "invoke-direct/range", "invoke-static/range",
+    "invoke-interface/range",
+];
+
+fn opcode(op: u8) -> Option<(&'static str, Format)> {
+    Some(match op {
+        0x00 => ("nop", Format::F10x),
+        0x01 => ("move", Format::F12x),
+        0x02 => ("move/from16", Format::F22x),
+        0x03 => ("move/16", Format::F32x),
+        0x04 => ("move-wide", Format::F12x),
+        0x05 => ("move-wide/from16", Format::F22x),
+        0x06 => ("move-wide/16", Format::F32x),
+        0x07 => ("move-object", Format::F12x),
+        0x08 => ("move-object/from16", Format::F22x),
+        0x09 => ("move-object/16", Format::F32x),
+        0x0a => ("move-result", Format::F11x),
+        0x0b => ("move-result-wide", Format::F11x),
+        0x0c => ("move-result-object", Format::F11x),
+        0x0d => ("move-exception", Format::F11x),
+        0x0e => ("return-void", Format::F10x),
+        0x0f => ("return", Format::F11x),
+        0x10 => ("return-wide", Format::F11x),
+        0x11 => ("return-object", Format::F11x),
+        0x12 => ("const/4", Format::F11n),
+        0x13 => ("const/16", Format::F21s),
+        0x14 => ("const", Format::F31i),
+        0x15 => ("const/high16", Format::F21h(16)),
+        0x16 => ("const-wide/16", Format::F21s),
+        0x17 => ("const-wide/32", Format::F31i),
+        0x18 => ("const-wide", Format::F51l),
+        0x19 => ("const-wide/high16", Format::F21h(48)),
+        0x1a => ("const-string", Format::F21c(Ref::String)),
+        0x1b => ("const-string/jumbo", Format::F31c),
+        0x1c => ("const-class", Format::F21c(Ref::Type)),
+        0x1d => ("monitor-enter", Format::F11x),
+        0x1e => ("monitor-exit", Format::F11x),
+        0x1f => ("check-cast", Format::F21c(Ref::Type)),
+        0x20 => ("instance-of", Format::F22c(Ref::Type)),
+        0x21 => ("array-length", Format::F12x),
+        0x22 => ("new-instance", Format::F21c(Ref::Type)),
+        0x23 => ("new-array", Format::F22c(Ref::Type)),
+        0x24 => ("filled-new-array", Format::F35c(Ref::Type)),
+        0x25 => ("filled-new-array/range", Format::F3rc(Ref::Type)),
+        0x26 => ("fill-array-data", Format::F31t),
+        0x27 => ("throw", Format::F11x),
+        0x28 => ("goto", Format::F10t),
+        0x29 => ("goto/16", Format::F20t),
+        0x2a => ("goto/32", Format::F30t),
+        0x2b => ("packed-switch", Format::F31t),
+        0x2c => ("sparse-switch", Format::F31t),
+        0x2d => ("cmpl-float", Format::F23x),
+        0x2e => ("cmpg-float", Format::F23x),
+        0x2f => ("cmpl-double", Format::F23x),
+        0x30 => ("cmpg-double", Format::F23x),
+        0x31 => ("cmp-long", Format::F23x),
+        0x32 => ("if-eq", Format::F22t),
+        0x33 => ("if-ne", Format::F22t),
+        0x34 => ("if-lt", Format::F22t),
+        0x35 => ("if-ge", Format::F22t),
+        0x36 => ("if-gt", Format::F22t),
+        0x37 => ("if-le", Format::F22t),
+        0x38 => ("if-eqz", Format::F21t),
+        0x39 => ("if-nez", Format::F21t),
+        0x3a => ("if-ltz", Format::F21t),
+        0x3b => ("if-gez", Format::F21t),
+        0x3c => ("if-gtz", Format::F21t),
+        0x3d => ("if-lez", Format::F21t),
+        0x44..=0x51 => (ARRAY_OPS[op as usize - 0x44], Format::F23x),
+        0x52..=0x5f => (INSTANCE_OPS[op as usize - 0x52], Format::F22c(Ref::Field)),
+        0x60..=0x6d => (STATIC_OPS[op as usize - 0x60], Format::F21c(Ref::Field)),
+        0x6e..=0x72 => (INVOKES[op as usize - 0x6e], Format::F35c(Ref::Method)),
+        0x74..=0x78 => (INVOKES_RANGE[op as usize - 0x74], Format::F3rc(Ref::Method)),
+        0x7b..=0x8f => (UNOPS[op as usize - 0x7b], Format::F12x),
+        0x90..=0xaf => (BINOPS[op as usize - 0x90], Format::F23x),
+        0xb0..=0xcf => (BINOPS_2ADDR[op as usize - 0xb0], Format::F12x),
+        0xd0..=0xd7 => (BINOPS_LIT16[op as usize - 0xd0], Format::F22s),
+        0xd8..=0xe2 => (BINOPS_LIT8[op as usize - 0xd8], Format::F22b),
+        0xfa => ("invoke-polymorphic", Format::F45cc),
+        0xfb => ("invoke-polymorphic/range", Format::F4rcc),
+        0xfc => ("invoke-custom", Format::F35c(Ref::CallSite)),
+        0xfd => ("invoke-custom/range", Format::F3rc(Ref::CallSite)),
+        0xfe => ("const-method-handle", Format::F21c(Ref::MethodHandle)),
+        0xff => ("const-method-type", Format::F21c(Ref::Proto)),
+        _ => return None,
+    })
+}
+
+/// Identifiers of the payloads stored in the high byte of a `nop`.
+const PACKED_SWITCH_PAYLOAD: u8 = 0x01;
+const SPARSE_SWITCH_PAYLOAD: u8 = 0x02;
+const FILL_ARRAY_DATA_PAYLOAD: u8 = 0x03;
+
+/// Dalvik decoder, indices are resolved using the tables of the DEX file the code is from.
+///
+/// Any index outside of the tables is shown as is, so a default decoder can decode code without
+/// a file.
+#[derive(Debug, Default, Clone)]
+pub struct Decoder {
+    pub strings: Vec<Arc<str>>,
+    pub types: Vec<Arc<str>>,
+    pub fields: Vec<Arc<str>>,
+    pub methods: Vec<Method>,
+    pub protos: Vec<Arc<str>>,
+}
+
+impl decoder::Decodable for Decoder {
+    type Instruction = Instruction;
+
+    fn decode(&self, reader: &mut Reader) -> Result<Self::Instruction, Error> {
+        reader.mark();
+
+        match self.decode_inst(reader) {
+            Ok(mut inst) => {
+                inst.width = reader.offset();
+                Ok(inst)
+            }
+            Err(err) => Err(Error::new(err, reader.offset().clamp(2, u8::MAX as usize))),
+        }
+    }
+
+    /// Longest instruction is `const-wide`, payloads are longer but can't be shown in full.
+    fn max_width(&self) -> usize {
+        10
+    }
+}
+
+impl Decoder {
+    fn decode_inst(&self, reader: &mut Reader) -> Result<Instruction, ErrorKind> {
+        let first = unit(reader)?;
+        let op = first as u8;
+        let aa = (first >> 8) as u8;
+        let (a, b) = ((aa & 0xf) as u16, (aa >> 4) as u16);
+        let aa = aa as u16;
+
+        if op == 0x00 && aa != 0 {
+            return payload(reader, aa as u8);
+        }
+
+        let (mnemomic, format) = opcode(op).ok_or(ErrorKind::InvalidOpcode)?;
+        let reg = Operand::Register;
+
+        let operands = match format {
+            Format::F10x => {
+                if aa != 0 {
+                    return Err(ErrorKind::InvalidOperand);
+                }
+                vec![]
+            }
+            Format::F12x => vec![reg(a), reg(b)],
+            Format::F11n => vec![reg(a), Operand::Immediate(((b as i8) << 4 >> 4) as i64)],
+            Format::F11x => vec![reg(aa)],
+            Format::F10t => vec![branch(aa as u8 as i8 as i64)],
+            Format::F20t => vec![branch(unit(reader)? as i16 as i64)],
+            Format::F22x => vec![reg(aa), reg(unit(reader)?)],
+            Format::F21t => vec![reg(aa), branch(unit(reader)? as i16 as i64)],
+            Format::F21s => vec![reg(aa), Operand::Immediate(unit(reader)? as i16 as i64)],
+            Format::F21h(shift) => {
+                let imm = (unit(reader)? as i16 as i64) << shift;
+                vec![reg(aa), Operand::Immediate(imm)]
+            }
+            Format::F21c(kind) => vec![reg(aa), self.reference(kind, unit(reader)? as u32)],
+            Format::F23x => {
+                let bbcc = unit(reader)?;
+                vec![reg(aa), reg(bbcc & 0xff), reg(bbcc >> 8)]
+            }
+            Format::F22b => {
+                let bbcc = unit(reader)?;
+                let imm = (bbcc >> 8) as u8 as i8 as i64;
+                vec![reg(aa), reg(bbcc & 0xff), Operand::Immediate(imm)]
+            }
+            Format::F22t => vec![reg(a), reg(b), branch(unit(reader)? as i16 as i64)],
+            Format::F22s => {
+                let imm = unit(reader)? as i16 as i64;
+                vec![reg(a), reg(b), Operand::Immediate(imm)]
+            }
+            Format::F22c(kind) => {
+                vec![reg(a), reg(b), self.reference(kind, unit(reader)? as u32)]
+            }
+            Format::F32x => {
+                if aa != 0 {
+                    return Err(ErrorKind::InvalidOperand);
+                }
+                vec![reg(unit(reader)?), reg(unit(reader)?)]
+            }
+            Format::F30t => {
+                if aa != 0 {
+                    return Err(ErrorKind::InvalidOperand);
+                }
+                vec![branch(word(reader)? as i32 as i64)]
+            }
+            Format::F31t => vec![reg(aa), branch(word(reader)? as i32 as i64)],
+            Format::F31i => vec![reg(aa), Operand::Immediate(word(reader)? as i32 as i64)],
+            Format::F31c => vec![reg(aa), self.reference(Ref::String, word(reader)?)],
+            Format::F35c(kind) => {
+                let idx = unit(reader)? as u32;
+                let regs = arguments(reader, first)?;
+                vec![regs, self.reference(kind, idx)]
+            }
+            Format::F3rc(kind) => {
+                let idx = unit(reader)? as u32;
+                let first = unit(reader)?;
+                vec![Operand::Range(first, aa), self.reference(kind, idx)]
+            }
+            Format::F45cc => {
+                let idx = unit(reader)? as u32;
+                let regs = arguments(reader, first)?;
+                let proto = unit(reader)? as u32;
+                vec![
+                    regs,
+                    self.reference(Ref::Method, idx),
+                    self.reference(Ref::Proto, proto),
+                ]
+            }
+            Format::F4rcc => {
+                let idx = unit(reader)? as u32;
+                let first = unit(reader)?;
+                let proto = unit(reader)? as u32;
+                vec![
+                    Operand::Range(first, aa),
+                    self.reference(Ref::Method, idx),
+                    self.reference(Ref::Proto, proto),
+                ]
+            }
+            Format::F51l => {
+                let lo = word(reader)? as u64;
+                let hi = word(reader)? as u64;
+                vec![reg(aa), Operand::Immediate((hi << 32 | lo) as i64)]
+            }
+        };
+
+        Ok(Instruction::new(mnemomic, operands))
+    }
+
+    /// Entry of one of the file's tables, or the index itself if it's out of bounds.
+    fn reference(&self, kind: Ref, idx: u32) -> Operand {
+        let get = |table: &[Arc<str>]| table.get(idx as usize).cloned();
+
+        let resolved = match kind {
+            Ref::String => get(&self.strings).map(Operand::String),
+            Ref::Type => get(&self.types).map(Operand::Type),
+            Ref::Field => get(&self.fields).map(Operand::Field),
+            Ref::Method => self.methods.get(idx as usize).cloned().map(Operand::Method),
+            Ref::Proto => get(&self.protos).map(Operand::Proto),
+            Ref::CallSite | Ref::MethodHandle => None,
+        };
+
+        resolved.unwrap_or_else(|| {
+            let table = match kind {
+                Ref::String => "string",
+                Ref::Type => "type",
+                Ref::Field => "field",
+                Ref::Method => "method",
+                Ref::Proto => "proto",
+                Ref::CallSite => "call_site",
+                Ref::MethodHandle => "method_handle",
+            };
+            Operand::Index(table, idx)
+        })
+    }
+}
+
+/// Branch target in code units, converted to bytes.
+fn branch(units: i64) -> Operand {
+    Operand::Branch(units * 2)
+}
+
+/// Reads a code unit.
+fn unit(reader: &mut Reader) -> Result<u16, ErrorKind> {
+    let mut bytes = [0u8; 2];
+    reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+/// Reads two code units, the low one first.
+fn word(reader: &mut Reader) -> Result<u32, ErrorKind> {
+    let lo = unit(reader)? as u32;
+    let hi = unit(reader)? as u32;
+    Ok(hi << 16 | lo)
+}
+
+/// Registers of an `invoke-*` or `filled-new-array`, given the first code unit holding the
+/// register count and the fifth register.
+fn arguments(reader: &mut Reader, first: u16) -> Result<Operand, ErrorKind> {
+    let count = (first >> 12) as usize;
+    if count > 5 {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
+    let regs = unit(reader)?;
+    let regs = [
+        regs & 0xf,
+        (regs >> 4) & 0xf,
+        (regs >> 8) & 0xf,
+        regs >> 12,
+        (first >> 8) & 0xf,
+    ];
+
+    Ok(Operand::List(regs[..count].to_vec()))
+}
+
+/// Decodes the data of a `packed-switch`, `sparse-switch` or `fill-array-data`.
+///
+/// Switch cases are shown by their key and target relative to the switch, as the payload doesn't
+/// know where it's used from. Arrays are shown by the width of their elements and how many there
+/// are.
+fn payload(reader: &mut Reader, ident: u8) -> Result<Instruction, ErrorKind> {
+    match ident {
+        PACKED_SWITCH_PAYLOAD => {
+            let size = unit(reader)?;
+            let first_key = word(reader)? as i32;
+            let mut cases = Vec::with_capacity(size as usize);
+            for idx in 0..size as i32 {
+                let target = word(reader)? as i32;
+                cases.push(Operand::Case(first_key.wrapping_add(idx), target));
+            }
+            Ok(Instruction::new("packed-switch-payload", cases))
+        }
+        SPARSE_SWITCH_PAYLOAD => {
+            let size = unit(reader)? as usize;
+            let keys = (0..size).map(|_| word(reader)).collect::<Result<Vec<_>, _>>()?;
+            let mut cases = Vec::with_capacity(size);
+            for key in keys {
+                cases.push(Operand::Case(key as i32, word(reader)? as i32));
+            }
+            Ok(Instruction::new("sparse-switch-payload", cases))
+        }
+        FILL_ARRAY_DATA_PAYLOAD => {
+            let width = unit(reader)?;
+            let size = word(reader)?;
+            let len =
+                (width as usize).checked_mul(size as usize).ok_or(ErrorKind::InvalidOperand)?;
+
+            // the data is padded to a whole number of code units
+            for _ in 0..len.div_ceil(2) {
+                unit(reader)?;
+            }
+
+            Ok(Instruction::new(
+                "fill-array-data-payload",
+                vec![
+                    Operand::Immediate(width as i64),
+                    Operand::Immediate(size as i64),
+                ],
+            ))
+        }
+        _ => Err(ErrorKind::InvalidOpcode),
+    }
+}
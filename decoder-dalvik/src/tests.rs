@@ -0,0 +1,268 @@
+#![cfg(test)]
+
+use decoder::{Decodable, Decoded, Direction, FlowKind, MemoryAccess, ToTokens};
+use std::sync::Arc;
+
+fn decoder() -> crate::Decoder {
+    crate::Decoder {
+        strings: vec![Arc::from("hello"), Arc::from("tab\t")],
+        types: vec![Arc::from("Ljava/lang/String;"), Arc::from("[I")],
+        fields: vec![Arc::from("Lcom/foo/Bar;->count:I")],
+        methods: vec![
+            crate::Method {
+                name: Arc::from("Ljava/io/PrintStream;->println(Ljava/lang/String;)V"),
+                addr: None,
+            },
+            crate::Method {
+                name: Arc::from("Lcom/foo/Bar;->baz(I)V"),
+                addr: Some(0x2000),
+            },
+        ],
+        protos: vec![Arc::from("(I)V")],
+    }
+}
+
+fn test_display(bytes: &[u8], str: &str) {
+    let mut reader = decoder::Reader::new(bytes);
+    let mut line = tokenizing::TokenStream::new();
+    let symbols = debugvault::Index::default();
+
+    let decoded = match decoder().decode(&mut reader) {
+        Ok(mut inst) => {
+            assert_eq!(inst.width(), bytes.len(), "{str}");
+            inst.update_rel_addrs(0x1000, None);
+            inst.tokenize(&mut line, &symbols);
+            line.to_string()
+        }
+        Err(err) => format!("{err:?}"),
+    };
+
+    assert_eq!(decoded, str);
+}
+
+fn decode(bytes: &[u8]) -> crate::Instruction {
+    let mut reader = decoder::Reader::new(bytes);
+    let mut inst = decoder().decode(&mut reader).unwrap();
+    inst.update_rel_addrs(0x1000, None);
+    inst
+}
+
+#[test]
+fn moves() {
+    test_display(&[0x00, 0x00], "nop");
+    test_display(&[0x01, 0x21], "move v1, v2");
+    test_display(&[0x02, 0x05, 0x00, 0x01], "move/from16 v5, v256");
+    test_display(&[0x03, 0x00, 0x00, 0x01, 0x01, 0x00], "move/16 v256, v1");
+    test_display(&[0x0c, 0x03], "move-result-object v3");
+    test_display(&[0x0d, 0x00], "move-exception v0");
+    test_display(
+        &[0x03, 0x01, 0x00, 0x01, 0x01, 0x00],
+        "Error { kind: InvalidOperand, size: 2 }",
+    );
+}
+
+#[test]
+fn constants() {
+    test_display(&[0x12, 0xf0], "const/4 v0, -0x1");
+    test_display(&[0x12, 0x71], "const/4 v1, 0x7");
+    test_display(&[0x13, 0x02, 0x00, 0x80], "const/16 v2, -0x8000");
+    test_display(
+        &[0x14, 0x00, 0x78, 0x56, 0x34, 0x12],
+        "const v0, 0x12345678",
+    );
+    test_display(&[0x15, 0x00, 0x80, 0x3f], "const/high16 v0, 0x3f800000");
+    test_display(
+        &[0x19, 0x00, 0xf0, 0x3f],
+        "const-wide/high16 v0, 0x3ff0000000000000",
+    );
+    test_display(
+        &[0x18, 0x02, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01],
+        "const-wide v2, 0x102030405060708",
+    );
+    test_display(
+        &[0x14, 0x00, 0x78, 0x56],
+        "Error { kind: ExhaustedInput, size: 4 }",
+    );
+}
+
+#[test]
+fn references() {
+    test_display(&[0x1a, 0x00, 0x00, 0x00], "const-string v0, \"hello\"");
+    test_display(&[0x1a, 0x00, 0x01, 0x00], "const-string v0, \"tab\\t\"");
+    test_display(&[0x1a, 0x00, 0x05, 0x00], "const-string v0, string@0x5");
+    test_display(
+        &[0x1b, 0x01, 0x00, 0x00, 0x00, 0x00],
+        "const-string/jumbo v1, \"hello\"",
+    );
+    test_display(
+        &[0x22, 0x00, 0x00, 0x00],
+        "new-instance v0, Ljava/lang/String;",
+    );
+    test_display(&[0x23, 0x10, 0x01, 0x00], "new-array v0, v1, [I");
+    test_display(
+        &[0x52, 0x10, 0x00, 0x00],
+        "iget v0, v1, Lcom/foo/Bar;->count:I",
+    );
+    test_display(&[0x67, 0x02, 0x00, 0x00], "sput v2, Lcom/foo/Bar;->count:I");
+    test_display(
+        &[0xfe, 0x00, 0x02, 0x00],
+        "const-method-handle v0, method_handle@0x2",
+    );
+    test_display(&[0xff, 0x00, 0x00, 0x00], "const-method-type v0, (I)V");
+}
+
+#[test]
+fn invokes() {
+    test_display(
+        &[0x6e, 0x20, 0x00, 0x00, 0x10, 0x00],
+        "invoke-virtual {v0, v1}, Ljava/io/PrintStream;->println(Ljava/lang/String;)V",
+    );
+    test_display(
+        &[0x71, 0x10, 0x01, 0x00, 0x03, 0x00],
+        "invoke-static {v3}, Lcom/foo/Bar;->baz(I)V",
+    );
+    test_display(
+        &[0x70, 0x54, 0x01, 0x00, 0x10, 0x32],
+        "invoke-direct {v0, v1, v2, v3, v4}, Lcom/foo/Bar;->baz(I)V",
+    );
+    test_display(
+        &[0x74, 0x03, 0x00, 0x00, 0x04, 0x00],
+        "invoke-virtual/range {v4 .. v6}, Ljava/io/PrintStream;->println(Ljava/lang/String;)V",
+    );
+    test_display(
+        &[0x77, 0x00, 0x01, 0x00, 0x00, 0x00],
+        "invoke-static/range {}, Lcom/foo/Bar;->baz(I)V",
+    );
+    test_display(
+        &[0x24, 0x10, 0x01, 0x00, 0x02, 0x00],
+        "filled-new-array {v2}, [I",
+    );
+    test_display(
+        &[0xfc, 0x00, 0x03, 0x00, 0x00, 0x00],
+        "invoke-custom {}, call_site@0x3",
+    );
+    test_display(
+        &[0xfa, 0x20, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00],
+        "invoke-polymorphic {v0, v1}, Ljava/io/PrintStream;->println(Ljava/lang/String;)V, (I)V",
+    );
+    test_display(
+        &[0x6e, 0x60, 0x00, 0x00, 0x00, 0x00],
+        "Error { kind: InvalidOperand, size: 4 }",
+    );
+}
+
+#[test]
+fn branches() {
+    test_display(&[0x28, 0x04], "goto 0x1008");
+    test_display(&[0x28, 0xfe], "goto 0xffc");
+    test_display(&[0x29, 0x00, 0x00, 0x01], "goto/16 0x1200");
+    test_display(&[0x2a, 0x00, 0x00, 0x10, 0x00, 0x00], "goto/32 0x3000");
+    test_display(&[0x38, 0x02, 0x03, 0x00], "if-eqz v2, 0x1006");
+    test_display(&[0x32, 0x10, 0xfd, 0xff], "if-eq v0, v1, 0xffa");
+    test_display(
+        &[0x2b, 0x00, 0x08, 0x00, 0x00, 0x00],
+        "packed-switch v0, 0x1010",
+    );
+}
+
+#[test]
+fn arithmetic() {
+    test_display(&[0x7b, 0x10], "neg-int v0, v1");
+    test_display(&[0x8f, 0x10], "int-to-short v0, v1");
+    test_display(&[0x90, 0x00, 0x01, 0x02], "add-int v0, v1, v2");
+    test_display(&[0xaf, 0x00, 0x01, 0x02], "rem-double v0, v1, v2");
+    test_display(&[0xb0, 0x10], "add-int/2addr v0, v1");
+    test_display(&[0xd1, 0x10, 0x0a, 0x00], "rsub-int v0, v1, 0xa");
+    test_display(&[0xd8, 0x00, 0x01, 0xff], "add-int/lit8 v0, v1, -0x1");
+    test_display(&[0xe2, 0x00, 0x01, 0x04], "ushr-int/lit8 v0, v1, 0x4");
+    test_display(&[0x31, 0x00, 0x02, 0x04], "cmp-long v0, v2, v4");
+    test_display(&[0x3e, 0x00], "Error { kind: InvalidOpcode, size: 2 }");
+    test_display(&[0xe3, 0x00], "Error { kind: InvalidOpcode, size: 2 }");
+}
+
+#[test]
+fn payloads() {
+    test_display(
+        &[
+            0x00, 0x01, 0x02, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0xfc, 0xff,
+            0xff, 0xff,
+        ],
+        "packed-switch-payload 0xa: +0x4, 0xb: -0x4",
+    );
+    test_display(
+        &[
+            0x00, 0x02, 0x02, 0x00, 0xff, 0xff, 0xff, 0xff, 0x00, 0x01, 0x00, 0x00, 0x06, 0x00,
+            0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+        ],
+        "sparse-switch-payload -0x1: +0x6, 0x100: +0x8",
+    );
+    test_display(
+        &[
+            0x00, 0x03, 0x01, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x00,
+        ],
+        "fill-array-data-payload 0x1, 0x3",
+    );
+    test_display(&[0x00, 0x04], "Error { kind: InvalidOpcode, size: 2 }");
+}
+
+#[test]
+fn behavior() {
+    assert_eq!(decode(&[0x28, 0x04]).flow(), FlowKind::Jump);
+    assert_eq!(decode(&[0x28, 0x04]).targets(), vec![0x1008]);
+    assert_eq!(
+        decode(&[0x38, 0x02, 0x03, 0x00]).flow(),
+        FlowKind::ConditionalJump
+    );
+    assert_eq!(decode(&[0x0e, 0x00]).flow(), FlowKind::Return);
+    assert!(!decode(&[0x27, 0x00]).can_fall_through());
+
+    let inst = decode(&[0x71, 0x10, 0x01, 0x00, 0x03, 0x00]);
+    assert_eq!(inst.flow(), FlowKind::Call);
+    assert_eq!(inst.targets(), vec![0x2000]);
+    assert_eq!(inst.regs_read(), vec!["v3"]);
+    assert_eq!(inst.regs_written(), Vec::<&str>::new());
+
+    // methods outside of the file don't have an address
+    let inst = decode(&[0x6e, 0x20, 0x00, 0x00, 0x10, 0x00]);
+    assert_eq!(inst.targets(), Vec::<usize>::new());
+
+    // the payload isn't a target
+    let inst = decode(&[0x2b, 0x00, 0x08, 0x00, 0x00, 0x00]);
+    assert_eq!(inst.flow(), FlowKind::ConditionalJump);
+    assert_eq!(inst.targets(), Vec::<usize>::new());
+
+    let inst = decode(&[0x90, 0x00, 0x01, 0x02]);
+    assert_eq!(inst.regs_read(), vec!["v1", "v2"]);
+    assert_eq!(inst.regs_written(), vec!["v0"]);
+
+    let inst = decode(&[0xb0, 0x10]);
+    assert_eq!(inst.regs_read(), vec!["v0", "v1"]);
+    assert_eq!(inst.regs_written(), vec!["v0"]);
+
+    let inst = decode(&[0x74, 0x03, 0x00, 0x00, 0x04, 0x00]);
+    assert_eq!(inst.regs_read(), vec!["v4", "v5", "v6"]);
+
+    assert_eq!(
+        decode(&[0x52, 0x10, 0x00, 0x00]).memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::Read
+        })
+    );
+    assert_eq!(
+        decode(&[0x4c, 0x00, 0x01, 0x02]).memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Write
+        })
+    );
+    assert_eq!(
+        decode(&[0x54, 0x10, 0x00, 0x00]).memory().unwrap().size,
+        None
+    );
+    assert_eq!(decode(&[0x90, 0x00, 0x01, 0x02]).memory(), None);
+
+    let inst = decode(&[0x4c, 0x00, 0x01, 0x02]);
+    assert_eq!(inst.regs_read(), vec!["v0", "v1", "v2"]);
+    assert_eq!(inst.regs_written(), Vec::<&str>::new());
+}
@@ -0,0 +1,173 @@
+//! Semantic queries on decoded instructions: control flow, registers and memory accesses.
+//!
+//! Writes to and reads from `zero` aren't reported. Control and status registers, the timers and
+//! the floating point control registers other than those named by an operand aren't tracked.
+
+use crate::{
+    Instruction, Operand, FCC_REGISTERS, FCSR_REGISTERS, FLOAT_REGISTERS, LASX_REGISTERS, RA,
+    REGISTERS, VECTOR_REGISTERS,
+};
+use decoder::{Direction, FlowKind, MemoryAccess};
+
+/// Number of bytes and direction of the memory access.
+fn access(mnemomic: &str) -> Option<(Option<usize>, Direction)> {
+    let access = match mnemomic {
+        "ld.b" | "ld.bu" | "ldx.b" | "ldx.bu" | "ldgt.b" | "ldle.b" | "vldrepl.b"
+        | "xvldrepl.b" => (1, Direction::Read),
+        "ld.h" | "ld.hu" | "ldx.h" | "ldx.hu" | "ldgt.h" | "ldle.h" | "vldrepl.h"
+        | "xvldrepl.h" => (2, Direction::Read),
+        "ld.w" | "ld.wu" | "ldx.w" | "ldx.wu" | "ldgt.w" | "ldle.w" | "ll.w" | "ldptr.w"
+        | "fld.s" | "fldx.s" | "fldgt.s" | "fldle.s" | "vldrepl.w" | "xvldrepl.w" => {
+            (4, Direction::Read)
+        }
+        "ld.d" | "ldx.d" | "ldgt.d" | "ldle.d" | "ll.d" | "ldptr.d" | "fld.d" | "fldx.d"
+        | "fldgt.d" | "fldle.d" | "vldrepl.d" | "xvldrepl.d" => (8, Direction::Read),
+        "vld" | "vldx" => (16, Direction::Read),
+        "xvld" | "xvldx" => (32, Direction::Read),
+        "st.b" | "stx.b" | "stgt.b" | "stle.b" | "vstelm.b" | "xvstelm.b" => (1, Direction::Write),
+        "st.h" | "stx.h" | "stgt.h" | "stle.h" | "vstelm.h" | "xvstelm.h" => (2, Direction::Write),
+        "st.w" | "stx.w" | "stgt.w" | "stle.w" | "sc.w" | "stptr.w" | "fst.s" | "fstx.s"
+        | "fstgt.s" | "fstle.s" | "vstelm.w" | "xvstelm.w" => (4, Direction::Write),
+        "st.d" | "stx.d" | "stgt.d" | "stle.d" | "sc.d" | "stptr.d" | "fst.d" | "fstx.d"
+        | "fstgt.d" | "fstle.d" | "vstelm.d" | "xvstelm.d" => (8, Direction::Write),
+        "vst" | "vstx" => (16, Direction::Write),
+        "xvst" | "xvstx" => (32, Direction::Write),
+        // atomics load the old value and store the new one
+        _ if mnemomic.starts_with("am") && mnemomic.ends_with(['w', 'u']) => {
+            let size = if mnemomic.ends_with(".w") || mnemomic.ends_with(".wu") {
+                4
+            } else {
+                8
+            };
+            (size, Direction::ReadWrite)
+        }
+        _ if mnemomic.starts_with("am") && mnemomic.ends_with('d') => (8, Direction::ReadWrite),
+        _ => return None,
+    };
+
+    Some((Some(access.0), access.1))
+}
+
+fn is_conditional(mnemomic: &str) -> bool {
+    matches!(
+        mnemomic,
+        "beqz" | "bnez" | "bceqz" | "bcnez" | "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu"
+    )
+}
+
+pub(crate) fn flow(inst: &Instruction) -> FlowKind {
+    match (inst.mnemomic, inst.operands()) {
+        ("b", _) => FlowKind::Jump,
+        ("bl", _) => FlowKind::Call,
+        ("jirl", [Operand::Register(0), Operand::Register(RA), Operand::Immediate(0)]) => {
+            FlowKind::Return
+        }
+        ("jirl", [Operand::Register(0), ..]) => FlowKind::Jump,
+        ("jirl", _) => FlowKind::Call,
+        ("syscall" | "break" | "dbcl", _) => FlowKind::Interrupt,
+        ("ertn", _) => FlowKind::Return,
+        ("idle", _) => FlowKind::Halt,
+        (mnemomic, _) if is_conditional(mnemomic) => FlowKind::ConditionalJump,
+        _ => FlowKind::Sequential,
+    }
+}
+
+pub(crate) fn targets(inst: &Instruction) -> Vec<usize> {
+    inst.operands()
+        .iter()
+        .filter_map(|operand| match *operand {
+            Operand::Branch(target) => Some(target as usize),
+            _ => None,
+        })
+        .collect()
+}
+
+fn push(regs: &mut Vec<&'static str>, reg: &'static str) {
+    if reg != REGISTERS[0] && !regs.contains(&reg) {
+        regs.push(reg);
+    }
+}
+
+/// Collects the registers read and written by an instruction.
+pub(crate) fn registers(inst: &Instruction) -> (Vec<&'static str>, Vec<&'static str>) {
+    let mut read = Vec::new();
+    let mut written = Vec::new();
+
+    let mnemomic = inst.mnemomic;
+    let direction = access(mnemomic).map(|(_, direction)| direction);
+
+    // the first operand isn't a destination for stores, branches and hints
+    let reads_only = direction == Some(Direction::Write) && !mnemomic.starts_with("sc.")
+        || is_conditional(mnemomic)
+        || mnemomic.starts_with("asrt")
+        || mnemomic.starts_with("iocsrwr")
+        || matches!(
+            mnemomic,
+            "preld" | "preldx" | "cacop" | "invtlb" | "ldpte" | "dbar" | "ibar"
+        );
+
+    // instructions that keep part of their destination, or accumulate into it
+    let reads_and_writes = matches!(
+        mnemomic,
+        "bstrins.w"
+            | "bstrins.d"
+            | "lu32i.d"
+            | "csrwr"
+            | "csrxchg"
+            | "movgr2frh.w"
+            | "sc.w"
+            | "sc.d"
+    ) || {
+        // LASX instructions are named like the LSX ones with an `x` in front
+        let name = mnemomic.strip_prefix('x').unwrap_or(mnemomic);
+        name.starts_with("vinsgr2vr")
+            || name.starts_with("vextrins")
+            || name.starts_with("vbitseli")
+            || name.starts_with("vfrstp")
+            || name.starts_with("vmadd")
+            || name.starts_with("vmsub")
+            || name.starts_with("vshuf.")
+            || name.starts_with("vpermi.w")
+            || name.starts_with("vpermi.q")
+            || name.starts_with("vinsve0")
+            || name.starts_with("vsrlni")
+            || name.starts_with("vsrlrni")
+            || name.starts_with("vssrlni")
+            || name.starts_with("vssrlrni")
+            || name.starts_with("vsrani")
+            || name.starts_with("vsrarni")
+            || name.starts_with("vssrani")
+            || name.starts_with("vssrarni")
+    };
+
+    for (idx, operand) in inst.operands().iter().enumerate() {
+        let reg = match *operand {
+            Operand::Register(reg) => REGISTERS[reg as usize],
+            Operand::Float(reg) => FLOAT_REGISTERS[reg as usize],
+            Operand::Vector(reg) => VECTOR_REGISTERS[reg as usize],
+            Operand::Lasx(reg) => LASX_REGISTERS[reg as usize],
+            Operand::Fcc(cc) => FCC_REGISTERS[cc as usize],
+            Operand::Fcsr(reg) => FCSR_REGISTERS[reg as usize],
+            _ => continue,
+        };
+
+        if idx == 0 && !reads_only {
+            push(&mut written, reg);
+            if reads_and_writes {
+                push(&mut read, reg);
+            }
+        } else {
+            push(&mut read, reg);
+        }
+    }
+
+    if mnemomic == "bl" {
+        push(&mut written, REGISTERS[RA as usize]);
+    }
+
+    (read, written)
+}
+
+pub(crate) fn memory(inst: &Instruction) -> Option<MemoryAccess> {
+    access(inst.mnemomic).map(|(size, direction)| MemoryAccess { size, direction })
+}
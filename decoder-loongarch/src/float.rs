@@ -0,0 +1,217 @@
+//! Floating point arithmetic, conversions and moves, together with the fused multiply-add,
+//! comparison and select instructions that share their encoding with the vector extensions.
+
+use crate::{fcc, fpr, gpr, vr, Fields, Instruction, Operand};
+use decoder::ErrorKind;
+
+/// Comparisons indexed by the `cond` field, in the order of the scalar, LSX and LASX single
+/// and double precision variants.
+#[rustfmt::skip]
+const CONDITIONS: [Option<[&str; 6]>; 26] = {
+    macro_rules! cond {
+        ($cond:literal) => {
+            Some([
+                concat!("fcmp.", $cond, ".s"),
+                concat!("fcmp.", $cond, ".d"),
+                concat!("vfcmp.", $cond, ".s"),
+                concat!("vfcmp.", $cond, ".d"),
+                concat!("xvfcmp.", $cond, ".s"),
+                concat!("xvfcmp.", $cond, ".d"),
+            ])
+        };
+    }
+
+    [
+        cond!("caf"), cond!("saf"), cond!("clt"), cond!("slt"),
+        cond!("ceq"), cond!("seq"), cond!("cle"), cond!("sle"),
+        cond!("cun"), cond!("sun"), cond!("cult"), cond!("sult"),
+        cond!("cueq"), cond!("sueq"), cond!("cule"), cond!("sule"),
+        cond!("cne"), cond!("sne"), None, None,
+        cond!("cor"), cond!("sor"), None, None,
+        cond!("cune"), cond!("sune"),
+    ]
+};
+
+/// Single and double precision arithmetic of opcodes `0x0100` to `0x011f`.
+pub(crate) fn decode(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = f.word;
+    let (fd, fj, fk) = (fpr(f.rd), fpr(f.rj), fpr(f.rk));
+
+    let binary = match word & 0xffff_8000 {
+        0x0100_8000 => Some("fadd.s"),
+        0x0101_0000 => Some("fadd.d"),
+        0x0102_8000 => Some("fsub.s"),
+        0x0103_0000 => Some("fsub.d"),
+        0x0104_8000 => Some("fmul.s"),
+        0x0105_0000 => Some("fmul.d"),
+        0x0106_8000 => Some("fdiv.s"),
+        0x0107_0000 => Some("fdiv.d"),
+        0x0108_8000 => Some("fmax.s"),
+        0x0109_0000 => Some("fmax.d"),
+        0x010a_8000 => Some("fmin.s"),
+        0x010b_0000 => Some("fmin.d"),
+        0x010c_8000 => Some("fmaxa.s"),
+        0x010d_0000 => Some("fmaxa.d"),
+        0x010e_8000 => Some("fmina.s"),
+        0x010f_0000 => Some("fmina.d"),
+        0x0110_8000 => Some("fscaleb.s"),
+        0x0111_0000 => Some("fscaleb.d"),
+        0x0112_8000 => Some("fcopysign.s"),
+        0x0113_0000 => Some("fcopysign.d"),
+        _ => None,
+    };
+
+    if let Some(mnemomic) = binary {
+        return Ok(new(mnemomic, &[fd, fj, fk]));
+    }
+
+    let inst = match word & 0xffff_fc00 {
+        0x0114_0400 => new("fabs.s", &[fd, fj]),
+        0x0114_0800 => new("fabs.d", &[fd, fj]),
+        0x0114_1400 => new("fneg.s", &[fd, fj]),
+        0x0114_1800 => new("fneg.d", &[fd, fj]),
+        0x0114_2400 => new("flogb.s", &[fd, fj]),
+        0x0114_2800 => new("flogb.d", &[fd, fj]),
+        0x0114_3400 => new("fclass.s", &[fd, fj]),
+        0x0114_3800 => new("fclass.d", &[fd, fj]),
+        0x0114_4400 => new("fsqrt.s", &[fd, fj]),
+        0x0114_4800 => new("fsqrt.d", &[fd, fj]),
+        0x0114_5400 => new("frecip.s", &[fd, fj]),
+        0x0114_5800 => new("frecip.d", &[fd, fj]),
+        0x0114_6400 => new("frsqrt.s", &[fd, fj]),
+        0x0114_6800 => new("frsqrt.d", &[fd, fj]),
+        0x0114_9400 => new("fmov.s", &[fd, fj]),
+        0x0114_9800 => new("fmov.d", &[fd, fj]),
+        0x0114_a400 => new("movgr2fr.w", &[fd, gpr(f.rj)]),
+        0x0114_a800 => new("movgr2fr.d", &[fd, gpr(f.rj)]),
+        0x0114_ac00 => new("movgr2frh.w", &[fd, gpr(f.rj)]),
+        0x0114_b400 => new("movfr2gr.s", &[gpr(f.rd), fj]),
+        0x0114_b800 => new("movfr2gr.d", &[gpr(f.rd), fj]),
+        0x0114_bc00 => new("movfrh2gr.s", &[gpr(f.rd), fj]),
+        0x0114_c000 if f.rd < 4 => new("movgr2fcsr", &[Operand::Fcsr(f.rd as u8), gpr(f.rj)]),
+        0x0114_c800 if f.rj < 4 => new("movfcsr2gr", &[gpr(f.rd), Operand::Fcsr(f.rj as u8)]),
+        0x0114_d000 if f.rd < 8 => new("movfr2cf", &[fcc(f.rd), fj]),
+        0x0114_d400 if f.rj < 8 => new("movcf2fr", &[fd, fcc(f.rj)]),
+        0x0114_d800 if f.rd < 8 => new("movgr2cf", &[fcc(f.rd), gpr(f.rj)]),
+        0x0114_dc00 if f.rj < 8 => new("movcf2gr", &[gpr(f.rd), fcc(f.rj)]),
+        0x0119_1800 => new("fcvt.s.d", &[fd, fj]),
+        0x0119_2400 => new("fcvt.d.s", &[fd, fj]),
+        0x011a_0400 => new("ftintrm.w.s", &[fd, fj]),
+        0x011a_0800 => new("ftintrm.w.d", &[fd, fj]),
+        0x011a_2400 => new("ftintrm.l.s", &[fd, fj]),
+        0x011a_2800 => new("ftintrm.l.d", &[fd, fj]),
+        0x011a_4400 => new("ftintrp.w.s", &[fd, fj]),
+        0x011a_4800 => new("ftintrp.w.d", &[fd, fj]),
+        0x011a_6400 => new("ftintrp.l.s", &[fd, fj]),
+        0x011a_6800 => new("ftintrp.l.d", &[fd, fj]),
+        0x011a_8400 => new("ftintrz.w.s", &[fd, fj]),
+        0x011a_8800 => new("ftintrz.w.d", &[fd, fj]),
+        0x011a_a400 => new("ftintrz.l.s", &[fd, fj]),
+        0x011a_a800 => new("ftintrz.l.d", &[fd, fj]),
+        0x011a_c400 => new("ftintrne.w.s", &[fd, fj]),
+        0x011a_c800 => new("ftintrne.w.d", &[fd, fj]),
+        0x011a_e400 => new("ftintrne.l.s", &[fd, fj]),
+        0x011a_e800 => new("ftintrne.l.d", &[fd, fj]),
+        0x011b_0400 => new("ftint.w.s", &[fd, fj]),
+        0x011b_0800 => new("ftint.w.d", &[fd, fj]),
+        0x011b_2400 => new("ftint.l.s", &[fd, fj]),
+        0x011b_2800 => new("ftint.l.d", &[fd, fj]),
+        0x011d_1000 => new("ffint.s.w", &[fd, fj]),
+        0x011d_1800 => new("ffint.s.l", &[fd, fj]),
+        0x011d_2000 => new("ffint.d.w", &[fd, fj]),
+        0x011d_2800 => new("ffint.d.l", &[fd, fj]),
+        0x011e_4400 => new("frint.s", &[fd, fj]),
+        0x011e_4800 => new("frint.d", &[fd, fj]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Fused multiply-add, comparisons and selects, with four operands.
+pub(crate) fn decode_fused(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = f.word;
+
+    let fused = match word >> 20 {
+        0x081 => Some(("fmadd.s", None)),
+        0x082 => Some(("fmadd.d", None)),
+        0x085 => Some(("fmsub.s", None)),
+        0x086 => Some(("fmsub.d", None)),
+        0x089 => Some(("fnmadd.s", None)),
+        0x08a => Some(("fnmadd.d", None)),
+        0x08d => Some(("fnmsub.s", None)),
+        0x08e => Some(("fnmsub.d", None)),
+        0x091 => Some(("vfmadd.s", Some(false))),
+        0x092 => Some(("vfmadd.d", Some(false))),
+        0x095 => Some(("vfmsub.s", Some(false))),
+        0x096 => Some(("vfmsub.d", Some(false))),
+        0x099 => Some(("vfnmadd.s", Some(false))),
+        0x09a => Some(("vfnmadd.d", Some(false))),
+        0x09d => Some(("vfnmsub.s", Some(false))),
+        0x09e => Some(("vfnmsub.d", Some(false))),
+        0x0a1 => Some(("xvfmadd.s", Some(true))),
+        0x0a2 => Some(("xvfmadd.d", Some(true))),
+        0x0a5 => Some(("xvfmsub.s", Some(true))),
+        0x0a6 => Some(("xvfmsub.d", Some(true))),
+        0x0a9 => Some(("xvfnmadd.s", Some(true))),
+        0x0aa => Some(("xvfnmadd.d", Some(true))),
+        0x0ad => Some(("xvfnmsub.s", Some(true))),
+        0x0ae => Some(("xvfnmsub.d", Some(true))),
+        _ => None,
+    };
+
+    if let Some((mnemomic, vector)) = fused {
+        let reg = |num| match vector {
+            Some(lasx) => vr(num, lasx),
+            None => fpr(num),
+        };
+
+        return Ok(new(mnemomic, &[reg(f.rd), reg(f.rj), reg(f.rk), reg(f.ra)]));
+    }
+
+    let inst = match word >> 20 {
+        op @ (0x0c1 | 0x0c2 | 0x0c5 | 0x0c6 | 0x0c9 | 0x0ca) => {
+            let cond = CONDITIONS.get(f.ra as usize).copied().flatten();
+            let Some(names) = cond else {
+                return Err(ErrorKind::InvalidOpcode);
+            };
+
+            // scalar comparisons set a condition flag
+            let variant = (op >> 2 & 0b11) * 2 + (op & 1 ^ 1);
+            match variant {
+                0 | 1 if word & 0b11000 != 0 => return Err(ErrorKind::InvalidOpcode),
+                0 | 1 => new(names[variant as usize], &[fcc(f.rd), fpr(f.rj), fpr(f.rk)]),
+                _ => {
+                    let lasx = variant >= 4;
+                    let (vd, vj, vk) = (vr(f.rd, lasx), vr(f.rj, lasx), vr(f.rk, lasx));
+                    new(names[variant as usize], &[vd, vj, vk])
+                }
+            }
+        }
+        0x0d0 if word >> 18 & 0b11 == 0 && f.ra < 8 => {
+            new("fsel", &[fpr(f.rd), fpr(f.rj), fpr(f.rk), fcc(f.ra)])
+        }
+        op @ (0x0d1 | 0x0d2 | 0x0d5 | 0x0d6) => {
+            let lasx = op & 0b11 == 0b10;
+            let mnemomic = match op {
+                0x0d1 => "vbitsel.v",
+                0x0d2 => "xvbitsel.v",
+                0x0d5 => "vshuf.b",
+                _ => "xvshuf.b",
+            };
+
+            let (vd, vj, vk, va) = (
+                vr(f.rd, lasx),
+                vr(f.rj, lasx),
+                vr(f.rk, lasx),
+                vr(f.ra, lasx),
+            );
+            new(mnemomic, &[vd, vj, vk, va])
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
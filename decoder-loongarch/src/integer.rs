@@ -0,0 +1,191 @@
+//! Integer arithmetic, logical, shift and bit manipulation instructions, and the instructions
+//! building up constants and addresses from large immediates.
+
+use crate::{gpr, imm, Fields, Instruction};
+use decoder::ErrorKind;
+
+/// Instructions with two registers, in the lowest part of the opcode space.
+fn two_reg(word: u32) -> Option<&'static str> {
+    let mnemomic = match word & 0xffff_fc00 {
+        0x0000_1000 => "clo.w",
+        0x0000_1400 => "clz.w",
+        0x0000_1800 => "cto.w",
+        0x0000_1c00 => "ctz.w",
+        0x0000_2000 => "clo.d",
+        0x0000_2400 => "clz.d",
+        0x0000_2800 => "cto.d",
+        0x0000_2c00 => "ctz.d",
+        0x0000_3000 => "revb.2h",
+        0x0000_3400 => "revb.4h",
+        0x0000_3800 => "revb.2w",
+        0x0000_3c00 => "revb.d",
+        0x0000_4000 => "revh.2w",
+        0x0000_4400 => "revh.d",
+        0x0000_4800 => "bitrev.4b",
+        0x0000_4c00 => "bitrev.8b",
+        0x0000_5000 => "bitrev.w",
+        0x0000_5400 => "bitrev.d",
+        0x0000_5800 => "ext.w.h",
+        0x0000_5c00 => "ext.w.b",
+        0x0000_6000 => "rdtimel.w",
+        0x0000_6400 => "rdtimeh.w",
+        0x0000_6800 => "rdtime.d",
+        0x0000_6c00 => "cpucfg",
+        _ => return None,
+    };
+
+    Some(mnemomic)
+}
+
+/// Instructions with three registers.
+fn three_reg(word: u32) -> Option<&'static str> {
+    let mnemomic = match word & 0xffff_8000 {
+        0x0010_0000 => "add.w",
+        0x0010_8000 => "add.d",
+        0x0011_0000 => "sub.w",
+        0x0011_8000 => "sub.d",
+        0x0012_0000 => "slt",
+        0x0012_8000 => "sltu",
+        0x0013_0000 => "maskeqz",
+        0x0013_8000 => "masknez",
+        0x0014_0000 => "nor",
+        0x0014_8000 => "and",
+        0x0015_0000 => "or",
+        0x0015_8000 => "xor",
+        0x0016_0000 => "orn",
+        0x0016_8000 => "andn",
+        0x0017_0000 => "sll.w",
+        0x0017_8000 => "srl.w",
+        0x0018_0000 => "sra.w",
+        0x0018_8000 => "sll.d",
+        0x0019_0000 => "srl.d",
+        0x0019_8000 => "sra.d",
+        0x001b_0000 => "rotr.w",
+        0x001b_8000 => "rotr.d",
+        0x001c_0000 => "mul.w",
+        0x001c_8000 => "mulh.w",
+        0x001d_0000 => "mulh.wu",
+        0x001d_8000 => "mul.d",
+        0x001e_0000 => "mulh.d",
+        0x001e_8000 => "mulh.du",
+        0x001f_0000 => "mulw.d.w",
+        0x001f_8000 => "mulw.d.wu",
+        0x0020_0000 => "div.w",
+        0x0020_8000 => "mod.w",
+        0x0021_0000 => "div.wu",
+        0x0021_8000 => "mod.wu",
+        0x0022_0000 => "div.d",
+        0x0022_8000 => "mod.d",
+        0x0023_0000 => "div.du",
+        0x0023_8000 => "mod.du",
+        0x0024_0000 => "crc.w.b.w",
+        0x0024_8000 => "crc.w.h.w",
+        0x0025_0000 => "crc.w.w.w",
+        0x0025_8000 => "crc.w.d.w",
+        0x0026_0000 => "crcc.w.b.w",
+        0x0026_8000 => "crcc.w.h.w",
+        0x0027_0000 => "crcc.w.w.w",
+        0x0027_8000 => "crcc.w.d.w",
+        _ => return None,
+    };
+
+    Some(mnemomic)
+}
+
+/// Opcodes starting with six zero bits, other than the floating point instructions.
+pub(crate) fn decode(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = f.word;
+    let (rd, rj, rk) = (gpr(f.rd), gpr(f.rj), gpr(f.rk));
+
+    if let Some(mnemomic) = two_reg(word) {
+        return Ok(new(mnemomic, &[rd, rj]));
+    }
+
+    if let Some(mnemomic) = three_reg(word) {
+        return Ok(new(mnemomic, &[rd, rj, rk]));
+    }
+
+    // the shift amount of `alsl` is stored one less than it's value
+    let sa2 = imm((word >> 15 & 0b11) + 1);
+
+    let inst = match word & 0xffff_8000 {
+        0x0001_0000 if f.rd == 0 => new("asrtle.d", &[rj, rk]),
+        0x0001_8000 if f.rd == 0 => new("asrtgt.d", &[rj, rk]),
+        0x002a_0000 => new("break", &[f.uimm(0, 15)]),
+        0x002a_8000 => new("dbcl", &[f.uimm(0, 15)]),
+        0x002b_0000 => new("syscall", &[f.uimm(0, 15)]),
+        0x0040_8000 => new("slli.w", &[rd, rj, f.uimm(10, 5)]),
+        0x0044_8000 => new("srli.w", &[rd, rj, f.uimm(10, 5)]),
+        0x0048_8000 => new("srai.w", &[rd, rj, f.uimm(10, 5)]),
+        0x004c_8000 => new("rotri.w", &[rd, rj, f.uimm(10, 5)]),
+        _ => match word & 0xffff_0000 {
+            0x0041_0000 => new("slli.d", &[rd, rj, f.uimm(10, 6)]),
+            0x0045_0000 => new("srli.d", &[rd, rj, f.uimm(10, 6)]),
+            0x0049_0000 => new("srai.d", &[rd, rj, f.uimm(10, 6)]),
+            0x004d_0000 => new("rotri.d", &[rd, rj, f.uimm(10, 6)]),
+            _ => match word & 0xfffe_0000 {
+                0x0004_0000 => new("alsl.w", &[rd, rj, rk, sa2]),
+                0x0006_0000 => new("alsl.wu", &[rd, rj, rk, sa2]),
+                0x0008_0000 => new("bytepick.w", &[rd, rj, rk, f.uimm(15, 2)]),
+                0x002c_0000 => new("alsl.d", &[rd, rj, rk, sa2]),
+                _ if word & 0xfffc_0000 == 0x000c_0000 => {
+                    new("bytepick.d", &[rd, rj, rk, f.uimm(15, 3)])
+                }
+                _ => return decode_bit_field(f),
+            },
+        },
+    };
+
+    Ok(inst)
+}
+
+/// Bit field inserts and extracts, and the instructions with a 12-bit immediate.
+fn decode_bit_field(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = f.word;
+    let (rd, rj) = (gpr(f.rd), gpr(f.rj));
+
+    let inst = match word & 0xffe0_8000 {
+        0x0060_0000 => new("bstrins.w", &[rd, rj, f.uimm(16, 5), f.uimm(10, 5)]),
+        0x0060_8000 => new("bstrpick.w", &[rd, rj, f.uimm(16, 5), f.uimm(10, 5)]),
+        _ => match word & 0xffc0_0000 {
+            0x0080_0000 => new("bstrins.d", &[rd, rj, f.uimm(16, 6), f.uimm(10, 6)]),
+            0x00c0_0000 => new("bstrpick.d", &[rd, rj, f.uimm(16, 6), f.uimm(10, 6)]),
+            0x0200_0000 => new("slti", &[rd, rj, f.simm(10, 12, 0)]),
+            0x0240_0000 => new("sltui", &[rd, rj, f.simm(10, 12, 0)]),
+            0x0280_0000 => new("addi.w", &[rd, rj, f.simm(10, 12, 0)]),
+            0x02c0_0000 => new("addi.d", &[rd, rj, f.simm(10, 12, 0)]),
+            0x0300_0000 => new("lu52i.d", &[rd, rj, f.simm(10, 12, 0)]),
+            0x0340_0000 => new("andi", &[rd, rj, f.uimm(10, 12)]),
+            0x0380_0000 => new("ori", &[rd, rj, f.uimm(10, 12)]),
+            0x03c0_0000 => new("xori", &[rd, rj, f.uimm(10, 12)]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+    };
+
+    Ok(inst)
+}
+
+/// Instructions with a 16 or 20-bit immediate, used to build up constants and addresses.
+pub(crate) fn decode_upper(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let rd = gpr(f.rd);
+
+    if f.word >> 26 == 0x04 {
+        return Ok(new("addu16i.d", &[rd, gpr(f.rj), f.simm(10, 16, 0)]));
+    }
+
+    let si20 = f.simm(5, 20, 0);
+    let inst = match f.word >> 25 {
+        0x0a => new("lu12i.w", &[rd, si20]),
+        0x0b => new("lu32i.d", &[rd, si20]),
+        0x0c => new("pcaddi", &[rd, si20]),
+        0x0d => new("pcalau12i", &[rd, si20]),
+        0x0e => new("pcaddu12i", &[rd, si20]),
+        0x0f => new("pcaddu18i", &[rd, si20]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
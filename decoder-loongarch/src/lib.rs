@@ -0,0 +1,352 @@
+//! LoongArch64 disassembler, covering the base integer and privileged instructions, the floating
+//! point instructions and the 128-bit LSX and 256-bit LASX vector extensions.
+
+mod behavior;
+mod float;
+mod integer;
+mod memory;
+mod privileged;
+mod tests;
+mod vector;
+
+use debugvault::Index;
+use decoder::{Error, ErrorKind, FlowKind, MemoryAccess, ToTokens};
+use tokenizing::{ColorScheme, Colors, TokenStream};
+
+#[rustfmt::skip]
+pub const REGISTERS: [&str; 32] = [
+    "zero", "ra", "tp", "sp", "a0", "a1", "a2", "a3",
+    "a4", "a5", "a6", "a7", "t0", "t1", "t2", "t3",
+    "t4", "t5", "t6", "t7", "t8", "r21", "fp", "s0",
+    "s1", "s2", "s3", "s4", "s5", "s6", "s7", "s8",
+];
+
+#[rustfmt::skip]
+pub const FLOAT_REGISTERS: [&str; 32] = [
+    "fa0", "fa1", "fa2", "fa3", "fa4", "fa5", "fa6", "fa7",
+    "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7",
+    "ft8", "ft9", "ft10", "ft11", "ft12", "ft13", "ft14", "ft15",
+    "fs0", "fs1", "fs2", "fs3", "fs4", "fs5", "fs6", "fs7",
+];
+
+#[rustfmt::skip]
+pub const VECTOR_REGISTERS: [&str; 32] = [
+    "vr0", "vr1", "vr2", "vr3", "vr4", "vr5", "vr6", "vr7",
+    "vr8", "vr9", "vr10", "vr11", "vr12", "vr13", "vr14", "vr15",
+    "vr16", "vr17", "vr18", "vr19", "vr20", "vr21", "vr22", "vr23",
+    "vr24", "vr25", "vr26", "vr27", "vr28", "vr29", "vr30", "vr31",
+];
+
+#[rustfmt::skip]
+pub const LASX_REGISTERS: [&str; 32] = [
+    "xr0", "xr1", "xr2", "xr3", "xr4", "xr5", "xr6", "xr7",
+    "xr8", "xr9", "xr10", "xr11", "xr12", "xr13", "xr14", "xr15",
+    "xr16", "xr17", "xr18", "xr19", "xr20", "xr21", "xr22", "xr23",
+    "xr24", "xr25", "xr26", "xr27", "xr28", "xr29", "xr30", "xr31",
+];
+
+pub const FCC_REGISTERS: [&str; 8] = [
+    "fcc0", "fcc1", "fcc2", "fcc3", "fcc4", "fcc5", "fcc6", "fcc7",
+];
+
+pub const FCSR_REGISTERS: [&str; 4] = ["fcsr0", "fcsr1", "fcsr2", "fcsr3"];
+
+/// Register `ra`, which receives the return address of `bl`.
+const RA: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Operand {
+    /// General purpose register.
+    Register(u8),
+    /// Floating point register.
+    Float(u8),
+    /// 128-bit LSX vector register.
+    Vector(u8),
+    /// 256-bit LASX vector register, the low half of which overlaps the LSX register.
+    Lasx(u8),
+    /// Condition flag register, set by floating point comparisons.
+    Fcc(u8),
+    /// Floating point control and status register.
+    Fcsr(u8),
+    Immediate(i64),
+    /// Target of a branch, relative to the instruction until it's address is known.
+    Branch(i64),
+    #[default]
+    Nothing,
+}
+
+impl ToTokens for Operand {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        match *self {
+            Self::Register(reg) => stream.push(REGISTERS[reg as usize], Colors::register()),
+            Self::Float(reg) => stream.push(FLOAT_REGISTERS[reg as usize], Colors::register()),
+            Self::Vector(reg) => stream.push(VECTOR_REGISTERS[reg as usize], Colors::register()),
+            Self::Lasx(reg) => stream.push(LASX_REGISTERS[reg as usize], Colors::register()),
+            Self::Fcc(cc) => stream.push(FCC_REGISTERS[cc as usize], Colors::register()),
+            Self::Fcsr(reg) => stream.push(FCSR_REGISTERS[reg as usize], Colors::register()),
+            Self::Immediate(imm) => {
+                stream.push_owned(decoder::encode_hex(imm), Colors::immediate())
+            }
+            Self::Branch(target) => match symbols.get_sym_by_addr(target as usize) {
+                Some(symbol) => {
+                    for token in symbol.name() {
+                        stream.push_token(token.clone());
+                    }
+                }
+                None => stream.push_owned(format!("{target:#x}"), Colors::immediate()),
+            },
+            Self::Nothing => unreachable!("empty operand encountered"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    mnemomic: &'static str,
+    operands: [Operand; 4],
+    operand_count: usize,
+}
+
+impl Instruction {
+    fn new(mnemomic: &'static str, operands: &[Operand]) -> Self {
+        let mut inst = Self {
+            mnemomic,
+            operands: [Operand::Nothing; 4],
+            operand_count: operands.len(),
+        };
+
+        inst.operands[..operands.len()].copy_from_slice(operands);
+        inst
+    }
+
+    pub fn mnemomic(&self) -> &'static str {
+        self.mnemomic
+    }
+
+    pub fn operands(&self) -> &[Operand] {
+        &self.operands[..self.operand_count]
+    }
+}
+
+impl decoder::Decoded for Instruction {
+    fn width(&self) -> usize {
+        4
+    }
+
+    fn tokenize_in(&self, stream: &mut TokenStream, symbols: &Index, dialect: decoder::Dialect) {
+        self.tokenize_with(stream, symbols, dialect.pseudo)
+    }
+
+    fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+        for operand in &mut self.operands[..self.operand_count] {
+            if let Operand::Branch(offset) = operand {
+                *offset = offset.wrapping_add(addr as i64)
+            }
+        }
+    }
+
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
+    }
+
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
+
+    fn regs_read(&self) -> Vec<&'static str> {
+        behavior::registers(self).0
+    }
+
+    fn regs_written(&self) -> Vec<&'static str> {
+        behavior::registers(self).1
+    }
+
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
+}
+
+/// LoongArch64 decoder, instructions are always stored little-endian.
+#[derive(Default, Clone, Copy)]
+pub struct Decoder;
+
+impl decoder::Decodable for Decoder {
+    type Instruction = Instruction;
+
+    fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
+        let mut bytes = [0u8; 4];
+        reader.next_n(&mut bytes).ok_or(Error::new(ErrorKind::ExhaustedInput, 4))?;
+
+        decode_word(u32::from_le_bytes(bytes)).map_err(|err| Error::new(err, 4))
+    }
+
+    fn max_width(&self) -> usize {
+        4
+    }
+}
+
+/// Register fields of an instruction word, not all of them are meaningful for every format.
+#[derive(Clone, Copy)]
+struct Fields {
+    word: u32,
+    rd: u32,
+    rj: u32,
+    rk: u32,
+    /// Fourth register of the fused multiply-add and select instructions.
+    ra: u32,
+}
+
+impl Fields {
+    fn new(word: u32) -> Self {
+        Self {
+            word,
+            rd: word & 0b11111,
+            rj: word >> 5 & 0b11111,
+            rk: word >> 10 & 0b11111,
+            ra: word >> 15 & 0b11111,
+        }
+    }
+
+    /// Unsigned immediate of `bits` bits starting at bit `lo`.
+    fn uimm(&self, lo: u32, bits: u32) -> Operand {
+        Operand::Immediate((self.word >> lo & ((1 << bits) - 1)) as i64)
+    }
+
+    /// Signed immediate of `bits` bits starting at bit `lo`, scaled by `1 << shift`.
+    fn simm(&self, lo: u32, bits: u32, shift: u32) -> Operand {
+        Operand::Immediate(sign_extend(self.word >> lo, bits) << shift)
+    }
+}
+
+fn gpr(num: u32) -> Operand {
+    Operand::Register(num as u8 & 0b11111)
+}
+
+fn fpr(num: u32) -> Operand {
+    Operand::Float(num as u8 & 0b11111)
+}
+
+/// LSX or LASX register, depending on the width of the instruction.
+fn vr(num: u32, lasx: bool) -> Operand {
+    match lasx {
+        false => Operand::Vector(num as u8 & 0b11111),
+        true => Operand::Lasx(num as u8 & 0b11111),
+    }
+}
+
+fn fcc(num: u32) -> Operand {
+    Operand::Fcc(num as u8 & 0b111)
+}
+
+fn imm(imm: impl Into<i64>) -> Operand {
+    Operand::Immediate(imm.into())
+}
+
+/// Sign extend the low `bits` bits of a field.
+fn sign_extend(value: u32, bits: u32) -> i64 {
+    ((value << (32 - bits)) as i32 >> (32 - bits)) as i64
+}
+
+fn decode_word(word: u32) -> Result<Instruction, ErrorKind> {
+    match word >> 26 {
+        // the floating point instructions are interleaved with the integer ones
+        0x00 if word >> 21 == 0b1000 => float::decode(Fields::new(word)),
+        0x00 => integer::decode(Fields::new(word)),
+        0x01 => privileged::decode(Fields::new(word)),
+        0x02 | 0x03 => float::decode_fused(Fields::new(word)),
+        0x04..=0x07 => integer::decode_upper(Fields::new(word)),
+        0x08..=0x0e => memory::decode(Fields::new(word)),
+        0x10..=0x1b => decode_branch(Fields::new(word)),
+        0x1c | 0x1d => vector::decode(Fields::new(word)),
+        _ => Err(ErrorKind::InvalidOpcode),
+    }
+}
+
+/// Branches, with offsets in instructions that are split across the instruction when they
+/// don't fit in the 16 bits at the top.
+fn decode_branch(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rd, rj) = (gpr(f.rd), gpr(f.rj));
+
+    let offs16 = Operand::Branch(sign_extend(f.word >> 10, 16) << 2);
+    let offs21 =
+        Operand::Branch(sign_extend(f.word >> 10 & 0xffff | (f.word & 0x1f) << 16, 21) << 2);
+    let offs26 =
+        Operand::Branch(sign_extend(f.word >> 10 & 0xffff | (f.word & 0x3ff) << 16, 26) << 2);
+
+    let inst = match f.word >> 26 {
+        0x10 => new("beqz", &[rj, offs21]),
+        0x11 => new("bnez", &[rj, offs21]),
+        0x12 => match f.word >> 8 & 0b11 {
+            0b00 => new("bceqz", &[fcc(f.rj), offs21]),
+            0b01 => new("bcnez", &[fcc(f.rj), offs21]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        0x13 => new("jirl", &[rd, rj, f.simm(10, 16, 2)]),
+        0x14 => new("b", &[offs26]),
+        0x15 => new("bl", &[offs26]),
+        0x16 => new("beq", &[rj, rd, offs16]),
+        0x17 => new("bne", &[rj, rd, offs16]),
+        0x18 => new("blt", &[rj, rd, offs16]),
+        0x19 => new("bge", &[rj, rd, offs16]),
+        0x1a => new("bltu", &[rj, rd, offs16]),
+        0x1b => new("bgeu", &[rj, rd, offs16]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+impl Instruction {
+    /// The alias this is usually written as, together with it's operands.
+    fn pseudo(&self) -> Option<(&'static str, Vec<Operand>)> {
+        use Operand::{Immediate as Imm, Register as Reg};
+
+        let ops = self.operands();
+        let alias = match (self.mnemomic, ops) {
+            ("andi", [Reg(0), Reg(0), Imm(0)]) => ("nop", vec![]),
+            ("or", [rd, rj, Reg(0)]) => ("move", vec![*rd, *rj]),
+            ("addi.w", [rd, Reg(0), v]) => ("li.w", vec![*rd, *v]),
+            ("jirl", [Reg(0), Reg(RA), Imm(0)]) => ("ret", vec![]),
+            ("jirl", [Reg(0), rj, Imm(0)]) => ("jr", vec![*rj]),
+            ("blt", [rj, Reg(0), offs]) => ("bltz", vec![*rj, *offs]),
+            ("blt", [Reg(0), rd, offs]) => ("bgtz", vec![*rd, *offs]),
+            ("bge", [rj, Reg(0), offs]) => ("bgez", vec![*rj, *offs]),
+            ("bge", [Reg(0), rd, offs]) => ("blez", vec![*rd, *offs]),
+            _ => return None,
+        };
+
+        Some(alias)
+    }
+
+    /// Tokenize the instruction, either using aliases or the canonical encoding.
+    pub fn tokenize_with(&self, stream: &mut TokenStream, symbols: &Index, pseudo: bool) {
+        let (mnemomic, operands) = match self.pseudo().filter(|_| pseudo) {
+            Some((mnemomic, operands)) => (mnemomic, operands),
+            None => (self.mnemomic, self.operands().to_vec()),
+        };
+
+        stream.push(mnemomic, Colors::opcode());
+
+        // there are operands
+        if !operands.is_empty() {
+            stream.push(" ", Colors::spacing());
+
+            // iterate through operands
+            for (idx, operand) in operands.iter().enumerate() {
+                operand.tokenize(stream, symbols);
+
+                // separator
+                if idx != operands.len() - 1 {
+                    stream.push(", ", Colors::expr());
+                }
+            }
+        }
+    }
+}
+
+impl decoder::ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        self.tokenize_with(stream, symbols, decoder::Dialect::default().pseudo)
+    }
+}
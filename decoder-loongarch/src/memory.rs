@@ -0,0 +1,180 @@
+//! Loads, stores, atomic memory operations and barriers, including the vector loads and stores.
+
+use crate::{fpr, gpr, vr, Fields, Instruction};
+use decoder::ErrorKind;
+
+/// Atomic memory operations of opcodes `0x3860` to `0x3871`, in opcode order.
+#[rustfmt::skip]
+const ATOMICS: [&str; 36] = [
+    "amswap.w", "amswap.d", "amadd.w", "amadd.d", "amand.w", "amand.d",
+    "amor.w", "amor.d", "amxor.w", "amxor.d", "ammax.w", "ammax.d",
+    "ammin.w", "ammin.d", "ammax.wu", "ammax.du", "ammin.wu", "ammin.du",
+    "amswap_db.w", "amswap_db.d", "amadd_db.w", "amadd_db.d", "amand_db.w", "amand_db.d",
+    "amor_db.w", "amor_db.d", "amxor_db.w", "amxor_db.d", "ammax_db.w", "ammax_db.d",
+    "ammin_db.w", "ammin_db.d", "ammax_db.wu", "ammax_db.du", "ammin_db.wu", "ammin_db.du",
+];
+
+/// Loads and stores with bound checks of opcodes `0x3874` to `0x387f`, in opcode order.
+#[rustfmt::skip]
+const BOUNDED: [&str; 24] = [
+    "fldgt.s", "fldgt.d", "fldle.s", "fldle.d", "fstgt.s", "fstgt.d", "fstle.s", "fstle.d",
+    "ldgt.b", "ldgt.h", "ldgt.w", "ldgt.d", "ldle.b", "ldle.h", "ldle.w", "ldle.d",
+    "stgt.b", "stgt.h", "stgt.w", "stgt.d", "stle.b", "stle.h", "stle.w", "stle.d",
+];
+
+pub(crate) fn decode(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = f.word;
+    let (rd, rj) = (gpr(f.rd), gpr(f.rj));
+    let si12 = f.simm(10, 12, 0);
+    let si14 = f.simm(10, 14, 2);
+
+    let inst = match word >> 24 {
+        0x20 => new("ll.w", &[rd, rj, si14]),
+        0x21 => new("sc.w", &[rd, rj, si14]),
+        0x22 => new("ll.d", &[rd, rj, si14]),
+        0x23 => new("sc.d", &[rd, rj, si14]),
+        0x24 => new("ldptr.w", &[rd, rj, si14]),
+        0x25 => new("stptr.w", &[rd, rj, si14]),
+        0x26 => new("ldptr.d", &[rd, rj, si14]),
+        0x27 => new("stptr.d", &[rd, rj, si14]),
+        0x28..=0x2c => match word >> 22 {
+            0xa0 => new("ld.b", &[rd, rj, si12]),
+            0xa1 => new("ld.h", &[rd, rj, si12]),
+            0xa2 => new("ld.w", &[rd, rj, si12]),
+            0xa3 => new("ld.d", &[rd, rj, si12]),
+            0xa4 => new("st.b", &[rd, rj, si12]),
+            0xa5 => new("st.h", &[rd, rj, si12]),
+            0xa6 => new("st.w", &[rd, rj, si12]),
+            0xa7 => new("st.d", &[rd, rj, si12]),
+            0xa8 => new("ld.bu", &[rd, rj, si12]),
+            0xa9 => new("ld.hu", &[rd, rj, si12]),
+            0xaa => new("ld.wu", &[rd, rj, si12]),
+            0xab => new("preld", &[f.uimm(0, 5), rj, si12]),
+            0xac => new("fld.s", &[fpr(f.rd), rj, si12]),
+            0xad => new("fst.s", &[fpr(f.rd), rj, si12]),
+            0xae => new("fld.d", &[fpr(f.rd), rj, si12]),
+            0xaf => new("fst.d", &[fpr(f.rd), rj, si12]),
+            0xb0 => new("vld", &[vr(f.rd, false), rj, si12]),
+            0xb1 => new("vst", &[vr(f.rd, false), rj, si12]),
+            0xb2 => new("xvld", &[vr(f.rd, true), rj, si12]),
+            0xb3 => new("xvst", &[vr(f.rd, true), rj, si12]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        0x30..=0x33 => return decode_element(f),
+        0x38 => return decode_indexed(f),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Loads replicated to all elements and stores of a single element of a vector.
+///
+/// The element size is marked by the highest set bit of bits 20 to 23, the offset and element
+/// index are below it and scaled by the element size.
+fn decode_element(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = f.word;
+    let lasx = word >> 25 & 1 == 1;
+    let store = word >> 24 & 1 == 1;
+
+    let marker = word >> 20 & 0xf;
+    if marker == 0 {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    // log2 of the element size in bytes
+    let size = marker.leading_zeros() - 28;
+    let (vd, rj) = (vr(f.rd, lasx), gpr(f.rj));
+
+    // the bit below the marker is reserved, other than for LASX stores
+    let index_bits = 4 - size + lasx as u32;
+    if !(store && lasx) && word >> (22 - size) & 1 != 0 {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    let inst = if store {
+        let mnemomic = match (lasx, size) {
+            (false, 0) => "vstelm.b",
+            (false, 1) => "vstelm.h",
+            (false, 2) => "vstelm.w",
+            (false, _) => "vstelm.d",
+            (true, 0) => "xvstelm.b",
+            (true, 1) => "xvstelm.h",
+            (true, 2) => "xvstelm.w",
+            (true, _) => "xvstelm.d",
+        };
+
+        let index = f.uimm(18, index_bits);
+        new(mnemomic, &[vd, rj, f.simm(10, 8, size), index])
+    } else {
+        let mnemomic = match (lasx, size) {
+            (false, 0) => "vldrepl.b",
+            (false, 1) => "vldrepl.h",
+            (false, 2) => "vldrepl.w",
+            (false, _) => "vldrepl.d",
+            (true, 0) => "xvldrepl.b",
+            (true, 1) => "xvldrepl.h",
+            (true, 2) => "xvldrepl.w",
+            (true, _) => "xvldrepl.d",
+        };
+
+        new(mnemomic, &[vd, rj, f.simm(10, 12 - size, size)])
+    };
+
+    Ok(inst)
+}
+
+/// Loads and stores indexed by a register, atomics, barriers and bound checked accesses.
+fn decode_indexed(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = f.word;
+    let (rd, rj, rk) = (gpr(f.rd), gpr(f.rj), gpr(f.rk));
+
+    // indexed loads and stores come before the atomics
+    if word >> 22 == 0xe0 || word >> 20 == 0x384 {
+        if word >> 15 & 0b111 != 0 {
+            return Err(ErrorKind::InvalidOpcode);
+        }
+
+        let inst = match word >> 18 & 0x1f {
+            0x00 => new("ldx.b", &[rd, rj, rk]),
+            0x01 => new("ldx.h", &[rd, rj, rk]),
+            0x02 => new("ldx.w", &[rd, rj, rk]),
+            0x03 => new("ldx.d", &[rd, rj, rk]),
+            0x04 => new("stx.b", &[rd, rj, rk]),
+            0x05 => new("stx.h", &[rd, rj, rk]),
+            0x06 => new("stx.w", &[rd, rj, rk]),
+            0x07 => new("stx.d", &[rd, rj, rk]),
+            0x08 => new("ldx.bu", &[rd, rj, rk]),
+            0x09 => new("ldx.hu", &[rd, rj, rk]),
+            0x0a => new("ldx.wu", &[rd, rj, rk]),
+            0x0b => new("preldx", &[f.uimm(0, 5), rj, rk]),
+            0x0c => new("fldx.s", &[fpr(f.rd), rj, rk]),
+            0x0d => new("fldx.d", &[fpr(f.rd), rj, rk]),
+            0x0e => new("fstx.s", &[fpr(f.rd), rj, rk]),
+            0x0f => new("fstx.d", &[fpr(f.rd), rj, rk]),
+            0x10 => new("vldx", &[vr(f.rd, false), rj, rk]),
+            0x11 => new("vstx", &[vr(f.rd, false), rj, rk]),
+            0x12 => new("xvldx", &[vr(f.rd, true), rj, rk]),
+            0x13 => new("xvstx", &[vr(f.rd, true), rj, rk]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        };
+
+        return Ok(inst);
+    }
+
+    // operations on memory at `rj`, in the order of their opcodes
+    let op = (word >> 15).wrapping_sub(0x3860_0000 >> 15) as usize;
+    let inst = match op {
+        0..=35 => new(ATOMICS[op], &[rd, rk, rj]),
+        36 => new("dbar", &[f.uimm(0, 15)]),
+        37 => new("ibar", &[f.uimm(0, 15)]),
+        40..=47 => new(BOUNDED[op - 40], &[fpr(f.rd), rj, rk]),
+        48..=63 => new(BOUNDED[op - 40], &[rd, rj, rk]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
@@ -0,0 +1,57 @@
+//! Control and status registers, caches, the TLB and other instructions that are only
+//! available in the privileged architecture.
+
+use crate::{gpr, Fields, Instruction};
+use decoder::ErrorKind;
+
+pub(crate) fn decode(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = f.word;
+    let (rd, rj, rk) = (gpr(f.rd), gpr(f.rj), gpr(f.rk));
+
+    // the register holding the mask selects between reading and writing
+    if word >> 24 == 0x04 {
+        let csr = f.uimm(10, 14);
+        let inst = match f.rj {
+            0 => new("csrrd", &[rd, csr]),
+            1 => new("csrwr", &[rd, csr]),
+            _ => new("csrxchg", &[rd, rj, csr]),
+        };
+
+        return Ok(inst);
+    }
+
+    let inst = match word {
+        0x0648_2000 => new("tlbclr", &[]),
+        0x0648_2400 => new("tlbflush", &[]),
+        0x0648_2800 => new("tlbsrch", &[]),
+        0x0648_2c00 => new("tlbrd", &[]),
+        0x0648_3000 => new("tlbwr", &[]),
+        0x0648_3400 => new("tlbfill", &[]),
+        0x0648_3800 => new("ertn", &[]),
+        _ => match word & 0xffff_fc00 {
+            0x0648_0000 => new("iocsrrd.b", &[rd, rj]),
+            0x0648_0400 => new("iocsrrd.h", &[rd, rj]),
+            0x0648_0800 => new("iocsrrd.w", &[rd, rj]),
+            0x0648_0c00 => new("iocsrrd.d", &[rd, rj]),
+            0x0648_1000 => new("iocsrwr.b", &[rd, rj]),
+            0x0648_1400 => new("iocsrwr.h", &[rd, rj]),
+            0x0648_1800 => new("iocsrwr.w", &[rd, rj]),
+            0x0648_1c00 => new("iocsrwr.d", &[rd, rj]),
+            _ => match word & 0xffff_8000 {
+                0x0648_8000 => new("idle", &[f.uimm(0, 15)]),
+                0x0649_8000 => new("invtlb", &[f.uimm(0, 5), rj, rk]),
+                _ => match word & 0xfffc_0000 {
+                    0x0640_0000 => new("lddir", &[rd, rj, f.uimm(10, 8)]),
+                    0x0644_0000 if f.rd == 0 => new("ldpte", &[rj, f.uimm(10, 8)]),
+                    _ if word & 0xffc0_0000 == 0x0600_0000 => {
+                        new("cacop", &[f.uimm(0, 5), rj, f.simm(10, 12, 0)])
+                    }
+                    _ => return Err(ErrorKind::InvalidOpcode),
+                },
+            },
+        },
+    };
+
+    Ok(inst)
+}
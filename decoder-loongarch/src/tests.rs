@@ -0,0 +1,188 @@
+#![cfg(test)]
+
+use decoder::{Decodable, Decoded};
+
+fn test_display(bytes: &[u8], str: &str) {
+    let decoder = crate::Decoder;
+    let mut reader = decoder::Reader::new(bytes);
+    let mut line = tokenizing::TokenStream::new();
+    let symbols = debugvault::Index::default();
+
+    let decoded = match decoder.decode(&mut reader) {
+        Ok(mut inst) => {
+            inst.update_rel_addrs(0x1000, None);
+            inst.tokenize_with(&mut line, &symbols, true);
+            line.to_string()
+        }
+        Err(err) => format!("{err:?}"),
+    };
+
+    assert_eq!(decoded, str);
+}
+
+fn decode(bytes: &[u8]) -> crate::Instruction {
+    let mut reader = decoder::Reader::new(bytes);
+    crate::Decoder.decode(&mut reader).unwrap()
+}
+
+#[test]
+fn integer() {
+    test_display(&[0x63, 0xc0, 0xff, 0x02], "addi.d sp, sp, -0x10");
+    test_display(&[0x61, 0x20, 0xc0, 0x29], "st.d ra, sp, 0x8");
+    test_display(&[0x84, 0x94, 0x10, 0x00], "add.d a0, a0, a1");
+    test_display(&[0x85, 0x0c, 0x41, 0x00], "slli.d a1, a0, 0x3");
+    test_display(&[0x24, 0x00, 0x00, 0x14], "lu12i.w a0, 0x1");
+    test_display(&[0xc4, 0x0c, 0x60, 0x38], "amswap.w a0, sp, a2");
+    test_display(&[0x00, 0x00, 0x72, 0x38], "dbar 0x0");
+    test_display(&[0x00, 0x00, 0x2b, 0x00], "syscall 0x0");
+    test_display(
+        &[0xff, 0xff, 0xff, 0xff],
+        "Error { kind: InvalidOpcode, size: 4 }",
+    );
+}
+
+#[test]
+fn branches() {
+    test_display(&[0x00, 0x04, 0x00, 0x54], "bl 0x1004");
+    test_display(&[0xff, 0xfb, 0xff, 0x53], "b 0xff8");
+    test_display(&[0x80, 0x0c, 0x00, 0x40], "beqz a0, 0x100c");
+    test_display(&[0x85, 0x10, 0x00, 0x58], "beq a0, a1, 0x1010");
+    test_display(&[0x85, 0x10, 0x00, 0x68], "bltu a0, a1, 0x1010");
+}
+
+#[test]
+fn float() {
+    test_display(&[0x00, 0x04, 0x01, 0x01], "fadd.d fa0, fa0, fa1");
+    test_display(&[0x00, 0x04, 0x21, 0x0c], "fcmp.clt.d fcc0, fa0, fa1");
+}
+
+#[test]
+fn vector() {
+    test_display(&[0x20, 0x08, 0x0b, 0x70], "vadd.w vr0, vr1, vr2");
+    test_display(&[0x20, 0x08, 0x0b, 0x74], "xvadd.w xr0, xr1, xr2");
+    test_display(&[0x80, 0x00, 0x00, 0x2c], "vld vr0, a0, 0x0");
+}
+
+#[test]
+fn pseudo() {
+    let tokenize = |bytes: &[u8], pseudo: bool| {
+        let mut line = tokenizing::TokenStream::new();
+        decode(bytes).tokenize_with(&mut line, &debugvault::Index::default(), pseudo);
+        line.to_string()
+    };
+
+    assert_eq!(tokenize(&[0x00, 0x00, 0x40, 0x03], true), "nop");
+    assert_eq!(
+        tokenize(&[0x00, 0x00, 0x40, 0x03], false),
+        "andi zero, zero, 0x0"
+    );
+    assert_eq!(
+        tokenize(&[0x00, 0x00, 0x80, 0x03], true),
+        "ori zero, zero, 0x0"
+    );
+
+    assert_eq!(tokenize(&[0xa4, 0x00, 0x15, 0x00], true), "move a0, a1");
+    assert_eq!(
+        tokenize(&[0xa4, 0x00, 0x15, 0x00], false),
+        "or a0, a1, zero"
+    );
+
+    assert_eq!(tokenize(&[0x0c, 0x04, 0x80, 0x02], true), "li.w t0, 0x1");
+    assert_eq!(tokenize(&[0x20, 0x00, 0x00, 0x4c], true), "ret");
+    assert_eq!(
+        tokenize(&[0x20, 0x00, 0x00, 0x4c], false),
+        "jirl zero, ra, 0x0"
+    );
+    assert_eq!(tokenize(&[0x80, 0x01, 0x00, 0x4c], true), "jr t0");
+    assert_eq!(tokenize(&[0x04, 0x10, 0x00, 0x60], true), "bgtz a0, 0x10");
+}
+
+#[test]
+fn rel_addrs() {
+    let resolve = |bytes: &[u8], addr: usize| {
+        let mut inst = decode(bytes);
+        inst.update_rel_addrs(addr, None);
+        inst.targets()
+    };
+
+    // beq a0, a1, 0x10
+    assert_eq!(resolve(&[0x85, 0x10, 0x00, 0x58], 0x2000), vec![0x2010]);
+    // b -0x8
+    assert_eq!(resolve(&[0xff, 0xfb, 0xff, 0x53], 0x2000), vec![0x1ff8]);
+    // jirl has no static target
+    assert_eq!(
+        resolve(&[0x81, 0x01, 0x00, 0x4c], 0x2000),
+        Vec::<usize>::new()
+    );
+}
+
+#[test]
+fn behavior() {
+    use decoder::{Direction, FlowKind, MemoryAccess};
+
+    // ret
+    let inst = decode(&[0x20, 0x00, 0x00, 0x4c]);
+    assert_eq!(inst.flow(), FlowKind::Return);
+    assert_eq!(inst.regs_read(), vec!["ra"]);
+    assert_eq!(inst.regs_written(), Vec::<&str>::new());
+
+    // jr t0
+    assert_eq!(decode(&[0x80, 0x01, 0x00, 0x4c]).flow(), FlowKind::Jump);
+
+    // jirl ra, t0, 0x0
+    let inst = decode(&[0x81, 0x01, 0x00, 0x4c]);
+    assert_eq!(inst.flow(), FlowKind::Call);
+    assert_eq!(inst.regs_written(), vec!["ra"]);
+
+    // bl 0x4
+    let inst = decode(&[0x00, 0x04, 0x00, 0x54]);
+    assert_eq!(inst.flow(), FlowKind::Call);
+    assert_eq!(inst.regs_written(), vec!["ra"]);
+
+    // beq a0, a1, 0x10
+    let inst = decode(&[0x85, 0x10, 0x00, 0x58]);
+    assert_eq!(inst.flow(), FlowKind::ConditionalJump);
+    assert_eq!(inst.regs_read(), vec!["a0", "a1"]);
+    assert_eq!(inst.regs_written(), Vec::<&str>::new());
+
+    // st.d ra, sp, 0x8
+    let inst = decode(&[0x61, 0x20, 0xc0, 0x29]);
+    assert_eq!(inst.regs_read(), vec!["ra", "sp"]);
+    assert_eq!(inst.regs_written(), Vec::<&str>::new());
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Write
+        })
+    );
+
+    // amswap.w a0, sp, a2
+    let inst = decode(&[0xc4, 0x0c, 0x60, 0x38]);
+    assert_eq!(inst.regs_read(), vec!["sp", "a2"]);
+    assert_eq!(inst.regs_written(), vec!["a0"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::ReadWrite
+        })
+    );
+
+    // vld vr0, a0, 0x0
+    let inst = decode(&[0x80, 0x00, 0x00, 0x2c]);
+    assert_eq!(inst.regs_written(), vec!["vr0"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(16),
+            direction: Direction::Read
+        })
+    );
+
+    // syscall 0x0
+    assert_eq!(
+        decode(&[0x00, 0x00, 0x2b, 0x00]).flow(),
+        FlowKind::Interrupt
+    );
+}
@@ -0,0 +1,548 @@
+//! LSX and LASX vector instructions of opcodes `0x70` to `0x77`.
+//!
+//! LASX instructions are encoded `0x0400_0000` above the LSX instruction of the same name, the
+//! operations are performed on 256-bit registers instead. Only a few of them have no LSX
+//! counterpart or operate on elements of different sizes.
+
+use crate::{fcc, gpr, vr, Fields, Instruction, Operand};
+use decoder::ErrorKind;
+
+/// LSX and LASX mnemonics of a group of instructions, indexed by element size. Missing
+/// sizes are left empty.
+macro_rules! sized {
+    ($name:literal) => {
+        sized!($name, ["b", "h", "w", "d"])
+    };
+    ($name:literal, unsigned) => {
+        sized!($name, ["bu", "hu", "wu", "du"])
+    };
+    ($name:literal, [$($size:tt),*]) => {
+        [
+            [$(sized!(@one "", $name, $size)),*],
+            [$(sized!(@one "x", $name, $size)),*],
+        ]
+    };
+    (@one $prefix:literal, $name:literal, _) => {
+        ""
+    };
+    (@one $prefix:literal, $name:literal, $size:literal) => {
+        concat!($prefix, $name, ".", $size)
+    };
+}
+
+/// LSX and LASX mnemonics of instructions with unrelated names, missing ones are left empty.
+macro_rules! named {
+    ($($name:tt),*) => {
+        [
+            [$(named!(@one "", $name)),*],
+            [$(named!(@one "x", $name)),*],
+        ]
+    };
+    (@one $prefix:literal, _) => {
+        ""
+    };
+    (@one $prefix:literal, $name:literal) => {
+        concat!($prefix, $name)
+    };
+}
+
+/// LSX and LASX mnemonic of a single instruction.
+macro_rules! both {
+    ($name:literal) => {
+        [$name, concat!("x", $name)]
+    };
+}
+
+type Names = [[&'static str; 4]; 2];
+
+/// Operands of an instruction.
+#[derive(Clone, Copy)]
+enum Shape {
+    /// `vd, vj, vk`.
+    Binary,
+    /// `vd, vj`.
+    Unary,
+    /// `vd, vj, rk`.
+    Scalar,
+    /// `vd, rj`.
+    Splat,
+    /// `cd, vj`, setting a condition flag.
+    Flag,
+    /// `vd, vj, si5`.
+    Signed,
+    /// `vd, vj, ui5`.
+    Unsigned,
+}
+
+pub(crate) fn decode(f: Fields) -> Result<Instruction, ErrorKind> {
+    let lasx = f.word >> 26 == 0x1d;
+
+    if lasx {
+        if let Some(inst) = decode_lasx(f) {
+            return inst;
+        }
+    }
+
+    // the encoding of the LSX instruction with the same name
+    let word = f.word & !0x0400_0000;
+
+    let inst = match word >> 24 {
+        0x70 | 0x71 => decode_binary(f, word, lasx),
+        0x72 | 0x73 => match word {
+            0x7280_0000..=0x729b_ffff => decode_small(f, word, lasx),
+            0x729c_0000..=0x729f_ffff => decode_unary(f, word, lasx),
+            0x72a0_0000..=0x72af_ffff | 0x7308_0000..=0x733f_ffff => {
+                decode_shift(f, word, lasx, false)
+            }
+            0x7340_0000..=0x737f_ffff => decode_shift(f, word, lasx, true),
+            0x7380_0000..=0x73ff_ffff => decode_byte(f, word, lasx),
+            _ => decode_element(f, word, lasx),
+        },
+        _ => None,
+    };
+
+    inst.ok_or(ErrorKind::InvalidOpcode)
+}
+
+fn build(f: Fields, mnemomic: &'static str, shape: Shape, lasx: bool) -> Option<Instruction> {
+    let new = Instruction::new;
+    let (vd, vj, vk) = (vr(f.rd, lasx), vr(f.rj, lasx), vr(f.rk, lasx));
+
+    if mnemomic.is_empty() {
+        return None;
+    }
+
+    let inst = match shape {
+        Shape::Binary => new(mnemomic, &[vd, vj, vk]),
+        Shape::Unary => new(mnemomic, &[vd, vj]),
+        Shape::Scalar => new(mnemomic, &[vd, vj, gpr(f.rk)]),
+        Shape::Splat => new(mnemomic, &[vd, gpr(f.rj)]),
+        Shape::Flag if f.rd >> 3 == 0 => new(mnemomic, &[fcc(f.rd), vj]),
+        Shape::Flag => return None,
+        Shape::Signed => new(mnemomic, &[vd, vj, f.simm(10, 5, 0)]),
+        Shape::Unsigned => new(mnemomic, &[vd, vj, f.uimm(10, 5)]),
+    };
+
+    Some(inst)
+}
+
+/// Instructions with three vector registers, with the element size in the lowest two bits of
+/// the opcode.
+fn decode_binary(f: Fields, word: u32, lasx: bool) -> Option<Instruction> {
+    let names: Names = match word & 0xfffe_0000 {
+        0x7000_0000 => sized!("vseq"),
+        0x7002_0000 => sized!("vsle"),
+        0x7004_0000 => sized!("vsle", unsigned),
+        0x7006_0000 => sized!("vslt"),
+        0x7008_0000 => sized!("vslt", unsigned),
+        0x700a_0000 => sized!("vadd"),
+        0x700c_0000 => sized!("vsub"),
+        0x701e_0000 => sized!("vaddwev", ["h.b", "w.h", "d.w", "q.d"]),
+        0x7020_0000 => sized!("vsubwev", ["h.b", "w.h", "d.w", "q.d"]),
+        0x7022_0000 => sized!("vaddwod", ["h.b", "w.h", "d.w", "q.d"]),
+        0x7024_0000 => sized!("vsubwod", ["h.b", "w.h", "d.w", "q.d"]),
+        0x702e_0000 => sized!("vaddwev", ["h.bu", "w.hu", "d.wu", "q.du"]),
+        0x7030_0000 => sized!("vsubwev", ["h.bu", "w.hu", "d.wu", "q.du"]),
+        0x7032_0000 => sized!("vaddwod", ["h.bu", "w.hu", "d.wu", "q.du"]),
+        0x7034_0000 => sized!("vsubwod", ["h.bu", "w.hu", "d.wu", "q.du"]),
+        0x703e_0000 => sized!("vaddwev", ["h.bu.b", "w.hu.h", "d.wu.w", "q.du.d"]),
+        0x7040_0000 => sized!("vaddwod", ["h.bu.b", "w.hu.h", "d.wu.w", "q.du.d"]),
+        0x7046_0000 => sized!("vsadd"),
+        0x7048_0000 => sized!("vssub"),
+        0x704a_0000 => sized!("vsadd", unsigned),
+        0x704c_0000 => sized!("vssub", unsigned),
+        0x7054_0000 => sized!("vhaddw", ["h.b", "w.h", "d.w", "q.d"]),
+        0x7056_0000 => sized!("vhsubw", ["h.b", "w.h", "d.w", "q.d"]),
+        0x7058_0000 => sized!("vhaddw", ["hu.bu", "wu.hu", "du.wu", "qu.du"]),
+        0x705a_0000 => sized!("vhsubw", ["hu.bu", "wu.hu", "du.wu", "qu.du"]),
+        0x705c_0000 => sized!("vadda"),
+        0x7060_0000 => sized!("vabsd"),
+        0x7062_0000 => sized!("vabsd", unsigned),
+        0x7064_0000 => sized!("vavg"),
+        0x7066_0000 => sized!("vavg", unsigned),
+        0x7068_0000 => sized!("vavgr"),
+        0x706a_0000 => sized!("vavgr", unsigned),
+        0x7070_0000 => sized!("vmax"),
+        0x7072_0000 => sized!("vmin"),
+        0x7074_0000 => sized!("vmax", unsigned),
+        0x7076_0000 => sized!("vmin", unsigned),
+        0x7084_0000 => sized!("vmul"),
+        0x7086_0000 => sized!("vmuh"),
+        0x7088_0000 => sized!("vmuh", unsigned),
+        0x7090_0000 => sized!("vmulwev", ["h.b", "w.h", "d.w", "q.d"]),
+        0x7092_0000 => sized!("vmulwod", ["h.b", "w.h", "d.w", "q.d"]),
+        0x7098_0000 => sized!("vmulwev", ["h.bu", "w.hu", "d.wu", "q.du"]),
+        0x709a_0000 => sized!("vmulwod", ["h.bu", "w.hu", "d.wu", "q.du"]),
+        0x70a0_0000 => sized!("vmulwev", ["h.bu.b", "w.hu.h", "d.wu.w", "q.du.d"]),
+        0x70a2_0000 => sized!("vmulwod", ["h.bu.b", "w.hu.h", "d.wu.w", "q.du.d"]),
+        0x70a8_0000 => sized!("vmadd"),
+        0x70aa_0000 => sized!("vmsub"),
+        0x70ac_0000 => sized!("vmaddwev", ["h.b", "w.h", "d.w", "q.d"]),
+        0x70ae_0000 => sized!("vmaddwod", ["h.b", "w.h", "d.w", "q.d"]),
+        0x70b4_0000 => sized!("vmaddwev", ["h.bu", "w.hu", "d.wu", "q.du"]),
+        0x70b6_0000 => sized!("vmaddwod", ["h.bu", "w.hu", "d.wu", "q.du"]),
+        0x70bc_0000 => sized!("vmaddwev", ["h.bu.b", "w.hu.h", "d.wu.w", "q.du.d"]),
+        0x70be_0000 => sized!("vmaddwod", ["h.bu.b", "w.hu.h", "d.wu.w", "q.du.d"]),
+        0x70e0_0000 => sized!("vdiv"),
+        0x70e2_0000 => sized!("vmod"),
+        0x70e4_0000 => sized!("vdiv", unsigned),
+        0x70e6_0000 => sized!("vmod", unsigned),
+        0x70e8_0000 => sized!("vsll"),
+        0x70ea_0000 => sized!("vsrl"),
+        0x70ec_0000 => sized!("vsra"),
+        0x70ee_0000 => sized!("vrotr"),
+        0x70f0_0000 => sized!("vsrlr"),
+        0x70f2_0000 => sized!("vsrar"),
+        0x70f4_0000 => sized!("vsrln", [_, "b.h", "h.w", "w.d"]),
+        0x70f6_0000 => sized!("vsran", [_, "b.h", "h.w", "w.d"]),
+        0x70f8_0000 => sized!("vsrlrn", [_, "b.h", "h.w", "w.d"]),
+        0x70fa_0000 => sized!("vsrarn", [_, "b.h", "h.w", "w.d"]),
+        0x70fc_0000 => sized!("vssrln", [_, "b.h", "h.w", "w.d"]),
+        0x70fe_0000 => sized!("vssran", [_, "b.h", "h.w", "w.d"]),
+        0x7100_0000 => sized!("vssrlrn", [_, "b.h", "h.w", "w.d"]),
+        0x7102_0000 => sized!("vssrarn", [_, "b.h", "h.w", "w.d"]),
+        0x7104_0000 => sized!("vssrln", [_, "bu.h", "hu.w", "wu.d"]),
+        0x7106_0000 => sized!("vssran", [_, "bu.h", "hu.w", "wu.d"]),
+        0x7108_0000 => sized!("vssrlrn", [_, "bu.h", "hu.w", "wu.d"]),
+        0x710a_0000 => sized!("vssrarn", [_, "bu.h", "hu.w", "wu.d"]),
+        0x710c_0000 => sized!("vbitclr"),
+        0x710e_0000 => sized!("vbitset"),
+        0x7110_0000 => sized!("vbitrev"),
+        0x7116_0000 => sized!("vpackev"),
+        0x7118_0000 => sized!("vpackod"),
+        0x711a_0000 => sized!("vilvl"),
+        0x711c_0000 => sized!("vilvh"),
+        0x711e_0000 => sized!("vpickev"),
+        0x7120_0000 => sized!("vpickod"),
+        0x7122_0000 => {
+            let names: Names = sized!("vreplve");
+            let mnemomic = names[lasx as usize][(word >> 15 & 0b11) as usize];
+            return build(f, mnemomic, Shape::Scalar, lasx);
+        }
+        0x7126_0000 => named!("vand.v", "vor.v", "vxor.v", "vnor.v"),
+        0x7128_0000 => named!("vandn.v", "vorn.v", _, _),
+        0x712a_0000 => named!(_, _, "vfrstp.b", "vfrstp.h"),
+        0x712c_0000 => named!(_, _, "vadd.q", "vsub.q"),
+        0x712e_0000 => sized!("vsigncov"),
+        0x7130_0000 => sized!("vfadd", [_, "s", "d", _]),
+        0x7132_0000 => sized!("vfsub", [_, "s", "d", _]),
+        0x7138_0000 => sized!("vfmul", [_, "s", "d", _]),
+        0x713a_0000 => sized!("vfdiv", [_, "s", "d", _]),
+        0x713c_0000 => sized!("vfmax", [_, "s", "d", _]),
+        0x713e_0000 => sized!("vfmin", [_, "s", "d", _]),
+        0x7140_0000 => sized!("vfmaxa", [_, "s", "d", _]),
+        0x7142_0000 => sized!("vfmina", [_, "s", "d", _]),
+        0x7146_0000 => named!("vfcvt.h.s", "vfcvt.s.d", _, _),
+        0x7148_0000 => named!("vffint.s.l", _, _, "vftint.w.d"),
+        0x714a_0000 => named!(
+            "vftintrm.w.d",
+            "vftintrp.w.d",
+            "vftintrz.w.d",
+            "vftintrne.w.d"
+        ),
+        0x717a_0000 => named!(_, "vshuf.h", "vshuf.w", "vshuf.d"),
+        _ => return None,
+    };
+
+    let mnemomic = names[lasx as usize][(word >> 15 & 0b11) as usize];
+    build(f, mnemomic, Shape::Binary, lasx)
+}
+
+/// Comparisons, arithmetic and extremes with a 5-bit immediate.
+fn decode_small(f: Fields, word: u32, lasx: bool) -> Option<Instruction> {
+    let (names, shape): (Names, Shape) = match word & 0xfffe_0000 {
+        0x7280_0000 => (sized!("vseqi"), Shape::Signed),
+        0x7282_0000 => (sized!("vslei"), Shape::Signed),
+        0x7284_0000 => (sized!("vslei", unsigned), Shape::Unsigned),
+        0x7286_0000 => (sized!("vslti"), Shape::Signed),
+        0x7288_0000 => (sized!("vslti", unsigned), Shape::Unsigned),
+        0x728a_0000 => (sized!("vaddi", unsigned), Shape::Unsigned),
+        0x728c_0000 => (sized!("vsubi", unsigned), Shape::Unsigned),
+        0x728e_0000 => (named!("vbsll.v", "vbsrl.v", _, _), Shape::Unsigned),
+        0x7290_0000 => (sized!("vmaxi"), Shape::Signed),
+        0x7292_0000 => (sized!("vmini"), Shape::Signed),
+        0x7294_0000 => (sized!("vmaxi", unsigned), Shape::Unsigned),
+        0x7296_0000 => (sized!("vmini", unsigned), Shape::Unsigned),
+        0x729a_0000 => (named!("vfrstpi.b", "vfrstpi.h", _, _), Shape::Unsigned),
+        _ => return None,
+    };
+
+    let mnemomic = names[lasx as usize][(word >> 15 & 0b11) as usize];
+    build(f, mnemomic, shape, lasx)
+}
+
+/// Instructions with a single source, with the element size in the lowest two bits of the
+/// opcode.
+fn decode_unary(f: Fields, word: u32, lasx: bool) -> Option<Instruction> {
+    let sized = match word & 0xffff_f000 {
+        0x729c_0000 => Some((sized!("vclo"), Shape::Unary)),
+        0x729c_1000 => Some((sized!("vclz"), Shape::Unary)),
+        0x729c_2000 => Some((sized!("vpcnt"), Shape::Unary)),
+        0x729c_3000 => Some((sized!("vneg"), Shape::Unary)),
+        0x729c_4000 => Some((sized!("vmskltz"), Shape::Unary)),
+        0x729c_a000 => Some((sized!("vsetanyeqz"), Shape::Flag)),
+        0x729c_b000 => Some((sized!("vsetallnez"), Shape::Flag)),
+        0x729e_e000 => Some((sized!("vexth", ["h.b", "w.h", "d.w", "q.d"]), Shape::Unary)),
+        0x729e_f000 => Some((
+            sized!("vexth", ["hu.bu", "wu.hu", "du.wu", "qu.du"]),
+            Shape::Unary,
+        )),
+        0x729f_0000 => Some((sized!("vreplgr2vr"), Shape::Splat)),
+        _ => None,
+    };
+
+    if let Some((names, shape)) = sized {
+        let mnemomic = names[lasx as usize][(word >> 10 & 0b11) as usize];
+        return build(f, mnemomic, shape, lasx);
+    }
+
+    let (names, shape) = match word & 0xffff_fc00 {
+        0x729c_5000 => (both!("vmskgez.b"), Shape::Unary),
+        0x729c_6000 => (both!("vmsknz.b"), Shape::Unary),
+        0x729c_9800 => (both!("vseteqz.v"), Shape::Flag),
+        0x729c_9c00 => (both!("vsetnez.v"), Shape::Flag),
+        0x729c_c400 => (both!("vflogb.s"), Shape::Unary),
+        0x729c_c800 => (both!("vflogb.d"), Shape::Unary),
+        0x729c_d400 => (both!("vfclass.s"), Shape::Unary),
+        0x729c_d800 => (both!("vfclass.d"), Shape::Unary),
+        0x729c_e400 => (both!("vfsqrt.s"), Shape::Unary),
+        0x729c_e800 => (both!("vfsqrt.d"), Shape::Unary),
+        0x729c_f400 => (both!("vfrecip.s"), Shape::Unary),
+        0x729c_f800 => (both!("vfrecip.d"), Shape::Unary),
+        0x729d_0400 => (both!("vfrsqrt.s"), Shape::Unary),
+        0x729d_0800 => (both!("vfrsqrt.d"), Shape::Unary),
+        0x729d_3400 => (both!("vfrint.s"), Shape::Unary),
+        0x729d_3800 => (both!("vfrint.d"), Shape::Unary),
+        0x729d_4400 => (both!("vfrintrm.s"), Shape::Unary),
+        0x729d_4800 => (both!("vfrintrm.d"), Shape::Unary),
+        0x729d_5400 => (both!("vfrintrp.s"), Shape::Unary),
+        0x729d_5800 => (both!("vfrintrp.d"), Shape::Unary),
+        0x729d_6400 => (both!("vfrintrz.s"), Shape::Unary),
+        0x729d_6800 => (both!("vfrintrz.d"), Shape::Unary),
+        0x729d_7400 => (both!("vfrintrne.s"), Shape::Unary),
+        0x729d_7800 => (both!("vfrintrne.d"), Shape::Unary),
+        0x729d_e800 => (both!("vfcvtl.s.h"), Shape::Unary),
+        0x729d_ec00 => (both!("vfcvth.s.h"), Shape::Unary),
+        0x729d_f000 => (both!("vfcvtl.d.s"), Shape::Unary),
+        0x729d_f400 => (both!("vfcvth.d.s"), Shape::Unary),
+        0x729e_0000 => (both!("vffint.s.w"), Shape::Unary),
+        0x729e_0400 => (both!("vffint.s.wu"), Shape::Unary),
+        0x729e_0800 => (both!("vffint.d.l"), Shape::Unary),
+        0x729e_0c00 => (both!("vffint.d.lu"), Shape::Unary),
+        0x729e_1000 => (both!("vffintl.d.w"), Shape::Unary),
+        0x729e_1400 => (both!("vffinth.d.w"), Shape::Unary),
+        0x729e_3000 => (both!("vftint.w.s"), Shape::Unary),
+        0x729e_3400 => (both!("vftint.l.d"), Shape::Unary),
+        0x729e_3800 => (both!("vftintrm.w.s"), Shape::Unary),
+        0x729e_3c00 => (both!("vftintrm.l.d"), Shape::Unary),
+        0x729e_4000 => (both!("vftintrp.w.s"), Shape::Unary),
+        0x729e_4400 => (both!("vftintrp.l.d"), Shape::Unary),
+        0x729e_4800 => (both!("vftintrz.w.s"), Shape::Unary),
+        0x729e_4c00 => (both!("vftintrz.l.d"), Shape::Unary),
+        0x729e_5000 => (both!("vftintrne.w.s"), Shape::Unary),
+        0x729e_5400 => (both!("vftintrne.l.d"), Shape::Unary),
+        0x729e_5800 => (both!("vftint.wu.s"), Shape::Unary),
+        0x729e_5c00 => (both!("vftint.lu.d"), Shape::Unary),
+        0x729e_7000 => (both!("vftintrz.wu.s"), Shape::Unary),
+        0x729e_7400 => (both!("vftintrz.lu.d"), Shape::Unary),
+        0x729e_8000 => (both!("vftintl.l.s"), Shape::Unary),
+        0x729e_8400 => (both!("vftinth.l.s"), Shape::Unary),
+        0x729e_8800 => (both!("vftintrml.l.s"), Shape::Unary),
+        0x729e_8c00 => (both!("vftintrmh.l.s"), Shape::Unary),
+        0x729e_9000 => (both!("vftintrpl.l.s"), Shape::Unary),
+        0x729e_9400 => (both!("vftintrph.l.s"), Shape::Unary),
+        0x729e_9800 => (both!("vftintrzl.l.s"), Shape::Unary),
+        0x729e_9c00 => (both!("vftintrzh.l.s"), Shape::Unary),
+        0x729e_a000 => (both!("vftintrnel.l.s"), Shape::Unary),
+        0x729e_a400 => (both!("vftintrneh.l.s"), Shape::Unary),
+        _ => return None,
+    };
+
+    build(f, names[lasx as usize], shape, lasx)
+}
+
+/// Shifts and bit operations by an immediate, where the element size is marked by the highest
+/// set bit above the immediate. Narrowing shifts have an immediate one bit wider.
+fn decode_shift(f: Fields, word: u32, lasx: bool, narrow: bool) -> Option<Instruction> {
+    let new = Instruction::new;
+    let lo = if narrow { 14 } else { 13 };
+    let marker = word >> lo & ((1 << (18 - lo)) - 1);
+    if marker == 0 || marker >> 4 != 0 {
+        return None;
+    }
+
+    let size = (31 - marker.leading_zeros()) as usize;
+    let shift = f.uimm(10, lo - 10 + size as u32);
+    let (vd, vj) = (vr(f.rd, lasx), vr(f.rj, lasx));
+
+    let names: Names = match (word & 0xfffc_0000, narrow) {
+        (0x72a0_0000, false) => sized!("vrotri"),
+        (0x72a4_0000, false) => sized!("vsrlri"),
+        (0x72a8_0000, false) => sized!("vsrari"),
+        // extending the last element takes no shift
+        (0x7308_0000 | 0x730c_0000, false) if size == 3 => {
+            let names = [both!("vextl.q.d"), both!("vextl.qu.du")];
+            let mnemomic = names[(word >> 18 & 1) as usize][lasx as usize];
+            return match word >> 10 & 0x3f {
+                0 => Some(new(mnemomic, &[vd, vj])),
+                _ => None,
+            };
+        }
+        (0x7308_0000, false) => sized!("vsllwil", [_, "h.b", "w.h", "d.w"]),
+        (0x730c_0000, false) => sized!("vsllwil", [_, "hu.bu", "wu.hu", "du.wu"]),
+        (0x7310_0000, false) => sized!("vbitclri"),
+        (0x7314_0000, false) => sized!("vbitseti"),
+        (0x7318_0000, false) => sized!("vbitrevi"),
+        (0x7324_0000, false) => sized!("vsat"),
+        (0x7328_0000, false) => sized!("vsat", unsigned),
+        (0x732c_0000, false) => sized!("vslli"),
+        (0x7330_0000, false) => sized!("vsrli"),
+        (0x7334_0000, false) => sized!("vsrai"),
+        (0x7340_0000, true) => sized!("vsrlni", ["b.h", "h.w", "w.d", "d.q"]),
+        (0x7344_0000, true) => sized!("vsrlrni", ["b.h", "h.w", "w.d", "d.q"]),
+        (0x7348_0000, true) => sized!("vssrlni", ["b.h", "h.w", "w.d", "d.q"]),
+        (0x734c_0000, true) => sized!("vssrlni", ["bu.h", "hu.w", "wu.d", "du.q"]),
+        (0x7350_0000, true) => sized!("vssrlrni", ["b.h", "h.w", "w.d", "d.q"]),
+        (0x7354_0000, true) => sized!("vssrlrni", ["bu.h", "hu.w", "wu.d", "du.q"]),
+        (0x7358_0000, true) => sized!("vsrani", ["b.h", "h.w", "w.d", "d.q"]),
+        (0x735c_0000, true) => sized!("vsrarni", ["b.h", "h.w", "w.d", "d.q"]),
+        (0x7360_0000, true) => sized!("vssrani", ["b.h", "h.w", "w.d", "d.q"]),
+        (0x7364_0000, true) => sized!("vssrani", ["bu.h", "hu.w", "wu.d", "du.q"]),
+        (0x7368_0000, true) => sized!("vssrarni", ["b.h", "h.w", "w.d", "d.q"]),
+        (0x736c_0000, true) => sized!("vssrarni", ["bu.h", "hu.w", "wu.d", "du.q"]),
+        _ => return None,
+    };
+
+    let mnemomic = names[lasx as usize][size];
+    if mnemomic.is_empty() {
+        return None;
+    }
+
+    Some(new(mnemomic, &[vd, vj, shift]))
+}
+
+/// Element size marked by the number of leading ones in bits 14 to 11, followed by the element
+/// index.
+fn element_index(f: Fields) -> Option<(usize, Operand)> {
+    let size = (f.word << 17).leading_ones();
+    if size > 3 {
+        return None;
+    }
+
+    Some((size as usize, f.uimm(10, 4 - size)))
+}
+
+/// Moves between elements and general purpose registers, and element replication.
+fn decode_element(f: Fields, word: u32, lasx: bool) -> Option<Instruction> {
+    let new = Instruction::new;
+    let (size, index) = element_index(f)?;
+    let (vd, vj) = (vr(f.rd, lasx), vr(f.rj, lasx));
+
+    let names: Names = match word & 0xffff_8000 {
+        0x72eb_8000 => sized!("vinsgr2vr"),
+        0x72ef_8000 => sized!("vpickve2gr"),
+        0x72f3_8000 => sized!("vpickve2gr", unsigned),
+        0x72f7_8000 => [sized!("vreplvei")[0], sized!("vrepl128vei")[1]],
+        _ => return None,
+    };
+
+    // LASX moves words and doublewords with the encodings of LSX halfwords and words
+    let size = match (lasx, word & 0xffff_8000) {
+        (false, _) | (true, 0x72f7_8000) => size,
+        (true, _) if matches!(size, 1 | 2) => size + 1,
+        _ => return None,
+    };
+
+    let mnemomic = names[lasx as usize][size];
+    let inst = match word & 0xffff_8000 {
+        0x72eb_8000 => new(mnemomic, &[vd, gpr(f.rj), index]),
+        0x72ef_8000 | 0x72f3_8000 => new(mnemomic, &[gpr(f.rd), vj, index]),
+        _ => new(mnemomic, &[vd, vj, index]),
+    };
+
+    Some(inst)
+}
+
+/// Instructions with an 8-bit immediate.
+fn decode_byte(f: Fields, word: u32, lasx: bool) -> Option<Instruction> {
+    let new = Instruction::new;
+    let (vd, vj) = (vr(f.rd, lasx), vr(f.rj, lasx));
+
+    // the only instruction with a 13-bit immediate, which selects a pattern to load
+    if word & 0xfffc_0000 == 0x73e0_0000 {
+        return Some(new(both!("vldi")[lasx as usize], &[vd, f.simm(5, 13, 0)]));
+    }
+
+    let names = match word & 0xfffc_0000 {
+        0x7380_0000 => both!("vextrins.d"),
+        0x7384_0000 => both!("vextrins.w"),
+        0x7388_0000 => both!("vextrins.h"),
+        0x738c_0000 => both!("vextrins.b"),
+        0x7390_0000 => both!("vshuf4i.b"),
+        0x7394_0000 => both!("vshuf4i.h"),
+        0x7398_0000 => both!("vshuf4i.w"),
+        0x739c_0000 => both!("vshuf4i.d"),
+        0x73c4_0000 => both!("vbitseli.b"),
+        0x73d0_0000 => both!("vandi.b"),
+        0x73d4_0000 => both!("vori.b"),
+        0x73d8_0000 => both!("vxori.b"),
+        0x73dc_0000 => both!("vnori.b"),
+        0x73e4_0000 => both!("vpermi.w"),
+        _ => return None,
+    };
+
+    Some(new(names[lasx as usize], &[vd, vj, f.uimm(10, 8)]))
+}
+
+/// LASX instructions without an LSX counterpart, most of which operate across the two 128-bit
+/// halves of a register.
+fn decode_lasx(f: Fields) -> Option<Result<Instruction, ErrorKind>> {
+    let new = Instruction::new;
+    let word = f.word;
+    let (xd, xj, xk) = (vr(f.rd, true), vr(f.rj, true), vr(f.rk, true));
+
+    let inst = match word & 0xffff_fc00 {
+        0x7707_0000 => new("xvreplve0.b", &[xd, xj]),
+        0x7707_8000 => new("xvreplve0.h", &[xd, xj]),
+        0x7707_c000 => new("xvreplve0.w", &[xd, xj]),
+        0x7707_e000 => new("xvreplve0.d", &[xd, xj]),
+        0x7707_f000 => new("xvreplve0.q", &[xd, xj]),
+        0x771f_1000 => new("vext2xv.h.b", &[xd, xj]),
+        0x771f_1400 => new("vext2xv.w.b", &[xd, xj]),
+        0x771f_1800 => new("vext2xv.d.b", &[xd, xj]),
+        0x771f_1c00 => new("vext2xv.w.h", &[xd, xj]),
+        0x771f_2000 => new("vext2xv.d.h", &[xd, xj]),
+        0x771f_2400 => new("vext2xv.d.w", &[xd, xj]),
+        0x771f_2800 => new("vext2xv.hu.bu", &[xd, xj]),
+        0x771f_2c00 => new("vext2xv.wu.bu", &[xd, xj]),
+        0x771f_3000 => new("vext2xv.du.bu", &[xd, xj]),
+        0x771f_3400 => new("vext2xv.wu.hu", &[xd, xj]),
+        0x771f_3800 => new("vext2xv.du.hu", &[xd, xj]),
+        0x771f_3c00 => new("vext2xv.du.wu", &[xd, xj]),
+        _ => match word & 0xffff_8000 {
+            0x757d_0000 => new("xvperm.w", &[xd, xj, xk]),
+            0x76ff_8000 | 0x7703_8000 => {
+                let (size, index) = match element_index(f) {
+                    Some((size @ (1 | 2), index)) => (size, index),
+                    _ => return Some(Err(ErrorKind::InvalidOpcode)),
+                };
+
+                let mnemomic = match (word & 0xffff_8000, size) {
+                    (0x76ff_8000, 1) => "xvinsve0.w",
+                    (0x76ff_8000, _) => "xvinsve0.d",
+                    (_, 1) => "xvpickve.w",
+                    _ => "xvpickve.d",
+                };
+
+                new(mnemomic, &[xd, xj, index])
+            }
+            _ => match word & 0xfffc_0000 {
+                0x77e8_0000 => new("xvpermi.d", &[xd, xj, f.uimm(10, 8)]),
+                0x77ec_0000 => new("xvpermi.q", &[xd, xj, f.uimm(10, 8)]),
+                _ => return None,
+            },
+        },
+    };
+
+    Some(Ok(inst))
+}
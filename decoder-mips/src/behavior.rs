@@ -0,0 +1,373 @@
+//! Semantic queries on decoded instructions: control flow, registers and memory accesses.
+//!
+//! The multiply/divide results are reported as `hi` and `lo`. Floating point condition codes
+//! and coprocessor registers aren't tracked.
+//!
+//! The 16-bit microMIPS instructions behave like the 32-bit instruction without the `16` suffix.
+
+use crate::{Instruction, Operand, Register, FLOAT_REGISTERS};
+use decoder::{Direction, FlowKind, MemoryAccess};
+
+fn is_branch(mnemomic: &str) -> bool {
+    matches!(
+        mnemomic,
+        "beq"
+            | "bne"
+            | "blez"
+            | "bgtz"
+            | "bltz"
+            | "bgez"
+            | "beql"
+            | "bnel"
+            | "blezl"
+            | "bgtzl"
+            | "bltzl"
+            | "bgezl"
+            | "bc1f"
+            | "bc1t"
+            | "bc1fl"
+            | "bc1tl"
+            | "bc1eqz"
+            | "bc1nez"
+            | "bc2f"
+            | "bc2t"
+            | "bc2fl"
+            | "bc2tl"
+            | "bc2eqz"
+            | "bc2nez"
+            | "beqc"
+            | "bnec"
+            | "bovc"
+            | "bnvc"
+            | "bgec"
+            | "bltc"
+            | "bgeuc"
+            | "bltuc"
+            | "blezc"
+            | "bgezc"
+            | "bgtzc"
+            | "bltzc"
+            | "beqzc"
+            | "bnezc"
+            | "beqz"
+            | "bnez"
+            | "bteqz"
+            | "btnez"
+    )
+}
+
+/// Conditional branches that save the return address in `ra`.
+fn is_branch_and_link(mnemomic: &str) -> bool {
+    matches!(
+        mnemomic,
+        "bltzal"
+            | "bgezal"
+            | "bltzall"
+            | "bgezall"
+            | "blezalc"
+            | "bgezalc"
+            | "bgtzalc"
+            | "bltzalc"
+            | "beqzalc"
+            | "bnezalc"
+            | "bltzals"
+            | "bgezals"
+    )
+}
+
+fn is_trap(mnemomic: &str) -> bool {
+    matches!(
+        mnemomic,
+        "tge"
+            | "tgeu"
+            | "tlt"
+            | "tltu"
+            | "teq"
+            | "tne"
+            | "tgei"
+            | "tgeiu"
+            | "tlti"
+            | "tltiu"
+            | "teqi"
+            | "tnei"
+    )
+}
+
+/// Loads and stores of a list of registers, which write the list when loading.
+fn list_access(mnemomic: &str) -> Option<Direction> {
+    match mnemomic {
+        "lwm" | "lwm32" | "restore" => Some(Direction::Read),
+        "swm" | "swm32" | "save" => Some(Direction::Write),
+        _ => None,
+    }
+}
+
+/// The mnemonic of 16-bit microMIPS instructions without their `16` suffix.
+fn base_mnemomic(inst: &Instruction) -> &'static str {
+    inst.mnemomic.strip_suffix("16").unwrap_or(inst.mnemomic)
+}
+
+/// Number of bytes and direction of the memory access.
+fn access(mnemomic: &str) -> Option<(usize, Direction)> {
+    let access = match mnemomic {
+        "lb" | "lbu" => (1, Direction::Read),
+        "lh" | "lhu" => (2, Direction::Read),
+        "lw" | "lwu" | "lwl" | "lwr" | "ll" | "lwc1" | "lwc2" | "lwxc1" | "lwpc" | "lwupc"
+        | "lwxs" => (4, Direction::Read),
+        "ld" | "ldl" | "ldr" | "lld" | "ldc1" | "ldc2" | "ldxc1" | "luxc1" | "ldpc" | "lwp" => {
+            (8, Direction::Read)
+        }
+        "sb" => (1, Direction::Write),
+        "sh" => (2, Direction::Write),
+        "sw" | "swl" | "swr" | "sc" | "swc1" | "swc2" | "swxc1" => (4, Direction::Write),
+        "sd" | "sdl" | "sdr" | "scd" | "sdc1" | "sdc2" | "sdxc1" | "suxc1" | "swp" => {
+            (8, Direction::Write)
+        }
+        _ => return None,
+    };
+
+    Some(access)
+}
+
+pub(crate) fn flow(inst: &Instruction) -> FlowKind {
+    let mnemomic = base_mnemomic(inst);
+    let operands = inst.operands();
+
+    match mnemomic {
+        "jal" | "jalx" | "jals" | "balc" | "jialc" | "jalrc" => FlowKind::Call,
+        // the 16-bit forms always link to `ra`
+        "jalr" | "jalrs" if operands.len() == 1 => FlowKind::Call,
+        "jalr" | "jalr.hb" | "jalrs" | "jalrs.hb"
+            if operands[0] == Operand::Register(Register::Zero) =>
+        {
+            match operands[1] {
+                Operand::Register(Register::Ra) => FlowKind::Return,
+                _ => FlowKind::Jump,
+            }
+        }
+        "jalr" | "jalr.hb" | "jalrs" | "jalrs.hb" => FlowKind::Call,
+        "jr" | "jr.hb" | "jrc" if operands[0] == Operand::Register(Register::Ra) => {
+            FlowKind::Return
+        }
+        "jic" if operands[0] == Operand::Register(Register::Ra) => FlowKind::Return,
+        "jraddiusp" => FlowKind::Return,
+        "j" | "jr" | "jr.hb" | "jrc" | "b" | "bc" | "jic" => FlowKind::Jump,
+        "beq" if operands[0] == operands[1] => FlowKind::Jump,
+        "bgezal" | "bgezals" if operands[0] == Operand::Register(Register::Zero) => FlowKind::Call,
+        // `nal` only reads the program counter
+        "bltzal" if operands[0] == Operand::Register(Register::Zero) => FlowKind::Sequential,
+        "eret" | "eretnc" | "deret" => FlowKind::Return,
+        "syscall" | "break" | "sdbbp" | "sigrie" => FlowKind::Interrupt,
+        "wait" => FlowKind::Halt,
+        mnemomic if is_trap(mnemomic) => FlowKind::Interrupt,
+        mnemomic if is_branch(mnemomic) || is_branch_and_link(mnemomic) => {
+            FlowKind::ConditionalJump
+        }
+        _ => FlowKind::Sequential,
+    }
+}
+
+pub(crate) fn targets(inst: &Instruction) -> Vec<usize> {
+    inst.operands()
+        .iter()
+        .filter_map(|operand| match *operand {
+            Operand::Branch(target) => Some(target as usize),
+            Operand::Jump { target, .. } => Some(target as usize),
+            _ => None,
+        })
+        .collect()
+}
+
+fn push(regs: &mut Vec<&'static str>, reg: &'static str) {
+    if reg != "zero" && !regs.contains(&reg) {
+        regs.push(reg);
+    }
+}
+
+/// Collects the registers read and written by an instruction.
+pub(crate) fn registers(inst: &Instruction) -> (Vec<&'static str>, Vec<&'static str>) {
+    let mut read = Vec::new();
+    let mut written = Vec::new();
+
+    let mnemomic = base_mnemomic(inst);
+    let count = inst.operand_count;
+
+    // release 6 reused the names for three operand forms that don't use `hi` and `lo`
+    let writes_hilo = count == 2
+        && matches!(
+            mnemomic,
+            "mult" | "multu" | "div" | "divu" | "dmult" | "dmultu" | "ddiv" | "ddivu"
+        );
+
+    // MIPS16 compares write their result to `t8`
+    let writes_t8 = matches!(mnemomic, "cmp" | "cmpi")
+        || count == 2 && matches!(mnemomic, "slt" | "sltu" | "slti" | "sltiu");
+
+    let reads_only = writes_hilo
+        || writes_t8
+        || is_branch(mnemomic)
+        || is_branch_and_link(mnemomic)
+        || is_trap(mnemomic)
+        || access(mnemomic).is_some_and(|(_, dir)| dir == Direction::Write)
+        || matches!(
+            mnemomic,
+            "jr" | "jr.hb"
+                | "jrc"
+                | "jic"
+                | "jialc"
+                | "madd"
+                | "maddu"
+                | "msub"
+                | "msubu"
+                | "mthi"
+                | "mtlo"
+                | "mtc0"
+                | "dmtc0"
+                | "mtc1"
+                | "dmtc1"
+                | "mthc1"
+                | "ctc1"
+                | "mtc2"
+                | "dmtc2"
+                | "mthc2"
+                | "ctc2"
+                | "wrpgpr"
+                | "synci"
+                | "cache"
+                | "pref"
+                | "prefx"
+        );
+
+    // inserting a bit field keeps the other bits of the destination, and the two operand
+    // forms of the compressed instruction sets use the destination as a source
+    let reads_and_writes = matches!(mnemomic, "ins" | "dins" | "dinsm" | "dinsu")
+        || count == 2
+            && matches!(
+                mnemomic,
+                "and"
+                    | "or"
+                    | "xor"
+                    | "addiu"
+                    | "addius5"
+                    | "sllv"
+                    | "srlv"
+                    | "srav"
+                    | "dsllv"
+                    | "dsrlv"
+                    | "dsrav"
+                    | "dsrl"
+                    | "dsra"
+            )
+        || count == 1 && matches!(mnemomic, "zeb" | "zeh" | "zew" | "seb" | "seh" | "sew");
+
+    // the 16-bit jumps and `movep` write more than one register
+    let (is_jalr16, is_movep) = (
+        count == 1 && matches!(mnemomic, "jalr" | "jalrs"),
+        mnemomic == "movep",
+    );
+
+    for (idx, operand) in inst.operands().iter().enumerate() {
+        let reg = match *operand {
+            Operand::Register(reg) => reg.as_str(),
+            Operand::Float(reg) => FLOAT_REGISTERS[reg as usize],
+            Operand::Memory { base, .. } => {
+                push(&mut read, base.as_str());
+                continue;
+            }
+            Operand::Indexed { base, index } => {
+                push(&mut read, base.as_str());
+                push(&mut read, index.as_str());
+                continue;
+            }
+            Operand::List(regs) => {
+                let regs = (0..32).filter(|reg| regs & (1 << reg) != 0);
+                for reg in regs.map(|reg| Register::get(reg).as_str()) {
+                    match list_access(mnemomic) {
+                        Some(Direction::Read) => push(&mut written, reg),
+                        _ => push(&mut read, reg),
+                    }
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        // moves to a coprocessor write their second operand
+        let is_destination = match mnemomic {
+            "mtc1" | "dmtc1" | "mthc1" => idx == 1,
+            "movep" => idx < 2,
+            _ => idx == 0 && !reads_only && !is_jalr16 && !is_movep,
+        };
+
+        if is_destination {
+            push(&mut written, reg);
+            if reads_and_writes {
+                push(&mut read, reg);
+            }
+        } else {
+            push(&mut read, reg);
+        }
+    }
+
+    match mnemomic {
+        _ if writes_hilo => {
+            push(&mut written, "hi");
+            push(&mut written, "lo");
+        }
+        "madd" | "maddu" | "msub" | "msubu" => {
+            push(&mut read, "hi");
+            push(&mut read, "lo");
+            push(&mut written, "hi");
+            push(&mut written, "lo");
+        }
+        "mfhi" => push(&mut read, "hi"),
+        "mflo" => push(&mut read, "lo"),
+        "mthi" => push(&mut written, "hi"),
+        "mtlo" => push(&mut written, "lo"),
+        // `sc` writes whether it succeeded back into it's source
+        "sc" | "scd" => {
+            if let Operand::Register(reg) = inst.operands()[0] {
+                push(&mut written, reg.as_str());
+            }
+        }
+        // pairs of registers are loaded into and stored from consecutive registers
+        "lwp" | "swp" => {
+            if let Operand::Register(reg) = inst.operands()[0] {
+                let next = Register::get(reg as u32 + 1).as_str();
+                match mnemomic {
+                    "lwp" => push(&mut written, next),
+                    _ => push(&mut read, next),
+                }
+            }
+        }
+        "addiusp" | "jraddiusp" | "save" | "restore" => {
+            push(&mut read, "sp");
+            push(&mut written, "sp");
+        }
+        "addiur1sp" => push(&mut read, "sp"),
+        "bteqz" | "btnez" => push(&mut read, "t8"),
+        _ if writes_t8 => push(&mut written, "t8"),
+        _ if is_jalr16 => push(&mut written, "ra"),
+        "jal" | "jalx" | "jals" | "balc" | "jialc" => push(&mut written, "ra"),
+        mnemomic if is_branch_and_link(mnemomic) => push(&mut written, "ra"),
+        _ => {}
+    }
+
+    (read, written)
+}
+
+pub(crate) fn memory(inst: &Instruction) -> Option<MemoryAccess> {
+    let mnemomic = base_mnemomic(inst);
+
+    if let Some(direction) = list_access(mnemomic) {
+        return Some(MemoryAccess {
+            size: None,
+            direction,
+        });
+    }
+
+    access(mnemomic).map(|(size, direction)| MemoryAccess {
+        size: Some(size),
+        direction,
+    })
+}
@@ -0,0 +1,304 @@
+//! Coprocessor 0 (system control), coprocessor 1 (floating point), the generic coprocessor 2
+//! encodings and, before release 6, the COP1X indexed and fused multiply-add instructions.
+
+use crate::{branch, fpr, gpr, imm, mem, Decoder, Fields, Instruction, Operand, Register};
+use decoder::ErrorKind;
+
+// floating point formats, encoded in the fmt field
+const FMT_S: u32 = 16;
+const FMT_D: u32 = 17;
+const FMT_W: u32 = 20;
+const FMT_L: u32 = 21;
+
+pub(crate) fn decode_cop0(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rt, rd) = (gpr(f.rt), Operand::Cop(f.rd as u8));
+    let sel = imm(f.dword & 0b111);
+    let zeroed = f.dword >> 3 & 0xff == 0;
+
+    let inst = match f.rs {
+        0x00 if zeroed => new("mfc0", &[rt, rd, sel]),
+        0x01 if zeroed && decoder.is_64 => new("dmfc0", &[rt, rd, sel]),
+        0x04 if zeroed => new("mtc0", &[rt, rd, sel]),
+        0x05 if zeroed && decoder.is_64 => new("dmtc0", &[rt, rd, sel]),
+        0x0a if f.dword & 0x7ff == 0 => new("rdpgpr", &[gpr(f.rd), rt]),
+        0x0e if f.dword & 0x7ff == 0 => new("wrpgpr", &[gpr(f.rd), rt]),
+        0x0b if f.rd == 12 && f.dword & 0x7df == 0 => {
+            let mnemomic = if f.dword >> 5 & 1 == 0 { "di" } else { "ei" };
+            match f.rt {
+                0 => new(mnemomic, &[]),
+                _ => new(mnemomic, &[rt]),
+            }
+        }
+        0x10..=0x1f => match f.funct {
+            0x01 if f.dword & 0x1ff_ffc0 == 0 => new("tlbr", &[]),
+            0x02 if f.dword & 0x1ff_ffc0 == 0 => new("tlbwi", &[]),
+            0x06 if f.dword & 0x1ff_ffc0 == 0 => new("tlbwr", &[]),
+            0x08 if f.dword & 0x1ff_ffc0 == 0 => new("tlbp", &[]),
+            0x18 if f.dword & 0x1ff_ffc0 == 0 => new("eret", &[]),
+            0x18 if decoder.is_r6 && f.dword & 0x1ff_ffc0 == 0x40 => new("eretnc", &[]),
+            0x1f if f.dword & 0x1ff_ffc0 == 0 => new("deret", &[]),
+            0x20 => match f.dword >> 6 & 0x7ffff {
+                0 => new("wait", &[]),
+                code => new("wait", &[imm(code)]),
+            },
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Pre-release 6 `c.cond.fmt` compares, indexed by condition and whether they're double precision.
+pub(crate) const CONDITIONS: [[&str; 2]; 16] = [
+    ["c.f.s", "c.f.d"],
+    ["c.un.s", "c.un.d"],
+    ["c.eq.s", "c.eq.d"],
+    ["c.ueq.s", "c.ueq.d"],
+    ["c.olt.s", "c.olt.d"],
+    ["c.ult.s", "c.ult.d"],
+    ["c.ole.s", "c.ole.d"],
+    ["c.ule.s", "c.ule.d"],
+    ["c.sf.s", "c.sf.d"],
+    ["c.ngle.s", "c.ngle.d"],
+    ["c.seq.s", "c.seq.d"],
+    ["c.ngl.s", "c.ngl.d"],
+    ["c.lt.s", "c.lt.d"],
+    ["c.nge.s", "c.nge.d"],
+    ["c.le.s", "c.le.d"],
+    ["c.ngt.s", "c.ngt.d"],
+];
+
+/// Joins a mnemonic with a format at compile time for every format we decode.
+macro_rules! with_fmt {
+    ($fmt:expr, $name:literal) => {
+        match $fmt {
+            FMT_S => concat!($name, ".s"),
+            FMT_D => concat!($name, ".d"),
+            FMT_W => concat!($name, ".w"),
+            _ => concat!($name, ".l"),
+        }
+    };
+}
+
+pub(crate) fn decode_cop1(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (is_64, r6) = (decoder.is_64, decoder.is_r6);
+    let (rt, fs) = (gpr(f.rt), fpr(f.rd));
+    let moves = f.dword & 0x7ff == 0;
+
+    let inst = match f.rs {
+        0x00 if moves => new("mfc1", &[rt, fs]),
+        0x01 if moves && is_64 => new("dmfc1", &[rt, fs]),
+        0x02 if moves => new("cfc1", &[rt, Operand::Cop(f.rd as u8)]),
+        0x03 if moves => new("mfhc1", &[rt, fs]),
+        0x04 if moves => new("mtc1", &[rt, fs]),
+        0x05 if moves && is_64 => new("dmtc1", &[rt, fs]),
+        0x06 if moves => new("ctc1", &[rt, Operand::Cop(f.rd as u8)]),
+        0x07 if moves => new("mthc1", &[rt, fs]),
+        0x08 if !r6 => {
+            let mnemomic = match f.rt & 0b11 {
+                0b00 => "bc1f",
+                0b01 => "bc1t",
+                0b10 => "bc1fl",
+                _ => "bc1tl",
+            };
+
+            // the condition code is omitted when it's the default `fcc0`
+            match f.rt >> 2 {
+                0 => new(mnemomic, &[branch(f.dword, 16)]),
+                cc => new(mnemomic, &[Operand::Fcc(cc as u8), branch(f.dword, 16)]),
+            }
+        }
+        0x09 if r6 => new("bc1eqz", &[fpr(f.rt), branch(f.dword, 16)]),
+        0x0d if r6 => new("bc1nez", &[fpr(f.rt), branch(f.dword, 16)]),
+        FMT_S | FMT_D => return decode_arith(f, decoder),
+        FMT_W | FMT_L if r6 && f.funct < 0x20 => return decode_compare(f),
+        FMT_W | FMT_L if f.rt == 0 => {
+            let fd = fpr(f.sa);
+            match f.funct {
+                0x20 => new(with_fmt!(f.rs, "cvt.s"), &[fd, fs]),
+                0x21 => new(with_fmt!(f.rs, "cvt.d"), &[fd, fs]),
+                _ => return Err(ErrorKind::InvalidOpcode),
+            }
+        }
+        // paired single
+        0x16 => return Err(ErrorKind::IncompleteDecoder),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Single and double precision arithmetic, conversions and (before release 6) compares.
+fn decode_arith(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let r6 = decoder.is_r6;
+    let fmt = f.rs;
+    let (ft, fs, fd) = (fpr(f.rt), fpr(f.rd), fpr(f.sa));
+    let unary = f.rt == 0;
+
+    let inst = match f.funct {
+        0x00 => new(with_fmt!(fmt, "add"), &[fd, fs, ft]),
+        0x01 => new(with_fmt!(fmt, "sub"), &[fd, fs, ft]),
+        0x02 => new(with_fmt!(fmt, "mul"), &[fd, fs, ft]),
+        0x03 => new(with_fmt!(fmt, "div"), &[fd, fs, ft]),
+        0x04 if unary => new(with_fmt!(fmt, "sqrt"), &[fd, fs]),
+        0x05 if unary => new(with_fmt!(fmt, "abs"), &[fd, fs]),
+        0x06 if unary => new(with_fmt!(fmt, "mov"), &[fd, fs]),
+        0x07 if unary => new(with_fmt!(fmt, "neg"), &[fd, fs]),
+        0x08 if unary => new(with_fmt!(fmt, "round.l"), &[fd, fs]),
+        0x09 if unary => new(with_fmt!(fmt, "trunc.l"), &[fd, fs]),
+        0x0a if unary => new(with_fmt!(fmt, "ceil.l"), &[fd, fs]),
+        0x0b if unary => new(with_fmt!(fmt, "floor.l"), &[fd, fs]),
+        0x0c if unary => new(with_fmt!(fmt, "round.w"), &[fd, fs]),
+        0x0d if unary => new(with_fmt!(fmt, "trunc.w"), &[fd, fs]),
+        0x0e if unary => new(with_fmt!(fmt, "ceil.w"), &[fd, fs]),
+        0x0f if unary => new(with_fmt!(fmt, "floor.w"), &[fd, fs]),
+        0x10 if r6 => new(with_fmt!(fmt, "sel"), &[fd, fs, ft]),
+        0x11 if !r6 && f.rt & 0b10 == 0 => {
+            let cc = Operand::Fcc((f.rt >> 2) as u8);
+            match f.rt & 1 {
+                0 => new(with_fmt!(fmt, "movf"), &[fd, fs, cc]),
+                _ => new(with_fmt!(fmt, "movt"), &[fd, fs, cc]),
+            }
+        }
+        0x12 if !r6 => new(with_fmt!(fmt, "movz"), &[fd, fs, gpr(f.rt)]),
+        0x13 if !r6 => new(with_fmt!(fmt, "movn"), &[fd, fs, gpr(f.rt)]),
+        0x14 if r6 => new(with_fmt!(fmt, "seleqz"), &[fd, fs, ft]),
+        0x17 if r6 => new(with_fmt!(fmt, "selnez"), &[fd, fs, ft]),
+        0x15 if unary => new(with_fmt!(fmt, "recip"), &[fd, fs]),
+        0x16 if unary => new(with_fmt!(fmt, "rsqrt"), &[fd, fs]),
+        0x18 if r6 => new(with_fmt!(fmt, "maddf"), &[fd, fs, ft]),
+        0x19 if r6 => new(with_fmt!(fmt, "msubf"), &[fd, fs, ft]),
+        0x1a if r6 && unary => new(with_fmt!(fmt, "rint"), &[fd, fs]),
+        0x1b if r6 && unary => new(with_fmt!(fmt, "class"), &[fd, fs]),
+        0x1c if r6 => new(with_fmt!(fmt, "min"), &[fd, fs, ft]),
+        0x1d if r6 => new(with_fmt!(fmt, "mina"), &[fd, fs, ft]),
+        0x1e if r6 => new(with_fmt!(fmt, "max"), &[fd, fs, ft]),
+        0x1f if r6 => new(with_fmt!(fmt, "maxa"), &[fd, fs, ft]),
+        0x20 if unary && fmt == FMT_D => new("cvt.s.d", &[fd, fs]),
+        0x21 if unary && fmt == FMT_S => new("cvt.d.s", &[fd, fs]),
+        0x24 if unary => new(with_fmt!(fmt, "cvt.w"), &[fd, fs]),
+        0x25 if unary => new(with_fmt!(fmt, "cvt.l"), &[fd, fs]),
+        0x30..=0x3f if !r6 && f.sa & 0b11 == 0 => {
+            let mnemomic = CONDITIONS[f.funct as usize & 0b1111][(fmt == FMT_D) as usize];
+            match f.sa >> 2 {
+                0 => new(mnemomic, &[fs, ft]),
+                cc => new(mnemomic, &[Operand::Fcc(cc as u8), fs, ft]),
+            }
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Release 6 compares, which write all ones or all zeros to a floating point register.
+fn decode_compare(f: Fields) -> Result<Instruction, ErrorKind> {
+    #[rustfmt::skip]
+    const CONDITIONS: [Option<[&str; 2]>; 32] = [
+        Some(["cmp.af.s", "cmp.af.d"]), Some(["cmp.un.s", "cmp.un.d"]),
+        Some(["cmp.eq.s", "cmp.eq.d"]), Some(["cmp.ueq.s", "cmp.ueq.d"]),
+        Some(["cmp.lt.s", "cmp.lt.d"]), Some(["cmp.ult.s", "cmp.ult.d"]),
+        Some(["cmp.le.s", "cmp.le.d"]), Some(["cmp.ule.s", "cmp.ule.d"]),
+        Some(["cmp.saf.s", "cmp.saf.d"]), Some(["cmp.sun.s", "cmp.sun.d"]),
+        Some(["cmp.seq.s", "cmp.seq.d"]), Some(["cmp.sueq.s", "cmp.sueq.d"]),
+        Some(["cmp.slt.s", "cmp.slt.d"]), Some(["cmp.sult.s", "cmp.sult.d"]),
+        Some(["cmp.sle.s", "cmp.sle.d"]), Some(["cmp.sule.s", "cmp.sule.d"]),
+        None, Some(["cmp.or.s", "cmp.or.d"]),
+        Some(["cmp.une.s", "cmp.une.d"]), Some(["cmp.ne.s", "cmp.ne.d"]),
+        None, None, None, None,
+        None, Some(["cmp.sor.s", "cmp.sor.d"]),
+        Some(["cmp.sune.s", "cmp.sune.d"]), Some(["cmp.sne.s", "cmp.sne.d"]),
+        None, None, None, None,
+    ];
+
+    let names = CONDITIONS[f.funct as usize].ok_or(ErrorKind::InvalidOpcode)?;
+    let mnemomic = names[(f.rs == FMT_L) as usize];
+
+    Ok(Instruction::new(
+        mnemomic,
+        &[fpr(f.sa), fpr(f.rd), fpr(f.rt)],
+    ))
+}
+
+/// Indexed loads and stores and fused multiply-add, removed in release 6.
+pub(crate) fn decode_cop1x(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let index = Operand::Indexed {
+        base: Register::get(f.rs),
+        index: Register::get(f.rt),
+    };
+    let (fr, ft, fs, fd) = (fpr(f.rs), fpr(f.rt), fpr(f.rd), fpr(f.sa));
+
+    let inst = match f.funct {
+        0x00 if f.rd == 0 => new("lwxc1", &[fd, index]),
+        0x01 if f.rd == 0 => new("ldxc1", &[fd, index]),
+        0x05 if f.rd == 0 => new("luxc1", &[fd, index]),
+        0x08 if f.sa == 0 => new("swxc1", &[fs, index]),
+        0x09 if f.sa == 0 => new("sdxc1", &[fs, index]),
+        0x0d if f.sa == 0 => new("suxc1", &[fs, index]),
+        0x0f if f.sa == 0 => new("prefx", &[imm(f.rd), index]),
+        0x20 => new("madd.s", &[fd, fr, fs, ft]),
+        0x21 => new("madd.d", &[fd, fr, fs, ft]),
+        0x28 => new("msub.s", &[fd, fr, fs, ft]),
+        0x29 => new("msub.d", &[fd, fr, fs, ft]),
+        0x30 => new("nmadd.s", &[fd, fr, fs, ft]),
+        0x31 => new("nmadd.d", &[fd, fr, fs, ft]),
+        0x38 => new("nmsub.s", &[fd, fr, fs, ft]),
+        0x39 => new("nmsub.d", &[fd, fr, fs, ft]),
+        // paired single
+        0x1e | 0x26 | 0x2e | 0x36 | 0x3e => return Err(ErrorKind::IncompleteDecoder),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Coprocessor 2 is implementation specific, so only the moves, branches and release 6 loads
+/// and stores have a fixed encoding.
+pub(crate) fn decode_cop2(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (is_64, r6) = (decoder.is_64, decoder.is_r6);
+    let (rt, rd) = (gpr(f.rt), Operand::Cop(f.rd as u8));
+    let sel = imm(f.dword & 0b111);
+    let cop = Operand::Cop(f.rt as u8);
+    // release 6 moved the loads and stores here with an 11-bit offset
+    let offset = ((f.dword << 21) as i32 >> 21) as i64;
+
+    let inst = match f.rs {
+        0x00 => new("mfc2", &[rt, rd, sel]),
+        0x01 if is_64 => new("dmfc2", &[rt, rd, sel]),
+        0x02 => new("cfc2", &[rt, rd]),
+        0x03 => new("mfhc2", &[rt, rd, sel]),
+        0x04 => new("mtc2", &[rt, rd, sel]),
+        0x05 if is_64 => new("dmtc2", &[rt, rd, sel]),
+        0x06 => new("ctc2", &[rt, rd]),
+        0x07 => new("mthc2", &[rt, rd, sel]),
+        0x08 if !r6 => {
+            let mnemomic = match f.rt & 0b11 {
+                0b00 => "bc2f",
+                0b01 => "bc2t",
+                0b10 => "bc2fl",
+                _ => "bc2tl",
+            };
+
+            match f.rt >> 2 {
+                0 => new(mnemomic, &[branch(f.dword, 16)]),
+                cc => new(mnemomic, &[imm(cc), branch(f.dword, 16)]),
+            }
+        }
+        0x09 if r6 => new("bc2eqz", &[cop, branch(f.dword, 16)]),
+        0x0d if r6 => new("bc2nez", &[cop, branch(f.dword, 16)]),
+        0x0a if r6 => new("lwc2", &[cop, mem(f.rd, offset)]),
+        0x0b if r6 => new("swc2", &[cop, mem(f.rd, offset)]),
+        0x0e if r6 => new("ldc2", &[cop, mem(f.rd, offset)]),
+        0x0f if r6 => new("sdc2", &[cop, mem(f.rd, offset)]),
+        0x10..=0x1f => new("c2", &[imm(f.dword & 0x1ff_ffff)]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
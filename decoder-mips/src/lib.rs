@@ -1,25 +1,15 @@
-//! MIPS V disassembler.
+//! MIPS32/MIPS64 disassembler, covering release 2 and release 6 encodings and the compressed
+//! microMIPS and MIPS16e instruction sets.
 
+mod behavior;
+mod cop;
+mod micromips;
+mod mips16;
 mod tests;
 
-use decoder::{Error, ErrorKind};
 use debugvault::Index;
-use std::borrow::Cow;
-use tokenizing::{TokenStream, ColorScheme, Colors};
-
-macro_rules! operands {
-    [] => {([$crate::EMPTY_OPERAND; 3], 0)};
-    [$($x:expr),+ $(,)?] => {{
-        let mut operands = [$crate::EMPTY_OPERAND; 3];
-        let mut idx = 0;
-        $(
-            idx += 1;
-            operands[idx - 1] = $x;
-        )*
-
-        (operands, idx)
-    }};
-}
+use decoder::{Error, ErrorKind, FlowKind, MemoryAccess, ToTokens};
+use tokenizing::{ColorScheme, Colors, TokenStream};
 
 #[rustfmt::skip]
 pub const REGISTERS: [&str; 32] = [
@@ -31,6 +21,14 @@ pub const REGISTERS: [&str; 32] = [
     "k0", "k1", "gp", "sp", "fp", "ra",
 ];
 
+#[rustfmt::skip]
+pub const FLOAT_REGISTERS: [&str; 32] = [
+    "f0", "f1", "f2", "f3", "f4", "f5", "f6", "f7",
+    "f8", "f9", "f10", "f11", "f12", "f13", "f14", "f15",
+    "f16", "f17", "f18", "f19", "f20", "f21", "f22", "f23",
+    "f24", "f25", "f26", "f27", "f28", "f29", "f30", "f31",
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
 #[rustfmt::skip]
@@ -43,454 +41,814 @@ pub enum Register {
     K0, K1, Gp, Sp, Fp, Ra
 }
 
+impl Register {
+    /// Register encoded in a 5-bit field.
+    fn get(num: u32) -> Self {
+        unsafe { std::mem::transmute::<u32, Register>(num & 0b11111) }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        REGISTERS[self as usize]
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum Operand {
+    /// General purpose register.
     Register(Register),
+    /// Floating point register.
+    Float(u8),
+    /// Coprocessor or hardware register only known by it's number (e.g. `$12` for `Status`).
+    Cop(u8),
+    /// Floating point condition code.
+    Fcc(u8),
     Immediate(i64),
+    /// Memory at a base register plus an offset, e.g. `-0x10(sp)`.
+    Memory {
+        base: Register,
+        offset: i64,
+    },
+    /// Memory at a base register plus an index register, e.g. `a1(a0)`.
+    Indexed {
+        base: Register,
+        index: Register,
+    },
+    /// Target of a branch, relative to the instruction until it's address is known.
+    Branch(i64),
+    /// Target of a jump within the current region of `1 << region` bytes, absolute once the
+    /// instruction's address is known.
+    Jump {
+        target: u64,
+        region: u8,
+    },
+    /// Set of general purpose registers saved or restored together, e.g. `s0-s3, ra`.
+    List(u32),
     #[default]
     Nothing,
 }
 
-enum Format {
-    R,
-    I,
-    J,
+impl ToTokens for Operand {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        match *self {
+            Self::Register(reg) => stream.push(reg.as_str(), Colors::register()),
+            Self::Float(reg) => stream.push(FLOAT_REGISTERS[reg as usize], Colors::register()),
+            Self::Cop(reg) => stream.push_owned(format!("${reg}"), Colors::register()),
+            Self::Fcc(cc) => stream.push_owned(format!("fcc{cc}"), Colors::register()),
+            Self::Immediate(imm) => stream.push_owned(hex(imm), Colors::immediate()),
+            Self::Memory { base, offset } => {
+                stream.push_owned(hex(offset), Colors::immediate());
+                stream.push("(", Colors::brackets());
+                stream.push(base.as_str(), Colors::register());
+                stream.push(")", Colors::brackets());
+            }
+            Self::Indexed { base, index } => {
+                stream.push(index.as_str(), Colors::register());
+                stream.push("(", Colors::brackets());
+                stream.push(base.as_str(), Colors::register());
+                stream.push(")", Colors::brackets());
+            }
+            Self::Branch(target) => tokenize_target(stream, symbols, target as usize),
+            Self::Jump { target, .. } => tokenize_target(stream, symbols, target as usize),
+            Self::List(regs) => tokenize_list(stream, regs),
+            Self::Nothing => unreachable!("empty operand encountered"),
+        }
+    }
 }
 
-struct TableInstruction {
-    mnemomic: &'static str,
-    #[allow(dead_code)]
-    desc: &'static str,
-    format: &'static [usize],
+fn tokenize_target(stream: &mut TokenStream, symbols: &Index, target: usize) {
+    match symbols.get_sym_by_addr(target) {
+        Some(symbol) => {
+            for token in symbol.name() {
+                stream.push_token(token.clone());
+            }
+        }
+        None => stream.push_owned(format!("{target:#x}"), Colors::immediate()),
+    }
+}
+
+/// Registers in ascending order, with runs of consecutive registers written as a range.
+fn tokenize_list(stream: &mut TokenStream, regs: u32) {
+    let mut reg = 0;
+    let mut first = true;
+
+    while reg < 32 {
+        if regs & (1 << reg) == 0 {
+            reg += 1;
+            continue;
+        }
+
+        // `ra` is always listed on it's own
+        let mut last = reg;
+        while last + 1 < Register::Ra as usize && regs & (1 << (last + 1)) != 0 {
+            last += 1;
+        }
+
+        if !first {
+            stream.push(", ", Colors::expr());
+        }
+
+        stream.push(REGISTERS[reg], Colors::register());
+        if last != reg {
+            stream.push("-", Colors::expr());
+            stream.push(REGISTERS[last], Colors::register());
+        }
+
+        first = false;
+        reg = last + 1;
+    }
+}
+
+/// Signed hexadecimal, e.g. `-0x10`.
+fn hex(imm: i64) -> String {
+    if imm < 0 {
+        format!("-{:#x}", imm.unsigned_abs())
+    } else {
+        format!("{imm:#x}")
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Instruction {
     mnemomic: &'static str,
-    operands: [Cow<'static, str>; 3],
+    operands: [Operand; 4],
     operand_count: usize,
+    width: u8,
 }
 
-impl decoder::Decoded for Instruction {
-    fn width(&self) -> usize {
-        4
+impl Instruction {
+    fn new(mnemomic: &'static str, operands: &[Operand]) -> Self {
+        let mut inst = Self {
+            mnemomic,
+            operands: [Operand::Nothing; 4],
+            operand_count: operands.len(),
+            width: 4,
+        };
+
+        inst.operands[..operands.len()].copy_from_slice(operands);
+        inst
     }
 
-    fn update_rel_addrs(&mut self, _: usize, _: Option<&Instruction>) {}
-}
+    /// Instruction with a different encoded size, for the compressed instruction sets.
+    fn with_width(mut self, width: u8) -> Self {
+        self.width = width;
+        self
+    }
 
-#[derive(Default)]
-pub struct Decoder;
+    pub fn mnemomic(&self) -> &'static str {
+        self.mnemomic
+    }
 
-impl decoder::Decodable for Decoder {
-    type Instruction = Instruction;
+    pub fn operands(&self) -> &[Operand] {
+        &self.operands[..self.operand_count]
+    }
+}
 
-    fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
-        decode(reader).map_err(|err| Error::new(err, 4))
+impl decoder::Decoded for Instruction {
+    fn width(&self) -> usize {
+        self.width as usize
     }
 
-    fn max_width(&self) -> usize {
-        4
+    fn tokenize_in(&self, stream: &mut TokenStream, symbols: &Index, dialect: decoder::Dialect) {
+        self.tokenize_with(stream, symbols, dialect.pseudo)
     }
-}
 
-fn decode(reader: &mut decoder::Reader) -> Result<Instruction, ErrorKind> {
-    let mut bytes = [0u8; 4];
-    reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
-    let dword = u32::from_be_bytes(bytes) as usize;
-
-    // nop instruction isn't included in any MIPS spec
-    if dword == 0b00000000_00000000_00000000_00000000 {
-        let (operands, operand_count) = operands![];
-        return Ok(Instruction {
-            mnemomic: "nop",
-            operands,
-            operand_count,
-        });
+    fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+        // jump regions are those of the instruction following the jump (the delay slot)
+        let next = addr as u64 + self.width as u64;
+
+        for operand in &mut self.operands[..self.operand_count] {
+            match operand {
+                Operand::Branch(offset) => *offset = offset.wrapping_add(addr as i64),
+                Operand::Jump { target, region } => *target |= next & !((1 << *region) - 1),
+                _ => {}
+            }
+        }
     }
 
-    // break instruction has a unique instruction format
-    if dword & 0b111111 == 0b001101 {
-        let (operands, operand_count) = operands![];
-        return Ok(Instruction {
-            mnemomic: "break",
-            operands,
-            operand_count,
-        });
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
     }
 
-    let mut operands = [EMPTY_OPERAND; 3];
-    let opcode = dword >> 26;
-    let funct = dword & 0b111111;
-
-    let (format, inst) = match opcode {
-        0 => (
-            Format::R,
-            R_TYPES.get(funct).ok_or(ErrorKind::InvalidOpcode)?,
-        ),
-        2 | 3 => (
-            Format::J,
-            J_TYPES.get(opcode).ok_or(ErrorKind::InvalidOpcode)?,
-        ),
-        _ => (
-            Format::I,
-            I_TYPES.get(opcode).ok_or(ErrorKind::InvalidOpcode)?,
-        ),
-    };
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
 
-    let rs = dword >> 21 & 0b11111;
-    let rt = dword >> 16 & 0b11111;
-    let rd = dword >> 11 & 0b11111;
+    fn regs_read(&self) -> Vec<&'static str> {
+        behavior::registers(self).0
+    }
 
-    if inst.mnemomic.is_empty() {
-        return Err(ErrorKind::IncompleteDecoder);
+    fn regs_written(&self) -> Vec<&'static str> {
+        behavior::registers(self).1
     }
 
-    match format {
-        Format::R => {
-            match (REGISTERS.get(rs), REGISTERS.get(rt), REGISTERS.get(rd)) {
-                (Some(_), Some(_), Some(_)) => {}
-                _ => return Err(ErrorKind::InvalidRegister),
-            }
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
+}
 
-            let shamt = dword >> 6 & 0b11111;
-
-            for idx in 0..inst.format.len() {
-                // index into next operand
-                let mask = inst.format[idx];
-
-                // operand specified by the bitmask
-                let operand = match mask {
-                    0 => rd,
-                    1 => rt,
-                    2 => rs,
-                    3 => shamt,
-                    _ => unsafe { core::hint::unreachable_unchecked() },
-                };
-
-                if operand == shamt {
-                    operands[idx] = Cow::Owned(format!("0x{shamt:x}"));
-                } else {
-                    operands[idx] = Cow::Borrowed(REGISTERS[operand]);
-                }
-            }
+/// Instruction set the processor is executing, switched by jumping to an odd address
+/// (compressed) or by `jalx`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Isa {
+    #[default]
+    Mips,
+    /// Mixed 16 and 32-bit encodings replacing the standard ones on microMIPS processors.
+    MicroMips,
+    /// Mixed 16 and 32-bit encodings, executed alongside the standard ones.
+    Mips16,
+}
+
+/// Which encodings to decode, defaulting to big-endian MIPS32 release 2.
+#[derive(Default, Clone, Copy)]
+pub struct Decoder {
+    /// Also decode MIPS64 instructions.
+    pub is_64: bool,
+    /// Decode release 6 encodings, which reuse the opcodes of removed instructions.
+    pub is_r6: bool,
+    /// Instructions are stored little-endian (`mipsel`).
+    pub little_endian: bool,
+    /// Instruction set to decode.
+    pub isa: Isa,
+}
 
-            Ok(Instruction {
-                mnemomic: inst.mnemomic,
-                operands,
-                operand_count: inst.format.len(),
-            })
+impl decoder::Decodable for Decoder {
+    type Instruction = Instruction;
+
+    fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
+        match self.isa {
+            Isa::Mips => decode(reader, self).map_err(|err| Error::new(err, 4)),
+            Isa::MicroMips => micromips::decode(reader, self),
+            Isa::Mips16 => mips16::decode(reader, self),
         }
-        Format::I => {
-            match (REGISTERS.get(rs), REGISTERS.get(rt)) {
-                (Some(_), Some(_)) => {}
-                _ => return Err(ErrorKind::InvalidRegister),
-            }
+    }
 
-            let immediate = dword & 0b11111111_11111111;
-
-            // check if the instruction uses an offset (load/store instructions)
-            if inst.format == [1, 3, 2] {
-                let (operands, operand_count) = operands![
-                    Cow::Borrowed(REGISTERS[rt]),
-                    Cow::Borrowed(REGISTERS[rs]),
-                    Cow::Owned(format!("{immediate:#x}")),
-                ];
-
-                return Ok(Instruction {
-                    mnemomic: inst.mnemomic,
-                    operands,
-                    operand_count,
-                });
-            }
+    fn max_width(&self) -> usize {
+        4
+    }
+}
 
-            for idx in 0..inst.format.len() {
-                // index into next operand
-                let mask = inst.format[idx];
-
-                // operand specified by the bitmask
-                let operand = match mask {
-                    0 => rd,
-                    1 => rt,
-                    2 => rs,
-                    3 => immediate,
-                    _ => unsafe { core::hint::unreachable_unchecked() },
-                };
-
-                if operand == immediate {
-                    operands[idx] = Cow::Owned(format!("0x{immediate:x}"));
-                } else {
-                    operands[idx] = Cow::Borrowed(REGISTERS[operand]);
-                }
-            }
+/// Fields of an instruction word, not all of them are meaningful for every format.
+#[derive(Clone, Copy)]
+struct Fields {
+    dword: u32,
+    rs: u32,
+    rt: u32,
+    rd: u32,
+    sa: u32,
+    funct: u32,
+    /// Sign extended 16-bit immediate.
+    imm: i64,
+    /// Zero extended 16-bit immediate.
+    uimm: i64,
+}
 
-            Ok(Instruction {
-                mnemomic: inst.mnemomic,
-                operands,
-                operand_count: inst.format.len(),
-            })
-        }
-        Format::J => {
-            let immediate = dword & 0b11111111_11111111_11111111;
-            let (operands, operand_count) = operands![Cow::Owned(format!("0x{immediate:x}"))];
-
-            Ok(Instruction {
-                mnemomic: inst.mnemomic,
-                operands,
-                operand_count,
-            })
+impl Fields {
+    fn new(dword: u32) -> Self {
+        Self {
+            dword,
+            rs: dword >> 21 & 0b11111,
+            rt: dword >> 16 & 0b11111,
+            rd: dword >> 11 & 0b11111,
+            sa: dword >> 6 & 0b11111,
+            funct: dword & 0b111111,
+            imm: dword as u16 as i16 as i64,
+            uimm: dword as u16 as i64,
         }
     }
 }
 
-impl decoder::ToTokens for Instruction {
-    fn tokenize(&self, stream: &mut TokenStream, _: &Index) {
-        stream.push(self.mnemomic, Colors::opcode());
+fn gpr(num: u32) -> Operand {
+    Operand::Register(Register::get(num))
+}
 
-        // there are operands
-        if self.operand_count > 0 {
-            stream.push(" ", Colors::spacing());
+fn fpr(num: u32) -> Operand {
+    Operand::Float(num as u8 & 0b11111)
+}
 
-            // iterate through operands
-            for idx in 0..self.operand_count {
-                let operand = self.operands[idx].clone();
+fn imm(imm: impl Into<i64>) -> Operand {
+    Operand::Immediate(imm.into())
+}
 
-                match operand {
-                    Cow::Owned(s) => stream.push_owned(s, Colors::immediate()),
-                    Cow::Borrowed(s) => stream.push(s, Colors::register()),
-                };
+fn mem(base: u32, offset: i64) -> Operand {
+    Operand::Memory {
+        base: Register::get(base),
+        offset,
+    }
+}
 
-                // separator
-                if idx != self.operand_count - 1 {
-                    stream.push(", ", Colors::expr());
-                }
-            }
-        }
+/// Jump to a word aligned index within the current 256 MB region.
+fn jump(index: u32) -> Operand {
+    Operand::Jump {
+        target: (index as u64) << 2,
+        region: 28,
     }
 }
 
-/// Bitmask for order of operands [rd, rt, rs, imm].
-macro_rules! mips {
-    () => {
-        $crate::TableInstruction {
-            mnemomic: "",
-            desc: "",
-            format: &[],
-        }
-    };
+/// Branch target of a sign extended, word aligned offset of `bits` bits.
+fn branch(dword: u32, bits: u32) -> Operand {
+    let offset = ((dword << (32 - bits)) as i32 >> (32 - bits)) as i64;
+    Operand::Branch((offset << 2) + 4)
+}
 
-    ($mnemomic:literal : $desc:literal, rd, rt, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[0, 1, 3],
-        }
-    };
+/// Next halfword of an instruction in the byte order of the decoder.
+fn read_half(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<u16, ErrorKind> {
+    let mut bytes = [0u8; 2];
+    reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+    Ok(if decoder.little_endian {
+        u16::from_le_bytes(bytes)
+    } else {
+        u16::from_be_bytes(bytes)
+    })
+}
 
-    ($mnemomic:literal : $desc:literal, rt, rs, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[1, 2, 3],
-        }
+fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let mut bytes = [0u8; 4];
+    reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+    let dword = if decoder.little_endian {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
     };
 
-    ($mnemomic:literal : $desc:literal, rs, rt, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[2, 1, 3],
-        }
-    };
+    decode_word(dword, decoder)
+}
 
-    ($mnemomic:literal : $desc:literal, rd, rt, rs) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[0, 1, 2],
-        }
+fn decode_word(dword: u32, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let f = Fields::new(dword);
+    let (is_64, r6) = (decoder.is_64, decoder.is_r6);
+    let new = Instruction::new;
+
+    let inst = match dword >> 26 {
+        0x00 => return decode_special(f, decoder),
+        0x01 => return decode_regimm(f, decoder),
+        0x02 => new("j", &[jump(dword & 0x3ff_ffff)]),
+        0x03 => new("jal", &[jump(dword & 0x3ff_ffff)]),
+        0x04 => new("beq", &[gpr(f.rs), gpr(f.rt), branch(dword, 16)]),
+        0x05 => new("bne", &[gpr(f.rs), gpr(f.rt), branch(dword, 16)]),
+        0x06 if f.rt == 0 => new("blez", &[gpr(f.rs), branch(dword, 16)]),
+        0x07 if f.rt == 0 => new("bgtz", &[gpr(f.rs), branch(dword, 16)]),
+        0x06 | 0x07 | 0x08 | 0x16 | 0x17 | 0x18 if r6 => return decode_compact_branch(f),
+        0x08 => new("addi", &[gpr(f.rt), gpr(f.rs), imm(f.imm)]),
+        0x09 => new("addiu", &[gpr(f.rt), gpr(f.rs), imm(f.imm)]),
+        0x0a => new("slti", &[gpr(f.rt), gpr(f.rs), imm(f.imm)]),
+        0x0b => new("sltiu", &[gpr(f.rt), gpr(f.rs), imm(f.imm)]),
+        0x0c => new("andi", &[gpr(f.rt), gpr(f.rs), imm(f.uimm)]),
+        0x0d => new("ori", &[gpr(f.rt), gpr(f.rs), imm(f.uimm)]),
+        0x0e => new("xori", &[gpr(f.rt), gpr(f.rs), imm(f.uimm)]),
+        0x0f if f.rs == 0 => new("lui", &[gpr(f.rt), imm(f.uimm)]),
+        0x0f if r6 => new("aui", &[gpr(f.rt), gpr(f.rs), imm(f.uimm)]),
+        0x10 => return cop::decode_cop0(f, decoder),
+        0x11 => return cop::decode_cop1(f, decoder),
+        0x12 => return cop::decode_cop2(f, decoder),
+        0x13 if !r6 => return cop::decode_cop1x(f),
+        0x14 if !r6 => new("beql", &[gpr(f.rs), gpr(f.rt), branch(dword, 16)]),
+        0x15 if !r6 => new("bnel", &[gpr(f.rs), gpr(f.rt), branch(dword, 16)]),
+        0x16 if f.rt == 0 => new("blezl", &[gpr(f.rs), branch(dword, 16)]),
+        0x17 if f.rt == 0 => new("bgtzl", &[gpr(f.rs), branch(dword, 16)]),
+        0x18 if is_64 => new("daddi", &[gpr(f.rt), gpr(f.rs), imm(f.imm)]),
+        0x19 if is_64 => new("daddiu", &[gpr(f.rt), gpr(f.rs), imm(f.imm)]),
+        0x1a if is_64 && !r6 => new("ldl", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x1b if is_64 && !r6 => new("ldr", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x1c if !r6 => return decode_special2(f, decoder),
+        0x1d if !r6 => new("jalx", &[jump(dword & 0x3ff_ffff)]),
+        0x1d if is_64 && f.rs != 0 => new("daui", &[gpr(f.rt), gpr(f.rs), imm(f.uimm)]),
+        // MSA
+        0x1e => return Err(ErrorKind::IncompleteDecoder),
+        0x1f => return decode_special3(f, decoder),
+        0x20 => new("lb", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x21 => new("lh", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x22 if !r6 => new("lwl", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x23 => new("lw", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x24 => new("lbu", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x25 => new("lhu", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x26 if !r6 => new("lwr", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x27 if is_64 => new("lwu", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x28 => new("sb", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x29 => new("sh", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x2a if !r6 => new("swl", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x2b => new("sw", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x2c if is_64 && !r6 => new("sdl", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x2d if is_64 && !r6 => new("sdr", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x2e if !r6 => new("swr", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x2f if !r6 => new("cache", &[imm(f.rt), mem(f.rs, f.imm)]),
+        0x30 if !r6 => new("ll", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x31 => new("lwc1", &[fpr(f.rt), mem(f.rs, f.imm)]),
+        0x32 if r6 => new("bc", &[branch(dword, 26)]),
+        0x33 if !r6 => new("pref", &[imm(f.rt), mem(f.rs, f.imm)]),
+        0x34 if is_64 && !r6 => new("lld", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x35 => new("ldc1", &[fpr(f.rt), mem(f.rs, f.imm)]),
+        0x36 if r6 && f.rs == 0 => new("jic", &[gpr(f.rt), imm(f.imm)]),
+        0x36 if r6 => new("beqzc", &[gpr(f.rs), branch(dword, 21)]),
+        0x37 if is_64 => new("ld", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x38 if !r6 => new("sc", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x39 => new("swc1", &[fpr(f.rt), mem(f.rs, f.imm)]),
+        0x3a if r6 => new("balc", &[branch(dword, 26)]),
+        0x3b if r6 => return decode_pcrel(f, decoder),
+        0x3c if is_64 && !r6 => new("scd", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x3d => new("sdc1", &[fpr(f.rt), mem(f.rs, f.imm)]),
+        0x3e if r6 && f.rs == 0 => new("jialc", &[gpr(f.rt), imm(f.imm)]),
+        0x3e if r6 => new("bnezc", &[gpr(f.rs), branch(dword, 21)]),
+        0x3f if is_64 => new("sd", &[gpr(f.rt), mem(f.rs, f.imm)]),
+        0x32 => new("lwc2", &[Operand::Cop(f.rt as u8), mem(f.rs, f.imm)]),
+        0x36 => new("ldc2", &[Operand::Cop(f.rt as u8), mem(f.rs, f.imm)]),
+        0x3a => new("swc2", &[Operand::Cop(f.rt as u8), mem(f.rs, f.imm)]),
+        0x3e => new("sdc2", &[Operand::Cop(f.rt as u8), mem(f.rs, f.imm)]),
+        _ => return Err(ErrorKind::InvalidOpcode),
     };
 
-    ($mnemomic:literal : $desc:literal, rd, rs, rt) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[0, 2, 1],
-        }
-    };
+    Ok(inst)
+}
 
-    ($mnemomic:literal : $desc:literal, rt, imm, rs) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[1, 3, 2],
-        }
+/// Release 6 compact branches, which took over the opcodes of `addi`, `daddi`, the likely
+/// branches and the non-zero `rt` forms of `blez` and `bgtz`.
+fn decode_compact_branch(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rs, rt) = (gpr(f.rs), gpr(f.rt));
+    let target = branch(f.dword, 16);
+
+    let inst = match f.dword >> 26 {
+        0x06 if f.rs == 0 => new("blezalc", &[rt, target]),
+        0x06 if f.rs == f.rt => new("bgezalc", &[rt, target]),
+        0x06 => new("bgeuc", &[rs, rt, target]),
+        0x07 if f.rs == 0 => new("bgtzalc", &[rt, target]),
+        0x07 if f.rs == f.rt => new("bltzalc", &[rt, target]),
+        0x07 => new("bltuc", &[rs, rt, target]),
+        0x08 if f.rs >= f.rt => new("bovc", &[rs, rt, target]),
+        0x08 if f.rs == 0 => new("beqzalc", &[rt, target]),
+        0x08 => new("beqc", &[rs, rt, target]),
+        0x16 if f.rt == 0 => return Err(ErrorKind::InvalidOpcode),
+        0x16 if f.rs == 0 => new("blezc", &[rt, target]),
+        0x16 if f.rs == f.rt => new("bgezc", &[rt, target]),
+        0x16 => new("bgec", &[rs, rt, target]),
+        0x17 if f.rt == 0 => return Err(ErrorKind::InvalidOpcode),
+        0x17 if f.rs == 0 => new("bgtzc", &[rt, target]),
+        0x17 if f.rs == f.rt => new("bltzc", &[rt, target]),
+        0x17 => new("bltc", &[rs, rt, target]),
+        0x18 if f.rs >= f.rt => new("bnvc", &[rs, rt, target]),
+        0x18 if f.rs == 0 => new("bnezalc", &[rt, target]),
+        0x18 => new("bnec", &[rs, rt, target]),
+        _ => return Err(ErrorKind::InvalidOpcode),
     };
 
-    ($mnemomic:literal : $desc:literal, rs, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[2, 3],
-        }
-    };
+    Ok(inst)
+}
 
-    ($mnemomic:literal : $desc:literal, rt, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[1, 3],
-        }
+/// Release 6 PC-relative instructions.
+fn decode_pcrel(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let rs = gpr(f.rs);
+    let imm19 = ((f.dword << 13) as i32 >> 13) as i64;
+    let imm18 = ((f.dword << 14) as i32 >> 14) as i64;
+
+    let inst = match f.dword >> 16 & 0b11111 {
+        0b11110 => new("auipc", &[rs, imm(f.uimm)]),
+        0b11111 => new("aluipc", &[rs, imm(f.uimm)]),
+        op => match op >> 3 {
+            0b00 => new("addiupc", &[rs, imm(imm19 << 2)]),
+            0b01 => new("lwpc", &[rs, imm(imm19 << 2)]),
+            0b10 if decoder.is_64 => new("lwupc", &[rs, imm(imm19 << 2)]),
+            0b11 if decoder.is_64 && op >> 2 & 1 == 0 => new("ldpc", &[rs, imm(imm18 << 3)]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
     };
 
-    ($mnemomic:literal : $desc:literal, rd, rs) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[0, 2],
-        }
-    };
+    Ok(inst)
+}
 
-    ($mnemomic:literal : $desc:literal, rs, rt) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[2, 1],
-        }
-    };
+fn decode_special(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (is_64, r6) = (decoder.is_64, decoder.is_r6);
+    let (rs, rt, rd, sa) = (gpr(f.rs), gpr(f.rt), gpr(f.rd), imm(f.sa));
+
+    // most instructions require the fields they don't use to be zero
+    let no_rs = f.rs == 0;
+    let no_sa = f.sa == 0;
 
-    ($mnemomic:literal : $desc:literal, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[3],
+    let inst = match f.funct {
+        0x00 if no_rs => new("sll", &[rd, rt, sa]),
+        0x01 if !r6 && no_sa && f.rt & 0b10 == 0 => {
+            let cc = Operand::Fcc((f.rt >> 2) as u8);
+            new(if f.rt & 1 == 0 { "movf" } else { "movt" }, &[rd, rs, cc])
+        }
+        0x02 if no_rs => new("srl", &[rd, rt, sa]),
+        0x02 if f.rs == 1 => new("rotr", &[rd, rt, sa]),
+        0x03 if no_rs => new("sra", &[rd, rt, sa]),
+        0x04 if no_sa => new("sllv", &[rd, rt, rs]),
+        0x05 if r6 && f.sa >> 2 == 0 => new("lsa", &[rd, rs, rt, imm((f.sa & 0b11) + 1)]),
+        0x06 if no_sa => new("srlv", &[rd, rt, rs]),
+        0x06 if f.sa == 1 => new("rotrv", &[rd, rt, rs]),
+        0x07 if no_sa => new("srav", &[rd, rt, rs]),
+        0x08 if !r6 && f.rt == 0 && f.rd == 0 => match f.sa {
+            0b00000 => new("jr", &[rs]),
+            0b10000 => new("jr.hb", &[rs]),
+            _ => return Err(ErrorKind::InvalidOperand),
+        },
+        0x09 if f.rt == 0 => match f.sa {
+            0b00000 => new("jalr", &[rd, rs]),
+            0b10000 => new("jalr.hb", &[rd, rs]),
+            _ => return Err(ErrorKind::InvalidOperand),
+        },
+        0x0a if !r6 && no_sa => new("movz", &[rd, rs, rt]),
+        0x0b if !r6 && no_sa => new("movn", &[rd, rs, rt]),
+        0x0c => code("syscall", f.dword >> 6 & 0xfffff),
+        // unlike the other exceptions, the code is split in two 10-bit halves
+        0x0d => match (f.dword >> 16 & 0x3ff, f.dword >> 6 & 0x3ff) {
+            (0, 0) => new("break", &[]),
+            (code, 0) => new("break", &[imm(code)]),
+            (code, extra) => new("break", &[imm(code), imm(extra)]),
+        },
+        0x0e if r6 => code("sdbbp", f.dword >> 6 & 0xfffff),
+        0x0f if f.dword >> 11 == 0 => match f.sa {
+            0 => new("sync", &[]),
+            stype => new("sync", &[imm(stype)]),
+        },
+        0x10 if !r6 && no_rs && f.rt == 0 && no_sa => new("mfhi", &[rd]),
+        0x11 if !r6 && f.rt == 0 && f.rd == 0 && no_sa => new("mthi", &[rs]),
+        0x12 if !r6 && no_rs && f.rt == 0 && no_sa => new("mflo", &[rd]),
+        0x13 if !r6 && f.rt == 0 && f.rd == 0 && no_sa => new("mtlo", &[rs]),
+        0x10 if r6 && f.rt == 0 && f.sa == 1 => new("clz", &[rd, rs]),
+        0x11 if r6 && f.rt == 0 && f.sa == 1 => new("clo", &[rd, rs]),
+        0x12 if r6 && is_64 && f.rt == 0 && f.sa == 1 => new("dclz", &[rd, rs]),
+        0x13 if r6 && is_64 && f.rt == 0 && f.sa == 1 => new("dclo", &[rd, rs]),
+        0x14 if is_64 && no_sa => new("dsllv", &[rd, rt, rs]),
+        0x15 if r6 && is_64 && f.sa >> 2 == 0 => new("dlsa", &[rd, rs, rt, imm((f.sa & 0b11) + 1)]),
+        0x16 if is_64 && no_sa => new("dsrlv", &[rd, rt, rs]),
+        0x16 if is_64 && f.sa == 1 => new("drotrv", &[rd, rt, rs]),
+        0x17 if is_64 && no_sa => new("dsrav", &[rd, rt, rs]),
+        0x18..=0x1f if !r6 && f.rd == 0 && no_sa => {
+            let mnemomic = match f.funct {
+                0x18 => "mult",
+                0x19 => "multu",
+                0x1a => "div",
+                0x1b => "divu",
+                0x1c if is_64 => "dmult",
+                0x1d if is_64 => "dmultu",
+                0x1e if is_64 => "ddiv",
+                0x1f if is_64 => "ddivu",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            new(mnemomic, &[rs, rt])
+        }
+        0x18..=0x1f if r6 && (f.sa == 2 || f.sa == 3) => {
+            let mnemomic = match (f.funct, f.sa) {
+                (0x18, 2) => "mul",
+                (0x18, 3) => "muh",
+                (0x19, 2) => "mulu",
+                (0x19, 3) => "muhu",
+                (0x1a, 2) => "div",
+                (0x1a, 3) => "mod",
+                (0x1b, 2) => "divu",
+                (0x1b, 3) => "modu",
+                (0x1c, 2) if is_64 => "dmul",
+                (0x1c, 3) if is_64 => "dmuh",
+                (0x1d, 2) if is_64 => "dmulu",
+                (0x1d, 3) if is_64 => "dmuhu",
+                (0x1e, 2) if is_64 => "ddiv",
+                (0x1e, 3) if is_64 => "dmod",
+                (0x1f, 2) if is_64 => "ddivu",
+                (0x1f, 3) if is_64 => "dmodu",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            new(mnemomic, &[rd, rs, rt])
+        }
+        0x20..=0x2f if no_sa => {
+            let mnemomic = match f.funct {
+                0x20 => "add",
+                0x21 => "addu",
+                0x22 => "sub",
+                0x23 => "subu",
+                0x24 => "and",
+                0x25 => "or",
+                0x26 => "xor",
+                0x27 => "nor",
+                0x2a => "slt",
+                0x2b => "sltu",
+                0x2c if is_64 => "dadd",
+                0x2d if is_64 => "daddu",
+                0x2e if is_64 => "dsub",
+                0x2f if is_64 => "dsubu",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            new(mnemomic, &[rd, rs, rt])
         }
+        0x30..=0x36 => {
+            let mnemomic = match f.funct {
+                0x30 => "tge",
+                0x31 => "tgeu",
+                0x32 => "tlt",
+                0x33 => "tltu",
+                0x34 => "teq",
+                0x35 if r6 && no_sa => return Ok(new("seleqz", &[rd, rs, rt])),
+                0x36 => "tne",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            match f.dword >> 6 & 0b11_1111_1111 {
+                0 => new(mnemomic, &[rs, rt]),
+                code => new(mnemomic, &[rs, rt, imm(code)]),
+            }
+        }
+        0x37 if r6 && no_sa => new("selnez", &[rd, rs, rt]),
+        0x38 if is_64 && no_rs => new("dsll", &[rd, rt, sa]),
+        0x3a if is_64 && no_rs => new("dsrl", &[rd, rt, sa]),
+        0x3a if is_64 && f.rs == 1 => new("drotr", &[rd, rt, sa]),
+        0x3b if is_64 && no_rs => new("dsra", &[rd, rt, sa]),
+        0x3c if is_64 && no_rs => new("dsll32", &[rd, rt, sa]),
+        0x3e if is_64 && no_rs => new("dsrl32", &[rd, rt, sa]),
+        0x3e if is_64 && f.rs == 1 => new("drotr32", &[rd, rt, sa]),
+        0x3f if is_64 && no_rs => new("dsra32", &[rd, rt, sa]),
+        _ => return Err(ErrorKind::InvalidOpcode),
     };
 
-    ($mnemomic:literal : $desc:literal, rs) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[2],
-        }
+    Ok(inst)
+}
+
+/// Instructions with an optional 20-bit code for the exception handler.
+fn code(mnemomic: &'static str, code: u32) -> Instruction {
+    match code {
+        0 => Instruction::new(mnemomic, &[]),
+        code => Instruction::new(mnemomic, &[imm(code)]),
+    }
+}
+
+fn decode_regimm(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (is_64, r6) = (decoder.is_64, decoder.is_r6);
+    let rs = gpr(f.rs);
+    let target = branch(f.dword, 16);
+
+    let inst = match f.rt {
+        0x00 => new("bltz", &[rs, target]),
+        0x01 => new("bgez", &[rs, target]),
+        0x02 if !r6 => new("bltzl", &[rs, target]),
+        0x03 if !r6 => new("bgezl", &[rs, target]),
+        0x06 if r6 && is_64 => new("dahi", &[rs, imm(f.uimm)]),
+        0x08 if !r6 => new("tgei", &[rs, imm(f.imm)]),
+        0x09 if !r6 => new("tgeiu", &[rs, imm(f.imm)]),
+        0x0a if !r6 => new("tlti", &[rs, imm(f.imm)]),
+        0x0b if !r6 => new("tltiu", &[rs, imm(f.imm)]),
+        0x0c if !r6 => new("teqi", &[rs, imm(f.imm)]),
+        0x0e if !r6 => new("tnei", &[rs, imm(f.imm)]),
+        0x10 if !r6 || f.rs == 0 => new("bltzal", &[rs, target]),
+        0x11 if !r6 || f.rs == 0 => new("bgezal", &[rs, target]),
+        0x12 if !r6 => new("bltzall", &[rs, target]),
+        0x13 if !r6 => new("bgezall", &[rs, target]),
+        0x17 if r6 && f.rs == 0 => new("sigrie", &[imm(f.uimm)]),
+        0x1e if r6 && is_64 => new("dati", &[rs, imm(f.uimm)]),
+        0x1f => new("synci", &[mem(f.rs, f.imm)]),
+        _ => return Err(ErrorKind::InvalidOpcode),
     };
 
-    ($mnemomic:literal : $desc:literal, rd) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[0],
-        }
+    Ok(inst)
+}
+
+/// Pre-release 6 multiply-accumulate, count leading bits and debug breakpoint.
+fn decode_special2(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rs, rt, rd) = (gpr(f.rs), gpr(f.rt), gpr(f.rd));
+
+    let inst = match f.funct {
+        0x00 if f.rd == 0 && f.sa == 0 => new("madd", &[rs, rt]),
+        0x01 if f.rd == 0 && f.sa == 0 => new("maddu", &[rs, rt]),
+        0x02 if f.sa == 0 => new("mul", &[rd, rs, rt]),
+        0x04 if f.rd == 0 && f.sa == 0 => new("msub", &[rs, rt]),
+        0x05 if f.rd == 0 && f.sa == 0 => new("msubu", &[rs, rt]),
+        0x20 if f.sa == 0 => new("clz", &[rd, rs]),
+        0x21 if f.sa == 0 => new("clo", &[rd, rs]),
+        0x24 if decoder.is_64 && f.sa == 0 => new("dclz", &[rd, rs]),
+        0x25 if decoder.is_64 && f.sa == 0 => new("dclo", &[rd, rs]),
+        0x3f => code("sdbbp", f.dword >> 6 & 0xfffff),
+        _ => return Err(ErrorKind::InvalidOpcode),
     };
 
-    ($mnemomic:literal : $desc:literal) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[],
-        }
+    Ok(inst)
+}
+
+/// Bit field, byte shuffling and, since release 6, the relocated load linked/store
+/// conditional, cache and prefetch instructions.
+fn decode_special3(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (is_64, r6) = (decoder.is_64, decoder.is_r6);
+    let (rs, rt, rd) = (gpr(f.rs), gpr(f.rt), gpr(f.rd));
+    let (msb, lsb) = (f.rd, f.sa);
+
+    // release 6 memory instructions have a 9-bit offset
+    let offset9 = ((f.dword << 16) as i32 >> 23) as i64;
+    let r6_mem = f.dword >> 6 & 1 == 0;
+
+    let inst = match f.funct {
+        0x00 => new("ext", &[rt, rs, imm(lsb), imm(msb + 1)]),
+        0x01 if is_64 => new("dextm", &[rt, rs, imm(lsb), imm(msb + 33)]),
+        0x02 if is_64 => new("dextu", &[rt, rs, imm(lsb + 32), imm(msb + 1)]),
+        0x03 if is_64 => new("dext", &[rt, rs, imm(lsb), imm(msb + 1)]),
+        0x04 if msb >= lsb => new("ins", &[rt, rs, imm(lsb), imm(msb - lsb + 1)]),
+        0x05 if is_64 => new("dinsm", &[rt, rs, imm(lsb), imm(msb + 32 - lsb + 1)]),
+        0x06 if is_64 && msb >= lsb => new("dinsu", &[rt, rs, imm(lsb + 32), imm(msb - lsb + 1)]),
+        0x07 if is_64 && msb >= lsb => new("dins", &[rt, rs, imm(lsb), imm(msb - lsb + 1)]),
+        0x20 => match f.sa {
+            0x02 if f.rs == 0 => new("wsbh", &[rd, rt]),
+            0x10 if f.rs == 0 => new("seb", &[rd, rt]),
+            0x18 if f.rs == 0 => new("seh", &[rd, rt]),
+            0x00 if r6 && f.rs == 0 => new("bitswap", &[rd, rt]),
+            0x08..=0x0b if r6 => new("align", &[rd, rs, rt, imm(f.sa & 0b11)]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        0x24 if is_64 => match f.sa {
+            0x02 if f.rs == 0 => new("dsbh", &[rd, rt]),
+            0x05 if f.rs == 0 => new("dshd", &[rd, rt]),
+            0x00 if r6 && f.rs == 0 => new("dbitswap", &[rd, rt]),
+            0x08..=0x0f if r6 => new("dalign", &[rd, rs, rt, imm(f.sa & 0b111)]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        0x25 if r6 && r6_mem => new("cache", &[imm(f.rt), mem(f.rs, offset9)]),
+        0x35 if r6 && r6_mem => new("pref", &[imm(f.rt), mem(f.rs, offset9)]),
+        0x36 if r6 && r6_mem => new("ll", &[rt, mem(f.rs, offset9)]),
+        0x37 if r6 && r6_mem && is_64 => new("lld", &[rt, mem(f.rs, offset9)]),
+        0x26 if r6 && r6_mem => new("sc", &[rt, mem(f.rs, offset9)]),
+        0x27 if r6 && r6_mem && is_64 => new("scd", &[rt, mem(f.rs, offset9)]),
+        0x3b if f.rs == 0 => match f.sa {
+            0 => new("rdhwr", &[rt, Operand::Cop(f.rd as u8)]),
+            sel if r6 && sel >> 3 == 0 => new("rdhwr", &[rt, Operand::Cop(f.rd as u8), imm(sel)]),
+            _ => return Err(ErrorKind::InvalidOperand),
+        },
+        _ => return Err(ErrorKind::InvalidOpcode),
     };
+
+    Ok(inst)
 }
 
-const I_TYPES: [TableInstruction; 44] = [
-    mips!("bgez" : "Branch to immediate if value of $rs is greater than or equal to zero", rs, imm),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!("beq" : "Branch to immediate if values of $rs and $rt are equal", rs, rt, imm),
-    mips!("bne" : "Branch to immediate if values of $rs and $rt are not equal", rs, rt, imm),
-    mips!("blez" : "Branch to immediate if value of $rs is less than or equal to zero", rs, imm),
-    mips!("bgtz" : "Branch to immediate if value of $rs is greater than or equal to zero", rs, imm),
-    mips!("addi" : "Add $rs to the immediate and store result in $rt (signed)", rt, rs, imm),
-    mips!("addiu" : "Add $rs to the immediate and store result in $rt (unsigned)", rt, rs, imm),
-    mips!("slti" : "If $rs is less then immediate, $rt is set to 1 otherwise to 0 (signed)", rt, rs, imm),
-    mips!("sltiu" : "If $rs is less then immediate, $rt is set to 1 otherwise to 0 (unsigned)", rt, rs, imm),
-    mips!("andi" : "Bitwise AND between $rs and immediate storing the result in $rt", rt, rs, imm),
-    mips!("ori" : "Bitwise OR between $rs and immediate storing the result in $rt", rt, rs, imm),
-    mips!("xori" : "Bitwise XOR between $rs and immediate storing the result in $rt", rt, rs, imm),
-    mips!("lui" : "Stores immediate in the upper 16 bits of $rt", rt, imm),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!("lb" : "Load byte from value at address in $rs with a given offset into $rt sign extending it to 32 bits (signed)", rt, imm, rs),
-    mips!("lh" : "Load 2 bytes from value at address in $rs with a given offset into $rt sign extending it to 32 bits (signed)", rt, imm, rs),
-    mips!("lwl" : "Load the most-significant part of a word as a signed value from an unaligned memory address", rt, imm, rs),
-    mips!("lw" : "Load 4 bytes from value at address in $rs with a given offset into $rt (signed)", rt, imm, rs),
-    mips!("lbu" : "Load byte from value at address in $rs with a given offset into $rt sign extending it to 32 bits (unsigned)", rt, imm, rs),
-    mips!("lhu" : "Load 2 bytes from value at address in $rs with a given offset into $rt sign extending it to 32 bits (unsigned)", rt, imm, rs),
-    mips!("lwr" : "Load the least-significant part of a word as a signed value from an unaligned memory address", rt, imm, rs),
-    mips!("lwu" : "Load 4 bytes from value at address in $rs with a given offset into $rt (unsigned)", rt, imm, rs),
-    mips!("sb" : "Store byte at address in $rs with a given offset into $rt", rt, imm, rs),
-    mips!("sh" : "Store 2 bytes at address in $rs with a given offset into $rt", rt, imm, rs),
-    mips!(),
-    mips!("sw" : "Store 4 bytes at address in $rs with a given offset into $rt", rt, imm, rs),
-];
+impl Instruction {
+    /// The pseudo-instruction this is usually written as, with the indices of the kept operands.
+    fn pseudo(&self) -> Option<(&'static str, &'static [usize])> {
+        use Operand::{Immediate, Register as Reg};
+        use Register::{Ra, Zero};
+
+        let pseudo: (&str, &[usize]) = match (self.mnemomic, self.operands()) {
+            ("sll", [Reg(Zero), Reg(Zero), Immediate(imm)]) => match imm {
+                0 => ("nop", &[]),
+                1 => ("ssnop", &[]),
+                3 => ("ehb", &[]),
+                5 => ("pause", &[]),
+                _ => return None,
+            },
+            ("move", [Reg(Zero), Reg(Zero)]) => ("nop", &[]),
+            ("addu" | "daddu" | "or", [_, _, Reg(Zero)]) => ("move", &[0, 1]),
+            ("addiu" | "ori", [_, Reg(Zero), _]) => ("li", &[0, 2]),
+            ("nor", [_, _, Reg(Zero)]) => ("not", &[0, 1]),
+            ("subu", [_, Reg(Zero), _]) => ("negu", &[0, 2]),
+            ("dsubu", [_, Reg(Zero), _]) => ("dnegu", &[0, 2]),
+            ("sub", [_, Reg(Zero), _]) => ("neg", &[0, 2]),
+            ("dsub", [_, Reg(Zero), _]) => ("dneg", &[0, 2]),
+            ("beq", [Reg(Zero), Reg(Zero), _]) => ("b", &[2]),
+            ("beq", [_, Reg(Zero), _]) => ("beqz", &[0, 2]),
+            ("bne", [_, Reg(Zero), _]) => ("bnez", &[0, 2]),
+            ("beql", [_, Reg(Zero), _]) => ("beqzl", &[0, 2]),
+            ("bnel", [_, Reg(Zero), _]) => ("bnezl", &[0, 2]),
+            ("bgezal", [Reg(Zero), _]) => ("bal", &[1]),
+            ("bltzal", [Reg(Zero), _]) => ("nal", &[]),
+            ("jalr", [Reg(Zero), _]) => ("jr", &[1]),
+            ("jalr.hb", [Reg(Zero), _]) => ("jr.hb", &[1]),
+            ("jalr", [Reg(Ra), _]) => ("jalr", &[1]),
+            ("jalr.hb", [Reg(Ra), _]) => ("jalr.hb", &[1]),
+            ("jic", [_, Immediate(0)]) => ("jrc", &[0]),
+            ("jialc", [_, Immediate(0)]) => ("jalrc", &[0]),
+            _ => return None,
+        };
+
+        Some(pseudo)
+    }
 
-const J_TYPES: [TableInstruction; 4] = [
-    mips!(),
-    mips!(),
-    mips!("j" : "Jump to target address", imm),
-    mips!("jr" : "Call the target address and save return addr in $ra", imm),
-];
+    /// Tokenize the instruction, either using pseudo-instructions or the canonical encoding.
+    pub fn tokenize_with(&self, stream: &mut TokenStream, symbols: &Index, pseudo: bool) {
+        let (mnemomic, kept) = match self.pseudo().filter(|_| pseudo) {
+            Some(pseudo) => pseudo,
+            None => (self.mnemomic, &[0, 1, 2, 3][..self.operand_count]),
+        };
 
-const R_TYPES: [TableInstruction; 44] = [
-    mips!("sll" : "Shift value in $rt `immediate` number of times to the left storing the result in $rd and zero extending the shifted bits", rd, rt, imm),
-    mips!(),
-    mips!("srl" : "Shift value in $rt `immediate` number of times to the right storing the result in $rd and zero extending the shifted bits", rd, rt, imm),
-    mips!("sra" : "Shift value in $rt `immediate` number of times to the right storing the result in $rd and sign extending the shifted bits", rd, rt, imm),
-    mips!("sllv" : "Shift value in $rt `$rs` number of times to the left storing the result in $rd and zero extending the shifted bits", rd, rt, rs),
-    mips!(),
-    mips!("srlv" : "Shift value in $rt `$rs` number of times to the right storing the result in $rd and zero extending the shifted bits", rd, rt, rs),
-    mips!("srav" : "Shift value in $rt `$rs` number of times to the right storing the result in $rd and sign extending the shifted bits", rd, rt, rs),
-    mips!("jr" : "Jump to address of $rs", rs),
-    mips!(),
-    mips!(),
-    mips!("syscall" : "Trigger exception tranfering control from user space to kernel space where the call is handled"),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!("mfhi" : "Store value from $hi (internal register used for multiplication/division) in $rd", rd),
-    mips!("mthi" : "Store value from $rs in $hi (internal register used for multiplication/division)", rs),
-    mips!("mflo" : "Store value from $lo (internal register used for multiplication/division) in $rd", rd),
-    mips!("mtlo" : "Store value from $rs in $lo (internal register used for multiplication/division)", rs),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!("mult" : "Multiply $rs and $rt (signed) storing the result in the split between $hi and $lo (internal registers)", rs, rt),
-    mips!("multu" : "Multiply $rs and $rt (unsigned) storing the result in the split between $hi and $lo (internal registers)", rs, rt),
-    mips!("div" : "Divide $rs by $rt (signed) storing the result in the split between $hi and $lo (internal registers)", rs, rt),
-    mips!("divu" : "Divide $rs by $rt (unsigned) storing the result in the split between $hi and $lo (internal registers)", rs, rt),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!("add" : "Add $rs to $rt storing the result in $rd (signed)", rd, rs, rt),
-    mips!("addu" : "Add $rs to $rt storing the result in $rd (unsigned)", rd, rs, rt),
-    mips!("sub" : "Subtract $rs from $rt storing the result in $rd (signed)", rd, rs, rt),
-    mips!("subu" : "Subtract $rs from $rt storing the result in $rd (unsigned)", rd, rs, rt),
-    mips!("and" : "Bitwise AND between $rs and $rt storing the result in $rd", rd, rs, rt),
-    mips!("or" : "Bitwise OR between $rs and $rt storing the result in $rd", rd, rs, rt),
-    mips!("xor" : "Bitwise XOR between $rs and $rt storing the result in $rd", rd, rs, rt),
-    mips!("nor" : "Bitwise NOR between $rs and $rt storing the result in $rd", rd, rs, rt),
-    mips!(),
-    mips!(),
-    mips!("slt" : "If $rs is less then $rt, $rd is set to 1 otherwise to 0 (signed)", rd, rs, rt),
-    mips!("sltu" : "If $rs is less then $rt, $rd is set to 1 otherwise to 0 (unsigned)", rd, rs, rt),
-];
+        stream.push(mnemomic, Colors::opcode());
+
+        // there are operands
+        if !kept.is_empty() {
+            stream.push(" ", Colors::spacing());
+
+            // iterate through operands
+            for (idx, &operand) in kept.iter().enumerate() {
+                self.operands[operand].tokenize(stream, symbols);
+
+                // separator
+                if idx != kept.len() - 1 {
+                    stream.push(", ", Colors::expr());
+                }
+            }
+        }
+    }
+}
 
-const EMPTY_OPERAND: std::borrow::Cow<'static, str> = std::borrow::Cow::Borrowed("");
+impl decoder::ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        self.tokenize_with(stream, symbols, decoder::Dialect::default().pseudo)
+    }
+}
@@ -0,0 +1,708 @@
+//! microMIPS32 release 3, which replaces the standard encodings with a mix of 16 and 32-bit
+//! instructions. The major opcode in the first halfword determines the size of an instruction.
+//!
+//! The 16-bit instructions mostly address the eight registers most used by compiled code
+//! through a 3-bit field, and are suffixed with `16` when there is a 32-bit instruction of the
+//! same name.
+
+use crate::{fpr, gpr, imm, jump, mem, read_half, Decoder, Instruction, Operand, Register};
+use decoder::{Error, ErrorKind};
+
+/// Registers encoded in a 3-bit field.
+const GPR3: [u32; 8] = [16, 17, 2, 3, 4, 5, 6, 7];
+
+/// Registers encoded in the 3-bit source field of the 16-bit stores, which can store `zero`.
+const GPR3_STORE: [u32; 8] = [0, 17, 2, 3, 4, 5, 6, 7];
+
+/// Destination pairs of `movep`.
+const MOVEP_PAIRS: [(u32, u32); 8] = [
+    (5, 6),
+    (5, 7),
+    (6, 7),
+    (4, 21),
+    (4, 22),
+    (4, 5),
+    (4, 6),
+    (4, 7),
+];
+
+/// Sources of `movep`.
+const MOVEP_SOURCES: [u32; 8] = [0, 17, 2, 3, 16, 18, 19, 20];
+
+/// Immediates of `andi16`.
+const ANDI16_IMMEDIATES: [i64; 16] = [
+    128, 1, 2, 3, 4, 7, 8, 15, 16, 31, 32, 63, 64, 255, 32768, 65535,
+];
+
+/// Immediates of `addiur2`.
+const ADDIUR2_IMMEDIATES: [i64; 8] = [1, 4, 8, 12, 16, 20, 24, -1];
+
+pub(crate) fn decode(
+    reader: &mut decoder::Reader,
+    decoder: &Decoder,
+) -> Result<Instruction, Error> {
+    let first = read_half(reader, decoder).map_err(|err| Error::new(err, 2))?;
+
+    // the low three bits of the major opcode select between 16 and 32-bit instructions
+    let width = match first >> 10 & 0b111 {
+        1..=3 => 2,
+        _ => 4,
+    };
+
+    // release 6 reencoded most of microMIPS
+    if decoder.is_r6 {
+        return Err(Error::new(ErrorKind::IncompleteDecoder, width));
+    }
+
+    if width == 2 {
+        return decode16(first).map(|inst| inst.with_width(2)).map_err(|err| Error::new(err, 2));
+    }
+
+    let second = read_half(reader, decoder).map_err(|err| Error::new(err, 2))?;
+    let dword = (first as u32) << 16 | second as u32;
+    decode32(dword, decoder).map_err(|err| Error::new(err, 4))
+}
+
+/// Register in a 3-bit field at `shift`.
+fn gpr3(half: u16, shift: u32) -> Operand {
+    gpr(GPR3[(half >> shift & 0b111) as usize])
+}
+
+/// Sign extended field of `bits` bits at `shift`.
+fn sext(word: u32, shift: u32, bits: u32) -> i64 {
+    ((word >> shift << (32 - bits)) as i32 >> (32 - bits)) as i64
+}
+
+fn decode16(half: u16) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = half as u32;
+    let reg5 = |shift: u32| gpr(word >> shift & 0b11111);
+
+    // offset of the 16-bit loads and stores, scaled by the access size
+    let offset4 = word & 0b1111;
+    let base = GPR3[(half >> 4 & 0b111) as usize];
+
+    // branches are relative to the instruction following the delay slot
+    let branch = |bits: u32| Operand::Branch((sext(word, 0, bits) << 1) + 2);
+
+    let inst = match half >> 10 {
+        0x01 => {
+            let mnemomic = if half & 1 == 0 { "addu16" } else { "subu16" };
+            new(mnemomic, &[gpr3(half, 7), gpr3(half, 1), gpr3(half, 4)])
+        }
+        0x02 => {
+            let offset = match offset4 {
+                0b1111 => -1,
+                offset => offset as i64,
+            };
+
+            new("lbu16", &[gpr3(half, 7), mem(base, offset)])
+        }
+        0x03 => new("move", &[reg5(5), reg5(0)]),
+        0x09 => {
+            let mnemomic = if half & 1 == 0 { "sll16" } else { "srl16" };
+            let sa = match word >> 1 & 0b111 {
+                0 => 8,
+                sa => sa,
+            };
+
+            new(mnemomic, &[gpr3(half, 7), gpr3(half, 4), imm(sa)])
+        }
+        0x0a => new("lhu16", &[gpr3(half, 7), mem(base, offset4 as i64 * 2)]),
+        0x0b => {
+            let imm = ANDI16_IMMEDIATES[offset4 as usize];
+            new(
+                "andi16",
+                &[gpr3(half, 7), gpr3(half, 4), Operand::Immediate(imm)],
+            )
+        }
+        0x11 => return decode_pool16c(half),
+        0x12 => new(
+            "lw",
+            &[
+                reg5(5),
+                mem(Register::Sp as u32, (word & 0b11111) as i64 * 4),
+            ],
+        ),
+        0x13 if half & 1 == 0 => new("addius5", &[reg5(5), imm(sext(word, 1, 4))]),
+        0x13 => {
+            // the encodings that would be too small to be useful extend the range instead
+            let imm = match word >> 1 & 0x1ff {
+                enc @ (0 | 1) => (enc as i64 + 256) * 4,
+                enc @ (510 | 511) => (enc as i64 - 768) * 4,
+                _ => sext(word, 1, 9) * 4,
+            };
+
+            new("addiusp", &[Operand::Immediate(imm)])
+        }
+        0x19 => new(
+            "lw",
+            &[
+                gpr3(half, 7),
+                mem(Register::Gp as u32, (word & 0x7f) as i64 * 4),
+            ],
+        ),
+        0x1a => new("lw16", &[gpr3(half, 7), mem(base, offset4 as i64 * 4)]),
+        0x1b if half & 1 == 0 => {
+            let imm = ADDIUR2_IMMEDIATES[(half >> 1 & 0b111) as usize];
+            new(
+                "addiur2",
+                &[gpr3(half, 7), gpr3(half, 4), Operand::Immediate(imm)],
+            )
+        }
+        0x1b => new("addiur1sp", &[gpr3(half, 7), imm((word >> 1 & 0x3f) * 4)]),
+        0x21 if half & 1 == 0 => {
+            let (rd, re) = MOVEP_PAIRS[(half >> 7 & 0b111) as usize];
+            let rs = MOVEP_SOURCES[(half >> 1 & 0b111) as usize];
+            let rt = MOVEP_SOURCES[(half >> 4 & 0b111) as usize];
+            new("movep", &[gpr(rd), gpr(re), gpr(rs), gpr(rt)])
+        }
+        0x22 | 0x2a | 0x3a => {
+            let rt = gpr(GPR3_STORE[(half >> 7 & 0b111) as usize]);
+            match half >> 10 {
+                0x22 => new("sb16", &[rt, mem(base, offset4 as i64)]),
+                0x2a => new("sh16", &[rt, mem(base, offset4 as i64 * 2)]),
+                _ => new("sw16", &[rt, mem(base, offset4 as i64 * 4)]),
+            }
+        }
+        0x23 => new("beqz16", &[gpr3(half, 7), branch(7)]),
+        0x2b => new("bnez16", &[gpr3(half, 7), branch(7)]),
+        0x32 => new(
+            "sw",
+            &[
+                reg5(5),
+                mem(Register::Sp as u32, (word & 0b11111) as i64 * 4),
+            ],
+        ),
+        0x33 => new("b16", &[branch(10)]),
+        0x3b => {
+            let imm = match word & 0x7f {
+                0x7f => -1,
+                imm => imm as i64,
+            };
+
+            new("li16", &[gpr3(half, 7), Operand::Immediate(imm)])
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// 16-bit logical operations, register lists, jumps and exceptions.
+fn decode_pool16c(half: u16) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = half as u32;
+    let rs = gpr(word & 0b11111);
+
+    let inst = match word >> 6 & 0b1111 {
+        0b0000 => new("not16", &[gpr3(half, 3), gpr3(half, 0)]),
+        0b0001 => new("xor16", &[gpr3(half, 3), gpr3(half, 0)]),
+        0b0010 => new("and16", &[gpr3(half, 3), gpr3(half, 0)]),
+        0b0011 => new("or16", &[gpr3(half, 3), gpr3(half, 0)]),
+        op @ (0b0100 | 0b0101) => {
+            let mnemomic = if op == 0b0100 { "lwm16" } else { "swm16" };
+            let regs = saved((word >> 4 & 0b11) + 1) | 1 << Register::Ra as u32;
+            let offset = (word & 0b1111) as i64 * 4;
+            new(
+                mnemomic,
+                &[Operand::List(regs), mem(Register::Sp as u32, offset)],
+            )
+        }
+        _ => match word >> 5 & 0b11111 {
+            0b01100 => new("jr16", &[rs]),
+            0b01101 => new("jrc", &[rs]),
+            0b01110 => new("jalr16", &[rs]),
+            0b01111 => new("jalrs16", &[rs]),
+            0b10000 => new("mfhi16", &[rs]),
+            0b10010 => new("mflo16", &[rs]),
+            0b11000 => new("jraddiusp", &[imm((word & 0b11111) * 4)]),
+            _ => match word >> 4 & 0b111111 {
+                0b101000 => new("break16", &[imm(word & 0b1111)]),
+                0b101100 => new("sdbbp16", &[imm(word & 0b1111)]),
+                _ => return Err(ErrorKind::InvalidOpcode),
+            },
+        },
+    };
+
+    Ok(inst)
+}
+
+/// The first `count` of `s0` to `s7` followed by `fp`, as saved by the register list
+/// loads and stores.
+fn saved(count: u32) -> u32 {
+    match count {
+        0 => 0,
+        count @ 1..=8 => ((1 << count) - 1) << Register::S0 as u32,
+        _ => 0xff << Register::S0 as u32 | 1 << Register::Fp as u32,
+    }
+}
+
+fn decode32(dword: u32, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rt, rs) = (dword >> 21 & 0b11111, dword >> 16 & 0b11111);
+    let imm16 = dword as u16 as i16 as i64;
+    let uimm16 = dword as u16 as i64;
+
+    // branches are relative to the instruction following the delay slot
+    let branch16 = Operand::Branch((imm16 << 1) + 4);
+
+    let inst = match dword >> 26 {
+        0x00 => return decode_pool32a(dword),
+        0x04 => new("addi", &[gpr(rt), gpr(rs), imm(imm16)]),
+        0x05 => new("lbu", &[gpr(rt), mem(rs, imm16)]),
+        0x06 => new("sb", &[gpr(rt), mem(rs, imm16)]),
+        0x07 => new("lb", &[gpr(rt), mem(rs, imm16)]),
+        0x08 => return decode_pool32b(dword),
+        0x0c => new("addiu", &[gpr(rt), gpr(rs), imm(imm16)]),
+        0x0d => new("lhu", &[gpr(rt), mem(rs, imm16)]),
+        0x0e => new("sh", &[gpr(rt), mem(rs, imm16)]),
+        0x0f => new("lh", &[gpr(rt), mem(rs, imm16)]),
+        0x10 => return decode_pool32i(dword),
+        0x14 => new("ori", &[gpr(rt), gpr(rs), imm(uimm16)]),
+        0x15 => return decode_pool32f(dword),
+        0x18 => return decode_pool32c(dword, decoder),
+        0x1c => new("xori", &[gpr(rt), gpr(rs), imm(uimm16)]),
+        0x1d => new("jals", &[half_jump(dword)]),
+        0x1e => {
+            let rs = GPR3[(dword >> 23 & 0b111) as usize];
+            new("addiupc", &[gpr(rs), imm(sext(dword, 0, 23) << 2)])
+        }
+        0x24 => new("slti", &[gpr(rt), gpr(rs), imm(imm16)]),
+        0x25 => new("beq", &[gpr(rs), gpr(rt), branch16]),
+        0x26 => new("swc1", &[fpr(rt), mem(rs, imm16)]),
+        0x27 => new("lwc1", &[fpr(rt), mem(rs, imm16)]),
+        0x2c => new("sltiu", &[gpr(rt), gpr(rs), imm(imm16)]),
+        0x2d => new("bne", &[gpr(rs), gpr(rt), branch16]),
+        0x2e => new("sdc1", &[fpr(rt), mem(rs, imm16)]),
+        0x2f => new("ldc1", &[fpr(rt), mem(rs, imm16)]),
+        0x34 => new("andi", &[gpr(rt), gpr(rs), imm(uimm16)]),
+        0x35 => new("j", &[half_jump(dword)]),
+        0x3c => new("jalx", &[jump(dword & 0x3ff_ffff)]),
+        0x3d => new("jal", &[half_jump(dword)]),
+        0x3e => new("sw", &[gpr(rt), mem(rs, imm16)]),
+        0x3f => new("lw", &[gpr(rt), mem(rs, imm16)]),
+        // microMIPS64
+        0x16 | 0x17 | 0x36 | 0x37 if decoder.is_64 => return Err(ErrorKind::IncompleteDecoder),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Jump to a halfword aligned index within the current 128 MB region.
+fn half_jump(dword: u32) -> Operand {
+    Operand::Jump {
+        target: ((dword & 0x3ff_ffff) << 1) as u64,
+        region: 27,
+    }
+}
+
+/// Shifts, three register arithmetic and bit fields.
+fn decode_pool32a(dword: u32) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rt, rs, rd) = (
+        dword >> 21 & 0b11111,
+        dword >> 16 & 0b11111,
+        dword >> 11 & 0b11111,
+    );
+    let field = dword >> 6 & 0b11111;
+
+    let inst = match dword & 0b111111 {
+        0x07 => match (dword >> 16 & 0x3ff, dword >> 6 & 0x3ff) {
+            (0, 0) => new("break", &[]),
+            (code, 0) => new("break", &[imm(code)]),
+            (code, extra) => new("break", &[imm(code), imm(extra)]),
+        },
+        0x0c if rd >= field => new("ins", &[gpr(rt), gpr(rs), imm(field), imm(rd - field + 1)]),
+        0x2c => new("ext", &[gpr(rt), gpr(rs), imm(field), imm(rd + 1)]),
+        0x3c => return decode_pool32axf(dword),
+        _ => {
+            let (rt, rs, rd) = (gpr(rt), gpr(rs), gpr(rd));
+            let sa = imm(dword >> 11 & 0b11111);
+
+            match dword & 0x7ff {
+                0x000 => new("sll", &[rt, rs, sa]),
+                0x040 => new("srl", &[rt, rs, sa]),
+                0x080 => new("sra", &[rt, rs, sa]),
+                0x0c0 => new("rotr", &[rt, rs, sa]),
+                0x010 => new("sllv", &[rd, rt, rs]),
+                0x050 => new("srlv", &[rd, rt, rs]),
+                0x090 => new("srav", &[rd, rt, rs]),
+                0x0d0 => new("rotrv", &[rd, rt, rs]),
+                0x110 => new("add", &[rd, rs, rt]),
+                0x150 => new("addu", &[rd, rs, rt]),
+                0x190 => new("sub", &[rd, rs, rt]),
+                0x1d0 => new("subu", &[rd, rs, rt]),
+                0x210 => new("mul", &[rd, rs, rt]),
+                0x250 => new("and", &[rd, rs, rt]),
+                0x290 => new("or", &[rd, rs, rt]),
+                0x2d0 => new("nor", &[rd, rs, rt]),
+                0x310 => new("xor", &[rd, rs, rt]),
+                0x350 => new("slt", &[rd, rs, rt]),
+                0x390 => new("sltu", &[rd, rs, rt]),
+                0x018 => new("movn", &[rd, rs, rt]),
+                0x058 => new("movz", &[rd, rs, rt]),
+                0x118 => new(
+                    "lwxs",
+                    &[
+                        rd,
+                        Operand::Indexed {
+                            base: Register::get(dword >> 16),
+                            index: Register::get(dword >> 21),
+                        },
+                    ],
+                ),
+                _ => return Err(ErrorKind::InvalidOpcode),
+            }
+        }
+    };
+
+    Ok(inst)
+}
+
+/// Two register operations, jumps, multiply/divide, traps and system instructions.
+fn decode_pool32axf(dword: u32) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rt, rs) = (gpr(dword >> 21), gpr(dword >> 16));
+    let cop = Operand::Cop((dword >> 16 & 0b11111) as u8);
+    let code10 = dword >> 16 & 0x3ff;
+
+    let inst = match dword >> 6 & 0x3ff {
+        0x03c => new("jalr", &[rt, rs]),
+        0x07c => new("jalr.hb", &[rt, rs]),
+        0x13c => new("jalrs", &[rt, rs]),
+        0x17c => new("jalrs.hb", &[rt, rs]),
+        0x0ac => new("seb", &[rt, rs]),
+        0x0ec => new("seh", &[rt, rs]),
+        0x12c => new("clo", &[rt, rs]),
+        0x16c => new("clz", &[rt, rs]),
+        minor if minor & 0x31f == 0x003 => new("mfc0", &[rt, cop, imm(minor >> 5 & 0b111)]),
+        minor if minor & 0x31f == 0x00b => new("mtc0", &[rt, cop, imm(minor >> 5 & 0b111)]),
+        0x1ac => new("rdhwr", &[rt, cop]),
+        0x1ec => new("wsbh", &[rt, rs]),
+        0x22c => new("mult", &[rs, rt]),
+        0x26c => new("multu", &[rs, rt]),
+        0x2ac => new("div", &[rs, rt]),
+        0x2ec => new("divu", &[rs, rt]),
+        0x32c => new("madd", &[rs, rt]),
+        0x36c => new("maddu", &[rs, rt]),
+        0x3ac => new("msub", &[rs, rt]),
+        0x3ec => new("msubu", &[rs, rt]),
+        0x334 => new("cfc2", &[rt, cop]),
+        0x374 => new("ctc2", &[rt, cop]),
+        0x035 if dword >> 21 & 0b11111 == 0 => new("mfhi", &[rs]),
+        0x075 if dword >> 21 & 0b11111 == 0 => new("mflo", &[rs]),
+        0x0b5 if dword >> 21 & 0b11111 == 0 => new("mthi", &[rs]),
+        0x0f5 if dword >> 21 & 0b11111 == 0 => new("mtlo", &[rs]),
+        0x00d if code10 == 0 => new("tlbp", &[]),
+        0x04d if code10 == 0 => new("tlbr", &[]),
+        0x08d if code10 == 0 => new("tlbwi", &[]),
+        0x0cd if code10 == 0 => new("tlbwr", &[]),
+        0x11d | 0x15d if dword >> 21 & 0b11111 == 0 => {
+            let mnemomic = if dword >> 12 & 1 == 0 { "di" } else { "ei" };
+            match dword >> 16 & 0b11111 {
+                0 => new(mnemomic, &[]),
+                _ => new(mnemomic, &[rs]),
+            }
+        }
+        0x1ad if dword >> 21 & 0b11111 == 0 => match dword >> 16 & 0b11111 {
+            0 => new("sync", &[]),
+            stype => new("sync", &[imm(stype)]),
+        },
+        0x22d => crate::code("syscall", code10),
+        0x24d => crate::code("wait", code10),
+        0x36d => crate::code("sdbbp", code10),
+        0x38d if code10 == 0 => new("deret", &[]),
+        0x3cd if code10 == 0 => new("eret", &[]),
+        minor => {
+            let mnemomic = match minor & 0b111111 {
+                0x00 => "teq",
+                0x08 => "tge",
+                0x10 => "tgeu",
+                0x20 => "tlt",
+                0x28 => "tltu",
+                0x30 => "tne",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            match minor >> 6 {
+                0 => new(mnemomic, &[rs, rt]),
+                code => new(mnemomic, &[rs, rt, imm(code)]),
+            }
+        }
+    };
+
+    Ok(inst)
+}
+
+/// Immediate branches, traps and `lui`.
+fn decode_pool32i(dword: u32) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let rs = gpr(dword >> 16);
+    let imm16 = dword as u16 as i16 as i64;
+    let target = Operand::Branch((imm16 << 1) + 4);
+
+    let inst = match dword >> 21 & 0b11111 {
+        0x00 => new("bltz", &[rs, target]),
+        0x01 => new("bltzal", &[rs, target]),
+        0x02 => new("bgez", &[rs, target]),
+        0x03 => new("bgezal", &[rs, target]),
+        0x04 => new("blez", &[rs, target]),
+        0x05 => new("bnezc", &[rs, target]),
+        0x06 => new("bgtz", &[rs, target]),
+        0x07 => new("beqzc", &[rs, target]),
+        0x08 => new("tlti", &[rs, imm(imm16)]),
+        0x09 => new("tgei", &[rs, imm(imm16)]),
+        0x0a => new("tltiu", &[rs, imm(imm16)]),
+        0x0b => new("tgeiu", &[rs, imm(imm16)]),
+        0x0c => new("tnei", &[rs, imm(imm16)]),
+        0x0d => new("lui", &[rs, imm(dword as u16)]),
+        0x0e => new("teqi", &[rs, imm(imm16)]),
+        0x10 => new("synci", &[mem(dword >> 16 & 0b11111, imm16)]),
+        0x11 => new("bltzals", &[rs, target]),
+        0x13 => new("bgezals", &[rs, target]),
+        op @ (0x14 | 0x15 | 0x1c | 0x1d) if dword >> 16 & 0b11 == 0 => {
+            let mnemomic = match op {
+                0x14 => "bc2f",
+                0x15 => "bc2t",
+                0x1c => "bc1f",
+                _ => "bc1t",
+            };
+
+            match dword >> 18 & 0b111 {
+                0 => new(mnemomic, &[target]),
+                cc => new(mnemomic, &[Operand::Fcc(cc as u8), target]),
+            }
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Loads and stores of register pairs and lists, with a 12-bit offset.
+fn decode_pool32b(dword: u32) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rd, base) = (dword >> 21 & 0b11111, dword >> 16 & 0b11111);
+    let offset = mem(base, sext(dword, 0, 12));
+    let list = || Operand::List(saved(rd & 0b1111) | (rd >> 4) << Register::Ra as u32);
+
+    let inst = match dword >> 12 & 0b1111 {
+        0x0 => new("lwc2", &[Operand::Cop(rd as u8), offset]),
+        0x1 => new("lwp", &[gpr(rd), offset]),
+        0x2 => new("ldc2", &[Operand::Cop(rd as u8), offset]),
+        0x5 if rd != 0 && rd & 0b1111 <= 9 => new("lwm32", &[list(), offset]),
+        0x6 => new("cache", &[imm(rd), offset]),
+        0x8 => new("swc2", &[Operand::Cop(rd as u8), offset]),
+        0x9 => new("swp", &[gpr(rd), offset]),
+        0xa => new("sdc2", &[Operand::Cop(rd as u8), offset]),
+        0xd if rd != 0 && rd & 0b1111 <= 9 => new("swm32", &[list(), offset]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Unaligned and atomic loads and stores, and prefetching, with a 12-bit offset.
+fn decode_pool32c(dword: u32, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rt, base) = (dword >> 21 & 0b11111, dword >> 16 & 0b11111);
+    let offset = mem(base, sext(dword, 0, 12));
+
+    let inst = match dword >> 12 & 0b1111 {
+        0x0 => new("lwl", &[gpr(rt), offset]),
+        0x1 => new("lwr", &[gpr(rt), offset]),
+        0x2 => new("pref", &[imm(rt), offset]),
+        0x3 => new("ll", &[gpr(rt), offset]),
+        0x8 => new("swl", &[gpr(rt), offset]),
+        0x9 => new("swr", &[gpr(rt), offset]),
+        0xb => new("sc", &[gpr(rt), offset]),
+        0xe if decoder.is_64 => new("lwu", &[gpr(rt), offset]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Floating point arithmetic, conversions, moves and compares.
+fn decode_pool32f(dword: u32) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (ft, fs, fd) = (
+        dword >> 21 & 0b11111,
+        dword >> 16 & 0b11111,
+        dword >> 11 & 0b11111,
+    );
+    let is_double = dword >> 8 & 1 == 1;
+
+    let inst = match dword & 0b111111 {
+        op @ (0x01 | 0x02 | 0x21 | 0x22 | 0x09 | 0x0a | 0x29 | 0x2a) => {
+            let mnemomic = match op {
+                0x01 => "madd.s",
+                0x02 => "nmadd.s",
+                0x21 => "msub.s",
+                0x22 => "nmsub.s",
+                0x09 => "madd.d",
+                0x0a => "nmadd.d",
+                0x29 => "msub.d",
+                _ => "nmsub.d",
+            };
+
+            let fr = dword >> 6 & 0b11111;
+            new(mnemomic, &[fpr(fd), fpr(fr), fpr(fs), fpr(ft)])
+        }
+        0x08 => {
+            let mnemomic = match dword >> 6 & 0b11111 {
+                0b00001 => "lwxc1",
+                0b00010 => "swxc1",
+                0b00011 => "ldxc1",
+                0b00100 => "sdxc1",
+                0b00101 => "luxc1",
+                0b00110 => "suxc1",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            let addr = Operand::Indexed {
+                base: Register::get(fs),
+                index: Register::get(ft),
+            };
+
+            new(mnemomic, &[fpr(fd), addr])
+        }
+        0x20 if dword >> 6 & 0b11111 == 0b00110 => {
+            let addr = Operand::Indexed {
+                base: Register::get(fs),
+                index: Register::get(ft),
+            };
+
+            new("prefx", &[imm(fd), addr])
+        }
+        0x20 if dword >> 6 & 0b110_0110 == 0 => {
+            let mnemomic = match (dword >> 9 & 0b11, dword >> 6 & 1) {
+                (0, 0) => "movf.s",
+                (0, _) => "movt.s",
+                (1, 0) => "movf.d",
+                (1, _) => "movt.d",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            let cc = dword >> 13 & 0b111;
+            new(mnemomic, &[fpr(ft), fpr(fs), Operand::Fcc(cc as u8)])
+        }
+        0x30 if dword >> 9 & 0b11 == 0 => {
+            let mnemomic = match (dword >> 6 & 0b11, is_double) {
+                (0, false) => "add.s",
+                (1, false) => "sub.s",
+                (2, false) => "mul.s",
+                (3, false) => "div.s",
+                (0, true) => "add.d",
+                (1, true) => "sub.d",
+                (2, true) => "mul.d",
+                (_, true) => "div.d",
+                _ => unreachable!(),
+            };
+
+            new(mnemomic, &[fpr(fd), fpr(fs), fpr(ft)])
+        }
+        0x38 if dword >> 9 & 0b11 == 0 && dword >> 7 & 1 == 0 => {
+            let mnemomic = match (is_double, dword >> 6 & 1) {
+                (false, 0) => "movn.s",
+                (false, _) => "movz.s",
+                (true, 0) => "movn.d",
+                (true, _) => "movz.d",
+            };
+
+            new(mnemomic, &[fpr(fd), fpr(fs), gpr(ft)])
+        }
+        0x3b => return decode_pool32fxf(dword),
+        0x3c if dword >> 11 & 0b11 == 0 => {
+            let names = crate::cop::CONDITIONS[(dword >> 6 & 0b1111) as usize];
+            let mnemomic = names[(dword >> 10 & 1) as usize];
+            let (fs, ft) = (fpr(fs), fpr(ft));
+
+            match dword >> 13 & 0b111 {
+                0 => new(mnemomic, &[fs, ft]),
+                cc => new(mnemomic, &[Operand::Fcc(cc as u8), fs, ft]),
+            }
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Unary floating point operations, conversions and moves between register files.
+fn decode_pool32fxf(dword: u32) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rt, rs) = (dword >> 21 & 0b11111, dword >> 16 & 0b11111);
+    let (ft, fs) = (fpr(rt), fpr(rs));
+
+    let inst = match dword >> 6 & 0x3ff {
+        // the condition code is in the top bits of the minor opcode
+        minor if minor & 0x7f == 0x05 || minor & 0x7f == 0x25 => {
+            let mnemomic = if minor & 0x7f == 0x05 { "movf" } else { "movt" };
+            let cc = Operand::Fcc((dword >> 13 & 0b111) as u8);
+            new(mnemomic, &[gpr(rt), gpr(rs), cc])
+        }
+        minor if minor & 0b111 == 0 && minor >> 9 == 0 => {
+            let mnemomic = match minor {
+                0x008 => "rsqrt.s",
+                0x108 => "rsqrt.d",
+                0x028 => "sqrt.s",
+                0x128 => "sqrt.d",
+                0x048 => "recip.s",
+                0x148 => "recip.d",
+                0x040 => return Ok(new("cfc1", &[gpr(rt), Operand::Cop(rs as u8)])),
+                0x060 => return Ok(new("ctc1", &[gpr(rt), Operand::Cop(rs as u8)])),
+                0x080 => return Ok(new("mfc1", &[gpr(rt), fs])),
+                0x0a0 => return Ok(new("mtc1", &[gpr(rt), fs])),
+                0x0c0 => return Ok(new("mfhc1", &[gpr(rt), fs])),
+                0x0e0 => return Ok(new("mthc1", &[gpr(rt), fs])),
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            new(mnemomic, &[ft, fs])
+        }
+        minor => {
+            let mnemomic = match minor {
+                0x004 => "cvt.l.s",
+                0x104 => "cvt.l.d",
+                0x024 => "cvt.w.s",
+                0x124 => "cvt.w.d",
+                0x00c => "floor.l.s",
+                0x10c => "floor.l.d",
+                0x02c => "floor.w.s",
+                0x12c => "floor.w.d",
+                0x04c => "ceil.l.s",
+                0x14c => "ceil.l.d",
+                0x06c => "ceil.w.s",
+                0x16c => "ceil.w.d",
+                0x08c => "trunc.l.s",
+                0x18c => "trunc.l.d",
+                0x0ac => "trunc.w.s",
+                0x1ac => "trunc.w.d",
+                0x0cc => "round.l.s",
+                0x1cc => "round.l.d",
+                0x0ec => "round.w.s",
+                0x1ec => "round.w.d",
+                0x001 => "mov.s",
+                0x081 => "mov.d",
+                0x00d => "abs.s",
+                0x08d => "abs.d",
+                0x02d => "neg.s",
+                0x0ad => "neg.d",
+                0x04d => "cvt.d.s",
+                0x0cd => "cvt.d.w",
+                0x14d => "cvt.d.l",
+                0x06d => "cvt.s.d",
+                0x0ed => "cvt.s.w",
+                0x16d => "cvt.s.l",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            new(mnemomic, &[ft, fs])
+        }
+    };
+
+    Ok(inst)
+}
@@ -0,0 +1,354 @@
+//! MIPS16e, a 16-bit encoding of a subset of MIPS32/MIPS64 executed alongside the standard
+//! instruction set.
+//!
+//! Most instructions can only address eight registers and use a small immediate, which an
+//! `extend` prefix widens to 16 bits. Prefixed instructions are decoded as a single 32-bit
+//! instruction, just like `jal` and `jalx`.
+
+use crate::{gpr, imm, jump, mem, read_half, Decoder, Instruction, Operand, Register};
+use decoder::{Error, ErrorKind};
+
+/// Registers encoded in a 3-bit field.
+const GPR3: [u32; 8] = [16, 17, 2, 3, 4, 5, 6, 7];
+
+/// Number of arguments saved to the caller's frame and of static registers saved from `a3`
+/// downwards by `save`, indexed by the `aregs` field.
+const AREGS: [Option<(u32, u32)>; 16] = [
+    Some((0, 0)),
+    Some((1, 0)),
+    Some((2, 0)),
+    Some((3, 0)),
+    Some((0, 1)),
+    Some((1, 1)),
+    Some((2, 1)),
+    Some((3, 1)),
+    Some((0, 2)),
+    Some((1, 2)),
+    Some((2, 2)),
+    Some((0, 3)),
+    Some((1, 3)),
+    Some((0, 4)),
+    Some((4, 0)),
+    None,
+];
+
+pub(crate) fn decode(
+    reader: &mut decoder::Reader,
+    decoder: &Decoder,
+) -> Result<Instruction, Error> {
+    let first = read_half(reader, decoder).map_err(|err| Error::new(err, 2))?;
+
+    match first >> 11 {
+        // jal and jalx
+        0b00011 => {
+            let second = read_half(reader, decoder).map_err(|err| Error::new(err, 2))?;
+            let index = (first as u32 & 0x1f) << 21 | (first as u32 >> 5 & 0x1f) << 16;
+            let mnemomic = if first >> 10 & 1 == 0 { "jal" } else { "jalx" };
+            let inst = Instruction::new(mnemomic, &[jump(index | second as u32)]);
+            Ok(inst.with_width(4))
+        }
+        // extend
+        0b11110 => {
+            let second = read_half(reader, decoder).map_err(|err| Error::new(err, 2))?;
+            decode_inst(second, Some(first as u32 & 0x7ff), decoder)
+                .map(|inst| inst.with_width(4))
+                .map_err(|err| Error::new(err, 4))
+        }
+        _ => decode_inst(first, None, decoder)
+            .map(|inst| inst.with_width(2))
+            .map_err(|err| Error::new(err, 2)),
+    }
+}
+
+/// Register in a 3-bit field at `shift`.
+fn gpr3(word: u32, shift: u32) -> Operand {
+    gpr(GPR3[(word >> shift & 0b111) as usize])
+}
+
+/// Sign extended field of `bits` bits.
+fn sext(word: u32, bits: u32) -> i64 {
+    ((word << (32 - bits)) as i32 >> (32 - bits)) as i64
+}
+
+fn decode_inst(
+    half: u16,
+    extend: Option<u32>,
+    decoder: &Decoder,
+) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let word = half as u32;
+    let (rx, ry) = (gpr3(word, 8), gpr3(word, 5));
+    let (sp, is_64) = (Register::Sp as u32, decoder.is_64);
+
+    let extended = extended(word, extend);
+
+    // the immediate when extended, otherwise the immediate of the short encoding
+    let signed = |short: i64| extended.map_or(short, |imm| imm as u16 as i16 as i64);
+    let unsigned = |short: i64| extended.map_or(short, |imm| imm as i64);
+
+    // branches are relative to the instruction following them, there are no delay slots
+    let width = if extend.is_some() { 4 } else { 2 };
+    let branch = |short: i64| Operand::Branch((signed(short) << 1) + width);
+
+    // unextended loads and stores scale their offset by the access size
+    let offset5 = |scale: i64| signed((word & 0x1f) as i64 * scale);
+    let offset8 = |scale: i64| signed((word & 0xff) as i64 * scale);
+
+    // these instructions have no extended form
+    if extend.is_some() && matches!(word >> 11, 0b00011 | 0b11100 | 0b11101 | 0b11110) {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    let inst = match word >> 11 {
+        0b00000 => new("addiu", &[rx, gpr(sp), imm(offset8(4))]),
+        0b00001 => new("addiupc", &[rx, imm(offset8(4))]),
+        0b00010 => new("b", &[branch(sext(word, 11))]),
+        0b00100 => new("beqz", &[rx, branch(sext(word, 8))]),
+        0b00101 => new("bnez", &[rx, branch(sext(word, 8))]),
+        0b00110 => {
+            let sa = match extend {
+                Some(_) if word >> 2 & 0b111 != 0 => return Err(ErrorKind::InvalidOperand),
+                Some(ext) => ext >> 6 & 0x1f | (ext >> 5 & 1) << 5,
+                None => match word >> 2 & 0b111 {
+                    0 => 8,
+                    sa => sa,
+                },
+            };
+
+            let mnemomic = match word & 0b11 {
+                0b00 if sa < 32 => "sll",
+                0b01 if is_64 => "dsll",
+                0b10 if sa < 32 => "srl",
+                0b11 if sa < 32 => "sra",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            new(mnemomic, &[rx, ry, imm(sa)])
+        }
+        0b00111 if is_64 => new("ld", &[ry, mem_rx(word, offset5(8))]),
+        0b01000 => {
+            let imm = match extend {
+                Some(ext) => sext((ext & 0xf) << 11 | (ext >> 4 & 0x7f) << 4 | word & 0xf, 15),
+                None => sext(word, 4),
+            };
+
+            match word >> 4 & 1 {
+                0 => new("addiu", &[ry, rx, Operand::Immediate(imm)]),
+                _ if is_64 => new("daddiu", &[ry, rx, Operand::Immediate(imm)]),
+                _ => return Err(ErrorKind::InvalidOpcode),
+            }
+        }
+        0b01001 => new("addiu", &[rx, imm(signed(sext(word, 8)))]),
+        0b01010 => new("slti", &[rx, imm(signed((word & 0xff) as i64))]),
+        0b01011 => new("sltiu", &[rx, imm(signed((word & 0xff) as i64))]),
+        0b01100 => return decode_i8(word, extend),
+        0b01101 => new("li", &[rx, imm(unsigned((word & 0xff) as i64))]),
+        0b01110 => new("cmpi", &[rx, imm(unsigned((word & 0xff) as i64))]),
+        0b01111 if is_64 => new("sd", &[ry, mem_rx(word, offset5(8))]),
+        0b10000 => new("lb", &[ry, mem_rx(word, offset5(1))]),
+        0b10001 => new("lh", &[ry, mem_rx(word, offset5(2))]),
+        0b10010 => new("lw", &[rx, mem(sp, offset8(4))]),
+        0b10011 => new("lw", &[ry, mem_rx(word, offset5(4))]),
+        0b10100 => new("lbu", &[ry, mem_rx(word, offset5(1))]),
+        0b10101 => new("lhu", &[ry, mem_rx(word, offset5(2))]),
+        0b10110 => new("lwpc", &[rx, imm(offset8(4))]),
+        0b10111 if is_64 => new("lwu", &[ry, mem_rx(word, offset5(4))]),
+        0b11000 => new("sb", &[ry, mem_rx(word, offset5(1))]),
+        0b11001 => new("sh", &[ry, mem_rx(word, offset5(2))]),
+        0b11010 => new("sw", &[rx, mem(sp, offset8(4))]),
+        0b11011 => new("sw", &[ry, mem_rx(word, offset5(4))]),
+        0b11100 => {
+            let rz = gpr3(word, 2);
+            let mnemomic = match word & 0b11 {
+                0b00 if is_64 => "daddu",
+                0b01 => "addu",
+                0b10 if is_64 => "dsubu",
+                0b11 => "subu",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            new(mnemomic, &[rz, rx, ry])
+        }
+        0b11101 => return decode_rr(word, decoder),
+        // 64-bit sp and pc relative instructions
+        0b11111 if is_64 => return Err(ErrorKind::IncompleteDecoder),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// The 16-bit immediate of an extended instruction, which is split over both halfwords.
+fn extended(word: u32, extend: Option<u32>) -> Option<u32> {
+    extend.map(|ext| (ext & 0x1f) << 11 | (ext >> 5 & 0x3f) << 5 | word & 0x1f)
+}
+
+/// Memory at the register in the `rx` field.
+fn mem_rx(word: u32, offset: i64) -> Operand {
+    mem(GPR3[(word >> 8 & 0b111) as usize], offset)
+}
+
+/// Branches on `t8`, stack pointer adjustments and moves from and to any register.
+fn decode_i8(word: u32, extend: Option<u32>) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let sp = Register::Sp as u32;
+    let signed = |short: i64| extended(word, extend).map_or(short, |imm| imm as u16 as i16 as i64);
+    let width = if extend.is_some() { 4 } else { 2 };
+    let branch = Operand::Branch((signed(sext(word, 8)) << 1) + width);
+
+    let inst = match word >> 8 & 0b111 {
+        0b000 => new("bteqz", &[branch]),
+        0b001 => new("btnez", &[branch]),
+        0b010 => new(
+            "sw",
+            &[
+                gpr(Register::Ra as u32),
+                mem(sp, signed((word & 0xff) as i64 * 4)),
+            ],
+        ),
+        0b011 => new("addiu", &[gpr(sp), imm(signed(sext(word, 8) * 8))]),
+        0b100 => return decode_save_restore(word, extend),
+        0b101 if extend.is_none() => {
+            let r32 = (word >> 5 & 0b111) | (word >> 3 & 0b11) << 3;
+            new("move", &[gpr(r32), gpr3(word, 0)])
+        }
+        0b111 if extend.is_none() => new("move", &[gpr3(word, 5), gpr(word & 0x1f)]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// `save` and `restore`, which push or pop a frame along with the return address and the
+/// callee saved registers.
+fn decode_save_restore(word: u32, extend: Option<u32>) -> Result<Instruction, ErrorKind> {
+    let mnemomic = if word >> 7 & 1 == 1 {
+        "save"
+    } else {
+        "restore"
+    };
+    let mut regs = 0;
+
+    if word >> 6 & 1 == 1 {
+        regs |= 1 << Register::Ra as u32;
+    }
+    if word >> 5 & 1 == 1 {
+        regs |= 1 << Register::S0 as u32;
+    }
+    if word >> 4 & 1 == 1 {
+        regs |= 1 << Register::S1 as u32;
+    }
+
+    let framesize = match extend {
+        Some(ext) => {
+            // s2 up to s7 followed by s8, also known as fp
+            regs |= match ext >> 8 & 0b111 {
+                0 => 0,
+                7 => 0b11_1111 << Register::S2 as u32 | 1 << Register::Fp as u32,
+                count => ((1 << count) - 1) << Register::S2 as u32,
+            };
+
+            let (args, statics) =
+                AREGS[(ext & 0b1111) as usize].ok_or(ErrorKind::InvalidOperand)?;
+            regs |= ((1 << args) - 1) << Register::A0 as u32;
+            regs |= ((1 << statics) - 1) << (Register::A3 as u32 + 1 - statics);
+
+            ((ext >> 4 & 0xf) << 4 | word & 0xf) * 8
+        }
+        None => match word & 0xf {
+            0 => 128,
+            size => size * 8,
+        },
+    };
+
+    Ok(Instruction::new(
+        mnemomic,
+        &[Operand::List(regs), imm(framesize)],
+    ))
+}
+
+/// Two register operations and jumps through a register.
+fn decode_rr(word: u32, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (rx, ry) = (gpr3(word, 8), gpr3(word, 5));
+    let is_64 = decoder.is_64;
+    let ra = gpr(Register::Ra as u32);
+    let code = imm(word >> 5 & 0x3f);
+
+    let inst = match word & 0x1f {
+        0b00000 => match word >> 5 & 0b111 {
+            0b000 => new("jr", &[rx]),
+            0b001 if word >> 8 & 0b111 == 0 => new("jr", &[ra]),
+            0b010 => new("jalr", &[ra, rx]),
+            0b100 => new("jrc", &[rx]),
+            0b101 if word >> 8 & 0b111 == 0 => new("jrc", &[ra]),
+            0b110 => new("jalrc", &[ra, rx]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        0b00001 => new("sdbbp", &[code]),
+        0b00010 => new("slt", &[rx, ry]),
+        0b00011 => new("sltu", &[rx, ry]),
+        0b00100 => new("sllv", &[ry, rx]),
+        0b00101 => new("break", &[code]),
+        0b00110 => new("srlv", &[ry, rx]),
+        0b00111 => new("srav", &[ry, rx]),
+        0b01000 if is_64 => {
+            let sa = match word >> 8 & 0b111 {
+                0 => 8,
+                sa => sa,
+            };
+
+            new("dsrl", &[ry, imm(sa)])
+        }
+        0b01010 => new("cmp", &[rx, ry]),
+        0b01011 => new("neg", &[rx, ry]),
+        0b01100 => new("and", &[rx, ry]),
+        0b01101 => new("or", &[rx, ry]),
+        0b01110 => new("xor", &[rx, ry]),
+        0b01111 => new("not", &[rx, ry]),
+        0b10000 if word >> 5 & 0b111 == 0 => new("mfhi", &[rx]),
+        0b10001 => {
+            let mnemomic = match word >> 5 & 0b111 {
+                0b000 => "zeb",
+                0b001 => "zeh",
+                0b010 if is_64 => "zew",
+                0b100 => "seb",
+                0b101 => "seh",
+                0b110 if is_64 => "sew",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            new(mnemomic, &[rx])
+        }
+        0b10010 if word >> 5 & 0b111 == 0 => new("mflo", &[rx]),
+        0b10011 if is_64 => {
+            let sa = match word >> 8 & 0b111 {
+                0 => 8,
+                sa => sa,
+            };
+
+            new("dsra", &[ry, imm(sa)])
+        }
+        0b10100 if is_64 => new("dsllv", &[ry, rx]),
+        0b10110 if is_64 => new("dsrlv", &[ry, rx]),
+        0b10111 if is_64 => new("dsrav", &[ry, rx]),
+        funct @ 0b11000..=0b11111 => {
+            let mnemomic = match funct {
+                0b11000 => "mult",
+                0b11001 => "multu",
+                0b11010 => "div",
+                0b11011 => "divu",
+                0b11100 if is_64 => "dmult",
+                0b11101 if is_64 => "dmultu",
+                0b11110 if is_64 => "ddiv",
+                0b11111 if is_64 => "ddivu",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            new(mnemomic, &[rx, ry])
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
@@ -1,15 +1,19 @@
 #![cfg(test)]
 
-use decoder::{ToTokens, Decodable};
+use decoder::{Decodable, Decoded, ToTokens};
 
 fn test_display(bytes: &[u8], str: &str) {
+    test_display_with(&crate::Decoder::default(), bytes, str);
+}
+
+fn test_display_with(decoder: &crate::Decoder, bytes: &[u8], str: &str) {
     let mut reader = decoder::Reader::new(bytes);
     let mut line = tokenizing::TokenStream::new();
     let symbols = debugvault::Index::default();
-    let decoder = crate::Decoder::default();
 
     let decoded = match decoder.decode(&mut reader) {
-        Ok(inst) => {
+        Ok(mut inst) => {
+            inst.update_rel_addrs(0x1000, None);
             inst.tokenize(&mut line, &symbols);
             line.to_string()
         }
@@ -21,12 +25,12 @@ fn test_display(bytes: &[u8], str: &str) {
 
 #[test]
 fn jump() {
-    test_display(&[0x9, 0, 0, 0], "j 0x0");
+    test_display(&[0x9, 0, 0, 0], "j 0x4000000");
 }
 
 #[test]
 fn beq() {
-    test_display(&[0x11, 0x2a, 0x10, 0x0], "beq t1, t2, 0x1000");
+    test_display(&[0x11, 0x2a, 0x10, 0x0], "beq t1, t2, 0x5004");
 }
 
 #[test]
@@ -41,5 +45,334 @@ fn sllv() {
 
 #[test]
 fn lb() {
-    test_display(&[0x81, 0x49, 0x0, 0x10], "lb t1, t2, 0x10");
+    test_display(&[0x81, 0x49, 0x0, 0x10], "lb t1, 0x10(t2)");
+}
+
+#[test]
+fn mips64() {
+    let decoder = crate::Decoder {
+        is_64: true,
+        ..Default::default()
+    };
+
+    test_display_with(&decoder, &[0xdf, 0xbf, 0x00, 0x18], "ld ra, 0x18(sp)");
+    test_display_with(&decoder, &[0x64, 0x42, 0xff, 0xf0], "daddiu v0, v0, -0x10");
+    test_display_with(&decoder, &[0x00, 0x02, 0x10, 0x3c], "dsll32 v0, v0, 0x0");
+    test_display_with(
+        &decoder,
+        &[0x7c, 0x43, 0xf8, 0x03],
+        "dext v1, v0, 0x0, 0x20",
+    );
+
+    // only decoded as MIPS64
+    test_display(
+        &[0xdf, 0xbf, 0x00, 0x18],
+        "Error { kind: InvalidOpcode, size: 4 }",
+    );
+}
+
+#[test]
+fn release6() {
+    let decoder = crate::Decoder {
+        is_r6: true,
+        ..Default::default()
+    };
+
+    test_display_with(&decoder, &[0x00, 0x85, 0x10, 0x98], "mul v0, a0, a1");
+    test_display_with(&decoder, &[0x00, 0x85, 0x10, 0xda], "mod v0, a0, a1");
+    test_display_with(&decoder, &[0x00, 0x80, 0x10, 0x50], "clz v0, a0");
+    test_display_with(&decoder, &[0xe8, 0x00, 0x00, 0x10], "balc 0x1044");
+    test_display_with(&decoder, &[0xd8, 0x80, 0x00, 0x00], "beqzc a0, 0x1004");
+    test_display_with(&decoder, &[0xd8, 0x1f, 0x00, 0x00], "jrc ra");
+    test_display_with(&decoder, &[0x7c, 0x82, 0x00, 0x36], "ll v0, 0x0(a0)");
+    test_display_with(&decoder, &[0x45, 0x22, 0x00, 0x01], "bc1eqz f2, 0x1008");
+
+    // removed in release 6
+    test_display_with(
+        &decoder,
+        &[0x00, 0x85, 0x00, 0x18],
+        "Error { kind: InvalidOpcode, size: 4 }",
+    );
+    test_display_with(
+        &decoder,
+        &[0xc0, 0x82, 0x00, 0x00],
+        "Error { kind: InvalidOpcode, size: 4 }",
+    );
+}
+
+#[test]
+fn coprocessors() {
+    test_display(&[0x40, 0x08, 0x60, 0x00], "mfc0 t0, $12, 0x0");
+    test_display(&[0x40, 0x89, 0x78, 0x01], "mtc0 t1, $15, 0x1");
+    test_display(&[0x42, 0x00, 0x00, 0x18], "eret");
+    test_display(&[0x41, 0x60, 0x60, 0x00], "di");
+    test_display(&[0x44, 0x82, 0x00, 0x00], "mtc1 v0, f0");
+    test_display(&[0xc4, 0x80, 0x00, 0x04], "lwc1 f0, 0x4(a0)");
+    test_display(&[0x46, 0x22, 0x00, 0x80], "add.d f2, f0, f2");
+    test_display(&[0x46, 0x02, 0x00, 0x3c], "c.lt.s f0, f2");
+    test_display(&[0x46, 0x02, 0x04, 0x3c], "c.lt.s fcc4, f0, f2");
+    test_display(&[0x45, 0x01, 0x00, 0x03], "bc1t 0x1010");
+    test_display(&[0x46, 0x80, 0x10, 0xa0], "cvt.s.w f2, f2");
+    test_display(&[0x4c, 0x85, 0x00, 0x01], "ldxc1 f0, a1(a0)");
+    test_display(&[0x4c, 0x42, 0x00, 0x20], "madd.s f0, f2, f0, f2");
+}
+
+#[test]
+fn rel_addrs() {
+    let resolve = |bytes: &[u8], addr: usize| {
+        let mut reader = decoder::Reader::new(bytes);
+        let mut inst = crate::Decoder::default().decode(&mut reader).unwrap();
+        inst.update_rel_addrs(addr, None);
+        inst.targets()
+    };
+
+    // beq t1, t2, -0x4
+    assert_eq!(resolve(&[0x11, 0x2a, 0xff, 0xff], 0x400100), vec![0x400100]);
+    // j 0x400000 from the same 256 MiB region
+    assert_eq!(
+        resolve(&[0x08, 0x10, 0x00, 0x00], 0x80001000),
+        vec![0x80400000]
+    );
+    // bal 0x8
+    assert_eq!(resolve(&[0x04, 0x11, 0x00, 0x01], 0x2000), vec![0x2008]);
+}
+
+#[test]
+fn little_endian() {
+    let decoder = crate::Decoder {
+        little_endian: true,
+        ..Default::default()
+    };
+
+    test_display_with(&decoder, &[0x10, 0x00, 0x09, 0x24], "li t1, 0x10");
+}
+
+#[test]
+fn pseudo() {
+    let tokenize = |bytes: &[u8], pseudo: bool| {
+        let mut reader = decoder::Reader::new(bytes);
+        let mut line = tokenizing::TokenStream::new();
+        let inst = crate::Decoder::default().decode(&mut reader).unwrap();
+        inst.tokenize_with(&mut line, &debugvault::Index::default(), pseudo);
+        line.to_string()
+    };
+
+    assert_eq!(tokenize(&[0x0, 0x0, 0x0, 0x0], true), "nop");
+    assert_eq!(
+        tokenize(&[0x0, 0x0, 0x0, 0x0], false),
+        "sll zero, zero, 0x0"
+    );
+
+    // addu t1, t2, zero
+    assert_eq!(tokenize(&[0x01, 0x40, 0x48, 0x21], true), "move t1, t2");
+    assert_eq!(
+        tokenize(&[0x01, 0x40, 0x48, 0x21], false),
+        "addu t1, t2, zero"
+    );
+
+    // addiu t1, zero, 0x10
+    assert_eq!(tokenize(&[0x24, 0x09, 0x00, 0x10], true), "li t1, 0x10");
+    assert_eq!(
+        tokenize(&[0x24, 0x09, 0x00, 0x10], false),
+        "addiu t1, zero, 0x10"
+    );
+
+    // beq zero, zero, 0x14
+    assert_eq!(tokenize(&[0x10, 0x00, 0x00, 0x04], true), "b 0x14");
+
+    // bne t1, zero, 0x14
+    assert_eq!(tokenize(&[0x15, 0x20, 0x00, 0x04], true), "bnez t1, 0x14");
+    assert_eq!(
+        tokenize(&[0x15, 0x20, 0x00, 0x04], false),
+        "bne t1, zero, 0x14"
+    );
+}
+
+#[test]
+fn behavior() {
+    use decoder::{Direction, FlowKind, MemoryAccess};
+
+    let decode = |bytes: &[u8]| {
+        let mut reader = decoder::Reader::new(bytes);
+        crate::Decoder::default().decode(&mut reader).unwrap()
+    };
+
+    let inst = decode(&[0x11, 0x2a, 0x10, 0x0]);
+    assert_eq!(inst.flow(), FlowKind::ConditionalJump);
+    assert_eq!(inst.regs_read(), vec!["t1", "t2"]);
+    assert_eq!(inst.regs_written(), Vec::<&str>::new());
+
+    // jr ra
+    let inst = decode(&[0x03, 0xe0, 0x00, 0x08]);
+    assert_eq!(inst.flow(), FlowKind::Return);
+    assert_eq!(inst.regs_read(), vec!["ra"]);
+
+    let inst = decode(&[0x1, 0x49, 0x48, 0x4]);
+    assert_eq!(inst.flow(), FlowKind::Sequential);
+    assert_eq!(inst.regs_read(), vec!["t1", "t2"]);
+    assert_eq!(inst.regs_written(), vec!["t1"]);
+
+    let inst = decode(&[0x81, 0x49, 0x0, 0x10]);
+    assert_eq!(inst.regs_read(), vec!["t2"]);
+    assert_eq!(inst.regs_written(), vec!["t1"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(1),
+            direction: Direction::Read
+        })
+    );
+
+    // mult t1, t2
+    let inst = decode(&[0x01, 0x2a, 0x00, 0x18]);
+    assert_eq!(inst.regs_read(), vec!["t1", "t2"]);
+    assert_eq!(inst.regs_written(), vec!["hi", "lo"]);
+}
+
+#[test]
+fn behavior_extended() {
+    use decoder::{Direction, FlowKind, MemoryAccess};
+
+    let decode = |bytes: &[u8]| {
+        let mut reader = decoder::Reader::new(bytes);
+        crate::Decoder::default().decode(&mut reader).unwrap()
+    };
+
+    // jalr t9
+    let inst = decode(&[0x03, 0x20, 0xf8, 0x09]);
+    assert_eq!(inst.flow(), FlowKind::Call);
+    assert_eq!(inst.regs_read(), vec!["t9"]);
+    assert_eq!(inst.regs_written(), vec!["ra"]);
+
+    // sdc1 f2, 0x8(sp)
+    let inst = decode(&[0xf7, 0xa2, 0x00, 0x08]);
+    assert_eq!(inst.regs_read(), vec!["f2", "sp"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Write
+        })
+    );
+
+    // mtc1 v0, f0
+    let inst = decode(&[0x44, 0x82, 0x00, 0x00]);
+    assert_eq!(inst.regs_read(), vec!["v0"]);
+    assert_eq!(inst.regs_written(), vec!["f0"]);
+
+    // syscall
+    assert_eq!(
+        decode(&[0x00, 0x00, 0x00, 0x0c]).flow(),
+        FlowKind::Interrupt
+    );
+}
+
+#[test]
+fn micromips() {
+    let decoder = crate::Decoder {
+        isa: crate::Isa::MicroMips,
+        ..Default::default()
+    };
+
+    test_display_with(&decoder, &[0x0c, 0x44], "move v0, a0");
+    test_display_with(&decoder, &[0x25, 0x13], "srl16 v0, s1, 0x1");
+    test_display_with(&decoder, &[0x6d, 0x20], "addiur2 v0, v0, 0x1");
+    test_display_with(&decoder, &[0x6c, 0x03], "addiur1sp s0, 0x4");
+    test_display_with(&decoder, &[0x45, 0xbf], "jrc ra");
+    test_display_with(&decoder, &[0x47, 0x13], "jraddiusp 0x4c");
+    test_display_with(&decoder, &[0xcf, 0xff], "b16 0x1000");
+    test_display_with(&decoder, &[0x45, 0x5b], "swm16 s0-s1, ra, 0x2c(sp)");
+    test_display_with(
+        &decoder,
+        &[0x20, 0xa0, 0xd0, 0x04],
+        "swm32 s0-s4, 0x4(zero)",
+    );
+    test_display_with(&decoder, &[0xf4, 0x00, 0x00, 0x10], "jal 0x20");
+}
+
+#[test]
+fn mips16() {
+    let decoder = crate::Decoder {
+        isa: crate::Isa::Mips16,
+        ..Default::default()
+    };
+
+    test_display_with(&decoder, &[0xe8, 0x20], "jr ra");
+    test_display_with(&decoder, &[0x6a, 0x01], "li v0, 0x1");
+    test_display_with(&decoder, &[0x63, 0xfc], "addiu sp, -0x20");
+    test_display_with(&decoder, &[0x64, 0xc4], "save ra, 0x20");
+    test_display_with(&decoder, &[0x64, 0x74], "restore s0-s1, ra, 0x20");
+    test_display_with(&decoder, &[0xe9, 0x8a], "cmp s1, a0");
+    test_display_with(&decoder, &[0x18, 0x00, 0x00, 0x10], "jal 0x40");
+    // extended `li`
+    test_display_with(&decoder, &[0xf0, 0x10, 0x6a, 0x00], "li v0, 0x8000");
+}
+
+#[test]
+fn compressed_widths() {
+    let decode = |isa: crate::Isa, bytes: &[u8], addr: usize| {
+        let decoder = crate::Decoder {
+            isa,
+            ..Default::default()
+        };
+        let mut reader = decoder::Reader::new(bytes);
+        let mut inst = decoder.decode(&mut reader).unwrap();
+        inst.update_rel_addrs(addr, None);
+        (inst.width(), inst.targets())
+    };
+
+    // b16 0x0
+    assert_eq!(
+        decode(crate::Isa::MicroMips, &[0xcf, 0xff], 0x1000),
+        (2, vec![0x1000])
+    );
+    // jal 0x20 from the same 128 MiB region
+    assert_eq!(
+        decode(crate::Isa::MicroMips, &[0xf4, 0x00, 0x00, 0x10], 0x88001000),
+        (4, vec![0x88000020])
+    );
+    // extended `li v0, 0x8000`
+    assert_eq!(
+        decode(crate::Isa::Mips16, &[0xf0, 0x10, 0x6a, 0x00], 0x1000),
+        (4, vec![])
+    );
+}
+
+#[test]
+fn behavior_compressed() {
+    use decoder::{Direction, FlowKind, MemoryAccess};
+
+    let decode = |isa: crate::Isa, bytes: &[u8]| {
+        let decoder = crate::Decoder {
+            isa,
+            ..Default::default()
+        };
+        let mut reader = decoder::Reader::new(bytes);
+        decoder.decode(&mut reader).unwrap()
+    };
+
+    // jrc ra
+    let inst = decode(crate::Isa::MicroMips, &[0x45, 0xbf]);
+    assert_eq!(inst.flow(), FlowKind::Return);
+
+    // swm16 s0-s1, ra, 0x2c(sp)
+    let inst = decode(crate::Isa::MicroMips, &[0x45, 0x5b]);
+    assert_eq!(inst.regs_read(), vec!["s0", "s1", "ra", "sp"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: None,
+            direction: Direction::Write
+        })
+    );
+
+    // cmp s1, a0
+    let inst = decode(crate::Isa::Mips16, &[0xe9, 0x8a]);
+    assert_eq!(inst.regs_read(), vec!["s1", "a0"]);
+    assert_eq!(inst.regs_written(), vec!["t8"]);
+
+    // restore s0-s1, ra, 0x20
+    let inst = decode(crate::Isa::Mips16, &[0x64, 0x74]);
+    assert_eq!(inst.regs_read(), vec!["sp"]);
+    assert_eq!(inst.regs_written(), vec!["s0", "s1", "ra", "sp"]);
 }
@@ -0,0 +1,299 @@
+//! Semantic queries on decoded instructions: control flow, registers and memory accesses.
+//!
+//! Condition register bits are reported as the field holding them. Recording instructions
+//! write `cr0`, or `cr1` for floating point and `cr6` for vector comparisons. The floating point
+//! status register and special purpose registers other than `xer`, `lr` and `ctr` aren't
+//! tracked.
+
+use crate::{
+    Instruction, Operand, CR_FIELDS, FLOAT_REGISTERS, REGISTERS, SPR_CTR, SPR_LR, SPR_XER,
+    VECTOR_REGISTERS, VSX_REGISTERS,
+};
+use decoder::{Direction, FlowKind, MemoryAccess};
+
+/// The branch condition in `BO` is ignored, without decrementing `ctr`.
+const BO_ALWAYS: i64 = 0b10100;
+/// Branch without testing a condition register bit.
+const BO_IGNORE_CR: i64 = 0b10000;
+/// Branch without decrementing `ctr`.
+const BO_IGNORE_CTR: i64 = 0b00100;
+
+/// The `BO` field of a conditional branch.
+fn branch_options(inst: &Instruction) -> Option<i64> {
+    match (inst.mnemomic, inst.operands()) {
+        (
+            "bc" | "bcl" | "bca" | "bcla" | "bclr" | "bclrl" | "bcctr" | "bcctrl" | "bctar"
+            | "bctarl",
+            [Operand::Immediate(bo), ..],
+        ) => Some(*bo),
+        _ => None,
+    }
+}
+
+fn links(mnemomic: &str) -> bool {
+    matches!(
+        mnemomic,
+        "bl" | "bla" | "bcl" | "bcla" | "bclrl" | "bcctrl" | "bctarl"
+    )
+}
+
+fn is_trap(mnemomic: &str) -> bool {
+    matches!(mnemomic, "tw" | "twi" | "td" | "tdi")
+}
+
+/// Number of bytes and direction of the memory access.
+fn access(mnemomic: &str) -> Option<(Option<usize>, Direction)> {
+    let (size, direction) = match mnemomic {
+        "lbz" | "lbzu" | "lbzx" | "lbzux" | "lbarx" | "plbz" | "lvebx" | "lxsibzx" | "lxvrbx" => {
+            (1, Direction::Read)
+        }
+        "lhz" | "lhzu" | "lhzx" | "lhzux" | "lha" | "lhau" | "lhax" | "lhaux" | "lhbrx"
+        | "lharx" | "plhz" | "plha" | "lvehx" | "lxsihzx" | "lxvrhx" => (2, Direction::Read),
+        "lwz" | "lwzu" | "lwzx" | "lwzux" | "lwa" | "lwax" | "lwaux" | "lwbrx" | "lwarx"
+        | "plwz" | "plwa" | "lfs" | "lfsu" | "lfsx" | "lfsux" | "lfiwax" | "lfiwzx" | "plfs"
+        | "lvewx" | "lxsiwzx" | "lxsiwax" | "lxsspx" | "lxssp" | "plxssp" | "lxvwsx" | "lxvrwx" => {
+            (4, Direction::Read)
+        }
+        "ld" | "ldu" | "ldx" | "ldux" | "ldbrx" | "ldarx" | "pld" | "lfd" | "lfdu" | "lfdx"
+        | "lfdux" | "plfd" | "lxsd" | "lxsdx" | "plxsd" | "lxvdsx" | "lxvrdx" => {
+            (8, Direction::Read)
+        }
+        "lq" | "plq" | "lqarx" | "lfdp" | "lvx" | "lvxl" | "lxv" | "lxvx" | "plxv" | "lxvd2x"
+        | "lxvw4x" | "lxvh8x" | "lxvb16x" => (16, Direction::Read),
+        "lxvp" | "lxvpx" | "plxvp" => (32, Direction::Read),
+        "stb" | "stbu" | "stbx" | "stbux" | "stbcx" | "pstb" | "stvebx" | "stxsibx" | "stxvrbx" => {
+            (1, Direction::Write)
+        }
+        "sth" | "sthu" | "sthx" | "sthux" | "sthbrx" | "sthcx" | "psth" | "stvehx" | "stxsihx"
+        | "stxvrhx" => (2, Direction::Write),
+        "stw" | "stwu" | "stwx" | "stwux" | "stwbrx" | "stwcx" | "pstw" | "stfs" | "stfsu"
+        | "stfsx" | "stfsux" | "stfiwx" | "pstfs" | "stvewx" | "stxsiwx" | "stxsspx" | "stxssp"
+        | "pstxssp" | "stxvrwx" => (4, Direction::Write),
+        "std" | "stdu" | "stdx" | "stdux" | "stdbrx" | "stdcx" | "pstd" | "stfd" | "stfdu"
+        | "stfdx" | "stfdux" | "pstfd" | "stxsd" | "stxsdx" | "pstxsd" | "stxvrdx" => {
+            (8, Direction::Write)
+        }
+        "stq" | "pstq" | "stqcx" | "stfdp" | "stvx" | "stvxl" | "stxv" | "stxvx" | "pstxv"
+        | "stxvd2x" | "stxvw4x" | "stxvh8x" | "stxvb16x" => (16, Direction::Write),
+        "stxvp" | "stxvpx" | "pstxvp" => (32, Direction::Write),
+        // the number of bytes is in a register or depends on the registers
+        "lmw" | "lswi" | "lswx" | "lxvl" | "lxvll" => return Some((None, Direction::Read)),
+        "stmw" | "stswi" | "stswx" | "stxvl" | "stxvll" => return Some((None, Direction::Write)),
+        _ => return None,
+    };
+
+    Some((Some(size), direction))
+}
+
+/// Loads and stores that write the effective address back into their base register.
+fn is_update(mnemomic: &str) -> bool {
+    access(mnemomic).is_some() && (mnemomic.ends_with('u') || mnemomic.ends_with("ux"))
+}
+
+pub(crate) fn flow(inst: &Instruction) -> FlowKind {
+    let mnemomic = inst.mnemomic;
+    let link = links(mnemomic);
+
+    match mnemomic {
+        "b" | "ba" => FlowKind::Jump,
+        "bl" | "bla" => FlowKind::Call,
+        "sc" | "scv" => FlowKind::Interrupt,
+        "rfi" | "rfid" | "hrfid" | "rfscv" => FlowKind::Return,
+        "stop" | "wait" => FlowKind::Halt,
+        _ if is_trap(mnemomic) => FlowKind::Interrupt,
+        _ => match branch_options(inst) {
+            Some(bo) if bo & BO_ALWAYS != BO_ALWAYS => FlowKind::ConditionalJump,
+            Some(_) if link => FlowKind::Call,
+            Some(_) if mnemomic == "bclr" => FlowKind::Return,
+            Some(_) => FlowKind::Jump,
+            None => FlowKind::Sequential,
+        },
+    }
+}
+
+pub(crate) fn targets(inst: &Instruction) -> Vec<usize> {
+    inst.operands()
+        .iter()
+        .filter_map(|operand| match *operand {
+            Operand::Branch(target) => Some(target as usize),
+            Operand::Absolute(target) => Some(target as usize),
+            _ => None,
+        })
+        .collect()
+}
+
+fn push(regs: &mut Vec<&'static str>, reg: &'static str) {
+    if !regs.contains(&reg) {
+        regs.push(reg);
+    }
+}
+
+fn spr_name(spr: u16) -> Option<&'static str> {
+    match spr {
+        SPR_XER => Some("xer"),
+        SPR_LR => Some("lr"),
+        SPR_CTR => Some("ctr"),
+        _ => None,
+    }
+}
+
+/// Field written by recording instructions.
+fn record_field(mnemomic: &str) -> &'static str {
+    if mnemomic.starts_with("vcmp") || mnemomic.starts_with("xvcmp") {
+        "cr6"
+    } else if mnemomic.starts_with('f')
+        || mnemomic.starts_with("mffs")
+        || mnemomic.starts_with("mtfs")
+    {
+        "cr1"
+    } else {
+        "cr0"
+    }
+}
+
+/// Collects the registers read and written by an instruction.
+pub(crate) fn registers(inst: &Instruction) -> (Vec<&'static str>, Vec<&'static str>) {
+    let mut read = Vec::new();
+    let mut written = Vec::new();
+
+    let mnemomic = inst.mnemomic;
+    let direction = access(mnemomic).map(|(_, direction)| direction);
+
+    let reads_only = direction == Some(Direction::Write)
+        || is_trap(mnemomic)
+        || branch_options(inst).is_some()
+        || matches!(
+            mnemomic,
+            "mtmsr"
+                | "mtmsrd"
+                | "mtcrf"
+                | "mtocrf"
+                | "mtvscr"
+                | "mtfsf"
+                | "mtfsfi"
+                | "mtfsb0"
+                | "mtfsb1"
+                | "wrtee"
+                | "mtsr"
+                | "mtsrin"
+                | "slbmte"
+                | "slbie"
+        )
+        || mnemomic.starts_with("tlb")
+        || mnemomic.starts_with("dst")
+        || mnemomic.starts_with("dcb")
+        || mnemomic.starts_with("icb");
+
+    // inserts keep the other bits of the destination, and the condition register logic only
+    // changes a single bit
+    let reads_and_writes = matches!(mnemomic, "rlwimi" | "rldimi")
+        || mnemomic.starts_with("vins")
+        || mnemomic.starts_with("xxins")
+        || mnemomic.starts_with("cr");
+
+    for (idx, operand) in inst.operands().iter().enumerate() {
+        let reg = match *operand {
+            Operand::Register(reg) => REGISTERS[reg as usize],
+            Operand::Base(0) => continue,
+            Operand::Base(reg) => REGISTERS[reg as usize],
+            Operand::Float(reg) => FLOAT_REGISTERS[reg as usize],
+            Operand::Vector(reg) => VECTOR_REGISTERS[reg as usize],
+            Operand::Vsx(reg) => VSX_REGISTERS[reg as usize],
+            Operand::Cr(field) => CR_FIELDS[field as usize],
+            Operand::CrBit(bit) => CR_FIELDS[bit as usize / 4],
+            Operand::Spr(spr) => match spr_name(spr) {
+                Some(reg) => reg,
+                None => continue,
+            },
+            Operand::Memory { base, .. } => {
+                if base != 0 {
+                    push(&mut read, REGISTERS[base as usize]);
+                    if is_update(mnemomic) {
+                        push(&mut written, REGISTERS[base as usize]);
+                    }
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        // indexed updates encode the base as a plain register
+        let is_destination = match mnemomic {
+            "mtspr" => idx == 0,
+            _ if is_update(mnemomic) && idx == 1 => true,
+            _ => idx == 0 && !reads_only,
+        };
+
+        if is_destination {
+            push(&mut written, reg);
+            if reads_and_writes || is_update(mnemomic) && idx == 1 {
+                push(&mut read, reg);
+            }
+        } else {
+            push(&mut read, reg);
+        }
+    }
+
+    if let Some(bo) = branch_options(inst) {
+        if bo & BO_IGNORE_CTR == 0 {
+            push(&mut read, "ctr");
+            push(&mut written, "ctr");
+        }
+
+        // the condition register bit is only read when it's tested
+        if bo & BO_IGNORE_CR != 0 {
+            if let Some(Operand::CrBit(bit)) = inst.operands().get(1) {
+                read.retain(|&reg| reg != CR_FIELDS[*bit as usize / 4]);
+            }
+        }
+    }
+
+    match mnemomic {
+        "bclr" | "bclrl" => push(&mut read, "lr"),
+        "bcctr" | "bcctrl" => push(&mut read, "ctr"),
+        "bctar" | "bctarl" => push(&mut read, "tar"),
+        "mfcr" => CR_FIELDS.iter().for_each(|field| push(&mut read, field)),
+        "mfocrf" | "mtcrf" | "mtocrf" => {
+            let mask = inst.operands().iter().find_map(|operand| match operand {
+                Operand::Immediate(mask) => Some(*mask),
+                _ => None,
+            });
+
+            let fields = (0..8).filter(|field| mask.unwrap_or(0) & (0x80 >> field) != 0);
+            for field in fields.map(|field| CR_FIELDS[field]) {
+                match mnemomic {
+                    "mfocrf" => push(&mut read, field),
+                    _ => push(&mut written, field),
+                }
+            }
+        }
+        "mcrxrx" => push(&mut read, "xer"),
+        // extended arithmetic adds the carry
+        "adde" | "addme" | "addze" | "subfe" | "subfme" | "subfze" => {
+            push(&mut read, "xer");
+            push(&mut written, "xer");
+        }
+        "addc" | "addic" | "subfc" | "subfic" | "sraw" | "srawi" | "srad" | "sradi" => {
+            push(&mut written, "xer")
+        }
+        _ => {}
+    }
+
+    if links(mnemomic) {
+        push(&mut written, "lr");
+    }
+
+    if inst.overflow {
+        push(&mut written, "xer");
+    }
+
+    if inst.record {
+        push(&mut written, record_field(mnemomic));
+    }
+
+    (read, written)
+}
+
+pub(crate) fn memory(inst: &Instruction) -> Option<MemoryAccess> {
+    access(inst.mnemomic).map(|(size, direction)| MemoryAccess { size, direction })
+}
@@ -0,0 +1,125 @@
+//! Floating point instructions on single (opcode 59) and double precision (opcode 63).
+//!
+//! Decimal floating point and the quad precision instructions aren't decoded.
+
+use crate::{cr, fpr, imm, Fields, Instruction};
+use decoder::ErrorKind;
+
+/// A-form arithmetic shared by both precisions, the single precision mnemonics have an `s`
+/// suffix.
+fn decode_arithmetic(f: Fields, single: bool) -> Option<Instruction> {
+    let (frt, fra, frb, frc) = (fpr(f.rt), fpr(f.ra), fpr(f.rb), fpr(f.rc));
+    let new = Instruction::new;
+
+    let pick = |mnemomics: [&'static str; 2]| mnemomics[single as usize];
+
+    let inst = match f.word >> 1 & 0b11111 {
+        18 if f.rc == 0 => new(pick(["fdiv", "fdivs"]), &[frt, fra, frb]),
+        20 if f.rc == 0 => new(pick(["fsub", "fsubs"]), &[frt, fra, frb]),
+        21 if f.rc == 0 => new(pick(["fadd", "fadds"]), &[frt, fra, frb]),
+        22 if f.ra == 0 && f.rc == 0 => new(pick(["fsqrt", "fsqrts"]), &[frt, frb]),
+        23 if !single => new("fsel", &[frt, fra, frc, frb]),
+        24 if f.ra == 0 && f.rc == 0 => new(pick(["fre", "fres"]), &[frt, frb]),
+        25 if f.rb == 0 => new(pick(["fmul", "fmuls"]), &[frt, fra, frc]),
+        26 if f.ra == 0 && f.rc == 0 => new(pick(["frsqrte", "frsqrtes"]), &[frt, frb]),
+        28 => new(pick(["fmsub", "fmsubs"]), &[frt, fra, frc, frb]),
+        29 => new(pick(["fmadd", "fmadds"]), &[frt, fra, frc, frb]),
+        30 => new(pick(["fnmsub", "fnmsubs"]), &[frt, fra, frc, frb]),
+        31 => new(pick(["fnmadd", "fnmadds"]), &[frt, fra, frc, frb]),
+        _ => return None,
+    };
+
+    Some(inst.with_record(f.record))
+}
+
+pub(crate) fn decode_single(f: Fields) -> Result<Instruction, ErrorKind> {
+    if let Some(inst) = decode_arithmetic(f, true) {
+        return Ok(inst);
+    }
+
+    let (frt, frb) = (fpr(f.rt), fpr(f.rb));
+    let inst = match f.xo {
+        846 if f.ra == 0 => Instruction::new("fcfids", &[frt, frb]),
+        974 if f.ra == 0 => Instruction::new("fcfidus", &[frt, frb]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst.with_record(f.record))
+}
+
+pub(crate) fn decode_double(f: Fields) -> Result<Instruction, ErrorKind> {
+    if let Some(inst) = decode_arithmetic(f, false) {
+        return Ok(inst);
+    }
+
+    let new = Instruction::new;
+    let (frt, fra, frb) = (fpr(f.rt), fpr(f.ra), fpr(f.rb));
+    let bf = cr(f.rt >> 2);
+
+    // comparisons and tests write a condition register field instead of recording
+    let inst = match f.xo {
+        _ if f.rt & 0b11 != 0 && matches!(f.xo, 0 | 32 | 64 | 128 | 160) => {
+            return Err(ErrorKind::InvalidOperand)
+        }
+        0 if !f.record => return Ok(new("fcmpu", &[bf, fra, frb])),
+        32 if !f.record => return Ok(new("fcmpo", &[bf, fra, frb])),
+        64 if !f.record && f.ra & 0b11 == 0 && f.rb == 0 => {
+            return Ok(new("mcrfs", &[bf, imm(f.ra >> 2)]))
+        }
+        128 if !f.record => return Ok(new("ftdiv", &[bf, fra, frb])),
+        160 if !f.record && f.ra == 0 => return Ok(new("ftsqrt", &[bf, frb])),
+        8 => new("fcpsgn", &[frt, fra, frb]),
+        12 if f.ra == 0 => new("frsp", &[frt, frb]),
+        14 if f.ra == 0 => new("fctiw", &[frt, frb]),
+        15 if f.ra == 0 => new("fctiwz", &[frt, frb]),
+        38 if f.ra == 0 && f.rb == 0 => new("mtfsb1", &[imm(f.rt)]),
+        40 if f.ra == 0 => new("fneg", &[frt, frb]),
+        70 if f.ra == 0 && f.rb == 0 => new("mtfsb0", &[imm(f.rt)]),
+        72 if f.ra == 0 => new("fmr", &[frt, frb]),
+        134 if f.rt & 0b11 == 0 && f.ra >> 1 == 0 && f.rb & 1 == 0 => new("mtfsfi", &mtfsfi(f)),
+        136 if f.ra == 0 => new("fnabs", &[frt, frb]),
+        142 if f.ra == 0 => new("fctiwu", &[frt, frb]),
+        143 if f.ra == 0 => new("fctiwuz", &[frt, frb]),
+        264 if f.ra == 0 => new("fabs", &[frt, frb]),
+        392 if f.ra == 0 => new("frin", &[frt, frb]),
+        424 if f.ra == 0 => new("friz", &[frt, frb]),
+        456 if f.ra == 0 => new("frip", &[frt, frb]),
+        488 if f.ra == 0 => new("frim", &[frt, frb]),
+        583 => match f.ra {
+            0 if f.rb == 0 => new("mffs", &[frt]),
+            1 if f.rb == 0 && !f.record => new("mffsce", &[frt]),
+            20 if !f.record => new("mffscdrn", &[frt, frb]),
+            21 if !f.record => new("mffscdrni", &[frt, imm(f.rb & 0b111)]),
+            22 if !f.record => new("mffscrn", &[frt, frb]),
+            23 if !f.record => new("mffscrni", &[frt, imm(f.rb & 0b11)]),
+            24 if f.rb == 0 && !f.record => new("mffsl", &[frt]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        711 => {
+            let (flm, l, w) = (f.word >> 17 & 0xff, f.word >> 25 & 1, f.word >> 16 & 1);
+            match (l, w) {
+                (0, 0) => new("mtfsf", &[imm(flm), frb]),
+                _ => new("mtfsf", &[imm(flm), frb, imm(l), imm(w)]),
+            }
+        }
+        814 if f.ra == 0 => new("fctid", &[frt, frb]),
+        815 if f.ra == 0 => new("fctidz", &[frt, frb]),
+        846 if f.ra == 0 => new("fcfid", &[frt, frb]),
+        942 if f.ra == 0 => new("fctidu", &[frt, frb]),
+        943 if f.ra == 0 => new("fctiduz", &[frt, frb]),
+        974 if f.ra == 0 => new("fcfidu", &[frt, frb]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst.with_record(f.record))
+}
+
+/// Field of the status register set to an immediate, `W` selects the upper half of the
+/// status register.
+fn mtfsfi(f: Fields) -> Vec<crate::Operand> {
+    let (bf, u, w) = (imm(f.rt >> 2), imm(f.rb >> 1), f.ra & 1);
+    match w {
+        0 => vec![bf, u],
+        w => vec![bf, u, imm(w)],
+    }
+}
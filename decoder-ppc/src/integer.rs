@@ -0,0 +1,294 @@
+//! Opcode 31, which holds the integer arithmetic, logical and shift instructions on registers,
+//! indexed loads and stores, moves to and from special registers, caches and synchronization.
+
+use crate::{base, cr, fpr, gpr, imm, vector, vsx, Decoder, Fields, Instruction, Operand};
+use decoder::ErrorKind;
+
+pub(crate) fn decode(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let is_64 = decoder.is_64;
+    let (rt, ra, rb) = (gpr(f.rt), gpr(f.ra), gpr(f.rb));
+
+    // the source of logical and shift instructions is encoded where the target usually is
+    let rs = rt;
+    let ra0 = base(f.ra);
+
+    // the condition of `isel` takes up half of the extended opcode
+    if f.word >> 1 & 0b11111 == 15 && !f.record {
+        return Ok(new("isel", &[rt, ra0, rb, crate::crb(f.rc)]));
+    }
+
+    if let Some(inst) = decode_arithmetic(f, is_64) {
+        return Ok(inst);
+    }
+
+    // shifts by an immediate of up to 63 bits, which is split across the instruction
+    let sh = imm(f.rb | (f.word >> 1 & 1) << 5);
+
+    let inst = match f.xo {
+        // logical, shift and counting instructions, that may record their result
+        24 => new("slw", &[ra, rs, rb]).with_record(f.record),
+        26 if f.rb == 0 => new("cntlzw", &[ra, rs]).with_record(f.record),
+        27 if is_64 => new("sld", &[ra, rs, rb]).with_record(f.record),
+        28 => new("and", &[ra, rs, rb]).with_record(f.record),
+        58 if is_64 && f.rb == 0 => new("cntlzd", &[ra, rs]).with_record(f.record),
+        60 => new("andc", &[ra, rs, rb]).with_record(f.record),
+        124 => new("nor", &[ra, rs, rb]).with_record(f.record),
+        284 => new("eqv", &[ra, rs, rb]).with_record(f.record),
+        316 => new("xor", &[ra, rs, rb]).with_record(f.record),
+        412 => new("orc", &[ra, rs, rb]).with_record(f.record),
+        444 => new("or", &[ra, rs, rb]).with_record(f.record),
+        476 => new("nand", &[ra, rs, rb]).with_record(f.record),
+        536 => new("srw", &[ra, rs, rb]).with_record(f.record),
+        538 if f.rb == 0 => new("cnttzw", &[ra, rs]).with_record(f.record),
+        539 if is_64 => new("srd", &[ra, rs, rb]).with_record(f.record),
+        570 if is_64 && f.rb == 0 => new("cnttzd", &[ra, rs]).with_record(f.record),
+        792 => new("sraw", &[ra, rs, rb]).with_record(f.record),
+        794 if is_64 => new("srad", &[ra, rs, rb]).with_record(f.record),
+        824 => new("srawi", &[ra, rs, imm(f.rb)]).with_record(f.record),
+        826 | 827 if is_64 => new("sradi", &[ra, rs, sh]).with_record(f.record),
+        890 | 891 if is_64 => new("extswsli", &[ra, rs, sh]).with_record(f.record),
+        922 if f.rb == 0 => new("extsh", &[ra, rs]).with_record(f.record),
+        954 if f.rb == 0 => new("extsb", &[ra, rs]).with_record(f.record),
+        986 if is_64 && f.rb == 0 => new("extsw", &[ra, rs]).with_record(f.record),
+        // load and reserve, where the last bit is a hint that the lock is acquired
+        20 | 52 | 84 | 116 | 276 => {
+            let mnemomic = match f.xo {
+                20 => "lwarx",
+                52 => "lbarx",
+                84 if is_64 => "ldarx",
+                116 => "lharx",
+                276 if is_64 && f.rt & 1 == 0 => "lqarx",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            match f.record {
+                false => new(mnemomic, &[rt, ra0, rb]),
+                true => new(mnemomic, &[rt, ra0, rb, imm(1)]),
+            }
+        }
+        // store conditional always records whether the store happened
+        150 | 182 | 214 | 694 | 726 if f.record => {
+            let mnemomic = match f.xo {
+                150 => "stwcx",
+                182 if is_64 && f.rt & 1 == 0 => "stqcx",
+                214 if is_64 => "stdcx",
+                694 => "stbcx",
+                726 => "sthcx",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            new(mnemomic, &[rs, ra0, rb]).with_record(true)
+        }
+        // VSX loads and stores use the last bit to extend the register number
+        _ if f.record => return vsx::decode_indexed(f, decoder),
+        0 | 32 if f.rt & 0b10 == 0 => {
+            let mnemomic = if f.xo == 0 { "cmp" } else { "cmpl" };
+            new(mnemomic, &[cr(f.rt >> 2), imm(f.rt & 1), ra, rb])
+        }
+        4 => new("tw", &[imm(f.rt), ra, rb]),
+        68 if is_64 => new("td", &[imm(f.rt), ra, rb]),
+        _ if matches!(f.xo, 19 | 144) && f.word & 0x0800 != 0 => {
+            return Err(ErrorKind::InvalidOpcode)
+        }
+        19 if f.word >> 20 & 1 == 0 && f.word >> 12 & 0xff == 0 => new("mfcr", &[rt]),
+        // the single field variants select exactly one field
+        19 if (f.word >> 12 & 0xff).count_ones() == 1 => {
+            new("mfocrf", &[rt, imm(f.word >> 12 & 0xff)])
+        }
+        144 if f.word >> 20 & 1 == 0 => new("mtcrf", &[imm(f.word >> 12 & 0xff), rs]),
+        144 if (f.word >> 12 & 0xff).count_ones() == 1 => {
+            new("mtocrf", &[imm(f.word >> 12 & 0xff), rs])
+        }
+        83 if f.ra == 0 && f.rb == 0 => new("mfmsr", &[rt]),
+        146 | 178 if f.word & 0x001e_f800 == 0 => {
+            let mnemomic = match f.xo {
+                146 => "mtmsr",
+                _ if is_64 => "mtmsrd",
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            // only the external interrupt and recoverable interrupt bits are written when set
+            match f.word >> 16 & 1 {
+                0 => new(mnemomic, &[rs]),
+                l => new(mnemomic, &[rs, imm(l)]),
+            }
+        }
+        // the halves of special register numbers are swapped
+        339 => new("mfspr", &[rt, spr(f)]),
+        371 => new("mftb", &[rt, spr(f)]),
+        467 => new("mtspr", &[spr(f), rs]),
+        128 if f.ra & 0b11 == 0 && f.rb == 0 => new("setb", &[rt, cr(f.ra >> 2)]),
+        192 if f.rt & 0b10 == 0 => new("cmprb", &[cr(f.rt >> 2), imm(f.rt & 1), ra, rb]),
+        224 if is_64 && f.rt & 0b11 == 0 => new("cmpeqb", &[cr(f.rt >> 2), ra, rb]),
+        576 if f.rt & 0b11 == 0 && f.ra == 0 && f.rb == 0 => new("mcrxrx", &[cr(f.rt >> 2)]),
+        755 if f.ra >> 2 == 0 && f.rb == 0 => new("darn", &[rt, imm(f.ra)]),
+        // add extended with the carry in one of the bits of `xer` selected by `CY`
+        _ if f.xo & 0xff == 170 && is_64 => new("addex", &[rt, ra, rb, imm(f.xo >> 8)]),
+        122 if f.rb == 0 => new("popcntb", &[ra, rs]),
+        154 if f.rb == 0 => new("prtyw", &[ra, rs]),
+        186 if is_64 && f.rb == 0 => new("prtyd", &[ra, rs]),
+        252 if is_64 => new("bpermd", &[ra, rs, rb]),
+        378 if f.rb == 0 => new("popcntw", &[ra, rs]),
+        506 if is_64 && f.rb == 0 => new("popcntd", &[ra, rs]),
+        508 => new("cmpb", &[ra, rs, rb]),
+        59 if is_64 => new("cntlzdm", &[ra, rs, rb]),
+        156 if is_64 => new("pdepd", &[ra, rs, rb]),
+        188 if is_64 => new("pextd", &[ra, rs, rb]),
+        220 if is_64 => new("cfuged", &[ra, rs, rb]),
+        571 if is_64 => new("cnttzdm", &[ra, rs, rb]),
+        // set a register to whether a condition register bit is set
+        384 if f.rb == 0 => new("setbc", &[rt, crate::crb(f.ra)]),
+        416 if f.rb == 0 => new("setbcr", &[rt, crate::crb(f.ra)]),
+        448 if f.rb == 0 => new("setnbc", &[rt, crate::crb(f.ra)]),
+        480 if f.rb == 0 => new("setnbcr", &[rt, crate::crb(f.ra)]),
+        265 if is_64 => new("modud", &[rt, ra, rb]),
+        267 => new("moduw", &[rt, ra, rb]),
+        777 if is_64 => new("modsd", &[rt, ra, rb]),
+        779 => new("modsw", &[rt, ra, rb]),
+        // indexed loads and stores
+        21 if is_64 => new("ldx", &[rt, ra0, rb]),
+        23 => new("lwzx", &[rt, ra0, rb]),
+        53 if is_64 => new("ldux", &[rt, ra, rb]),
+        55 => new("lwzux", &[rt, ra, rb]),
+        87 => new("lbzx", &[rt, ra0, rb]),
+        119 => new("lbzux", &[rt, ra, rb]),
+        149 if is_64 => new("stdx", &[rs, ra0, rb]),
+        151 => new("stwx", &[rs, ra0, rb]),
+        181 if is_64 => new("stdux", &[rs, ra, rb]),
+        183 => new("stwux", &[rs, ra, rb]),
+        215 => new("stbx", &[rs, ra0, rb]),
+        247 => new("stbux", &[rs, ra, rb]),
+        279 => new("lhzx", &[rt, ra0, rb]),
+        311 => new("lhzux", &[rt, ra, rb]),
+        341 if is_64 => new("lwax", &[rt, ra0, rb]),
+        343 => new("lhax", &[rt, ra0, rb]),
+        373 if is_64 => new("lwaux", &[rt, ra, rb]),
+        375 => new("lhaux", &[rt, ra, rb]),
+        407 => new("sthx", &[rs, ra0, rb]),
+        439 => new("sthux", &[rs, ra, rb]),
+        532 if is_64 => new("ldbrx", &[rt, ra0, rb]),
+        533 => new("lswx", &[rt, ra0, rb]),
+        534 => new("lwbrx", &[rt, ra0, rb]),
+        597 => new("lswi", &[rt, ra0, imm(f.rb)]),
+        660 if is_64 => new("stdbrx", &[rs, ra0, rb]),
+        661 => new("stswx", &[rs, ra0, rb]),
+        662 => new("stwbrx", &[rs, ra0, rb]),
+        725 => new("stswi", &[rs, ra0, imm(f.rb)]),
+        790 => new("lhbrx", &[rt, ra0, rb]),
+        918 => new("sthbrx", &[rs, ra0, rb]),
+        535 => new("lfsx", &[fpr(f.rt), ra0, rb]),
+        567 => new("lfsux", &[fpr(f.rt), ra, rb]),
+        599 => new("lfdx", &[fpr(f.rt), ra0, rb]),
+        631 => new("lfdux", &[fpr(f.rt), ra, rb]),
+        663 => new("stfsx", &[fpr(f.rt), ra0, rb]),
+        695 => new("stfsux", &[fpr(f.rt), ra, rb]),
+        727 => new("stfdx", &[fpr(f.rt), ra0, rb]),
+        759 => new("stfdux", &[fpr(f.rt), ra, rb]),
+        855 => new("lfiwax", &[fpr(f.rt), ra0, rb]),
+        887 => new("lfiwzx", &[fpr(f.rt), ra0, rb]),
+        983 => new("stfiwx", &[fpr(f.rt), ra0, rb]),
+        // caches and synchronization
+        22 if f.rt >> 4 == 0 => new("icbt", &[imm(f.rt), ra0, rb]),
+        54 if f.rt == 0 => new("dcbst", &[ra0, rb]),
+        86 if f.rt >> 3 == 0 => new("dcbf", &[ra0, rb, imm(f.rt & 0b111)]),
+        246 => new("dcbtst", &hinted(ra0, rb, f.rt)),
+        278 => new("dcbt", &hinted(ra0, rb, f.rt)),
+        470 if f.rt == 0 => new("dcbi", &[ra0, rb]),
+        758 if f.rt == 0 => new("dcba", &[ra0, rb]),
+        982 if f.rt == 0 => new("icbi", &[ra0, rb]),
+        1014 if f.rt == 0 => new("dcbz", &[ra0, rb]),
+        1014 if f.rt == 1 => new("dcbzl", &[ra0, rb]),
+        30 if f.rt >> 2 == 0 && f.ra == 0 && f.rb == 0 => match f.rt {
+            0 => new("wait", &[]),
+            wc => new("wait", &[imm(wc)]),
+        },
+        // segments, translation and the embedded category
+        131 if f.ra == 0 && f.rb == 0 => new("wrtee", &[rs]),
+        163 if f.rt == 0 && f.ra == 0 => new("wrteei", &[imm(f.word >> 15 & 1)]),
+        210 if f.ra >> 4 == 0 && f.rb == 0 => new("mtsr", &[imm(f.ra), rs]),
+        242 if f.ra == 0 => new("mtsrin", &[rs, rb]),
+        274 if f.rt == 0 && f.ra == 0 => new("tlbiel", &[rb]),
+        306 if f.rt == 0 && f.ra == 0 => new("tlbie", &[rb]),
+        323 => new("mfdcr", &[rt, imm(f.ra | f.rb << 5)]),
+        334 => new("mfpmr", &[rt, imm(f.ra | f.rb << 5)]),
+        402 if is_64 && f.ra == 0 => new("slbmte", &[rs, rb]),
+        434 if is_64 && f.rt == 0 && f.ra == 0 => new("slbie", &[rb]),
+        451 => new("mtdcr", &[imm(f.ra | f.rb << 5), rs]),
+        462 => new("mtpmr", &[imm(f.ra | f.rb << 5), rs]),
+        498 if is_64 && f.rt == 0 && f.ra == 0 && f.rb == 0 => new("slbia", &[]),
+        595 if f.ra >> 4 == 0 && f.rb == 0 => new("mfsr", &[rt, imm(f.ra)]),
+        659 if f.ra == 0 => new("mfsrin", &[rt, rb]),
+        786 if f.rt == 0 => new("tlbivax", &[ra0, rb]),
+        914 if f.rt == 0 => new("tlbsx", &[ra0, rb]),
+        915 if is_64 && f.ra == 0 => new("slbmfee", &[rt, rb]),
+        946 if f.rt == 0 && f.ra == 0 && f.rb == 0 => new("tlbre", &[]),
+        978 if f.rt == 0 && f.ra == 0 && f.rb == 0 => new("tlbwe", &[]),
+        566 if f.rt == 0 && f.ra == 0 && f.rb == 0 => new("tlbsync", &[]),
+        598 if f.rt >> 2 == 0 && f.ra == 0 && f.rb == 0 => new("sync", &[imm(f.rt)]),
+        854 if f.ra == 0 && f.rb == 0 => match f.rt {
+            0 => new("eieio", &[]),
+            mo => new("mbar", &[imm(mo)]),
+        },
+        _ => return vector::decode_indexed(f).or_else(|_| vsx::decode_indexed(f, decoder)),
+    };
+
+    Ok(inst)
+}
+
+/// Special register number, of which the low and high halves are swapped in the encoding.
+fn spr(f: Fields) -> Operand {
+    Operand::Spr((f.ra | f.rb << 5) as u16)
+}
+
+/// Cache touches with an optional hint about the access pattern.
+fn hinted(ra: Operand, rb: Operand, th: u32) -> Vec<Operand> {
+    match th {
+        0 => vec![ra, rb],
+        th => vec![ra, rb, imm(th)],
+    }
+}
+
+/// XO-form arithmetic, where the overflow bit extends the opcode.
+fn decode_arithmetic(f: Fields, is_64: bool) -> Option<Instruction> {
+    let (rt, ra, rb) = (gpr(f.rt), gpr(f.ra), gpr(f.rb));
+    let overflow = f.word >> 10 & 1 == 1;
+
+    let (mnemomic, binary) = match f.word >> 1 & 0x1ff {
+        8 => ("subfc", true),
+        10 => ("addc", true),
+        40 => ("subf", true),
+        104 => ("neg", false),
+        136 => ("subfe", true),
+        138 => ("adde", true),
+        200 => ("subfze", false),
+        202 => ("addze", false),
+        232 => ("subfme", false),
+        233 if is_64 => ("mulld", true),
+        234 => ("addme", false),
+        235 => ("mullw", true),
+        266 => ("add", true),
+        393 if is_64 => ("divdeu", true),
+        395 => ("divweu", true),
+        425 if is_64 => ("divde", true),
+        427 => ("divwe", true),
+        457 if is_64 => ("divdu", true),
+        459 => ("divwu", true),
+        489 if is_64 => ("divd", true),
+        491 => ("divw", true),
+        // the high half of products can't overflow
+        9 if is_64 && !overflow => ("mulhdu", true),
+        11 if !overflow => ("mulhwu", true),
+        73 if is_64 && !overflow => ("mulhd", true),
+        75 if !overflow => ("mulhw", true),
+        _ => return None,
+    };
+
+    let inst = match binary {
+        true => Instruction::new(mnemomic, &[rt, ra, rb]),
+        false if f.rb == 0 => Instruction::new(mnemomic, &[rt, ra]),
+        false => return None,
+    };
+
+    Some(inst.with_record(f.record).with_overflow(overflow))
+}
@@ -0,0 +1,839 @@
+//! PowerPC and POWER disassembler, covering the 32 and 64-bit integer, floating point, AltiVec
+//! and VSX instructions, including the prefixed instructions introduced by POWER10.
+
+mod behavior;
+mod float;
+mod integer;
+mod prefixed;
+mod tests;
+mod vector;
+mod vsx;
+
+use std::borrow::Cow;
+
+use debugvault::Index;
+use decoder::{Error, ErrorKind, FlowKind, MemoryAccess, ToTokens};
+use tokenizing::{ColorScheme, Colors, TokenStream};
+
+#[rustfmt::skip]
+pub const REGISTERS: [&str; 32] = [
+    "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7",
+    "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15",
+    "r16", "r17", "r18", "r19", "r20", "r21", "r22", "r23",
+    "r24", "r25", "r26", "r27", "r28", "r29", "r30", "r31",
+];
+
+#[rustfmt::skip]
+pub const FLOAT_REGISTERS: [&str; 32] = [
+    "f0", "f1", "f2", "f3", "f4", "f5", "f6", "f7",
+    "f8", "f9", "f10", "f11", "f12", "f13", "f14", "f15",
+    "f16", "f17", "f18", "f19", "f20", "f21", "f22", "f23",
+    "f24", "f25", "f26", "f27", "f28", "f29", "f30", "f31",
+];
+
+#[rustfmt::skip]
+pub const VECTOR_REGISTERS: [&str; 32] = [
+    "v0", "v1", "v2", "v3", "v4", "v5", "v6", "v7",
+    "v8", "v9", "v10", "v11", "v12", "v13", "v14", "v15",
+    "v16", "v17", "v18", "v19", "v20", "v21", "v22", "v23",
+    "v24", "v25", "v26", "v27", "v28", "v29", "v30", "v31",
+];
+
+#[rustfmt::skip]
+pub const VSX_REGISTERS: [&str; 64] = [
+    "vs0", "vs1", "vs2", "vs3", "vs4", "vs5", "vs6", "vs7",
+    "vs8", "vs9", "vs10", "vs11", "vs12", "vs13", "vs14", "vs15",
+    "vs16", "vs17", "vs18", "vs19", "vs20", "vs21", "vs22", "vs23",
+    "vs24", "vs25", "vs26", "vs27", "vs28", "vs29", "vs30", "vs31",
+    "vs32", "vs33", "vs34", "vs35", "vs36", "vs37", "vs38", "vs39",
+    "vs40", "vs41", "vs42", "vs43", "vs44", "vs45", "vs46", "vs47",
+    "vs48", "vs49", "vs50", "vs51", "vs52", "vs53", "vs54", "vs55",
+    "vs56", "vs57", "vs58", "vs59", "vs60", "vs61", "vs62", "vs63",
+];
+
+pub const CR_FIELDS: [&str; 8] = ["cr0", "cr1", "cr2", "cr3", "cr4", "cr5", "cr6", "cr7"];
+
+/// Conditions held by the bits of a condition register field.
+const CR_BITS: [&str; 4] = ["lt", "gt", "eq", "so"];
+
+/// Special purpose registers with a name, other than the time base.
+const SPR_XER: u16 = 1;
+const SPR_LR: u16 = 8;
+const SPR_CTR: u16 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Operand {
+    /// General purpose register.
+    Register(u8),
+    /// General purpose register that reads as zero when it's `r0`, used for addresses.
+    Base(u8),
+    /// Floating point register.
+    Float(u8),
+    /// AltiVec vector register.
+    Vector(u8),
+    /// Vector-scalar register, the first half overlaps the floating point registers and the
+    /// second half the vector registers.
+    Vsx(u8),
+    /// Condition register field.
+    Cr(u8),
+    /// Single bit of the condition register, e.g. `4*cr1+eq`.
+    CrBit(u8),
+    /// Special purpose register only known by it's number.
+    Spr(u16),
+    Immediate(i64),
+    /// Memory at a base register plus an offset, e.g. `-0x10(r1)`.
+    Memory {
+        base: u8,
+        offset: i64,
+    },
+    /// Target of a branch, relative to the instruction until it's address is known.
+    Branch(i64),
+    /// Target of a branch to an absolute address.
+    Absolute(u64),
+    /// Memory relative to the instruction, absolute once the instruction's address is known.
+    Relative(i64),
+    #[default]
+    Nothing,
+}
+
+impl ToTokens for Operand {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        match *self {
+            Self::Register(reg) => stream.push(REGISTERS[reg as usize], Colors::register()),
+            Self::Base(0) => stream.push("0", Colors::immediate()),
+            Self::Base(reg) => stream.push(REGISTERS[reg as usize], Colors::register()),
+            Self::Float(reg) => stream.push(FLOAT_REGISTERS[reg as usize], Colors::register()),
+            Self::Vector(reg) => stream.push(VECTOR_REGISTERS[reg as usize], Colors::register()),
+            Self::Vsx(reg) => stream.push(VSX_REGISTERS[reg as usize], Colors::register()),
+            Self::Cr(field) => stream.push(CR_FIELDS[field as usize], Colors::register()),
+            Self::CrBit(bit) => tokenize_cr_bit(stream, bit),
+            Self::Spr(spr) => {
+                stream.push_owned(decoder::encode_uhex(spr as u64), Colors::register())
+            }
+            Self::Immediate(imm) => {
+                stream.push_owned(decoder::encode_hex(imm), Colors::immediate())
+            }
+            Self::Memory { base, offset } => {
+                stream.push_owned(decoder::encode_hex(offset), Colors::immediate());
+                stream.push("(", Colors::brackets());
+                Self::Base(base).tokenize(stream, symbols);
+                stream.push(")", Colors::brackets());
+            }
+            Self::Branch(target) => tokenize_target(stream, symbols, target as usize),
+            Self::Absolute(target) => tokenize_target(stream, symbols, target as usize),
+            Self::Relative(target) => tokenize_target(stream, symbols, target as usize),
+            Self::Nothing => unreachable!("empty operand encountered"),
+        }
+    }
+}
+
+fn tokenize_target(stream: &mut TokenStream, symbols: &Index, target: usize) {
+    match symbols.get_sym_by_addr(target) {
+        Some(symbol) => {
+            for token in symbol.name() {
+                stream.push_token(token.clone());
+            }
+        }
+        None => stream.push_owned(format!("{target:#x}"), Colors::immediate()),
+    }
+}
+
+/// Condition register bits are written as the condition they hold within their field, e.g.
+/// `eq` for `cr0` and `4*cr1+eq` for the others.
+fn tokenize_cr_bit(stream: &mut TokenStream, bit: u8) {
+    let (field, bit) = (bit as usize / 4, bit as usize % 4);
+
+    if field != 0 {
+        stream.push("4", Colors::immediate());
+        stream.push("*", Colors::expr());
+        stream.push(CR_FIELDS[field], Colors::register());
+        stream.push("+", Colors::expr());
+    }
+
+    stream.push(CR_BITS[bit], Colors::register());
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    mnemomic: &'static str,
+    operands: [Operand; 5],
+    operand_count: usize,
+    width: u8,
+    /// The result is compared against zero into a condition register field, written as a `.`
+    /// suffix.
+    record: bool,
+    /// Overflow is recorded in `xer`, written as an `o` suffix.
+    overflow: bool,
+}
+
+impl Instruction {
+    fn new(mnemomic: &'static str, operands: &[Operand]) -> Self {
+        let mut inst = Self {
+            mnemomic,
+            operands: [Operand::Nothing; 5],
+            operand_count: operands.len(),
+            width: 4,
+            record: false,
+            overflow: false,
+        };
+
+        inst.operands[..operands.len()].copy_from_slice(operands);
+        inst
+    }
+
+    fn with_record(mut self, record: bool) -> Self {
+        self.record = record;
+        self
+    }
+
+    fn with_overflow(mut self, overflow: bool) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Instruction with a different encoded size, for the prefixed instructions.
+    fn with_width(mut self, width: u8) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn mnemomic(&self) -> &'static str {
+        self.mnemomic
+    }
+
+    pub fn operands(&self) -> &[Operand] {
+        &self.operands[..self.operand_count]
+    }
+
+    /// Whether the result is compared against zero into a condition register field.
+    pub fn record(&self) -> bool {
+        self.record
+    }
+
+    /// Whether overflow is recorded in `xer`.
+    pub fn overflow(&self) -> bool {
+        self.overflow
+    }
+}
+
+impl decoder::Decoded for Instruction {
+    fn width(&self) -> usize {
+        self.width as usize
+    }
+
+    fn tokenize_in(&self, stream: &mut TokenStream, symbols: &Index, dialect: decoder::Dialect) {
+        self.tokenize_with(stream, symbols, dialect.pseudo)
+    }
+
+    fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+        for operand in &mut self.operands[..self.operand_count] {
+            match operand {
+                Operand::Branch(offset) | Operand::Relative(offset) => {
+                    *offset = offset.wrapping_add(addr as i64)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
+    }
+
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
+
+    fn regs_read(&self) -> Vec<&'static str> {
+        behavior::registers(self).0
+    }
+
+    fn regs_written(&self) -> Vec<&'static str> {
+        behavior::registers(self).1
+    }
+
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
+}
+
+/// Which encodings to decode, defaulting to big-endian 32-bit PowerPC.
+#[derive(Default, Clone, Copy)]
+pub struct Decoder {
+    /// Also decode 64-bit and prefixed instructions.
+    pub is_64: bool,
+    /// Instructions are stored little-endian (`ppc64le`).
+    pub little_endian: bool,
+}
+
+impl decoder::Decodable for Decoder {
+    type Instruction = Instruction;
+
+    fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
+        let word = read_word(reader, self).map_err(|err| Error::new(err, 4))?;
+
+        // prefixed instructions are followed by a second word holding the actual instruction
+        if word >> 26 == 1 && self.is_64 {
+            let suffix = read_word(reader, self).map_err(|err| Error::new(err, 8))?;
+            return prefixed::decode(word, suffix).map_err(|err| Error::new(err, 8));
+        }
+
+        decode_word(word, self).map_err(|err| Error::new(err, 4))
+    }
+
+    fn max_width(&self) -> usize {
+        8
+    }
+}
+
+/// Fields of an instruction word, not all of them are meaningful for every format.
+#[derive(Clone, Copy)]
+struct Fields {
+    word: u32,
+    /// Target or source register, also the `BO`, `TO` and `BF` fields.
+    rt: u32,
+    /// First source register, also the `BI` field.
+    ra: u32,
+    rb: u32,
+    /// Third source register of A-form instructions.
+    rc: u32,
+    /// Extended opcode of X, XL and XFX-form instructions.
+    xo: u32,
+    /// Record bit, which is also used to extend register numbers of VSX instructions.
+    record: bool,
+    /// Sign extended 16-bit immediate.
+    imm: i64,
+    /// Zero extended 16-bit immediate.
+    uimm: i64,
+}
+
+impl Fields {
+    fn new(word: u32) -> Self {
+        Self {
+            word,
+            rt: word >> 21 & 0b11111,
+            ra: word >> 16 & 0b11111,
+            rb: word >> 11 & 0b11111,
+            rc: word >> 6 & 0b11111,
+            xo: word >> 1 & 0x3ff,
+            record: word & 1 == 1,
+            imm: word as u16 as i16 as i64,
+            uimm: word as u16 as i64,
+        }
+    }
+}
+
+fn gpr(num: u32) -> Operand {
+    Operand::Register(num as u8 & 0b11111)
+}
+
+fn base(num: u32) -> Operand {
+    Operand::Base(num as u8 & 0b11111)
+}
+
+fn fpr(num: u32) -> Operand {
+    Operand::Float(num as u8 & 0b11111)
+}
+
+fn vr(num: u32) -> Operand {
+    Operand::Vector(num as u8 & 0b11111)
+}
+
+fn vsr(num: u32) -> Operand {
+    Operand::Vsx(num as u8 & 0b111111)
+}
+
+fn cr(field: u32) -> Operand {
+    Operand::Cr(field as u8 & 0b111)
+}
+
+fn crb(bit: u32) -> Operand {
+    Operand::CrBit(bit as u8 & 0b11111)
+}
+
+fn imm(imm: impl Into<i64>) -> Operand {
+    Operand::Immediate(imm.into())
+}
+
+fn mem(base: u32, offset: i64) -> Operand {
+    Operand::Memory {
+        base: base as u8 & 0b11111,
+        offset,
+    }
+}
+
+/// Sign extend the low `bits` bits of a field.
+fn sign_extend(value: u32, bits: u32) -> i64 {
+    ((value << (32 - bits)) as i32 >> (32 - bits)) as i64
+}
+
+/// Next word of an instruction in the byte order of the decoder.
+fn read_word(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<u32, ErrorKind> {
+    let mut bytes = [0u8; 4];
+    reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+    Ok(if decoder.little_endian {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+/// Loads and stores of the D-form opcodes 32 to 55, in opcode order.
+#[rustfmt::skip]
+const LOAD_STORE: [&str; 24] = [
+    "lwz", "lwzu", "lbz", "lbzu", "stw", "stwu", "stb", "stbu",
+    "lhz", "lhzu", "lha", "lhau", "sth", "sthu", "lmw", "stmw",
+    "lfs", "lfsu", "lfd", "lfdu", "stfs", "stfsu", "stfd", "stfdu",
+];
+
+fn decode_word(word: u32, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let f = Fields::new(word);
+    let is_64 = decoder.is_64;
+    let new = Instruction::new;
+    let (rt, ra) = (gpr(f.rt), gpr(f.ra));
+
+    // offsets of DS and DQ-form instructions drop the low bits used for the extended opcode
+    let ds = (word & 0xfffc) as u16 as i16 as i64;
+    let dq = (word & 0xfff0) as u16 as i16 as i64;
+
+    let inst = match word >> 26 {
+        2 if is_64 => new("tdi", &[imm(f.rt), ra, imm(f.imm)]),
+        3 => new("twi", &[imm(f.rt), ra, imm(f.imm)]),
+        4 => return vector::decode(f, decoder),
+        6 if is_64 => match word & 0xf {
+            0b0000 => new("lxvp", &[vsx::pair(f.rt), mem(f.ra, dq)]),
+            0b0001 => new("stxvp", &[vsx::pair(f.rt), mem(f.ra, dq)]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        7 => new("mulli", &[rt, ra, imm(f.imm)]),
+        8 => new("subfic", &[rt, ra, imm(f.imm)]),
+        10 if f.rt & 0b10 == 0 => new("cmpli", &[cr(f.rt >> 2), imm(f.rt & 1), ra, imm(f.uimm)]),
+        11 if f.rt & 0b10 == 0 => new("cmpi", &[cr(f.rt >> 2), imm(f.rt & 1), ra, imm(f.imm)]),
+        12 => new("addic", &[rt, ra, imm(f.imm)]),
+        13 => new("addic", &[rt, ra, imm(f.imm)]).with_record(true),
+        14 => new("addi", &[rt, base(f.ra), imm(f.imm)]),
+        15 => new("addis", &[rt, base(f.ra), imm(f.imm)]),
+        16 => {
+            let offset = sign_extend(word & 0xfffc, 16);
+            let mnemomic = ["bc", "bcl", "bca", "bcla"][word as usize & 0b11];
+            new(
+                mnemomic,
+                &[imm(f.rt), crb(f.ra), target(offset, word, decoder)],
+            )
+        }
+        17 if word & 0b10 == 0b10 => match word >> 5 & 0x7f {
+            0 => new("sc", &[]),
+            lev => new("sc", &[imm(lev)]),
+        },
+        17 if word & 0x03ff_f01f == 0b01 && is_64 => new("scv", &[imm(word >> 5 & 0x7f)]),
+        18 => {
+            let offset = sign_extend(word & 0x3ff_fffc, 26);
+            let mnemomic = ["b", "bl", "ba", "bla"][word as usize & 0b11];
+            new(mnemomic, &[target(offset, word, decoder)])
+        }
+        19 => return decode_branch_control(f, decoder),
+        20 => new("rlwimi", &rotate_word(f, imm(f.rb))).with_record(f.record),
+        21 => new("rlwinm", &rotate_word(f, imm(f.rb))).with_record(f.record),
+        23 => new("rlwnm", &rotate_word(f, gpr(f.rb))).with_record(f.record),
+        24 => new("ori", &[ra, rt, imm(f.uimm)]),
+        25 => new("oris", &[ra, rt, imm(f.uimm)]),
+        26 => new("xori", &[ra, rt, imm(f.uimm)]),
+        27 => new("xoris", &[ra, rt, imm(f.uimm)]),
+        28 => new("andi", &[ra, rt, imm(f.uimm)]).with_record(true),
+        29 => new("andis", &[ra, rt, imm(f.uimm)]).with_record(true),
+        30 if is_64 => return decode_rotate(f),
+        31 => return integer::decode(f, decoder),
+        op @ 32..=55 => {
+            let mnemomic = LOAD_STORE[op as usize - 32];
+            let reg = if op >= 48 { fpr(f.rt) } else { rt };
+            new(mnemomic, &[reg, mem(f.ra, f.imm)])
+        }
+        56 if is_64 && word & 0xf == 0 => new("lq", &[rt, mem(f.ra, dq)]),
+        57 => match word & 0b11 {
+            // register pairs start at an even register
+            0b00 if f.rt & 1 == 0 => new("lfdp", &[fpr(f.rt), mem(f.ra, ds)]),
+            0b10 => new("lxsd", &[vr(f.rt), mem(f.ra, ds)]),
+            0b11 => new("lxssp", &[vr(f.rt), mem(f.ra, ds)]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        58 if is_64 => match word & 0b11 {
+            0b00 => new("ld", &[rt, mem(f.ra, ds)]),
+            0b01 => new("ldu", &[rt, mem(f.ra, ds)]),
+            0b10 => new("lwa", &[rt, mem(f.ra, ds)]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        59 => return float::decode_single(f),
+        60 => return vsx::decode(f),
+        61 => match word & 0b111 {
+            0b001 => new("lxv", &[vsr(f.rt | (word >> 3 & 1) << 5), mem(f.ra, dq)]),
+            0b101 => new("stxv", &[vsr(f.rt | (word >> 3 & 1) << 5), mem(f.ra, dq)]),
+            op if op & 0b11 == 0b00 && f.rt & 1 == 0 => new("stfdp", &[fpr(f.rt), mem(f.ra, ds)]),
+            op if op & 0b11 == 0b10 => new("stxsd", &[vr(f.rt), mem(f.ra, ds)]),
+            op if op & 0b11 == 0b11 => new("stxssp", &[vr(f.rt), mem(f.ra, ds)]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        62 if is_64 => match word & 0b11 {
+            0b00 => new("std", &[rt, mem(f.ra, ds)]),
+            0b01 => new("stdu", &[rt, mem(f.ra, ds)]),
+            0b10 => new("stq", &[rt, mem(f.ra, ds)]),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        63 => return float::decode_double(f),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Branch target, which is absolute when the `AA` bit is set.
+fn target(offset: i64, word: u32, decoder: &Decoder) -> Operand {
+    match word & 0b10 {
+        0 => Operand::Branch(offset),
+        _ if decoder.is_64 => Operand::Absolute(offset as u64),
+        _ => Operand::Absolute(offset as u32 as u64),
+    }
+}
+
+/// Operands of the 32-bit rotates, the source register is encoded where the target usually is.
+fn rotate_word(f: Fields, shift: Operand) -> [Operand; 5] {
+    [
+        gpr(f.ra),
+        gpr(f.rt),
+        shift,
+        imm(f.rc),
+        imm(f.word >> 1 & 0b11111),
+    ]
+}
+
+/// 64-bit rotates, with 6-bit shifts and masks split across the instruction.
+fn decode_rotate(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (ra, rs) = (gpr(f.ra), gpr(f.rt));
+    let sh = imm(f.rb | (f.word >> 1 & 1) << 5);
+    let mask = imm(f.rc | (f.word >> 5 & 1) << 5);
+
+    let inst = match f.word >> 1 & 0b1111 {
+        0b0000 | 0b0001 => new("rldicl", &[ra, rs, sh, mask]),
+        0b0010 | 0b0011 => new("rldicr", &[ra, rs, sh, mask]),
+        0b0100 | 0b0101 => new("rldic", &[ra, rs, sh, mask]),
+        0b0110 | 0b0111 => new("rldimi", &[ra, rs, sh, mask]),
+        0b1000 => new("rldcl", &[ra, rs, gpr(f.rb), mask]),
+        0b1001 => new("rldcr", &[ra, rs, gpr(f.rb), mask]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst.with_record(f.record))
+}
+
+/// Branches to the link, count and target address registers, condition register logic and
+/// returns from interrupts.
+fn decode_branch_control(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (bt, ba, bb) = (crb(f.rt), crb(f.ra), crb(f.rb));
+    let link = f.record as usize;
+
+    // the offset is split across three fields
+    if f.word >> 1 & 0b11111 == 2 {
+        let offset = (f.word >> 6 & 0x3ff) << 6 | f.ra << 1 | f.word & 1;
+        return Ok(new("addpcis", &[gpr(f.rt), imm(sign_extend(offset, 16))]));
+    }
+
+    // only branches have a link bit, and returns from interrupts and barriers have no operands
+    let plain = f.word & 0x03ff_f800 == 0;
+    if f.record && !matches!(f.xo, 16 | 528 | 560) {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    let inst = match f.xo {
+        0 if f.word & 0x0063_f801 == 0 => new("mcrf", &[cr(f.rt >> 2), cr(f.ra >> 2)]),
+        // the count register can't be decremented when branching to it, or the target register
+        16 | 528 | 560 if f.word & 0xe000 == 0 && (f.xo == 16 || f.rt & 0b00100 != 0) => {
+            let mnemomic = match f.xo {
+                16 => ["bclr", "bclrl"][link],
+                528 => ["bcctr", "bcctrl"][link],
+                _ => ["bctar", "bctarl"][link],
+            };
+
+            // the hint about how the branch is used is omitted when it's zero
+            match f.rb & 0b11 {
+                0 => new(mnemomic, &[imm(f.rt), crb(f.ra)]),
+                bh => new(mnemomic, &[imm(f.rt), crb(f.ra), imm(bh)]),
+            }
+        }
+        18 if plain && decoder.is_64 => new("rfid", &[]),
+        33 => new("crnor", &[bt, ba, bb]),
+        38 if plain => new("rfmci", &[]),
+        39 if plain => new("rfdi", &[]),
+        50 if plain => new("rfi", &[]),
+        51 if plain => new("rfci", &[]),
+        82 if plain && decoder.is_64 => new("rfscv", &[]),
+        129 => new("crandc", &[bt, ba, bb]),
+        150 if plain => new("isync", &[]),
+        193 => new("crxor", &[bt, ba, bb]),
+        225 => new("crnand", &[bt, ba, bb]),
+        257 => new("crand", &[bt, ba, bb]),
+        274 if plain && decoder.is_64 => new("hrfid", &[]),
+        289 => new("creqv", &[bt, ba, bb]),
+        370 if plain => new("stop", &[]),
+        417 => new("crorc", &[bt, ba, bb]),
+        449 => new("cror", &[bt, ba, bb]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Extended mnemonics of `tw`, `twi`, `td` and `tdi` for the conditions of their `TO` field.
+fn trap_mnemomic(mnemomic: &str, to: i64) -> Option<&'static str> {
+    let idx = match mnemomic {
+        "tw" => 0,
+        "twi" => 1,
+        "td" => 2,
+        _ => 3,
+    };
+
+    let mnemomics = match to {
+        1 => ["twlgt", "twlgti", "tdlgt", "tdlgti"],
+        2 => ["twllt", "twllti", "tdllt", "tdllti"],
+        4 => ["tweq", "tweqi", "tdeq", "tdeqi"],
+        5 => ["twlge", "twlgei", "tdlge", "tdlgei"],
+        6 => ["twlle", "twllei", "tdlle", "tdllei"],
+        8 => ["twgt", "twgti", "tdgt", "tdgti"],
+        12 => ["twge", "twgei", "tdge", "tdgei"],
+        16 => ["twlt", "twlti", "tdlt", "tdlti"],
+        20 => ["twle", "twlei", "tdle", "tdlei"],
+        24 => ["twne", "twnei", "tdne", "tdnei"],
+        31 => ["twu", "twui", "tdu", "tdui"],
+        _ => return None,
+    };
+
+    Some(mnemomics[idx])
+}
+
+impl Instruction {
+    /// Extended mnemonic of a conditional branch with it's link, absolute and prediction
+    /// suffixes, e.g. `beqlr+`.
+    fn pseudo_branch(&self) -> Option<(String, Vec<Operand>)> {
+        let (base, link, absolute) = match self.mnemomic {
+            "bc" => ("", false, false),
+            "bcl" => ("", true, false),
+            "bca" => ("", false, true),
+            "bcla" => ("", true, true),
+            "bclr" => ("lr", false, false),
+            "bclrl" => ("lr", true, false),
+            "bcctr" => ("ctr", false, false),
+            "bcctrl" => ("ctr", true, false),
+            "bctar" => ("tar", false, false),
+            "bctarl" => ("tar", true, false),
+            _ => return None,
+        };
+
+        let [Operand::Immediate(bo), Operand::CrBit(bi), last] = self.operands[..3] else {
+            return None;
+        };
+
+        // register branches with a hint about how they're used are left as is
+        let register = !base.is_empty();
+        if register && last != Operand::Nothing {
+            return None;
+        }
+
+        let mut operands = Vec::new();
+        let (condition, hint) = match bo {
+            // branch always, only written without a condition when branching to a register
+            0b10100 if register && bi == 0 => ("", 0),
+            // branch if the condition is true or false, with an optional prediction
+            0b00100..=0b00111 | 0b01100..=0b01111 if bo & 0b11 != 0b01 => {
+                let conditions = match bo & 0b01000 {
+                    0 => ["ge", "le", "ne", "ns"],
+                    _ => ["lt", "gt", "eq", "so"],
+                };
+
+                if bi >= 4 {
+                    operands.push(Operand::Cr(bi / 4));
+                }
+
+                (conditions[bi as usize % 4], bo & 0b11)
+            }
+            // the count register can't be decremented while branching to it
+            _ if base == "ctr" => return None,
+            // decrement the count register and branch if it's (not) zero, the ignored condition
+            // register bit has to be zero to be omitted
+            0b10000 | 0b11000 | 0b11001 if bi == 0 => ("dnz", (bo & 0b01000) >> 2 | bo & 1),
+            0b10010 | 0b11010 | 0b11011 if bi == 0 => ("dz", (bo & 0b01000) >> 2 | bo & 1),
+            // decrement the count register and also test a condition
+            0b00000 | 0b00010 | 0b01000 | 0b01010 => {
+                operands.push(Operand::CrBit(bi));
+                match bo {
+                    0b00000 => ("dnzf", 0),
+                    0b00010 => ("dzf", 0),
+                    0b01000 => ("dnzt", 0),
+                    _ => ("dzt", 0),
+                }
+            }
+            _ => return None,
+        };
+
+        if !register {
+            operands.push(last);
+        }
+
+        let mut mnemomic = format!("b{condition}{base}");
+        if link {
+            mnemomic.push('l');
+        }
+        if absolute {
+            mnemomic.push('a');
+        }
+        match hint {
+            0b10 => mnemomic.push('-'),
+            0b11 => mnemomic.push('+'),
+            _ => {}
+        }
+
+        Some((mnemomic, operands))
+    }
+
+    /// The extended mnemonic this is usually written as, together with it's operands.
+    fn pseudo(&self) -> Option<(Cow<'static, str>, Vec<Operand>)> {
+        use Operand::{Base, Cr, CrBit, Immediate as Imm, Register as Reg, Spr, Vsx};
+
+        if let Some((mnemomic, operands)) = self.pseudo_branch() {
+            return Some((Cow::Owned(mnemomic), operands));
+        }
+
+        let ops = self.operands();
+        let (mnemomic, operands): (&'static str, Vec<Operand>) = match (self.mnemomic, ops) {
+            ("addi", [rt, Base(0), v]) => ("li", vec![*rt, *v]),
+            ("addis", [rt, Base(0), v]) => ("lis", vec![*rt, *v]),
+            ("paddi", [rt, Base(0), v @ Imm(_)]) => ("pli", vec![*rt, *v]),
+            ("paddi", [rt, Base(0), v]) => ("pla", vec![*rt, *v]),
+            ("ori", [Reg(0), Reg(0), Imm(0)]) => ("nop", vec![]),
+            ("xori", [Reg(0), Reg(0), Imm(0)]) => ("xnop", vec![]),
+            ("or", [ra, rs, rb]) if rs == rb => ("mr", vec![*ra, *rs]),
+            ("nor", [ra, rs, rb]) if rs == rb => ("not", vec![*ra, *rs]),
+            ("subf", [rt, ra, rb]) => ("sub", vec![*rt, *rb, *ra]),
+            ("subfc", [rt, ra, rb]) => ("subc", vec![*rt, *rb, *ra]),
+            ("addpcis", [rt, Imm(0)]) => ("lnia", vec![*rt]),
+            ("cmpi" | "cmpli" | "cmp" | "cmpl", [Cr(bf), Imm(l), a, b]) => {
+                let mnemomic = match (self.mnemomic, l) {
+                    ("cmpi", 0) => "cmpwi",
+                    ("cmpi", _) => "cmpdi",
+                    ("cmpli", 0) => "cmplwi",
+                    ("cmpli", _) => "cmpldi",
+                    ("cmp", 0) => "cmpw",
+                    ("cmp", _) => "cmpd",
+                    ("cmpl", 0) => "cmplw",
+                    _ => "cmpld",
+                };
+
+                match bf {
+                    0 => (mnemomic, vec![*a, *b]),
+                    _ => (mnemomic, vec![Cr(*bf), *a, *b]),
+                }
+            }
+            ("rlwinm", [ra, rs, Imm(sh), Imm(mb), Imm(me)]) => match (*sh, *mb, *me) {
+                (sh, 0, 31) => ("rotlwi", vec![*ra, *rs, Imm(sh)]),
+                (0, mb, 31) => ("clrlwi", vec![*ra, *rs, Imm(mb)]),
+                (sh, 0, me) if sh + me == 31 => ("slwi", vec![*ra, *rs, Imm(sh)]),
+                (sh, mb, 31) if sh + mb == 32 => ("srwi", vec![*ra, *rs, Imm(mb)]),
+                (0, 0, me) => ("clrrwi", vec![*ra, *rs, Imm(31 - me)]),
+                _ => return None,
+            },
+            ("rlwnm", [ra, rs, rb, Imm(0), Imm(31)]) => ("rotlw", vec![*ra, *rs, *rb]),
+            ("rldicl", [ra, rs, Imm(sh), Imm(mb)]) => match (*sh, *mb) {
+                (sh, 0) => ("rotldi", vec![*ra, *rs, Imm(sh)]),
+                (0, mb) => ("clrldi", vec![*ra, *rs, Imm(mb)]),
+                (sh, mb) if sh + mb == 64 => ("srdi", vec![*ra, *rs, Imm(mb)]),
+                _ => return None,
+            },
+            ("rldicr", [ra, rs, Imm(sh), Imm(me)]) => match (*sh, *me) {
+                (sh, me) if sh + me == 63 => ("sldi", vec![*ra, *rs, Imm(sh)]),
+                (0, me) => ("clrrdi", vec![*ra, *rs, Imm(63 - me)]),
+                _ => return None,
+            },
+            ("rldcl", [ra, rs, rb, Imm(0)]) => ("rotld", vec![*ra, *rs, *rb]),
+            ("mfspr", [rt, Spr(SPR_XER)]) => ("mfxer", vec![*rt]),
+            ("mfspr", [rt, Spr(SPR_LR)]) => ("mflr", vec![*rt]),
+            ("mfspr", [rt, Spr(SPR_CTR)]) => ("mfctr", vec![*rt]),
+            ("mtspr", [Spr(SPR_XER), rs]) => ("mtxer", vec![*rs]),
+            ("mtspr", [Spr(SPR_LR), rs]) => ("mtlr", vec![*rs]),
+            ("mtspr", [Spr(SPR_CTR), rs]) => ("mtctr", vec![*rs]),
+            ("mftb", [rt, Spr(268)]) => ("mftb", vec![*rt]),
+            ("mftb", [rt, Spr(269)]) => ("mftbu", vec![*rt]),
+            ("mtcrf", [Imm(0xff), rs]) => ("mtcr", vec![*rs]),
+            ("crxor", [bt, ba, bb]) if bt == ba && ba == bb => ("crclr", vec![*bt]),
+            ("creqv", [bt, ba, bb]) if bt == ba && ba == bb => ("crset", vec![*bt]),
+            ("cror", [bt, ba, bb]) if ba == bb => ("crmove", vec![*bt, *ba]),
+            ("crnor", [bt, ba, bb]) if ba == bb => ("crnot", vec![*bt, *ba]),
+            ("tw", [Imm(31), Reg(0), Reg(0)]) => ("trap", vec![]),
+            ("tw" | "twi" | "td" | "tdi", [Imm(to), a, b]) => {
+                (trap_mnemomic(self.mnemomic, *to)?, vec![*a, *b])
+            }
+            ("sync", [Imm(0)]) => ("sync", vec![]),
+            ("sync", [Imm(1)]) => ("lwsync", vec![]),
+            ("sync", [Imm(2)]) => ("ptesync", vec![]),
+            ("dcbf", [ra, rb, Imm(1)]) => ("dcbfl", vec![*ra, *rb]),
+            ("dcbf", [ra, rb, Imm(3)]) => ("dcbflp", vec![*ra, *rb]),
+            ("isel", [rt, ra, rb, CrBit(bc @ 0..=2)]) => (
+                ["isellt", "iselgt", "iseleq"][*bc as usize],
+                vec![*rt, *ra, *rb],
+            ),
+            ("vor", [vt, va, vb]) if va == vb => ("vmr", vec![*vt, *va]),
+            ("vnor", [vt, va, vb]) if va == vb => ("vnot", vec![*vt, *va]),
+            ("xxlor", [xt, xa, xb]) if xa == xb => ("xxlmr", vec![*xt, *xa]),
+            ("xxlnor", [xt, xa, xb]) if xa == xb => ("xxlnot", vec![*xt, *xa]),
+            ("xvcpsgnsp", [xt, xa, xb]) if xa == xb => ("xvmovsp", vec![*xt, *xa]),
+            ("xvcpsgndp", [xt, xa, xb]) if xa == xb => ("xvmovdp", vec![*xt, *xa]),
+            ("xxpermdi", [xt, xa @ Vsx(a), xb @ Vsx(b), Imm(dm)]) => match (a == b, *dm) {
+                (true, 0) => ("xxspltd", vec![*xt, *xa, Imm(0)]),
+                (true, 3) => ("xxspltd", vec![*xt, *xa, Imm(1)]),
+                (true, 2) => ("xxswapd", vec![*xt, *xa]),
+                (_, 0) => ("xxmrghd", vec![*xt, *xa, *xb]),
+                (_, 3) => ("xxmrgld", vec![*xt, *xa, *xb]),
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        Some((Cow::Borrowed(mnemomic), operands))
+    }
+
+    /// Tokenize the instruction, either using extended mnemonics or the canonical encoding.
+    pub fn tokenize_with(&self, stream: &mut TokenStream, symbols: &Index, pseudo: bool) {
+        let (mnemomic, operands) = match self.pseudo().filter(|_| pseudo) {
+            Some((mnemomic, operands)) => (mnemomic, operands),
+            None => (Cow::Borrowed(self.mnemomic), self.operands().to_vec()),
+        };
+
+        match (mnemomic, self.overflow, self.record) {
+            (Cow::Borrowed(mnemomic), false, false) => stream.push(mnemomic, Colors::opcode()),
+            (mnemomic, overflow, record) => {
+                let overflow = if overflow { "o" } else { "" };
+                let record = if record { "." } else { "" };
+                stream.push_owned(format!("{mnemomic}{overflow}{record}"), Colors::opcode());
+            }
+        }
+
+        // there are operands
+        if !operands.is_empty() {
+            stream.push(" ", Colors::spacing());
+
+            // iterate through operands
+            for (idx, operand) in operands.iter().enumerate() {
+                operand.tokenize(stream, symbols);
+
+                // separator
+                if idx != operands.len() - 1 {
+                    stream.push(", ", Colors::expr());
+                }
+            }
+        }
+    }
+}
+
+impl decoder::ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        self.tokenize_with(stream, symbols, decoder::Dialect::default().pseudo)
+    }
+}
@@ -0,0 +1,70 @@
+//! Prefixed instructions of POWER10, where a prefix word extends the immediate of the
+//! instruction word following it to 34 bits.
+//!
+//! Only the loads, stores and `paddi` are decoded, which may address memory relative to the
+//! instruction.
+
+use crate::vsx::pair;
+use crate::{base, fpr, gpr, imm, mem, vr, vsr, Instruction, Operand};
+use decoder::ErrorKind;
+
+/// Prefix of the 8-byte load and store instructions.
+const PREFIX_8LS: u32 = 0b00;
+/// Prefix of the modified load and store instructions, which also includes `paddi`.
+const PREFIX_MLS: u32 = 0b10;
+
+pub(crate) fn decode(prefix: u32, suffix: u32) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let kind = prefix >> 24 & 0b11;
+    let relative = prefix >> 20 & 1 == 1;
+    let (rt, ra) = (suffix >> 21 & 0b11111, suffix >> 16 & 0b11111);
+
+    // the 34-bit displacement is split across both words
+    let offset = ((prefix as i64 & 0x3ffff) << 16 | suffix as i64 & 0xffff) << 30 >> 30;
+
+    // relative addressing replaces the base register
+    let address = match (relative, ra) {
+        (false, _) => mem(ra, offset),
+        (true, 0) => Operand::Relative(offset),
+        (true, _) => return Err(ErrorKind::InvalidOperand),
+    };
+
+    if prefix >> 23 & 1 != 0 {
+        return Err(ErrorKind::InvalidPrefixes);
+    }
+
+    let inst = match (kind, suffix >> 26) {
+        (PREFIX_MLS, 14) => match relative {
+            false => new("paddi", &[gpr(rt), base(ra), imm(offset)]),
+            true => new("paddi", &[gpr(rt), base(ra), Operand::Relative(offset)]),
+        },
+        (PREFIX_MLS, 32) => new("plwz", &[gpr(rt), address]),
+        (PREFIX_MLS, 34) => new("plbz", &[gpr(rt), address]),
+        (PREFIX_MLS, 36) => new("pstw", &[gpr(rt), address]),
+        (PREFIX_MLS, 38) => new("pstb", &[gpr(rt), address]),
+        (PREFIX_MLS, 40) => new("plhz", &[gpr(rt), address]),
+        (PREFIX_MLS, 42) => new("plha", &[gpr(rt), address]),
+        (PREFIX_MLS, 44) => new("psth", &[gpr(rt), address]),
+        (PREFIX_MLS, 48) => new("plfs", &[fpr(rt), address]),
+        (PREFIX_MLS, 50) => new("plfd", &[fpr(rt), address]),
+        (PREFIX_MLS, 52) => new("pstfs", &[fpr(rt), address]),
+        (PREFIX_MLS, 54) => new("pstfd", &[fpr(rt), address]),
+        (PREFIX_8LS, 41) => new("plwa", &[gpr(rt), address]),
+        (PREFIX_8LS, 42) => new("plxsd", &[vr(rt), address]),
+        (PREFIX_8LS, 43) => new("plxssp", &[vr(rt), address]),
+        (PREFIX_8LS, 46) => new("pstxsd", &[vr(rt), address]),
+        (PREFIX_8LS, 47) => new("pstxssp", &[vr(rt), address]),
+        (PREFIX_8LS, op @ (50 | 51)) => new("plxv", &[vsr(rt | (op & 1) << 5), address]),
+        (PREFIX_8LS, op @ (54 | 55)) => new("pstxv", &[vsr(rt | (op & 1) << 5), address]),
+        (PREFIX_8LS, 56) => new("plq", &[gpr(rt), address]),
+        (PREFIX_8LS, 57) => new("pld", &[gpr(rt), address]),
+        (PREFIX_8LS, 58) => new("plxvp", &[pair(rt), address]),
+        (PREFIX_8LS, 60) => new("pstq", &[gpr(rt), address]),
+        (PREFIX_8LS, 61) => new("pstd", &[gpr(rt), address]),
+        (PREFIX_8LS, 62) => new("pstxvp", &[pair(rt), address]),
+        // MMA and the 8-byte register to register instructions
+        _ => return Err(ErrorKind::IncompleteDecoder),
+    };
+
+    Ok(inst.with_width(8))
+}
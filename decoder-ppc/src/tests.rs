@@ -0,0 +1,259 @@
+#![cfg(test)]
+
+use decoder::{Decodable, Decoded, ToTokens};
+
+fn test_display(bytes: &[u8], str: &str) {
+    test_display_with(&crate::Decoder::default(), bytes, str);
+}
+
+fn test_display_with(decoder: &crate::Decoder, bytes: &[u8], str: &str) {
+    let mut reader = decoder::Reader::new(bytes);
+    let mut line = tokenizing::TokenStream::new();
+    let symbols = debugvault::Index::default();
+
+    let decoded = match decoder.decode(&mut reader) {
+        Ok(mut inst) => {
+            inst.update_rel_addrs(0x1000, None);
+            inst.tokenize(&mut line, &symbols);
+            line.to_string()
+        }
+        Err(err) => format!("{err:?}"),
+    };
+
+    assert_eq!(decoded, str);
+}
+
+const PPC64: crate::Decoder = crate::Decoder {
+    is_64: true,
+    little_endian: false,
+};
+
+#[test]
+fn integer() {
+    test_display(&[0x38, 0x60, 0xff, 0xff], "li r3, -0x1");
+    test_display(&[0x7c, 0x3f, 0x0b, 0x78], "mr r31, r1");
+    test_display(&[0x54, 0x83, 0x10, 0x3a], "slwi r3, r4, 0x2");
+    test_display(&[0x7f, 0xa3, 0x20, 0x00], "cmpd cr7, r3, r4");
+    test_display(&[0x80, 0x61, 0x00, 0x08], "lwz r3, 0x8(r1)");
+    test_display(&[0x7c, 0x08, 0x02, 0xa6], "mflr r0");
+    test_display(&[0x7d, 0x8f, 0xf1, 0x20], "mtcr r12");
+    test_display(&[0x7c, 0x64, 0x28, 0x9e], "iseleq r3, r4, r5");
+    test_display(&[0x7c, 0x60, 0x21, 0x2d], "stwcx. r3, 0, r4");
+    test_display(&[0x7c, 0x83, 0x20, 0x08], "tweq r3, r4");
+    test_display(&[0x44, 0x00, 0x00, 0x02], "sc");
+}
+
+#[test]
+fn ppc64() {
+    test_display_with(&PPC64, &[0xe8, 0x61, 0x00, 0x08], "ld r3, 0x8(r1)");
+    test_display_with(&PPC64, &[0xf8, 0x21, 0xff, 0x91], "stdu r1, -0x70(r1)");
+
+    // only decoded as PowerPC64
+    test_display(
+        &[0xe8, 0x61, 0x00, 0x08],
+        "Error { kind: InvalidOpcode, size: 4 }",
+    );
+}
+
+#[test]
+fn branches() {
+    test_display(&[0x41, 0x82, 0x00, 0x10], "beq 0x1010");
+    test_display(&[0x40, 0x9e, 0xff, 0xf8], "bne cr7, 0xff8");
+    test_display(&[0x42, 0x00, 0x00, 0x20], "bdnz 0x1020");
+    test_display(&[0x4b, 0xff, 0xff, 0xfd], "bl 0xffc");
+    test_display(&[0x4e, 0x80, 0x00, 0x20], "blr");
+    test_display(&[0x4e, 0x80, 0x04, 0x21], "bctrl");
+    test_display(&[0x4d, 0x86, 0x00, 0x20], "beqlr cr1");
+}
+
+#[test]
+fn float() {
+    test_display(&[0xfc, 0x22, 0x20, 0xfb], "fmadd. f1, f2, f3, f4");
+    test_display(&[0xfc, 0x82, 0x18, 0x00], "fcmpu cr1, f2, f3");
+    test_display(&[0xfc, 0x20, 0x10, 0x18], "frsp f1, f2");
+    test_display(&[0xc8, 0x21, 0x00, 0x08], "lfd f1, 0x8(r1)");
+    test_display(&[0xdf, 0xe1, 0xff, 0xf8], "stfdu f31, -0x8(r1)");
+}
+
+#[test]
+fn vector() {
+    test_display(&[0x10, 0x22, 0x1c, 0x86], "vcmpequw. v1, v2, v3");
+    test_display(&[0x10, 0x30, 0x03, 0x8c], "vspltisw v1, -0x10");
+    test_display(&[0x10, 0x22, 0x14, 0x84], "vmr v1, v2");
+    test_display(&[0xf3, 0xe0, 0x0b, 0x05], "xvadddp vs63, vs32, vs1");
+    test_display(&[0x7c, 0x00, 0x1e, 0x99], "lxvd2x vs32, 0, r3");
+    test_display(&[0xf0, 0x22, 0x14, 0x90], "xxlmr vs1, vs2");
+    test_display_with(&PPC64, &[0xf4, 0x21, 0x00, 0x1d], "stxv vs33, 0x10(r1)");
+    test_display_with(&PPC64, &[0x18, 0x63, 0x00, 0x20], "lxvp vs34, 0x20(r3)");
+    test_display_with(&PPC64, &[0x7c, 0x45, 0x01, 0x67], "mtvsrd vs34, r5");
+}
+
+#[test]
+fn prefixed() {
+    test_display_with(
+        &PPC64,
+        &[0x04, 0x00, 0x00, 0x00, 0xe4, 0x64, 0x00, 0x10],
+        "pld r3, 0x10(r4)",
+    );
+    test_display_with(
+        &PPC64,
+        &[0x06, 0x00, 0x12, 0x34, 0x38, 0x60, 0x56, 0x78],
+        "pli r3, 0x12345678",
+    );
+    test_display_with(
+        &PPC64,
+        &[0x04, 0x02, 0x00, 0x00, 0xf7, 0xe1, 0x00, 0x00],
+        "pstd r31, -0x200000000(r1)",
+    );
+
+    // relative to the prefix
+    test_display_with(
+        &PPC64,
+        &[0x06, 0x10, 0x00, 0x00, 0x38, 0x60, 0x00, 0x20],
+        "pla r3, 0x1020",
+    );
+    test_display_with(
+        &PPC64,
+        &[0x04, 0x10, 0x00, 0x00, 0xe4, 0xa0, 0x00, 0x40],
+        "pld r5, 0x1040",
+    );
+}
+
+#[test]
+fn little_endian() {
+    let decoder = crate::Decoder {
+        is_64: true,
+        little_endian: true,
+    };
+
+    test_display_with(&decoder, &[0xff, 0xff, 0x60, 0x38], "li r3, -0x1");
+    test_display_with(
+        &decoder,
+        &[0x00, 0x00, 0x00, 0x04, 0x10, 0x00, 0x64, 0xe4],
+        "pld r3, 0x10(r4)",
+    );
+}
+
+#[test]
+fn pseudo() {
+    let tokenize = |bytes: &[u8], pseudo: bool| {
+        let mut reader = decoder::Reader::new(bytes);
+        let mut line = tokenizing::TokenStream::new();
+        let inst = PPC64.decode(&mut reader).unwrap();
+        inst.tokenize_with(&mut line, &debugvault::Index::default(), pseudo);
+        line.to_string()
+    };
+
+    assert_eq!(tokenize(&[0x60, 0x00, 0x00, 0x00], true), "nop");
+    assert_eq!(
+        tokenize(&[0x60, 0x00, 0x00, 0x00], false),
+        "ori r0, r0, 0x0"
+    );
+
+    assert_eq!(tokenize(&[0x7c, 0x08, 0x02, 0xa6], false), "mfspr r0, 0x8");
+    assert_eq!(
+        tokenize(&[0x7f, 0xa3, 0x20, 0x00], false),
+        "cmp cr7, 0x1, r3, r4"
+    );
+    assert_eq!(
+        tokenize(&[0x54, 0x83, 0x10, 0x3a], false),
+        "rlwinm r3, r4, 0x2, 0x0, 0x1d"
+    );
+
+    // bclr 0x14, lt
+    assert_eq!(tokenize(&[0x4e, 0x80, 0x00, 0x20], true), "blr");
+    assert_eq!(tokenize(&[0x4e, 0x80, 0x00, 0x20], false), "bclr 0x14, lt");
+    assert_eq!(
+        tokenize(&[0x4d, 0x86, 0x00, 0x20], false),
+        "bclr 0xc, 4*cr1+eq"
+    );
+
+    assert_eq!(
+        tokenize(&[0x06, 0x00, 0x12, 0x34, 0x38, 0x60, 0x56, 0x78], false),
+        "paddi r3, 0, 0x12345678"
+    );
+}
+
+#[test]
+fn rel_addrs() {
+    let resolve = |bytes: &[u8], addr: usize| {
+        let mut reader = decoder::Reader::new(bytes);
+        let mut inst = PPC64.decode(&mut reader).unwrap();
+        inst.update_rel_addrs(addr, None);
+        inst.targets()
+    };
+
+    // beq 0x10
+    assert_eq!(resolve(&[0x41, 0x82, 0x00, 0x10], 0x2000), vec![0x2010]);
+    // bl -0x4
+    assert_eq!(resolve(&[0x4b, 0xff, 0xff, 0xfd], 0x2000), vec![0x1ffc]);
+    // blr has no static target
+    assert_eq!(
+        resolve(&[0x4e, 0x80, 0x00, 0x20], 0x2000),
+        Vec::<usize>::new()
+    );
+}
+
+#[test]
+fn behavior() {
+    use decoder::{Direction, FlowKind, MemoryAccess};
+
+    let decode = |bytes: &[u8]| {
+        let mut reader = decoder::Reader::new(bytes);
+        PPC64.decode(&mut reader).unwrap()
+    };
+
+    // blr
+    let inst = decode(&[0x4e, 0x80, 0x00, 0x20]);
+    assert_eq!(inst.flow(), FlowKind::Return);
+    assert_eq!(inst.regs_read(), vec!["lr"]);
+
+    // bctrl
+    let inst = decode(&[0x4e, 0x80, 0x04, 0x21]);
+    assert_eq!(inst.flow(), FlowKind::Call);
+    assert_eq!(inst.regs_read(), vec!["ctr"]);
+    assert_eq!(inst.regs_written(), vec!["lr"]);
+
+    // bdnz 0x20
+    let inst = decode(&[0x42, 0x00, 0x00, 0x20]);
+    assert_eq!(inst.flow(), FlowKind::ConditionalJump);
+    assert_eq!(inst.regs_read(), vec!["ctr"]);
+    assert_eq!(inst.regs_written(), vec!["ctr"]);
+
+    // stdu r1, -0x70(r1)
+    let inst = decode(&[0xf8, 0x21, 0xff, 0x91]);
+    assert_eq!(inst.regs_read(), vec!["r1"]);
+    assert_eq!(inst.regs_written(), vec!["r1"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Write
+        })
+    );
+
+    // fmadd. f1, f2, f3, f4
+    let inst = decode(&[0xfc, 0x22, 0x20, 0xfb]);
+    assert_eq!(inst.regs_read(), vec!["f2", "f3", "f4"]);
+    assert_eq!(inst.regs_written(), vec!["f1", "cr1"]);
+
+    // vcmpequw. v1, v2, v3
+    let inst = decode(&[0x10, 0x22, 0x1c, 0x86]);
+    assert_eq!(inst.regs_written(), vec!["v1", "cr6"]);
+
+    // lxvp vs34, 0x20(r3)
+    let inst = decode(&[0x18, 0x63, 0x00, 0x20]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(32),
+            direction: Direction::Read
+        })
+    );
+
+    // tweq r3, r4
+    assert_eq!(
+        decode(&[0x7c, 0x83, 0x20, 0x08]).flow(),
+        FlowKind::Interrupt
+    );
+}
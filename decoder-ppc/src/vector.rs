@@ -0,0 +1,573 @@
+//! AltiVec (VMX) instructions of opcode 4 and the vector loads and stores of opcode 31.
+
+use crate::{base, gpr, imm, sign_extend, vr, Decoder, Fields, Instruction};
+use decoder::ErrorKind;
+
+/// Operands of a VX-form instruction.
+#[derive(Clone, Copy)]
+enum Shape {
+    /// `vD, vA, vB`.
+    Binary,
+    /// `vD, vB`, where `vA` must be zero.
+    Unary,
+    /// `vD, vA`, where `vB` must be zero.
+    UnaryA,
+    /// `vD, vB, UIM`, with the immediate in the `vA` field.
+    Immediate,
+    /// `vD, SIM`, a sign extended immediate in the `vA` field.
+    Splat,
+    /// `vD` only.
+    Target,
+    /// `vB` only.
+    Source,
+}
+
+pub(crate) fn decode(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let (vt, va, vb, vc) = (vr(f.rt), vr(f.ra), vr(f.rb), vr(f.rc));
+
+    // move of a mask of bytes, with the immediate split across the instruction
+    if f.word >> 1 & 0b11111 == 10 {
+        let mask = (f.word >> 6 & 0x3ff) << 6 | f.ra << 1 | f.word & 1;
+        return Ok(new("mtvsrbmi", &[vt, imm(mask)]));
+    }
+
+    // VA-form, with a fourth register
+    let inst = match f.word & 0x3f {
+        22 if f.rc >> 4 == 0 => {
+            let mnemomic = if f.rc >> 3 == 0 { "vsldbi" } else { "vsrdbi" };
+            Some(new(mnemomic, &[vt, va, vb, imm(f.rc & 0b111)]))
+        }
+        23 => Some(new("vmsumcud", &[vt, va, vb, vc])),
+        // extracts of a doubled element, indexed by a general purpose register
+        24..=31 => {
+            let mnemomic = [
+                "vextdubvlx",
+                "vextdubvrx",
+                "vextduhvlx",
+                "vextduhvrx",
+                "vextduwvlx",
+                "vextduwvrx",
+                "vextddvlx",
+                "vextddvrx",
+            ][(f.word & 0b111) as usize];
+
+            Some(new(mnemomic, &[vt, va, vb, gpr(f.rc)]))
+        }
+        32 => Some(new("vmhaddshs", &[vt, va, vb, vc])),
+        33 => Some(new("vmhraddshs", &[vt, va, vb, vc])),
+        34 => Some(new("vmladduhm", &[vt, va, vb, vc])),
+        35 => Some(new("vmsumudm", &[vt, va, vb, vc])),
+        36 => Some(new("vmsumubm", &[vt, va, vb, vc])),
+        37 => Some(new("vmsummbm", &[vt, va, vb, vc])),
+        38 => Some(new("vmsumuhm", &[vt, va, vb, vc])),
+        39 => Some(new("vmsumuhs", &[vt, va, vb, vc])),
+        40 => Some(new("vmsumshm", &[vt, va, vb, vc])),
+        41 => Some(new("vmsumshs", &[vt, va, vb, vc])),
+        42 => Some(new("vsel", &[vt, va, vb, vc])),
+        43 => Some(new("vperm", &[vt, va, vb, vc])),
+        44 if f.rc >> 4 == 0 => Some(new("vsldoi", &[vt, va, vb, imm(f.rc & 0xf)])),
+        45 => Some(new("vpermxor", &[vt, va, vb, vc])),
+        46 => Some(new("vmaddfp", &[vt, va, vc, vb])),
+        47 => Some(new("vnmsubfp", &[vt, va, vc, vb])),
+        48 if decoder.is_64 => Some(new("maddhd", &gprs(f))),
+        49 if decoder.is_64 => Some(new("maddhdu", &gprs(f))),
+        51 if decoder.is_64 => Some(new("maddld", &gprs(f))),
+        59 => Some(new("vpermr", &[vt, va, vb, vc])),
+        60 => Some(new("vaddeuqm", &[vt, va, vb, vc])),
+        61 => Some(new("vaddecuq", &[vt, va, vb, vc])),
+        62 => Some(new("vsubeuqm", &[vt, va, vb, vc])),
+        63 => Some(new("vsubecuq", &[vt, va, vb, vc])),
+        _ => None,
+    };
+
+    if let Some(inst) = inst {
+        return Ok(inst);
+    }
+
+    // VC-form comparisons, which may record whether all or none of the elements matched
+    let compare = match f.word & 0x3ff {
+        6 => Some("vcmpequb"),
+        7 => Some("vcmpneb"),
+        70 => Some("vcmpequh"),
+        71 => Some("vcmpneh"),
+        134 => Some("vcmpequw"),
+        135 => Some("vcmpnew"),
+        198 => Some("vcmpeqfp"),
+        199 => Some("vcmpequd"),
+        263 => Some("vcmpnezb"),
+        327 => Some("vcmpnezh"),
+        391 => Some("vcmpnezw"),
+        454 => Some("vcmpgefp"),
+        518 => Some("vcmpgtub"),
+        582 => Some("vcmpgtuh"),
+        646 => Some("vcmpgtuw"),
+        710 => Some("vcmpgtfp"),
+        711 => Some("vcmpgtud"),
+        774 => Some("vcmpgtsb"),
+        838 => Some("vcmpgtsh"),
+        902 => Some("vcmpgtsw"),
+        966 => Some("vcmpbfp"),
+        967 => Some("vcmpgtsd"),
+        455 => Some("vcmpequq"),
+        647 => Some("vcmpgtuq"),
+        903 => Some("vcmpgtsq"),
+        _ => None,
+    };
+
+    if let Some(mnemomic) = compare {
+        return Ok(new(mnemomic, &[vt, va, vb]).with_record(f.word >> 10 & 1 == 1));
+    }
+
+    use Shape::*;
+    let (mnemomic, shape) = match f.word & 0x7ff {
+        0 => ("vaddubm", Binary),
+        1 => ("vmul10cuq", UnaryA),
+        2 => ("vmaxub", Binary),
+        4 => ("vrlb", Binary),
+        5 => ("vrlq", Binary),
+        8 => ("vmuloub", Binary),
+        10 => ("vaddfp", Binary),
+        11 => ("vdivuq", Binary),
+        12 => ("vmrghb", Binary),
+        14 => ("vpkuhum", Binary),
+        64 => ("vadduhm", Binary),
+        65 => ("vmul10ecuq", Binary),
+        66 => ("vmaxuh", Binary),
+        68 => ("vrlh", Binary),
+        72 => ("vmulouh", Binary),
+        74 => ("vsubfp", Binary),
+        76 => ("vmrghh", Binary),
+        78 => ("vpkuwum", Binary),
+        128 => ("vadduwm", Binary),
+        130 => ("vmaxuw", Binary),
+        132 => ("vrlw", Binary),
+        133 => ("vrlwmi", Binary),
+        136 => ("vmulouw", Binary),
+        137 => ("vmuluwm", Binary),
+        139 => ("vdivuw", Binary),
+        140 => ("vmrghw", Binary),
+        142 => ("vpkuhus", Binary),
+        192 => ("vaddudm", Binary),
+        194 => ("vmaxud", Binary),
+        196 => ("vrld", Binary),
+        197 => ("vrldmi", Binary),
+        200 => ("vmuloud", Binary),
+        203 => ("vdivud", Binary),
+        206 => ("vpkuwus", Binary),
+        256 => ("vadduqm", Binary),
+        258 => ("vmaxsb", Binary),
+        260 => ("vslb", Binary),
+        261 => ("vslq", Binary),
+        264 => ("vmulosb", Binary),
+        266 => ("vrefp", Unary),
+        267 => ("vdivsq", Binary),
+        268 => ("vmrglb", Binary),
+        270 => ("vpkshus", Binary),
+        320 => ("vaddcuq", Binary),
+        322 => ("vmaxsh", Binary),
+        324 => ("vslh", Binary),
+        328 => ("vmulosh", Binary),
+        330 => ("vrsqrtefp", Unary),
+        332 => ("vmrglh", Binary),
+        334 => ("vpkswus", Binary),
+        384 => ("vaddcuw", Binary),
+        386 => ("vmaxsw", Binary),
+        388 => ("vslw", Binary),
+        389 => ("vrlwnm", Binary),
+        392 => ("vmulosw", Binary),
+        394 => ("vexptefp", Unary),
+        395 => ("vdivsw", Binary),
+        396 => ("vmrglw", Binary),
+        398 => ("vpkshss", Binary),
+        450 => ("vmaxsd", Binary),
+        452 => ("vsl", Binary),
+        453 => ("vrldnm", Binary),
+        456 => ("vmulosd", Binary),
+        457 => ("vmulld", Binary),
+        458 => ("vlogefp", Unary),
+        459 => ("vdivsd", Binary),
+        462 => ("vpkswss", Binary),
+        512 => ("vaddubs", Binary),
+        513 => ("vmul10uq", UnaryA),
+        514 => ("vminub", Binary),
+        516 => ("vsrb", Binary),
+        517 => ("vsrq", Binary),
+        520 => ("vmuleub", Binary),
+        522 => ("vrfin", Unary),
+        523 => ("vdiveuq", Binary),
+        524 => ("vspltb", Immediate),
+        525 => ("vextractub", Immediate),
+        526 => ("vupkhsb", Unary),
+        576 => ("vadduhs", Binary),
+        577 => ("vmul10euq", Binary),
+        578 => ("vminuh", Binary),
+        580 => ("vsrh", Binary),
+        584 => ("vmuleuh", Binary),
+        586 => ("vrfiz", Unary),
+        588 => ("vsplth", Immediate),
+        589 => ("vextractuh", Immediate),
+        590 => ("vupkhsh", Unary),
+        640 => ("vadduws", Binary),
+        642 => ("vminuw", Binary),
+        644 => ("vsrw", Binary),
+        648 => ("vmuleuw", Binary),
+        649 => ("vmulhuw", Binary),
+        650 => ("vrfip", Unary),
+        651 => ("vdiveuw", Binary),
+        652 => ("vspltw", Immediate),
+        653 => ("vextractuw", Immediate),
+        654 => ("vupklsb", Unary),
+        706 => ("vminud", Binary),
+        708 => ("vsr", Binary),
+        712 => ("vmuleud", Binary),
+        713 => ("vmulhud", Binary),
+        714 => ("vrfim", Unary),
+        715 => ("vdiveud", Binary),
+        717 => ("vextractd", Immediate),
+        718 => ("vupklsh", Unary),
+        768 => ("vaddsbs", Binary),
+        770 => ("vminsb", Binary),
+        772 => ("vsrab", Binary),
+        773 => ("vsraq", Binary),
+        776 => ("vmulesb", Binary),
+        778 => ("vcfux", Immediate),
+        779 => ("vdivesq", Binary),
+        780 => ("vspltisb", Splat),
+        781 => ("vinsertb", Immediate),
+        782 => ("vpkpx", Binary),
+        832 => ("vaddshs", Binary),
+        834 => ("vminsh", Binary),
+        836 => ("vsrah", Binary),
+        840 => ("vmulesh", Binary),
+        842 => ("vcfsx", Immediate),
+        844 => ("vspltish", Splat),
+        845 => ("vinserth", Immediate),
+        846 => ("vupkhpx", Unary),
+        896 => ("vaddsws", Binary),
+        898 => ("vminsw", Binary),
+        900 => ("vsraw", Binary),
+        904 => ("vmulesw", Binary),
+        905 => ("vmulhsw", Binary),
+        906 => ("vctuxs", Immediate),
+        907 => ("vdivesw", Binary),
+        908 => ("vspltisw", Splat),
+        909 => ("vinsertw", Immediate),
+        962 => ("vminsd", Binary),
+        964 => ("vsrad", Binary),
+        968 => ("vmulesd", Binary),
+        969 => ("vmulhsd", Binary),
+        970 => ("vctsxs", Immediate),
+        971 => ("vdivesd", Binary),
+        973 => ("vinsertd", Immediate),
+        974 => ("vupklpx", Unary),
+        1024 => ("vsububm", Binary),
+        1026 => ("vavgub", Binary),
+        1027 => ("vabsdub", Binary),
+        1028 => ("vand", Binary),
+        1032 => ("vpmsumb", Binary),
+        1034 => ("vmaxfp", Binary),
+        1036 => ("vslo", Binary),
+        1088 => ("vsubuhm", Binary),
+        1090 => ("vavguh", Binary),
+        1091 => ("vabsduh", Binary),
+        1092 => ("vandc", Binary),
+        1096 => ("vpmsumh", Binary),
+        1098 => ("vminfp", Binary),
+        1100 => ("vsro", Binary),
+        1102 => ("vpkudum", Binary),
+        1152 => ("vsubuwm", Binary),
+        1154 => ("vavguw", Binary),
+        1155 => ("vabsduw", Binary),
+        1156 => ("vor", Binary),
+        1160 => ("vpmsumw", Binary),
+        1216 => ("vsubudm", Binary),
+        1220 => ("vxor", Binary),
+        1224 => ("vpmsumd", Binary),
+        1230 => ("vpkudus", Binary),
+        1280 => ("vsubuqm", Binary),
+        1282 => ("vavgsb", Binary),
+        1284 => ("vnor", Binary),
+        1288 => ("vcipher", Binary),
+        1289 => ("vcipherlast", Binary),
+        1292 => ("vgbbd", Unary),
+        1344 => ("vsubcuq", Binary),
+        1346 => ("vavgsh", Binary),
+        1348 => ("vorc", Binary),
+        1352 => ("vncipher", Binary),
+        1353 => ("vncipherlast", Binary),
+        1356 => ("vbpermq", Binary),
+        1357 => ("vcfuged", Binary),
+        1358 => ("vpksdus", Binary),
+        1408 => ("vsubcuw", Binary),
+        1410 => ("vavgsw", Binary),
+        1412 => ("vnand", Binary),
+        1421 => ("vpextd", Binary),
+        1476 => ("vsld", Binary),
+        1480 => ("vsbox", UnaryA),
+        1484 => ("vbpermd", Binary),
+        1485 => ("vpdepd", Binary),
+        1486 => ("vpksdss", Binary),
+        1536 => ("vsububs", Binary),
+        1540 => ("mfvscr", Target),
+        1544 => ("vsum4ubs", Binary),
+        1547 => ("vmoduq", Binary),
+        1600 => ("vsubuhs", Binary),
+        1604 => ("mtvscr", Source),
+        1608 => ("vsum4shs", Binary),
+        1614 => ("vupkhsw", Unary),
+        1664 => ("vsubuws", Binary),
+        1668 => ("veqv", Binary),
+        1672 => ("vsum2sws", Binary),
+        1675 => ("vmoduw", Binary),
+        1676 => ("vmrgow", Binary),
+        1732 => ("vsrd", Binary),
+        1739 => ("vmodud", Binary),
+        1742 => ("vupklsw", Unary),
+        1792 => ("vsubsbs", Binary),
+        1794 => ("vclzb", Unary),
+        1795 => ("vpopcntb", Unary),
+        1796 => ("vsrv", Binary),
+        1800 => ("vsum4sbs", Binary),
+        1803 => ("vmodsq", Binary),
+        1856 => ("vsubshs", Binary),
+        1858 => ("vclzh", Unary),
+        1859 => ("vpopcnth", Unary),
+        1860 => ("vslv", Binary),
+        1920 => ("vsubsws", Binary),
+        1922 => ("vclzw", Unary),
+        1923 => ("vpopcntw", Unary),
+        1924 => ("vclzdm", Binary),
+        1928 => ("vsumsws", Binary),
+        1931 => ("vmodsw", Binary),
+        1932 => ("vmrgew", Binary),
+        1986 => ("vclzd", Unary),
+        1987 => ("vpopcntd", Unary),
+        1988 => ("vctzdm", Binary),
+        1995 => ("vmodsd", Binary),
+        1538 => return decode_unary(f),
+        1602 => return decode_mask(f),
+        257 | 321 if f.rt & 0b11 == 0 => {
+            let mnemomic = if f.word & 0x7ff == 257 {
+                "vcmpuq"
+            } else {
+                "vcmpsq"
+            };
+            return Ok(new(mnemomic, &[crate::cr(f.rt >> 2), va, vb]));
+        }
+        397 | 461 => {
+            let mnemomic = if f.word & 0x7ff == 397 {
+                "vclrlb"
+            } else {
+                "vclrrb"
+            };
+            return Ok(new(mnemomic, &[vt, va, gpr(f.rb)]));
+        }
+        1228 if f.ra >> 3 == 0 => return Ok(new("vgnb", &[gpr(f.rt), vb, imm(f.ra)])),
+        // inserts of an element from general purpose registers, at an index in a register or
+        // of an immediate
+        15 | 79 | 143 | 271 | 335 | 399 => {
+            let mnemomic = match f.word & 0x7ff {
+                15 => "vinsbvlx",
+                79 => "vinshvlx",
+                143 => "vinswvlx",
+                271 => "vinsbvrx",
+                335 => "vinshvrx",
+                _ => "vinswvrx",
+            };
+
+            return Ok(new(mnemomic, &[vt, gpr(f.ra), vb]));
+        }
+        527 | 591 | 655 | 719 | 783 | 847 | 911 | 975 => {
+            let mnemomic = match f.word & 0x7ff {
+                527 => "vinsblx",
+                591 => "vinshlx",
+                655 => "vinswlx",
+                719 => "vinsdlx",
+                783 => "vinsbrx",
+                847 => "vinshrx",
+                911 => "vinswrx",
+                _ => "vinsdrx",
+            };
+
+            return Ok(new(mnemomic, &[vt, gpr(f.ra), gpr(f.rb)]));
+        }
+        207 | 463 if f.ra >> 4 == 0 => {
+            let mnemomic = if f.word & 0x7ff == 207 {
+                "vinsw"
+            } else {
+                "vinsd"
+            };
+            return Ok(new(mnemomic, &[vt, gpr(f.rb), imm(f.ra)]));
+        }
+        1666 | 1730 => {
+            let mnemomic = if f.word & 0x7ff == 1666 {
+                "vshasigmaw"
+            } else {
+                "vshasigmad"
+            };
+            let (st, six) = (f.rb >> 4, f.rb & 0xf);
+            return Ok(new(mnemomic, &[vt, va, imm(st), imm(six)]));
+        }
+        1549 | 1613 | 1677 | 1805 | 1869 | 1933 => {
+            let mnemomic = match f.word & 0x7ff {
+                1549 => "vextublx",
+                1613 => "vextuhlx",
+                1677 => "vextuwlx",
+                1805 => "vextubrx",
+                1869 => "vextuhrx",
+                _ => "vextuwrx",
+            };
+
+            return Ok(new(mnemomic, &[gpr(f.rt), gpr(f.ra), vb]));
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    let inst = match shape {
+        Binary => new(mnemomic, &[vt, va, vb]),
+        Unary if f.ra == 0 => new(mnemomic, &[vt, vb]),
+        UnaryA if f.rb == 0 => new(mnemomic, &[vt, va]),
+        // element indices of the inserts and extracts are one bit wider
+        Immediate if mnemomic.starts_with("vextract") || mnemomic.starts_with("vinsert") => {
+            match f.ra >> 4 {
+                0 => new(mnemomic, &[vt, vb, imm(f.ra)]),
+                _ => return Err(ErrorKind::InvalidOperand),
+            }
+        }
+        Immediate => new(mnemomic, &[vt, vb, imm(f.ra)]),
+        Splat if f.rb == 0 => new(mnemomic, &[vt, imm(sign_extend(f.ra, 5))]),
+        Target if f.ra == 0 && f.rb == 0 => new(mnemomic, &[vt]),
+        Source if f.rt == 0 && f.ra == 0 => new(mnemomic, &[vb]),
+        _ => return Err(ErrorKind::InvalidOperand),
+    };
+
+    Ok(inst)
+}
+
+/// Unary instructions of which the `vA` field extends the opcode.
+fn decode_unary(f: Fields) -> Result<Instruction, ErrorKind> {
+    let (vt, vb) = (vr(f.rt), vr(f.rb));
+
+    let (mnemomic, target) = match f.ra {
+        0 => ("vclzlsbb", gpr(f.rt)),
+        1 => ("vctzlsbb", gpr(f.rt)),
+        6 => ("vnegw", vt),
+        7 => ("vnegd", vt),
+        8 => ("vprtybw", vt),
+        9 => ("vprtybd", vt),
+        10 => ("vprtybq", vt),
+        16 => ("vextsb2w", vt),
+        17 => ("vextsh2w", vt),
+        24 => ("vextsb2d", vt),
+        25 => ("vextsh2d", vt),
+        26 => ("vextsw2d", vt),
+        27 => ("vextsd2q", vt),
+        28 => ("vctzb", vt),
+        29 => ("vctzh", vt),
+        30 => ("vctzw", vt),
+        31 => ("vctzd", vt),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(Instruction::new(mnemomic, &[target, vb]))
+}
+
+/// Expansion and extraction of masks of elements, selected by the `vA` field, where the low
+/// bits select the element size.
+fn decode_mask(f: Fields) -> Result<Instruction, ErrorKind> {
+    let (vt, vb) = (vr(f.rt), vr(f.rb));
+    let size = f.ra as usize & 0b111;
+
+    let inst = match f.ra >> 3 {
+        0 if size <= 4 => {
+            let mnemomic = [
+                "vexpandbm",
+                "vexpandhm",
+                "vexpandwm",
+                "vexpanddm",
+                "vexpandqm",
+            ];
+            Instruction::new(mnemomic[size], &[vt, vb])
+        }
+        1 if size <= 4 => {
+            let mnemomic = [
+                "vextractbm",
+                "vextracthm",
+                "vextractwm",
+                "vextractdm",
+                "vextractqm",
+            ];
+            Instruction::new(mnemomic[size], &[gpr(f.rt), vb])
+        }
+        2 if size <= 4 => {
+            let mnemomic = ["mtvsrbm", "mtvsrhm", "mtvsrwm", "mtvsrdm", "mtvsrqm"];
+            Instruction::new(mnemomic[size], &[vt, gpr(f.rb)])
+        }
+        // counts of the elements of which the mask bit matches the last bit
+        3 => {
+            let mnemomic = ["vcntmbb", "vcntmbh", "vcntmbw", "vcntmbd"][size >> 1];
+            Instruction::new(mnemomic, &[gpr(f.rt), vb, imm(f.ra & 1)])
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Integer multiply-add on general purpose registers, which is encoded like the vector
+/// instructions with four registers.
+fn gprs(f: Fields) -> [crate::Operand; 4] {
+    [gpr(f.rt), gpr(f.ra), gpr(f.rb), gpr(f.rc)]
+}
+
+/// Vector loads and stores of opcode 31.
+pub(crate) fn decode_indexed(f: Fields) -> Result<Instruction, ErrorKind> {
+    if f.record {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    // data stream touches, where the high bit of the target field marks transient data
+    if matches!(f.xo, 342 | 374) && f.rt >> 2 & 0b111 == 0 {
+        let mnemomic = match (f.xo, f.rt >> 4) {
+            (342, 0) => "dst",
+            (342, _) => "dstt",
+            (_, 0) => "dstst",
+            _ => "dststt",
+        };
+
+        return Ok(Instruction::new(
+            mnemomic,
+            &[gpr(f.ra), gpr(f.rb), imm(f.rt & 0b11)],
+        ));
+    }
+
+    // stops data streams, or all of them when the high bit of the target field is set
+    if f.xo == 822 && f.rt >> 2 & 0b111 == 0 && f.ra == 0 && f.rb == 0 {
+        return match f.rt >> 4 {
+            0 => Ok(Instruction::new("dss", &[imm(f.rt & 0b11)])),
+            _ => Ok(Instruction::new("dssall", &[])),
+        };
+    }
+
+    let mnemomic = match f.xo {
+        6 => "lvsl",
+        7 => "lvebx",
+        38 => "lvsr",
+        39 => "lvehx",
+        71 => "lvewx",
+        103 => "lvx",
+        135 => "stvebx",
+        167 => "stvehx",
+        199 => "stvewx",
+        231 => "stvx",
+        359 => "lvxl",
+        487 => "stvxl",
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(Instruction::new(
+        mnemomic,
+        &[vr(f.rt), base(f.ra), gpr(f.rb)],
+    ))
+}
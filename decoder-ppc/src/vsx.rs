@@ -0,0 +1,420 @@
+//! Vector-scalar (VSX) instructions of opcode 60 and the VSX loads, stores and moves of
+//! opcode 31.
+//!
+//! VSX registers are numbered 0 to 63, the sixth bit of each register is stored separately at
+//! the end of the instruction.
+
+use crate::{base, cr, gpr, imm, vsr, Decoder, Fields, Instruction};
+use decoder::ErrorKind;
+
+/// Registers of XX-form instructions with their extension bits applied.
+#[derive(Clone, Copy)]
+struct Registers {
+    t: crate::Operand,
+    a: crate::Operand,
+    b: crate::Operand,
+    c: crate::Operand,
+}
+
+impl Registers {
+    fn new(f: Fields) -> Self {
+        Self {
+            t: vsr(f.rt | (f.word & 1) << 5),
+            a: vsr(f.ra | (f.word >> 2 & 1) << 5),
+            b: vsr(f.rb | (f.word >> 1 & 1) << 5),
+            c: vsr(f.rc | (f.word >> 3 & 1) << 5),
+        }
+    }
+}
+
+pub(crate) fn decode(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let Registers { t, a, b, c } = Registers::new(f);
+    let bf = cr(f.rt >> 2);
+    let xo = f.word >> 3 & 0xff;
+
+    // XX4-form select
+    if f.word >> 4 & 0b11 == 0b11 {
+        return Ok(new("xxsel", &[t, a, b, c]));
+    }
+
+    // shifts and permutes with an immediate in the extended opcode
+    match xo & 0b1001_1111 {
+        0b0000_0010 => return Ok(new("xxsldwi", &[t, a, b, imm(xo >> 5 & 0b11)])),
+        0b0000_1010 => return Ok(new("xxpermdi", &[t, a, b, imm(xo >> 5 & 0b11)])),
+        _ => {}
+    }
+
+    // vector comparisons may record whether all or none of the elements matched
+    let compare = match xo & 0x7f {
+        67 => Some("xvcmpeqsp"),
+        75 => Some("xvcmpgtsp"),
+        83 => Some("xvcmpgesp"),
+        99 => Some("xvcmpeqdp"),
+        107 => Some("xvcmpgtdp"),
+        115 => Some("xvcmpgedp"),
+        _ => None,
+    };
+
+    if let Some(mnemomic) = compare {
+        return Ok(new(mnemomic, &[t, a, b]).with_record(xo >> 7 == 1));
+    }
+
+    // XX3-form, with two sources
+    let mnemomic = match xo {
+        0 => Some("xsaddsp"),
+        1 => Some("xsmaddasp"),
+        3 => Some("xscmpeqdp"),
+        8 => Some("xssubsp"),
+        9 => Some("xsmaddmsp"),
+        11 => Some("xscmpgtdp"),
+        16 => Some("xsmulsp"),
+        17 => Some("xsmsubasp"),
+        18 => Some("xxmrghw"),
+        19 => Some("xscmpgedp"),
+        24 => Some("xsdivsp"),
+        25 => Some("xsmsubmsp"),
+        26 => Some("xxperm"),
+        32 => Some("xsadddp"),
+        33 => Some("xsmaddadp"),
+        40 => Some("xssubdp"),
+        41 => Some("xsmaddmdp"),
+        48 => Some("xsmuldp"),
+        49 => Some("xsmsubadp"),
+        50 => Some("xxmrglw"),
+        56 => Some("xsdivdp"),
+        57 => Some("xsmsubmdp"),
+        58 => Some("xxpermr"),
+        64 => Some("xvaddsp"),
+        65 => Some("xvmaddasp"),
+        72 => Some("xvsubsp"),
+        73 => Some("xvmaddmsp"),
+        80 => Some("xvmulsp"),
+        81 => Some("xvmsubasp"),
+        88 => Some("xvdivsp"),
+        89 => Some("xvmsubmsp"),
+        96 => Some("xvadddp"),
+        97 => Some("xvmaddadp"),
+        104 => Some("xvsubdp"),
+        105 => Some("xvmaddmdp"),
+        112 => Some("xvmuldp"),
+        113 => Some("xvmsubadp"),
+        120 => Some("xvdivdp"),
+        121 => Some("xvmsubmdp"),
+        128 => Some("xsmaxcdp"),
+        129 => Some("xsnmaddasp"),
+        130 => Some("xxland"),
+        136 => Some("xsmincdp"),
+        137 => Some("xsnmaddmsp"),
+        138 => Some("xxlandc"),
+        144 => Some("xsmaxjdp"),
+        145 => Some("xsnmsubasp"),
+        146 => Some("xxlor"),
+        152 => Some("xsminjdp"),
+        153 => Some("xsnmsubmsp"),
+        154 => Some("xxlxor"),
+        160 => Some("xsmaxdp"),
+        161 => Some("xsnmaddadp"),
+        162 => Some("xxlnor"),
+        168 => Some("xsmindp"),
+        169 => Some("xsnmaddmdp"),
+        170 => Some("xxlorc"),
+        176 => Some("xscpsgndp"),
+        177 => Some("xsnmsubadp"),
+        178 => Some("xxlnand"),
+        185 => Some("xsnmsubmdp"),
+        186 => Some("xxleqv"),
+        192 => Some("xvmaxsp"),
+        193 => Some("xvnmaddasp"),
+        200 => Some("xvminsp"),
+        201 => Some("xvnmaddmsp"),
+        208 => Some("xvcpsgnsp"),
+        209 => Some("xvnmsubasp"),
+        216 => Some("xviexpsp"),
+        217 => Some("xvnmsubmsp"),
+        224 => Some("xvmaxdp"),
+        225 => Some("xvnmaddadp"),
+        232 => Some("xvmindp"),
+        233 => Some("xvnmaddmdp"),
+        240 => Some("xvcpsgndp"),
+        241 => Some("xvnmsubadp"),
+        248 => Some("xviexpdp"),
+        249 => Some("xvnmsubmdp"),
+        _ => None,
+    };
+
+    if let Some(mnemomic) = mnemomic {
+        return Ok(new(mnemomic, &[t, a, b]));
+    }
+
+    // XX3-form comparisons and tests into a condition register field
+    let mnemomic = match xo {
+        35 => Some("xscmpudp"),
+        43 => Some("xscmpodp"),
+        59 => Some("xscmpexpdp"),
+        61 => Some("xstdivdp"),
+        93 => Some("xvtdivsp"),
+        125 => Some("xvtdivdp"),
+        _ => None,
+    };
+
+    if let Some(mnemomic) = mnemomic {
+        return match f.rt & 0b11 == 0 && f.word & 1 == 0 {
+            true => Ok(new(mnemomic, &[bf, a, b])),
+            false => Err(ErrorKind::InvalidOperand),
+        };
+    }
+
+    decode_unary(f)
+}
+
+/// XX2-form instructions with a single source, and the splats.
+fn decode_unary(f: Fields) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let Registers { t, b, .. } = Registers::new(f);
+
+    // splat of an immediate byte
+    if f.xo == 360 && f.ra >> 3 == 0 {
+        return Ok(new("xxspltib", &[t, imm(f.word >> 11 & 0xff)]));
+    }
+
+    // insert of the exponent from general purpose registers
+    if f.xo == 918 {
+        return Ok(new("xsiexpdp", &[t, gpr(f.ra), gpr(f.rb)]));
+    }
+
+    // test of the data class, with the mask of classes split across the instruction
+    if f.word >> 3 & 0b111 == 0b101 && matches!(f.word >> 7 & 0xf, 13 | 15) {
+        let mnemomic = if f.word >> 7 & 0xf == 13 {
+            "xvtstdcsp"
+        } else {
+            "xvtstdcdp"
+        };
+        let dcmx = (f.word >> 6 & 1) << 6 | (f.word >> 2 & 1) << 5 | f.ra;
+        return Ok(new(mnemomic, &[t, b, imm(dcmx)]));
+    }
+
+    let mnemomic = match f.word >> 2 & 0x1ff {
+        10 => "xsrsqrtesp",
+        11 => "xssqrtsp",
+        26 => "xsresp",
+        72 => "xscvdpuxws",
+        73 => "xsrdpi",
+        74 => "xsrsqrtedp",
+        75 => "xssqrtdp",
+        88 => "xscvdpsxws",
+        89 => "xsrdpiz",
+        90 => "xsredp",
+        105 => "xsrdpip",
+        107 => "xsrdpic",
+        121 => "xsrdpim",
+        136 => "xvcvspuxws",
+        137 => "xvrspi",
+        138 => "xvrsqrtesp",
+        139 => "xvsqrtsp",
+        152 => "xvcvspsxws",
+        153 => "xvrspiz",
+        154 => "xvresp",
+        164 => {
+            let uim = f.ra & 0b11;
+            return match f.ra >> 2 {
+                0 => Ok(new("xxspltw", &[t, b, imm(uim)])),
+                _ => Err(ErrorKind::InvalidOperand),
+            };
+        }
+        165 | 181 => {
+            let mnemomic = if f.word >> 2 & 0x1ff == 165 {
+                "xxextractuw"
+            } else {
+                "xxinsertw"
+            };
+            return match f.ra >> 4 {
+                0 => Ok(new(mnemomic, &[t, b, imm(f.ra & 0xf)])),
+                _ => Err(ErrorKind::InvalidOperand),
+            };
+        }
+        168 => "xvcvuxwsp",
+        169 => "xvrspip",
+        184 => "xvcvsxwsp",
+        185 => "xvrspim",
+        171 => "xvrspic",
+        200 => "xvcvdpuxws",
+        201 => "xvrdpi",
+        202 => "xvrsqrtedp",
+        203 => "xvsqrtdp",
+        216 => "xvcvdpsxws",
+        217 => "xvrdpiz",
+        218 => "xvredp",
+        232 => "xvcvuxwdp",
+        233 => "xvrdpip",
+        235 => "xvrdpic",
+        248 => "xvcvsxwdp",
+        249 => "xvrdpim",
+        265 => "xscvdpsp",
+        267 => "xscvdpspn",
+        281 => "xsrsp",
+        296 => "xscvuxdsp",
+        312 => "xscvsxdsp",
+        328 => "xscvdpuxds",
+        329 => "xscvspdp",
+        331 => "xscvspdpn",
+        344 => "xscvdpsxds",
+        345 => "xsabsdp",
+        347 => return decode_scalar_parts(f),
+        360 => "xscvuxddp",
+        361 => "xsnabsdp",
+        376 => "xscvsxddp",
+        377 => "xsnegdp",
+        392 => "xvcvspuxds",
+        393 => "xvcvdpsp",
+        408 => "xvcvspsxds",
+        409 => "xvabssp",
+        424 => "xvcvuxdsp",
+        425 => "xvnabssp",
+        440 => "xvcvsxdsp",
+        441 => "xvnegsp",
+        456 => "xvcvdpuxds",
+        457 => "xvcvspdp",
+        472 => "xvcvdpsxds",
+        473 => "xvabsdp",
+        475 => return decode_vector_parts(f),
+        488 => "xvcvuxddp",
+        489 => "xvnabsdp",
+        504 => "xvcvsxddp",
+        505 => "xvnegdp",
+        298 | 362 if f.word & 1 == 0 => {
+            let mnemomic = if f.word >> 2 & 0x1ff == 298 {
+                "xststdcsp"
+            } else {
+                "xststdcdp"
+            };
+            let dcmx = f.word >> 16 & 0x7f;
+            return Ok(new(mnemomic, &[cr(f.rt >> 2), b, imm(dcmx)]));
+        }
+        106 | 170 | 234 => {
+            let mnemomic = match f.word >> 2 & 0x1ff {
+                106 => "xstsqrtdp",
+                170 => "xvtsqrtsp",
+                _ => "xvtsqrtdp",
+            };
+
+            return match f.rt & 0b11 == 0 && f.ra == 0 && f.word & 1 == 0 {
+                true => Ok(new(mnemomic, &[cr(f.rt >> 2), b])),
+                false => Err(ErrorKind::InvalidOperand),
+            };
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    match f.ra {
+        0 => Ok(new(mnemomic, &[t, b])),
+        _ => Err(ErrorKind::InvalidOperand),
+    }
+}
+
+/// Scalar conversions and extraction of the exponent and significand, selected by the `A`
+/// field.
+fn decode_scalar_parts(f: Fields) -> Result<Instruction, ErrorKind> {
+    let Registers { t, b, .. } = Registers::new(f);
+
+    let inst = match f.ra {
+        0 if f.word & 1 == 0 => Instruction::new("xsxexpdp", &[gpr(f.rt), b]),
+        1 if f.word & 1 == 0 => Instruction::new("xsxsigdp", &[gpr(f.rt), b]),
+        16 => Instruction::new("xscvhpdp", &[t, b]),
+        17 => Instruction::new("xscvdphp", &[t, b]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Vector conversions, byte reversals and extraction of the exponent and significand,
+/// selected by the `A` field.
+fn decode_vector_parts(f: Fields) -> Result<Instruction, ErrorKind> {
+    let Registers { t, b, .. } = Registers::new(f);
+
+    let mnemomic = match f.ra {
+        0 => "xvxexpdp",
+        1 => "xvxsigdp",
+        7 => "xxbrh",
+        8 => "xvxexpsp",
+        9 => "xvxsigsp",
+        15 => "xxbrw",
+        16 => "xvcvbf16spn",
+        17 => "xvcvspbf16",
+        23 => "xxbrd",
+        24 => "xvcvhpsp",
+        25 => "xvcvsphp",
+        31 => "xxbrq",
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(Instruction::new(mnemomic, &[t, b]))
+}
+
+/// VSX loads, stores and moves from and to general purpose registers of opcode 31.
+pub(crate) fn decode_indexed(f: Fields, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let new = Instruction::new;
+    let t = vsr(f.rt | (f.word & 1) << 5);
+    let (ra, ra0, rb) = (gpr(f.ra), base(f.ra), gpr(f.rb));
+    let is_64 = decoder.is_64;
+
+    let inst = match f.xo {
+        12 => new("lxsiwzx", &[t, ra0, rb]),
+        76 => new("lxsiwax", &[t, ra0, rb]),
+        140 => new("stxsiwx", &[t, ra0, rb]),
+        268 => new("lxvx", &[t, ra0, rb]),
+        269 if is_64 => new("lxvl", &[t, ra0, rb]),
+        301 if is_64 => new("lxvll", &[t, ra0, rb]),
+        332 => new("lxvdsx", &[t, ra0, rb]),
+        364 => new("lxvwsx", &[t, ra0, rb]),
+        396 => new("stxvx", &[t, ra0, rb]),
+        397 if is_64 => new("stxvl", &[t, ra0, rb]),
+        429 if is_64 => new("stxvll", &[t, ra0, rb]),
+        524 => new("lxsspx", &[t, ra0, rb]),
+        588 => new("lxsdx", &[t, ra0, rb]),
+        652 => new("stxsspx", &[t, ra0, rb]),
+        716 => new("stxsdx", &[t, ra0, rb]),
+        780 => new("lxvw4x", &[t, ra0, rb]),
+        781 => new("lxsibzx", &[t, ra0, rb]),
+        812 => new("lxvh8x", &[t, ra0, rb]),
+        813 => new("lxsihzx", &[t, ra0, rb]),
+        844 => new("lxvd2x", &[t, ra0, rb]),
+        876 => new("lxvb16x", &[t, ra0, rb]),
+        908 => new("stxvw4x", &[t, ra0, rb]),
+        909 => new("stxsibx", &[t, ra0, rb]),
+        940 => new("stxvh8x", &[t, ra0, rb]),
+        941 => new("stxsihx", &[t, ra0, rb]),
+        972 => new("stxvd2x", &[t, ra0, rb]),
+        1004 => new("stxvb16x", &[t, ra0, rb]),
+        13 => new("lxvrbx", &[t, ra0, rb]),
+        45 => new("lxvrhx", &[t, ra0, rb]),
+        77 => new("lxvrwx", &[t, ra0, rb]),
+        109 => new("lxvrdx", &[t, ra0, rb]),
+        141 => new("stxvrbx", &[t, ra0, rb]),
+        173 => new("stxvrhx", &[t, ra0, rb]),
+        205 => new("stxvrwx", &[t, ra0, rb]),
+        237 => new("stxvrdx", &[t, ra0, rb]),
+        // pairs of registers, where the low bit of the target extends the register number
+        333 if f.word & 1 == 0 => new("lxvpx", &[pair(f.rt), ra0, rb]),
+        461 if f.word & 1 == 0 => new("stxvpx", &[pair(f.rt), ra0, rb]),
+        // moves name the general purpose register in the `A` field
+        51 if is_64 && f.rb == 0 => new("mfvsrd", &[ra, t]),
+        115 if f.rb == 0 => new("mfvsrwz", &[ra, t]),
+        179 if is_64 && f.rb == 0 => new("mtvsrd", &[t, ra]),
+        211 if f.rb == 0 => new("mtvsrwa", &[t, ra]),
+        243 if f.rb == 0 => new("mtvsrwz", &[t, ra]),
+        307 if is_64 && f.rb == 0 => new("mfvsrld", &[ra, t]),
+        403 if f.rb == 0 => new("mtvsrws", &[t, ra]),
+        435 if is_64 => new("mtvsrdd", &[t, ra0, rb]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Even register of a pair of vector-scalar registers, of which the low bit of the field selects
+/// the upper half of the registers.
+pub(crate) fn pair(field: u32) -> crate::Operand {
+    vsr(field & 0b11110 | (field & 1) << 5)
+}
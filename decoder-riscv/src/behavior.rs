@@ -0,0 +1,344 @@
+//! Semantic queries on decoded instructions: control flow, registers and memory accesses.
+//!
+//! These work on instructions after they've been mapped to pseudo-instructions, so they have to
+//! account for operands that were folded away (e.g. `addi a0, 4` both reads and writes `a0`).
+
+use crate::{Instruction, Opcode, Operand, Register};
+use decoder::{Direction, FlowKind, MemoryAccess};
+
+fn is_branch(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::BEQ
+            | Opcode::BNE
+            | Opcode::BLT
+            | Opcode::BGE
+            | Opcode::BLTU
+            | Opcode::BGEU
+            | Opcode::BEQZ
+            | Opcode::BNEZ
+            | Opcode::BLEZ
+            | Opcode::BGEZ
+            | Opcode::BLTZ
+            | Opcode::BGTZ
+            | Opcode::BGT
+            | Opcode::BLE
+            | Opcode::BGTU
+            | Opcode::BLEU
+            | Opcode::C_BEQZ
+            | Opcode::C_BNEZ
+    )
+}
+
+/// Instructions that are printed as `op rd, rs` when `rd` is also the first source.
+fn is_two_address(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::ADD
+            | Opcode::ADDI
+            | Opcode::ADDW
+            | Opcode::ADDIW
+            | Opcode::XOR
+            | Opcode::AND
+            | Opcode::OR
+            | Opcode::SLLI
+            | Opcode::SRLI
+            | Opcode::SRAI
+            | Opcode::C_ADD
+            | Opcode::C_ADDI
+            | Opcode::C_ADDW
+            | Opcode::C_ADDIW
+            | Opcode::C_XOR
+            | Opcode::C_AND
+            | Opcode::C_OR
+            | Opcode::C_SUB
+            | Opcode::C_SUBW
+            | Opcode::C_ANDI
+            | Opcode::C_SLLI
+            | Opcode::C_SLLI64
+            | Opcode::C_SRLI
+            | Opcode::C_SRLI64
+            | Opcode::C_SRAI
+            | Opcode::C_SRAI64
+    )
+}
+
+/// Loads and stores that address memory relative to `sp` without naming it.
+fn is_sp_relative(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::C_LWSP
+            | Opcode::C_LDSP
+            | Opcode::C_FLWSP
+            | Opcode::C_FLDSP
+            | Opcode::C_SWSP
+            | Opcode::C_SDSP
+            | Opcode::C_FSWSP
+            | Opcode::C_FSDSP
+    )
+}
+
+/// Number of bytes and direction of the memory access.
+fn access(opcode: Opcode) -> Option<(usize, Direction)> {
+    let access = match opcode {
+        Opcode::LB | Opcode::LBU => (1, Direction::Read),
+        Opcode::LH | Opcode::LHU | Opcode::FLH => (2, Direction::Read),
+        Opcode::LW
+        | Opcode::LWU
+        | Opcode::FLW
+        | Opcode::LR_W
+        | Opcode::C_LW
+        | Opcode::C_FLW
+        | Opcode::C_LWSP
+        | Opcode::C_FLWSP => (4, Direction::Read),
+        Opcode::LD
+        | Opcode::FLD
+        | Opcode::LR_D
+        | Opcode::C_LD
+        | Opcode::C_FLD
+        | Opcode::C_LDSP
+        | Opcode::C_FLDSP => (8, Direction::Read),
+        Opcode::FLQ => (16, Direction::Read),
+        Opcode::SB => (1, Direction::Write),
+        Opcode::SH | Opcode::FSH => (2, Direction::Write),
+        Opcode::SW
+        | Opcode::FSW
+        | Opcode::SC_W
+        | Opcode::C_SW
+        | Opcode::C_FSW
+        | Opcode::C_SWSP
+        | Opcode::C_FSWSP => (4, Direction::Write),
+        Opcode::SD
+        | Opcode::FSD
+        | Opcode::SC_D
+        | Opcode::C_SD
+        | Opcode::C_FSD
+        | Opcode::C_SDSP
+        | Opcode::C_FSDSP => (8, Direction::Write),
+        Opcode::FSQ => (16, Direction::Write),
+        Opcode::AMOSWAP_W
+        | Opcode::AMOADD_W
+        | Opcode::AMOXOR_W
+        | Opcode::AMOAND_W
+        | Opcode::AMOOR_W
+        | Opcode::AMOMIN_W
+        | Opcode::AMOMAX_W
+        | Opcode::AMOMINU_W
+        | Opcode::AMOMAXU_W => (4, Direction::ReadWrite),
+        Opcode::AMOSWAP_D
+        | Opcode::AMOADD_D
+        | Opcode::AMOXOR_D
+        | Opcode::AMOAND_D
+        | Opcode::AMOOR_D
+        | Opcode::AMOMIN_D
+        | Opcode::AMOMAX_D
+        | Opcode::AMOMINU_D
+        | Opcode::AMOMAXU_D => (8, Direction::ReadWrite),
+        _ => return None,
+    };
+
+    Some(access)
+}
+
+/// Memory accesses whose size depends on state outside of the instruction, such as the vector
+/// length or the cache block size.
+fn unsized_access(opcode: Opcode) -> Option<Direction> {
+    // segment loads and stores are declared contiguously, loads before stores
+    let segment =
+        |first: Opcode, last: Opcode| (first as u32..=last as u32).contains(&(opcode as u32));
+
+    let direction = match opcode {
+        _ if segment(Opcode::VLSEG2E8_V, Opcode::VLOXSEG8EI64_V) => Direction::Read,
+        _ if segment(Opcode::VSSEG2E8_V, Opcode::VSOXSEG8EI64_V) => Direction::Write,
+        Opcode::VLE8_V
+        | Opcode::VLE16_V
+        | Opcode::VLE32_V
+        | Opcode::VLE64_V
+        | Opcode::VLE8FF_V
+        | Opcode::VLE16FF_V
+        | Opcode::VLE32FF_V
+        | Opcode::VLE64FF_V
+        | Opcode::VLM_V
+        | Opcode::VLSE8_V
+        | Opcode::VLSE16_V
+        | Opcode::VLSE32_V
+        | Opcode::VLSE64_V
+        | Opcode::VLUXEI8_V
+        | Opcode::VLUXEI16_V
+        | Opcode::VLUXEI32_V
+        | Opcode::VLUXEI64_V
+        | Opcode::VLOXEI8_V
+        | Opcode::VLOXEI16_V
+        | Opcode::VLOXEI32_V
+        | Opcode::VLOXEI64_V
+        | Opcode::VL1RE8_V
+        | Opcode::VL1RE16_V
+        | Opcode::VL1RE32_V
+        | Opcode::VL1RE64_V
+        | Opcode::VL2RE8_V
+        | Opcode::VL2RE16_V
+        | Opcode::VL2RE32_V
+        | Opcode::VL2RE64_V
+        | Opcode::VL4RE8_V
+        | Opcode::VL4RE16_V
+        | Opcode::VL4RE32_V
+        | Opcode::VL4RE64_V
+        | Opcode::VL8RE8_V
+        | Opcode::VL8RE16_V
+        | Opcode::VL8RE32_V
+        | Opcode::VL8RE64_V => Direction::Read,
+        Opcode::VSE8_V
+        | Opcode::VSE16_V
+        | Opcode::VSE32_V
+        | Opcode::VSE64_V
+        | Opcode::VSM_V
+        | Opcode::VSSE8_V
+        | Opcode::VSSE16_V
+        | Opcode::VSSE32_V
+        | Opcode::VSSE64_V
+        | Opcode::VSUXEI8_V
+        | Opcode::VSUXEI16_V
+        | Opcode::VSUXEI32_V
+        | Opcode::VSUXEI64_V
+        | Opcode::VSOXEI8_V
+        | Opcode::VSOXEI16_V
+        | Opcode::VSOXEI32_V
+        | Opcode::VSOXEI64_V
+        | Opcode::VS1R_V
+        | Opcode::VS2R_V
+        | Opcode::VS4R_V
+        | Opcode::VS8R_V
+        | Opcode::CBO_ZERO => Direction::Write,
+        _ => return None,
+    };
+
+    Some(direction)
+}
+
+pub(crate) fn flow(inst: &Instruction) -> FlowKind {
+    let first = inst.operands[0];
+
+    match inst.opcode {
+        _ if is_branch(inst.opcode) => FlowKind::ConditionalJump,
+        Opcode::J | Opcode::C_J | Opcode::JR | Opcode::C_JR => FlowKind::Jump,
+        Opcode::JAL | Opcode::C_JAL => FlowKind::Call,
+        Opcode::JALR if first == Operand::Register(Register::Zero) => FlowKind::Jump,
+        Opcode::JALR => FlowKind::Call,
+        // `c.jr` is decoded as `c.jalr` with `ra` as its destination
+        Opcode::C_JALR => FlowKind::Jump,
+        // the `auipc` half of a `call` or `tail` only computes the upper bits of the address
+        Opcode::CALL | Opcode::TAIL if matches!(first, Operand::Immediate(_)) => {
+            FlowKind::Sequential
+        }
+        Opcode::CALL => FlowKind::Call,
+        Opcode::TAIL => FlowKind::Jump,
+        Opcode::RET | Opcode::SRET | Opcode::MRET => FlowKind::Return,
+        Opcode::ECALL | Opcode::EBREAK | Opcode::C_EBREAK => FlowKind::Interrupt,
+        _ => FlowKind::Sequential,
+    }
+}
+
+pub(crate) fn targets(inst: &Instruction) -> Vec<usize> {
+    // `jalr` is relative to a register, not to the instruction
+    if !inst.opcode.is_relative() || matches!(inst.opcode, Opcode::JALR | Opcode::C_JALR) {
+        return Vec::new();
+    }
+
+    inst.operands[..inst.operand_count]
+        .iter()
+        .filter_map(|operand| match operand {
+            Operand::Immediate(imm) => Some(*imm as u32 as usize),
+            _ => None,
+        })
+        .collect()
+}
+
+fn push(regs: &mut Vec<&'static str>, reg: Register) {
+    let name = reg.as_str();
+    if reg != Register::Zero && !regs.contains(&name) {
+        regs.push(name);
+    }
+}
+
+/// Collects the registers read and written by an instruction.
+pub(crate) fn registers(inst: &Instruction) -> (Vec<&'static str>, Vec<&'static str>) {
+    let mut read = Vec::new();
+    let mut written = Vec::new();
+
+    let opcode = inst.opcode;
+    let regs: Vec<Register> = inst.operands[..inst.operand_count]
+        .iter()
+        .filter_map(|operand| match operand {
+            Operand::Register(reg) => Some(*reg),
+            _ => None,
+        })
+        .collect();
+
+    // stores, branches and jumps only name registers they read, `sc` writes whether it succeeded
+    let reads_only = (access(opcode).is_some_and(|(_, dir)| dir == Direction::Write)
+        && !matches!(opcode, Opcode::SC_W | Opcode::SC_D))
+        || unsized_access(opcode) == Some(Direction::Write)
+        || is_branch(opcode)
+        || matches!(
+            opcode,
+            Opcode::J
+                | Opcode::C_J
+                | Opcode::JR
+                | Opcode::C_JR
+                | Opcode::C_JALR
+                | Opcode::CALL
+                | Opcode::TAIL
+                | Opcode::SFENCE_VMA
+                | Opcode::CBO_CLEAN
+                | Opcode::CBO_FLUSH
+                | Opcode::CBO_INVAL
+        );
+
+    for (idx, reg) in regs.iter().enumerate() {
+        if idx == 0 && !reads_only {
+            push(&mut written, *reg);
+            if inst.operand_count == 2 && is_two_address(opcode) {
+                push(&mut read, *reg);
+            }
+        } else {
+            push(&mut read, *reg);
+        }
+    }
+
+    if inst.operands[..inst.operand_count].contains(&Operand::Mask) {
+        push(&mut read, Register::V0);
+    }
+
+    if is_sp_relative(opcode) || opcode == Opcode::C_ADDI4SPN {
+        push(&mut read, Register::Sp);
+    }
+
+    match opcode {
+        Opcode::C_ADDI16SP => {
+            push(&mut read, Register::Sp);
+            push(&mut written, Register::Sp);
+        }
+        Opcode::RET => push(&mut read, Register::Ra),
+        // `jal ra, offset` is printed as `jal offset`
+        Opcode::JAL | Opcode::C_JAL if inst.operand_count == 1 => push(&mut written, Register::Ra),
+        Opcode::CALL => push(&mut written, Register::Ra),
+        Opcode::TAIL => push(&mut written, Register::T1),
+        _ => {}
+    }
+
+    (read, written)
+}
+
+pub(crate) fn memory(inst: &Instruction) -> Option<MemoryAccess> {
+    if let Some(direction) = unsized_access(inst.opcode) {
+        return Some(MemoryAccess {
+            size: None,
+            direction,
+        });
+    }
+
+    access(inst.opcode).map(|(size, direction)| MemoryAccess {
+        size: Some(size),
+        direction,
+    })
+}
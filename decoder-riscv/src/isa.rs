@@ -0,0 +1,252 @@
+//! ISA extensions a [`Decoder`](crate::Decoder) can be restricted to.
+//!
+//! Binaries record the ISA they were compiled for as a string such as
+//! `rv64i2p1_m2p0_a2p1_f2p2_d2p2_c2p0_zicsr2p0_zba1p0`, which [`Extensions::from_isa`] parses.
+
+use crate::Opcode;
+use std::ops::{BitOr, BitOrAssign};
+
+/// Set of ISA extensions on top of the base integer instruction set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extensions(u32);
+
+impl Extensions {
+    /// Only the base integer instruction set.
+    pub const NONE: Self = Self(0);
+    /// Every extension the decoder knows about.
+    pub const ALL: Self = Self((1 << 26) - 1);
+
+    pub const M: Self = Self(1 << 0);
+    pub const A: Self = Self(1 << 1);
+    pub const F: Self = Self(1 << 2);
+    pub const D: Self = Self(1 << 3);
+    pub const Q: Self = Self(1 << 4);
+    pub const C: Self = Self(1 << 5);
+    pub const V: Self = Self(1 << 6);
+    pub const ZICSR: Self = Self(1 << 7);
+    pub const ZIFENCEI: Self = Self(1 << 8);
+    pub const ZICOND: Self = Self(1 << 9);
+    pub const ZICBOM: Self = Self(1 << 10);
+    pub const ZICBOZ: Self = Self(1 << 11);
+    pub const ZBA: Self = Self(1 << 12);
+    pub const ZBB: Self = Self(1 << 13);
+    pub const ZBC: Self = Self(1 << 14);
+    pub const ZBS: Self = Self(1 << 15);
+    pub const ZBKB: Self = Self(1 << 16);
+    pub const ZBKC: Self = Self(1 << 17);
+    pub const ZBKX: Self = Self(1 << 18);
+    pub const ZKND: Self = Self(1 << 19);
+    pub const ZKNE: Self = Self(1 << 20);
+    pub const ZKNH: Self = Self(1 << 21);
+    pub const ZKSED: Self = Self(1 << 22);
+    pub const ZKSH: Self = Self(1 << 23);
+    pub const ZFH: Self = Self(1 << 24);
+    pub const ZFHMIN: Self = Self(1 << 25);
+
+    /// Whether every extension in `other` is part of the set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether any extension in `other` is part of the set.
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Parses an ISA string like `rv64imafdc_zba_zbb` or `rv32i2p1_m2p0_zicsr2p0`.
+    ///
+    /// Extensions the decoder doesn't know about, such as vendor `x` extensions, are ignored.
+    pub fn from_isa(isa: &str) -> Option<Self> {
+        let isa = isa.to_ascii_lowercase();
+        let isa = isa.strip_prefix("rv32").or_else(|| isa.strip_prefix("rv64"))?;
+        let mut extensions = Self::NONE;
+
+        for part in isa.split('_') {
+            let bytes = part.as_bytes();
+            let mut idx = 0;
+
+            while idx < bytes.len() {
+                // multi-letter extensions run until the next underscore
+                if let b'z' | b's' | b'x' = bytes[idx] {
+                    extensions |= Self::multi_letter(strip_version(&part[idx..]));
+                    break;
+                }
+
+                // single-letter extensions are optionally followed by a `<major>p<minor>` version
+                let c = bytes[idx];
+                idx += 1;
+                let start = idx;
+                idx += digits(&bytes[idx..]);
+                if idx != start
+                    && bytes.get(idx) == Some(&b'p')
+                    && bytes.get(idx + 1).is_some_and(u8::is_ascii_digit)
+                {
+                    idx += 1 + digits(&bytes[idx + 1..]);
+                }
+                let version = &part[start..idx];
+
+                extensions |= match c {
+                    // before version 2.1 the base ISA still included these
+                    b'i' | b'e' if matches!(version, "" | "2" | "2p0") => {
+                        Self::ZICSR | Self::ZIFENCEI
+                    }
+                    b'g' => Self::M | Self::A | Self::F | Self::D | Self::ZICSR | Self::ZIFENCEI,
+                    b'b' => Self::ZBA | Self::ZBB | Self::ZBS,
+                    b'm' => Self::M,
+                    b'a' => Self::A,
+                    b'f' => Self::F,
+                    b'd' => Self::D,
+                    b'q' => Self::Q,
+                    b'c' => Self::C,
+                    b'v' => Self::V,
+                    _ => Self::NONE,
+                };
+            }
+        }
+
+        // extensions that depend on others
+        if extensions.contains(Self::V) {
+            extensions |= Self::D;
+        }
+        if extensions.contains(Self::Q) {
+            extensions |= Self::D;
+        }
+        if extensions.contains(Self::D) {
+            extensions |= Self::F;
+        }
+        if extensions.contains(Self::ZFH) {
+            extensions |= Self::ZFHMIN;
+        }
+        if extensions.intersects(Self::F | Self::ZFHMIN) {
+            extensions |= Self::F | Self::ZICSR;
+        }
+
+        Some(extensions)
+    }
+
+    fn multi_letter(name: &str) -> Self {
+        match name {
+            "zicsr" => Self::ZICSR,
+            "zifencei" => Self::ZIFENCEI,
+            "zicond" => Self::ZICOND,
+            "zicbom" => Self::ZICBOM,
+            "zicboz" => Self::ZICBOZ,
+            "zba" => Self::ZBA,
+            "zbb" => Self::ZBB,
+            "zbc" => Self::ZBC,
+            "zbs" => Self::ZBS,
+            "zbkb" => Self::ZBKB,
+            "zbkc" => Self::ZBKC,
+            "zbkx" => Self::ZBKX,
+            "zknd" => Self::ZKND,
+            "zkne" => Self::ZKNE,
+            "zknh" => Self::ZKNH,
+            "zksed" => Self::ZKSED,
+            "zksh" => Self::ZKSH,
+            "zkn" | "zk" => {
+                Self::ZBKB | Self::ZBKC | Self::ZBKX | Self::ZKND | Self::ZKNE | Self::ZKNH
+            }
+            "zks" => Self::ZBKB | Self::ZBKC | Self::ZBKX | Self::ZKSED | Self::ZKSH,
+            "zfh" => Self::ZFH,
+            "zfhmin" => Self::ZFHMIN,
+            // the embedded vector subsets are decoded like the full extension
+            "zve32x" | "zve32f" | "zve64x" | "zve64f" | "zve64d" => Self::V,
+            _ => Self::NONE,
+        }
+    }
+
+    /// Whether instructions with the given opcode are part of the set.
+    pub(crate) fn allows(self, opcode: Opcode) -> bool {
+        use Opcode::*;
+
+        match opcode {
+            ANDN | ORN | XNOR | ROL | ROR | RORI | ROLW | RORW | RORIW | REV8 => {
+                self.intersects(Self::ZBB | Self::ZBKB)
+            }
+            CLMUL | CLMULH => self.intersects(Self::ZBC | Self::ZBKC),
+            AES32ESI | AES32ESMI | AES64ES | AES64ESM => self.contains(Self::ZKNE),
+            AES64KS1I | AES64KS2 => self.intersects(Self::ZKND | Self::ZKNE),
+            FLH | FSH | FMV_X_H | FMV_H_X | FCVT_S_H | FCVT_H_S | FCVT_D_H | FCVT_H_D
+            | FCVT_Q_H | FCVT_H_Q => self.intersects(Self::ZFH | Self::ZFHMIN),
+            C_FLD | C_FSD | C_FLDSP | C_FSDSP => self.contains(Self::C | Self::D),
+            C_FLW | C_FSW | C_FLWSP | C_FSWSP => self.contains(Self::C | Self::F),
+            _ => {
+                let (_, required) =
+                    GROUPS.iter().rev().find(|(first, _)| *first as u32 <= opcode as u32).unwrap();
+
+                self.contains(*required)
+            }
+        }
+    }
+}
+
+impl Default for Extensions {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl BitOr for Extensions {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Extensions {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Number of leading ascii digits.
+fn digits(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|b| b.is_ascii_digit()).count()
+}
+
+/// Strips a trailing version such as `1p0` or `2` of an extension name.
+fn strip_version(name: &str) -> &str {
+    let major = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    if major.len() == name.len() {
+        return name;
+    }
+
+    if let Some(rest) = major.strip_suffix('p') {
+        let stripped = rest.trim_end_matches(|c: char| c.is_ascii_digit());
+        if stripped.len() != rest.len() {
+            return stripped;
+        }
+    }
+
+    major
+}
+
+/// First opcode of each group in [`Opcode`] and the extensions required to decode the group.
+const GROUPS: [(Opcode, Extensions); 25] = [
+    (Opcode::Invalid, Extensions::NONE),
+    (Opcode::FENCE_I, Extensions::ZIFENCEI),
+    (Opcode::CSRRW, Extensions::ZICSR),
+    (Opcode::MUL, Extensions::M),
+    (Opcode::LR_W, Extensions::A),
+    (Opcode::FLW, Extensions::F),
+    (Opcode::FLD, Extensions::D),
+    (Opcode::FLQ, Extensions::Q),
+    (Opcode::C_ADDI4SPN, Extensions::C),
+    (Opcode::SRET, Extensions::NONE),
+    (Opcode::SH1ADD, Extensions::ZBA),
+    (Opcode::ANDN, Extensions::ZBB),
+    (Opcode::CLMUL, Extensions::ZBC),
+    (Opcode::BCLR, Extensions::ZBS),
+    (Opcode::PACK, Extensions::ZBKB),
+    (Opcode::XPERM4, Extensions::ZBKX),
+    (Opcode::CZERO_EQZ, Extensions::ZICOND),
+    (Opcode::CBO_CLEAN, Extensions::ZICBOM),
+    (Opcode::CBO_ZERO, Extensions::ZICBOZ),
+    (Opcode::FLH, Extensions::ZFH),
+    (Opcode::AES32DSI, Extensions::ZKND),
+    (Opcode::SHA256SIG0, Extensions::ZKNH),
+    (Opcode::SM4ED, Extensions::ZKSED),
+    (Opcode::SM3P0, Extensions::ZKSH),
+    (Opcode::VSETVLI, Extensions::V),
+];
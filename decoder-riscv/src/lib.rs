@@ -1,16 +1,22 @@
 //! Riscv64gc/Riscv32gc disassembler.
 
+mod behavior;
+mod isa;
+mod lift;
 mod tests;
+mod vector;
 
-use decoder::{Error, ErrorKind, ToTokens};
+pub use isa::Extensions;
+
+use decoder::{Error, ErrorKind, FlowKind, MemoryAccess, ToTokens};
 use debugvault::Index;
 use once_cell::sync::Lazy;
 use tokenizing::{TokenStream, ColorScheme, Colors};
 
 macro_rules! operands {
-    [] => {([$crate::Operand::Nothing; 3], 0)};
+    [] => {([$crate::Operand::Nothing; 4], 0)};
     [$($x:expr),+ $(,)?] => {{
-        let mut operands = [$crate::Operand::Nothing; 3];
+        let mut operands = [$crate::Operand::Nothing; 4];
         let mut idx = 0;
         $(
             idx += 1;
@@ -35,13 +41,17 @@ pub enum Register {
     Fs0, Fs1,
     Fa0, Fa1, Fa2, Fa3, Fa4, Fa5, Fa6, Fa7,
     Fs2, Fs3, Fs4, Fs5, Fs6, Fs7, Fs8, Fs9, Fs10, Fs11,
-    Ft8, Ft9, Ft10, Ft11
+    Ft8, Ft9, Ft10, Ft11,
+    V0, V1, V2, V3, V4, V5, V6, V7,
+    V8, V9, V10, V11, V12, V13, V14, V15,
+    V16, V17, V18, V19, V20, V21, V22, V23,
+    V24, V25, V26, V27, V28, V29, V30, V31
 }
 
 impl Register {
     pub fn as_str(&self) -> &'static str {
         #[rustfmt::skip]
-        const REG_LITERALS: [&str; 64] = [
+        const REG_LITERALS: [&str; 96] = [
             "zero", "ra", "sp", "gp", "tp",
             "t0", "t1", "t2",
             "s0", "s1",
@@ -52,7 +62,11 @@ impl Register {
             "fs0", "fs1",
             "fa0", "fa1", "fa2", "fa3", "fa4", "fa5", "fa6", "fa7",
             "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9", "fs10", "fs11",
-            "ft8", "ft9", "ft10", "ft11"
+            "ft8", "ft9", "ft10", "ft11",
+            "v0", "v1", "v2", "v3", "v4", "v5", "v6", "v7",
+            "v8", "v9", "v10", "v11", "v12", "v13", "v14", "v15",
+            "v16", "v17", "v18", "v19", "v20", "v21", "v22", "v23",
+            "v24", "v25", "v26", "v27", "v28", "v29", "v30", "v31"
         ];
 
         REG_LITERALS[*self as usize]
@@ -89,6 +103,26 @@ impl Register {
 
         Ok(unsafe { std::mem::transmute(num as u32 + 40) })
     }
+
+    #[inline]
+    fn get_float(num: u32) -> Result<Self, ErrorKind> {
+        // if the num isn't between $ft0 and $ft11
+        if num >= 32 {
+            return Err(ErrorKind::InvalidRegister);
+        }
+
+        Self::get(num + 32)
+    }
+
+    #[inline]
+    fn get_vec(num: u32) -> Result<Self, ErrorKind> {
+        // if the num isn't between $v0 and $v31
+        if num >= 32 {
+            return Err(ErrorKind::InvalidRegister);
+        }
+
+        Ok(unsafe { std::mem::transmute::<u32, Register>(num + 64) })
+    }
 }
 
 /// Opcodes for risc-v 32-bit and 64-bit instructions.
@@ -401,6 +435,773 @@ pub enum Opcode {
     C_SWSP,
     C_FSWSP,
     C_SDSP,
+    // *privileged instructions*
+    SRET,
+    MRET,
+    WFI,
+    SFENCE_VMA,
+    // *zba instructions*
+    SH1ADD,
+    SH2ADD,
+    SH3ADD,
+    ADD_UW,
+    SH1ADD_UW,
+    SH2ADD_UW,
+    SH3ADD_UW,
+    SLLI_UW,
+    // *zbb instructions*
+    ANDN,
+    ORN,
+    XNOR,
+    CLZ,
+    CTZ,
+    CPOP,
+    CLZW,
+    CTZW,
+    CPOPW,
+    MAX,
+    MAXU,
+    MIN,
+    MINU,
+    SEXT_B,
+    SEXT_H,
+    ZEXT_H,
+    ROL,
+    ROR,
+    RORI,
+    ROLW,
+    RORW,
+    RORIW,
+    ORC_B,
+    REV8,
+    // *zbc instructions*
+    CLMUL,
+    CLMULH,
+    CLMULR,
+    // *zbs instructions*
+    BCLR,
+    BCLRI,
+    BEXT,
+    BEXTI,
+    BINV,
+    BINVI,
+    BSET,
+    BSETI,
+    // *zbkb/zbkx instructions*
+    PACK,
+    PACKH,
+    PACKW,
+    BREV8,
+    ZIP,
+    UNZIP,
+    XPERM4,
+    XPERM8,
+    // *zicond instructions*
+    CZERO_EQZ,
+    CZERO_NEZ,
+    // *zicbom/zicboz instructions*
+    CBO_CLEAN,
+    CBO_FLUSH,
+    CBO_INVAL,
+    CBO_ZERO,
+    // *zfh/zfhmin instructions*
+    FLH,
+    FSH,
+    FMADD_H,
+    FMSUB_H,
+    FNMSUB_H,
+    FNMADD_H,
+    FADD_H,
+    FSUB_H,
+    FMUL_H,
+    FDIV_H,
+    FSQRT_H,
+    FSGNJ_H,
+    FSGNJN_H,
+    FSGNJX_H,
+    FMIN_H,
+    FMAX_H,
+    FCVT_S_H,
+    FCVT_H_S,
+    FCVT_D_H,
+    FCVT_H_D,
+    FCVT_Q_H,
+    FCVT_H_Q,
+    FEQ_H,
+    FLT_H,
+    FLE_H,
+    FCLASS_H,
+    FCVT_W_H,
+    FCVT_WU_H,
+    FMV_X_H,
+    FCVT_H_W,
+    FCVT_H_WU,
+    FMV_H_X,
+    FCVT_L_H,
+    FCVT_LU_H,
+    FCVT_H_L,
+    FCVT_H_LU,
+    // *zknd/zkne instructions*
+    AES32DSI,
+    AES32DSMI,
+    AES32ESI,
+    AES32ESMI,
+    AES64DS,
+    AES64DSM,
+    AES64ES,
+    AES64ESM,
+    AES64IM,
+    AES64KS1I,
+    AES64KS2,
+    // *zknh instructions*
+    SHA256SIG0,
+    SHA256SIG1,
+    SHA256SUM0,
+    SHA256SUM1,
+    SHA512SIG0H,
+    SHA512SIG0L,
+    SHA512SIG1H,
+    SHA512SIG1L,
+    SHA512SUM0R,
+    SHA512SUM1R,
+    SHA512SIG0,
+    SHA512SIG1,
+    SHA512SUM0,
+    SHA512SUM1,
+    // *zksed/zksh instructions*
+    SM4ED,
+    SM4KS,
+    SM3P0,
+    SM3P1,
+    // *rvv configuration and memory instructions*
+    VSETVLI,
+    VSETIVLI,
+    VSETVL,
+    VLE8_V,
+    VLE16_V,
+    VLE32_V,
+    VLE64_V,
+    VLE8FF_V,
+    VLE16FF_V,
+    VLE32FF_V,
+    VLE64FF_V,
+    VLM_V,
+    VLSE8_V,
+    VLSE16_V,
+    VLSE32_V,
+    VLSE64_V,
+    VLUXEI8_V,
+    VLUXEI16_V,
+    VLUXEI32_V,
+    VLUXEI64_V,
+    VLOXEI8_V,
+    VLOXEI16_V,
+    VLOXEI32_V,
+    VLOXEI64_V,
+    VL1RE8_V,
+    VL1RE16_V,
+    VL1RE32_V,
+    VL1RE64_V,
+    VL2RE8_V,
+    VL2RE16_V,
+    VL2RE32_V,
+    VL2RE64_V,
+    VL4RE8_V,
+    VL4RE16_V,
+    VL4RE32_V,
+    VL4RE64_V,
+    VL8RE8_V,
+    VL8RE16_V,
+    VL8RE32_V,
+    VL8RE64_V,
+    VLSEG2E8_V,
+    VLSEG2E16_V,
+    VLSEG2E32_V,
+    VLSEG2E64_V,
+    VLSEG3E8_V,
+    VLSEG3E16_V,
+    VLSEG3E32_V,
+    VLSEG3E64_V,
+    VLSEG4E8_V,
+    VLSEG4E16_V,
+    VLSEG4E32_V,
+    VLSEG4E64_V,
+    VLSEG5E8_V,
+    VLSEG5E16_V,
+    VLSEG5E32_V,
+    VLSEG5E64_V,
+    VLSEG6E8_V,
+    VLSEG6E16_V,
+    VLSEG6E32_V,
+    VLSEG6E64_V,
+    VLSEG7E8_V,
+    VLSEG7E16_V,
+    VLSEG7E32_V,
+    VLSEG7E64_V,
+    VLSEG8E8_V,
+    VLSEG8E16_V,
+    VLSEG8E32_V,
+    VLSEG8E64_V,
+    VLSEG2E8FF_V,
+    VLSEG2E16FF_V,
+    VLSEG2E32FF_V,
+    VLSEG2E64FF_V,
+    VLSEG3E8FF_V,
+    VLSEG3E16FF_V,
+    VLSEG3E32FF_V,
+    VLSEG3E64FF_V,
+    VLSEG4E8FF_V,
+    VLSEG4E16FF_V,
+    VLSEG4E32FF_V,
+    VLSEG4E64FF_V,
+    VLSEG5E8FF_V,
+    VLSEG5E16FF_V,
+    VLSEG5E32FF_V,
+    VLSEG5E64FF_V,
+    VLSEG6E8FF_V,
+    VLSEG6E16FF_V,
+    VLSEG6E32FF_V,
+    VLSEG6E64FF_V,
+    VLSEG7E8FF_V,
+    VLSEG7E16FF_V,
+    VLSEG7E32FF_V,
+    VLSEG7E64FF_V,
+    VLSEG8E8FF_V,
+    VLSEG8E16FF_V,
+    VLSEG8E32FF_V,
+    VLSEG8E64FF_V,
+    VLSSEG2E8_V,
+    VLSSEG2E16_V,
+    VLSSEG2E32_V,
+    VLSSEG2E64_V,
+    VLSSEG3E8_V,
+    VLSSEG3E16_V,
+    VLSSEG3E32_V,
+    VLSSEG3E64_V,
+    VLSSEG4E8_V,
+    VLSSEG4E16_V,
+    VLSSEG4E32_V,
+    VLSSEG4E64_V,
+    VLSSEG5E8_V,
+    VLSSEG5E16_V,
+    VLSSEG5E32_V,
+    VLSSEG5E64_V,
+    VLSSEG6E8_V,
+    VLSSEG6E16_V,
+    VLSSEG6E32_V,
+    VLSSEG6E64_V,
+    VLSSEG7E8_V,
+    VLSSEG7E16_V,
+    VLSSEG7E32_V,
+    VLSSEG7E64_V,
+    VLSSEG8E8_V,
+    VLSSEG8E16_V,
+    VLSSEG8E32_V,
+    VLSSEG8E64_V,
+    VLUXSEG2EI8_V,
+    VLUXSEG2EI16_V,
+    VLUXSEG2EI32_V,
+    VLUXSEG2EI64_V,
+    VLUXSEG3EI8_V,
+    VLUXSEG3EI16_V,
+    VLUXSEG3EI32_V,
+    VLUXSEG3EI64_V,
+    VLUXSEG4EI8_V,
+    VLUXSEG4EI16_V,
+    VLUXSEG4EI32_V,
+    VLUXSEG4EI64_V,
+    VLUXSEG5EI8_V,
+    VLUXSEG5EI16_V,
+    VLUXSEG5EI32_V,
+    VLUXSEG5EI64_V,
+    VLUXSEG6EI8_V,
+    VLUXSEG6EI16_V,
+    VLUXSEG6EI32_V,
+    VLUXSEG6EI64_V,
+    VLUXSEG7EI8_V,
+    VLUXSEG7EI16_V,
+    VLUXSEG7EI32_V,
+    VLUXSEG7EI64_V,
+    VLUXSEG8EI8_V,
+    VLUXSEG8EI16_V,
+    VLUXSEG8EI32_V,
+    VLUXSEG8EI64_V,
+    VLOXSEG2EI8_V,
+    VLOXSEG2EI16_V,
+    VLOXSEG2EI32_V,
+    VLOXSEG2EI64_V,
+    VLOXSEG3EI8_V,
+    VLOXSEG3EI16_V,
+    VLOXSEG3EI32_V,
+    VLOXSEG3EI64_V,
+    VLOXSEG4EI8_V,
+    VLOXSEG4EI16_V,
+    VLOXSEG4EI32_V,
+    VLOXSEG4EI64_V,
+    VLOXSEG5EI8_V,
+    VLOXSEG5EI16_V,
+    VLOXSEG5EI32_V,
+    VLOXSEG5EI64_V,
+    VLOXSEG6EI8_V,
+    VLOXSEG6EI16_V,
+    VLOXSEG6EI32_V,
+    VLOXSEG6EI64_V,
+    VLOXSEG7EI8_V,
+    VLOXSEG7EI16_V,
+    VLOXSEG7EI32_V,
+    VLOXSEG7EI64_V,
+    VLOXSEG8EI8_V,
+    VLOXSEG8EI16_V,
+    VLOXSEG8EI32_V,
+    VLOXSEG8EI64_V,
+    VSE8_V,
+    VSE16_V,
+    VSE32_V,
+    VSE64_V,
+    VSM_V,
+    VSSE8_V,
+    VSSE16_V,
+    VSSE32_V,
+    VSSE64_V,
+    VSUXEI8_V,
+    VSUXEI16_V,
+    VSUXEI32_V,
+    VSUXEI64_V,
+    VSOXEI8_V,
+    VSOXEI16_V,
+    VSOXEI32_V,
+    VSOXEI64_V,
+    VS1R_V,
+    VS2R_V,
+    VS4R_V,
+    VS8R_V,
+    VSSEG2E8_V,
+    VSSEG2E16_V,
+    VSSEG2E32_V,
+    VSSEG2E64_V,
+    VSSEG3E8_V,
+    VSSEG3E16_V,
+    VSSEG3E32_V,
+    VSSEG3E64_V,
+    VSSEG4E8_V,
+    VSSEG4E16_V,
+    VSSEG4E32_V,
+    VSSEG4E64_V,
+    VSSEG5E8_V,
+    VSSEG5E16_V,
+    VSSEG5E32_V,
+    VSSEG5E64_V,
+    VSSEG6E8_V,
+    VSSEG6E16_V,
+    VSSEG6E32_V,
+    VSSEG6E64_V,
+    VSSEG7E8_V,
+    VSSEG7E16_V,
+    VSSEG7E32_V,
+    VSSEG7E64_V,
+    VSSEG8E8_V,
+    VSSEG8E16_V,
+    VSSEG8E32_V,
+    VSSEG8E64_V,
+    VSSSEG2E8_V,
+    VSSSEG2E16_V,
+    VSSSEG2E32_V,
+    VSSSEG2E64_V,
+    VSSSEG3E8_V,
+    VSSSEG3E16_V,
+    VSSSEG3E32_V,
+    VSSSEG3E64_V,
+    VSSSEG4E8_V,
+    VSSSEG4E16_V,
+    VSSSEG4E32_V,
+    VSSSEG4E64_V,
+    VSSSEG5E8_V,
+    VSSSEG5E16_V,
+    VSSSEG5E32_V,
+    VSSSEG5E64_V,
+    VSSSEG6E8_V,
+    VSSSEG6E16_V,
+    VSSSEG6E32_V,
+    VSSSEG6E64_V,
+    VSSSEG7E8_V,
+    VSSSEG7E16_V,
+    VSSSEG7E32_V,
+    VSSSEG7E64_V,
+    VSSSEG8E8_V,
+    VSSSEG8E16_V,
+    VSSSEG8E32_V,
+    VSSSEG8E64_V,
+    VSUXSEG2EI8_V,
+    VSUXSEG2EI16_V,
+    VSUXSEG2EI32_V,
+    VSUXSEG2EI64_V,
+    VSUXSEG3EI8_V,
+    VSUXSEG3EI16_V,
+    VSUXSEG3EI32_V,
+    VSUXSEG3EI64_V,
+    VSUXSEG4EI8_V,
+    VSUXSEG4EI16_V,
+    VSUXSEG4EI32_V,
+    VSUXSEG4EI64_V,
+    VSUXSEG5EI8_V,
+    VSUXSEG5EI16_V,
+    VSUXSEG5EI32_V,
+    VSUXSEG5EI64_V,
+    VSUXSEG6EI8_V,
+    VSUXSEG6EI16_V,
+    VSUXSEG6EI32_V,
+    VSUXSEG6EI64_V,
+    VSUXSEG7EI8_V,
+    VSUXSEG7EI16_V,
+    VSUXSEG7EI32_V,
+    VSUXSEG7EI64_V,
+    VSUXSEG8EI8_V,
+    VSUXSEG8EI16_V,
+    VSUXSEG8EI32_V,
+    VSUXSEG8EI64_V,
+    VSOXSEG2EI8_V,
+    VSOXSEG2EI16_V,
+    VSOXSEG2EI32_V,
+    VSOXSEG2EI64_V,
+    VSOXSEG3EI8_V,
+    VSOXSEG3EI16_V,
+    VSOXSEG3EI32_V,
+    VSOXSEG3EI64_V,
+    VSOXSEG4EI8_V,
+    VSOXSEG4EI16_V,
+    VSOXSEG4EI32_V,
+    VSOXSEG4EI64_V,
+    VSOXSEG5EI8_V,
+    VSOXSEG5EI16_V,
+    VSOXSEG5EI32_V,
+    VSOXSEG5EI64_V,
+    VSOXSEG6EI8_V,
+    VSOXSEG6EI16_V,
+    VSOXSEG6EI32_V,
+    VSOXSEG6EI64_V,
+    VSOXSEG7EI8_V,
+    VSOXSEG7EI16_V,
+    VSOXSEG7EI32_V,
+    VSOXSEG7EI64_V,
+    VSOXSEG8EI8_V,
+    VSOXSEG8EI16_V,
+    VSOXSEG8EI32_V,
+    VSOXSEG8EI64_V,
+    // *rvv arithmetic instructions*
+    VADD_VV,
+    VADD_VX,
+    VADD_VI,
+    VSUB_VV,
+    VSUB_VX,
+    VRSUB_VX,
+    VRSUB_VI,
+    VMINU_VV,
+    VMINU_VX,
+    VMIN_VV,
+    VMIN_VX,
+    VMAXU_VV,
+    VMAXU_VX,
+    VMAX_VV,
+    VMAX_VX,
+    VAND_VV,
+    VAND_VX,
+    VAND_VI,
+    VOR_VV,
+    VOR_VX,
+    VOR_VI,
+    VXOR_VV,
+    VXOR_VX,
+    VXOR_VI,
+    VRGATHER_VV,
+    VRGATHER_VX,
+    VRGATHER_VI,
+    VRGATHEREI16_VV,
+    VSLIDEUP_VX,
+    VSLIDEUP_VI,
+    VSLIDEDOWN_VX,
+    VSLIDEDOWN_VI,
+    VMSEQ_VV,
+    VMSEQ_VX,
+    VMSEQ_VI,
+    VMSNE_VV,
+    VMSNE_VX,
+    VMSNE_VI,
+    VMSLTU_VV,
+    VMSLTU_VX,
+    VMSLT_VV,
+    VMSLT_VX,
+    VMSLEU_VV,
+    VMSLEU_VX,
+    VMSLEU_VI,
+    VMSLE_VV,
+    VMSLE_VX,
+    VMSLE_VI,
+    VMSGTU_VX,
+    VMSGTU_VI,
+    VMSGT_VX,
+    VMSGT_VI,
+    VSADDU_VV,
+    VSADDU_VX,
+    VSADDU_VI,
+    VSADD_VV,
+    VSADD_VX,
+    VSADD_VI,
+    VSSUBU_VV,
+    VSSUBU_VX,
+    VSSUB_VV,
+    VSSUB_VX,
+    VSLL_VV,
+    VSLL_VX,
+    VSLL_VI,
+    VSMUL_VV,
+    VSMUL_VX,
+    VSRL_VV,
+    VSRL_VX,
+    VSRL_VI,
+    VSRA_VV,
+    VSRA_VX,
+    VSRA_VI,
+    VSSRL_VV,
+    VSSRL_VX,
+    VSSRL_VI,
+    VSSRA_VV,
+    VSSRA_VX,
+    VSSRA_VI,
+    VNSRL_WV,
+    VNSRL_WX,
+    VNSRL_WI,
+    VNSRA_WV,
+    VNSRA_WX,
+    VNSRA_WI,
+    VNCLIPU_WV,
+    VNCLIPU_WX,
+    VNCLIPU_WI,
+    VNCLIP_WV,
+    VNCLIP_WX,
+    VNCLIP_WI,
+    VWREDSUMU_VS,
+    VWREDSUM_VS,
+    VREDSUM_VS,
+    VREDAND_VS,
+    VREDOR_VS,
+    VREDXOR_VS,
+    VREDMINU_VS,
+    VREDMIN_VS,
+    VREDMAXU_VS,
+    VREDMAX_VS,
+    VAADDU_VV,
+    VAADDU_VX,
+    VAADD_VV,
+    VAADD_VX,
+    VASUBU_VV,
+    VASUBU_VX,
+    VASUB_VV,
+    VASUB_VX,
+    VSLIDE1UP_VX,
+    VSLIDE1DOWN_VX,
+    VCOMPRESS_VM,
+    VMANDN_MM,
+    VMAND_MM,
+    VMOR_MM,
+    VMXOR_MM,
+    VMORN_MM,
+    VMNAND_MM,
+    VMNOR_MM,
+    VMXNOR_MM,
+    VDIVU_VV,
+    VDIVU_VX,
+    VDIV_VV,
+    VDIV_VX,
+    VREMU_VV,
+    VREMU_VX,
+    VREM_VV,
+    VREM_VX,
+    VMULHU_VV,
+    VMULHU_VX,
+    VMUL_VV,
+    VMUL_VX,
+    VMULHSU_VV,
+    VMULHSU_VX,
+    VMULH_VV,
+    VMULH_VX,
+    VMADD_VV,
+    VMADD_VX,
+    VNMSUB_VV,
+    VNMSUB_VX,
+    VMACC_VV,
+    VMACC_VX,
+    VNMSAC_VV,
+    VNMSAC_VX,
+    VWADDU_VV,
+    VWADDU_VX,
+    VWADD_VV,
+    VWADD_VX,
+    VWSUBU_VV,
+    VWSUBU_VX,
+    VWSUB_VV,
+    VWSUB_VX,
+    VWADDU_WV,
+    VWADDU_WX,
+    VWADD_WV,
+    VWADD_WX,
+    VWSUBU_WV,
+    VWSUBU_WX,
+    VWSUB_WV,
+    VWSUB_WX,
+    VWMULU_VV,
+    VWMULU_VX,
+    VWMULSU_VV,
+    VWMULSU_VX,
+    VWMUL_VV,
+    VWMUL_VX,
+    VWMACCU_VV,
+    VWMACCU_VX,
+    VWMACC_VV,
+    VWMACC_VX,
+    VWMACCUS_VX,
+    VWMACCSU_VV,
+    VWMACCSU_VX,
+    VFADD_VV,
+    VFADD_VF,
+    VFREDUSUM_VS,
+    VFSUB_VV,
+    VFSUB_VF,
+    VFREDOSUM_VS,
+    VFMIN_VV,
+    VFMIN_VF,
+    VFREDMIN_VS,
+    VFMAX_VV,
+    VFMAX_VF,
+    VFREDMAX_VS,
+    VFSGNJ_VV,
+    VFSGNJ_VF,
+    VFSGNJN_VV,
+    VFSGNJN_VF,
+    VFSGNJX_VV,
+    VFSGNJX_VF,
+    VFSLIDE1UP_VF,
+    VFSLIDE1DOWN_VF,
+    VMFEQ_VV,
+    VMFEQ_VF,
+    VMFLE_VV,
+    VMFLE_VF,
+    VMFLT_VV,
+    VMFLT_VF,
+    VMFNE_VV,
+    VMFNE_VF,
+    VMFGT_VF,
+    VMFGE_VF,
+    VFDIV_VV,
+    VFDIV_VF,
+    VFRDIV_VF,
+    VFMUL_VV,
+    VFMUL_VF,
+    VFRSUB_VF,
+    VFMADD_VV,
+    VFMADD_VF,
+    VFNMADD_VV,
+    VFNMADD_VF,
+    VFMSUB_VV,
+    VFMSUB_VF,
+    VFNMSUB_VV,
+    VFNMSUB_VF,
+    VFMACC_VV,
+    VFMACC_VF,
+    VFNMACC_VV,
+    VFNMACC_VF,
+    VFMSAC_VV,
+    VFMSAC_VF,
+    VFNMSAC_VV,
+    VFNMSAC_VF,
+    VFWADD_VV,
+    VFWADD_VF,
+    VFWREDUSUM_VS,
+    VFWSUB_VV,
+    VFWSUB_VF,
+    VFWREDOSUM_VS,
+    VFWADD_WV,
+    VFWADD_WF,
+    VFWSUB_WV,
+    VFWSUB_WF,
+    VFWMUL_VV,
+    VFWMUL_VF,
+    VFWMACC_VV,
+    VFWMACC_VF,
+    VFWNMACC_VV,
+    VFWNMACC_VF,
+    VFWMSAC_VV,
+    VFWMSAC_VF,
+    VFWNMSAC_VV,
+    VFWNMSAC_VF,
+    VADC_VVM,
+    VADC_VXM,
+    VADC_VIM,
+    VMADC_VVM,
+    VMADC_VXM,
+    VMADC_VIM,
+    VMADC_VV,
+    VMADC_VX,
+    VMADC_VI,
+    VSBC_VVM,
+    VSBC_VXM,
+    VMSBC_VVM,
+    VMSBC_VXM,
+    VMSBC_VV,
+    VMSBC_VX,
+    VMERGE_VVM,
+    VMERGE_VXM,
+    VMERGE_VIM,
+    VMV_V_V,
+    VMV_V_X,
+    VMV_V_I,
+    VMV1R_V,
+    VMV2R_V,
+    VMV4R_V,
+    VMV8R_V,
+    VMV_X_S,
+    VMV_S_X,
+    VCPOP_M,
+    VFIRST_M,
+    VZEXT_VF2,
+    VSEXT_VF2,
+    VZEXT_VF4,
+    VSEXT_VF4,
+    VZEXT_VF8,
+    VSEXT_VF8,
+    VMSBF_M,
+    VMSOF_M,
+    VMSIF_M,
+    VIOTA_M,
+    VID_V,
+    VFMERGE_VFM,
+    VFMV_V_F,
+    VFMV_F_S,
+    VFMV_S_F,
+    VFCVT_XU_F_V,
+    VFCVT_X_F_V,
+    VFCVT_F_XU_V,
+    VFCVT_F_X_V,
+    VFCVT_RTZ_XU_F_V,
+    VFCVT_RTZ_X_F_V,
+    VFWCVT_XU_F_V,
+    VFWCVT_X_F_V,
+    VFWCVT_F_XU_V,
+    VFWCVT_F_X_V,
+    VFWCVT_F_F_V,
+    VFWCVT_RTZ_XU_F_V,
+    VFWCVT_RTZ_X_F_V,
+    VFNCVT_XU_F_W,
+    VFNCVT_X_F_W,
+    VFNCVT_F_XU_W,
+    VFNCVT_F_X_W,
+    VFNCVT_F_F_W,
+    VFNCVT_ROD_F_F_W,
+    VFNCVT_RTZ_XU_F_W,
+    VFNCVT_RTZ_X_F_W,
+    VFSQRT_V,
+    VFRSQRT7_V,
+    VFREC7_V,
+    VFCLASS_V,
 }
 
 impl Opcode {
@@ -428,7 +1229,7 @@ impl Opcode {
     }
 }
 
-static OPCODE_NAMES: [&str; 284] = [
+static OPCODE_NAMES: [&str; 1037] = [
     "invalid",
     "la",
     "lla",
@@ -713,6 +1514,759 @@ static OPCODE_NAMES: [&str; 284] = [
     "c.swsp",
     "c.fswsp",
     "c.sdsp",
+    "sret",
+    "mret",
+    "wfi",
+    "sfence.vma",
+    "sh1add",
+    "sh2add",
+    "sh3add",
+    "add.uw",
+    "sh1add.uw",
+    "sh2add.uw",
+    "sh3add.uw",
+    "slli.uw",
+    "andn",
+    "orn",
+    "xnor",
+    "clz",
+    "ctz",
+    "cpop",
+    "clzw",
+    "ctzw",
+    "cpopw",
+    "max",
+    "maxu",
+    "min",
+    "minu",
+    "sext.b",
+    "sext.h",
+    "zext.h",
+    "rol",
+    "ror",
+    "rori",
+    "rolw",
+    "rorw",
+    "roriw",
+    "orc.b",
+    "rev8",
+    "clmul",
+    "clmulh",
+    "clmulr",
+    "bclr",
+    "bclri",
+    "bext",
+    "bexti",
+    "binv",
+    "binvi",
+    "bset",
+    "bseti",
+    "pack",
+    "packh",
+    "packw",
+    "brev8",
+    "zip",
+    "unzip",
+    "xperm4",
+    "xperm8",
+    "czero.eqz",
+    "czero.nez",
+    "cbo.clean",
+    "cbo.flush",
+    "cbo.inval",
+    "cbo.zero",
+    "flh",
+    "fsh",
+    "fmadd.h",
+    "fmsub.h",
+    "fnmsub.h",
+    "fnmadd.h",
+    "fadd.h",
+    "fsub.h",
+    "fmul.h",
+    "fdiv.h",
+    "fsqrt.h",
+    "fsgnj.h",
+    "fsgnjn.h",
+    "fsgnjx.h",
+    "fmin.h",
+    "fmax.h",
+    "fcvt.s.h",
+    "fcvt.h.s",
+    "fcvt.d.h",
+    "fcvt.h.d",
+    "fcvt.q.h",
+    "fcvt.h.q",
+    "feq.h",
+    "flt.h",
+    "fle.h",
+    "fclass.h",
+    "fcvt.w.h",
+    "fcvt.wu.h",
+    "fmv.x.h",
+    "fcvt.h.w",
+    "fcvt.h.wu",
+    "fmv.h.x",
+    "fcvt.l.h",
+    "fcvt.lu.h",
+    "fcvt.h.l",
+    "fcvt.h.lu",
+    "aes32dsi",
+    "aes32dsmi",
+    "aes32esi",
+    "aes32esmi",
+    "aes64ds",
+    "aes64dsm",
+    "aes64es",
+    "aes64esm",
+    "aes64im",
+    "aes64ks1i",
+    "aes64ks2",
+    "sha256sig0",
+    "sha256sig1",
+    "sha256sum0",
+    "sha256sum1",
+    "sha512sig0h",
+    "sha512sig0l",
+    "sha512sig1h",
+    "sha512sig1l",
+    "sha512sum0r",
+    "sha512sum1r",
+    "sha512sig0",
+    "sha512sig1",
+    "sha512sum0",
+    "sha512sum1",
+    "sm4ed",
+    "sm4ks",
+    "sm3p0",
+    "sm3p1",
+    "vsetvli",
+    "vsetivli",
+    "vsetvl",
+    "vle8.v",
+    "vle16.v",
+    "vle32.v",
+    "vle64.v",
+    "vle8ff.v",
+    "vle16ff.v",
+    "vle32ff.v",
+    "vle64ff.v",
+    "vlm.v",
+    "vlse8.v",
+    "vlse16.v",
+    "vlse32.v",
+    "vlse64.v",
+    "vluxei8.v",
+    "vluxei16.v",
+    "vluxei32.v",
+    "vluxei64.v",
+    "vloxei8.v",
+    "vloxei16.v",
+    "vloxei32.v",
+    "vloxei64.v",
+    "vl1re8.v",
+    "vl1re16.v",
+    "vl1re32.v",
+    "vl1re64.v",
+    "vl2re8.v",
+    "vl2re16.v",
+    "vl2re32.v",
+    "vl2re64.v",
+    "vl4re8.v",
+    "vl4re16.v",
+    "vl4re32.v",
+    "vl4re64.v",
+    "vl8re8.v",
+    "vl8re16.v",
+    "vl8re32.v",
+    "vl8re64.v",
+    "vlseg2e8.v",
+    "vlseg2e16.v",
+    "vlseg2e32.v",
+    "vlseg2e64.v",
+    "vlseg3e8.v",
+    "vlseg3e16.v",
+    "vlseg3e32.v",
+    "vlseg3e64.v",
+    "vlseg4e8.v",
+    "vlseg4e16.v",
+    "vlseg4e32.v",
+    "vlseg4e64.v",
+    "vlseg5e8.v",
+    "vlseg5e16.v",
+    "vlseg5e32.v",
+    "vlseg5e64.v",
+    "vlseg6e8.v",
+    "vlseg6e16.v",
+    "vlseg6e32.v",
+    "vlseg6e64.v",
+    "vlseg7e8.v",
+    "vlseg7e16.v",
+    "vlseg7e32.v",
+    "vlseg7e64.v",
+    "vlseg8e8.v",
+    "vlseg8e16.v",
+    "vlseg8e32.v",
+    "vlseg8e64.v",
+    "vlseg2e8ff.v",
+    "vlseg2e16ff.v",
+    "vlseg2e32ff.v",
+    "vlseg2e64ff.v",
+    "vlseg3e8ff.v",
+    "vlseg3e16ff.v",
+    "vlseg3e32ff.v",
+    "vlseg3e64ff.v",
+    "vlseg4e8ff.v",
+    "vlseg4e16ff.v",
+    "vlseg4e32ff.v",
+    "vlseg4e64ff.v",
+    "vlseg5e8ff.v",
+    "vlseg5e16ff.v",
+    "vlseg5e32ff.v",
+    "vlseg5e64ff.v",
+    "vlseg6e8ff.v",
+    "vlseg6e16ff.v",
+    "vlseg6e32ff.v",
+    "vlseg6e64ff.v",
+    "vlseg7e8ff.v",
+    "vlseg7e16ff.v",
+    "vlseg7e32ff.v",
+    "vlseg7e64ff.v",
+    "vlseg8e8ff.v",
+    "vlseg8e16ff.v",
+    "vlseg8e32ff.v",
+    "vlseg8e64ff.v",
+    "vlsseg2e8.v",
+    "vlsseg2e16.v",
+    "vlsseg2e32.v",
+    "vlsseg2e64.v",
+    "vlsseg3e8.v",
+    "vlsseg3e16.v",
+    "vlsseg3e32.v",
+    "vlsseg3e64.v",
+    "vlsseg4e8.v",
+    "vlsseg4e16.v",
+    "vlsseg4e32.v",
+    "vlsseg4e64.v",
+    "vlsseg5e8.v",
+    "vlsseg5e16.v",
+    "vlsseg5e32.v",
+    "vlsseg5e64.v",
+    "vlsseg6e8.v",
+    "vlsseg6e16.v",
+    "vlsseg6e32.v",
+    "vlsseg6e64.v",
+    "vlsseg7e8.v",
+    "vlsseg7e16.v",
+    "vlsseg7e32.v",
+    "vlsseg7e64.v",
+    "vlsseg8e8.v",
+    "vlsseg8e16.v",
+    "vlsseg8e32.v",
+    "vlsseg8e64.v",
+    "vluxseg2ei8.v",
+    "vluxseg2ei16.v",
+    "vluxseg2ei32.v",
+    "vluxseg2ei64.v",
+    "vluxseg3ei8.v",
+    "vluxseg3ei16.v",
+    "vluxseg3ei32.v",
+    "vluxseg3ei64.v",
+    "vluxseg4ei8.v",
+    "vluxseg4ei16.v",
+    "vluxseg4ei32.v",
+    "vluxseg4ei64.v",
+    "vluxseg5ei8.v",
+    "vluxseg5ei16.v",
+    "vluxseg5ei32.v",
+    "vluxseg5ei64.v",
+    "vluxseg6ei8.v",
+    "vluxseg6ei16.v",
+    "vluxseg6ei32.v",
+    "vluxseg6ei64.v",
+    "vluxseg7ei8.v",
+    "vluxseg7ei16.v",
+    "vluxseg7ei32.v",
+    "vluxseg7ei64.v",
+    "vluxseg8ei8.v",
+    "vluxseg8ei16.v",
+    "vluxseg8ei32.v",
+    "vluxseg8ei64.v",
+    "vloxseg2ei8.v",
+    "vloxseg2ei16.v",
+    "vloxseg2ei32.v",
+    "vloxseg2ei64.v",
+    "vloxseg3ei8.v",
+    "vloxseg3ei16.v",
+    "vloxseg3ei32.v",
+    "vloxseg3ei64.v",
+    "vloxseg4ei8.v",
+    "vloxseg4ei16.v",
+    "vloxseg4ei32.v",
+    "vloxseg4ei64.v",
+    "vloxseg5ei8.v",
+    "vloxseg5ei16.v",
+    "vloxseg5ei32.v",
+    "vloxseg5ei64.v",
+    "vloxseg6ei8.v",
+    "vloxseg6ei16.v",
+    "vloxseg6ei32.v",
+    "vloxseg6ei64.v",
+    "vloxseg7ei8.v",
+    "vloxseg7ei16.v",
+    "vloxseg7ei32.v",
+    "vloxseg7ei64.v",
+    "vloxseg8ei8.v",
+    "vloxseg8ei16.v",
+    "vloxseg8ei32.v",
+    "vloxseg8ei64.v",
+    "vse8.v",
+    "vse16.v",
+    "vse32.v",
+    "vse64.v",
+    "vsm.v",
+    "vsse8.v",
+    "vsse16.v",
+    "vsse32.v",
+    "vsse64.v",
+    "vsuxei8.v",
+    "vsuxei16.v",
+    "vsuxei32.v",
+    "vsuxei64.v",
+    "vsoxei8.v",
+    "vsoxei16.v",
+    "vsoxei32.v",
+    "vsoxei64.v",
+    "vs1r.v",
+    "vs2r.v",
+    "vs4r.v",
+    "vs8r.v",
+    "vsseg2e8.v",
+    "vsseg2e16.v",
+    "vsseg2e32.v",
+    "vsseg2e64.v",
+    "vsseg3e8.v",
+    "vsseg3e16.v",
+    "vsseg3e32.v",
+    "vsseg3e64.v",
+    "vsseg4e8.v",
+    "vsseg4e16.v",
+    "vsseg4e32.v",
+    "vsseg4e64.v",
+    "vsseg5e8.v",
+    "vsseg5e16.v",
+    "vsseg5e32.v",
+    "vsseg5e64.v",
+    "vsseg6e8.v",
+    "vsseg6e16.v",
+    "vsseg6e32.v",
+    "vsseg6e64.v",
+    "vsseg7e8.v",
+    "vsseg7e16.v",
+    "vsseg7e32.v",
+    "vsseg7e64.v",
+    "vsseg8e8.v",
+    "vsseg8e16.v",
+    "vsseg8e32.v",
+    "vsseg8e64.v",
+    "vssseg2e8.v",
+    "vssseg2e16.v",
+    "vssseg2e32.v",
+    "vssseg2e64.v",
+    "vssseg3e8.v",
+    "vssseg3e16.v",
+    "vssseg3e32.v",
+    "vssseg3e64.v",
+    "vssseg4e8.v",
+    "vssseg4e16.v",
+    "vssseg4e32.v",
+    "vssseg4e64.v",
+    "vssseg5e8.v",
+    "vssseg5e16.v",
+    "vssseg5e32.v",
+    "vssseg5e64.v",
+    "vssseg6e8.v",
+    "vssseg6e16.v",
+    "vssseg6e32.v",
+    "vssseg6e64.v",
+    "vssseg7e8.v",
+    "vssseg7e16.v",
+    "vssseg7e32.v",
+    "vssseg7e64.v",
+    "vssseg8e8.v",
+    "vssseg8e16.v",
+    "vssseg8e32.v",
+    "vssseg8e64.v",
+    "vsuxseg2ei8.v",
+    "vsuxseg2ei16.v",
+    "vsuxseg2ei32.v",
+    "vsuxseg2ei64.v",
+    "vsuxseg3ei8.v",
+    "vsuxseg3ei16.v",
+    "vsuxseg3ei32.v",
+    "vsuxseg3ei64.v",
+    "vsuxseg4ei8.v",
+    "vsuxseg4ei16.v",
+    "vsuxseg4ei32.v",
+    "vsuxseg4ei64.v",
+    "vsuxseg5ei8.v",
+    "vsuxseg5ei16.v",
+    "vsuxseg5ei32.v",
+    "vsuxseg5ei64.v",
+    "vsuxseg6ei8.v",
+    "vsuxseg6ei16.v",
+    "vsuxseg6ei32.v",
+    "vsuxseg6ei64.v",
+    "vsuxseg7ei8.v",
+    "vsuxseg7ei16.v",
+    "vsuxseg7ei32.v",
+    "vsuxseg7ei64.v",
+    "vsuxseg8ei8.v",
+    "vsuxseg8ei16.v",
+    "vsuxseg8ei32.v",
+    "vsuxseg8ei64.v",
+    "vsoxseg2ei8.v",
+    "vsoxseg2ei16.v",
+    "vsoxseg2ei32.v",
+    "vsoxseg2ei64.v",
+    "vsoxseg3ei8.v",
+    "vsoxseg3ei16.v",
+    "vsoxseg3ei32.v",
+    "vsoxseg3ei64.v",
+    "vsoxseg4ei8.v",
+    "vsoxseg4ei16.v",
+    "vsoxseg4ei32.v",
+    "vsoxseg4ei64.v",
+    "vsoxseg5ei8.v",
+    "vsoxseg5ei16.v",
+    "vsoxseg5ei32.v",
+    "vsoxseg5ei64.v",
+    "vsoxseg6ei8.v",
+    "vsoxseg6ei16.v",
+    "vsoxseg6ei32.v",
+    "vsoxseg6ei64.v",
+    "vsoxseg7ei8.v",
+    "vsoxseg7ei16.v",
+    "vsoxseg7ei32.v",
+    "vsoxseg7ei64.v",
+    "vsoxseg8ei8.v",
+    "vsoxseg8ei16.v",
+    "vsoxseg8ei32.v",
+    "vsoxseg8ei64.v",
+    "vadd.vv",
+    "vadd.vx",
+    "vadd.vi",
+    "vsub.vv",
+    "vsub.vx",
+    "vrsub.vx",
+    "vrsub.vi",
+    "vminu.vv",
+    "vminu.vx",
+    "vmin.vv",
+    "vmin.vx",
+    "vmaxu.vv",
+    "vmaxu.vx",
+    "vmax.vv",
+    "vmax.vx",
+    "vand.vv",
+    "vand.vx",
+    "vand.vi",
+    "vor.vv",
+    "vor.vx",
+    "vor.vi",
+    "vxor.vv",
+    "vxor.vx",
+    "vxor.vi",
+    "vrgather.vv",
+    "vrgather.vx",
+    "vrgather.vi",
+    "vrgatherei16.vv",
+    "vslideup.vx",
+    "vslideup.vi",
+    "vslidedown.vx",
+    "vslidedown.vi",
+    "vmseq.vv",
+    "vmseq.vx",
+    "vmseq.vi",
+    "vmsne.vv",
+    "vmsne.vx",
+    "vmsne.vi",
+    "vmsltu.vv",
+    "vmsltu.vx",
+    "vmslt.vv",
+    "vmslt.vx",
+    "vmsleu.vv",
+    "vmsleu.vx",
+    "vmsleu.vi",
+    "vmsle.vv",
+    "vmsle.vx",
+    "vmsle.vi",
+    "vmsgtu.vx",
+    "vmsgtu.vi",
+    "vmsgt.vx",
+    "vmsgt.vi",
+    "vsaddu.vv",
+    "vsaddu.vx",
+    "vsaddu.vi",
+    "vsadd.vv",
+    "vsadd.vx",
+    "vsadd.vi",
+    "vssubu.vv",
+    "vssubu.vx",
+    "vssub.vv",
+    "vssub.vx",
+    "vsll.vv",
+    "vsll.vx",
+    "vsll.vi",
+    "vsmul.vv",
+    "vsmul.vx",
+    "vsrl.vv",
+    "vsrl.vx",
+    "vsrl.vi",
+    "vsra.vv",
+    "vsra.vx",
+    "vsra.vi",
+    "vssrl.vv",
+    "vssrl.vx",
+    "vssrl.vi",
+    "vssra.vv",
+    "vssra.vx",
+    "vssra.vi",
+    "vnsrl.wv",
+    "vnsrl.wx",
+    "vnsrl.wi",
+    "vnsra.wv",
+    "vnsra.wx",
+    "vnsra.wi",
+    "vnclipu.wv",
+    "vnclipu.wx",
+    "vnclipu.wi",
+    "vnclip.wv",
+    "vnclip.wx",
+    "vnclip.wi",
+    "vwredsumu.vs",
+    "vwredsum.vs",
+    "vredsum.vs",
+    "vredand.vs",
+    "vredor.vs",
+    "vredxor.vs",
+    "vredminu.vs",
+    "vredmin.vs",
+    "vredmaxu.vs",
+    "vredmax.vs",
+    "vaaddu.vv",
+    "vaaddu.vx",
+    "vaadd.vv",
+    "vaadd.vx",
+    "vasubu.vv",
+    "vasubu.vx",
+    "vasub.vv",
+    "vasub.vx",
+    "vslide1up.vx",
+    "vslide1down.vx",
+    "vcompress.vm",
+    "vmandn.mm",
+    "vmand.mm",
+    "vmor.mm",
+    "vmxor.mm",
+    "vmorn.mm",
+    "vmnand.mm",
+    "vmnor.mm",
+    "vmxnor.mm",
+    "vdivu.vv",
+    "vdivu.vx",
+    "vdiv.vv",
+    "vdiv.vx",
+    "vremu.vv",
+    "vremu.vx",
+    "vrem.vv",
+    "vrem.vx",
+    "vmulhu.vv",
+    "vmulhu.vx",
+    "vmul.vv",
+    "vmul.vx",
+    "vmulhsu.vv",
+    "vmulhsu.vx",
+    "vmulh.vv",
+    "vmulh.vx",
+    "vmadd.vv",
+    "vmadd.vx",
+    "vnmsub.vv",
+    "vnmsub.vx",
+    "vmacc.vv",
+    "vmacc.vx",
+    "vnmsac.vv",
+    "vnmsac.vx",
+    "vwaddu.vv",
+    "vwaddu.vx",
+    "vwadd.vv",
+    "vwadd.vx",
+    "vwsubu.vv",
+    "vwsubu.vx",
+    "vwsub.vv",
+    "vwsub.vx",
+    "vwaddu.wv",
+    "vwaddu.wx",
+    "vwadd.wv",
+    "vwadd.wx",
+    "vwsubu.wv",
+    "vwsubu.wx",
+    "vwsub.wv",
+    "vwsub.wx",
+    "vwmulu.vv",
+    "vwmulu.vx",
+    "vwmulsu.vv",
+    "vwmulsu.vx",
+    "vwmul.vv",
+    "vwmul.vx",
+    "vwmaccu.vv",
+    "vwmaccu.vx",
+    "vwmacc.vv",
+    "vwmacc.vx",
+    "vwmaccus.vx",
+    "vwmaccsu.vv",
+    "vwmaccsu.vx",
+    "vfadd.vv",
+    "vfadd.vf",
+    "vfredusum.vs",
+    "vfsub.vv",
+    "vfsub.vf",
+    "vfredosum.vs",
+    "vfmin.vv",
+    "vfmin.vf",
+    "vfredmin.vs",
+    "vfmax.vv",
+    "vfmax.vf",
+    "vfredmax.vs",
+    "vfsgnj.vv",
+    "vfsgnj.vf",
+    "vfsgnjn.vv",
+    "vfsgnjn.vf",
+    "vfsgnjx.vv",
+    "vfsgnjx.vf",
+    "vfslide1up.vf",
+    "vfslide1down.vf",
+    "vmfeq.vv",
+    "vmfeq.vf",
+    "vmfle.vv",
+    "vmfle.vf",
+    "vmflt.vv",
+    "vmflt.vf",
+    "vmfne.vv",
+    "vmfne.vf",
+    "vmfgt.vf",
+    "vmfge.vf",
+    "vfdiv.vv",
+    "vfdiv.vf",
+    "vfrdiv.vf",
+    "vfmul.vv",
+    "vfmul.vf",
+    "vfrsub.vf",
+    "vfmadd.vv",
+    "vfmadd.vf",
+    "vfnmadd.vv",
+    "vfnmadd.vf",
+    "vfmsub.vv",
+    "vfmsub.vf",
+    "vfnmsub.vv",
+    "vfnmsub.vf",
+    "vfmacc.vv",
+    "vfmacc.vf",
+    "vfnmacc.vv",
+    "vfnmacc.vf",
+    "vfmsac.vv",
+    "vfmsac.vf",
+    "vfnmsac.vv",
+    "vfnmsac.vf",
+    "vfwadd.vv",
+    "vfwadd.vf",
+    "vfwredusum.vs",
+    "vfwsub.vv",
+    "vfwsub.vf",
+    "vfwredosum.vs",
+    "vfwadd.wv",
+    "vfwadd.wf",
+    "vfwsub.wv",
+    "vfwsub.wf",
+    "vfwmul.vv",
+    "vfwmul.vf",
+    "vfwmacc.vv",
+    "vfwmacc.vf",
+    "vfwnmacc.vv",
+    "vfwnmacc.vf",
+    "vfwmsac.vv",
+    "vfwmsac.vf",
+    "vfwnmsac.vv",
+    "vfwnmsac.vf",
+    "vadc.vvm",
+    "vadc.vxm",
+    "vadc.vim",
+    "vmadc.vvm",
+    "vmadc.vxm",
+    "vmadc.vim",
+    "vmadc.vv",
+    "vmadc.vx",
+    "vmadc.vi",
+    "vsbc.vvm",
+    "vsbc.vxm",
+    "vmsbc.vvm",
+    "vmsbc.vxm",
+    "vmsbc.vv",
+    "vmsbc.vx",
+    "vmerge.vvm",
+    "vmerge.vxm",
+    "vmerge.vim",
+    "vmv.v.v",
+    "vmv.v.x",
+    "vmv.v.i",
+    "vmv1r.v",
+    "vmv2r.v",
+    "vmv4r.v",
+    "vmv8r.v",
+    "vmv.x.s",
+    "vmv.s.x",
+    "vcpop.m",
+    "vfirst.m",
+    "vzext.vf2",
+    "vsext.vf2",
+    "vzext.vf4",
+    "vsext.vf4",
+    "vzext.vf8",
+    "vsext.vf8",
+    "vmsbf.m",
+    "vmsof.m",
+    "vmsif.m",
+    "viota.m",
+    "vid.v",
+    "vfmerge.vfm",
+    "vfmv.v.f",
+    "vfmv.f.s",
+    "vfmv.s.f",
+    "vfcvt.xu.f.v",
+    "vfcvt.x.f.v",
+    "vfcvt.f.xu.v",
+    "vfcvt.f.x.v",
+    "vfcvt.rtz.xu.f.v",
+    "vfcvt.rtz.x.f.v",
+    "vfwcvt.xu.f.v",
+    "vfwcvt.x.f.v",
+    "vfwcvt.f.xu.v",
+    "vfwcvt.f.x.v",
+    "vfwcvt.f.f.v",
+    "vfwcvt.rtz.xu.f.v",
+    "vfwcvt.rtz.x.f.v",
+    "vfncvt.xu.f.w",
+    "vfncvt.x.f.w",
+    "vfncvt.f.xu.w",
+    "vfncvt.f.x.w",
+    "vfncvt.f.f.w",
+    "vfncvt.rod.f.f.w",
+    "vfncvt.rtz.xu.f.w",
+    "vfncvt.rtz.x.f.w",
+    "vfsqrt.v",
+    "vfrsqrt7.v",
+    "vfrec7.v",
+    "vfclass.v",
 ];
 
 impl Opcode {
@@ -727,6 +2281,10 @@ pub enum Operand {
     Nothing,
     Register(Register),
     Immediate(i32),
+    /// Vector type of `vsetvli` and `vsetivli`, e.g. `e32, m1, ta, ma`.
+    Vtype(u32),
+    /// The instruction only applies to elements enabled in `v0`.
+    Mask,
 }
 
 impl ToTokens for Operand {
@@ -743,6 +2301,18 @@ impl ToTokens for Operand {
                     None => stream.push_owned(imm.to_string(), Colors::immediate()),
                 }
             }
+            Self::Vtype(vtype) => match vector::vtype_fields(*vtype) {
+                Some(fields) => {
+                    for (idx, field) in fields.into_iter().enumerate() {
+                        if idx != 0 {
+                            stream.push(", ", Colors::expr());
+                        }
+                        stream.push(field, Colors::immediate());
+                    }
+                }
+                None => stream.push_owned(vtype.to_string(), Colors::immediate()),
+            },
+            Self::Mask => stream.push("v0.t", Colors::register()),
             Self::Nothing => unreachable!("empty operand encountered"),
         }
     }
@@ -751,9 +2321,70 @@ impl ToTokens for Operand {
 #[derive(Debug, Clone)]
 pub struct Instruction {
     opcode: Opcode,
-    operands: [Operand; 3],
+    operands: [Operand; 4],
     operand_count: usize,
     len: usize,
+    encoding: Encoding,
+}
+
+/// Where an instruction came from, so it can be shown without pseudo-instructions.
+#[derive(Debug, Default, Clone, Copy)]
+struct Encoding {
+    raw: u32,
+    is_64: bool,
+    addr: usize,
+}
+
+impl Instruction {
+    fn with_encoding(mut self, raw: u32, is_64: bool) -> Self {
+        self.encoding = Encoding { raw, is_64, addr: 0 };
+        self
+    }
+
+    /// Re-decode's the instruction as it's encoded, with offsets still relative to it.
+    fn encoded(&self) -> Option<Instruction> {
+        let bytes = self.encoding.raw.to_le_bytes();
+        let decoder = Decoder { is_64: self.encoding.is_64, ..Decoder::default() };
+        let mut reader = decoder::Reader::new(&bytes[..self.len]);
+        decode_canonical(&mut reader, &decoder).ok()
+    }
+
+    /// Re-decode's the instruction as it's encoded, without mapping to pseudo-instructions.
+    fn canonical(&self) -> Option<Instruction> {
+        let mut inst = self.encoded()?;
+        decoder::Decoded::update_rel_addrs(&mut inst, self.encoding.addr, None);
+        Some(inst)
+    }
+
+    /// Tokenize the instruction, either using pseudo-instructions or the canonical encoding.
+    pub fn tokenize_with(&self, stream: &mut TokenStream, symbols: &Index, pseudo: bool) {
+        if !pseudo {
+            if let Some(inst) = self.canonical() {
+                return inst.tokenize_operands(stream, symbols);
+            }
+        }
+
+        self.tokenize_operands(stream, symbols);
+    }
+
+    fn tokenize_operands(&self, stream: &mut TokenStream, symbols: &Index) {
+        stream.push(self.opcode.as_str(), Colors::opcode());
+
+        // there are operands
+        if self.operand_count > 0 {
+            stream.push(" ", Colors::spacing());
+
+            // iterate through operands
+            for idx in 0..self.operand_count {
+                self.operands[idx].tokenize(stream, symbols);
+
+                // separator
+                if idx != self.operand_count - 1 {
+                    stream.push(", ", Colors::expr());
+                }
+            }
+        }
+    }
 }
 
 impl decoder::Decoded for Instruction {
@@ -761,7 +2392,13 @@ impl decoder::Decoded for Instruction {
         self.len
     }
 
+    fn tokenize_in(&self, stream: &mut TokenStream, symbols: &Index, dialect: decoder::Dialect) {
+        self.tokenize_with(stream, symbols, dialect.pseudo)
+    }
+
     fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+        self.encoding.addr = addr;
+
         if !self.opcode.is_relative() {
             return;
         }
@@ -774,17 +2411,49 @@ impl decoder::Decoded for Instruction {
             }
         }
     }
+
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
+    }
+
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
+
+    fn regs_read(&self) -> Vec<&'static str> {
+        behavior::registers(self).0
+    }
+
+    fn regs_written(&self) -> Vec<&'static str> {
+        behavior::registers(self).1
+    }
+
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
+
+    fn lift(&self, addr: usize, builder: &mut decoder::ir::Builder) {
+        lift::lift(self, addr, builder)
+    }
 }
 
+#[derive(Default)]
 pub struct Decoder {
     pub is_64: bool,
+    /// Extensions to decode, instructions of any other extension fail to decode.
+    pub extensions: Extensions,
 }
 
 impl decoder::Decodable for Decoder {
     type Instruction = Instruction;
 
     fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
-        decode(reader, self).map_err(|err| Error::new(err, 4))
+        let inst = decode_canonical(reader, self).map_err(|err| Error::new(err, 4))?;
+        if !self.extensions.allows(inst.opcode) {
+            return Err(Error::new(ErrorKind::DisabledExtension, inst.len));
+        }
+
+        Ok(map_to_psuedo(inst))
     }
 
     fn max_width(&self) -> usize {
@@ -792,7 +2461,11 @@ impl decoder::Decodable for Decoder {
     }
 }
 
-fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+/// Decode's an instruction without mapping it to a pseudo-instruction.
+fn decode_canonical(
+    reader: &mut decoder::Reader,
+    decoder: &Decoder,
+) -> Result<Instruction, ErrorKind> {
     use Opcode::*;
 
     let is_64 = decoder.is_64;
@@ -867,7 +2540,7 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
             _ => Err(ErrorKind::InvalidOpcode),
         };
 
-        return decoded_inst.map(map_to_psuedo);
+        return decoded_inst.map(|inst| inst.with_encoding(bytes as u32, is_64));
     }
 
     let mut word2 = [0u8; 2];
@@ -878,7 +2551,33 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
     let decoded_inst = match opcode {
         _ if dword == 0b000000000000_00000_000_00000_1110011 => decode_unique(ECALL),
         _ if dword == 0b000000000001_00000_000_00000_1110011 => decode_unique(EBREAK),
-        0b0001111 => decode_unique(FENCE),
+        _ if dword == 0b000100000010_00000_000_00000_1110011 => decode_unique(SRET),
+        _ if dword == 0b001100000010_00000_000_00000_1110011 => decode_unique(MRET),
+        _ if dword == 0b000100000101_00000_000_00000_1110011 => decode_unique(WFI),
+        0b1110011 => match dword >> 12 & 0b111 {
+            0b000 if dword >> 25 == 0b0001001 && dword >> 7 & 0b11111 == 0 => {
+                decode_pair(SFENCE_VMA, dword)
+            }
+            0b001 => decode_csr(CSRRW, dword),
+            0b010 => decode_csr(CSRRS, dword),
+            0b011 => decode_csr(CSRRC, dword),
+            0b101 => decode_csr(CSRRWI, dword),
+            0b110 => decode_csr(CSRRSI, dword),
+            0b111 => decode_csr(CSRRCI, dword),
+            _ => Err(ErrorKind::InvalidOpcode),
+        },
+        0b0001111 => match dword >> 12 & 0b111 {
+            0b000 => decode_unique(FENCE),
+            0b001 => decode_unique(FENCE_I),
+            0b010 if dword >> 7 & 0b11111 == 0 => match dword >> 20 {
+                0b000 => decode_single(CBO_INVAL, dword),
+                0b001 => decode_single(CBO_CLEAN, dword),
+                0b010 => decode_single(CBO_FLUSH, dword),
+                0b100 => decode_single(CBO_ZERO, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            _ => Err(ErrorKind::InvalidOpcode),
+        },
         0b0110111 => decode_double(LUI, dword),
         0b0010111 => decode_double(AUIPC, dword),
         0b1101111 => decode_jump(dword),
@@ -909,6 +2608,20 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
             0b011 if is_64 => decode_store(SD, dword),
             _ => Err(ErrorKind::InvalidOpcode),
         },
+        0b0000111 => match dword >> 12 & 0b111 {
+            0b001 => decode_float_load(FLH, dword),
+            0b010 => decode_float_load(FLW, dword),
+            0b011 => decode_float_load(FLD, dword),
+            0b100 => decode_float_load(FLQ, dword),
+            _ => vector::decode_memory(dword, false),
+        },
+        0b0100111 => match dword >> 12 & 0b111 {
+            0b001 => decode_float_store(FSH, dword),
+            0b010 => decode_float_store(FSW, dword),
+            0b011 => decode_float_store(FSD, dword),
+            0b100 => decode_float_store(FSQ, dword),
+            _ => vector::decode_memory(dword, true),
+        },
         0b0010011 => match dword >> 12 & 0b111 {
             0b000 => decode_immediate(ADDI, dword),
             0b010 => decode_immediate(SLTI, dword),
@@ -916,85 +2629,180 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
             0b100 => decode_immediate(XORI, dword),
             0b110 => decode_immediate(ORI, dword),
             0b111 => decode_immediate(ANDI, dword),
-            0b001 => decode_arith(SLLI, dword, decoder),
-            0b101 if dword >> 26 == 0b0000001 => decode_arith(SRAI, dword, decoder),
-            0b101 if dword >> 26 == 0b0000000 => decode_arith(SRLI, dword, decoder),
+            0b001 => match dword >> 20 {
+                0x600 => decode_single_source(CLZ, dword),
+                0x601 => decode_single_source(CTZ, dword),
+                0x602 => decode_single_source(CPOP, dword),
+                0x604 => decode_single_source(SEXT_B, dword),
+                0x605 => decode_single_source(SEXT_H, dword),
+                0x100 => decode_single_source(SHA256SUM0, dword),
+                0x101 => decode_single_source(SHA256SUM1, dword),
+                0x102 => decode_single_source(SHA256SIG0, dword),
+                0x103 => decode_single_source(SHA256SIG1, dword),
+                0x104 if is_64 => decode_single_source(SHA512SUM0, dword),
+                0x105 if is_64 => decode_single_source(SHA512SUM1, dword),
+                0x106 if is_64 => decode_single_source(SHA512SIG0, dword),
+                0x107 if is_64 => decode_single_source(SHA512SIG1, dword),
+                0x108 => decode_single_source(SM3P0, dword),
+                0x109 => decode_single_source(SM3P1, dword),
+                0x300 if is_64 => decode_single_source(AES64IM, dword),
+                0x08f if !is_64 => decode_single_source(ZIP, dword),
+                imm if is_64 && imm >> 4 == 0x31 => decode_key_schedule(dword),
+                _ => match dword >> 26 {
+                    0b000000 => decode_arith(SLLI, dword, decoder),
+                    0b001010 => decode_arith(BSETI, dword, decoder),
+                    0b010010 => decode_arith(BCLRI, dword, decoder),
+                    0b011010 => decode_arith(BINVI, dword, decoder),
+                    _ => Err(ErrorKind::InvalidOpcode),
+                },
+            },
+            0b101 => match dword >> 20 {
+                0x287 => decode_single_source(ORC_B, dword),
+                0x687 => decode_single_source(BREV8, dword),
+                0x698 if !is_64 => decode_single_source(REV8, dword),
+                0x6b8 if is_64 => decode_single_source(REV8, dword),
+                0x08f if !is_64 => decode_single_source(UNZIP, dword),
+                _ => match dword >> 26 {
+                    0b000000 => decode_arith(SRLI, dword, decoder),
+                    0b010000 => decode_arith(SRAI, dword, decoder),
+                    0b011000 => decode_arith(RORI, dword, decoder),
+                    0b010010 => decode_arith(BEXTI, dword, decoder),
+                    _ => Err(ErrorKind::InvalidOpcode),
+                },
+            },
             _ => Err(ErrorKind::InvalidOpcode),
         },
         0b0011011 => match dword >> 12 & 0b111 {
             _ if !is_64 => Err(ErrorKind::InvalidOpcode),
             0b000 => decode_immediate(ADDIW, dword),
-            0b001 => decode_arith(SLLIW, dword, decoder),
+            0b001 => match dword >> 20 {
+                0x600 => decode_single_source(CLZW, dword),
+                0x601 => decode_single_source(CTZW, dword),
+                0x602 => decode_single_source(CPOPW, dword),
+                _ if dword >> 25 == 0b0000000 => decode_arith(SLLIW, dword, decoder),
+                _ if dword >> 26 == 0b000010 => decode_arith(SLLI_UW, dword, decoder),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
             0b101 if dword >> 25 == 0b0000000 => decode_arith(SRLIW, dword, decoder),
             0b101 if dword >> 25 == 0b0100000 => decode_arith(SRAIW, dword, decoder),
+            0b101 if dword >> 25 == 0b0110000 => decode_arith(RORIW, dword, decoder),
             _ => Err(ErrorKind::InvalidOpcode),
         },
-        0b0110011 => match dword >> 25 {
-            0b0000000 => match dword >> 12 & 0b111 {
-                0b000 => decode_triplet(ADD, dword),
-                0b001 => decode_triplet(SLL, dword),
-                0b010 => decode_triplet(SLT, dword),
-                0b011 => decode_triplet(SLTU, dword),
-                0b100 => decode_triplet(XOR, dword),
-                0b101 => decode_triplet(SRL, dword),
-                0b110 => decode_triplet(OR, dword),
-                0b111 => decode_triplet(AND, dword),
-                _ => Err(ErrorKind::InvalidOpcode),
-            },
-            0b0100000 => match dword >> 12 & 0b111 {
-                0b000 => decode_triplet(SUB, dword),
-                0b101 => decode_triplet(SRA, dword),
+        0b0110011 => match (dword >> 25, dword >> 12 & 0b111) {
+            (0b0000000, 0b000) => decode_triplet(ADD, dword),
+            (0b0000000, 0b001) => decode_triplet(SLL, dword),
+            (0b0000000, 0b010) => decode_triplet(SLT, dword),
+            (0b0000000, 0b011) => decode_triplet(SLTU, dword),
+            (0b0000000, 0b100) => decode_triplet(XOR, dword),
+            (0b0000000, 0b101) => decode_triplet(SRL, dword),
+            (0b0000000, 0b110) => decode_triplet(OR, dword),
+            (0b0000000, 0b111) => decode_triplet(AND, dword),
+            (0b0100000, 0b000) => decode_triplet(SUB, dword),
+            (0b0100000, 0b101) => decode_triplet(SRA, dword),
+            (0b0100000, 0b100) => decode_triplet(XNOR, dword),
+            (0b0100000, 0b110) => decode_triplet(ORN, dword),
+            (0b0100000, 0b111) => decode_triplet(ANDN, dword),
+            (0b0000001, 0b000) => decode_triplet(MUL, dword),
+            (0b0000001, 0b001) => decode_triplet(MULH, dword),
+            (0b0000001, 0b010) => decode_triplet(MULHSU, dword),
+            (0b0000001, 0b011) => decode_triplet(MULHU, dword),
+            (0b0000001, 0b100) => decode_triplet(DIV, dword),
+            (0b0000001, 0b101) => decode_triplet(DIVU, dword),
+            (0b0000001, 0b110) => decode_triplet(REM, dword),
+            (0b0000001, 0b111) => decode_triplet(REMU, dword),
+            (0b0010000, 0b010) => decode_triplet(SH1ADD, dword),
+            (0b0010000, 0b100) => decode_triplet(SH2ADD, dword),
+            (0b0010000, 0b110) => decode_triplet(SH3ADD, dword),
+            (0b0000101, 0b001) => decode_triplet(CLMUL, dword),
+            (0b0000101, 0b010) => decode_triplet(CLMULR, dword),
+            (0b0000101, 0b011) => decode_triplet(CLMULH, dword),
+            (0b0000101, 0b100) => decode_triplet(MIN, dword),
+            (0b0000101, 0b101) => decode_triplet(MINU, dword),
+            (0b0000101, 0b110) => decode_triplet(MAX, dword),
+            (0b0000101, 0b111) => decode_triplet(MAXU, dword),
+            (0b0000100, 0b100) if !is_64 && dword >> 20 & 0b11111 == 0 => {
+                decode_single_source(ZEXT_H, dword)
+            }
+            (0b0000100, 0b100) => decode_triplet(PACK, dword),
+            (0b0000100, 0b111) => decode_triplet(PACKH, dword),
+            (0b0000111, 0b101) => decode_triplet(CZERO_EQZ, dword),
+            (0b0000111, 0b111) => decode_triplet(CZERO_NEZ, dword),
+            (0b0110000, 0b001) => decode_triplet(ROL, dword),
+            (0b0110000, 0b101) => decode_triplet(ROR, dword),
+            (0b0100100, 0b001) => decode_triplet(BCLR, dword),
+            (0b0100100, 0b101) => decode_triplet(BEXT, dword),
+            (0b0110100, 0b001) => decode_triplet(BINV, dword),
+            (0b0010100, 0b001) => decode_triplet(BSET, dword),
+            (0b0010100, 0b010) => decode_triplet(XPERM4, dword),
+            (0b0010100, 0b100) => decode_triplet(XPERM8, dword),
+            (0b0011001, 0b000) if is_64 => decode_triplet(AES64ES, dword),
+            (0b0011011, 0b000) if is_64 => decode_triplet(AES64ESM, dword),
+            (0b0011101, 0b000) if is_64 => decode_triplet(AES64DS, dword),
+            (0b0011111, 0b000) if is_64 => decode_triplet(AES64DSM, dword),
+            (0b0111111, 0b000) if is_64 => decode_triplet(AES64KS2, dword),
+            (0b0101000, 0b000) if !is_64 => decode_triplet(SHA512SUM0R, dword),
+            (0b0101001, 0b000) if !is_64 => decode_triplet(SHA512SUM1R, dword),
+            (0b0101010, 0b000) if !is_64 => decode_triplet(SHA512SIG0L, dword),
+            (0b0101011, 0b000) if !is_64 => decode_triplet(SHA512SIG1L, dword),
+            (0b0101110, 0b000) if !is_64 => decode_triplet(SHA512SIG0H, dword),
+            (0b0101111, 0b000) if !is_64 => decode_triplet(SHA512SIG1H, dword),
+            // the top two bits select a byte of rs2
+            (funct7, 0b000) => match funct7 & 0b11111 {
+                0b10001 if !is_64 => decode_byte_select(AES32ESI, dword),
+                0b10011 if !is_64 => decode_byte_select(AES32ESMI, dword),
+                0b10101 if !is_64 => decode_byte_select(AES32DSI, dword),
+                0b10111 if !is_64 => decode_byte_select(AES32DSMI, dword),
+                0b11000 => decode_byte_select(SM4ED, dword),
+                0b11010 => decode_byte_select(SM4KS, dword),
                 _ => Err(ErrorKind::InvalidOpcode),
             },
             _ => Err(ErrorKind::InvalidOpcode),
         },
-        0b0111011 => match dword >> 25 {
+        0b0111011 => match (dword >> 25, dword >> 12 & 0b111) {
             _ if !is_64 => Err(ErrorKind::InvalidOpcode),
-            0b0000000 => match dword >> 12 & 0b111 {
-                0b000 => decode_triplet(ADDW, dword),
-                0b001 => decode_triplet(SLLW, dword),
-                0b101 => decode_triplet(SRLW, dword),
-                _ => Err(ErrorKind::InvalidOpcode),
-            },
-            0b0100000 => match dword >> 12 & 0b111 {
-                0b000 => decode_triplet(SUBW, dword),
-                0b101 => decode_triplet(SRAW, dword),
-                _ => Err(ErrorKind::InvalidOpcode),
-            },
+            (0b0000000, 0b000) => decode_triplet(ADDW, dword),
+            (0b0000000, 0b001) => decode_triplet(SLLW, dword),
+            (0b0000000, 0b101) => decode_triplet(SRLW, dword),
+            (0b0100000, 0b000) => decode_triplet(SUBW, dword),
+            (0b0100000, 0b101) => decode_triplet(SRAW, dword),
+            (0b0000001, 0b000) => decode_triplet(MULW, dword),
+            (0b0000001, 0b100) => decode_triplet(DIVW, dword),
+            (0b0000001, 0b101) => decode_triplet(DIVUW, dword),
+            (0b0000001, 0b110) => decode_triplet(REMW, dword),
+            (0b0000001, 0b111) => decode_triplet(REMUW, dword),
+            (0b0000100, 0b000) => decode_triplet(ADD_UW, dword),
+            (0b0000100, 0b100) if dword >> 20 & 0b11111 == 0 => {
+                decode_single_source(ZEXT_H, dword)
+            }
+            (0b0000100, 0b100) => decode_triplet(PACKW, dword),
+            (0b0010000, 0b010) => decode_triplet(SH1ADD_UW, dword),
+            (0b0010000, 0b100) => decode_triplet(SH2ADD_UW, dword),
+            (0b0010000, 0b110) => decode_triplet(SH3ADD_UW, dword),
+            (0b0110000, 0b001) => decode_triplet(ROLW, dword),
+            (0b0110000, 0b101) => decode_triplet(RORW, dword),
             _ => Err(ErrorKind::InvalidOpcode),
         },
+        0b0101111 => decode_atomic(dword, decoder),
+        0b1000011 | 0b1000111 | 0b1001011 | 0b1001111 => decode_fused(dword),
+        0b1010011 => decode_float(dword, decoder),
+        0b1010111 => vector::decode_arith(dword),
         _ => Err(ErrorKind::InvalidOpcode),
     };
 
-    decoded_inst.map(map_to_psuedo)
+    decoded_inst.map(|inst| inst.with_encoding(dword, is_64))
 }
 
 impl ToTokens for Instruction {
     fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
-        stream.push(self.opcode.as_str(), Colors::opcode());
-
-        // there are operands
-        if self.operand_count > 0 {
-            stream.push(" ", Colors::spacing());
-
-            // iterate through operands
-            for idx in 0..self.operand_count {
-                self.operands[idx].tokenize(stream, symbols);
-
-                // separator
-                if idx != self.operand_count - 1 {
-                    stream.push(", ", Colors::expr());
-                }
-            }
-        }
+        self.tokenize_with(stream, symbols, decoder::Dialect::default().pseudo)
     }
 }
 
 // NOTE: doing closure assignment in `map_to_psuedo` makes the compiler
 // assign function mappings in the array on each call.
-static MAPPING: Lazy<[fn(&mut Instruction); 284]> = Lazy::new(|| unsafe {
+static MAPPING: Lazy<[fn(&mut Instruction); 1037]> = Lazy::new(|| unsafe {
     const DO_NOTHING: fn(&mut Instruction) = |_| {};
-    static mut MAPPING: [fn(&mut Instruction); 284] = [DO_NOTHING; 284];
+    static mut MAPPING: [fn(&mut Instruction); 1037] = [DO_NOTHING; 1037];
 
     MAPPING[Opcode::C_ADDI as usize] = |inst| {
         if inst.operands[0] == Operand::Register(Register::Zero)
@@ -1593,6 +3401,7 @@ fn decode_comp_branch(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKin
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1623,6 +3432,7 @@ fn decode_comp_jump(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1641,6 +3451,7 @@ fn decode_comp_jumpr(word: u16) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1660,6 +3471,7 @@ fn decode_comp_arith(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1679,6 +3491,7 @@ fn decode_comp_shift(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1702,6 +3515,7 @@ fn decode_comp_addi(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1728,6 +3542,7 @@ fn decode_addi16sp(word: u16) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1749,6 +3564,7 @@ fn decode_addi4spn(word: u16) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1768,6 +3584,7 @@ fn decode_comp_add(word: u16) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1788,6 +3605,7 @@ fn decode_comp_li(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1804,6 +3622,7 @@ fn decode_comp_swsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1820,6 +3639,7 @@ fn decode_comp_sdsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1836,6 +3656,7 @@ fn decode_comp_lwsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 4,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1852,6 +3673,7 @@ fn decode_comp_ldsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1872,6 +3694,7 @@ fn decode_comp_slw(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1892,6 +3715,7 @@ fn decode_comp_sld(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1912,6 +3736,7 @@ fn decode_comp_fslw(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1932,6 +3757,7 @@ fn decode_comp_fsld(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1947,6 +3773,7 @@ fn decode_comp_mv(word: u16) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1959,6 +3786,7 @@ fn decode_comp_unique(opcode: Opcode) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 2,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1971,6 +3799,7 @@ fn decode_unique(opcode: Opcode) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 4,
+        encoding: Encoding::default(),
     })
 }
 
@@ -1996,6 +3825,7 @@ fn decode_store(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 4,
+        encoding: Encoding::default(),
     })
 }
 
@@ -2023,6 +3853,7 @@ fn decode_branch(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 4,
+        encoding: Encoding::default(),
     })
 }
 
@@ -2045,6 +3876,7 @@ fn decode_jump(dword: u32) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 4,
+        encoding: Encoding::default(),
     })
 }
 
@@ -2059,6 +3891,7 @@ fn decode_jumpr(bytes: u32) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 4,
+        encoding: Encoding::default(),
     })
 }
 
@@ -2079,6 +3912,7 @@ fn decode_immediate(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind
         operands,
         operand_count,
         len: 4,
+        encoding: Encoding::default(),
     })
 }
 
@@ -2104,6 +3938,7 @@ fn decode_arith(opcode: Opcode, dword: u32, opts: &Decoder) -> Result<Instructio
         operands,
         operand_count,
         len: 4,
+        encoding: Encoding::default(),
     })
 }
 
@@ -2124,6 +3959,7 @@ fn decode_triplet(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind>
         operands,
         operand_count,
         len: 4,
+        encoding: Encoding::default(),
     })
 }
 
@@ -2140,5 +3976,382 @@ fn decode_double(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
         operands,
         operand_count,
         len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Decode's instructions that only read two registers.
+fn decode_pair(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    let rs1 = Register::get(dword >> 15 & 0b11111)?;
+    let rs2 = Register::get(dword >> 20 & 0b11111)?;
+
+    let (operands, operand_count) = operands![Operand::Register(rs1), Operand::Register(rs2)];
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Decode's instructions that only read a single register, such as the cbo family.
+fn decode_single(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    let rs1 = Register::get(dword >> 15 & 0b11111)?;
+
+    let (operands, operand_count) = operands![Operand::Register(rs1)];
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Decode's instructions that have a destination and a single source register.
+fn decode_single_source(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    let rd = Register::get(dword >> 7 & 0b11111)?;
+    let rs1 = Register::get(dword >> 15 & 0b11111)?;
+
+    let (operands, operand_count) = operands![Operand::Register(rd), Operand::Register(rs1)];
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Decode's csrrw, csrrs, csrrc and their immediate variants.
+fn decode_csr(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    let rd = Register::get(dword >> 7 & 0b11111)?;
+    let csr = Operand::Immediate((dword >> 20) as i32);
+
+    // the immediate variants encode a 5-bit unsigned immediate in place of rs1
+    let source = match opcode {
+        Opcode::CSRRWI | Opcode::CSRRSI | Opcode::CSRRCI => {
+            Operand::Immediate((dword >> 15 & 0b11111) as i32)
+        }
+        _ => Operand::Register(Register::get(dword >> 15 & 0b11111)?),
+    };
+
+    let (operands, operand_count) = operands![Operand::Register(rd), csr, source];
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Decode's aes64ks1i, which has a round number in place of an immediate.
+fn decode_key_schedule(dword: u32) -> Result<Instruction, ErrorKind> {
+    let rd = Register::get(dword >> 7 & 0b11111)?;
+    let rs1 = Register::get(dword >> 15 & 0b11111)?;
+    let rnum = dword >> 20 & 0b1111;
+
+    // round numbers above 10 are reserved
+    if rnum > 0xa {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
+    let (operands, operand_count) = operands![
+        Operand::Register(rd),
+        Operand::Register(rs1),
+        Operand::Immediate(rnum as i32),
+    ];
+
+    Ok(Instruction {
+        opcode: Opcode::AES64KS1I,
+        operands,
+        operand_count,
+        len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Decode's aes32 and sm4 instructions, which operate on a single byte of rs2.
+fn decode_byte_select(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    let rd = Register::get(dword >> 7 & 0b11111)?;
+    let rs1 = Register::get(dword >> 15 & 0b11111)?;
+    let rs2 = Register::get(dword >> 20 & 0b11111)?;
+    let bs = dword >> 30;
+
+    let (operands, operand_count) = operands![
+        Operand::Register(rd),
+        Operand::Register(rs1),
+        Operand::Register(rs2),
+        Operand::Immediate(bs as i32),
+    ];
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Decode's lr, sc and amo instructions.
+fn decode_atomic(dword: u32, opts: &Decoder) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let rd = Register::get(dword >> 7 & 0b11111)?;
+    let rs1 = Register::get(dword >> 15 & 0b11111)?;
+    let rs2 = Register::get(dword >> 20 & 0b11111)?;
+
+    let (word, dword_op) = match dword >> 27 {
+        0b00010 => (LR_W, LR_D),
+        0b00011 => (SC_W, SC_D),
+        0b00001 => (AMOSWAP_W, AMOSWAP_D),
+        0b00000 => (AMOADD_W, AMOADD_D),
+        0b00100 => (AMOXOR_W, AMOXOR_D),
+        0b01100 => (AMOAND_W, AMOAND_D),
+        0b01000 => (AMOOR_W, AMOOR_D),
+        0b10000 => (AMOMIN_W, AMOMIN_D),
+        0b10100 => (AMOMAX_W, AMOMAX_D),
+        0b11000 => (AMOMINU_W, AMOMINU_D),
+        0b11100 => (AMOMAXU_W, AMOMAXU_D),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    let opcode = match dword >> 12 & 0b111 {
+        0b010 => word,
+        0b011 if opts.is_64 => dword_op,
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    let (operands, operand_count) = match opcode {
+        LR_W | LR_D if rs2 == Register::Zero => {
+            operands![Operand::Register(rd), Operand::Register(rs1)]
+        }
+        LR_W | LR_D => return Err(ErrorKind::InvalidOperand),
+        _ => operands![
+            Operand::Register(rd),
+            Operand::Register(rs2),
+            Operand::Register(rs1),
+        ],
+    };
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Opcodes of a single floating-point format.
+struct Format {
+    /// fmadd, fmsub, fnmsub and fnmadd.
+    fused: [Opcode; 4],
+    /// fadd, fsub, fmul and fdiv.
+    arith: [Opcode; 4],
+    sqrt: Opcode,
+    /// fsgnj, fsgnjn and fsgnjx.
+    sign: [Opcode; 3],
+    /// fmin and fmax.
+    min_max: [Opcode; 2],
+    /// fle, flt and feq.
+    compare: [Opcode; 3],
+    /// Conversions to a w, wu, l and lu integer.
+    to_int: [Opcode; 4],
+    /// Conversions from a w, wu, l and lu integer.
+    from_int: [Opcode; 4],
+    move_to_int: Opcode,
+    move_from_int: Opcode,
+    class: Opcode,
+    /// Conversions from each other format, indexed by their fmt field.
+    convert: [Opcode; 4],
+}
+
+/// Floating-point formats, indexed by the fmt field of an instruction.
+#[rustfmt::skip]
+static FORMATS: [Format; 4] = {
+    use Opcode::*;
+
+    [
+        Format {
+            fused: [FMADD_S, FMSUB_S, FNMSUB_S, FNMADD_S],
+            arith: [FADD_S, FSUB_S, FMUL_S, FDIV_S],
+            sqrt: FSQRT_S,
+            sign: [FSGNJ_S, FSGNJN_S, FSGNJX_S],
+            min_max: [FMIN_S, FMAX_S],
+            compare: [FLE_S, FLT_S, FEQ_S],
+            to_int: [FCVT_W_S, FCVT_WU_S, FCVT_L_S, FCVT_LU_S],
+            from_int: [FCVT_S_W, FCVT_S_WU, FCVT_S_L, FCVT_S_LU],
+            move_to_int: FMV_X_W,
+            move_from_int: FMV_W_X,
+            class: FCLASS_S,
+            convert: [Invalid, FCVT_S_D, FCVT_S_H, FCVT_S_Q],
+        },
+        Format {
+            fused: [FMADD_D, FMSUB_D, FNMSUB_D, FNMADD_D],
+            arith: [FADD_D, FSUB_D, FMUL_D, FDIV_D],
+            sqrt: FSQRT_D,
+            sign: [FSGNJ_D, FSGNJN_D, FSGNJX_D],
+            min_max: [FMIN_D, FMAX_D],
+            compare: [FLE_D, FLT_D, FEQ_D],
+            to_int: [FCVT_W_D, FCVT_WU_D, FCVT_L_D, FCVT_LU_D],
+            from_int: [FCVT_D_W, FCVT_D_WU, FCVT_D_L, FCVT_D_LU],
+            move_to_int: FMV_X_D,
+            move_from_int: FMV_D_X,
+            class: FCLASS_D,
+            convert: [FCVT_D_S, Invalid, FCVT_D_H, FCVT_D_Q],
+        },
+        Format {
+            fused: [FMADD_H, FMSUB_H, FNMSUB_H, FNMADD_H],
+            arith: [FADD_H, FSUB_H, FMUL_H, FDIV_H],
+            sqrt: FSQRT_H,
+            sign: [FSGNJ_H, FSGNJN_H, FSGNJX_H],
+            min_max: [FMIN_H, FMAX_H],
+            compare: [FLE_H, FLT_H, FEQ_H],
+            to_int: [FCVT_W_H, FCVT_WU_H, FCVT_L_H, FCVT_LU_H],
+            from_int: [FCVT_H_W, FCVT_H_WU, FCVT_H_L, FCVT_H_LU],
+            move_to_int: FMV_X_H,
+            move_from_int: FMV_H_X,
+            class: FCLASS_H,
+            convert: [FCVT_H_S, FCVT_H_D, Invalid, FCVT_H_Q],
+        },
+        Format {
+            fused: [FMADD_Q, FMSUB_Q, FNMSUB_Q, FNMADD_Q],
+            arith: [FADD_Q, FSUB_Q, FMUL_Q, FDIV_Q],
+            sqrt: FSQRT_Q,
+            sign: [FSGNJ_Q, FSGNJN_Q, FSGNJX_Q],
+            min_max: [FMIN_Q, FMAX_Q],
+            compare: [FLE_Q, FLT_Q, FEQ_Q],
+            to_int: [FCVT_W_Q, FCVT_WU_Q, FCVT_L_Q, FCVT_LU_Q],
+            from_int: [FCVT_Q_W, FCVT_Q_WU, FCVT_Q_L, FCVT_Q_LU],
+            move_to_int: Invalid,
+            move_from_int: Invalid,
+            class: FCLASS_Q,
+            convert: [FCVT_Q_S, FCVT_Q_D, FCVT_Q_H, Invalid],
+        },
+    ]
+};
+
+/// Decode's flh, flw, fld and flq.
+fn decode_float_load(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    let rd = Register::get_float(dword >> 7 & 0b11111)?;
+    let rs = Register::get(dword >> 15 & 0b11111)?;
+    let imm = dword as i32 >> 20;
+
+    let (operands, operand_count) = operands![
+        Operand::Register(rd),
+        Operand::Register(rs),
+        Operand::Immediate(imm),
+    ];
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Decode's fsh, fsw, fsd and fsq.
+fn decode_float_store(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    let mut inst = decode_store(opcode, dword)?;
+    inst.operands[0] = Operand::Register(Register::get_float(dword >> 20 & 0b11111)?);
+    Ok(inst)
+}
+
+/// Decode's fmadd, fmsub, fnmsub and fnmadd.
+fn decode_fused(dword: u32) -> Result<Instruction, ErrorKind> {
+    let format = &FORMATS[(dword >> 25 & 0b11) as usize];
+    let opcode = format.fused[(dword >> 2 & 0b11) as usize];
+
+    let (operands, operand_count) = operands![
+        Operand::Register(Register::get_float(dword >> 7 & 0b11111)?),
+        Operand::Register(Register::get_float(dword >> 15 & 0b11111)?),
+        Operand::Register(Register::get_float(dword >> 20 & 0b11111)?),
+        Operand::Register(Register::get_float(dword >> 27)?),
+    ];
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Decode's instructions in the OP-FP opcode.
+fn decode_float(dword: u32, opts: &Decoder) -> Result<Instruction, ErrorKind> {
+    let format = &FORMATS[(dword >> 25 & 0b11) as usize];
+    let funct3 = (dword >> 12 & 0b111) as usize;
+    let rd = dword >> 7 & 0b11111;
+    let rs1 = dword >> 15 & 0b11111;
+    let rs2 = dword >> 20 & 0b11111;
+
+    let float = |num| Register::get_float(num).map(Operand::Register);
+    let int = |num| Register::get(num).map(Operand::Register);
+
+    let (opcode, (operands, operand_count)) = match dword >> 27 {
+        funct5 @ 0b00000..=0b00011 => (
+            format.arith[funct5 as usize],
+            operands![float(rd)?, float(rs1)?, float(rs2)?],
+        ),
+        0b01011 if rs2 == 0 => (format.sqrt, operands![float(rd)?, float(rs1)?]),
+        0b00100 if funct3 < 3 => (
+            format.sign[funct3],
+            operands![float(rd)?, float(rs1)?, float(rs2)?],
+        ),
+        0b00101 if funct3 < 2 => (
+            format.min_max[funct3],
+            operands![float(rd)?, float(rs1)?, float(rs2)?],
+        ),
+        0b10100 if funct3 < 3 => (
+            format.compare[funct3],
+            operands![int(rd)?, float(rs1)?, float(rs2)?],
+        ),
+        // conversions to and from l and lu are only on rv64
+        0b11000 if rs2 < 2 || (rs2 < 4 && opts.is_64) => (
+            format.to_int[rs2 as usize],
+            operands![int(rd)?, float(rs1)?],
+        ),
+        0b11010 if rs2 < 2 || (rs2 < 4 && opts.is_64) => (
+            format.from_int[rs2 as usize],
+            operands![float(rd)?, int(rs1)?],
+        ),
+        0b01000 if rs2 < 4 => (
+            format.convert[rs2 as usize],
+            operands![float(rd)?, float(rs1)?],
+        ),
+        0b11100 if rs2 == 0 && funct3 == 0b001 => (format.class, operands![int(rd)?, float(rs1)?]),
+        // fmv.x.d and fmv.d.x are only on rv64
+        0b11100 if rs2 == 0 && funct3 == 0b000 => match format.move_to_int {
+            Opcode::FMV_X_D if !opts.is_64 => return Err(ErrorKind::InvalidOpcode),
+            opcode => (opcode, operands![int(rd)?, float(rs1)?]),
+        },
+        0b11110 if rs2 == 0 && funct3 == 0b000 => match format.move_from_int {
+            Opcode::FMV_D_X if !opts.is_64 => return Err(ErrorKind::InvalidOpcode),
+            opcode => (opcode, operands![float(rd)?, int(rs1)?]),
+        },
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    if opcode == Opcode::Invalid {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+        encoding: Encoding::default(),
     })
 }
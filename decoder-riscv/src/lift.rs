@@ -0,0 +1,563 @@
+//! Lifting of instructions to the architecture neutral [`ir`](decoder::ir).
+//!
+//! Instructions are lifted as they're encoded rather than as the pseudo-instruction they're
+//! shown as. Registers are as wide as the base ISA, reading `zero` results in a constant and
+//! writes to it are dropped. Floating-point, vector and CSR instructions are unsupported.
+
+use crate::{Instruction, Opcode, Operand, Register};
+use decoder::ir::{BinOp, Builder, Cmp, UnOp, Value};
+
+pub(crate) fn lift(inst: &Instruction, addr: usize, builder: &mut Builder) {
+    builder.attempt(|b| {
+        let inst = inst.encoded()?;
+        let xlen = if inst.encoding.is_64 { 64 } else { 32 };
+        Lifter {
+            inst: &inst,
+            b,
+            addr: addr as u64,
+            xlen,
+        }
+        .lift()
+    });
+}
+
+fn imm(value: u64, bits: u8) -> Value {
+    Value::constant(value, bits)
+}
+
+/// Width in bits and whether a load sign extends the value.
+fn load(opcode: Opcode) -> Option<(u8, bool)> {
+    let load = match opcode {
+        Opcode::LB => (8, true),
+        Opcode::LBU => (8, false),
+        Opcode::LH => (16, true),
+        Opcode::LHU => (16, false),
+        Opcode::LW | Opcode::C_LW | Opcode::C_LWSP | Opcode::LR_W => (32, true),
+        Opcode::LWU => (32, false),
+        Opcode::LD | Opcode::C_LD | Opcode::C_LDSP | Opcode::LR_D => (64, false),
+        _ => return None,
+    };
+
+    Some(load)
+}
+
+/// Width in bits of a store.
+fn store(opcode: Opcode) -> Option<u8> {
+    let bits = match opcode {
+        Opcode::SB => 8,
+        Opcode::SH => 16,
+        Opcode::SW | Opcode::C_SW | Opcode::C_SWSP | Opcode::SC_W => 32,
+        Opcode::SD | Opcode::C_SD | Opcode::C_SDSP | Opcode::SC_D => 64,
+        _ => return None,
+    };
+
+    Some(bits)
+}
+
+/// How an atomic memory operation combines the value in memory with the register.
+#[derive(Debug, Clone, Copy)]
+enum Amo {
+    Swap,
+    Op(BinOp),
+    Min(Cmp),
+    Max(Cmp),
+}
+
+/// Operation of an atomic memory operation and the width of the memory it accesses.
+fn atomic(opcode: Opcode) -> Option<(Amo, u8)> {
+    let atomic = match opcode {
+        Opcode::AMOSWAP_W => (Amo::Swap, 32),
+        Opcode::AMOADD_W => (Amo::Op(BinOp::Add), 32),
+        Opcode::AMOXOR_W => (Amo::Op(BinOp::Xor), 32),
+        Opcode::AMOAND_W => (Amo::Op(BinOp::And), 32),
+        Opcode::AMOOR_W => (Amo::Op(BinOp::Or), 32),
+        Opcode::AMOMIN_W => (Amo::Min(Cmp::Slt), 32),
+        Opcode::AMOMINU_W => (Amo::Min(Cmp::Ult), 32),
+        Opcode::AMOMAX_W => (Amo::Max(Cmp::Slt), 32),
+        Opcode::AMOMAXU_W => (Amo::Max(Cmp::Ult), 32),
+        Opcode::AMOSWAP_D => (Amo::Swap, 64),
+        Opcode::AMOADD_D => (Amo::Op(BinOp::Add), 64),
+        Opcode::AMOXOR_D => (Amo::Op(BinOp::Xor), 64),
+        Opcode::AMOAND_D => (Amo::Op(BinOp::And), 64),
+        Opcode::AMOOR_D => (Amo::Op(BinOp::Or), 64),
+        Opcode::AMOMIN_D => (Amo::Min(Cmp::Slt), 64),
+        Opcode::AMOMINU_D => (Amo::Min(Cmp::Ult), 64),
+        Opcode::AMOMAX_D => (Amo::Max(Cmp::Slt), 64),
+        Opcode::AMOMAXU_D => (Amo::Max(Cmp::Ult), 64),
+        _ => return None,
+    };
+
+    Some(atomic)
+}
+
+struct Lifter<'a> {
+    inst: &'a Instruction,
+    b: &'a mut Builder,
+    addr: u64,
+    xlen: u8,
+}
+
+impl Lifter<'_> {
+    /// Low `bits` of an integer register.
+    fn get(&mut self, reg: Register, bits: u8) -> Option<Value> {
+        match reg {
+            Register::Zero => Some(imm(0, bits)),
+            _ if (reg as usize) < 32 => Some(self.b.reg(reg.as_str(), bits)),
+            _ => None,
+        }
+    }
+
+    fn set(&mut self, reg: Register, value: Value) -> Option<()> {
+        match reg {
+            Register::Zero => {}
+            _ if (reg as usize) < 32 => {
+                let value = self.b.sext(value, self.xlen);
+                self.b.set_reg(reg.as_str(), value);
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    fn reg(&self, idx: usize) -> Option<Register> {
+        match self.inst.operands[idx] {
+            Operand::Register(reg) => Some(reg),
+            _ => None,
+        }
+    }
+
+    fn imm(&self, idx: usize) -> Option<i32> {
+        match self.inst.operands[idx] {
+            Operand::Immediate(imm) => Some(imm),
+            _ => None,
+        }
+    }
+
+    /// Register or sign extended immediate operand.
+    fn read(&mut self, idx: usize, bits: u8) -> Option<Value> {
+        match self.inst.operands[idx] {
+            Operand::Register(reg) => self.get(reg, bits),
+            Operand::Immediate(value) => Some(imm(value as i64 as u64, bits)),
+            _ => None,
+        }
+    }
+
+    fn write(&mut self, idx: usize, value: Value) -> Option<()> {
+        let reg = self.reg(idx)?;
+        self.set(reg, value)
+    }
+
+    fn next(&self) -> Value {
+        imm(self.addr + self.inst.len as u64, self.xlen)
+    }
+
+    /// Address at `offset` from the instruction.
+    fn target(&self, offset: i32) -> Value {
+        imm(self.addr.wrapping_add(offset as i64 as u64), self.xlen)
+    }
+
+    /// `rd = rs1 op rs2`, where `rs2` can also be an immediate.
+    fn binary(&mut self, op: BinOp, bits: u8) -> Option<()> {
+        let a = self.read(1, bits)?;
+        let b = self.read(2, bits)?;
+        let res = self.b.binary(op, a, b);
+        self.write(0, res)
+    }
+
+    /// Shift, where the amount is taken modulo the width.
+    fn shift(&mut self, op: BinOp, bits: u8) -> Option<()> {
+        let a = self.read(1, bits)?;
+        let amount = self.read(2, bits)?;
+        let amount = self.b.binary(BinOp::And, amount, imm(bits as u64 - 1, bits));
+        let res = self.b.binary(op, a, amount);
+        self.write(0, res)
+    }
+
+    fn set_if(&mut self, cmp: Cmp) -> Option<()> {
+        let a = self.read(1, self.xlen)?;
+        let b = self.read(2, self.xlen)?;
+        let cond = self.b.compare(cmp, a, b);
+        let res = self.b.zext(cond, self.xlen);
+        self.write(0, res)
+    }
+
+    /// Minimum or maximum of two registers, where `cmp` orders them.
+    fn pick(&mut self, cmp: Cmp, max: bool) -> Option<()> {
+        let a = self.read(1, self.xlen)?;
+        let b = self.read(2, self.xlen)?;
+        let cond = self.b.compare(cmp, a, b);
+        let res = match max {
+            true => self.b.select(cond, b, a),
+            false => self.b.select(cond, a, b),
+        };
+        self.write(0, res)
+    }
+
+    /// `rd = (rs1 << amount) + rs2`.
+    fn shift_add(&mut self, amount: u64, unsigned_word: bool) -> Option<()> {
+        let a = match unsigned_word {
+            true => {
+                let a = self.read(1, 32)?;
+                self.b.zext(a, self.xlen)
+            }
+            false => self.read(1, self.xlen)?,
+        };
+        let b = self.read(2, self.xlen)?;
+        let a = match amount {
+            0 => a,
+            _ => self.b.binary(BinOp::Shl, a, imm(amount, self.xlen)),
+        };
+        let res = self.b.binary(BinOp::Add, a, b);
+        self.write(0, res)
+    }
+
+    /// Division where dividing by zero results in all ones, or the dividend for the remainder.
+    fn divide(&mut self, op: BinOp, bits: u8) -> Option<()> {
+        let a = self.read(1, bits)?;
+        let b = self.read(2, bits)?;
+        let zero = self.b.compare(Cmp::Eq, b, imm(0, bits));
+        let divisor = self.b.select(zero, imm(1, bits), b);
+        let res = self.b.binary(op, a, divisor);
+        let res = match op {
+            BinOp::UDiv | BinOp::SDiv => self.b.select(zero, imm(u64::MAX, bits), res),
+            _ => self.b.select(zero, a, res),
+        };
+        self.write(0, res)
+    }
+
+    /// `cond` holds if `rs1 cmp rs2`, where the condition is negated for the inverse branch.
+    fn branch(&mut self, cmp: Cmp, swap: bool, negate: bool) -> Option<()> {
+        let a = self.read(0, self.xlen)?;
+        let b = self.read(1, self.xlen)?;
+        let (a, b) = if swap { (b, a) } else { (a, b) };
+        let cond = self.b.compare(cmp, a, b);
+        let cond = if negate {
+            self.b.binary(BinOp::Xor, cond, imm(1, 1))
+        } else {
+            cond
+        };
+        let target = self.target(self.imm(2)?);
+        self.b.branch(cond, target);
+        Some(())
+    }
+
+    fn lift(&mut self) -> Option<()> {
+        let opcode = self.inst.opcode;
+        let xlen = self.xlen;
+
+        if let Some((bits, signed)) = load(opcode) {
+            return self.load(bits, signed);
+        }
+
+        if let Some(bits) = store(opcode) {
+            return self.store(bits);
+        }
+
+        if let Some((amo, bits)) = atomic(opcode) {
+            return self.atomic(amo, bits);
+        }
+
+        match opcode {
+            Opcode::C_NOP => {}
+            Opcode::LUI | Opcode::C_LUI | Opcode::AUIPC => {
+                let upper = match opcode {
+                    Opcode::C_LUI => self.imm(1)? << 12,
+                    _ => ((self.imm(1)? as u32) << 12) as i32,
+                };
+                let value = match opcode {
+                    Opcode::AUIPC => self.target(upper),
+                    _ => imm(upper as i64 as u64, xlen),
+                };
+                self.write(0, value)?;
+            }
+            Opcode::C_LI | Opcode::C_MV => {
+                let value = self.read(1, xlen)?;
+                self.write(0, value)?;
+            }
+            Opcode::C_ADDI16SP => {
+                let sp = self.b.reg("sp", xlen);
+                let res = self.b.binary(BinOp::Add, sp, imm(self.imm(0)? as i64 as u64, xlen));
+                self.b.set_reg("sp", res);
+            }
+            Opcode::C_ADDI4SPN => {
+                let sp = self.b.reg("sp", xlen);
+                let res = self.b.binary(BinOp::Add, sp, imm(self.imm(1)? as u64, xlen));
+                self.write(0, res)?;
+            }
+            Opcode::ADD | Opcode::ADDI | Opcode::C_ADD | Opcode::C_ADDI => {
+                self.binary(BinOp::Add, xlen)?
+            }
+            Opcode::SUB | Opcode::C_SUB => self.binary(BinOp::Sub, xlen)?,
+            Opcode::XOR | Opcode::XORI | Opcode::C_XOR => self.binary(BinOp::Xor, xlen)?,
+            Opcode::OR | Opcode::ORI | Opcode::C_OR => self.binary(BinOp::Or, xlen)?,
+            Opcode::AND | Opcode::ANDI | Opcode::C_AND => self.binary(BinOp::And, xlen)?,
+            Opcode::SLL | Opcode::SLLI | Opcode::C_SLLI | Opcode::C_SLLI64 => {
+                self.shift(BinOp::Shl, xlen)?
+            }
+            Opcode::SRL | Opcode::SRLI | Opcode::C_SRLI | Opcode::C_SRLI64 => {
+                self.shift(BinOp::LShr, xlen)?
+            }
+            Opcode::SRA | Opcode::SRAI | Opcode::C_SRAI | Opcode::C_SRAI64 => {
+                self.shift(BinOp::AShr, xlen)?
+            }
+            Opcode::ROR | Opcode::RORI => self.shift(BinOp::Ror, xlen)?,
+            Opcode::ROL => {
+                let a = self.read(1, xlen)?;
+                let amount = self.read(2, xlen)?;
+                let amount = self.b.unary(UnOp::Neg, amount);
+                let amount = self.b.binary(BinOp::And, amount, imm(xlen as u64 - 1, xlen));
+                let res = self.b.binary(BinOp::Ror, a, amount);
+                self.write(0, res)?;
+            }
+            Opcode::SLT | Opcode::SLTI => self.set_if(Cmp::Slt)?,
+            Opcode::SLTU | Opcode::SLTIU => self.set_if(Cmp::Ult)?,
+            Opcode::ADDW | Opcode::ADDIW | Opcode::C_ADDW | Opcode::C_ADDIW => {
+                self.binary(BinOp::Add, 32)?
+            }
+            Opcode::SUBW | Opcode::C_SUBW => self.binary(BinOp::Sub, 32)?,
+            Opcode::SLLW | Opcode::SLLIW => self.shift(BinOp::Shl, 32)?,
+            Opcode::SRLW | Opcode::SRLIW => self.shift(BinOp::LShr, 32)?,
+            Opcode::SRAW | Opcode::SRAIW => self.shift(BinOp::AShr, 32)?,
+            Opcode::RORW | Opcode::RORIW => self.shift(BinOp::Ror, 32)?,
+            Opcode::MUL => self.binary(BinOp::Mul, xlen)?,
+            Opcode::MULW => self.binary(BinOp::Mul, 32)?,
+            Opcode::MULH => self.binary(BinOp::MulHiS, xlen)?,
+            Opcode::MULHU => self.binary(BinOp::MulHiU, xlen)?,
+            Opcode::MULHSU => {
+                // the unsigned high half over-counts by rs2 if rs1 is negative
+                let a = self.read(1, xlen)?;
+                let b = self.read(2, xlen)?;
+                let high = self.b.binary(BinOp::MulHiU, a, b);
+                let negative = self.b.compare(Cmp::Slt, a, imm(0, xlen));
+                let fixup = self.b.select(negative, b, imm(0, xlen));
+                let res = self.b.binary(BinOp::Sub, high, fixup);
+                self.write(0, res)?;
+            }
+            Opcode::DIV => self.divide(BinOp::SDiv, xlen)?,
+            Opcode::DIVU => self.divide(BinOp::UDiv, xlen)?,
+            Opcode::REM => self.divide(BinOp::SRem, xlen)?,
+            Opcode::REMU => self.divide(BinOp::URem, xlen)?,
+            Opcode::DIVW => self.divide(BinOp::SDiv, 32)?,
+            Opcode::DIVUW => self.divide(BinOp::UDiv, 32)?,
+            Opcode::REMW => self.divide(BinOp::SRem, 32)?,
+            Opcode::REMUW => self.divide(BinOp::URem, 32)?,
+            Opcode::SH1ADD => self.shift_add(1, false)?,
+            Opcode::SH2ADD => self.shift_add(2, false)?,
+            Opcode::SH3ADD => self.shift_add(3, false)?,
+            Opcode::ADD_UW => self.shift_add(0, true)?,
+            Opcode::SH1ADD_UW => self.shift_add(1, true)?,
+            Opcode::SH2ADD_UW => self.shift_add(2, true)?,
+            Opcode::SH3ADD_UW => self.shift_add(3, true)?,
+            Opcode::ANDN | Opcode::ORN | Opcode::XNOR => {
+                let a = self.read(1, xlen)?;
+                let b = self.read(2, xlen)?;
+                let res = match opcode {
+                    Opcode::ANDN => {
+                        let b = self.b.unary(UnOp::Not, b);
+                        self.b.binary(BinOp::And, a, b)
+                    }
+                    Opcode::ORN => {
+                        let b = self.b.unary(UnOp::Not, b);
+                        self.b.binary(BinOp::Or, a, b)
+                    }
+                    _ => {
+                        let res = self.b.binary(BinOp::Xor, a, b);
+                        self.b.unary(UnOp::Not, res)
+                    }
+                };
+                self.write(0, res)?;
+            }
+            Opcode::MIN => self.pick(Cmp::Slt, false)?,
+            Opcode::MINU => self.pick(Cmp::Ult, false)?,
+            Opcode::MAX => self.pick(Cmp::Slt, true)?,
+            Opcode::MAXU => self.pick(Cmp::Ult, true)?,
+            Opcode::CLZ | Opcode::CTZ | Opcode::CPOP | Opcode::REV8 => {
+                let op = match opcode {
+                    Opcode::CLZ => UnOp::Clz,
+                    Opcode::CTZ => UnOp::Ctz,
+                    Opcode::CPOP => UnOp::Popcnt,
+                    _ => UnOp::Bswap,
+                };
+                let a = self.read(1, xlen)?;
+                let res = self.b.unary(op, a);
+                self.write(0, res)?;
+            }
+            Opcode::CLZW | Opcode::CTZW | Opcode::CPOPW => {
+                let op = match opcode {
+                    Opcode::CLZW => UnOp::Clz,
+                    Opcode::CTZW => UnOp::Ctz,
+                    _ => UnOp::Popcnt,
+                };
+                let a = self.read(1, 32)?;
+                let res = self.b.unary(op, a);
+                let res = self.b.zext(res, xlen);
+                self.write(0, res)?;
+            }
+            Opcode::SEXT_B | Opcode::SEXT_H | Opcode::ZEXT_H => {
+                let bits = if opcode == Opcode::SEXT_B { 8 } else { 16 };
+                let a = self.read(1, bits)?;
+                let res = match opcode {
+                    Opcode::ZEXT_H => self.b.zext(a, xlen),
+                    _ => self.b.sext(a, xlen),
+                };
+                self.write(0, res)?;
+            }
+            Opcode::CZERO_EQZ | Opcode::CZERO_NEZ => {
+                let a = self.read(1, xlen)?;
+                let b = self.read(2, xlen)?;
+                let cmp = if opcode == Opcode::CZERO_EQZ {
+                    Cmp::Eq
+                } else {
+                    Cmp::Ne
+                };
+                let cond = self.b.compare(cmp, b, imm(0, xlen));
+                let res = self.b.select(cond, imm(0, xlen), a);
+                self.write(0, res)?;
+            }
+            Opcode::BEQ => self.branch(Cmp::Eq, false, false)?,
+            Opcode::BNE => self.branch(Cmp::Ne, false, false)?,
+            Opcode::BLT => self.branch(Cmp::Slt, false, false)?,
+            Opcode::BGE => self.branch(Cmp::Slt, false, true)?,
+            Opcode::BLTU => self.branch(Cmp::Ult, false, false)?,
+            Opcode::BGEU => self.branch(Cmp::Ult, false, true)?,
+            Opcode::C_BEQZ | Opcode::C_BNEZ => {
+                let a = self.read(0, xlen)?;
+                let cmp = if opcode == Opcode::C_BEQZ {
+                    Cmp::Eq
+                } else {
+                    Cmp::Ne
+                };
+                let cond = self.b.compare(cmp, a, imm(0, xlen));
+                let target = self.target(self.imm(2)?);
+                self.b.branch(cond, target);
+            }
+            Opcode::JAL | Opcode::C_J | Opcode::C_JAL => {
+                let (link, offset) = match opcode {
+                    Opcode::JAL => (self.reg(0)?, self.imm(1)?),
+                    Opcode::C_JAL => (Register::Ra, self.imm(0)?),
+                    _ => (Register::Zero, self.imm(0)?),
+                };
+                let target = self.target(offset);
+                self.link(link, target)?;
+            }
+            Opcode::JALR => {
+                // the decoded operands don't include the base register
+                let raw = self.inst.encoding.raw;
+                let link = Register::get(raw >> 7 & 0b11111).ok()?;
+                let base = Register::get(raw >> 15 & 0b11111).ok()?;
+                let offset = raw as i32 >> 20;
+
+                let base_value = self.get(base, xlen)?;
+                let target = self.b.binary(BinOp::Add, base_value, imm(offset as i64 as u64, xlen));
+                let target = self.b.binary(BinOp::And, target, imm(!1, xlen));
+
+                if link == Register::Zero && base == Register::Ra && offset == 0 {
+                    self.b.ret(target);
+                } else {
+                    self.link(link, target)?;
+                }
+            }
+            Opcode::C_JALR => {
+                // decoded from `c.jr`, which doesn't link
+                let base = self.reg(1)?;
+                let target = self.get(base, xlen)?;
+                match base {
+                    Register::Ra => self.b.ret(target),
+                    _ => self.b.jump(target),
+                }
+            }
+            Opcode::FENCE | Opcode::FENCE_I => self.b.intrinsic("fence", Vec::new()),
+            Opcode::ECALL => self.b.intrinsic("ecall", Vec::new()),
+            Opcode::EBREAK | Opcode::C_EBREAK => self.b.intrinsic("ebreak", Vec::new()),
+            Opcode::WFI => self.b.intrinsic("wfi", Vec::new()),
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    /// Jump to `target`, saving the return address in `link` unless it's `zero`.
+    fn link(&mut self, link: Register, target: Value) -> Option<()> {
+        if link == Register::Zero {
+            self.b.jump(target);
+            return Some(());
+        }
+
+        let next = self.next();
+        self.set(link, next)?;
+        self.b.call(target);
+        Some(())
+    }
+
+    /// Address of a load or store, the stack relative compressed forms don't name `sp`.
+    fn address(&mut self) -> Option<Value> {
+        let (base, offset) = match self.inst.opcode {
+            Opcode::C_LWSP | Opcode::C_LDSP | Opcode::C_SWSP | Opcode::C_SDSP => {
+                (self.b.reg("sp", self.xlen), self.imm(1)?)
+            }
+            Opcode::LR_W | Opcode::LR_D => (self.read(1, self.xlen)?, 0),
+            Opcode::SC_W | Opcode::SC_D => (self.read(2, self.xlen)?, 0),
+            _ => (self.read(1, self.xlen)?, self.imm(2)?),
+        };
+
+        if offset == 0 {
+            return Some(base);
+        }
+
+        let offset = imm(offset as i64 as u64, self.xlen);
+        Some(self.b.binary(BinOp::Add, base, offset))
+    }
+
+    fn load(&mut self, bits: u8, signed: bool) -> Option<()> {
+        let addr = self.address()?;
+        let value = self.b.load(addr, bits);
+        let value = match signed {
+            true => self.b.sext(value, self.xlen),
+            false => self.b.zext(value, self.xlen),
+        };
+        self.write(0, value)
+    }
+
+    fn store(&mut self, bits: u8) -> Option<()> {
+        // `sc` writes whether it succeeded to the first operand, the value is the second
+        let src = match self.inst.opcode {
+            Opcode::SC_W | Opcode::SC_D => 1,
+            _ => 0,
+        };
+
+        let addr = self.address()?;
+        let value = self.read(src, bits)?;
+        self.b.store(addr, value);
+
+        // the reservation isn't modeled, so `sc` always succeeds
+        if src == 1 {
+            self.write(0, imm(0, self.xlen))?;
+        }
+
+        Some(())
+    }
+
+    /// `rd = [rs1]; [rs1] = rd op rs2`.
+    fn atomic(&mut self, amo: Amo, bits: u8) -> Option<()> {
+        let addr = self.read(2, self.xlen)?;
+        let src = self.read(1, bits)?;
+        let old = self.b.load(addr, bits);
+        let new = match amo {
+            Amo::Swap => src,
+            Amo::Op(op) => self.b.binary(op, old, src),
+            Amo::Min(cmp) => {
+                let cond = self.b.compare(cmp, old, src);
+                self.b.select(cond, old, src)
+            }
+            Amo::Max(cmp) => {
+                let cond = self.b.compare(cmp, old, src);
+                self.b.select(cond, src, old)
+            }
+        };
+
+        self.b.store(addr, new);
+        let old = self.b.sext(old, self.xlen);
+        self.write(0, old)
+    }
+}
@@ -64,7 +64,7 @@ macro_rules! decode_instructions {
         let mut decoded = Vec::new();
         let mut reader = decoder::Reader::new(&binary[..]);
         let mut line = tokenizing::TokenStream::new();
-        let decoder = crate::Decoder { is_64: true };
+        let decoder = crate::Decoder { is_64: true, ..Default::default() };
         let symbols = debugvault::Index::default();
 
         loop {
@@ -897,3 +897,460 @@ fn sha256() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn behavior() {
+    use decoder::{Decoded, Direction, FlowKind, MemoryAccess};
+
+    let decode = |bytes: &[u8]| {
+        let decoder = crate::Decoder { is_64: true, ..Default::default() };
+        decoder.decode(&mut decoder::Reader::new(bytes)).unwrap()
+    };
+
+    // ret
+    let inst = decode(&[0x82, 0x80]);
+    assert_eq!(inst.flow(), FlowKind::Return);
+    assert_eq!(inst.regs_read(), vec!["ra"]);
+    assert!(!inst.can_fall_through());
+
+    // beq a0, a1, 8
+    let mut inst = decode(&[0x63, 0x04, 0xb5, 0x00]);
+    assert_eq!(inst.flow(), FlowKind::ConditionalJump);
+    assert_eq!(inst.regs_read(), vec!["a0", "a1"]);
+    assert_eq!(inst.regs_written(), Vec::<&str>::new());
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![0x1008]);
+
+    // jal ra, 16
+    let mut inst = decode(&[0xef, 0x00, 0x00, 0x01]);
+    assert_eq!(inst.flow(), FlowKind::Call);
+    assert_eq!(inst.regs_written(), vec!["ra"]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![0x1010]);
+
+    assert_eq!(decode(&[0x73, 0x00, 0x00, 0x00]).flow(), FlowKind::Interrupt);
+
+    // addi a0, a0, 4
+    let inst = decode(&[0x13, 0x05, 0x45, 0x00]);
+    assert_eq!(inst.flow(), FlowKind::Sequential);
+    assert_eq!(inst.regs_read(), vec!["a0"]);
+    assert_eq!(inst.regs_written(), vec!["a0"]);
+    assert_eq!(inst.memory(), None);
+
+    // c.li a1, 12
+    let inst = decode(&[0xb1, 0x45]);
+    assert_eq!(inst.regs_read(), Vec::<&str>::new());
+    assert_eq!(inst.regs_written(), vec!["a1"]);
+
+    // c.sw a1, 0(a0)
+    let inst = decode(&[0x0c, 0xc1]);
+    assert_eq!(inst.regs_read(), vec!["a1", "a0"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::Write
+        })
+    );
+
+    // c.ldsp ra, 8
+    let inst = decode(&[0xa2, 0x60]);
+    assert_eq!(inst.regs_read(), vec!["sp"]);
+    assert_eq!(inst.regs_written(), vec!["ra"]);
+    assert_eq!(
+        inst.memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Read
+        })
+    );
+}
+
+#[test]
+fn pseudo() {
+    use decoder::Decoded;
+
+    let tokenize = |bytes: &[u8], pseudo: bool| {
+        let decoder = crate::Decoder { is_64: true, ..Default::default() };
+        let mut inst = decoder.decode(&mut decoder::Reader::new(bytes)).unwrap();
+        let mut line = tokenizing::TokenStream::new();
+        inst.update_rel_addrs(0x1000, None);
+        inst.tokenize_with(&mut line, &debugvault::Index::default(), pseudo);
+        line.to_string()
+    };
+
+    // addi a0, zero, 5
+    assert_eq!(tokenize(&[0x13, 0x05, 0x50, 0x00], true), "li a0, 5");
+    assert_eq!(tokenize(&[0x13, 0x05, 0x50, 0x00], false), "addi a0, zero, 5");
+
+    // addi a0, a0, 4
+    assert_eq!(tokenize(&[0x13, 0x05, 0x45, 0x00], true), "addi a0, 4");
+    assert_eq!(tokenize(&[0x13, 0x05, 0x45, 0x00], false), "addi a0, a0, 4");
+
+    // beq a0, zero, 8
+    assert_eq!(tokenize(&[0x63, 0x04, 0x05, 0x00], true), "beqz a0, 4104");
+    assert_eq!(tokenize(&[0x63, 0x04, 0x05, 0x00], false), "beq a0, zero, 4104");
+
+    // jal ra, 16
+    assert_eq!(tokenize(&[0xef, 0x00, 0x00, 0x01], true), "jal 4112");
+    assert_eq!(tokenize(&[0xef, 0x00, 0x00, 0x01], false), "jal ra, 4112");
+
+    // c.li a1, 12
+    assert_eq!(tokenize(&[0xb1, 0x45], false), "c.li a1, 12");
+}
+
+/// Tokenizes an instruction as it's encoded, without mapping it to a pseudo-instruction.
+fn canonical(bytes: &[u8], is_64: bool) -> String {
+    let decoder = crate::Decoder { is_64, ..Default::default() };
+    let inst = decoder.decode(&mut decoder::Reader::new(bytes)).unwrap();
+    let mut line = tokenizing::TokenStream::new();
+    inst.tokenize_with(&mut line, &debugvault::Index::default(), false);
+    line.to_string()
+}
+
+#[test]
+fn privileged() {
+    use decoder::{Decoded, FlowKind};
+
+    assert_eq!(canonical(&[0x73, 0x00, 0x20, 0x10], true), "sret");
+    assert_eq!(canonical(&[0x73, 0x00, 0x20, 0x30], true), "mret");
+    assert_eq!(canonical(&[0x73, 0x00, 0x50, 0x10], true), "wfi");
+    assert_eq!(canonical(&[0x73, 0x00, 0xb5, 0x12], true), "sfence.vma a0, a1");
+
+    let decoder = crate::Decoder { is_64: true, ..Default::default() };
+    let mret = decoder.decode(&mut decoder::Reader::new(&[0x73, 0x00, 0x20, 0x30])).unwrap();
+    assert_eq!(mret.flow(), FlowKind::Return);
+
+    let fence = decoder.decode(&mut decoder::Reader::new(&[0x73, 0x00, 0xb5, 0x12])).unwrap();
+    assert_eq!(fence.regs_read(), vec!["a0", "a1"]);
+    assert_eq!(fence.regs_written(), Vec::<&str>::new());
+}
+
+#[test]
+fn bitmanip() {
+    // zba
+    assert_eq!(canonical(&[0x33, 0xc5, 0xc5, 0x20], true), "sh2add a0, a1, a2");
+    assert_eq!(canonical(&[0x3b, 0x85, 0xc5, 0x08], true), "add.uw a0, a1, a2");
+    assert_eq!(canonical(&[0x1b, 0x95, 0x35, 0x0a], true), "slli.uw a0, a1, 35");
+
+    // zbb
+    assert_eq!(canonical(&[0x33, 0xf5, 0xc5, 0x40], true), "andn a0, a1, a2");
+    assert_eq!(canonical(&[0x13, 0x95, 0x05, 0x60], true), "clz a0, a1");
+    assert_eq!(canonical(&[0x1b, 0x95, 0x25, 0x60], true), "cpopw a0, a1");
+    assert_eq!(canonical(&[0x33, 0xe5, 0xc5, 0x0a], true), "max a0, a1, a2");
+    assert_eq!(canonical(&[0x13, 0x95, 0x45, 0x60], true), "sext.b a0, a1");
+    assert_eq!(canonical(&[0x3b, 0xc5, 0x05, 0x08], true), "zext.h a0, a1");
+    assert_eq!(canonical(&[0x13, 0xd5, 0x85, 0x62], true), "rori a0, a1, 40");
+    assert_eq!(canonical(&[0x13, 0xd5, 0x75, 0x28], true), "orc.b a0, a1");
+    assert_eq!(canonical(&[0x13, 0xd5, 0x85, 0x6b], true), "rev8 a0, a1");
+
+    // zbc
+    assert_eq!(canonical(&[0x33, 0x95, 0xc5, 0x0a], true), "clmul a0, a1, a2");
+    assert_eq!(canonical(&[0x33, 0xb5, 0xc5, 0x0a], true), "clmulh a0, a1, a2");
+
+    // zbs
+    assert_eq!(canonical(&[0x13, 0x95, 0x15, 0x4a], true), "bclri a0, a1, 33");
+    assert_eq!(canonical(&[0x33, 0xd5, 0xc5, 0x48], true), "bext a0, a1, a2");
+    assert_eq!(canonical(&[0x33, 0x95, 0xc5, 0x68], true), "binv a0, a1, a2");
+    assert_eq!(canonical(&[0x13, 0x95, 0x15, 0x28], true), "bseti a0, a1, 1");
+
+    // shifts share their encoding space with zbb and zbs
+    assert_eq!(canonical(&[0x13, 0xd5, 0x35, 0x40], true), "srai a0, a1, 3");
+}
+
+#[test]
+fn conditional_and_cache() {
+    use decoder::{Decoded, Direction, MemoryAccess};
+
+    // zicond
+    assert_eq!(canonical(&[0x33, 0xd5, 0xc5, 0x0e], true), "czero.eqz a0, a1, a2");
+    assert_eq!(canonical(&[0x33, 0xf5, 0xc5, 0x0e], true), "czero.nez a0, a1, a2");
+
+    // zicbom and zicboz
+    assert_eq!(canonical(&[0x0f, 0x20, 0x15, 0x00], true), "cbo.clean a0");
+    assert_eq!(canonical(&[0x0f, 0x20, 0x25, 0x00], true), "cbo.flush a0");
+    assert_eq!(canonical(&[0x0f, 0x20, 0x05, 0x00], true), "cbo.inval a0");
+    assert_eq!(canonical(&[0x0f, 0x20, 0x45, 0x00], true), "cbo.zero a0");
+
+    let decoder = crate::Decoder { is_64: true, ..Default::default() };
+    let zero = decoder.decode(&mut decoder::Reader::new(&[0x0f, 0x20, 0x45, 0x00])).unwrap();
+    assert_eq!(zero.regs_read(), vec!["a0"]);
+    assert_eq!(
+        zero.memory(),
+        Some(MemoryAccess {
+            size: None,
+            direction: Direction::Write
+        })
+    );
+}
+
+#[test]
+fn half_precision() {
+    use decoder::{Decoded, Direction, MemoryAccess};
+
+    assert_eq!(canonical(&[0x07, 0x10, 0x25, 0x00], true), "flh ft0, a0, 2");
+    assert_eq!(canonical(&[0x27, 0x1e, 0x15, 0xfe], true), "fsh ft1, a0, -4");
+    assert_eq!(canonical(&[0x43, 0xf5, 0xc5, 0x6c], true), "fmadd.h fa0, fa1, fa2, fa3");
+    assert_eq!(canonical(&[0x53, 0x80, 0x20, 0x40], true), "fcvt.s.h ft0, ft1");
+    assert_eq!(canonical(&[0x53, 0x05, 0x00, 0xe4], true), "fmv.x.h a0, ft0");
+    assert_eq!(canonical(&[0x53, 0x25, 0x10, 0xa4], true), "feq.h a0, ft0, ft1");
+
+    let decoder = crate::Decoder { is_64: true, ..Default::default() };
+    let load = decoder.decode(&mut decoder::Reader::new(&[0x07, 0x10, 0x25, 0x00])).unwrap();
+    assert_eq!(load.regs_read(), vec!["a0"]);
+    assert_eq!(load.regs_written(), vec!["ft0"]);
+    assert_eq!(
+        load.memory(),
+        Some(MemoryAccess {
+            size: Some(2),
+            direction: Direction::Read
+        })
+    );
+}
+
+#[test]
+fn crypto() {
+    // rv64
+    assert_eq!(canonical(&[0x33, 0x85, 0xc5, 0x32], true), "aes64es a0, a1, a2");
+    assert_eq!(canonical(&[0x13, 0x95, 0xa5, 0x31], true), "aes64ks1i a0, a1, 10");
+    assert_eq!(canonical(&[0x13, 0x95, 0x25, 0x10], true), "sha256sig0 a0, a1");
+    assert_eq!(canonical(&[0x13, 0x95, 0x55, 0x10], true), "sha512sum1 a0, a1");
+    assert_eq!(canonical(&[0x33, 0x85, 0xc5, 0xf0], true), "sm4ed a0, a1, a2, 3");
+    assert_eq!(canonical(&[0x13, 0x95, 0x95, 0x10], true), "sm3p1 a0, a1");
+    assert_eq!(canonical(&[0x33, 0xc5, 0xc5, 0x28], true), "xperm8 a0, a1, a2");
+
+    // rv32
+    assert_eq!(canonical(&[0x33, 0x85, 0xc5, 0xe2], false), "aes32esi a0, a1, a2, 3");
+    assert_eq!(canonical(&[0x33, 0x85, 0xc5, 0x5c], false), "sha512sig0h a0, a1, a2");
+    assert_eq!(canonical(&[0x13, 0x95, 0xf5, 0x08], false), "zip a0, a1");
+
+    // the 32-bit aes instructions don't exist on rv64
+    let decoder = crate::Decoder { is_64: true, ..Default::default() };
+    assert!(decoder.decode(&mut decoder::Reader::new(&[0x33, 0x85, 0xc5, 0xe2])).is_err());
+}
+
+#[test]
+fn vector() {
+    use decoder::{Decoded, Direction, ErrorKind, MemoryAccess};
+
+    // configuration
+    assert_eq!(canonical(&[0x57, 0xf5, 0x05, 0x0d], true), "vsetvli a0, a1, e32, m1, ta, ma");
+    assert_eq!(canonical(&[0x57, 0x70, 0x74, 0xc0], true), "vsetivli zero, 8, e8, mf2, tu, mu");
+
+    // arithmetic
+    assert_eq!(canonical(&[0xd7, 0x80, 0x21, 0x00], true), "vadd.vv v1, v2, v3, v0.t");
+    assert_eq!(canonical(&[0xd7, 0xb0, 0x2d, 0x02], true), "vadd.vi v1, v2, -5");
+    assert_eq!(canonical(&[0xd7, 0xb0, 0x2f, 0x96], true), "vsll.vi v1, v2, 31");
+    assert_eq!(canonical(&[0xd7, 0x50, 0x25, 0x02], true), "vfadd.vf v1, v2, fa0");
+    assert_eq!(canonical(&[0xd7, 0x60, 0x25, 0xb6], true), "vmacc.vx v1, a0, v2");
+    assert_eq!(canonical(&[0xd7, 0xa0, 0x21, 0x02], true), "vredsum.vs v1, v2, v3");
+    assert_eq!(canonical(&[0xd7, 0xa0, 0x21, 0x66], true), "vmand.mm v1, v2, v3");
+    assert_eq!(canonical(&[0xd7, 0x80, 0x21, 0x5c], true), "vmerge.vvm v1, v2, v3, v0");
+    assert_eq!(canonical(&[0xd7, 0x40, 0x05, 0x5e], true), "vmv.v.x v1, a0");
+    assert_eq!(canonical(&[0x57, 0x25, 0x20, 0x42], true), "vmv.x.s a0, v2");
+    assert_eq!(canonical(&[0xd7, 0x20, 0x23, 0x4a], true), "vzext.vf2 v1, v2");
+    assert_eq!(canonical(&[0xd7, 0x90, 0x2b, 0x4a], true), "vfncvt.rtz.x.f.w v1, v2");
+
+    // loads and stores
+    assert_eq!(canonical(&[0x87, 0x60, 0x05, 0x02], true), "vle32.v v1, a0");
+    assert_eq!(canonical(&[0xa7, 0x00, 0x05, 0x00], true), "vse8.v v1, a0, v0.t");
+    assert_eq!(canonical(&[0x87, 0x70, 0xb5, 0x0a], true), "vlse64.v v1, a0, a1");
+    assert_eq!(canonical(&[0x87, 0x50, 0x25, 0x06], true), "vluxei16.v v1, a0, v2");
+    assert_eq!(canonical(&[0x07, 0x61, 0x85, 0x22], true), "vl2re32.v v2, a0");
+    assert_eq!(canonical(&[0xa7, 0x00, 0x85, 0x02], true), "vs1r.v v1, a0");
+
+    // segment loads and stores
+    assert_eq!(canonical(&[0x87, 0x00, 0x05, 0x22], true), "vlseg2e8.v v1, a0");
+    assert_eq!(canonical(&[0x07, 0x74, 0x05, 0xe0], true), "vlseg8e64.v v8, a0, v0.t");
+    assert_eq!(canonical(&[0x07, 0xd2, 0x05, 0x43], true), "vlseg3e16ff.v v4, a1");
+    assert_eq!(canonical(&[0x07, 0x61, 0xb5, 0x6a], true), "vlsseg4e32.v v2, a0, a1");
+    assert_eq!(canonical(&[0x87, 0x50, 0x25, 0x86], true), "vluxseg5ei16.v v1, a0, v2");
+    assert_eq!(canonical(&[0x07, 0x72, 0x85, 0x2c], true), "vloxseg2ei64.v v4, a0, v8, v0.t");
+    assert_eq!(canonical(&[0xa7, 0x60, 0x05, 0xc2], true), "vsseg7e32.v v1, a0");
+    assert_eq!(canonical(&[0xa7, 0x50, 0xb5, 0x2a], true), "vssseg2e16.v v1, a0, a1");
+    assert_eq!(canonical(&[0xa7, 0x00, 0x25, 0x26], true), "vsuxseg2ei8.v v1, a0, v2");
+    assert_eq!(canonical(&[0xa7, 0x61, 0x65, 0x4e], true), "vsoxseg3ei32.v v3, a0, v6");
+
+    let decoder = crate::Decoder { is_64: true, ..Default::default() };
+    let decode = |bytes: &[u8]| decoder.decode(&mut decoder::Reader::new(bytes));
+
+    // fault-only-first segment stores don't exist
+    let err = decode(&[0xa7, 0x00, 0x05, 0x23]).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidOpcode);
+
+    let segment = decode(&[0xa7, 0x61, 0x65, 0x4e]).unwrap();
+    assert_eq!(segment.regs_read(), vec!["v3", "a0", "v6"]);
+    assert_eq!(segment.regs_written(), Vec::<&str>::new());
+    assert_eq!(
+        segment.memory(),
+        Some(MemoryAccess {
+            size: None,
+            direction: Direction::Write
+        })
+    );
+
+    let add = decode(&[0xd7, 0x80, 0x21, 0x00]).unwrap();
+    assert_eq!(add.regs_read(), vec!["v2", "v3", "v0"]);
+    assert_eq!(add.regs_written(), vec!["v1"]);
+
+    let store = decode(&[0xa7, 0x00, 0x05, 0x00]).unwrap();
+    assert_eq!(store.regs_read(), vec!["v1", "a0", "v0"]);
+    assert_eq!(store.regs_written(), Vec::<&str>::new());
+    assert_eq!(
+        store.memory(),
+        Some(MemoryAccess {
+            size: None,
+            direction: Direction::Write
+        })
+    );
+}
+
+#[test]
+fn extensions() {
+    use crate::Extensions;
+    use decoder::ErrorKind;
+
+    let isa = Extensions::from_isa("rv64imafdc_zba_zbb").unwrap();
+    assert!(isa.contains(Extensions::M | Extensions::A | Extensions::C | Extensions::ZBB));
+    assert!(isa.contains(Extensions::ZICSR | Extensions::ZIFENCEI));
+    assert!(!isa.intersects(Extensions::V | Extensions::ZBS));
+
+    let isa = Extensions::from_isa("rv64i2p1_m2p0_a2p1_c2p0_zicsr2p0_zba1p0").unwrap();
+    assert!(isa.contains(Extensions::M | Extensions::ZICSR | Extensions::ZBA));
+    assert!(!isa.intersects(Extensions::F | Extensions::ZIFENCEI));
+
+    let isa = Extensions::from_isa("RV32GCV_zfh1p0_zkn_xtheadba").unwrap();
+    assert!(isa.contains(Extensions::D | Extensions::V | Extensions::ZFHMIN | Extensions::ZKNE));
+    assert!(!isa.intersects(Extensions::Q | Extensions::ZKSED));
+
+    assert_eq!(Extensions::from_isa("armv7"), None);
+
+    let decoder = crate::Decoder {
+        is_64: true,
+        extensions: Extensions::from_isa("rv64i_zba").unwrap(),
+    };
+    let decode = |bytes: &[u8]| decoder.decode(&mut decoder::Reader::new(bytes));
+
+    assert_eq!(canonical(&[0x33, 0xa5, 0xc5, 0x20], true), "sh1add a0, a1, a2");
+    assert!(decode(&[0x33, 0xa5, 0xc5, 0x20]).is_ok());
+
+    let err = decode(&[0x33, 0xf5, 0xc5, 0x40]).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::DisabledExtension);
+    assert_eq!(err.size(), 4);
+
+    let err = decode(&[0x05, 0x05]).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::DisabledExtension);
+    assert_eq!(err.size(), 2);
+}
+
+
+#[test]
+fn lift() {
+    use decoder::Decoded;
+
+    let lift = |bytes: &[u8]| {
+        let decoder = crate::Decoder { is_64: true, ..Default::default() };
+        let mut inst = decoder.decode(&mut decoder::Reader::new(bytes)).unwrap();
+        inst.update_rel_addrs(0x1000, None);
+        let mut builder = decoder::ir::Builder::new();
+        inst.lift(0x1000, &mut builder);
+        builder.take().iter().map(ToString::to_string).collect::<Vec<_>>()
+    };
+
+    // li a0, 5
+    assert_eq!(lift(&[0x13, 0x05, 0x50, 0x00]), ["a0 = 0x5"]);
+
+    // lui a0, 0x12345
+    assert_eq!(lift(&[0x37, 0x55, 0x34, 0x12]), ["a0 = 0x12345000"]);
+
+    // auipc a1, 1
+    assert_eq!(lift(&[0x97, 0x15, 0x00, 0x00]), ["a1 = 0x2000"]);
+
+    // addi a0, a1, -4
+    assert_eq!(
+        lift(&[0x13, 0x85, 0xc5, 0xff]),
+        ["t0 = a1", "t1 = t0 + 0xfffffffffffffffc", "a0 = t1"]
+    );
+
+    // addw a0, a1, a2
+    assert_eq!(
+        lift(&[0x3b, 0x85, 0xc5, 0x00]),
+        ["t0 = a1", "t1 = a2", "t2 = t0 + t1", "t3 = sext64 t2", "a0 = t3"]
+    );
+
+    // sll a0, a1, a2
+    assert_eq!(
+        lift(&[0x33, 0x95, 0xc5, 0x00]),
+        ["t0 = a1", "t1 = a2", "t2 = t1 & 0x3f", "t3 = t0 << t2", "a0 = t3"]
+    );
+
+    // divu a0, a1, a2
+    assert_eq!(
+        lift(&[0x33, 0xd5, 0xc5, 0x02]),
+        [
+            "t0 = a1",
+            "t1 = a2",
+            "t2 = t1 == 0x0",
+            "t3 = t2 ? 0x1 : t1",
+            "t4 = t0 /u t3",
+            "t5 = t2 ? 0xffffffffffffffff : t4",
+            "a0 = t5"
+        ]
+    );
+
+    // lb a0, 8(sp)
+    assert_eq!(
+        lift(&[0x03, 0x05, 0x81, 0x00]),
+        ["t0 = sp", "t1 = t0 + 0x8", "t2 = load8 [t1]", "t3 = sext64 t2", "a0 = t3"]
+    );
+
+    // c.sdsp ra, 8(sp)
+    assert_eq!(
+        lift(&[0x06, 0xe4]),
+        ["t0 = sp", "t1 = t0 + 0x8", "t2 = ra", "store64 [t1], t2"]
+    );
+
+    // sc.w a2, a1, (a0)
+    assert_eq!(
+        lift(&[0x2f, 0x26, 0xb5, 0x18]),
+        ["t0 = a0", "t1 = a1", "store32 [t0], t1", "a2 = 0x0"]
+    );
+
+    // jal ra, 16
+    assert_eq!(lift(&[0xef, 0x00, 0x00, 0x01]), ["ra = 0x1004", "call 0x1010"]);
+
+    // c.j 8
+    assert_eq!(lift(&[0x21, 0xa0]), ["goto 0x1008"]);
+
+    // jalr ra, 8(a0)
+    assert_eq!(
+        lift(&[0xe7, 0x00, 0x85, 0x00]),
+        [
+            "t0 = a0",
+            "t1 = t0 + 0x8",
+            "t2 = t1 & 0xfffffffffffffffe",
+            "ra = 0x1004",
+            "call t2"
+        ]
+    );
+
+    // ret
+    assert_eq!(lift(&[0x82, 0x80]), ["t0 = ra", "ret t0"]);
+
+    // bge a0, a1, -4
+    assert_eq!(
+        lift(&[0xe3, 0x5e, 0xb5, 0xfe]),
+        ["t0 = a0", "t1 = a1", "t2 = t0 <s t1", "t3 = t2 ^ 0x1", "if t3 goto 0xffc"]
+    );
+
+    // c.beqz a0, 8
+    assert_eq!(
+        lift(&[0x01, 0xc5]),
+        ["t0 = a0", "t1 = t0 == 0x0", "if t1 goto 0x1008"]
+    );
+
+    assert_eq!(lift(&[0x73, 0x00, 0x00, 0x00]), ["ecall()"]);
+
+    // fadd.s fa0, fa0, fa1
+    assert_eq!(lift(&[0x53, 0x75, 0xb5, 0x00]), ["unsupported"]);
+}
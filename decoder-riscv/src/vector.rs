@@ -0,0 +1,735 @@
+//! Vector extension (RVV 1.0) decoding.
+
+use crate::{Encoding, Instruction, Opcode, Operand, Register};
+use decoder::ErrorKind;
+
+// operand categories, encoded in the funct3 field of OP-V instructions
+const OPIVV: u32 = 0b000;
+const OPFVV: u32 = 0b001;
+const OPMVV: u32 = 0b010;
+const OPIVI: u32 = 0b011;
+const OPIVX: u32 = 0b100;
+const OPFVF: u32 = 0b101;
+const OPMVX: u32 = 0b110;
+const OPCFG: u32 = 0b111;
+
+/// Splits a `vtype` into it's element width, register grouping and tail/mask policies.
+pub(crate) fn vtype_fields(vtype: u32) -> Option<[&'static str; 4]> {
+    let sew = match vtype >> 3 & 0b111 {
+        0b000 => "e8",
+        0b001 => "e16",
+        0b010 => "e32",
+        0b011 => "e64",
+        _ => return None,
+    };
+
+    let lmul = match vtype & 0b111 {
+        0b000 => "m1",
+        0b001 => "m2",
+        0b010 => "m4",
+        0b011 => "m8",
+        0b101 => "mf8",
+        0b110 => "mf4",
+        0b111 => "mf2",
+        _ => return None,
+    };
+
+    // any other bits set are reserved
+    if vtype >> 8 != 0 {
+        return None;
+    }
+
+    let tail = if vtype & 0b1000000 != 0 { "ta" } else { "tu" };
+    let mask = if vtype & 0b10000000 != 0 { "ma" } else { "mu" };
+
+    Some([sew, lmul, tail, mask])
+}
+
+fn instruction(opcode: Opcode, list: &[Operand]) -> Result<Instruction, ErrorKind> {
+    if opcode == Opcode::Invalid {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    let mut operands = [Operand::Nothing; 4];
+    operands[..list.len()].copy_from_slice(list);
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count: list.len(),
+        len: 4,
+        encoding: Encoding::default(),
+    })
+}
+
+/// Decode's vsetvli, vsetivli and vsetvl.
+fn decode_config(dword: u32) -> Result<Instruction, ErrorKind> {
+    let rd = Operand::Register(Register::get(dword >> 7 & 0b11111)?);
+    let rs1 = dword >> 15 & 0b11111;
+
+    if dword >> 31 == 0 {
+        let rs1 = Operand::Register(Register::get(rs1)?);
+        return instruction(
+            Opcode::VSETVLI,
+            &[rd, rs1, Operand::Vtype(dword >> 20 & 0x7ff)],
+        );
+    }
+
+    if dword >> 30 == 0b11 {
+        let avl = Operand::Immediate(rs1 as i32);
+        return instruction(
+            Opcode::VSETIVLI,
+            &[rd, avl, Operand::Vtype(dword >> 20 & 0x3ff)],
+        );
+    }
+
+    if dword >> 25 == 0b1000000 {
+        let rs1 = Operand::Register(Register::get(rs1)?);
+        let rs2 = Operand::Register(Register::get(dword >> 20 & 0b11111)?);
+        return instruction(Opcode::VSETVL, &[rd, rs1, rs2]);
+    }
+
+    Err(ErrorKind::InvalidOpcode)
+}
+
+/// Decode's vector loads and stores, found in the LOAD-FP and STORE-FP opcodes.
+pub(crate) fn decode_memory(dword: u32, store: bool) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    // indexed by direction, then number of fields and element width
+    const UNIT: [[[Opcode; 4]; 8]; 2] = [
+        [
+            [VLE8_V, VLE16_V, VLE32_V, VLE64_V],
+            [VLSEG2E8_V, VLSEG2E16_V, VLSEG2E32_V, VLSEG2E64_V],
+            [VLSEG3E8_V, VLSEG3E16_V, VLSEG3E32_V, VLSEG3E64_V],
+            [VLSEG4E8_V, VLSEG4E16_V, VLSEG4E32_V, VLSEG4E64_V],
+            [VLSEG5E8_V, VLSEG5E16_V, VLSEG5E32_V, VLSEG5E64_V],
+            [VLSEG6E8_V, VLSEG6E16_V, VLSEG6E32_V, VLSEG6E64_V],
+            [VLSEG7E8_V, VLSEG7E16_V, VLSEG7E32_V, VLSEG7E64_V],
+            [VLSEG8E8_V, VLSEG8E16_V, VLSEG8E32_V, VLSEG8E64_V],
+        ],
+        [
+            [VSE8_V, VSE16_V, VSE32_V, VSE64_V],
+            [VSSEG2E8_V, VSSEG2E16_V, VSSEG2E32_V, VSSEG2E64_V],
+            [VSSEG3E8_V, VSSEG3E16_V, VSSEG3E32_V, VSSEG3E64_V],
+            [VSSEG4E8_V, VSSEG4E16_V, VSSEG4E32_V, VSSEG4E64_V],
+            [VSSEG5E8_V, VSSEG5E16_V, VSSEG5E32_V, VSSEG5E64_V],
+            [VSSEG6E8_V, VSSEG6E16_V, VSSEG6E32_V, VSSEG6E64_V],
+            [VSSEG7E8_V, VSSEG7E16_V, VSSEG7E32_V, VSSEG7E64_V],
+            [VSSEG8E8_V, VSSEG8E16_V, VSSEG8E32_V, VSSEG8E64_V],
+        ],
+    ];
+    const STRIDED: [[[Opcode; 4]; 8]; 2] = [
+        [
+            [VLSE8_V, VLSE16_V, VLSE32_V, VLSE64_V],
+            [VLSSEG2E8_V, VLSSEG2E16_V, VLSSEG2E32_V, VLSSEG2E64_V],
+            [VLSSEG3E8_V, VLSSEG3E16_V, VLSSEG3E32_V, VLSSEG3E64_V],
+            [VLSSEG4E8_V, VLSSEG4E16_V, VLSSEG4E32_V, VLSSEG4E64_V],
+            [VLSSEG5E8_V, VLSSEG5E16_V, VLSSEG5E32_V, VLSSEG5E64_V],
+            [VLSSEG6E8_V, VLSSEG6E16_V, VLSSEG6E32_V, VLSSEG6E64_V],
+            [VLSSEG7E8_V, VLSSEG7E16_V, VLSSEG7E32_V, VLSSEG7E64_V],
+            [VLSSEG8E8_V, VLSSEG8E16_V, VLSSEG8E32_V, VLSSEG8E64_V],
+        ],
+        [
+            [VSSE8_V, VSSE16_V, VSSE32_V, VSSE64_V],
+            [VSSSEG2E8_V, VSSSEG2E16_V, VSSSEG2E32_V, VSSSEG2E64_V],
+            [VSSSEG3E8_V, VSSSEG3E16_V, VSSSEG3E32_V, VSSSEG3E64_V],
+            [VSSSEG4E8_V, VSSSEG4E16_V, VSSSEG4E32_V, VSSSEG4E64_V],
+            [VSSSEG5E8_V, VSSSEG5E16_V, VSSSEG5E32_V, VSSSEG5E64_V],
+            [VSSSEG6E8_V, VSSSEG6E16_V, VSSSEG6E32_V, VSSSEG6E64_V],
+            [VSSSEG7E8_V, VSSSEG7E16_V, VSSSEG7E32_V, VSSSEG7E64_V],
+            [VSSSEG8E8_V, VSSSEG8E16_V, VSSSEG8E32_V, VSSSEG8E64_V],
+        ],
+    ];
+    #[rustfmt::skip]
+    const UNORDERED: [[[Opcode; 4]; 8]; 2] = [
+        [
+            [VLUXEI8_V, VLUXEI16_V, VLUXEI32_V, VLUXEI64_V],
+            [VLUXSEG2EI8_V, VLUXSEG2EI16_V, VLUXSEG2EI32_V, VLUXSEG2EI64_V],
+            [VLUXSEG3EI8_V, VLUXSEG3EI16_V, VLUXSEG3EI32_V, VLUXSEG3EI64_V],
+            [VLUXSEG4EI8_V, VLUXSEG4EI16_V, VLUXSEG4EI32_V, VLUXSEG4EI64_V],
+            [VLUXSEG5EI8_V, VLUXSEG5EI16_V, VLUXSEG5EI32_V, VLUXSEG5EI64_V],
+            [VLUXSEG6EI8_V, VLUXSEG6EI16_V, VLUXSEG6EI32_V, VLUXSEG6EI64_V],
+            [VLUXSEG7EI8_V, VLUXSEG7EI16_V, VLUXSEG7EI32_V, VLUXSEG7EI64_V],
+            [VLUXSEG8EI8_V, VLUXSEG8EI16_V, VLUXSEG8EI32_V, VLUXSEG8EI64_V],
+        ],
+        [
+            [VSUXEI8_V, VSUXEI16_V, VSUXEI32_V, VSUXEI64_V],
+            [VSUXSEG2EI8_V, VSUXSEG2EI16_V, VSUXSEG2EI32_V, VSUXSEG2EI64_V],
+            [VSUXSEG3EI8_V, VSUXSEG3EI16_V, VSUXSEG3EI32_V, VSUXSEG3EI64_V],
+            [VSUXSEG4EI8_V, VSUXSEG4EI16_V, VSUXSEG4EI32_V, VSUXSEG4EI64_V],
+            [VSUXSEG5EI8_V, VSUXSEG5EI16_V, VSUXSEG5EI32_V, VSUXSEG5EI64_V],
+            [VSUXSEG6EI8_V, VSUXSEG6EI16_V, VSUXSEG6EI32_V, VSUXSEG6EI64_V],
+            [VSUXSEG7EI8_V, VSUXSEG7EI16_V, VSUXSEG7EI32_V, VSUXSEG7EI64_V],
+            [VSUXSEG8EI8_V, VSUXSEG8EI16_V, VSUXSEG8EI32_V, VSUXSEG8EI64_V],
+        ],
+    ];
+    #[rustfmt::skip]
+    const ORDERED: [[[Opcode; 4]; 8]; 2] = [
+        [
+            [VLOXEI8_V, VLOXEI16_V, VLOXEI32_V, VLOXEI64_V],
+            [VLOXSEG2EI8_V, VLOXSEG2EI16_V, VLOXSEG2EI32_V, VLOXSEG2EI64_V],
+            [VLOXSEG3EI8_V, VLOXSEG3EI16_V, VLOXSEG3EI32_V, VLOXSEG3EI64_V],
+            [VLOXSEG4EI8_V, VLOXSEG4EI16_V, VLOXSEG4EI32_V, VLOXSEG4EI64_V],
+            [VLOXSEG5EI8_V, VLOXSEG5EI16_V, VLOXSEG5EI32_V, VLOXSEG5EI64_V],
+            [VLOXSEG6EI8_V, VLOXSEG6EI16_V, VLOXSEG6EI32_V, VLOXSEG6EI64_V],
+            [VLOXSEG7EI8_V, VLOXSEG7EI16_V, VLOXSEG7EI32_V, VLOXSEG7EI64_V],
+            [VLOXSEG8EI8_V, VLOXSEG8EI16_V, VLOXSEG8EI32_V, VLOXSEG8EI64_V],
+        ],
+        [
+            [VSOXEI8_V, VSOXEI16_V, VSOXEI32_V, VSOXEI64_V],
+            [VSOXSEG2EI8_V, VSOXSEG2EI16_V, VSOXSEG2EI32_V, VSOXSEG2EI64_V],
+            [VSOXSEG3EI8_V, VSOXSEG3EI16_V, VSOXSEG3EI32_V, VSOXSEG3EI64_V],
+            [VSOXSEG4EI8_V, VSOXSEG4EI16_V, VSOXSEG4EI32_V, VSOXSEG4EI64_V],
+            [VSOXSEG5EI8_V, VSOXSEG5EI16_V, VSOXSEG5EI32_V, VSOXSEG5EI64_V],
+            [VSOXSEG6EI8_V, VSOXSEG6EI16_V, VSOXSEG6EI32_V, VSOXSEG6EI64_V],
+            [VSOXSEG7EI8_V, VSOXSEG7EI16_V, VSOXSEG7EI32_V, VSOXSEG7EI64_V],
+            [VSOXSEG8EI8_V, VSOXSEG8EI16_V, VSOXSEG8EI32_V, VSOXSEG8EI64_V],
+        ],
+    ];
+    const FAULT_ONLY_FIRST: [[Opcode; 4]; 8] = [
+        [VLE8FF_V, VLE16FF_V, VLE32FF_V, VLE64FF_V],
+        [VLSEG2E8FF_V, VLSEG2E16FF_V, VLSEG2E32FF_V, VLSEG2E64FF_V],
+        [VLSEG3E8FF_V, VLSEG3E16FF_V, VLSEG3E32FF_V, VLSEG3E64FF_V],
+        [VLSEG4E8FF_V, VLSEG4E16FF_V, VLSEG4E32FF_V, VLSEG4E64FF_V],
+        [VLSEG5E8FF_V, VLSEG5E16FF_V, VLSEG5E32FF_V, VLSEG5E64FF_V],
+        [VLSEG6E8FF_V, VLSEG6E16FF_V, VLSEG6E32FF_V, VLSEG6E64FF_V],
+        [VLSEG7E8FF_V, VLSEG7E16FF_V, VLSEG7E32FF_V, VLSEG7E64FF_V],
+        [VLSEG8E8FF_V, VLSEG8E16FF_V, VLSEG8E32FF_V, VLSEG8E64FF_V],
+    ];
+    const WHOLE_LOADS: [[Opcode; 4]; 4] = [
+        [VL1RE8_V, VL1RE16_V, VL1RE32_V, VL1RE64_V],
+        [VL2RE8_V, VL2RE16_V, VL2RE32_V, VL2RE64_V],
+        [VL4RE8_V, VL4RE16_V, VL4RE32_V, VL4RE64_V],
+        [VL8RE8_V, VL8RE16_V, VL8RE32_V, VL8RE64_V],
+    ];
+    const WHOLE_STORES: [Opcode; 4] = [VS1R_V, VS2R_V, VS4R_V, VS8R_V];
+
+    let eew = match dword >> 12 & 0b111 {
+        0b000 => 0,
+        0b101 => 1,
+        0b110 => 2,
+        0b111 => 3,
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    // elements wider than 64 bits are reserved
+    if dword >> 28 & 0b1 != 0 {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    let nf = dword >> 29;
+    let masked = dword >> 25 & 0b1 == 0;
+    let field = dword >> 20 & 0b11111;
+    let vd = Operand::Register(Register::get_vec(dword >> 7 & 0b11111)?);
+    let rs1 = Operand::Register(Register::get(dword >> 15 & 0b11111)?);
+    let dir = store as usize;
+    // segment loads and stores access `nf + 1` fields per element
+    let fields = nf as usize;
+
+    let (opcode, stride) = match (dword >> 26 & 0b11, field) {
+        // whole register loads and stores
+        (0b00, 0b01000) if !masked => {
+            let count = match nf {
+                0 => 0,
+                1 => 1,
+                3 => 2,
+                7 => 3,
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            match store {
+                false => (WHOLE_LOADS[count][eew], None),
+                true if eew == 0 => (WHOLE_STORES[count], None),
+                true => return Err(ErrorKind::InvalidOpcode),
+            }
+        }
+        (0b00, 0b01011) if !masked && eew == 0 && nf == 0 => {
+            (if store { VSM_V } else { VLM_V }, None)
+        }
+        (0b00, 0b00000) => (UNIT[dir][fields][eew], None),
+        (0b00, 0b10000) if !store => (FAULT_ONLY_FIRST[fields][eew], None),
+        (0b01, _) => (UNORDERED[dir][fields][eew], Some(Register::get_vec(field)?)),
+        (0b10, _) => (STRIDED[dir][fields][eew], Some(Register::get(field)?)),
+        (0b11, _) => (ORDERED[dir][fields][eew], Some(Register::get_vec(field)?)),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    let mut operands = vec![vd, rs1];
+    operands.extend(stride.map(Operand::Register));
+    if masked {
+        operands.push(Operand::Mask);
+    }
+
+    instruction(opcode, &operands)
+}
+
+/// Whether the vector-immediate form of an instruction takes an unsigned immediate.
+fn is_unsigned(funct6: u32) -> bool {
+    matches!(
+        funct6,
+        0b001100 | 0b001110 | 0b001111 | 0b100101 | 0b101000..=0b101111
+    )
+}
+
+/// Multiply-add instructions list their multiplicands as `vs1, vs2` rather than `vs2, vs1`.
+fn is_multiply_add(funct6: u32, category: u32) -> bool {
+    match category {
+        OPMVV | OPMVX => matches!(
+            funct6,
+            0b101001 | 0b101011 | 0b101101 | 0b101111 | 0b111100..
+        ),
+        OPFVV | OPFVF => matches!(funct6, 0b101000..=0b101111 | 0b111100..),
+        _ => false,
+    }
+}
+
+/// Decode's instructions in the OP-V opcode.
+pub(crate) fn decode_arith(dword: u32) -> Result<Instruction, ErrorKind> {
+    let funct6 = dword >> 26;
+    let category = dword >> 12 & 0b111;
+    let masked = dword >> 25 & 0b1 == 0;
+    let src = dword >> 15 & 0b11111;
+
+    if category == OPCFG {
+        return decode_config(dword);
+    }
+
+    let vd = Operand::Register(Register::get_vec(dword >> 7 & 0b11111)?);
+    let vs2 = Operand::Register(Register::get_vec(dword >> 20 & 0b11111)?);
+
+    // the operand in place of vs1 for the vector-scalar and vector-immediate forms
+    let source = match category {
+        OPIVV | OPMVV | OPFVV => Operand::Register(Register::get_vec(src)?),
+        OPIVX | OPMVX => Operand::Register(Register::get(src)?),
+        OPFVF => Operand::Register(Register::get_float(src)?),
+        _ if is_unsigned(funct6) => Operand::Immediate(src as i32),
+        _ => Operand::Immediate((src as i32) << 27 >> 27),
+    };
+
+    match (funct6, category) {
+        (0b010000..=0b010011 | 0b010111, OPIVV | OPIVX | OPIVI) | (0b010111, OPFVF) => {
+            return decode_merge(dword, vd, vs2, source)
+        }
+        (0b100111, OPIVI) if !masked => {
+            let opcode = match src {
+                0 => Opcode::VMV1R_V,
+                1 => Opcode::VMV2R_V,
+                3 => Opcode::VMV4R_V,
+                7 => Opcode::VMV8R_V,
+                _ => Opcode::Invalid,
+            };
+
+            return instruction(opcode, &[vd, vs2]);
+        }
+        (0b010000, OPMVV | OPMVX | OPFVV | OPFVF)
+        | (0b010010 | 0b010100, OPMVV)
+        | (0b010010 | 0b010011, OPFVV) => return decode_unary(dword, vd, vs2),
+        _ => {}
+    }
+
+    // mask-register logical instructions and vcompress are never masked
+    if masked && category == OPMVV && matches!(funct6, 0b010111..=0b011111) {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    let mut operands = match is_multiply_add(funct6, category) {
+        true => vec![vd, source, vs2],
+        false => vec![vd, vs2, source],
+    };
+
+    if masked {
+        operands.push(Operand::Mask);
+    }
+
+    instruction(opcode(funct6, category), &operands)
+}
+
+/// Decode's add-with-carry, subtract-with-borrow and merge instructions, which use `v0` as an
+/// operand rather than as a mask.
+fn decode_merge(
+    dword: u32,
+    vd: Operand,
+    vs2: Operand,
+    source: Operand,
+) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let funct6 = dword >> 26;
+    let category = dword >> 12 & 0b111;
+    let masked = dword >> 25 & 0b1 == 0;
+    let v0 = Operand::Register(Register::V0);
+
+    // the form taking `v0` and the form that doesn't
+    let (with_carry, without_carry) = match (funct6, category) {
+        (0b010000, OPIVV) => (VADC_VVM, Invalid),
+        (0b010000, OPIVX) => (VADC_VXM, Invalid),
+        (0b010000, OPIVI) => (VADC_VIM, Invalid),
+        (0b010001, OPIVV) => (VMADC_VVM, VMADC_VV),
+        (0b010001, OPIVX) => (VMADC_VXM, VMADC_VX),
+        (0b010001, OPIVI) => (VMADC_VIM, VMADC_VI),
+        (0b010010, OPIVV) => (VSBC_VVM, Invalid),
+        (0b010010, OPIVX) => (VSBC_VXM, Invalid),
+        (0b010011, OPIVV) => (VMSBC_VVM, VMSBC_VV),
+        (0b010011, OPIVX) => (VMSBC_VXM, VMSBC_VX),
+        (0b010111, OPIVV) => (VMERGE_VVM, VMV_V_V),
+        (0b010111, OPIVX) => (VMERGE_VXM, VMV_V_X),
+        (0b010111, OPIVI) => (VMERGE_VIM, VMV_V_I),
+        (0b010111, OPFVF) => (VFMERGE_VFM, VFMV_V_F),
+        _ => (Invalid, Invalid),
+    };
+
+    if masked {
+        return instruction(with_carry, &[vd, vs2, source, v0]);
+    }
+
+    // moves are merges without a mask, they don't have a vs2
+    if funct6 == 0b010111 {
+        if vs2 != v0 {
+            return Err(ErrorKind::InvalidOpcode);
+        }
+
+        return instruction(without_carry, &[vd, source]);
+    }
+
+    instruction(without_carry, &[vd, vs2, source])
+}
+
+/// Decode's instructions that encode their operation in the vs1 or vs2 field.
+fn decode_unary(dword: u32, vd: Operand, vs2: Operand) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let funct6 = dword >> 26;
+    let category = dword >> 12 & 0b111;
+    let masked = dword >> 25 & 0b1 == 0;
+    let rd = dword >> 7 & 0b11111;
+    let src = dword >> 15 & 0b11111;
+    let mask = masked.then_some(Operand::Mask);
+
+    let (opcode, operands) = match (funct6, category, src) {
+        // moves between the first element and a scalar register
+        (0b010000, OPMVV, 0b00000) if !masked => {
+            (VMV_X_S, vec![Operand::Register(Register::get(rd)?), vs2])
+        }
+        (0b010000, OPFVV, 0b00000) if !masked => (
+            VFMV_F_S,
+            vec![Operand::Register(Register::get_float(rd)?), vs2],
+        ),
+        (0b010000, OPMVX, _) if !masked && dword >> 20 & 0b11111 == 0 => {
+            (VMV_S_X, vec![vd, Operand::Register(Register::get(src)?)])
+        }
+        (0b010000, OPFVF, _) if !masked && dword >> 20 & 0b11111 == 0 => (
+            VFMV_S_F,
+            vec![vd, Operand::Register(Register::get_float(src)?)],
+        ),
+        // mask population count and find-first-set, these write a scalar register
+        (0b010000, OPMVV, 0b10000 | 0b10001) => {
+            let opcode = if src == 0b10000 { VCPOP_M } else { VFIRST_M };
+            let rd = Operand::Register(Register::get(rd)?);
+            (opcode, [rd, vs2].into_iter().chain(mask).collect())
+        }
+        (0b010100, OPMVV, 0b10001) if dword >> 20 & 0b11111 == 0 => {
+            (VID_V, [vd].into_iter().chain(mask).collect())
+        }
+        _ => {
+            let opcode = match (funct6, category, src) {
+                (0b010010, OPMVV, 0b00010) => VZEXT_VF8,
+                (0b010010, OPMVV, 0b00011) => VSEXT_VF8,
+                (0b010010, OPMVV, 0b00100) => VZEXT_VF4,
+                (0b010010, OPMVV, 0b00101) => VSEXT_VF4,
+                (0b010010, OPMVV, 0b00110) => VZEXT_VF2,
+                (0b010010, OPMVV, 0b00111) => VSEXT_VF2,
+                (0b010100, OPMVV, 0b00001) => VMSBF_M,
+                (0b010100, OPMVV, 0b00010) => VMSOF_M,
+                (0b010100, OPMVV, 0b00011) => VMSIF_M,
+                (0b010100, OPMVV, 0b10000) => VIOTA_M,
+                (0b010010, OPFVV, 0b00000) => VFCVT_XU_F_V,
+                (0b010010, OPFVV, 0b00001) => VFCVT_X_F_V,
+                (0b010010, OPFVV, 0b00010) => VFCVT_F_XU_V,
+                (0b010010, OPFVV, 0b00011) => VFCVT_F_X_V,
+                (0b010010, OPFVV, 0b00110) => VFCVT_RTZ_XU_F_V,
+                (0b010010, OPFVV, 0b00111) => VFCVT_RTZ_X_F_V,
+                (0b010010, OPFVV, 0b01000) => VFWCVT_XU_F_V,
+                (0b010010, OPFVV, 0b01001) => VFWCVT_X_F_V,
+                (0b010010, OPFVV, 0b01010) => VFWCVT_F_XU_V,
+                (0b010010, OPFVV, 0b01011) => VFWCVT_F_X_V,
+                (0b010010, OPFVV, 0b01100) => VFWCVT_F_F_V,
+                (0b010010, OPFVV, 0b01110) => VFWCVT_RTZ_XU_F_V,
+                (0b010010, OPFVV, 0b01111) => VFWCVT_RTZ_X_F_V,
+                (0b010010, OPFVV, 0b10000) => VFNCVT_XU_F_W,
+                (0b010010, OPFVV, 0b10001) => VFNCVT_X_F_W,
+                (0b010010, OPFVV, 0b10010) => VFNCVT_F_XU_W,
+                (0b010010, OPFVV, 0b10011) => VFNCVT_F_X_W,
+                (0b010010, OPFVV, 0b10100) => VFNCVT_F_F_W,
+                (0b010010, OPFVV, 0b10101) => VFNCVT_ROD_F_F_W,
+                (0b010010, OPFVV, 0b10110) => VFNCVT_RTZ_XU_F_W,
+                (0b010010, OPFVV, 0b10111) => VFNCVT_RTZ_X_F_W,
+                (0b010011, OPFVV, 0b00000) => VFSQRT_V,
+                (0b010011, OPFVV, 0b00100) => VFRSQRT7_V,
+                (0b010011, OPFVV, 0b00101) => VFREC7_V,
+                (0b010011, OPFVV, 0b10000) => VFCLASS_V,
+                _ => Invalid,
+            };
+
+            (opcode, [vd, vs2].into_iter().chain(mask).collect())
+        }
+    };
+
+    instruction(opcode, &operands)
+}
+
+/// Opcodes of OP-V instructions that take a destination, vs2 and a vector, scalar or immediate
+/// operand.
+#[rustfmt::skip]
+fn opcode(funct6: u32, category: u32) -> Opcode {
+    use Opcode::*;
+
+    match (funct6, category) {
+        (0b000000, OPIVV) => VADD_VV,
+        (0b000000, OPIVX) => VADD_VX,
+        (0b000000, OPIVI) => VADD_VI,
+        (0b000010, OPIVV) => VSUB_VV,
+        (0b000010, OPIVX) => VSUB_VX,
+        (0b000011, OPIVX) => VRSUB_VX,
+        (0b000011, OPIVI) => VRSUB_VI,
+        (0b000100, OPIVV) => VMINU_VV,
+        (0b000100, OPIVX) => VMINU_VX,
+        (0b000101, OPIVV) => VMIN_VV,
+        (0b000101, OPIVX) => VMIN_VX,
+        (0b000110, OPIVV) => VMAXU_VV,
+        (0b000110, OPIVX) => VMAXU_VX,
+        (0b000111, OPIVV) => VMAX_VV,
+        (0b000111, OPIVX) => VMAX_VX,
+        (0b001001, OPIVV) => VAND_VV,
+        (0b001001, OPIVX) => VAND_VX,
+        (0b001001, OPIVI) => VAND_VI,
+        (0b001010, OPIVV) => VOR_VV,
+        (0b001010, OPIVX) => VOR_VX,
+        (0b001010, OPIVI) => VOR_VI,
+        (0b001011, OPIVV) => VXOR_VV,
+        (0b001011, OPIVX) => VXOR_VX,
+        (0b001011, OPIVI) => VXOR_VI,
+        (0b001100, OPIVV) => VRGATHER_VV,
+        (0b001100, OPIVX) => VRGATHER_VX,
+        (0b001100, OPIVI) => VRGATHER_VI,
+        (0b001110, OPIVV) => VRGATHEREI16_VV,
+        (0b001110, OPIVX) => VSLIDEUP_VX,
+        (0b001110, OPIVI) => VSLIDEUP_VI,
+        (0b001111, OPIVX) => VSLIDEDOWN_VX,
+        (0b001111, OPIVI) => VSLIDEDOWN_VI,
+        (0b011000, OPIVV) => VMSEQ_VV,
+        (0b011000, OPIVX) => VMSEQ_VX,
+        (0b011000, OPIVI) => VMSEQ_VI,
+        (0b011001, OPIVV) => VMSNE_VV,
+        (0b011001, OPIVX) => VMSNE_VX,
+        (0b011001, OPIVI) => VMSNE_VI,
+        (0b011010, OPIVV) => VMSLTU_VV,
+        (0b011010, OPIVX) => VMSLTU_VX,
+        (0b011011, OPIVV) => VMSLT_VV,
+        (0b011011, OPIVX) => VMSLT_VX,
+        (0b011100, OPIVV) => VMSLEU_VV,
+        (0b011100, OPIVX) => VMSLEU_VX,
+        (0b011100, OPIVI) => VMSLEU_VI,
+        (0b011101, OPIVV) => VMSLE_VV,
+        (0b011101, OPIVX) => VMSLE_VX,
+        (0b011101, OPIVI) => VMSLE_VI,
+        (0b011110, OPIVX) => VMSGTU_VX,
+        (0b011110, OPIVI) => VMSGTU_VI,
+        (0b011111, OPIVX) => VMSGT_VX,
+        (0b011111, OPIVI) => VMSGT_VI,
+        (0b100000, OPIVV) => VSADDU_VV,
+        (0b100000, OPIVX) => VSADDU_VX,
+        (0b100000, OPIVI) => VSADDU_VI,
+        (0b100001, OPIVV) => VSADD_VV,
+        (0b100001, OPIVX) => VSADD_VX,
+        (0b100001, OPIVI) => VSADD_VI,
+        (0b100010, OPIVV) => VSSUBU_VV,
+        (0b100010, OPIVX) => VSSUBU_VX,
+        (0b100011, OPIVV) => VSSUB_VV,
+        (0b100011, OPIVX) => VSSUB_VX,
+        (0b100101, OPIVV) => VSLL_VV,
+        (0b100101, OPIVX) => VSLL_VX,
+        (0b100101, OPIVI) => VSLL_VI,
+        (0b100111, OPIVV) => VSMUL_VV,
+        (0b100111, OPIVX) => VSMUL_VX,
+        (0b101000, OPIVV) => VSRL_VV,
+        (0b101000, OPIVX) => VSRL_VX,
+        (0b101000, OPIVI) => VSRL_VI,
+        (0b101001, OPIVV) => VSRA_VV,
+        (0b101001, OPIVX) => VSRA_VX,
+        (0b101001, OPIVI) => VSRA_VI,
+        (0b101010, OPIVV) => VSSRL_VV,
+        (0b101010, OPIVX) => VSSRL_VX,
+        (0b101010, OPIVI) => VSSRL_VI,
+        (0b101011, OPIVV) => VSSRA_VV,
+        (0b101011, OPIVX) => VSSRA_VX,
+        (0b101011, OPIVI) => VSSRA_VI,
+        (0b101100, OPIVV) => VNSRL_WV,
+        (0b101100, OPIVX) => VNSRL_WX,
+        (0b101100, OPIVI) => VNSRL_WI,
+        (0b101101, OPIVV) => VNSRA_WV,
+        (0b101101, OPIVX) => VNSRA_WX,
+        (0b101101, OPIVI) => VNSRA_WI,
+        (0b101110, OPIVV) => VNCLIPU_WV,
+        (0b101110, OPIVX) => VNCLIPU_WX,
+        (0b101110, OPIVI) => VNCLIPU_WI,
+        (0b101111, OPIVV) => VNCLIP_WV,
+        (0b101111, OPIVX) => VNCLIP_WX,
+        (0b101111, OPIVI) => VNCLIP_WI,
+        (0b110000, OPIVV) => VWREDSUMU_VS,
+        (0b110001, OPIVV) => VWREDSUM_VS,
+        (0b000000, OPMVV) => VREDSUM_VS,
+        (0b000001, OPMVV) => VREDAND_VS,
+        (0b000010, OPMVV) => VREDOR_VS,
+        (0b000011, OPMVV) => VREDXOR_VS,
+        (0b000100, OPMVV) => VREDMINU_VS,
+        (0b000101, OPMVV) => VREDMIN_VS,
+        (0b000110, OPMVV) => VREDMAXU_VS,
+        (0b000111, OPMVV) => VREDMAX_VS,
+        (0b001000, OPMVV) => VAADDU_VV,
+        (0b001000, OPMVX) => VAADDU_VX,
+        (0b001001, OPMVV) => VAADD_VV,
+        (0b001001, OPMVX) => VAADD_VX,
+        (0b001010, OPMVV) => VASUBU_VV,
+        (0b001010, OPMVX) => VASUBU_VX,
+        (0b001011, OPMVV) => VASUB_VV,
+        (0b001011, OPMVX) => VASUB_VX,
+        (0b001110, OPMVX) => VSLIDE1UP_VX,
+        (0b001111, OPMVX) => VSLIDE1DOWN_VX,
+        (0b010111, OPMVV) => VCOMPRESS_VM,
+        (0b011000, OPMVV) => VMANDN_MM,
+        (0b011001, OPMVV) => VMAND_MM,
+        (0b011010, OPMVV) => VMOR_MM,
+        (0b011011, OPMVV) => VMXOR_MM,
+        (0b011100, OPMVV) => VMORN_MM,
+        (0b011101, OPMVV) => VMNAND_MM,
+        (0b011110, OPMVV) => VMNOR_MM,
+        (0b011111, OPMVV) => VMXNOR_MM,
+        (0b100000, OPMVV) => VDIVU_VV,
+        (0b100000, OPMVX) => VDIVU_VX,
+        (0b100001, OPMVV) => VDIV_VV,
+        (0b100001, OPMVX) => VDIV_VX,
+        (0b100010, OPMVV) => VREMU_VV,
+        (0b100010, OPMVX) => VREMU_VX,
+        (0b100011, OPMVV) => VREM_VV,
+        (0b100011, OPMVX) => VREM_VX,
+        (0b100100, OPMVV) => VMULHU_VV,
+        (0b100100, OPMVX) => VMULHU_VX,
+        (0b100101, OPMVV) => VMUL_VV,
+        (0b100101, OPMVX) => VMUL_VX,
+        (0b100110, OPMVV) => VMULHSU_VV,
+        (0b100110, OPMVX) => VMULHSU_VX,
+        (0b100111, OPMVV) => VMULH_VV,
+        (0b100111, OPMVX) => VMULH_VX,
+        (0b101001, OPMVV) => VMADD_VV,
+        (0b101001, OPMVX) => VMADD_VX,
+        (0b101011, OPMVV) => VNMSUB_VV,
+        (0b101011, OPMVX) => VNMSUB_VX,
+        (0b101101, OPMVV) => VMACC_VV,
+        (0b101101, OPMVX) => VMACC_VX,
+        (0b101111, OPMVV) => VNMSAC_VV,
+        (0b101111, OPMVX) => VNMSAC_VX,
+        (0b110000, OPMVV) => VWADDU_VV,
+        (0b110000, OPMVX) => VWADDU_VX,
+        (0b110001, OPMVV) => VWADD_VV,
+        (0b110001, OPMVX) => VWADD_VX,
+        (0b110010, OPMVV) => VWSUBU_VV,
+        (0b110010, OPMVX) => VWSUBU_VX,
+        (0b110011, OPMVV) => VWSUB_VV,
+        (0b110011, OPMVX) => VWSUB_VX,
+        (0b110100, OPMVV) => VWADDU_WV,
+        (0b110100, OPMVX) => VWADDU_WX,
+        (0b110101, OPMVV) => VWADD_WV,
+        (0b110101, OPMVX) => VWADD_WX,
+        (0b110110, OPMVV) => VWSUBU_WV,
+        (0b110110, OPMVX) => VWSUBU_WX,
+        (0b110111, OPMVV) => VWSUB_WV,
+        (0b110111, OPMVX) => VWSUB_WX,
+        (0b111000, OPMVV) => VWMULU_VV,
+        (0b111000, OPMVX) => VWMULU_VX,
+        (0b111010, OPMVV) => VWMULSU_VV,
+        (0b111010, OPMVX) => VWMULSU_VX,
+        (0b111011, OPMVV) => VWMUL_VV,
+        (0b111011, OPMVX) => VWMUL_VX,
+        (0b111100, OPMVV) => VWMACCU_VV,
+        (0b111100, OPMVX) => VWMACCU_VX,
+        (0b111101, OPMVV) => VWMACC_VV,
+        (0b111101, OPMVX) => VWMACC_VX,
+        (0b111110, OPMVX) => VWMACCUS_VX,
+        (0b111111, OPMVV) => VWMACCSU_VV,
+        (0b111111, OPMVX) => VWMACCSU_VX,
+        (0b000000, OPFVV) => VFADD_VV,
+        (0b000000, OPFVF) => VFADD_VF,
+        (0b000001, OPFVV) => VFREDUSUM_VS,
+        (0b000010, OPFVV) => VFSUB_VV,
+        (0b000010, OPFVF) => VFSUB_VF,
+        (0b000011, OPFVV) => VFREDOSUM_VS,
+        (0b000100, OPFVV) => VFMIN_VV,
+        (0b000100, OPFVF) => VFMIN_VF,
+        (0b000101, OPFVV) => VFREDMIN_VS,
+        (0b000110, OPFVV) => VFMAX_VV,
+        (0b000110, OPFVF) => VFMAX_VF,
+        (0b000111, OPFVV) => VFREDMAX_VS,
+        (0b001000, OPFVV) => VFSGNJ_VV,
+        (0b001000, OPFVF) => VFSGNJ_VF,
+        (0b001001, OPFVV) => VFSGNJN_VV,
+        (0b001001, OPFVF) => VFSGNJN_VF,
+        (0b001010, OPFVV) => VFSGNJX_VV,
+        (0b001010, OPFVF) => VFSGNJX_VF,
+        (0b001110, OPFVF) => VFSLIDE1UP_VF,
+        (0b001111, OPFVF) => VFSLIDE1DOWN_VF,
+        (0b011000, OPFVV) => VMFEQ_VV,
+        (0b011000, OPFVF) => VMFEQ_VF,
+        (0b011001, OPFVV) => VMFLE_VV,
+        (0b011001, OPFVF) => VMFLE_VF,
+        (0b011011, OPFVV) => VMFLT_VV,
+        (0b011011, OPFVF) => VMFLT_VF,
+        (0b011100, OPFVV) => VMFNE_VV,
+        (0b011100, OPFVF) => VMFNE_VF,
+        (0b011101, OPFVF) => VMFGT_VF,
+        (0b011111, OPFVF) => VMFGE_VF,
+        (0b100000, OPFVV) => VFDIV_VV,
+        (0b100000, OPFVF) => VFDIV_VF,
+        (0b100001, OPFVF) => VFRDIV_VF,
+        (0b100100, OPFVV) => VFMUL_VV,
+        (0b100100, OPFVF) => VFMUL_VF,
+        (0b100111, OPFVF) => VFRSUB_VF,
+        (0b101000, OPFVV) => VFMADD_VV,
+        (0b101000, OPFVF) => VFMADD_VF,
+        (0b101001, OPFVV) => VFNMADD_VV,
+        (0b101001, OPFVF) => VFNMADD_VF,
+        (0b101010, OPFVV) => VFMSUB_VV,
+        (0b101010, OPFVF) => VFMSUB_VF,
+        (0b101011, OPFVV) => VFNMSUB_VV,
+        (0b101011, OPFVF) => VFNMSUB_VF,
+        (0b101100, OPFVV) => VFMACC_VV,
+        (0b101100, OPFVF) => VFMACC_VF,
+        (0b101101, OPFVV) => VFNMACC_VV,
+        (0b101101, OPFVF) => VFNMACC_VF,
+        (0b101110, OPFVV) => VFMSAC_VV,
+        (0b101110, OPFVF) => VFMSAC_VF,
+        (0b101111, OPFVV) => VFNMSAC_VV,
+        (0b101111, OPFVF) => VFNMSAC_VF,
+        (0b110000, OPFVV) => VFWADD_VV,
+        (0b110000, OPFVF) => VFWADD_VF,
+        (0b110001, OPFVV) => VFWREDUSUM_VS,
+        (0b110010, OPFVV) => VFWSUB_VV,
+        (0b110010, OPFVF) => VFWSUB_VF,
+        (0b110011, OPFVV) => VFWREDOSUM_VS,
+        (0b110100, OPFVV) => VFWADD_WV,
+        (0b110100, OPFVF) => VFWADD_WF,
+        (0b110110, OPFVV) => VFWSUB_WV,
+        (0b110110, OPFVF) => VFWSUB_WF,
+        (0b111000, OPFVV) => VFWMUL_VV,
+        (0b111000, OPFVF) => VFWMUL_VF,
+        (0b111100, OPFVV) => VFWMACC_VV,
+        (0b111100, OPFVF) => VFWMACC_VF,
+        (0b111101, OPFVV) => VFWNMACC_VV,
+        (0b111101, OPFVF) => VFWNMACC_VF,
+        (0b111110, OPFVV) => VFWMSAC_VV,
+        (0b111110, OPFVF) => VFWMSAC_VF,
+        (0b111111, OPFVV) => VFWNMSAC_VV,
+        (0b111111, OPFVF) => VFWNMSAC_VF,
+        _ => Invalid,
+    }
+}
@@ -0,0 +1,59 @@
+//! Semantic queries on decoded instructions: control flow and memory accesses.
+//!
+//! WebAssembly is a stack machine, locals and globals aren't reported as registers. Branches name
+//! their target by how many blocks they break out of, so only calls have known targets.
+
+use crate::{Function, Instruction, Operand};
+use decoder::{Direction, FlowKind, MemoryAccess};
+
+pub(crate) fn flow(inst: &Instruction) -> FlowKind {
+    match inst.mnemomic {
+        "br"
+        | "br_table"
+        | "else"
+        | "return_call"
+        | "return_call_indirect"
+        | "return_call_ref"
+        | "delegate" => FlowKind::Jump,
+        "br_if" | "if" => FlowKind::ConditionalJump,
+        "call" | "call_indirect" | "call_ref" => FlowKind::Call,
+        "return" | "throw" | "rethrow" => FlowKind::Return,
+        "unreachable" => FlowKind::Halt,
+        _ => FlowKind::Sequential,
+    }
+}
+
+pub(crate) fn targets(inst: &Instruction) -> Vec<usize> {
+    if !matches!(inst.mnemomic, "call" | "return_call") {
+        return Vec::new();
+    }
+
+    inst.operands
+        .iter()
+        .filter_map(|operand| match *operand {
+            Operand::Function(Function::Local(addr)) => Some(addr),
+            _ => None,
+        })
+        .collect()
+}
+
+pub(crate) fn memory(inst: &Instruction) -> Option<MemoryAccess> {
+    if inst.size == 0 {
+        return None;
+    }
+
+    let mnemomic = inst.mnemomic;
+    let direction = if mnemomic.contains(".rmw") {
+        Direction::ReadWrite
+    } else if mnemomic.contains(".store") {
+        Direction::Write
+    } else {
+        // loads and the wait and notify operations
+        Direction::Read
+    };
+
+    Some(MemoryAccess {
+        size: Some(inst.size as usize),
+        direction,
+    })
+}
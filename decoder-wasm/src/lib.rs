@@ -0,0 +1,433 @@
+//! WebAssembly bytecode disassembler, covering the MVP instruction set and the sign extension,
+//! non-trapping conversion, bulk memory, reference types, tail call, exception handling, SIMD
+//! (including relaxed SIMD) and threads proposals.
+//!
+//! Instructions are printed like the text format, with calls to functions defined in the module
+//! resolved to their body's address.
+
+mod behavior;
+mod memory;
+mod numeric;
+mod simd;
+mod tests;
+
+use debugvault::Index;
+use decoder::{Error, ErrorKind, FlowKind, MemoryAccess, Reader, ToTokens};
+use std::sync::Arc;
+use tokenizing::{ColorScheme, Colors, TokenStream};
+
+/// Function in a module's index space, imported functions come before those defined in the
+/// module.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Function {
+    /// Imported function, by it's field name.
+    Import(Arc<str>),
+    /// Function defined in the module, by the address of it's body.
+    Local(usize),
+}
+
+/// Type of a block's results.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockType {
+    Empty,
+    Value(&'static str),
+    /// Index of a function type, for blocks with parameters or multiple results.
+    Type(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Immediate(i64),
+    Float(f64),
+    /// Index of a local variable.
+    Local(u32),
+    /// Index into one of the index spaces other than functions and locals.
+    Index(u32),
+    /// Label, by the number of blocks to break out of.
+    Depth(u32),
+    /// Labels of a `br_table`, the last being the default.
+    Table(Arc<[u32]>),
+    Function(Function),
+    Block(BlockType),
+    /// Value or reference type.
+    Type(&'static str),
+    /// Memory immediate of a load or store.
+    Memory {
+        offset: u64,
+        align: u32,
+    },
+    /// Lane of a vector.
+    Lane(u8),
+    V128([u8; 16]),
+    /// Lanes selected by `i8x16.shuffle`.
+    Shuffle([u8; 16]),
+}
+
+impl ToTokens for Operand {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        match *self {
+            Self::Immediate(imm) => {
+                // `encode_hex` can't negate the smallest 64-bit constant
+                let imm = match imm {
+                    i64::MIN => format!("-{:#x}", imm as u64),
+                    _ => decoder::encode_hex(imm),
+                };
+                stream.push_owned(imm, Colors::immediate())
+            }
+            Self::Float(imm) => stream.push_owned(format!("{imm}"), Colors::immediate()),
+            Self::Local(idx) => stream.push_owned(idx.to_string(), Colors::register()),
+            Self::Index(idx) | Self::Depth(idx) => {
+                stream.push_owned(idx.to_string(), Colors::immediate())
+            }
+            Self::Table(ref labels) => {
+                for (idx, label) in labels.iter().enumerate() {
+                    if idx != 0 {
+                        stream.push(" ", Colors::spacing());
+                    }
+                    stream.push_owned(label.to_string(), Colors::immediate());
+                }
+            }
+            Self::Function(Function::Local(addr)) => match symbols.get_sym_by_addr(addr) {
+                Some(symbol) => {
+                    for token in symbol.name() {
+                        stream.push_token(token.clone());
+                    }
+                }
+                None => stream.push_owned(format!("{addr:#x}"), Colors::immediate()),
+            },
+            Self::Function(Function::Import(ref name)) => {
+                stream.push_owned(name.to_string(), Colors::item())
+            }
+            Self::Block(BlockType::Empty) => {}
+            Self::Block(BlockType::Value(ty)) => {
+                stream.push("(", Colors::brackets());
+                stream.push("result ", Colors::expr());
+                stream.push(ty, Colors::item());
+                stream.push(")", Colors::brackets());
+            }
+            Self::Block(BlockType::Type(idx)) => {
+                stream.push("(", Colors::brackets());
+                stream.push("type ", Colors::expr());
+                stream.push_owned(idx.to_string(), Colors::immediate());
+                stream.push(")", Colors::brackets());
+            }
+            Self::Type(ty) => stream.push(ty, Colors::item()),
+            Self::Memory { .. } => unreachable!("memory operands are tokenized by instructions"),
+            Self::Lane(lane) => stream.push_owned(lane.to_string(), Colors::immediate()),
+            Self::V128(bytes) => {
+                let value = u128::from_le_bytes(bytes);
+                stream.push("i32x4 ", Colors::item());
+                for lane in 0..4 {
+                    if lane != 0 {
+                        stream.push(" ", Colors::spacing());
+                    }
+                    let lane = (value >> (lane * 32)) as u32;
+                    stream.push_owned(format!("{lane:#x}"), Colors::immediate());
+                }
+            }
+            Self::Shuffle(lanes) => {
+                for (idx, lane) in lanes.iter().enumerate() {
+                    if idx != 0 {
+                        stream.push(" ", Colors::spacing());
+                    }
+                    stream.push_owned(lane.to_string(), Colors::immediate());
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    mnemomic: &'static str,
+    operands: Vec<Operand>,
+    /// Number of bytes accessed by a load, store or atomic operation.
+    size: u8,
+    width: usize,
+}
+
+impl Instruction {
+    fn new(mnemomic: &'static str, operands: Vec<Operand>) -> Self {
+        Self {
+            mnemomic,
+            operands,
+            size: 0,
+            width: 0,
+        }
+    }
+
+    pub fn mnemomic(&self) -> &'static str {
+        self.mnemomic
+    }
+
+    pub fn operands(&self) -> &[Operand] {
+        &self.operands
+    }
+}
+
+impl ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        stream.push(self.mnemomic, Colors::opcode());
+
+        for operand in self.operands.iter() {
+            match *operand {
+                Operand::Block(BlockType::Empty) => continue,
+                // the text format leaves out a zero offset and the natural alignment
+                Operand::Memory { offset, align } => {
+                    if offset != 0 {
+                        stream.push(" offset", Colors::expr());
+                        stream.push("=", Colors::expr());
+                        stream.push_owned(format!("{offset:#x}"), Colors::immediate());
+                    }
+                    if 1 << align != self.size as u64 {
+                        stream.push(" align", Colors::expr());
+                        stream.push("=", Colors::expr());
+                        stream.push_owned((1u64 << align).to_string(), Colors::immediate());
+                    }
+                }
+                _ => {
+                    stream.push(" ", Colors::spacing());
+                    operand.tokenize(stream, symbols);
+                }
+            }
+        }
+    }
+}
+
+impl decoder::Decoded for Instruction {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn update_rel_addrs(&mut self, _: usize, _: Option<&Instruction>) {}
+
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
+    }
+
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
+
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
+}
+
+/// WebAssembly decoder, calls are resolved using the module's function index space.
+#[derive(Debug, Default, Clone)]
+pub struct Decoder {
+    pub functions: Vec<Function>,
+}
+
+impl decoder::Decodable for Decoder {
+    type Instruction = Instruction;
+
+    fn decode(&self, reader: &mut Reader) -> Result<Self::Instruction, Error> {
+        reader.mark();
+
+        match self.decode_inst(reader) {
+            Ok(mut inst) => {
+                inst.width = reader.offset();
+                Ok(inst)
+            }
+            Err(err) => Err(Error::new(err, reader.offset().clamp(1, u8::MAX as usize))),
+        }
+    }
+
+    /// Instructions are variable length, this is the most that's shown of an instruction's bytes.
+    fn max_width(&self) -> usize {
+        16
+    }
+}
+
+impl Decoder {
+    fn decode_inst(&self, reader: &mut Reader) -> Result<Instruction, ErrorKind> {
+        let opcode = reader.next().ok_or(ErrorKind::ExhaustedInput)?;
+
+        let inst = match opcode {
+            0x00 => Instruction::new("unreachable", vec![]),
+            0x01 => Instruction::new("nop", vec![]),
+            0x02 => Instruction::new("block", vec![block_type(reader)?]),
+            0x03 => Instruction::new("loop", vec![block_type(reader)?]),
+            0x04 => Instruction::new("if", vec![block_type(reader)?]),
+            0x05 => Instruction::new("else", vec![]),
+            0x06 => Instruction::new("try", vec![block_type(reader)?]),
+            0x07 => Instruction::new("catch", vec![index(reader)?]),
+            0x08 => Instruction::new("throw", vec![index(reader)?]),
+            0x09 => Instruction::new("rethrow", vec![depth(reader)?]),
+            0x0b => Instruction::new("end", vec![]),
+            0x0c => Instruction::new("br", vec![depth(reader)?]),
+            0x0d => Instruction::new("br_if", vec![depth(reader)?]),
+            0x0e => {
+                let count = u32(reader)?;
+                let mut labels = Vec::new();
+                // the default label follows the others
+                for _ in 0..=count {
+                    labels.push(u32(reader)?);
+                }
+                Instruction::new("br_table", vec![Operand::Table(Arc::from(labels))])
+            }
+            0x0f => Instruction::new("return", vec![]),
+            0x10 => Instruction::new("call", vec![self.function(reader)?]),
+            0x11 => Instruction::new("call_indirect", call_indirect(reader)?),
+            0x12 => Instruction::new("return_call", vec![self.function(reader)?]),
+            0x13 => Instruction::new("return_call_indirect", call_indirect(reader)?),
+            0x14 => Instruction::new("call_ref", vec![index(reader)?]),
+            0x15 => Instruction::new("return_call_ref", vec![index(reader)?]),
+            0x18 => Instruction::new("delegate", vec![depth(reader)?]),
+            0x19 => Instruction::new("catch_all", vec![]),
+            0x1a => Instruction::new("drop", vec![]),
+            0x1b => Instruction::new("select", vec![]),
+            0x1c => {
+                let count = u32(reader)?;
+                let mut types = Vec::new();
+                for _ in 0..count {
+                    types.push(Operand::Type(value_type(byte(reader)?)?));
+                }
+                Instruction::new("select", types)
+            }
+            0x20 => Instruction::new("local.get", vec![Operand::Local(u32(reader)?)]),
+            0x21 => Instruction::new("local.set", vec![Operand::Local(u32(reader)?)]),
+            0x22 => Instruction::new("local.tee", vec![Operand::Local(u32(reader)?)]),
+            0x23 => Instruction::new("global.get", vec![index(reader)?]),
+            0x24 => Instruction::new("global.set", vec![index(reader)?]),
+            0x25 => Instruction::new("table.get", vec![index(reader)?]),
+            0x26 => Instruction::new("table.set", vec![index(reader)?]),
+            0x28..=0x3e => memory::decode(opcode, reader)?,
+            0x3f => Instruction::new("memory.size", memory::index(reader)?),
+            0x40 => Instruction::new("memory.grow", memory::index(reader)?),
+            0x41 => Instruction::new("i32.const", vec![Operand::Immediate(sleb(reader, 32)?)]),
+            0x42 => Instruction::new("i64.const", vec![Operand::Immediate(sleb(reader, 64)?)]),
+            0x43 => {
+                let mut bytes = [0u8; 4];
+                reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+                let imm = f32::from_le_bytes(bytes) as f64;
+                Instruction::new("f32.const", vec![Operand::Float(imm)])
+            }
+            0x44 => {
+                let mut bytes = [0u8; 8];
+                reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+                let imm = f64::from_le_bytes(bytes);
+                Instruction::new("f64.const", vec![Operand::Float(imm)])
+            }
+            0x45..=0xc4 => Instruction::new(numeric::NUMERIC[opcode as usize - 0x45], vec![]),
+            0xd0 => Instruction::new("ref.null", vec![Operand::Type(value_type(byte(reader)?)?)]),
+            0xd1 => Instruction::new("ref.is_null", vec![]),
+            0xd2 => Instruction::new("ref.func", vec![self.function(reader)?]),
+            0xfc => numeric::decode_misc(u32(reader)?, reader)?,
+            0xfd => simd::decode(u32(reader)?, reader)?,
+            0xfe => memory::decode_atomic(u32(reader)?, reader)?,
+            _ => return Err(ErrorKind::InvalidOpcode),
+        };
+
+        Ok(inst)
+    }
+
+    /// Function by it's index, indices outside of the index space are shown as is.
+    fn function(&self, reader: &mut Reader) -> Result<Operand, ErrorKind> {
+        let idx = u32(reader)?;
+        Ok(match self.functions.get(idx as usize) {
+            Some(function) => Operand::Function(function.clone()),
+            None => Operand::Index(idx),
+        })
+    }
+}
+
+/// Type and table index of an indirect call.
+fn call_indirect(reader: &mut Reader) -> Result<Vec<Operand>, ErrorKind> {
+    let ty = u32(reader)?;
+    let table = u32(reader)?;
+
+    // the table index is left out for the default table
+    let mut operands = vec![Operand::Block(BlockType::Type(ty))];
+    if table != 0 {
+        operands.insert(0, Operand::Index(table));
+    }
+
+    Ok(operands)
+}
+
+fn value_type(byte: u8) -> Result<&'static str, ErrorKind> {
+    Ok(match byte {
+        0x7f => "i32",
+        0x7e => "i64",
+        0x7d => "f32",
+        0x7c => "f64",
+        0x7b => "v128",
+        0x70 => "funcref",
+        0x6f => "externref",
+        0x69 => "exnref",
+        _ => return Err(ErrorKind::InvalidOperand),
+    })
+}
+
+fn block_type(reader: &mut Reader) -> Result<Operand, ErrorKind> {
+    let ty = sleb(reader, 33)?;
+    let ty = match ty {
+        -0x40 => BlockType::Empty,
+        // value types are single byte negative numbers
+        -0x3f..=-1 => BlockType::Value(value_type(ty as u8 & 0x7f)?),
+        _ => BlockType::Type(ty as u32),
+    };
+
+    Ok(Operand::Block(ty))
+}
+
+fn index(reader: &mut Reader) -> Result<Operand, ErrorKind> {
+    Ok(Operand::Index(u32(reader)?))
+}
+
+fn depth(reader: &mut Reader) -> Result<Operand, ErrorKind> {
+    Ok(Operand::Depth(u32(reader)?))
+}
+
+fn byte(reader: &mut Reader) -> Result<u8, ErrorKind> {
+    reader.next().ok_or(ErrorKind::ExhaustedInput)
+}
+
+fn u32(reader: &mut Reader) -> Result<u32, ErrorKind> {
+    let value = uleb(reader, 32)?;
+    Ok(value as u32)
+}
+
+/// Unsigned LEB128 number of at most `bits` bits.
+fn uleb(reader: &mut Reader, bits: u32) -> Result<u64, ErrorKind> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = byte(reader)?;
+        if shift >= bits {
+            return Err(ErrorKind::InvalidOperand);
+        }
+
+        value |= ((byte & 0x7f) as u64) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+/// Signed LEB128 number of at most `bits` bits.
+fn sleb(reader: &mut Reader, bits: u32) -> Result<i64, ErrorKind> {
+    let mut value = 0i64;
+    let mut shift = 0;
+    loop {
+        let byte = byte(reader)?;
+        if shift >= bits {
+            return Err(ErrorKind::InvalidOperand);
+        }
+
+        value |= ((byte & 0x7f) as i64) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                value |= -1 << shift;
+            }
+            return Ok(value);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Loads, stores and the atomic memory operations of the threads proposal.
+
+use crate::{u32, uleb, Instruction, Operand};
+use decoder::{ErrorKind, Reader};
+
+/// Loads and stores with the number of bytes they access.
+#[rustfmt::skip]
+const MEMORY: [(&str, u8); 23] = [
+    ("i32.load", 4), ("i64.load", 8), ("f32.load", 4), ("f64.load", 8),
+    ("i32.load8_s", 1), ("i32.load8_u", 1), ("i32.load16_s", 2), ("i32.load16_u", 2),
+    ("i64.load8_s", 1), ("i64.load8_u", 1), ("i64.load16_s", 2), ("i64.load16_u", 2),
+    ("i64.load32_s", 4), ("i64.load32_u", 4),
+    ("i32.store", 4), ("i64.store", 8), ("f32.store", 4), ("f64.store", 8),
+    ("i32.store8", 1), ("i32.store16", 2), ("i64.store8", 1), ("i64.store16", 2),
+    ("i64.store32", 4),
+];
+
+/// Atomic loads and stores with the number of bytes they access, starting at opcode `0x10`.
+#[rustfmt::skip]
+const ATOMIC: [(&str, u8); 14] = [
+    ("i32.atomic.load", 4), ("i64.atomic.load", 8), ("i32.atomic.load8_u", 1),
+    ("i32.atomic.load16_u", 2), ("i64.atomic.load8_u", 1), ("i64.atomic.load16_u", 2),
+    ("i64.atomic.load32_u", 4),
+    ("i32.atomic.store", 4), ("i64.atomic.store", 8), ("i32.atomic.store8", 1),
+    ("i32.atomic.store16", 2), ("i64.atomic.store8", 1), ("i64.atomic.store16", 2),
+    ("i64.atomic.store32", 4),
+];
+
+/// Read-modify-write operations, each in the same seven widths starting at opcode `0x1e`.
+#[rustfmt::skip]
+const RMW: [[&str; 7]; 7] = [
+    ["i32.atomic.rmw.add", "i64.atomic.rmw.add", "i32.atomic.rmw8.add_u", "i32.atomic.rmw16.add_u",
+     "i64.atomic.rmw8.add_u", "i64.atomic.rmw16.add_u", "i64.atomic.rmw32.add_u"],
+    ["i32.atomic.rmw.sub", "i64.atomic.rmw.sub", "i32.atomic.rmw8.sub_u", "i32.atomic.rmw16.sub_u",
+     "i64.atomic.rmw8.sub_u", "i64.atomic.rmw16.sub_u", "i64.atomic.rmw32.sub_u"],
+    ["i32.atomic.rmw.and", "i64.atomic.rmw.and", "i32.atomic.rmw8.and_u", "i32.atomic.rmw16.and_u",
+     "i64.atomic.rmw8.and_u", "i64.atomic.rmw16.and_u", "i64.atomic.rmw32.and_u"],
+    ["i32.atomic.rmw.or", "i64.atomic.rmw.or", "i32.atomic.rmw8.or_u", "i32.atomic.rmw16.or_u",
+     "i64.atomic.rmw8.or_u", "i64.atomic.rmw16.or_u", "i64.atomic.rmw32.or_u"],
+    ["i32.atomic.rmw.xor", "i64.atomic.rmw.xor", "i32.atomic.rmw8.xor_u", "i32.atomic.rmw16.xor_u",
+     "i64.atomic.rmw8.xor_u", "i64.atomic.rmw16.xor_u", "i64.atomic.rmw32.xor_u"],
+    ["i32.atomic.rmw.xchg", "i64.atomic.rmw.xchg", "i32.atomic.rmw8.xchg_u",
+     "i32.atomic.rmw16.xchg_u", "i64.atomic.rmw8.xchg_u", "i64.atomic.rmw16.xchg_u",
+     "i64.atomic.rmw32.xchg_u"],
+    ["i32.atomic.rmw.cmpxchg", "i64.atomic.rmw.cmpxchg", "i32.atomic.rmw8.cmpxchg_u",
+     "i32.atomic.rmw16.cmpxchg_u", "i64.atomic.rmw8.cmpxchg_u", "i64.atomic.rmw16.cmpxchg_u",
+     "i64.atomic.rmw32.cmpxchg_u"],
+];
+
+/// Bytes accessed by each width of a read-modify-write operation.
+const RMW_SIZES: [u8; 7] = [4, 8, 1, 2, 1, 2, 4];
+
+/// Memory immediate, with the memory index in bit 6 of the alignment for multiple memories.
+pub(crate) fn memarg(reader: &mut Reader) -> Result<Vec<Operand>, ErrorKind> {
+    let align = u32(reader)?;
+    if align & !(1 << 6) >= 64 {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
+    let memory = match align & 1 << 6 != 0 {
+        true => Some(u32(reader)?),
+        false => None,
+    };
+    let offset = uleb(reader, 64)?;
+
+    let mut operands = Vec::with_capacity(2);
+    if let Some(memory) = memory.filter(|&memory| memory != 0) {
+        operands.push(Operand::Index(memory));
+    }
+    operands.push(Operand::Memory {
+        offset,
+        align: align & !(1 << 6),
+    });
+
+    Ok(operands)
+}
+
+/// Memory index of `memory.size` and `memory.grow`, only shown for memories other than the
+/// first.
+pub(crate) fn index(reader: &mut Reader) -> Result<Vec<Operand>, ErrorKind> {
+    Ok(match u32(reader)? {
+        0 => Vec::new(),
+        memory => vec![Operand::Index(memory)],
+    })
+}
+
+fn access(mnemomic: &'static str, size: u8, operands: Vec<Operand>) -> Instruction {
+    let mut inst = Instruction::new(mnemomic, operands);
+    inst.size = size;
+    inst
+}
+
+pub(crate) fn decode(opcode: u8, reader: &mut Reader) -> Result<Instruction, ErrorKind> {
+    let (mnemomic, size) = MEMORY[opcode as usize - 0x28];
+    Ok(access(mnemomic, size, memarg(reader)?))
+}
+
+pub(crate) fn decode_atomic(opcode: u32, reader: &mut Reader) -> Result<Instruction, ErrorKind> {
+    let inst = match opcode {
+        0x00 => access("memory.atomic.notify", 4, memarg(reader)?),
+        0x01 => access("memory.atomic.wait32", 4, memarg(reader)?),
+        0x02 => access("memory.atomic.wait64", 8, memarg(reader)?),
+        0x03 => {
+            // reserved byte
+            if crate::byte(reader)? != 0 {
+                return Err(ErrorKind::InvalidOperand);
+            }
+            Instruction::new("atomic.fence", vec![])
+        }
+        0x10..=0x1d => {
+            let (mnemomic, size) = ATOMIC[opcode as usize - 0x10];
+            access(mnemomic, size, memarg(reader)?)
+        }
+        0x1e..=0x4e => {
+            let opcode = opcode as usize - 0x1e;
+            let mnemomic = RMW[opcode / 7][opcode % 7];
+            access(mnemomic, RMW_SIZES[opcode % 7], memarg(reader)?)
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
@@ -0,0 +1,93 @@
+//! Numeric instructions without immediates and the miscellaneous (`0xfc` prefixed) instructions
+//! of the non-trapping conversion, bulk memory and reference types proposals.
+
+use crate::{u32, Instruction, Operand};
+use decoder::{ErrorKind, Reader};
+
+/// Comparisons, arithmetic and conversions, starting at opcode `0x45`.
+#[rustfmt::skip]
+pub(crate) const NUMERIC: [&str; 128] = [
+    "i32.eqz", "i32.eq", "i32.ne", "i32.lt_s", "i32.lt_u", "i32.gt_s", "i32.gt_u", "i32.le_s",
+    "i32.le_u", "i32.ge_s", "i32.ge_u",
+    "i64.eqz", "i64.eq", "i64.ne", "i64.lt_s", "i64.lt_u", "i64.gt_s", "i64.gt_u", "i64.le_s",
+    "i64.le_u", "i64.ge_s", "i64.ge_u",
+    "f32.eq", "f32.ne", "f32.lt", "f32.gt", "f32.le", "f32.ge",
+    "f64.eq", "f64.ne", "f64.lt", "f64.gt", "f64.le", "f64.ge",
+    "i32.clz", "i32.ctz", "i32.popcnt", "i32.add", "i32.sub", "i32.mul", "i32.div_s", "i32.div_u",
+    "i32.rem_s", "i32.rem_u", "i32.and", "i32.or", "i32.xor", "i32.shl", "i32.shr_s", "i32.shr_u",
+    "i32.rotl", "i32.rotr",
+    "i64.clz", "i64.ctz", "i64.popcnt", "i64.add", "i64.sub", "i64.mul", "i64.div_s", "i64.div_u",
+    "i64.rem_s", "i64.rem_u", "i64.and", "i64.or", "i64.xor", "i64.shl", "i64.shr_s", "i64.shr_u",
+    "i64.rotl", "i64.rotr",
+    "f32.abs", "f32.neg", "f32.ceil", "f32.floor", "f32.trunc", "f32.nearest", "f32.sqrt",
+    "f32.add", "f32.sub", "f32.mul", "f32.div", "f32.min", "f32.max", "f32.copysign",
+    "f64.abs", "f64.neg", "f64.ceil", "f64.floor", "f64.trunc", "f64.nearest", "f64.sqrt",
+    "f64.add", "f64.sub", "f64.mul", "f64.div", "f64.min", "f64.max", "f64.copysign",
+    "i32.wrap_i64", "i32.trunc_f32_s", "i32.trunc_f32_u", "i32.trunc_f64_s", "i32.trunc_f64_u",
+    "i64.extend_i32_s", "i64.extend_i32_u", "i64.trunc_f32_s", "i64.trunc_f32_u",
+    "i64.trunc_f64_s", "i64.trunc_f64_u", "f32.convert_i32_s", "f32.convert_i32_u",
+    "f32.convert_i64_s", "f32.convert_i64_u", "f32.demote_f64", "f64.convert_i32_s",
+    "f64.convert_i32_u", "f64.convert_i64_s", "f64.convert_i64_u", "f64.promote_f32",
+    "i32.reinterpret_f32", "i64.reinterpret_f64", "f32.reinterpret_i32", "f64.reinterpret_i64",
+    "i32.extend8_s", "i32.extend16_s", "i64.extend8_s", "i64.extend16_s", "i64.extend32_s",
+];
+
+/// Saturating float to integer conversions.
+#[rustfmt::skip]
+const TRUNC_SAT: [&str; 8] = [
+    "i32.trunc_sat_f32_s", "i32.trunc_sat_f32_u", "i32.trunc_sat_f64_s", "i32.trunc_sat_f64_u",
+    "i64.trunc_sat_f32_s", "i64.trunc_sat_f32_u", "i64.trunc_sat_f64_s", "i64.trunc_sat_f64_u",
+];
+
+fn index(reader: &mut Reader) -> Result<Operand, ErrorKind> {
+    Ok(Operand::Index(u32(reader)?))
+}
+
+/// Memory index, which is a reserved zero byte without multiple memories.
+fn memory(reader: &mut Reader) -> Result<Option<Operand>, ErrorKind> {
+    Ok(match u32(reader)? {
+        0 => None,
+        memory => Some(Operand::Index(memory)),
+    })
+}
+
+pub(crate) fn decode_misc(opcode: u32, reader: &mut Reader) -> Result<Instruction, ErrorKind> {
+    let inst = match opcode {
+        0..=7 => Instruction::new(TRUNC_SAT[opcode as usize], vec![]),
+        8 => {
+            let data = index(reader)?;
+            let operands = match memory(reader)? {
+                Some(memory) => vec![memory, data],
+                None => vec![data],
+            };
+            Instruction::new("memory.init", operands)
+        }
+        9 => Instruction::new("data.drop", vec![index(reader)?]),
+        10 => {
+            let dst = memory(reader)?;
+            let src = memory(reader)?;
+            let operands = match (dst, src) {
+                (None, None) => vec![],
+                (dst, src) => vec![
+                    dst.unwrap_or(Operand::Index(0)),
+                    src.unwrap_or(Operand::Index(0)),
+                ],
+            };
+            Instruction::new("memory.copy", operands)
+        }
+        11 => Instruction::new("memory.fill", memory(reader)?.into_iter().collect()),
+        12 => {
+            let elem = index(reader)?;
+            let table = index(reader)?;
+            Instruction::new("table.init", vec![table, elem])
+        }
+        13 => Instruction::new("elem.drop", vec![index(reader)?]),
+        14 => Instruction::new("table.copy", vec![index(reader)?, index(reader)?]),
+        15 => Instruction::new("table.grow", vec![index(reader)?]),
+        16 => Instruction::new("table.size", vec![index(reader)?]),
+        17 => Instruction::new("table.fill", vec![index(reader)?]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
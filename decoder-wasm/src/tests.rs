@@ -0,0 +1,190 @@
+#![cfg(test)]
+
+use decoder::{Decodable, Decoded, Direction, FlowKind, MemoryAccess, ToTokens};
+use std::sync::Arc;
+
+fn decoder() -> crate::Decoder {
+    crate::Decoder {
+        functions: vec![
+            crate::Function::Import(Arc::from("fd_write")),
+            crate::Function::Local(0x1000),
+            crate::Function::Local(0x1040),
+        ],
+    }
+}
+
+fn test_display(bytes: &[u8], str: &str) {
+    let mut reader = decoder::Reader::new(bytes);
+    let mut line = tokenizing::TokenStream::new();
+    let symbols = debugvault::Index::default();
+
+    let decoded = match decoder().decode(&mut reader) {
+        Ok(inst) => {
+            assert_eq!(inst.width(), bytes.len(), "{str}");
+            inst.tokenize(&mut line, &symbols);
+            line.to_string()
+        }
+        Err(err) => format!("{err:?}"),
+    };
+
+    assert_eq!(decoded, str);
+}
+
+fn decode(bytes: &[u8]) -> crate::Instruction {
+    let mut reader = decoder::Reader::new(bytes);
+    decoder().decode(&mut reader).unwrap()
+}
+
+#[test]
+fn control() {
+    test_display(&[0x00], "unreachable");
+    test_display(&[0x02, 0x40], "block");
+    test_display(&[0x03, 0x7f], "loop (result i32)");
+    test_display(&[0x04, 0x02], "if (type 2)");
+    test_display(&[0x0c, 0x01], "br 1");
+    test_display(&[0x0d, 0x00], "br_if 0");
+    test_display(&[0x0e, 0x02, 0x00, 0x01, 0x02], "br_table 0 1 2");
+    test_display(&[0x0b], "end");
+    test_display(&[0x0f], "return");
+    test_display(&[0x06], "Error { kind: ExhaustedInput, size: 1 }");
+    test_display(&[0x02, 0x7a], "Error { kind: InvalidOperand, size: 2 }");
+    test_display(&[0x27], "Error { kind: InvalidOpcode, size: 1 }");
+}
+
+#[test]
+fn calls() {
+    test_display(&[0x10, 0x00], "call fd_write");
+    test_display(&[0x10, 0x02], "call 0x1040");
+    test_display(&[0x10, 0x03], "call 3");
+    test_display(&[0x11, 0x04, 0x00], "call_indirect (type 4)");
+    test_display(&[0x11, 0x04, 0x01], "call_indirect 1 (type 4)");
+    test_display(&[0x12, 0x01], "return_call 0x1000");
+}
+
+#[test]
+fn variables() {
+    test_display(&[0x20, 0x03], "local.get 3");
+    test_display(&[0x22, 0x80, 0x01], "local.tee 128");
+    test_display(&[0x23, 0x00], "global.get 0");
+    test_display(&[0x1c, 0x01, 0x7e], "select i64");
+}
+
+#[test]
+fn constants() {
+    test_display(&[0x41, 0x7f], "i32.const -0x1");
+    test_display(&[0x41, 0x80, 0x80, 0xc0, 0x00], "i32.const 0x100000");
+    test_display(
+        &[
+            0x42, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f,
+        ],
+        "i64.const -0x8000000000000000",
+    );
+    test_display(&[0x43, 0x00, 0x00, 0xc0, 0x3f], "f32.const 1.5");
+    test_display(
+        &[0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0xc0],
+        "f64.const -2.5",
+    );
+    test_display(
+        &[0x41, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00],
+        "Error { kind: InvalidOperand, size: 7 }",
+    );
+}
+
+#[test]
+fn memory() {
+    test_display(&[0x28, 0x02, 0x00], "i32.load");
+    test_display(&[0x28, 0x02, 0x08], "i32.load offset=0x8");
+    test_display(&[0x29, 0x00, 0x10], "i64.load offset=0x10 align=1");
+    test_display(&[0x3a, 0x00, 0x00], "i32.store8");
+    test_display(&[0x3f, 0x00], "memory.size");
+    test_display(&[0x40, 0x00], "memory.grow");
+    test_display(&[0xfc, 0x0a, 0x00, 0x00], "memory.copy");
+    test_display(&[0xfc, 0x0b, 0x00], "memory.fill");
+    test_display(&[0xfc, 0x08, 0x01, 0x00], "memory.init 1");
+}
+
+#[test]
+fn numeric() {
+    test_display(&[0x45], "i32.eqz");
+    test_display(&[0x6a], "i32.add");
+    test_display(&[0xa7], "i32.wrap_i64");
+    test_display(&[0xc4], "i64.extend32_s");
+    test_display(&[0xfc, 0x00], "i32.trunc_sat_f32_s");
+    test_display(&[0xfc, 0x12], "Error { kind: InvalidOpcode, size: 2 }");
+}
+
+#[test]
+fn references() {
+    test_display(&[0xd0, 0x70], "ref.null funcref");
+    test_display(&[0xd2, 0x01], "ref.func 0x1000");
+    test_display(&[0xfc, 0x0e, 0x00, 0x01], "table.copy 0 1");
+}
+
+#[test]
+fn simd() {
+    test_display(&[0xfd, 0x00, 0x04, 0x00], "v128.load");
+    test_display(&[0xfd, 0x0a, 0x03, 0x04], "v128.load64_splat offset=0x4");
+    test_display(
+        &[
+            0xfd, 0x0c, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+            0x04, 0x00, 0x00, 0x00,
+        ],
+        "v128.const i32x4 0x1 0x2 0x3 0x4",
+    );
+    test_display(&[0xfd, 0x15, 0x03], "i8x16.extract_lane_s 3");
+    test_display(&[0xfd, 0x55, 0x01, 0x00, 0x07], "v128.load16_lane 7");
+    test_display(&[0xfd, 0xae, 0x01], "i32x4.add");
+    test_display(&[0xfd, 0x81, 0x02], "i32x4.relaxed_trunc_f32x4_s");
+    test_display(
+        &[0xfd, 0x9a, 0x01],
+        "Error { kind: InvalidOpcode, size: 3 }",
+    );
+}
+
+#[test]
+fn atomics() {
+    test_display(&[0xfe, 0x03, 0x00], "atomic.fence");
+    test_display(&[0xfe, 0x10, 0x02, 0x00], "i32.atomic.load");
+    test_display(&[0xfe, 0x1e, 0x02, 0x00], "i32.atomic.rmw.add");
+    test_display(&[0xfe, 0x4e, 0x02, 0x00], "i64.atomic.rmw32.cmpxchg_u");
+    test_display(&[0xfe, 0x4f], "Error { kind: InvalidOpcode, size: 2 }");
+}
+
+#[test]
+fn behavior() {
+    assert_eq!(decode(&[0x0c, 0x00]).flow(), FlowKind::Jump);
+    assert_eq!(decode(&[0x0d, 0x00]).flow(), FlowKind::ConditionalJump);
+    assert_eq!(decode(&[0x0f]).flow(), FlowKind::Return);
+    assert_eq!(decode(&[0x00]).flow(), FlowKind::Halt);
+    assert!(!decode(&[0x00]).can_fall_through());
+
+    let inst = decode(&[0x10, 0x01]);
+    assert_eq!(inst.flow(), FlowKind::Call);
+    assert_eq!(inst.targets(), vec![0x1000]);
+
+    // imported functions don't have an address
+    assert_eq!(decode(&[0x10, 0x00]).targets(), Vec::<usize>::new());
+
+    assert_eq!(
+        decode(&[0x2d, 0x00, 0x00]).memory(),
+        Some(MemoryAccess {
+            size: Some(1),
+            direction: Direction::Read
+        })
+    );
+    assert_eq!(
+        decode(&[0x37, 0x03, 0x00]).memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Write
+        })
+    );
+    assert_eq!(
+        decode(&[0xfe, 0x20, 0x00, 0x00]).memory(),
+        Some(MemoryAccess {
+            size: Some(1),
+            direction: Direction::ReadWrite
+        })
+    );
+    assert_eq!(decode(&[0x6a]).memory(), None);
+}
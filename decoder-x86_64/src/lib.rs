@@ -1,15 +1,18 @@
 //! # `yaxpeax-x86`, a decoder for x86-family instruction sets
 //!
-//! `yaxpeax-x86` provides x86 decoders, for 64-bit and 32-bit modes.
+//! `yaxpeax-x86` provides x86 decoders, for 64-bit, 32-bit and 16-bit modes.
 //!
 //! instructions, operands, registers, and generally all decoding structures, are in their mode's
 //! respective submodule:
 //! * `x86_64`/`amd64` decoding is under [`long_mode`]
 //! * `x86_32`/`x86` decoding is under [`protected_mode`]
+//! * `x86_16`/`8086` decoding is also under [`protected_mode`], with a decoder built through
+//!   [`protected_mode::Decoder::with_real_mode`]
 
 pub mod long_mode;
 pub mod protected_mode;
 mod safer_unchecked;
+mod uarch;
 
 use debugvault::Index;
 use tokenizing::{ColorScheme, Colors, TokenStream};
@@ -0,0 +1,1015 @@
+//! Lifting of instructions to the architecture neutral [`ir`](decoder::ir).
+//!
+//! General purpose registers are accessed by their full 64-bit name. Writing the lower 32 bits
+//! of a register zeroes the upper half, writing 8 or 16 bits merges them with the rest of the
+//! register. Flags are 1-bit registers named `cf`, `pf`, `zf`, `sf`, `of` and `df`, the
+//! auxiliary carry flag isn't modeled. Vector, x87 and system instructions are unsupported.
+
+use super::{ConditionCode, Instruction, Opcode, Operand, RegSpec, RegisterBank, Segment};
+use decoder::ir::{mask, BinOp, Builder, Cmp, UnOp, Value};
+
+const GPRS: [&str; 16] = [
+    "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12", "r13",
+    "r14", "r15",
+];
+
+const RAX: u8 = 0;
+const RDX: u8 = 2;
+
+pub(super) fn lift(inst: &Instruction, addr: usize, builder: &mut Builder) {
+    builder.attempt(|b| {
+        Lifter {
+            inst,
+            b,
+            addr: addr as u64,
+            next: (addr as u64).wrapping_add(inst.length as u64),
+            mem: None,
+            flag_cond: None,
+        }
+        .lift()
+    });
+}
+
+fn imm(value: u64, bits: u8) -> Value {
+    Value::constant(value, bits)
+}
+
+/// Full register a general purpose register is part of, with the offset and width of its bits.
+fn gpr(reg: RegSpec) -> Option<(&'static str, u8, u8)> {
+    let num = reg.num as usize;
+    match reg.bank {
+        RegisterBank::Q => Some((GPRS[num], 0, 64)),
+        RegisterBank::D => Some((GPRS[num], 0, 32)),
+        RegisterBank::W => Some((GPRS[num], 0, 16)),
+        RegisterBank::rB => Some((GPRS[num], 0, 8)),
+        RegisterBank::B if num >= 4 => Some((GPRS[num - 4], 8, 8)),
+        RegisterBank::B => Some((GPRS[num], 0, 8)),
+        _ => None,
+    }
+}
+
+/// The part of `rax`, `rcx`, ... that's `bits` wide.
+fn sized(num: u8, bits: u8) -> RegSpec {
+    let bank = match bits {
+        64 => RegisterBank::Q,
+        32 => RegisterBank::D,
+        16 => RegisterBank::W,
+        _ => RegisterBank::rB,
+    };
+
+    RegSpec { num, bank }
+}
+
+fn is_immediate(op: &Operand) -> bool {
+    matches!(
+        op,
+        Operand::ImmediateI8(_)
+            | Operand::ImmediateU8(_)
+            | Operand::ImmediateI16(_)
+            | Operand::ImmediateU16(_)
+            | Operand::ImmediateI32(_)
+            | Operand::ImmediateU32(_)
+            | Operand::ImmediateI64(_)
+            | Operand::ImmediateU64(_)
+    )
+}
+
+struct Lifter<'a> {
+    inst: &'a Instruction,
+    b: &'a mut Builder,
+    addr: u64,
+    /// Address of the next instruction.
+    next: u64,
+    /// Address of the memory operand, once it's computed.
+    mem: Option<Value>,
+    /// Flags are only updated if this is non-zero, for shifts by a register.
+    flag_cond: Option<Value>,
+}
+
+impl Lifter<'_> {
+    fn get(&mut self, reg: RegSpec) -> Option<Value> {
+        let (name, offset, bits) = gpr(reg)?;
+        if offset == 0 {
+            return Some(self.b.reg(name, bits));
+        }
+
+        let full = self.b.reg(name, 64);
+        let value = self.b.binary(BinOp::LShr, full, imm(offset as u64, 64));
+        Some(self.b.trunc(value, bits))
+    }
+
+    fn set(&mut self, reg: RegSpec, value: Value) -> Option<()> {
+        let (name, offset, bits) = gpr(reg)?;
+        let value = match bits {
+            64 => value,
+            32 => self.b.zext(value, 64),
+            _ => {
+                let full = self.b.reg(name, 64);
+                let rest = self.b.binary(BinOp::And, full, imm(!(mask(bits) << offset), 64));
+                let value = self.b.zext(value, 64);
+                let value = self.b.binary(BinOp::Shl, value, imm(offset as u64, 64));
+                self.b.binary(BinOp::Or, rest, value)
+            }
+        };
+
+        self.b.set_reg(name, value);
+        Some(())
+    }
+
+    fn flag(&mut self, name: &'static str) -> Value {
+        self.b.reg(name, 1)
+    }
+
+    fn set_flag(&mut self, name: &'static str, value: Value) {
+        let value = match self.flag_cond {
+            Some(cond) => {
+                let old = self.flag(name);
+                self.b.select(cond, value, old)
+            }
+            None => value,
+        };
+
+        self.b.set_reg(name, value);
+    }
+
+    fn address(&mut self, op: &Operand) -> Option<Value> {
+        if let Some(addr) = self.mem {
+            return Some(addr);
+        }
+
+        let (base, index, scale, disp) = match *op {
+            Operand::DisplacementU32(disp) => (None, None, 1, disp as i32 as i64),
+            Operand::DisplacementU64(disp) => (None, None, 1, disp as i64),
+            Operand::RegDeref(base) => (Some(base), None, 1, 0),
+            Operand::RegDisp(base, disp) => (Some(base), None, 1, disp as i64),
+            Operand::RegScale(index, scale) => (None, Some(index), scale, 0),
+            Operand::RegIndexBase(base, index) => (Some(base), Some(index), 1, 0),
+            Operand::RegIndexBaseDisp(base, index, disp) => {
+                (Some(base), Some(index), 1, disp as i64)
+            }
+            Operand::RegScaleDisp(index, scale, disp) => (None, Some(index), scale, disp as i64),
+            Operand::RegIndexBaseScale(base, index, scale) => (Some(base), Some(index), scale, 0),
+            Operand::RegIndexBaseScaleDisp(base, index, scale, disp) => {
+                (Some(base), Some(index), scale, disp as i64)
+            }
+            _ => return None,
+        };
+
+        // an address size prefix selects 32-bit registers, the address then wraps around at 4GiB
+        let bits = match base.or(index) {
+            Some(reg) if reg.bank == RegisterBank::D => 32,
+            _ => 64,
+        };
+
+        let mut addr = match base {
+            Some(RegSpec::RIP) => Some(imm(self.next, bits)),
+            Some(base) => Some(self.get(base)?),
+            None => None,
+        };
+
+        if let Some(index) = index {
+            let mut index = self.get(index)?;
+            if scale > 1 {
+                let shift = imm(scale.trailing_zeros() as u64, bits);
+                index = self.b.binary(BinOp::Shl, index, shift);
+            }
+
+            addr = Some(match addr {
+                Some(base) => self.b.binary(BinOp::Add, base, index),
+                None => index,
+            });
+        }
+
+        let mut addr = match addr {
+            Some(addr) if disp != 0 => self.b.binary(BinOp::Add, addr, imm(disp as u64, bits)),
+            Some(addr) => addr,
+            None => imm(disp as u64, bits),
+        };
+
+        addr = self.b.zext(addr, 64);
+        let base = match self.inst.prefixes.segment {
+            Segment::FS => Some("fs_base"),
+            Segment::GS => Some("gs_base"),
+            _ => None,
+        };
+
+        if let Some(base) = base {
+            let base = self.b.reg(base, 64);
+            addr = self.b.binary(BinOp::Add, base, addr);
+        }
+
+        self.mem = Some(addr);
+        Some(addr)
+    }
+
+    /// Width in bits of a register or memory operand.
+    fn width(&self, idx: u8) -> Option<u8> {
+        match self.inst.operand(idx) {
+            Operand::Register(reg) => gpr(reg).map(|(_, _, bits)| bits),
+            op if op.is_memory() && (1..=8).contains(&self.inst.mem_size) => {
+                Some(self.inst.mem_size * 8)
+            }
+            _ => None,
+        }
+    }
+
+    /// Value of an operand, immediates are extended to `bits`.
+    fn read(&mut self, idx: u8, bits: u8) -> Option<Value> {
+        let value = match self.inst.operand(idx) {
+            Operand::ImmediateI8(imm) => imm as i64 as u64,
+            Operand::ImmediateU8(imm) => imm as u64,
+            Operand::ImmediateI16(imm) => imm as i64 as u64,
+            Operand::ImmediateU16(imm) => imm as u64,
+            Operand::ImmediateI32(imm) => imm as i64 as u64,
+            Operand::ImmediateU32(imm) => imm as u64,
+            Operand::ImmediateI64(imm) => imm as u64,
+            Operand::ImmediateU64(imm) => imm,
+            Operand::Register(reg) => return self.get(reg),
+            op if op.is_memory() => {
+                let bits = self.width(idx)?;
+                let addr = self.address(&op)?;
+                return Some(self.b.load(addr, bits));
+            }
+            _ => return None,
+        };
+
+        Some(imm(value, bits))
+    }
+
+    fn write(&mut self, idx: u8, value: Value) -> Option<()> {
+        match self.inst.operand(idx) {
+            Operand::Register(reg) => self.set(reg, value),
+            op if op.is_memory() => {
+                let addr = self.address(&op)?;
+                self.b.store(addr, value);
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    /// Destination of a branch.
+    fn target(&mut self) -> Option<Value> {
+        let op = self.inst.operand(0);
+        if !is_immediate(&op) {
+            return self.read(0, 64);
+        }
+
+        if self.inst.imm_override {
+            return Some(imm(self.inst.imm, 64));
+        }
+
+        let rel = self.read(0, 64)?.as_const()?;
+        Some(imm(self.next.wrapping_add(rel), 64))
+    }
+
+    fn push(&mut self, value: Value) {
+        let rsp = self.b.reg("rsp", 64);
+        let rsp = self.b.binary(BinOp::Sub, rsp, imm(value.bits() as u64 / 8, 64));
+        self.b.store(rsp, value);
+        self.b.set_reg("rsp", rsp);
+    }
+
+    fn pop(&mut self, bits: u8) -> Value {
+        let rsp = self.b.reg("rsp", 64);
+        let value = self.b.load(rsp, bits);
+        let rsp = self.b.binary(BinOp::Add, rsp, imm(bits as u64 / 8, 64));
+        self.b.set_reg("rsp", rsp);
+        value
+    }
+
+    fn msb(&mut self, value: Value) -> Value {
+        self.b.compare(Cmp::Slt, value, imm(0, value.bits()))
+    }
+
+    fn bit(&mut self, value: Value, idx: Value) -> Value {
+        let value = self.b.binary(BinOp::LShr, value, idx);
+        self.b.trunc(value, 1)
+    }
+
+    /// Set `zf`, `sf` and `pf` from a result.
+    fn result_flags(&mut self, res: Value) {
+        let zf = self.b.compare(Cmp::Eq, res, imm(0, res.bits()));
+        let sf = self.msb(res);
+        let low = self.b.trunc(res, 8);
+        let ones = self.b.unary(UnOp::Popcnt, low);
+        let odd = self.b.trunc(ones, 1);
+        let pf = self.b.unary(UnOp::Not, odd);
+        self.set_flag("zf", zf);
+        self.set_flag("sf", sf);
+        self.set_flag("pf", pf);
+    }
+
+    /// Set the flags of an addition or subtraction, except for the carry flag.
+    fn arith_flags(&mut self, sub: bool, a: Value, b: Value, res: Value) {
+        // the sign flips if both operands of an addition have a sign different from the result,
+        // or if the operands of a subtraction differ and the result differs from the first
+        let (x, y) = if sub {
+            (
+                self.b.binary(BinOp::Xor, a, b),
+                self.b.binary(BinOp::Xor, a, res),
+            )
+        } else {
+            (
+                self.b.binary(BinOp::Xor, a, res),
+                self.b.binary(BinOp::Xor, b, res),
+            )
+        };
+        let overflow = self.b.binary(BinOp::And, x, y);
+        let of = self.msb(overflow);
+        self.set_flag("of", of);
+        self.result_flags(res);
+    }
+
+    fn logic_flags(&mut self, res: Value) {
+        self.set_flag("cf", imm(0, 1));
+        self.set_flag("of", imm(0, 1));
+        self.result_flags(res);
+    }
+
+    fn condition(&mut self, cc: ConditionCode) -> Value {
+        let cond = match cc {
+            ConditionCode::O | ConditionCode::NO => self.flag("of"),
+            ConditionCode::B | ConditionCode::AE => self.flag("cf"),
+            ConditionCode::Z | ConditionCode::NZ => self.flag("zf"),
+            ConditionCode::S | ConditionCode::NS => self.flag("sf"),
+            ConditionCode::P | ConditionCode::NP => self.flag("pf"),
+            ConditionCode::A | ConditionCode::BE => {
+                let cf = self.flag("cf");
+                let zf = self.flag("zf");
+                self.b.binary(BinOp::Or, cf, zf)
+            }
+            ConditionCode::L | ConditionCode::GE => {
+                let sf = self.flag("sf");
+                let of = self.flag("of");
+                self.b.binary(BinOp::Xor, sf, of)
+            }
+            ConditionCode::G | ConditionCode::LE => {
+                let zf = self.flag("zf");
+                let sf = self.flag("sf");
+                let of = self.flag("of");
+                let less = self.b.binary(BinOp::Xor, sf, of);
+                self.b.binary(BinOp::Or, zf, less)
+            }
+        };
+
+        let negate = matches!(
+            cc,
+            ConditionCode::NO
+                | ConditionCode::AE
+                | ConditionCode::NZ
+                | ConditionCode::A
+                | ConditionCode::NS
+                | ConditionCode::NP
+                | ConditionCode::GE
+                | ConditionCode::G
+        );
+
+        if negate {
+            self.b.unary(UnOp::Not, cond)
+        } else {
+            cond
+        }
+    }
+
+    fn lift(&mut self) -> Option<()> {
+        let inst = self.inst;
+        let opcode = inst.opcode;
+
+        if let Some(cc) = opcode.condition() {
+            let cond = self.condition(cc);
+            if opcode.is_jcc() {
+                let target = self.target()?;
+                self.b.branch(cond, target);
+            } else if opcode.is_setcc() {
+                let value = self.b.zext(cond, 8);
+                self.write(0, value)?;
+            } else {
+                // a 32-bit destination is zero extended, even if the condition doesn't hold
+                let bits = self.width(0)?;
+                let src = self.read(1, bits)?;
+                let dst = self.read(0, bits)?;
+                let value = self.b.select(cond, src, dst);
+                self.write(0, value)?;
+            }
+
+            return Some(());
+        }
+
+        match opcode {
+            Opcode::NOP
+            | Opcode::ENDBR64
+            | Opcode::ENDBR32
+            | Opcode::PREFETCHNTA
+            | Opcode::PREFETCH0
+            | Opcode::PREFETCH1
+            | Opcode::PREFETCH2 => {}
+            Opcode::MOV => {
+                let bits = self.width(0)?;
+                let value = self.read(1, bits)?;
+                self.write(0, value)?;
+            }
+            Opcode::MOVZX | Opcode::MOVSX | Opcode::MOVSXD => {
+                let bits = self.width(0)?;
+                let value = self.read(1, self.width(1)?)?;
+                let value = match opcode {
+                    Opcode::MOVZX => self.b.zext(value, bits),
+                    _ => self.b.sext(value, bits),
+                };
+                self.write(0, value)?;
+            }
+            Opcode::LEA => {
+                let bits = self.width(0)?;
+                let addr = self.address(&inst.operand(1))?;
+                let addr = self.b.trunc(addr, bits);
+                self.write(0, addr)?;
+            }
+            Opcode::XCHG => {
+                let bits = self.width(0)?;
+                let a = self.read(0, bits)?;
+                let b = self.read(1, bits)?;
+                self.write(0, b)?;
+                self.write(1, a)?;
+            }
+            Opcode::PUSH => {
+                let value = self.read(0, self.width(0).unwrap_or(64))?;
+                self.push(value);
+            }
+            Opcode::POP => {
+                let value = self.pop(self.width(0)?);
+                self.write(0, value)?;
+            }
+            Opcode::ADD
+            | Opcode::ADC
+            | Opcode::SUB
+            | Opcode::SBB
+            | Opcode::CMP
+            | Opcode::AND
+            | Opcode::OR
+            | Opcode::XOR
+            | Opcode::TEST => {
+                let bits = self.width(0)?;
+                let zeroing = matches!(opcode, Opcode::XOR | Opcode::SUB)
+                    && matches!(inst.operand(0), Operand::Register(_))
+                    && inst.operand(0) == inst.operand(1);
+
+                if zeroing {
+                    let zero = imm(0, bits);
+                    self.write(0, zero)?;
+                    self.logic_flags(zero);
+                    return Some(());
+                }
+
+                let a = self.read(0, bits)?;
+                let b = self.read(1, bits)?;
+                let res = match opcode {
+                    Opcode::ADD => self.b.binary(BinOp::Add, a, b),
+                    Opcode::ADC => {
+                        let carry = self.flag("cf");
+                        let carry_in = self.b.zext(carry, bits);
+                        let res = self.b.binary(BinOp::Add, a, b);
+                        let res = self.b.binary(BinOp::Add, res, carry_in);
+                        let wrapped = self.b.compare(Cmp::Ult, res, a);
+                        let same = self.b.compare(Cmp::Eq, res, a);
+                        let same = self.b.binary(BinOp::And, same, carry);
+                        let cf = self.b.binary(BinOp::Or, wrapped, same);
+                        self.set_flag("cf", cf);
+                        res
+                    }
+                    Opcode::SUB | Opcode::CMP => self.b.binary(BinOp::Sub, a, b),
+                    Opcode::SBB => {
+                        let borrow = self.flag("cf");
+                        let borrow_in = self.b.zext(borrow, bits);
+                        let res = self.b.binary(BinOp::Sub, a, b);
+                        let res = self.b.binary(BinOp::Sub, res, borrow_in);
+                        let below = self.b.compare(Cmp::Ult, a, b);
+                        let same = self.b.compare(Cmp::Eq, a, b);
+                        let same = self.b.binary(BinOp::And, same, borrow);
+                        let cf = self.b.binary(BinOp::Or, below, same);
+                        self.set_flag("cf", cf);
+                        res
+                    }
+                    Opcode::OR => self.b.binary(BinOp::Or, a, b),
+                    Opcode::XOR => self.b.binary(BinOp::Xor, a, b),
+                    _ => self.b.binary(BinOp::And, a, b),
+                };
+
+                match opcode {
+                    Opcode::ADD => {
+                        let cf = self.b.compare(Cmp::Ult, res, a);
+                        self.set_flag("cf", cf);
+                        self.arith_flags(false, a, b, res);
+                    }
+                    Opcode::SUB | Opcode::CMP => {
+                        let cf = self.b.compare(Cmp::Ult, a, b);
+                        self.set_flag("cf", cf);
+                        self.arith_flags(true, a, b, res);
+                    }
+                    Opcode::ADC => self.arith_flags(false, a, b, res),
+                    Opcode::SBB => self.arith_flags(true, a, b, res),
+                    _ => self.logic_flags(res),
+                }
+
+                if !matches!(opcode, Opcode::CMP | Opcode::TEST) {
+                    self.write(0, res)?;
+                }
+            }
+            Opcode::INC | Opcode::DEC => {
+                let bits = self.width(0)?;
+                let a = self.read(0, bits)?;
+                let one = imm(1, bits);
+                let sub = opcode == Opcode::DEC;
+                let res = self.b.binary(if sub { BinOp::Sub } else { BinOp::Add }, a, one);
+                self.arith_flags(sub, a, one, res);
+                self.write(0, res)?;
+            }
+            Opcode::NEG => {
+                let bits = self.width(0)?;
+                let a = self.read(0, bits)?;
+                let res = self.b.unary(UnOp::Neg, a);
+                let cf = self.b.compare(Cmp::Ne, a, imm(0, bits));
+                self.set_flag("cf", cf);
+                self.arith_flags(true, imm(0, bits), a, res);
+                self.write(0, res)?;
+            }
+            Opcode::NOT => {
+                let bits = self.width(0)?;
+                let a = self.read(0, bits)?;
+                let res = self.b.unary(UnOp::Not, a);
+                self.write(0, res)?;
+            }
+            Opcode::SHL | Opcode::SAL | Opcode::SHR | Opcode::SAR | Opcode::ROL | Opcode::ROR => {
+                self.shift()?
+            }
+            Opcode::IMUL if inst.operand_count > 1 => {
+                let bits = self.width(0)?;
+                let (a, b) = match inst.operand_count {
+                    2 => (self.read(0, bits)?, self.read(1, bits)?),
+                    _ => (self.read(1, bits)?, self.read(2, bits)?),
+                };
+
+                let res = self.b.binary(BinOp::Mul, a, b);
+                let high = self.b.binary(BinOp::MulHiS, a, b);
+                let sign = self.b.binary(BinOp::AShr, res, imm(bits as u64 - 1, bits));
+                let overflow = self.b.compare(Cmp::Ne, high, sign);
+                self.set_flag("cf", overflow);
+                self.set_flag("of", overflow);
+                self.write(0, res)?;
+            }
+            Opcode::MUL | Opcode::IMUL => {
+                let bits = self.width(0)?;
+                let src = self.read(0, bits)?;
+                let acc = self.get(sized(RAX, bits))?;
+                let signed = opcode == Opcode::IMUL;
+                let low = self.b.binary(BinOp::Mul, acc, src);
+                let high = if signed { BinOp::MulHiS } else { BinOp::MulHiU };
+                let high = self.b.binary(high, acc, src);
+                let overflow = if signed {
+                    let sign = self.b.binary(BinOp::AShr, low, imm(bits as u64 - 1, bits));
+                    self.b.compare(Cmp::Ne, high, sign)
+                } else {
+                    self.b.compare(Cmp::Ne, high, imm(0, bits))
+                };
+
+                self.set_flag("cf", overflow);
+                self.set_flag("of", overflow);
+                if bits == 8 {
+                    let low = self.b.zext(low, 16);
+                    let high = self.b.zext(high, 16);
+                    let high = self.b.binary(BinOp::Shl, high, imm(8, 16));
+                    let product = self.b.binary(BinOp::Or, high, low);
+                    self.set(sized(RAX, 16), product)?;
+                } else {
+                    self.set(sized(RAX, bits), low)?;
+                    self.set(sized(RDX, bits), high)?;
+                }
+            }
+            Opcode::DIV | Opcode::IDIV => self.divide()?,
+            Opcode::CBW | Opcode::CWDE | Opcode::CDQE => {
+                let bits = match opcode {
+                    Opcode::CBW => 16,
+                    Opcode::CWDE => 32,
+                    _ => 64,
+                };
+
+                let value = self.get(sized(RAX, bits / 2))?;
+                let value = self.b.sext(value, bits);
+                self.set(sized(RAX, bits), value)?;
+            }
+            Opcode::CWD | Opcode::CDQ | Opcode::CQO => {
+                let bits = match opcode {
+                    Opcode::CWD => 16,
+                    Opcode::CDQ => 32,
+                    _ => 64,
+                };
+
+                let value = self.get(sized(RAX, bits))?;
+                let sign = self.b.binary(BinOp::AShr, value, imm(bits as u64 - 1, bits));
+                self.set(sized(RDX, bits), sign)?;
+            }
+            Opcode::BSWAP => {
+                let bits = self.width(0)?;
+                let value = self.read(0, bits)?;
+                let value = self.b.unary(UnOp::Bswap, value);
+                self.write(0, value)?;
+            }
+            Opcode::BSF | Opcode::BSR => {
+                let bits = self.width(0)?;
+                let src = self.read(1, bits)?;
+                let zero = self.b.compare(Cmp::Eq, src, imm(0, bits));
+                let res = if opcode == Opcode::BSF {
+                    self.b.unary(UnOp::Ctz, src)
+                } else {
+                    let leading = self.b.unary(UnOp::Clz, src);
+                    self.b.binary(BinOp::Sub, imm(bits as u64 - 1, bits), leading)
+                };
+
+                // the destination is left unchanged if there are no set bits
+                let old = self.read(0, bits)?;
+                let res = self.b.select(zero, old, res);
+                self.set_flag("zf", zero);
+                self.write(0, res)?;
+            }
+            Opcode::TZCNT | Opcode::LZCNT | Opcode::POPCNT => {
+                let bits = self.width(0)?;
+                let src = self.read(1, bits)?;
+                let zero = self.b.compare(Cmp::Eq, src, imm(0, bits));
+                let res = match opcode {
+                    Opcode::TZCNT => self.b.unary(UnOp::Ctz, src),
+                    Opcode::LZCNT => self.b.unary(UnOp::Clz, src),
+                    _ => self.b.unary(UnOp::Popcnt, src),
+                };
+
+                if opcode == Opcode::POPCNT {
+                    self.set_flag("cf", imm(0, 1));
+                    self.set_flag("zf", zero);
+                } else {
+                    let zf = self.b.compare(Cmp::Eq, res, imm(0, bits));
+                    self.set_flag("cf", zero);
+                    self.set_flag("zf", zf);
+                }
+
+                self.write(0, res)?;
+            }
+            Opcode::BT | Opcode::BTS | Opcode::BTR | Opcode::BTC => {
+                // a register bit offset can select bits outside of the addressed memory
+                if inst.operand(0).is_memory() && !is_immediate(&inst.operand(1)) {
+                    return None;
+                }
+
+                let bits = self.width(0)?;
+                let value = self.read(0, bits)?;
+                let idx = self.read(1, bits)?;
+                let idx = self.b.binary(BinOp::And, idx, imm(bits as u64 - 1, bits));
+                let cf = self.bit(value, idx);
+                self.set_flag("cf", cf);
+
+                if opcode != Opcode::BT {
+                    let bit = self.b.binary(BinOp::Shl, imm(1, bits), idx);
+                    let res = match opcode {
+                        Opcode::BTS => self.b.binary(BinOp::Or, value, bit),
+                        Opcode::BTR => {
+                            let clear = self.b.unary(UnOp::Not, bit);
+                            self.b.binary(BinOp::And, value, clear)
+                        }
+                        _ => self.b.binary(BinOp::Xor, value, bit),
+                    };
+                    self.write(0, res)?;
+                }
+            }
+            Opcode::XADD => {
+                let bits = self.width(0)?;
+                let a = self.read(0, bits)?;
+                let b = self.read(1, bits)?;
+                let res = self.b.binary(BinOp::Add, a, b);
+                let cf = self.b.compare(Cmp::Ult, res, a);
+                self.set_flag("cf", cf);
+                self.arith_flags(false, a, b, res);
+                self.write(1, a)?;
+                self.write(0, res)?;
+            }
+            Opcode::CMPXCHG => {
+                let bits = self.width(0)?;
+                let dst = self.read(0, bits)?;
+                let src = self.read(1, bits)?;
+                let acc = self.get(sized(RAX, bits))?;
+                let res = self.b.binary(BinOp::Sub, acc, dst);
+                let cf = self.b.compare(Cmp::Ult, acc, dst);
+                self.set_flag("cf", cf);
+                self.arith_flags(true, acc, dst, res);
+
+                let equal = self.b.compare(Cmp::Eq, acc, dst);
+                let value = self.b.select(equal, src, dst);
+                let acc = self.b.select(equal, acc, dst);
+                self.write(0, value)?;
+                self.set(sized(RAX, bits), acc)?;
+            }
+            Opcode::CLC | Opcode::STC | Opcode::CMC => {
+                let cf = match opcode {
+                    Opcode::CLC => imm(0, 1),
+                    Opcode::STC => imm(1, 1),
+                    _ => {
+                        let cf = self.flag("cf");
+                        self.b.unary(UnOp::Not, cf)
+                    }
+                };
+                self.set_flag("cf", cf);
+            }
+            Opcode::CLD => self.set_flag("df", imm(0, 1)),
+            Opcode::STD => self.set_flag("df", imm(1, 1)),
+            Opcode::MOVS | Opcode::STOS | Opcode::LODS | Opcode::CMPS | Opcode::SCAS => {
+                self.string()?
+            }
+            Opcode::JMP => {
+                let target = self.target()?;
+                self.b.jump(target);
+            }
+            Opcode::JRCXZ => {
+                let target = self.target()?;
+                let rcx = self.b.reg("rcx", 64);
+                let zero = self.b.compare(Cmp::Eq, rcx, imm(0, 64));
+                self.b.branch(zero, target);
+            }
+            Opcode::LOOP | Opcode::LOOPZ | Opcode::LOOPNZ => {
+                let target = self.target()?;
+                let rcx = self.b.reg("rcx", 64);
+                let rcx = self.b.binary(BinOp::Sub, rcx, imm(1, 64));
+                self.b.set_reg("rcx", rcx);
+                let mut cond = self.b.compare(Cmp::Ne, rcx, imm(0, 64));
+                if opcode != Opcode::LOOP {
+                    let mut zf = self.flag("zf");
+                    if opcode == Opcode::LOOPNZ {
+                        zf = self.b.unary(UnOp::Not, zf);
+                    }
+                    cond = self.b.binary(BinOp::And, cond, zf);
+                }
+                self.b.branch(cond, target);
+            }
+            Opcode::CALL => {
+                let target = self.target()?;
+                self.push(imm(self.next, 64));
+                self.b.call(target);
+            }
+            Opcode::RETURN => {
+                let target = self.pop(64);
+                if inst.operand_count > 0 {
+                    let bytes = self.read(0, 64)?;
+                    let rsp = self.b.reg("rsp", 64);
+                    let rsp = self.b.binary(BinOp::Add, rsp, bytes);
+                    self.b.set_reg("rsp", rsp);
+                }
+                self.b.ret(target);
+            }
+            Opcode::LEAVE => {
+                let rbp = self.b.reg("rbp", 64);
+                let saved = self.b.load(rbp, 64);
+                let rsp = self.b.binary(BinOp::Add, rbp, imm(8, 64));
+                self.b.set_reg("rsp", rsp);
+                self.b.set_reg("rbp", saved);
+            }
+            Opcode::INT => {
+                let vector = self.read(0, 8)?;
+                self.b.intrinsic("int", vec![vector]);
+            }
+            Opcode::SYSCALL => self.b.intrinsic("syscall", Vec::new()),
+            Opcode::HLT => self.b.intrinsic("hlt", Vec::new()),
+            Opcode::UD2 => self.b.intrinsic("ud2", Vec::new()),
+            Opcode::CPUID => self.b.intrinsic("cpuid", Vec::new()),
+            Opcode::RDTSC => self.b.intrinsic("rdtsc", Vec::new()),
+            Opcode::LFENCE | Opcode::MFENCE | Opcode::SFENCE => {
+                self.b.intrinsic("fence", Vec::new())
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    fn shift(&mut self) -> Option<()> {
+        let opcode = self.inst.opcode;
+        let bits = self.width(0)?;
+        let count = self.read(1, 8)?;
+        let count = self.b.binary(
+            BinOp::And,
+            count,
+            imm(if bits == 64 { 0x3f } else { 0x1f }, 8),
+        );
+        let count = self.b.zext(count, bits);
+
+        // flags aren't touched when shifting by zero
+        match count.as_const() {
+            Some(0) => return Some(()),
+            Some(_) => {}
+            None => self.flag_cond = Some(self.b.compare(Cmp::Ne, count, imm(0, bits))),
+        }
+
+        let a = self.read(0, bits)?;
+
+        let res = match opcode {
+            Opcode::SHL | Opcode::SAL => {
+                let res = self.b.binary(BinOp::Shl, a, count);
+                let shift = self.b.binary(BinOp::Sub, imm(bits as u64, bits), count);
+                let cf = self.bit(a, shift);
+                let msb = self.msb(res);
+                let of = self.b.binary(BinOp::Xor, msb, cf);
+                self.set_flag("cf", cf);
+                self.set_flag("of", of);
+                self.result_flags(res);
+                res
+            }
+            Opcode::SHR | Opcode::SAR => {
+                let op = if opcode == Opcode::SHR {
+                    BinOp::LShr
+                } else {
+                    BinOp::AShr
+                };
+                let res = self.b.binary(op, a, count);
+                let last = self.b.binary(BinOp::Sub, count, imm(1, bits));
+                let last = self.b.binary(op, a, last);
+                let cf = self.b.trunc(last, 1);
+                let of = if opcode == Opcode::SHR {
+                    self.msb(a)
+                } else {
+                    imm(0, 1)
+                };
+                self.set_flag("cf", cf);
+                self.set_flag("of", of);
+                self.result_flags(res);
+                res
+            }
+            _ => {
+                let count = if opcode == Opcode::ROL {
+                    self.b.binary(BinOp::Sub, imm(bits as u64, bits), count)
+                } else {
+                    count
+                };
+
+                let res = self.b.binary(BinOp::Ror, a, count);
+                let msb = self.msb(res);
+                let (cf, of) = if opcode == Opcode::ROL {
+                    let cf = self.b.trunc(res, 1);
+                    (cf, self.b.binary(BinOp::Xor, msb, cf))
+                } else {
+                    let next = self.bit(res, imm(bits as u64 - 2, bits));
+                    (msb, self.b.binary(BinOp::Xor, msb, next))
+                };
+
+                self.set_flag("cf", cf);
+                self.set_flag("of", of);
+                res
+            }
+        };
+
+        self.flag_cond = None;
+        self.write(0, res)
+    }
+
+    fn divide(&mut self) -> Option<()> {
+        let bits = self.width(0)?;
+        let src = self.read(0, bits)?;
+        let signed = self.inst.opcode == Opcode::IDIV;
+        let (div, rem) = if signed {
+            (BinOp::SDiv, BinOp::SRem)
+        } else {
+            (BinOp::UDiv, BinOp::URem)
+        };
+
+        if bits == 64 {
+            // the 128-bit dividend doesn't fit in a value, so it's assumed that rdx only holds
+            // the extension of rax, as it does after `cqo` or `xor edx, edx`
+            let dividend = self.b.reg("rax", 64);
+            let quotient = self.b.binary(div, dividend, src);
+            let remainder = self.b.binary(rem, dividend, src);
+            self.b.set_reg("rax", quotient);
+            self.b.set_reg("rdx", remainder);
+            return Some(());
+        }
+
+        let wide = bits * 2;
+        let dividend = if bits == 8 {
+            self.get(sized(RAX, 16))?
+        } else {
+            let low = self.get(sized(RAX, bits))?;
+            let high = self.get(sized(RDX, bits))?;
+            let low = self.b.zext(low, wide);
+            let high = self.b.zext(high, wide);
+            let high = self.b.binary(BinOp::Shl, high, imm(bits as u64, wide));
+            self.b.binary(BinOp::Or, high, low)
+        };
+
+        let divisor = if signed {
+            self.b.sext(src, wide)
+        } else {
+            self.b.zext(src, wide)
+        };
+
+        let quotient = self.b.binary(div, dividend, divisor);
+        let quotient = self.b.trunc(quotient, bits);
+        let remainder = self.b.binary(rem, dividend, divisor);
+        let remainder = self.b.trunc(remainder, bits);
+
+        if bits == 8 {
+            self.set(sized(RAX, 8), quotient)?;
+            self.set(
+                RegSpec {
+                    num: 4,
+                    bank: RegisterBank::B,
+                },
+                remainder,
+            )
+        } else {
+            self.set(sized(RAX, bits), quotient)?;
+            self.set(sized(RDX, bits), remainder)
+        }
+    }
+
+    /// String instructions, a `rep` prefix repeats the instruction until `rcx` is zero.
+    fn string(&mut self) -> Option<()> {
+        let opcode = self.inst.opcode;
+        let bytes = self.inst.mem_size;
+        if !(1..=8).contains(&bytes) {
+            return None;
+        }
+
+        let bits = bytes * 8;
+        let rep = self.inst.prefixes.rep_any();
+        let rcx = if rep {
+            let rcx = self.b.reg("rcx", 64);
+            let done = self.b.compare(Cmp::Eq, rcx, imm(0, 64));
+            self.b.branch(done, imm(self.next, 64));
+            Some(rcx)
+        } else {
+            None
+        };
+
+        let df = self.flag("df");
+        let step = self.b.select(
+            df,
+            imm((bytes as u64).wrapping_neg(), 64),
+            imm(bytes as u64, 64),
+        );
+        let reads = matches!(opcode, Opcode::MOVS | Opcode::LODS | Opcode::CMPS);
+        let writes = opcode != Opcode::LODS;
+
+        let rsi = if reads {
+            Some(self.b.reg("rsi", 64))
+        } else {
+            None
+        };
+        let rdi = if writes {
+            Some(self.b.reg("rdi", 64))
+        } else {
+            None
+        };
+
+        match opcode {
+            Opcode::MOVS => {
+                let value = self.b.load(rsi?, bits);
+                self.b.store(rdi?, value);
+            }
+            Opcode::STOS => {
+                let value = self.get(sized(RAX, bits))?;
+                self.b.store(rdi?, value);
+            }
+            Opcode::LODS => {
+                let value = self.b.load(rsi?, bits);
+                self.set(sized(RAX, bits), value)?;
+            }
+            _ => {
+                let a = match rsi {
+                    Some(rsi) => self.b.load(rsi, bits),
+                    None => self.get(sized(RAX, bits))?,
+                };
+                let b = self.b.load(rdi?, bits);
+                let res = self.b.binary(BinOp::Sub, a, b);
+                let cf = self.b.compare(Cmp::Ult, a, b);
+                self.set_flag("cf", cf);
+                self.arith_flags(true, a, b, res);
+            }
+        }
+
+        for (name, reg) in [("rsi", rsi), ("rdi", rdi)] {
+            if let Some(reg) = reg {
+                let reg = self.b.binary(BinOp::Add, reg, step);
+                self.b.set_reg(name, reg);
+            }
+        }
+
+        if let Some(rcx) = rcx {
+            let rcx = self.b.binary(BinOp::Sub, rcx, imm(1, 64));
+            self.b.set_reg("rcx", rcx);
+
+            if matches!(opcode, Opcode::CMPS | Opcode::SCAS) {
+                let mut zf = self.flag("zf");
+                if self.inst.prefixes.repnz() {
+                    zf = self.b.unary(UnOp::Not, zf);
+                }
+                self.b.branch(zf, imm(self.addr, 64));
+            } else {
+                self.b.jump(imm(self.addr, 64));
+            }
+        }
+
+        Some(())
+    }
+}
@@ -1,5 +1,8 @@
+mod att;
+mod behavior;
 mod display;
 mod evex;
+mod lift;
 mod tests;
 pub mod uarch;
 mod vex;
@@ -11,7 +14,7 @@ use std::hash::{Hash, Hasher};
 use crate::safer_unchecked::unreachable_kinda_unchecked as unreachable_unchecked;
 pub use crate::MemoryAccessSize;
 
-use decoder::{Decoded, Decodable, Error, ErrorKind, Reader, ToTokens};
+use decoder::{Decoded, Decodable, Error, ErrorKind, FlowKind, MemoryAccess, Reader, ToTokens};
 use debugvault::Index;
 use tokenizing::{TokenStream, ColorScheme, Colors};
 
@@ -2638,7 +2641,33 @@ impl Decoded for Instruction {
         self.length as usize
     }
 
+    fn tokenize_in(&self, stream: &mut TokenStream, symbols: &Index, dialect: decoder::Dialect) {
+        self.tokenize_with(stream, symbols, dialect.syntax);
+    }
+
     fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+        // only branches have immediates relative to the next instruction, and an address must
+        // not overwrite the immediate of an instruction that also has a memory operand
+        let branch = matches!(
+            behavior::flow(self),
+            FlowKind::Jump | FlowKind::ConditionalJump | FlowKind::Call
+        );
+        let has_imm = self.operands[..self.operand_count as usize].iter().any(|op| {
+            matches!(
+                op,
+                OperandSpec::ImmI8
+                    | OperandSpec::ImmI16
+                    | OperandSpec::ImmI32
+                    | OperandSpec::ImmI64
+                    | OperandSpec::ImmU8
+                    | OperandSpec::ImmU16
+            )
+        });
+
+        if has_imm && !branch {
+            return;
+        }
+
         for idx in 0..self.operand_count as usize {
             let operand = Operand::from_spec(&self, self.operands[idx]);
             let addr = addr as u64;
@@ -2736,6 +2765,30 @@ impl Decoded for Instruction {
             self.imm_override = true;
         }
     }
+
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
+    }
+
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
+
+    fn regs_read(&self) -> Vec<&'static str> {
+        behavior::registers(self).0
+    }
+
+    fn regs_written(&self) -> Vec<&'static str> {
+        behavior::registers(self).1
+    }
+
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
+
+    fn lift(&self, addr: usize, builder: &mut decoder::ir::Builder) {
+        lift::lift(self, addr, builder)
+    }
 }
 
 impl Decodable for Decoder {
@@ -0,0 +1,75 @@
+use crate::long_mode::{Decoder, Instruction};
+use decoder::{Decoded, Syntax};
+
+fn decode(data: &[u8]) -> Instruction {
+    Decoder::default().decode_slice(data).unwrap()
+}
+
+fn att(inst: &Instruction) -> String {
+    let mut stream = tokenizing::TokenStream::new();
+    inst.tokenize_with(&mut stream, &debugvault::Index::default(), Syntax::Att);
+    stream.to_string()
+}
+
+fn test_att(data: &[u8], expected: &str) {
+    assert_eq!(att(&decode(data)), expected, "for {data:02x?}");
+}
+
+#[test]
+fn operands() {
+    test_att(&[0x48, 0x89, 0xe5], "mov %rsp,%rbp");
+    test_att(&[0x48, 0x83, 0xec, 0x10], "sub $0x10,%rsp");
+    test_att(&[0x48, 0x8b, 0x45, 0xf8], "mov -0x8(%rbp),%rax");
+    test_att(&[0x8b, 0x04, 0x88], "mov (%rax,%rcx,4),%eax");
+    test_att(&[0x8b, 0x04, 0x8d, 0x10, 0x00, 0x00, 0x00], "mov 0x10(,%rcx,4),%eax");
+    test_att(
+        &[0x64, 0x48, 0x8b, 0x04, 0x25, 0x28, 0x00, 0x00, 0x00],
+        "mov %fs:0x28,%rax",
+    );
+    test_att(&[0xc2, 0x08, 0x00], "ret $0x8");
+    test_att(&[0xc8, 0x10, 0x00, 0x00], "enter $0x10,$0x0");
+    test_att(&[0xf0, 0x48, 0x0f, 0xb1, 0x0a], "lock cmpxchg %rcx,(%rdx)");
+}
+
+#[test]
+fn suffixes() {
+    test_att(&[0xc7, 0x45, 0xfc, 0x00, 0x00, 0x00, 0x00], "movl $0x0,-0x4(%rbp)");
+    test_att(&[0x80, 0x38, 0x00], "cmpb $0x0,(%rax)");
+    test_att(&[0x48, 0xff, 0x00], "incq (%rax)");
+    test_att(&[0x0f, 0xb6, 0xc0], "movzbl %al,%eax");
+    test_att(&[0x48, 0x0f, 0xbf, 0x07], "movswq (%rdi),%rax");
+    test_att(&[0x48, 0x63, 0xc7], "movslq %edi,%rax");
+    test_att(&[0x48, 0x98], "cltq");
+    test_att(&[0x99], "cltd");
+    test_att(&[0xdd, 0x45, 0xf8], "fldl -0x8(%rbp)");
+    test_att(&[0xdb, 0x28], "fldt (%rax)");
+    test_att(&[0xdf, 0x28], "fildll (%rax)");
+    test_att(&[0x0f, 0x94, 0x00], "setz (%rax)");
+    test_att(&[0xff, 0x30], "push (%rax)");
+}
+
+#[test]
+fn branches() {
+    test_att(&[0xff, 0xd0], "call *%rax");
+    test_att(&[0xff, 0x20], "jmp *(%rax)");
+
+    let mut inst = decode(&[0xeb, 0x10]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(att(&inst), "jmp 0x1012");
+
+    let mut inst = decode(&[0xe8, 0xfb, 0xff, 0xff, 0xff]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(att(&inst), "call 0x1000");
+}
+
+#[test]
+fn avx512() {
+    test_att(
+        &[0x62, 0xf1, 0x74, 0xc9, 0x58, 0xc2],
+        "vaddps %zmm2,%zmm1,%zmm0{%k1}{z}",
+    );
+    test_att(
+        &[0x62, 0xf1, 0x74, 0x58, 0x58, 0x00],
+        "vaddps (%rax){1to16},%zmm1,%zmm0",
+    );
+}
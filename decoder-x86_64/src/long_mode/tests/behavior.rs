@@ -0,0 +1,180 @@
+use crate::long_mode::{Decoder, Instruction};
+use decoder::{Decoded, Direction, FlowKind, MemoryAccess};
+
+fn decode(data: &[u8]) -> Instruction {
+    Decoder::default().decode_slice(data).unwrap()
+}
+
+#[test]
+fn flow() {
+    assert_eq!(decode(&[0x31, 0xc0]).flow(), FlowKind::Sequential);
+    assert_eq!(
+        decode(&[0xe8, 0x00, 0x00, 0x00, 0x00]).flow(),
+        FlowKind::Call
+    );
+    assert_eq!(decode(&[0xff, 0xd0]).flow(), FlowKind::Call);
+    assert_eq!(decode(&[0xeb, 0x10]).flow(), FlowKind::Jump);
+    assert_eq!(decode(&[0x74, 0x10]).flow(), FlowKind::ConditionalJump);
+    assert_eq!(decode(&[0xe2, 0x10]).flow(), FlowKind::ConditionalJump);
+    assert_eq!(decode(&[0xc3]).flow(), FlowKind::Return);
+    assert_eq!(decode(&[0x0f, 0x05]).flow(), FlowKind::Interrupt);
+    assert_eq!(decode(&[0xcc]).flow(), FlowKind::Interrupt);
+    assert_eq!(decode(&[0x0f, 0x0b]).flow(), FlowKind::Halt);
+
+    assert!(decode(&[0x74, 0x10]).can_fall_through());
+    assert!(decode(&[0xe8, 0x00, 0x00, 0x00, 0x00]).can_fall_through());
+    assert!(!decode(&[0xeb, 0x10]).can_fall_through());
+    assert!(!decode(&[0xc3]).can_fall_through());
+    assert!(!decode(&[0xf4]).can_fall_through());
+}
+
+#[test]
+fn targets() {
+    let mut inst = decode(&[0xeb, 0x10]);
+    assert_eq!(inst.targets(), vec![]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![0x1012]);
+
+    let mut inst = decode(&[0xe8, 0xfb, 0xff, 0xff, 0xff]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![0x1000]);
+
+    // indirect through memory isn't a static target
+    let mut inst = decode(&[0xff, 0x15, 0x10, 0x00, 0x00, 0x00]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![]);
+
+    let mut inst = decode(&[0x48, 0x8d, 0x05, 0x10, 0x00, 0x00, 0x00]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![]);
+}
+
+#[test]
+fn registers() {
+    // xor ecx, dword [rax]
+    let inst = decode(&[0x33, 0x08]);
+    assert_eq!(inst.regs_read(), vec!["ecx", "rax"]);
+    assert_eq!(inst.regs_written(), vec!["ecx", "rflags"]);
+
+    // mov qword [rsp + 0x8], rdi
+    let inst = decode(&[0x48, 0x89, 0x7c, 0x24, 0x08]);
+    assert_eq!(inst.regs_read(), vec!["rsp", "rdi"]);
+    assert_eq!(inst.regs_written(), Vec::<&str>::new());
+
+    // lea rax, [rbx + rcx * 4]
+    let inst = decode(&[0x48, 0x8d, 0x04, 0x8b]);
+    assert_eq!(inst.regs_read(), vec!["rbx", "rcx"]);
+    assert_eq!(inst.regs_written(), vec!["rax"]);
+
+    // push rbp
+    let inst = decode(&[0x55]);
+    assert_eq!(inst.regs_read(), vec!["rbp", "rsp"]);
+    assert_eq!(inst.regs_written(), vec!["rsp"]);
+
+    // cmp eax, 0x1
+    let inst = decode(&[0x83, 0xf8, 0x01]);
+    assert_eq!(inst.regs_read(), vec!["eax"]);
+    assert_eq!(inst.regs_written(), vec!["rflags"]);
+
+    // jz
+    let inst = decode(&[0x74, 0x10]);
+    assert_eq!(inst.regs_read(), vec!["rflags"]);
+
+    // rep stos qword [rdi], rax
+    let inst = decode(&[0xf3, 0x48, 0xab]);
+    assert!(inst.regs_read().contains(&"rcx"));
+    assert!(inst.regs_written().contains(&"rdi"));
+
+    // vaddps xmm0, xmm1, xmm2
+    let inst = decode(&[0xc5, 0xf0, 0x58, 0xc2]);
+    assert_eq!(inst.regs_read(), vec!["xmm1", "xmm2"]);
+    assert_eq!(inst.regs_written(), vec!["xmm0"]);
+
+    // vaddps zmm0{k1}, zmm1, zmm2 keeps the masked off elements of zmm0
+    let inst = decode(&[0x62, 0xf1, 0x74, 0x49, 0x58, 0xc2]);
+    assert_eq!(inst.regs_read(), vec!["k1", "zmm0", "zmm1", "zmm2"]);
+
+    // vfmadd231ps ymm0, ymm1, ymm2 accumulates into ymm0
+    let inst = decode(&[0xc4, 0xe2, 0x75, 0xb8, 0xc2]);
+    assert_eq!(inst.regs_read(), vec!["ymm0", "ymm1", "ymm2"]);
+
+    // movaps xmm0, xmm1
+    let inst = decode(&[0x0f, 0x28, 0xc1]);
+    assert_eq!(inst.regs_read(), vec!["xmm1"]);
+
+    // movss xmm0, xmm1 only replaces the low element of xmm0
+    let inst = decode(&[0xf3, 0x0f, 0x10, 0xc1]);
+    assert_eq!(inst.regs_read(), vec!["xmm0", "xmm1"]);
+
+    // cvtsi2sd xmm0, eax merges into xmm0, opcodes that aren't known to only write their
+    // destination read it too
+    let inst = decode(&[0xf2, 0x0f, 0x2a, 0xc0]);
+    assert_eq!(inst.regs_read(), vec!["xmm0", "eax"]);
+    assert_eq!(inst.regs_written(), vec!["xmm0"]);
+}
+
+#[test]
+fn memory() {
+    assert_eq!(decode(&[0x31, 0xc0]).memory(), None);
+
+    // lea computes an address without touching it
+    assert_eq!(decode(&[0x48, 0x8d, 0x04, 0x8b]).memory(), None);
+
+    assert_eq!(
+        decode(&[0x48, 0x89, 0x7c, 0x24, 0x08]).memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Write
+        })
+    );
+
+    assert_eq!(
+        decode(&[0x33, 0x08]).memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::Read
+        })
+    );
+
+    // add byte [rax], al
+    assert_eq!(
+        decode(&[0x00, 0x00]).memory(),
+        Some(MemoryAccess {
+            size: Some(1),
+            direction: Direction::ReadWrite
+        })
+    );
+
+    assert_eq!(
+        decode(&[0x55]).memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Write
+        })
+    );
+
+    assert_eq!(
+        decode(&[0xc3]).memory(),
+        Some(MemoryAccess {
+            size: Some(8),
+            direction: Direction::Read
+        })
+    );
+
+    // stack accesses without a memory operand
+    for (bytes, direction) in [
+        (&[0xc9][..], Direction::Read),
+        (&[0x9c], Direction::Write),
+        (&[0x9d], Direction::Read),
+        (&[0xc8, 0x10, 0x00, 0x00], Direction::Write),
+    ] {
+        assert_eq!(
+            decode(bytes).memory(),
+            Some(MemoryAccess {
+                size: None,
+                direction
+            }),
+            "{bytes:02x?}"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use crate::long_mode::{Decoder, Instruction};
+use decoder::ir::Builder;
+use decoder::Decoded;
+
+fn decode(data: &[u8]) -> Instruction {
+    Decoder::default().decode_slice(data).unwrap()
+}
+
+fn lift(data: &[u8]) -> Vec<String> {
+    let mut inst = decode(data);
+    inst.update_rel_addrs(0x1000, None);
+    let mut builder = Builder::new();
+    inst.lift(0x1000, &mut builder);
+    builder.take().iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn moves() {
+    assert_eq!(
+        lift(&[0x48, 0x8b, 0x43, 0x08]),
+        ["t0 = rbx", "t1 = t0 + 0x8", "t2 = load64 [t1]", "rax = t2"]
+    );
+    assert_eq!(lift(&[0xb8, 0x01, 0x00, 0x00, 0x00]), ["rax = 0x1"]);
+    assert_eq!(
+        lift(&[0xb0, 0x01]),
+        [
+            "t0 = rax",
+            "t1 = t0 & 0xffffffffffffff00",
+            "t2 = t1 | 0x1",
+            "rax = t2"
+        ]
+    );
+    assert_eq!(
+        lift(&[0x48, 0x8d, 0x05, 0x10, 0x00, 0x00, 0x00]),
+        ["rax = 0x1017"]
+    );
+    assert_eq!(
+        lift(&[0x64, 0x48, 0x8b, 0x04, 0x25, 0x28, 0x00, 0x00, 0x00]),
+        [
+            "t0 = fs_base",
+            "t1 = t0 + 0x28",
+            "t2 = load64 [t1]",
+            "rax = t2"
+        ]
+    );
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(
+        lift(&[0x01, 0xd8]),
+        [
+            "t0 = rax",
+            "t1 = rbx",
+            "t2 = t0 + t1",
+            "t3 = t2 <u t0",
+            "cf = t3",
+            "t4 = t0 ^ t2",
+            "t5 = t1 ^ t2",
+            "t6 = t4 & t5",
+            "t7 = t6 <s 0x0",
+            "of = t7",
+            "t8 = t2 == 0x0",
+            "t9 = t2 <s 0x0",
+            "t10 = trunc8 t2",
+            "t11 = popcnt t10",
+            "t12 = trunc1 t11",
+            "t13 = ~t12",
+            "zf = t8",
+            "sf = t9",
+            "pf = t13",
+            "t14 = zext64 t2",
+            "rax = t14",
+        ]
+    );
+    assert_eq!(
+        lift(&[0x31, 0xc0]),
+        [
+            "rax = 0x0",
+            "cf = 0x0",
+            "of = 0x0",
+            "zf = 0x1",
+            "sf = 0x0",
+            "pf = 0x1"
+        ]
+    );
+    assert_eq!(
+        lift(&[0x48, 0xf7, 0xf1]),
+        [
+            "t0 = rcx",
+            "t1 = rax",
+            "t2 = t1 /u t0",
+            "t3 = t1 %u t0",
+            "rax = t2",
+            "rdx = t3"
+        ]
+    );
+}
+
+#[test]
+fn shifts() {
+    // flags are unchanged when shifting by zero
+    let stmts = lift(&[0xd3, 0xe0]);
+    assert!(stmts.contains(&"t3 = t2 != 0x0".to_string()));
+    assert!(stmts.contains(&"t12 = t3 ? t8 : t11".to_string()));
+    assert!(lift(&[0xc1, 0xe0, 0x00]).is_empty());
+}
+
+#[test]
+fn control_flow() {
+    assert_eq!(lift(&[0x74, 0x10]), ["t0 = zf", "if t0 goto 0x1012"]);
+    assert_eq!(
+        lift(&[0x7f, 0x10]),
+        [
+            "t0 = zf",
+            "t1 = sf",
+            "t2 = of",
+            "t3 = t1 ^ t2",
+            "t4 = t0 | t3",
+            "t5 = ~t4",
+            "if t5 goto 0x1012"
+        ]
+    );
+    assert_eq!(
+        lift(&[0xe8, 0xfb, 0xff, 0xff, 0xff]),
+        [
+            "t0 = rsp",
+            "t1 = t0 - 0x8",
+            "store64 [t1], 0x1005",
+            "rsp = t1",
+            "call 0x1000"
+        ]
+    );
+    assert_eq!(
+        lift(&[0xc3]),
+        [
+            "t0 = rsp",
+            "t1 = load64 [t0]",
+            "t2 = t0 + 0x8",
+            "rsp = t2",
+            "ret t1"
+        ]
+    );
+    assert_eq!(lift(&[0xff, 0xe0]), ["t0 = rax", "goto t0"]);
+}
+
+#[test]
+fn strings() {
+    assert_eq!(
+        lift(&[0xf3, 0xaa]),
+        [
+            "t0 = rcx",
+            "t1 = t0 == 0x0",
+            "if t1 goto 0x1002",
+            "t2 = df",
+            "t3 = t2 ? 0xffffffffffffffff : 0x1",
+            "t4 = rdi",
+            "t5 = rax",
+            "store8 [t4], t5",
+            "t6 = t4 + t3",
+            "rdi = t6",
+            "t7 = t0 - 0x1",
+            "rcx = t7",
+            "goto 0x1000",
+        ]
+    );
+}
+
+#[test]
+fn intrinsics() {
+    assert_eq!(lift(&[0x0f, 0x05]), ["syscall()"]);
+    assert_eq!(lift(&[0xcc]), ["int(0x3)"]);
+    assert_eq!(lift(&[0x0f, 0x28, 0xc1]), ["unsupported"]);
+}
@@ -1,9 +1,13 @@
 #![cfg(test)]
 
+mod att;
+mod behavior;
 mod evex_generated;
+mod lift;
 mod opcode;
 mod operand;
 mod regspec;
+mod uarch;
 
 use std::fmt::Write;
 
@@ -0,0 +1,67 @@
+use crate::long_mode::uarch::{self, amd, intel};
+use crate::long_mode::{Decoder, Instruction};
+
+fn decode(data: &[u8]) -> Instruction {
+    Decoder::default().decode_slice(data).unwrap()
+}
+
+#[test]
+fn supports() {
+    // add eax, ecx
+    let add = decode(&[0x01, 0xc8]);
+    // vaddps ymm0, ymm1, ymm2
+    let avx = decode(&[0xc5, 0xf4, 0x58, 0xc2]);
+    // vaddps zmm0{k1}{z}, zmm1, zmm2
+    let avx512 = decode(&[0x62, 0xf1, 0x74, 0xc9, 0x58, 0xc2]);
+    // tzcnt eax, ecx
+    let tzcnt = decode(&[0xf3, 0x0f, 0xbc, 0xc1]);
+
+    assert!(amd::k8().supports(&add));
+    assert!(!amd::k8().supports(&avx));
+    assert!(intel::haswell().supports(&avx));
+    assert!(!intel::haswell().supports(&avx512));
+    assert!(intel::skylake().with_avx512().supports(&avx512));
+    assert!(amd::zen().supports(&tzcnt));
+    assert!(!intel::westmere().supports(&tzcnt));
+}
+
+#[test]
+fn extensions() {
+    assert_eq!(
+        uarch::extensions(&decode(&[0x01, 0xc8])),
+        Vec::<&str>::new()
+    );
+    assert_eq!(
+        uarch::extensions(&decode(&[0xc5, 0xf4, 0x58, 0xc2])),
+        vec!["avx"]
+    );
+    assert_eq!(
+        uarch::extensions(&decode(&[0x62, 0xf1, 0x74, 0xc9, 0x58, 0xc2])),
+        vec!["avx512"]
+    );
+    assert_eq!(
+        uarch::extensions(&decode(&[0x66, 0x0f, 0x38, 0xdc, 0xc1])),
+        vec!["aesni"]
+    );
+    assert_eq!(
+        uarch::extensions(&decode(&[0xf3, 0x0f, 0xbc, 0xc1])),
+        vec!["bmi1"]
+    );
+}
+
+#[test]
+fn vendor() {
+    // clzero
+    let clzero = decode(&[0x0f, 0x01, 0xfc]);
+    assert_eq!(uarch::vendor(&clzero), Some("amd"));
+    assert_eq!(uarch::extensions(&clzero), Vec::<&str>::new());
+
+    assert_eq!(uarch::vendor(&decode(&[0xc5, 0xf4, 0x58, 0xc2])), None);
+}
+
+#[test]
+fn profiles() {
+    assert!(uarch::by_name("zen").is_some());
+    assert!(uarch::by_name("haswell_ex").is_some());
+    assert!(uarch::by_name("pentium").is_none());
+}
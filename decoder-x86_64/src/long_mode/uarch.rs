@@ -1,3 +1,5 @@
+use crate::long_mode::{Decoder, Instruction};
+
 pub mod amd {
     //! most information about instruction set extensions for microarchitectures here was sourced
     //! from
@@ -197,3 +199,5 @@ pub mod intel {
     }
     // ice lake is shipping so that should probably be included...
 }
+
+crate::uarch::queries!();
@@ -0,0 +1,399 @@
+//! AT&T syntax, as printed by GCC and objdump.
+//!
+//! Operands are in reverse order, registers are prefixed by `%` and immediates by `$`. Memory
+//! operands are written as `segment:disp(base,index,scale)` and their size is encoded in the
+//! mnemonic (e.g. `movl`) when no register operand already implies it.
+
+use super::display::regspec_label;
+use super::{Instruction, MergeMode, Opcode, Operand, OperandSpec, RegSpec, RegisterBank};
+use decoder::{Decoded, FlowKind, ToTokens};
+use debugvault::Index;
+use tokenizing::{ColorScheme, Colors, TokenStream};
+
+fn size_suffix(size: u8) -> &'static str {
+    match size {
+        1 => "b",
+        2 => "w",
+        4 => "l",
+        8 => "q",
+        _ => "",
+    }
+}
+
+fn is_register(op: &Operand) -> bool {
+    matches!(
+        op,
+        Operand::Register(_)
+            | Operand::RegisterMaskMerge(..)
+            | Operand::RegisterMaskMergeSae(..)
+            | Operand::RegisterMaskMergeSaeNoround(..)
+    )
+}
+
+fn is_x87(op: &Operand) -> bool {
+    matches!(op, Operand::Register(spec) if spec.bank == RegisterBank::ST)
+}
+
+fn is_immediate(op: &Operand) -> bool {
+    matches!(
+        op,
+        Operand::ImmediateI8(_)
+            | Operand::ImmediateU8(_)
+            | Operand::ImmediateI16(_)
+            | Operand::ImmediateU16(_)
+            | Operand::ImmediateI32(_)
+            | Operand::ImmediateU32(_)
+    )
+}
+
+/// Mnemonic of an instruction, including the suffixes that describe the size of it's operands.
+fn mnemonic(inst: &Instruction) -> String {
+    let name = inst.opcode.name();
+    let operands: Vec<Operand> =
+        (0..inst.operand_count).map(|idx| inst.operand(idx)).collect();
+    let width = |op: &Operand| {
+        if op.is_memory() {
+            inst.mem_size
+        } else {
+            op.width().unwrap_or(0)
+        }
+    };
+
+    let renamed = match inst.opcode {
+        Opcode::CBW => "cbtw",
+        Opcode::CWDE => "cwtl",
+        Opcode::CDQE => "cltq",
+        Opcode::CWD => "cwtd",
+        Opcode::CDQ => "cltd",
+        Opcode::CQO => "cqto",
+        Opcode::JMPF => "ljmp",
+        Opcode::CALLF => "lcall",
+        Opcode::RETF => "lret",
+        Opcode::MOVZX | Opcode::MOVSX | Opcode::MOVSXD if operands.len() == 2 => {
+            let src = size_suffix(width(&operands[1]));
+            let dst = size_suffix(width(&operands[0]));
+            let extend = if inst.opcode == Opcode::MOVZX { "z" } else { "s" };
+
+            if src.is_empty() || dst.is_empty() || src == dst {
+                return name.to_string();
+            }
+
+            return format!("mov{extend}{src}{dst}");
+        }
+        _ => name,
+    };
+
+    // x87 registers don't tell the size of a memory operand
+    let ambiguous = operands.iter().any(Operand::is_memory)
+        && !operands.iter().any(|op| is_register(op) && !is_x87(op));
+    if !ambiguous {
+        return renamed.to_string();
+    }
+
+    let suffix = match inst.opcode {
+        Opcode::FILD
+        | Opcode::FIST
+        | Opcode::FISTP
+        | Opcode::FISTTP
+        | Opcode::FIADD
+        | Opcode::FISUB
+        | Opcode::FISUBR
+        | Opcode::FIMUL
+        | Opcode::FIDIV
+        | Opcode::FIDIVR
+        | Opcode::FICOM
+        | Opcode::FICOMP => match inst.mem_size {
+            2 => "s",
+            4 => "l",
+            8 => "ll",
+            _ => "",
+        },
+        Opcode::FLD
+        | Opcode::FST
+        | Opcode::FSTP
+        | Opcode::FADD
+        | Opcode::FSUB
+        | Opcode::FSUBR
+        | Opcode::FMUL
+        | Opcode::FDIV
+        | Opcode::FDIVR
+        | Opcode::FCOM
+        | Opcode::FCOMP => match inst.mem_size {
+            4 => "s",
+            8 => "l",
+            10 => "t",
+            _ => "",
+        },
+        // the operand size of these is never ambiguous
+        Opcode::JMP
+        | Opcode::CALL
+        | Opcode::LDMXCSR
+        | Opcode::STMXCSR
+        | Opcode::VLDMXCSR
+        | Opcode::VSTMXCSR => "",
+        opcode if opcode.is_setcc() || name.starts_with('f') => "",
+        _ => size_suffix(inst.mem_size),
+    };
+
+    format!("{renamed}{suffix}")
+}
+
+fn register(stream: &mut TokenStream, spec: &RegSpec) {
+    stream.push("%", Colors::register());
+    if *spec == RegSpec::st(0) {
+        stream.push("st", Colors::register());
+    } else {
+        stream.push(regspec_label(spec), Colors::register());
+    }
+}
+
+fn mask(stream: &mut TokenStream, mask: &RegSpec) {
+    stream.push("{", Colors::brackets());
+    register(stream, mask);
+    stream.push("}", Colors::brackets());
+}
+
+fn mask_merge(stream: &mut TokenStream, spec: &RegSpec, merge_mode: MergeMode) {
+    if spec.num != 0 {
+        mask(stream, spec);
+    }
+    if let MergeMode::Zero = merge_mode {
+        stream.push("{", Colors::brackets());
+        stream.push("z", Colors::register());
+        stream.push("}", Colors::brackets());
+    }
+}
+
+fn immediate(stream: &mut TokenStream, imm: i64) {
+    stream.push("$", Colors::immediate());
+    stream.push_owned(decoder::encode_hex(imm), Colors::immediate());
+}
+
+fn address(stream: &mut TokenStream, symbols: &Index, addr: usize) {
+    match symbols.get_sym_by_addr(addr) {
+        Some(symbol) => {
+            for token in symbol.name() {
+                stream.push_token(token.clone());
+            }
+        }
+        None => stream.push_owned(decoder::encode_hex(addr as i64), Colors::immediate()),
+    }
+}
+
+/// `disp(base,index,scale)`.
+fn memory(
+    stream: &mut TokenStream,
+    disp: i32,
+    base: Option<&RegSpec>,
+    index: Option<(&RegSpec, u8)>,
+) {
+    if disp != 0 {
+        stream.push_owned(decoder::encode_hex(disp as i64), Colors::immediate());
+    }
+
+    stream.push("(", Colors::brackets());
+    if let Some(base) = base {
+        register(stream, base);
+    }
+    if let Some((index, scale)) = index {
+        stream.push(",", Colors::expr());
+        register(stream, index);
+        stream.push(",", Colors::expr());
+        stream.push_owned(scale.to_string(), Colors::immediate());
+    }
+    stream.push(")", Colors::brackets());
+}
+
+/// Operands that were resolved to an address by [`Decoded::update_rel_addrs`].
+fn tokenize_symbolic(
+    op: &Operand,
+    stream: &mut TokenStream,
+    symbols: &Index,
+    addr: usize,
+    is_branch: bool,
+) -> bool {
+    match *op {
+        _ if is_immediate(op) => {
+            // branch targets aren't immediates in AT&T syntax
+            if !is_branch {
+                stream.push("$", Colors::immediate());
+            }
+            address(stream, symbols, addr);
+        }
+        Operand::DisplacementU16(_) | Operand::DisplacementU32(_) => {
+            address(stream, symbols, addr)
+        }
+        Operand::RegDisp(RegSpec::EIP, _) => match symbols.get_sym_by_addr(addr) {
+            Some(symbol) => {
+                for token in symbol.name() {
+                    stream.push_token(token.clone());
+                }
+                memory(stream, 0, Some(&RegSpec::EIP), None);
+            }
+            None => return false,
+        },
+        _ => return false,
+    }
+
+    true
+}
+
+fn tokenize_operand(
+    op: &Operand,
+    stream: &mut TokenStream,
+    symbols: &Index,
+    imm_override: Option<usize>,
+    is_branch: bool,
+) {
+    if let Some(addr) = imm_override {
+        if tokenize_symbolic(op, stream, symbols, addr, is_branch) {
+            return;
+        }
+    }
+
+    match *op {
+        Operand::ImmediateU8(imm) => immediate(stream, imm as i64),
+        Operand::ImmediateI8(imm) => immediate(stream, imm as i64),
+        Operand::ImmediateU16(imm) => immediate(stream, imm as i64),
+        Operand::ImmediateI16(imm) => immediate(stream, imm as i64),
+        Operand::ImmediateU32(imm) => immediate(stream, imm as i64),
+        Operand::ImmediateI32(imm) => immediate(stream, imm as i64),
+        Operand::AbsoluteFarAddress { segment, address } => {
+            immediate(stream, segment as i64);
+            stream.push(",", Colors::expr());
+            immediate(stream, address as i64);
+        }
+        Operand::Register(ref spec) => register(stream, spec),
+        Operand::RegisterMaskMerge(ref spec, ref mask, merge_mode) => {
+            register(stream, spec);
+            mask_merge(stream, mask, merge_mode);
+        }
+        Operand::RegisterMaskMergeSae(ref spec, ref mask, merge_mode, sae_mode) => {
+            // rounding comes before the operand it applies to
+            sae_mode.tokenize(stream, symbols);
+            stream.push(",", Colors::expr());
+            register(stream, spec);
+            mask_merge(stream, mask, merge_mode);
+        }
+        Operand::RegisterMaskMergeSaeNoround(ref spec, ref mask, merge_mode) => {
+            stream.push("{", Colors::brackets());
+            stream.push("sae", Colors::register());
+            stream.push("}", Colors::brackets());
+            stream.push(",", Colors::expr());
+            register(stream, spec);
+            mask_merge(stream, mask, merge_mode);
+        }
+        Operand::DisplacementU32(imm) => {
+            stream.push_owned(decoder::encode_hex(imm as i64), Colors::immediate());
+        }
+        Operand::DisplacementU16(imm) => {
+            stream.push_owned(decoder::encode_hex(imm as i64), Colors::immediate());
+        }
+        Operand::RegDeref(ref spec) => memory(stream, 0, Some(spec), None),
+        Operand::RegDisp(ref spec, disp) => memory(stream, disp, Some(spec), None),
+        Operand::RegScale(ref spec, scale) => memory(stream, 0, None, Some((spec, scale))),
+        Operand::RegScaleDisp(ref spec, scale, disp) => {
+            memory(stream, disp, None, Some((spec, scale)))
+        }
+        Operand::RegIndexBase(ref base, ref index) => {
+            memory(stream, 0, Some(base), Some((index, 1)))
+        }
+        Operand::RegIndexBaseDisp(ref base, ref index, disp) => {
+            memory(stream, disp, Some(base), Some((index, 1)))
+        }
+        Operand::RegIndexBaseScale(ref base, ref index, scale) => {
+            memory(stream, 0, Some(base), Some((index, scale)))
+        }
+        Operand::RegIndexBaseScaleDisp(ref base, ref index, scale, disp) => {
+            memory(stream, disp, Some(base), Some((index, scale)))
+        }
+        Operand::RegDerefMasked(ref spec, ref mask_reg) => {
+            memory(stream, 0, Some(spec), None);
+            mask(stream, mask_reg);
+        }
+        Operand::RegDispMasked(ref spec, disp, ref mask_reg) => {
+            memory(stream, disp, Some(spec), None);
+            mask(stream, mask_reg);
+        }
+        Operand::RegScaleMasked(ref spec, scale, ref mask_reg) => {
+            memory(stream, 0, None, Some((spec, scale)));
+            mask(stream, mask_reg);
+        }
+        Operand::RegScaleDispMasked(ref spec, scale, disp, ref mask_reg) => {
+            memory(stream, disp, None, Some((spec, scale)));
+            mask(stream, mask_reg);
+        }
+        Operand::RegIndexBaseMasked(ref base, ref index, ref mask_reg) => {
+            memory(stream, 0, Some(base), Some((index, 1)));
+            mask(stream, mask_reg);
+        }
+        Operand::RegIndexBaseDispMasked(ref base, ref index, disp, ref mask_reg) => {
+            memory(stream, disp, Some(base), Some((index, 1)));
+            mask(stream, mask_reg);
+        }
+        Operand::RegIndexBaseScaleMasked(ref base, ref index, scale, ref mask_reg) => {
+            memory(stream, 0, Some(base), Some((index, scale)));
+            mask(stream, mask_reg);
+        }
+        Operand::RegIndexBaseScaleDispMasked(ref base, ref index, scale, disp, ref mask_reg) => {
+            memory(stream, disp, Some(base), Some((index, scale)));
+            mask(stream, mask_reg);
+        }
+        Operand::Nothing => {}
+    }
+}
+
+pub(super) fn tokenize(inst: &Instruction, stream: &mut TokenStream, symbols: &Index) {
+    let mut op = String::new();
+    inst.push_prefixes(&mut op);
+    op.push_str(&mnemonic(inst));
+    stream.push_owned(op, Colors::opcode());
+
+    let imm_override = inst.imm_override();
+    let is_branch = matches!(
+        inst.flow(),
+        FlowKind::Jump | FlowKind::ConditionalJump | FlowKind::Call
+    );
+
+    // the stack top is implied when x87 instructions access memory
+    let has_memory = (0..inst.operand_count).any(|idx| inst.operand(idx).is_memory());
+    let mut order: Vec<u8> = (0..inst.operand_count)
+        .filter(|&idx| inst.operands[idx as usize] != OperandSpec::Nothing)
+        .filter(|&idx| !(has_memory && inst.operand(idx) == Operand::Register(RegSpec::st(0))))
+        .collect();
+
+    // `enter` is the one instruction that keeps it's operand order
+    if inst.opcode != Opcode::ENTER {
+        order.reverse();
+    }
+
+    for (nth, idx) in order.into_iter().enumerate() {
+        if nth == 0 {
+            stream.push(" ", Colors::spacing());
+        } else {
+            stream.push(",", Colors::expr());
+        }
+
+        let op = inst.operand(idx);
+
+        // indirect jumps and calls
+        if is_branch && !is_immediate(&op) {
+            stream.push("*", Colors::expr());
+        }
+
+        if let Some(prefix) = inst.segment_override_for_op(idx) {
+            stream.push("%", Colors::segment());
+            stream.push_owned(prefix.to_string(), Colors::segment());
+            stream.push(":", Colors::expr());
+        }
+
+        tokenize_operand(&op, stream, symbols, imm_override, is_branch);
+
+        if let Some(scale) = inst.broadcast(idx, &op) {
+            stream.push("{", Colors::brackets());
+            stream.push("1to", Colors::expr());
+            stream.push_owned(scale.to_string(), Colors::immediate());
+            stream.push("}", Colors::brackets());
+        }
+    }
+}
@@ -0,0 +1,742 @@
+//! Semantic queries on decoded instructions: control flow, registers and memory accesses.
+//!
+//! Explicit operands are classified by a handful of rules that hold for the vast majority of
+//! instructions, implicit operands are listed per opcode. Implicit registers are named by their
+//! full 32-bit name, or their 16-bit name in real mode where that's the default size.
+
+use super::{Instruction, MergeMode, Opcode, Operand, RegSpec};
+use decoder::{Direction, FlowKind, MemoryAccess};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+pub(super) fn flow(inst: &Instruction) -> FlowKind {
+    match inst.opcode {
+        Opcode::CALL | Opcode::CALLF => FlowKind::Call,
+        Opcode::JMP | Opcode::JMPF | Opcode::JMPE => FlowKind::Jump,
+        Opcode::JCXZ | Opcode::JECXZ | Opcode::LOOP | Opcode::LOOPZ | Opcode::LOOPNZ => {
+            FlowKind::ConditionalJump
+        }
+        op if op.is_jcc() => FlowKind::ConditionalJump,
+        Opcode::RETURN
+        | Opcode::RETF
+        | Opcode::IRET
+        | Opcode::IRETD
+        | Opcode::IRETQ
+        | Opcode::UIRET
+        | Opcode::SYSRET
+        | Opcode::SYSEXIT => FlowKind::Return,
+        Opcode::INT
+        | Opcode::INTO
+        | Opcode::SYSCALL
+        | Opcode::SYSENTER
+        | Opcode::VMCALL
+        | Opcode::VMMCALL => FlowKind::Interrupt,
+        Opcode::HLT | Opcode::UD0 | Opcode::UD1 | Opcode::UD2 => FlowKind::Halt,
+        _ => FlowKind::Sequential,
+    }
+}
+
+pub(super) fn targets(inst: &Instruction) -> Vec<usize> {
+    // far branches in real mode encode a segment:offset pair which resolves to a linear address
+    if let Operand::AbsoluteFarAddress { segment, address } = inst.operand(0) {
+        let far = matches!(inst.opcode, Opcode::CALLF | Opcode::JMPF);
+        if inst.real_mode && far && inst.operand_count == 1 {
+            return vec![((segment as usize) << 4) + address as usize];
+        }
+    }
+
+    if !inst.imm_override || inst.operand_count == 0 {
+        return Vec::new();
+    }
+
+    if !matches!(
+        flow(inst),
+        FlowKind::Jump | FlowKind::ConditionalJump | FlowKind::Call
+    ) {
+        return Vec::new();
+    }
+
+    match inst.operand(0) {
+        Operand::ImmediateI8(_)
+        | Operand::ImmediateU8(_)
+        | Operand::ImmediateI16(_)
+        | Operand::ImmediateU16(_)
+        | Operand::ImmediateI32(_)
+        | Operand::ImmediateU32(_) => vec![inst.imm as usize],
+        _ => Vec::new(),
+    }
+}
+
+/// How the first operand is accessed.
+///
+/// Opcodes that aren't known to only write it are assumed to read it too, so its register is
+/// over-reported rather than missed.
+fn destination(inst: &Instruction) -> Access {
+    match inst.opcode {
+        op if op.is_jcc() => Access::Read,
+        op if op.is_setcc() => Access::Write,
+        Opcode::IMUL if inst.operand_count == 1 => Access::Read,
+        Opcode::CMP
+        | Opcode::TEST
+        | Opcode::BT
+        | Opcode::PUSH
+        | Opcode::CALL
+        | Opcode::CALLF
+        | Opcode::JMP
+        | Opcode::JMPF
+        | Opcode::JMPE
+        | Opcode::JCXZ
+        | Opcode::JECXZ
+        | Opcode::LOOP
+        | Opcode::LOOPZ
+        | Opcode::LOOPNZ
+        | Opcode::RETURN
+        | Opcode::RETF
+        | Opcode::INT
+        | Opcode::OUT
+        | Opcode::COMISS
+        | Opcode::COMISD
+        | Opcode::UCOMISS
+        | Opcode::UCOMISD
+        | Opcode::PTEST
+        | Opcode::VPTEST
+        | Opcode::MUL
+        | Opcode::DIV
+        | Opcode::IDIV
+        | Opcode::NOP => Access::Read,
+        Opcode::MOV
+        | Opcode::MOVZX
+        | Opcode::MOVSX
+        | Opcode::MOVSXD
+        | Opcode::LEA
+        | Opcode::POP
+        | Opcode::IN
+        | Opcode::BSF
+        | Opcode::BSR
+        | Opcode::LZCNT
+        | Opcode::TZCNT
+        | Opcode::POPCNT => Access::Write,
+        _ if merge_masked(inst) => Access::ReadWrite,
+        _ if overwrites(inst) || non_destructive(inst) => Access::Write,
+        _ => Access::ReadWrite,
+    }
+}
+
+/// Whether elements of the destination that are masked off keep their value.
+fn merge_masked(inst: &Instruction) -> bool {
+    match inst.operand(0) {
+        Operand::RegisterMaskMerge(_, mask, MergeMode::Merge)
+        | Operand::RegisterMaskMergeSae(_, mask, MergeMode::Merge, _)
+        | Operand::RegisterMaskMergeSaeNoround(_, mask, MergeMode::Merge) => mask.num() != 0,
+        Operand::RegDerefMasked(_, mask)
+        | Operand::RegDispMasked(_, _, mask)
+        | Operand::RegScaleMasked(_, _, mask)
+        | Operand::RegScaleDispMasked(_, _, _, mask)
+        | Operand::RegIndexBaseMasked(_, _, mask)
+        | Operand::RegIndexBaseDispMasked(_, _, _, mask)
+        | Operand::RegIndexBaseScaleMasked(_, _, _, mask)
+        | Operand::RegIndexBaseScaleDispMasked(_, _, _, _, mask) => mask.num() != 0,
+        _ => false,
+    }
+}
+
+/// Moves and conversions that replace their whole destination.
+fn overwrites(inst: &Instruction) -> bool {
+    match inst.opcode {
+        Opcode::MOVS
+        | Opcode::MOVAPS
+        | Opcode::MOVAPD
+        | Opcode::MOVUPS
+        | Opcode::MOVUPD
+        | Opcode::MOVDQA
+        | Opcode::MOVDQU
+        | Opcode::MOVD
+        | Opcode::MOVQ
+        | Opcode::MOVQ2DQ
+        | Opcode::MOVDQ2Q
+        | Opcode::MOVDDUP
+        | Opcode::MOVSHDUP
+        | Opcode::MOVSLDUP
+        | Opcode::MOVMSKPS
+        | Opcode::MOVMSKPD
+        | Opcode::MOVNTI
+        | Opcode::MOVNTPS
+        | Opcode::MOVNTPD
+        | Opcode::MOVNTSS
+        | Opcode::MOVNTSD
+        | Opcode::MOVNTQ
+        | Opcode::MOVNTDQ
+        | Opcode::MOVNTDQA
+        | Opcode::MOVBE
+        | Opcode::MOVDIRI
+        | Opcode::MOVDIR64B
+        | Opcode::CVTDQ2PS
+        | Opcode::CVTDQ2PD
+        | Opcode::CVTPS2DQ
+        | Opcode::CVTTPS2DQ
+        | Opcode::CVTPD2DQ
+        | Opcode::CVTTPD2DQ
+        | Opcode::CVTPS2PD
+        | Opcode::CVTPD2PS
+        | Opcode::CVTPI2PD
+        | Opcode::CVTPS2PI
+        | Opcode::CVTTPS2PI
+        | Opcode::CVTPD2PI
+        | Opcode::CVTTPD2PI
+        | Opcode::CVTSS2SI
+        | Opcode::CVTTSS2SI
+        | Opcode::CVTSD2SI
+        | Opcode::CVTTSD2SI
+        | Opcode::VMOVDDUP
+        | Opcode::VCVTPD2DQ
+        | Opcode::VCVTDQ2PD
+        | Opcode::VCVTDQ2PS
+        | Opcode::VCVTPD2PS
+        | Opcode::VCVTPH2PS
+        | Opcode::VCVTPS2DQ
+        | Opcode::VCVTPS2PD
+        | Opcode::VCVTSS2SD
+        | Opcode::VCVTSI2SS
+        | Opcode::VCVTSI2SD
+        | Opcode::VCVTSD2SI
+        | Opcode::VCVTSD2SS
+        | Opcode::VCVTPS2PH
+        | Opcode::VCVTSS2SI
+        | Opcode::VCVTTPD2DQ
+        | Opcode::VCVTTPS2DQ
+        | Opcode::VCVTTSS2SI
+        | Opcode::VCVTTSD2SI
+        | Opcode::VMOVAPD
+        | Opcode::VMOVAPS
+        | Opcode::VMOVD
+        | Opcode::VMOVDQA
+        | Opcode::VMOVDQU
+        | Opcode::VMOVHLPS
+        | Opcode::VMOVHPD
+        | Opcode::VMOVHPS
+        | Opcode::VMOVLHPS
+        | Opcode::VMOVLPD
+        | Opcode::VMOVLPS
+        | Opcode::VMOVMSKPD
+        | Opcode::VMOVMSKPS
+        | Opcode::VMOVNTDQ
+        | Opcode::VMOVNTDQA
+        | Opcode::VMOVNTPD
+        | Opcode::VMOVNTPS
+        | Opcode::VMOVQ
+        | Opcode::VMOVSS
+        | Opcode::VMOVSD
+        | Opcode::VMOVSHDUP
+        | Opcode::VMOVSLDUP
+        | Opcode::VMOVUPD
+        | Opcode::VMOVUPS
+        | Opcode::VCVTPD2UDQ
+        | Opcode::VCVTTPD2UDQ
+        | Opcode::VCVTPS2UDQ
+        | Opcode::VCVTTPS2UDQ
+        | Opcode::VCVTQQ2PD
+        | Opcode::VCVTQQ2PS
+        | Opcode::VCVTSD2USI
+        | Opcode::VCVTTSD2USI
+        | Opcode::VCVTSS2USI
+        | Opcode::VCVTTSS2USI
+        | Opcode::VCVTUDQ2PD
+        | Opcode::VCVTUDQ2PS
+        | Opcode::VCVTUSI2USD
+        | Opcode::VCVTUSI2USS
+        | Opcode::VMOVDQA32
+        | Opcode::VMOVDQA64
+        | Opcode::VMOVDQU32
+        | Opcode::VMOVDQU64
+        | Opcode::VCVTTPD2QQ
+        | Opcode::VCVTPD2QQ
+        | Opcode::VCVTTPD2UQQ
+        | Opcode::VCVTPD2UQQ
+        | Opcode::VCVTTPS2QQ
+        | Opcode::VCVTPS2QQ
+        | Opcode::VCVTTPS2UQQ
+        | Opcode::VCVTPS2UQQ
+        | Opcode::VCVTUQQ2PD
+        | Opcode::VCVTUQQ2PS
+        | Opcode::VMOVDQU8
+        | Opcode::VMOVDQU16
+        | Opcode::VCVTNEPS2BF16
+        | Opcode::VCVTNE2PS2BF16
+        | Opcode::VCVTUSI2SD
+        | Opcode::VCVTUSI2SS => true,
+        // scalar loads zero the rest of the register, moves between registers merge into it
+        Opcode::MOVSS | Opcode::MOVSD => {
+            inst.operand(0).is_memory() || inst.operand(1).is_memory()
+        }
+        // moves of half a register only replace their destination when it's in memory
+        Opcode::MOVLPS
+        | Opcode::MOVLPD
+        | Opcode::MOVHPS
+        | Opcode::MOVHPD
+        | Opcode::MOVLHPS
+        | Opcode::MOVHLPS => inst.operand(0).is_memory(),
+        _ => false,
+    }
+}
+
+/// Three operand vex and evex forms take their sources from other operands than the
+/// destination, except for those accumulating into it.
+fn non_destructive(inst: &Instruction) -> bool {
+    let vex = inst.prefixes.vex().is_some() || inst.prefixes.evex().is_some();
+    if !vex || inst.operand_count < 3 {
+        return false;
+    }
+
+    !matches!(
+        inst.opcode,
+        Opcode::VFMADD132PD
+            | Opcode::VFMADD132PS
+            | Opcode::VFMADD132SD
+            | Opcode::VFMADD132SS
+            | Opcode::VFMADD213PD
+            | Opcode::VFMADD213PS
+            | Opcode::VFMADD213SD
+            | Opcode::VFMADD213SS
+            | Opcode::VFMADD231PD
+            | Opcode::VFMADD231PS
+            | Opcode::VFMADD231SD
+            | Opcode::VFMADD231SS
+            | Opcode::VFMADDSUB132PD
+            | Opcode::VFMADDSUB132PS
+            | Opcode::VFMADDSUB213PD
+            | Opcode::VFMADDSUB213PS
+            | Opcode::VFMADDSUB231PD
+            | Opcode::VFMADDSUB231PS
+            | Opcode::VFMSUB132PD
+            | Opcode::VFMSUB132PS
+            | Opcode::VFMSUB132SD
+            | Opcode::VFMSUB132SS
+            | Opcode::VFMSUB213PD
+            | Opcode::VFMSUB213PS
+            | Opcode::VFMSUB213SD
+            | Opcode::VFMSUB213SS
+            | Opcode::VFMSUB231PD
+            | Opcode::VFMSUB231PS
+            | Opcode::VFMSUB231SD
+            | Opcode::VFMSUB231SS
+            | Opcode::VFMSUBADD132PD
+            | Opcode::VFMSUBADD132PS
+            | Opcode::VFMSUBADD213PD
+            | Opcode::VFMSUBADD213PS
+            | Opcode::VFMSUBADD231PD
+            | Opcode::VFMSUBADD231PS
+            | Opcode::VFNMADD132PD
+            | Opcode::VFNMADD132PS
+            | Opcode::VFNMADD132SD
+            | Opcode::VFNMADD132SS
+            | Opcode::VFNMADD213PD
+            | Opcode::VFNMADD213PS
+            | Opcode::VFNMADD213SD
+            | Opcode::VFNMADD213SS
+            | Opcode::VFNMADD231PD
+            | Opcode::VFNMADD231PS
+            | Opcode::VFNMADD231SD
+            | Opcode::VFNMADD231SS
+            | Opcode::VFNMSUB132PD
+            | Opcode::VFNMSUB132PS
+            | Opcode::VFNMSUB132SD
+            | Opcode::VFNMSUB132SS
+            | Opcode::VFNMSUB213PD
+            | Opcode::VFNMSUB213PS
+            | Opcode::VFNMSUB213SD
+            | Opcode::VFNMSUB213SS
+            | Opcode::VFNMSUB231PD
+            | Opcode::VFNMSUB231PS
+            | Opcode::VFNMSUB231SD
+            | Opcode::VFNMSUB231SS
+            | Opcode::V4FMADDPS
+            | Opcode::V4FMADDSS
+            | Opcode::V4FNMADDPS
+            | Opcode::V4FNMADDSS
+            | Opcode::VP4DPWSSD
+            | Opcode::VP4DPWSSDS
+            | Opcode::VPDPBUSD
+            | Opcode::VPDPBUSDS
+            | Opcode::VPDPWSSD
+            | Opcode::VPDPWSSDS
+            | Opcode::VPMADD52HUQ
+            | Opcode::VPMADD52LUQ
+            | Opcode::VPSHLDVW
+            | Opcode::VPSHLDVD
+            | Opcode::VPSHLDVQ
+            | Opcode::VPSHRDVW
+            | Opcode::VPSHRDVD
+            | Opcode::VPSHRDVQ
+            | Opcode::VPERMI2B
+            | Opcode::VPERMI2W
+            | Opcode::VPERMI2D
+            | Opcode::VPERMI2Q
+            | Opcode::VPERMI2PS
+            | Opcode::VPERMI2PD
+            | Opcode::VPERMT2B
+            | Opcode::VPERMT2W
+            | Opcode::VPERMT2D
+            | Opcode::VPERMT2Q
+            | Opcode::VPERMT2PS
+            | Opcode::VPERMT2PD
+            | Opcode::VPTERNLOGD
+            | Opcode::VPTERNLOGQ
+            | Opcode::VGATHERDPS
+            | Opcode::VGATHERDPD
+            | Opcode::VGATHERQPS
+            | Opcode::VGATHERQPD
+            | Opcode::VPGATHERDD
+            | Opcode::VPGATHERDQ
+            | Opcode::VPGATHERQD
+            | Opcode::VPGATHERQQ
+            | Opcode::VMASKMOVPS
+            | Opcode::VMASKMOVPD
+            | Opcode::VPMASKMOVD
+            | Opcode::VPMASKMOVQ
+    )
+}
+
+/// How any operand after the first is accessed.
+fn source(inst: &Instruction) -> Access {
+    match inst.opcode {
+        Opcode::XCHG | Opcode::XADD => Access::ReadWrite,
+        _ => Access::Read,
+    }
+}
+
+fn push(regs: &mut Vec<&'static str>, reg: &'static str) {
+    if !regs.contains(&reg) {
+        regs.push(reg);
+    }
+}
+
+/// Registers used to compute the address of a memory operand or the register itself.
+fn operand_regs(operand: &Operand, addr: &mut Vec<&'static str>, reg: &mut Option<RegSpec>) {
+    match *operand {
+        Operand::Register(r) => *reg = Some(r),
+        Operand::RegisterMaskMerge(r, mask, _)
+        | Operand::RegisterMaskMergeSae(r, mask, _, _)
+        | Operand::RegisterMaskMergeSaeNoround(r, mask, _) => {
+            *reg = Some(r);
+            if mask.num() != 0 {
+                push(addr, mask.name());
+            }
+        }
+        Operand::RegDeref(base) | Operand::RegDisp(base, _) | Operand::RegScale(base, _) => {
+            push(addr, base.name());
+        }
+        Operand::RegScaleDisp(index, _, _) => push(addr, index.name()),
+        Operand::RegIndexBase(base, index)
+        | Operand::RegIndexBaseDisp(base, index, _)
+        | Operand::RegIndexBaseScale(base, index, _)
+        | Operand::RegIndexBaseScaleDisp(base, index, _, _) => {
+            push(addr, base.name());
+            push(addr, index.name());
+        }
+        Operand::RegDerefMasked(base, mask)
+        | Operand::RegDispMasked(base, _, mask)
+        | Operand::RegScaleMasked(base, _, mask)
+        | Operand::RegScaleDispMasked(base, _, _, mask) => {
+            push(addr, base.name());
+            push(addr, mask.name());
+        }
+        Operand::RegIndexBaseMasked(base, index, mask)
+        | Operand::RegIndexBaseDispMasked(base, index, _, mask)
+        | Operand::RegIndexBaseScaleMasked(base, index, _, mask)
+        | Operand::RegIndexBaseScaleDispMasked(base, index, _, _, mask) => {
+            push(addr, base.name());
+            push(addr, index.name());
+            push(addr, mask.name());
+        }
+        _ => {}
+    }
+}
+
+fn reads_flags(opcode: Opcode) -> bool {
+    opcode.is_jcc()
+        || opcode.is_cmovcc()
+        || opcode.is_setcc()
+        || matches!(
+            opcode,
+            Opcode::ADC
+                | Opcode::SBB
+                | Opcode::RCL
+                | Opcode::RCR
+                | Opcode::LOOPZ
+                | Opcode::LOOPNZ
+                | Opcode::PUSHF
+                | Opcode::LAHF
+                | Opcode::INTO
+                | Opcode::CMC
+        )
+}
+
+fn writes_flags(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::ADD
+            | Opcode::ADC
+            | Opcode::SUB
+            | Opcode::SBB
+            | Opcode::AND
+            | Opcode::OR
+            | Opcode::XOR
+            | Opcode::CMP
+            | Opcode::TEST
+            | Opcode::INC
+            | Opcode::DEC
+            | Opcode::NEG
+            | Opcode::SAL
+            | Opcode::SHL
+            | Opcode::SHR
+            | Opcode::SAR
+            | Opcode::ROL
+            | Opcode::ROR
+            | Opcode::RCL
+            | Opcode::RCR
+            | Opcode::MUL
+            | Opcode::IMUL
+            | Opcode::DIV
+            | Opcode::IDIV
+            | Opcode::BT
+            | Opcode::BTS
+            | Opcode::BTR
+            | Opcode::BTC
+            | Opcode::BSF
+            | Opcode::BSR
+            | Opcode::LZCNT
+            | Opcode::TZCNT
+            | Opcode::POPCNT
+            | Opcode::CMPXCHG
+            | Opcode::CMPXCHG8B
+            | Opcode::CMPXCHG16B
+            | Opcode::XADD
+            | Opcode::POPF
+            | Opcode::SAHF
+            | Opcode::STC
+            | Opcode::CLC
+            | Opcode::CMC
+            | Opcode::STD
+            | Opcode::CLD
+            | Opcode::SCAS
+            | Opcode::CMPS
+            | Opcode::COMISS
+            | Opcode::COMISD
+            | Opcode::UCOMISS
+            | Opcode::UCOMISD
+            | Opcode::PTEST
+            | Opcode::VPTEST
+    )
+}
+
+type Implicit = (&'static [&'static str], &'static [&'static str]);
+
+/// Registers that are read and written without being named as an operand.
+fn implicit_regs(inst: &Instruction) -> Implicit {
+    if inst.real_mode {
+        if let Some(regs) = implicit_word_regs(inst) {
+            return regs;
+        }
+    }
+
+    match inst.opcode {
+        Opcode::PUSH
+        | Opcode::POP
+        | Opcode::PUSHF
+        | Opcode::POPF
+        | Opcode::CALL
+        | Opcode::CALLF
+        | Opcode::RETURN
+        | Opcode::RETF
+        | Opcode::IRET
+        | Opcode::IRETD
+        | Opcode::IRETQ => (&["esp"], &["esp"]),
+        Opcode::PUSHA => (
+            &["esp", "eax", "ecx", "edx", "ebx", "ebp", "esi", "edi"],
+            &["esp"],
+        ),
+        Opcode::POPA => (
+            &["esp"],
+            &["esp", "eax", "ecx", "edx", "ebx", "ebp", "esi", "edi"],
+        ),
+        Opcode::ENTER | Opcode::LEAVE => (&["esp", "ebp"], &["esp", "ebp"]),
+        Opcode::MUL | Opcode::DIV | Opcode::IDIV => (&["eax", "edx"], &["eax", "edx"]),
+        Opcode::IMUL if inst.operand_count == 1 => (&["eax", "edx"], &["eax", "edx"]),
+        Opcode::CBW | Opcode::CWDE | Opcode::CDQE => (&["eax"], &["eax"]),
+        Opcode::CWD | Opcode::CDQ | Opcode::CQO => (&["eax"], &["edx"]),
+        Opcode::CMPXCHG => (&["eax"], &["eax"]),
+        Opcode::CMPXCHG8B | Opcode::CMPXCHG16B => (&["eax", "edx", "ebx", "ecx"], &["eax", "edx"]),
+        Opcode::LOOP | Opcode::LOOPZ | Opcode::LOOPNZ => (&["ecx"], &["ecx"]),
+        Opcode::JCXZ => (&["cx"], &[]),
+        Opcode::JECXZ => (&["ecx"], &[]),
+        Opcode::MOVS | Opcode::CMPS => (&["esi", "edi"], &["esi", "edi"]),
+        Opcode::LODS => (&["esi"], &["esi", "eax"]),
+        Opcode::STOS | Opcode::SCAS => (&["edi", "eax"], &["edi"]),
+        Opcode::INS => (&["edi", "edx"], &["edi"]),
+        Opcode::OUTS => (&["esi", "edx"], &["esi"]),
+        Opcode::XLAT => (&["ebx", "eax"], &["eax"]),
+        Opcode::CPUID => (&["eax", "ecx"], &["eax", "ebx", "ecx", "edx"]),
+        Opcode::RDTSC => (&[], &["eax", "edx"]),
+        Opcode::RDTSCP => (&[], &["eax", "edx", "ecx"]),
+        Opcode::SYSCALL => (&["eax"], &["eax", "ecx"]),
+        _ => (&[], &[]),
+    }
+}
+
+/// Implicit registers that are narrowed to 16 bits in real mode.
+fn implicit_word_regs(inst: &Instruction) -> Option<Implicit> {
+    let regs: Implicit = match inst.opcode {
+        Opcode::PUSH
+        | Opcode::POP
+        | Opcode::PUSHF
+        | Opcode::POPF
+        | Opcode::CALL
+        | Opcode::CALLF
+        | Opcode::RETURN
+        | Opcode::RETF
+        | Opcode::IRET
+        | Opcode::IRETD
+        | Opcode::IRETQ => (&["sp"], &["sp"]),
+        Opcode::PUSHA => (
+            &["sp", "ax", "cx", "dx", "bx", "bp", "si", "di"],
+            &["sp"],
+        ),
+        Opcode::POPA => (
+            &["sp"],
+            &["sp", "ax", "cx", "dx", "bx", "bp", "si", "di"],
+        ),
+        Opcode::ENTER | Opcode::LEAVE => (&["sp", "bp"], &["sp", "bp"]),
+        Opcode::MUL | Opcode::DIV | Opcode::IDIV => (&["ax", "dx"], &["ax", "dx"]),
+        Opcode::IMUL if inst.operand_count == 1 => (&["ax", "dx"], &["ax", "dx"]),
+        Opcode::CBW => (&["ax"], &["ax"]),
+        Opcode::CWD => (&["ax"], &["dx"]),
+        Opcode::CMPXCHG => (&["ax"], &["ax"]),
+        Opcode::LOOP | Opcode::LOOPZ | Opcode::LOOPNZ => (&["cx"], &["cx"]),
+        Opcode::MOVS | Opcode::CMPS => (&["si", "di"], &["si", "di"]),
+        Opcode::LODS => (&["si"], &["si", "ax"]),
+        Opcode::STOS | Opcode::SCAS => (&["di", "ax"], &["di"]),
+        Opcode::INS => (&["di", "dx"], &["di"]),
+        Opcode::OUTS => (&["si", "dx"], &["si"]),
+        Opcode::XLAT => (&["bx", "ax"], &["ax"]),
+        _ => return None,
+    };
+
+    Some(regs)
+}
+
+/// Collects the registers read and written by an instruction.
+pub(super) fn registers(inst: &Instruction) -> (Vec<&'static str>, Vec<&'static str>) {
+    let mut read = Vec::new();
+    let mut written = Vec::new();
+
+    for idx in 0..inst.operand_count {
+        let mut reg = None;
+        operand_regs(&inst.operand(idx), &mut read, &mut reg);
+
+        let access = if idx == 0 {
+            destination(inst)
+        } else {
+            source(inst)
+        };
+        if let Some(reg) = reg {
+            if access != Access::Write {
+                push(&mut read, reg.name());
+            }
+            if access != Access::Read {
+                push(&mut written, reg.name());
+            }
+        }
+    }
+
+    let (implicit_read, implicit_written) = implicit_regs(inst);
+    implicit_read.iter().for_each(|reg| push(&mut read, reg));
+    implicit_written.iter().for_each(|reg| push(&mut written, reg));
+
+    let string_op = matches!(
+        inst.opcode,
+        Opcode::MOVS
+            | Opcode::CMPS
+            | Opcode::LODS
+            | Opcode::STOS
+            | Opcode::SCAS
+            | Opcode::INS
+            | Opcode::OUTS
+    );
+
+    if string_op && inst.prefixes.rep_any() {
+        let counter = if inst.real_mode { "cx" } else { "ecx" };
+        push(&mut read, counter);
+        push(&mut written, counter);
+    }
+
+    if reads_flags(inst.opcode) {
+        push(&mut read, "eflags");
+    }
+
+    if writes_flags(inst.opcode) {
+        push(&mut written, "eflags");
+    }
+
+    (read, written)
+}
+
+pub(super) fn memory(inst: &Instruction) -> Option<MemoryAccess> {
+    if matches!(inst.opcode, Opcode::LEA | Opcode::NOP) {
+        return None;
+    }
+
+    // the operand size is reported for register operands too, so check there's an actual memory
+    // operand or an implied stack access
+    let explicit = (0..inst.operand_count).find(|&idx| inst.operand(idx).is_memory());
+    let stack = implicit_regs(inst).0.iter().any(|&reg| reg == "esp" || reg == "sp");
+    if explicit.is_none() && !stack {
+        return None;
+    }
+
+    let size = inst.mem_size().and_then(|size| size.bytes_size()).map(usize::from);
+
+    let direction = match inst.opcode {
+        Opcode::MOVS => Direction::ReadWrite,
+        Opcode::STOS | Opcode::INS => Direction::Write,
+        Opcode::LODS | Opcode::SCAS | Opcode::CMPS | Opcode::OUTS => Direction::Read,
+        // moves the operand to or from the stack
+        Opcode::PUSH | Opcode::POP | Opcode::CALL | Opcode::CALLF if explicit.is_some() => {
+            Direction::ReadWrite
+        }
+        Opcode::PUSH
+        | Opcode::PUSHF
+        | Opcode::PUSHA
+        | Opcode::CALL
+        | Opcode::CALLF
+        | Opcode::ENTER => Direction::Write,
+        Opcode::POP
+        | Opcode::POPF
+        | Opcode::POPA
+        | Opcode::RETURN
+        | Opcode::RETF
+        | Opcode::IRET
+        | Opcode::IRETD
+        | Opcode::IRETQ
+        | Opcode::LEAVE => Direction::Read,
+        _ => {
+            let access = match explicit {
+                Some(0) => destination(inst),
+                Some(_) => source(inst),
+                None => Access::Read,
+            };
+
+            match access {
+                Access::Read => Direction::Read,
+                Access::Write => Direction::Write,
+                Access::ReadWrite => Direction::ReadWrite,
+            }
+        }
+    };
+
+    Some(MemoryAccess { size, direction })
+}
@@ -6,15 +6,19 @@ use crate::protected_mode::{
 use crate::safer_unchecked::GetSaferUnchecked as _;
 use crate::{Number, MEM_SIZE_STRINGS};
 
-use decoder::ToTokens;
+use super::att;
+use decoder::{Syntax, ToTokens};
 use debugvault::Index;
 use tokenizing::{ColorScheme, Colors, TokenStream};
 
 impl fmt::Display for Decoder {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self == &Decoder::default() {
+        if self.real_mode() {
+            write!(f, "real mode ")?
+        }
+        if self.flags == Decoder::default().flags {
             return write!(f, "<all features>");
-        } else if self == &Decoder::minimal() {
+        } else if self.flags == Decoder::minimal().flags {
             return write!(f, "<no features>");
         }
         if self.sse3() {
@@ -1880,6 +1884,7 @@ const MNEMONICS: &[&str] = &[
     "loopz",
     "loop",
     "jecxz",
+    "jcxz",
     "pusha",
     "popa",
     "bound",
@@ -2340,16 +2345,14 @@ const MNEMONICS: &[&str] = &[
 ];
 
 impl Opcode {
-    fn name(&self) -> &'static str {
+    pub(super) fn name(&self) -> &'static str {
         unsafe { MNEMONICS.get_kinda_unchecked(*self as usize) }
     }
 }
 
-impl ToTokens for Instruction {
-    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
-        let opcode_name = self.opcode().name();
-        let mut op = String::with_capacity(opcode_name.len());
-
+impl Instruction {
+    /// Push the prefixes that are shown in front of the mnemonic, e.g. `lock `.
+    pub(super) fn push_prefixes(&self, op: &mut String) {
         if self.xacquire() {
             op.push_str("xacquire ");
         }
@@ -2377,18 +2380,104 @@ impl ToTokens for Instruction {
                 }
             }
         }
+    }
 
-        op.push_str(opcode_name);
-        stream.push_owned(op, Colors::opcode());
-
+    /// Address an operand resolves to, if it should be shown symbolically.
+    pub(super) fn imm_override(&self) -> Option<usize> {
         // slightly hacky but for `int` instructions we tend to incorrectly try to
         // do symbolic resolution on the immediate which isn't correct
-        let imm_override =
-            if self.imm_override && self.opcode != Opcode::INT && self.opcode != Opcode::INTO {
-                Some(self.imm as usize)
+        if self.imm_override && self.opcode != Opcode::INT && self.opcode != Opcode::INTO {
+            Some(self.imm as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Number of elements a broadcasted memory operand at `idx` is expanded to.
+    pub(super) fn broadcast(&self, idx: u8, op: &Operand) -> Option<u8> {
+        let evex = self.prefixes.evex()?;
+
+        // broadcasts only apply to sources, never to the first operand
+        if !evex.broadcast() || !op.is_memory() || idx == 0 {
+            return None;
+        }
+
+        let scale = if self.opcode == Opcode::VCVTPD2PS
+            || self.opcode == Opcode::VCVTTPD2UDQ
+            || self.opcode == Opcode::VCVTPD2UDQ
+            || self.opcode == Opcode::VCVTUDQ2PD
+            || self.opcode == Opcode::VCVTPS2PD
+            || self.opcode == Opcode::VCVTQQ2PS
+            || self.opcode == Opcode::VCVTDQ2PD
+            || self.opcode == Opcode::VCVTTPD2DQ
+            || self.opcode == Opcode::VFPCLASSPS
+            || self.opcode == Opcode::VFPCLASSPD
+            || self.opcode == Opcode::VCVTNEPS2BF16
+            || self.opcode == Opcode::VCVTUQQ2PS
+            || self.opcode == Opcode::VCVTPD2DQ
+            || self.opcode == Opcode::VCVTTPS2UQQ
+            || self.opcode == Opcode::VCVTPS2UQQ
+            || self.opcode == Opcode::VCVTTPS2QQ
+            || self.opcode == Opcode::VCVTPS2QQ
+        {
+            if self.opcode == Opcode::VFPCLASSPS || self.opcode == Opcode::VCVTNEPS2BF16 {
+                if evex.vex().l() {
+                    8
+                } else if evex.lp() {
+                    16
+                } else {
+                    4
+                }
             } else {
-                None
-            };
+                // vcvtpd2ps is "cool": in broadcast mode, it can read a
+                // double-precision float (qword), resize to single-precision,
+                // then broadcast that to the whole destination register. this
+                // means we need to show `xmm, qword [addr]{1to4}` if vector
+                // size is 256. likewise, scale of 8 for the same truncation
+                // reason if vector size is 512.
+                // vcvtudq2pd is the same story.
+                // vfpclassp{s,d} is a mystery to me.
+                if evex.vex().l() {
+                    4
+                } else if evex.lp() {
+                    8
+                } else {
+                    2
+                }
+            }
+        } else {
+            // this should never be `None` - that would imply two
+            // memory operands for a broadcasted operation.
+            if let Some(width) =
+                Operand::from_spec(self, self.operands[idx as usize - 1]).width()
+            {
+                width / self.mem_size
+            } else {
+                0
+            }
+        };
+
+        Some(scale)
+    }
+
+    /// Tokenize the instruction in a given syntax, regardless of the current
+    /// [`decoder::dialect`].
+    pub fn tokenize_with(&self, stream: &mut TokenStream, symbols: &Index, syntax: Syntax) {
+        match syntax {
+            Syntax::Intel => self.tokenize_intel(stream, symbols),
+            Syntax::Att => att::tokenize(self, stream, symbols),
+        }
+    }
+
+    fn tokenize_intel(&self, stream: &mut TokenStream, symbols: &Index) {
+        let opcode_name = self.opcode().name();
+        let mut op = String::with_capacity(opcode_name.len());
+
+        self.push_prefixes(&mut op);
+        op.push_str(opcode_name);
+        stream.push_owned(op, Colors::opcode());
+
+        let imm_override = self.imm_override();
 
         if self.operand_count > 0 {
             stream.push(" ", Colors::spacing());
@@ -2430,72 +2519,19 @@ impl ToTokens for Instruction {
 
                 op.tokenize(stream, symbols, imm_override);
 
-                if let Some(evex) = self.prefixes.evex() {
-                    if evex.broadcast() && op.is_memory() {
-                        let scale = if self.opcode == Opcode::VCVTPD2PS
-                            || self.opcode == Opcode::VCVTTPD2UDQ
-                            || self.opcode == Opcode::VCVTPD2UDQ
-                            || self.opcode == Opcode::VCVTUDQ2PD
-                            || self.opcode == Opcode::VCVTPS2PD
-                            || self.opcode == Opcode::VCVTQQ2PS
-                            || self.opcode == Opcode::VCVTDQ2PD
-                            || self.opcode == Opcode::VCVTTPD2DQ
-                            || self.opcode == Opcode::VFPCLASSPS
-                            || self.opcode == Opcode::VFPCLASSPD
-                            || self.opcode == Opcode::VCVTNEPS2BF16
-                            || self.opcode == Opcode::VCVTUQQ2PS
-                            || self.opcode == Opcode::VCVTPD2DQ
-                            || self.opcode == Opcode::VCVTTPS2UQQ
-                            || self.opcode == Opcode::VCVTPS2UQQ
-                            || self.opcode == Opcode::VCVTTPS2QQ
-                            || self.opcode == Opcode::VCVTPS2QQ
-                        {
-                            if self.opcode == Opcode::VFPCLASSPS
-                                || self.opcode == Opcode::VCVTNEPS2BF16
-                            {
-                                if evex.vex().l() {
-                                    8
-                                } else if evex.lp() {
-                                    16
-                                } else {
-                                    4
-                                }
-                            } else {
-                                // vcvtpd2ps is "cool": in broadcast mode, it can read a
-                                // double-precision float (qword), resize to single-precision,
-                                // then broadcast that to the whole destination register. this
-                                // means we need to show `xmm, qword [addr]{1to4}` if vector
-                                // size is 256. likewise, scale of 8 for the same truncation
-                                // reason if vector size is 512.
-                                // vcvtudq2pd is the same story.
-                                // vfpclassp{s,d} is a mystery to me.
-                                if evex.vex().l() {
-                                    4
-                                } else if evex.lp() {
-                                    8
-                                } else {
-                                    2
-                                }
-                            }
-                        } else {
-                            // this should never be `None` - that would imply two
-                            // memory operands for a broadcasted operation.
-                            if let Some(width) =
-                                Operand::from_spec(self, self.operands[idx as usize - 1]).width()
-                            {
-                                width / self.mem_size
-                            } else {
-                                0
-                            }
-                        };
-
-                        stream.push("{", Colors::brackets());
-                        stream.push("1to", Colors::expr());
-                        stream.push_owned(scale.to_string(), Colors::immediate());
-                        stream.push("}", Colors::brackets());
-                    }
+                if let Some(scale) = self.broadcast(idx, &op) {
+                    stream.push("{", Colors::brackets());
+                    stream.push("1to", Colors::expr());
+                    stream.push_owned(scale.to_string(), Colors::immediate());
+                    stream.push("}", Colors::brackets());
                 }
             }
         }
     }
 }
+
+impl ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        self.tokenize_with(stream, symbols, Syntax::default());
+    }
+}
@@ -1,3 +1,5 @@
+mod att;
+mod behavior;
 mod display;
 mod evex;
 mod tests;
@@ -11,7 +13,7 @@ use std::hash::{Hash, Hasher};
 use crate::safer_unchecked::unreachable_kinda_unchecked as unreachable_unchecked;
 pub use crate::MemoryAccessSize;
 
-use decoder::{Decoded, Decodable, Error, ErrorKind, Reader, ToTokens};
+use decoder::{Decoded, Decodable, Error, ErrorKind, FlowKind, MemoryAccess, Reader, ToTokens};
 use tokenizing::{ColorScheme, Colors, TokenStream};
 use debugvault::Index;
 
@@ -2007,6 +2009,7 @@ pub enum Opcode {
     LOOPZ,
     LOOP,
     JECXZ,
+    JCXZ,
 
     PUSHA,
     POPA,
@@ -2543,6 +2546,7 @@ pub struct Instruction {
     opcode: Opcode,
     mem_size: u8,
     imm_override: bool,
+    real_mode: bool,
 }
 
 impl fmt::Debug for Instruction {
@@ -2567,7 +2571,32 @@ impl Decoded for Instruction {
         self.length as usize
     }
 
+    fn tokenize_in(&self, stream: &mut TokenStream, symbols: &Index, dialect: decoder::Dialect) {
+        self.tokenize_with(stream, symbols, dialect.syntax);
+    }
+
     fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+        // only branches have immediates relative to the next instruction, and an address must
+        // not overwrite the immediate of an instruction that also has a memory operand
+        let branch = matches!(
+            behavior::flow(self),
+            FlowKind::Jump | FlowKind::ConditionalJump | FlowKind::Call
+        );
+        let has_imm = self.operands[..self.operand_count as usize].iter().any(|op| {
+            matches!(
+                op,
+                OperandSpec::ImmI8
+                    | OperandSpec::ImmI16
+                    | OperandSpec::ImmI32
+                    | OperandSpec::ImmU8
+                    | OperandSpec::ImmU16
+            )
+        });
+
+        if has_imm && !branch {
+            return;
+        }
+
         for idx in 0..self.operand_count as usize {
             let operand = Operand::from_spec(&self, self.operands[idx]);
             let addr = addr as u32;
@@ -2658,6 +2687,26 @@ impl Decoded for Instruction {
             self.imm_override = true;
         }
     }
+
+    fn flow(&self) -> FlowKind {
+        behavior::flow(self)
+    }
+
+    fn targets(&self) -> Vec<usize> {
+        behavior::targets(self)
+    }
+
+    fn regs_read(&self) -> Vec<&'static str> {
+        behavior::registers(self).0
+    }
+
+    fn regs_written(&self) -> Vec<&'static str> {
+        behavior::registers(self).1
+    }
+
+    fn memory(&self) -> Option<MemoryAccess> {
+        behavior::memory(self)
+    }
 }
 
 impl decoder::Decodable for Decoder {
@@ -2672,7 +2721,7 @@ impl decoder::Decodable for Decoder {
             return Err(Error::new(ErrorKind::TooLong, reader.offset()));
         }
 
-        if *self != Decoder::default() {
+        if self.flags != Decoder::default().flags {
             self.revise_instruction(&mut inst).map_err(|err| Error::new(err, 1))?;
         }
 
@@ -2824,6 +2873,8 @@ pub struct Decoder {
     // 62. tsx
     // 63. lzcnt
     flags: u64,
+    /// operands and addresses default to 16 bits, with `0x66` and `0x67` selecting 32 bits.
+    real_mode: bool,
 }
 
 impl Decoder {
@@ -2832,7 +2883,22 @@ impl Decoder {
     /// pedantic and only decodes what the spec says is well-defined, rejecting undefined sequences
     /// and any instructions defined by extensions.
     pub fn minimal() -> Self {
-        Decoder { flags: 0 }
+        Decoder {
+            flags: 0,
+            real_mode: false,
+        }
+    }
+
+    /// whether operands and addresses default to 16 bits, as they do in real mode.
+    pub fn real_mode(&self) -> bool {
+        self.real_mode
+    }
+
+    /// decode with 16-bit default operand and address sizes, as in real mode. the `0x66` and
+    /// `0x67` prefixes then select 32-bit operands and addresses.
+    pub fn with_real_mode(mut self) -> Self {
+        self.real_mode = true;
+        self
     }
 
     /// helper to decode an instruction directly from a byte slice.
@@ -4147,6 +4213,7 @@ impl Default for Decoder {
     fn default() -> Self {
         Self {
             flags: 0xffffffff_ffffffff,
+            real_mode: false,
         }
     }
 }
@@ -4322,9 +4389,31 @@ impl Instruction {
             operand_count: 0,
             operands: [OperandSpec::Nothing; 4],
             imm_override: false,
+            real_mode: false,
         }
     }
 
+    /// whether operands are 16 bits wide, by default in real mode or through an operand-size
+    /// override otherwise.
+    #[inline]
+    fn word_operands(&self) -> bool {
+        self.prefixes.operand_size() != self.real_mode
+    }
+
+    /// whether operands are 16 bits wide in real mode. outside of real mode, near branches through
+    /// a register and pushes of a register keep their 32-bit width regardless of prefixes.
+    #[inline]
+    fn real_word_operands(&self) -> bool {
+        self.real_mode && !self.prefixes.operand_size()
+    }
+
+    /// whether addresses are 16 bits wide, by default in real mode or through an address-size
+    /// override otherwise.
+    #[inline]
+    fn word_addresses(&self) -> bool {
+        self.prefixes.address_size() != self.real_mode
+    }
+
     /// get the `Segment` that will *actually* be used for accessing the operand at index `i`.
     ///
     /// `stos`, `lods`, `movs`, and `cmps` specifically name some segments for use regardless of
@@ -5934,7 +6023,7 @@ const OPCODES: [OpcodeRecord; 256] = [
 // 0xe8
     OpcodeRecord(Interpretation::Instruction(Opcode::CALL), OperandCode::Jvds),
     OpcodeRecord(Interpretation::Instruction(Opcode::JMP), OperandCode::Jvds),
-    OpcodeRecord(Interpretation::Instruction(Opcode::Invalid), OperandCode::Nothing),
+    OpcodeRecord(Interpretation::Instruction(Opcode::JMPF), OperandCode::AbsFar),
     OpcodeRecord(Interpretation::Instruction(Opcode::JMP), OperandCode::Ibs),
     OpcodeRecord(Interpretation::Instruction(Opcode::IN), OperandCode::AL_DX),
     OpcodeRecord(Interpretation::Instruction(Opcode::IN), OperandCode::AX_DX),
@@ -6233,7 +6322,7 @@ fn read_M(
     instr: &mut Instruction,
     modrm: u8,
 ) -> Result<OperandSpec, ErrorKind> {
-    if instr.prefixes.address_size() {
+    if instr.word_addresses() {
         return read_M_16bit(words, instr, modrm);
     }
     instr.regs[1].bank = RegisterBank::D;
@@ -6944,6 +7033,7 @@ fn read(
     let mut nextb = words.next().ok_or(ErrorKind::ExhaustedInput)?;
     let mut next_rec = OPCODES[nextb as usize];
     instruction.prefixes = Prefixes::new(0);
+    instruction.real_mode = decoder.real_mode;
 
     // default registers to `[eax; 4]`
     instruction.regs = unsafe { std::mem::transmute(0u64) };
@@ -7085,19 +7175,19 @@ fn read_operands(
             let reg = z_operand_code.reg();
             match z_operand_code.category() {
                 0 => {
-                    // these are Zv_R
-                    let bank = if !instruction.prefixes.operand_size() {
+                    // these are Zv_R, the stack slot is only narrowed in real mode
+                    let bank = if !instruction.word_operands() {
                         RegisterBank::D
                     } else {
                         RegisterBank::W
                     };
                     instruction.regs[0] = RegSpec::from_parts(reg, bank);
-                    instruction.mem_size = 4;
+                    instruction.mem_size = if instruction.real_word_operands() { 2 } else { 4 };
                     instruction.operand_count = 1;
                 }
                 1 => {
                     // Zv_AX
-                    let bank = if !instruction.prefixes.operand_size() {
+                    let bank = if !instruction.word_operands() {
                         RegisterBank::D
                     } else {
                         RegisterBank::W
@@ -7115,7 +7205,7 @@ fn read_operands(
                 }
                 3 => {
                     // category == 3, Zv_Iv_R
-                    if !instruction.prefixes.operand_size() {
+                    if !instruction.word_operands() {
                         instruction.regs[0] = RegSpec::from_parts(reg, RegisterBank::D);
                         instruction.imm = read_imm_unsigned(words, 4)?;
                         instruction.operands[1] = OperandSpec::ImmI32;
@@ -7142,7 +7232,7 @@ fn read_operands(
         // cool! we can precompute opwidth and know we need to read_E.
         if !operand_code.has_byte_operands() {
             // further, this is an vd E
-            if !instruction.prefixes.operand_size() {
+            if !instruction.word_operands() {
                 instruction.mem_size = 4;
                 bank = RegisterBank::D;
             } else {
@@ -7168,15 +7258,29 @@ fn read_operands(
     }
 
     if let Some((only_imm, immsz)) = operand_code.has_imm() {
-        instruction.imm = read_imm_signed(words, 1 << (immsz * 2))? as u32;
+        let numwidth = if immsz == 0 {
+            1
+        } else if instruction.word_operands() {
+            2
+        } else {
+            4
+        };
+        instruction.imm = read_imm_signed(words, numwidth)? as u32;
         if only_imm {
             if immsz == 0 {
+                if instruction.opcode == Opcode::JECXZ && instruction.word_addresses() {
+                    instruction.opcode = Opcode::JCXZ;
+                }
                 instruction.operands[0] = OperandSpec::ImmI8;
             } else {
                 if instruction.opcode == Opcode::CALL {
-                    instruction.mem_size = 4;
+                    instruction.mem_size = numwidth;
                 }
-                instruction.operands[0] = OperandSpec::ImmI32;
+                instruction.operands[0] = if numwidth == 2 {
+                    OperandSpec::ImmI16
+                } else {
+                    OperandSpec::ImmI32
+                };
             }
             instruction.operand_count = 1;
             return Ok(());
@@ -7203,7 +7307,7 @@ fn read_operands(
             }
             2 => {
                 instruction.operands[0] = mem_oper;
-                let numwidth = if instruction.prefixes.operand_size() {
+                let numwidth = if instruction.word_operands() {
                     2
                 } else {
                     4
@@ -7241,7 +7345,7 @@ fn read_operands(
                 // ModRM_0xc7_Ev_Iv
                 if modrm == 0xf8 {
                     instruction.opcode = Opcode::XBEGIN;
-                    instruction.imm = if instruction.prefixes.operand_size() {
+                    instruction.imm = if instruction.word_operands() {
                         read_imm_signed(words, 2)? as i16 as i32 as u32
                     } else {
                         read_imm_signed(words, 4)? as u32
@@ -7257,7 +7361,7 @@ fn read_operands(
 
                 instruction.operands[0] = mem_oper;
                 instruction.opcode = Opcode::MOV;
-                if instruction.prefixes.operand_size() {
+                if instruction.word_operands() {
                     instruction.imm = read_imm_signed(words, 2)? as u32;
                     instruction.operands[1] = OperandSpec::ImmI16;
                 } else {
@@ -7288,7 +7392,7 @@ fn read_operands(
             op @ 11 | op @ 12 => {
                 let opwidth = if op == 11 {
                     1
-                } else if instruction.prefixes.operand_size() {
+                } else if instruction.word_operands() {
                     2
                 } else {
                     4
@@ -7346,9 +7450,12 @@ fn read_operands(
                 ][r as usize];
                 if instruction.operands[0] == OperandSpec::RegMMM {
                     if opcode == Opcode::CALL || opcode == Opcode::JMP {
-                        instruction.regs[1].bank = RegisterBank::D;
+                        if !instruction.real_word_operands() {
+                            instruction.regs[1].bank = RegisterBank::D;
+                        }
                         if opcode == Opcode::CALL {
-                            instruction.mem_size = 4;
+                            instruction.mem_size =
+                                if instruction.real_word_operands() { 2 } else { 4 };
                         }
                     } else if opcode == Opcode::CALLF || opcode == Opcode::JMPF {
                         return Err(ErrorKind::InvalidOperand);
@@ -7358,19 +7465,22 @@ fn read_operands(
                     || opcode == Opcode::PUSH
                     || opcode == Opcode::POP
                 {
-                    if instruction.prefixes.operand_size() {
+                    if instruction.word_operands() {
                         instruction.mem_size = 2;
                     } else {
                         instruction.mem_size = 4;
                     }
                 } else if opcode == Opcode::CALLF || opcode == Opcode::JMPF {
-                    instruction.mem_size = 6;
+                    instruction.mem_size = if instruction.word_operands() { 4 } else { 6 };
                 }
                 if instruction.operands[0] == OperandSpec::RegMMM {
                     if opcode == Opcode::CALL || opcode == Opcode::JMP {
-                        instruction.regs[1].bank = RegisterBank::D;
+                        if !instruction.real_word_operands() {
+                            instruction.regs[1].bank = RegisterBank::D;
+                        }
                         if opcode == Opcode::CALL {
-                            instruction.mem_size = 4;
+                            instruction.mem_size =
+                                if instruction.real_word_operands() { 2 } else { 4 };
                         }
                     } else if opcode == Opcode::CALLF || opcode == Opcode::JMPF {
                         return Err(ErrorKind::InvalidOperand);
@@ -7380,13 +7490,13 @@ fn read_operands(
                     || opcode == Opcode::PUSH
                     || opcode == Opcode::POP
                 {
-                    if instruction.prefixes.operand_size() {
+                    if instruction.word_operands() {
                         instruction.mem_size = 2;
                     } else {
                         instruction.mem_size = 4;
                     }
                 } else if opcode == Opcode::CALLF || opcode == Opcode::JMPF {
-                    instruction.mem_size = 6;
+                    instruction.mem_size = if instruction.word_operands() { 4 } else { 6 };
                 }
                 instruction.opcode = opcode;
                 instruction.operand_count = 1;
@@ -7395,7 +7505,7 @@ fn read_operands(
                 let modrm = read_modrm(words)?;
 
                 instruction.operands[1] = read_E(words, instruction, modrm, 1)?;
-                instruction.regs[0] = if instruction.prefixes.operand_size() {
+                instruction.regs[0] = if instruction.word_operands() {
                     RegSpec::from_parts((modrm >> 3) & 7, RegisterBank::W)
                 } else {
                     RegSpec::from_parts((modrm >> 3) & 7, RegisterBank::D)
@@ -7409,7 +7519,7 @@ fn read_operands(
                 let modrm = read_modrm(words)?;
 
                 instruction.operands[1] = read_E(words, instruction, modrm, 2)?;
-                instruction.regs[0] = if instruction.prefixes.operand_size() {
+                instruction.regs[0] = if instruction.word_operands() {
                     RegSpec::from_parts((modrm >> 3) & 7, RegisterBank::W)
                 } else {
                     RegSpec::from_parts((modrm >> 3) & 7, RegisterBank::D)
@@ -7475,7 +7585,7 @@ fn read_operands(
                 instruction.operand_count = 2;
             }
             24 => {
-                let opwidth = if instruction.prefixes.operand_size() {
+                let opwidth = if instruction.word_operands() {
                     instruction.regs[0] = RegSpec::from_parts(0, RegisterBank::W);
                     2
                 } else {
@@ -7491,7 +7601,7 @@ fn read_operands(
                 instruction.operand_count = 2;
             }
             25 => {
-                let opwidth = if instruction.prefixes.operand_size() {
+                let opwidth = if instruction.word_operands() {
                     2
                 } else {
                     4
@@ -7587,7 +7697,7 @@ fn unlikely_operands(
             instruction.operand_count = 1;
             instruction.mem_size = 0;
             // read segment
-            let addr_size = if instruction.prefixes.operand_size() {
+            let addr_size = if instruction.word_operands() {
                 2
             } else {
                 4
@@ -7703,7 +7813,8 @@ fn unlikely_operands(
             instruction.operands[0] = OperandSpec::RegRRR;
             instruction.operands[1] = read_M(words, instruction, modrm)?;
             if [Opcode::LFS, Opcode::LGS, Opcode::LSS].contains(&instruction.opcode) {
-                if instruction.prefixes.operand_size() {
+                if instruction.word_operands() {
+                    instruction.regs[0].bank = RegisterBank::W;
                     instruction.mem_size = 4;
                 } else {
                     instruction.mem_size = 6;
@@ -7728,7 +7839,7 @@ fn unlikely_operands(
                 } else {
                     vex::three_byte_vex(words, modrm, instruction)?;
 
-                    if decoder != &Decoder::default() {
+                    if decoder.flags != Decoder::default().flags {
                         decoder.revise_instruction(instruction)?;
                     }
                     return Ok(());
@@ -7737,7 +7848,7 @@ fn unlikely_operands(
                 // LES
                 instruction.regs[0] = RegSpec::from_parts(
                     (modrm >> 3) & 7,
-                    if instruction.prefixes.operand_size() {
+                    if instruction.word_operands() {
                         RegisterBank::W
                     } else {
                         RegisterBank::D
@@ -7745,7 +7856,7 @@ fn unlikely_operands(
                 );
                 instruction.operands[0] = OperandSpec::RegRRR;
                 instruction.operands[1] = read_M(words, instruction, modrm)?;
-                if instruction.prefixes.operand_size() {
+                if instruction.word_operands() {
                     instruction.mem_size = 4;
                 } else {
                     instruction.mem_size = 6;
@@ -7766,7 +7877,7 @@ fn unlikely_operands(
                 } else {
                     vex::two_byte_vex(words, modrm, instruction)?;
 
-                    if decoder != &Decoder::default() {
+                    if decoder.flags != Decoder::default().flags {
                         decoder.revise_instruction(instruction)?;
                     }
                     return Ok(());
@@ -7775,7 +7886,7 @@ fn unlikely_operands(
                 // LDS
                 instruction.regs[0] = RegSpec::from_parts(
                     (modrm >> 3) & 7,
-                    if instruction.prefixes.operand_size() {
+                    if instruction.word_operands() {
                         RegisterBank::W
                     } else {
                         RegisterBank::D
@@ -7783,7 +7894,7 @@ fn unlikely_operands(
                 );
                 instruction.operands[0] = OperandSpec::RegRRR;
                 instruction.operands[1] = read_M(words, instruction, modrm)?;
-                if instruction.prefixes.operand_size() {
+                if instruction.word_operands() {
                     instruction.mem_size = 4;
                 } else {
                     instruction.mem_size = 6;
@@ -7854,7 +7965,7 @@ fn unlikely_operands(
                     instruction.operand_count = 0;
                 }
                 _ => {
-                    let (sz, bank) = if !instruction.prefixes.operand_size() {
+                    let (sz, bank) = if !instruction.word_operands() {
                         (4, RegisterBank::D)
                     } else {
                         (2, RegisterBank::W)
@@ -7909,7 +8020,7 @@ fn unlikely_operands(
             instruction.operand_count = 3;
         }
         OperandCode::Gv_Ev_Iv => {
-            let opwidth = if instruction.prefixes.operand_size() {
+            let opwidth = if instruction.word_operands() {
                 2
             } else {
                 4
@@ -7976,7 +8087,7 @@ fn unlikely_operands(
         }
         OperandCode::Gv_Ew_LSL => {
             let modrm = read_modrm(words)?;
-            if instruction.prefixes.operand_size() {
+            if instruction.word_operands() {
                 instruction.regs[0] = RegSpec::from_parts((modrm >> 3) & 7, RegisterBank::W);
             } else {
                 instruction.regs[0] = RegSpec::from_parts((modrm >> 3) & 7, RegisterBank::D);
@@ -7994,7 +8105,7 @@ fn unlikely_operands(
         OperandCode::Gd_Ev => {
             let modrm = read_modrm(words)?;
 
-            let opwidth = if instruction.prefixes.operand_size() {
+            let opwidth = if instruction.word_operands() {
                 2
             } else {
                 4
@@ -8013,7 +8124,7 @@ fn unlikely_operands(
                     RegSpec::al()
                 }
                 OperandCode::AX_Ov => {
-                    if instruction.prefixes.operand_size() {
+                    if instruction.word_operands() {
                         instruction.mem_size = 2;
                         RegSpec::ax()
                     } else {
@@ -8023,14 +8134,14 @@ fn unlikely_operands(
                 }
                 _ => unsafe { unreachable_unchecked() },
             };
-            let addr_width = if instruction.prefixes.address_size() {
+            let addr_width = if instruction.word_addresses() {
                 2
             } else {
                 4
             };
             let imm = read_num(words, addr_width)?;
             instruction.disp = imm;
-            if instruction.prefixes.address_size() {
+            if instruction.word_addresses() {
                 instruction.operands[1] = OperandSpec::DispU16;
             } else {
                 instruction.operands[1] = OperandSpec::DispU32;
@@ -8044,7 +8155,7 @@ fn unlikely_operands(
                     RegSpec::al()
                 }
                 OperandCode::Ov_AX => {
-                    if instruction.prefixes.operand_size() {
+                    if instruction.word_operands() {
                         instruction.mem_size = 2;
                         RegSpec::ax()
                     } else {
@@ -8054,14 +8165,14 @@ fn unlikely_operands(
                 }
                 _ => unsafe { unreachable_unchecked() },
             };
-            let addr_width = if instruction.prefixes.address_size() {
+            let addr_width = if instruction.word_addresses() {
                 2
             } else {
                 4
             };
             let imm = read_num(words, addr_width)?;
             instruction.disp = imm;
-            instruction.operands[0] = if instruction.prefixes.address_size() {
+            instruction.operands[0] = if instruction.word_addresses() {
                 OperandSpec::DispU16
             } else {
                 OperandSpec::DispU32
@@ -8087,7 +8198,7 @@ fn unlikely_operands(
             instruction.operand_count = 2;
         }
         OperandCode::Fw => {
-            if instruction.prefixes.operand_size() {
+            if instruction.word_operands() {
                 instruction.opcode = Opcode::IRET;
             } else {
                 instruction.opcode = Opcode::IRETD;
@@ -8105,7 +8216,7 @@ fn unlikely_operands(
             instruction.operand_count = 2;
         }
         OperandCode::E_G_q => {
-            if instruction.prefixes.operand_size() {
+            if instruction.word_operands() {
                 return Err(ErrorKind::InvalidOpcode);
             }
 
@@ -8120,7 +8231,7 @@ fn unlikely_operands(
             }
         }
         OperandCode::G_E_q => {
-            if instruction.prefixes.operand_size() {
+            if instruction.word_operands() {
                 return Err(ErrorKind::InvalidOpcode);
             }
 
@@ -8159,7 +8270,7 @@ fn unlikely_operands(
             let modrm = read_modrm(words)?;
             let r = (modrm >> 3) & 0b111;
 
-            let opwidth = if instruction.prefixes.operand_size() {
+            let opwidth = if instruction.word_operands() {
                 2
             } else {
                 4
@@ -8297,7 +8408,7 @@ fn unlikely_operands(
                 }
             }
             if instruction.prefixes.operand_size() {
-                let opwidth = if instruction.prefixes.operand_size() {
+                let opwidth = if instruction.word_operands() {
                     2
                 } else {
                     4
@@ -8360,7 +8471,7 @@ fn unlikely_operands(
             }
 
             if instruction.prefixes.rep() {
-                let opwidth = if instruction.prefixes.operand_size() {
+                let opwidth = if instruction.word_operands() {
                     2
                 } else {
                     4
@@ -8469,7 +8580,7 @@ fn unlikely_operands(
 
             instruction.opcode = opcode;
             instruction.operand_count = 1;
-            let opwidth = if instruction.prefixes.operand_size() {
+            let opwidth = if instruction.word_operands() {
                 2
             } else {
                 4
@@ -8833,7 +8944,7 @@ fn unlikely_operands(
         // sure hope these aren't backwards huh
         OperandCode::AL_Xb => {
             instruction.regs[0] = RegSpec::al();
-            if instruction.prefixes.address_size() {
+            if instruction.word_addresses() {
                 instruction.regs[1] = RegSpec::si();
             } else {
                 instruction.regs[1] = RegSpec::esi();
@@ -8844,7 +8955,7 @@ fn unlikely_operands(
             instruction.operand_count = 2;
         }
         OperandCode::Yb_Xb => {
-            if instruction.prefixes.address_size() {
+            if instruction.word_addresses() {
                 instruction.operands[0] = OperandSpec::Deref_di;
                 instruction.operands[1] = OperandSpec::Deref_si;
             } else {
@@ -8856,7 +8967,7 @@ fn unlikely_operands(
         }
         OperandCode::Yb_AL => {
             instruction.regs[0] = RegSpec::al();
-            if instruction.prefixes.address_size() {
+            if instruction.word_addresses() {
                 instruction.regs[1] = RegSpec::di();
             } else {
                 instruction.regs[1] = RegSpec::edi();
@@ -8867,14 +8978,14 @@ fn unlikely_operands(
             instruction.operand_count = 2;
         }
         OperandCode::AX_Xv => {
-            instruction.regs[0] = if instruction.prefixes.operand_size() {
+            instruction.regs[0] = if instruction.word_operands() {
                 instruction.mem_size = 2;
                 RegSpec::ax()
             } else {
                 instruction.mem_size = 4;
                 RegSpec::eax()
             };
-            if instruction.prefixes.address_size() {
+            if instruction.word_addresses() {
                 instruction.regs[1] = RegSpec::si();
             } else {
                 instruction.regs[1] = RegSpec::esi();
@@ -8882,14 +8993,14 @@ fn unlikely_operands(
             instruction.operands[1] = OperandSpec::Deref;
         }
         OperandCode::Yv_AX => {
-            instruction.regs[0] = if instruction.prefixes.operand_size() {
+            instruction.regs[0] = if instruction.word_operands() {
                 instruction.mem_size = 2;
                 RegSpec::ax()
             } else {
                 instruction.mem_size = 4;
                 RegSpec::eax()
             };
-            if instruction.prefixes.address_size() {
+            if instruction.word_addresses() {
                 instruction.regs[1] = RegSpec::di();
             } else {
                 instruction.regs[1] = RegSpec::edi();
@@ -8898,12 +9009,12 @@ fn unlikely_operands(
             instruction.operands[1] = OperandSpec::RegRRR;
         }
         OperandCode::Yv_Xv => {
-            instruction.mem_size = if instruction.prefixes.operand_size() {
+            instruction.mem_size = if instruction.word_operands() {
                 2
             } else {
                 4
             };
-            if instruction.prefixes.address_size() {
+            if instruction.word_addresses() {
                 instruction.operands[0] = OperandSpec::Deref_di;
                 instruction.operands[1] = OperandSpec::Deref_si;
             } else {
@@ -9079,7 +9190,7 @@ fn unlikely_operands(
         OperandCode::CVT_AA => {
             instruction.operands[0] = OperandSpec::Nothing;
             instruction.operand_count = 0;
-            instruction.opcode = if !instruction.prefixes.operand_size() {
+            instruction.opcode = if !instruction.word_operands() {
                 Opcode::CWDE
             } else {
                 Opcode::CBW
@@ -9088,7 +9199,7 @@ fn unlikely_operands(
         OperandCode::CVT_DA => {
             instruction.operands[0] = OperandSpec::Nothing;
             instruction.operand_count = 0;
-            instruction.opcode = if !instruction.prefixes.operand_size() {
+            instruction.opcode = if !instruction.word_operands() {
                 Opcode::CDQ
             } else {
                 Opcode::CWD
@@ -9138,7 +9249,7 @@ fn unlikely_operands(
             }
         }
         OperandCode::ModRM_0x0f01 => {
-            let opwidth = if instruction.prefixes.operand_size() {
+            let opwidth = if instruction.word_operands() {
                 2
             } else {
                 4
@@ -9653,7 +9764,7 @@ fn unlikely_operands(
                         }
                         6 => {
                             instruction.opcode = Opcode::UMONITOR;
-                            if instruction.prefixes.address_size() {
+                            if instruction.word_addresses() {
                                 instruction.regs[1] = RegSpec::from_parts(m, RegisterBank::W);
                             } else {
                                 instruction.regs[1] = RegSpec::from_parts(m, RegisterBank::D);
@@ -9746,7 +9857,7 @@ fn unlikely_operands(
             }
         }
         OperandCode::ModRM_0x0fba => {
-            let opwidth = if instruction.prefixes.operand_size() {
+            let opwidth = if instruction.word_operands() {
                 2
             } else {
                 4
@@ -9859,7 +9970,7 @@ fn unlikely_operands(
             instruction.operand_count = 2;
         }
         OperandCode::AX_Ib => {
-            instruction.regs[0] = if !instruction.prefixes.operand_size() {
+            instruction.regs[0] = if !instruction.word_operands() {
                 RegSpec::eax()
             } else {
                 RegSpec::ax()
@@ -9875,7 +9986,7 @@ fn unlikely_operands(
             instruction.operand_count = 2;
         }
         OperandCode::Ib_AX => {
-            instruction.regs[0] = if !instruction.prefixes.operand_size() {
+            instruction.regs[0] = if !instruction.word_operands() {
                 RegSpec::eax()
             } else {
                 RegSpec::ax()
@@ -9885,7 +9996,7 @@ fn unlikely_operands(
             instruction.operand_count = 2;
         }
         OperandCode::AX_DX => {
-            instruction.regs[0] = if !instruction.prefixes.operand_size() {
+            instruction.regs[0] = if !instruction.word_operands() {
                 RegSpec::eax()
             } else {
                 RegSpec::ax()
@@ -9903,7 +10014,7 @@ fn unlikely_operands(
             instruction.operand_count = 2;
         }
         OperandCode::DX_AX => {
-            instruction.regs[0] = if !instruction.prefixes.operand_size() {
+            instruction.regs[0] = if !instruction.word_operands() {
                 RegSpec::eax()
             } else {
                 RegSpec::ax()
@@ -9922,7 +10033,11 @@ fn unlikely_operands(
         }
         OperandCode::Yb_DX => {
             instruction.regs[0] = RegSpec::dl();
-            instruction.regs[1] = RegSpec::edi();
+            instruction.regs[1] = if instruction.word_addresses() {
+                RegSpec::di()
+            } else {
+                RegSpec::edi()
+            };
             instruction.operands[0] = OperandSpec::Deref;
             instruction.operands[1] = OperandSpec::RegRRR;
             instruction.operand_count = 2;
@@ -9930,10 +10045,14 @@ fn unlikely_operands(
         }
         OperandCode::Yv_DX => {
             instruction.regs[0] = RegSpec::dx();
-            instruction.regs[1] = RegSpec::edi();
+            instruction.regs[1] = if instruction.word_addresses() {
+                RegSpec::di()
+            } else {
+                RegSpec::edi()
+            };
             instruction.operands[0] = OperandSpec::Deref;
             instruction.operands[1] = OperandSpec::RegRRR;
-            if instruction.prefixes.operand_size() {
+            if instruction.word_operands() {
                 instruction.mem_size = 2;
             } else {
                 instruction.mem_size = 4;
@@ -9942,7 +10061,11 @@ fn unlikely_operands(
         }
         OperandCode::DX_Xb => {
             instruction.regs[0] = RegSpec::dl();
-            instruction.regs[1] = RegSpec::esi();
+            instruction.regs[1] = if instruction.word_addresses() {
+                RegSpec::si()
+            } else {
+                RegSpec::esi()
+            };
             instruction.operands[0] = OperandSpec::RegRRR;
             instruction.operands[1] = OperandSpec::Deref;
             instruction.operand_count = 2;
@@ -9954,10 +10077,14 @@ fn unlikely_operands(
         }
         OperandCode::DX_Xv => {
             instruction.regs[0] = RegSpec::dx();
-            instruction.regs[1] = RegSpec::esi();
+            instruction.regs[1] = if instruction.word_addresses() {
+                RegSpec::si()
+            } else {
+                RegSpec::esi()
+            };
             instruction.operands[0] = OperandSpec::RegRRR;
             instruction.operands[1] = OperandSpec::Deref;
-            if instruction.prefixes.operand_size() {
+            if instruction.word_operands() {
                 instruction.mem_size = 2;
             } else {
                 instruction.mem_size = 4;
@@ -9992,7 +10119,7 @@ fn unlikely_operands(
                     bank: RegisterBank::D,
                     num: (modrm >> 3) & 7,
                 };
-                if instruction.prefixes.operand_size() {
+                if instruction.word_operands() {
                     instruction.regs[0].bank = RegisterBank::W;
                     instruction.mem_size = 4;
                 } else {
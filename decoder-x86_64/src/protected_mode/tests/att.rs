@@ -0,0 +1,44 @@
+use crate::protected_mode::{Decoder, Instruction};
+use decoder::{Decoded, Syntax};
+
+fn decode(data: &[u8]) -> Instruction {
+    Decoder::default().decode_slice(data).unwrap()
+}
+
+fn att(inst: &Instruction) -> String {
+    let mut stream = tokenizing::TokenStream::new();
+    inst.tokenize_with(&mut stream, &debugvault::Index::default(), Syntax::Att);
+    stream.to_string()
+}
+
+fn test_att(data: &[u8], expected: &str) {
+    assert_eq!(att(&decode(data)), expected, "for {data:02x?}");
+}
+
+#[test]
+fn operands() {
+    test_att(&[0x89, 0xe5], "mov %esp,%ebp");
+    test_att(&[0x83, 0xec, 0x10], "sub $0x10,%esp");
+    test_att(&[0x8b, 0x45, 0x08], "mov 0x8(%ebp),%eax");
+    test_att(&[0x8b, 0x04, 0x88], "mov (%eax,%ecx,4),%eax");
+    test_att(&[0x65, 0xa1, 0x14, 0x00, 0x00, 0x00], "mov %gs:0x14,%eax");
+}
+
+#[test]
+fn suffixes() {
+    test_att(&[0xc7, 0x45, 0xfc, 0x00, 0x00, 0x00, 0x00], "movl $0x0,-0x4(%ebp)");
+    test_att(&[0x0f, 0xbe, 0x00], "movsbl (%eax),%eax");
+    test_att(&[0x98], "cwtl");
+    test_att(&[0xff, 0x30], "pushl (%eax)");
+    test_att(&[0xd9, 0x00], "flds (%eax)");
+}
+
+#[test]
+fn branches() {
+    test_att(&[0xff, 0xd0], "call *%eax");
+    test_att(&[0xff, 0x25, 0x00, 0x10, 0x00, 0x00], "jmp *0x1000");
+
+    let mut inst = decode(&[0xeb, 0x10]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(att(&inst), "jmp 0x1012");
+}
@@ -0,0 +1,75 @@
+use crate::protected_mode::{Decoder, Instruction};
+use decoder::{Decoded, Direction, FlowKind, MemoryAccess};
+
+fn decode(data: &[u8]) -> Instruction {
+    Decoder::default().decode_slice(data).unwrap()
+}
+
+#[test]
+fn flow() {
+    assert_eq!(decode(&[0x31, 0xc0]).flow(), FlowKind::Sequential);
+    assert_eq!(
+        decode(&[0xe8, 0x00, 0x00, 0x00, 0x00]).flow(),
+        FlowKind::Call
+    );
+    assert_eq!(decode(&[0xeb, 0x10]).flow(), FlowKind::Jump);
+    assert_eq!(decode(&[0x74, 0x10]).flow(), FlowKind::ConditionalJump);
+    assert_eq!(decode(&[0xe3, 0x10]).flow(), FlowKind::ConditionalJump);
+    assert_eq!(decode(&[0xc3]).flow(), FlowKind::Return);
+    assert_eq!(decode(&[0xcd, 0x80]).flow(), FlowKind::Interrupt);
+    assert_eq!(decode(&[0xf4]).flow(), FlowKind::Halt);
+
+    assert!(decode(&[0xcd, 0x80]).can_fall_through());
+    assert!(!decode(&[0xeb, 0x10]).can_fall_through());
+}
+
+#[test]
+fn targets() {
+    let mut inst = decode(&[0x74, 0xfe]);
+    assert_eq!(inst.targets(), vec![]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![0x1000]);
+
+    // call dword [eax]
+    let mut inst = decode(&[0xff, 0x10]);
+    inst.update_rel_addrs(0x1000, None);
+    assert_eq!(inst.targets(), vec![]);
+}
+
+#[test]
+fn registers() {
+    // xor ecx, dword [eax]
+    let inst = decode(&[0x33, 0x08]);
+    assert_eq!(inst.regs_read(), vec!["ecx", "eax"]);
+    assert_eq!(inst.regs_written(), vec!["ecx", "eflags"]);
+
+    // pop ebp
+    let inst = decode(&[0x5d]);
+    assert_eq!(inst.regs_read(), vec!["esp"]);
+    assert_eq!(inst.regs_written(), vec!["ebp", "esp"]);
+
+    // div ecx
+    let inst = decode(&[0xf7, 0xf1]);
+    assert_eq!(inst.regs_read(), vec!["ecx", "eax", "edx"]);
+    assert_eq!(inst.regs_written(), vec!["eax", "edx", "eflags"]);
+}
+
+#[test]
+fn memory() {
+    // mov dword [esp + 0x4], eax
+    assert_eq!(
+        decode(&[0x89, 0x44, 0x24, 0x04]).memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::Write
+        })
+    );
+
+    assert_eq!(
+        decode(&[0x5d]).memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::Read
+        })
+    );
+}
@@ -1,9 +1,13 @@
 #![cfg(test)]
 
+mod att;
+mod behavior;
 mod evex_generated;
 mod opcode;
 mod operand;
+mod real_mode;
 mod regspec;
+mod uarch;
 
 use std::fmt::Write;
 
@@ -0,0 +1,154 @@
+use super::test_display_under;
+use crate::protected_mode::{Decoder, Instruction};
+use decoder::{Decoded, Direction, FlowKind, MemoryAccess};
+
+fn real_mode() -> Decoder {
+    Decoder::default().with_real_mode()
+}
+
+fn test_display(data: &[u8], expected: &'static str) {
+    test_display_under(&real_mode(), data, expected);
+}
+
+fn decode(data: &[u8]) -> Instruction {
+    real_mode().decode_slice(data).unwrap()
+}
+
+#[test]
+fn modrm_decode() {
+    test_display(&[0x89, 0xd8], "mov ax, bx");
+    test_display(&[0x8b, 0x00], "mov ax, word [bx + si]");
+    test_display(&[0x8b, 0x46, 0x04], "mov ax, word [bp + 0x4]");
+    test_display(&[0x8b, 0x0e, 0x34, 0x12], "mov cx, word [0x1234]");
+    test_display(&[0x88, 0x07], "mov byte [bx], al");
+    test_display(&[0x8d, 0x36, 0x00, 0x7c], "lea si, word [0x7c00]");
+}
+
+#[test]
+fn size_overrides() {
+    // operand size
+    test_display(&[0x66, 0x8b, 0x00], "mov eax, dword [bx + si]");
+    test_display(&[0x66, 0x31, 0xc0], "xor eax, eax");
+    // address size
+    test_display(&[0x67, 0x8b, 0x00], "mov ax, word [eax]");
+    test_display(&[0x67, 0x8b, 0x04, 0x8a], "mov ax, word [edx + ecx * 4]");
+    // both
+    test_display(&[0x66, 0x67, 0x8b, 0x00], "mov eax, dword [eax]");
+}
+
+#[test]
+fn immediates() {
+    test_display(&[0xb8, 0x34, 0x12], "mov ax, 0x1234");
+    test_display(&[0x66, 0xb8, 0x78, 0x56, 0x34, 0x12], "mov eax, 0x12345678");
+    test_display(
+        &[0xc7, 0x06, 0x13, 0x04, 0x00, 0x02],
+        "mov word [0x413], 0x200",
+    );
+    test_display(&[0x83, 0xc4, 0xfe], "add sp, -0x2");
+    test_display(&[0x68, 0x00, 0x7c], "push 0x7c00");
+}
+
+#[test]
+fn moffs() {
+    test_display(&[0xa1, 0x34, 0x12], "mov ax, word [0x1234]");
+    test_display(&[0xa2, 0x34, 0x12], "mov byte [0x1234], al");
+    test_display(
+        &[0x67, 0xa1, 0x78, 0x56, 0x34, 0x12],
+        "mov ax, word [0x12345678]",
+    );
+}
+
+#[test]
+fn control_flow() {
+    test_display(&[0xe8, 0x10, 0x00], "call 0x10");
+    test_display(&[0xe9, 0xfd, 0xff], "jmp -0x3");
+    test_display(&[0x0f, 0x84, 0x00, 0x01], "jz 0x100");
+    test_display(&[0x66, 0xe8, 0x10, 0x00, 0x00, 0x00], "call 0x10");
+    test_display(&[0xea, 0x5b, 0xe0, 0x00, 0xf0], "jmpf 0xf000:0xe05b");
+    test_display(
+        &[0x66, 0xea, 0x00, 0x7c, 0x00, 0x00, 0x00, 0x00],
+        "jmpf 0x0:0x7c00",
+    );
+    test_display(&[0xff, 0xd3], "call bx");
+    test_display(&[0xff, 0x27], "jmp word [bx]");
+    test_display(&[0xff, 0x1e, 0x00, 0x10], "callf dword [0x1000]");
+    test_display(&[0xe3, 0x02], "jcxz 0x2");
+    test_display(&[0x67, 0xe3, 0x02], "jecxz 0x2");
+    test_display(&[0xcd, 0x10], "int 0x10");
+    test_display(&[0xcf], "iret");
+    test_display(&[0x66, 0xcf], "iretd");
+}
+
+#[test]
+fn string_ops() {
+    test_display(&[0xa4], "movs byte es:[di], byte ds:[si]");
+    test_display(&[0xf3, 0xa5], "rep movs word es:[di], word ds:[si]");
+    test_display(&[0xac], "lods al, byte ds:[si]");
+    test_display(&[0xaa], "stos byte es:[di], al");
+    test_display(&[0x67, 0xac], "lods al, byte ds:[esi]");
+}
+
+#[test]
+fn segments() {
+    test_display(&[0x8e, 0xd8], "mov ds, ax");
+    test_display(&[0x26, 0x8b, 0x07], "mov ax, word es:[bx]");
+    test_display(&[0xc4, 0x1e, 0x00, 0x10], "les bx, dword [0x1000]");
+    test_display(&[0x0f, 0xb2, 0x26, 0x00, 0x10], "lss sp, dword [0x1000]");
+}
+
+#[test]
+fn flow() {
+    assert_eq!(decode(&[0xe8, 0x00, 0x00]).flow(), FlowKind::Call);
+    assert_eq!(decode(&[0xe3, 0x10]).flow(), FlowKind::ConditionalJump);
+    assert_eq!(decode(&[0xcd, 0x13]).flow(), FlowKind::Interrupt);
+    assert_eq!(decode(&[0xcf]).flow(), FlowKind::Return);
+    assert_eq!(
+        decode(&[0xea, 0x5b, 0xe0, 0x00, 0xf0]).flow(),
+        FlowKind::Jump
+    );
+}
+
+#[test]
+fn targets() {
+    let mut inst = decode(&[0xe9, 0xfd, 0xff]);
+    inst.update_rel_addrs(0x7c00, None);
+    assert_eq!(inst.targets(), vec![0x7c00]);
+
+    // far branches resolve to a linear address
+    let inst = decode(&[0xea, 0x5b, 0xe0, 0x00, 0xf0]);
+    assert_eq!(inst.targets(), vec![0xfe05b]);
+}
+
+#[test]
+fn registers() {
+    // pop bp
+    let inst = decode(&[0x5d]);
+    assert_eq!(inst.regs_read(), vec!["sp"]);
+    assert_eq!(inst.regs_written(), vec!["bp", "sp"]);
+
+    // rep stosb
+    let inst = decode(&[0xf3, 0xaa]);
+    assert_eq!(inst.regs_read(), vec!["di", "al", "ax", "cx"]);
+    assert_eq!(inst.regs_written(), vec!["di", "cx"]);
+
+    // jcxz
+    assert_eq!(decode(&[0xe3, 0x10]).regs_read(), vec!["cx"]);
+}
+
+#[test]
+fn memory() {
+    assert_eq!(
+        decode(&[0x5d]).memory(),
+        Some(MemoryAccess {
+            size: Some(2),
+            direction: Direction::Read
+        })
+    );
+    assert_eq!(
+        decode(&[0x66, 0x50]).memory(),
+        Some(MemoryAccess {
+            size: Some(4),
+            direction: Direction::Write
+        })
+    );
+}
@@ -0,0 +1,125 @@
+//! Microarchitecture queries shared by the decoders of every mode.
+//!
+//! Each mode has its own `Decoder` and `Instruction`, so the queries are expanded into the
+//! mode's `uarch` module by [`queries`] rather than written out twice.
+
+/// Names of the flags tracked by a `Decoder`, indexed by their bit.
+#[rustfmt::skip]
+pub(crate) const FLAGS: [&str; 64] = [
+    "sse3", "ssse3", "monitor", "vmx", "fma3", "cmpxchg16b", "sse4.1", "sse4.2", "movbe",
+    "popcnt", "aesni", "xsave", "rdrand", "sgx", "bmi1", "avx2", "bmi2", "invpcid", "mpx",
+    "avx512f", "avx512dq", "rdseed", "adx", "avx512ifma", "pcommit", "clflushopt", "clwb",
+    "avx512pf", "avx512er", "avx512cd", "sha", "avx512bw", "avx512vl", "prefetchwt1",
+    "avx512vbmi", "avx512vbmi2", "gfni", "vaes", "pclmulqdq", "avx-vnni", "avx512bitalg",
+    "avx512vpopcntdq", "avx512-4vnniw", "avx512-4fmaps", "cx8", "syscall", "rdtscp", "abm",
+    "sse4a", "3dnowprefetch", "xop", "skinit", "tbm", "intel", "amd", "avx", "svm", "lahfsahf",
+    "cmov", "f16c", "fma4", "prefetchw", "tsx", "lzcnt",
+];
+
+/// Bits of the flags selecting a vendor's quirks, which aren't extensions.
+pub(crate) const VENDORS: [usize; 2] = [53, 54];
+
+/// Bits of the flags selecting an instruction set extension.
+pub(crate) fn extension_bits() -> impl Iterator<Item = usize> {
+    (0..64).filter(|bit| !VENDORS.contains(bit))
+}
+
+/// Expands to the microarchitecture profiles and queries of a mode, `Decoder`, `Instruction` and
+/// the `amd` and `intel` profiles have to be in scope.
+macro_rules! queries {
+    () => {
+        /// Name of a microarchitecture and the decoder of its instruction set.
+        pub type Profile = (&'static str, fn() -> Decoder);
+
+        /// Microarchitecture profiles by name, oldest first for each vendor.
+        pub const PROFILES: [Profile; 19] = [
+            ("k8", amd::k8),
+            ("k10", amd::k10),
+            ("bulldozer", amd::bulldozer),
+            ("piledriver", amd::piledriver),
+            ("steamroller", amd::steamroller),
+            ("excavator", amd::excavator),
+            ("zen", amd::zen),
+            ("netburst", intel::netburst),
+            ("core", intel::core),
+            ("penryn", intel::penryn),
+            ("nehalem", intel::nehalem),
+            ("westmere", intel::westmere),
+            ("sandybridge", intel::sandybridge),
+            ("ivybridge", intel::ivybridge),
+            ("haswell", intel::haswell),
+            ("haswell_ex", intel::haswell_ex),
+            ("broadwell", intel::broadwell),
+            ("skylake", intel::skylake),
+            ("kabylake", intel::kabylake),
+        ];
+
+        /// Find the decoder of a microarchitecture by name, e.g. `haswell` or `zen`.
+        pub fn by_name(name: &str) -> Option<Decoder> {
+            PROFILES
+                .iter()
+                .find(|(profile, _)| *profile == name)
+                .map(|(_, decoder)| decoder())
+        }
+
+        impl Decoder {
+            /// Whether an instruction decoded by the default decoder is understood the same way
+            /// by this decoder.
+            pub fn supports(&self, inst: &Instruction) -> bool {
+                let mut revised = inst.clone();
+                self.revise_instruction(&mut revised).is_ok() && revised.opcode == inst.opcode
+            }
+
+            /// Minimal decoder with only the flags of `flags`.
+            fn with_only(flags: u64) -> Self {
+                let mut decoder = Decoder::minimal();
+                decoder.flags = flags;
+                decoder
+            }
+        }
+
+        /// Extensions an instruction requires, empty if it's part of the baseline instruction set
+        /// or only depends on the vendor, see [`vendor`].
+        ///
+        /// If any one of several extensions is enough to support the instruction, only the first
+        /// of them is returned.
+        pub fn extensions(inst: &Instruction) -> Vec<&'static str> {
+            if Decoder::minimal().supports(inst) {
+                return Vec::new();
+            }
+
+            // avx512 subsets aren't distinguished when decoding, evex encoded instructions need
+            // them all
+            if inst.prefixes.evex().is_some() {
+                return vec!["avx512"];
+            }
+
+            let all = Decoder::default().flags;
+            let required: Vec<&str> = crate::uarch::extension_bits()
+                .filter(|bit| !Decoder::with_only(all & !(1 << bit)).supports(inst))
+                .map(|bit| crate::uarch::FLAGS[bit])
+                .collect();
+
+            if !required.is_empty() {
+                return required;
+            }
+
+            crate::uarch::extension_bits()
+                .find(|bit| Decoder::with_only(1 << bit).supports(inst))
+                .map(|bit| vec![crate::uarch::FLAGS[bit]])
+                .unwrap_or_default()
+        }
+
+        /// Vendor whose processors are the only ones to decode an instruction this way, either
+        /// `intel` or `amd`.
+        pub fn vendor(inst: &Instruction) -> Option<&'static str> {
+            let all = Decoder::default().flags;
+            crate::uarch::VENDORS
+                .into_iter()
+                .find(|bit| !Decoder::with_only(all & !(1 << bit)).supports(inst))
+                .map(|bit| crate::uarch::FLAGS[bit])
+        }
+    };
+}
+
+pub(crate) use queries;
@@ -0,0 +1,468 @@
+//! Architecture neutral intermediate representation instructions are lifted to.
+//!
+//! An instruction lifts to a handful of statements over temporaries, which are each assigned
+//! exactly once. Registers and memory are only accessed through explicit statements, so a
+//! sequence of lifted instructions is in SSA form once the registers in it are renamed.
+//!
+//! Every value has a width of at most 64 bits, condition flags are separate 1-bit registers.
+
+use std::fmt;
+
+/// Name of an architectural register, as it's shown by the decoders.
+pub type Reg = &'static str;
+
+/// A temporary that's assigned once.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Temp {
+    pub id: u32,
+    pub bits: u8,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Value {
+    Temp(Temp),
+    Const { value: u64, bits: u8 },
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum UnOp {
+    Not,
+    Neg,
+    /// Count of leading zero bits.
+    Clz,
+    /// Count of trailing zero bits.
+    Ctz,
+    /// Count of set bits.
+    Popcnt,
+    /// Reverse the order of the bytes.
+    Bswap,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    /// Upper half of the unsigned product.
+    MulHiU,
+    /// Upper half of the signed product.
+    MulHiS,
+    UDiv,
+    SDiv,
+    URem,
+    SRem,
+    And,
+    Or,
+    Xor,
+    Shl,
+    LShr,
+    AShr,
+    Ror,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Cmp {
+    Eq,
+    Ne,
+    Ult,
+    Ule,
+    Slt,
+    Sle,
+}
+
+/// Computation assigned to a temporary, it has the width of the temporary.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Expr {
+    Reg(Reg),
+    Load(Value),
+    Unary(UnOp, Value),
+    Binary(BinOp, Value, Value),
+    /// 1 if the comparison holds, 0 otherwise.
+    Compare(Cmp, Value, Value),
+    ZeroExtend(Value),
+    SignExtend(Value),
+    /// The low bits of a value.
+    Truncate(Value),
+    /// The second value if the first is non-zero, the third otherwise.
+    Select(Value, Value, Value),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Stmt {
+    Assign(Temp, Expr),
+    SetReg(Reg, Value),
+    /// Write a value to the address, storing as many bytes as the value is wide.
+    Store(Value, Value),
+    Jump(Value),
+    /// Jump to the target if the condition is non-zero, continue at the next instruction
+    /// otherwise.
+    Branch(Value, Value),
+    /// Jump to a subroutine, the return address has already been saved.
+    Call(Value),
+    /// Return to an address that has already been restored.
+    Return(Value),
+    /// Operation that isn't modeled, such as a system call or a fence.
+    Intrinsic(&'static str, Vec<Value>),
+    /// The lifter doesn't know the semantics of the instruction.
+    Unsupported,
+}
+
+/// Bits set in a value of `bits` width.
+pub fn mask(bits: u8) -> u64 {
+    if bits >= 64 {
+        u64::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+/// Interpret the low `bits` of a value as a signed integer.
+pub fn sign_extend(value: u64, bits: u8) -> i64 {
+    let shift = 64 - bits.clamp(1, 64) as u32;
+    ((value << shift) as i64) >> shift
+}
+
+impl Value {
+    /// A constant, truncated to `bits`.
+    pub fn constant(value: u64, bits: u8) -> Self {
+        Value::Const {
+            value: value & mask(bits),
+            bits,
+        }
+    }
+
+    pub fn bits(&self) -> u8 {
+        match *self {
+            Value::Temp(temp) => temp.bits,
+            Value::Const { bits, .. } => bits,
+        }
+    }
+
+    pub fn as_const(&self) -> Option<u64> {
+        match *self {
+            Value::Const { value, .. } => Some(value),
+            Value::Temp(_) => None,
+        }
+    }
+}
+
+impl UnOp {
+    pub fn eval(self, value: u64, bits: u8) -> u64 {
+        let value = value & mask(bits);
+        let result = match self {
+            UnOp::Not => !value,
+            UnOp::Neg => value.wrapping_neg(),
+            UnOp::Clz => (value.leading_zeros() - (64 - bits as u32)) as u64,
+            UnOp::Ctz => value.trailing_zeros().min(bits as u32) as u64,
+            UnOp::Popcnt => value.count_ones() as u64,
+            UnOp::Bswap => value.swap_bytes() >> (64 - bits as u32),
+        };
+
+        result & mask(bits)
+    }
+}
+
+impl BinOp {
+    /// Result of the operation, `None` in case of a division by zero.
+    ///
+    /// Shifting by the width or more shifts out all bits, rotations wrap around.
+    pub fn eval(self, lhs: u64, rhs: u64, bits: u8) -> Option<u64> {
+        let (lhs, rhs) = (lhs & mask(bits), rhs & mask(bits));
+        let (slhs, srhs) = (sign_extend(lhs, bits), sign_extend(rhs, bits));
+        let width = bits as u64;
+
+        let result = match self {
+            BinOp::Add => lhs.wrapping_add(rhs),
+            BinOp::Sub => lhs.wrapping_sub(rhs),
+            BinOp::Mul => lhs.wrapping_mul(rhs),
+            BinOp::MulHiU => ((lhs as u128 * rhs as u128) >> bits) as u64,
+            BinOp::MulHiS => ((slhs as i128 * srhs as i128) >> bits) as u64,
+            BinOp::UDiv => lhs.checked_div(rhs)?,
+            BinOp::URem => lhs.checked_rem(rhs)?,
+            BinOp::SDiv if rhs == 0 => return None,
+            BinOp::SDiv => slhs.wrapping_div(srhs) as u64,
+            BinOp::SRem if rhs == 0 => return None,
+            BinOp::SRem => slhs.wrapping_rem(srhs) as u64,
+            BinOp::And => lhs & rhs,
+            BinOp::Or => lhs | rhs,
+            BinOp::Xor => lhs ^ rhs,
+            BinOp::Shl if rhs >= width => 0,
+            BinOp::Shl => lhs << rhs,
+            BinOp::LShr if rhs >= width => 0,
+            BinOp::LShr => lhs >> rhs,
+            BinOp::AShr => (slhs >> rhs.min(63)) as u64,
+            BinOp::Ror => {
+                let rhs = rhs % width;
+                if rhs == 0 {
+                    lhs
+                } else {
+                    (lhs >> rhs) | (lhs << (width - rhs))
+                }
+            }
+        };
+
+        Some(result & mask(bits))
+    }
+}
+
+impl Cmp {
+    pub fn eval(self, lhs: u64, rhs: u64, bits: u8) -> bool {
+        let (lhs, rhs) = (lhs & mask(bits), rhs & mask(bits));
+        let (slhs, srhs) = (sign_extend(lhs, bits), sign_extend(rhs, bits));
+
+        match self {
+            Cmp::Eq => lhs == rhs,
+            Cmp::Ne => lhs != rhs,
+            Cmp::Ult => lhs < rhs,
+            Cmp::Ule => lhs <= rhs,
+            Cmp::Slt => slhs < srhs,
+            Cmp::Sle => slhs <= srhs,
+        }
+    }
+}
+
+/// Collects the statements of lifted instructions.
+///
+/// Expressions on constants are folded, so addresses computed from the instruction pointer end
+/// up as constants.
+#[derive(Debug, Default)]
+pub struct Builder {
+    stmts: Vec<Stmt>,
+    temps: u32,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Statements lifted so far, temporaries keep being numbered from where they left off.
+    pub fn take(&mut self) -> Vec<Stmt> {
+        std::mem::take(&mut self.stmts)
+    }
+
+    pub fn stmts(&self) -> &[Stmt] {
+        &self.stmts
+    }
+
+    fn assign(&mut self, bits: u8, expr: Expr) -> Value {
+        let temp = Temp {
+            id: self.temps,
+            bits,
+        };
+
+        self.temps += 1;
+        self.stmts.push(Stmt::Assign(temp, expr));
+        Value::Temp(temp)
+    }
+
+    pub fn reg(&mut self, reg: Reg, bits: u8) -> Value {
+        self.assign(bits, Expr::Reg(reg))
+    }
+
+    pub fn set_reg(&mut self, reg: Reg, value: Value) {
+        self.stmts.push(Stmt::SetReg(reg, value));
+    }
+
+    pub fn load(&mut self, addr: Value, bits: u8) -> Value {
+        self.assign(bits, Expr::Load(addr))
+    }
+
+    pub fn store(&mut self, addr: Value, value: Value) {
+        self.stmts.push(Stmt::Store(addr, value));
+    }
+
+    pub fn unary(&mut self, op: UnOp, value: Value) -> Value {
+        let bits = value.bits();
+        match value.as_const() {
+            Some(value) => Value::constant(op.eval(value, bits), bits),
+            None => self.assign(bits, Expr::Unary(op, value)),
+        }
+    }
+
+    /// Operation on two values, the result is as wide as `lhs`.
+    pub fn binary(&mut self, op: BinOp, lhs: Value, rhs: Value) -> Value {
+        let bits = lhs.bits();
+        if let (Some(a), Some(b)) = (lhs.as_const(), rhs.as_const()) {
+            if let Some(value) = op.eval(a, b, bits) {
+                return Value::constant(value, bits);
+            }
+        }
+
+        self.assign(bits, Expr::Binary(op, lhs, rhs))
+    }
+
+    pub fn compare(&mut self, cmp: Cmp, lhs: Value, rhs: Value) -> Value {
+        match (lhs.as_const(), rhs.as_const()) {
+            (Some(a), Some(b)) => Value::constant(cmp.eval(a, b, lhs.bits()) as u64, 1),
+            _ => self.assign(1, Expr::Compare(cmp, lhs, rhs)),
+        }
+    }
+
+    pub fn zext(&mut self, value: Value, bits: u8) -> Value {
+        match value.as_const() {
+            _ if value.bits() == bits => value,
+            Some(value) => Value::constant(value, bits),
+            None => self.assign(bits, Expr::ZeroExtend(value)),
+        }
+    }
+
+    pub fn sext(&mut self, value: Value, bits: u8) -> Value {
+        match value.as_const() {
+            _ if value.bits() == bits => value,
+            Some(c) => Value::constant(sign_extend(c, value.bits()) as u64, bits),
+            None => self.assign(bits, Expr::SignExtend(value)),
+        }
+    }
+
+    pub fn trunc(&mut self, value: Value, bits: u8) -> Value {
+        match value.as_const() {
+            _ if value.bits() == bits => value,
+            Some(value) => Value::constant(value, bits),
+            None => self.assign(bits, Expr::Truncate(value)),
+        }
+    }
+
+    pub fn select(&mut self, cond: Value, then: Value, otherwise: Value) -> Value {
+        match cond.as_const() {
+            Some(0) => otherwise,
+            Some(_) => then,
+            None => self.assign(then.bits(), Expr::Select(cond, then, otherwise)),
+        }
+    }
+
+    pub fn jump(&mut self, target: Value) {
+        self.stmts.push(Stmt::Jump(target));
+    }
+
+    pub fn branch(&mut self, cond: Value, target: Value) {
+        self.stmts.push(Stmt::Branch(cond, target));
+    }
+
+    pub fn call(&mut self, target: Value) {
+        self.stmts.push(Stmt::Call(target));
+    }
+
+    pub fn ret(&mut self, target: Value) {
+        self.stmts.push(Stmt::Return(target));
+    }
+
+    pub fn intrinsic(&mut self, name: &'static str, args: Vec<Value>) {
+        self.stmts.push(Stmt::Intrinsic(name, args));
+    }
+
+    pub fn unsupported(&mut self) {
+        self.stmts.push(Stmt::Unsupported);
+    }
+
+    /// Run a lifter, the statements it added are replaced by [`Stmt::Unsupported`] if it fails.
+    pub fn attempt(&mut self, lift: impl FnOnce(&mut Self) -> Option<()>) {
+        let len = self.stmts.len();
+        if lift(self).is_none() {
+            self.stmts.truncate(len);
+            self.unsupported();
+        }
+    }
+}
+
+impl fmt::Display for Temp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "t{}", self.id)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Temp(temp) => temp.fmt(f),
+            Value::Const { value, .. } => write!(f, "{value:#x}"),
+        }
+    }
+}
+
+impl UnOp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UnOp::Not => "~",
+            UnOp::Neg => "-",
+            UnOp::Clz => "clz ",
+            UnOp::Ctz => "ctz ",
+            UnOp::Popcnt => "popcnt ",
+            UnOp::Bswap => "bswap ",
+        }
+    }
+}
+
+impl BinOp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::MulHiU => "*hu",
+            BinOp::MulHiS => "*hs",
+            BinOp::UDiv => "/u",
+            BinOp::SDiv => "/s",
+            BinOp::URem => "%u",
+            BinOp::SRem => "%s",
+            BinOp::And => "&",
+            BinOp::Or => "|",
+            BinOp::Xor => "^",
+            BinOp::Shl => "<<",
+            BinOp::LShr => ">>u",
+            BinOp::AShr => ">>s",
+            BinOp::Ror => "ror",
+        }
+    }
+}
+
+impl Cmp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Cmp::Eq => "==",
+            Cmp::Ne => "!=",
+            Cmp::Ult => "<u",
+            Cmp::Ule => "<=u",
+            Cmp::Slt => "<s",
+            Cmp::Sle => "<=s",
+        }
+    }
+}
+
+impl fmt::Display for Stmt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stmt::Assign(temp, expr) => {
+                write!(f, "{temp} = ")?;
+                match expr {
+                    Expr::Reg(reg) => f.write_str(reg),
+                    Expr::Load(addr) => write!(f, "load{} [{addr}]", temp.bits),
+                    Expr::Unary(op, value) => write!(f, "{}{value}", op.as_str()),
+                    Expr::Binary(op, lhs, rhs) => write!(f, "{lhs} {} {rhs}", op.as_str()),
+                    Expr::Compare(cmp, lhs, rhs) => write!(f, "{lhs} {} {rhs}", cmp.as_str()),
+                    Expr::ZeroExtend(value) => write!(f, "zext{} {value}", temp.bits),
+                    Expr::SignExtend(value) => write!(f, "sext{} {value}", temp.bits),
+                    Expr::Truncate(value) => write!(f, "trunc{} {value}", temp.bits),
+                    Expr::Select(cond, a, b) => write!(f, "{cond} ? {a} : {b}"),
+                }
+            }
+            Stmt::SetReg(reg, value) => write!(f, "{reg} = {value}"),
+            Stmt::Store(addr, value) => write!(f, "store{} [{addr}], {value}", value.bits()),
+            Stmt::Jump(target) => write!(f, "goto {target}"),
+            Stmt::Branch(cond, target) => write!(f, "if {cond} goto {target}"),
+            Stmt::Call(target) => write!(f, "call {target}"),
+            Stmt::Return(target) => write!(f, "ret {target}"),
+            Stmt::Intrinsic(name, args) => {
+                write!(f, "{name}(")?;
+                for (idx, arg) in args.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_str(", ")?;
+                    }
+                    arg.fmt(f)?;
+                }
+                f.write_str(")")
+            }
+            Stmt::Unsupported => f.write_str("unsupported"),
+        }
+    }
+}
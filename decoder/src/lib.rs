@@ -1,5 +1,7 @@
 //! Shared behaviour required between decoder crates.
 
+pub mod ir;
+
 use std::fmt::Debug;
 use debugvault::Index;
 use tokenizing::{TokenStream, Token};
@@ -45,8 +47,8 @@ pub enum ErrorKind {
     /// `decoder-arm` doesn't know how to decode this, but it may be a valid instruction. the
     /// instruction decoder is not complete, sorry. :(
     ///
-    /// In practice this typically indicates some kinds of coprocessor instruction, or `ARMv7` SIMD
-    /// instruction.
+    /// In practice this typically indicates an `ARMv7` media instruction, or one of the
+    /// unconditional memory hints and miscellaneous instructions.
     Incomplete,
     /// the instruction includes reserved bits that were not set as required.
     Nonconforming,
@@ -54,6 +56,71 @@ pub enum ErrorKind {
     Undefined,
     /// the input encodes an instruction with unpredictable behavior.
     Unpredictable,
+    /// the instruction belongs to an ISA extension the decoder wasn't configured with.
+    DisabledExtension,
+}
+
+/// How an instruction transfers control once it has executed.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum FlowKind {
+    /// Execution continues at the next instruction.
+    Sequential,
+    /// Unconditional transfer of control, direct or indirect.
+    Jump,
+    /// Transfer of control that depends on some condition, falls through otherwise.
+    ConditionalJump,
+    /// Transfer of control that is expected to return to the next instruction.
+    Call,
+    /// Return from a subroutine, exception or system call.
+    Return,
+    /// Software interrupt, system call or breakpoint, execution resumes after it.
+    Interrupt,
+    /// Stops execution or traps unconditionally (e.g. `hlt`, `ud2`, `udf`).
+    Halt,
+}
+
+/// Whether a memory operand is read from, written to or both.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Direction {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// A memory access performed by an instruction, explicit or implied (e.g. `push`).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct MemoryAccess {
+    /// Number of bytes accessed, if it can be known from the instruction alone.
+    pub size: Option<usize>,
+    pub direction: Direction,
+}
+
+/// Assembly syntax x86 instructions are tokenized in.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub enum Syntax {
+    /// `mov rax, qword [rbp - 0x8]`.
+    #[default]
+    Intel,
+    /// `mov -0x8(%rbp),%rax`, as printed by GCC and objdump.
+    Att,
+}
+
+/// How instructions are rendered when tokenized.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Dialect {
+    pub syntax: Syntax,
+    /// Show pseudo-instructions such as `li` and `mv` instead of their canonical encoding
+    /// (RISC-V, MIPS, PowerPC and LoongArch).
+    pub pseudo: bool,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Self {
+            syntax: Syntax::Intel,
+            pseudo: true,
+        }
+    }
 }
 
 pub trait ToTokens {
@@ -62,12 +129,60 @@ pub trait ToTokens {
 
 pub trait Decoded: ToTokens {
     fn width(&self) -> usize;
-    fn tokens(&self, symbols: &Index) -> Vec<Token> {
+    fn tokens(&self, symbols: &Index, dialect: Dialect) -> Vec<Token> {
         let mut stream = TokenStream::new();
-        self.tokenize(&mut stream, symbols);
+        self.tokenize_in(&mut stream, symbols, dialect);
         stream.inner
     }
+
+    /// Tokenize in the given dialect, [`ToTokens::tokenize`] uses the default one.
+    ///
+    /// Architectures without dialects don't have to implement this.
+    fn tokenize_in(&self, stream: &mut TokenStream, symbols: &Index, _dialect: Dialect) {
+        self.tokenize(stream, symbols);
+    }
     fn update_rel_addrs(&mut self, addr: usize, prev_inst: Option<&Self>);
+
+    /// How this instruction affects control flow.
+    fn flow(&self) -> FlowKind {
+        FlowKind::Sequential
+    }
+
+    /// Statically known destinations of a jump or call.
+    ///
+    /// Relative targets are only absolute after [`Decoded::update_rel_addrs`] has been called.
+    fn targets(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// Registers read by this instruction, including implicit ones and those used to compute
+    /// the address of a memory operand.
+    fn regs_read(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Registers written by this instruction, including implicit ones.
+    fn regs_written(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Memory accessed by this instruction, if any.
+    fn memory(&self) -> Option<MemoryAccess> {
+        None
+    }
+
+    /// Whether execution may continue at the next instruction.
+    fn can_fall_through(&self) -> bool {
+        !matches!(
+            self.flow(),
+            FlowKind::Jump | FlowKind::Return | FlowKind::Halt
+        )
+    }
+
+    /// Lift the semantics of this instruction, placed at `addr`, to [`ir`] statements.
+    fn lift(&self, _addr: usize, builder: &mut ir::Builder) {
+        builder.unsupported();
+    }
 }
 
 pub trait Decodable {
@@ -182,7 +297,7 @@ fn unlikely(b: bool) -> bool {
 /// Encode 64-bit signed integer with a leading '0x' and in lowercase.
 pub fn encode_hex(mut imm: i64) -> String {
     unsafe {
-        let mut buffer = Vec::with_capacity(19);
+        let mut buffer = [0u8; 19];
         let slice = &mut buffer[..];
         let mut idx = 0;
 
@@ -200,8 +315,7 @@ pub fn encode_hex(mut imm: i64) -> String {
         if unlikely(imm == 0) {
             *slice.get_unchecked_mut(idx) = b'0';
             idx += 1;
-            buffer.set_len(idx);
-            return String::from_utf8_unchecked(buffer);
+            return String::from_utf8_unchecked(buffer[..idx].to_vec());
         }
 
         // imm is already checked to not be zero, therefore this can't fail
@@ -218,15 +332,14 @@ pub fn encode_hex(mut imm: i64) -> String {
             *slice.get_unchecked_mut(jdx) = chr;
         }
 
-        buffer.set_len(idx + len);
-        String::from_utf8_unchecked(buffer)
+        String::from_utf8_unchecked(buffer[..idx + len].to_vec())
     }
 }
 
 /// Encode 64-bit unsigned integer with a leading '0x' and in lowercase.
 pub fn encode_uhex(mut imm: u64) -> String {
     unsafe {
-        let mut buffer = Vec::with_capacity(19);
+        let mut buffer = [0u8; 19];
         let slice = &mut buffer[..];
         let mut idx = 0;
 
@@ -238,8 +351,7 @@ pub fn encode_uhex(mut imm: u64) -> String {
         if unlikely(imm == 0) {
             *slice.get_unchecked_mut(idx) = b'0';
             idx += 1;
-            buffer.set_len(idx);
-            return String::from_utf8_unchecked(buffer);
+            return String::from_utf8_unchecked(buffer[..idx].to_vec());
         }
 
         // imm is already checked to not be zero, therefore this can't fail
@@ -256,8 +368,7 @@ pub fn encode_uhex(mut imm: u64) -> String {
             *slice.get_unchecked_mut(jdx) = chr;
         }
 
-        buffer.set_len(idx + len);
-        String::from_utf8_unchecked(buffer)
+        String::from_utf8_unchecked(buffer[..idx + len].to_vec())
     }
 }
 
@@ -1,5 +1,7 @@
 use crate::tprint;
-use commands::{Command, CommandError};
+use commands::{Command, CommandError, CompleteExpr, WatchKind};
+use debugger::{Debugger, StopReason};
+use processor::Uarch;
 
 impl super::UI {
     /// Runs all queued commands, returning if they trigger a process exit.
@@ -54,6 +56,463 @@ impl super::UI {
 
                 self.panels.load_src(addr);
             }
+            Ok(Command::Attach(pid)) => {
+                if cfg!(target_os = "linux") {
+                    tprint!(self.panels.terminal(), "Attaching to process {pid}.");
+                    self.offload_process_attach(pid);
+                } else {
+                    tprint!(self.panels.terminal(), "Attaching is only supported on linux.");
+                }
+            }
+            Ok(Command::Connect(addr)) => {
+                tprint!(self.panels.terminal(), "Connecting to {addr}.");
+                self.offload_debugger_connect(addr);
+            }
+            Ok(Command::StepInstruction) => {
+                let result = self.with_debugger(|dbg| dbg.step());
+                if let Some(reason) = result {
+                    self.report_stop(reason);
+                }
+            }
+            Ok(Command::Continue) => {
+                if self.with_debugger(|dbg| dbg.cont()).is_some() {
+                    tprint!(self.panels.terminal(), "Continuing.");
+                }
+            }
+            Ok(Command::Stop) => {
+                self.with_debugger(|dbg| dbg.interrupt());
+            }
+            Ok(Command::Break { addr, condition }) => {
+                if let Some(addr) = self.eval_runtime_expr(&addr) {
+                    // Without a target, breakpoints are where emulation stops.
+                    if self.panels.debugger().is_none() && condition.is_none() {
+                        self.panels.emulated_breakpoints().insert(addr);
+                        tprint!(
+                            self.panels.terminal(),
+                            "Breakpoint set at {addr:#x} for emulation."
+                        );
+                        return true;
+                    }
+
+                    if self.with_debugger(|dbg| dbg.set_breakpoint(addr)).is_none() {
+                        return true;
+                    }
+
+                    match condition {
+                        Some(condition) => {
+                            self.panels.conditions().insert(addr, condition);
+                            tprint!(
+                                self.panels.terminal(),
+                                "Conditional breakpoint set at {addr:#x}."
+                            );
+                        }
+                        None => {
+                            self.panels.conditions().remove(&addr);
+                            tprint!(self.panels.terminal(), "Breakpoint set at {addr:#x}.");
+                        }
+                    }
+                }
+            }
+            Ok(Command::Delete(expr)) => {
+                if let Some(addr) = self.eval_runtime_expr(&expr) {
+                    if self.panels.debugger().is_none() {
+                        match self.panels.emulated_breakpoints().remove(&addr) {
+                            true => tprint!(
+                                self.panels.terminal(),
+                                "Breakpoint at {addr:#x} deleted."
+                            ),
+                            false => tprint!(self.panels.terminal(), "No breakpoint at {addr:#x}."),
+                        }
+                        return true;
+                    }
+
+                    if self.with_debugger(|dbg| dbg.remove_breakpoint(addr)).is_some() {
+                        self.panels.conditions().remove(&addr);
+                        tprint!(self.panels.terminal(), "Breakpoint at {addr:#x} deleted.");
+                    }
+                }
+            }
+            Ok(Command::Watch { kind, len, addr }) => {
+                let addr = match self.eval_runtime_expr(&addr) {
+                    Some(addr) => addr,
+                    None => return true,
+                };
+
+                // Watch the largest naturally aligned unit, up to a doubleword.
+                let len = len.unwrap_or(1 << addr.trailing_zeros().min(3));
+                let kind = match kind {
+                    WatchKind::Write => debugger::WatchKind::Write,
+                    WatchKind::Read => debugger::WatchKind::Read,
+                    WatchKind::Access => debugger::WatchKind::Access,
+                };
+
+                if self.with_debugger(|dbg| dbg.set_watchpoint(addr, len, kind)).is_some() {
+                    self.panels.watchpoints().push((addr, len, kind));
+                    tprint!(
+                        self.panels.terminal(),
+                        "{kind:?} watchpoint set on {len} bytes at {addr:#x}."
+                    );
+                }
+            }
+            Ok(Command::Unwatch(expr)) => {
+                let addr = match self.eval_runtime_expr(&expr) {
+                    Some(addr) => addr,
+                    None => return true,
+                };
+
+                let watchpoints = self.panels.watchpoints();
+                let (len, kind) = match watchpoints.iter().position(|wp| wp.0 == addr) {
+                    Some(idx) => {
+                        let (_, len, kind) = watchpoints.remove(idx);
+                        (len, kind)
+                    }
+                    None => {
+                        tprint!(self.panels.terminal(), "No watchpoint at {addr:#x}.");
+                        return true;
+                    }
+                };
+
+                if self.with_debugger(|dbg| dbg.remove_watchpoint(addr, len, kind)).is_some() {
+                    tprint!(self.panels.terminal(), "Watchpoint at {addr:#x} deleted.");
+                }
+            }
+            Ok(Command::Examine {
+                count,
+                format,
+                unit,
+                addr,
+            }) => {
+                let addr = match self.eval_runtime_expr(&addr) {
+                    Some(addr) => addr,
+                    None => return true,
+                };
+
+                // Strings are of unknown length, so read a reasonable amount and cut them off.
+                let len = match format {
+                    's' => count * 256,
+                    _ => count * unit,
+                };
+
+                let bytes = match self.with_debugger(|dbg| dbg.read_memory(addr, len)) {
+                    Some(bytes) => bytes,
+                    None => return true,
+                };
+
+                let processor = self.panels.processor().cloned();
+                let empty_index = debugvault::Index::default();
+                let index = processor.as_ref().map(|proc| &proc.index).unwrap_or(&empty_index);
+
+                let out = debugger::examine(&bytes, addr, format, unit, index);
+                let lines = match format {
+                    's' => out.lines().take(count).collect::<Vec<_>>().join("\n"),
+                    _ => out.trim_end().to_string(),
+                };
+
+                tprint!(self.panels.terminal(), "{lines}");
+            }
+            Ok(Command::Print(expr)) => {
+                if let Some(reg) = expr.as_register() {
+                    let reg = reg.to_string();
+                    let regs = self.with_debugger(|dbg| dbg.registers());
+                    let found = regs.and_then(|regs| regs.into_iter().find(|r| r.name == reg));
+
+                    // Print whole registers, as vector registers don't fit in 64 bits.
+                    if let Some(reg) = found.filter(|reg| reg.value.len() > 8) {
+                        let hex: String =
+                            reg.value.iter().rev().map(|byte| format!("{byte:02x}")).collect();
+                        tprint!(self.panels.terminal(), "${} = 0x{hex}", reg.name);
+                        return true;
+                    }
+                }
+
+                if let Some(val) = self.eval_runtime_expr(&expr) {
+                    tprint!(self.panels.terminal(), "{val:#x} ({})", val as isize);
+                }
+            }
+            Ok(Command::SetRegister(reg, expr)) => {
+                if let Some(val) = self.eval_runtime_expr(&expr) {
+                    // Without a target, registers are what emulation starts with.
+                    if self.panels.debugger().is_none() {
+                        tprint!(self.panels.terminal(), "${reg} = {val:#x} for emulation.");
+                        self.panels.emulated_registers().insert(reg, val as u64);
+                        return true;
+                    }
+
+                    let reg = self.with_debugger(|dbg| {
+                        let reg = alias_register(dbg, &reg).to_string();
+                        dbg.write_register(&reg, val as u64).map(|_| reg)
+                    });
+
+                    if let Some(reg) = reg {
+                        tprint!(self.panels.terminal(), "${reg} = {val:#x}");
+                    }
+                }
+            }
+            Ok(Command::FollowChildren(follow)) => {
+                if self.with_debugger(|dbg| dbg.follow_children(follow)).is_some() {
+                    match follow {
+                        true => tprint!(self.panels.terminal(), "Following forked children."),
+                        false => tprint!(self.panels.terminal(), "Detaching from forked children."),
+                    }
+                }
+            }
+            Ok(Command::Inferior(None)) => {
+                let inferiors = match self.panels.debugger() {
+                    Some(debugger) => debugger.inferiors(),
+                    None => {
+                        tprint!(self.panels.terminal(), "Not debugging a target.");
+                        return true;
+                    }
+                };
+
+                for inferior in inferiors {
+                    let marker = if inferior.selected { '*' } else { ' ' };
+                    let state = if inferior.running { "running" } else { "stopped" };
+                    tprint!(
+                        self.panels.terminal(),
+                        "{marker} process {} ({state})",
+                        inferior.pid
+                    );
+                }
+            }
+            Ok(Command::Inferior(Some(pid))) => {
+                if self.with_debugger(|dbg| dbg.select_inferior(pid)).is_some() {
+                    tprint!(self.panels.terminal(), "Switched to process {pid}.");
+                    self.show_pc();
+                }
+            }
+            Ok(Command::Syntax(syntax)) => {
+                let processor = match self.panels.processor() {
+                    Some(processor) => processor.clone(),
+                    None => {
+                        tprint!(self.panels.terminal(), "No targets loaded.");
+                        return true;
+                    }
+                };
+
+                processor.set_dialect(decoder::Dialect { syntax, ..processor.dialect() });
+                if let Some(listing) = self.panels.listing() {
+                    listing.refresh();
+                }
+
+                match syntax {
+                    decoder::Syntax::Intel => tprint!(self.panels.terminal(), "Using intel syntax."),
+                    decoder::Syntax::Att => tprint!(self.panels.terminal(), "Using AT&T syntax."),
+                }
+            }
+            Ok(Command::Pseudo(pseudo)) => {
+                let processor = match self.panels.processor() {
+                    Some(processor) => processor.clone(),
+                    None => {
+                        tprint!(self.panels.terminal(), "No targets loaded.");
+                        return true;
+                    }
+                };
+
+                processor.set_dialect(decoder::Dialect { pseudo, ..processor.dialect() });
+                if let Some(listing) = self.panels.listing() {
+                    listing.refresh();
+                }
+
+                match pseudo {
+                    true => tprint!(self.panels.terminal(), "Showing pseudo-instructions."),
+                    false => tprint!(self.panels.terminal(), "Showing canonical instructions."),
+                }
+            }
+            Ok(Command::Ir(show)) => {
+                let processor = match self.panels.processor() {
+                    Some(processor) => processor.clone(),
+                    None => {
+                        tprint!(self.panels.terminal(), "No targets loaded.");
+                        return true;
+                    }
+                };
+
+                if !processor.can_lift() {
+                    tprint!(
+                        self.panels.terminal(),
+                        "Instructions can only be lifted for x86_64, aarch64 and riscv."
+                    );
+                    return true;
+                }
+
+                processor.set_show_ir(show);
+                if let Some(listing) = self.panels.listing() {
+                    listing.refresh();
+                }
+
+                match show {
+                    true => tprint!(self.panels.terminal(), "Showing lifted instructions."),
+                    false => tprint!(self.panels.terminal(), "Hiding lifted instructions."),
+                }
+            }
+            Ok(Command::Uarch(name)) => {
+                let processor = match self.panels.processor() {
+                    Some(processor) => processor.clone(),
+                    None => {
+                        tprint!(self.panels.terminal(), "No targets loaded.");
+                        return true;
+                    }
+                };
+
+                if !processor.has_uarchs() {
+                    tprint!(self.panels.terminal(), "Microarchitectures are only known for x86.");
+                    return true;
+                }
+
+                match name.as_deref() {
+                    None => {
+                        let current = processor.uarch().map_or("none", |uarch| uarch.name);
+                        let names: Vec<&str> = Uarch::names().collect();
+                        tprint!(
+                            self.panels.terminal(),
+                            "Checking against {current}, available are: {}.",
+                            names.join(", ")
+                        );
+                        return true;
+                    }
+                    Some("none") => {
+                        processor.set_uarch(None);
+                        tprint!(self.panels.terminal(), "Stopped checking instructions.");
+                    }
+                    Some(name) => match Uarch::by_name(name) {
+                        Some(uarch) => {
+                            processor.set_uarch(Some(uarch));
+                            tprint!(self.panels.terminal(), "Checking instructions against {name}.");
+                        }
+                        None => {
+                            tprint!(self.panels.terminal(), "Unknown microarchitecture '{name}'.");
+                            return true;
+                        }
+                    },
+                }
+
+                if let Some(listing) = self.panels.listing() {
+                    listing.refresh();
+                }
+            }
+            Ok(Command::Extensions) => {
+                let processor = match self.panels.processor() {
+                    Some(processor) => processor.clone(),
+                    None => {
+                        tprint!(self.panels.terminal(), "No targets loaded.");
+                        return true;
+                    }
+                };
+
+                let extensions = processor.extensions();
+                if extensions.is_empty() {
+                    tprint!(self.panels.terminal(), "No instruction set extensions are used.");
+                }
+
+                for ext in extensions {
+                    let unsupported = match ext.unsupported {
+                        0 => String::new(),
+                        count => format!(", {count} unsupported"),
+                    };
+
+                    tprint!(
+                        self.panels.terminal(),
+                        "{:<16} {} instructions{unsupported}, first at {:#x}",
+                        ext.name,
+                        ext.count,
+                        ext.first
+                    );
+                }
+            }
+            Ok(Command::Patch { addr, bytes }) => self.patch(addr, bytes),
+            Ok(Command::PatchAsm { addr, asm }) => {
+                let processor = match self.panels.processor() {
+                    Some(processor) => processor.clone(),
+                    None => {
+                        tprint!(self.panels.terminal(), "No targets loaded.");
+                        return true;
+                    }
+                };
+
+                match processor.assemble(addr, &asm) {
+                    Ok(bytes) => self.patch(addr, bytes),
+                    Err(err) => tprint!(self.panels.terminal(), "{err}"),
+                }
+            }
+            Ok(Command::Asm { addr, asm }) => {
+                let encoded = match self.panels.processor() {
+                    Some(processor) => processor.encode(addr, &asm).map_err(|err| err.to_string()),
+                    None => {
+                        let arch = if cfg!(target_arch = "aarch64") {
+                            assembler::Arch::Aarch64
+                        } else {
+                            assembler::Arch::X86_64
+                        };
+
+                        assembler::assemble(arch, addr as u64, &asm, &|_| None)
+                            .map_err(|err| format!("Failed to assemble: {err}."))
+                    }
+                };
+
+                match encoded {
+                    Ok(encoded) => {
+                        for inst in encoded {
+                            let bytes: Vec<String> =
+                                inst.bytes.iter().map(|b| format!("{b:02x}")).collect();
+
+                            tprint!(
+                                self.panels.terminal(),
+                                "{:#010x}  {:<24} {}",
+                                inst.addr,
+                                bytes.join(" "),
+                                inst.src
+                            );
+                        }
+                    }
+                    Err(err) => tprint!(self.panels.terminal(), "{err}"),
+                }
+            }
+            Ok(Command::Undo) => {
+                let mut patches = match self.panels.processor() {
+                    Some(processor) => processor.patches().to_vec(),
+                    None => {
+                        tprint!(self.panels.terminal(), "No targets loaded.");
+                        return true;
+                    }
+                };
+
+                match patches.pop() {
+                    Some(patch) => {
+                        tprint!(self.panels.terminal(), "Reverted patch at {:#x}.", patch.addr);
+                        self.offload_patching(patches, patch.addr);
+                    }
+                    None => tprint!(self.panels.terminal(), "No patches to revert."),
+                }
+            }
+            Ok(Command::Save(path)) => {
+                let processor = match self.panels.processor() {
+                    Some(processor) => processor.clone(),
+                    None => {
+                        tprint!(self.panels.terminal(), "No targets loaded.");
+                        return true;
+                    }
+                };
+
+                match processor.save_patched(&path) {
+                    Ok(()) => tprint!(
+                        self.panels.terminal(),
+                        "Wrote {} patches to {}.",
+                        processor.patches().len(),
+                        path.display()
+                    ),
+                    Err(err) => tprint!(self.panels.terminal(), "{err}"),
+                }
+            }
+            Ok(Command::Emulate { addr, steps }) => self.emulate(addr, steps),
+            Ok(Command::Up(count)) => self.select_frame(count as isize),
+            Ok(Command::Down(count)) => self.select_frame(-(count as isize)),
+            Ok(Command::Detach) => {
+                if self.with_debugger(|dbg| dbg.detach()).is_some() {
+                    self.panels.stop_debugging();
+                    tprint!(self.panels.terminal(), "Detached from target.");
+                }
+            }
             Ok(Command::Quit) => return false,
             Ok(Command::Clear) => {
                 log::LOGGER.write().unwrap().clear();
@@ -70,3 +529,359 @@ impl super::UI {
         true
     }
 }
+
+/// Resolve architecture independent names for registers, such as `$pc`.
+fn alias_register<'a>(dbg: &dyn Debugger, name: &'a str) -> &'a str {
+    match name {
+        "pc" => debugger::pc_name(dbg.arch()).unwrap_or(name),
+        _ => name,
+    }
+}
+
+/// Registers and memory of a stopped target, which expressions can read.
+struct Runtime<'a> {
+    dbg: &'a mut dyn Debugger,
+    /// Registers are only fetched once they're referenced.
+    registers: Option<Vec<debugger::Register>>,
+}
+
+impl<'a> Runtime<'a> {
+    fn new(dbg: &'a mut dyn Debugger) -> Self {
+        Self {
+            dbg,
+            registers: None,
+        }
+    }
+}
+
+impl commands::Target for Runtime<'_> {
+    fn register(&mut self, name: &str) -> Option<u64> {
+        if self.registers.is_none() {
+            self.registers = self.dbg.registers().ok();
+        }
+
+        let name = alias_register(self.dbg, name);
+        let registers = self.registers.as_ref()?;
+        registers.iter().find(|reg| reg.name == name).map(|reg| reg.as_u64())
+    }
+
+    fn read_ptr(&mut self, addr: usize) -> Option<u64> {
+        let size = self.dbg.arch().address_size().map_or(8, |size| size.bytes() as usize);
+        self.dbg.read_word(addr, size).ok()
+    }
+}
+
+impl super::UI {
+    /// Overwrite the bytes at `addr`, loading the binary again to show them.
+    fn patch(&mut self, addr: usize, bytes: Vec<u8>) {
+        let processor = match self.panels.processor() {
+            Some(processor) => processor.clone(),
+            None => {
+                tprint!(self.panels.terminal(), "No targets loaded.");
+                return;
+            }
+        };
+
+        let len = bytes.len();
+        match processor.patch(addr, bytes) {
+            Ok(patches) => {
+                tprint!(self.panels.terminal(), "Patched {len} bytes at {addr:#x}.");
+                self.offload_patching(patches, addr);
+            }
+            Err(err) => tprint!(self.panels.terminal(), "{err}"),
+        }
+    }
+
+    /// Emulate at most `steps` instructions starting at `addr`, printing where it stopped.
+    fn emulate(&mut self, addr: usize, steps: usize) {
+        let processor = match self.panels.processor() {
+            Some(processor) => processor.clone(),
+            None => {
+                tprint!(self.panels.terminal(), "No targets loaded.");
+                return;
+            }
+        };
+
+        let mut emulator = match processor.emulator(addr) {
+            Some(emulator) => emulator,
+            None => {
+                tprint!(self.panels.terminal(), "Emulation isn't supported for this architecture.");
+                return;
+            }
+        };
+
+        for (reg, val) in self.panels.emulated_registers().clone() {
+            if !emulator.set_register(&reg, val) {
+                tprint!(self.panels.terminal(), "Can't emulate with unknown register ${reg}.");
+                return;
+            }
+        }
+
+        for addr in self.panels.emulated_breakpoints().iter() {
+            emulator.add_breakpoint(*addr);
+        }
+
+        let stop = emulator.run(steps);
+        let terminal = self.panels.terminal();
+        tprint!(terminal, "Stopped by {stop} after {} instructions.", emulator.steps());
+
+        for row in emulator.registers().chunks(4) {
+            let row: Vec<String> =
+                row.iter().map(|(name, val)| format!("{name:>7} {val:#018x}")).collect();
+            tprint!(terminal, "{}", row.join(" "));
+        }
+
+        let flags: Vec<String> =
+            emulator.flags().iter().map(|(name, val)| format!("{name}={val}")).collect();
+        tprint!(terminal, "{:>7} {}", "flags", flags.join(" "));
+
+        // Only the most recent writes are shown, as loops can write a lot.
+        let writes = emulator.writes();
+        let shown = writes.len().saturating_sub(16);
+        if shown > 0 {
+            tprint!(terminal, "{shown} earlier memory writes not shown.");
+        }
+
+        for write in &writes[shown..] {
+            let width = write.len * 2;
+            tprint!(
+                terminal,
+                "{:#x}: [{:#x}] = {:#0w$x}",
+                write.pc,
+                write.addr,
+                write.value,
+                w = width + 2
+            );
+        }
+    }
+
+    /// Run `f` on the debugger, printing any errors.
+    fn with_debugger<T>(
+        &mut self,
+        f: impl FnOnce(&mut dyn Debugger) -> Result<T, debugger::Error>,
+    ) -> Option<T> {
+        let debugger = match self.panels.debugger() {
+            Some(debugger) => debugger,
+            None => {
+                tprint!(self.panels.terminal(), "Not debugging a target.");
+                return None;
+            }
+        };
+
+        match f(debugger.as_mut()) {
+            Ok(val) => Some(val),
+            Err(err) => {
+                if let debugger::Error::Exited = err {
+                    self.panels.stop_debugging();
+                }
+
+                tprint!(self.panels.terminal(), "{err}");
+                None
+            }
+        }
+    }
+
+    /// Evaluate an expression, reading registers and memory from the target if required.
+    fn eval_runtime_expr(&mut self, expr: &CompleteExpr) -> Option<usize> {
+        let processor = self.panels.processor().cloned();
+        let empty_index = debugvault::Index::default();
+        let index = processor.as_ref().map(|proc| &proc.index).unwrap_or(&empty_index);
+
+        let result = if expr.uses_target() {
+            let dbg = match self.panels.debugger() {
+                Some(dbg) => dbg,
+                None => {
+                    tprint!(self.panels.terminal(), "Not debugging a target.");
+                    return None;
+                }
+            };
+
+            expr.eval_with_target(index, &mut Runtime::new(dbg.as_mut()))
+        } else {
+            expr.eval(index)
+        };
+
+        match result {
+            Ok(val) => Some(val as usize),
+            Err(err) => {
+                tprint!(self.panels.terminal(), "{err}");
+                None
+            }
+        }
+    }
+
+    /// Whether the target stopped at a breakpoint whose condition doesn't hold.
+    ///
+    /// The target is resumed if so, errors in evaluating the condition stop the target.
+    fn skip_conditional_breakpoint(&mut self, reason: &StopReason) -> bool {
+        // Remote targets report breakpoints as trace traps.
+        if !matches!(reason, StopReason::Breakpoint | StopReason::Signal(5)) {
+            return false;
+        }
+
+        let pc = match self.panels.debugger() {
+            Some(dbg) => dbg.pc().ok(),
+            None => None,
+        };
+
+        let condition = match pc.and_then(|pc| self.panels.conditions().get(&pc)) {
+            Some(condition) => condition.clone(),
+            None => return false,
+        };
+
+        match self.eval_runtime_expr(&condition) {
+            Some(0) => self.with_debugger(|dbg| dbg.cont()).is_some(),
+            _ => false,
+        }
+    }
+
+    /// Check whether a running target has stopped.
+    pub(super) fn poll_debugger(&mut self) {
+        let result = match self.panels.debugger() {
+            Some(debugger) => debugger.poll(),
+            None => return,
+        };
+
+        match result {
+            Ok(Some(reason)) if self.skip_conditional_breakpoint(&reason) => {}
+            Ok(Some(reason)) => self.report_stop(reason),
+            Ok(None) => {}
+            Err(err) => {
+                self.panels.stop_debugging();
+                tprint!(self.panels.terminal(), "{err}");
+            }
+        }
+    }
+
+    fn report_stop(&mut self, reason: StopReason) {
+        if let StopReason::Exited(_) | StopReason::Killed(_) = reason {
+            self.panels.stop_debugging();
+            tprint!(self.panels.terminal(), "Target {reason}.");
+            return;
+        }
+
+        // The other processes may still be running.
+        if let StopReason::InferiorExited { pid, reason } = reason {
+            tprint!(self.panels.terminal(), "Process {pid} {reason}.");
+            return;
+        }
+
+        // An exec reads the new program's memory already.
+        let refresh = !matches!(reason, StopReason::Exec(_));
+
+        match reason {
+            StopReason::Signal(5) => {}
+            StopReason::Forked { parent, child } => {
+                self.panels.fork_inferior(parent, child);
+                tprint!(
+                    self.panels.terminal(),
+                    "Attaching after process {parent} fork to child process {child}."
+                );
+            }
+            StopReason::Exec(ref path) => {
+                let selected = self.panels.debugger().as_ref().and_then(|dbg| {
+                    let inferiors = dbg.inferiors();
+                    inferiors.into_iter().find(|inferior| inferior.selected)
+                });
+
+                if let Some(inferior) = selected {
+                    tprint!(
+                        self.panels.terminal(),
+                        "Process {} is executing new program: {}.",
+                        inferior.pid,
+                        path.display()
+                    );
+
+                    // The new program is mapped by now, so read it back like when attaching.
+                    self.offload_process_attach(inferior.pid);
+                }
+            }
+            reason => tprint!(self.panels.terminal(), "Stopped by {reason}."),
+        }
+
+        self.show_pc();
+
+        // The listing shows a copy of the process's memory, which changed while it ran.
+        let pc = self.panels.debugger().as_mut().and_then(|debugger| debugger.pc().ok());
+        if let Some(pc) = pc.filter(|_| refresh) {
+            self.offload_memory_refresh(pc);
+        }
+    }
+
+    /// Print the current instruction and jump to it in the listing.
+    pub(super) fn show_pc(&mut self) {
+        let pc = match self.with_debugger(|dbg| dbg.pc()) {
+            Some(pc) => pc,
+            None => return,
+        };
+
+        // The selected inferior might run a different binary than the one shown.
+        let inferiors = self.panels.debugger().as_ref().map(|dbg| dbg.inferiors());
+        if let Some(inferior) = inferiors.unwrap_or_default().iter().find(|inf| inf.selected) {
+            self.panels.show_inferior(inferior.pid);
+        }
+
+        let sym = self.panels.processor().and_then(|proc| {
+            let (sym, offset) = proc.index.get_sym_containing_addr(pc)?;
+            Some(match offset {
+                0 => format!(" <{}>", sym.as_str()),
+                offset => format!(" <{}+{offset}>", sym.as_str()),
+            })
+        });
+
+        tprint!(self.panels.terminal(), "{pc:#x}{}", sym.unwrap_or_default());
+
+        if let Some(listing) = self.panels.listing() {
+            listing.jump(pc);
+        }
+
+        self.panels.load_src(pc);
+        self.update_debug_state();
+    }
+
+    /// Refresh the register and call stack panels.
+    fn update_debug_state(&mut self) {
+        let cfi = self.panels.cfi();
+        let state = self.with_debugger(|dbg| {
+            let registers = dbg.registers()?;
+            let frames = debugger::unwind::unwind(dbg, cfi.as_deref())?;
+            Ok((registers, frames))
+        });
+
+        if let Some((registers, frames)) = state {
+            self.panels.update_debug_state(registers, frames);
+        }
+    }
+
+    /// Move `delta` frames up the call stack and jump to the frame's current instruction.
+    fn select_frame(&mut self, delta: isize) {
+        if self.panels.debugger().is_none() {
+            tprint!(self.panels.terminal(), "Not debugging a target.");
+            return;
+        }
+
+        let pc = match self.panels.select_frame(delta) {
+            Some(pc) => pc,
+            None if delta > 0 => {
+                tprint!(
+                    self.panels.terminal(),
+                    "Initial frame selected; you cannot go up."
+                );
+                return;
+            }
+            None => {
+                tprint!(
+                    self.panels.terminal(),
+                    "Bottom (innermost) frame selected; you cannot go down."
+                );
+                return;
+            }
+        };
+
+        if let Some(listing) = self.panels.listing() {
+            listing.jump(pc);
+        }
+
+        self.panels.load_src(pc);
+    }
+}
@@ -57,6 +57,12 @@ pub enum UIEvent {
     BinaryRequested(std::path::PathBuf),
     BinaryFailed(processor::Error),
     BinaryLoaded(processor::Processor),
+    /// The binary was loaded again after patching the bytes at an address.
+    BinaryPatched(processor::Processor, usize),
+    /// The memory of a process was read again after it stopped at an address.
+    MemoryRefreshed(processor::Processor, usize),
+    DebuggerConnected(Box<dyn debugger::Debugger>),
+    DebuggerFailed(debugger::Error),
 }
 
 #[derive(Clone)]
@@ -136,7 +142,10 @@ impl UI {
 
     pub fn process_args(&mut self) {
         if let Some(path) = commands::ARGS.path.as_ref().cloned() {
-            self.offload_binary_processing(path);
+            match commands::ARGS.raw.as_ref().cloned() {
+                Some((arch, base)) => self.offload_raw_processing(path, arch, base),
+                None => self.offload_binary_processing(path),
+            }
         }
     }
 
@@ -157,6 +166,151 @@ impl UI {
         });
     }
 
+    fn offload_raw_processing(&mut self, path: std::path::PathBuf, arch: String, base: usize) {
+        // don't load multiple binaries at a time
+        if self.panels.is_loading() {
+            return;
+        }
+
+        self.panels.start_loading();
+        let ui_queue = self.ui_queue.clone();
+
+        std::thread::spawn(move || {
+            match processor::Processor::parse_raw(&path, &arch, base) {
+                Ok(diss) => ui_queue.push(UIEvent::BinaryLoaded(diss)),
+                Err(err) => ui_queue.push(UIEvent::BinaryFailed(err)),
+            };
+        });
+    }
+
+    /// Load the binary again with `patches` applied, jumping back to `addr` once it's loaded.
+    fn offload_patching(&mut self, patches: Vec<processor::Patch>, addr: usize) {
+        // don't load multiple binaries at a time
+        if self.panels.is_loading() {
+            return;
+        }
+
+        let processor = match self.panels.processor() {
+            Some(processor) => Arc::clone(processor),
+            None => return,
+        };
+
+        self.panels.start_loading();
+        let ui_queue = self.ui_queue.clone();
+
+        std::thread::spawn(move || {
+            match processor.patched(patches) {
+                Ok(diss) => ui_queue.push(UIEvent::BinaryPatched(diss, addr)),
+                Err(err) => ui_queue.push(UIEvent::BinaryFailed(err)),
+            };
+        });
+    }
+
+    /// Read the memory of the process that's shown again once it stopped at `pc`, the listing
+    /// otherwise shows its memory from when we attached.
+    #[allow(unused_variables)]
+    fn offload_memory_refresh(&mut self, pc: usize) {
+        // don't load multiple binaries at a time
+        if self.panels.is_loading() {
+            return;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let processor = match self.panels.processor() {
+                Some(processor) if processor.pid().is_some() => Arc::clone(processor),
+                _ => return,
+            };
+
+            // the traps of our breakpoints are in the process's memory
+            let breakpoints = match self.panels.debugger() {
+                Some(debugger) => debugger.breakpoints(),
+                None => return,
+            };
+
+            self.panels.start_loading();
+            let ui_queue = self.ui_queue.clone();
+
+            std::thread::spawn(move || {
+                match processor.refreshed(&breakpoints) {
+                    Ok(diss) => ui_queue.push(UIEvent::MemoryRefreshed(diss, pc)),
+                    Err(err) => ui_queue.push(UIEvent::BinaryFailed(err)),
+                };
+            });
+        }
+    }
+
+    #[allow(unused_variables)]
+    fn offload_process_attach(&mut self, pid: i32) {
+        // don't load multiple binaries at a time
+        if self.panels.is_loading() {
+            return;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.panels.start_loading();
+            let ui_queue = self.ui_queue.clone();
+
+            std::thread::spawn(move || {
+                match processor::Processor::attach(pid) {
+                    Ok(diss) => ui_queue.push(UIEvent::BinaryLoaded(diss)),
+                    Err(err) => ui_queue.push(UIEvent::BinaryFailed(err)),
+                };
+            });
+        }
+    }
+
+    fn offload_debugger_connect(&mut self, addr: String) {
+        let arch = self.panels.processor().map(|proc| proc.arch());
+        let endianness = self.panels.processor().map(|proc| proc.endianness());
+        let ui_queue = self.ui_queue.clone();
+
+        std::thread::spawn(move || {
+            match debugger::gdb::Client::connect(&addr, arch, endianness) {
+                Ok(client) => ui_queue.push(UIEvent::DebuggerConnected(Box::new(client))),
+                Err(err) => ui_queue.push(UIEvent::DebuggerFailed(err)),
+            };
+        });
+    }
+
+    /// Whether `pid` is one of the processes being debugged.
+    fn is_inferior(&mut self, pid: i32) -> bool {
+        let debugger = self.panels.debugger().as_ref();
+        let inferiors = debugger.map(|dbg| dbg.inferiors()).unwrap_or_default();
+        inferiors.iter().any(|inferior| inferior.pid == pid)
+    }
+
+    /// Debug a process we attached to, all ptrace requests have to come from this thread.
+    #[allow(unused_variables)]
+    fn trace_process(&mut self, pid: i32) {
+        #[cfg(all(
+            target_os = "linux",
+            any(target_arch = "x86_64", target_arch = "aarch64")
+        ))]
+        {
+            match debugger::ptrace::Session::attach(pid) {
+                Ok(session) => {
+                    tprint!(self.panels.terminal(), "Tracing process {pid}.");
+                    self.start_debugging(Box::new(session));
+                    self.panels.add_inferior(pid);
+                }
+                Err(err) => {
+                    tprint!(self.panels.terminal(), "Failed to trace process {pid}: {err}")
+                }
+            }
+        }
+    }
+
+    fn start_debugging(&mut self, debugger: Box<dyn debugger::Debugger>) {
+        self.panels.stop_debugging();
+        *self.panels.debugger() = Some(debugger);
+        self.panels.goto_window(panels::REGISTERS);
+        self.panels.goto_window(panels::CALL_STACK);
+        self.panels.goto_window(panels::DISASSEMBLY);
+        self.show_pc();
+    }
+
     fn handle_ui_events(&mut self) {
         #[cfg(target_os = "macos")]
         while let Ok(event) = self.arch.menu_channel.try_recv() {
@@ -178,6 +332,18 @@ impl UI {
                     self.panels.goto_window(panels::LOGGING);
                     self.arch.bar.set_checked(panels::LOGGING);
                 }
+                panels::REGISTERS => {
+                    self.panels.goto_window(panels::REGISTERS);
+                    self.arch.bar.set_checked(panels::REGISTERS);
+                }
+                panels::CALL_STACK => {
+                    self.panels.goto_window(panels::CALL_STACK);
+                    self.arch.bar.set_checked(panels::CALL_STACK);
+                }
+                panels::DECOMPILE => {
+                    self.panels.goto_window(panels::DECOMPILE);
+                    self.arch.bar.set_checked(panels::DECOMPILE);
+                }
                 _ => { dbg!(event.id.0.as_str()); }
             }
         }
@@ -195,11 +361,55 @@ impl UI {
                     #[cfg(target_os = "macos")]
                     self.arch.bar.set_path(&disassembly.path);
 
+                    self.panels.stop_loading();
+                    match disassembly.pid() {
+                        // The binary of an inferior that exec'd was reloaded.
+                        Some(pid) if self.is_inferior(pid) => {
+                            self.panels.load_inferior_binary(pid, disassembly);
+                        }
+                        Some(pid) => {
+                            self.panels.load_binary(disassembly);
+                            self.trace_process(pid);
+                        }
+                        None => self.panels.load_binary(disassembly),
+                    }
+                }
+                UIEvent::BinaryPatched(disassembly, addr) => {
                     self.panels.stop_loading();
                     self.panels.load_binary(disassembly);
+
+                    if let Some(listing) = self.panels.listing() {
+                        listing.jump(addr);
+                    }
+                }
+                UIEvent::MemoryRefreshed(disassembly, pc) => {
+                    self.panels.stop_loading();
+                    if let Some(pid) = disassembly.pid() {
+                        self.panels.load_inferior_binary(pid, disassembly);
+                    }
+
+                    if let Some(listing) = self.panels.listing() {
+                        listing.jump(pc);
+                    }
+
+                    // the target could've stopped somewhere else while its memory was read
+                    let debugger = self.panels.debugger().as_mut();
+                    match debugger.and_then(|debugger| debugger.pc().ok()) {
+                        Some(current) if current != pc => self.offload_memory_refresh(current),
+                        _ => {}
+                    }
+                }
+                UIEvent::DebuggerConnected(debugger) => {
+                    tprint!(self.panels.terminal(), "Connected to remote target.");
+                    self.start_debugging(debugger);
+                }
+                UIEvent::DebuggerFailed(err) => {
+                    tprint!(self.panels.terminal(), "Failed to connect: {err}");
                 }
             }
         }
+
+        self.poll_debugger();
     }
 
     pub fn run(mut self) {
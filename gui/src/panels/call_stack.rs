@@ -0,0 +1,110 @@
+use crate::common::*;
+
+use debugger::unwind::Frame;
+use egui::text::LayoutJob;
+use processor::Processor;
+use std::sync::Arc;
+use tokenizing::{colors, Token};
+
+pub struct CallStack {
+    processor: Option<Arc<Processor>>,
+    frames: Vec<Frame>,
+    /// Frame selected by `up` and `down`.
+    selected: usize,
+    lines: LayoutJob,
+}
+
+impl CallStack {
+    pub fn new(processor: Option<Arc<Processor>>) -> Self {
+        Self {
+            processor,
+            frames: Vec::new(),
+            selected: 0,
+            lines: LayoutJob::default(),
+        }
+    }
+
+    pub fn update(&mut self, frames: Vec<Frame>) {
+        self.frames = frames;
+        self.selected = 0;
+        self.lines = tokens_to_layoutjob(self.tokenize());
+    }
+
+    /// Move the selected frame towards the caller (positive) or callee (negative),
+    /// returning the newly selected frame.
+    pub fn select(&mut self, delta: isize) -> Option<&Frame> {
+        let selected = self.selected.checked_add_signed(delta)?;
+        if selected >= self.frames.len() {
+            return None;
+        }
+
+        self.selected = selected;
+        self.lines = tokens_to_layoutjob(self.tokenize());
+        self.frames.get(selected)
+    }
+
+    fn tokenize(&self) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let index = self.processor.as_ref().map(|proc| &proc.index);
+
+        for (idx, frame) in self.frames.iter().enumerate() {
+            let marker = if idx == self.selected { "> " } else { "  " };
+            tokens.push(Token::from_str(marker, colors::WHITE));
+            tokens.push(Token::from_string(format!("#{idx:<3}"), colors::GRAY99));
+            tokens.push(Token::from_string(
+                format!("{:#014x}", frame.pc),
+                colors::WHITE,
+            ));
+
+            // Return addresses point past the call instruction.
+            let addr = if idx == 0 {
+                frame.pc
+            } else {
+                frame.pc.saturating_sub(1)
+            };
+
+            if let Some((sym, offset)) = index.and_then(|index| index.get_sym_containing_addr(addr))
+            {
+                tokens.push(Token::from_str(" in ", colors::GRAY99));
+                if let Some(module) = sym.module() {
+                    tokens.push(Token::from_string(module.to_string(), colors::MAGENTA));
+                    tokens.push(Token::from_str("!", colors::GRAY60));
+                }
+
+                tokens.extend(sym.name().iter().cloned());
+
+                let offset = frame.pc - (addr - offset);
+                if offset != 0 {
+                    tokens.push(Token::from_string(format!("+{offset}"), colors::WHITE));
+                }
+            }
+
+            if let Some(file) = index.and_then(|index| index.get_file_containing_addr(addr)) {
+                tokens.push(Token::from_str(" at ", colors::GRAY99));
+                tokens.push(Token::from_string(
+                    format!("{}:{}", file.path.display(), file.line),
+                    colors::GREEN,
+                ));
+            }
+
+            tokens.push(Token::from_str("\n", colors::WHITE));
+        }
+
+        tokens
+    }
+}
+
+impl Display for CallStack {
+    fn show(&mut self, ui: &mut egui::Ui) {
+        let area = egui::ScrollArea::both().auto_shrink([false, false]).drag_to_scroll(false);
+
+        area.show(ui, |ui| {
+            if self.frames.is_empty() {
+                ui.label("Target isn't stopped.");
+                return;
+            }
+
+            ui.label(self.lines.clone());
+        });
+    }
+}
@@ -0,0 +1,140 @@
+use crate::common::*;
+
+use commands::CONFIG;
+use processor::{Decompiled, Processor};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use tokenizing::{colors, Token};
+
+pub struct Decompile {
+    processor: Arc<Processor>,
+    decompiled: Option<Decompiled>,
+    /// Function being decompiled on a separate thread.
+    pending: Option<Receiver<Option<Decompiled>>>,
+    /// Address of the instruction the listing and source are at.
+    cursor: usize,
+    /// Whether the cursor moved outside of the decompiled function.
+    stale: bool,
+    scroll: Option<usize>,
+    /// Address of the line that was clicked on.
+    clicked: Option<usize>,
+}
+
+impl Decompile {
+    pub fn new(processor: Arc<Processor>) -> Self {
+        Self {
+            processor,
+            decompiled: None,
+            pending: None,
+            cursor: 0,
+            stale: false,
+            scroll: None,
+            clicked: None,
+        }
+    }
+
+    /// Move the cursor to `addr`, decompiling the function containing it the next time
+    /// the panel is shown.
+    pub fn goto(&mut self, addr: usize) {
+        self.cursor = addr;
+        match self.decompiled {
+            Some(ref decompiled)
+                if self.pending.is_none() && (decompiled.start..decompiled.end).contains(&addr) =>
+            {
+                self.scroll = decompiled.line_by_addr(addr);
+            }
+            _ => self.stale = true,
+        }
+    }
+
+    /// Address of the line that was clicked on since the last call.
+    pub fn take_clicked(&mut self) -> Option<usize> {
+        self.clicked.take()
+    }
+
+    /// Decompile the function at the cursor on a separate thread, as large functions would
+    /// block the GUI.
+    fn start_decompiling(&mut self) {
+        let processor = Arc::clone(&self.processor);
+        let cursor = self.cursor;
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let _ = sender.send(processor.decompile(cursor));
+        });
+
+        // a function that's still being decompiled is no longer of interest
+        self.pending = Some(receiver);
+    }
+}
+
+impl Display for Decompile {
+    fn show(&mut self, ui: &mut egui::Ui) {
+        if self.stale {
+            self.stale = false;
+            self.start_decompiling();
+        }
+
+        if let Some(ref pending) = self.pending {
+            match pending.try_recv() {
+                Ok(decompiled) => {
+                    self.decompiled = decompiled;
+                    self.scroll =
+                        self.decompiled.as_ref().and_then(|d| d.line_by_addr(self.cursor));
+                    self.pending = None;
+                }
+                Err(TryRecvError::Empty) => {
+                    ui.label("Decompiling the function at the cursor.");
+                    return;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    self.decompiled = None;
+                    self.pending = None;
+                }
+            }
+        }
+
+        let decompiled = match self.decompiled {
+            Some(ref decompiled) => decompiled,
+            None if !self.processor.can_lift() => {
+                ui.label("Decompiling isn't supported for this architecture.");
+                return;
+            }
+            None => {
+                ui.label("No function at the cursor.");
+                return;
+            }
+        };
+
+        let mut area = egui::ScrollArea::both().auto_shrink(false).drag_to_scroll(false);
+
+        if let Some(scroll) = self.scroll.take() {
+            let row_height = FONT.size;
+            let spacing_y = ui.spacing().item_spacing.y;
+            let y = scroll as f32 * (row_height + spacing_y);
+            area = area.vertical_scroll_offset(y)
+        }
+
+        let selected = decompiled.line_by_addr(self.cursor);
+        area.show_rows(ui, FONT.size, decompiled.lines.len(), |ui, row_range| {
+            for idx in row_range {
+                let line = &decompiled.lines[idx];
+                let mut tokens = vec![Token::from_string("    ".repeat(line.depth), colors::WHITE)];
+                tokens.extend(line.tokens.iter().cloned());
+
+                let mut job = tokens_to_layoutjob(tokens);
+                if selected == Some(idx) {
+                    for section in &mut job.sections {
+                        section.format.background = CONFIG.colors.highlight;
+                    }
+                }
+
+                let label = egui::Label::new(job).sense(egui::Sense::click());
+                if ui.add(label).clicked() {
+                    self.cursor = line.addr;
+                    self.clicked = Some(line.addr);
+                }
+            }
+        });
+    }
+}
@@ -22,10 +22,15 @@ impl Listing {
             // Compute boundaries on a separate thread to prevent GUI from blocking.
             let processor = Arc::clone(&processor);
             let boundaries = Arc::clone(&boundaries);
+            let (locked, wait) = std::sync::mpsc::channel();
             std::thread::spawn(move || {
                 let mut locked_boundaries = boundaries.write();
+                let _ = locked.send(());
                 *locked_boundaries = processor.compute_block_boundaries();
             });
+
+            // Jumps right after loading have to wait for the boundaries instead of seeing none.
+            let _ = wait.recv();
         };
 
         let reset_position = Arc::new(AtomicUsize::new(0));
@@ -143,6 +148,11 @@ impl Listing {
 
         false
     }
+
+    /// Tokenize the shown blocks again, e.g. after the assembly dialect changed.
+    pub fn refresh(&mut self) {
+        self.jump(self.current_addr);
+    }
 }
 
 fn draw_horizontal_line(ui: &mut egui::Ui) {
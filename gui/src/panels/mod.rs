@@ -1,5 +1,8 @@
+mod call_stack;
+mod decompile;
 mod functions;
 mod listing;
+mod registers;
 mod source_code;
 
 use crate::common::*;
@@ -8,7 +11,7 @@ use crate::widgets::{Donut, Terminal};
 use egui_tiles::{Container, SimplificationOptions, Tile, TileId, Tiles, Tree, UiResponse};
 use processor::Processor;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
 pub type Identifier = &'static str;
@@ -17,11 +20,17 @@ pub const SOURCE: Identifier = crate::icon!(EMBED2, " Source");
 pub const DISASSEMBLY: Identifier = crate::icon!(PARAGRAPH_LEFT, " Disassembly");
 pub const FUNCTIONS: Identifier = crate::icon!(LIGATURE, " Functions");
 pub const LOGGING: Identifier = crate::icon!(TERMINAL, " Logs");
+pub const REGISTERS: Identifier = crate::icon!(TABLE, " Registers");
+pub const CALL_STACK: Identifier = crate::icon!(STACK, " Call Stack");
+pub const DECOMPILE: Identifier = crate::icon!(TREE, " Decompile");
 
 enum PanelKind {
     Disassembly(listing::Listing),
     Functions(functions::Functions),
     Source(source_code::Source),
+    Registers(registers::Registers),
+    CallStack(call_stack::CallStack),
+    Decompile(decompile::Decompile),
     Logging,
 }
 
@@ -29,6 +38,19 @@ pub struct Tabs {
     mapping: BTreeMap<Identifier, PanelKind>,
     terminal: Terminal,
     processor: Option<Arc<Processor>>,
+    /// Binary each inferior is running, as they can exec different programs.
+    inferior_processors: BTreeMap<i32, Arc<Processor>>,
+    debugger: Option<Box<dyn debugger::Debugger>>,
+    /// Conditions of breakpoints, the target only stops if they evaluate to non-zero.
+    conditions: BTreeMap<usize, commands::CompleteExpr>,
+    /// Watchpoints set on the target, as the address, length and kind of access.
+    watchpoints: Vec<(usize, usize, debugger::WatchKind)>,
+    /// Registers `emulate` starts with, set while not debugging a target.
+    emulated_registers: BTreeMap<String, u64>,
+    /// Breakpoints `emulate` stops at, set while not debugging a target.
+    emulated_breakpoints: BTreeSet<usize>,
+    /// Unwinding information of the loaded binary.
+    cfi: Option<Arc<debugger::unwind::Cfi>>,
     donut: Donut,
 }
 
@@ -42,6 +64,13 @@ impl Tabs {
             },
             terminal: Terminal::new(),
             processor: None,
+            inferior_processors: BTreeMap::new(),
+            debugger: None,
+            conditions: BTreeMap::new(),
+            watchpoints: Vec::new(),
+            emulated_registers: BTreeMap::new(),
+            emulated_breakpoints: BTreeSet::new(),
+            cfi: None,
             donut: Donut::new(false),
         }
     }
@@ -107,6 +136,9 @@ impl egui_tiles::Behavior<Identifier> for Tabs {
                 Some(PanelKind::Disassembly(disassembly)) => disassembly.show(ui),
                 Some(PanelKind::Functions(functions)) => functions.show(ui),
                 Some(PanelKind::Source(src)) => src.show(ui),
+                Some(PanelKind::Registers(registers)) => registers.show(ui),
+                Some(PanelKind::CallStack(call_stack)) => call_stack.show(ui),
+                Some(PanelKind::Decompile(decompile)) => decompile.show(ui),
                 Some(PanelKind::Logging) => {
                     let area = egui::ScrollArea::vertical()
                         .auto_shrink([false, false])
@@ -126,6 +158,22 @@ impl egui_tiles::Behavior<Identifier> for Tabs {
     }
 }
 
+/// Collect the sections describing how to unwind the binary's frames.
+fn cfi(processor: &Processor) -> debugger::unwind::Cfi {
+    let section = |names: &[&str]| names.iter().find_map(|name| processor.section_by_name(name));
+    let eh_frame = section(&[".eh_frame", "__eh_frame"]);
+    let debug_frame = section(&[".debug_frame", "__debug_frame"]);
+    let text = section(&[".text", "__text"]);
+
+    debugger::unwind::Cfi::new(
+        processor.arch(),
+        processor.endianness(),
+        eh_frame.map(|s| (s.bytes(), s.start)),
+        debug_frame.map(|s| s.bytes()),
+        text.map_or(0, |s| s.start),
+    )
+}
+
 pub struct Panels {
     pub tree: egui_tiles::Tree<Identifier>,
     pub panes: Tabs,
@@ -166,6 +214,79 @@ impl Panels {
         self.panes.processor.as_ref()
     }
 
+    #[inline]
+    pub fn debugger(&mut self) -> &mut Option<Box<dyn debugger::Debugger>> {
+        &mut self.panes.debugger
+    }
+
+    #[inline]
+    pub fn conditions(&mut self) -> &mut BTreeMap<usize, commands::CompleteExpr> {
+        &mut self.panes.conditions
+    }
+
+    #[inline]
+    pub fn watchpoints(&mut self) -> &mut Vec<(usize, usize, debugger::WatchKind)> {
+        &mut self.panes.watchpoints
+    }
+
+    #[inline]
+    pub fn emulated_registers(&mut self) -> &mut BTreeMap<String, u64> {
+        &mut self.panes.emulated_registers
+    }
+
+    #[inline]
+    pub fn emulated_breakpoints(&mut self) -> &mut BTreeSet<usize> {
+        &mut self.panes.emulated_breakpoints
+    }
+
+    /// Drop the debugger along with any state kept about the target.
+    pub fn stop_debugging(&mut self) {
+        self.panes.debugger = None;
+        self.panes.inferior_processors.clear();
+        self.panes.conditions.clear();
+        self.panes.watchpoints.clear();
+    }
+
+    /// Show the state of a stopped debug target.
+    pub fn update_debug_state(
+        &mut self,
+        registers: Vec<debugger::Register>,
+        frames: Vec<debugger::unwind::Frame>,
+    ) {
+        let processor = self.panes.processor.clone();
+
+        match self.panes.mapping.get_mut(REGISTERS) {
+            Some(PanelKind::Registers(panel)) => panel.update(registers),
+            _ => {
+                let mut panel = registers::Registers::new();
+                panel.update(registers);
+                self.panes.mapping.insert(REGISTERS, PanelKind::Registers(panel));
+            }
+        }
+
+        match self.panes.mapping.get_mut(CALL_STACK) {
+            Some(PanelKind::CallStack(panel)) => panel.update(frames),
+            _ => {
+                let mut panel = call_stack::CallStack::new(processor);
+                panel.update(frames);
+                self.panes.mapping.insert(CALL_STACK, PanelKind::CallStack(panel));
+            }
+        }
+    }
+
+    /// Select a frame relative to the currently selected frame, returning its address.
+    pub fn select_frame(&mut self, delta: isize) -> Option<usize> {
+        match self.panes.mapping.get_mut(CALL_STACK) {
+            Some(PanelKind::CallStack(panel)) => panel.select(delta).map(|frame| frame.pc),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn cfi(&self) -> Option<Arc<debugger::unwind::Cfi>> {
+        self.panes.cfi.clone()
+    }
+
     #[inline]
     pub fn terminal(&mut self) -> &mut Terminal {
         &mut self.panes.terminal
@@ -185,8 +306,12 @@ impl Panels {
         self.loading = false;
     }
 
-    /// Jump to both the source and the assembly.
+    /// Jump to both the source and the decompiled function.
     pub fn load_src(&mut self, addr: usize) {
+        if let Some(PanelKind::Decompile(decompile)) = self.panes.mapping.get_mut(DECOMPILE) {
+            decompile.goto(addr);
+        }
+
         let file_attr = match self.processor().and_then(|proc| proc.index.get_file_by_addr(addr)) {
             Some(file_attr) => file_attr,
             None => return,
@@ -198,9 +323,66 @@ impl Panels {
         }
     }
 
+    /// Move the listing and source to the line clicked on in the decompiled function.
+    fn jump_to_decompiled(&mut self) {
+        let addr = match self.panes.mapping.get_mut(DECOMPILE) {
+            Some(PanelKind::Decompile(decompile)) => decompile.take_clicked(),
+            _ => None,
+        };
+
+        if let Some(addr) = addr {
+            if let Some(listing) = self.listing() {
+                listing.jump(addr);
+            }
+
+            self.load_src(addr);
+        }
+    }
+
     pub fn load_binary(&mut self, processor: Processor) {
+        self.show_binary(Arc::new(processor));
+    }
+
+    /// Remember that inferior `pid` runs the binary that's shown.
+    pub fn add_inferior(&mut self, pid: i32) {
+        if let Some(ref processor) = self.panes.processor {
+            self.panes.inferior_processors.insert(pid, Arc::clone(processor));
+        }
+    }
+
+    /// Remember that a forked child runs the same binary as its parent.
+    pub fn fork_inferior(&mut self, parent: i32, child: i32) {
+        if let Some(processor) = self.panes.inferior_processors.get(&parent).cloned() {
+            self.panes.inferior_processors.insert(child, processor);
+        }
+    }
+
+    /// Load the binary an inferior runs after an exec, only showing it if the inferior is still
+    /// selected.
+    pub fn load_inferior_binary(&mut self, pid: i32, processor: Processor) {
         let processor = Arc::new(processor);
+        self.panes.inferior_processors.insert(pid, Arc::clone(&processor));
+
+        let inferiors = self.panes.debugger.as_ref().map(|dbg| dbg.inferiors());
+        if inferiors.unwrap_or_default().iter().any(|inf| inf.pid == pid && inf.selected) {
+            self.show_binary(processor);
+        }
+    }
 
+    /// Show the binary inferior `pid` runs, if it isn't shown already.
+    pub fn show_inferior(&mut self, pid: i32) {
+        let processor = match self.panes.inferior_processors.get(&pid) {
+            Some(processor) => Arc::clone(processor),
+            None => return,
+        };
+
+        let shown = self.panes.processor.as_ref();
+        if !shown.is_some_and(|shown| Arc::ptr_eq(shown, &processor)) {
+            self.show_binary(processor);
+        }
+    }
+
+    fn show_binary(&mut self, processor: Arc<Processor>) {
         self.panes.mapping.insert(
             DISASSEMBLY,
             PanelKind::Disassembly(listing::Listing::new(processor.clone())),
@@ -211,6 +393,12 @@ impl Panels {
             PanelKind::Functions(functions::Functions::new(processor.clone())),
         );
 
+        self.panes.mapping.insert(
+            DECOMPILE,
+            PanelKind::Decompile(decompile::Decompile::new(processor.clone())),
+        );
+
+        self.panes.cfi = Some(Arc::new(cfi(&processor)));
         self.panes.processor = Some(processor);
     }
 
@@ -328,6 +516,21 @@ impl Panels {
                     self.goto_window(LOGGING);
                     ui.close_menu();
                 }
+
+                if ui.button(REGISTERS).clicked() {
+                    self.goto_window(REGISTERS);
+                    ui.close_menu();
+                }
+
+                if ui.button(CALL_STACK).clicked() {
+                    self.goto_window(CALL_STACK);
+                    ui.close_menu();
+                }
+
+                if ui.button(DECOMPILE).clicked() {
+                    self.goto_window(DECOMPILE);
+                    ui.close_menu();
+                }
             });
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Max), |ui| {
@@ -440,6 +643,7 @@ impl Panels {
                 );
             } else {
                 self.tree.ui(&mut self.panes, ui);
+                self.jump_to_decompiled();
             }
 
             // give focus to terminal if any valid keyboard input happened
@@ -0,0 +1,134 @@
+use crate::common::*;
+
+use debugger::{Register, RegisterGroup};
+use egui::text::LayoutJob;
+use tokenizing::{colors, Token};
+
+const GROUPS: [(RegisterGroup, &str); 5] = [
+    (RegisterGroup::General, "General"),
+    (RegisterGroup::Flags, "Flags"),
+    (RegisterGroup::Vector, "Vector"),
+    (RegisterGroup::Float, "Float"),
+    (RegisterGroup::System, "System"),
+];
+
+pub struct Registers {
+    registers: Vec<Register>,
+    /// Whether each register changed since the target last stopped.
+    changed: Vec<bool>,
+    lines: LayoutJob,
+}
+
+impl Registers {
+    pub fn new() -> Self {
+        Self {
+            registers: Vec::new(),
+            changed: Vec::new(),
+            lines: LayoutJob::default(),
+        }
+    }
+
+    pub fn update(&mut self, registers: Vec<Register>) {
+        self.changed = registers
+            .iter()
+            .map(|reg| {
+                self.registers
+                    .iter()
+                    .find(|prev| prev.name == reg.name)
+                    .is_some_and(|prev| prev.value != reg.value)
+            })
+            .collect();
+
+        self.registers = registers;
+        self.lines = tokens_to_layoutjob(self.tokenize());
+    }
+
+    fn tokenize(&self) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let name_width = self.registers.iter().map(|reg| reg.name.len()).max().unwrap_or(0);
+
+        for (group, title) in GROUPS {
+            let mut registers = self
+                .registers
+                .iter()
+                .zip(self.changed.iter())
+                .filter(|(reg, _)| reg.group == group)
+                .peekable();
+
+            if registers.peek().is_none() {
+                continue;
+            }
+
+            if !tokens.is_empty() {
+                tokens.push(Token::from_str("\n", colors::WHITE));
+            }
+
+            tokens.push(Token::from_string(format!("{title}\n"), colors::GRAY99));
+
+            for (reg, &changed) in registers {
+                tokens.push(Token::from_string(
+                    format!("{:name_width$}  ", reg.name),
+                    colors::BLUE,
+                ));
+
+                let hex: String =
+                    reg.value.iter().rev().map(|byte| format!("{byte:02x}")).collect();
+                let color = if changed { colors::RED } else { colors::WHITE };
+                tokens.push(Token::from_string(format!("0x{hex}"), color));
+
+                if group == RegisterGroup::Flags {
+                    let flags = flag_names(&reg.name, reg.as_u64());
+                    if !flags.is_empty() {
+                        tokens.push(Token::from_string(
+                            format!("  [ {} ]", flags.join(" ")),
+                            colors::GRAY99,
+                        ));
+                    }
+                }
+
+                tokens.push(Token::from_str("\n", colors::WHITE));
+            }
+        }
+
+        tokens
+    }
+}
+
+/// Names of the flags set in a flags register.
+fn flag_names(name: &str, value: u64) -> Vec<&'static str> {
+    let bits: &[(u32, &str)] = match name {
+        "eflags" | "rflags" => &[
+            (0, "CF"),
+            (2, "PF"),
+            (4, "AF"),
+            (6, "ZF"),
+            (7, "SF"),
+            (8, "TF"),
+            (9, "IF"),
+            (10, "DF"),
+            (11, "OF"),
+        ],
+        "cpsr" | "pstate" | "fpscr" => &[(31, "N"), (30, "Z"), (29, "C"), (28, "V")],
+        _ => &[],
+    };
+
+    bits.iter()
+        .filter(|(bit, _)| value & (1 << bit) != 0)
+        .map(|&(_, name)| name)
+        .collect()
+}
+
+impl Display for Registers {
+    fn show(&mut self, ui: &mut egui::Ui) {
+        let area = egui::ScrollArea::both().auto_shrink([false, false]).drag_to_scroll(false);
+
+        area.show(ui, |ui| {
+            if self.registers.is_empty() {
+                ui.label("Target isn't stopped.");
+                return;
+            }
+
+            ui.label(self.lines.clone());
+        });
+    }
+}
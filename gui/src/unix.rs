@@ -104,6 +104,27 @@ mod macos {
                 false,
                 None,
             ));
+            windows.push(CheckMenuItem::with_id(
+                panels::REGISTERS,
+                "Registers",
+                true,
+                false,
+                None,
+            ));
+            windows.push(CheckMenuItem::with_id(
+                panels::CALL_STACK,
+                "Call Stack",
+                true,
+                false,
+                None,
+            ));
+            windows.push(CheckMenuItem::with_id(
+                panels::DECOMPILE,
+                "Decompile",
+                true,
+                false,
+                None,
+            ));
 
             for item in windows.iter() {
                 window_m.append(item)?;
@@ -1,6 +1,7 @@
 use crate::Processor;
-use binformat::elf::{Elf32Dyn, Elf32Sym, Elf64Dyn, Elf64Sym};
+use binformat::elf::{Elf32Dyn, Elf32Sym, Elf64Dyn, Elf64Sym, RiscvAttributes};
 use binformat::pe::ExceptionDirectoryEntry;
+use binformat::wasm::Entries;
 use binformat::ToData;
 use commands::CONFIG;
 use debugvault::Symbol;
@@ -26,10 +27,17 @@ pub enum BlockContent {
     Instruction {
         inst: Vec<Token>,
         bytes: String,
+        /// Extensions required by the instruction, if the selected microarchitecture lacks them.
+        unsupported: Option<Vec<&'static str>>,
+        /// Whether any of the instruction's bytes were patched.
+        patched: bool,
+        /// Statements the instruction lifts to, empty unless they're shown.
+        ir: Vec<decoder::ir::Stmt>,
     },
     Error {
         err: decoder::ErrorKind,
         bytes: String,
+        patched: bool,
     },
     CString {
         bytes: Vec<u8>,
@@ -49,6 +57,8 @@ pub enum BlockContent {
     },
     Bytes {
         bytes: Vec<u8>,
+        /// Which of the lines of bytes were patched.
+        patched: Vec<bool>,
     },
 }
 
@@ -65,13 +75,13 @@ impl Block {
             BlockContent::SectionStart { .. } => 2,
             BlockContent::SectionEnd { .. } => 2,
             BlockContent::Label { .. } => 2,
-            BlockContent::Instruction { .. } => 1,
+            BlockContent::Instruction { ir, .. } => 1 + ir.len(),
             BlockContent::Error { .. } => 1,
             BlockContent::CString { bytes } => bytes.len() + 1,
             BlockContent::Pointer { .. } => 1,
             BlockContent::Got { .. } => 1,
             BlockContent::DataStructure { fields, .. } => 2 + fields.len(),
-            BlockContent::Bytes { bytes } => (bytes.len() / 32) + 1,
+            BlockContent::Bytes { bytes, .. } => (bytes.len() / 32) + 1,
         }
     }
 
@@ -106,14 +116,32 @@ impl Block {
                 stream.push("-", colors::GRAY60);
                 stream.push_owned(format!("{:x}", section.end), colors::GREEN);
             }
-            BlockContent::Instruction { inst, bytes } => {
+            BlockContent::Instruction { inst, bytes, unsupported, patched, ir } => {
                 stream.push_owned(format!("{:0>10X}  ", self.addr), colors::GRAY40);
-                stream.push_owned(bytes.clone(), colors::GREEN);
+                stream.push_owned(bytes.clone(), bytes_color(*patched));
                 stream.inner.extend_from_slice(&inst);
+                if let Some(extensions) = unsupported {
+                    stream.push("  <", colors::GRAY40);
+                    match extensions.is_empty() {
+                        true => stream.push("unsupported", colors::RED),
+                        false => stream.push_owned(
+                            format!("requires {}", extensions.join(", ")),
+                            colors::RED,
+                        ),
+                    }
+                    stream.push(">", colors::GRAY40);
+                }
+
+                // statements are indented to line up with the instruction
+                let indent = " ".repeat(12 + bytes.chars().count());
+                for stmt in ir {
+                    stream.push("\n", colors::WHITE);
+                    stream.push_owned(format!("{indent}{stmt}"), colors::GRAY60);
+                }
             }
-            BlockContent::Error { err, bytes } => {
+            BlockContent::Error { err, bytes, patched } => {
                 stream.push_owned(format!("{:0>10X}  ", self.addr), colors::GRAY40);
-                stream.push_owned(bytes.clone(), colors::GREEN);
+                stream.push_owned(bytes.clone(), bytes_color(*patched));
                 stream.push("<", colors::GRAY40);
                 stream.push_owned(format!("{err:?}"), colors::RED);
                 stream.push(">", colors::GRAY40);
@@ -168,12 +196,12 @@ impl Block {
                     stream.push(">", colors::BLUE);
                 }
             }
-            BlockContent::Bytes { bytes } => {
+            BlockContent::Bytes { bytes, patched } => {
                 let mut off = 0;
-                for chunk in bytes.chunks(32) {
+                for (chunk, &patched) in bytes.chunks(32).zip(patched) {
                     stream.push_owned(format!("{:0>10X}  ", self.addr + off), colors::GRAY40);
                     let s = processor_shared::encode_hex_bytes_truncated(chunk, usize::MAX, false);
-                    stream.push_owned(s, colors::GREEN);
+                    stream.push_owned(s, bytes_color(patched));
                     stream.push("\n", colors::WHITE);
                     off += chunk.len();
                 }
@@ -184,6 +212,15 @@ impl Block {
     }
 }
 
+/// Color of an instruction's bytes, patched bytes stand out.
+fn bytes_color(patched: bool) -> tokenizing::Color {
+    if patched {
+        CONFIG.colors.patched
+    } else {
+        colors::GREEN
+    }
+}
+
 impl Processor {
     /// Use this instead of get_sym_by_addr for any case where a section symbol
     /// might conflict with a label.
@@ -273,6 +310,34 @@ impl Processor {
             SectionKind::Elf64Dyn => {
                 self.parse_datastructure::<Elf64Dyn>(addr, section, &mut blocks);
             }
+            SectionKind::RiscvAttributes => {
+                let datastructure = RiscvAttributes::new(section.bytes()).to_fields(addr);
+                if !datastructure.fields.is_empty() {
+                    blocks.push(Block {
+                        addr,
+                        content: BlockContent::DataStructure {
+                            ident: datastructure.ident,
+                            fields: datastructure.fields,
+                        },
+                    });
+                }
+            }
+            SectionKind::WasmType
+            | SectionKind::WasmImport
+            | SectionKind::WasmExport
+            | SectionKind::WasmGlobal => {
+                let entries = Entries::new(section.kind.clone(), section.bytes(), section.start);
+                let datastructure = entries.to_fields(addr);
+                if !datastructure.fields.is_empty() {
+                    blocks.push(Block {
+                        addr,
+                        content: BlockContent::DataStructure {
+                            ident: datastructure.ident,
+                            fields: datastructure.fields,
+                        },
+                    });
+                }
+            }
             // For any other section kinds just assume they're made of bytes.
             // As a note, we calculate the byte boundaries in blocks of [`BYTES_BLOCK_SIZE`],
             // so this block can be up to [`BYTES_BLOCK_SIZE`] bytes.
@@ -280,7 +345,7 @@ impl Processor {
                 let bytes = section.bytes_by_addr(addr, BYTES_BLOCK_SIZE).to_vec();
                 blocks.push(Block {
                     addr,
-                    content: BlockContent::Bytes { bytes },
+                    content: self.bytes_content(addr, bytes),
                 });
             }
         }
@@ -356,6 +421,12 @@ impl Processor {
 
         if let Some(inst) = opt_inst {
             let width = self.instruction_width(&inst);
+            let unsupported = self.unsupported(inst);
+            let patched = self.is_patched(addr, width);
+            let ir = match self.shows_ir() {
+                true => self.lift(addr).unwrap_or_default(),
+                false => Vec::new(),
+            };
             let inst = self.instruction_tokens(&inst, &self.index);
             let bytes = section.bytes_by_addr(addr, width);
             let bytes =
@@ -363,7 +434,13 @@ impl Processor {
 
             blocks.push(Block {
                 addr,
-                content: BlockContent::Instruction { inst, bytes },
+                content: BlockContent::Instruction {
+                    inst,
+                    bytes,
+                    unsupported,
+                    patched,
+                    ir,
+                },
             });
             return;
         }
@@ -378,6 +455,7 @@ impl Processor {
                 content: BlockContent::Error {
                     err: err.kind,
                     bytes,
+                    patched: self.is_patched(addr, err.size()),
                 },
             });
             return;
@@ -387,6 +465,16 @@ impl Processor {
         self.parse_bytes(addr, section, blocks);
     }
 
+    /// Bytes shown 32 to a line, marking the lines that were patched.
+    fn bytes_content(&self, addr: usize, bytes: Vec<u8>) -> BlockContent {
+        let patched = (0..bytes.len())
+            .step_by(32)
+            .map(|off| self.is_patched(addr + off, 32.min(bytes.len() - off)))
+            .collect();
+
+        BlockContent::Bytes { bytes, patched }
+    }
+
     fn parse_bytes(&self, addr: usize, section: &Section, blocks: &mut Vec<Block>) {
         let mut baddr = addr;
         loop {
@@ -414,7 +502,7 @@ impl Processor {
             let bytes = section.bytes_by_addr(addr, bytes_len).to_vec();
             blocks.push(Block {
                 addr,
-                content: BlockContent::Bytes { bytes },
+                content: self.bytes_content(addr, bytes),
             });
         }
     }
@@ -507,6 +595,16 @@ impl Processor {
                     addr += size_of::<Elf64Dyn>();
                 }
             }
+            // The attributes are variable length so they're shown as a single block.
+            SectionKind::RiscvAttributes => {}
+            // Entries are variable length so they have to be parsed to find where they start.
+            SectionKind::WasmType
+            | SectionKind::WasmImport
+            | SectionKind::WasmExport
+            | SectionKind::WasmGlobal => {
+                let entries = Entries::new(section.kind.clone(), section.bytes(), section.start);
+                boundaries.extend(entries.offsets());
+            }
             // For any other section kinds just assume they evenly
             // split in blocks of [`BYTES_BLOCK_SIZE`].
             _ => {
@@ -0,0 +1,2129 @@
+//! Decompiling functions to pseudo-C from what their instructions lift to.
+//!
+//! A function is split into basic blocks, whose statements are folded into expressions for
+//! each register write or store that's observable. Loops and conditionals are recovered from
+//! the order the blocks are laid out in, anything else is left as a `goto`. Stack slots and
+//! registers are named after the function's variables when there's debug info describing them.
+
+use crate::Processor;
+use debugvault::{Location, Variable};
+use decoder::ir::{sign_extend, BinOp, Cmp, Expr, Reg, Stmt, UnOp, Value};
+use object::Architecture;
+use processor_shared::PhysAddr;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use tokenizing::{colors, Token};
+
+/// Functions larger than this are cut off, as it's likely the end of one wasn't found.
+const MAX_FUNCTION_SIZE: usize = 0x10000;
+
+/// Placeholder for the value a register is being assigned.
+const NEW_VALUE: Reg = "";
+
+/// Line of pseudo-C.
+pub struct Line {
+    /// Address of the instruction the line was decompiled from.
+    pub addr: PhysAddr,
+    /// How deeply the line is nested in blocks.
+    pub depth: usize,
+    pub tokens: Vec<Token>,
+}
+
+pub struct Decompiled {
+    /// Address of the function's first instruction.
+    pub start: PhysAddr,
+    /// Address past the last instruction that belongs to the function.
+    pub end: PhysAddr,
+    pub lines: Vec<Line>,
+}
+
+impl Decompiled {
+    /// Index of the line that's closest to the instruction at `addr`.
+    pub fn line_by_addr(&self, addr: PhysAddr) -> Option<usize> {
+        let mut closest: Option<(PhysAddr, usize)> = None;
+        for (idx, line) in self.lines.iter().enumerate() {
+            if line.addr <= addr && closest.is_none_or(|(best, _)| line.addr > best) {
+                closest = Some((line.addr, idx));
+            }
+        }
+
+        closest.map(|(_, idx)| idx)
+    }
+}
+
+/// How functions use registers on an architecture.
+struct Convention {
+    sp: Reg,
+    fp: Reg,
+    ret: Reg,
+    args: &'static [Reg],
+    /// Registers a call may overwrite, including flags.
+    clobbered: &'static [Reg],
+    /// Registers by their DWARF number.
+    dwarf: &'static [Reg],
+    /// Offset of the canonical frame address from the stack pointer at the function's entry.
+    cfa: i64,
+}
+
+const SYSV: Convention = Convention {
+    sp: "rsp",
+    fp: "rbp",
+    ret: "rax",
+    args: &["rdi", "rsi", "rdx", "rcx", "r8", "r9"],
+    clobbered: &[
+        "rax", "rcx", "rdx", "rsi", "rdi", "r8", "r9", "r10", "r11", "cf", "pf", "zf", "sf", "of",
+    ],
+    dwarf: &[
+        "rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11", "r12",
+        "r13", "r14", "r15",
+    ],
+    cfa: 8,
+};
+
+const WIN64: Convention = Convention {
+    args: &["rcx", "rdx", "r8", "r9"],
+    clobbered: &[
+        "rax", "rcx", "rdx", "r8", "r9", "r10", "r11", "cf", "pf", "zf", "sf", "of",
+    ],
+    ..SYSV
+};
+
+const AAPCS64: Convention = Convention {
+    sp: "sp",
+    fp: "x29",
+    ret: "x0",
+    args: &["x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7"],
+    clobbered: &[
+        "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+        "x14", "x15", "x16", "x17", "x18", "x30", "n", "z", "c", "v",
+    ],
+    dwarf: &[
+        "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+        "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26",
+        "x27", "x28", "x29", "x30", "sp",
+    ],
+    cfa: 0,
+};
+
+const RISCV: Convention = Convention {
+    sp: "sp",
+    fp: "s0",
+    ret: "a0",
+    args: &["a0", "a1", "a2", "a3", "a4", "a5", "a6", "a7"],
+    clobbered: &[
+        "ra", "t0", "t1", "t2", "a0", "a1", "a2", "a3", "a4", "a5", "a6", "a7", "t3", "t4", "t5",
+        "t6",
+    ],
+    dwarf: &[
+        "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+        "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+        "t5", "t6",
+    ],
+    cfa: 0,
+};
+
+/// Expression a value is folded into.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// Value of a register at the start of the block, or since it was last assigned.
+    Var(Reg),
+    Const(u64, u8),
+    /// Address of a stack slot, as an offset from the canonical frame address.
+    Slot(i64),
+    Load(Box<Node>, u8),
+    Unary(UnOp, u8, Box<Node>),
+    Binary(BinOp, Box<Node>, Box<Node>),
+    Compare(Cmp, Box<Node>, Box<Node>),
+    SignExtend(u8, Box<Node>),
+    Truncate(u8, Box<Node>),
+    Select(Box<Node>, Box<Node>, Box<Node>),
+}
+
+impl Node {
+    fn uses(&self, reg: Reg) -> bool {
+        match self {
+            Node::Var(var) => *var == reg,
+            Node::Const(..) | Node::Slot(_) => false,
+            Node::Load(node, _)
+            | Node::Unary(_, _, node)
+            | Node::SignExtend(_, node)
+            | Node::Truncate(_, node) => node.uses(reg),
+            Node::Binary(_, lhs, rhs) | Node::Compare(_, lhs, rhs) => {
+                lhs.uses(reg) || rhs.uses(reg)
+            }
+            Node::Select(cond, a, b) => cond.uses(reg) || a.uses(reg) || b.uses(reg),
+        }
+    }
+
+    /// Replace each occurrence of `from` with `to`.
+    fn substitute(&mut self, from: &Node, to: &Node) {
+        if self == from {
+            *self = to.clone();
+            return;
+        }
+
+        match self {
+            Node::Var(_) | Node::Const(..) | Node::Slot(_) => {}
+            Node::Load(node, _)
+            | Node::Unary(_, _, node)
+            | Node::SignExtend(_, node)
+            | Node::Truncate(_, node) => node.substitute(from, to),
+            Node::Binary(_, lhs, rhs) | Node::Compare(_, lhs, rhs) => {
+                lhs.substitute(from, to);
+                rhs.substitute(from, to);
+            }
+            Node::Select(cond, a, b) => {
+                cond.substitute(from, to);
+                a.substitute(from, to);
+                b.substitute(from, to);
+            }
+        }
+    }
+}
+
+fn boxed(node: Node) -> Box<Node> {
+    Box::new(node)
+}
+
+/// Whether `node` is the sign of `a - b`.
+fn is_sign_of_sub(node: &Node) -> Option<(&Node, &Node)> {
+    match node {
+        Node::Compare(Cmp::Slt, diff, zero) if matches!(**zero, Node::Const(0, _)) => match &**diff
+        {
+            Node::Binary(BinOp::Sub, a, b) => Some((a, b)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether `node` is the overflow flag of a subtraction.
+fn is_overflow(node: &Node) -> bool {
+    match node {
+        Node::Compare(Cmp::Slt, value, zero) if matches!(**zero, Node::Const(0, _)) => {
+            matches!(&**value, Node::Binary(BinOp::And, x, y)
+                if matches!(**x, Node::Binary(BinOp::Xor, ..))
+                    && matches!(**y, Node::Binary(BinOp::Xor, ..)))
+        }
+        _ => false,
+    }
+}
+
+/// `a < b` if `sign` and `overflow` are the flags of `a - b`.
+fn less_from_flags(sign: &Node, overflow: &Node) -> Option<Node> {
+    let (a, b) = is_sign_of_sub(sign)?;
+    if !is_overflow(overflow) {
+        return None;
+    }
+
+    Some(Node::Compare(Cmp::Slt, boxed(a.clone()), boxed(b.clone())))
+}
+
+fn compare(cmp: Cmp, lhs: Node, rhs: Node, bits: u8) -> Node {
+    if let (Node::Const(a, _), Node::Const(b, _)) = (&lhs, &rhs) {
+        return Node::Const(cmp.eval(*a, *b, bits) as u64, 1);
+    }
+
+    // `a - b == 0` is `a == b`
+    if matches!(cmp, Cmp::Eq | Cmp::Ne) && matches!(rhs, Node::Const(0, _)) {
+        if let Node::Binary(BinOp::Sub, a, b) = lhs {
+            return Node::Compare(cmp, a, b);
+        }
+    }
+
+    // the sign being equal to the overflow flag is `a >= b`
+    if cmp == Cmp::Eq {
+        if let Some(less) = less_from_flags(&lhs, &rhs) {
+            return not(less);
+        }
+    }
+
+    Node::Compare(cmp, boxed(lhs), boxed(rhs))
+}
+
+fn not(node: Node) -> Node {
+    match node {
+        Node::Const(value, bits) => Node::Const(UnOp::Not.eval(value, bits), bits),
+        Node::Unary(UnOp::Not, 1, node) => *node,
+        Node::Compare(cmp, a, b) => match cmp {
+            Cmp::Eq => Node::Compare(Cmp::Ne, a, b),
+            Cmp::Ne => Node::Compare(Cmp::Eq, a, b),
+            Cmp::Ult => Node::Compare(Cmp::Ule, b, a),
+            Cmp::Ule => Node::Compare(Cmp::Ult, b, a),
+            Cmp::Slt => Node::Compare(Cmp::Sle, b, a),
+            Cmp::Sle => Node::Compare(Cmp::Slt, b, a),
+        },
+        node => Node::Unary(UnOp::Not, 1, boxed(node)),
+    }
+}
+
+fn unary(op: UnOp, value: Node, bits: u8) -> Node {
+    match (op, value) {
+        (UnOp::Not, value) if bits == 1 => not(value),
+        (op, Node::Const(value, _)) => Node::Const(op.eval(value, bits), bits),
+        (op, value) => Node::Unary(op, bits, boxed(value)),
+    }
+}
+
+/// Combine two comparisons of the same operands, where one of them is equality.
+fn merge_compares(op: BinOp, lhs: &Node, rhs: &Node) -> Option<Node> {
+    let (Node::Compare(x, a, b), Node::Compare(y, c, d)) = (lhs, rhs) else {
+        return None;
+    };
+
+    let (eq, other, a, b, c, d) = match (x, y) {
+        (Cmp::Eq | Cmp::Ne, _) => (*x, *y, c, d, a, b),
+        (_, Cmp::Eq | Cmp::Ne) => (*y, *x, a, b, c, d),
+        _ => return None,
+    };
+
+    // `a` and `b` are the operands of the ordering, `c` and `d` of the equality
+    let same = (a == c && b == d) || (a == d && b == c);
+    if !same {
+        return None;
+    }
+
+    let merged = match (op, eq, other) {
+        (BinOp::Or, Cmp::Eq, Cmp::Ult) => Cmp::Ule,
+        (BinOp::Or, Cmp::Eq, Cmp::Slt) => Cmp::Sle,
+        (BinOp::And, Cmp::Ne, Cmp::Ule) => Cmp::Ult,
+        (BinOp::And, Cmp::Ne, Cmp::Sle) => Cmp::Slt,
+        _ => return None,
+    };
+
+    Some(Node::Compare(merged, a.clone(), b.clone()))
+}
+
+fn binary(op: BinOp, lhs: Node, rhs: Node, bits: u8) -> Node {
+    if let (Node::Const(a, _), Node::Const(b, _)) = (&lhs, &rhs) {
+        if let Some(value) = op.eval(*a, *b, bits) {
+            return Node::Const(value, bits);
+        }
+    }
+
+    match (op, &lhs, &rhs) {
+        (BinOp::Add | BinOp::Sub, Node::Slot(slot), Node::Const(offset, _)) => {
+            let offset = sign_extend(*offset, bits);
+            return Node::Slot(match op {
+                BinOp::Add => slot.wrapping_add(offset),
+                _ => slot.wrapping_sub(offset),
+            });
+        }
+        (BinOp::Add, Node::Const(offset, _), Node::Slot(slot)) => {
+            return Node::Slot(slot.wrapping_add(sign_extend(*offset, bits)));
+        }
+        (
+            BinOp::Add
+            | BinOp::Sub
+            | BinOp::Or
+            | BinOp::Xor
+            | BinOp::Shl
+            | BinOp::LShr
+            | BinOp::AShr,
+            _,
+            Node::Const(0, _),
+        ) => return lhs,
+        (BinOp::Add | BinOp::Or | BinOp::Xor, Node::Const(0, _), _) => return rhs,
+        (BinOp::Mul, _, Node::Const(1, _)) => return lhs,
+        (BinOp::And | BinOp::Or, _, _) if lhs == rhs => return lhs,
+        // adding a negative constant is shown as a subtraction
+        (BinOp::Add, _, Node::Const(value, _)) if bits > 1 && sign_extend(*value, bits) < 0 => {
+            let value = sign_extend(*value, bits).unsigned_abs();
+            return Node::Binary(BinOp::Sub, boxed(lhs), boxed(Node::Const(value, bits)));
+        }
+        // the sign of `a - b` differing from the overflow flag is `a < b`
+        (BinOp::Xor, _, _) => {
+            if let Some(less) = less_from_flags(&lhs, &rhs) {
+                return less;
+            }
+        }
+        (BinOp::Or | BinOp::And, _, _) => {
+            if let Some(merged) = merge_compares(op, &lhs, &rhs) {
+                return merged;
+            }
+        }
+        _ => {}
+    }
+
+    Node::Binary(op, boxed(lhs), boxed(rhs))
+}
+
+/// Where control goes after a block.
+enum Term {
+    /// Falls through to the next instruction.
+    Fall(PhysAddr),
+    Goto(PhysAddr),
+    /// Goes to the first address if the condition holds, otherwise to the second.
+    Cond(Node, PhysAddr, PhysAddr),
+    Indirect(Node),
+    Return(Option<Node>),
+    /// Jumps to another function, returning what it returns.
+    TailCall(Vec<Token>),
+}
+
+enum Item {
+    Line(PhysAddr, Vec<Token>),
+    /// Conditional jump in the middle of a block.
+    Branch(PhysAddr, Node, PhysAddr),
+}
+
+struct Block {
+    addr: PhysAddr,
+    /// Indices of the block's instructions.
+    insts: std::ops::Range<usize>,
+    /// Address of the block's last instruction.
+    last: PhysAddr,
+    succs: Vec<PhysAddr>,
+    live_out: HashSet<Reg>,
+    items: Vec<Item>,
+    term: Term,
+}
+
+/// Names of the function's variables.
+#[derive(Default)]
+struct Names {
+    regs: HashMap<Reg, String>,
+    slots: HashMap<i64, String>,
+}
+
+impl Names {
+    fn new(conv: &Convention, vars: &[Variable], frame: &HashMap<Reg, i64>) -> Self {
+        let mut names = Names::default();
+        for var in vars {
+            let reg = |num: u16| conv.dwarf.get(num as usize).copied();
+            match var.location {
+                Location::Register(num) => {
+                    if let Some(reg) = reg(num) {
+                        names.regs.insert(reg, var.name.clone());
+                    }
+                }
+                Location::Relative(num, offset) => {
+                    if let Some(base) = reg(num).and_then(|reg| frame.get(reg)) {
+                        names.slots.insert(base + offset, var.name.clone());
+                    }
+                }
+                Location::Frame(offset) => {
+                    names.slots.insert(offset, var.name.clone());
+                }
+            }
+        }
+
+        names
+    }
+
+    fn reg(&self, reg: Reg) -> String {
+        match self.regs.get(reg) {
+            Some(name) => name.clone(),
+            None => reg.to_string(),
+        }
+    }
+
+    fn slot(&self, slot: i64) -> String {
+        match self.slots.get(&slot) {
+            Some(name) => name.clone(),
+            None if slot < 0 => format!("local_{:x}", slot.unsigned_abs()),
+            None => format!("arg_{slot:x}"),
+        }
+    }
+}
+
+fn token(text: &'static str, color: tokenizing::Color) -> Token {
+    Token::from_str(text, color)
+}
+
+/// Whether the statements of an instruction transfer control.
+fn transfers_control(stmts: &[Stmt]) -> bool {
+    stmts
+        .iter()
+        .any(|stmt| matches!(stmt, Stmt::Jump(_) | Stmt::Branch(..) | Stmt::Return(_)))
+}
+
+/// Whether control can continue to the next instruction.
+fn falls_through(stmts: &[Stmt]) -> bool {
+    !matches!(stmts.last(), Some(Stmt::Jump(_) | Stmt::Return(_)))
+}
+
+fn const_targets(stmts: &[Stmt]) -> impl Iterator<Item = PhysAddr> + '_ {
+    stmts.iter().filter_map(|stmt| match stmt {
+        Stmt::Jump(target) | Stmt::Branch(_, target) => target.as_const().map(|t| t as PhysAddr),
+        _ => None,
+    })
+}
+
+/// Decompiler state for a single function.
+struct Decompiler<'a> {
+    processor: &'a Processor,
+    conv: &'static Convention,
+    insts: Vec<(PhysAddr, Vec<Stmt>)>,
+    blocks: Vec<Block>,
+    by_addr: HashMap<PhysAddr, usize>,
+    names: Names,
+    /// Stack slots of the registers at the start of the block following the entry block.
+    prologue: HashMap<Reg, i64>,
+    /// Whether the function sets the register holding the return value.
+    returns: bool,
+}
+
+impl<'a> Decompiler<'a> {
+    fn new(processor: &'a Processor, conv: &'static Convention, start: PhysAddr) -> Self {
+        let end = processor.function_end(start);
+        let lifted = processor.lift_range(start, end);
+        let idx_by_addr: HashMap<PhysAddr, usize> =
+            lifted.iter().enumerate().map(|(idx, (addr, _))| (*addr, idx)).collect();
+
+        // only keep instructions reachable from the function's entry
+        let mut reachable = vec![false; lifted.len()];
+        let mut stack = Vec::from_iter(idx_by_addr.get(&start).copied());
+        while let Some(idx) = stack.pop() {
+            if std::mem::replace(&mut reachable[idx], true) {
+                continue;
+            }
+
+            let stmts = &lifted[idx].1;
+            stack.extend(const_targets(stmts).filter_map(|addr| idx_by_addr.get(&addr).copied()));
+            if falls_through(stmts) && idx + 1 < lifted.len() {
+                stack.push(idx + 1);
+            }
+        }
+
+        let mut targets = HashSet::new();
+        for (idx, (_, stmts)) in lifted.iter().enumerate() {
+            if reachable[idx] {
+                targets.extend(const_targets(stmts));
+            }
+        }
+
+        let insts: Vec<_> = lifted
+            .into_iter()
+            .zip(reachable)
+            .filter_map(|(inst, reachable)| reachable.then_some(inst))
+            .collect();
+
+        // split the instructions into blocks
+        let mut blocks: Vec<Block> = Vec::new();
+        for idx in 0..insts.len() {
+            let (addr, _) = insts[idx];
+            let leader = match idx.checked_sub(1) {
+                None => true,
+                Some(prev) => {
+                    let prev = &insts[prev].1;
+                    targets.contains(&addr) || transfers_control(prev) || !falls_through(prev)
+                }
+            };
+
+            // instructions that aren't directly after each other aren't in the same block
+            let adjacent = idx > 0 && {
+                let last = &blocks.last().unwrap().insts;
+                last.end == idx && !leader
+            };
+
+            if adjacent {
+                let block = blocks.last_mut().unwrap();
+                block.insts.end = idx + 1;
+                block.last = addr;
+            } else {
+                blocks.push(Block {
+                    addr,
+                    insts: idx..idx + 1,
+                    last: addr,
+                    succs: Vec::new(),
+                    live_out: HashSet::new(),
+                    items: Vec::new(),
+                    term: Term::Return(None),
+                });
+            }
+        }
+
+        let by_addr = blocks.iter().enumerate().map(|(idx, block)| (block.addr, idx)).collect();
+        let returns = insts
+            .iter()
+            .flat_map(|(_, stmts)| stmts)
+            .any(|stmt| matches!(stmt, Stmt::SetReg(reg, _) if *reg == conv.ret));
+
+        let mut this = Self {
+            processor,
+            conv,
+            insts,
+            blocks,
+            by_addr,
+            names: Names::default(),
+            prologue: HashMap::new(),
+            returns,
+        };
+
+        this.successors();
+        this.liveness();
+        this.prologue = this.frame_after_entry();
+
+        let vars = processor.index.get_vars_by_addr(start);
+        this.names = Names::new(conv, vars, &this.prologue);
+        this
+    }
+
+    /// Address following the instruction at `idx`.
+    fn next_addr(&self, idx: usize) -> PhysAddr {
+        match self.insts.get(idx + 1) {
+            Some((addr, _)) => *addr,
+            None => self.insts[idx].0 + 1,
+        }
+    }
+
+    fn successors(&mut self) {
+        for idx in 0..self.blocks.len() {
+            let last = self.blocks[idx].insts.end - 1;
+            let stmts = &self.insts[last].1;
+            let mut succs: Vec<PhysAddr> = self.blocks[idx]
+                .insts
+                .clone()
+                .flat_map(|inst| const_targets(&self.insts[inst].1).collect::<Vec<_>>())
+                .collect();
+
+            if falls_through(stmts) {
+                succs.push(self.next_addr(last));
+            }
+
+            succs.retain(|addr| self.by_addr.contains_key(addr));
+            self.blocks[idx].succs = succs;
+        }
+    }
+
+    /// Whether `stmt` jumps to another function.
+    fn is_tail_call(&self, stmt: &Stmt) -> bool {
+        match stmt {
+            Stmt::Jump(target) => target
+                .as_const()
+                .is_some_and(|target| !self.by_addr.contains_key(&(target as PhysAddr))),
+            _ => false,
+        }
+    }
+
+    /// Registers read by a call, a return or an instruction we don't know the effects of.
+    fn reads_at(&self, stmt: &Stmt, reg: Reg) -> Option<bool> {
+        match stmt {
+            _ if self.is_tail_call(stmt) => Some(self.conv.args.contains(&reg)),
+            Stmt::Assign(_, Expr::Reg(read)) if *read == reg => Some(true),
+            Stmt::SetReg(written, _) if *written == reg => Some(false),
+            Stmt::Call(_) if self.conv.args.contains(&reg) => Some(true),
+            Stmt::Call(_) if self.conv.clobbered.contains(&reg) => Some(false),
+            Stmt::Return(_) => Some(reg == self.conv.ret && self.returns),
+            _ => None,
+        }
+    }
+
+    /// Compute which registers are live at the end of each block.
+    fn liveness(&mut self) {
+        let mut uses: Vec<HashSet<Reg>> = Vec::new();
+        let mut defs: Vec<HashSet<Reg>> = Vec::new();
+
+        for block in &self.blocks {
+            let mut used = HashSet::new();
+            let mut defined = HashSet::new();
+            for (_, stmts) in &self.insts[block.insts.clone()] {
+                for stmt in stmts {
+                    match stmt {
+                        Stmt::Assign(_, Expr::Reg(reg)) if !defined.contains(reg) => {
+                            used.insert(*reg);
+                        }
+                        Stmt::SetReg(reg, _) => {
+                            defined.insert(*reg);
+                        }
+                        Stmt::Call(_) | Stmt::Jump(_)
+                            if matches!(stmt, Stmt::Call(_)) || self.is_tail_call(stmt) =>
+                        {
+                            let args = self.conv.args.iter().filter(|r| !defined.contains(*r));
+                            used.extend(args.copied().collect::<Vec<_>>());
+                            defined.extend(self.conv.clobbered);
+                        }
+                        Stmt::Return(_) if self.returns && !defined.contains(self.conv.ret) => {
+                            used.insert(self.conv.ret);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            uses.push(used);
+            defs.push(defined);
+        }
+
+        let mut live_in: Vec<HashSet<Reg>> = vec![HashSet::new(); self.blocks.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for idx in (0..self.blocks.len()).rev() {
+                let mut live_out = HashSet::new();
+                for succ in &self.blocks[idx].succs {
+                    live_out.extend(live_in[self.by_addr[succ]].iter().copied());
+                }
+
+                let mut live: HashSet<Reg> =
+                    live_out.iter().filter(|reg| !defs[idx].contains(*reg)).copied().collect();
+                live.extend(uses[idx].iter().copied());
+
+                if live != live_in[idx] {
+                    live_in[idx] = live;
+                    changed = true;
+                }
+
+                self.blocks[idx].live_out = live_out;
+            }
+        }
+    }
+
+    /// Whether `reg` is read after statement `stmt` of instruction `inst` in a block.
+    fn live_after(&self, block: usize, inst: usize, stmt: usize, reg: Reg) -> bool {
+        let block = &self.blocks[block];
+        let mut first = stmt + 1;
+        for idx in inst..block.insts.end {
+            for stmt in &self.insts[idx].1[first.min(self.insts[idx].1.len())..] {
+                if let Some(read) = self.reads_at(stmt, reg) {
+                    return read;
+                }
+            }
+            first = 0;
+        }
+
+        block.live_out.contains(reg)
+    }
+
+    /// Stack slots the registers point to after the entry block, usually after the prologue.
+    fn frame_after_entry(&self) -> HashMap<Reg, i64> {
+        let mut frame = HashMap::from([(self.conv.sp, -self.conv.cfa)]);
+        let mut temps: HashMap<u32, i64> = HashMap::new();
+        let block = match self.blocks.first() {
+            Some(block) => block,
+            None => return frame,
+        };
+
+        for (_, stmts) in &self.insts[block.insts.clone()] {
+            let before = frame.get(self.conv.sp).copied();
+            for stmt in stmts {
+                let slot = |value: &Value| match value {
+                    Value::Temp(temp) => temps.get(&temp.id).copied(),
+                    Value::Const { .. } => None,
+                };
+
+                match stmt {
+                    Stmt::Assign(temp, Expr::Reg(reg)) => {
+                        if let Some(slot) = frame.get(reg) {
+                            temps.insert(temp.id, *slot);
+                        }
+                    }
+                    Stmt::Assign(temp, Expr::Binary(op @ (BinOp::Add | BinOp::Sub), a, b)) => {
+                        if let (Some(slot), Some(offset)) = (slot(a), b.as_const()) {
+                            let offset = sign_extend(offset, b.bits());
+                            let slot = match op {
+                                BinOp::Add => slot.wrapping_add(offset),
+                                _ => slot.wrapping_sub(offset),
+                            };
+                            temps.insert(temp.id, slot);
+                        }
+                    }
+                    Stmt::Assign(temp, Expr::ZeroExtend(value) | Expr::Truncate(value)) => {
+                        if let Some(slot) = slot(value).filter(|_| temp.bits >= 32) {
+                            temps.insert(temp.id, slot);
+                        }
+                    }
+                    Stmt::SetReg(reg, value) => match slot(value) {
+                        Some(slot) => {
+                            frame.insert(reg, slot);
+                        }
+                        None => {
+                            frame.remove(reg);
+                        }
+                    },
+                    // the callee pops the return address
+                    Stmt::Call(_) => {
+                        if let Some(before) = before {
+                            frame.insert(self.conv.sp, before);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        frame
+    }
+
+    fn jump_name(&self, target: PhysAddr) -> String {
+        match self.processor.function_name(target) {
+            Some(name) => name.to_string(),
+            None => format!("sub_{target:x}"),
+        }
+    }
+
+    /// Lines and terminator of each block.
+    fn render_blocks(&mut self) {
+        for idx in 0..self.blocks.len() {
+            let mut renderer = BlockRenderer::new(self, idx);
+            renderer.run();
+            let (items, term) = (renderer.items, renderer.term);
+            self.blocks[idx].items = items;
+            self.blocks[idx].term = term;
+        }
+    }
+
+    fn tokens(&self, node: &Node, out: &mut Vec<Token>) {
+        self.tokens_prec(node, out, 0);
+    }
+
+    fn tokens_prec(&self, node: &Node, out: &mut Vec<Token>, parent: u8) {
+        let prec = precedence(node);
+        let wrap = prec < parent;
+        if wrap {
+            out.push(token("(", colors::GRAY99));
+        }
+
+        match node {
+            Node::Var(reg) => out.push(Token::from_string(self.names.reg(reg), colors::WHITE)),
+            Node::Const(value, bits) => {
+                out.push(Token::from_string(constant(*value, *bits), colors::GREEN))
+            }
+            Node::Slot(slot) => {
+                out.push(token("&", colors::GRAY99));
+                out.push(Token::from_string(self.names.slot(*slot), colors::WHITE));
+            }
+            Node::Load(addr, bits) => match **addr {
+                Node::Slot(slot) => {
+                    out.push(Token::from_string(self.names.slot(slot), colors::WHITE))
+                }
+                _ => {
+                    out.push(Token::from_string(
+                        format!("*(uint{bits}_t *)"),
+                        colors::GRAY99,
+                    ));
+                    self.tokens_prec(addr, out, 15);
+                }
+            },
+            Node::Unary(op, bits, value) => {
+                let name = match op {
+                    UnOp::Not if *bits == 1 => "!",
+                    UnOp::Not => "~",
+                    UnOp::Neg => "-",
+                    UnOp::Clz => "clz",
+                    UnOp::Ctz => "ctz",
+                    UnOp::Popcnt => "popcount",
+                    UnOp::Bswap => "bswap",
+                };
+
+                match op {
+                    UnOp::Not | UnOp::Neg => {
+                        out.push(token(name, colors::GRAY99));
+                        self.tokens_prec(value, out, 14);
+                    }
+                    _ => {
+                        out.push(token(name, colors::BLUE));
+                        out.push(token("(", colors::GRAY99));
+                        self.tokens(value, out);
+                        out.push(token(")", colors::GRAY99));
+                    }
+                }
+            }
+            Node::Binary(op @ (BinOp::MulHiU | BinOp::MulHiS | BinOp::Ror), lhs, rhs) => {
+                let name = match op {
+                    BinOp::Ror => "ror",
+                    _ => "mulhi",
+                };
+                out.push(token(name, colors::BLUE));
+                out.push(token("(", colors::GRAY99));
+                self.tokens(lhs, out);
+                out.push(token(", ", colors::GRAY99));
+                self.tokens(rhs, out);
+                out.push(token(")", colors::GRAY99));
+            }
+            Node::Binary(op, lhs, rhs) => {
+                let name = match op {
+                    BinOp::Add => " + ",
+                    BinOp::Sub => " - ",
+                    BinOp::Mul => " * ",
+                    BinOp::UDiv | BinOp::SDiv => " / ",
+                    BinOp::URem | BinOp::SRem => " % ",
+                    BinOp::And => " & ",
+                    BinOp::Or => " | ",
+                    BinOp::Xor => " ^ ",
+                    BinOp::Shl => " << ",
+                    _ => " >> ",
+                };
+                self.tokens_prec(lhs, out, prec);
+                out.push(token(name, colors::GRAY99));
+                self.tokens_prec(rhs, out, prec + 1);
+            }
+            Node::Compare(cmp, lhs, rhs) => {
+                // constants go on the right
+                let (lhs, rhs, flipped) = match (&**lhs, &**rhs) {
+                    (Node::Const(..), rhs) if !matches!(rhs, Node::Const(..)) => {
+                        (rhs, &**lhs, true)
+                    }
+                    _ => (&**lhs, &**rhs, false),
+                };
+                let name = match (cmp, flipped) {
+                    (Cmp::Eq, _) => " == ",
+                    (Cmp::Ne, _) => " != ",
+                    (Cmp::Ult | Cmp::Slt, false) => " < ",
+                    (Cmp::Ule | Cmp::Sle, false) => " <= ",
+                    (Cmp::Ult | Cmp::Slt, true) => " > ",
+                    (Cmp::Ule | Cmp::Sle, true) => " >= ",
+                };
+                self.tokens_prec(lhs, out, prec + 1);
+                out.push(token(name, colors::GRAY99));
+                self.tokens_prec(rhs, out, prec + 1);
+            }
+            Node::SignExtend(bits, value) => {
+                out.push(Token::from_string(format!("(int{bits}_t)"), colors::GRAY99));
+                self.tokens_prec(value, out, 14);
+            }
+            Node::Truncate(bits, value) => {
+                let bits = (*bits).max(8).next_power_of_two();
+                out.push(Token::from_string(
+                    format!("(uint{bits}_t)"),
+                    colors::GRAY99,
+                ));
+                self.tokens_prec(value, out, 14);
+            }
+            Node::Select(cond, a, b) => {
+                self.tokens_prec(cond, out, 4);
+                out.push(token(" ? ", colors::GRAY99));
+                self.tokens_prec(a, out, 4);
+                out.push(token(" : ", colors::GRAY99));
+                self.tokens_prec(b, out, 3);
+            }
+        }
+
+        if wrap {
+            out.push(token(")", colors::GRAY99));
+        }
+    }
+}
+
+/// C operator precedence, higher binds tighter.
+fn precedence(node: &Node) -> u8 {
+    match node {
+        Node::Binary(op, ..) => match op {
+            BinOp::Mul | BinOp::UDiv | BinOp::SDiv | BinOp::URem | BinOp::SRem => 13,
+            BinOp::Add | BinOp::Sub => 12,
+            BinOp::Shl | BinOp::LShr | BinOp::AShr => 11,
+            BinOp::And => 8,
+            BinOp::Xor => 7,
+            BinOp::Or => 6,
+            BinOp::MulHiU | BinOp::MulHiS | BinOp::Ror => 15,
+        },
+        Node::Compare(Cmp::Eq | Cmp::Ne, ..) => 9,
+        Node::Compare(..) => 10,
+        Node::Select(..) => 3,
+        Node::Unary(UnOp::Not | UnOp::Neg, ..) | Node::SignExtend(..) | Node::Truncate(..) => 14,
+        Node::Slot(_) => 14,
+        Node::Load(addr, _) if !matches!(**addr, Node::Slot(_)) => 14,
+        _ => 15,
+    }
+}
+
+fn constant(value: u64, bits: u8) -> String {
+    let signed = sign_extend(value, bits);
+    if bits > 1 && (-256..0).contains(&signed) {
+        return signed.to_string();
+    }
+
+    match value < 10 {
+        true => value.to_string(),
+        false => format!("{value:#x}"),
+    }
+}
+
+/// Folds the statements of a block into lines.
+struct BlockRenderer<'d, 'a> {
+    dec: &'d Decompiler<'a>,
+    block: usize,
+    temps: HashMap<u32, Node>,
+    /// Register writes that haven't been shown yet, in the order they happened.
+    pending: Vec<(Reg, Node, PhysAddr)>,
+    /// Registers that point into the stack.
+    frame: HashMap<Reg, i64>,
+    /// Stack slots holding the value a register had when the function was entered.
+    saved: HashMap<i64, Reg>,
+    /// Registers that were written to in the block.
+    written: HashSet<Reg>,
+    items: Vec<Item>,
+    term: Term,
+}
+
+impl<'d, 'a> BlockRenderer<'d, 'a> {
+    fn new(dec: &'d Decompiler<'a>, block: usize) -> Self {
+        let frame = match block {
+            0 => HashMap::from([(dec.conv.sp, -dec.conv.cfa)]),
+            _ => dec.prologue.clone(),
+        };
+
+        Self {
+            dec,
+            block,
+            temps: HashMap::new(),
+            pending: Vec::new(),
+            frame,
+            saved: HashMap::new(),
+            written: HashSet::new(),
+            items: Vec::new(),
+            term: Term::Return(None),
+        }
+    }
+
+    fn value(&self, value: &Value) -> Node {
+        match value {
+            Value::Const { value, bits } => Node::Const(*value, *bits),
+            Value::Temp(temp) => match self.temps.get(&temp.id) {
+                Some(node) => node.clone(),
+                None => Node::Var("?"),
+            },
+        }
+    }
+
+    fn read(&self, reg: Reg) -> Node {
+        if let Some((_, node, _)) = self.pending.iter().find(|(pending, ..)| *pending == reg) {
+            return node.clone();
+        }
+
+        match self.frame.get(reg) {
+            Some(slot) => Node::Slot(*slot),
+            None => Node::Var(reg),
+        }
+    }
+
+    fn line(&mut self, addr: PhysAddr, tokens: Vec<Token>) {
+        self.items.push(Item::Line(addr, tokens));
+    }
+
+    fn assignment(&mut self, addr: PhysAddr, dest: Vec<Token>, value: &Node) {
+        let mut tokens = dest;
+        tokens.push(token(" = ", colors::GRAY99));
+        self.dec.tokens(value, &mut tokens);
+        tokens.push(token(";", colors::GRAY99));
+        self.line(addr, tokens);
+    }
+
+    /// Show the pending register writes that are read later, `others` are expressions that
+    /// are used after them.
+    fn flush(&mut self, inst: usize, stmt: usize, others: &mut [&mut Node]) {
+        let mut pending = std::mem::take(&mut self.pending);
+        let dec = self.dec;
+        pending.retain(|(reg, ..)| match stmt {
+            usize::MAX => dec.blocks[self.block].live_out.contains(reg),
+            _ => dec.live_after(self.block, inst, stmt, reg),
+        });
+
+        while !pending.is_empty() {
+            // writes are shown before the writes that read the register's previous value
+            let idx = (0..pending.len())
+                .find(|&idx| {
+                    let reg = pending[idx].0;
+                    pending
+                        .iter()
+                        .enumerate()
+                        .all(|(other, (_, node, _))| other == idx || !node.uses(reg))
+                })
+                .unwrap_or(0);
+
+            let (reg, node, addr) = pending.remove(idx);
+            let var = Node::Var(reg);
+
+            // replacing a register or constant with the register would only rename it
+            let leaf = matches!(node, Node::Var(_) | Node::Const(..) | Node::Slot(_));
+
+            // expressions using the previous value have to be shown before it changes
+            for other in others.iter_mut() {
+                let mut substituted = (*other).clone();
+                if !leaf {
+                    substituted.substitute(&node, &Node::Var(NEW_VALUE));
+                }
+
+                if !substituted.uses(reg) {
+                    substituted.substitute(&Node::Var(NEW_VALUE), &var);
+                    **other = substituted;
+                } else {
+                    let mut tokens = vec![token("cond", colors::WHITE)];
+                    tokens.push(token(" = ", colors::GRAY99));
+                    dec.tokens(other, &mut tokens);
+                    tokens.push(token(";", colors::GRAY99));
+                    self.line(addr, tokens);
+                    **other = Node::Var("cond");
+                }
+            }
+
+            if !leaf {
+                for (_, other, _) in pending.iter_mut() {
+                    other.substitute(&node, &var);
+                }
+            }
+
+            let dest = vec![Token::from_string(dec.names.reg(reg), colors::WHITE)];
+            self.assignment(addr, dest, &node);
+        }
+    }
+
+    fn set_reg(&mut self, addr: PhysAddr, reg: Reg, node: Node) {
+        let conv = self.dec.conv;
+        self.pending.retain(|(pending, ..)| *pending != reg);
+
+        // stack and frame pointer adjustments aren't shown
+        if let Node::Slot(slot) = node {
+            if reg == conv.sp || reg == conv.fp {
+                self.frame.insert(reg, slot);
+                return;
+            }
+        }
+
+        // neither are callee saved registers being restored
+        if let Node::Load(ref slot, _) = node {
+            if let Node::Slot(slot) = **slot {
+                if self.saved.get(&slot) == Some(&reg) {
+                    self.frame.remove(reg);
+                    return;
+                }
+            }
+        }
+
+        self.frame.remove(reg);
+        self.written.insert(reg);
+        self.pending.push((reg, node, addr));
+    }
+
+    fn store(&mut self, addr: PhysAddr, dest: Node, value: Node, bits: u8) {
+        let conv = self.dec.conv;
+        if let Node::Slot(slot) = dest {
+            // callee saved registers being spilled in the entry block aren't shown
+            if let Node::Var(reg) = value {
+                let spill = self.block == 0
+                    && !self.written.contains(reg)
+                    && !conv.args.contains(&reg)
+                    && !self.dec.names.slots.contains_key(&slot);
+
+                if spill {
+                    self.saved.insert(slot, reg);
+                    return;
+                }
+            }
+
+            let dest = vec![Token::from_string(self.dec.names.slot(slot), colors::WHITE)];
+            return self.assignment(addr, dest, &value);
+        }
+
+        let mut tokens = vec![Token::from_string(
+            format!("*(uint{bits}_t *)"),
+            colors::GRAY99,
+        )];
+        self.dec.tokens_prec(&dest, &mut tokens, 15);
+        self.assignment(addr, tokens, &value);
+    }
+
+    /// Call of `target`, with the value it returns assigned if it's read afterwards.
+    fn call(&mut self, addr: PhysAddr, inst: usize, stmt: usize, target: Node) {
+        let mut tokens = Vec::new();
+        if self.dec.live_after(self.block, inst, stmt, self.dec.conv.ret) {
+            let ret = self.dec.names.reg(self.dec.conv.ret);
+            tokens.push(Token::from_string(ret, colors::WHITE));
+            tokens.push(token(" = ", colors::GRAY99));
+        }
+
+        tokens.extend(self.call_tokens(inst, stmt, target));
+        tokens.push(token(";", colors::GRAY99));
+        self.line(addr, tokens);
+    }
+
+    fn call_tokens(&mut self, inst: usize, stmt: usize, target: Node) -> Vec<Token> {
+        let conv = self.dec.conv;
+
+        // arguments are the argument registers that were just written, in order
+        let mut args = Vec::new();
+        for reg in conv.args {
+            match self.pending.iter().position(|(pending, ..)| pending == reg) {
+                Some(idx) => args.push(self.pending.remove(idx).1),
+                None => break,
+            }
+        }
+
+        // whatever else was left in the registers the call overwrites isn't read
+        self.pending.retain(|(reg, ..)| !conv.clobbered.contains(reg));
+        self.flush(inst, stmt, &mut []);
+
+        let mut tokens = Vec::new();
+        match target {
+            Node::Const(target, _) => {
+                let name = self.dec.jump_name(target as PhysAddr);
+                tokens.push(Token::from_string(name, colors::BLUE));
+            }
+            target => {
+                tokens.push(token("(*", colors::GRAY99));
+                self.dec.tokens(&target, &mut tokens);
+                tokens.push(token(")", colors::GRAY99));
+            }
+        }
+
+        tokens.push(token("(", colors::GRAY99));
+        for (idx, arg) in args.iter().enumerate() {
+            if idx != 0 {
+                tokens.push(token(", ", colors::GRAY99));
+            }
+            self.dec.tokens(arg, &mut tokens);
+        }
+        tokens.push(token(")", colors::GRAY99));
+        tokens
+    }
+
+    fn unsupported(&mut self, addr: PhysAddr) {
+        let processor = self.dec.processor;
+        let text: String = match processor.instruction_by_addr(addr) {
+            Some(inst) => processor
+                .instruction_tokens(inst, &processor.index)
+                .iter()
+                .map(|token| &token.text as &str)
+                .collect(),
+            None => String::new(),
+        };
+
+        let mut tokens = vec![token("__asm__", colors::MAGENTA)];
+        tokens.push(Token::from_string(
+            format!("(\"{}\");", text.trim()),
+            colors::GRAY60,
+        ));
+        self.line(addr, tokens);
+    }
+
+    fn run(&mut self) {
+        let dec = self.dec;
+        let conv = dec.conv;
+        let range = dec.blocks[self.block].insts.clone();
+
+        for inst in range.clone() {
+            let (addr, ref stmts) = dec.insts[inst];
+            let has_call = stmts.iter().any(|stmt| matches!(stmt, Stmt::Call(_)));
+            let sp_before = self.frame.get(conv.sp).copied();
+            let last = inst + 1 == range.end;
+
+            for (idx, stmt) in stmts.iter().enumerate() {
+                let is_last = last && idx + 1 == stmts.len();
+                match stmt {
+                    Stmt::Assign(temp, expr) => {
+                        let bits = temp.bits;
+                        let node = match expr {
+                            Expr::Reg(reg) => self.read(reg),
+                            Expr::Load(addr) => Node::Load(boxed(self.value(addr)), bits),
+                            Expr::Unary(op, value) => unary(*op, self.value(value), bits),
+                            Expr::Binary(op, a, b) => {
+                                binary(*op, self.value(a), self.value(b), a.bits())
+                            }
+                            Expr::Compare(cmp, a, b) => {
+                                compare(*cmp, self.value(a), self.value(b), a.bits())
+                            }
+                            Expr::ZeroExtend(value) => self.value(value),
+                            Expr::SignExtend(value) => match self.value(value) {
+                                Node::Const(v, from) => {
+                                    Node::Const(sign_extend(v, from) as u64 & mask(bits), bits)
+                                }
+                                node => Node::SignExtend(bits, boxed(node)),
+                            },
+                            Expr::Truncate(value) => match self.value(value) {
+                                Node::Const(v, _) => Node::Const(v & mask(bits), bits),
+                                node @ Node::Slot(_) => node,
+                                node => Node::Truncate(bits, boxed(node)),
+                            },
+                            Expr::Select(cond, a, b) => Node::Select(
+                                boxed(self.value(cond)),
+                                boxed(self.value(a)),
+                                boxed(self.value(b)),
+                            ),
+                        };
+                        self.temps.insert(temp.id, node);
+                    }
+                    Stmt::SetReg(reg, value) => {
+                        let node = self.value(value);
+                        self.set_reg(addr, reg, node);
+                    }
+                    // the return address pushed by a call isn't shown
+                    Stmt::Store(..) if has_call => {}
+                    Stmt::Store(dest, value) => {
+                        let (dest, value) = (self.value(dest), self.value(value));
+                        self.flush(inst, idx.wrapping_sub(1), &mut []);
+                        self.store(addr, dest, value, value_bits(stmts, idx));
+                    }
+                    Stmt::Call(target) => {
+                        let target = self.value(target);
+                        self.call(addr, inst, idx, target);
+                        if let Some(sp) = sp_before {
+                            self.frame.insert(conv.sp, sp);
+                        }
+                    }
+                    Stmt::Intrinsic(name, args) => {
+                        let args: Vec<Node> = args.iter().map(|arg| self.value(arg)).collect();
+                        self.flush(inst, idx, &mut []);
+                        let mut tokens = vec![token(name, colors::BLUE)];
+                        tokens.push(token("(", colors::GRAY99));
+                        for (idx, arg) in args.iter().enumerate() {
+                            if idx != 0 {
+                                tokens.push(token(", ", colors::GRAY99));
+                            }
+                            dec.tokens(arg, &mut tokens);
+                        }
+                        tokens.push(token(");", colors::GRAY99));
+                        self.line(addr, tokens);
+                    }
+                    Stmt::Unsupported => {
+                        self.flush(inst, idx, &mut []);
+                        self.unsupported(addr);
+                    }
+                    Stmt::Branch(cond, target) if is_last => {
+                        let mut cond = self.value(cond);
+                        let fall = dec.next_addr(inst);
+                        self.flush(inst, usize::MAX, &mut [&mut cond]);
+                        self.term = match self.value(target) {
+                            Node::Const(target, _) => Term::Cond(cond, target as PhysAddr, fall),
+                            target => {
+                                let target = Node::Select(
+                                    boxed(cond),
+                                    boxed(target),
+                                    boxed(Node::Const(fall as u64, 64)),
+                                );
+                                Term::Indirect(target)
+                            }
+                        };
+                        return;
+                    }
+                    Stmt::Branch(cond, target) => {
+                        let mut cond = self.value(cond);
+                        self.flush(inst, idx, &mut [&mut cond]);
+                        if let Some(target) = target.as_const() {
+                            self.items.push(Item::Branch(addr, cond, target as PhysAddr));
+                        }
+                    }
+                    Stmt::Jump(target) => {
+                        let mut target = self.value(target);
+                        if let Node::Const(addr, _) = target {
+                            if !dec.by_addr.contains_key(&(addr as PhysAddr)) {
+                                let call = self.call_tokens(inst, idx, target);
+                                self.term = Term::TailCall(call);
+                                return;
+                            }
+                        }
+
+                        self.flush(inst, usize::MAX, &mut [&mut target]);
+                        self.term = match target {
+                            Node::Const(target, _) => Term::Goto(target as PhysAddr),
+                            target => Term::Indirect(target),
+                        };
+                        return;
+                    }
+                    Stmt::Return(_) => {
+                        let mut value = self.read(conv.ret);
+                        self.pending.retain(|(reg, ..)| *reg != conv.ret);
+                        self.flush(inst, usize::MAX, &mut [&mut value]);
+                        self.term = Term::Return(dec.returns.then_some(value));
+                        return;
+                    }
+                }
+            }
+        }
+
+        self.flush(range.end - 1, usize::MAX, &mut []);
+        self.term = Term::Fall(dec.next_addr(range.end - 1));
+    }
+}
+
+fn mask(bits: u8) -> u64 {
+    decoder::ir::mask(bits)
+}
+
+/// Width of the value stored by statement `idx`.
+fn value_bits(stmts: &[Stmt], idx: usize) -> u8 {
+    match &stmts[idx] {
+        Stmt::Store(_, value) => value.bits(),
+        _ => 64,
+    }
+}
+
+/// Recovers control flow structures from the blocks.
+struct Structurer<'d, 'a> {
+    dec: &'d Decompiler<'a>,
+    lines: Vec<Line>,
+    /// Blocks that are the target of a `goto`.
+    labels: BTreeSet<usize>,
+    /// Line each block starts at, with its depth.
+    starts: HashMap<usize, (usize, usize)>,
+    /// Loops being emitted, as the block `continue` goes to and the block `break` goes to.
+    loops: Vec<(usize, usize)>,
+}
+
+impl Structurer<'_, '_> {
+    fn line(&mut self, addr: PhysAddr, depth: usize, tokens: Vec<Token>) {
+        self.lines.push(Line {
+            addr,
+            depth,
+            tokens,
+        });
+    }
+
+    fn block_idx(&self, addr: PhysAddr) -> Option<usize> {
+        self.dec.by_addr.get(&addr).copied()
+    }
+
+    /// Statement that continues at `target`.
+    fn jump(&mut self, target: PhysAddr) -> Vec<Token> {
+        let idx = match self.block_idx(target) {
+            Some(idx) => idx,
+            // jumps out of the function are tail calls
+            None => {
+                let mut tokens = vec![token("return ", colors::MAGENTA)];
+                tokens.push(Token::from_string(self.dec.jump_name(target), colors::BLUE));
+                tokens.push(token("();", colors::GRAY99));
+                return tokens;
+            }
+        };
+
+        if let Some(&(header, exit)) = self.loops.last() {
+            if idx == header {
+                return vec![
+                    token("continue", colors::MAGENTA),
+                    token(";", colors::GRAY99),
+                ];
+            }
+
+            if idx == exit {
+                return vec![token("break", colors::MAGENTA), token(";", colors::GRAY99)];
+            }
+        }
+
+        self.labels.insert(idx);
+        vec![
+            token("goto ", colors::MAGENTA),
+            Token::from_string(label(self.dec.blocks[idx].addr), colors::GRAY60),
+            token(";", colors::GRAY99),
+        ]
+    }
+
+    fn cond(&self, keyword: &'static str, cond: &Node, suffix: &'static str) -> Vec<Token> {
+        let mut tokens = vec![token(keyword, colors::MAGENTA), token("(", colors::GRAY99)];
+        self.dec.tokens(cond, &mut tokens);
+        tokens.push(token(suffix, colors::GRAY99));
+        tokens
+    }
+
+    fn close(&mut self, addr: PhysAddr, depth: usize) {
+        self.line(addr, depth, vec![token("}", colors::GRAY99)]);
+    }
+
+    /// Back edge to `idx` from the furthest block before `to`.
+    fn latch(&self, idx: usize, to: usize) -> Option<usize> {
+        let header = self.dec.blocks[idx].addr;
+        (idx..to).rev().find(|&latch| {
+            let block = &self.dec.blocks[latch];
+            let branches = block
+                .items
+                .iter()
+                .any(|item| matches!(item, Item::Branch(_, _, target) if *target == header));
+
+            branches
+                || match block.term {
+                    Term::Goto(target) | Term::Cond(_, target, _) => target == header,
+                    _ => false,
+                }
+        })
+    }
+
+    fn items(&mut self, idx: usize, depth: usize) {
+        let dec = self.dec;
+        for item in &dec.blocks[idx].items {
+            match item {
+                Item::Line(addr, tokens) => self.line(*addr, depth, tokens.clone()),
+                Item::Branch(addr, cond, target) => {
+                    let mut tokens = self.cond("if ", cond, ") ");
+                    tokens.extend(self.jump(*target));
+                    self.line(*addr, depth, tokens);
+                }
+            }
+        }
+    }
+
+    /// Emit blocks `from..to`, where the terminator of block `skip_term` is left out.
+    fn emit(
+        &mut self,
+        from: usize,
+        to: usize,
+        depth: usize,
+        header: Option<usize>,
+        skip_term: Option<usize>,
+    ) {
+        let dec = self.dec;
+        let mut idx = from;
+
+        while idx < to {
+            let block = &dec.blocks[idx];
+            self.starts.entry(idx).or_insert((self.lines.len(), depth));
+
+            if header != Some(idx) {
+                if let Some(latch) = self.latch(idx, to) {
+                    self.emit_loop(idx, latch, depth);
+                    idx = latch + 1;
+                    continue;
+                }
+
+                if let Some(cond) = self.while_loop(idx, to) {
+                    self.items(idx, depth);
+                    let (body, latch) = (idx + 1, cond);
+                    let Term::Cond(ref cond, ..) = dec.blocks[latch].term else {
+                        unreachable!()
+                    };
+                    self.line(block.last, depth, self.cond("while ", cond, ") {"));
+                    self.loops.push((latch, latch + 1));
+                    self.emit(body, latch, depth + 1, Some(body), None);
+                    self.loops.pop();
+                    self.close(dec.blocks[latch].last, depth);
+                    idx = latch + 1;
+                    continue;
+                }
+            }
+
+            self.items(idx, depth);
+
+            if skip_term == Some(idx) {
+                idx += 1;
+                continue;
+            }
+
+            let next = dec.blocks.get(idx + 1).map(|block| block.addr);
+            match &block.term {
+                Term::Fall(target) | Term::Goto(target) => {
+                    if Some(*target) != next {
+                        let tokens = self.jump(*target);
+                        self.line(block.last, depth, tokens);
+                    }
+                }
+                Term::Cond(cond, taken, fall) => {
+                    let taken_idx = self.block_idx(*taken);
+                    let in_loop = self.loops.last().is_some_and(|&(header, exit)| {
+                        taken_idx == Some(header) || taken_idx == Some(exit)
+                    });
+
+                    match taken_idx {
+                        Some(target)
+                            if !in_loop
+                                && Some(*fall) == next
+                                && target > idx + 1
+                                && target <= to =>
+                        {
+                            self.emit_if(idx, target, to, depth);
+                            idx = self.if_end(idx, target, to);
+                            continue;
+                        }
+                        _ => {
+                            let mut tokens = self.cond("if ", cond, ") ");
+                            tokens.extend(self.jump(*taken));
+                            self.line(block.last, depth, tokens);
+                            if Some(*fall) != next {
+                                let tokens = self.jump(*fall);
+                                self.line(block.last, depth, tokens);
+                            }
+                        }
+                    }
+                }
+                Term::Indirect(target) => {
+                    let mut tokens =
+                        vec![token("goto ", colors::MAGENTA), token("*", colors::GRAY99)];
+                    dec.tokens_prec(target, &mut tokens, 14);
+                    tokens.push(token(";", colors::GRAY99));
+                    self.line(block.last, depth, tokens);
+                }
+                Term::TailCall(call) => {
+                    let mut tokens = Vec::new();
+                    if dec.returns {
+                        tokens.push(token("return ", colors::MAGENTA));
+                    }
+                    tokens.extend(call.iter().cloned());
+                    tokens.push(token(";", colors::GRAY99));
+                    self.line(block.last, depth, tokens);
+                    if !dec.returns {
+                        let tokens =
+                            vec![token("return", colors::MAGENTA), token(";", colors::GRAY99)];
+                        self.line(block.last, depth, tokens);
+                    }
+                }
+                Term::Return(value) => {
+                    let mut tokens = vec![token("return", colors::MAGENTA)];
+                    if let Some(value) = value {
+                        tokens.push(token(" ", colors::WHITE));
+                        dec.tokens(value, &mut tokens);
+                    }
+                    tokens.push(token(";", colors::GRAY99));
+                    self.line(block.last, depth, tokens);
+                }
+            }
+
+            idx += 1;
+        }
+    }
+
+    /// Block a `goto` at the end of the `then` branch of a conditional at `idx` goes to,
+    /// if it's the end of an `else` branch.
+    fn else_end(&self, idx: usize, target: usize, to: usize) -> Option<usize> {
+        if target <= idx + 1 {
+            return None;
+        }
+
+        match self.dec.blocks[target - 1].term {
+            Term::Goto(join) => self.block_idx(join).filter(|&join| join > target && join <= to),
+            _ => None,
+        }
+    }
+
+    fn if_end(&self, idx: usize, target: usize, to: usize) -> usize {
+        self.else_end(idx, target, to).unwrap_or(target)
+    }
+
+    fn emit_if(&mut self, idx: usize, target: usize, to: usize, depth: usize) {
+        let dec = self.dec;
+        let block = &dec.blocks[idx];
+        let Term::Cond(ref cond, ..) = block.term else {
+            unreachable!()
+        };
+
+        // the `then` branch runs if the jump isn't taken
+        let cond = not(cond.clone());
+        self.line(block.last, depth, self.cond("if ", &cond, ") {"));
+
+        match self.else_end(idx, target, to) {
+            Some(join) => {
+                self.emit(idx + 1, target, depth + 1, None, Some(target - 1));
+                let addr = dec.blocks[target].addr;
+                self.line(
+                    addr,
+                    depth,
+                    vec![
+                        token("} ", colors::GRAY99),
+                        token("else", colors::MAGENTA),
+                        token(" {", colors::GRAY99),
+                    ],
+                );
+                self.emit(target, join, depth + 1, None, None);
+                self.close(dec.blocks[join - 1].last, depth);
+            }
+            None => {
+                self.emit(idx + 1, target, depth + 1, None, None);
+                self.close(dec.blocks[target - 1].last, depth);
+            }
+        }
+    }
+
+    fn emit_loop(&mut self, idx: usize, latch: usize, depth: usize) {
+        let dec = self.dec;
+        let header = &dec.blocks[idx];
+        let end = &dec.blocks[latch];
+
+        match &end.term {
+            Term::Cond(cond, taken, _)
+                if *taken == header.addr
+                    && end.items.iter().all(|item| !matches!(item, Item::Branch(..))) =>
+            {
+                self.line(
+                    header.addr,
+                    depth,
+                    vec![token("do", colors::MAGENTA), token(" {", colors::GRAY99)],
+                );
+                self.loops.push((idx, latch + 1));
+                self.emit(idx, latch + 1, depth + 1, Some(idx), Some(latch));
+                self.loops.pop();
+                let mut tokens = vec![token("} ", colors::GRAY99)];
+                tokens.extend(self.cond("while ", cond, ");"));
+                self.line(end.last, depth, tokens);
+            }
+            _ => {
+                let skip = matches!(end.term, Term::Goto(target) if target == header.addr);
+                let mut tokens = vec![token("while ", colors::MAGENTA)];
+                tokens.push(token("(", colors::GRAY99));
+                tokens.push(token("1", colors::GREEN));
+                tokens.push(token(") {", colors::GRAY99));
+                self.line(header.addr, depth, tokens);
+                self.loops.push((idx, latch + 1));
+                self.emit(idx, latch + 1, depth + 1, Some(idx), skip.then_some(latch));
+                self.loops.pop();
+                self.close(end.last, depth);
+            }
+        }
+    }
+
+    /// Loop whose condition is checked at the end, but is entered by jumping to the condition.
+    fn while_loop(&self, idx: usize, to: usize) -> Option<usize> {
+        let dec = self.dec;
+        let Term::Goto(target) = dec.blocks[idx].term else {
+            return None;
+        };
+
+        let cond = self.block_idx(target)?;
+        let block = &dec.blocks[cond];
+        let loops_back = matches!(block.term, Term::Cond(_, taken, _)
+            if self.block_idx(taken) == Some(idx + 1));
+
+        let shape = cond > idx + 1 && cond < to && block.items.is_empty() && loops_back;
+        (shape && self.latch(idx + 1, to) == Some(cond)).then_some(cond)
+    }
+
+    /// Insert the labels of blocks that are jumped to.
+    fn insert_labels(&mut self) {
+        let mut labels: Vec<_> = self
+            .labels
+            .iter()
+            .filter_map(|idx| {
+                self.starts
+                    .get(idx)
+                    .map(|&(line, depth)| (line, depth, self.dec.blocks[*idx].addr))
+            })
+            .collect();
+
+        labels.sort_by_key(|(line, ..)| std::cmp::Reverse(*line));
+        for (line, depth, addr) in labels {
+            let tokens = vec![Token::from_string(
+                format!("{}:", label(addr)),
+                colors::GRAY60,
+            )];
+            self.lines.insert(
+                line,
+                Line {
+                    addr,
+                    depth: depth.saturating_sub(1),
+                    tokens,
+                },
+            );
+        }
+    }
+}
+
+fn label(addr: PhysAddr) -> String {
+    format!("label_{addr:x}")
+}
+
+impl Processor {
+    fn convention(&self) -> Option<&'static Convention> {
+        Some(match self.arch {
+            // only PE's have a `.pdata` section
+            Architecture::X86_64 if self.section_by_name(".pdata").is_some() => &WIN64,
+            Architecture::X86_64 => &SYSV,
+            Architecture::Aarch64 => &AAPCS64,
+            Architecture::Riscv32 | Architecture::Riscv64 => &RISCV,
+            _ => return None,
+        })
+    }
+
+    /// Name of the function at `addr`, preferring it over the section's name.
+    fn function_name(&self, addr: PhysAddr) -> Option<&str> {
+        let syms = &self.index.syms;
+        let idx = syms.search(addr).ok()?;
+        let first = syms[..idx].iter().rposition(|sym| sym.addr != addr).map_or(0, |idx| idx + 1);
+        let names = syms[first..].iter().take_while(|sym| sym.addr == addr);
+        let names: Vec<&str> = names.map(|sym| sym.item.as_str()).collect();
+        names.iter().find(|name| !name.starts_with('.')).or(names.first()).copied()
+    }
+
+    /// Address of the next function after `start`, or the end of the section.
+    fn function_end(&self, start: PhysAddr) -> PhysAddr {
+        let section_end = self.section_by_addr(start).map_or(start, |section| section.end);
+        let next = match self.index.syms.search(start) {
+            Ok(idx) => idx + 1,
+            Err(idx) => idx,
+        };
+
+        let next = self.index.syms[next..].iter().find(|sym| !sym.item.intrinsic());
+        let end = next.map_or(section_end, |sym| sym.addr.min(section_end));
+        end.min(start + MAX_FUNCTION_SIZE)
+    }
+
+    /// Decompile the function containing `addr` to pseudo-C.
+    pub fn decompile(&self, addr: PhysAddr) -> Option<Decompiled> {
+        let conv = self.convention()?;
+        let (_, offset) = self.index.get_sym_containing_addr(addr)?;
+        let start = addr - offset;
+
+        let mut dec = Decompiler::new(self, conv, start);
+        if dec.blocks.is_empty() {
+            return None;
+        }
+
+        dec.render_blocks();
+
+        let params: Vec<&Variable> =
+            self.index.get_vars_by_addr(start).iter().filter(|var| var.param).collect();
+        let name = self.function_name(start).unwrap_or_default().to_string();
+        let mut signature = vec![Token::from_string(name, colors::BLUE)];
+        signature.push(token("(", colors::GRAY99));
+        for (idx, param) in params.iter().enumerate() {
+            if idx != 0 {
+                signature.push(token(", ", colors::GRAY99));
+            }
+            signature.push(Token::from_string(param.name.clone(), colors::WHITE));
+        }
+        signature.push(token(") {", colors::GRAY99));
+
+        let mut structurer = Structurer {
+            dec: &dec,
+            lines: vec![Line {
+                addr: start,
+                depth: 0,
+                tokens: signature,
+            }],
+            labels: BTreeSet::new(),
+            starts: HashMap::new(),
+            loops: Vec::new(),
+        };
+
+        structurer.emit(0, dec.blocks.len(), 1, None, None);
+        structurer.insert_labels();
+
+        let last = dec.insts.len() - 1;
+        let end = dec.next_addr(last);
+        let mut lines = structurer.lines;
+        lines.push(Line {
+            addr: dec.insts[last].0,
+            depth: 0,
+            tokens: vec![token("}", colors::GRAY99)],
+        });
+
+        Some(Decompiled { start, end, lines })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use assembler::Arch;
+
+    /// Assemble `src` as a raw image and decompile the function `funcs[0]`.
+    ///
+    /// The labels in `funcs` are the starts of functions.
+    fn decompile(arch: Arch, src: &str, funcs: &[&str]) -> String {
+        let (mut processor, labels) = testing::assemble(arch, src, funcs[0]);
+
+        let mut funcs: Vec<(PhysAddr, &str)> =
+            funcs.iter().map(|name| (labels[*name], *name)).collect();
+        let (start, _) = funcs[0];
+        funcs.sort();
+        for (addr, name) in funcs {
+            processor.index.insert_func(addr, name);
+        }
+
+        let decompiled = processor.decompile(start).unwrap();
+        let mut text = String::new();
+        for line in decompiled.lines {
+            text += &"    ".repeat(line.depth);
+            text.extend(line.tokens.iter().map(|token| &token.text[..]));
+            text += "\n";
+        }
+
+        text
+    }
+
+    #[test]
+    fn x86_64_if_else() {
+        let src = "
+            f:
+                cmp edi, 10
+                jle small
+                mov eax, 1
+                jmp done
+            small:
+                mov eax, 2
+            done:
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::X86_64, src, &["f"]),
+            "f() {
+    if (rdi > 0xa) {
+        rax = 1;
+    } else {
+        rax = 2;
+    }
+    return rax;
+}
+"
+        );
+    }
+
+    #[test]
+    fn x86_64_early_return() {
+        let src = "
+            f:
+                test rdi, rdi
+                je null
+                mov eax, dword ptr [rdi]
+                ret
+            null:
+                mov eax, 0xffffffff
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::X86_64, src, &["f"]),
+            "f() {
+    if (rdi != 0) {
+        return *(uint32_t *)rdi;
+    }
+    return 0xffffffff;
+}
+"
+        );
+    }
+
+    #[test]
+    fn x86_64_while_loop() {
+        let src = "
+            sum:
+                xor eax, eax
+                xor ecx, ecx
+            cond:
+                cmp ecx, edi
+                jge done
+                add eax, ecx
+                inc ecx
+                jmp cond
+            done:
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::X86_64, src, &["sum"]),
+            "sum() {
+    rax = 0;
+    rcx = 0;
+    while (1) {
+        if (rdi <= rcx) break;
+        rax = rax + rcx;
+        rcx = rcx + 1;
+    }
+    return rax;
+}
+"
+        );
+    }
+
+    #[test]
+    fn x86_64_do_while_loop() {
+        let src = "
+            f:
+                xor eax, eax
+            body:
+                add eax, edi
+                dec edi
+                jne body
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::X86_64, src, &["f"]),
+            "f() {
+    rax = 0;
+    do {
+        rax = rax + rdi;
+        cond = rdi != 1;
+        rdi = rdi - 1;
+    } while (cond);
+    return rax;
+}
+"
+        );
+    }
+
+    #[test]
+    fn x86_64_call() {
+        let src = "
+            f:
+                push rbx
+                mov ebx, edi
+                mov edi, 1
+                call g
+                add eax, ebx
+                pop rbx
+                ret
+            g:
+                lea eax, [rdi + rdi]
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::X86_64, src, &["f", "g"]),
+            "f() {
+    rbx = rdi;
+    rax = g(1);
+    return rax + rbx;
+}
+"
+        );
+    }
+
+    #[test]
+    fn x86_64_irreducible() {
+        // the loop is entered both at its start and in its middle
+        let src = "
+            f:
+                test edi, edi
+                je second
+            first:
+                add esi, 1
+            second:
+                add esi, 2
+                cmp esi, 100
+                jl first
+                mov eax, esi
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::X86_64, src, &["f"]),
+            "f() {
+    if (rdi != 0) {
+    label_1004:
+        rsi = rsi + 1;
+    }
+    rsi = rsi + 2;
+    if (rsi < 0x64) goto label_1004;
+    return rsi;
+}
+"
+        );
+    }
+
+    #[test]
+    fn aarch64_if_else() {
+        let src = "
+            f:
+                cmp w0, #10
+                b.le small
+                mov w0, #1
+                b done
+            small:
+                mov w0, #2
+            done:
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::Aarch64, src, &["f"]),
+            "f() {
+    if (x0 > 0xa) {
+        x0 = 1;
+    } else {
+        x0 = 2;
+    }
+    return x0;
+}
+"
+        );
+    }
+
+    #[test]
+    fn aarch64_early_return() {
+        let src = "
+            f:
+                cbz x0, null
+                ldr w0, [x0]
+                ret
+            null:
+                mov w0, #-1
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::Aarch64, src, &["f"]),
+            "f() {
+    if (x0 != 0) {
+        return *(uint32_t *)x0;
+    }
+    return 0xffffffff;
+}
+"
+        );
+    }
+
+    #[test]
+    fn aarch64_loop() {
+        let src = "
+            sum:
+                mov w8, wzr
+                mov w9, wzr
+            cond:
+                cmp w9, w0
+                b.ge done
+                add w8, w8, w9
+                add w9, w9, #1
+                b cond
+            done:
+                mov w0, w8
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::Aarch64, src, &["sum"]),
+            "sum() {
+    x8 = 0;
+    x9 = 0;
+    while (1) {
+        if (x0 <= x9) break;
+        x8 = x8 + x9;
+        x9 = x9 + 1;
+    }
+    return x8;
+}
+"
+        );
+    }
+
+    #[test]
+    fn aarch64_call() {
+        let src = "
+            f:
+                stp x29, x30, [sp, #-16]!
+                mov x29, sp
+                add w0, w0, #1
+                bl g
+                add w0, w0, #2
+                ldp x29, x30, [sp], #16
+                ret
+            g:
+                lsl w0, w0, #1
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::Aarch64, src, &["f", "g"]),
+            "f() {
+    x0 = g(x0 + 1);
+    return x0 + 2;
+}
+"
+        );
+    }
+
+    #[test]
+    fn aarch64_irreducible() {
+        let src = "
+            f:
+                cbz w0, second
+            first:
+                add w1, w1, #1
+            second:
+                add w1, w1, #2
+                cmp w1, #100
+                b.lt first
+                mov w0, w1
+                ret
+        ";
+
+        assert_eq!(
+            decompile(Arch::Aarch64, src, &["f"]),
+            "f() {
+    if (x0 != 0) {
+    label_1004:
+        x1 = x1 + 1;
+    }
+    x1 = x1 + 2;
+    if (x1 < 0x64) goto label_1004;
+    return x1;
+}
+"
+        );
+    }
+}
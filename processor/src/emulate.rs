@@ -0,0 +1,614 @@
+//! Emulating instructions by interpreting what they lift to.
+//!
+//! Memory is backed by the binary's sections and a zeroed stack, writes are kept apart from
+//! the binary so nothing is modified. Calls are followed, emulation stops once the function
+//! it started in returns.
+
+use crate::Processor;
+use decoder::ir::{mask, sign_extend, Expr, Reg, Stmt, Value};
+use object::{Architecture, Endianness};
+use processor_shared::PhysAddr;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Address the stack grows down from.
+const STACK_TOP: PhysAddr = 0x7fff_0000_0000;
+
+/// Size of the stack, reading past it is an error.
+const STACK_SIZE: usize = 0x10_0000;
+
+/// Address of the thread pointer, thread-local storage is zeroed so stack canaries can be read.
+const TLS_BASE: PhysAddr = 0x7ffe_0000_0000;
+
+/// Size of thread-local storage on either side of the thread pointer, as it's below the
+/// thread pointer on x86 and above it on AArch64.
+const TLS_SIZE: usize = 0x1000;
+
+/// Return address of the function emulation started in.
+const RETURN_ADDR: PhysAddr = 0xffff_ffff_ffff_f000;
+
+const X86_64_REGS: &[Reg] = &[
+    "rax", "rbx", "rcx", "rdx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11", "r12", "r13",
+    "r14", "r15",
+];
+
+const X86_64_SYSTEM: &[Reg] = &["fs_base", "gs_base"];
+
+const X86_64_FLAGS: &[Reg] = &["cf", "pf", "zf", "sf", "of", "df"];
+
+const AARCH64_REGS: &[Reg] = &[
+    "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13", "x14",
+    "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26", "x27",
+    "x28", "x29", "x30", "sp",
+];
+
+const AARCH64_SYSTEM: &[Reg] = &["tpidr_el0"];
+
+const AARCH64_FLAGS: &[Reg] = &["n", "z", "c", "v"];
+
+/// Why emulation stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Breakpoint(PhysAddr),
+    /// The instruction budget ran out.
+    Budget,
+    /// The function emulation started in returned.
+    Returned,
+    /// Instruction at an address that couldn't be decoded.
+    Undecodable(PhysAddr),
+    /// Instruction whose effects aren't known.
+    Unsupported(PhysAddr),
+    /// Instruction that interacts with the system, such as a syscall.
+    Intrinsic(PhysAddr, &'static str),
+    /// Read from memory that isn't part of the binary, the stack or thread-local storage.
+    Unmapped {
+        pc: PhysAddr,
+        addr: PhysAddr,
+    },
+    DivideByZero(PhysAddr),
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Breakpoint(addr) => write!(f, "breakpoint at {addr:#x}"),
+            Self::Budget => f.write_str("running out of instructions"),
+            Self::Returned => f.write_str("returning"),
+            Self::Undecodable(addr) => write!(f, "undecodable instruction at {addr:#x}"),
+            Self::Unsupported(addr) => write!(f, "unsupported instruction at {addr:#x}"),
+            Self::Intrinsic(addr, name) => write!(f, "{name} at {addr:#x}"),
+            Self::Unmapped { pc, addr } => {
+                write!(f, "reading unmapped memory at {addr:#x} from {pc:#x}")
+            }
+            Self::DivideByZero(addr) => write!(f, "division by zero at {addr:#x}"),
+        }
+    }
+}
+
+/// Store to memory made while emulating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Write {
+    /// Address of the instruction that stored.
+    pub pc: PhysAddr,
+    pub addr: PhysAddr,
+    pub value: u64,
+    /// Number of bytes stored.
+    pub len: usize,
+}
+
+pub struct Emulator<'p> {
+    processor: &'p Processor,
+    names: &'static [Reg],
+    /// Registers such as the thread pointer, shown after the others.
+    system: &'static [Reg],
+    flags: &'static [Reg],
+    registers: HashMap<Reg, u64>,
+    /// Bytes written, taking precedence over the binary's sections.
+    memory: HashMap<PhysAddr, u8>,
+    writes: Vec<Write>,
+    breakpoints: BTreeSet<PhysAddr>,
+    pc: PhysAddr,
+    /// Number of instructions executed.
+    steps: usize,
+}
+
+impl<'p> Emulator<'p> {
+    fn new(
+        processor: &'p Processor,
+        [names, system, flags]: [&'static [Reg]; 3],
+        pc: PhysAddr,
+    ) -> Self {
+        let mut emulator = Self {
+            processor,
+            names,
+            system,
+            flags,
+            registers: HashMap::new(),
+            memory: HashMap::new(),
+            writes: Vec::new(),
+            breakpoints: BTreeSet::new(),
+            pc,
+            steps: 0,
+        };
+
+        // calls push their return address on x86, others keep it in a register
+        match processor.arch {
+            Architecture::X86_64 => {
+                let sp = STACK_TOP - 8;
+                emulator.registers.insert("rsp", sp as u64);
+                emulator.registers.insert("fs_base", TLS_BASE as u64);
+                for (idx, byte) in RETURN_ADDR.to_le_bytes().into_iter().enumerate() {
+                    emulator.memory.insert(sp + idx, byte);
+                }
+            }
+            _ => {
+                emulator.registers.insert("sp", STACK_TOP as u64);
+                emulator.registers.insert("x30", RETURN_ADDR as u64);
+                emulator.registers.insert("tpidr_el0", TLS_BASE as u64);
+            }
+        }
+
+        emulator
+    }
+
+    /// Set a register to start with, returning whether the register exists.
+    pub fn set_register(&mut self, name: &str, value: u64) -> bool {
+        if matches!(name, "pc" | "rip") {
+            self.pc = value as PhysAddr;
+            return true;
+        }
+
+        let mut names = self.names.iter().chain(self.system).chain(self.flags);
+        match names.find(|reg| **reg == name) {
+            Some(reg) => {
+                self.registers.insert(reg, value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, addr: PhysAddr) {
+        self.breakpoints.insert(addr);
+    }
+
+    #[inline]
+    pub fn pc(&self) -> PhysAddr {
+        self.pc
+    }
+
+    /// Number of instructions executed so far.
+    #[inline]
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Stores made so far, in the order they happened.
+    #[inline]
+    pub fn writes(&self) -> &[Write] {
+        &self.writes
+    }
+
+    /// Value of each register, starting with the program counter.
+    pub fn registers(&self) -> Vec<(&'static str, u64)> {
+        let pc = match self.processor.arch {
+            Architecture::X86_64 => "rip",
+            _ => "pc",
+        };
+
+        let mut registers = vec![(pc, self.pc as u64)];
+        for reg in self.names.iter().chain(self.system) {
+            registers.push((reg, self.registers.get(reg).copied().unwrap_or(0)));
+        }
+
+        registers
+    }
+
+    /// Value of each flag, either 0 or 1.
+    pub fn flags(&self) -> Vec<(&'static str, u64)> {
+        let flag = |flag: &&'static str| (*flag, self.registers.get(flag).copied().unwrap_or(0));
+        self.flags.iter().map(flag).collect()
+    }
+
+    fn read_byte(&self, addr: PhysAddr) -> Option<u8> {
+        if let Some(byte) = self.memory.get(&addr) {
+            return Some(*byte);
+        }
+
+        if let Some(section) = self.processor.section_by_addr(addr) {
+            // sections such as .bss aren't backed by the file
+            return Some(section.bytes_by_addr(addr, 1).first().copied().unwrap_or(0));
+        }
+
+        let stack = STACK_TOP - STACK_SIZE..STACK_TOP;
+        let tls = TLS_BASE - TLS_SIZE..TLS_BASE + TLS_SIZE;
+        (stack.contains(&addr) || tls.contains(&addr)).then_some(0)
+    }
+
+    fn read(&self, addr: PhysAddr, len: usize) -> Result<u64, Stop> {
+        let mut bytes = [0u8; 8];
+        for (idx, byte) in bytes[..len].iter_mut().enumerate() {
+            let addr = addr.wrapping_add(idx);
+            *byte = self.read_byte(addr).ok_or(Stop::Unmapped { pc: self.pc, addr })?;
+        }
+
+        Ok(match self.processor.endianness() {
+            Endianness::Little => u64::from_le_bytes(bytes),
+            Endianness::Big => u64::from_be_bytes(bytes) >> (64 - len * 8),
+        })
+    }
+
+    fn write(&mut self, addr: PhysAddr, value: u64, len: usize) {
+        let bytes = match self.processor.endianness() {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => (value << (64 - len * 8)).to_be_bytes(),
+        };
+
+        for (idx, byte) in bytes[..len].iter().enumerate() {
+            self.memory.insert(addr.wrapping_add(idx), *byte);
+        }
+
+        self.writes.push(Write {
+            pc: self.pc,
+            addr,
+            value,
+            len,
+        });
+    }
+
+    /// Execute a single instruction.
+    fn step(&mut self) -> Result<(), Stop> {
+        let stmts = match self.processor.lift(self.pc) {
+            Some(stmts) => stmts,
+            None => return Err(Stop::Undecodable(self.pc)),
+        };
+
+        let inst = self.processor.instruction_by_addr(self.pc).unwrap();
+        let mut next = self.pc + self.processor.instruction_width(inst);
+        let mut temps: HashMap<u32, u64> = HashMap::new();
+        let value = |temps: &HashMap<u32, u64>, value: &Value| match value {
+            Value::Const { value, .. } => *value,
+            Value::Temp(temp) => temps.get(&temp.id).copied().unwrap_or(0),
+        };
+
+        for stmt in &stmts {
+            match stmt {
+                Stmt::Assign(temp, expr) => {
+                    let bits = temp.bits;
+                    let result = match expr {
+                        Expr::Reg(reg) => self.registers.get(reg).copied().unwrap_or(0),
+                        Expr::Load(addr) => {
+                            if bits > 64 {
+                                return Err(Stop::Unsupported(self.pc));
+                            }
+
+                            let len = (bits as usize).div_ceil(8);
+                            self.read(value(&temps, addr) as PhysAddr, len)?
+                        }
+                        Expr::Unary(op, a) => op.eval(value(&temps, a), a.bits()),
+                        Expr::Binary(op, a, b) => {
+                            let (lhs, rhs) = (value(&temps, a), value(&temps, b));
+                            match op.eval(lhs, rhs, a.bits()) {
+                                Some(result) => result,
+                                None => return Err(Stop::DivideByZero(self.pc)),
+                            }
+                        }
+                        Expr::Compare(cmp, a, b) => {
+                            cmp.eval(value(&temps, a), value(&temps, b), a.bits()) as u64
+                        }
+                        Expr::ZeroExtend(a) => value(&temps, a) & mask(a.bits()),
+                        Expr::SignExtend(a) => sign_extend(value(&temps, a), a.bits()) as u64,
+                        Expr::Truncate(a) => value(&temps, a),
+                        Expr::Select(cond, a, b) => match value(&temps, cond) & 1 {
+                            0 => value(&temps, b),
+                            _ => value(&temps, a),
+                        },
+                    };
+
+                    temps.insert(temp.id, result & mask(bits));
+                }
+                Stmt::SetReg(reg, val) => {
+                    let val = value(&temps, val) & mask(val.bits());
+                    self.registers.insert(reg, val);
+                }
+                Stmt::Store(addr, val) => {
+                    if val.bits() > 64 {
+                        return Err(Stop::Unsupported(self.pc));
+                    }
+
+                    let len = (val.bits() as usize).div_ceil(8);
+                    self.write(value(&temps, addr) as PhysAddr, value(&temps, val), len);
+                }
+                Stmt::Jump(target) | Stmt::Call(target) | Stmt::Return(target) => {
+                    next = value(&temps, target) as PhysAddr;
+                    break;
+                }
+                Stmt::Branch(cond, target) => {
+                    if value(&temps, cond) & 1 == 1 {
+                        next = value(&temps, target) as PhysAddr;
+                        break;
+                    }
+                }
+                // memory is only accessed by a single thread
+                Stmt::Intrinsic("fence", _) => {}
+                Stmt::Intrinsic(name, _) => return Err(Stop::Intrinsic(self.pc, name)),
+                Stmt::Unsupported => return Err(Stop::Unsupported(self.pc)),
+            }
+        }
+
+        self.pc = next;
+        self.steps += 1;
+        Ok(())
+    }
+
+    /// Execute at most `budget` instructions, stopping early at a breakpoint or once the
+    /// function emulation started in returns.
+    ///
+    /// Breakpoints at the current instruction are stepped over, so emulation can resume.
+    pub fn run(&mut self, budget: usize) -> Stop {
+        for idx in 0..budget {
+            if self.pc == RETURN_ADDR {
+                return Stop::Returned;
+            }
+
+            if idx != 0 && self.breakpoints.contains(&self.pc) {
+                return Stop::Breakpoint(self.pc);
+            }
+
+            if let Err(stop) = self.step() {
+                return stop;
+            }
+        }
+
+        match self.pc {
+            RETURN_ADDR => Stop::Returned,
+            _ => Stop::Budget,
+        }
+    }
+}
+
+impl Processor {
+    /// Emulator that starts executing at `pc`, if the architecture is supported.
+    pub fn emulator(&self, pc: PhysAddr) -> Option<Emulator<'_>> {
+        let registers = match self.arch {
+            Architecture::X86_64 => [X86_64_REGS, X86_64_SYSTEM, X86_64_FLAGS],
+            Architecture::Aarch64 => [AARCH64_REGS, AARCH64_SYSTEM, AARCH64_FLAGS],
+            _ => return None,
+        };
+
+        Some(Emulator::new(self, registers, pc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, BASE};
+    use assembler::Arch;
+
+    fn register(emulator: &Emulator, name: &str) -> u64 {
+        let mut registers = emulator.registers().into_iter().chain(emulator.flags());
+        registers.find(|(reg, _)| *reg == name).unwrap().1
+    }
+
+    #[test]
+    fn x86_64_flags() {
+        let src = "
+            mov eax, -0x1
+            add eax, 0x1
+            mov ecx, 0x7fffffff
+            add ecx, 0x1
+            ret
+        ";
+
+        let (processor, _) = testing::assemble(Arch::X86_64, src, "flags");
+        let mut emulator = processor.emulator(BASE).unwrap();
+        assert_eq!(emulator.run(4), Stop::Budget);
+        assert_eq!(register(&emulator, "rcx"), 0x8000_0000);
+        assert_eq!(register(&emulator, "of"), 1);
+        assert_eq!(register(&emulator, "sf"), 1);
+        assert_eq!(register(&emulator, "zf"), 0);
+        assert_eq!(register(&emulator, "cf"), 0);
+
+        let mut emulator = processor.emulator(BASE).unwrap();
+        assert_eq!(emulator.run(2), Stop::Budget);
+        assert_eq!(register(&emulator, "rax"), 0);
+        assert_eq!(register(&emulator, "cf"), 1);
+        assert_eq!(register(&emulator, "zf"), 1);
+        assert_eq!(register(&emulator, "of"), 0);
+    }
+
+    #[test]
+    fn x86_64_load_store() {
+        let src = "
+            mov qword [rsp - 0x8], 0x1234
+            mov rax, qword [rsp - 0x8]
+            add rax, 0x1
+            mov dword [rdi], eax
+            ret
+        ";
+
+        let (processor, _) = testing::assemble(Arch::X86_64, src, "load_store");
+        let mut emulator = processor.emulator(BASE).unwrap();
+        let buf = STACK_TOP - 0x100;
+        assert!(emulator.set_register("rdi", buf as u64));
+        assert_eq!(emulator.run(100), Stop::Returned);
+        assert_eq!(register(&emulator, "rax"), 0x1235);
+        assert_eq!(emulator.read(buf, 4), Ok(0x1235));
+
+        let stores: Vec<_> = emulator.writes().iter().map(|w| (w.addr, w.value, w.len)).collect();
+        assert_eq!(stores, [(STACK_TOP - 16, 0x1234, 8), (buf, 0x1235, 4)]);
+    }
+
+    #[test]
+    fn x86_64_loop() {
+        let src = "
+            xor eax, eax
+            mov ecx, 0xa
+            next:
+            add eax, ecx
+            dec ecx
+            jne next
+            ret
+        ";
+
+        let (processor, _) = testing::assemble(Arch::X86_64, src, "loop");
+        let mut emulator = processor.emulator(BASE).unwrap();
+        assert_eq!(emulator.run(100), Stop::Returned);
+        assert_eq!(register(&emulator, "rax"), 55);
+        assert_eq!(register(&emulator, "rcx"), 0);
+        assert_eq!(emulator.steps(), 33);
+    }
+
+    #[test]
+    fn x86_64_budget() {
+        let src = "
+            xor eax, eax
+            mov ecx, 0xa
+            next:
+            add eax, ecx
+            dec ecx
+            jne next
+            ret
+        ";
+
+        let (processor, _) = testing::assemble(Arch::X86_64, src, "budget");
+        let mut emulator = processor.emulator(BASE).unwrap();
+        assert_eq!(emulator.run(5), Stop::Budget);
+        assert_eq!(emulator.steps(), 5);
+        assert_eq!(register(&emulator, "rax"), 10);
+
+        // running out of instructions can be resumed from
+        assert_eq!(emulator.run(100), Stop::Returned);
+        assert_eq!(register(&emulator, "rax"), 55);
+        assert_eq!(emulator.steps(), 33);
+    }
+
+    #[test]
+    fn x86_64_tls() {
+        let src = "
+            mov rax, qword fs:[0x28]
+            mov qword fs:[0x30], rax
+            ret
+        ";
+
+        let (processor, _) = testing::assemble(Arch::X86_64, src, "tls");
+        let mut emulator = processor.emulator(BASE).unwrap();
+        assert_eq!(emulator.run(100), Stop::Returned);
+        assert_eq!(emulator.writes()[0].addr, TLS_BASE + 0x30);
+
+        let mut emulator = processor.emulator(BASE).unwrap();
+        assert!(emulator.set_register("fs_base", 0x10));
+        assert_eq!(register(&emulator, "fs_base"), 0x10);
+        assert_eq!(
+            emulator.run(100),
+            Stop::Unmapped {
+                pc: BASE,
+                addr: 0x38
+            }
+        );
+    }
+
+    #[test]
+    fn aarch64_flags() {
+        let src = "
+            adds w2, w3, #0x1
+            subs x1, x4, #0x1
+            ret
+        ";
+
+        let (processor, _) = testing::assemble(Arch::Aarch64, src, "flags");
+        let mut emulator = processor.emulator(BASE).unwrap();
+        assert!(emulator.set_register("x3", 0xffff_ffff));
+        assert_eq!(emulator.run(1), Stop::Budget);
+        assert_eq!(register(&emulator, "x2"), 0);
+        assert_eq!(register(&emulator, "z"), 1);
+        assert_eq!(register(&emulator, "c"), 1);
+        assert_eq!(register(&emulator, "n"), 0);
+        assert_eq!(register(&emulator, "v"), 0);
+
+        assert_eq!(emulator.run(100), Stop::Returned);
+        assert_eq!(register(&emulator, "x1"), u64::MAX);
+        assert_eq!(register(&emulator, "z"), 0);
+        assert_eq!(register(&emulator, "c"), 0);
+        assert_eq!(register(&emulator, "n"), 1);
+        assert_eq!(register(&emulator, "v"), 0);
+    }
+
+    #[test]
+    fn aarch64_load_store() {
+        let src = "
+            sub sp, sp, #0x10
+            str x0, [sp, #0x8]
+            ldr x1, [sp, #0x8]
+            add x1, x1, #0x1
+            str w1, [x2]
+            add sp, sp, #0x10
+            ret
+        ";
+
+        let (processor, _) = testing::assemble(Arch::Aarch64, src, "load_store");
+        let mut emulator = processor.emulator(BASE).unwrap();
+        let buf = STACK_TOP - 0x100;
+        assert!(emulator.set_register("x0", 0x1234));
+        assert!(emulator.set_register("x2", buf as u64));
+        assert_eq!(emulator.run(100), Stop::Returned);
+        assert_eq!(register(&emulator, "x1"), 0x1235);
+        assert_eq!(register(&emulator, "sp"), STACK_TOP as u64);
+        assert_eq!(emulator.read(buf, 4), Ok(0x1235));
+
+        let stores: Vec<_> = emulator.writes().iter().map(|w| (w.addr, w.value, w.len)).collect();
+        assert_eq!(stores, [(STACK_TOP - 8, 0x1234, 8), (buf, 0x1235, 4)]);
+    }
+
+    #[test]
+    fn aarch64_loop() {
+        let src = "
+            mov x0, xzr
+            mov x1, #0xa
+            next:
+            add x0, x0, x1
+            subs x1, x1, #0x1
+            b.ne next
+            ret
+        ";
+
+        let (processor, _) = testing::assemble(Arch::Aarch64, src, "loop");
+        let mut emulator = processor.emulator(BASE).unwrap();
+        assert_eq!(emulator.run(5), Stop::Budget);
+        assert_eq!(emulator.steps(), 5);
+        assert_eq!(register(&emulator, "x0"), 10);
+
+        assert_eq!(emulator.run(100), Stop::Returned);
+        assert_eq!(register(&emulator, "x0"), 55);
+        assert_eq!(emulator.steps(), 33);
+    }
+
+    #[test]
+    fn aarch64_tls() {
+        // mrs x0, tpidr_el0
+        let src = "
+            .inst 0xd53bd040
+            ldr x1, [x0, #0x28]
+            str x1, [x0, #0x10]
+            ret
+        ";
+
+        let (processor, _) = testing::assemble(Arch::Aarch64, src, "tls");
+        let mut emulator = processor.emulator(BASE).unwrap();
+        assert_eq!(emulator.run(100), Stop::Returned);
+        assert_eq!(register(&emulator, "x0"), TLS_BASE as u64);
+        assert_eq!(emulator.writes()[0].addr, TLS_BASE + 0x10);
+
+        let mut emulator = processor.emulator(BASE).unwrap();
+        assert!(emulator.set_register("tpidr_el0", 0x10));
+        assert_eq!(register(&emulator, "tpidr_el0"), 0x10);
+        assert_eq!(
+            emulator.run(100),
+            Stop::Unmapped {
+                pc: BASE + 4,
+                addr: 0x38
+            }
+        );
+    }
+}
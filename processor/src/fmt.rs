@@ -15,6 +15,25 @@ impl fmt::Debug for super::Error {
             Self::UnknownArchitecture(arch) => {
                 f.write_fmt(format_args!("Unsupported architecture: '{arch:?}'."))
             }
+            Self::UnknownRawArchitecture(arch) => {
+                f.write_fmt(format_args!("Unsupported raw image architecture: '{arch}'."))
+            }
+        }
+    }
+}
+
+impl Display for super::PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unmapped(addr) => {
+                f.write_fmt(format_args!("Address {addr:#x} isn't backed by the file."))
+            }
+            Self::UnsupportedArchitecture(arch) => {
+                f.write_fmt(format_args!("Can't assemble instructions for '{arch:?}'."))
+            }
+            Self::Assembler(err) => f.write_fmt(format_args!("Failed to assemble: {err}.")),
+            Self::OverwritesBinary => f.write_str("Can't overwrite the loaded binary."),
+            Self::IO(err) => f.write_fmt(format_args!("{err}.")),
         }
     }
 }
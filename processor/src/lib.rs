@@ -1,14 +1,23 @@
 mod fmt;
 mod blocks;
+mod lift;
+mod decompile;
+mod emulate;
+mod live;
+mod patch;
+mod uarch;
+#[cfg(test)]
+mod testing;
 
-use decoder::{Decodable, Decoded};
-use object::{Endianness, Object, ObjectSegment};
+use decoder::{Decodable, Decoded, Dialect};
+use object::{Endianness, Object, ObjectSegment, ObjectSymbol, SymbolFlags, SymbolKind};
+use object::{ObjectSection, RelocationTarget, SectionIndex};
 use object::{Architecture, BinaryFormat};
 use object::read::File as ObjectFile;
 use processor_shared::{AddressMap, Addressed, PhysAddr, Section, SectionKind, Segment};
 use debugvault::Index;
 use tokenizing::Token;
-use binformat::{elf, macho, pe, RawSymbol};
+use binformat::{apk, dex, elf, macho, pe, RawSymbol};
 
 use memmap2::Mmap;
 use x86_64::long_mode as x64;
@@ -20,6 +29,10 @@ use std::fs::File;
 use std::mem::ManuallyDrop;
 
 pub use blocks::{BlockContent, Block};
+pub use decompile::{Decompiled, Line};
+pub use emulate::{Emulator, Stop, Write};
+pub use patch::{Patch, PatchError};
+pub use uarch::{ExtensionUsage, Uarch};
 
 /// FIXME: This is way too large and way too broad.
 ///        Especially since these are being started for any address with a faulty decoding.
@@ -30,6 +43,7 @@ pub enum Error {
     NotAnExecutable,
     DecompressionFailed(object::Error),
     UnknownArchitecture(object::Architecture),
+    UnknownRawArchitecture(String),
 }
 
 pub union Instruction {
@@ -37,12 +51,17 @@ pub union Instruction {
     x64: ManuallyDrop<x86_64::long_mode::Instruction>,
     riscv: ManuallyDrop<riscv::Instruction>,
     mips: ManuallyDrop<mips::Instruction>,
+    ppc: ManuallyDrop<ppc::Instruction>,
+    loongarch: ManuallyDrop<loongarch::Instruction>,
+    bpf: ManuallyDrop<bpf::Instruction>,
+    wasm: ManuallyDrop<wasm::Instruction>,
+    dalvik: ManuallyDrop<dalvik::Instruction>,
     armv7: ManuallyDrop<armv7::Instruction>,
     aarch64: ManuallyDrop<aarch64::Instruction>,
 }
 
 macro_rules! impl_recursion {
-    ($symbols:expr, $errors:expr, $instructions:expr, $sections:expr,
+    ($errors:expr, $instructions:expr, $sections:expr,
      $max_instruction_width:expr, $decoder:expr, $arch:ident) => {{
         $max_instruction_width = $decoder.max_width();
 
@@ -126,6 +145,684 @@ macro_rules! impl_recursion {
     }};
 }
 
+/// What the [`Section`]'s of a [`Processor`] borrow their bytes from.
+enum Backing {
+    /// A memory map of the binary and the file handle keeping it alive.
+    Mapped { _file: File, _mmap: Mmap },
+    /// Memory read out of a running process.
+    #[cfg(target_os = "linux")]
+    Live(live::Memory),
+    /// Files extracted from an archive, like the DEX files of an APK.
+    Extracted { _bytes: Vec<u8> },
+}
+
+/// Result of decoding all code sections of a given architecture.
+struct Disassembly {
+    instructions: AddressMap<Instruction>,
+    errors: AddressMap<decoder::Error>,
+    max_instruction_width: usize,
+    instruction_tokens: fn(&Instruction, &Index, Dialect) -> Vec<Token>,
+    instruction_width: fn(&Instruction) -> usize,
+}
+
+impl Disassembly {
+    fn decode(obj: &ObjectFile, sections: &[Section]) -> Result<Self, Error> {
+        let arch = obj.architecture();
+        let (instruction_tokens, instruction_width) = unsafe {
+            match arch {
+                Architecture::Riscv32 | Architecture::Riscv64 => (
+                    std::mem::transmute(<riscv::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<riscv::Instruction as Decoded>::width as usize),
+                ),
+                Architecture::Mips | Architecture::Mips64 => (
+                    std::mem::transmute(<mips::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<mips::Instruction as Decoded>::width as usize),
+                ),
+                Architecture::PowerPc | Architecture::PowerPc64 => (
+                    std::mem::transmute(<ppc::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<ppc::Instruction as Decoded>::width as usize),
+                ),
+                Architecture::LoongArch64 => (
+                    std::mem::transmute(<loongarch::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<loongarch::Instruction as Decoded>::width as usize),
+                ),
+                Architecture::Bpf => (
+                    std::mem::transmute(<bpf::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<bpf::Instruction as Decoded>::width as usize),
+                ),
+                Architecture::Wasm32 | Architecture::Wasm64 => (
+                    std::mem::transmute(<wasm::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<wasm::Instruction as Decoded>::width as usize),
+                ),
+                Architecture::X86_64_X32 | Architecture::I386 => (
+                    std::mem::transmute(<x86::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<x86::Instruction as Decoded>::width as usize),
+                ),
+                Architecture::X86_64 => (
+                    std::mem::transmute(<x64::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<x64::Instruction as Decoded>::width as usize),
+                ),
+                Architecture::Arm => (
+                    std::mem::transmute(<armv7::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<armv7::Instruction as Decoded>::width as usize),
+                ),
+                Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => (
+                    std::mem::transmute(<aarch64::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<aarch64::Instruction as Decoded>::width as usize),
+                ),
+                arch => return Err(Error::UnknownArchitecture(arch)),
+            }
+        };
+
+        let mut instructions = AddressMap::default();
+        let mut errors = AddressMap::default();
+        let max_instruction_width;
+
+        match arch {
+            Architecture::Riscv32 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    riscv::Decoder { is_64: false, extensions: riscv_extensions(sections) },
+                    riscv
+                )
+            }
+            Architecture::Riscv64 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    riscv::Decoder { is_64: true, extensions: riscv_extensions(sections) },
+                    riscv
+                )
+            }
+            Architecture::Mips | Architecture::Mips64 => {
+                let decoder = mips_decoder(obj);
+                let switches = mips_isa_switches(obj, decoder.isa);
+                let mut width = decoder.max_width();
+
+                for (isa, sections) in mips_regions(sections, &switches, decoder.isa) {
+                    impl_recursion!(
+                        &mut errors,
+                        &mut instructions,
+                        sections,
+                        width,
+                        mips::Decoder { isa, ..decoder },
+                        mips
+                    )
+                }
+
+                max_instruction_width = width;
+            }
+            Architecture::PowerPc | Architecture::PowerPc64 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    ppc::Decoder {
+                        is_64: arch == Architecture::PowerPc64,
+                        little_endian: obj.endianness() == Endianness::Little,
+                    },
+                    ppc
+                )
+            }
+            Architecture::LoongArch64 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    loongarch::Decoder,
+                    loongarch
+                )
+            }
+            Architecture::Bpf => {
+                let decoder = bpf_decoder(obj);
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    decoder,
+                    bpf
+                )
+            }
+            Architecture::Wasm32 | Architecture::Wasm64 => {
+                let module = binformat::wasm::Module::parse(obj);
+                let decoder = wasm_decoder(&module);
+
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    wasm_regions(sections, &module),
+                    max_instruction_width,
+                    decoder,
+                    wasm
+                )
+            }
+            Architecture::X86_64_X32 | Architecture::I386 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    x86::Decoder::default(),
+                    x86
+                )
+            }
+            Architecture::X86_64 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    x64::Decoder::default(),
+                    x64
+                )
+            }
+            Architecture::Arm => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    armv7::Decoder::default(),
+                    armv7
+                )
+            },
+            Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    aarch64::Decoder::default(),
+                    aarch64
+                )
+            }
+            _ => unreachable!(),
+        };
+
+        instructions.sort_unstable();
+        errors.sort_unstable();
+
+        if arch == Architecture::Bpf {
+            bpf_relocate(obj, &mut instructions);
+        }
+
+        Ok(Self {
+            instructions,
+            errors,
+            max_instruction_width,
+            instruction_tokens,
+            instruction_width,
+        })
+    }
+
+    /// Decode a raw image, which has no object to inspect for how its code is encoded.
+    fn decode_raw(
+        arch: Architecture,
+        real_mode: bool,
+        sections: &[Section],
+    ) -> Result<Self, Error> {
+        let mut instructions = AddressMap::default();
+        let mut errors = AddressMap::default();
+        let max_instruction_width;
+
+        let (instruction_tokens, instruction_width) = match arch {
+            Architecture::I386 => {
+                let decoder = if real_mode {
+                    x86::Decoder::default().with_real_mode()
+                } else {
+                    x86::Decoder::default()
+                };
+
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    decoder,
+                    x86
+                );
+                unsafe {
+                    (
+                        std::mem::transmute(<x86::Instruction as Decoded>::tokens as usize),
+                        std::mem::transmute(<x86::Instruction as Decoded>::width as usize),
+                    )
+                }
+            }
+            Architecture::X86_64 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    x64::Decoder::default(),
+                    x64
+                );
+                unsafe {
+                    (
+                        std::mem::transmute(<x64::Instruction as Decoded>::tokens as usize),
+                        std::mem::transmute(<x64::Instruction as Decoded>::width as usize),
+                    )
+                }
+            }
+            Architecture::Arm => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    armv7::Decoder::default(),
+                    armv7
+                );
+                unsafe {
+                    (
+                        std::mem::transmute(<armv7::Instruction as Decoded>::tokens as usize),
+                        std::mem::transmute(<armv7::Instruction as Decoded>::width as usize),
+                    )
+                }
+            }
+            Architecture::Aarch64 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    aarch64::Decoder::default(),
+                    aarch64
+                );
+                unsafe {
+                    (
+                        std::mem::transmute(<aarch64::Instruction as Decoded>::tokens as usize),
+                        std::mem::transmute(<aarch64::Instruction as Decoded>::width as usize),
+                    )
+                }
+            }
+            Architecture::Riscv32 | Architecture::Riscv64 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    riscv::Decoder {
+                        is_64: arch == Architecture::Riscv64,
+                        extensions: riscv_extensions(sections),
+                    },
+                    riscv
+                );
+                unsafe {
+                    (
+                        std::mem::transmute(<riscv::Instruction as Decoded>::tokens as usize),
+                        std::mem::transmute(<riscv::Instruction as Decoded>::width as usize),
+                    )
+                }
+            }
+            Architecture::LoongArch64 => {
+                impl_recursion!(
+                    &mut errors,
+                    &mut instructions,
+                    sections,
+                    max_instruction_width,
+                    loongarch::Decoder,
+                    loongarch
+                );
+                unsafe {
+                    (
+                        std::mem::transmute(<loongarch::Instruction as Decoded>::tokens as usize),
+                        std::mem::transmute(<loongarch::Instruction as Decoded>::width as usize),
+                    )
+                }
+            }
+            arch => return Err(Error::UnknownArchitecture(arch)),
+        };
+
+        instructions.sort_unstable();
+        errors.sort_unstable();
+
+        Ok(Self {
+            instructions,
+            errors,
+            max_instruction_width,
+            instruction_tokens,
+            instruction_width,
+        })
+    }
+}
+
+impl Disassembly {
+    /// Decode the methods of DEX files, which don't have an architecture known to `object`.
+    fn decode_dex(dexes: &[dex::Dex], sections: &[Section]) -> Result<Self, Error> {
+        let mut instructions = AddressMap::default();
+        let mut errors = AddressMap::default();
+        let mut max_instruction_width = dalvik::Decoder::default().max_width();
+
+        for dex in dexes {
+            impl_recursion!(
+                &mut errors,
+                &mut instructions,
+                dex_regions(sections, dex),
+                max_instruction_width,
+                dalvik_decoder(dex),
+                dalvik
+            )
+        }
+
+        instructions.sort_unstable();
+        errors.sort_unstable();
+
+        let (instruction_tokens, instruction_width) = unsafe {
+            (
+                std::mem::transmute(<dalvik::Instruction as Decoded>::tokens as usize),
+                std::mem::transmute(<dalvik::Instruction as Decoded>::width as usize),
+            )
+        };
+
+        Ok(Self {
+            instructions,
+            errors,
+            max_instruction_width,
+            instruction_tokens,
+            instruction_width,
+        })
+    }
+}
+
+/// Instruction set of a raw image by name and whether x86 code runs in real mode.
+fn raw_architecture(name: &str) -> Option<(Architecture, bool)> {
+    Some(match name {
+        "x86_16" | "8086" => (Architecture::I386, true),
+        "x86" | "i386" => (Architecture::I386, false),
+        "x86_64" | "amd64" => (Architecture::X86_64, false),
+        "arm" | "armv7" => (Architecture::Arm, false),
+        "aarch64" | "arm64" => (Architecture::Aarch64, false),
+        "riscv32" => (Architecture::Riscv32, false),
+        "riscv64" => (Architecture::Riscv64, false),
+        "loongarch64" => (Architecture::LoongArch64, false),
+        _ => return None,
+    })
+}
+
+/// Extensions recorded in the build attributes of a RISC-V binary, all of them if there are none.
+fn riscv_extensions(sections: &[Section]) -> riscv::Extensions {
+    let isa = sections
+        .iter()
+        .filter(|s| s.kind == SectionKind::RiscvAttributes)
+        .find_map(|s| elf::RiscvAttributes::new(s.bytes()).arch());
+
+    match isa.and_then(|isa| Some((isa, riscv::Extensions::from_isa(isa)?))) {
+        Some((isa, extensions)) => {
+            log::complex!(
+                w "[processor::decode] decoding extensions of ",
+                g isa,
+                w ".",
+            );
+            extensions
+        }
+        None => riscv::Extensions::default(),
+    }
+}
+
+/// MIPS decoder matching the word size, byte order, ISA revision and instruction set of the
+/// binary.
+fn mips_decoder(obj: &ObjectFile) -> mips::Decoder {
+    // the ISA revision is stored in the top four bits of the ELF flags
+    const EF_MIPS_ARCH: u32 = 0xf000_0000;
+    const EF_MIPS_ARCH_32R6: u32 = 0x9000_0000;
+    const EF_MIPS_ARCH_64R6: u32 = 0xa000_0000;
+    const EF_MIPS_ARCH_ASE_MICROMIPS: u32 = 0x0200_0000;
+    const EF_MIPS_ARCH_ASE_M16: u32 = 0x0400_0000;
+
+    let e_flags = match obj.flags() {
+        object::FileFlags::Elf { e_flags, .. } => e_flags,
+        _ => 0,
+    };
+
+    let is_r6 = matches!(
+        e_flags & EF_MIPS_ARCH,
+        EF_MIPS_ARCH_32R6 | EF_MIPS_ARCH_64R6
+    );
+
+    let isa = if e_flags & EF_MIPS_ARCH_ASE_MICROMIPS != 0 {
+        mips::Isa::MicroMips
+    } else if e_flags & EF_MIPS_ARCH_ASE_M16 != 0 {
+        mips::Isa::Mips16
+    } else {
+        mips::Isa::Mips
+    };
+
+    mips::Decoder {
+        is_64: obj.architecture() == Architecture::Mips64,
+        is_r6,
+        little_endian: obj.endianness() == Endianness::Little,
+        isa,
+    }
+}
+
+/// eBPF decoder matching the byte order of the object, naming calls with it's type information.
+fn bpf_decoder(obj: &ObjectFile) -> bpf::Decoder {
+    let little_endian = obj.endianness() == Endianness::Little;
+    let btf = obj
+        .section_by_name(".BTF")
+        .and_then(|section| section.data().ok())
+        .and_then(|data| bpf::Btf::parse(data, little_endian))
+        .unwrap_or_default();
+
+    bpf::Decoder { little_endian, btf }
+}
+
+/// Applies the relocations of an eBPF object to its decoded instructions.
+///
+/// `ld_imm64` instructions that load a map get the map's symbol as operand and calls to other
+/// functions or kernel functions get their target.
+fn bpf_relocate(obj: &ObjectFile, instructions: &mut AddressMap<Instruction>) {
+    let bases = elf::section_bases(obj);
+    let base = |idx: SectionIndex, addr: u64| match bases {
+        Some(ref bases) => bases[idx.0] + addr as usize,
+        None => addr as usize,
+    };
+
+    for section in obj.sections().filter(|s| s.kind() == object::SectionKind::Text) {
+        for (offset, reloc) in section.relocations() {
+            let sym = match reloc.target() {
+                RelocationTarget::Symbol(idx) => match obj.symbol_by_index(idx) {
+                    Ok(sym) => sym,
+                    Err(..) => continue,
+                },
+                _ => continue,
+            };
+
+            let addr = base(section.index(), section.address() + offset);
+            let inst = match instructions.search(addr) {
+                Ok(idx) => unsafe { &mut instructions[idx].item.bpf },
+                Err(..) => continue,
+            };
+
+            match sym.section_index() {
+                Some(idx) => inst.relocate(base(idx, sym.address())),
+                None => match sym.name() {
+                    Ok(name) => inst.relocate_extern(name),
+                    Err(..) => continue,
+                },
+            }
+        }
+    }
+}
+
+/// WebAssembly decoder resolving calls through the module's function index space.
+fn wasm_decoder(module: &binformat::wasm::Module) -> wasm::Decoder {
+    let functions = module
+        .functions
+        .iter()
+        .map(|function| match function {
+            binformat::wasm::Function::Import { name, .. } => {
+                wasm::Function::Import((*name).into())
+            }
+            binformat::wasm::Function::Local(body) => wasm::Function::Local(body.start),
+        })
+        .collect();
+
+    wasm::Decoder { functions }
+}
+
+/// Instructions of every function body, without the size and local declarations in front of them.
+fn wasm_regions(sections: &[Section], module: &binformat::wasm::Module) -> Vec<Section> {
+    let mut regions = Vec::new();
+
+    for function in module.functions.iter() {
+        let code = match function {
+            binformat::wasm::Function::Local(body) => &body.code,
+            binformat::wasm::Function::Import { .. } => continue,
+        };
+
+        let section = sections
+            .iter()
+            .find(|s| s.kind == SectionKind::Code && s.start <= code.start && code.end <= s.end);
+
+        if let Some(section) = section {
+            regions.push(section.slice(code.start, code.end));
+        }
+    }
+
+    regions
+}
+
+/// Addresses where MIPS code switches instruction set, sorted by address.
+///
+/// Compressed functions are marked in the symbol's `st_other` and by setting the low bit of their
+/// address, which is the only hint left for the entrypoint. Labels without a size only count if
+/// they're marked.
+fn mips_isa_switches(obj: &ObjectFile, isa: mips::Isa) -> Vec<(PhysAddr, mips::Isa)> {
+    const STO_MIPS_ISA: u8 = 0xc0;
+    const STO_MICROMIPS: u8 = 0x80;
+    const STO_MIPS16: u8 = 0xf0;
+
+    // odd addresses without any other marking are assumed to be the binary's compressed set
+    let compressed = match isa {
+        mips::Isa::Mips => mips::Isa::MicroMips,
+        isa => isa,
+    };
+
+    let mut switches = Vec::new();
+    for sym in obj.symbols().filter(|sym| sym.kind() == SymbolKind::Text) {
+        let addr = sym.address() as PhysAddr;
+        let isa = match sym.flags() {
+            SymbolFlags::Elf { st_other, .. } if st_other & STO_MIPS16 == STO_MIPS16 => {
+                mips::Isa::Mips16
+            }
+            SymbolFlags::Elf { st_other, .. } if st_other & STO_MIPS_ISA == STO_MICROMIPS => {
+                mips::Isa::MicroMips
+            }
+            _ if addr & 1 == 1 => compressed,
+            _ if sym.size() > 0 => mips::Isa::Mips,
+            _ => continue,
+        };
+
+        switches.push((addr & !1, isa));
+    }
+
+    let entry = obj.entry() as PhysAddr;
+    if entry & 1 == 1 {
+        switches.push((entry & !1, compressed));
+    }
+
+    // symbols are preferred over the entrypoint as they're more specific
+    switches.sort_by_key(|(addr, _)| *addr);
+    switches.dedup_by_key(|(addr, _)| *addr);
+    switches
+}
+
+/// Dalvik decoder resolving indices through the tables of a DEX file.
+fn dalvik_decoder(dex: &dex::Dex) -> dalvik::Decoder {
+    let table = |table: &[String]| table.iter().map(|entry| entry.as_str().into()).collect();
+
+    let mut methods: Vec<dalvik::Method> = dex
+        .methods
+        .iter()
+        .map(|name| dalvik::Method {
+            name: name.as_str().into(),
+            addr: None,
+        })
+        .collect();
+
+    for code in dex.code.iter() {
+        if let Some(method) = methods.get_mut(code.method) {
+            method.addr = Some(code.insns.start);
+        }
+    }
+
+    dalvik::Decoder {
+        strings: table(&dex.strings),
+        types: table(&dex.types),
+        fields: table(&dex.fields),
+        methods,
+        protos: table(&dex.protos),
+    }
+}
+
+/// Instructions of every method in a DEX file.
+fn dex_regions(sections: &[Section], dex: &dex::Dex) -> Vec<Section> {
+    let mut regions = Vec::new();
+
+    for code in dex.code.iter() {
+        let insns = &code.insns;
+        let section = sections
+            .iter()
+            .find(|s| s.kind == SectionKind::Code && s.start <= insns.start && insns.end <= s.end);
+
+        if let Some(section) = section {
+            regions.push(section.slice(insns.start, insns.end));
+        }
+    }
+
+    regions
+}
+
+/// Code sections split up by the instruction set they're encoded in, sections start out in the
+/// binary's default instruction set.
+fn mips_regions(
+    sections: &[Section],
+    switches: &[(PhysAddr, mips::Isa)],
+    isa: mips::Isa,
+) -> Vec<(mips::Isa, Vec<Section>)> {
+    let mut regions: Vec<(mips::Isa, Vec<Section>)> = Vec::new();
+    let mut push = |isa: mips::Isa, section: Section| {
+        match regions.iter_mut().find(|(region_isa, _)| *region_isa == isa) {
+            Some((_, sections)) => sections.push(section),
+            None => regions.push((isa, vec![section])),
+        }
+    };
+
+    for section in sections.iter().filter(|s| s.kind == SectionKind::Code) {
+        let inside = switches
+            .iter()
+            .filter(|(addr, _)| *addr > section.start && *addr < section.end);
+
+        let mut start = section.start;
+        let mut current = switches
+            .iter()
+            .find(|(addr, _)| *addr == section.start)
+            .map_or(isa, |(_, isa)| *isa);
+
+        for &(addr, isa) in inside {
+            if isa != current {
+                push(current, section.slice(start, addr));
+                start = addr;
+                current = isa;
+            }
+        }
+
+        push(current, section.slice(start, section.end));
+    }
+
+    regions
+}
+
 /// Architecture agnostic analysis of a module.
 pub struct Processor {
     /// Where execution start. Might be zero in case of libraries.
@@ -137,11 +834,8 @@ pub struct Processor {
     /// Symbol lookup by physical address.
     pub index: Index,
 
-    /// File handle to binary,
-    _file: File,
-
-    /// A memory map of the binary.
-    _mmap: Mmap,
+    /// What the sections are read from.
+    _backing: Backing,
 
     /// Object's sections sorted by address.
     sections: Vec<Section>,
@@ -161,7 +855,7 @@ pub struct Processor {
     max_instruction_width: usize,
 
     /// Function pointer to an [`Instruction`]'s implementation of [`Decoded::tokens`].
-    instruction_tokens: fn(&Instruction, &Index) -> Vec<Token>,
+    instruction_tokens: fn(&Instruction, &Index, Dialect) -> Vec<Token>,
 
     /// Function pointer to an [`Instruction`]'s implementation of [`Decoded::width`].
     instruction_width: fn(&Instruction) -> usize,
@@ -169,15 +863,45 @@ pub struct Processor {
     /// Target's instruction set.
     arch: Architecture,
 
-    /// Target's endianness.
-    endianness: Endianness,
+    /// Target's endianness.
+    endianness: Endianness,
+
+    /// Whether x86 code is decoded as 16-bit real mode code.
+    real_mode: bool,
+
+    /// Whether the instructions are Dalvik bytecode, which doesn't have an [`Architecture`].
+    dalvik: bool,
+
+    /// Where a raw image is loaded, as there's no object describing it.
+    raw_base: Option<PhysAddr>,
+
+    /// Bytes overwritten in the binary, in the order they were applied.
+    patches: Vec<Patch>,
+
+    /// Microarchitecture instructions are checked against.
+    uarch: std::sync::RwLock<Option<Uarch>>,
+
+    /// Whether the listing shows what instructions lift to.
+    show_ir: std::sync::atomic::AtomicBool,
+
+    /// Dialect instructions are tokenized in.
+    dialect: std::sync::RwLock<Dialect>,
 }
 
 impl Processor {
     pub fn parse<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
-        let file = std::fs::File::open(path.as_ref()).map_err(Error::IO)?;
-        let mmap = unsafe { Mmap::map(&file).map_err(Error::IO)? };
+        Self::load(path, Vec::new())
+    }
+
+    /// Load an object, DEX file or APK with `patches` applied.
+    fn load<P: AsRef<std::path::Path>>(path: P, patches: Vec<Patch>) -> Result<Self, Error> {
+        let (file, mmap) = patch::map(path.as_ref(), &patches)?;
         let binary: &'static [u8] = unsafe { std::mem::transmute(&mmap[..]) };
+
+        if dex::is_dex(binary) || apk::is_apk(binary) {
+            return Self::parse_dex(path, file, mmap, patches);
+        }
+
         let obj = ObjectFile::parse(binary)?;
 
         let path = path.as_ref().to_path_buf();
@@ -216,9 +940,27 @@ impl Processor {
                 sections.extend(debug_info.sections);
                 syms.extend(debug_info.syms);
             }
+            object::File::Wasm(wasm) => {
+                let debug_info = binformat::wasm::WasmDebugInfo::parse(wasm)?;
+                sections.extend(debug_info.sections);
+                syms.extend(debug_info.syms);
+            }
             _ => {}
         }
 
+        // compressed MIPS functions have the low bit of their address set
+        if matches!(obj.architecture(), Architecture::Mips | Architecture::Mips64) {
+            for sym in syms.mapping.iter_mut() {
+                let in_code = sections
+                    .iter()
+                    .any(|s| s.kind == SectionKind::Code && (s.start..s.end).contains(&sym.addr));
+
+                if in_code {
+                    sym.addr &= !1;
+                }
+            }
+        }
+
         for section in sections.iter() {
             syms.push(Addressed {
                 addr: section.start,
@@ -284,129 +1026,352 @@ impl Processor {
         }
 
         let arch = obj.architecture();
-        let (instruction_tokens, instruction_width) = unsafe {
-            match arch {
-                Architecture::Riscv32 | Architecture::Riscv64 => (
-                    std::mem::transmute(<riscv::Instruction as Decoded>::tokens as usize),
-                    std::mem::transmute(<riscv::Instruction as Decoded>::width as usize),
-                ),
-                Architecture::Mips | Architecture::Mips64 => (
-                    std::mem::transmute(<mips::Instruction as Decoded>::tokens as usize),
-                    std::mem::transmute(<mips::Instruction as Decoded>::width as usize),
-                ),
-                Architecture::X86_64_X32 | Architecture::I386 => (
-                    std::mem::transmute(<x86::Instruction as Decoded>::tokens as usize),
-                    std::mem::transmute(<x86::Instruction as Decoded>::width as usize),
-                ),
-                Architecture::X86_64 => (
-                    std::mem::transmute(<x64::Instruction as Decoded>::tokens as usize),
-                    std::mem::transmute(<x64::Instruction as Decoded>::width as usize),
-                ),
-                Architecture::Arm => (
-                    std::mem::transmute(<armv7::Instruction as Decoded>::tokens as usize),
-                    std::mem::transmute(<armv7::Instruction as Decoded>::width as usize),
-                ),
-                Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => (
-                    std::mem::transmute(<aarch64::Instruction as Decoded>::tokens as usize),
-                    std::mem::transmute(<aarch64::Instruction as Decoded>::width as usize),
-                ),
-                arch => return Err(Error::UnknownArchitecture(arch)),
-            }
-        };
+        let disassembly = Disassembly::decode(&obj, &sections)?;
 
-        let mut instructions = AddressMap::default();
-        let mut errors = AddressMap::default();
-        let max_instruction_width;
+        log::complex!(
+            w "[processor::parse] took ",
+            y format!("{:#?}", now.elapsed()),
+            w " to parse ",
+            w format!("{path:?}.")
+        );
 
-        match arch {
-            Architecture::Riscv32 => {
-                impl_recursion!(
-                    &index,
-                    &mut errors,
-                    &mut instructions,
-                    &mut sections,
-                    max_instruction_width,
-                    riscv::Decoder { is_64: false },
-                    riscv
-                )
+        Ok(Self {
+            entrypoint,
+            path,
+            sections,
+            segments,
+            errors: disassembly.errors,
+            instructions: disassembly.instructions,
+            index,
+            _backing: Backing::Mapped {
+                _file: file,
+                _mmap: mmap,
+            },
+            max_instruction_width: disassembly.max_instruction_width,
+            instruction_tokens: disassembly.instruction_tokens,
+            instruction_width: disassembly.instruction_width,
+            arch,
+            endianness: obj.endianness(),
+            real_mode: false,
+            dalvik: false,
+            raw_base: None,
+            patches,
+            uarch: Self::default_uarch(),
+            show_ir: Default::default(),
+            dialect: std::sync::RwLock::new(commands::CONFIG.disassembly.dialect()),
+        })
+    }
+
+    /// Load a raw image such as a boot sector, option ROM or firmware dump.
+    ///
+    /// Raw images don't have headers describing their architecture or where they're loaded, so
+    /// the whole file is decoded as a single code section starting at `base`.
+    pub fn parse_raw<P: AsRef<std::path::Path>>(
+        path: P,
+        arch: &str,
+        base: PhysAddr,
+    ) -> Result<Self, Error> {
+        let (arch, real_mode) = raw_architecture(arch)
+            .ok_or_else(|| Error::UnknownRawArchitecture(arch.to_string()))?;
+
+        Self::load_raw(path, arch, real_mode, base, Vec::new())
+    }
+
+    fn load_raw<P: AsRef<std::path::Path>>(
+        path: P,
+        arch: Architecture,
+        real_mode: bool,
+        base: PhysAddr,
+        patches: Vec<Patch>,
+    ) -> Result<Self, Error> {
+        let (file, mmap) = patch::map(path.as_ref(), &patches)?;
+        let binary: &'static [u8] = unsafe { std::mem::transmute(&mmap[..]) };
+
+        let path = path.as_ref().to_path_buf();
+        let now = std::time::Instant::now();
+
+        let end = base + binary.len();
+        let sections = vec![Section::new(
+            "raw".to_string(),
+            "RAW",
+            SectionKind::Code,
+            binary,
+            base,
+            end,
+        )];
+        let segments = vec![Segment {
+            name: "raw".to_string(),
+            start: base,
+            end,
+        }];
+
+        let disassembly = Disassembly::decode_raw(arch, real_mode, &sections)?;
+
+        log::complex!(
+            w "[processor::parse_raw] took ",
+            y format!("{:#?}", now.elapsed()),
+            w " to parse ",
+            w format!("{path:?}.")
+        );
+
+        Ok(Self {
+            entrypoint: base,
+            path,
+            sections,
+            segments,
+            errors: disassembly.errors,
+            instructions: disassembly.instructions,
+            index: Index::default(),
+            _backing: Backing::Mapped {
+                _file: file,
+                _mmap: mmap,
+            },
+            max_instruction_width: disassembly.max_instruction_width,
+            instruction_tokens: disassembly.instruction_tokens,
+            instruction_width: disassembly.instruction_width,
+            arch,
+            endianness: Endianness::Little,
+            real_mode,
+            dalvik: false,
+            raw_base: Some(base),
+            patches,
+            uarch: Self::default_uarch(),
+            show_ir: Default::default(),
+            dialect: std::sync::RwLock::new(commands::CONFIG.disassembly.dialect()),
+        })
+    }
+
+    /// Load a DEX file or the DEX files of an APK.
+    ///
+    /// DEX files extracted from an APK are placed one after another, each starting on a new page,
+    /// and their sections are prefixed with the file's name.
+    fn parse_dex<P: AsRef<std::path::Path>>(
+        path: P,
+        file: File,
+        mmap: Mmap,
+        patches: Vec<Patch>,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let now = std::time::Instant::now();
+
+        // The bytes are owned by the backing which lives as long as the sections.
+        let (files, binary, backing) = if apk::is_apk(&mmap) {
+            let mut bytes = Vec::new();
+            let mut files = Vec::new();
+
+            for (name, data) in apk::dex_files(&mmap) {
+                let base = bytes.len().next_multiple_of(0x1000);
+                bytes.resize(base, 0);
+                bytes.extend(data);
+                files.push((format!("{name}:"), base..bytes.len()));
             }
-            Architecture::Riscv64 => {
-                impl_recursion!(
-                    &index,
-                    &mut errors,
-                    &mut instructions,
-                    &mut sections,
-                    max_instruction_width,
-                    riscv::Decoder { is_64: true },
-                    riscv
-                )
+
+            let binary: &'static [u8] = unsafe { std::mem::transmute(&bytes[..]) };
+            (files, binary, Backing::Extracted { _bytes: bytes })
+        } else {
+            let files = vec![(String::new(), 0..mmap.len())];
+            let binary: &'static [u8] = unsafe { std::mem::transmute(&mmap[..]) };
+            (files, binary, Backing::Mapped { _file: file, _mmap: mmap })
+        };
+
+        let mut dexes = Vec::new();
+        let mut sections = Vec::new();
+        for (prefix, range) in files {
+            let base = range.start;
+            match dex::Dex::parse(&binary[range], base) {
+                Some(dex) => {
+                    sections.extend(dex.sections(&prefix));
+                    dexes.push(dex);
+                }
+                None => log::complex!(
+                    w "[processor::parse_dex] failed to parse ",
+                    b prefix.trim_end_matches(':'),
+                    w ".",
+                ),
             }
-            Architecture::Mips | Architecture::Mips64 => {
-                impl_recursion!(
-                    &index,
-                    &mut errors,
-                    &mut instructions,
-                    &mut sections,
-                    max_instruction_width,
-                    mips::Decoder::default(),
-                    mips
-                )
+        }
+
+        if dexes.is_empty() {
+            return Err(Error::NotAnExecutable);
+        }
+
+        sections.sort_unstable_by_key(|s| s.start);
+
+        let mut syms = AddressMap::default();
+        for dex in dexes.iter() {
+            syms.extend(dex.symbols());
+        }
+
+        for section in sections.iter() {
+            syms.push(Addressed {
+                addr: section.start,
+                item: RawSymbol { name: &section.name, module: None }
+            });
+        }
+
+        let index = Index::from_symbols(syms);
+        let segments = vec![Segment {
+            name: "dex".to_string(),
+            start: 0,
+            end: binary.len(),
+        }];
+
+        let disassembly = Disassembly::decode_dex(&dexes, &sections)?;
+
+        log::complex!(
+            w "[processor::parse_dex] took ",
+            y format!("{:#?}", now.elapsed()),
+            w " to parse ",
+            w format!("{path:?}.")
+        );
+
+        Ok(Self {
+            entrypoint: 0,
+            path,
+            sections,
+            segments,
+            errors: disassembly.errors,
+            instructions: disassembly.instructions,
+            index,
+            _backing: backing,
+            max_instruction_width: disassembly.max_instruction_width,
+            instruction_tokens: disassembly.instruction_tokens,
+            instruction_width: disassembly.instruction_width,
+            arch: Architecture::Unknown,
+            endianness: Endianness::Little,
+            real_mode: false,
+            dalvik: true,
+            raw_base: None,
+            patches,
+            uarch: Self::default_uarch(),
+            show_ir: Default::default(),
+            dialect: std::sync::RwLock::new(commands::CONFIG.disassembly.dialect()),
+        })
+    }
+
+    /// Attach to a running process, building sections from its memory mappings.
+    ///
+    /// Code is read from the process's memory instead of the files on disk, so the listing
+    /// shows what is actually executing (including JIT'd regions). Large anonymous data mappings
+    /// aren't copied. The memory is only read once, see [`Processor::refreshed`].
+    #[cfg(target_os = "linux")]
+    pub fn attach(pid: i32) -> Result<Self, Error> {
+        Self::read_process(nix::unistd::Pid::from_raw(pid), &[])
+    }
+
+    /// Read the process's memory again, as it changes while the process runs.
+    ///
+    /// `breakpoints` are the addresses of software breakpoints along with the bytes they
+    /// replaced, which are shown instead of the traps.
+    #[cfg(target_os = "linux")]
+    pub fn refreshed(&self, breakpoints: &[(PhysAddr, Vec<u8>)]) -> Result<Self, Error> {
+        let pid = match self._backing {
+            Backing::Live(ref memory) => memory.pid,
+            _ => {
+                return Err(Error::IO(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "the binary isn't read from a process",
+                )))
             }
-            Architecture::X86_64_X32 | Architecture::I386 => {
-                impl_recursion!(
-                    &index,
-                    &mut errors,
-                    &mut instructions,
-                    &mut sections,
-                    max_instruction_width,
-                    x86::Decoder::default(),
-                    x86
-                )
+        };
+
+        let processor = Self::read_process(pid, breakpoints)?;
+        processor.set_dialect(self.dialect());
+        processor.set_uarch(self.uarch());
+        processor.set_show_ir(self.shows_ir());
+        Ok(processor)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_process(
+        pid: nix::unistd::Pid,
+        breakpoints: &[(PhysAddr, Vec<u8>)],
+    ) -> Result<Self, Error> {
+        let now = std::time::Instant::now();
+        let maps = live::parse_maps(pid).map_err(Error::IO)?;
+        let path = std::fs::read_link(format!("/proc/{pid}/exe")).map_err(Error::IO)?;
+
+        // The main executable decides which architecture we're decoding.
+        let exe = std::fs::File::open(&path).map_err(Error::IO)?;
+        let exe = unsafe { Mmap::map(&exe).map_err(Error::IO)? };
+        let exe = ObjectFile::parse(&exe[..])?;
+        let arch = exe.architecture();
+        let endianness = exe.endianness();
+
+        let mut modules = Vec::new();
+        let mut visited = Vec::new();
+        for map in maps.iter() {
+            let module_path = match map.path() {
+                Some(module_path) => module_path,
+                None => continue,
+            };
+
+            if visited.contains(&module_path) {
+                continue;
             }
-            Architecture::X86_64 => {
-                impl_recursion!(
-                    &index,
-                    &mut errors,
-                    &mut instructions,
-                    &mut sections,
-                    max_instruction_width,
-                    x64::Decoder::default(),
-                    x64
-                )
+
+            visited.push(module_path.clone());
+            match live::parse_module(&module_path, map, module_path == path) {
+                Ok(module) => modules.push(module),
+                Err(err) => log::complex!(
+                    w "[processor::attach] failed to parse symbols of ",
+                    b format!("{module_path:?}"),
+                    y format!(": {err:?}"),
+                ),
             }
-            Architecture::Arm => {
-                impl_recursion!(
-                    &index,
-                    &mut errors,
-                    &mut instructions,
-                    &mut sections,
-                    max_instruction_width,
-                    armv7::Decoder::default(),
-                    armv7
-                )
-            },
-            Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => {
-                impl_recursion!(
-                    &index,
-                    &mut errors,
-                    &mut instructions,
-                    &mut sections,
-                    max_instruction_width,
-                    aarch64::Decoder::default(),
-                    aarch64
-                )
+        }
+
+        let mut regions = Vec::new();
+        let mut sections = Vec::new();
+        let mut segments = Vec::new();
+        for map in maps.iter() {
+            let name = match map.name {
+                Some(ref name) => name.rsplit('/').next().unwrap_or(name).to_string(),
+                None => "anonymous".to_string(),
+            };
+
+            segments.push(Segment {
+                name: name.clone(),
+                start: map.start,
+                end: map.end,
+            });
+
+            if !map.should_read() {
+                continue;
             }
-            _ => unreachable!(),
-        };
 
-        instructions.sort_unstable();
-        errors.sort_unstable();
+            let mut region = vec![0u8; map.end - map.start].into_boxed_slice();
+            let read = live::read_memory(pid, map.start, &mut region);
+            live::hide_breakpoints(&mut region[..read], map.start, breakpoints);
+
+            // The region is owned by `Backing::Live` which lives as long as the sections.
+            let bytes: &'static [u8] = unsafe { std::mem::transmute(&region[..read]) };
+            let kind = if map.executable {
+                SectionKind::Code
+            } else {
+                SectionKind::Raw
+            };
+
+            sections.push(Section::new(name, "LIVE", kind, bytes, map.start, map.end));
+            regions.push(region);
+        }
+
+        if sections.is_empty() {
+            return Err(Error::NotAnExecutable);
+        }
+
+        sections.sort_unstable_by_key(|s| s.start);
+        segments.sort_unstable_by_key(|s| s.start);
+
+        let index = Index::combine(modules);
+        let entrypoint = index.get_func_by_name("entry").unwrap_or(0);
+        let disassembly = Disassembly::decode(&exe, &sections)?;
 
         log::complex!(
-            w "[processor::parse] took ",
+            w "[processor::attach] took ",
             y format!("{:#?}", now.elapsed()),
-            w " to parse ",
-            w format!("{path:?}.")
+            w " to attach to ",
+            g format!("{pid}."),
         );
 
         Ok(Self {
@@ -414,23 +1379,60 @@ impl Processor {
             path,
             sections,
             segments,
-            errors,
-            instructions,
+            errors: disassembly.errors,
+            instructions: disassembly.instructions,
             index,
-            _file: file,
-            _mmap: mmap,
-            max_instruction_width,
-            instruction_tokens,
-            instruction_width,
+            _backing: Backing::Live(live::Memory {
+                pid,
+                _regions: regions,
+            }),
+            max_instruction_width: disassembly.max_instruction_width,
+            instruction_tokens: disassembly.instruction_tokens,
+            instruction_width: disassembly.instruction_width,
             arch,
-            endianness: obj.endianness(),
+            endianness,
+            real_mode: false,
+            dalvik: false,
+            raw_base: None,
+            patches: Vec::new(),
+            uarch: Self::default_uarch(),
+            show_ir: Default::default(),
+            dialect: std::sync::RwLock::new(commands::CONFIG.disassembly.dialect()),
         })
     }
 
+    /// Process identifier if the processor is reading from a running process.
+    pub fn pid(&self) -> Option<i32> {
+        match self._backing {
+            #[cfg(target_os = "linux")]
+            Backing::Live(ref memory) => Some(memory.pid.as_raw()),
+            _ => None,
+        }
+    }
+
+    /// Target's instruction set.
+    pub fn arch(&self) -> Architecture {
+        self.arch
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
     /// Relatively slow tokenization of an [`Instruction`].
     /// Xref's get resolved which requires some extra computation.
     pub fn instruction_tokens(&self, instruction: &Instruction, symbols: &Index) -> Vec<Token> {
-        (self.instruction_tokens)(instruction, symbols)
+        (self.instruction_tokens)(instruction, symbols, self.dialect())
+    }
+
+    /// Dialect instructions are tokenized in.
+    pub fn dialect(&self) -> Dialect {
+        *self.dialect.read().unwrap()
+    }
+
+    /// Change the dialect, blocks have to be parsed again to show the change.
+    pub fn set_dialect(&self, dialect: Dialect) {
+        *self.dialect.write().unwrap() = dialect;
     }
 
     pub fn instruction_width(&self, instruction: &Instruction) -> usize {
@@ -478,6 +1480,11 @@ impl Processor {
         found
     }
 
+    /// Find a section by name, including debug sections.
+    pub fn section_by_name(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
     pub fn section_name(&self, addr: PhysAddr) -> Option<&str> {
         self.sections()
             .find(|s| (s.start..=s.end).contains(&addr))
@@ -500,6 +1507,17 @@ impl Drop for Processor {
                 Architecture::Mips | Architecture::Mips64 => unsafe {
                     ManuallyDrop::drop(&mut inst.mips)
                 },
+                Architecture::PowerPc | Architecture::PowerPc64 => unsafe {
+                    ManuallyDrop::drop(&mut inst.ppc)
+                },
+                Architecture::LoongArch64 => unsafe { ManuallyDrop::drop(&mut inst.loongarch) },
+                Architecture::Bpf => unsafe { ManuallyDrop::drop(&mut inst.bpf) },
+                Architecture::Wasm32 | Architecture::Wasm64 => unsafe {
+                    ManuallyDrop::drop(&mut inst.wasm)
+                },
+                Architecture::Unknown if self.dalvik => unsafe {
+                    ManuallyDrop::drop(&mut inst.dalvik)
+                },
                 _ => {}
             }
         }
@@ -0,0 +1,84 @@
+//! Lifting instructions to the architecture neutral [`ir`](decoder::ir).
+
+use crate::{Instruction, Processor};
+use decoder::ir::{Builder, Stmt};
+use decoder::Decoded;
+use object::Architecture;
+use processor_shared::PhysAddr;
+use std::sync::atomic::Ordering;
+
+impl Processor {
+    /// Whether there's a lifter for the architecture.
+    pub fn can_lift(&self) -> bool {
+        matches!(
+            self.arch,
+            Architecture::X86_64
+                | Architecture::Aarch64
+                | Architecture::Riscv32
+                | Architecture::Riscv64
+        )
+    }
+
+    /// Whether the listing shows what instructions lift to.
+    pub fn shows_ir(&self) -> bool {
+        self.show_ir.load(Ordering::Relaxed)
+    }
+
+    /// Show or hide lifted instructions, blocks have to be parsed again to show the change.
+    pub fn set_show_ir(&self, show: bool) {
+        self.show_ir.store(show, Ordering::Relaxed);
+    }
+
+    fn lift_instruction(&self, addr: PhysAddr, inst: &Instruction, builder: &mut Builder) {
+        match self.arch {
+            Architecture::X86_64 => unsafe { inst.x64.lift(addr, builder) },
+            Architecture::Aarch64 => unsafe { inst.aarch64.lift(addr, builder) },
+            Architecture::Riscv32 | Architecture::Riscv64 => unsafe {
+                inst.riscv.lift(addr, builder)
+            },
+            _ => builder.unsupported(),
+        }
+    }
+
+    /// Statements the instruction at `addr` lifts to.
+    pub fn lift(&self, addr: PhysAddr) -> Option<Vec<Stmt>> {
+        if !self.can_lift() {
+            return None;
+        }
+
+        let inst = self.instruction_by_addr(addr)?;
+        let mut builder = Builder::new();
+        self.lift_instruction(addr, inst, &mut builder);
+        Some(builder.take())
+    }
+
+    /// Statements of each instruction in `start..end`.
+    ///
+    /// Temporaries are numbered across all instructions, so they're only assigned once.
+    pub fn lift_range(&self, start: PhysAddr, end: PhysAddr) -> Vec<(PhysAddr, Vec<Stmt>)> {
+        if !self.can_lift() {
+            return Vec::new();
+        }
+
+        let mut builder = Builder::new();
+        let mut lifted = Vec::new();
+        let mut addr = start;
+
+        while addr < end {
+            match self.instruction_by_addr(addr) {
+                Some(inst) => {
+                    self.lift_instruction(addr, inst, &mut builder);
+                    lifted.push((addr, builder.take()));
+                    addr += self.instruction_width(inst);
+                }
+                // skip over bytes that failed to decode
+                None => match self.error_by_addr(addr) {
+                    Some(err) => addr += err.size(),
+                    None => break,
+                },
+            }
+        }
+
+        lifted
+    }
+}
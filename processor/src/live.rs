@@ -0,0 +1,240 @@
+//! Reading the address space of a running process.
+
+#![cfg(target_os = "linux")]
+
+use crate::Error;
+use binformat::elf;
+use debugvault::Index;
+use memmap2::Mmap;
+use nix::sys::ptrace;
+use nix::sys::uio::{process_vm_readv, RemoteIoVec};
+use nix::unistd::Pid;
+use object::read::File as ObjectFile;
+use object::{Object, ObjectSegment};
+use processor_shared::{AddressMap, PhysAddr};
+use std::io::IoSliceMut;
+use std::path::{Path, PathBuf};
+
+/// Size of a page, reads are split on this boundary so that an unmapped page
+/// doesn't fail the whole region.
+const PAGE_SIZE: usize = 0x1000;
+
+/// Largest anonymous data mapping that's copied when attaching. Bigger ones are mostly arenas
+/// reserved by allocators and runtimes, copying them could exhaust our own memory.
+const MAX_ANONYMOUS_LEN: usize = 64 * 1024 * 1024;
+
+/// A single line of `/proc/<pid>/maps`.
+#[derive(Debug, Clone)]
+pub struct Mapping {
+    pub start: PhysAddr,
+    pub end: PhysAddr,
+    pub readable: bool,
+    pub executable: bool,
+    /// Offset into the backing file.
+    pub offset: usize,
+    /// Either a path to the backing file or a pseudo-path like `[heap]`.
+    pub name: Option<String>,
+}
+
+impl Mapping {
+    /// Whether the mapping is backed by a file on disk.
+    pub fn is_file(&self) -> bool {
+        matches!(&self.name, Some(name) if name.starts_with('/'))
+    }
+
+    pub fn path(&self) -> Option<PathBuf> {
+        self.is_file().then(|| PathBuf::from(self.name.as_deref().unwrap()))
+    }
+
+    /// Whether the contents of the mapping are copied when attaching.
+    ///
+    /// Reading the vsyscall page always fails, non-readable mappings are guard pages and large
+    /// anonymous data mappings aren't worth the memory.
+    pub fn should_read(&self) -> bool {
+        if !self.readable || self.name.as_deref() == Some("[vsyscall]") {
+            return false;
+        }
+
+        self.executable || self.is_file() || self.end - self.start <= MAX_ANONYMOUS_LEN
+    }
+}
+
+/// Parses the memory mappings of `pid`.
+pub fn parse_maps(pid: Pid) -> std::io::Result<Vec<Mapping>> {
+    let maps = std::fs::read_to_string(format!("/proc/{pid}/maps"))?;
+    Ok(maps.lines().filter_map(parse_map_line).collect())
+}
+
+/// 7f1c2c000000-7f1c2c021000 r-xp 00002000 fd:01 1316 /usr/lib/libc.so.6
+fn parse_map_line(line: &str) -> Option<Mapping> {
+    let mut parts = line.split_ascii_whitespace();
+    let (start, end) = parts.next()?.split_once('-')?;
+    let perms = parts.next()?.as_bytes();
+    let offset = parts.next()?;
+    let _dev = parts.next()?;
+    let _inode = parts.next()?;
+    let name = parts.collect::<Vec<&str>>().join(" ");
+
+    if perms.len() < 3 {
+        return None;
+    }
+
+    Some(Mapping {
+        start: usize::from_str_radix(start, 16).ok()?,
+        end: usize::from_str_radix(end, 16).ok()?,
+        readable: perms[0] == b'r',
+        executable: perms[2] == b'x',
+        offset: usize::from_str_radix(offset, 16).ok()?,
+        name: (!name.is_empty()).then_some(name),
+    })
+}
+
+/// Reads `buf.len()` bytes at `addr` out of `pid`, returning how many bytes were read.
+///
+/// Tries `process_vm_readv` first, falling back to `PTRACE_PEEKDATA` which only
+/// succeeds if we're tracing the process.
+pub fn read_memory(pid: Pid, addr: PhysAddr, buf: &mut [u8]) -> usize {
+    let mut read = 0;
+
+    for chunk in buf.chunks_mut(PAGE_SIZE) {
+        let len = chunk.len();
+        let remote = [RemoteIoVec { base: addr + read, len }];
+        let mut local = [IoSliceMut::new(chunk)];
+
+        match process_vm_readv(pid, &mut local, &remote) {
+            Ok(n) if n == len => read += n,
+            _ => match peek_memory(pid, addr + read, local[0].as_mut()) {
+                Some(()) => read += len,
+                None => break,
+            },
+        }
+    }
+
+    read
+}
+
+/// Put back the bytes replaced by `breakpoints` in a copy of the memory at `addr`.
+pub fn hide_breakpoints(buf: &mut [u8], addr: PhysAddr, breakpoints: &[(PhysAddr, Vec<u8>)]) {
+    for (bp, original) in breakpoints {
+        for (idx, &byte) in original.iter().enumerate() {
+            if let Some(dst) = (bp + idx).checked_sub(addr).and_then(|off| buf.get_mut(off)) {
+                *dst = byte;
+            }
+        }
+    }
+}
+
+fn peek_memory(pid: Pid, addr: PhysAddr, buf: &mut [u8]) -> Option<()> {
+    const WORD: usize = std::mem::size_of::<std::ffi::c_long>();
+
+    for (idx, chunk) in buf.chunks_mut(WORD).enumerate() {
+        let word = ptrace::read(pid, (addr + idx * WORD) as ptrace::AddressType).ok()?;
+        chunk.copy_from_slice(&word.to_ne_bytes()[..chunk.len()]);
+    }
+
+    Some(())
+}
+
+/// Parses the symbols of an ELF mapped into a process.
+///
+/// Returns the base address the module was loaded at alongside its symbols, `map` has to be
+/// the first mapping of the file.
+pub fn parse_module(path: &Path, map: &Mapping, is_main: bool) -> Result<(usize, Index), Error> {
+    let file = std::fs::File::open(path).map_err(Error::IO)?;
+    let mmap = unsafe { Mmap::map(&file).map_err(Error::IO)? };
+    let obj = ObjectFile::parse(&mmap[..])?;
+
+    let mut syms = AddressMap::default();
+    match &obj {
+        object::File::Elf32(elf) => syms.extend(elf::ElfDebugInfo::parse(elf)?.syms),
+        object::File::Elf64(elf) => syms.extend(elf::ElfDebugInfo::parse(elf)?.syms),
+        _ => return Err(Error::NotAnExecutable),
+    }
+
+    // Every module has an entrypoint, only the executable's is interesting.
+    if !is_main {
+        syms.retain(|sym| sym.item.name != "entry");
+    }
+
+    // Find the segment that was mapped at `map.start` to calculate the load bias.
+    let offset = map.offset as u64;
+    let segment = obj.segments().find(|segment| {
+        let (start, size) = segment.file_range();
+        (start..start + size).contains(&offset)
+    });
+
+    let base = match segment {
+        Some(segment) => {
+            let addr = segment.address() + (offset - segment.file_range().0);
+            map.start.wrapping_sub(addr as usize)
+        }
+        None => map.start,
+    };
+
+    let index = Index::parse(&obj, path, syms).map_err(Error::Debug)?;
+    Ok((base, index))
+}
+
+/// Snapshots of a process's memory, these outlive the [`Section`]'s that borrow them.
+///
+/// [`Section`]: processor_shared::Section
+pub struct Memory {
+    pub pid: Pid,
+    pub _regions: Vec<Box<[u8]>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_line() {
+        let line = "7f1c2c000000-7f1c2c021000 r-xp 00002000 fd:01 1316  /usr/lib/libc.so.6";
+        let map = parse_map_line(line).unwrap();
+        assert_eq!(map.start, 0x7f1c2c000000);
+        assert_eq!(map.end, 0x7f1c2c021000);
+        assert_eq!(map.offset, 0x2000);
+        assert!(map.readable && map.executable);
+        assert_eq!(map.path(), Some(PathBuf::from("/usr/lib/libc.so.6")));
+
+        let line = "7ffd4b5e1000-7ffd4b602000 rw-p 00000000 00:00 0 [stack]";
+        let map = parse_map_line(line).unwrap();
+        assert!(!map.is_file());
+        assert_eq!(map.name.as_deref(), Some("[stack]"));
+
+        let line = "7f1c2c021000-7f1c2c022000 ---p 00000000 00:00 0";
+        assert_eq!(parse_map_line(line).unwrap().name, None);
+    }
+
+    #[test]
+    fn should_read() {
+        let read = |line| parse_map_line(line).unwrap().should_read();
+
+        assert!(read("7f1c2c000000-7f1c2c021000 r-xp 00002000 fd:01 1316 /usr/lib/libc.so.6"));
+        assert!(read("7ffd4b5e1000-7ffd4b602000 rw-p 00000000 00:00 0 [stack]"));
+        assert!(!read("7f1c2c021000-7f1c2c022000 ---p 00000000 00:00 0"));
+        assert!(!read("ffffffffff600000-ffffffffff601000 --xp 00000000 00:00 0 [vsyscall]"));
+
+        // reserved arenas are skipped, unless they hold code
+        assert!(!read("7f0000000000-7f0100000000 rw-p 00000000 00:00 0"));
+        assert!(read("7f0000000000-7f0100000000 rwxp 00000000 00:00 0"));
+        assert!(read("7f0000000000-7f0000100000 rw-p 00000000 00:00 0"));
+    }
+
+    #[test]
+    fn breakpoints_are_hidden() {
+        let mut buf = [0xcc; 4];
+        let breakpoints = [(0x0fff, vec![0x11, 0x22]), (0x1003, vec![0x33, 0x44])];
+        hide_breakpoints(&mut buf, 0x1000, &breakpoints);
+        assert_eq!(buf, [0x22, 0xcc, 0xcc, 0x33]);
+    }
+
+    #[test]
+    fn read_self() {
+        let data = *b"live memory";
+        let mut buf = [0u8; 11];
+        let pid = nix::unistd::getpid();
+        assert_eq!(read_memory(pid, data.as_ptr() as usize, &mut buf), buf.len());
+        assert_eq!(&buf, &data);
+    }
+}
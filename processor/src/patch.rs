@@ -0,0 +1,607 @@
+//! Overwriting bytes of a binary and writing a patched copy of it to disk.
+//!
+//! Patches are applied to a private copy-on-write mapping of the file, the binary is then loaded
+//! again so that instructions, symbols and blocks all reflect the patched bytes.
+
+use crate::{Backing, Error, Processor};
+use memmap2::{Mmap, MmapOptions};
+use object::Architecture;
+use processor_shared::PhysAddr;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// Bytes overwritten in a binary.
+#[derive(Debug, Clone)]
+pub struct Patch {
+    /// Address of the first overwritten byte.
+    pub addr: PhysAddr,
+    /// Offset of the first overwritten byte in the file.
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+pub enum PatchError {
+    /// The address isn't backed by bytes in the file, e.g. it's part of `.bss`.
+    Unmapped(PhysAddr),
+    /// Instructions can't be assembled for the architecture.
+    UnsupportedArchitecture(Architecture),
+    /// The assembler rejected the instructions.
+    Assembler(String),
+    /// Saving would overwrite the binary that's currently loaded.
+    OverwritesBinary,
+    IO(io::Error),
+}
+
+/// Map a binary into memory, with `patches` applied to a private copy of it's pages.
+pub(crate) fn map(path: &Path, patches: &[Patch]) -> Result<(File, Mmap), Error> {
+    let file = File::open(path).map_err(Error::IO)?;
+
+    if patches.is_empty() {
+        let mmap = unsafe { Mmap::map(&file).map_err(Error::IO)? };
+        return Ok((file, mmap));
+    }
+
+    let mut mmap = unsafe { MmapOptions::new().map_copy(&file).map_err(Error::IO)? };
+    for patch in patches {
+        let end = patch.offset + patch.bytes.len();
+        match mmap.get_mut(patch.offset..end) {
+            Some(bytes) => bytes.copy_from_slice(&patch.bytes),
+            None => {
+                return Err(Error::IO(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "patch lies past the end of the file",
+                )))
+            }
+        }
+    }
+
+    let mmap = mmap.make_read_only().map_err(Error::IO)?;
+    Ok((file, mmap))
+}
+
+impl Processor {
+    /// Bytes overwritten in the binary, in the order they were applied.
+    pub fn patches(&self) -> &[Patch] {
+        &self.patches
+    }
+
+    /// Whether any of the `len` bytes at `addr` were patched.
+    pub fn is_patched(&self, addr: PhysAddr, len: usize) -> bool {
+        self.patches
+            .iter()
+            .any(|patch| addr < patch.addr + patch.bytes.len() && patch.addr < addr + len)
+    }
+
+    /// The bytes of the file, if the sections are read from it.
+    fn file(&self) -> Option<&[u8]> {
+        match self._backing {
+            Backing::Mapped { ref _mmap, .. } => Some(&_mmap[..]),
+            _ => None,
+        }
+    }
+
+    /// Offset in the file of the byte at `addr`.
+    ///
+    /// Sections borrow their bytes from the mapped file, so the offset is where the section's
+    /// bytes are in the mapping. This holds for any format, whether the address is relative to
+    /// the image base like in PE or is taken from a segment like in ELF and Mach-O.
+    pub fn file_offset(&self, addr: PhysAddr) -> Option<usize> {
+        let file = self.file()?;
+        let section = self.sections().find(|s| (s.start..s.end).contains(&addr))?;
+        let bytes = section.bytes();
+
+        // zero-initialized parts of a section aren't stored in the file
+        let rel = addr - section.start;
+        if rel >= bytes.len() {
+            return None;
+        }
+
+        let offset = (bytes.as_ptr() as usize).checked_sub(file.as_ptr() as usize)?;
+        (offset + bytes.len() <= file.len()).then_some(offset + rel)
+    }
+
+    /// Patches of the binary with `bytes` written at `addr`.
+    ///
+    /// The binary has to be loaded again with [`Processor::patched`] for the patch to show.
+    pub fn patch(&self, addr: PhysAddr, bytes: Vec<u8>) -> Result<Vec<Patch>, PatchError> {
+        let offset = self.file_offset(addr).ok_or(PatchError::Unmapped(addr))?;
+
+        // the bytes can't cross into another section, as it's not necessarily next in the file
+        if let Some(last) = bytes.len().checked_sub(1) {
+            if self.file_offset(addr + last) != Some(offset + last) {
+                return Err(PatchError::Unmapped(addr + last));
+            }
+        }
+
+        let mut patches = self.patches.clone();
+        patches.push(Patch {
+            addr,
+            offset,
+            bytes,
+        });
+
+        Ok(patches)
+    }
+
+    /// Load the binary again with only `patches` applied, keeping the settings changed since
+    /// it was loaded.
+    pub fn patched(&self, patches: Vec<Patch>) -> Result<Self, Error> {
+        let processor = match self.raw_base {
+            Some(base) => Self::load_raw(&self.path, self.arch, self.real_mode, base, patches)?,
+            None => Self::load(&self.path, patches)?,
+        };
+
+        processor.set_dialect(self.dialect());
+        processor.set_uarch(self.uarch());
+        processor.set_show_ir(self.shows_ir());
+        Ok(processor)
+    }
+
+    /// Write a copy of the binary with all patches applied.
+    pub fn save_patched(&self, path: &Path) -> Result<(), PatchError> {
+        let file = self.file().ok_or_else(|| {
+            PatchError::IO(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the binary isn't read from a file",
+            ))
+        })?;
+
+        // truncating the file we're reading from would invalidate the mapping
+        if let (Ok(a), Ok(b)) = (path.canonicalize(), self.path.canonicalize()) {
+            if a == b {
+                return Err(PatchError::OverwritesBinary);
+            }
+        }
+
+        std::fs::write(path, file).map_err(PatchError::IO)
+    }
+
+    /// Assemble instructions separated by `;` to be placed at `addr`.
+    ///
+    /// Branches to absolute addresses are encoded relative to where they're placed and names of
+    /// functions resolve to their address.
+    pub fn encode(&self, addr: PhysAddr, src: &str) -> Result<Vec<assembler::Encoded>, PatchError> {
+        let arch = match self.arch {
+            Architecture::X86_64 => assembler::Arch::X86_64,
+            Architecture::Aarch64 => assembler::Arch::Aarch64,
+            arch => return Err(PatchError::UnsupportedArchitecture(arch)),
+        };
+
+        let symbols = |name: &str| self.index.get_func_by_name(name).map(|addr| addr as u64);
+        assembler::assemble(arch, addr as u64, src, &symbols)
+            .map_err(|err| PatchError::Assembler(err.to_string()))
+    }
+
+    /// Assemble instructions separated by `;` to overwrite the ones at `addr`.
+    ///
+    /// The encoding is padded with nops up to the end of the last instruction it overwrites.
+    pub fn assemble(&self, addr: PhysAddr, src: &str) -> Result<Vec<u8>, PatchError> {
+        let mut bytes: Vec<u8> =
+            self.encode(addr, src)?.into_iter().flat_map(|encoded| encoded.bytes).collect();
+
+        if bytes.is_empty() {
+            return Err(PatchError::Assembler("no instructions were given".to_string()));
+        }
+
+        // instructions overwritten by the patch, the last one might be partially overwritten
+        let mut end = addr;
+        while end < addr + bytes.len() {
+            match self.instruction_by_addr(end) {
+                Some(inst) => end += self.instruction_width(inst),
+                None => return Ok(bytes),
+            }
+        }
+
+        let nop: &[u8] = match self.arch {
+            Architecture::Aarch64 => &[0x1f, 0x20, 0x03, 0xd5],
+            _ => &[0x90],
+        };
+
+        while addr + bytes.len() + nop.len() <= end {
+            bytes.extend_from_slice(nop);
+        }
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use object::endian::{LittleEndian as LE, U32, U64};
+    use object::{elf, macho, pe, Endianness};
+
+    fn bytes_at(processor: &Processor, addr: PhysAddr, len: usize) -> &[u8] {
+        processor.section_by_addr(addr).unwrap().bytes_by_addr(addr, len)
+    }
+
+    /// Offsets of code and data point at their bytes in the file, `.bss` isn't in the file.
+    fn check_offsets(image: Image, name: &str) {
+        let path = testing::write(&image.bytes, name);
+        let processor = Processor::parse(&path).unwrap();
+        testing::remove(&path);
+
+        let text = processor.file_offset(image.text).unwrap();
+        assert_eq!(image.bytes[text..][..CODE.len()], CODE);
+
+        let data = processor.file_offset(image.data).unwrap();
+        assert_eq!(image.bytes[data..][..DATA.len()], DATA);
+        assert_eq!(processor.file_offset(image.data + 3), Some(data + 3));
+
+        assert_eq!(processor.file_offset(image.bss), None);
+        assert_eq!(processor.file_offset(image.bss + BSS_LEN - 1), None);
+        assert!(matches!(
+            processor.patch(image.bss, vec![0; 4]),
+            Err(PatchError::Unmapped(addr)) if addr == image.bss
+        ));
+
+        // a patch can't run past the end of `.data` into whatever follows it in the file
+        let last = image.data + DATA.len() - 1;
+        assert!(matches!(
+            processor.patch(last, vec![0; 2]),
+            Err(PatchError::Unmapped(addr)) if addr == last + 1
+        ));
+    }
+
+    #[test]
+    fn elf_file_offset() {
+        check_offsets(elf(), "offset.elf");
+    }
+
+    #[test]
+    fn pe_file_offset() {
+        check_offsets(pe(), "offset.exe");
+    }
+
+    #[test]
+    fn macho_file_offset() {
+        check_offsets(macho(), "offset.macho");
+    }
+
+    #[test]
+    fn patch_and_undo() {
+        let image = elf();
+        let path = testing::write(&image.bytes, "undo");
+        let processor = Processor::parse(&path).unwrap();
+
+        let patches = processor.patch(image.text, vec![0x90, 0x90]).unwrap_or_else(|err| {
+            panic!("{err}");
+        });
+        let patched = processor.patched(patches).unwrap();
+        assert_eq!(bytes_at(&patched, image.text, CODE.len()), [0x90, 0x90, 0xc3, 0x90]);
+        assert!(patched.is_patched(image.text + 1, 1));
+        assert!(!patched.is_patched(image.text + 2, 2));
+
+        let mut patches = patched.patches().to_vec();
+        patches.pop();
+        let undone = patched.patched(patches).unwrap();
+        testing::remove(&path);
+
+        assert_eq!(bytes_at(&undone, image.text, CODE.len()), CODE);
+        assert!(undone.patches().is_empty());
+        assert!(!undone.is_patched(image.text, CODE.len()));
+    }
+
+    #[test]
+    fn patch_keeps_settings() {
+        let image = elf();
+        let path = testing::write(&image.bytes, "settings");
+        let processor = Processor::parse(&path).unwrap();
+
+        let dialect = decoder::Dialect {
+            syntax: decoder::Syntax::Att,
+            pseudo: false,
+        };
+        let uarch = crate::Uarch::names().last().unwrap();
+        processor.set_dialect(dialect);
+        processor.set_uarch(crate::Uarch::by_name(uarch));
+        processor.set_show_ir(true);
+
+        let patches = processor.patch(image.text, vec![0x90]).unwrap_or_else(|err| {
+            panic!("{err}");
+        });
+        let patched = processor.patched(patches).unwrap();
+        let undone = patched.patched(Vec::new()).unwrap();
+        testing::remove(&path);
+
+        for processor in [&patched, &undone] {
+            assert_eq!(processor.dialect(), dialect);
+            assert_eq!(processor.uarch().map(|uarch| uarch.name), Some(uarch));
+            assert!(processor.shows_ir());
+        }
+    }
+
+    #[test]
+    fn save_patched() {
+        let image = elf();
+        let path = testing::write(&image.bytes, "save");
+        let saved = testing::temp_path("save-patched");
+        let processor = Processor::parse(&path).unwrap();
+
+        let patches = processor.patch(image.data, b"modified".to_vec()).unwrap_or_else(|err| {
+            panic!("{err}");
+        });
+        let patched = processor.patched(patches).unwrap();
+        assert!(matches!(patched.save_patched(&path), Err(PatchError::OverwritesBinary)));
+        if let Err(err) = patched.save_patched(&saved) {
+            panic!("{err}");
+        }
+
+        let reopened = Processor::parse(&saved).unwrap();
+        let original = std::fs::read(&path).unwrap();
+        testing::remove(&path);
+        std::fs::remove_file(&saved).unwrap();
+
+        assert_eq!(bytes_at(&reopened, image.data, DATA.len()), b"modified");
+        assert!(reopened.patches().is_empty());
+        assert_eq!(original, image.bytes);
+    }
+
+    /// Contents of the `.text` section of the images built by [`elf`], [`pe`] and [`macho`].
+    pub const CODE: [u8; 4] = [0x31, 0xc0, 0xc3, 0x90];
+
+    /// Contents of the `.data` section of the built images.
+    pub const DATA: [u8; 8] = *b"original";
+
+    /// Size of the zero-initialized `.bss` section of the built images.
+    pub const BSS_LEN: usize = 0x10;
+
+    /// A minimal x86_64 executable with code, data and zero-initialized data.
+    pub struct Image {
+        pub bytes: Vec<u8>,
+        pub text: PhysAddr,
+        pub data: PhysAddr,
+        pub bss: PhysAddr,
+    }
+
+    /// ELF executable with a single segment mapping the whole file.
+    pub fn elf() -> Image {
+        use object::write::elf::{FileHeader, ProgramHeader, SectionHeader, Writer};
+
+        const VADDR: u64 = 0x400000;
+
+        let mut bytes = Vec::new();
+        let mut writer = Writer::new(Endianness::Little, true, &mut bytes);
+
+        writer.reserve_file_header();
+        writer.reserve_program_headers(1);
+        let text_name = writer.add_section_name(b".text");
+        let data_name = writer.add_section_name(b".data");
+        let bss_name = writer.add_section_name(b".bss");
+        writer.reserve_null_section_index();
+        writer.reserve_section_index();
+        writer.reserve_section_index();
+        writer.reserve_section_index();
+        writer.reserve_shstrtab_section_index();
+        let text = writer.reserve(CODE.len(), 16) as u64;
+        let data = writer.reserve(DATA.len(), 16) as u64;
+        let data_end = data + DATA.len() as u64;
+        let bss = data_end.next_multiple_of(16);
+        writer.reserve_shstrtab();
+        writer.reserve_section_headers();
+
+        writer
+            .write_file_header(&FileHeader {
+                os_abi: elf::ELFOSABI_SYSV,
+                abi_version: 0,
+                e_type: elf::ET_EXEC,
+                e_machine: elf::EM_X86_64,
+                e_entry: VADDR + text,
+                e_flags: 0,
+            })
+            .unwrap();
+        writer.write_align_program_headers();
+        writer.write_program_header(&ProgramHeader {
+            p_type: elf::PT_LOAD,
+            p_flags: elf::PF_R | elf::PF_W | elf::PF_X,
+            p_offset: 0,
+            p_vaddr: VADDR,
+            p_paddr: VADDR,
+            p_filesz: data_end,
+            p_memsz: bss + BSS_LEN as u64,
+            p_align: 0x1000,
+        });
+        writer.write_align(16);
+        writer.write(&CODE);
+        writer.write_align(16);
+        writer.write(&DATA);
+        writer.write_shstrtab();
+
+        let alloc = elf::SHF_ALLOC as u64;
+        let write = elf::SHF_WRITE as u64;
+        let exec = elf::SHF_EXECINSTR as u64;
+        writer.write_null_section_header();
+        for (name, sh_type, sh_flags, offset, size) in [
+            (text_name, elf::SHT_PROGBITS, alloc | exec, text, CODE.len()),
+            (
+                data_name,
+                elf::SHT_PROGBITS,
+                alloc | write,
+                data,
+                DATA.len(),
+            ),
+            (bss_name, elf::SHT_NOBITS, alloc | write, bss, BSS_LEN),
+        ] {
+            writer.write_section_header(&SectionHeader {
+                name: Some(name),
+                sh_type,
+                sh_flags,
+                sh_addr: VADDR + offset,
+                sh_offset: offset,
+                sh_size: size as u64,
+                sh_link: 0,
+                sh_info: 0,
+                sh_addralign: 16,
+                sh_entsize: 0,
+            });
+        }
+        writer.write_shstrtab_section_header();
+
+        Image {
+            bytes,
+            text: (VADDR + text) as PhysAddr,
+            data: (VADDR + data) as PhysAddr,
+            bss: (VADDR + bss) as PhysAddr,
+        }
+    }
+
+    /// PE executable, sections are at their RVA plus the image base.
+    pub fn pe() -> Image {
+        use object::write::pe::{NtHeaders, Writer};
+
+        const IMAGE_BASE: u64 = 0x140000000;
+
+        let mut bytes = Vec::new();
+        let mut writer = Writer::new(true, 0x1000, 0x200, &mut bytes);
+
+        writer.reserve_dos_header_and_stub();
+        writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+        writer.reserve_section_headers(3);
+        let text = writer.reserve_text_section(CODE.len() as u32);
+        let data = writer.reserve_data_section(DATA.len() as u32, DATA.len() as u32);
+        let bss = writer.reserve_bss_section(BSS_LEN as u32);
+
+        writer.write_dos_header_and_stub().unwrap();
+        writer.write_nt_headers(NtHeaders {
+            machine: pe::IMAGE_FILE_MACHINE_AMD64,
+            time_date_stamp: 0,
+            characteristics: pe::IMAGE_FILE_EXECUTABLE_IMAGE | pe::IMAGE_FILE_LARGE_ADDRESS_AWARE,
+            major_linker_version: 0,
+            minor_linker_version: 0,
+            address_of_entry_point: text.virtual_address,
+            image_base: IMAGE_BASE,
+            major_operating_system_version: 6,
+            minor_operating_system_version: 0,
+            major_image_version: 0,
+            minor_image_version: 0,
+            major_subsystem_version: 6,
+            minor_subsystem_version: 0,
+            subsystem: pe::IMAGE_SUBSYSTEM_WINDOWS_CUI,
+            dll_characteristics: 0,
+            size_of_stack_reserve: 0x100000,
+            size_of_stack_commit: 0x1000,
+            size_of_heap_reserve: 0x100000,
+            size_of_heap_commit: 0x1000,
+        });
+        writer.write_section_headers();
+        writer.write_section(text.file_offset, &CODE);
+        writer.write_section(data.file_offset, &DATA);
+
+        Image {
+            bytes,
+            text: (IMAGE_BASE + text.virtual_address as u64) as PhysAddr,
+            data: (IMAGE_BASE + data.virtual_address as u64) as PhysAddr,
+            bss: (IMAGE_BASE + bss.virtual_address as u64) as PhysAddr,
+        }
+    }
+
+    /// Mach-O executable with a `__TEXT` and a `__DATA` segment.
+    pub fn macho() -> Image {
+        const VMADDR: u64 = 0x100000000;
+        const TEXT: u64 = 0x200;
+        const DATA_OFFSET: u64 = TEXT + CODE.len() as u64;
+        const DATA_VMADDR: u64 = VMADDR + 0x1000;
+        const BSS: u64 = DATA_VMADDR + 0x10;
+
+        let name = |name: &[u8]| {
+            let mut padded = [0; 16];
+            padded[..name.len()].copy_from_slice(name);
+            padded
+        };
+
+        let segment = |segname, vmaddr, fileoff, filesize, nsects: u32| macho::SegmentCommand64 {
+            cmd: U32::new(LE, macho::LC_SEGMENT_64),
+            cmdsize: U32::new(LE, 72 + 80 * nsects),
+            segname: name(segname),
+            vmaddr: U64::new(LE, vmaddr),
+            vmsize: U64::new(LE, 0x1000),
+            fileoff: U64::new(LE, fileoff),
+            filesize: U64::new(LE, filesize),
+            maxprot: U32::new(LE, macho::VM_PROT_READ | macho::VM_PROT_WRITE),
+            initprot: U32::new(LE, macho::VM_PROT_READ | macho::VM_PROT_WRITE),
+            nsects: U32::new(LE, nsects),
+            flags: U32::new(LE, 0),
+        };
+
+        let section = |sectname, segname, addr, size: usize, offset, flags| macho::Section64 {
+            sectname: name(sectname),
+            segname: name(segname),
+            addr: U64::new(LE, addr),
+            size: U64::new(LE, size as u64),
+            offset: U32::new(LE, offset as u32),
+            align: U32::new(LE, 0),
+            reloff: U32::new(LE, 0),
+            nreloc: U32::new(LE, 0),
+            flags: U32::new(LE, flags),
+            reserved1: U32::new(LE, 0),
+            reserved2: U32::new(LE, 0),
+            reserved3: U32::new(LE, 0),
+        };
+
+        let header = macho::MachHeader64 {
+            magic: U32::new(object::BigEndian, macho::MH_CIGAM_64),
+            cputype: U32::new(LE, macho::CPU_TYPE_X86_64),
+            cpusubtype: U32::new(LE, macho::CPU_SUBTYPE_X86_64_ALL),
+            filetype: U32::new(LE, macho::MH_EXECUTE),
+            ncmds: U32::new(LE, 2),
+            sizeofcmds: U32::new(LE, (72 + 80) + (72 + 80 * 2)),
+            flags: U32::new(LE, 0),
+            reserved: U32::new(LE, 0),
+        };
+
+        let code = macho::S_REGULAR | macho::S_ATTR_PURE_INSTRUCTIONS;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(object::bytes_of(&header));
+        bytes.extend_from_slice(object::bytes_of(&segment(
+            b"__TEXT",
+            VMADDR,
+            0,
+            DATA_OFFSET,
+            1,
+        )));
+        bytes.extend_from_slice(object::bytes_of(&section(
+            b"__text",
+            b"__TEXT",
+            VMADDR + TEXT,
+            CODE.len(),
+            TEXT,
+            code,
+        )));
+        bytes.extend_from_slice(object::bytes_of(&segment(
+            b"__DATA",
+            DATA_VMADDR,
+            DATA_OFFSET,
+            DATA.len() as u64,
+            2,
+        )));
+        bytes.extend_from_slice(object::bytes_of(&section(
+            b"__data",
+            b"__DATA",
+            DATA_VMADDR,
+            DATA.len(),
+            DATA_OFFSET,
+            macho::S_REGULAR,
+        )));
+        bytes.extend_from_slice(object::bytes_of(&section(
+            b"__bss",
+            b"__DATA",
+            BSS,
+            BSS_LEN,
+            0,
+            macho::S_ZEROFILL,
+        )));
+
+        bytes.resize(TEXT as usize, 0);
+        bytes.extend_from_slice(&CODE);
+        bytes.extend_from_slice(&DATA);
+
+        Image {
+            bytes,
+            text: (VMADDR + TEXT) as PhysAddr,
+            data: DATA_VMADDR as PhysAddr,
+            bss: BSS as PhysAddr,
+        }
+    }
+}
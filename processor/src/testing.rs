@@ -0,0 +1,107 @@
+//! Building processors from assembly for tests.
+
+use crate::Processor;
+use assembler::Arch;
+use processor_shared::PhysAddr;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Address the assembled image is loaded at.
+pub const BASE: PhysAddr = 0x1000;
+
+/// Path of a temporary file, `name` keeps the files of parallel tests apart.
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("bite-test-{}-{}", std::process::id(), name))
+}
+
+/// Assemble `src` as a raw image, returning the processor and the address of each label.
+///
+/// Lines ending in `:` are labels, `.inst` emits a 32-bit word for instructions the
+/// assembler doesn't know. `name` keeps the temporary files of parallel tests apart.
+pub fn assemble(arch: Arch, src: &str, name: &str) -> (Processor, HashMap<String, PhysAddr>) {
+    let lines: Vec<&str> = src.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+
+    // labels are placeholders until their address is known, branches may change size
+    // with the address they're assembled for so repeat until the labels stop moving
+    let mut labels: HashMap<&str, u64> = HashMap::new();
+    let mut first = true;
+    let bytes = loop {
+        let mut addr = BASE as u64;
+        let mut found = HashMap::new();
+        let mut bytes = Vec::new();
+        for line in &lines {
+            if let Some(label) = line.strip_suffix(':') {
+                found.insert(label, addr);
+                continue;
+            }
+
+            if let Some(word) = line.strip_prefix(".inst 0x") {
+                let word = u32::from_str_radix(word, 16).unwrap();
+                addr += 4;
+                bytes.extend(word.to_le_bytes());
+                continue;
+            }
+
+            let symbols = |name: &str| labels.get(name).copied().or(first.then_some(addr));
+            for encoded in assembler::assemble(arch, addr, line, &symbols).unwrap() {
+                addr += encoded.bytes.len() as u64;
+                bytes.extend(encoded.bytes);
+            }
+        }
+
+        if !first && found == labels {
+            break bytes;
+        }
+
+        labels = found;
+        first = false;
+    };
+
+    let arch_name = match arch {
+        Arch::X86_64 => "x86_64",
+        Arch::Aarch64 => "aarch64",
+    };
+
+    let path = temp_path(&format!("{arch_name}-{name}"));
+    std::fs::write(&path, bytes).unwrap();
+    let processor = Processor::parse_raw(&path, arch_name, BASE).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let labels = labels
+        .into_iter()
+        .map(|(label, addr)| (label.to_string(), addr as PhysAddr))
+        .collect();
+
+    (processor, labels)
+}
+
+/// Write `bytes` to a temporary file.
+///
+/// Mach-O binaries get a copy of themselves as their dSYM, so loading them doesn't run `dsymutil`.
+pub fn write(bytes: &[u8], name: &str) -> PathBuf {
+    let path = temp_path(name);
+    std::fs::write(&path, bytes).unwrap();
+
+    if object::FileKind::parse(bytes).ok() != Some(object::FileKind::MachO64) {
+        return path;
+    }
+
+    let dwarf = dsym_path(&path).join("Contents/Resources/DWARF");
+    std::fs::create_dir_all(&dwarf).unwrap();
+    std::fs::write(dwarf.join(path.file_name().unwrap()), bytes).unwrap();
+
+    path
+}
+
+/// Remove a file written by [`write`].
+pub fn remove(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_dir_all(dsym_path(path));
+}
+
+/// Where the debug info of a Mach-O binary is looked up, next to it with `.dSYM` appended.
+fn dsym_path(path: &Path) -> PathBuf {
+    let mut dsym = path.as_os_str().to_owned();
+    dsym.push(".dSYM");
+    PathBuf::from(dsym)
+}
@@ -0,0 +1,162 @@
+//! Checking x86 instructions against the instruction set of a microarchitecture.
+
+use crate::{Instruction, Processor};
+use commands::CONFIG;
+use object::Architecture;
+use processor_shared::{Addressed, PhysAddr};
+use std::collections::HashMap;
+use x86_64::long_mode as x64;
+use x86_64::protected_mode as x86;
+
+/// Microarchitecture the instructions of a binary are checked against.
+#[derive(Clone, Copy)]
+pub struct Uarch {
+    pub name: &'static str,
+    x64: x64::Decoder,
+    x86: x86::Decoder,
+}
+
+impl Uarch {
+    /// Find a microarchitecture by name, e.g. `haswell` or `zen`.
+    pub fn by_name(name: &str) -> Option<Self> {
+        let (name, x64) = x64::uarch::PROFILES.iter().find(|(profile, _)| *profile == name)?;
+
+        Some(Self {
+            name,
+            x64: x64(),
+            x86: x86::uarch::by_name(name)?,
+        })
+    }
+
+    /// Names of all known microarchitectures.
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        x64::uarch::PROFILES.iter().map(|(name, _)| *name)
+    }
+
+    /// Microarchitecture selected in the config.
+    fn from_config() -> Option<Self> {
+        let name = CONFIG.disassembly.uarch.as_deref()?;
+        let uarch = Self::by_name(name);
+
+        if uarch.is_none() {
+            log::warning!("Unknown microarchitecture '{name}' in config.");
+        }
+
+        uarch
+    }
+}
+
+/// How much an instruction set extension is used by a binary.
+#[derive(Debug)]
+pub struct ExtensionUsage {
+    pub name: &'static str,
+    /// Number of instructions requiring the extension.
+    pub count: usize,
+    /// Number of those instructions the selected microarchitecture doesn't support.
+    pub unsupported: usize,
+    /// First instruction requiring the extension.
+    pub first: PhysAddr,
+}
+
+impl Processor {
+    pub(crate) fn default_uarch() -> std::sync::RwLock<Option<Uarch>> {
+        std::sync::RwLock::new(Uarch::from_config())
+    }
+
+    /// Whether the instructions can be checked against a microarchitecture, only x86 has them.
+    pub fn has_uarchs(&self) -> bool {
+        matches!(
+            self.arch,
+            Architecture::X86_64 | Architecture::X86_64_X32 | Architecture::I386
+        )
+    }
+
+    /// Microarchitecture the instructions are checked against.
+    pub fn uarch(&self) -> Option<Uarch> {
+        *self.uarch.read().unwrap()
+    }
+
+    /// Change the microarchitecture, blocks have to be parsed again to show the change.
+    pub fn set_uarch(&self, uarch: Option<Uarch>) {
+        *self.uarch.write().unwrap() = uarch;
+    }
+
+    /// Extensions an instruction requires and whether the microarchitecture supports it.
+    fn requirements(&self, uarch: Option<Uarch>, inst: &Instruction) -> (Vec<&'static str>, bool) {
+        match self.arch {
+            Architecture::X86_64 => {
+                let inst = unsafe { &*inst.x64 };
+                let supported = uarch.is_none_or(|uarch| uarch.x64.supports(inst));
+                (x64::uarch::extensions(inst), supported)
+            }
+            Architecture::X86_64_X32 | Architecture::I386 => {
+                let inst = unsafe { &*inst.x86 };
+                let supported = uarch.is_none_or(|uarch| uarch.x86.supports(inst));
+                (x86::uarch::extensions(inst), supported)
+            }
+            _ => (Vec::new(), true),
+        }
+    }
+
+    /// Extensions required by an instruction the selected microarchitecture doesn't support.
+    pub(crate) fn unsupported(&self, inst: &Instruction) -> Option<Vec<&'static str>> {
+        let supported = match (self.uarch()?, self.arch) {
+            (uarch, Architecture::X86_64) => uarch.x64.supports(unsafe { &inst.x64 }),
+            (uarch, Architecture::X86_64_X32 | Architecture::I386) => {
+                uarch.x86.supports(unsafe { &inst.x86 })
+            }
+            _ => return None,
+        };
+
+        (!supported).then(|| self.requirements(None, inst).0)
+    }
+
+    /// Instruction set extensions used by the binary, sorted by name.
+    pub fn extensions(&self) -> Vec<ExtensionUsage> {
+        let uarch = self.uarch();
+        let mut usage: Vec<ExtensionUsage> = Vec::new();
+
+        // requirements only depend on the opcode and whether it's evex encoded
+        let mut cache = HashMap::new();
+
+        for Addressed { addr, item: inst } in self.instructions.iter() {
+            let key = match self.arch {
+                Architecture::X86_64 => {
+                    let inst = unsafe { &*inst.x64 };
+                    (inst.opcode() as u32, inst.prefixes.evex().is_some())
+                }
+                Architecture::X86_64_X32 | Architecture::I386 => {
+                    let inst = unsafe { &*inst.x86 };
+                    (inst.opcode() as u32, inst.prefixes.evex().is_some())
+                }
+                _ => return usage,
+            };
+
+            let (extensions, supported) =
+                cache.entry(key).or_insert_with(|| self.requirements(uarch, inst));
+
+            for &name in extensions.iter() {
+                let idx = match usage.iter().position(|ext| ext.name == name) {
+                    Some(idx) => idx,
+                    None => {
+                        usage.push(ExtensionUsage {
+                            name,
+                            count: 0,
+                            unsupported: 0,
+                            first: *addr,
+                        });
+                        usage.len() - 1
+                    }
+                };
+
+                usage[idx].count += 1;
+                if !*supported {
+                    usage[idx].unsupported += 1;
+                }
+            }
+        }
+
+        usage.sort_unstable_by_key(|ext| ext.name);
+        usage
+    }
+}
@@ -38,6 +38,16 @@ pub enum SectionKind {
     Elf32Dyn,
     /// Elf64Dyn.
     Elf64Dyn,
+    /// RISC-V build attributes (ELF only).
+    RiscvAttributes,
+    /// Function signatures (Wasm only).
+    WasmType,
+    /// Imported functions, tables, memories and globals (Wasm only).
+    WasmImport,
+    /// Exported functions, tables, memories and globals (Wasm only).
+    WasmExport,
+    /// Global variables and their initializers (Wasm only).
+    WasmGlobal,
     /// DWARF debug info.
     Debug,
     /// Zero sized special sections.
@@ -89,6 +99,23 @@ impl Section {
         self.bytes
     }
 
+    /// Part of the section between two addresses, clamped to the bounds of the section.
+    pub fn slice(&self, start: PhysAddr, end: PhysAddr) -> Self {
+        let start = start.clamp(self.start, self.end);
+        let end = end.clamp(start, self.end);
+        let len = self.bytes.len();
+        let bytes = &self.bytes[len.min(start - self.start)..len.min(end - self.start)];
+
+        Self {
+            name: self.name.clone(),
+            ident: self.ident,
+            kind: self.kind.clone(),
+            bytes,
+            start,
+            end
+        }
+    }
+
     pub fn bytes_by_addr(&self, addr: PhysAddr, len: usize) -> &[u8] {
         let rva = addr - self.start;
         let bytes = &self.bytes.get(rva..).unwrap_or(&[]);